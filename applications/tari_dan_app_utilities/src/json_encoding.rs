@@ -176,6 +176,7 @@ mod tests {
             encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
             minimum_value_promise: 0,
             viewable_balance: None,
+            encrypted_memo: None,
         };
         let commitment = Some((commitment, confidential_output));
 
@@ -39,7 +39,14 @@ use tari_template_builtin::{
     get_template_builtin,
     ACCOUNT_NFT_TEMPLATE_ADDRESS,
     ACCOUNT_TEMPLATE_ADDRESS,
+    AMM_TEMPLATE_ADDRESS,
+    AUCTION_TEMPLATE_ADDRESS,
+    ESCROW_TEMPLATE_ADDRESS,
     FAUCET_TEMPLATE_ADDRESS,
+    MULTISIG_TEMPLATE_ADDRESS,
+    NAME_SERVICE_TEMPLATE_ADDRESS,
+    PAYMENT_SPLITTER_TEMPLATE_ADDRESS,
+    STAKING_TEMPLATE_ADDRESS,
 };
 use tari_template_lib::models::TemplateAddress;
 
@@ -76,7 +83,7 @@ impl<TAddr: NodeAddressable> TemplateManager<TAddr> {
 
         // Precache builtins
         for addr in builtin_templates.keys() {
-            cache.insert(*addr, WasmModule::load_template_from_code(get_template_builtin(addr))?);
+            cache.insert(*addr, config.wasm_backend().load_template(get_template_builtin(addr))?);
         }
 
         Ok(Self {
@@ -90,7 +97,7 @@ impl<TAddr: NodeAddressable> TemplateManager<TAddr> {
 
     fn load_builtin_templates() -> HashMap<TemplateAddress, Template> {
         // for now, we only load the "account" template
-        let mut builtin_templates = HashMap::with_capacity(3);
+        let mut builtin_templates = HashMap::with_capacity(10);
 
         // get the builtin WASM code of the account template
         let compiled_code = get_template_builtin(&ACCOUNT_TEMPLATE_ADDRESS);
@@ -108,6 +115,46 @@ impl<TAddr: NodeAddressable> TemplateManager<TAddr> {
         let template = Self::convert_code_to_template("XtrFaucet", FAUCET_TEMPLATE_ADDRESS, compiled_code.to_vec());
         builtin_templates.insert(FAUCET_TEMPLATE_ADDRESS, template);
 
+        // get the builtin WASM code of the multisig template
+        let compiled_code = get_template_builtin(&MULTISIG_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template("Multisig", MULTISIG_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(MULTISIG_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the escrow template
+        let compiled_code = get_template_builtin(&ESCROW_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template("Escrow", ESCROW_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(ESCROW_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the AMM template
+        let compiled_code = get_template_builtin(&AMM_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template("AmmPool", AMM_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(AMM_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the auction template
+        let compiled_code = get_template_builtin(&AUCTION_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template("Auction", AUCTION_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(AUCTION_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the name service template
+        let compiled_code = get_template_builtin(&NAME_SERVICE_TEMPLATE_ADDRESS);
+        let template =
+            Self::convert_code_to_template("NameService", NAME_SERVICE_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(NAME_SERVICE_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the payment splitter template
+        let compiled_code = get_template_builtin(&PAYMENT_SPLITTER_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template(
+            "PaymentSplitter",
+            PAYMENT_SPLITTER_TEMPLATE_ADDRESS,
+            compiled_code.to_vec(),
+        );
+        builtin_templates.insert(PAYMENT_SPLITTER_TEMPLATE_ADDRESS, template);
+
+        // get the builtin WASM code of the staking template
+        let compiled_code = get_template_builtin(&STAKING_TEMPLATE_ADDRESS);
+        let template = Self::convert_code_to_template("StakingPool", STAKING_TEMPLATE_ADDRESS, compiled_code.to_vec());
+        builtin_templates.insert(STAKING_TEMPLATE_ADDRESS, template);
+
         builtin_templates
     }
 
@@ -266,7 +313,7 @@ impl<TAddr: NodeAddressable + Send + Sync + 'static> TemplateProvider for Templa
         debug!(target: LOG_TARGET, "CACHE MISS: Template {}", address);
         let loaded = match template.executable {
             TemplateExecutable::CompiledWasm(wasm) => {
-                let module = WasmModule::from_code(wasm);
+                let module = WasmModule::from_code(wasm).with_backend(self.config.wasm_backend());
                 module.load_template()?
             },
             TemplateExecutable::Manifest(_) => return Err(TemplateManagerError::UnsupportedTemplateType),
@@ -23,12 +23,17 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
+use tari_dan_engine::wasm::WasmBackendKind;
 use tari_engine_types::TemplateAddress;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TemplateConfig {
     max_cache_size_bytes: u64,
     debug_replacements: Vec<String>,
+    /// The backend used to compile and execute WASM templates. All validators in a network should use the same
+    /// backend.
+    #[serde(default)]
+    wasm_backend: WasmBackendKind,
 }
 
 impl Default for TemplateConfig {
@@ -36,6 +41,7 @@ impl Default for TemplateConfig {
         Self {
             max_cache_size_bytes: 200 * 1024 * 1024,
             debug_replacements: Vec::new(),
+            wasm_backend: WasmBackendKind::default(),
         }
     }
 }
@@ -54,4 +60,8 @@ impl TemplateConfig {
     pub fn max_cache_size_bytes(&self) -> u64 {
         self.max_cache_size_bytes
     }
+
+    pub fn wasm_backend(&self) -> WasmBackendKind {
+        self.wasm_backend
+    }
 }
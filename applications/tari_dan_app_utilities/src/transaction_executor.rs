@@ -18,6 +18,7 @@ use tari_dan_engine::{
     runtime::{AuthParams, RuntimeModule},
     state_store::{memory::ReadOnlyMemoryStateStore, StateStoreError},
     template::LoadedTemplate,
+    trace::TraceModule,
     transaction::{TransactionError, TransactionProcessor},
 };
 use tari_dan_storage::consensus_models::VersionedSubstateIdLockIntent;
@@ -90,6 +91,7 @@ pub struct TariDanTransactionProcessor<TTemplateProvider> {
     template_provider: Arc<TTemplateProvider>,
     fee_table: FeeTable,
     network: Network,
+    execution_traces_enabled: bool,
 }
 
 impl<TTemplateProvider> TariDanTransactionProcessor<TTemplateProvider> {
@@ -98,8 +100,15 @@ impl<TTemplateProvider> TariDanTransactionProcessor<TTemplateProvider> {
             template_provider: Arc::new(template_provider),
             fee_table,
             network,
+            execution_traces_enabled: false,
         }
     }
+
+    /// Records an execution trace in the result of every executed transaction
+    pub fn with_execution_traces(mut self, enabled: bool) -> Self {
+        self.execution_traces_enabled = enabled;
+        self
+    }
 }
 
 impl<TTemplateProvider> TransactionExecutor for TariDanTransactionProcessor<TTemplateProvider>
@@ -125,7 +134,13 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
         };
 
         let initial_cost = 0;
-        let modules: Vec<Arc<dyn RuntimeModule>> = vec![Arc::new(FeeModule::new(initial_cost, self.fee_table.clone()))];
+        let mut modules: Vec<Arc<dyn RuntimeModule>> =
+            vec![Arc::new(FeeModule::new(initial_cost, self.fee_table.clone()))];
+        // The trace module must come after the fee module so that it records the fees charged
+        let trace_module = self.execution_traces_enabled.then(|| Arc::new(TraceModule::new()));
+        if let Some(ref trace_module) = trace_module {
+            modules.push(trace_module.clone());
+        }
 
         let processor = TransactionProcessor::new(
             self.template_provider.clone(),
@@ -135,7 +150,8 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
             modules,
             self.network,
         );
-        let result = processor.execute(transaction.clone())?;
+        let mut result = processor.execute(transaction.clone())?;
+        result.execution_trace = trace_module.map(|m| m.take_trace());
 
         Ok(ExecutionOutput { transaction, result })
     }
@@ -53,7 +53,7 @@ use tari_template_lib::{
     models::{Amount, BucketId, NonFungibleAddress, NonFungibleId},
     prelude::ResourceAddress,
 };
-use tari_transaction::{Transaction, TransactionId, UnsignedTransaction};
+use tari_transaction::{IdempotencyKey, Transaction, TransactionId, UnsignedTransaction};
 use tari_transaction_manifest::{parse_manifest, ManifestValue};
 use tari_utilities::{hex::to_hex, ByteArray};
 use tari_wallet_daemon_client::{
@@ -120,6 +120,12 @@ pub struct CommonSubmitArgs {
     pub min_epoch: Option<u64>,
     #[clap(long)]
     pub max_epoch: Option<u64>,
+    /// Hex-encoded 32-byte key that prevents this transaction from being accepted twice if it is resubmitted
+    #[clap(long)]
+    pub idempotency_key: Option<FromHex<IdempotencyKey>>,
+    /// Number of component addresses to reserve for outputs created by this transaction
+    #[clap(long, default_value_t = 0)]
+    pub reserved_outputs: u32,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -152,6 +158,9 @@ pub struct ConfidentialTransferArgs {
     /// The address of the resource to send. If not provided, use the default Tari confidential resource
     #[clap(long)]
     resource_address: Option<ResourceAddress>,
+    /// Optional memo to encrypt for the recipient e.g. a deposit reference
+    #[clap(long)]
+    memo: Option<String>,
 }
 
 #[derive(Debug, Subcommand, Clone)]
@@ -168,6 +177,10 @@ pub enum CliInstruction {
         #[clap(long, short = 'a')]
         args: Vec<CliArg>,
     },
+    PayRent {
+        substate_id: SubstateId,
+        epochs: u64,
+    },
 }
 
 impl TransactionSubcommand {
@@ -232,6 +245,7 @@ pub async fn handle_submit(args: SubmitArgs, client: &mut WalletDaemonClient) ->
             method: method_name,
             args: args.into_iter().map(|s| s.into_arg()).collect(),
         },
+        CliInstruction::PayRent { substate_id, epochs } => Instruction::PayRent { substate_id, epochs },
     };
 
     let fee_account;
@@ -249,7 +263,9 @@ pub async fn handle_submit(args: SubmitArgs, client: &mut WalletDaemonClient) ->
         .add_instruction(instruction)
         .with_inputs(common.inputs)
         .with_min_epoch(common.min_epoch.map(Epoch))
-        .with_max_epoch(common.max_epoch.map(Epoch));
+        .with_max_epoch(common.max_epoch.map(Epoch))
+        .with_idempotency_key(common.idempotency_key.map(FromHex::into_inner))
+        .with_reserved_outputs(common.reserved_outputs);
 
     if let Some(dump_account) = common.dump_outputs_into {
         let AccountGetResponse { account, .. } = client.accounts_get(dump_account).await?;
@@ -323,7 +339,9 @@ async fn handle_submit_manifest(
         .with_instructions(instructions.instructions)
         .with_inputs(common.inputs)
         .with_min_epoch(common.min_epoch.map(Epoch))
-        .with_max_epoch(common.max_epoch.map(Epoch));
+        .with_max_epoch(common.max_epoch.map(Epoch))
+        .with_idempotency_key(common.idempotency_key.map(FromHex::into_inner))
+        .with_reserved_outputs(common.reserved_outputs);
 
     let transaction = builder.build_unsigned_transaction();
     summarize_transaction(&transaction);
@@ -402,6 +420,7 @@ pub async fn handle_confidential_transfer(
         amount,
         destination_public_key,
         common,
+        memo,
     } = args;
 
     // let AccountByNameResponse { account, .. } = client.accounts_get_by_name(&source_account_name).await?;
@@ -418,6 +437,7 @@ pub async fn handle_confidential_transfer(
             output_to_revealed: false,
             proof_from_badge_resource: None,
             dry_run: false,
+            memo,
         })
         .await?;
 
@@ -542,6 +562,12 @@ pub fn print_substate_diff(diff: &SubstateDiff) {
                     to_hex(fee_claim.validator_public_key.as_bytes())
                 );
             },
+            SubstateValue::KeyValueStore(_) => {
+                println!("      ▶ key-value store: {}", address);
+            },
+            SubstateValue::KeyValueEntry(_) => {
+                println!("      ▶ key-value entry: {}", address);
+            },
         }
         println!();
     }
@@ -834,6 +860,8 @@ impl CliArg {
                 SubstateId::NonFungibleIndex(v) => arg!(v),
                 SubstateId::TransactionReceipt(v) => arg!(v),
                 SubstateId::FeeClaim(v) => arg!(v),
+                SubstateId::KeyValueStore(v) => arg!(v),
+                SubstateId::KeyValueEntry(v) => arg!(v),
             },
             CliArg::TemplateAddress(v) => arg!(v),
             CliArg::NonFungibleId(v) => arg!(v),
@@ -32,7 +32,7 @@ use tari_key_manager::key_manager::DerivedKey;
 use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
 use tari_template_lib::{
     args,
-    constants::{XTR_FAUCET_COMPONENT_ADDRESS, XTR_FAUCET_VAULT_ADDRESS},
+    constants::XTR_FAUCET_COMPONENT_ADDRESS,
     models::{Amount, UnclaimedConfidentialOutputAddress},
     prelude::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
 };
@@ -364,6 +364,7 @@ pub async fn handle_reveal_funds(
             minimum_value_promise: 0,
             encrypted_data,
             resource_view_key: None,
+            encrypted_memo: None,
         };
 
         let inputs = sdk
@@ -603,6 +604,7 @@ pub async fn handle_claim_burn(
         minimum_value_promise: 0,
         encrypted_data,
         resource_view_key: None,
+        encrypted_memo: None,
     };
 
     let reveal_proof = sdk.confidential_crypto_api().generate_withdraw_proof(
@@ -752,10 +754,11 @@ pub async fn handle_create_free_test_coins(
         return Err(invalid_params("fee", Some("cannot be negative")));
     }
 
-    let mut inputs = vec![
-        SubstateRequirement::unversioned(XTR_FAUCET_COMPONENT_ADDRESS),
-        SubstateRequirement::unversioned(XTR_FAUCET_VAULT_ADDRESS),
-    ];
+    // The faucet creates its claims store on the first claim, so its dependent substates are located on the network
+    let mut inputs = sdk
+        .substate_api()
+        .locate_dependent_substates(&[XTR_FAUCET_COMPONENT_ADDRESS.into()])
+        .await?;
     let accounts_api = sdk.accounts_api();
     let (account_address, account_secret_key, new_account_name) =
         get_or_create_account(&account, &accounts_api, key_id, sdk, &mut inputs)?;
@@ -1030,6 +1033,7 @@ pub async fn handle_confidential_transfer(
                 output_to_revealed: req.output_to_revealed,
                 proof_from_resource: req.proof_from_badge_resource,
                 is_dry_run: req.dry_run,
+                memo: req.memo,
             })
             .await?;
 
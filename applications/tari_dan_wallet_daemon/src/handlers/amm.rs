@@ -0,0 +1,393 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Convenience handlers for the built-in constant-product AMM template
+
+use anyhow::anyhow;
+use log::*;
+use serde::Deserialize;
+use tari_dan_common_types::SubstateRequirement;
+use tari_dan_wallet_sdk::{
+    apis::{jwt::JrpcPermission, key_manager},
+    models::Account,
+};
+use tari_engine_types::{commit_result::FinalizeResult, instruction::Instruction, substate::SubstateId};
+use tari_template_builtin::AMM_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    models::{Amount, ComponentAddress, ResourceAddress, VaultId},
+};
+use tari_transaction::{Transaction, TransactionId};
+use tari_wallet_daemon_client::types::{
+    AmmAddLiquidityRequest,
+    AmmAddLiquidityResponse,
+    AmmCreatePoolRequest,
+    AmmCreatePoolResponse,
+    AmmRemoveLiquidityRequest,
+    AmmRemoveLiquidityResponse,
+    AmmSwapRequest,
+    AmmSwapResponse,
+};
+
+use super::{
+    context::HandlerContext,
+    helpers::{get_account_with_inputs, wait_for_result},
+};
+use crate::DEFAULT_FEE;
+
+const LOG_TARGET: &str = "tari::dan::wallet_daemon::handlers::amm";
+
+/// Mirrors the state of the AMM template component
+#[derive(Debug, Deserialize)]
+struct AmmPoolState {
+    a_vault: VaultId,
+    b_vault: VaultId,
+    lp_resource: ResourceAddress,
+}
+
+/// The pool details needed to build transactions against it
+struct AmmPool {
+    a_resource: ResourceAddress,
+    b_resource: ResourceAddress,
+    lp_resource: ResourceAddress,
+    inputs: Vec<SubstateRequirement>,
+}
+
+pub async fn handle_create_pool(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: AmmCreatePoolRequest,
+) -> Result<AmmCreatePoolResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let (account, inputs) = get_account_with_inputs(req.account, sdk)?;
+    let mut inputs = inputs.into_iter().map(Into::into).collect::<Vec<SubstateRequirement>>();
+    inputs.extend([
+        SubstateRequirement::new(SubstateId::Resource(req.a_resource), None),
+        SubstateRequirement::new(SubstateId::Resource(req.b_resource), None),
+    ]);
+
+    info!(
+        target: LOG_TARGET,
+        "Creating AMM pool for {} and {} with fee {}", req.a_resource, req.b_resource, req.fee
+    );
+
+    let instructions = vec![Instruction::CallFunction {
+        template_address: AMM_TEMPLATE_ADDRESS,
+        function: "new".to_string(),
+        args: args![req.a_resource, req.b_resource, req.fee],
+    }];
+    let (transaction_id, result, fee) =
+        submit_amm_transaction(context, &account, instructions, inputs, req.max_fee, false).await?;
+
+    let diff = result
+        .result
+        .accept()
+        .ok_or_else(|| anyhow!("AMM pool creation was not accepted"))?;
+    let (pool_address, pool_state) = diff
+        .up_iter()
+        .filter_map(|(id, s)| Some((id.as_component_address()?, s.substate_value().component()?)))
+        .find(|(_, component)| component.template_address == AMM_TEMPLATE_ADDRESS)
+        .ok_or_else(|| anyhow!("Failed to find AMM pool component address"))?;
+    let pool_state = tari_bor::from_value::<AmmPoolState>(pool_state.state())?;
+
+    Ok(AmmCreatePoolResponse {
+        transaction_id,
+        pool_address,
+        lp_resource: pool_state.lp_resource,
+        fee,
+        result,
+    })
+}
+
+pub async fn handle_add_liquidity(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: AmmAddLiquidityRequest,
+) -> Result<AmmAddLiquidityResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let (account, inputs) = get_account_with_inputs(req.account, sdk)?;
+    let pool = get_pool(context, req.pool_address).await?;
+    let account_address = account_component_address(&account)?;
+
+    let instructions = vec![
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "withdraw".to_string(),
+            args: args![pool.a_resource, req.a_amount],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace { key: b"a".to_vec() },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "withdraw".to_string(),
+            args: args![pool.b_resource, req.b_amount],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace { key: b"b".to_vec() },
+        Instruction::CallMethod {
+            component_address: req.pool_address,
+            method: "add_liquidity".to_string(),
+            args: args![Workspace("a"), Workspace("b")],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace {
+            key: b"liquidity".to_vec(),
+        },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("liquidity.0")],
+        },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("liquidity.1")],
+        },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("liquidity.2")],
+        },
+    ];
+
+    let inputs = inputs.into_iter().map(Into::into).chain(pool.inputs).collect();
+    let (transaction_id, result, fee) =
+        submit_amm_transaction(context, &account, instructions, inputs, req.max_fee, req.dry_run).await?;
+
+    Ok(AmmAddLiquidityResponse {
+        transaction_id,
+        lp_amount: get_event_amount(&result, "add_liquidity", "lp_amount")?,
+        fee,
+        result,
+    })
+}
+
+pub async fn handle_remove_liquidity(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: AmmRemoveLiquidityRequest,
+) -> Result<AmmRemoveLiquidityResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let (account, inputs) = get_account_with_inputs(req.account, sdk)?;
+    let pool = get_pool(context, req.pool_address).await?;
+    let account_address = account_component_address(&account)?;
+
+    let instructions = vec![
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "withdraw".to_string(),
+            args: args![pool.lp_resource, req.lp_amount],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace { key: b"lp".to_vec() },
+        Instruction::CallMethod {
+            component_address: req.pool_address,
+            method: "remove_liquidity".to_string(),
+            args: args![Workspace("lp")],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace { key: b"funds".to_vec() },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("funds.0")],
+        },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("funds.1")],
+        },
+    ];
+
+    let inputs = inputs.into_iter().map(Into::into).chain(pool.inputs).collect();
+    let (transaction_id, result, fee) =
+        submit_amm_transaction(context, &account, instructions, inputs, req.max_fee, req.dry_run).await?;
+
+    Ok(AmmRemoveLiquidityResponse {
+        transaction_id,
+        a_amount: get_event_amount(&result, "remove_liquidity", "a_amount")?,
+        b_amount: get_event_amount(&result, "remove_liquidity", "b_amount")?,
+        fee,
+        result,
+    })
+}
+
+pub async fn handle_swap(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: AmmSwapRequest,
+) -> Result<AmmSwapResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let (account, inputs) = get_account_with_inputs(req.account, sdk)?;
+    let pool = get_pool(context, req.pool_address).await?;
+    let account_address = account_component_address(&account)?;
+
+    let output_resource = if req.input_resource == pool.a_resource {
+        pool.b_resource
+    } else if req.input_resource == pool.b_resource {
+        pool.a_resource
+    } else {
+        return Err(anyhow!(
+            "Resource {} is not in AMM pool {}",
+            req.input_resource,
+            req.pool_address
+        ));
+    };
+
+    let instructions = vec![
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "withdraw".to_string(),
+            args: args![req.input_resource, req.input_amount],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace { key: b"input".to_vec() },
+        Instruction::CallMethod {
+            component_address: req.pool_address,
+            method: "swap".to_string(),
+            args: args![Workspace("input"), req.min_output.unwrap_or_default()],
+        },
+        Instruction::PutLastInstructionOutputOnWorkspace {
+            key: b"output".to_vec(),
+        },
+        Instruction::CallMethod {
+            component_address: account_address,
+            method: "deposit".to_string(),
+            args: args![Workspace("output")],
+        },
+    ];
+
+    let inputs = inputs.into_iter().map(Into::into).chain(pool.inputs).collect();
+    let (transaction_id, result, fee) =
+        submit_amm_transaction(context, &account, instructions, inputs, req.max_fee, req.dry_run).await?;
+
+    Ok(AmmSwapResponse {
+        transaction_id,
+        output_resource,
+        output_amount: get_event_amount(&result, "swap", "output_amount")?,
+        fee,
+        result,
+    })
+}
+
+/// Fetches the pool component and its vaults, returning the pool resources and the substates required to transact
+/// with it
+async fn get_pool(context: &HandlerContext, pool_address: ComponentAddress) -> Result<AmmPool, anyhow::Error> {
+    let substate_api = context.wallet_sdk().substate_api();
+    let pool = substate_api
+        .scan_for_substate(&SubstateId::Component(pool_address), None)
+        .await?;
+    let component = pool
+        .substate
+        .component()
+        .ok_or_else(|| anyhow!("Substate {} is not a component", pool_address))?;
+    if component.template_address != AMM_TEMPLATE_ADDRESS {
+        return Err(anyhow!("Component {} is not an AMM pool", pool_address));
+    }
+    let state = tari_bor::from_value::<AmmPoolState>(component.state())?;
+
+    let mut resources = Vec::with_capacity(2);
+    for vault_id in [state.a_vault, state.b_vault] {
+        let vault = substate_api
+            .scan_for_substate(&SubstateId::Vault(vault_id), None)
+            .await?;
+        let vault = vault
+            .substate
+            .vault()
+            .ok_or_else(|| anyhow!("Substate {} is not a vault", vault_id))?;
+        resources.push(*vault.resource_address());
+    }
+
+    let mut inputs = substate_api
+        .locate_dependent_substates(&[SubstateId::Component(pool_address)])
+        .await?;
+    inputs.extend(
+        resources
+            .iter()
+            .chain([&state.lp_resource])
+            .map(|resource| SubstateRequirement::new(SubstateId::Resource(*resource), None)),
+    );
+
+    Ok(AmmPool {
+        a_resource: resources[0],
+        b_resource: resources[1],
+        lp_resource: state.lp_resource,
+        inputs,
+    })
+}
+
+fn account_component_address(account: &Account) -> Result<ComponentAddress, anyhow::Error> {
+    account
+        .address
+        .as_component_address()
+        .ok_or_else(|| anyhow!("Invalid account address"))
+}
+
+/// Submits (or dry-runs) the AMM transaction, paying fees from the account, and waits for the result
+async fn submit_amm_transaction(
+    context: &HandlerContext,
+    account: &Account,
+    instructions: Vec<Instruction>,
+    inputs: Vec<SubstateRequirement>,
+    max_fee: Option<Amount>,
+    dry_run: bool,
+) -> Result<(TransactionId, FinalizeResult, Amount), anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    let account_secret_key = sdk
+        .key_manager_api()
+        .derive_key(key_manager::TRANSACTION_BRANCH, account.key_index)?;
+
+    let transaction = Transaction::builder()
+        .fee_transaction_pay_from_component(account_component_address(account)?, max_fee.unwrap_or(DEFAULT_FEE))
+        .with_instructions(instructions)
+        .sign(&account_secret_key.key)
+        .build();
+    let transaction_id = *transaction.id();
+
+    let (finalize, fee) = if dry_run {
+        let execute_result = context
+            .transaction_service()
+            .submit_dry_run_transaction(transaction, inputs)
+            .await?;
+        let fee = execute_result.finalize.fee_receipt.total_fees_paid;
+        (execute_result.finalize, fee)
+    } else {
+        let mut events = context.notifier().subscribe();
+        context
+            .transaction_service()
+            .submit_transaction(transaction, inputs)
+            .await?;
+        let finalized = wait_for_result(&mut events, transaction_id).await?;
+        (finalized.finalize, finalized.final_fee)
+    };
+
+    if let Some(reject) = finalize.result.reject() {
+        return Err(anyhow!("AMM transaction {} was rejected: {}", transaction_id, reject));
+    }
+    if let Some(reason) = finalize.reject() {
+        return Err(anyhow!(
+            "AMM transaction {} failed (fees charged): {}",
+            transaction_id,
+            reason
+        ));
+    }
+
+    Ok((transaction_id, finalize, fee))
+}
+
+/// Reads an amount from the payload of an event emitted by the AMM pool
+fn get_event_amount(result: &FinalizeResult, topic: &str, key: &str) -> Result<Amount, anyhow::Error> {
+    let value = result
+        .events
+        .iter()
+        .filter(|event| event.template_address() == AMM_TEMPLATE_ADDRESS)
+        .find(|event| event.topic() == topic)
+        .and_then(|event| event.get_payload(key))
+        .ok_or_else(|| anyhow!("AMM event '{}' with payload '{}' not found", topic, key))?;
+    let amount = value
+        .parse::<i64>()
+        .map_err(|e| anyhow!("Invalid amount '{}' in AMM event '{}': {}", value, topic, e))?;
+    Ok(Amount::new(amount))
+}
@@ -1,7 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{convert::TryInto, fs};
+use std::{collections::HashMap, convert::TryInto, fs};
 
 use anyhow::anyhow;
 use axum_jrpc::error::{JsonRpcError, JsonRpcErrorReason};
@@ -21,6 +21,8 @@ use tari_template_lib::models::Amount;
 use tari_wallet_daemon_client::types::{
     ConfidentialCreateOutputProofRequest,
     ConfidentialCreateOutputProofResponse,
+    ConfidentialDecryptMemosRequest,
+    ConfidentialDecryptMemosResponse,
     ConfidentialViewVaultBalanceRequest,
     ConfidentialViewVaultBalanceResponse,
     ProofsCancelRequest,
@@ -121,6 +123,7 @@ pub async fn handle_create_transfer_proof(
         minimum_value_promise: 0,
         encrypted_data,
         resource_view_key: resource_view_key.clone(),
+        encrypted_memo: None,
     };
 
     let change_amount = total_input_value - req.amount.value() as u64 - req.reveal_amount.value() as u64;
@@ -155,6 +158,7 @@ pub async fn handle_create_transfer_proof(
             encrypted_data,
             minimum_value_promise: 0,
             resource_view_key,
+            encrypted_memo: None,
         })
     } else {
         None
@@ -232,6 +236,7 @@ pub async fn handle_create_output_proof(
         encrypted_data,
         // TODO: the request must include the resource address so that we can fetch the view key
         resource_view_key: None,
+        encrypted_memo: None,
     };
     let proof = sdk
         .confidential_crypto_api()
@@ -301,3 +306,50 @@ pub async fn handle_view_vault_balance(
             .collect(),
     })
 }
+
+pub async fn handle_decrypt_memos(
+    context: &HandlerContext,
+    token: Option<String>,
+    req: ConfidentialDecryptMemosRequest,
+) -> Result<ConfidentialDecryptMemosResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    sdk.jwt_api().check_auth(token, &[JrpcPermission::Admin])?;
+
+    let substate = sdk.substate_api().scan_for_substate(&req.vault_id.into(), None).await?;
+    let vault = substate
+        .substate
+        .as_vault()
+        .ok_or_else(|| anyhow::anyhow!("Indexer returned a non-vault substate when scanning for a vault address"))?;
+
+    #[allow(clippy::mutable_key_type)]
+    let commitments = vault
+        .get_confidential_commitments()
+        .ok_or_else(|| invalid_params("vault_id", Some("Vault does not contain a confidential resource")))?;
+
+    let account_key = sdk
+        .key_manager_api()
+        .derive_key(key_manager::TRANSACTION_BRANCH, req.key_id)?;
+    let crypto_api = sdk.confidential_crypto_api();
+
+    let mut memos = HashMap::new();
+    for (commitment, output) in commitments {
+        let Some(encrypted_memo) = output.encrypted_memo.as_ref() else {
+            continue;
+        };
+        let decrypt_key =
+            crypto_api.derive_encrypted_data_key_for_receiver(&output.stealth_public_nonce, &account_key.key);
+        match crypto_api.decrypt_memo(&decrypt_key, commitment, encrypted_memo) {
+            Ok(memo) => {
+                memos.insert(
+                    commitment.as_public_key().clone(),
+                    String::from_utf8_lossy(&memo).into_owned(),
+                );
+            },
+            Err(err) => {
+                debug!(target: LOG_TARGET, "Unable to decrypt memo for commitment {}: {}", commitment.as_public_key(), err);
+            },
+        }
+    }
+
+    Ok(ConfidentialDecryptMemosResponse { memos })
+}
@@ -2,6 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 pub mod accounts;
+pub mod amm;
 pub mod confidential;
 mod context;
 pub mod error;
@@ -207,6 +207,7 @@ pub async fn handle_submit_dry_run(
 
     Ok(TransactionSubmitDryRunResponse {
         transaction_id: exec_result.finalize.transaction_hash.into_array().into(),
+        fee_breakdown: exec_result.finalize.fee_receipt.to_cost_breakdown(),
         result: exec_result,
         json_result,
     })
@@ -29,6 +29,7 @@ use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use super::handlers::{substates, templates, HandlerContext};
 use crate::handlers::{
     accounts,
+    amm,
     confidential,
     error::HandlerError,
     keys,
@@ -159,6 +160,7 @@ async fn handler(
                 call_handler(context, value, token, confidential::handle_create_output_proof).await
             },
             "view_vault_balance" => call_handler(context, value, token, confidential::handle_view_vault_balance).await,
+            "decrypt_memos" => call_handler(context, value, token, confidential::handle_decrypt_memos).await,
             _ => Ok(value.method_not_found(&value.method)),
         },
         Some(("substates", method)) => match method {
@@ -173,6 +175,13 @@ async fn handler(
             "list" => call_handler(context, value, token, nfts::handle_list_nfts).await,
             _ => Ok(value.method_not_found(&value.method)),
         },
+        Some(("amm", method)) => match method {
+            "create_pool" => call_handler(context, value, token, amm::handle_create_pool).await,
+            "add_liquidity" => call_handler(context, value, token, amm::handle_add_liquidity).await,
+            "remove_liquidity" => call_handler(context, value, token, amm::handle_remove_liquidity).await,
+            "swap" => call_handler(context, value, token, amm::handle_swap).await,
+            _ => Ok(value.method_not_found(&value.method)),
+        },
         Some(("validators", method)) => match method {
             "get_fee_summary" => call_handler(context, value, token, validator::handle_get_validator_fees).await,
             "claim_fees" => call_handler(context, value, token, validator::handle_claim_validator_fees).await,
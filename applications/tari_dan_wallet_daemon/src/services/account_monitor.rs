@@ -19,6 +19,7 @@ use tari_dan_wallet_sdk::{
     DanWalletSdk,
 };
 use tari_engine_types::{
+    events::Event,
     indexed_value::{IndexedValueError, IndexedWellKnownTypes},
     non_fungible::NonFungibleContainer,
     resource::Resource,
@@ -28,6 +29,14 @@ use tari_engine_types::{
 use tari_shutdown::ShutdownSignal;
 use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
 use tari_template_lib::{
+    events::account::{
+        DepositEvent,
+        FeePaidEvent,
+        WithdrawEvent,
+        DEPOSIT_EVENT_TOPIC,
+        FEE_PAID_EVENT_TOPIC,
+        WITHDRAW_EVENT_TOPIC,
+    },
     models::{NonFungibleAddress, VaultId},
     prelude::{NonFungibleId, ResourceAddress},
     resource::TOKEN_SYMBOL,
@@ -353,7 +362,12 @@ where
         Ok(())
     }
 
-    async fn process_result(&mut self, tx_id: TransactionId, diff: &SubstateDiff) -> Result<(), AccountMonitorError> {
+    async fn process_result(
+        &mut self,
+        tx_id: TransactionId,
+        diff: &SubstateDiff,
+        events: &[Event],
+    ) -> Result<(), AccountMonitorError> {
         let substate_api = self.wallet_sdk.substate_api();
         let accounts_api = self.wallet_sdk.accounts_api();
 
@@ -460,6 +474,14 @@ where
             updated_accounts.push(account_addr);
         }
 
+        // Accounts whose balances changed in this transaction are notified even if their vault substates were processed
+        // above
+        for account_addr in self.accounts_with_balance_events(tx_id, events)? {
+            if !updated_accounts.contains(&account_addr) {
+                updated_accounts.push(account_addr);
+            }
+        }
+
         if let Some(account) = new_account {
             self.notify.notify(AccountCreatedEvent {
                 account,
@@ -474,6 +496,62 @@ where
         Ok(())
     }
 
+    /// Decodes the typed deposit, withdraw and fee events emitted by the wallet's accounts in a transaction and returns
+    /// the addresses of the accounts whose balances changed
+    fn accounts_with_balance_events(
+        &self,
+        tx_id: TransactionId,
+        events: &[Event],
+    ) -> Result<Vec<SubstateId>, AccountMonitorError> {
+        let accounts_api = self.wallet_sdk.accounts_api();
+        let mut accounts = vec![];
+        for event in events {
+            if event.template_address() != ACCOUNT_TEMPLATE_ADDRESS {
+                continue;
+            }
+            let Some(account_addr) = event.substate_id() else {
+                continue;
+            };
+            if accounts.contains(&account_addr) || !accounts_api.exists_by_address(&account_addr)? {
+                continue;
+            }
+
+            let change = match event.topic().as_str() {
+                DEPOSIT_EVENT_TOPIC => event
+                    .decode_data::<DepositEvent>()
+                    .map(|e| e.map(|e| format!("deposited {} of {}", e.amount, e.resource_address))),
+                WITHDRAW_EVENT_TOPIC => event
+                    .decode_data::<WithdrawEvent>()
+                    .map(|e| e.map(|e| format!("withdrew {} of {}", e.amount, e.resource_address))),
+                FEE_PAID_EVENT_TOPIC => event
+                    .decode_data::<FeePaidEvent>()
+                    .map(|e| e.map(|e| format!("paid {} in fees", e.amount))),
+                _ => continue,
+            };
+            match change {
+                Ok(Some(change)) => {
+                    info!(target: LOG_TARGET, "👁️‍🗨️ Account {} {} in tx {}", account_addr, change, tx_id);
+                },
+                // Events emitted before the account template emitted typed payloads
+                Ok(None) => {},
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "👁️‍🗨️ Failed to decode {} event of account {} in tx {}: {}",
+                        event.topic(),
+                        account_addr,
+                        tx_id,
+                        e
+                    );
+                    continue;
+                },
+            }
+            accounts.push(account_addr);
+        }
+
+        Ok(accounts)
+    }
+
     async fn fetch_resource(&self, resx_addr: ResourceAddress) -> Result<Resource, AccountMonitorError> {
         let substate_api = self.wallet_sdk.substate_api();
         let resx_addr = SubstateId::Resource(resx_addr);
@@ -545,7 +623,8 @@ where
             },
             WalletEvent::TransactionFinalized(event) => {
                 if let Some(diff) = event.finalize.result.accept() {
-                    self.process_result(event.transaction_id, diff).await?;
+                    self.process_result(event.transaction_id, diff, &event.finalize.events)
+                        .await?;
                 }
             },
             WalletEvent::TransactionInvalid(event) => {
@@ -140,6 +140,7 @@ where
                             .send(finalize.map(|finalize| ExecuteResult {
                                 finalize,
                                 execution_time: finalized_transaction.execution_time.unwrap_or_default(),
+                                execution_trace: None,
                             }))
                             .map_err(|_| TransactionServiceError::ServiceShutdown)?;
                     },
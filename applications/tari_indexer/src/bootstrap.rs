@@ -26,10 +26,7 @@ use anyhow::Context;
 use libp2p::identity;
 use minotari_app_utilities::identity_management;
 use tari_base_node_client::grpc::GrpcBaseNodeClient;
-use tari_common::{
-    configuration::bootstrap::{grpc_default_port, ApplicationType},
-    exit_codes::{ExitCode, ExitError},
-};
+use tari_common::exit_codes::{ExitCode, ExitError};
 use tari_consensus::consensus_constants::ConsensusConstants;
 use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_app_utilities::{
@@ -40,15 +37,19 @@ use tari_dan_app_utilities::{
 };
 use tari_dan_common_types::PeerAddress;
 use tari_dan_p2p::TariMessagingSpec;
-use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tari_dan_storage::global::DbFactory;
+use tari_dan_storage_sqlite::SqliteDbFactory;
 use tari_epoch_manager::base_layer::{EpochManagerConfig, EpochManagerHandle};
 use tari_networking::{MessagingMode, NetworkingHandle, RelayCircuitLimits, RelayReservationLimits, SwarmConfig};
 use tari_shutdown::ShutdownSignal;
 use tari_state_store_sqlite::SqliteStateStore;
 use tari_validator_node_rpc::client::TariValidatorNodeRpcClientFactory;
 
-use crate::{substate_storage_sqlite::sqlite_substate_store_factory::SqliteSubstateStore, ApplicationConfig};
+use crate::{
+    config::IndexerNetworkConfig,
+    substate_storage_sqlite::sqlite_substate_store_factory::SqliteSubstateStore,
+    ApplicationConfig,
+};
 
 const _LOG_TARGET: &str = "tari_indexer::bootstrap";
 
@@ -56,19 +57,10 @@ pub async fn spawn_services(
     config: &ApplicationConfig,
     shutdown: ShutdownSignal,
     keypair: RistrettoKeypair,
-    global_db: GlobalDb<SqliteGlobalDbAdapter<PeerAddress>>,
     consensus_constants: ConsensusConstants,
 ) -> Result<Services, anyhow::Error> {
     ensure_directories_exist(config)?;
 
-    // GRPC client connection to base node
-    let base_node_client = GrpcBaseNodeClient::new(config.indexer.base_node_grpc_url.clone().unwrap_or_else(|| {
-        let port = grpc_default_port(ApplicationType::BaseNode, config.network);
-        format!("http://127.0.0.1:{port}")
-            .parse()
-            .expect("Default base node GRPC URL is malformed")
-    }));
-
     // Initialize networking
     let identity = identity::Keypair::sr25519_from_bytes(keypair.secret_key().as_bytes().to_vec()).map_err(|e| {
         ExitError::new(
@@ -111,11 +103,54 @@ pub async fn spawn_services(
         shutdown.clone(),
     )?;
 
+    // All networks share the same p2p connectivity
+    let validator_node_client_factory = TariValidatorNodeRpcClientFactory::new(networking.clone());
+
+    let mut networks = Vec::new();
+    for network_config in config.indexer.all_networks() {
+        let network = spawn_network_services(
+            config,
+            network_config,
+            shutdown.clone(),
+            &keypair,
+            consensus_constants.clone(),
+        )?;
+        networks.push(network);
+    }
+
+    // Save final node identity after comms has initialized. This is required because the public_address can be
+    // changed by comms during initialization when using tor.
+    save_identities(config, &keypair)?;
+    Ok(Services {
+        keypair,
+        networking,
+        validator_node_client_factory,
+        networks,
+    })
+}
+
+fn spawn_network_services(
+    config: &ApplicationConfig,
+    network_config: IndexerNetworkConfig,
+    shutdown: ShutdownSignal,
+    keypair: &RistrettoKeypair,
+    consensus_constants: ConsensusConstants,
+) -> Result<NetworkServices, anyhow::Error> {
+    let data_dir = config.indexer.network_data_dir(&network_config.name);
+
+    let db_factory = SqliteDbFactory::new(data_dir.clone());
+    db_factory.migrate()?;
+    let global_db = db_factory.get_or_create_global_db()?;
+
+    // GRPC client connection to base node
+    let base_node_grpc_url =
+        network_config.base_node_grpc_url_or_default(config.indexer.base_node_grpc_url.as_ref(), config.network);
+    let base_node_client = GrpcBaseNodeClient::new(base_node_grpc_url);
+
     // Connect to substate db
-    let substate_store = SqliteSubstateStore::try_create(config.indexer.state_db_path())?;
+    let substate_store = SqliteSubstateStore::try_create(config.indexer.state_db_path(&network_config.name))?;
 
     // Epoch manager
-    let validator_node_client_factory = TariValidatorNodeRpcClientFactory::new(networking.clone());
     let (epoch_manager, _) = tari_epoch_manager::base_layer::spawn_service(
         EpochManagerConfig {
             num_preshards: consensus_constants.num_preshards,
@@ -124,7 +159,9 @@ pub async fn spawn_services(
                 .committee_size
                 .try_into()
                 .context("committee_size must be non-zero")?,
-            validator_node_sidechain_id: config.indexer.sidechain_id.clone(),
+            validator_node_sidechain_id: network_config.sidechain_id.clone(),
+            stake_weighted_voting: consensus_constants.stake_weighted_voting,
+            validator_allowlist: None,
         },
         global_db.clone(),
         base_node_client.clone(),
@@ -140,47 +177,59 @@ pub async fn spawn_services(
     // Base Node scanner
     base_layer_scanner::spawn(
         global_db,
-        base_node_client.clone(),
+        base_node_client,
         epoch_manager.clone(),
         template_manager_service.clone(),
-        shutdown.clone(),
-        consensus_constants,
+        shutdown,
+        consensus_constants.clone(),
         // TODO: Remove coupling between scanner and shard store
         SqliteStateStore::connect(&format!(
             "sqlite://{}",
-            config.indexer.data_dir.join("unused-shard-store.sqlite").display()
+            data_dir.join("unused-shard-store.sqlite").display()
         ))?,
         true,
         config.indexer.base_layer_scanning_interval,
-        config.indexer.sidechain_id.clone(),
-        config.indexer.templates_sidechain_id.clone(),
-        config.indexer.burnt_utxo_sidechain_id.clone(),
+        network_config.sidechain_id.clone(),
+        network_config.templates_sidechain_id.clone(),
+        network_config.burnt_utxo_sidechain_id.clone(),
     );
 
-    // Save final node identity after comms has initialized. This is required because the public_address can be
-    // changed by comms during initialization when using tor.
-    save_identities(config, &keypair)?;
-    Ok(Services {
-        keypair,
-        networking,
+    Ok(NetworkServices {
+        config: network_config,
         epoch_manager,
-        validator_node_client_factory,
         substate_store,
         template_manager,
+        consensus_constants,
     })
 }
 
 pub struct Services {
     pub keypair: RistrettoKeypair,
     pub networking: NetworkingHandle<TariMessagingSpec>,
-    pub epoch_manager: EpochManagerHandle<PeerAddress>,
     pub validator_node_client_factory: TariValidatorNodeRpcClientFactory,
+    /// The services of each indexed network, starting with the default network
+    pub networks: Vec<NetworkServices>,
+}
+
+pub struct NetworkServices {
+    pub config: IndexerNetworkConfig,
+    pub epoch_manager: EpochManagerHandle<PeerAddress>,
     pub substate_store: SqliteSubstateStore,
     pub template_manager: TemplateManager<PeerAddress>,
+    pub consensus_constants: ConsensusConstants,
+}
+
+impl NetworkServices {
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
 }
 
 fn ensure_directories_exist(config: &ApplicationConfig) -> io::Result<()> {
     fs::create_dir_all(&config.indexer.data_dir)?;
+    for network in &config.indexer.networks {
+        fs::create_dir_all(config.indexer.network_data_dir(&network.name))?;
+    }
     Ok(())
 }
 
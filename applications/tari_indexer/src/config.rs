@@ -29,7 +29,12 @@ use std::{
 use config::Config;
 use serde::{Deserialize, Serialize};
 use tari_common::{
-    configuration::{serializers, CommonConfig, Network},
+    configuration::{
+        bootstrap::{grpc_default_port, ApplicationType},
+        serializers,
+        CommonConfig,
+        Network,
+    },
     ConfigurationError,
     DefaultConfigLoader,
     SubConfigPath,
@@ -41,6 +46,9 @@ use tari_dan_app_utilities::{
 };
 use url::Url;
 
+/// The name of the network configured by the top-level indexer settings
+pub const DEFAULT_NETWORK_NAME: &str = "default";
+
 #[derive(Debug, Clone)]
 pub struct ApplicationConfig {
     pub common: CommonConfig,
@@ -58,6 +66,7 @@ impl ApplicationConfig {
             network: cfg.get("network")?,
         };
         config.indexer.set_base_path(config.common.base_path());
+        config.indexer.validate_networks()?;
         Ok(config)
     }
 }
@@ -94,6 +103,8 @@ pub struct IndexerConfig {
     pub dan_layer_scanning_internal: Duration,
     /// Template config
     pub templates: TemplateConfig,
+    /// The maximum number of substates kept in the in-memory substate cache
+    pub substate_cache_capacity: u64,
     /// The sidechain to listen on.
     pub sidechain_id: Option<RistrettoPublicKey>,
     /// The templates sidechain id
@@ -102,11 +113,69 @@ pub struct IndexerConfig {
     pub burnt_utxo_sidechain_id: Option<RistrettoPublicKey>,
     /// The event filtering configuration
     pub event_filters: Vec<EventFilterConfig>,
+    /// The number of preshards of the network, which must match the validator node configuration
+    pub num_preshards: Option<u32>,
+    /// Additional networks to index in this process. Each network has its own storage and scanners, and its APIs are
+    /// served under the `/<name>/json_rpc` and `/<name>/graphql` paths.
+    #[serde(default)]
+    pub networks: Vec<IndexerNetworkConfig>,
 }
 
 impl IndexerConfig {
-    pub fn state_db_path(&self) -> PathBuf {
-        self.data_dir.join("state.db")
+    /// Returns the configuration of all indexed networks, starting with the default network
+    pub fn all_networks(&self) -> Vec<IndexerNetworkConfig> {
+        let default_network = IndexerNetworkConfig {
+            name: DEFAULT_NETWORK_NAME.to_string(),
+            base_node_grpc_url: self.base_node_grpc_url.clone(),
+            sidechain_id: self.sidechain_id.clone(),
+            templates_sidechain_id: self.templates_sidechain_id.clone(),
+            burnt_utxo_sidechain_id: self.burnt_utxo_sidechain_id.clone(),
+            event_filters: self.event_filters.clone(),
+        };
+        Some(default_network)
+            .into_iter()
+            .chain(self.networks.iter().cloned())
+            .collect()
+    }
+
+    /// The directory where the data of a network is stored. The default network uses the root data directory.
+    pub fn network_data_dir(&self, network_name: &str) -> PathBuf {
+        if network_name == DEFAULT_NETWORK_NAME {
+            self.data_dir.clone()
+        } else {
+            self.data_dir.join("networks").join(network_name)
+        }
+    }
+
+    pub fn state_db_path(&self, network_name: &str) -> PathBuf {
+        self.network_data_dir(network_name).join("state.db")
+    }
+
+    pub fn validate_networks(&self) -> Result<(), ConfigurationError> {
+        let mut names = vec![DEFAULT_NETWORK_NAME];
+        for network in &self.networks {
+            let is_valid_name = !network.name.is_empty() &&
+                network
+                    .name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !is_valid_name {
+                return Err(ConfigurationError::new(
+                    "indexer.networks.name",
+                    Some(network.name.clone()),
+                    "network names must be non-empty and only contain alphanumeric characters, '-' or '_'",
+                ));
+            }
+            if names.contains(&network.name.as_str()) {
+                return Err(ConfigurationError::new(
+                    "indexer.networks.name",
+                    Some(network.name.clone()),
+                    "network names must be unique",
+                ));
+            }
+            names.push(&network.name);
+        }
+        Ok(())
     }
 
     pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
@@ -138,10 +207,13 @@ impl Default for IndexerConfig {
             ui_connect_address: None,
             dan_layer_scanning_internal: Duration::from_secs(10),
             templates: TemplateConfig::default(),
+            substate_cache_capacity: 10_000,
             sidechain_id: None,
             templates_sidechain_id: None,
             burnt_utxo_sidechain_id: None,
             event_filters: vec![],
+            num_preshards: None,
+            networks: vec![],
         }
     }
 }
@@ -159,3 +231,35 @@ pub struct EventFilterConfig {
     pub substate_id: Option<String>,
     pub template_address: Option<String>,
 }
+
+/// The configuration of a single network (sidechain) indexed by this process
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexerNetworkConfig {
+    /// Unique name of the network, used to namespace its storage and APIs
+    pub name: String,
+    /// The Tari base node's GRPC URL. If not set, the base node of the default network is used.
+    pub base_node_grpc_url: Option<Url>,
+    /// The sidechain to listen on.
+    pub sidechain_id: Option<RistrettoPublicKey>,
+    /// The templates sidechain id
+    pub templates_sidechain_id: Option<RistrettoPublicKey>,
+    /// The burnt utxos sidechain id
+    pub burnt_utxo_sidechain_id: Option<RistrettoPublicKey>,
+    /// The event filtering configuration
+    #[serde(default)]
+    pub event_filters: Vec<EventFilterConfig>,
+}
+
+impl IndexerNetworkConfig {
+    pub fn base_node_grpc_url_or_default(&self, default_url: Option<&Url>, network: Network) -> Url {
+        self.base_node_grpc_url
+            .clone()
+            .or_else(|| default_url.cloned())
+            .unwrap_or_else(|| {
+                let port = grpc_default_port(ApplicationType::BaseNode, network);
+                format!("http://127.0.0.1:{port}")
+                    .parse()
+                    .expect("Default base node GRPC URL is malformed")
+            })
+    }
+}
@@ -24,6 +24,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use log::info;
 use tari_common::configuration::Network;
+use tari_consensus::consensus_constants::ConsensusConstants;
 use tari_dan_app_utilities::{
     template_manager::implementation::TemplateManager,
     transaction_executor::{TariDanTransactionProcessor, TransactionExecutor as _},
@@ -129,11 +130,16 @@ where TSubstateCache: SubstateCache + 'static
         // simulate fees if the transaction requires it
         let fee_table = if Self::transaction_includes_fees(transaction) {
             // TODO: should match the VN fee table, should the fee table values be a consensus constant?
+            let consensus_constants = ConsensusConstants::devnet();
             FeeTable {
                 per_module_call_cost: 1,
                 per_byte_storage_cost: 1,
                 per_event_cost: 1,
                 per_log_cost: 1,
+                max_logs_per_transaction: FeeTable::DEFAULT_MAX_LOGS_PER_TRANSACTION,
+                max_log_bytes_per_transaction: FeeTable::DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION,
+                per_byte_rent_cost_per_epoch: consensus_constants.per_byte_rent_cost_per_epoch,
+                initial_rent_epochs: consensus_constants.initial_rent_epochs,
             }
         } else {
             FeeTable::zero_rated()
@@ -230,6 +236,16 @@ where TSubstateCache: SubstateCache + 'static
             VirtualSubstateId::CurrentEpoch,
             VirtualSubstate::CurrentEpoch(epoch.as_u64()),
         );
+        // Validators provide the consensus timestamp. Dry runs are not prepared in a block, so this is the latest
+        // consensus timestamp rather than the timestamp that the transaction will be executed with.
+        let receipt_address = SubstateAddress::for_transaction_receipt((*transaction.id()).into());
+        let current_timestamp = self
+            .substate_scanner
+            .get_virtual_substate_from_committee(VirtualSubstateId::CurrentTimestamp, receipt_address)
+            .await?;
+        virtual_substates.insert(VirtualSubstateId::CurrentTimestamp, current_timestamp);
+        // The random beacon is only known once the transaction is prepared in a block, so templates that use it cannot
+        // be dry run
 
         let claim_instructions = transaction
             .instructions()
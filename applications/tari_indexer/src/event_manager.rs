@@ -78,15 +78,16 @@ impl EventManager {
         timestamp: u64,
     ) -> Result<(), anyhow::Error> {
         self.substate_store.with_write_tx(|tx| {
-            let new_event = NewEvent {
-                substate_id: Some(substate_id.to_string()),
-                template_address: template_address.to_string(),
-                tx_hash: tx_hash.to_string(),
+            let new_event = NewEvent::new(
+                template_address.to_string(),
+                tx_hash.to_string(),
                 topic,
-                payload: payload.to_json().expect("Failed to convert to JSON"),
-                version: version as i32,
-                timestamp: timestamp as i64,
-            };
+                payload.to_json().expect("Failed to convert to JSON"),
+                version as i32,
+                Some(substate_id.to_string()),
+                timestamp as i64,
+                None,
+            );
             tx.save_event(new_event)
         })?;
         Ok(())
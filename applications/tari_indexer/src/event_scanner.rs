@@ -25,10 +25,15 @@ use std::{collections::HashMap, str::FromStr};
 use anyhow::anyhow;
 use futures::StreamExt;
 use log::*;
-use tari_bor::decode;
+use tari_bor::{decode, json_encoding::CborValueJsonSerializeWrapper};
 use tari_crypto::tari_utilities::message_format::MessageFormat;
 use tari_dan_common_types::{committee::Committee, Epoch, PeerAddress, ShardGroup};
-use tari_dan_p2p::proto::rpc::{GetTransactionResultRequest, PayloadResultStatus, SyncBlocksRequest};
+use tari_dan_p2p::proto::rpc::{
+    GetTransactionResultRequest,
+    PayloadResultStatus,
+    SyncBlocksRequest,
+    SyncBlocksResponse,
+};
 use tari_dan_storage::consensus_models::{Block, BlockId, Decision, TransactionRecord};
 use tari_engine_types::{
     commit_result::{ExecuteResult, TransactionResult},
@@ -36,17 +41,24 @@ use tari_engine_types::{
     substate::{Substate, SubstateId, SubstateValue},
 };
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
-use tari_template_lib::models::{EntityId, TemplateAddress};
+use tari_rpc_framework::ClientStreaming;
+use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    events::account::{DepositEvent, WithdrawEvent, DEPOSIT_EVENT_TOPIC, WITHDRAW_EVENT_TOPIC},
+    models::{ComponentAddress, EntityId, ResourceAddress, TemplateAddress},
+};
 use tari_transaction::{Transaction, TransactionId};
 use tari_validator_node_rpc::client::{TariValidatorNodeRpcClientFactory, ValidatorNodeClientFactory};
 
 use crate::{
     config::EventFilterConfig,
     event_data::EventData,
+    substate_hot_cache::SubstateHotCache,
     substate_storage_sqlite::{
         models::{
             events::{NewEvent, NewScannedBlockId},
             substate::NewSubstate,
+            transfers::NewTransfer,
         },
         sqlite_substate_store_factory::{
             SqliteSubstateStore,
@@ -97,6 +109,7 @@ pub struct EventScanner {
     epoch_manager: EpochManagerHandle<PeerAddress>,
     client_factory: TariValidatorNodeRpcClientFactory,
     substate_store: SqliteSubstateStore,
+    substate_hot_cache: SubstateHotCache,
     event_filters: Vec<EventFilter>,
 }
 
@@ -105,12 +118,14 @@ impl EventScanner {
         epoch_manager: EpochManagerHandle<PeerAddress>,
         client_factory: TariValidatorNodeRpcClientFactory,
         substate_store: SqliteSubstateStore,
+        substate_hot_cache: SubstateHotCache,
         event_filters: Vec<EventFilter>,
     ) -> Self {
         Self {
             epoch_manager,
             client_factory,
             substate_store,
+            substate_hot_cache,
             event_filters,
         }
     }
@@ -188,6 +203,9 @@ impl EventScanner {
                 let events = self.get_events_for_transaction(transaction.transaction_id).await?;
                 event_count += events.len();
 
+                // transfers are derived from all account events, regardless of the indexer filter
+                let transfers = Self::extract_transfers_from_events(&events, &transaction);
+
                 // only keep the events specified by the indexer filter
                 let filtered_events: Vec<EventData> =
                     events.into_iter().filter(|ev| self.should_persist_event(ev)).collect();
@@ -197,7 +215,8 @@ impl EventScanner {
                     epoch,
                     filtered_events.len()
                 );
-                self.store_events_in_db(&filtered_events, transaction).await?;
+                self.store_events_in_db(&filtered_events, transfers, transaction)
+                    .await?;
             }
         }
 
@@ -260,26 +279,54 @@ impl EventScanner {
     async fn store_events_in_db(
         &self,
         events_data: &Vec<EventData>,
+        transfers: Vec<NewTransfer>,
         transaction: TransactionMetadata,
     ) -> Result<(), anyhow::Error> {
         let mut tx = self.substate_store.create_write_tx()?;
+        let mut ingested_substates = vec![];
+
+        // transfers are stored all at once for a transaction, so we skip them if the transaction was already scanned
+        if !transfers.is_empty() && !tx.transfers_exist_for_transaction(&transaction.transaction_id)? {
+            for transfer in transfers {
+                tx.save_transfer(transfer)?;
+            }
+        }
 
         for data in events_data {
-            let event_row = NewEvent {
-                template_address: data.event.template_address().to_string(),
-                tx_hash: data.event.tx_hash().to_string(),
-                topic: data.event.topic(),
-                payload: data.event.payload().to_json().expect("Failed to convert to JSON"),
-                substate_id: data.event.substate_id().map(|s| s.to_string()),
-                version: 0_i32,
-                timestamp: transaction.timestamp as i64,
+            // events related to a substate are identified by the version of the substate
+            let version = data.substate.as_ref().map(|s| s.version()).unwrap_or_default();
+            // event payloads come from untrusted templates, so we skip events that cannot be encoded
+            let (payload, event_data) = match Self::encode_event_payload(&data.event) {
+                Ok(encoded) => encoded,
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Skipping event {} that could not be converted to JSON: {}",
+                        data.event,
+                        err
+                    );
+                    continue;
+                },
             };
+            let event_row = NewEvent::new(
+                data.event.template_address().to_string(),
+                data.event.tx_hash().to_string(),
+                data.event.topic(),
+                payload,
+                version as i32,
+                data.event.substate_id().map(|s| s.to_string()),
+                transaction.timestamp as i64,
+                event_data,
+            );
 
-            // TODO: properly avoid or handle duplicated events
-            //       For now we will just check if a similar event exists in the db
-            let event_already_exists = tx.event_exists(event_row.clone())?;
-            if event_already_exists {
-                // the event was already stored previously
+            info!(
+                target: LOG_TARGET,
+                "Saving event: {:?}",
+                event_row
+            );
+            // the same event can be scanned from multiple validators, in that case it is merged by the storage layer
+            let is_new_event = tx.save_event(event_row)?;
+            if !is_new_event {
                 warn!(
                     target: LOG_TARGET,
                     "Duplicated event {:}",
@@ -288,13 +335,6 @@ impl EventScanner {
                 continue;
             }
 
-            info!(
-                target: LOG_TARGET,
-                "Saving event: {:?}",
-                event_row
-            );
-            tx.save_event(event_row)?;
-
             // store/update the related substate if any
             if let (Some(substate_id), Some(substate)) = (data.event.substate_id(), &data.substate) {
                 let template_address = Self::extract_template_address_from_substate(substate).map(|t| t.to_string());
@@ -314,14 +354,103 @@ impl EventScanner {
                     substate_row
                 );
                 tx.set_substate(substate_row)?;
+                ingested_substates.push((substate_id, substate.version()));
             }
         }
 
         tx.commit()?;
 
+        // cached substates must not be served after a newer version has been stored
+        for (substate_id, version) in ingested_substates {
+            self.substate_hot_cache.invalidate_older_than(&substate_id, version);
+        }
+
         Ok(())
     }
 
+    /// Derives the fungible transfers of a transaction from the withdraw/deposit events emitted by accounts.
+    /// Each deposit is paired with a withdrawal of the same resource and amount, if any. Unpaired deposits (e.g.
+    /// from minting) have no sender, and unpaired withdrawals (e.g. burns or fee payments) have no recipient.
+    fn extract_transfers_from_events(events: &[EventData], transaction: &TransactionMetadata) -> Vec<NewTransfer> {
+        let mut withdrawals = vec![];
+        let mut deposits = vec![];
+        for data in events {
+            let event = &data.event;
+            if event.template_address() != ACCOUNT_TEMPLATE_ADDRESS {
+                continue;
+            }
+            let Some(account) = event.substate_id().and_then(|id| id.as_component_address()) else {
+                continue;
+            };
+            let Some((resource, amount)) = Self::decode_account_balance_event(event) else {
+                continue;
+            };
+            // Confidential deposits do not reveal the amount
+            if amount <= 0 {
+                continue;
+            }
+
+            match event.topic().as_str() {
+                WITHDRAW_EVENT_TOPIC => withdrawals.push((account, resource, amount)),
+                DEPOSIT_EVENT_TOPIC => deposits.push((account, resource, amount)),
+                _ => {},
+            }
+        }
+
+        let new_transfer = |resource: ResourceAddress,
+                            from: Option<ComponentAddress>,
+                            to: Option<ComponentAddress>,
+                            amount: i64| NewTransfer {
+            resource_address: resource.to_string(),
+            from_account: from.map(|a| a.to_string()),
+            to_account: to.map(|a| a.to_string()),
+            amount,
+            tx_hash: transaction.transaction_id.to_string(),
+            timestamp: transaction.timestamp as i64,
+        };
+
+        let mut transfers = Vec::with_capacity(deposits.len() + withdrawals.len());
+        for (to, resource, amount) in deposits {
+            let from = withdrawals
+                .iter()
+                .position(|(_, r, a)| *r == resource && *a == amount)
+                .map(|i| withdrawals.remove(i).0);
+            transfers.push(new_transfer(resource, from, Some(to), amount));
+        }
+        transfers.extend(
+            withdrawals
+                .into_iter()
+                .map(|(from, resource, amount)| new_transfer(resource, Some(from), None, amount)),
+        );
+
+        transfers
+    }
+
+    /// Returns the resource and amount of an account deposit or withdraw event. Events emitted before the account
+    /// template emitted typed payloads are decoded from their string search keys.
+    fn decode_account_balance_event(event: &Event) -> Option<(ResourceAddress, i64)> {
+        let typed = match event.topic().as_str() {
+            DEPOSIT_EVENT_TOPIC => event
+                .decode_data::<DepositEvent>()
+                .ok()?
+                .map(|e| (e.resource_address, e.amount)),
+            WITHDRAW_EVENT_TOPIC => event
+                .decode_data::<WithdrawEvent>()
+                .ok()?
+                .map(|e| (e.resource_address, e.amount)),
+            _ => return None,
+        };
+        if let Some((resource, amount)) = typed {
+            return Some((resource, amount.value()));
+        }
+
+        let resource = event
+            .get_payload("resource")
+            .and_then(|r| ResourceAddress::from_str(&r).ok())?;
+        let amount = event.get_payload("amount").and_then(|a| a.parse::<i64>().ok())?;
+        Some((resource, amount))
+    }
+
     fn extract_template_address_from_substate(substate: &Substate) -> Option<TemplateAddress> {
         match substate.substate_value() {
             SubstateValue::Component(c) => Some(c.template_address),
@@ -336,6 +465,15 @@ impl EventScanner {
         }
     }
 
+    fn encode_event_payload(event: &Event) -> Result<(String, Option<String>), anyhow::Error> {
+        let payload = event.payload().to_json()?;
+        let data = event
+            .data()
+            .map(|d| serde_json::to_string(&CborValueJsonSerializeWrapper(d)))
+            .transpose()?;
+        Ok((payload, data))
+    }
+
     fn encode_substate(substate: &Substate) -> Result<String, anyhow::Error> {
         let pretty_json = serde_json::to_string_pretty(&substate)?;
         Ok(pretty_json)
@@ -564,15 +702,39 @@ impl EventScanner {
         let mut rpc_client = self.client_factory.create_client(vn_addr);
         let mut client = rpc_client.client_connection().await?;
 
-        let mut stream = client
-            .sync_blocks(SyncBlocksRequest {
-                start_block_id: start_block_id.map(|id| id.as_bytes().to_vec()).unwrap_or_default(),
-                epoch: Some(up_to_epoch.into()),
-            })
-            .await?;
+        let mut start_block_id = start_block_id;
+        loop {
+            let mut stream = client
+                .sync_blocks(SyncBlocksRequest {
+                    start_block_id: start_block_id.map(|id| id.as_bytes().to_vec()).unwrap_or_default(),
+                    epoch: Some(up_to_epoch.into()),
+                    max_blocks: 0,
+                })
+                .await?;
+
+            let next_page_start = Self::read_blocks_page(&mut stream, &mut blocks).await?;
+            // Resume from the last block of the page if the peer has more blocks
+            match next_page_start {
+                Some(block_id) => start_block_id = Some(block_id),
+                None => break,
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    /// Reads a page of blocks from the stream, returning the block id to resume from if the peer has more blocks
+    async fn read_blocks_page(
+        stream: &mut ClientStreaming<SyncBlocksResponse>,
+        blocks: &mut Vec<Block>,
+    ) -> Result<Option<BlockId>, anyhow::Error> {
         while let Some(resp) = stream.next().await {
             let msg = resp?;
 
+            if let Some(page_end) = msg.page_end() {
+                return Ok(Some(BlockId::try_from(page_end.last_block_id.as_slice())?));
+            }
+
             let new_block = msg
                 .into_block()
                 .ok_or_else(|| anyhow::anyhow!("Expected peer to return a newblock"))?;
@@ -613,6 +775,142 @@ impl EventScanner {
             blocks.push(block);
         }
 
-        Ok(blocks)
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_template_lib::models::{Amount, Metadata, ObjectKey};
+
+    use super::*;
+
+    fn account(seed: u8) -> ComponentAddress {
+        ComponentAddress::from_array([seed; ObjectKey::LENGTH])
+    }
+
+    fn resource(seed: u8) -> ResourceAddress {
+        ResourceAddress::new(ObjectKey::from_array([seed; ObjectKey::LENGTH]))
+    }
+
+    fn account_event(topic: &str, account: ComponentAddress, resource: ResourceAddress, amount: i64) -> EventData {
+        let data = match topic {
+            DEPOSIT_EVENT_TOPIC => tari_bor::to_value(&DepositEvent {
+                resource_address: resource,
+                amount: Amount(amount),
+            }),
+            _ => tari_bor::to_value(&WithdrawEvent {
+                resource_address: resource,
+                amount: Amount(amount),
+            }),
+        }
+        .unwrap();
+        let event = Event::new(
+            Some(account.into()),
+            ACCOUNT_TEMPLATE_ADDRESS,
+            Default::default(),
+            topic.to_string(),
+            Metadata::new(),
+        )
+        .with_data(data);
+        EventData { event, substate: None }
+    }
+
+    fn deposit(account: ComponentAddress, resource: ResourceAddress, amount: i64) -> EventData {
+        account_event(DEPOSIT_EVENT_TOPIC, account, resource, amount)
+    }
+
+    fn withdraw(account: ComponentAddress, resource: ResourceAddress, amount: i64) -> EventData {
+        account_event(WITHDRAW_EVENT_TOPIC, account, resource, amount)
+    }
+
+    fn extract_transfers(events: &[EventData]) -> Vec<(String, Option<String>, Option<String>, i64)> {
+        let transaction = TransactionMetadata {
+            transaction_id: TransactionId::default(),
+            timestamp: 0,
+        };
+        EventScanner::extract_transfers_from_events(events, &transaction)
+            .into_iter()
+            .map(|t| (t.resource_address, t.from_account, t.to_account, t.amount))
+            .collect()
+    }
+
+    fn transfer(
+        resource: ResourceAddress,
+        from: Option<ComponentAddress>,
+        to: Option<ComponentAddress>,
+        amount: i64,
+    ) -> (String, Option<String>, Option<String>, i64) {
+        (
+            resource.to_string(),
+            from.map(|a| a.to_string()),
+            to.map(|a| a.to_string()),
+            amount,
+        )
+    }
+
+    #[test]
+    fn it_pairs_each_deposit_with_a_withdrawal_of_the_same_resource_and_amount() {
+        let transfers = extract_transfers(&[
+            withdraw(account(1), resource(1), 10),
+            withdraw(account(1), resource(2), 5),
+            deposit(account(2), resource(2), 5),
+            deposit(account(3), resource(1), 10),
+        ]);
+        assert_eq!(transfers, vec![
+            transfer(resource(2), Some(account(1)), Some(account(2)), 5),
+            transfer(resource(1), Some(account(1)), Some(account(3)), 10),
+        ]);
+    }
+
+    #[test]
+    fn it_pairs_each_withdrawal_at_most_once() {
+        let transfers = extract_transfers(&[
+            withdraw(account(1), resource(1), 10),
+            withdraw(account(2), resource(1), 10),
+            deposit(account(3), resource(1), 10),
+            deposit(account(4), resource(1), 10),
+            deposit(account(5), resource(1), 10),
+        ]);
+        assert_eq!(transfers, vec![
+            transfer(resource(1), Some(account(1)), Some(account(3)), 10),
+            transfer(resource(1), Some(account(2)), Some(account(4)), 10),
+            transfer(resource(1), None, Some(account(5)), 10),
+        ]);
+    }
+
+    #[test]
+    fn it_records_unpaired_deposits_and_withdrawals() {
+        let transfers = extract_transfers(&[
+            // A burn or fee payment
+            withdraw(account(1), resource(1), 7),
+            // A mint
+            deposit(account(2), resource(2), 3),
+            // Amounts do not match
+            deposit(account(3), resource(1), 6),
+        ]);
+        assert_eq!(transfers, vec![
+            transfer(resource(2), None, Some(account(2)), 3),
+            transfer(resource(1), None, Some(account(3)), 6),
+            transfer(resource(1), Some(account(1)), None, 7),
+        ]);
+    }
+
+    #[test]
+    fn it_ignores_events_that_are_not_account_transfers() {
+        let mut from_other_template = withdraw(account(1), resource(1), 10);
+        from_other_template.event = Event::new(
+            Some(account(1).into()),
+            Default::default(),
+            Default::default(),
+            WITHDRAW_EVENT_TOPIC.to_string(),
+            Metadata::new(),
+        );
+        let transfers = extract_transfers(&[
+            from_other_template,
+            // Confidential deposits do not reveal the amount
+            deposit(account(2), resource(1), 0),
+        ]);
+        assert!(transfers.is_empty());
     }
 }
@@ -25,6 +25,7 @@ use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
 use log::*;
 use serde::{Deserialize, Serialize};
+use tari_bor::json_encoding::CborValueJsonSerializeWrapper;
 use tari_engine_types::substate::SubstateId;
 use tari_template_lib::Hash;
 use tari_transaction::TransactionId;
@@ -41,6 +42,8 @@ pub struct Event {
     pub tx_hash: [u8; 32],
     pub topic: String,
     pub payload: BTreeMap<String, String>,
+    /// The typed payload of the event encoded as JSON, if any
+    pub data: Option<String>,
 }
 
 impl Event {
@@ -50,6 +53,10 @@ impl Event {
             template_address: event.template_address().into_array(),
             tx_hash: event.tx_hash().into_array(),
             topic: event.topic(),
+            data: event
+                .data()
+                .map(|d| serde_json::to_string(&CborValueJsonSerializeWrapper(d)))
+                .transpose()?,
             payload: event.into_payload().into_iter().collect(),
         })
     }
@@ -195,6 +202,7 @@ impl EventQuery {
             tx_hash: tx_hash.into_array(),
             topic,
             payload: payload.into_iter().collect(),
+            data: None,
         })
     }
 }
@@ -20,7 +20,7 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
@@ -30,10 +30,10 @@ use async_graphql::{
 };
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
-    extract::Extension,
+    extract::{Extension, Path},
     http::StatusCode,
     response::{Html, IntoResponse},
-    routing::get,
+    routing::{get, post},
     Json,
     Router,
 };
@@ -42,6 +42,7 @@ use serde::Serialize;
 use tower_http::cors::CorsLayer;
 
 use crate::{
+    config::DEFAULT_NETWORK_NAME,
     graphql::model::events::{EventQuery, EventSchema},
     substate_manager::SubstateManager,
     EventManager,
@@ -51,18 +52,24 @@ const LOG_TARGET: &str = "tari::indexer::graphql";
 
 pub async fn run_graphql(
     preferred_address: SocketAddr,
-    substate_manager: Arc<SubstateManager>,
-    event_manager: Arc<EventManager>,
+    networks: Vec<(String, Arc<SubstateManager>, Arc<EventManager>)>,
 ) -> Result<(), anyhow::Error> {
-    let schema = Schema::build(EventQuery, EmptyMutation, EmptySubscription)
-        .data(substate_manager)
-        .data(event_manager)
-        .finish();
+    let schemas = networks
+        .into_iter()
+        .map(|(name, substate_manager, event_manager)| {
+            let schema = Schema::build(EventQuery, EmptyMutation, EmptySubscription)
+                .data(substate_manager)
+                .data(event_manager)
+                .finish();
+            (name, schema)
+        })
+        .collect::<HashMap<_, _>>();
     let router = Router::new()
         .route("/", get(graphql_playground).post(graphql_handler))
         .route("/health", get(health))
+        .route("/:network/graphql", post(network_graphql_handler))
         .layer(CorsLayer::permissive())
-        .layer(Extension(schema));
+        .layer(Extension(Arc::new(schemas)));
 
     axum::Server::try_bind(&preferred_address)
         .or_else(|_| {
@@ -94,6 +101,26 @@ pub(crate) async fn graphql_playground() -> impl IntoResponse {
     ))
 }
 
-pub(crate) async fn graphql_handler(Extension(schema): Extension<EventSchema>, req: GraphQLRequest) -> GraphQLResponse {
-    schema.execute(req.into_inner()).await.into()
+pub(crate) async fn graphql_handler(
+    Extension(schemas): Extension<Arc<HashMap<String, EventSchema>>>,
+    req: GraphQLRequest,
+) -> Result<GraphQLResponse, StatusCode> {
+    execute_network_request(&schemas, DEFAULT_NETWORK_NAME, req).await
+}
+
+pub(crate) async fn network_graphql_handler(
+    Path(network): Path<String>,
+    Extension(schemas): Extension<Arc<HashMap<String, EventSchema>>>,
+    req: GraphQLRequest,
+) -> Result<GraphQLResponse, StatusCode> {
+    execute_network_request(&schemas, &network, req).await
+}
+
+async fn execute_network_request(
+    schemas: &HashMap<String, EventSchema>,
+    network: &str,
+    req: GraphQLRequest,
+) -> Result<GraphQLResponse, StatusCode> {
+    let schema = schemas.get(network).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(schema.execute(req.into_inner()).await.into())
 }
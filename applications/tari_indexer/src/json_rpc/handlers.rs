@@ -20,7 +20,11 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    sync::Arc,
+};
 
 use axum_jrpc::{
     error::{JsonRpcError, JsonRpcErrorReason},
@@ -30,25 +34,35 @@ use axum_jrpc::{
 };
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use log::{info, warn};
+use serde::Deserialize;
 use serde_json::{self as json, json, Value};
 use tari_base_node_client::{grpc::GrpcBaseNodeClient, types::BaseLayerConsensusConstants, BaseNodeClient};
-use tari_crypto::tari_utilities::hex::to_hex;
+use tari_common_types::types::PublicKey;
+use tari_crypto::tari_utilities::{hex::to_hex, ByteArray};
 use tari_dan_app_utilities::{
     json_encoding::{encode_finalize_result_into_json, encode_finalized_result_into_json},
     keypair::RistrettoKeypair,
     substate_file_cache::SubstateFileCache,
-    template_manager::{implementation::TemplateManager, interface::TemplateExecutable},
+    template_manager::implementation::TemplateManager,
+};
+use tari_dan_common_types::{
+    optional::Optional,
+    public_key_to_peer_id,
+    services::template_provider::TemplateProvider,
+    PeerAddress,
+    SubstateRequirement,
 };
-use tari_dan_common_types::{optional::Optional, public_key_to_peer_id, PeerAddress};
-use tari_dan_engine::{template::TemplateModuleLoader, wasm::WasmModule};
 use tari_dan_p2p::TariMessagingSpec;
 use tari_dan_storage::consensus_models::Decision;
+use tari_engine_types::substate::SubstateId;
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
 use tari_indexer_client::types::{
     self,
     AddPeerRequest,
     AddPeerResponse,
     ConnectionDirection,
+    DryRunTransactionRequest,
+    DryRunTransactionResponse,
     GetAllVnsRequest,
     GetAllVnsResponse,
     GetCommsStatsResponse,
@@ -68,6 +82,8 @@ use tari_indexer_client::types::{
     GetTemplateDefinitionResponse,
     GetTransactionResultRequest,
     GetTransactionResultResponse,
+    GetTransfersRequest,
+    GetTransfersResponse,
     IndexerTransactionFinalizedResult,
     InspectSubstateRequest,
     InspectSubstateResponse,
@@ -75,16 +91,24 @@ use tari_indexer_client::types::{
     ListSubstatesResponse,
     ListTemplatesRequest,
     ListTemplatesResponse,
+    NameTarget,
     NonFungibleSubstate,
+    ResolveNameRequest,
+    ResolveNameResponse,
     SubmitTransactionRequest,
     SubmitTransactionResponse,
     TemplateMetadata,
 };
 use tari_networking::{is_supported_multiaddr, NetworkingHandle, NetworkingService};
+use tari_template_builtin::NAME_SERVICE_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    crypto::RistrettoPublicKeyBytes,
+    models::{ComponentAddress, NonFungibleId},
+};
 use tari_validator_node_rpc::client::{SubstateResult, TariValidatorNodeRpcClientFactory, TransactionResultStatus};
 
 use crate::{
-    bootstrap::Services,
+    bootstrap::{NetworkServices, Services},
     dry_run::processor::DryRunTransactionProcessor,
     json_rpc::error::internal_error,
     substate_manager::SubstateManager,
@@ -93,6 +117,25 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::indexer::json_rpc::handlers";
 
+/// Mirrors the state of the builtin name service template component
+#[derive(Debug, Deserialize)]
+struct NameServiceState {
+    names: BTreeMap<String, NameRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NameRecord {
+    target: NameRecordTarget,
+    badge: NonFungibleId,
+    expiry_epoch: u64,
+}
+
+#[derive(Debug, Deserialize)]
+enum NameRecordTarget {
+    Component(ComponentAddress),
+    PublicKey(RistrettoPublicKeyBytes),
+}
+
 pub struct JsonRpcHandlers {
     consensus_constants: BaseLayerConsensusConstants,
     keypair: RistrettoKeypair,
@@ -104,12 +147,15 @@ pub struct JsonRpcHandlers {
         TransactionManager<EpochManagerHandle<PeerAddress>, TariValidatorNodeRpcClientFactory, SubstateFileCache>,
     template_manager: TemplateManager<PeerAddress>,
     dry_run_transaction_processor: DryRunTransactionProcessor<SubstateFileCache>,
+    max_transaction_bytes: usize,
+    max_instructions: usize,
 }
 
 impl JsonRpcHandlers {
     pub fn new(
         consensus_constants: BaseLayerConsensusConstants,
         services: &Services,
+        network: &NetworkServices,
         base_node_client: GrpcBaseNodeClient,
         substate_manager: Arc<SubstateManager>,
         transaction_manager: TransactionManager<
@@ -117,7 +163,6 @@ impl JsonRpcHandlers {
             TariValidatorNodeRpcClientFactory,
             SubstateFileCache,
         >,
-        template_manager: TemplateManager<PeerAddress>,
         dry_run_transaction_processor: DryRunTransactionProcessor<SubstateFileCache>,
     ) -> Self {
         Self {
@@ -126,10 +171,12 @@ impl JsonRpcHandlers {
             networking: services.networking.clone(),
             base_node_client,
             substate_manager,
-            epoch_manager: services.epoch_manager.clone(),
+            epoch_manager: network.epoch_manager.clone(),
             transaction_manager,
-            template_manager,
+            template_manager: network.template_manager.clone(),
             dry_run_transaction_processor,
+            max_transaction_bytes: network.consensus_constants.max_transaction_bytes,
+            max_instructions: network.consensus_constants.max_instructions,
         }
     }
 
@@ -469,9 +516,51 @@ impl JsonRpcHandlers {
         }))
     }
 
+    pub async fn get_transfers(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetTransfersRequest = value.parse_params()?;
+
+        let transfers = self
+            .substate_manager
+            .get_transfers(request.resource_address, request.account, request.offset, request.limit)
+            .await
+            .map_err(|e| {
+                warn!(target: LOG_TARGET, "Error getting transfers: {}", e);
+                Self::internal_error(answer_id, format!("Error getting transfers: {}", e))
+            })?;
+
+        Ok(JsonRpcResponse::success(answer_id, GetTransfersResponse { transfers }))
+    }
+
     pub async fn submit_transaction(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
-        let request: SubmitTransactionRequest = value.parse_params()?;
+        let mut request: SubmitTransactionRequest = value.parse_params()?;
+
+        // If the client did not specify any inputs, we discover the required substates from the instructions
+        if request.required_substates.is_empty() && request.transaction.inputs().is_empty() {
+            request.required_substates = request
+                .transaction
+                .to_referenced_substates()
+                .map_err(|e| {
+                    JsonRpcResponse::error(
+                        answer_id,
+                        JsonRpcError::new(
+                            JsonRpcErrorReason::InvalidParams,
+                            format!("Could not discover the transaction inputs: {}", e),
+                            json::Value::Null,
+                        ),
+                    )
+                })?
+                .into_iter()
+                .map(SubstateRequirement::unversioned)
+                .collect();
+            info!(
+                target: LOG_TARGET,
+                "🔍 Discovered {} input(s) for transaction {}",
+                request.required_substates.len(),
+                request.transaction.id()
+            );
+        }
 
         if request.is_dry_run {
             let transaction_id = *request.transaction.id();
@@ -543,6 +632,30 @@ impl JsonRpcHandlers {
         }))
     }
 
+    pub async fn dry_run_transaction(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: DryRunTransactionRequest = value.parse_params()?;
+
+        let transaction_id = *request.transaction.id();
+        let exec_result = self
+            .dry_run_transaction_processor
+            .process_transaction(request.transaction, request.required_substates)
+            .await
+            .map_err(|e| {
+                warn!(target: LOG_TARGET, "Dry run for transaction {} failed: {}", transaction_id, e);
+                Self::internal_error(answer_id, e)
+            })?;
+
+        let json_results =
+            encode_finalize_result_into_json(&exec_result.finalize).map_err(|e| Self::internal_error(answer_id, e))?;
+
+        Ok(JsonRpcResponse::success(answer_id, DryRunTransactionResponse {
+            transaction_id,
+            result: exec_result,
+            json_results,
+        }))
+    }
+
     pub async fn get_epoch_manager_stats(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let current_epoch = self.epoch_manager.current_epoch().await.map_err(|e| {
@@ -571,6 +684,8 @@ impl JsonRpcHandlers {
             current_epoch,
             current_block_height,
             current_block_hash,
+            max_transaction_bytes: self.max_transaction_bytes,
+            max_instructions: self.max_instructions,
         };
         Ok(JsonRpcResponse::success(answer_id, response))
     }
@@ -578,25 +693,12 @@ impl JsonRpcHandlers {
     pub async fn get_template_definition(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let request: GetTemplateDefinitionRequest = value.parse_params()?;
+        // Loaded templates are cached by the template manager, so repeated requests do not recompile the template
         let template = self
             .template_manager
-            .fetch_template(&request.template_address)
-            .map_err(|e| Self::internal_error(answer_id, e))?;
-        let template = match template.executable {
-            TemplateExecutable::CompiledWasm(code) => WasmModule::from_code(code)
-                .load_template()
-                .map_err(|e| Self::internal_error(answer_id, format!("Error loading template: {}", e)))?,
-            TemplateExecutable::Manifest(_) | TemplateExecutable::Flow(_) => {
-                return Err(JsonRpcResponse::error(
-                    answer_id,
-                    JsonRpcError::new(
-                        JsonRpcErrorReason::InvalidRequest,
-                        "Template is not a wasm module".to_string(),
-                        json::Value::Null,
-                    ),
-                ));
-            },
-        };
+            .get_template_module(&request.template_address)
+            .map_err(|e| Self::internal_error(answer_id, format!("Error loading template: {}", e)))?
+            .ok_or_else(|| Self::not_found(answer_id, format!("Template {} not found", request.template_address)))?;
 
         Ok(JsonRpcResponse::success(answer_id, GetTemplateDefinitionResponse {
             definition: template.template_def().clone(),
@@ -722,6 +824,61 @@ impl JsonRpcHandlers {
         Ok(JsonRpcResponse::success(answer_id, resp))
     }
 
+    pub async fn resolve_name(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: ResolveNameRequest = value.parse_params()?;
+
+        let substate_id = SubstateId::Component(request.name_service);
+        let resp = self
+            .substate_manager
+            .get_substate(&substate_id, None)
+            .await
+            .map_err(|e| {
+                warn!(target: LOG_TARGET, "Error getting substate: {}", e);
+                Self::internal_error(answer_id, format!("Error getting substate: {}", e))
+            })?
+            .ok_or_else(|| Self::not_found(answer_id, format!("Name service {} not found", request.name_service)))?;
+        let component = resp
+            .substate
+            .substate_value()
+            .component()
+            .filter(|component| component.template_address == NAME_SERVICE_TEMPLATE_ADDRESS)
+            .ok_or_else(|| {
+                Self::error_response(
+                    answer_id,
+                    JsonRpcErrorReason::InvalidParams,
+                    format!("Component {} is not a name service", request.name_service),
+                )
+            })?;
+        let mut state = tari_bor::from_value::<NameServiceState>(component.state())
+            .map_err(|e| Self::internal_error(answer_id, format!("Invalid name service state: {}", e)))?;
+
+        let record = state
+            .names
+            .remove(&request.name)
+            .ok_or_else(|| Self::not_found(answer_id, format!("Name '{}' is not registered", request.name)))?;
+        let current_epoch = self
+            .epoch_manager
+            .current_epoch()
+            .await
+            .map_err(|e| Self::internal_error(answer_id, format!("Could not get current epoch: {}", e)))?;
+        let target = match record.target {
+            NameRecordTarget::Component(address) => NameTarget::Component(address),
+            NameRecordTarget::PublicKey(public_key) => NameTarget::PublicKey(
+                PublicKey::from_canonical_bytes(public_key.as_bytes())
+                    .map_err(|e| Self::internal_error(answer_id, format!("Invalid public key: {}", e)))?,
+            ),
+        };
+
+        Ok(JsonRpcResponse::success(answer_id, ResolveNameResponse {
+            name: request.name,
+            target,
+            badge: record.badge,
+            expiry_epoch: record.expiry_epoch,
+            is_expired: current_epoch.as_u64() >= record.expiry_epoch,
+        }))
+    }
+
     fn error_response<T: Display>(answer_id: i64, reason: JsonRpcErrorReason, message: T) -> JsonRpcResponse {
         JsonRpcResponse::error(
             answer_id,
@@ -20,21 +20,37 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{net::SocketAddr, sync::Arc};
-
-use axum::{extract::Extension, middleware, routing::post, Router};
-use axum_jrpc::{JrpcResult, JsonRpcExtractor};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{Extension, Path},
+    middleware,
+    routing::post,
+    Router,
+};
+use axum_jrpc::{
+    error::{JsonRpcError, JsonRpcErrorReason},
+    JrpcResult,
+    JsonRpcExtractor,
+    JsonRpcResponse,
+};
 use log::*;
+use serde_json::Value;
 use tower_http::cors::CorsLayer;
 
 use super::handlers::JsonRpcHandlers;
+use crate::config::DEFAULT_NETWORK_NAME;
 
 const LOG_TARGET: &str = "tari::indexer::json_rpc";
 
-pub fn spawn_json_rpc(preferred_address: SocketAddr, handlers: JsonRpcHandlers) -> anyhow::Result<SocketAddr> {
+/// JSON-RPC handlers for each indexed network, keyed by network name
+type NetworkHandlers = HashMap<String, JsonRpcHandlers>;
+
+pub fn spawn_json_rpc(preferred_address: SocketAddr, handlers: NetworkHandlers) -> anyhow::Result<SocketAddr> {
     let router = Router::new()
-        .route("/", post(handler))
-        .route("/json_rpc", post(handler))
+        .route("/", post(default_network_handler))
+        .route("/json_rpc", post(default_network_handler))
+        .route("/:network/json_rpc", post(network_handler))
         .layer(middleware::from_fn(logger::middleware_fn))
         .layer(Extension(Arc::new(handlers)))
         .layer(CorsLayer::permissive());
@@ -54,7 +70,36 @@ pub fn spawn_json_rpc(preferred_address: SocketAddr, handlers: JsonRpcHandlers)
     Ok(listen_addr)
 }
 
-async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: JsonRpcExtractor) -> JrpcResult {
+async fn default_network_handler(
+    Extension(handlers): Extension<Arc<NetworkHandlers>>,
+    value: JsonRpcExtractor,
+) -> JrpcResult {
+    handle_network_request(&handlers, DEFAULT_NETWORK_NAME, value).await
+}
+
+async fn network_handler(
+    Path(network): Path<String>,
+    Extension(handlers): Extension<Arc<NetworkHandlers>>,
+    value: JsonRpcExtractor,
+) -> JrpcResult {
+    handle_network_request(&handlers, &network, value).await
+}
+
+async fn handle_network_request(handlers: &NetworkHandlers, network: &str, value: JsonRpcExtractor) -> JrpcResult {
+    let Some(handlers) = handlers.get(network) else {
+        return Err(JsonRpcResponse::error(
+            value.get_answer_id(),
+            JsonRpcError::new(
+                JsonRpcErrorReason::InvalidRequest,
+                format!("Network '{}' is not indexed by this node", network),
+                Value::Null,
+            ),
+        ));
+    };
+    handler(handlers, value).await
+}
+
+async fn handler(handlers: &JsonRpcHandlers, value: JsonRpcExtractor) -> JrpcResult {
     info!(target: LOG_TARGET, "🌐 JSON-RPC request: {}", value.method);
     debug!(target: LOG_TARGET, "🌐 JSON-RPC body: {:?}", value);
     match value.method.as_str() {
@@ -70,12 +115,15 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_non_fungible_collections" => handlers.get_non_fungible_collections(value).await,
         "get_non_fungible_count" => handlers.get_non_fungible_count(value).await,
         "get_non_fungibles" => handlers.get_non_fungibles(value).await,
+        "get_transfers" => handlers.get_transfers(value).await,
         "submit_transaction" => handlers.submit_transaction(value).await,
+        "dry_run_transaction" => handlers.dry_run_transaction(value).await,
         "get_transaction_result" => handlers.get_transaction_result(value).await,
         "get_substate_transactions" => handlers.get_substate_transactions(value).await,
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
         "get_template_definition" => handlers.get_template_definition(value).await,
         "list_templates" => handlers.list_templates(value).await,
+        "resolve_name" => handlers.resolve_name(value).await,
         method => Ok(value.method_not_found(method)),
     }
 }
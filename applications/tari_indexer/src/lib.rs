@@ -36,38 +36,36 @@ mod event_data;
 mod event_manager;
 mod event_scanner;
 mod json_rpc;
+mod substate_hot_cache;
 mod substate_manager;
 mod substate_storage_sqlite;
 mod transaction_manager;
 
-use std::{fs, sync::Arc};
+use std::{collections::HashMap, fs, sync::Arc};
 
 use event_scanner::{EventFilter, EventScanner};
 use http_ui::server::run_http_ui_server;
 use log::*;
 use substate_manager::SubstateManager;
 use tari_base_node_client::grpc::GrpcBaseNodeClient;
-use tari_common::{
-    configuration::bootstrap::{grpc_default_port, ApplicationType},
-    exit_codes::{ExitCode, ExitError},
-};
+use tari_common::exit_codes::{ExitCode, ExitError};
 use tari_consensus::consensus_constants::ConsensusConstants;
 use tari_dan_app_utilities::{keypair::setup_keypair_prompt, substate_file_cache::SubstateFileCache};
-use tari_dan_storage::global::DbFactory;
-use tari_dan_storage_sqlite::SqliteDbFactory;
+use tari_dan_common_types::NumPreshards;
 use tari_epoch_manager::{EpochManagerEvent, EpochManagerReader};
 use tari_indexer_lib::substate_scanner::SubstateScanner;
 use tari_networking::NetworkingService;
 use tari_shutdown::ShutdownSignal;
-use tokio::{task, time};
+use tokio::{sync::mpsc, task, time};
 
 use crate::{
     bootstrap::{spawn_services, Services},
-    config::ApplicationConfig,
+    config::{ApplicationConfig, IndexerNetworkConfig, DEFAULT_NETWORK_NAME},
     dry_run::processor::DryRunTransactionProcessor,
     event_manager::EventManager,
     graphql::server::run_graphql,
     json_rpc::{spawn_json_rpc, JsonRpcHandlers},
+    substate_hot_cache::SubstateHotCache,
     transaction_manager::TransactionManager,
 };
 
@@ -78,74 +76,131 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
     info!(target: LOG_TARGET, "Starting indexer node on network {}", config.network);
     let keypair = setup_keypair_prompt(&config.indexer.identity_file, true)?;
 
-    let db_factory = SqliteDbFactory::new(config.indexer.data_dir.clone());
-    db_factory
-        .migrate()
-        .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
-    let global_db = db_factory
-        .get_or_create_global_db()
-        .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
-
-    let base_node_client = create_base_layer_clients(&config).await?;
-    let services: Services = spawn_services(
-        &config,
-        shutdown_signal.clone(),
-        keypair.clone(),
-        global_db,
-        ConsensusConstants::devnet(), // TODO: change this eventually
-    )
-    .await?;
-
-    let mut epoch_manager_events = services.epoch_manager.subscribe();
-
-    let substate_cache_dir = config.common.base_path.join("substate_cache");
-    let substate_cache = SubstateFileCache::new(substate_cache_dir)
-        .map_err(|e| ExitError::new(ExitCode::ConfigError, format!("Substate cache error: {}", e)))?;
-
-    let dan_layer_scanner = Arc::new(SubstateScanner::new(
-        services.epoch_manager.clone(),
-        services.validator_node_client_factory.clone(),
-        substate_cache,
-    ));
-
-    let substate_manager = Arc::new(SubstateManager::new(
-        dan_layer_scanner.clone(),
-        services.substate_store.clone(),
-    ));
-    let transaction_manager = TransactionManager::new(
-        services.epoch_manager.clone(),
-        services.validator_node_client_factory.clone(),
-        dan_layer_scanner.clone(),
-    );
-
-    // dry run
-    let dry_run_transaction_processor = DryRunTransactionProcessor::new(
-        services.epoch_manager.clone(),
-        services.validator_node_client_factory.clone(),
-        dan_layer_scanner.clone(),
-        services.template_manager.clone(),
-        config.network,
-    );
+    let mut consensus_constants = ConsensusConstants::devnet(); // TODO: change this eventually
+    if let Some(num_preshards) = config.indexer.num_preshards {
+        consensus_constants.num_preshards =
+            NumPreshards::try_from(num_preshards).map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
+    }
+
+    let services: Services =
+        spawn_services(&config, shutdown_signal.clone(), keypair.clone(), consensus_constants).await?;
+
+    // Forward the epoch manager events of all networks into a single channel
+    let (epoch_events_tx, mut epoch_manager_events) = mpsc::unbounded_channel();
+    for network in &services.networks {
+        let mut events = network.epoch_manager.subscribe();
+        let epoch_events_tx = epoch_events_tx.clone();
+        task::spawn(async move {
+            while let Ok(event) = events.recv().await {
+                if epoch_events_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    let mut jrpc_handlers = HashMap::new();
+    let mut graphql_networks = Vec::new();
+    let mut event_scanners = Vec::new();
+    for network in &services.networks {
+        let network_name = network.name().to_string();
+        let substate_cache_dir = if network_name == DEFAULT_NETWORK_NAME {
+            config.common.base_path.join("substate_cache")
+        } else {
+            config.common.base_path.join(format!("substate_cache_{}", network_name))
+        };
+        let substate_cache = SubstateFileCache::new(substate_cache_dir)
+            .map_err(|e| ExitError::new(ExitCode::ConfigError, format!("Substate cache error: {}", e)))?;
+
+        let dan_layer_scanner = Arc::new(SubstateScanner::new(
+            network.epoch_manager.clone(),
+            services.validator_node_client_factory.clone(),
+            substate_cache,
+        ));
+
+        // Substates ingested by the event scanner invalidate the cached latest versions, and substates that are not
+        // ingested expire from the latest version index after one scanning interval
+        let substate_hot_cache = SubstateHotCache::new(
+            config.indexer.substate_cache_capacity,
+            config.indexer.dan_layer_scanning_internal,
+        );
+
+        let substate_manager = Arc::new(SubstateManager::new(
+            dan_layer_scanner.clone(),
+            network.substate_store.clone(),
+            substate_hot_cache.clone(),
+        ));
+
+        if config.indexer.json_rpc_address.is_some() {
+            let transaction_manager = TransactionManager::new(
+                network.epoch_manager.clone(),
+                services.validator_node_client_factory.clone(),
+                dan_layer_scanner.clone(),
+            );
+
+            // dry run
+            let dry_run_transaction_processor = DryRunTransactionProcessor::new(
+                network.epoch_manager.clone(),
+                services.validator_node_client_factory.clone(),
+                dan_layer_scanner.clone(),
+                network.template_manager.clone(),
+                config.network,
+            );
+
+            let base_node_client = create_base_layer_client(&config, &network.config).await?;
+            let consensus_constants = network
+                .epoch_manager
+                .get_base_layer_consensus_constants()
+                .await
+                .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
+            let handlers = JsonRpcHandlers::new(
+                consensus_constants,
+                &services,
+                network,
+                base_node_client,
+                substate_manager.clone(),
+                transaction_manager,
+                dry_run_transaction_processor,
+            );
+            jrpc_handlers.insert(network_name.clone(), handlers);
+        }
+
+        // Run the event manager
+        let event_manager = Arc::new(EventManager::new(
+            network.substate_store.clone(),
+            dan_layer_scanner.clone(),
+        ));
+        graphql_networks.push((network_name.clone(), substate_manager, event_manager));
+
+        // Run the event scanner
+        let event_filters: Vec<EventFilter> = network
+            .config
+            .event_filters
+            .clone()
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()
+            .map_err(|e| {
+                ExitError::new(
+                    ExitCode::ConfigError,
+                    format!("Invalid event filters for network {}: {}", network_name, e),
+                )
+            })?;
+        let event_scanner = EventScanner::new(
+            network.epoch_manager.clone(),
+            services.validator_node_client_factory.clone(),
+            network.substate_store.clone(),
+            substate_hot_cache,
+            event_filters,
+        );
+        event_scanners.push((network_name, event_scanner));
+    }
 
     // Run the JSON-RPC API
     let jrpc_address = config.indexer.json_rpc_address;
     if let Some(jrpc_address) = jrpc_address {
         info!(target: LOG_TARGET, "🌐 Started JSON-RPC server on {}", jrpc_address);
-        let consensus_constants = services
-            .epoch_manager
-            .get_base_layer_consensus_constants()
-            .await
-            .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
-        let handlers = JsonRpcHandlers::new(
-            consensus_constants,
-            &services,
-            base_node_client,
-            substate_manager.clone(),
-            transaction_manager,
-            services.template_manager.clone(),
-            dry_run_transaction_processor,
-        );
-        let jrpc_address = spawn_json_rpc(jrpc_address, handlers)?;
+        let jrpc_address = spawn_json_rpc(jrpc_address, jrpc_handlers)?;
         // Run the http ui
         if let Some(address) = config.indexer.http_ui_address {
             task::spawn(run_http_ui_server(
@@ -158,32 +213,11 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
         }
     }
 
-    // Run the event manager
-    let event_manager = Arc::new(EventManager::new(
-        services.substate_store.clone(),
-        dan_layer_scanner.clone(),
-    ));
-
-    // Run the event scanner
-    let event_filters: Vec<EventFilter> = config
-        .indexer
-        .event_filters
-        .into_iter()
-        .map(TryInto::try_into)
-        .collect::<Result<_, _>>()
-        .map_err(|e| ExitError::new(ExitCode::ConfigError, format!("Invalid event filters: {}", e)))?;
-    let event_scanner = EventScanner::new(
-        services.epoch_manager.clone(),
-        services.validator_node_client_factory.clone(),
-        services.substate_store.clone(),
-        event_filters,
-    );
-
     // Run the GraphQL API
     let graphql_address = config.indexer.graphql_address;
     if let Some(address) = graphql_address {
         info!(target: LOG_TARGET, "🌐 Started GraphQL server on {}", address);
-        task::spawn(run_graphql(address, substate_manager.clone(), event_manager.clone()));
+        task::spawn(run_graphql(address, graphql_networks));
     }
 
     // Create pid to allow watchers to know that the process has started
@@ -194,14 +228,16 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
         tokio::select! {
             // keep scanning the dan layer for new events
             _ = time::sleep(config.indexer.dan_layer_scanning_internal) => {
-                match event_scanner.scan_events().await {
-                    Ok(0) => {},
-                    Ok(cnt) => info!(target: LOG_TARGET, "Scanned {} events(s) successfully", cnt),
-                    Err(e) =>  error!(target: LOG_TARGET, "Event auto-scan failed: {}", e),
-                };
+                for (network_name, event_scanner) in &event_scanners {
+                    match event_scanner.scan_events().await {
+                        Ok(0) => {},
+                        Ok(cnt) => info!(target: LOG_TARGET, "Scanned {} events(s) successfully on network {}", cnt, network_name),
+                        Err(e) =>  error!(target: LOG_TARGET, "Event auto-scan failed on network {}: {}", network_name, e),
+                    };
+                }
             },
 
-            Ok(event) = epoch_manager_events.recv() => {
+            Some(event) = epoch_manager_events.recv() => {
                 if let Err(err) = handle_epoch_manager_event(&services, event).await {
                     error!(target: LOG_TARGET, "Error handling epoch manager event: {}", err);
                 }
@@ -220,23 +256,24 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
 }
 
 async fn handle_epoch_manager_event(services: &Services, event: EpochManagerEvent) -> Result<(), anyhow::Error> {
-    let EpochManagerEvent::EpochChanged { epoch, .. } = event;
-    let all_vns = services.epoch_manager.get_all_validator_nodes(epoch).await?;
-    services
-        .networking
-        .set_want_peers(all_vns.into_iter().map(|vn| vn.address.as_peer_id()))
-        .await?;
+    let EpochManagerEvent::EpochChanged { .. } = event;
+    // We want to be connected to the validators of every network, not only the one that changed epoch
+    let mut want_peers = Vec::new();
+    for network in &services.networks {
+        let epoch = network.epoch_manager.current_epoch().await?;
+        let all_vns = network.epoch_manager.get_all_validator_nodes(epoch).await?;
+        want_peers.extend(all_vns.into_iter().map(|vn| vn.address.as_peer_id()));
+    }
+    services.networking.set_want_peers(want_peers).await?;
 
     Ok(())
 }
 
-async fn create_base_layer_clients(config: &ApplicationConfig) -> Result<GrpcBaseNodeClient, ExitError> {
-    let url = config.indexer.base_node_grpc_url.clone().unwrap_or_else(|| {
-        let port = grpc_default_port(ApplicationType::BaseNode, config.network);
-        format!("http://127.0.0.1:{port}")
-            .parse()
-            .expect("Default base node GRPC URL is malformed")
-    });
+async fn create_base_layer_client(
+    config: &ApplicationConfig,
+    network_config: &IndexerNetworkConfig,
+) -> Result<GrpcBaseNodeClient, ExitError> {
+    let url = network_config.base_node_grpc_url_or_default(config.indexer.base_node_grpc_url.as_ref(), config.network);
     GrpcBaseNodeClient::connect(url)
         .await
         .map_err(|err| ExitError::new(ExitCode::ConfigError, format!("Could not connect to base node {}", err)))
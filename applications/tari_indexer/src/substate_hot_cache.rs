@@ -0,0 +1,164 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{sync::Arc, time::Duration};
+
+use tari_engine_types::substate::SubstateId;
+
+use crate::substate_manager::SubstateResponse;
+
+/// In-memory LRU cache for frequently requested substates, sitting in front of the substate store and the DAN layer
+/// scanner.
+///
+/// Entries are keyed by substate id and version. A specific version of a substate never changes, so those entries only
+/// leave the cache by eviction. Requests for the latest version go through a separate id -> version index, which is
+/// invalidated when a newer version of the substate is ingested. Substates that are not ingested by the event scanner
+/// are never invalidated, so the index entries also expire after `latest_version_ttl`.
+#[derive(Clone)]
+pub struct SubstateHotCache {
+    substates: mini_moka::sync::Cache<(SubstateId, u32), Arc<SubstateResponse>>,
+    latest_versions: mini_moka::sync::Cache<SubstateId, u32>,
+}
+
+impl SubstateHotCache {
+    pub fn new(capacity: u64, latest_version_ttl: Duration) -> Self {
+        Self {
+            substates: mini_moka::sync::Cache::new(capacity),
+            latest_versions: mini_moka::sync::Cache::builder()
+                .max_capacity(capacity)
+                .time_to_live(latest_version_ttl)
+                .build(),
+        }
+    }
+
+    /// Returns the cached substate, or the latest known version of the substate if no version is given
+    pub fn get(&self, substate_id: &SubstateId, version: Option<u32>) -> Option<Arc<SubstateResponse>> {
+        let version = match version {
+            Some(version) => version,
+            None => self.latest_versions.get(substate_id)?,
+        };
+        self.substates.get(&(substate_id.clone(), version))
+    }
+
+    /// Caches a substate. If `is_latest` is true, the substate is also returned for requests without a version.
+    pub fn insert(&self, substate: SubstateResponse, is_latest: bool) {
+        let id = substate.address.clone();
+        let version = substate.version;
+        self.substates.insert((id.clone(), version), Arc::new(substate));
+        if is_latest {
+            match self.latest_versions.get(&id) {
+                // never go back to an older version if a newer one was ingested in the meantime
+                Some(latest) if latest > version => {},
+                _ => self.latest_versions.insert(id, version),
+            }
+        }
+    }
+
+    /// Invalidates the cached latest version of a substate if it is older than the ingested `version`
+    pub fn invalidate_older_than(&self, substate_id: &SubstateId, version: u32) {
+        if let Some(latest) = self.latest_versions.get(substate_id) {
+            if latest < version {
+                self.latest_versions.invalidate(substate_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mini_moka::sync::ConcurrentCacheExt;
+    use tari_engine_types::{fee_claim::FeeClaim, substate::Substate};
+    use tari_template_lib::models::{Amount, ComponentAddress, ObjectKey};
+    use tari_transaction::TransactionId;
+
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn substate_id(seed: u8) -> SubstateId {
+        ComponentAddress::from_array([seed; ObjectKey::LENGTH]).into()
+    }
+
+    fn substate_response(id: &SubstateId, version: u32) -> SubstateResponse {
+        SubstateResponse {
+            address: id.clone(),
+            version,
+            substate: Substate::new(version, FeeClaim {
+                epoch: u64::from(version),
+                validator_public_key: Default::default(),
+                amount: Amount(0),
+            }),
+            created_by_transaction: TransactionId::default(),
+        }
+    }
+
+    #[test]
+    fn it_returns_cached_substates() {
+        let cache = SubstateHotCache::new(10, TTL);
+        let id = substate_id(1);
+        assert!(cache.get(&id, None).is_none());
+
+        cache.insert(substate_response(&id, 0), false);
+        assert_eq!(cache.get(&id, Some(0)).unwrap().version, 0);
+        // Not cached as the latest version
+        assert!(cache.get(&id, None).is_none());
+
+        cache.insert(substate_response(&id, 1), true);
+        assert_eq!(cache.get(&id, Some(1)).unwrap().version, 1);
+        assert_eq!(cache.get(&id, None).unwrap().version, 1);
+        assert!(cache.get(&id, Some(2)).is_none());
+        assert!(cache.get(&substate_id(2), None).is_none());
+    }
+
+    #[test]
+    fn it_does_not_go_back_to_an_older_latest_version() {
+        let cache = SubstateHotCache::new(10, TTL);
+        let id = substate_id(1);
+
+        cache.insert(substate_response(&id, 2), true);
+        cache.insert(substate_response(&id, 1), true);
+        assert_eq!(cache.get(&id, None).unwrap().version, 2);
+        assert_eq!(cache.get(&id, Some(1)).unwrap().version, 1);
+    }
+
+    #[test]
+    fn it_invalidates_the_latest_version_when_a_newer_version_is_ingested() {
+        let cache = SubstateHotCache::new(10, TTL);
+        let id = substate_id(1);
+        cache.insert(substate_response(&id, 1), true);
+
+        // Ingesting the same or an older version does not invalidate the latest version
+        cache.invalidate_older_than(&id, 1);
+        assert_eq!(cache.get(&id, None).unwrap().version, 1);
+
+        cache.invalidate_older_than(&id, 2);
+        assert!(cache.get(&id, None).is_none());
+        // Specific versions never change so they remain cached
+        assert_eq!(cache.get(&id, Some(1)).unwrap().version, 1);
+    }
+
+    #[test]
+    fn it_expires_the_latest_version_after_the_ttl() {
+        let cache = SubstateHotCache::new(10, Duration::from_millis(10));
+        let id = substate_id(1);
+        cache.insert(substate_response(&id, 1), true);
+        assert!(cache.get(&id, None).is_some());
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(cache.get(&id, None).is_none());
+        assert!(cache.get(&id, Some(1)).is_some());
+    }
+
+    #[test]
+    fn it_bounds_the_number_of_cached_substates() {
+        let cache = SubstateHotCache::new(10, TTL);
+        for seed in 0..100 {
+            cache.insert(substate_response(&substate_id(seed), 0), true);
+        }
+
+        cache.substates.sync();
+        cache.latest_versions.sync();
+        assert!(cache.substates.entry_count() <= 10);
+        assert!(cache.latest_versions.entry_count() <= 10);
+    }
+}
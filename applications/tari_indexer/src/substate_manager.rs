@@ -28,19 +28,22 @@ use tari_dan_app_utilities::substate_file_cache::SubstateFileCache;
 use tari_dan_common_types::{substate_type::SubstateType, PeerAddress};
 use tari_engine_types::substate::{Substate, SubstateId};
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_indexer_client::types::ListSubstateItem;
+use tari_indexer_client::types::{ListSubstateItem, TransferItem};
 use tari_indexer_lib::{substate_scanner::SubstateScanner, NonFungibleSubstate};
-use tari_template_lib::models::TemplateAddress;
+use tari_template_lib::models::{ComponentAddress, ResourceAddress, TemplateAddress};
 use tari_transaction::TransactionId;
 use tari_validator_node_rpc::client::{SubstateResult, TariValidatorNodeRpcClientFactory};
 
-use crate::substate_storage_sqlite::sqlite_substate_store_factory::{
-    SqliteSubstateStore,
-    SubstateStore,
-    SubstateStoreReadTransaction,
+use crate::{
+    substate_hot_cache::SubstateHotCache,
+    substate_storage_sqlite::sqlite_substate_store_factory::{
+        SqliteSubstateStore,
+        SubstateStore,
+        SubstateStoreReadTransaction,
+    },
 };
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubstateResponse {
     pub address: SubstateId,
     pub version: u32,
@@ -75,6 +78,7 @@ pub struct SubstateManager {
     substate_scanner:
         Arc<SubstateScanner<EpochManagerHandle<PeerAddress>, TariValidatorNodeRpcClientFactory, SubstateFileCache>>,
     substate_store: SqliteSubstateStore,
+    hot_cache: SubstateHotCache,
 }
 
 impl SubstateManager {
@@ -83,10 +87,12 @@ impl SubstateManager {
             SubstateScanner<EpochManagerHandle<PeerAddress>, TariValidatorNodeRpcClientFactory, SubstateFileCache>,
         >,
         substate_store: SqliteSubstateStore,
+        hot_cache: SubstateHotCache,
     ) -> Self {
         Self {
             substate_scanner: dan_layer_scanner,
             substate_store,
+            hot_cache,
         }
     }
 
@@ -107,9 +113,14 @@ impl SubstateManager {
         substate_address: &SubstateId,
         version: Option<u32>,
     ) -> Result<Option<SubstateResponse>, anyhow::Error> {
+        if let Some(substate) = self.hot_cache.get(substate_address, version) {
+            return Ok(Some(substate.as_ref().clone()));
+        }
+
         // we store the latest version of the substates related to the events
         // so we will return the substate directly from database if it's there
         if let Some(substate) = self.get_substate_from_db(substate_address, version).await? {
+            self.hot_cache.insert(substate.clone(), version.is_none());
             return Ok(Some(substate));
         }
 
@@ -120,12 +131,16 @@ impl SubstateManager {
                 id,
                 substate,
                 created_by_tx,
-            } => Ok(Some(SubstateResponse {
-                address: id,
-                version: substate.version(),
-                substate,
-                created_by_transaction: created_by_tx,
-            })),
+            } => {
+                let substate = SubstateResponse {
+                    address: id,
+                    version: substate.version(),
+                    substate,
+                    created_by_transaction: created_by_tx,
+                };
+                self.hot_cache.insert(substate.clone(), version.is_none());
+                Ok(Some(substate))
+            },
             _ => Ok(None),
         }
     }
@@ -196,4 +211,16 @@ impl SubstateManager {
 
         Ok(non_fungibles)
     }
+
+    pub async fn get_transfers(
+        &self,
+        resource_address: Option<ResourceAddress>,
+        account: Option<ComponentAddress>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<TransferItem>, anyhow::Error> {
+        let mut tx = self.substate_store.create_read_tx()?;
+        let rows = tx.get_transfers(resource_address, account, offset, limit)?;
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
 }
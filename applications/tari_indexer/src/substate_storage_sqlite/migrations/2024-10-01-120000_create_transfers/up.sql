@@ -0,0 +1,19 @@
+-- Fungible transfers derived from the account template's withdraw/deposit events
+create table transfers
+(
+    id               integer not NULL primary key AUTOINCREMENT,
+    resource_address text    not NULL,
+    -- NULL if the funds did not come from an account (e.g. minted or taken from a faucet)
+    from_account     text    NULL,
+    -- NULL if the funds did not end up in an account (e.g. burnt or used to pay fees)
+    to_account       text    NULL,
+    amount           bigint  not NULL,
+    tx_hash          text    not NULL,
+    timestamp        bigint  not NULL
+);
+
+-- DB indexes for faster retrieval of transfer feeds
+create index transfers_resource_address on transfers (resource_address);
+create index transfers_from_account on transfers (from_account);
+create index transfers_to_account on transfers (to_account);
+create index transfers_tx_hash on transfers (tx_hash);
@@ -0,0 +1,15 @@
+-- Events are identified by their content, so the same event scanned from different validators is only stored once
+alter table events
+    add column payload_hash text null;
+
+-- Remove the duplicated events that were already stored, keeping the first one
+delete
+from event_payloads
+where event_id not in (select min(id) from events group by tx_hash, topic, payload, version);
+
+delete
+from events
+where id not in (select min(id) from events group by tx_hash, topic, payload, version);
+
+-- The payload hash of the remaining events is backfilled after this migration runs (see
+-- `backfill_event_payload_hashes`), before the unique index is created by the next migration
@@ -0,0 +1 @@
+drop index unique_events_content;
@@ -0,0 +1,2 @@
+-- All events have a payload hash at this point, so the index also covers events stored before deduplication
+create unique index unique_events_content on events (tx_hash, topic, payload_hash, version);
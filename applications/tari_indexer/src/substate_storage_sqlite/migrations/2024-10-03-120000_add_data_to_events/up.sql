@@ -0,0 +1,3 @@
+-- The typed (CBOR) payload of events, encoded as JSON
+alter table events
+    add column data text null;
@@ -25,7 +25,11 @@ use std::{convert::TryFrom, str::FromStr};
 
 use diesel::sql_types::{Integer, Nullable, Text};
 use serde::{Deserialize, Serialize};
-use tari_engine_types::substate::SubstateId;
+use tari_bor::json_encoding::CborValueJsonDeserializeWrapper;
+use tari_engine_types::{
+    hashing::{hasher32, EngineHashDomainLabel},
+    substate::SubstateId,
+};
 use tari_template_lib::Hash;
 
 use crate::substate_storage_sqlite::schema::*;
@@ -41,6 +45,8 @@ pub struct Event {
     pub version: i32,
     pub substate_id: Option<String>,
     pub timestamp: i64,
+    pub payload_hash: Option<String>,
+    pub data: Option<String>,
 }
 
 #[derive(Debug, Clone, Insertable, AsChangeset)]
@@ -54,6 +60,44 @@ pub struct NewEvent {
     pub version: i32,
     pub substate_id: Option<String>,
     pub timestamp: i64,
+    pub payload_hash: String,
+    pub data: Option<String>,
+}
+
+impl NewEvent {
+    pub fn new(
+        template_address: String,
+        tx_hash: String,
+        topic: String,
+        payload: String,
+        version: i32,
+        substate_id: Option<String>,
+        timestamp: i64,
+        data: Option<String>,
+    ) -> Self {
+        let payload_hash = hash_event_payload(&payload, data.as_deref());
+        Self {
+            template_address,
+            tx_hash,
+            topic,
+            payload,
+            version,
+            substate_id,
+            timestamp,
+            payload_hash,
+            data,
+        }
+    }
+}
+
+/// Returns the hash that identifies the content of an event, together with its transaction hash, topic and version
+pub fn hash_event_payload(payload: &str, data: Option<&str>) -> String {
+    let mut hasher = hasher32(EngineHashDomainLabel::EventPayload).chain(payload);
+    // Events without a typed payload keep the same hash as before typed payloads were introduced
+    if let Some(data) = data {
+        hasher = hasher.chain(data);
+    }
+    hasher.result().to_string()
 }
 
 #[derive(Debug, Clone, Insertable, AsChangeset)]
@@ -79,6 +123,8 @@ pub struct EventData {
     pub version: i32,
     #[diesel(sql_type = Nullable<Text>)]
     pub substate_id: Option<String>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub data: Option<String>,
 }
 
 impl TryFrom<EventData> for crate::graphql::model::events::Event {
@@ -98,6 +144,7 @@ impl TryFrom<EventData> for crate::graphql::model::events::Event {
             template_address,
             tx_hash,
             payload,
+            data: event_data.data,
             topic: event_data.topic,
         })
     }
@@ -116,13 +163,14 @@ impl TryFrom<EventData> for tari_engine_types::events::Event {
         let tx_hash = Hash::from_hex(&event_data.tx_hash)?;
         let payload = serde_json::from_str(event_data.payload.as_str())?;
 
-        Ok(Self::new(
-            substate_id,
-            template_address,
-            tx_hash,
-            event_data.topic,
-            payload,
-        ))
+        let event = Self::new(substate_id, template_address, tx_hash, event_data.topic, payload);
+        match event_data.data {
+            Some(data) => {
+                let data = serde_json::from_str::<CborValueJsonDeserializeWrapper>(&data)?;
+                Ok(event.with_data(data.0))
+            },
+            None => Ok(event),
+        }
     }
 }
 
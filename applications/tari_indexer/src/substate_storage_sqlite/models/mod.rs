@@ -23,3 +23,4 @@
 pub mod events;
 pub mod non_fungible_index;
 pub mod substate;
+pub mod transfers;
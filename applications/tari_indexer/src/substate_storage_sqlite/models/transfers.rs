@@ -0,0 +1,68 @@
+//   Copyright 2024. The Tari Project
+//
+//   Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+//   following conditions are met:
+//
+//   1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+//   disclaimer.
+//
+//   2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+//   following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+//   3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+//   products derived from this software without specific prior written permission.
+//
+//   THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+//   INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+//   DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+//   SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+//   SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+//   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+//   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+use std::{convert::TryFrom, str::FromStr};
+
+use tari_indexer_client::types::TransferItem;
+use tari_template_lib::models::{Amount, ComponentAddress, ResourceAddress};
+use tari_transaction::TransactionId;
+
+use crate::substate_storage_sqlite::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+#[diesel(table_name = transfers)]
+pub struct Transfer {
+    pub id: i32,
+    pub resource_address: String,
+    pub from_account: Option<String>,
+    pub to_account: Option<String>,
+    pub amount: i64,
+    pub tx_hash: String,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = transfers)]
+#[diesel(treat_none_as_null = true)]
+pub struct NewTransfer {
+    pub resource_address: String,
+    pub from_account: Option<String>,
+    pub to_account: Option<String>,
+    pub amount: i64,
+    pub tx_hash: String,
+    pub timestamp: i64,
+}
+
+impl TryFrom<Transfer> for TransferItem {
+    type Error = anyhow::Error;
+
+    fn try_from(row: Transfer) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_address: ResourceAddress::from_str(&row.resource_address)?,
+            from: row.from_account.map(|s| ComponentAddress::from_str(&s)).transpose()?,
+            to: row.to_account.map(|s| ComponentAddress::from_str(&s)).transpose()?,
+            amount: Amount::new(row.amount),
+            transaction_id: TransactionId::from_hex(&row.tx_hash)?,
+            timestamp: u64::try_from(row.timestamp)?,
+        })
+    }
+}
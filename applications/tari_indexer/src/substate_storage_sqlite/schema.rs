@@ -19,6 +19,8 @@ diesel::table! {
         version -> Integer,
         substate_id -> Nullable<Text>,
         timestamp -> BigInt,
+        payload_hash -> Nullable<Text>,
+        data -> Nullable<Text>,
     }
 }
 
@@ -53,6 +55,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    transfers (id) {
+        id -> Integer,
+        resource_address -> Text,
+        from_account -> Nullable<Text>,
+        to_account -> Nullable<Text>,
+        amount -> BigInt,
+        tx_hash -> Text,
+        timestamp -> BigInt,
+    }
+}
+
 diesel::joinable!(event_payloads -> events (event_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
@@ -61,4 +75,5 @@ diesel::allow_tables_to_appear_in_same_query!(
     non_fungible_indexes,
     scanned_block_ids,
     substates,
+    transfers,
 );
@@ -44,17 +44,18 @@ use tari_dan_storage::{consensus_models::BlockId, StorageError};
 use tari_dan_storage_sqlite::{error::SqliteStorageError, SqliteTransaction};
 use tari_engine_types::substate::SubstateId;
 use tari_indexer_client::types::ListSubstateItem;
-use tari_template_lib::models::TemplateAddress;
+use tari_template_lib::models::{ComponentAddress, ResourceAddress, TemplateAddress};
 use tari_transaction::TransactionId;
 use thiserror::Error;
 
 use super::models::{
-    events::{EventData, NewEvent, NewScannedBlockId},
+    events::{hash_event_payload, EventData, NewEvent, NewScannedBlockId},
     non_fungible_index::{IndexedNftSubstate, NewNonFungibleIndex},
 };
 use crate::substate_storage_sqlite::models::{
     events::{Event, NewEventPayloadField, ScannedBlockId},
     substate::{NewSubstate, Substate},
+    transfers::{NewTransfer, Transfer},
 };
 
 const LOG_TARGET: &str = "tari::indexer::substate_storage_sqlite";
@@ -71,8 +72,7 @@ impl SqliteSubstateStore {
         let database_url = path.to_str().expect("database_url utf-8 error").to_string();
         let mut connection = SqliteConnection::establish(&database_url).map_err(SqliteStorageError::from)?;
 
-        pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./src/substate_storage_sqlite/migrations");
-        if let Err(err) = connection.run_pending_migrations(MIGRATIONS) {
+        if let Err(err) = Self::run_migrations(&mut connection) {
             log::error!(target: LOG_TARGET, "Error running migrations: {}", err);
         }
         sql_query("PRAGMA foreign_keys = ON;")
@@ -87,6 +87,51 @@ impl SqliteSubstateStore {
         })
     }
 
+    fn run_migrations(connection: &mut SqliteConnection) -> diesel::migration::Result<()> {
+        pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./src/substate_storage_sqlite/migrations");
+        // The event deduplication migration leaves the payload hash of existing events empty because it cannot be
+        // computed in SQL. It is backfilled before the following migration creates the unique index on it.
+        const DEDUPLICATE_EVENTS_MIGRATION_VERSION: &str = "20241002120000";
+
+        for migration in connection.pending_migrations(MIGRATIONS)? {
+            connection.run_migration(&*migration)?;
+            if migration.name().version().to_string() == DEDUPLICATE_EVENTS_MIGRATION_VERSION {
+                Self::backfill_event_payload_hashes(connection)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn backfill_event_payload_hashes(connection: &mut SqliteConnection) -> Result<(), StorageError> {
+        use crate::substate_storage_sqlite::schema::events;
+
+        connection
+            .transaction(|conn| {
+                // Only select columns that exist at this point of the migrations
+                let existing_events = events::table
+                    .select((events::id, events::payload))
+                    .filter(events::payload_hash.is_null())
+                    .load::<(i32, String)>(conn)?;
+
+                for (id, payload) in &existing_events {
+                    diesel::update(events::table)
+                        .filter(events::id.eq(id))
+                        .set(events::payload_hash.eq(hash_event_payload(payload, None)))
+                        .execute(conn)?;
+                }
+
+                info!(
+                    target: LOG_TARGET,
+                    "Backfilled the payload hash of {} existing events",
+                    existing_events.len()
+                );
+                Ok::<_, diesel::result::Error>(())
+            })
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("backfill_event_payload_hashes: {}", e),
+            })
+    }
+
     pub fn find_by_address(address: String, conn: &mut SqliteConnection) -> Result<Option<Substate>, StorageError> {
         use crate::substate_storage_sqlite::schema::substates;
 
@@ -229,13 +274,20 @@ pub trait SubstateStoreReadTransaction {
         offset: u32,
         limit: u32,
     ) -> Result<Vec<Event>, StorageError>;
-    fn event_exists(&mut self, event: NewEvent) -> Result<bool, StorageError>;
     fn get_oldest_scanned_epoch(&mut self) -> Result<Option<Epoch>, StorageError>;
     fn get_last_scanned_block_id(
         &mut self,
         epoch: Epoch,
         shard_group: ShardGroup,
     ) -> Result<Option<BlockId>, StorageError>;
+    fn get_transfers(
+        &mut self,
+        resource_address_filter: Option<ResourceAddress>,
+        account_filter: Option<ComponentAddress>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Transfer>, StorageError>;
+    fn transfers_exist_for_transaction(&mut self, tx_id: &TransactionId) -> Result<bool, StorageError>;
 }
 
 impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
@@ -433,7 +485,8 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
             "Querying substate scanner database: get_events_for_transaction with tx_hash = {}", tx_id
         );
         let res = sql_query(
-            "SELECT substate_id, template_address, tx_hash, topic, payload, version FROM events WHERE tx_hash = ?",
+            "SELECT substate_id, template_address, tx_hash, topic, payload, version, data FROM events WHERE tx_hash = \
+             ?",
         )
         .bind::<Text, _>(tx_id.to_string())
         .get_results::<EventData>(self.connection())
@@ -486,8 +539,8 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
             version
         );
         let res = sql_query(
-            "SELECT substate_id, template_address, tx_hash, topic, payload FROM events WHERE substate_id = ? AND \
-             version = ?",
+            "SELECT substate_id, template_address, tx_hash, topic, payload, version, data FROM events WHERE \
+             substate_id = ? AND version = ?",
         )
         .bind::<Nullable<Text>, _>(Some(substate_id.to_string()))
         .bind::<Integer, _>(version as i32)
@@ -513,7 +566,7 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
             payload_value
         );
         let res = sql_query(
-            "SELECT substate_id, template_address, tx_hash, topic, payload, version FROM events e INNER JOIN \
+            "SELECT substate_id, template_address, tx_hash, topic, payload, version, data FROM events e INNER JOIN \
              event_payloads p ON p.event_id = e.id WHERE p.payload_key = ? AND p.payload_value = ? LIMIT ?,?",
         )
         .bind::<Text, _>(payload_key)
@@ -530,7 +583,8 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
 
     fn get_all_events(&mut self, substate_id: &SubstateId) -> Result<Vec<EventData>, StorageError> {
         let res = sql_query(
-            "SELECT substate_id, template_address, tx_hash, topic, payload, version FROM events WHERE substate_id = ?",
+            "SELECT substate_id, template_address, tx_hash, topic, payload, version, data FROM events WHERE \
+             substate_id = ?",
         )
         .bind::<Text, _>(substate_id.to_string())
         .get_results::<EventData>(self.connection())
@@ -581,29 +635,6 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
         Ok(events)
     }
 
-    fn event_exists(&mut self, value: NewEvent) -> Result<bool, StorageError> {
-        use crate::substate_storage_sqlite::schema::events;
-
-        let count = events::table
-            .filter(
-                events::substate_id
-                    .eq(value.substate_id)
-                    .and(events::template_address.eq(value.template_address))
-                    .and(events::topic.eq(value.topic))
-                    .and(events::version.eq(value.version))
-                    .and(events::payload.eq(value.payload))
-                    .and(events::tx_hash.eq(value.tx_hash)),
-            )
-            .count()
-            .get_result::<i64>(self.connection())
-            .map_err(|e| StorageError::QueryError {
-                reason: format!("event_exists: {}", e),
-            })?;
-        let exists = count > 0;
-
-        Ok(exists)
-    }
-
     fn get_oldest_scanned_epoch(&mut self) -> Result<Option<Epoch>, StorageError> {
         use crate::substate_storage_sqlite::schema::scanned_block_ids;
 
@@ -647,6 +678,58 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
 
         Ok(block_id_option)
     }
+
+    fn get_transfers(
+        &mut self,
+        resource_address_filter: Option<ResourceAddress>,
+        account_filter: Option<ComponentAddress>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<Transfer>, StorageError> {
+        use crate::substate_storage_sqlite::schema::transfers;
+
+        let mut query = transfers::table.into_boxed();
+
+        if let Some(resource_address) = resource_address_filter {
+            query = query.filter(transfers::resource_address.eq(resource_address.to_string()));
+        }
+
+        if let Some(account) = account_filter {
+            let account = account.to_string();
+            query = query.filter(
+                transfers::from_account
+                    .eq(account.clone())
+                    .or(transfers::to_account.eq(account)),
+            );
+        }
+
+        query = query.order_by(transfers::id.desc()).offset(offset.into());
+        if limit > 0 {
+            query = query.limit(limit.into());
+        }
+
+        let transfers = query
+            .get_results::<Transfer>(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("get_transfers: {}", e),
+            })?;
+
+        Ok(transfers)
+    }
+
+    fn transfers_exist_for_transaction(&mut self, tx_id: &TransactionId) -> Result<bool, StorageError> {
+        use crate::substate_storage_sqlite::schema::transfers;
+
+        let count = transfers::table
+            .filter(transfers::tx_hash.eq(tx_id.to_string()))
+            .count()
+            .get_result::<i64>(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("transfers_exist_for_transaction: {}", e),
+            })?;
+
+        Ok(count > 0)
+    }
 }
 
 pub struct SqliteSubstateStoreWriteTransaction<'a> {
@@ -664,6 +747,42 @@ impl<'a> SqliteSubstateStoreWriteTransaction<'a> {
     pub fn connection(&mut self) -> &mut SqliteConnection {
         self.transaction.as_mut().unwrap().connection()
     }
+
+    /// Merges a duplicated event into the stored one, keeping the earliest timestamp and filling in the substate id
+    /// if the stored event did not have it
+    fn merge_duplicated_event(&mut self, new_event: &NewEvent) -> Result<(), StorageError> {
+        use crate::substate_storage_sqlite::schema::events;
+
+        let existing_event = events::table
+            .filter(events::tx_hash.eq(&new_event.tx_hash))
+            .filter(events::topic.eq(&new_event.topic))
+            .filter(events::payload_hash.eq(&new_event.payload_hash))
+            .filter(events::version.eq(new_event.version))
+            .first::<Event>(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("merge_duplicated_event: {}", e),
+            })?;
+
+        let substate_id = existing_event.substate_id.or_else(|| new_event.substate_id.clone());
+        let timestamp = existing_event.timestamp.min(new_event.timestamp);
+        diesel::update(events::table)
+            .filter(events::id.eq(existing_event.id))
+            .set((events::substate_id.eq(&substate_id), events::timestamp.eq(timestamp)))
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("merge_duplicated_event: {}", e),
+            })?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Merged duplicated event with id = {}, topic = {} and for transaction hash = {}",
+            existing_event.id,
+            new_event.topic,
+            new_event.tx_hash,
+        );
+
+        Ok(())
+    }
 }
 
 // TODO: remove the allow dead_code attributes as these become used.
@@ -677,7 +796,10 @@ pub trait SubstateStoreWriteTransaction {
     fn clear_substates(&mut self) -> Result<(), StorageError>;
     #[allow(dead_code)]
     fn add_non_fungible_index(&mut self, new_nft_index: NewNonFungibleIndex) -> Result<(), StorageError>;
-    fn save_event(&mut self, new_event: NewEvent) -> Result<(), StorageError>;
+    /// Saves an event, returning false if an event with the same content was already stored, in which case both are
+    /// merged into the existing row
+    fn save_event(&mut self, new_event: NewEvent) -> Result<bool, StorageError>;
+    fn save_transfer(&mut self, new_transfer: NewTransfer) -> Result<(), StorageError>;
     fn save_scanned_block_id(&mut self, new_scanned_block_id: NewScannedBlockId) -> Result<(), StorageError>;
     fn delete_scanned_epochs_older_than(&mut self, epoch: Epoch) -> Result<(), StorageError>;
 }
@@ -774,17 +896,23 @@ impl SubstateStoreWriteTransaction for SqliteSubstateStoreWriteTransaction<'_> {
         Ok(())
     }
 
-    fn save_event(&mut self, new_event: NewEvent) -> Result<(), StorageError> {
+    fn save_event(&mut self, new_event: NewEvent) -> Result<bool, StorageError> {
         use crate::substate_storage_sqlite::schema::{event_payloads, events};
 
-        // Save the event into the database
-        let event_row: Event = diesel::insert_into(events::table)
+        // Save the event into the database, the same event could have been already scanned from another validator
+        let maybe_event_row = diesel::insert_or_ignore_into(events::table)
             .values(&new_event)
             .get_result::<Event>(self.connection())
+            .optional()
             .map_err(|e| StorageError::QueryError {
                 reason: format!("save_event: {}", e),
             })?;
 
+        let Some(event_row) = maybe_event_row else {
+            self.merge_duplicated_event(&new_event)?;
+            return Ok(false);
+        };
+
         // Save all the key-value pairs of the payload to be able to query them later
         let payload: BTreeMap<String, String> =
             serde_json::from_str(new_event.payload.as_str()).map_err(|e| StorageError::QueryError {
@@ -819,6 +947,29 @@ impl SubstateStoreWriteTransaction for SqliteSubstateStoreWriteTransaction<'_> {
             new_event.version,
         );
 
+        Ok(true)
+    }
+
+    fn save_transfer(&mut self, new_transfer: NewTransfer) -> Result<(), StorageError> {
+        use crate::substate_storage_sqlite::schema::transfers;
+
+        diesel::insert_into(transfers::table)
+            .values(&new_transfer)
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("save_transfer: {}", e),
+            })?;
+
+        debug!(
+            target: LOG_TARGET,
+            "Added new transfer of {} {} from {:?} to {:?} in transaction {}",
+            new_transfer.amount,
+            new_transfer.resource_address,
+            new_transfer.from_account,
+            new_transfer.to_account,
+            new_transfer.tx_hash,
+        );
+
         Ok(())
     }
 
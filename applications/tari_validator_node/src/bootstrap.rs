@@ -78,14 +78,13 @@ use tari_engine_types::{
     vault::Vault,
 };
 use tari_epoch_manager::{
-    base_layer::{EpochManagerConfig, EpochManagerHandle},
+    base_layer::{EpochManagerConfig, EpochManagerHandle, ValidatorAllowlistConfig},
     EpochManagerReader,
 };
 use tari_indexer_lib::substate_scanner::SubstateScanner;
 use tari_networking::{MessagingMode, NetworkingHandle, RelayCircuitLimits, RelayReservationLimits, SwarmConfig};
 use tari_rpc_framework::RpcServer;
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_template_lib::{
     auth::ResourceAccessRules,
     constants::{
@@ -107,6 +106,7 @@ use crate::consensus::metrics::PrometheusConsensusMetrics;
 use crate::{
     consensus::{self, ConsensusHandle, TariDanBlockTransactionExecutor},
     dry_run_transaction_processor::DryRunTransactionProcessor,
+    genesis::Genesis,
     p2p::{
         create_tari_validator_node_rpc_service,
         services::{
@@ -116,8 +116,15 @@ use crate::{
         },
         NopLogger,
     },
+    state_store::{connect_state_store, ValidatorNodeStateStore},
     substate_resolver::TariSubstateResolver,
-    transaction_validators::{FeeTransactionValidator, HasInputs, TemplateExistsValidator, TransactionValidationError},
+    transaction_validators::{
+        FeeTransactionValidator,
+        HasInputs,
+        TemplateExistsValidator,
+        TransactionSizeValidator,
+        TransactionValidationError,
+    },
     validator::Validator,
     validator_registration_file::ValidatorRegistrationFile,
     virtual_substate::VirtualSubstateManager,
@@ -133,6 +140,7 @@ pub async fn spawn_services(
     keypair: RistrettoKeypair,
     global_db: GlobalDb<SqliteGlobalDbAdapter<PeerAddress>>,
     consensus_constants: ConsensusConstants,
+    genesis: &Genesis,
     base_node_client: GrpcBaseNodeClient,
     #[cfg(feature = "metrics")] metrics_registry: &prometheus::Registry,
 ) -> Result<Services, anyhow::Error> {
@@ -201,8 +209,7 @@ pub async fn spawn_services(
 
     info!(target: LOG_TARGET, "State store initializing");
     // Connect to shard db
-    let state_store =
-        SqliteStateStore::connect(&format!("sqlite://{}", config.validator_node.state_db_path().display()))?;
+    let state_store = connect_state_store(&config.validator_node)?;
     let sidechain_id = config.validator_node.validator_node_sidechain_id.clone();
     state_store.with_write_tx(|tx| {
         bootstrap_state(
@@ -210,10 +217,26 @@ pub async fn spawn_services(
             config.network,
             consensus_constants.num_preshards,
             sidechain_id.clone(),
+            genesis,
         )
     })?;
 
     info!(target: LOG_TARGET, "Epoch manager initializing");
+    let validator_allowlist = match config.validator_node.validator_allowlist_file.clone() {
+        Some(_) if config.validator_node.validator_allowlist_reload_interval.is_zero() => {
+            return Err(anyhow!("validator_allowlist_reload_interval must be greater than zero"));
+        },
+        Some(path) => Some(ValidatorAllowlistConfig {
+            path,
+            authority_public_key: config
+                .validator_node
+                .validator_allowlist_authority_public_key
+                .clone()
+                .context("validator_allowlist_authority_public_key must be set when using a validator allowlist")?,
+            reload_interval: config.validator_node.validator_allowlist_reload_interval,
+        }),
+        None => None,
+    };
     let epoch_manager_config = EpochManagerConfig {
         base_layer_confirmations: consensus_constants.base_layer_confirmations,
         committee_size: consensus_constants
@@ -222,6 +245,8 @@ pub async fn spawn_services(
             .context("committee size must be non-zero")?,
         validator_node_sidechain_id: config.validator_node.validator_node_sidechain_id.clone(),
         num_preshards: consensus_constants.num_preshards,
+        stake_weighted_voting: consensus_constants.stake_weighted_voting,
+        validator_allowlist,
     };
     // Epoch manager
     let (epoch_manager, epoch_manager_join_handle) = tari_epoch_manager::base_layer::spawn_service(
@@ -260,6 +285,10 @@ pub async fn spawn_services(
         per_byte_storage_cost: 1,
         per_event_cost: 1,
         per_log_cost: 1,
+        max_logs_per_transaction: FeeTable::DEFAULT_MAX_LOGS_PER_TRANSACTION,
+        max_log_bytes_per_transaction: FeeTable::DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION,
+        per_byte_rent_cost_per_epoch: consensus_constants.per_byte_rent_cost_per_epoch,
+        initial_rent_epochs: consensus_constants.initial_rent_epochs,
     };
 
     // Consensus gossip
@@ -289,7 +318,8 @@ pub async fn spawn_services(
     );
 
     // Consensus
-    let payload_processor = TariDanTransactionProcessor::new(config.network, template_manager.clone(), fee_table);
+    let payload_processor = TariDanTransactionProcessor::new(config.network, template_manager.clone(), fee_table)
+        .with_execution_traces(config.validator_node.record_execution_traces);
     let transaction_executor = TariDanBlockTransactionExecutor::new(
         payload_processor.clone(),
         consensus::create_transaction_validator(template_manager.clone()).boxed(),
@@ -316,14 +346,16 @@ pub async fn spawn_services(
         shutdown.clone(),
         transaction_executor,
         consensus_constants.clone(),
+        config.validator_node.state_tree_retained_versions,
     )
     .await;
     handles.push(consensus_join_handle);
 
     let (mempool, join_handle) = mempool::spawn(
         consensus_constants.num_preshards,
+        consensus_constants.idempotency_key_window,
         epoch_manager.clone(),
-        create_mempool_transaction_validator(template_manager.clone()),
+        create_mempool_transaction_validator(template_manager.clone(), &consensus_constants),
         state_store.clone(),
         consensus_handle.clone(),
         networking.clone(),
@@ -340,7 +372,7 @@ pub async fn spawn_services(
         epoch_manager.clone(),
         template_manager_service.clone(),
         shutdown.clone(),
-        consensus_constants,
+        consensus_constants.clone(),
         state_store.clone(),
         config.validator_node.scan_base_layer,
         config.validator_node.base_layer_scanning_interval,
@@ -396,6 +428,7 @@ pub async fn spawn_services(
         // global_db,
         state_store,
         dry_run_transaction_processor,
+        consensus_constants,
         handles,
         // validator_node_client_factory,
         // consensus_gossip_service,
@@ -451,7 +484,8 @@ pub struct Services {
     pub dry_run_transaction_processor: DryRunTransactionProcessor,
     // pub validator_node_client_factory: TariValidatorNodeRpcClientFactory,
     // pub consensus_gossip_service: ConsensusGossipHandle,
-    pub state_store: SqliteStateStore<PeerAddress>,
+    pub state_store: ValidatorNodeStateStore,
+    pub consensus_constants: ConsensusConstants,
 
     pub handles: Vec<JoinHandle<Result<(), anyhow::Error>>>,
 }
@@ -469,11 +503,14 @@ async fn spawn_p2p_rpc(
     config: &ApplicationConfig,
     networking: &mut NetworkingHandle<TariMessagingSpec>,
     epoch_manager: EpochManagerHandle<PeerAddress>,
-    shard_store_store: SqliteStateStore<PeerAddress>,
+    shard_store_store: ValidatorNodeStateStore,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PeerAddress>, EpochManagerHandle<PeerAddress>>,
+    virtual_substate_manager: VirtualSubstateManager<ValidatorNodeStateStore, EpochManagerHandle<PeerAddress>>,
     consensus: ConsensusHandle,
 ) -> anyhow::Result<()> {
+    if config.validator_node.block_sync.max_blocks_per_page == 0 {
+        return Err(anyhow!("block_sync.max_blocks_per_page must be greater than zero"));
+    }
     let rpc_server = RpcServer::builder()
         .with_maximum_simultaneous_sessions(config.validator_node.rpc.max_simultaneous_sessions)
         .with_maximum_sessions_per_client(config.validator_node.rpc.max_sessions_per_client)
@@ -484,6 +521,7 @@ async fn spawn_p2p_rpc(
             mempool,
             virtual_substate_manager,
             consensus,
+            &config.validator_node.block_sync,
         ));
 
     let (notify_tx, notify_rx) = mpsc::unbounded_channel();
@@ -499,6 +537,7 @@ fn bootstrap_state<TTx>(
     network: Network,
     num_preshards: NumPreshards,
     sidechain_id: Option<RistrettoPublicKey>,
+    genesis: &Genesis,
 ) -> Result<(), StorageError>
 where
     TTx: StateStoreWriteTransaction + Deref,
@@ -527,6 +566,7 @@ where
         network,
         num_preshards,
         &sidechain_id,
+        genesis.hash(),
         PUBLIC_IDENTITY_RESOURCE_ADDRESS,
         value,
     )?;
@@ -541,15 +581,25 @@ where
         None,
     );
 
+    let faucet_supply = match genesis.xtr_faucet_supply {
+        Some(supply) => supply,
+        None if matches!(network, Network::MainNet) => Amount::zero(),
+        None => Amount::MAX,
+    };
+
     // Create faucet component
-    if !matches!(network, Network::MainNet) {
+    if faucet_supply.is_positive() {
         let value = ComponentHeader {
             template_address: tari_template_builtin::FAUCET_TEMPLATE_ADDRESS,
             module_name: "XtrFaucet".to_string(),
+            previous_template_address: None,
             owner_key: None,
             owner_rule: OwnerRule::None,
             access_rules: ComponentAccessRules::allow_all(),
             entity_id: EntityId::default(),
+            non_reentrant: false,
+            fee_sponsor: None,
+            rent_paid_until: None,
             body: ComponentBody {
                 state: cbor!({"vault" => XTR_FAUCET_VAULT_ADDRESS}).unwrap(),
             },
@@ -559,15 +609,16 @@ where
             network,
             num_preshards,
             &sidechain_id,
+            genesis.hash(),
             XTR_FAUCET_COMPONENT_ADDRESS,
             value,
         )?;
 
-        xtr_resource.increase_total_supply(Amount::MAX);
+        xtr_resource.increase_total_supply(faucet_supply);
         let value = Vault::new(ResourceContainer::Confidential {
             address: CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
             commitments: Default::default(),
-            revealed_amount: Amount::MAX,
+            revealed_amount: faucet_supply,
             locked_commitments: Default::default(),
             locked_revealed_amount: Default::default(),
         });
@@ -577,6 +628,7 @@ where
             network,
             num_preshards,
             &sidechain_id,
+            genesis.hash(),
             XTR_FAUCET_VAULT_ADDRESS,
             value,
         )?;
@@ -587,10 +639,23 @@ where
         network,
         num_preshards,
         &sidechain_id,
+        genesis.hash(),
         CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
         xtr_resource,
     )?;
 
+    for substate in &genesis.substates {
+        create_substate(
+            tx,
+            network,
+            num_preshards,
+            &sidechain_id,
+            genesis.hash(),
+            substate.substate_id.clone(),
+            substate.value.clone(),
+        )?;
+    }
+
     Ok(())
 }
 
@@ -599,6 +664,7 @@ fn create_substate<TTx, TId, TVal>(
     network: Network,
     num_preshards: NumPreshards,
     sidechain_id: &Option<RistrettoPublicKey>,
+    genesis_hash: Option<FixedHash>,
     substate_id: TId,
     value: TVal,
 ) -> Result<(), StorageError>
@@ -613,8 +679,11 @@ where
         network,
         Epoch(0),
         ShardGroup::all_shards(num_preshards),
+        num_preshards,
         FixedHash::default(),
         sidechain_id.clone(),
+        FixedHash::default(),
+        genesis_hash,
     );
     let substate_id = substate_id.into();
     let id = VersionedSubstateId::new(substate_id, 0);
@@ -637,8 +706,13 @@ where
 
 fn create_mempool_transaction_validator(
     template_manager: TemplateManager<PeerAddress>,
+    consensus_constants: &ConsensusConstants,
 ) -> impl Validator<Transaction, Context = (), Error = TransactionValidationError> {
-    HasInputs::new()
-        .and_then(TemplateExistsValidator::new(template_manager))
-        .and_then(FeeTransactionValidator)
+    TransactionSizeValidator::new(
+        consensus_constants.max_transaction_bytes,
+        consensus_constants.max_instructions,
+    )
+    .and_then(HasInputs::new())
+    .and_then(TemplateExistsValidator::new(template_manager))
+    .and_then(FeeTransactionValidator)
 }
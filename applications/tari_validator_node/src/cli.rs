@@ -59,6 +59,10 @@ pub struct Cli {
     /// FOR DEBUGGING PURPOSES ONLY
     #[clap(long, short = 'd')]
     pub debug_templates: Vec<String>,
+    /// Recompute the state merkle root from the stored substates, compare it to the last committed block and exit.
+    /// The validator node should not be running.
+    #[clap(long)]
+    pub check_state_integrity: bool,
 }
 
 impl ConfigOverrideProvider for Cli {
@@ -21,6 +21,8 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    fmt,
+    fmt::Display,
     net::SocketAddr,
     path::{Path, PathBuf},
     time::Duration,
@@ -40,6 +42,7 @@ use tari_dan_app_utilities::{
     p2p_config::{P2pConfig, PeerSeedsConfig, RpcConfig},
     template_manager::implementation::TemplateConfig,
 };
+use tari_state_store_sqlite::{SqliteConnectionOptions, SqliteJournalMode, SqliteSynchronous};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -107,6 +110,25 @@ pub struct ValidatorNodeConfig {
     pub template_sidechain_id: Option<RistrettoPublicKey>,
     /// The burnt utxo sidechain id
     pub burnt_utxo_sidechain_id: Option<RistrettoPublicKey>,
+    /// The number of historical state tree versions to keep for each shard. 0 keeps all versions.
+    pub state_tree_retained_versions: u64,
+    /// The number of preshards for a new network. This is fixed at network genesis and overrides the network default.
+    pub num_preshards: Option<u32>,
+    /// An optional JSON or TOML genesis file that defines the initial state and consensus constants of a new network
+    pub genesis_file: Option<PathBuf>,
+    /// Record an execution trace for every executed transaction. Traces are stored with the transaction result.
+    pub record_execution_traces: bool,
+    /// An optional signed validator allowlist. If set, only validators in the allowlist are admitted into committees.
+    pub validator_allowlist_file: Option<PathBuf>,
+    /// The public key of the authority that signs the validator allowlist
+    pub validator_allowlist_authority_public_key: Option<RistrettoPublicKey>,
+    /// How often the validator allowlist file is checked for changes
+    #[serde(with = "serializers::seconds")]
+    pub validator_allowlist_reload_interval: Duration,
+    /// The state store database configuration
+    pub state_store: StateStoreConfig,
+    /// Limits for serving block sync requests from peers
+    pub block_sync: BlockSyncConfig,
 }
 
 impl ValidatorNodeConfig {
@@ -114,6 +136,14 @@ impl ValidatorNodeConfig {
         self.data_dir.join("state.db")
     }
 
+    pub fn state_rocksdb_path(&self) -> PathBuf {
+        self.data_dir.join("state_rocksdb")
+    }
+
+    pub fn state_archive_dir(&self) -> PathBuf {
+        self.data_dir.join("state_archive")
+    }
+
     pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
         if !self.shard_key_file.is_absolute() {
             self.shard_key_file = base_path.as_ref().join(&self.shard_key_file);
@@ -124,6 +154,16 @@ impl ValidatorNodeConfig {
         if !self.data_dir.is_absolute() {
             self.data_dir = base_path.as_ref().join(&self.data_dir);
         }
+        if let Some(genesis_file) = self.genesis_file.as_mut() {
+            if !genesis_file.is_absolute() {
+                *genesis_file = base_path.as_ref().join(&genesis_file);
+            }
+        }
+        if let Some(allowlist_file) = self.validator_allowlist_file.as_mut() {
+            if !allowlist_file.is_absolute() {
+                *allowlist_file = base_path.as_ref().join(&allowlist_file);
+            }
+        }
     }
 }
 
@@ -150,6 +190,15 @@ impl Default for ValidatorNodeConfig {
             validator_node_sidechain_id: None,
             template_sidechain_id: None,
             burnt_utxo_sidechain_id: None,
+            state_tree_retained_versions: 1000,
+            num_preshards: None,
+            genesis_file: None,
+            record_execution_traces: false,
+            validator_allowlist_file: None,
+            validator_allowlist_authority_public_key: None,
+            validator_allowlist_reload_interval: Duration::from_secs(30),
+            state_store: StateStoreConfig::default(),
+            block_sync: BlockSyncConfig::default(),
         }
     }
 }
@@ -159,3 +208,82 @@ impl SubConfigPath for ValidatorNodeConfig {
         "validator_node"
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct StateStoreConfig {
+    /// The state store database backend. The rocksdb backend is only available if the node is built with the rocksdb
+    /// feature.
+    pub backend: StateStoreBackend,
+    /// The SQLite journal mode. WAL allows JSON-RPC reads to proceed while consensus is writing.
+    pub journal_mode: SqliteJournalMode,
+    /// The SQLite synchronous level
+    pub synchronous: SqliteSynchronous,
+    /// How long to wait for a database lock held by another connection before failing, in milliseconds
+    pub busy_timeout_ms: u64,
+    /// The maximum size of the SQLite page cache in KiB. If not set, the SQLite default is used.
+    pub cache_size_kib: Option<u32>,
+    /// The number of past epochs to keep in the SQLite state store. Committed blocks, QCs and diffs for older epochs
+    /// are moved into per-epoch archive files in `<data_dir>/state_archive`. 0 disables archiving.
+    pub archive_retained_epochs: u64,
+}
+
+impl StateStoreConfig {
+    pub fn to_connection_options(&self) -> SqliteConnectionOptions {
+        SqliteConnectionOptions {
+            journal_mode: self.journal_mode,
+            synchronous: self.synchronous,
+            busy_timeout: Duration::from_millis(self.busy_timeout_ms),
+            cache_size_kib: self.cache_size_kib,
+        }
+    }
+}
+
+impl Default for StateStoreConfig {
+    fn default() -> Self {
+        let options = SqliteConnectionOptions::default();
+        Self {
+            backend: StateStoreBackend::Sqlite,
+            journal_mode: options.journal_mode,
+            synchronous: options.synchronous,
+            busy_timeout_ms: options.busy_timeout.as_millis() as u64,
+            cache_size_kib: options.cache_size_kib,
+            archive_retained_epochs: 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BlockSyncConfig {
+    /// The maximum number of blocks streamed in a single sync_blocks request. Peers request the next page from the
+    /// last block they received.
+    pub max_blocks_per_page: usize,
+    /// The maximum rate at which blocks are streamed to each syncing peer. 0 disables rate limiting.
+    pub max_blocks_per_second_per_peer: u32,
+}
+
+impl Default for BlockSyncConfig {
+    fn default() -> Self {
+        Self {
+            max_blocks_per_page: 1000,
+            max_blocks_per_second_per_peer: 200,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateStoreBackend {
+    Sqlite,
+    RocksDb,
+}
+
+impl Display for StateStoreBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateStoreBackend::Sqlite => write!(f, "sqlite"),
+            StateStoreBackend::RocksDb => write!(f, "rocksdb"),
+        }
+    }
+}
@@ -4,6 +4,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use log::info;
+use tari_common_types::types::FixedHash;
 use tari_consensus::traits::{BlockTransactionExecutor, BlockTransactionExecutorError};
 use tari_dan_app_utilities::transaction_executor::TransactionExecutor;
 use tari_dan_common_types::{Epoch, SubstateRequirement};
@@ -72,6 +73,8 @@ where
         &self,
         transaction: Transaction,
         current_epoch: Epoch,
+        current_timestamp: u64,
+        random_beacon: FixedHash,
         resolved_inputs: &HashMap<SubstateRequirement, Substate>,
     ) -> Result<ExecutedTransaction, BlockTransactionExecutorError> {
         let id = *transaction.id();
@@ -87,6 +90,24 @@ where
             VirtualSubstateId::CurrentEpoch,
             VirtualSubstate::CurrentEpoch(current_epoch.as_u64()),
         );
+        virtual_substates.insert(
+            VirtualSubstateId::CurrentTimestamp,
+            VirtualSubstate::CurrentTimestamp(current_timestamp),
+        );
+        virtual_substates.insert(
+            VirtualSubstateId::RandomBeacon,
+            VirtualSubstate::random_beacon(&random_beacon),
+        );
+        virtual_substates.insert(
+            VirtualSubstateId::RandomSeed,
+            VirtualSubstate::random_seed(
+                current_epoch.as_u64(),
+                &random_beacon,
+                resolved_inputs
+                    .iter()
+                    .map(|(req, substate)| (req.substate_id(), substate)),
+            ),
+        );
 
         // Execute the transaction and get the result
         let exec_output = self
@@ -1,19 +1,30 @@
 //    Copyright 2023 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
+use tari_common_types::types::FixedHash;
 use tari_consensus::traits::LeaderStrategy;
-use tari_dan_common_types::{committee::Committee, NodeAddressable, NodeHeight};
+use tari_dan_common_types::{committee::Committee, hashing::leader_selection_hasher, NodeAddressable, NodeHeight};
 
+/// Selects the leader for a height by sampling the committee, weighted by voting power, using the leader seed of the
+/// block that the proposal justifies. The seed is derived from the VRF output of that block's proposer, which is unique
+/// for the proposer's key and the block's height and seed. Proposers cannot grind the seed and the leader for a height
+/// is not known until the block before it has been proposed.
 #[derive(Debug, Clone, Copy, Default)]
-pub struct RoundRobinLeaderStrategy;
-impl RoundRobinLeaderStrategy {
+pub struct VrfLeaderStrategy;
+impl VrfLeaderStrategy {
     pub fn new() -> Self {
         Self
     }
 }
 
-impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for RoundRobinLeaderStrategy {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, height: NodeHeight) -> u32 {
-        (height.as_u64() % committee.members.len() as u64) as u32
+impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for VrfLeaderStrategy {
+    fn calculate_leader(&self, committee: &Committee<TAddr>, leader_seed: &FixedHash, height: NodeHeight) -> u32 {
+        let hash = leader_selection_hasher().chain(leader_seed).chain(&height).result();
+        let mut value = [0u8; 8];
+        value.copy_from_slice(&hash[..8]);
+        // Members are selected with a probability proportional to their voting power. If all members have a voting
+        // power of 1, this is the same as selecting the member at the value modulo the committee size.
+        let voting_power = u64::from_le_bytes(value) % committee.total_voting_power();
+        committee.index_of_voting_power(voting_power).unwrap_or(0) as u32
     }
 }
@@ -1,27 +1,32 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, str::FromStr, time::Duration};
 
 use prometheus::{core::Collector, IntCounter, IntGauge, IntGaugeVec, Opts, Registry};
 use tari_consensus::{hotstuff::HotStuffError, messages::HotstuffMessage, traits::hooks::ConsensusHooks};
-use tari_dan_common_types::{NodeHeight, PeerAddress};
+use tari_dan_common_types::NodeHeight;
 use tari_dan_storage::{
-    consensus_models::{Decision, QuorumDecision, TransactionAtom, ValidBlock},
+    consensus_models::{Block, Decision, QuorumCertificate, QuorumDecision, TransactionAtom, ValidBlock},
     StateStore,
 };
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::TransactionId;
 
-use crate::metrics::{CollectorRegister, LabelledCollector};
+use crate::{
+    metrics::{CollectorRegister, LabelledCollector},
+    state_store::ValidatorNodeStateStore,
+};
 
 #[derive(Debug, Clone)]
-pub struct PrometheusConsensusMetrics<S = SqliteStateStore<PeerAddress>> {
+pub struct PrometheusConsensusMetrics<S = ValidatorNodeStateStore> {
     _state_store: S,
     local_blocks_received: IntCounter,
     blocks_accepted: IntCounter,
     blocks_rejected: IntCounter,
     blocks_validation_failed: IntCounter,
+    blocks_committed: IntCounter,
+    votes_sent: IntCounter,
+    qcs_formed: IntCounter,
 
     commands_count: IntGaugeVec,
 
@@ -76,6 +81,15 @@ impl<S: StateStore> PrometheusConsensusMetrics<S> {
             blocks_rejected: IntCounter::new("consensus_blocks_rejected", "Number of blocks rejected")
                 .unwrap()
                 .register_at(registry),
+            blocks_committed: IntCounter::new("consensus_blocks_committed", "Number of blocks committed")
+                .unwrap()
+                .register_at(registry),
+            votes_sent: IntCounter::new("consensus_votes_sent", "Number of votes sent")
+                .unwrap()
+                .register_at(registry),
+            qcs_formed: IntCounter::new("consensus_qcs_formed", "Number of QCs formed from collected votes")
+                .unwrap()
+                .register_at(registry),
             needs_sync: IntCounter::new("consensus_needs_sync", "Number of times consensus needs to sync")
                 .unwrap()
                 .register_at(registry),
@@ -130,6 +144,8 @@ impl<S: StateStore> PrometheusConsensusMetrics<S> {
 }
 
 impl<S: StateStore> ConsensusHooks for PrometheusConsensusMetrics<S> {
+    fn on_proposal_received(&mut self, _block: &Block) {}
+
     fn on_local_block_decide(&mut self, block: &ValidBlock, decision: Option<QuorumDecision>) {
         self.local_blocks_received.inc();
         match decision {
@@ -151,6 +167,18 @@ impl<S: StateStore> ConsensusHooks for PrometheusConsensusMetrics<S> {
         }
     }
 
+    fn on_vote_sent(&mut self, _block: &Block, _decision: QuorumDecision, _processing_time: Duration) {
+        self.votes_sent.inc();
+    }
+
+    fn on_qc_formed(&mut self, _qc: &QuorumCertificate, _collection_time: Duration) {
+        self.qcs_formed.inc();
+    }
+
+    fn on_block_committed(&mut self, _block: &Block, _num_finalized_transactions: usize, _commit_latency: Duration) {
+        self.blocks_committed.inc();
+    }
+
     fn on_block_validation_failed<E: ToString>(&mut self, _err: &E) {
         self.blocks_validation_failed.inc();
     }
@@ -179,7 +207,7 @@ impl<S: StateStore> ConsensusHooks for PrometheusConsensusMetrics<S> {
         self.transactions_ready_for_consensus.inc();
     }
 
-    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom) {
+    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom, _committed_block: &Block) {
         match transaction.decision {
             Decision::Commit => {
                 self.transactions_finalized_committed.inc();
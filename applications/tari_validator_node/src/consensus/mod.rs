@@ -16,7 +16,6 @@ use tari_dan_storage::consensus_models::TransactionPool;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
 use tari_rpc_state_sync::RpcStateSyncManager;
 use tari_shutdown::ShutdownSignal;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::Transaction;
 use tari_validator_node_rpc::client::TariValidatorNodeRpcClientFactory;
 use tokio::{
@@ -25,9 +24,10 @@ use tokio::{
 };
 
 use crate::{
-    consensus::{leader_selection::RoundRobinLeaderStrategy, spec::TariConsensusSpec},
+    consensus::{leader_selection::VrfLeaderStrategy, spec::TariConsensusSpec},
     event_subscription::EventSubscription,
     p2p::services::messaging::{ConsensusInboundMessaging, ConsensusOutboundMessaging},
+    state_store::ValidatorNodeStateStore,
     transaction_validators::{
         ClaimFeeTransactionValidator,
         EpochRangeValidator,
@@ -60,7 +60,7 @@ pub type ConsensusTransactionValidator = BoxedValidator<ValidationContext, Trans
 pub async fn spawn(
     network: Network,
     sidechain_id: Option<RistrettoPublicKey>,
-    store: SqliteStateStore<PeerAddress>,
+    store: ValidatorNodeStateStore,
     local_addr: PeerAddress,
     signing_service: TariSignatureService,
     epoch_manager: EpochManagerHandle<PeerAddress>,
@@ -74,10 +74,11 @@ pub async fn spawn(
         ConsensusTransactionValidator,
     >,
     consensus_constants: ConsensusConstants,
+    state_tree_retained_versions: u64,
 ) -> (JoinHandle<Result<(), anyhow::Error>>, ConsensusHandle) {
     let (tx_new_transaction, rx_new_transactions) = mpsc::channel(10);
 
-    let leader_strategy = RoundRobinLeaderStrategy::new();
+    let leader_strategy = VrfLeaderStrategy::new();
     let transaction_pool = TransactionPool::new();
     let (tx_hotstuff_events, _) = broadcast::channel(100);
 
@@ -85,6 +86,7 @@ pub async fn spawn(
         network,
         sidechain_id,
         consensus_constants,
+        state_tree_retained_versions,
     };
 
     let hotstuff_worker = HotstuffWorker::<TariConsensusSpec>::new(
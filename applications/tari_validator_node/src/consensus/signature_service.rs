@@ -5,6 +5,7 @@ use rand::rngs::OsRng;
 use tari_common_types::types::PublicKey;
 use tari_consensus::traits::{ValidatorSignatureService, VoteSignatureService};
 use tari_dan_app_utilities::keypair::RistrettoKeypair;
+use tari_dan_common_types::vrf::VrfProof;
 use tari_dan_storage::consensus_models::{BlockId, QuorumDecision, ValidatorSchnorrSignature, ValidatorSignature};
 
 #[derive(Debug, Clone)]
@@ -23,6 +24,10 @@ impl ValidatorSignatureService for TariSignatureService {
         ValidatorSchnorrSignature::sign(self.keypair.secret_key(), message, &mut OsRng).unwrap()
     }
 
+    fn vrf_prove<M: AsRef<[u8]>>(&self, input: M) -> VrfProof {
+        VrfProof::prove(self.keypair.secret_key(), input.as_ref())
+    }
+
     fn public_key(&self) -> &PublicKey {
         self.keypair.public_key()
     }
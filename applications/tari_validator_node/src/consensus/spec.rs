@@ -11,13 +11,12 @@ use tari_dan_app_utilities::{
 use tari_dan_common_types::PeerAddress;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
 use tari_rpc_state_sync::RpcStateSyncManager;
-use tari_state_store_sqlite::SqliteStateStore;
 
 #[cfg(feature = "metrics")]
 use crate::consensus::metrics::PrometheusConsensusMetrics;
 use crate::{
     consensus::{
-        leader_selection::RoundRobinLeaderStrategy,
+        leader_selection::VrfLeaderStrategy,
         signature_service::TariSignatureService,
         ConsensusTransactionValidator,
         TariDanBlockTransactionExecutor,
@@ -26,6 +25,7 @@ use crate::{
         services::messaging::{ConsensusInboundMessaging, ConsensusOutboundMessaging},
         NopLogger,
     },
+    state_store::ValidatorNodeStateStore,
 };
 
 #[derive(Clone)]
@@ -39,10 +39,10 @@ impl ConsensusSpec for TariConsensusSpec {
     #[cfg(feature = "metrics")]
     type Hooks = PrometheusConsensusMetrics;
     type InboundMessaging = ConsensusInboundMessaging<NopLogger>;
-    type LeaderStrategy = RoundRobinLeaderStrategy;
+    type LeaderStrategy = VrfLeaderStrategy;
     type OutboundMessaging = ConsensusOutboundMessaging<NopLogger>;
     type SignatureService = TariSignatureService;
-    type StateStore = SqliteStateStore<Self::Addr>;
+    type StateStore = ValidatorNodeStateStore;
     type SyncManager = RpcStateSyncManager<Self>;
     type TransactionExecutor = TariDanBlockTransactionExecutor<
         TariDanTransactionProcessor<TemplateManager<PeerAddress>>,
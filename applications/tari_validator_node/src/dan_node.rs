@@ -27,17 +27,27 @@ use tari_epoch_manager::{EpochManagerEvent, EpochManagerReader};
 use tari_networking::NetworkingService;
 use tari_shutdown::ShutdownSignal;
 
-use crate::Services;
+use crate::{slashing_evidence::SlashingEvidenceExporter, state_store::StateStoreArchiver, Services};
 
 const LOG_TARGET: &str = "tari::validator_node::dan_node";
 
 pub struct DanNode {
     services: Services,
+    evidence_exporter: SlashingEvidenceExporter,
+    state_archiver: Option<StateStoreArchiver>,
 }
 
 impl DanNode {
-    pub fn new(services: Services) -> Self {
-        Self { services }
+    pub fn new(
+        services: Services,
+        evidence_exporter: SlashingEvidenceExporter,
+        state_archiver: Option<StateStoreArchiver>,
+    ) -> Self {
+        Self {
+            services,
+            evidence_exporter,
+            state_archiver,
+        }
     }
 
     pub async fn start(mut self, mut shutdown: ShutdownSignal) -> Result<(), anyhow::Error> {
@@ -82,14 +92,33 @@ impl DanNode {
             .set_want_peers(all_vns.into_iter().map(|vn| vn.address.as_peer_id()))
             .await?;
 
+        if let Some(archiver) = self.state_archiver.clone() {
+            let state_store = self.services.state_store.clone();
+            tokio::spawn(async move {
+                if let Err(err) = archiver.archive_old_epochs(&state_store, epoch).await {
+                    error!(target: LOG_TARGET, "Failed to archive old epochs: {}", err);
+                }
+            });
+        }
+
         Ok(())
     }
 
     async fn handle_hotstuff_event(&self, event: HotstuffEvent) -> Result<(), anyhow::Error> {
         info!(target: LOG_TARGET, "🔥 consensus event: {event}");
 
-        let HotstuffEvent::BlockCommitted { block_id, .. } = event else {
-            return Ok(());
+        let block_id = match event {
+            HotstuffEvent::BlockCommitted { block_id, .. } => block_id,
+            HotstuffEvent::DoubleVoteDetected { evidence } => {
+                let path = self.evidence_exporter.export_double_vote(*evidence)?;
+                warn!(
+                    target: LOG_TARGET,
+                    "🚨 Slashing evidence written to {}. The evidence has not been submitted to the base layer.",
+                    path.display()
+                );
+                return Ok(());
+            },
+            _ => return Ok(()),
         };
 
         let block = self.services.state_store.with_read_tx(|tx| Block::get(tx, &block_id))?;
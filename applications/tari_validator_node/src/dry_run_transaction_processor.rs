@@ -32,7 +32,6 @@ use tari_dan_storage::StorageError;
 use tari_engine_types::commit_result::ExecuteResult;
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerError, EpochManagerReader};
 use tari_rpc_framework::RpcStatus;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::Transaction;
 use tari_validator_node_client::ValidatorNodeClientError;
 use tari_validator_node_rpc::client::TariValidatorNodeRpcClientFactory;
@@ -41,6 +40,7 @@ use tokio::task;
 
 use crate::{
     p2p::services::mempool::{ResolvedSubstates, SubstateResolver},
+    state_store::ValidatorNodeStateStore,
     substate_resolver::{SubstateResolverError, TariSubstateResolver},
     virtual_substate::VirtualSubstateError,
 };
@@ -72,7 +72,7 @@ pub enum DryRunTransactionProcessorError {
 #[derive(Clone, Debug)]
 pub struct DryRunTransactionProcessor {
     substate_resolver: TariSubstateResolver<
-        SqliteStateStore<PeerAddress>,
+        ValidatorNodeStateStore,
         EpochManagerHandle<PeerAddress>,
         TariValidatorNodeRpcClientFactory,
         SubstateFileCache,
@@ -86,7 +86,7 @@ impl DryRunTransactionProcessor {
         epoch_manager: EpochManagerHandle<PeerAddress>,
         payload_processor: TariDanTransactionProcessor<TemplateManager<PeerAddress>>,
         substate_resolver: TariSubstateResolver<
-            SqliteStateStore<PeerAddress>,
+            ValidatorNodeStateStore,
             EpochManagerHandle<PeerAddress>,
             TariValidatorNodeRpcClientFactory,
             SubstateFileCache,
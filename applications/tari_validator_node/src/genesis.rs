@@ -0,0 +1,404 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{collections::HashSet, fs, path::Path, time::Duration};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use tari_common_types::types::FixedHash;
+use tari_consensus::consensus_constants::ConsensusConstants;
+use tari_dan_common_types::{hashing::genesis_file_hasher, Epoch, NumPreshards};
+use tari_engine_types::{
+    serde_with,
+    substate::{SubstateId, SubstateValue},
+};
+use tari_template_lib::{
+    constants::{
+        CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+        PUBLIC_IDENTITY_RESOURCE_ADDRESS,
+        XTR_FAUCET_COMPONENT_ADDRESS,
+        XTR_FAUCET_VAULT_ADDRESS,
+    },
+    models::Amount,
+};
+
+/// Defines the initial state and consensus constants of a network, so that private networks can customise them. All
+/// validators on a network must use the same genesis file. The file may be JSON or TOML, determined by its extension.
+///
+/// Genesis blocks commit to the hash of the file contents, so validators must use a byte-for-byte identical file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Genesis {
+    /// Overrides for the default consensus constants of the network
+    #[serde(default)]
+    pub consensus_constants: GenesisConsensusConstants,
+    /// The XTR supply held by the faucet. 0 disables the faucet. If not set, the faucet holds an unlimited supply,
+    /// except on mainnet where there is no faucet.
+    pub xtr_faucet_supply: Option<Amount>,
+    /// Substates created at genesis in addition to the built-in substates e.g. resources, components and vaults
+    /// holding a preminted supply
+    #[serde(default)]
+    pub substates: Vec<GenesisSubstate>,
+    /// The hash of the file that this genesis was loaded from
+    #[serde(skip)]
+    hash: Option<FixedHash>,
+}
+
+impl Genesis {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Failed to read genesis file {}", path.display()))?;
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => GenesisFileFormat::Json,
+            Some("toml") => GenesisFileFormat::Toml,
+            _ => {
+                return Err(anyhow!(
+                    "Genesis file {} must have a .json or .toml extension",
+                    path.display()
+                ))
+            },
+        };
+        Self::parse(&contents, format).with_context(|| format!("Failed to parse genesis file {}", path.display()))
+    }
+
+    fn parse(contents: &str, format: GenesisFileFormat) -> anyhow::Result<Self> {
+        let mut genesis: Self = match format {
+            GenesisFileFormat::Json => serde_json::from_str(contents)?,
+            GenesisFileFormat::Toml => toml::from_str(contents)?,
+        };
+        genesis.validate()?;
+        genesis.hash = Some(genesis_file_hasher().chain(contents).result());
+        Ok(genesis)
+    }
+
+    /// Returns the hash of the genesis file, or None if the network uses the built-in genesis
+    pub fn hash(&self) -> Option<FixedHash> {
+        self.hash
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.xtr_faucet_supply.is_some_and(|supply| supply.is_negative()) {
+            return Err(anyhow!("Genesis XTR faucet supply must not be negative"));
+        }
+
+        let builtin = [
+            SubstateId::from(PUBLIC_IDENTITY_RESOURCE_ADDRESS),
+            SubstateId::from(CONFIDENTIAL_TARI_RESOURCE_ADDRESS),
+            SubstateId::from(XTR_FAUCET_COMPONENT_ADDRESS),
+            SubstateId::from(XTR_FAUCET_VAULT_ADDRESS),
+        ];
+        let mut seen = HashSet::with_capacity(self.substates.len());
+        for substate in &self.substates {
+            if builtin.contains(&substate.substate_id) {
+                return Err(anyhow!(
+                    "Genesis substate {} conflicts with a built-in substate",
+                    substate.substate_id
+                ));
+            }
+            if !seen.insert(&substate.substate_id) {
+                return Err(anyhow!("Duplicate genesis substate {}", substate.substate_id));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GenesisFileFormat {
+    Json,
+    Toml,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisSubstate {
+    #[serde(with = "serde_with::string")]
+    pub substate_id: SubstateId,
+    pub value: SubstateValue,
+}
+
+/// Consensus constants that override the network defaults. Fields that are not set keep the network default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisConsensusConstants {
+    pub base_layer_confirmations: Option<u64>,
+    pub committee_size: Option<u32>,
+    pub max_base_layer_blocks_ahead: Option<u64>,
+    pub max_base_layer_blocks_behind: Option<u64>,
+    pub num_preshards: Option<u32>,
+    pub pacemaker_block_time_secs: Option<u64>,
+    pub missed_proposal_suspend_threshold: Option<u64>,
+    pub missed_proposal_evict_threshold: Option<u64>,
+    pub missed_proposal_recovery_threshold: Option<u64>,
+    pub max_block_size: Option<usize>,
+    pub max_transaction_bytes: Option<usize>,
+    pub max_instructions: Option<usize>,
+    pub fee_exhaust_divisor: Option<u64>,
+    pub per_byte_rent_cost_per_epoch: Option<u64>,
+    pub initial_rent_epochs: Option<u64>,
+    pub epochs_per_era: Option<u64>,
+    pub idempotency_key_window: Option<u64>,
+    pub committee_layout_switch_blocks: Option<u64>,
+    pub full_proposal_miss_rate_threshold: Option<u64>,
+    pub foreign_proposal_request_interval: Option<u64>,
+    pub substate_lock_lease_blocks: Option<u64>,
+    pub cross_shard_deadlock_blocks: Option<u64>,
+    pub block_timestamp_median_window: Option<usize>,
+    pub block_timestamp_tolerance_secs: Option<u64>,
+    pub max_block_timestamp_future_drift_secs: Option<u64>,
+    pub fallen_behind_sync_threshold: Option<u64>,
+    pub stake_weighted_voting: Option<bool>,
+}
+
+impl GenesisConsensusConstants {
+    pub fn apply_to(&self, consensus_constants: &mut ConsensusConstants) -> anyhow::Result<()> {
+        macro_rules! apply {
+            ($($field:ident),+ $(,)?) => {
+                $(
+                    if let Some(value) = self.$field {
+                        consensus_constants.$field = value;
+                    }
+                )+
+            };
+        }
+
+        apply!(
+            base_layer_confirmations,
+            committee_size,
+            max_base_layer_blocks_ahead,
+            max_base_layer_blocks_behind,
+            missed_proposal_suspend_threshold,
+            missed_proposal_evict_threshold,
+            missed_proposal_recovery_threshold,
+            max_block_size,
+            max_transaction_bytes,
+            max_instructions,
+            fee_exhaust_divisor,
+            per_byte_rent_cost_per_epoch,
+            initial_rent_epochs,
+            committee_layout_switch_blocks,
+            full_proposal_miss_rate_threshold,
+            foreign_proposal_request_interval,
+            substate_lock_lease_blocks,
+            cross_shard_deadlock_blocks,
+            block_timestamp_median_window,
+            fallen_behind_sync_threshold,
+            stake_weighted_voting,
+        );
+
+        if let Some(num_preshards) = self.num_preshards {
+            consensus_constants.num_preshards = NumPreshards::try_from(num_preshards)?;
+        }
+        if let Some(secs) = self.pacemaker_block_time_secs {
+            consensus_constants.pacemaker_block_time = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.block_timestamp_tolerance_secs {
+            consensus_constants.block_timestamp_tolerance = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.max_block_timestamp_future_drift_secs {
+            consensus_constants.max_block_timestamp_future_drift = Duration::from_secs(secs);
+        }
+        if let Some(epochs) = self.epochs_per_era {
+            consensus_constants.epochs_per_era = Epoch(epochs);
+        }
+        if let Some(epochs) = self.idempotency_key_window {
+            consensus_constants.idempotency_key_window = Epoch(epochs);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_engine_types::fee_claim::FeeClaim;
+    use tari_template_lib::models::{ComponentAddress, ObjectKey};
+
+    use super::*;
+
+    fn genesis_substate(substate_id: SubstateId) -> GenesisSubstate {
+        GenesisSubstate {
+            substate_id,
+            value: FeeClaim {
+                epoch: 0,
+                validator_public_key: Default::default(),
+                amount: Amount(0),
+            }
+            .into(),
+        }
+    }
+
+    fn component_id(seed: u8) -> SubstateId {
+        ComponentAddress::from_array([seed; ObjectKey::LENGTH]).into()
+    }
+
+    mod validate {
+        use super::*;
+
+        #[test]
+        fn it_accepts_unique_substates() {
+            let genesis = Genesis {
+                substates: vec![genesis_substate(component_id(1)), genesis_substate(component_id(2))],
+                ..Default::default()
+            };
+            genesis.validate().unwrap();
+        }
+
+        #[test]
+        fn it_rejects_duplicate_substates() {
+            let genesis = Genesis {
+                substates: vec![genesis_substate(component_id(1)), genesis_substate(component_id(1))],
+                ..Default::default()
+            };
+            let err = genesis.validate().unwrap_err();
+            assert!(err.to_string().contains("Duplicate genesis substate"), "{err}");
+        }
+
+        #[test]
+        fn it_rejects_substates_that_clash_with_built_in_substates() {
+            for builtin in [
+                SubstateId::from(PUBLIC_IDENTITY_RESOURCE_ADDRESS),
+                SubstateId::from(CONFIDENTIAL_TARI_RESOURCE_ADDRESS),
+                SubstateId::from(XTR_FAUCET_COMPONENT_ADDRESS),
+                SubstateId::from(XTR_FAUCET_VAULT_ADDRESS),
+            ] {
+                let genesis = Genesis {
+                    substates: vec![genesis_substate(builtin)],
+                    ..Default::default()
+                };
+                let err = genesis.validate().unwrap_err();
+                assert!(err.to_string().contains("conflicts with a built-in substate"), "{err}");
+            }
+        }
+
+        #[test]
+        fn it_rejects_a_negative_faucet_supply() {
+            let genesis = Genesis {
+                xtr_faucet_supply: Some(Amount(-1)),
+                ..Default::default()
+            };
+            genesis.validate().unwrap_err();
+        }
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn it_parses_json_and_toml() {
+            let genesis = Genesis::parse(
+                r#"{"xtr_faucet_supply": 1000, "consensus_constants": {"committee_size": 3}}"#,
+                GenesisFileFormat::Json,
+            )
+            .unwrap();
+            assert_eq!(genesis.xtr_faucet_supply, Some(Amount(1000)));
+            assert_eq!(genesis.consensus_constants.committee_size, Some(3));
+
+            let genesis = Genesis::parse(
+                "xtr_faucet_supply = 1000\n[consensus_constants]\ncommittee_size = 3\n",
+                GenesisFileFormat::Toml,
+            )
+            .unwrap();
+            assert_eq!(genesis.xtr_faucet_supply, Some(Amount(1000)));
+            assert_eq!(genesis.consensus_constants.committee_size, Some(3));
+        }
+
+        #[test]
+        fn it_rejects_unknown_fields() {
+            Genesis::parse(r#"{"unknown_field": 1}"#, GenesisFileFormat::Json).unwrap_err();
+            Genesis::parse(
+                r#"{"consensus_constants": {"unknown_field": 1}}"#,
+                GenesisFileFormat::Json,
+            )
+            .unwrap_err();
+            Genesis::parse("unknown_field = 1\n", GenesisFileFormat::Toml).unwrap_err();
+            Genesis::parse("[consensus_constants]\nunknown_field = 1\n", GenesisFileFormat::Toml).unwrap_err();
+        }
+
+        #[test]
+        fn it_hashes_the_file_contents() {
+            assert!(Genesis::default().hash().is_none());
+
+            let a = Genesis::parse(r#"{"xtr_faucet_supply": 1000}"#, GenesisFileFormat::Json).unwrap();
+            let b = Genesis::parse(r#"{"xtr_faucet_supply": 1000}"#, GenesisFileFormat::Json).unwrap();
+            let c = Genesis::parse(r#"{"xtr_faucet_supply": 1001}"#, GenesisFileFormat::Json).unwrap();
+            assert!(a.hash().is_some());
+            assert_eq!(a.hash(), b.hash());
+            assert_ne!(a.hash(), c.hash());
+        }
+    }
+
+    mod apply_to {
+        use super::*;
+
+        #[test]
+        fn it_keeps_the_defaults_if_nothing_is_overridden() {
+            let mut consensus_constants = ConsensusConstants::devnet();
+            GenesisConsensusConstants::default()
+                .apply_to(&mut consensus_constants)
+                .unwrap();
+
+            let defaults = ConsensusConstants::devnet();
+            assert_eq!(consensus_constants.committee_size, defaults.committee_size);
+            assert_eq!(consensus_constants.num_preshards, defaults.num_preshards);
+            assert_eq!(consensus_constants.pacemaker_block_time, defaults.pacemaker_block_time);
+            assert_eq!(consensus_constants.epochs_per_era, defaults.epochs_per_era);
+            assert_eq!(
+                consensus_constants.stake_weighted_voting,
+                defaults.stake_weighted_voting
+            );
+        }
+
+        #[test]
+        fn it_overrides_the_given_constants() {
+            let mut consensus_constants = ConsensusConstants::devnet();
+            GenesisConsensusConstants {
+                committee_size: Some(3),
+                num_preshards: Some(16),
+                pacemaker_block_time_secs: Some(5),
+                block_timestamp_tolerance_secs: Some(1),
+                max_block_timestamp_future_drift_secs: Some(10),
+                epochs_per_era: Some(20),
+                idempotency_key_window: Some(30),
+                stake_weighted_voting: Some(true),
+                per_byte_rent_cost_per_epoch: Some(2),
+                initial_rent_epochs: Some(50),
+                ..Default::default()
+            }
+            .apply_to(&mut consensus_constants)
+            .unwrap();
+
+            assert_eq!(consensus_constants.committee_size, 3);
+            assert_eq!(consensus_constants.num_preshards, NumPreshards::P16);
+            assert_eq!(consensus_constants.pacemaker_block_time, Duration::from_secs(5));
+            assert_eq!(consensus_constants.block_timestamp_tolerance, Duration::from_secs(1));
+            assert_eq!(
+                consensus_constants.max_block_timestamp_future_drift,
+                Duration::from_secs(10)
+            );
+            assert_eq!(consensus_constants.epochs_per_era, Epoch(20));
+            assert_eq!(consensus_constants.idempotency_key_window, Epoch(30));
+            assert!(consensus_constants.stake_weighted_voting);
+            assert_eq!(consensus_constants.per_byte_rent_cost_per_epoch, 2);
+            assert_eq!(consensus_constants.initial_rent_epochs, 50);
+            // Constants that are not overridden keep the default
+            assert_eq!(
+                consensus_constants.max_block_size,
+                ConsensusConstants::devnet().max_block_size
+            );
+        }
+
+        #[test]
+        fn it_rejects_an_invalid_number_of_preshards() {
+            let mut consensus_constants = ConsensusConstants::devnet();
+            GenesisConsensusConstants {
+                num_preshards: Some(3),
+                ..Default::default()
+            }
+            .apply_to(&mut consensus_constants)
+            .unwrap_err();
+        }
+    }
+}
@@ -30,6 +30,7 @@ use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use log::*;
 use serde_json::{self as json, json};
 use tari_base_node_client::{grpc::GrpcBaseNodeClient, BaseNodeClient};
+use tari_consensus::{consensus_constants::ConsensusConstants, hotstuff::substate_store::check_state_integrity};
 use tari_dan_app_utilities::{keypair::RistrettoKeypair, template_manager::interface::TemplateManagerHandle};
 use tari_dan_common_types::{optional::Optional, public_key_to_peer_id, PeerAddress, SubstateAddress};
 use tari_dan_p2p::TariMessagingSpec;
@@ -41,11 +42,11 @@ use tari_dan_storage::{
 };
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
 use tari_networking::{is_supported_multiaddr, NetworkingHandle, NetworkingService};
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_validator_node_client::types::{
     self,
     AddPeerRequest,
     AddPeerResponse,
+    CheckStateIntegrityResponse,
     ConnectionDirection,
     DryRunTransactionFinalizeResult,
     GetAllVnsRequest,
@@ -55,6 +56,8 @@ use tari_validator_node_client::types::{
     GetBlocksCountResponse,
     GetBlocksRequest,
     GetBlocksResponse,
+    GetCommandMerkleProofRequest,
+    GetCommandMerkleProofResponse,
     GetCommitteeRequest,
     GetCommitteeResponse,
     GetCommsStatsResponse,
@@ -94,6 +97,7 @@ use crate::{
     dry_run_transaction_processor::DryRunTransactionProcessor,
     json_rpc::jrpc_errors::{internal_error, not_found},
     p2p::services::mempool::MempoolHandle,
+    state_store::ValidatorNodeStateStore,
     Services,
 };
 
@@ -106,8 +110,9 @@ pub struct JsonRpcHandlers {
     epoch_manager: EpochManagerHandle<PeerAddress>,
     networking: NetworkingHandle<TariMessagingSpec>,
     base_node_client: GrpcBaseNodeClient,
-    state_store: SqliteStateStore<PeerAddress>,
+    state_store: ValidatorNodeStateStore,
     dry_run_transaction_processor: DryRunTransactionProcessor,
+    consensus_constants: ConsensusConstants,
 }
 
 impl JsonRpcHandlers {
@@ -121,6 +126,7 @@ impl JsonRpcHandlers {
             base_node_client,
             state_store: services.state_store.clone(),
             dry_run_transaction_processor: services.dry_run_transaction_processor.clone(),
+            consensus_constants: services.consensus_constants.clone(),
         }
     }
 
@@ -421,6 +427,48 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn get_command_merkle_proof(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetCommandMerkleProofRequest = value.parse_params()?;
+
+        let block = self
+            .state_store
+            .with_read_tx(|tx| Block::get(tx, &request.block_id))
+            .optional()
+            .map_err(internal_error(answer_id))?
+            .ok_or_else(|| not_found(answer_id, format!("Block {} not found", request.block_id)))?;
+
+        // Uncommitted blocks may be on a fork that never finalises the transaction
+        if !block.is_committed() {
+            return Err(JsonRpcResponse::error(
+                answer_id,
+                JsonRpcError::new(
+                    JsonRpcErrorReason::InvalidParams,
+                    format!("Block {} is not committed", request.block_id),
+                    json!(null),
+                ),
+            ));
+        }
+
+        let proof = block
+            .finalising_command_merkle_proof(&request.transaction_id)
+            .map_err(internal_error(answer_id))?
+            .ok_or_else(|| {
+                not_found(
+                    answer_id,
+                    format!(
+                        "Transaction {} was not finalised in block {}",
+                        request.transaction_id, request.block_id
+                    ),
+                )
+            })?;
+
+        Ok(JsonRpcResponse::success(answer_id, GetCommandMerkleProofResponse {
+            header: block.header().clone(),
+            proof,
+        }))
+    }
+
     pub async fn get_blocks_count(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let tx = self.state_store.create_read_tx().map_err(internal_error(answer_id))?;
@@ -569,6 +617,17 @@ impl JsonRpcHandlers {
         Ok(JsonRpcResponse::success(answer_id, GetMempoolStatsResponse { size }))
     }
 
+    pub async fn check_state_integrity(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let report = self
+            .state_store
+            .with_read_tx(|tx| check_state_integrity(tx))
+            .map_err(internal_error(answer_id))?;
+        Ok(JsonRpcResponse::success(answer_id, CheckStateIntegrityResponse {
+            report,
+        }))
+    }
+
     pub async fn get_epoch_manager_stats(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         self.epoch_manager
@@ -642,6 +701,8 @@ impl JsonRpcHandlers {
             is_valid: committee_info.is_some(),
             start_epoch: local_vn_start_epoch,
             committee_info,
+            max_transaction_bytes: self.consensus_constants.max_transaction_bytes,
+            max_instructions: self.consensus_constants.max_instructions,
         };
         Ok(JsonRpcResponse::success(answer_id, response))
     }
@@ -78,6 +78,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_tx_pool" => handlers.get_tx_pool(value).await,
         // Blocks
         "get_block" => handlers.get_block(value).await,
+        "get_command_merkle_proof" => handlers.get_command_merkle_proof(value).await,
         "get_blocks_count" => handlers.get_blocks_count(value).await,
         "get_blocks" => handlers.get_blocks(value).await,
         "get_filtered_blocks_count" => handlers.get_filtered_blocks_count(value).await,
@@ -88,6 +89,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_identity" => handlers.get_identity(value).await,
         "get_mempool_stats" => handlers.get_mempool_stats(value).await,
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
+        "check_state_integrity" => handlers.check_state_integrity(value).await,
         "get_shard_key" => handlers.get_shard_key(value).await,
         "get_committee" => handlers.get_committee(value).await,
         "get_all_vns" => handlers.get_all_vns(value).await,
@@ -27,11 +27,14 @@ mod consensus;
 mod dan_node;
 mod dry_run_transaction_processor;
 mod event_subscription;
+mod genesis;
 mod http_ui;
 mod json_rpc;
 #[cfg(feature = "metrics")]
 mod metrics;
 mod p2p;
+mod slashing_evidence;
+mod state_store;
 mod substate_resolver;
 mod virtual_substate;
 
@@ -48,10 +51,10 @@ use tari_common::{
     configuration::bootstrap::{grpc_default_port, ApplicationType},
     exit_codes::{ExitCode, ExitError},
 };
-use tari_consensus::consensus_constants::ConsensusConstants;
+use tari_consensus::{consensus_constants::ConsensusConstants, hotstuff::substate_store};
 use tari_dan_app_utilities::keypair::setup_keypair_prompt;
-use tari_dan_common_types::SubstateAddress;
-use tari_dan_storage::global::DbFactory;
+use tari_dan_common_types::{NumPreshards, SubstateAddress};
+use tari_dan_storage::{consensus_models::StateIntegrityReport, global::DbFactory, StateStore};
 use tari_dan_storage_sqlite::SqliteDbFactory;
 use tari_shutdown::ShutdownSignal;
 use tokio::task;
@@ -61,8 +64,11 @@ pub use crate::config::{ApplicationConfig, ValidatorNodeConfig};
 use crate::{
     bootstrap::{spawn_services, Services},
     dan_node::DanNode,
+    genesis::Genesis,
     http_ui::server::run_http_ui_server,
     json_rpc::{spawn_json_rpc, JsonRpcHandlers},
+    slashing_evidence::SlashingEvidenceExporter,
+    state_store::{connect_state_store, StateStoreArchiver},
 };
 
 const LOG_TARGET: &str = "tari::validator_node::app";
@@ -118,7 +124,24 @@ pub async fn run_validator_node(
     #[cfg(feature = "metrics")]
     let metrics_registry = create_metrics_registry(keypair.public_key());
 
-    let consensus_constants = ConsensusConstants::from(config.network);
+    let mut consensus_constants = ConsensusConstants::from(config.network);
+    let genesis = config
+        .validator_node
+        .genesis_file
+        .as_ref()
+        .map(Genesis::load_from_file)
+        .transpose()
+        .map_err(|e| ExitError::new(ExitCode::ConfigError, e))?
+        .unwrap_or_default();
+    genesis
+        .consensus_constants
+        .apply_to(&mut consensus_constants)
+        .map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
+    consensus_constants.genesis_hash = genesis.hash();
+    if let Some(num_preshards) = config.validator_node.num_preshards {
+        consensus_constants.num_preshards =
+            NumPreshards::try_from(num_preshards).map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
+    }
     let base_node_client = create_base_layer_client(config).await?;
     let services = spawn_services(
         config,
@@ -126,6 +149,7 @@ pub async fn run_validator_node(
         keypair.clone(),
         global_db,
         consensus_constants,
+        &genesis,
         base_node_client.clone(),
         #[cfg(feature = "metrics")]
         &metrics_registry,
@@ -160,7 +184,9 @@ pub async fn run_validator_node(
 
     fs::write(config.common.base_path.join("pid"), process::id().to_string())
         .map_err(|e| ExitError::new(ExitCode::UnknownError, e))?;
-    let node = DanNode::new(services);
+    let evidence_exporter = SlashingEvidenceExporter::new(config.validator_node.data_dir.join("slashing_evidence"));
+    let state_archiver = StateStoreArchiver::new(&config.validator_node);
+    let node = DanNode::new(services, evidence_exporter, state_archiver);
     info!(target: LOG_TARGET, "🚀 Validator node started!");
     node.start(shutdown_signal)
         .await
@@ -169,6 +195,15 @@ pub async fn run_validator_node(
     Ok(())
 }
 
+/// Recomputes the state merkle root from the substates in the state store and compares it to the state merkle root of
+/// the last committed block. This opens the state store directly and should be run while the validator node is stopped.
+pub fn check_state_integrity(config: &ApplicationConfig) -> Result<StateIntegrityReport, anyhow::Error> {
+    let state_store =
+        connect_state_store(&config.validator_node).map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
+    let report = state_store.with_read_tx(|tx| substate_store::check_state_integrity(tx))?;
+    Ok(report)
+}
+
 async fn create_base_layer_client(config: &ApplicationConfig) -> Result<GrpcBaseNodeClient, ExitError> {
     let base_node_address = config.validator_node.base_node_grpc_url.clone().unwrap_or_else(|| {
         let port = grpc_default_port(ApplicationType::BaseNode, config.network);
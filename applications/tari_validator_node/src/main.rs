@@ -32,7 +32,7 @@ use tari_common::{
 };
 use tari_dan_app_utilities::configuration::load_configuration;
 use tari_shutdown::Shutdown;
-use tari_validator_node::{cli::Cli, run_validator_node, ApplicationConfig};
+use tari_validator_node::{check_state_integrity, cli::Cli, run_validator_node, ApplicationConfig};
 
 const LOG_TARGET: &str = "tari::validator_node::app";
 
@@ -81,6 +81,10 @@ async fn main_inner() -> Result<(), ExitError> {
         eprintln!("{}", e);
     }
 
+    if cli.check_state_integrity {
+        return run_state_integrity_check(&config);
+    }
+
     match run_validator_node(&config, shutdown.to_signal()).await {
         Ok(_) => info!(target: LOG_TARGET, "Validator node shutdown successfully"),
         Err(e) => match e.downcast() {
@@ -99,3 +103,15 @@ async fn main_inner() -> Result<(), ExitError> {
 
     Ok(())
 }
+
+fn run_state_integrity_check(config: &ApplicationConfig) -> Result<(), ExitError> {
+    let report = check_state_integrity(config).map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
+    println!("{}", report);
+    if !report.is_ok() {
+        return Err(ExitError::new(
+            ExitCode::DatabaseError,
+            "State store integrity check failed. The state store may be corrupt.",
+        ));
+    }
+    Ok(())
+}
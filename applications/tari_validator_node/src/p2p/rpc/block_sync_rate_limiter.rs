@@ -0,0 +1,195 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use libp2p::PeerId;
+
+/// Limits the rate at which blocks are streamed to each peer and allows a single block sync session per peer. Each
+/// peer has a budget of up to one second's worth of blocks that refills continuously, so an out-of-date peer syncing
+/// a long history is served at a steady rate instead of saturating this node.
+#[derive(Debug, Clone)]
+pub struct BlockSyncRateLimiter {
+    max_blocks_per_second: u32,
+    peers: Arc<Mutex<HashMap<PeerId, PeerBudget>>>,
+}
+
+impl BlockSyncRateLimiter {
+    /// Creates a new rate limiter. A limit of 0 disables rate limiting.
+    pub fn new(max_blocks_per_second: u32) -> Self {
+        Self {
+            max_blocks_per_second,
+            peers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Starts a block sync session for the peer. Returns None if the peer already has an active session.
+    pub fn start_session(&self, peer_id: PeerId) -> Option<BlockSyncPermit> {
+        let mut peers = self.peers.lock().unwrap();
+        let now = Instant::now();
+        // Forget idle peers that have a full budget
+        peers.retain(|_, budget| budget.is_active || !budget.is_full(now, self.max_blocks_per_second));
+
+        let budget = peers
+            .entry(peer_id)
+            .or_insert_with(|| PeerBudget::new(now, self.max_blocks_per_second));
+        if budget.is_active {
+            return None;
+        }
+        budget.is_active = true;
+
+        Some(BlockSyncPermit {
+            peer_id,
+            limiter: self.clone(),
+        })
+    }
+
+    fn reserve(&self, peer_id: &PeerId, num_blocks: usize) -> Duration {
+        if self.max_blocks_per_second == 0 {
+            return Duration::ZERO;
+        }
+
+        let mut peers = self.peers.lock().unwrap();
+        let now = Instant::now();
+        let budget = peers
+            .entry(*peer_id)
+            .or_insert_with(|| PeerBudget::new(now, self.max_blocks_per_second));
+        budget.refill(now, self.max_blocks_per_second);
+        // The budget may go negative, in which case the caller waits until it has been repaid
+        budget.available -= num_blocks as f64;
+        if budget.available >= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(-budget.available / f64::from(self.max_blocks_per_second))
+    }
+
+    fn end_session(&self, peer_id: &PeerId) {
+        if let Some(budget) = self.peers.lock().unwrap().get_mut(peer_id) {
+            budget.is_active = false;
+        }
+    }
+}
+
+/// An active block sync session for a peer. The session ends when the permit is dropped.
+#[derive(Debug)]
+pub struct BlockSyncPermit {
+    peer_id: PeerId,
+    limiter: BlockSyncRateLimiter,
+}
+
+impl BlockSyncPermit {
+    /// Waits until the given number of blocks may be sent to the peer
+    pub async fn acquire(&self, num_blocks: usize) {
+        let wait = self.limiter.reserve(&self.peer_id, num_blocks);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Drop for BlockSyncPermit {
+    fn drop(&mut self) {
+        self.limiter.end_session(&self.peer_id);
+    }
+}
+
+#[derive(Debug)]
+struct PeerBudget {
+    available: f64,
+    last_refill: Instant,
+    is_active: bool,
+}
+
+impl PeerBudget {
+    fn new(now: Instant, max_blocks_per_second: u32) -> Self {
+        Self {
+            available: f64::from(max_blocks_per_second),
+            last_refill: now,
+            is_active: false,
+        }
+    }
+
+    fn refill(&mut self, now: Instant, max_blocks_per_second: u32) {
+        let max = f64::from(max_blocks_per_second);
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * max).min(max);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self, now: Instant, max_blocks_per_second: u32) -> bool {
+        let max = f64::from(max_blocks_per_second);
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available + elapsed * max >= max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_allows_a_burst_of_one_seconds_worth_of_blocks() {
+        let limiter = BlockSyncRateLimiter::new(10);
+        let peer_id = PeerId::random();
+        assert_eq!(limiter.reserve(&peer_id, 10), Duration::ZERO);
+
+        // The next 5 blocks must wait until half of the budget has been refilled
+        let wait = limiter.reserve(&peer_id, 5);
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn it_refills_the_budget_up_to_one_second_of_blocks() {
+        let now = Instant::now();
+        let mut budget = PeerBudget::new(now, 10);
+        budget.available -= 10.0;
+        assert!(!budget.is_full(now, 10));
+
+        budget.refill(now + Duration::from_millis(500), 10);
+        assert!((budget.available - 5.0).abs() < f64::EPSILON);
+        assert!(!budget.is_full(now + Duration::from_millis(500), 10));
+        assert!(budget.is_full(now + Duration::from_secs(1), 10));
+
+        // Idle time does not accumulate a larger burst
+        budget.refill(now + Duration::from_secs(10), 10);
+        assert!((budget.available - 10.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn it_repays_an_overdrawn_budget_before_refilling() {
+        let limiter = BlockSyncRateLimiter::new(10);
+        let peer_id = PeerId::random();
+        limiter.reserve(&peer_id, 30);
+        {
+            let mut peers = limiter.peers.lock().unwrap();
+            let budget = peers.get_mut(&peer_id).unwrap();
+            budget.last_refill -= Duration::from_secs(1);
+        }
+        // One second only repays half of the 20 blocks that were overdrawn
+        let wait = limiter.reserve(&peer_id, 0);
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn it_does_not_limit_when_disabled() {
+        let limiter = BlockSyncRateLimiter::new(0);
+        let peer_id = PeerId::random();
+        assert_eq!(limiter.reserve(&peer_id, 1_000_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn it_allows_one_session_per_peer() {
+        let limiter = BlockSyncRateLimiter::new(10);
+        let peer_id = PeerId::random();
+        let permit = limiter.start_session(peer_id).unwrap();
+        assert!(limiter.start_session(peer_id).is_none());
+        assert!(limiter.start_session(PeerId::random()).is_some());
+
+        drop(permit);
+        assert!(limiter.start_session(peer_id).is_some());
+    }
+}
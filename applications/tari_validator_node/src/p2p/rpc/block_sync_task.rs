@@ -5,7 +5,13 @@ use std::collections::HashSet;
 
 use log::*;
 use tari_dan_common_types::{optional::Optional, Epoch};
-use tari_dan_p2p::proto::rpc::{sync_blocks_response::SyncData, QuorumCertificates, SyncBlocksResponse, Transactions};
+use tari_dan_p2p::proto::rpc::{
+    sync_blocks_response::SyncData,
+    QuorumCertificates,
+    SyncBlocksPageEnd,
+    SyncBlocksResponse,
+    Transactions,
+};
 use tari_dan_storage::{
     consensus_models::{Block, BlockId, QuorumCertificate, SubstateUpdate, TransactionRecord},
     StateStore,
@@ -15,6 +21,8 @@ use tari_dan_storage::{
 use tari_rpc_framework::RpcStatus;
 use tokio::sync::mpsc;
 
+use crate::p2p::rpc::block_sync_rate_limiter::BlockSyncPermit;
+
 const LOG_TARGET: &str = "tari::dan::rpc::sync_task";
 
 const BLOCK_BUFFER_SIZE: usize = 15;
@@ -31,6 +39,8 @@ pub struct BlockSyncTask<TStateStore: StateStore> {
     store: TStateStore,
     start_block_id: BlockId,
     up_to_epoch: Option<Epoch>,
+    max_blocks: usize,
+    permit: Option<BlockSyncPermit>,
     sender: mpsc::Sender<Result<SyncBlocksResponse, RpcStatus>>,
 }
 
@@ -39,12 +49,16 @@ impl<TStateStore: StateStore> BlockSyncTask<TStateStore> {
         store: TStateStore,
         start_block_id: BlockId,
         up_to_epoch: Option<Epoch>,
+        max_blocks: usize,
+        permit: Option<BlockSyncPermit>,
         sender: mpsc::Sender<Result<SyncBlocksResponse, RpcStatus>>,
     ) -> Self {
         Self {
             store,
             start_block_id,
             up_to_epoch,
+            max_blocks,
+            permit,
             sender,
         }
     }
@@ -54,7 +68,8 @@ impl<TStateStore: StateStore> BlockSyncTask<TStateStore> {
         let mut current_block_id = self.start_block_id;
         let mut counter = 0;
         loop {
-            match self.fetch_next_batch(&mut buffer, &current_block_id) {
+            let limit = BLOCK_BUFFER_SIZE.min(self.max_blocks - counter);
+            match self.fetch_next_batch(&mut buffer, &current_block_id, limit) {
                 Ok(last_block) => {
                     current_block_id = last_block;
                 },
@@ -72,16 +87,36 @@ impl<TStateStore: StateStore> BlockSyncTask<TStateStore> {
                 current_block_id,
             );
 
-            counter += buffer.len();
+            if let Some(permit) = &self.permit {
+                permit.acquire(num_items).await;
+            }
+
+            counter += num_items;
             for data in buffer.drain(..) {
                 self.send_block_data(data).await?;
             }
 
             // If we didn't fill up the buffer, send the final blocks
-            if num_items < buffer.capacity() {
+            if num_items < limit {
                 debug!( target: LOG_TARGET, "Sync to last commit complete. Streamed {} item(s)", counter);
                 break;
             }
+
+            if counter >= self.max_blocks {
+                debug!(
+                    target: LOG_TARGET,
+                    "Page of {} block(s) complete. Last block id: {}",
+                    counter,
+                    current_block_id,
+                );
+                self.send(Ok(SyncBlocksResponse {
+                    sync_data: Some(SyncData::PageEnd(SyncBlocksPageEnd {
+                        last_block_id: current_block_id.as_bytes().to_vec(),
+                    })),
+                }))
+                .await?;
+                return Ok(());
+            }
         }
 
         // match self.fetch_last_blocks(&mut buffer, &current_block_id).await {
@@ -105,7 +140,12 @@ impl<TStateStore: StateStore> BlockSyncTask<TStateStore> {
         Ok(())
     }
 
-    fn fetch_next_batch(&self, buffer: &mut BlockBuffer, current_block_id: &BlockId) -> Result<BlockId, StorageError> {
+    fn fetch_next_batch(
+        &self,
+        buffer: &mut BlockBuffer,
+        current_block_id: &BlockId,
+        limit: usize,
+    ) -> Result<BlockId, StorageError> {
         self.store.with_read_tx(|tx| {
             let mut current_block_id = *current_block_id;
             let mut last_block_id = current_block_id;
@@ -154,7 +194,7 @@ impl<TStateStore: StateStore> BlockSyncTask<TStateStore> {
                 let transactions = child.get_transactions(tx)?;
 
                 buffer.push((child, certificates, updates, transactions));
-                if buffer.len() == buffer.capacity() {
+                if buffer.len() >= limit {
                     break;
                 }
             }
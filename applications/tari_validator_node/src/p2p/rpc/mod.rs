@@ -20,6 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod block_sync_rate_limiter;
 mod block_sync_task;
 mod service_impl;
 mod state_sync_task;
@@ -27,21 +28,23 @@ mod state_sync_task;
 pub use service_impl::ValidatorNodeRpcServiceImpl;
 use tari_dan_common_types::PeerAddress;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_validator_node_rpc::rpc_service::ValidatorNodeRpcServer;
 
 use crate::{
+    config::BlockSyncConfig,
     consensus::ConsensusHandle,
     p2p::services::mempool::MempoolHandle,
+    state_store::ValidatorNodeStateStore,
     virtual_substate::VirtualSubstateManager,
 };
 
 pub fn create_tari_validator_node_rpc_service(
     epoch_manager: EpochManagerHandle<PeerAddress>,
-    shard_store_store: SqliteStateStore<PeerAddress>,
+    shard_store_store: ValidatorNodeStateStore,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PeerAddress>, EpochManagerHandle<PeerAddress>>,
+    virtual_substate_manager: VirtualSubstateManager<ValidatorNodeStateStore, EpochManagerHandle<PeerAddress>>,
     consensus: ConsensusHandle,
+    block_sync_config: &BlockSyncConfig,
 ) -> ValidatorNodeRpcServer<ValidatorNodeRpcServiceImpl> {
     ValidatorNodeRpcServer::new(ValidatorNodeRpcServiceImpl::new(
         epoch_manager,
@@ -49,5 +52,6 @@ pub fn create_tari_validator_node_rpc_service(
         mempool,
         virtual_substate_manager,
         consensus,
+        block_sync_config,
     ))
 }
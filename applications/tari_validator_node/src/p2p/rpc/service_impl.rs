@@ -51,17 +51,22 @@ use tari_dan_storage::{
 use tari_engine_types::virtual_substate::VirtualSubstateId;
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerReader};
 use tari_rpc_framework::{Request, Response, RpcStatus, Streaming};
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tari_validator_node_rpc::rpc_service::ValidatorNodeRpcService;
 use tokio::{sync::mpsc, task};
 
 use crate::{
+    config::BlockSyncConfig,
     consensus::ConsensusHandle,
     p2p::{
-        rpc::{block_sync_task::BlockSyncTask, state_sync_task::StateSyncTask},
+        rpc::{
+            block_sync_rate_limiter::BlockSyncRateLimiter,
+            block_sync_task::BlockSyncTask,
+            state_sync_task::StateSyncTask,
+        },
         services::mempool::MempoolHandle,
     },
+    state_store::ValidatorNodeStateStore,
     virtual_substate::VirtualSubstateManager,
 };
 
@@ -69,22 +74,22 @@ const LOG_TARGET: &str = "tari::dan::p2p::rpc";
 
 pub struct ValidatorNodeRpcServiceImpl {
     epoch_manager: EpochManagerHandle<PeerAddress>,
-    shard_state_store: SqliteStateStore<PeerAddress>,
+    shard_state_store: ValidatorNodeStateStore,
     mempool: MempoolHandle,
-    virtual_substate_manager: VirtualSubstateManager<SqliteStateStore<PeerAddress>, EpochManagerHandle<PeerAddress>>,
+    virtual_substate_manager: VirtualSubstateManager<ValidatorNodeStateStore, EpochManagerHandle<PeerAddress>>,
     consensus: ConsensusHandle,
+    block_sync_rate_limiter: BlockSyncRateLimiter,
+    max_blocks_per_sync_page: usize,
 }
 
 impl ValidatorNodeRpcServiceImpl {
     pub fn new(
         epoch_manager: EpochManagerHandle<PeerAddress>,
-        shard_state_store: SqliteStateStore<PeerAddress>,
+        shard_state_store: ValidatorNodeStateStore,
         mempool: MempoolHandle,
-        virtual_substate_manager: VirtualSubstateManager<
-            SqliteStateStore<PeerAddress>,
-            EpochManagerHandle<PeerAddress>,
-        >,
+        virtual_substate_manager: VirtualSubstateManager<ValidatorNodeStateStore, EpochManagerHandle<PeerAddress>>,
         consensus: ConsensusHandle,
+        block_sync_config: &BlockSyncConfig,
     ) -> Self {
         Self {
             epoch_manager,
@@ -92,6 +97,8 @@ impl ValidatorNodeRpcServiceImpl {
             mempool,
             virtual_substate_manager,
             consensus,
+            block_sync_rate_limiter: BlockSyncRateLimiter::new(block_sync_config.max_blocks_per_second_per_peer),
+            max_blocks_per_sync_page: block_sync_config.max_blocks_per_page,
         }
     }
 }
@@ -262,7 +269,17 @@ impl ValidatorNodeRpcService for ValidatorNodeRpcServiceImpl {
         &self,
         request: Request<SyncBlocksRequest>,
     ) -> Result<Streaming<SyncBlocksResponse>, RpcStatus> {
+        let permit = match request.peer_id() {
+            Some(peer_id) => Some(self.block_sync_rate_limiter.start_session(*peer_id).ok_or_else(|| {
+                RpcStatus::conflict(format!("Peer {peer_id} already has an active block sync session"))
+            })?),
+            None => None,
+        };
         let req = request.into_message();
+        let max_blocks = match req.max_blocks {
+            0 => self.max_blocks_per_sync_page,
+            n => (n as usize).min(self.max_blocks_per_sync_page),
+        };
         let store = self.shard_state_store.clone();
         let current_epoch = self
             .epoch_manager
@@ -316,7 +333,17 @@ impl ValidatorNodeRpcService for ValidatorNodeRpcServiceImpl {
         };
 
         let (sender, receiver) = mpsc::channel(10);
-        task::spawn(BlockSyncTask::new(self.shard_state_store.clone(), start_block_id, None, sender).run());
+        task::spawn(
+            BlockSyncTask::new(
+                self.shard_state_store.clone(),
+                start_block_id,
+                None,
+                max_blocks,
+                permit,
+                sender,
+            )
+            .run(),
+        );
 
         Ok(Streaming::new(receiver))
     }
@@ -4,6 +4,7 @@
 use tari_dan_storage::StorageError;
 use tari_epoch_manager::EpochManagerError;
 use tari_networking::NetworkingError;
+use tari_transaction::TransactionId;
 use tokio::sync::{mpsc, oneshot};
 
 use crate::{
@@ -30,6 +31,11 @@ pub enum MempoolError {
     TransactionValidationError(#[from] TransactionValidationError),
     #[error("Network error: {0}")]
     NetworkingError(#[from] NetworkingError),
+    #[error("Transaction {transaction_id} reuses the idempotency key of transaction {existing_id}")]
+    DuplicateIdempotencyKey {
+        transaction_id: TransactionId,
+        existing_id: TransactionId,
+    },
 }
 
 impl From<mpsc::error::SendError<MempoolRequest>> for MempoolError {
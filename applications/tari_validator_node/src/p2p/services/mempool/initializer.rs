@@ -22,11 +22,10 @@
 
 use libp2p::{gossipsub, PeerId};
 use log::*;
-use tari_dan_common_types::{NumPreshards, PeerAddress};
+use tari_dan_common_types::{Epoch, NumPreshards, PeerAddress};
 use tari_dan_p2p::TariMessagingSpec;
 use tari_epoch_manager::base_layer::EpochManagerHandle;
 use tari_networking::NetworkingHandle;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::Transaction;
 use tokio::{sync::mpsc, task, task::JoinHandle};
 
@@ -35,6 +34,7 @@ use super::metrics::PrometheusMempoolMetrics;
 use crate::{
     consensus::ConsensusHandle,
     p2p::services::mempool::{handle::MempoolHandle, service::MempoolService},
+    state_store::ValidatorNodeStateStore,
     transaction_validators::TransactionValidationError,
     validator::Validator,
 };
@@ -43,9 +43,10 @@ const LOG_TARGET: &str = "tari::dan::validator_node::mempool";
 
 pub fn spawn<TValidator>(
     num_preshards: NumPreshards,
+    idempotency_key_window: Epoch,
     epoch_manager: EpochManagerHandle<PeerAddress>,
     transaction_validator: TValidator,
-    state_store: SqliteStateStore<PeerAddress>,
+    state_store: ValidatorNodeStateStore,
     consensus_handle: ConsensusHandle,
     networking: NetworkingHandle<TariMessagingSpec>,
     rx_gossip: mpsc::UnboundedReceiver<(PeerId, gossipsub::Message)>,
@@ -62,6 +63,7 @@ where
     let metrics = PrometheusMempoolMetrics::new(metrics_registry);
     let mempool = MempoolService::new(
         num_preshards,
+        idempotency_key_window,
         rx_mempool_request,
         epoch_manager,
         transaction_validator,
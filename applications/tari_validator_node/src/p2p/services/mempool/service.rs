@@ -24,13 +24,16 @@ use std::{collections::HashSet, fmt::Display, iter};
 
 use libp2p::{gossipsub, PeerId};
 use log::*;
-use tari_dan_common_types::{optional::Optional, NumPreshards, PeerAddress, ShardGroup, ToSubstateAddress};
+use tari_dan_common_types::{optional::Optional, Epoch, NumPreshards, PeerAddress, ShardGroup, ToSubstateAddress};
 use tari_dan_p2p::{DanMessage, NewTransactionMessage, TariMessagingSpec};
-use tari_dan_storage::{consensus_models::TransactionRecord, StateStore};
+use tari_dan_storage::{
+    consensus_models::{LeafBlock, TransactionRecord},
+    StateStore,
+    StateStoreReadTransaction,
+};
 use tari_engine_types::commit_result::RejectReason;
 use tari_epoch_manager::{base_layer::EpochManagerHandle, EpochManagerEvent, EpochManagerReader};
 use tari_networking::NetworkingHandle;
-use tari_state_store_sqlite::SqliteStateStore;
 use tari_transaction::{Transaction, TransactionId};
 use tokio::sync::{mpsc, oneshot};
 
@@ -43,7 +46,8 @@ use crate::{
         gossip::{IncomingMessage, MempoolGossip},
         handle::MempoolRequest,
     },
-    transaction_validators::TransactionValidationError,
+    state_store::ValidatorNodeStateStore,
+    transaction_validators::{EpochRangeValidator, TransactionValidationError},
     validator::Validator,
 };
 
@@ -52,10 +56,11 @@ const LOG_TARGET: &str = "tari::validator_node::mempool::service";
 #[derive(Debug)]
 pub struct MempoolService<TValidator> {
     transactions: HashSet<TransactionId>,
+    idempotency_key_window: Epoch,
     mempool_requests: mpsc::Receiver<MempoolRequest>,
     epoch_manager: EpochManagerHandle<PeerAddress>,
     before_execute_validator: TValidator,
-    state_store: SqliteStateStore<PeerAddress>,
+    state_store: ValidatorNodeStateStore,
     gossip: MempoolGossip<PeerAddress>,
     consensus_handle: ConsensusHandle,
     #[cfg(feature = "metrics")]
@@ -67,10 +72,11 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
 {
     pub(super) fn new(
         num_preshards: NumPreshards,
+        idempotency_key_window: Epoch,
         mempool_requests: mpsc::Receiver<MempoolRequest>,
         epoch_manager: EpochManagerHandle<PeerAddress>,
         before_execute_validator: TValidator,
-        state_store: SqliteStateStore<PeerAddress>,
+        state_store: ValidatorNodeStateStore,
         consensus_handle: ConsensusHandle,
         networking: NetworkingHandle<TariMessagingSpec>,
         rx_gossip: mpsc::UnboundedReceiver<(PeerId, gossipsub::Message)>,
@@ -79,6 +85,7 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
         Self {
             gossip: MempoolGossip::new(num_preshards, epoch_manager.clone(), networking, rx_gossip),
             transactions: Default::default(),
+            idempotency_key_window,
             mempool_requests,
             epoch_manager,
             before_execute_validator,
@@ -221,7 +228,13 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
         #[cfg(feature = "metrics")]
         self.metrics.on_transaction_received(&transaction);
 
-        if let Err(e) = self.before_execute_validator.validate(&(), &transaction) {
+        let current_epoch = self.consensus_handle.current_view().get_epoch();
+        let validation_result = self
+            .before_execute_validator
+            .validate(&(), &transaction)
+            // Reject transactions that cannot be sequenced in the current epoch
+            .and_then(|_| EpochRangeValidator::new().validate(&current_epoch, &transaction));
+        if let Err(e) = validation_result {
             let transaction_id = *transaction.id();
             self.state_store.with_write_tx(|tx| {
                 TransactionRecord::new(transaction)
@@ -236,6 +249,21 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
             return Err(e.into());
         }
 
+        if let Some(existing_id) = self.get_conflicting_idempotency_key(current_epoch, &transaction)? {
+            let transaction_id = *transaction.id();
+            self.state_store.with_write_tx(|tx| {
+                TransactionRecord::new(transaction)
+                    .set_abort_reason(RejectReason::InvalidTransaction(format!(
+                        "Idempotency key already used by transaction {existing_id}"
+                    )))
+                    .insert(tx)
+            })?;
+            return Err(MempoolError::DuplicateIdempotencyKey {
+                transaction_id,
+                existing_id,
+            });
+        }
+
         // Get the shards involved in claim fees.
         let fee_claims = transaction.fee_claims().collect::<Vec<_>>();
 
@@ -251,7 +279,6 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
             warn!(target: LOG_TARGET, "⚠ No involved shards for payload");
         }
 
-        let current_epoch = self.consensus_handle.current_view().get_epoch();
         let tx_substate_address = transaction.id().to_substate_address();
 
         let local_committee_shard = self.epoch_manager.get_local_committee_info(current_epoch).await?;
@@ -321,6 +348,26 @@ where TValidator: Validator<Transaction, Context = (), Error = TransactionValida
         Ok(())
     }
 
+    /// Returns the id of another transaction that holds the signer-bound idempotency key of the given transaction
+    /// within the idempotency window, if any. This is only a pre-filter, consensus reserves the key when the
+    /// transaction is prepared.
+    fn get_conflicting_idempotency_key(
+        &self,
+        current_epoch: Epoch,
+        transaction: &Transaction,
+    ) -> Result<Option<TransactionId>, MempoolError> {
+        let Some(idempotency_key) = transaction.signer_idempotency_key() else {
+            return Ok(None);
+        };
+        let since_epoch = current_epoch.saturating_sub(self.idempotency_key_window);
+        let tx = self.state_store.create_read_tx()?;
+        let Some(leaf_block) = LeafBlock::get(&tx, current_epoch).optional()? else {
+            return Ok(None);
+        };
+        let existing_id = tx.transaction_idempotency_keys_get(&idempotency_key, since_epoch, leaf_block.block_id())?;
+        Ok(existing_id.filter(|id| id != transaction.id()))
+    }
+
     fn transaction_exists(&self, id: &TransactionId) -> Result<bool, MempoolError> {
         if self.transactions.contains(id) {
             debug!(
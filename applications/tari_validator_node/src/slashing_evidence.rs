@@ -0,0 +1,66 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::consensus_models::DoubleVoteEvidence;
+
+/// A self-contained record of validator misbehaviour. The evidence contains the signed votes and the headers of the
+/// blocks that were voted for, so it can be verified against the validator's registered public key without access to
+/// the sidechain.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SlashingEvidenceFile {
+    pub public_key: PublicKey,
+    pub epoch: Epoch,
+    pub height: NodeHeight,
+    pub double_vote: DoubleVoteEvidence,
+}
+
+/// Writes slashing evidence detected by consensus to a directory.
+///
+/// Submitting the evidence to the base layer is not implemented: neither the base node nor the wallet gRPC API can
+/// accept evidence or penalize a validator. Until the base layer provides this, evidence is only exported, and nothing
+/// penalizes the offending validator.
+// TODO: submit the evidence through the base node or wallet client once the base layer can verify it
+#[derive(Debug, Clone)]
+pub struct SlashingEvidenceExporter {
+    dir: PathBuf,
+}
+
+impl SlashingEvidenceExporter {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the evidence to a file named after the offending validator, epoch and height, returning the path. Only
+    /// one file is kept per offence.
+    pub fn export_double_vote(&self, evidence: DoubleVoteEvidence) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!(
+            "double_vote_{}_{}_{}.json",
+            evidence.public_key(),
+            evidence.epoch().as_u64(),
+            evidence.height().as_u64()
+        ));
+        let file = SlashingEvidenceFile {
+            public_key: evidence.public_key().clone(),
+            epoch: evidence.epoch(),
+            height: evidence.height(),
+            double_vote: evidence,
+        };
+        let json = serde_json::to_string_pretty(&file).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        fs::write(&path, json)?;
+        Ok(path)
+    }
+}
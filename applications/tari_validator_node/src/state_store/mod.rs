@@ -0,0 +1,161 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod transaction;
+
+use std::path::{Path, PathBuf};
+
+#[cfg(not(feature = "rocksdb"))]
+use anyhow::bail;
+use log::*;
+use tari_dan_common_types::{Epoch, PeerAddress};
+use tari_dan_storage::{StateStore, StorageError};
+#[cfg(feature = "rocksdb")]
+use tari_state_store_rocksdb::RocksDbStateStore;
+use tari_state_store_sqlite::{EpochArchiveStats, SqliteStateStore};
+pub use transaction::{ValidatorNodeReadTransaction, ValidatorNodeWriteTransaction};
+
+use crate::config::{StateStoreBackend, ValidatorNodeConfig};
+
+const LOG_TARGET: &str = "tari::validator_node::state_store";
+
+/// The consensus state store used by the validator node. The backend is selected by the `backend` configuration
+/// setting when the node starts. The RocksDB backend is only available if the node is built with the `rocksdb`
+/// feature.
+#[derive(Debug, Clone)]
+pub enum ValidatorNodeStateStore {
+    Sqlite(SqliteStateStore<PeerAddress>),
+    #[cfg(feature = "rocksdb")]
+    RocksDb(RocksDbStateStore<PeerAddress>),
+}
+
+impl ValidatorNodeStateStore {
+    pub fn backend(&self) -> StateStoreBackend {
+        match self {
+            Self::Sqlite(_) => StateStoreBackend::Sqlite,
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb(_) => StateStoreBackend::RocksDb,
+        }
+    }
+
+    /// Returns the epochs before `before_epoch` that have committed blocks in the state store. Epoch archiving is only
+    /// supported by the SQLite backend, so this is always empty for other backends.
+    pub fn get_archivable_epochs(&self, before_epoch: Epoch) -> Result<Vec<Epoch>, StorageError> {
+        match self {
+            Self::Sqlite(store) => store.get_archivable_epochs(before_epoch),
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb(_) => Ok(vec![]),
+        }
+    }
+
+    /// Moves the committed data for the epoch into an archive database in `archive_dir`
+    pub fn archive_epoch(&self, archive_dir: &Path, epoch: Epoch) -> Result<EpochArchiveStats, StorageError> {
+        match self {
+            Self::Sqlite(store) => store.archive_epoch(archive_dir, epoch),
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb(_) => Ok(EpochArchiveStats::default()),
+        }
+    }
+}
+
+impl StateStore for ValidatorNodeStateStore {
+    type Addr = PeerAddress;
+    type ReadTransaction<'a> = ValidatorNodeReadTransaction<'a>;
+    type WriteTransaction<'a> = ValidatorNodeWriteTransaction<'a>;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        match self {
+            Self::Sqlite(store) => Ok(store.create_read_tx()?.into()),
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb(store) => Ok(store.create_read_tx()?.into()),
+        }
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        match self {
+            Self::Sqlite(store) => Ok(store.create_write_tx()?.into()),
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb(store) => Ok(store.create_write_tx()?.into()),
+        }
+    }
+}
+
+pub fn connect_state_store(config: &ValidatorNodeConfig) -> anyhow::Result<ValidatorNodeStateStore> {
+    let store = match config.state_store.backend {
+        StateStoreBackend::Sqlite => ValidatorNodeStateStore::Sqlite(
+            SqliteStateStore::connect_with_options(
+                &format!("sqlite://{}", config.state_db_path().display()),
+                &config.state_store.to_connection_options(),
+            )?
+            .with_epoch_archive(config.state_archive_dir())?,
+        ),
+        #[cfg(feature = "rocksdb")]
+        StateStoreBackend::RocksDb => {
+            ValidatorNodeStateStore::RocksDb(RocksDbStateStore::connect(config.state_rocksdb_path())?)
+        },
+        #[cfg(not(feature = "rocksdb"))]
+        StateStoreBackend::RocksDb => {
+            bail!("This validator node was built without the rocksdb state store backend")
+        },
+    };
+    info!(target: LOG_TARGET, "🗄️ Using the {} state store backend", store.backend());
+
+    Ok(store)
+}
+
+/// Moves committed data for epochs older than the configured number of retained epochs out of the state store and into
+/// per-epoch archive files.
+#[derive(Debug, Clone)]
+pub struct StateStoreArchiver {
+    archive_dir: PathBuf,
+    retained_epochs: u64,
+}
+
+impl StateStoreArchiver {
+    /// Returns None if archiving is disabled or not supported by the state store backend
+    pub fn new(config: &ValidatorNodeConfig) -> Option<Self> {
+        if config.state_store.archive_retained_epochs == 0 {
+            return None;
+        }
+        if config.state_store.backend != StateStoreBackend::Sqlite {
+            warn!(
+                target: LOG_TARGET,
+                "⚠️ archive_retained_epochs is set but epoch archiving is not supported by the rocksdb state store"
+            );
+            return None;
+        }
+
+        Some(Self {
+            archive_dir: config.state_archive_dir(),
+            retained_epochs: config.state_store.archive_retained_epochs,
+        })
+    }
+
+    /// Archives all epochs that are more than the retained number of epochs before the current epoch. Each epoch is
+    /// archived on a blocking thread that holds the state store lock only for that epoch, so that consensus can use
+    /// the state store between epochs.
+    pub async fn archive_old_epochs(
+        &self,
+        store: &ValidatorNodeStateStore,
+        current_epoch: Epoch,
+    ) -> anyhow::Result<()> {
+        let before_epoch = current_epoch.saturating_sub(Epoch(self.retained_epochs));
+        let epochs = {
+            let store = store.clone();
+            tokio::task::spawn_blocking(move || store.get_archivable_epochs(before_epoch)).await??
+        };
+        for epoch in epochs {
+            let store = store.clone();
+            let archive_dir = self.archive_dir.clone();
+            let stats = tokio::task::spawn_blocking(move || store.archive_epoch(&archive_dir, epoch)).await??;
+            debug!(
+                target: LOG_TARGET,
+                "Archived epoch {} to {} ({:?})",
+                epoch,
+                self.archive_dir.display(),
+                stats
+            );
+        }
+        Ok(())
+    }
+}
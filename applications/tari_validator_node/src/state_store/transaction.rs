@@ -0,0 +1,1165 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    iter::Peekable,
+    ops::{Deref, RangeInclusive},
+};
+
+use indexmap::IndexMap;
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_dan_common_types::{
+    shard::Shard,
+    Epoch,
+    NodeHeight,
+    PeerAddress,
+    ShardGroup,
+    SubstateAddress,
+    SubstateRequirement,
+    ToSubstateAddress,
+    VersionedSubstateId,
+};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockDiff,
+        BlockId,
+        BlockTransactionExecution,
+        BurntUtxo,
+        Decision,
+        EpochCheckpoint,
+        ForeignParkedProposal,
+        ForeignProposal,
+        ForeignProposalAtom,
+        ForeignProposalStatus,
+        ForeignReceiveCounters,
+        ForeignSendCounters,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockConflict,
+        LockedBlock,
+        LockedSubstateValue,
+        NoVoteReason,
+        PendingShardStateTreeDiff,
+        QcId,
+        QuorumCertificate,
+        StateTransition,
+        StateTransitionId,
+        SubstateChange,
+        SubstateLock,
+        SubstatePledges,
+        SubstateRecord,
+        SubstateWrite,
+        TransactionOrdering,
+        TransactionPoolConfirmedStage,
+        TransactionPoolRecord,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+        TransactionRecord,
+        ValidatorConsensusStats,
+        ValidatorEpochStats,
+        ValidatorStatsUpdate,
+        VersionedStateHashTreeDiff,
+        Vote,
+    },
+    Ordering,
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateId;
+#[cfg(feature = "rocksdb")]
+use tari_state_store_rocksdb::RocksDbStateStore;
+use tari_state_store_sqlite::SqliteStateStore;
+use tari_state_tree::{Node, NodeKey, StaleTreeNode, Version};
+use tari_transaction::{IdempotencyKey, TransactionId};
+
+type SqliteReadTransaction<'a> = <SqliteStateStore<PeerAddress> as StateStore>::ReadTransaction<'a>;
+type SqliteWriteTransaction<'a> = <SqliteStateStore<PeerAddress> as StateStore>::WriteTransaction<'a>;
+#[cfg(feature = "rocksdb")]
+type RocksDbReadTransaction<'a> = <RocksDbStateStore<PeerAddress> as StateStore>::ReadTransaction<'a>;
+#[cfg(feature = "rocksdb")]
+type RocksDbWriteTransaction<'a> = <RocksDbStateStore<PeerAddress> as StateStore>::WriteTransaction<'a>;
+
+/// A read transaction on the state store backend that the validator node is configured to use.
+///
+/// A write transaction dereferences to a read transaction, so the write transaction of each backend is also a variant
+/// of the read transaction.
+pub enum ValidatorNodeReadTransaction<'a> {
+    Sqlite(SqliteReadTransaction<'a>),
+    SqliteWrite(SqliteWriteTransaction<'a>),
+    #[cfg(feature = "rocksdb")]
+    RocksDb(RocksDbReadTransaction<'a>),
+    #[cfg(feature = "rocksdb")]
+    RocksDbWrite(RocksDbWriteTransaction<'a>),
+}
+
+/// A write transaction on the state store backend that the validator node is configured to use. This always wraps one
+/// of the write variants of [`ValidatorNodeReadTransaction`].
+pub struct ValidatorNodeWriteTransaction<'a>(ValidatorNodeReadTransaction<'a>);
+
+impl<'a> From<SqliteReadTransaction<'a>> for ValidatorNodeReadTransaction<'a> {
+    fn from(tx: SqliteReadTransaction<'a>) -> Self {
+        Self::Sqlite(tx)
+    }
+}
+
+impl<'a> From<SqliteWriteTransaction<'a>> for ValidatorNodeWriteTransaction<'a> {
+    fn from(tx: SqliteWriteTransaction<'a>) -> Self {
+        Self(ValidatorNodeReadTransaction::SqliteWrite(tx))
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl<'a> From<RocksDbReadTransaction<'a>> for ValidatorNodeReadTransaction<'a> {
+    fn from(tx: RocksDbReadTransaction<'a>) -> Self {
+        Self::RocksDb(tx)
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl<'a> From<RocksDbWriteTransaction<'a>> for ValidatorNodeWriteTransaction<'a> {
+    fn from(tx: RocksDbWriteTransaction<'a>) -> Self {
+        Self(ValidatorNodeReadTransaction::RocksDbWrite(tx))
+    }
+}
+
+impl<'a> Deref for ValidatorNodeWriteTransaction<'a> {
+    type Target = ValidatorNodeReadTransaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Calls the read transaction method on the backend transaction
+macro_rules! dispatch_read {
+    ($self:ident, |$tx:ident| $call:expr) => {
+        match $self {
+            Self::Sqlite($tx) => $call,
+            Self::SqliteWrite(write_tx) => {
+                let $tx = &**write_tx;
+                $call
+            },
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDb($tx) => $call,
+            #[cfg(feature = "rocksdb")]
+            Self::RocksDbWrite(write_tx) => {
+                let $tx = &**write_tx;
+                $call
+            },
+        }
+    };
+}
+
+/// Calls the write transaction method on the backend write transaction
+macro_rules! dispatch_write {
+    ($self:ident, |$tx:ident| $call:expr) => {
+        match &mut $self.0 {
+            ValidatorNodeReadTransaction::SqliteWrite($tx) => $call,
+            #[cfg(feature = "rocksdb")]
+            ValidatorNodeReadTransaction::RocksDbWrite($tx) => $call,
+            _ => unreachable!("ValidatorNodeWriteTransaction always contains a write transaction"),
+        }
+    };
+}
+
+impl<'tx> StateStoreReadTransaction for ValidatorNodeReadTransaction<'tx> {
+    type Addr = PeerAddress;
+
+    fn last_sent_vote_get(&self) -> Result<LastSentVote, StorageError> {
+        dispatch_read!(self, |tx| tx.last_sent_vote_get())
+    }
+
+    fn last_voted_get(&self) -> Result<LastVoted, StorageError> {
+        dispatch_read!(self, |tx| tx.last_voted_get())
+    }
+
+    fn last_executed_get(&self) -> Result<LastExecuted, StorageError> {
+        dispatch_read!(self, |tx| tx.last_executed_get())
+    }
+
+    fn last_proposed_get(&self) -> Result<LastProposed, StorageError> {
+        dispatch_read!(self, |tx| tx.last_proposed_get())
+    }
+
+    fn locked_block_get(&self, epoch: Epoch) -> Result<LockedBlock, StorageError> {
+        dispatch_read!(self, |tx| tx.locked_block_get(epoch))
+    }
+
+    fn leaf_block_get(&self, epoch: Epoch) -> Result<LeafBlock, StorageError> {
+        dispatch_read!(self, |tx| tx.leaf_block_get(epoch))
+    }
+
+    fn high_qc_get(&self, epoch: Epoch) -> Result<HighQc, StorageError> {
+        dispatch_read!(self, |tx| tx.high_qc_get(epoch))
+    }
+
+    fn foreign_proposals_get_any<'a, I: IntoIterator<Item = &'a BlockId>>(
+        &self,
+        block_ids: I,
+    ) -> Result<Vec<ForeignProposal>, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_proposals_get_any(block_ids))
+    }
+
+    fn foreign_proposals_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_proposals_exists(block_id))
+    }
+
+    fn foreign_proposals_has_unconfirmed(&self, epoch: Epoch) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_proposals_has_unconfirmed(epoch))
+    }
+
+    fn foreign_proposals_get_all_new(
+        &self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<ForeignProposal>, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_proposals_get_all_new(block_id, limit))
+    }
+
+    fn foreign_proposal_get_all_pending(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+    ) -> Result<Vec<ForeignProposalAtom>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .foreign_proposal_get_all_pending(from_block_id, to_block_id))
+    }
+
+    fn foreign_send_counters_get(&self, block_id: &BlockId) -> Result<ForeignSendCounters, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_send_counters_get(block_id))
+    }
+
+    fn foreign_receive_counters_get(&self) -> Result<ForeignReceiveCounters, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_receive_counters_get())
+    }
+
+    fn transactions_get(&self, tx_id: &TransactionId) -> Result<TransactionRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get(tx_id))
+    }
+
+    fn transactions_exists(&self, tx_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_exists(tx_id))
+    }
+
+    fn transactions_get_any<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &self,
+        tx_ids: I,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get_any(tx_ids))
+    }
+
+    fn transactions_get_paginated(
+        &self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_get_paginated(
+            limit,
+            offset,
+            asc_desc_created_at
+        ))
+    }
+
+    fn transaction_idempotency_keys_get(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        since_epoch: Epoch,
+        from_block_id: &BlockId,
+    ) -> Result<Option<TransactionId>, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_idempotency_keys_get(
+            idempotency_key,
+            since_epoch,
+            from_block_id
+        ))
+    }
+
+    fn transaction_executions_get(
+        &self,
+        tx_id: &TransactionId,
+        block: &BlockId,
+    ) -> Result<BlockTransactionExecution, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_executions_get(tx_id, block))
+    }
+
+    fn transaction_executions_get_pending_for_block(
+        &self,
+        tx_id: &TransactionId,
+        from_block_id: &BlockId,
+    ) -> Result<BlockTransactionExecution, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .transaction_executions_get_pending_for_block(tx_id, from_block_id))
+    }
+
+    fn blocks_get(&self, block_id: &BlockId) -> Result<Block, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get(block_id))
+    }
+
+    fn blocks_get_all_ids_by_height(&self, epoch: Epoch, height: NodeHeight) -> Result<Vec<BlockId>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_all_ids_by_height(epoch, height))
+    }
+
+    fn blocks_get_genesis_for_epoch(&self, epoch: Epoch) -> Result<Block, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_genesis_for_epoch(epoch))
+    }
+
+    fn blocks_get_last_n_in_epoch(&self, n: usize, epoch: Epoch) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_last_n_in_epoch(n, epoch))
+    }
+
+    fn blocks_get_all_between(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        start_block_height: NodeHeight,
+        end_block_height: NodeHeight,
+        include_dummy_blocks: bool,
+        limit: u64,
+    ) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_all_between(
+            epoch,
+            shard_group,
+            start_block_height,
+            end_block_height,
+            include_dummy_blocks,
+            limit
+        ))
+    }
+
+    fn blocks_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_exists(block_id))
+    }
+
+    fn blocks_is_ancestor(&self, descendant: &BlockId, ancestor: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_is_ancestor(descendant, ancestor))
+    }
+
+    fn blocks_get_all_by_parent(&self, parent: &BlockId) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_all_by_parent(parent))
+    }
+
+    fn blocks_get_ids_by_parent(&self, parent: &BlockId) -> Result<Vec<BlockId>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_ids_by_parent(parent))
+    }
+
+    fn blocks_get_parent_chain(&self, block_id: &BlockId, limit: usize) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_parent_chain(block_id, limit))
+    }
+
+    fn blocks_get_pending_transactions(&self, block_id: &BlockId) -> Result<Vec<TransactionId>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_pending_transactions(block_id))
+    }
+
+    fn blocks_get_total_leader_fee_for_epoch(
+        &self,
+        epoch: Epoch,
+        validator_public_key: &PublicKey,
+    ) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .blocks_get_total_leader_fee_for_epoch(epoch, validator_public_key))
+    }
+
+    fn blocks_get_any_with_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        validator_public_key: Option<&PublicKey>,
+    ) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .blocks_get_any_with_epoch_range(epoch_range, validator_public_key))
+    }
+
+    fn blocks_get_paginated(
+        &self,
+        limit: u64,
+        offset: u64,
+        filter_index: Option<usize>,
+        filter: Option<String>,
+        ordering_index: Option<usize>,
+        ordering: Option<Ordering>,
+    ) -> Result<Vec<Block>, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_paginated(
+            limit,
+            offset,
+            filter_index,
+            filter,
+            ordering_index,
+            ordering
+        ))
+    }
+
+    fn blocks_get_count(&self) -> Result<i64, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_get_count())
+    }
+
+    fn filtered_blocks_get_count(
+        &self,
+        filter_index: Option<usize>,
+        filter: Option<String>,
+    ) -> Result<i64, StorageError> {
+        dispatch_read!(self, |tx| tx.filtered_blocks_get_count(filter_index, filter))
+    }
+
+    fn blocks_max_height(&self) -> Result<NodeHeight, StorageError> {
+        dispatch_read!(self, |tx| tx.blocks_max_height())
+    }
+
+    fn block_diffs_get(&self, block_id: &BlockId) -> Result<BlockDiff, StorageError> {
+        dispatch_read!(self, |tx| tx.block_diffs_get(block_id))
+    }
+
+    fn block_diffs_get_last_change_for_substate(
+        &self,
+        block_id: &BlockId,
+        substate_id: &SubstateId,
+    ) -> Result<SubstateChange, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .block_diffs_get_last_change_for_substate(block_id, substate_id))
+    }
+
+    fn quorum_certificates_get(&self, qc_id: &QcId) -> Result<QuorumCertificate, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get(qc_id))
+    }
+
+    fn quorum_certificates_get_all<'a, I: IntoIterator<Item = &'a QcId>>(
+        &self,
+        qc_ids: I,
+    ) -> Result<Vec<QuorumCertificate>, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get_all(qc_ids))
+    }
+
+    fn quorum_certificates_get_by_block_id(&self, block_id: &BlockId) -> Result<QuorumCertificate, StorageError> {
+        dispatch_read!(self, |tx| tx.quorum_certificates_get_by_block_id(block_id))
+    }
+
+    fn transaction_pool_get_for_blocks(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_id: &TransactionId,
+    ) -> Result<TransactionPoolRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get_for_blocks(
+            from_block_id,
+            to_block_id,
+            transaction_id
+        ))
+    }
+
+    fn transaction_pool_exists(&self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_exists(transaction_id))
+    }
+
+    fn transaction_pool_get_all(&self) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_get_all())
+    }
+
+    fn transaction_pool_get_many_ready(
+        &self,
+        max_txs: usize,
+        block_id: &BlockId,
+        ordering: TransactionOrdering,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .transaction_pool_get_many_ready(max_txs, block_id, ordering))
+    }
+
+    fn transaction_pool_count(
+        &self,
+        stage: Option<TransactionPoolStage>,
+        is_ready: Option<bool>,
+        confirmed_stage: Option<Option<TransactionPoolConfirmedStage>>,
+    ) -> Result<usize, StorageError> {
+        dispatch_read!(self, |tx| tx.transaction_pool_count(stage, is_ready, confirmed_stage))
+    }
+
+    fn transactions_fetch_involved_shards(
+        &self,
+        transaction_ids: HashSet<TransactionId>,
+    ) -> Result<HashSet<SubstateAddress>, StorageError> {
+        dispatch_read!(self, |tx| tx.transactions_fetch_involved_shards(transaction_ids))
+    }
+
+    fn votes_get_by_block_and_sender(
+        &self,
+        block_id: &BlockId,
+        sender_leaf_hash: &FixedHash,
+    ) -> Result<Vote, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_get_by_block_and_sender(block_id, sender_leaf_hash))
+    }
+
+    fn votes_count_for_block(&self, block_id: &BlockId) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_count_for_block(block_id))
+    }
+
+    fn votes_get_for_block(&self, block_id: &BlockId) -> Result<Vec<Vote>, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_get_for_block(block_id))
+    }
+
+    fn votes_get_by_sender(&self, epoch: Epoch, sender_leaf_hash: &FixedHash) -> Result<Vec<Vote>, StorageError> {
+        dispatch_read!(self, |tx| tx.votes_get_by_sender(epoch, sender_leaf_hash))
+    }
+
+    fn substates_get(&self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get(address))
+    }
+
+    fn substates_get_any(
+        &self,
+        substate_ids: &HashSet<SubstateRequirement>,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_any(substate_ids))
+    }
+
+    fn substates_get_any_max_version<'a, I: IntoIterator<Item = &'a SubstateId>>(
+        &self,
+        substate_ids: I,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_any_max_version(substate_ids))
+    }
+
+    fn substates_get_max_version_for_substate(&self, substate_id: &SubstateId) -> Result<(u32, bool), StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_max_version_for_substate(substate_id))
+    }
+
+    fn substates_any_exist<I, S>(&self, substates: I) -> Result<bool, StorageError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Borrow<VersionedSubstateId>,
+    {
+        dispatch_read!(self, |tx| tx.substates_any_exist(substates))
+    }
+
+    fn substates_exists_for_transaction(&self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_exists_for_transaction(transaction_id))
+    }
+
+    fn substates_get_n_after(&self, n: usize, after: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_n_after(n, after))
+    }
+
+    fn substates_get_many_within_range(
+        &self,
+        start: &SubstateAddress,
+        end: &SubstateAddress,
+        exclude_shards: &[SubstateAddress],
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_within_range(
+            start,
+            end,
+            exclude_shards
+        ))
+    }
+
+    fn substates_get_many_by_created_transaction(
+        &self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_by_created_transaction(tx_id))
+    }
+
+    fn substates_get_many_by_destroyed_transaction(
+        &self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_many_by_destroyed_transaction(tx_id))
+    }
+
+    fn substates_get_all_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        dispatch_read!(self, |tx| tx.substates_get_all_for_transaction(transaction_id))
+    }
+
+    fn substate_locks_get_locked_substates_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<LockedSubstateValue>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .substate_locks_get_locked_substates_for_transaction(transaction_id))
+    }
+
+    fn substate_locks_get_latest_for_substate(&self, substate_id: &SubstateId) -> Result<SubstateLock, StorageError> {
+        dispatch_read!(self, |tx| tx.substate_locks_get_latest_for_substate(substate_id))
+    }
+
+    fn pending_state_tree_diffs_get_all_up_to_commit_block(
+        &self,
+        block_id: &BlockId,
+    ) -> Result<HashMap<Shard, Vec<PendingShardStateTreeDiff>>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .pending_state_tree_diffs_get_all_up_to_commit_block(block_id))
+    }
+
+    fn state_transitions_get_n_after(
+        &self,
+        n: usize,
+        id: StateTransitionId,
+        end_epoch: Epoch,
+    ) -> Result<Vec<StateTransition>, StorageError> {
+        dispatch_read!(self, |tx| tx.state_transitions_get_n_after(n, id, end_epoch))
+    }
+
+    fn state_transitions_get_last_id(&self, shard: Shard) -> Result<StateTransitionId, StorageError> {
+        dispatch_read!(self, |tx| tx.state_transitions_get_last_id(shard))
+    }
+
+    fn state_tree_nodes_get(&self, shard: Shard, key: &NodeKey) -> Result<Node<Version>, StorageError> {
+        dispatch_read!(self, |tx| tx.state_tree_nodes_get(shard, key))
+    }
+
+    fn state_tree_versions_get_latest(&self, shard: Shard) -> Result<Option<Version>, StorageError> {
+        dispatch_read!(self, |tx| tx.state_tree_versions_get_latest(shard))
+    }
+
+    fn epoch_checkpoint_get(&self, epoch: Epoch) -> Result<EpochCheckpoint, StorageError> {
+        dispatch_read!(self, |tx| tx.epoch_checkpoint_get(epoch))
+    }
+
+    fn epoch_handover_checkpoints_get(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+    ) -> Result<EpochCheckpoint, StorageError> {
+        dispatch_read!(self, |tx| tx.epoch_handover_checkpoints_get(epoch, shard_group))
+    }
+
+    fn foreign_substate_pledges_exists_for_address<T: ToSubstateAddress>(
+        &self,
+        transaction_id: &TransactionId,
+        address: T,
+    ) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .foreign_substate_pledges_exists_for_address(transaction_id, address))
+    }
+
+    fn foreign_substate_pledges_get_all_by_transaction_id(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<SubstatePledges, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .foreign_substate_pledges_get_all_by_transaction_id(transaction_id))
+    }
+
+    fn burnt_utxos_get(&self, substate_id: &SubstateId) -> Result<BurntUtxo, StorageError> {
+        dispatch_read!(self, |tx| tx.burnt_utxos_get(substate_id))
+    }
+
+    fn burnt_utxos_get_all_unproposed(
+        &self,
+        leaf_block: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<BurntUtxo>, StorageError> {
+        dispatch_read!(self, |tx| tx.burnt_utxos_get_all_unproposed(leaf_block, limit))
+    }
+
+    fn burnt_utxos_count(&self) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx.burnt_utxos_count())
+    }
+
+    fn foreign_parked_blocks_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.foreign_parked_blocks_exists(block_id))
+    }
+
+    fn validator_epoch_stats_get(
+        &self,
+        epoch: Epoch,
+        public_key: &PublicKey,
+    ) -> Result<ValidatorConsensusStats, StorageError> {
+        dispatch_read!(self, |tx| tx.validator_epoch_stats_get(epoch, public_key))
+    }
+
+    fn validator_epoch_stats_get_all_in_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorEpochStats>, StorageError> {
+        dispatch_read!(self, |tx| tx
+            .validator_epoch_stats_get_all_in_epoch_range(epoch_range, public_key))
+    }
+
+    fn validator_epoch_stats_get_nodes_to_suspend(
+        &self,
+        block_id: &BlockId,
+        min_missed_proposals: u64,
+        limit: usize,
+    ) -> Result<Vec<PublicKey>, StorageError> {
+        dispatch_read!(self, |tx| tx.validator_epoch_stats_get_nodes_to_suspend(
+            block_id,
+            min_missed_proposals,
+            limit
+        ))
+    }
+
+    fn validator_epoch_stats_get_nodes_to_resume(
+        &self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<PublicKey>, StorageError> {
+        dispatch_read!(self, |tx| tx.validator_epoch_stats_get_nodes_to_resume(block_id, limit))
+    }
+
+    fn suspended_nodes_is_suspended(&self, block_id: &BlockId, public_key: &PublicKey) -> Result<bool, StorageError> {
+        dispatch_read!(self, |tx| tx.suspended_nodes_is_suspended(block_id, public_key))
+    }
+
+    fn suspended_nodes_count(&self) -> Result<u64, StorageError> {
+        dispatch_read!(self, |tx| tx.suspended_nodes_count())
+    }
+
+    fn suspended_nodes_get_all(&self) -> Result<Vec<PublicKey>, StorageError> {
+        dispatch_read!(self, |tx| tx.suspended_nodes_get_all())
+    }
+}
+
+impl<'tx> StateStoreWriteTransaction for ValidatorNodeWriteTransaction<'tx> {
+    type Addr = PeerAddress;
+
+    fn commit(&mut self) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.commit())
+    }
+
+    fn rollback(&mut self) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.rollback())
+    }
+
+    fn blocks_insert(&mut self, block: &Block) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_insert(block))
+    }
+
+    fn blocks_delete(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_delete(block_id))
+    }
+
+    fn blocks_set_flags(
+        &mut self,
+        block_id: &BlockId,
+        is_committed: Option<bool>,
+        is_justified: Option<bool>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.blocks_set_flags(block_id, is_committed, is_justified))
+    }
+
+    fn block_diffs_insert(&mut self, block_id: &BlockId, changes: &[SubstateChange]) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.block_diffs_insert(block_id, changes))
+    }
+
+    fn block_diffs_remove(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.block_diffs_remove(block_id))
+    }
+
+    fn quorum_certificates_insert(&mut self, qc: &QuorumCertificate) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.quorum_certificates_insert(qc))
+    }
+
+    fn quorum_certificates_set_shares_processed(&mut self, qc_id: &QcId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.quorum_certificates_set_shares_processed(qc_id))
+    }
+
+    fn last_sent_vote_set(&mut self, last_sent_vote: &LastSentVote) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_sent_vote_set(last_sent_vote))
+    }
+
+    fn last_voted_set(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_voted_set(last_voted))
+    }
+
+    fn last_votes_unset(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_votes_unset(last_voted))
+    }
+
+    fn last_executed_set(&mut self, last_exec: &LastExecuted) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_executed_set(last_exec))
+    }
+
+    fn last_proposed_set(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_proposed_set(last_proposed))
+    }
+
+    fn last_proposed_unset(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.last_proposed_unset(last_proposed))
+    }
+
+    fn leaf_block_set(&mut self, leaf_node: &LeafBlock) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.leaf_block_set(leaf_node))
+    }
+
+    fn locked_block_set(&mut self, locked_block: &LockedBlock) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.locked_block_set(locked_block))
+    }
+
+    fn high_qc_set(&mut self, high_qc: &HighQc) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.high_qc_set(high_qc))
+    }
+
+    fn foreign_proposals_upsert(
+        &mut self,
+        foreign_proposal: &ForeignProposal,
+        proposed_in_block: Option<BlockId>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .foreign_proposals_upsert(foreign_proposal, proposed_in_block))
+    }
+
+    fn foreign_proposals_delete(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_proposals_delete(block_id))
+    }
+
+    fn foreign_proposals_delete_in_epoch(&mut self, epoch: Epoch) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_proposals_delete_in_epoch(epoch))
+    }
+
+    fn foreign_proposals_set_status(
+        &mut self,
+        block_id: &BlockId,
+        status: ForeignProposalStatus,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_proposals_set_status(block_id, status))
+    }
+
+    fn foreign_proposals_set_proposed_in(
+        &mut self,
+        block_id: &BlockId,
+        proposed_in_block: &BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .foreign_proposals_set_proposed_in(block_id, proposed_in_block))
+    }
+
+    fn foreign_proposals_clear_proposed_in(&mut self, proposed_in_block: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_proposals_clear_proposed_in(proposed_in_block))
+    }
+
+    fn foreign_send_counters_set(
+        &mut self,
+        foreign_send_counter: &ForeignSendCounters,
+        block_id: &BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_send_counters_set(foreign_send_counter, block_id))
+    }
+
+    fn foreign_receive_counters_set(
+        &mut self,
+        foreign_send_counter: &ForeignReceiveCounters,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_receive_counters_set(foreign_send_counter))
+    }
+
+    fn transactions_insert(&mut self, transaction: &TransactionRecord) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_insert(transaction))
+    }
+
+    fn transactions_update(&mut self, transaction: &TransactionRecord) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_update(transaction))
+    }
+
+    fn transactions_save_all<'a, I: IntoIterator<Item = &'a TransactionRecord>>(
+        &mut self,
+        transaction: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_save_all(transaction))
+    }
+
+    fn transactions_finalize_all<'a, I: IntoIterator<Item = &'a TransactionPoolRecord>>(
+        &mut self,
+        block_id: BlockId,
+        transaction: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transactions_finalize_all(block_id, transaction))
+    }
+
+    fn transaction_idempotency_keys_insert_all<'a, I: IntoIterator<Item = (&'a IdempotencyKey, &'a TransactionId)>>(
+        &mut self,
+        block_id: &BlockId,
+        epoch: Epoch,
+        idempotency_keys: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_idempotency_keys_insert_all(
+            block_id,
+            epoch,
+            idempotency_keys
+        ))
+    }
+
+    fn transaction_idempotency_keys_set_committed(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_idempotency_keys_set_committed(block_id))
+    }
+
+    fn transaction_idempotency_keys_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .transaction_idempotency_keys_remove_any_by_block_id(block_id))
+    }
+
+    fn transaction_executions_insert_or_ignore(
+        &mut self,
+        transaction_execution: &BlockTransactionExecution,
+    ) -> Result<bool, StorageError> {
+        dispatch_write!(self, |tx| tx
+            .transaction_executions_insert_or_ignore(transaction_execution))
+    }
+
+    fn transaction_executions_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_executions_remove_any_by_block_id(block_id))
+    }
+
+    fn transaction_pool_insert_new(
+        &mut self,
+        tx_id: TransactionId,
+        decision: Decision,
+        is_ready: bool,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_insert_new(tx_id, decision, is_ready))
+    }
+
+    fn transaction_pool_add_pending_update(
+        &mut self,
+        block_id: &BlockId,
+        pool_update: &TransactionPoolStatusUpdate,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_add_pending_update(block_id, pool_update))
+    }
+
+    fn transaction_pool_remove(&mut self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_remove(transaction_id))
+    }
+
+    fn transaction_pool_remove_all<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        transaction_ids: I,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_remove_all(transaction_ids))
+    }
+
+    fn transaction_pool_confirm_all_transitions(&mut self, new_locked_block: &LockedBlock) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.transaction_pool_confirm_all_transitions(new_locked_block))
+    }
+
+    fn transaction_pool_state_updates_remove_any_by_block_id(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .transaction_pool_state_updates_remove_any_by_block_id(block_id))
+    }
+
+    fn missing_transactions_insert<'a, IMissing: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        park_block: &Block,
+        foreign_proposals: &[ForeignProposal],
+        missing_transaction_ids: IMissing,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.missing_transactions_insert(
+            park_block,
+            foreign_proposals,
+            missing_transaction_ids
+        ))
+    }
+
+    fn missing_transactions_remove(
+        &mut self,
+        height: NodeHeight,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<(Block, Vec<ForeignProposal>)>, StorageError> {
+        dispatch_write!(self, |tx| tx.missing_transactions_remove(height, transaction_id))
+    }
+
+    fn foreign_parked_blocks_insert(&mut self, park_block: &ForeignParkedProposal) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_parked_blocks_insert(park_block))
+    }
+
+    fn foreign_parked_blocks_insert_missing_transactions<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        park_block_id: &BlockId,
+        missing_transaction_ids: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_parked_blocks_insert_missing_transactions(
+            park_block_id,
+            missing_transaction_ids
+        ))
+    }
+
+    fn foreign_parked_blocks_remove_all_by_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<ForeignParkedProposal>, StorageError> {
+        dispatch_write!(self, |tx| tx
+            .foreign_parked_blocks_remove_all_by_transaction(transaction_id))
+    }
+
+    fn votes_insert(&mut self, vote: &Vote) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.votes_insert(vote))
+    }
+
+    fn votes_delete_all(&mut self) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.votes_delete_all())
+    }
+
+    fn substate_locks_insert_all<'a, I: IntoIterator<Item = (&'a SubstateId, &'a Vec<SubstateLock>)>>(
+        &mut self,
+        block_id: &BlockId,
+        locks: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substate_locks_insert_all(block_id, locks))
+    }
+
+    fn substate_locks_remove_many_for_transactions<'a, I: Iterator<Item = &'a TransactionId>>(
+        &mut self,
+        transaction_ids: Peekable<I>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .substate_locks_remove_many_for_transactions(transaction_ids))
+    }
+
+    fn substate_locks_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substate_locks_remove_any_by_block_id(block_id))
+    }
+
+    fn substates_write_many(&mut self, writes: &[SubstateWrite]) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.substates_write_many(writes))
+    }
+
+    #[allow(clippy::mutable_key_type)]
+    fn foreign_substate_pledges_save(
+        &mut self,
+        transaction_id: &TransactionId,
+        shard_group: ShardGroup,
+        pledges: &SubstatePledges,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_substate_pledges_save(
+            transaction_id,
+            shard_group,
+            pledges
+        ))
+    }
+
+    fn foreign_substate_pledges_remove_many<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        transaction_ids: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.foreign_substate_pledges_remove_many(transaction_ids))
+    }
+
+    fn pending_state_tree_diffs_insert(
+        &mut self,
+        block_id: BlockId,
+        shard: Shard,
+        diff: &VersionedStateHashTreeDiff,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.pending_state_tree_diffs_insert(block_id, shard, diff))
+    }
+
+    fn pending_state_tree_diffs_remove_by_block(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.pending_state_tree_diffs_remove_by_block(block_id))
+    }
+
+    fn pending_state_tree_diffs_remove_and_return_by_block(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<IndexMap<Shard, Vec<PendingShardStateTreeDiff>>, StorageError> {
+        dispatch_write!(self, |tx| tx
+            .pending_state_tree_diffs_remove_and_return_by_block(block_id))
+    }
+
+    fn state_tree_nodes_insert(&mut self, shard: Shard, key: NodeKey, node: Node<Version>) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.state_tree_nodes_insert(shard, key, node))
+    }
+
+    fn state_tree_nodes_record_stale_tree_node(
+        &mut self,
+        shard: Shard,
+        stale_since_version: Version,
+        node: StaleTreeNode,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.state_tree_nodes_record_stale_tree_node(
+            shard,
+            stale_since_version,
+            node
+        ))
+    }
+
+    fn state_tree_nodes_prune_stale(&mut self, shard: Shard, up_to_version: Version) -> Result<usize, StorageError> {
+        dispatch_write!(self, |tx| tx.state_tree_nodes_prune_stale(shard, up_to_version))
+    }
+
+    fn state_tree_shard_versions_set(&mut self, shard: Shard, version: Version) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.state_tree_shard_versions_set(shard, version))
+    }
+
+    fn epoch_checkpoint_save(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.epoch_checkpoint_save(checkpoint))
+    }
+
+    fn epoch_handover_checkpoints_insert(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.epoch_handover_checkpoints_insert(checkpoint))
+    }
+
+    fn burnt_utxos_insert(&mut self, burnt_utxo: &BurntUtxo) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.burnt_utxos_insert(burnt_utxo))
+    }
+
+    fn burnt_utxos_set_proposed_block(
+        &mut self,
+        substate_id: &SubstateId,
+        proposed_in_block: &BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .burnt_utxos_set_proposed_block(substate_id, proposed_in_block))
+    }
+
+    fn burnt_utxos_clear_proposed_block(&mut self, proposed_in_block: &BlockId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.burnt_utxos_clear_proposed_block(proposed_in_block))
+    }
+
+    fn burnt_utxos_delete(&mut self, substate_id: &SubstateId) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.burnt_utxos_delete(substate_id))
+    }
+
+    fn lock_conflicts_insert_all<'a, I: IntoIterator<Item = (&'a TransactionId, &'a Vec<LockConflict>)>>(
+        &mut self,
+        block_id: &BlockId,
+        conflicts: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.lock_conflicts_insert_all(block_id, conflicts))
+    }
+
+    fn validator_epoch_stats_updates<'a, I: IntoIterator<Item = ValidatorStatsUpdate<'a>>>(
+        &mut self,
+        epoch: Epoch,
+        updates: I,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.validator_epoch_stats_updates(epoch, updates))
+    }
+
+    fn suspended_nodes_insert(
+        &mut self,
+        public_key: &PublicKey,
+        suspended_in_block: BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.suspended_nodes_insert(public_key, suspended_in_block))
+    }
+
+    fn suspended_nodes_mark_for_removal(
+        &mut self,
+        public_key: &PublicKey,
+        resumed_in_block: BlockId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx
+            .suspended_nodes_mark_for_removal(public_key, resumed_in_block))
+    }
+
+    fn suspended_nodes_delete(&mut self, public_key: &PublicKey) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.suspended_nodes_delete(public_key))
+    }
+
+    fn diagnostics_add_no_vote(&mut self, block_id: BlockId, reason: NoVoteReason) -> Result<(), StorageError> {
+        dispatch_write!(self, |tx| tx.diagnostics_add_no_vote(block_id, reason))
+    }
+}
@@ -266,6 +266,14 @@ where
             VirtualSubstateId::CurrentEpoch,
             VirtualSubstate::CurrentEpoch(current_epoch.as_u64()),
         );
+        virtual_substates.insert(
+            VirtualSubstateId::CurrentTimestamp,
+            self.virtual_substate_manager
+                .generate_for_address(&VirtualSubstateId::CurrentTimestamp)
+                .await?,
+        );
+        // The random beacon is only known once the transaction is prepared in a block, so templates that use it cannot
+        // be dry run
 
         if claim_epoch_and_public_key.is_empty() {
             return Ok(virtual_substates);
@@ -42,6 +42,23 @@ pub enum TransactionValidationError {
     InvalidSignature,
     #[error("Transaction {transaction_id} is not signed")]
     TransactionNotSigned { transaction_id: TransactionId },
+    #[error(
+        "Transaction {transaction_id} has {num_instructions} instructions which exceeds the maximum of \
+         {max_instructions}"
+    )]
+    TooManyInstructions {
+        transaction_id: TransactionId,
+        num_instructions: usize,
+        max_instructions: usize,
+    },
+    #[error("Transaction {transaction_id} is {size} bytes which exceeds the maximum of {max_size} bytes")]
+    TransactionTooLarge {
+        transaction_id: TransactionId,
+        size: usize,
+        max_size: usize,
+    },
+    #[error("Failed to encode transaction: {0}")]
+    EncodingError(String),
     #[error("Network error: {0}")]
     NetworkingError(#[from] NetworkingError),
 }
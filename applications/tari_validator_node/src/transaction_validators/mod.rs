@@ -7,6 +7,7 @@ mod fee;
 mod has_inputs;
 mod signature;
 mod template_exists;
+mod transaction_size;
 
 pub use claim_fee_instructions::*;
 pub use epoch_range::*;
@@ -14,6 +15,7 @@ pub use fee::*;
 pub use has_inputs::*;
 pub use signature::*;
 pub use template_exists::*;
+pub use transaction_size::*;
 
 mod error;
 mod with_context;
@@ -0,0 +1,57 @@
+//    Copyright 2024 The Tari Project
+//    SPDX-License-Identifier: BSD-3-Clause
+
+use log::*;
+use tari_transaction::Transaction;
+
+use crate::{transaction_validators::TransactionValidationError, validator::Validator};
+
+const LOG_TARGET: &str = "tari::dan::mempool::validators::transaction_size";
+
+/// Refuse to process the transaction if it exceeds the network-agreed maximum size or instruction count.
+#[derive(Debug, Clone)]
+pub struct TransactionSizeValidator {
+    max_transaction_bytes: usize,
+    max_instructions: usize,
+}
+
+impl TransactionSizeValidator {
+    pub fn new(max_transaction_bytes: usize, max_instructions: usize) -> Self {
+        Self {
+            max_transaction_bytes,
+            max_instructions,
+        }
+    }
+}
+
+impl Validator<Transaction> for TransactionSizeValidator {
+    type Context = ();
+    type Error = TransactionValidationError;
+
+    fn validate(&self, _context: &(), transaction: &Transaction) -> Result<(), Self::Error> {
+        let num_instructions = transaction.num_instructions();
+        if num_instructions > self.max_instructions {
+            warn!(target: LOG_TARGET, "TransactionSizeValidator - FAIL: {num_instructions} instructions exceeds maximum {}", self.max_instructions);
+            return Err(TransactionValidationError::TooManyInstructions {
+                transaction_id: *transaction.id(),
+                num_instructions,
+                max_instructions: self.max_instructions,
+            });
+        }
+
+        let size = transaction
+            .encoded_size()
+            .map_err(|e| TransactionValidationError::EncodingError(e.to_string()))?;
+        if size > self.max_transaction_bytes {
+            warn!(target: LOG_TARGET, "TransactionSizeValidator - FAIL: {size} bytes exceeds maximum {}", self.max_transaction_bytes);
+            return Err(TransactionValidationError::TransactionTooLarge {
+                transaction_id: *transaction.id(),
+                size,
+                max_size: self.max_transaction_bytes,
+            });
+        }
+
+        debug!(target: LOG_TARGET, "TransactionSizeValidator - OK");
+        Ok(())
+    }
+}
@@ -4,7 +4,11 @@
 use log::*;
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::Epoch;
-use tari_dan_storage::{consensus_models::Block, StateStore, StorageError};
+use tari_dan_storage::{
+    consensus_models::{Block, HighQc},
+    StateStore,
+    StorageError,
+};
 use tari_engine_types::{
     fee_claim::FeeClaim,
     virtual_substate::{VirtualSubstate, VirtualSubstateId, VirtualSubstates},
@@ -41,6 +45,12 @@ where
             VirtualSubstateId::UnclaimedValidatorFee { epoch, address } => {
                 self.generate_validator_fee_claim(Epoch(*epoch), address)
             },
+            VirtualSubstateId::CurrentTimestamp => self.generate_current_timestamp().await,
+            VirtualSubstateId::RandomBeacon | VirtualSubstateId::RandomSeed => {
+                Err(VirtualSubstateError::NotGeneratable {
+                    address: address.clone(),
+                })
+            },
         }
     }
 
@@ -70,6 +80,19 @@ where
         Ok(VirtualSubstate::CurrentEpoch(current_epoch.as_u64()))
     }
 
+    /// Returns the consensus timestamp, which is the timestamp of the block certified by the current high QC. This is
+    /// not the timestamp that a transaction is executed with, which is only known once the transaction is prepared.
+    async fn generate_current_timestamp(&self) -> Result<VirtualSubstate, VirtualSubstateError> {
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let timestamp = self.store.with_read_tx(|tx| {
+            HighQc::get(tx, current_epoch)?
+                .get_quorum_certificate(tx)?
+                .get_block(tx)
+                .map(|block| block.timestamp())
+        })?;
+        Ok(VirtualSubstate::CurrentTimestamp(timestamp))
+    }
+
     fn generate_validator_fee_claim(
         &self,
         epoch: Epoch,
@@ -106,4 +129,6 @@ pub enum VirtualSubstateError {
     EpochManagerError(#[from] tari_epoch_manager::EpochManagerError),
     #[error("Storage error: {0}")]
     StorageError(#[from] StorageError),
+    #[error("Virtual substate {address} is derived during execution and cannot be generated on request")]
+    NotGeneratable { address: VirtualSubstateId },
 }
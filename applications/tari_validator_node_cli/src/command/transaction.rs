@@ -386,6 +386,12 @@ fn print_substate_diff(diff: &SubstateDiff) {
                 println!("        ▶ amount: {}", fee_claim.amount);
                 println!("        ▶ recipient: {}", fee_claim.validator_public_key);
             },
+            SubstateValue::KeyValueStore(_) => {
+                println!("      ▶ key-value store: {}", address);
+            },
+            SubstateValue::KeyValueEntry(_) => {
+                println!("      ▶ key-value entry: {}", address);
+            },
         }
         println!();
     }
@@ -713,6 +719,8 @@ impl CliArg {
                 SubstateId::NonFungibleIndex(v) => arg!(v),
                 SubstateId::TransactionReceipt(v) => arg!(v),
                 SubstateId::FeeClaim(v) => arg!(v),
+                SubstateId::KeyValueStore(v) => arg!(v),
+                SubstateId::KeyValueEntry(v) => arg!(v),
             },
             CliArg::TemplateAddress(v) => arg!(v),
             CliArg::NonFungibleId(v) => arg!(v),
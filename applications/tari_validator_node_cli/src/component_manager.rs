@@ -90,7 +90,9 @@ impl ComponentManager {
                 addr @ SubstateId::TransactionReceipt(_) |
                 addr @ SubstateId::Vault(_) |
                 addr @ SubstateId::NonFungible(_) |
-                addr @ SubstateId::NonFungibleIndex(_) => {
+                addr @ SubstateId::NonFungibleIndex(_) |
+                addr @ SubstateId::KeyValueStore(_) |
+                addr @ SubstateId::KeyValueEntry(_) => {
                     children.push(SubstateRequirement {
                         substate_id: addr.clone(),
                         version: Some(substate.version()),
@@ -23,6 +23,7 @@
 import type { GetFilteredBlocksCountRequest } from "@tari-project/typescript-bindings";
 import type {
   VNAddPeerRequest,
+  CheckStateIntegrityResponse,
   VNGetAllVnsRequest,
   VNGetAllVnsResponse,
   GetBlockRequest,
@@ -143,6 +144,7 @@ export const getIdentity = (): Promise<VNGetIdentityResponse> => jsonRpc("get_id
 
 export const getMempoolStats = (): Promise<GetMempoolStatsResponse> => jsonRpc("get_mempool_stats");
 export const getEpochManagerStats = (): Promise<GetEpochManagerStatsResponse> => jsonRpc("get_epoch_manager_stats");
+export const checkStateIntegrity = (): Promise<CheckStateIntegrityResponse> => jsonRpc("check_state_integrity");
 export const getShardKey = (request: GetShardKeyRequest): Promise<GetShardKeyResponse> =>
   jsonRpc("get_shard_key", request);
 export const getCommittee = (request: GetCommitteeRequest): Promise<GetCommitteeResponse> =>
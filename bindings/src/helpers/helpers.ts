@@ -35,6 +35,12 @@ export function substateIdToString(substateId: SubstateId | null): string {
   if ("FeeClaim" in substateId) {
     return substateId.FeeClaim;
   }
+  if ("KeyValueStore" in substateId) {
+    return substateId.KeyValueStore;
+  }
+  if ("KeyValueEntry" in substateId) {
+    return `${substateId.KeyValueEntry.store_id}:${substateId.KeyValueEntry.key_hash}`;
+  }
   console.error("Unknown substate id", substateId);
   return "Unknown";
 }
@@ -62,6 +68,8 @@ export function stringToSubstateId(substateId: string): SubstateId {
       return { TransactionReceipt: parts[1] };
     case "feeclaim":
       return { FeeClaim: parts[1] };
+    case "kvstore":
+      return { KeyValueStore: parts[1] };
     default:
       throw new Error(`Unknown substate id: ${substateId}`);
   }
@@ -78,7 +86,7 @@ export function rejectReasonToString(reason: RejectReason | null): string {
     return `ShardsNotPledged(${reason.ShardsNotPledged})`;
   }
   if ("ExecutionFailure" in reason) {
-    return `ExecutionFailure(${reason.ExecutionFailure})`;
+    return `ExecutionFailure(${reason.ExecutionFailure.message})`;
   }
   if ("ShardPledgedToAnotherPayload" in reason) {
     return `ShardPledgedToAnotherPayload(${reason.ShardPledgedToAnotherPayload})`;
@@ -96,7 +104,7 @@ export function rejectReasonToString(reason: RejectReason | null): string {
     return `InvalidTransaction(${reason.InvalidTransaction})`;
   }
   if ("ExecutionFailure" in reason) {
-    return `ExecutionFailure(${reason.ExecutionFailure})`;
+    return `ExecutionFailure(${reason.ExecutionFailure.message})`;
   }
   if ("OneOrMoreInputsNotFound" in reason) {
     return `OneOrMoreInputsNotFound(${reason.OneOrMoreInputsNotFound})`;
@@ -107,6 +115,21 @@ export function rejectReasonToString(reason: RejectReason | null): string {
   if ("FailedToLockOutputs" in reason) {
     return `FailedToLockOutputs(${reason.FailedToLockOutputs})`;
   }
+  if ("InvalidArguments" in reason) {
+    return `InvalidArguments(${reason.InvalidArguments.function}: ${reason.InvalidArguments.details})`;
+  }
+  if ("LockLeaseExpired" in reason) {
+    return `LockLeaseExpired(${reason.LockLeaseExpired})`;
+  }
+  if ("CrossShardDeadlock" in reason) {
+    return `CrossShardDeadlock(${reason.CrossShardDeadlock})`;
+  }
+  if ("CommitteeLayoutChanged" in reason) {
+    return `CommitteeLayoutChanged(${reason.CommitteeLayoutChanged})`;
+  }
+  if ("InputRentExpired" in reason) {
+    return `InputRentExpired(${reason.InputRentExpired})`;
+  }
   console.error("Unknown reason", reason);
   return "Unknown";
 }
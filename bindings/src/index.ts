@@ -4,6 +4,7 @@
 export * from "./types/AbortReason";
 export * from "./types/AccessRule";
 export * from "./types/Account";
+export * from "./types/AggregateValidatorSignature";
 export * from "./types/Amount";
 export * from "./types/ArgDef";
 export * from "./types/Arg";
@@ -13,6 +14,7 @@ export * from "./types/Block";
 export * from "./types/BucketId";
 export * from "./types/Claims";
 export * from "./types/Command";
+export * from "./types/CommandMerkleProof";
 export * from "./types/CommitteeInfo";
 export * from "./types/CommitteeShardInfo";
 export * from "./types/Committee";
@@ -37,6 +39,11 @@ export * from "./types/EvictNodeAtom";
 export * from "./types/Evidence";
 export * from "./types/ExecutedTransaction";
 export * from "./types/ExecuteResult";
+export * from "./types/ExecutionContext";
+export * from "./types/ExecutionError";
+export * from "./types/ExecutionFailure";
+export * from "./types/ExecutionTrace";
+export * from "./types/ExecutionTraceEntry";
 export * from "./types/ExtraData";
 export * from "./types/FeeBreakdown";
 export * from "./types/FeeClaimAddress";
@@ -44,6 +51,7 @@ export * from "./types/FeeClaim";
 export * from "./types/FeeCostBreakdown";
 export * from "./types/FeeReceipt";
 export * from "./types/FeeSource";
+export * from "./types/FeeSponsor";
 export * from "./types/FinalizeResult";
 export * from "./types/ForeignProposalAtom";
 export * from "./types/FunctionDef";
@@ -53,6 +61,10 @@ export * from "./types/InstructionResult";
 export * from "./types/Instruction";
 export * from "./types/JrpcPermissions";
 export * from "./types/JrpcPermission";
+export * from "./types/KeyValueEntryAddress";
+export * from "./types/KeyValueEntry";
+export * from "./types/KeyValueStoreContainer";
+export * from "./types/KeyValueStoreId";
 export * from "./types/LeaderFee";
 export * from "./types/LockFlag";
 export * from "./types/LogEntry";
@@ -85,15 +97,21 @@ export * from "./types/Resource";
 export * from "./types/ResourceType";
 export * from "./types/RestrictedAccessRule";
 export * from "./types/ResumeNodeAtom";
+export * from "./types/RoyaltyConfig";
 export * from "./types/RuleRequirement";
 export * from "./types/ShardEvidence";
 export * from "./types/ShardGroupEvidence";
 export * from "./types/ShardGroup";
+export * from "./types/ShardStateIntegrity";
 export * from "./types/Shard";
+export * from "./types/SignerBitmap";
+export * from "./types/StateIntegrityReport";
 export * from "./types/SubstateAddress";
 export * from "./types/SubstateDestroyed";
 export * from "./types/SubstateDiff";
 export * from "./types/SubstateId";
+export * from "./types/SubstateIntegrityMismatchReason";
+export * from "./types/SubstateIntegrityMismatch";
 export * from "./types/SubstateLockType";
 export * from "./types/SubstateRecord";
 export * from "./types/SubstateRequirementLockIntent";
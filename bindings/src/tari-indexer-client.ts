@@ -35,3 +35,11 @@ export * from "./types/tari-indexer-client/IndexerConnectionDirection";
 export * from "./types/tari-indexer-client/IndexerGetIdentityResponse";
 export * from "./types/tari-indexer-client/IndexerGetTransactionResultRequest";
 export * from "./types/tari-indexer-client/GetNonFungibleCountRequest";
+export * from "./types/tari-indexer-client/IndexerDryRunTransactionRequest";
+export * from "./types/tari-indexer-client/IndexerDryRunTransactionResponse";
+export * from "./types/tari-indexer-client/GetTransfersRequest";
+export * from "./types/tari-indexer-client/GetTransfersResponse";
+export * from "./types/tari-indexer-client/TransferItem";
+export * from "./types/tari-indexer-client/ResolveNameRequest";
+export * from "./types/tari-indexer-client/ResolveNameResponse";
+export * from "./types/tari-indexer-client/NameTarget";
@@ -14,4 +14,8 @@ export type AbortReason =
   | "OneOrMoreInputsNotFound"
   | "ForeignShardGroupDecidedToAbort"
   | "FeesNotPaid"
-  | "EarlyAbort";
+  | "EarlyAbort"
+  | "LockLeaseExpired"
+  | "CrossShardDeadlock"
+  | "CommitteeLayoutChanged"
+  | "InputRentExpired";
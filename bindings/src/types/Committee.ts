@@ -2,4 +2,5 @@
 
 export interface Committee<TAddr> {
   members: Array<[TAddr, string]>;
+  voting_powers: Record<string, number>;
 }
@@ -2,14 +2,19 @@
 import type { ComponentAccessRules } from "./ComponentAccessRules";
 import type { ComponentBody } from "./ComponentBody";
 import type { EntityId } from "./EntityId";
+import type { FeeSponsor } from "./FeeSponsor";
 import type { OwnerRule } from "./OwnerRule";
 
 export interface ComponentHeader {
   template_address: Uint8Array;
   module_name: string;
+  previous_template_address: Uint8Array | null;
   owner_key: string | null;
   owner_rule: OwnerRule;
   access_rules: ComponentAccessRules;
   entity_id: EntityId;
+  non_reentrant: boolean;
+  fee_sponsor: FeeSponsor | null;
+  rent_paid_until: number | null;
   body: ComponentBody;
 }
@@ -7,4 +7,5 @@ export interface ConfidentialOutput {
   encrypted_data: Array<number>;
   minimum_value_promise: number;
   viewable_balance: ElgamalVerifiableBalance | null;
+  encrypted_memo: Array<number> | null;
 }
@@ -7,4 +7,5 @@ export interface ConfidentialStatement {
   encrypted_data: Array<number>;
   minimum_value_promise: number;
   viewable_balance_proof: ViewableBalanceProof | null;
+  encrypted_memo: Array<number> | null;
 }
@@ -8,4 +8,5 @@ export interface Event {
   tx_hash: string;
   topic: string;
   payload: Metadata;
+  data: any;
 }
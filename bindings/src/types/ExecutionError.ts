@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "./Amount";
+import type { BucketId } from "./BucketId";
+import type { ComponentAddress } from "./ComponentAddress";
+import type { NonFungibleId } from "./NonFungibleId";
+import type { ResourceAddress } from "./ResourceAddress";
+import type { SubstateId } from "./SubstateId";
+import type { VaultId } from "./VaultId";
+
+export type ExecutionError =
+  | "Other"
+  | { Panic: { message: string } }
+  | { SubstateNotFound: { id: SubstateId } }
+  | { SubstateOutOfScope: { id: SubstateId } }
+  | { SubstateNotOwned: { id: SubstateId; requested_owner: SubstateId } }
+  | { ComponentNotFound: { address: ComponentAddress } }
+  | { VaultNotFound: { vault_id: VaultId } }
+  | { ResourceNotFound: { resource_address: ResourceAddress } }
+  | { NonFungibleNotFound: { resource_address: ResourceAddress; nft_id: NonFungibleId } }
+  | { TemplateNotFound: { template_address: string } }
+  | { FunctionNotFound: { name: string } }
+  | { InvalidArgument: { argument: string; reason: string } }
+  | { InvalidAmount: { amount: Amount; reason: string } }
+  | { AccessDenied: { action: string } }
+  | { ResourceError: { details: string } }
+  | {
+      MaxSupplyExceeded: {
+        resource_address: ResourceAddress;
+        amount: Amount;
+        total_supply: Amount;
+        max_supply: Amount;
+      };
+    }
+  | { ResourceFrozen: { address: SubstateId; action: string } }
+  | { InsufficientFeesPaid: { required_fee: Amount; fees_paid: Amount } }
+  | { ReentrantCall: { component_address: ComponentAddress } }
+  | { SubstateRentExpired: { id: SubstateId; paid_until: bigint; current_epoch: bigint } }
+  | { MaxCallDepthExceeded: { max_depth: bigint } }
+  | { OrphanedSubstates: { substates: Array<string> } }
+  | { BucketNotEmpty: { bucket_id: BucketId } }
+  | { WasmError: { details: string } };
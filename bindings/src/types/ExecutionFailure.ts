@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExecutionError } from "./ExecutionError";
+
+export interface ExecutionFailure {
+  code: number;
+  instruction_index: number | null;
+  error: ExecutionError;
+  message: string;
+}
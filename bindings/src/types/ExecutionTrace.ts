@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExecutionTraceEntry } from "./ExecutionTraceEntry";
+
+export interface ExecutionTrace {
+  entries: Array<ExecutionTraceEntry>;
+  is_truncated: boolean;
+}
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeeSource } from "./FeeSource";
+import type { SubstateId } from "./SubstateId";
+
+export type ExecutionTraceEntry =
+  | { Instruction: { index: number; instruction: string } }
+  | { EngineCall: { call: string } }
+  | { SubstateRead: { substate_id: SubstateId } }
+  | { SubstateWrite: { substate_id: SubstateId } }
+  | { FeeCharge: { source: FeeSource; amount: bigint } };
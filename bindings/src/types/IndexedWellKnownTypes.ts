@@ -1,6 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { BucketId } from "./BucketId";
 import type { ComponentAddress } from "./ComponentAddress";
+import type { KeyValueStoreId } from "./KeyValueStoreId";
 import type { Metadata } from "./Metadata";
 import type { NonFungibleAddress } from "./NonFungibleAddress";
 import type { ProofId } from "./ProofId";
@@ -17,6 +18,7 @@ export interface IndexedWellKnownTypes {
   transaction_receipt_addresses: Array<TransactionReceiptAddress>;
   non_fungible_addresses: Array<NonFungibleAddress>;
   vault_ids: Array<VaultId>;
+  key_value_store_ids: Array<KeyValueStoreId>;
   metadata: Array<Metadata>;
   unclaimed_confidential_output_address: Array<UnclaimedConfidentialOutputAddress>;
 }
@@ -1,7 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { LogLevel } from "./LogLevel";
+import type { Metadata } from "./Metadata";
 
 export interface LogEntry {
   message: string;
   level: LogLevel;
+  fields: Metadata;
 }
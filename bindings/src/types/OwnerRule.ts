@@ -1,4 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { AccessRule } from "./AccessRule";
+import type { ComponentAddress } from "./ComponentAddress";
 
-export type OwnerRule = "OwnedBySigner" | "None" | { ByAccessRule: AccessRule } | { ByPublicKey: Array<number> };
+export type OwnerRule =
+  | "OwnedBySigner"
+  | "None"
+  | { ByAccessRule: AccessRule }
+  | { ByPublicKey: Array<number> }
+  | { OwnedByComponent: ComponentAddress };
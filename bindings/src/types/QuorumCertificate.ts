@@ -1,9 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AggregateValidatorSignature } from "./AggregateValidatorSignature";
 import type { Epoch } from "./Epoch";
 import type { NodeHeight } from "./NodeHeight";
 import type { QuorumDecision } from "./QuorumDecision";
 import type { ShardGroup } from "./ShardGroup";
-import type { ValidatorSignature } from "./ValidatorSignature";
+import type { SignerBitmap } from "./SignerBitmap";
 
 export interface QuorumCertificate {
   qc_id: string;
@@ -11,7 +12,8 @@ export interface QuorumCertificate {
   block_height: NodeHeight;
   epoch: Epoch;
   shard_group: ShardGroup;
-  signatures: Array<ValidatorSignature>;
+  signers: SignerBitmap;
+  signature: AggregateValidatorSignature;
   leaf_hashes: Array<string>;
   decision: QuorumDecision;
   is_shares_processed: boolean;
@@ -1,11 +1,17 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExecutionFailure } from "./ExecutionFailure";
 
 export type RejectReason =
   | { InvalidTransaction: string }
-  | { ExecutionFailure: string }
+  | { ExecutionFailure: ExecutionFailure }
   | { OneOrMoreInputsNotFound: string }
   | { FailedToLockInputs: string }
   | { FailedToLockOutputs: string }
   | { ForeignShardGroupDecidedToAbort: { start_shard: number; end_shard: number } }
   | { FeesNotPaid: string }
+  | { InvalidArguments: { function: string; details: string } }
+  | { LockLeaseExpired: string }
+  | { CrossShardDeadlock: string }
+  | { CommitteeLayoutChanged: string }
+  | { InputRentExpired: string }
   | "Unknown";
@@ -4,4 +4,5 @@ import type { RuleRequirement } from "./RuleRequirement";
 export type RequireRule =
   | { Require: RuleRequirement }
   | { AnyOf: Array<RuleRequirement> }
-  | { AllOf: Array<RuleRequirement> };
+  | { AllOf: Array<RuleRequirement> }
+  | { Threshold: { threshold: number; requirements: Array<RuleRequirement> } };
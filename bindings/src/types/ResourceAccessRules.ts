@@ -8,4 +8,6 @@ export interface ResourceAccessRules {
   withdrawable: AccessRule;
   depositable: AccessRule;
   update_non_fungible_data: AccessRule;
+  update_metadata: AccessRule;
+  freezable: AccessRule;
 }
@@ -4,4 +4,5 @@ import type { RequireRule } from "./RequireRule";
 export type RestrictedAccessRule =
   | { Require: RequireRule }
   | { AnyOf: Array<RestrictedAccessRule> }
-  | { AllOf: Array<RestrictedAccessRule> };
+  | { AllOf: Array<RestrictedAccessRule> }
+  | { Not: RestrictedAccessRule };
@@ -7,4 +7,8 @@ export type RuleRequirement =
   | { Resource: ResourceAddress }
   | { NonFungibleAddress: NonFungibleAddress }
   | { ScopedToComponent: ComponentAddress }
-  | { ScopedToTemplate: Uint8Array };
+  | { ScopedToTemplate: Uint8Array }
+  | { BeforeEpoch: number }
+  | { AfterEpoch: number }
+  | { BeforeTimestamp: number }
+  | { AfterTimestamp: number };
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExecutionContext } from "./ExecutionContext";
 import type { SubstateAddress } from "./SubstateAddress";
 import type { SubstateLockType } from "./SubstateLockType";
 
@@ -6,4 +7,5 @@ export interface ShardGroupEvidence {
   substates: Record<SubstateAddress, SubstateLockType>;
   prepare_qc: string | null;
   accept_qc: string | null;
+  execution_context: ExecutionContext | null;
 }
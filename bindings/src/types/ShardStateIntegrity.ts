@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Shard } from "./Shard";
+import type { SubstateIntegrityMismatch } from "./SubstateIntegrityMismatch";
+
+export interface ShardStateIntegrity {
+  shard: Shard;
+  num_substates: number;
+  state_tree_version: number | null;
+  state_tree_root: string | null;
+  state_tree_error: string | null;
+  computed_root: string;
+  mismatches: Array<SubstateIntegrityMismatch>;
+}
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Epoch } from "./Epoch";
+import type { NodeHeight } from "./NodeHeight";
+import type { ShardStateIntegrity } from "./ShardStateIntegrity";
+
+export interface StateIntegrityReport {
+  block_id: string;
+  epoch: Epoch;
+  height: NodeHeight;
+  expected_state_merkle_root: string;
+  computed_state_merkle_root: string;
+  shards: Array<ShardStateIntegrity>;
+}
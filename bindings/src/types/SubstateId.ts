@@ -1,6 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ComponentAddress } from "./ComponentAddress";
 import type { FeeClaimAddress } from "./FeeClaimAddress";
+import type { KeyValueEntryAddress } from "./KeyValueEntryAddress";
+import type { KeyValueStoreId } from "./KeyValueStoreId";
 import type { NonFungibleAddress } from "./NonFungibleAddress";
 import type { NonFungibleIndexAddress } from "./NonFungibleIndexAddress";
 import type { ResourceAddress } from "./ResourceAddress";
@@ -16,4 +18,6 @@ export type SubstateId =
   | { NonFungible: NonFungibleAddress }
   | { NonFungibleIndex: NonFungibleIndexAddress }
   | { TransactionReceipt: TransactionReceiptAddress }
-  | { FeeClaim: FeeClaimAddress };
+  | { FeeClaim: FeeClaimAddress }
+  | { KeyValueStore: KeyValueStoreId }
+  | { KeyValueEntry: KeyValueEntryAddress };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubstateId } from "./SubstateId";
+import type { SubstateIntegrityMismatchReason } from "./SubstateIntegrityMismatchReason";
+
+export interface SubstateIntegrityMismatch {
+  substate_id: SubstateId;
+  version: number;
+  reason: SubstateIntegrityMismatchReason;
+}
@@ -1,6 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ComponentHeader } from "./ComponentHeader";
 import type { FeeClaim } from "./FeeClaim";
+import type { KeyValueEntry } from "./KeyValueEntry";
+import type { KeyValueStoreContainer } from "./KeyValueStoreContainer";
 import type { NonFungibleContainer } from "./NonFungibleContainer";
 import type { NonFungibleIndex } from "./NonFungibleIndex";
 import type { Resource } from "./Resource";
@@ -16,4 +18,6 @@ export type SubstateValue =
   | { NonFungibleIndex: NonFungibleIndex }
   | { UnclaimedConfidentialOutput: UnclaimedConfidentialOutput }
   | { TransactionReceipt: TransactionReceipt }
-  | { FeeClaim: FeeClaim };
+  | { FeeClaim: FeeClaim }
+  | { KeyValueStore: KeyValueStoreContainer }
+  | { KeyValueEntry: KeyValueEntry };
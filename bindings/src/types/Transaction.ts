@@ -12,6 +12,8 @@ export interface Transaction {
   inputs: Array<SubstateRequirement>;
   min_epoch: Epoch | null;
   max_epoch: Epoch | null;
+  idempotency_key: string | null;
+  num_reserved_outputs: number;
   signatures: Array<TransactionSignature>;
   filled_inputs: Array<VersionedSubstateId>;
 }
@@ -9,4 +9,6 @@ export interface UnsignedTransaction {
   inputs: Array<SubstateRequirement>;
   min_epoch: Epoch | null;
   max_epoch: Epoch | null;
+  idempotency_key: string | null;
+  num_reserved_outputs: number;
 }
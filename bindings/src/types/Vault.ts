@@ -3,4 +3,5 @@ import type { ResourceContainer } from "./ResourceContainer";
 
 export interface Vault {
   resource_container: ResourceContainer;
+  rent_paid_until: number | null;
 }
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExecuteResult } from "../ExecuteResult";
+
+export interface IndexerDryRunTransactionResponse {
+  transaction_id: string;
+  result: ExecuteResult;
+  json_results: Array<any>;
+}
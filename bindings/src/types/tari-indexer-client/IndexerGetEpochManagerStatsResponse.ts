@@ -5,4 +5,6 @@ export interface IndexerGetEpochManagerStatsResponse {
   current_epoch: Epoch;
   current_block_height: number;
   current_block_hash: string;
+  max_transaction_bytes: number;
+  max_instructions: number;
 }
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NameTarget } from "./NameTarget";
+import type { NonFungibleId } from "../NonFungibleId";
+
+export interface ResolveNameResponse {
+  name: string;
+  target: NameTarget;
+  badge: NonFungibleId;
+  expiry_epoch: number;
+  is_expired: boolean;
+}
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+
+export interface TransferItem {
+  resource_address: string;
+  from: string | null;
+  to: string | null;
+  amount: Amount;
+  transaction_id: string;
+  timestamp: number;
+}
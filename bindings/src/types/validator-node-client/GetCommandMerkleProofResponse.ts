@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BlockHeader } from "../BlockHeader";
+import type { CommandMerkleProof } from "../CommandMerkleProof";
+
+export interface GetCommandMerkleProofResponse {
+  header: BlockHeader;
+  proof: CommandMerkleProof;
+}
@@ -9,4 +9,6 @@ export interface GetEpochManagerStatsResponse {
   is_valid: boolean;
   start_epoch: Epoch | null;
   committee_info: CommitteeInfo | null;
+  max_transaction_bytes: number;
+  max_instructions: number;
 }
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { ComponentAddress } from "../ComponentAddress";
+import type { ComponentAddressOrName } from "./ComponentAddressOrName";
+
+export interface AmmAddLiquidityRequest {
+  account: ComponentAddressOrName | null;
+  pool_address: ComponentAddress;
+  a_amount: Amount;
+  b_amount: Amount;
+  max_fee: Amount | null;
+  dry_run: boolean;
+}
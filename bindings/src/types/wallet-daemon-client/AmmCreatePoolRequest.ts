@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { ComponentAddressOrName } from "./ComponentAddressOrName";
+import type { ResourceAddress } from "../ResourceAddress";
+
+export interface AmmCreatePoolRequest {
+  account: ComponentAddressOrName | null;
+  a_resource: ResourceAddress;
+  b_resource: ResourceAddress;
+  fee: number;
+  max_fee: Amount | null;
+}
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { ComponentAddress } from "../ComponentAddress";
+import type { FinalizeResult } from "../FinalizeResult";
+import type { ResourceAddress } from "../ResourceAddress";
+
+export interface AmmCreatePoolResponse {
+  transaction_id: string;
+  pool_address: ComponentAddress;
+  lp_resource: ResourceAddress;
+  fee: Amount;
+  result: FinalizeResult;
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { ComponentAddress } from "../ComponentAddress";
+import type { ComponentAddressOrName } from "./ComponentAddressOrName";
+
+export interface AmmRemoveLiquidityRequest {
+  account: ComponentAddressOrName | null;
+  pool_address: ComponentAddress;
+  lp_amount: Amount;
+  max_fee: Amount | null;
+  dry_run: boolean;
+}
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { FinalizeResult } from "../FinalizeResult";
+
+export interface AmmRemoveLiquidityResponse {
+  transaction_id: string;
+  a_amount: Amount;
+  b_amount: Amount;
+  fee: Amount;
+  result: FinalizeResult;
+}
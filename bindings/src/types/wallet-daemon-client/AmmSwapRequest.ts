@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { ComponentAddress } from "../ComponentAddress";
+import type { ComponentAddressOrName } from "./ComponentAddressOrName";
+import type { ResourceAddress } from "../ResourceAddress";
+
+export interface AmmSwapRequest {
+  account: ComponentAddressOrName | null;
+  pool_address: ComponentAddress;
+  input_resource: ResourceAddress;
+  input_amount: Amount;
+  min_output: Amount | null;
+  max_fee: Amount | null;
+  dry_run: boolean;
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Amount } from "../Amount";
+import type { FinalizeResult } from "../FinalizeResult";
+import type { ResourceAddress } from "../ResourceAddress";
+
+export interface AmmSwapResponse {
+  transaction_id: string;
+  output_resource: ResourceAddress;
+  output_amount: Amount;
+  fee: Amount;
+  result: FinalizeResult;
+}
@@ -14,4 +14,5 @@ export interface ConfidentialTransferRequest {
   output_to_revealed: boolean;
   proof_from_badge_resource: string | null;
   dry_run: boolean;
+  memo: string | null;
 }
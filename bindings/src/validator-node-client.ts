@@ -29,6 +29,8 @@ export * from "./types/validator-node-client/GetTransactionResponse";
 export * from "./types/validator-node-client/GetCommitteeResponse";
 export * from "./types/validator-node-client/VNGetAllVnsRequest";
 export * from "./types/validator-node-client/GetCommitteeRequest";
+export * from "./types/validator-node-client/GetCommandMerkleProofRequest";
+export * from "./types/validator-node-client/GetCommandMerkleProofResponse";
 export * from "./types/validator-node-client/DryRunTransactionFinalizeResult";
 export * from "./types/validator-node-client/VNGetValidatorFeesResponse";
 export * from "./types/validator-node-client/GetStateResponse";
@@ -55,5 +57,6 @@ export * from "./types/validator-node-client/VNGetSubstateResponse";
 export * from "./types/validator-node-client/VNGetAllVnsResponse";
 export * from "./types/validator-node-client/VNArgDef";
 export * from "./types/validator-node-client/GetEpochManagerStatsResponse";
+export * from "./types/validator-node-client/CheckStateIntegrityResponse";
 export * from "./types/validator-node-client/GetBlockResponse";
 export * from "./types/validator-node-client/VNCommitteeShardInfo";
@@ -87,6 +87,16 @@ export * from "./types/wallet-daemon-client/TransactionSubmitDryRunRequest";
 export * from "./types/wallet-daemon-client/KeysSetActiveRequest";
 export * from "./types/wallet-daemon-client/ConfidentialViewVaultBalanceRequest";
 export * from "./types/wallet-daemon-client/ConfidentialTransferRequest";
+export * from "./types/wallet-daemon-client/ConfidentialDecryptMemosRequest";
+export * from "./types/wallet-daemon-client/ConfidentialDecryptMemosResponse";
 export * from "./types/wallet-daemon-client/AccountsCreateResponse";
 export * from "./types/wallet-daemon-client/TransactionWaitResultResponse";
 export * from "./types/wallet-daemon-client/AccountGetRequest";
+export * from "./types/wallet-daemon-client/AmmCreatePoolRequest";
+export * from "./types/wallet-daemon-client/AmmCreatePoolResponse";
+export * from "./types/wallet-daemon-client/AmmAddLiquidityRequest";
+export * from "./types/wallet-daemon-client/AmmAddLiquidityResponse";
+export * from "./types/wallet-daemon-client/AmmRemoveLiquidityRequest";
+export * from "./types/wallet-daemon-client/AmmRemoveLiquidityResponse";
+export * from "./types/wallet-daemon-client/AmmSwapRequest";
+export * from "./types/wallet-daemon-client/AmmSwapResponse";
@@ -31,6 +31,8 @@ use crate::{
     types::{
         AddPeerRequest,
         AddPeerResponse,
+        DryRunTransactionRequest,
+        DryRunTransactionResponse,
         GetEpochManagerStatsResponse,
         GetNonFungiblesRequest,
         GetNonFungiblesResponse,
@@ -40,8 +42,12 @@ use crate::{
         GetTemplateDefinitionResponse,
         GetTransactionResultRequest,
         GetTransactionResultResponse,
+        GetTransfersRequest,
+        GetTransfersResponse,
         ListSubstatesRequest,
         ListSubstatesResponse,
+        ResolveNameRequest,
+        ResolveNameResponse,
         SubmitTransactionRequest,
         SubmitTransactionResponse,
     },
@@ -114,6 +120,13 @@ impl IndexerJsonRpcClient {
         self.send_request("submit_transaction", req).await
     }
 
+    pub async fn dry_run_transaction(
+        &mut self,
+        req: DryRunTransactionRequest,
+    ) -> Result<DryRunTransactionResponse, IndexerClientError> {
+        self.send_request("dry_run_transaction", req).await
+    }
+
     pub async fn get_transaction_result(
         &mut self,
         req: GetTransactionResultRequest,
@@ -135,6 +148,17 @@ impl IndexerJsonRpcClient {
         self.send_request("get_non_fungibles", req).await
     }
 
+    pub async fn get_transfers(
+        &mut self,
+        req: GetTransfersRequest,
+    ) -> Result<GetTransfersResponse, IndexerClientError> {
+        self.send_request("get_transfers", req).await
+    }
+
+    pub async fn resolve_name(&mut self, req: ResolveNameRequest) -> Result<ResolveNameResponse, IndexerClientError> {
+        self.send_request("resolve_name", req).await
+    }
+
     pub async fn get_epoch_manager_stats(&mut self) -> Result<GetEpochManagerStatsResponse, IndexerClientError> {
         self.send_request("get_epoch_manager_stats", ()).await
     }
@@ -18,6 +18,7 @@ use tari_engine_types::{
     TemplateAddress,
 };
 use tari_template_abi::TemplateDef;
+use tari_template_lib::models::{Amount, ComponentAddress, NonFungibleId, ResourceAddress};
 use tari_transaction::{Transaction, TransactionId};
 #[cfg(feature = "ts")]
 use ts_rs::TS;
@@ -141,6 +142,8 @@ pub struct InspectSubstateResponse {
 )]
 pub struct SubmitTransactionRequest {
     pub transaction: Transaction,
+    /// The substates that the transaction requires. If empty and the transaction has no inputs, the indexer discovers
+    /// them from the instructions and fills in their latest versions.
     #[serde(default)]
     pub required_substates: Vec<SubstateRequirement>,
     pub is_dry_run: bool,
@@ -162,6 +165,40 @@ pub struct SubmitTransactionResponse {
     pub result: IndexerTransactionFinalizedResult,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(
+        export,
+        export_to = "../../bindings/src/types/tari-indexer-client/",
+        rename = "IndexerDryRunTransactionRequest"
+    )
+)]
+pub struct DryRunTransactionRequest {
+    pub transaction: Transaction,
+    #[serde(default)]
+    pub required_substates: Vec<SubstateRequirement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(
+        export,
+        export_to = "../../bindings/src/types/tari-indexer-client/",
+        rename = "IndexerDryRunTransactionResponse"
+    )
+)]
+pub struct DryRunTransactionResponse {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    pub result: ExecuteResult,
+    #[cfg_attr(feature = "ts", ts(type = "Array<any>"))]
+    pub json_results: Vec<JsonValue>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ts",
@@ -454,6 +491,10 @@ pub struct GetEpochManagerStatsResponse {
     pub current_block_height: u64,
     #[cfg_attr(feature = "ts", ts(type = "string"))]
     pub current_block_hash: FixedHash,
+    /// The maximum encoded size in bytes of a signed transaction accepted by the network
+    pub max_transaction_bytes: usize,
+    /// The maximum number of instructions, including fee instructions, accepted by the network
+    pub max_instructions: usize,
 }
 
 #[derive(Serialize, Debug)]
@@ -530,3 +571,90 @@ pub struct GetTemplateDefinitionResponse {
     pub name: String,
     pub definition: TemplateDef,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub struct GetTransfersRequest {
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub resource_address: Option<ResourceAddress>,
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub account: Option<ComponentAddress>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub struct GetTransfersResponse {
+    pub transfers: Vec<TransferItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub struct TransferItem {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub resource_address: ResourceAddress,
+    /// The account that the funds were withdrawn from, if any
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub from: Option<ComponentAddress>,
+    /// The account that the funds were deposited into, if any
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub to: Option<ComponentAddress>,
+    pub amount: Amount,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub struct ResolveNameRequest {
+    /// The address of the name service component in which the name is registered
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub name_service: ComponentAddress,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub struct ResolveNameResponse {
+    pub name: String,
+    pub target: NameTarget,
+    pub badge: NonFungibleId,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub expiry_epoch: u64,
+    /// True if the registration has expired, in which case the name should not be used as a destination
+    pub is_expired: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/tari-indexer-client/")
+)]
+pub enum NameTarget {
+    Component(#[cfg_attr(feature = "ts", ts(type = "string"))] ComponentAddress),
+    PublicKey(#[cfg_attr(feature = "ts", ts(type = "string"))] PublicKey),
+}
@@ -67,6 +67,10 @@ impl ValidatorNodeClient {
         self.send_request("get_epoch_manager_stats", json!({})).await
     }
 
+    pub async fn check_state_integrity(&mut self) -> Result<CheckStateIntegrityResponse, ValidatorNodeClientError> {
+        self.send_request("check_state_integrity", json!({})).await
+    }
+
     pub async fn get_active_templates(
         &mut self,
         request: GetTemplatesRequest,
@@ -153,6 +157,13 @@ impl ValidatorNodeClient {
         self.send_request("get_block", request).await
     }
 
+    pub async fn get_command_merkle_proof(
+        &mut self,
+        request: GetCommandMerkleProofRequest,
+    ) -> Result<GetCommandMerkleProofResponse, ValidatorNodeClientError> {
+        self.send_request("get_command_merkle_proof", request).await
+    }
+
     fn next_request_id(&mut self) -> i64 {
         self.request_id += 1;
         self.request_id
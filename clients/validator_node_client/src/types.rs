@@ -36,10 +36,13 @@ use tari_dan_common_types::{
 use tari_dan_storage::{
     consensus_models::{
         Block,
+        BlockHeader,
         BlockId,
+        CommandMerkleProof,
         Decision,
         ExecutedTransaction,
         QuorumDecision,
+        StateIntegrityReport,
         SubstateRecord,
         TransactionPoolRecord,
     },
@@ -396,6 +399,30 @@ pub struct GetBlockResponse {
     pub block: Block,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/validator-node-client/")
+)]
+pub struct GetCommandMerkleProofRequest {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub block_id: BlockId,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/validator-node-client/")
+)]
+pub struct GetCommandMerkleProofResponse {
+    pub header: BlockHeader,
+    pub proof: CommandMerkleProof,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(
     feature = "ts",
@@ -675,6 +702,10 @@ pub struct GetEpochManagerStatsResponse {
     pub is_valid: bool,
     pub start_epoch: Option<Epoch>,
     pub committee_info: Option<CommitteeInfo>,
+    /// The maximum encoded size in bytes of a signed transaction accepted by the network
+    pub max_transaction_bytes: usize,
+    /// The maximum number of instructions, including fee instructions, accepted by the network
+    pub max_instructions: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -842,3 +873,13 @@ pub struct GetConnectionsResponse {
 pub struct GetMempoolStatsResponse {
     pub size: usize,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/validator-node-client/")
+)]
+pub struct CheckStateIntegrityResponse {
+    pub report: StateIntegrityReport,
+}
@@ -47,6 +47,14 @@ use types::{
     AccountsCreateFreeTestCoinsResponse,
     AccountsTransferRequest,
     AccountsTransferResponse,
+    AmmAddLiquidityRequest,
+    AmmAddLiquidityResponse,
+    AmmCreatePoolRequest,
+    AmmCreatePoolResponse,
+    AmmRemoveLiquidityRequest,
+    AmmRemoveLiquidityResponse,
+    AmmSwapRequest,
+    AmmSwapResponse,
     AuthLoginAcceptRequest,
     AuthLoginAcceptResponse,
     AuthLoginDenyRequest,
@@ -95,6 +103,8 @@ use crate::{
         ClaimValidatorFeesResponse,
         ConfidentialCreateOutputProofRequest,
         ConfidentialCreateOutputProofResponse,
+        ConfidentialDecryptMemosRequest,
+        ConfidentialDecryptMemosResponse,
         ConfidentialTransferRequest,
         ConfidentialTransferResponse,
         ConfidentialViewVaultBalanceRequest,
@@ -433,6 +443,34 @@ impl WalletDaemonClient {
         self.send_request("nfts.list", req.borrow()).await
     }
 
+    pub async fn amm_create_pool<T: Borrow<AmmCreatePoolRequest>>(
+        &mut self,
+        req: T,
+    ) -> Result<AmmCreatePoolResponse, WalletDaemonClientError> {
+        self.send_request("amm.create_pool", req.borrow()).await
+    }
+
+    pub async fn amm_add_liquidity<T: Borrow<AmmAddLiquidityRequest>>(
+        &mut self,
+        req: T,
+    ) -> Result<AmmAddLiquidityResponse, WalletDaemonClientError> {
+        self.send_request("amm.add_liquidity", req.borrow()).await
+    }
+
+    pub async fn amm_remove_liquidity<T: Borrow<AmmRemoveLiquidityRequest>>(
+        &mut self,
+        req: T,
+    ) -> Result<AmmRemoveLiquidityResponse, WalletDaemonClientError> {
+        self.send_request("amm.remove_liquidity", req.borrow()).await
+    }
+
+    pub async fn amm_swap<T: Borrow<AmmSwapRequest>>(
+        &mut self,
+        req: T,
+    ) -> Result<AmmSwapResponse, WalletDaemonClientError> {
+        self.send_request("amm.swap", req.borrow()).await
+    }
+
     pub async fn view_vault_balance<T: Borrow<ConfidentialViewVaultBalanceRequest>>(
         &mut self,
         req: T,
@@ -440,6 +478,13 @@ impl WalletDaemonClient {
         self.send_request("confidential.view_vault_balance", req.borrow()).await
     }
 
+    pub async fn decrypt_memos<T: Borrow<ConfidentialDecryptMemosRequest>>(
+        &mut self,
+        req: T,
+    ) -> Result<ConfidentialDecryptMemosResponse, WalletDaemonClientError> {
+        self.send_request("confidential.decrypt_memos", req.borrow()).await
+    }
+
     pub async fn auth_request<T: Borrow<AuthLoginRequest>>(
         &mut self,
         req: T,
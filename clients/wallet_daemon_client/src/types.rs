@@ -36,6 +36,7 @@ use tari_dan_wallet_sdk::{
 };
 use tari_engine_types::{
     commit_result::{ExecuteResult, FinalizeResult},
+    fees::FeeCostBreakdown,
     instruction::Instruction,
     instruction_result::InstructionResult,
     serde_with,
@@ -155,6 +156,7 @@ pub struct TransactionSubmitDryRunResponse {
     pub result: ExecuteResult,
     #[cfg_attr(feature = "ts", ts(type = "Array<any>"))]
     pub json_result: Vec<serde_json::Value>,
+    pub fee_breakdown: FeeCostBreakdown,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -582,6 +584,8 @@ pub struct AccountsTransferRequest {
     #[cfg_attr(feature = "ts", ts(type = "string | null"))]
     pub proof_from_badge_resource: Option<ResourceAddress>,
     pub dry_run: bool,
+    #[serde(default)]
+    pub memo: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -739,6 +743,31 @@ pub struct ConfidentialViewVaultBalanceResponse {
     pub balances: HashMap<PublicKey, Option<u64>>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct ConfidentialDecryptMemosRequest {
+    pub vault_id: VaultId,
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub key_id: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct ConfidentialDecryptMemosResponse {
+    /// Decrypted memos keyed by output commitment. Outputs without a memo, or with a memo that could not be decrypted
+    /// with the given key, are omitted.
+    #[cfg_attr(feature = "ts", ts(type = "Record<string, string>"))]
+    pub memos: HashMap<PublicKey, String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[cfg_attr(
     feature = "ts",
@@ -1196,3 +1225,127 @@ pub struct TemplatesGetRequest {
 pub struct TemplatesGetResponse {
     pub template_definition: TemplateDef,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmCreatePoolRequest {
+    #[serde(deserialize_with = "opt_string_or_struct")]
+    pub account: Option<ComponentAddressOrName>,
+    pub a_resource: ResourceAddress,
+    pub b_resource: ResourceAddress,
+    /// The swap fee in basis points
+    pub fee: u16,
+    pub max_fee: Option<Amount>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmCreatePoolResponse {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    pub pool_address: ComponentAddress,
+    pub lp_resource: ResourceAddress,
+    pub fee: Amount,
+    pub result: FinalizeResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmAddLiquidityRequest {
+    #[serde(deserialize_with = "opt_string_or_struct")]
+    pub account: Option<ComponentAddressOrName>,
+    pub pool_address: ComponentAddress,
+    pub a_amount: Amount,
+    pub b_amount: Amount,
+    pub max_fee: Option<Amount>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmAddLiquidityResponse {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    pub lp_amount: Amount,
+    pub fee: Amount,
+    pub result: FinalizeResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmRemoveLiquidityRequest {
+    #[serde(deserialize_with = "opt_string_or_struct")]
+    pub account: Option<ComponentAddressOrName>,
+    pub pool_address: ComponentAddress,
+    pub lp_amount: Amount,
+    pub max_fee: Option<Amount>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmRemoveLiquidityResponse {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    pub a_amount: Amount,
+    pub b_amount: Amount,
+    pub fee: Amount,
+    pub result: FinalizeResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmSwapRequest {
+    #[serde(deserialize_with = "opt_string_or_struct")]
+    pub account: Option<ComponentAddressOrName>,
+    pub pool_address: ComponentAddress,
+    pub input_resource: ResourceAddress,
+    pub input_amount: Amount,
+    /// The swap is rejected if fewer than this many output tokens would be received
+    pub min_output: Option<Amount>,
+    pub max_fee: Option<Amount>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(TS),
+    ts(export, export_to = "../../bindings/src/types/wallet-daemon-client/")
+)]
+pub struct AmmSwapResponse {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub transaction_id: TransactionId,
+    pub output_resource: ResourceAddress,
+    pub output_amount: Amount,
+    pub fee: Amount,
+    pub result: FinalizeResult,
+}
@@ -1,7 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{borrow::Borrow, cmp, ops::RangeInclusive};
+use std::{borrow::Borrow, cmp, collections::BTreeMap, ops::RangeInclusive};
 
 use rand::{rngs::OsRng, seq::SliceRandom};
 use serde::{Deserialize, Serialize};
@@ -20,6 +20,9 @@ pub struct Committee<TAddr> {
     // TODO: not pub
     #[cfg_attr(feature = "ts", ts(type = "Array<[TAddr, string]>"))]
     pub members: Vec<(TAddr, PublicKey)>,
+    /// The voting power of each member. Members without an entry have a voting power of 1.
+    #[cfg_attr(feature = "ts", ts(type = "Record<string, number>"))]
+    voting_powers: BTreeMap<PublicKey, u64>,
 }
 
 impl<TAddr: PartialEq> Committee<TAddr> {
@@ -32,19 +35,152 @@ impl<TAddr: PartialEq> Committee<TAddr> {
     }
 
     pub fn new(members: Vec<(TAddr, PublicKey)>) -> Self {
-        Self { members }
+        Self {
+            members,
+            voting_powers: BTreeMap::new(),
+        }
     }
 
     pub fn members(&self) -> impl Iterator<Item = &TAddr> + '_ {
         self.members.iter().map(|(addr, _)| addr)
     }
 
+    /// Returns the maximum number of members $f$ that can fail. If members have different voting powers, this is the
+    /// largest number of members whose combined voting power does not exceed [Committee::max_faulty_voting_power], so
+    /// any $f + 1$ members include at least one member that has not failed.
     pub fn max_failures(&self) -> usize {
         let len = self.members.len();
         if len == 0 {
             return 0;
         }
-        (len - 1) / 3
+        if !self.is_weighted() {
+            return (len - 1) / 3;
+        }
+
+        let max_faulty_voting_power = self.max_faulty_voting_power();
+        let mut voting_power = 0u64;
+        self.sorted_voting_powers()
+            .into_iter()
+            .take_while(|vp| {
+                voting_power = voting_power.saturating_add(*vp);
+                voting_power <= max_faulty_voting_power
+            })
+            .count()
+    }
+
+    /// Returns $n - f$ (i.e $2f + 1$) where n is the number of committee members and f is the tolerated failure nodes.
+    /// If members have different voting powers, this is the smallest number of members that hold a quorum of the voting
+    /// power regardless of which members they are.
+    pub fn quorum_threshold(&self) -> usize {
+        if !self.is_weighted() {
+            return self.len() - self.max_failures();
+        }
+
+        let quorum_voting_power = self.quorum_voting_power();
+        let mut voting_power = 0u64;
+        self.sorted_voting_powers()
+            .into_iter()
+            .position(|vp| {
+                voting_power = voting_power.saturating_add(vp);
+                voting_power >= quorum_voting_power
+            })
+            .map_or(self.len(), |i| i + 1)
+    }
+
+    /// Sets the voting power of a member. A voting power of 1 is the same as one-validator-one-vote.
+    pub fn set_voting_power(&mut self, public_key: PublicKey, voting_power: u64) {
+        if voting_power == 1 {
+            self.voting_powers.remove(&public_key);
+        } else {
+            self.voting_powers.insert(public_key, voting_power);
+        }
+    }
+
+    pub fn with_voting_power(mut self, public_key: PublicKey, voting_power: u64) -> Self {
+        self.set_voting_power(public_key, voting_power);
+        self
+    }
+
+    /// Returns the voting power of the member with the given public key, or 0 if they are not a member of the
+    /// committee.
+    pub fn voting_power_of(&self, public_key: &PublicKey) -> u64 {
+        if !self.contains_public_key(public_key) {
+            return 0;
+        }
+        self.member_voting_power(public_key)
+    }
+
+    /// Returns the voting power of the member with the given address, or 0 if they are not a member of the committee.
+    pub fn voting_power_of_member(&self, member: &TAddr) -> u64 {
+        self.members
+            .iter()
+            .find(|(addr, _)| addr == member)
+            .map_or(0, |(_, pk)| self.member_voting_power(pk))
+    }
+
+    fn member_voting_power(&self, public_key: &PublicKey) -> u64 {
+        self.voting_powers.get(public_key).copied().unwrap_or(1)
+    }
+
+    /// Returns the voting power of each member in ascending order
+    fn sorted_voting_powers(&self) -> Vec<u64> {
+        let mut voting_powers = self
+            .public_keys()
+            .map(|pk| self.member_voting_power(pk))
+            .collect::<Vec<_>>();
+        voting_powers.sort_unstable();
+        voting_powers
+    }
+
+    /// Returns true if any member has a voting power other than 1
+    pub fn is_weighted(&self) -> bool {
+        !self.voting_powers.is_empty()
+    }
+
+    pub fn total_voting_power(&self) -> u64 {
+        self.public_keys()
+            .fold(0u64, |acc, pk| acc.saturating_add(self.member_voting_power(pk)))
+    }
+
+    /// Returns the maximum voting power $f$ that can be held by faulty members. If all members have a voting power of
+    /// 1, this is the same as [Committee::max_failures].
+    pub fn max_faulty_voting_power(&self) -> u64 {
+        let total = self.total_voting_power();
+        if total == 0 {
+            return 0;
+        }
+        (total - 1) / 3
+    }
+
+    /// Returns the voting power $n - f$ required to reach quorum, where n is the total voting power of the committee.
+    /// If all members have a voting power of 1, this is the same as [Committee::quorum_threshold].
+    pub fn quorum_voting_power(&self) -> u64 {
+        self.total_voting_power() - self.max_faulty_voting_power()
+    }
+
+    /// Returns the sum of the voting power of the given public keys. Each member is only counted once and public keys
+    /// that are not in the committee are ignored.
+    pub fn sum_voting_power<'a, I: IntoIterator<Item = &'a PublicKey>>(&self, public_keys: I) -> u64 {
+        let mut seen = Vec::new();
+        public_keys.into_iter().fold(0u64, |acc, pk| {
+            if seen.contains(&pk) {
+                return acc;
+            }
+            seen.push(pk);
+            acc.saturating_add(self.voting_power_of(pk))
+        })
+    }
+
+    /// Returns the index of the member that holds the given unit of the committee's voting power. Members hold
+    /// consecutive ranges of the total voting power in the order of the committee, so a value chosen uniformly from
+    /// `0..total_voting_power()` selects each member with a probability proportional to its voting power. Returns None
+    /// if the value is not less than the total voting power.
+    pub fn index_of_voting_power(&self, voting_power: u64) -> Option<usize> {
+        let mut upper = 0u64;
+        self.public_keys().position(|pk| {
+            upper = upper.saturating_add(self.member_voting_power(pk));
+            voting_power < upper
+        })
     }
 
     pub fn is_empty(&self) -> bool {
@@ -156,12 +292,16 @@ impl<TAddr: PartialEq> FromIterator<(TAddr, PublicKey)> for Committee<TAddr> {
 impl<TAddr: PartialEq> FromIterator<Committee<TAddr>> for Committee<TAddr> {
     fn from_iter<T: IntoIterator<Item = Committee<TAddr>>>(iter: T) -> Self {
         let into_iter = iter.into_iter();
-        let members = into_iter.fold(Vec::new(), |mut acc, committee| {
-            acc.extend(committee.members);
-            acc
-        });
+        let (members, voting_powers) = into_iter.fold(
+            (Vec::new(), BTreeMap::new()),
+            |(mut members, mut voting_powers), committee| {
+                members.extend(committee.members);
+                voting_powers.extend(committee.voting_powers);
+                (members, voting_powers)
+            },
+        );
 
-        Self::new(members)
+        Self { members, voting_powers }
     }
 }
 
@@ -348,4 +488,81 @@ mod tests {
             assert!(selected.is_empty());
         }
     }
+
+    mod voting_power {
+        use tari_crypto::keys::PublicKey as _;
+
+        use super::*;
+
+        fn create_weighted_committee(voting_powers: &[u64]) -> Committee<u32> {
+            let mut committee = Committee::empty();
+            for (i, voting_power) in voting_powers.iter().enumerate() {
+                let (_, pk) = RistrettoPublicKey::random_keypair(&mut OsRng);
+                committee.members.push((i as u32, pk.clone()));
+                committee.set_voting_power(pk, *voting_power);
+            }
+            committee
+        }
+
+        #[test]
+        fn it_matches_member_counts_when_unweighted() {
+            let committee = create_weighted_committee(&[1, 1, 1, 1, 1, 1, 1]);
+            assert!(!committee.is_weighted());
+            assert_eq!(committee.total_voting_power(), 7);
+            assert_eq!(committee.max_faulty_voting_power(), committee.max_failures() as u64);
+            assert_eq!(committee.quorum_voting_power(), committee.quorum_threshold() as u64);
+        }
+
+        #[test]
+        fn it_calculates_weighted_thresholds() {
+            let committee = create_weighted_committee(&[10, 20, 30, 40]);
+            assert!(committee.is_weighted());
+            assert_eq!(committee.total_voting_power(), 100);
+            assert_eq!(committee.max_faulty_voting_power(), 33);
+            assert_eq!(committee.quorum_voting_power(), 67);
+
+            let pks = committee.public_keys().collect::<Vec<_>>();
+            // 30 + 40 = 70 >= 67
+            assert!(committee.sum_voting_power([pks[2], pks[3]]) >= committee.quorum_voting_power());
+            // 10 + 20 + 30 = 60 < 67, even though 3 of 4 members signed
+            assert!(committee.sum_voting_power([pks[0], pks[1], pks[2]]) < committee.quorum_voting_power());
+        }
+
+        #[test]
+        fn it_calculates_weighted_member_thresholds() {
+            let committee = create_weighted_committee(&[1, 1, 1, 1, 1, 1, 100]);
+            // The six members with a voting power of 1 can fail without exceeding the faulty voting power
+            assert_eq!(committee.max_faulty_voting_power(), 35);
+            assert_eq!(committee.max_failures(), 6);
+            // All members are needed to reach quorum if the member with a voting power of 100 is not one of them
+            assert_eq!(committee.quorum_threshold(), 7);
+
+            let committee = create_weighted_committee(&[10, 20, 30, 40]);
+            assert_eq!(committee.max_failures(), 2);
+            // 10 + 20 + 30 = 60 < 67
+            assert_eq!(committee.quorum_threshold(), 4);
+        }
+
+        #[test]
+        fn it_selects_members_by_voting_power() {
+            let committee = create_weighted_committee(&[1, 3, 2]);
+            let selected = (0..committee.total_voting_power())
+                .map(|vp| committee.index_of_voting_power(vp).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(selected, vec![0, 1, 1, 1, 2, 2]);
+            assert_eq!(committee.index_of_voting_power(6), None);
+
+            let committee = create_weighted_committee(&[1, 1, 1]);
+            assert_eq!(committee.index_of_voting_power(2), Some(2));
+        }
+
+        #[test]
+        fn it_ignores_duplicates_and_non_members() {
+            let committee = create_weighted_committee(&[5, 5]);
+            let (_, non_member) = RistrettoPublicKey::random_keypair(&mut OsRng);
+            let pks = committee.public_keys().collect::<Vec<_>>();
+            assert_eq!(committee.voting_power_of(&non_member), 0);
+            assert_eq!(committee.sum_voting_power([pks[0], pks[0], &non_member]), 5);
+        }
+    }
 }
@@ -35,6 +35,16 @@ type ExtraFieldValue = MaxSizeBytes<MAX_DATA_SIZE>;
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ExtraFieldKey {
     SidechainId = 0x00,
+    /// VRF proof of the block proposer, see [crate::vrf::VrfProof]
+    VrfProof = 0x01,
+    /// Seed used to select leaders for the epoch. Only present in genesis blocks.
+    LeaderSeed = 0x02,
+    /// The number of preshards that the network was configured with, as a little-endian u32. Only present in genesis
+    /// blocks.
+    NumPreshards = 0x03,
+    /// Hash of the genesis file that the network was created with. Only present in genesis blocks of networks that
+    /// use a genesis file.
+    GenesisHash = 0x04,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -56,6 +56,42 @@ pub fn vote_signature_hasher() -> TariHasher {
     dan_hasher("VoteSignature")
 }
 
+pub fn random_beacon_hasher() -> TariHasher {
+    dan_hasher("RandomBeacon")
+}
+
+pub fn vrf_output_hasher() -> TariHasher {
+    dan_hasher("VrfOutput")
+}
+
+pub fn leader_vrf_input_hasher() -> TariHasher {
+    dan_hasher("LeaderVrfInput")
+}
+
+pub fn leader_seed_hasher() -> TariHasher {
+    dan_hasher("LeaderSeed")
+}
+
+pub fn block_leader_seed_hasher() -> TariHasher {
+    dan_hasher("BlockLeaderSeed")
+}
+
+pub fn leader_selection_hasher() -> TariHasher {
+    dan_hasher("LeaderSelection")
+}
+
+pub fn transaction_ordering_hasher() -> TariHasher {
+    dan_hasher("TransactionOrdering")
+}
+
+pub fn validator_allowlist_hasher() -> TariHasher {
+    dan_hasher("ValidatorAllowlist")
+}
+
+pub fn genesis_file_hasher() -> TariHasher {
+    dan_hasher("GenesisFile")
+}
+
 fn dan_hasher(label: &'static str) -> TariHasher {
     tari_hasher::<TariDanConsensusHashDomain>(label)
 }
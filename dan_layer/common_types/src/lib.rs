@@ -26,6 +26,8 @@ pub use node_height::NodeHeight;
 pub mod shard;
 mod shard_group;
 pub use shard_group::*;
+mod shard_group_transition;
+pub use shard_group_transition::*;
 mod validator_metadata;
 pub use validator_metadata::{vn_node_hash, ValidatorMetadata};
 
@@ -44,6 +46,7 @@ pub use peer_address::*;
 mod num_preshards;
 pub use num_preshards::*;
 pub mod uint;
+pub mod vrf;
 
 pub use tari_engine_types::serde_with;
 
@@ -37,6 +37,26 @@ impl ShardGroup {
         Self::new(Shard::zero(), Shard::from(num_preshards.as_u32() - 1))
     }
 
+    /// Returns every shard group, in shard order, for the given number of committees. This is the same layout as
+    /// [SubstateAddress::to_shard_group].
+    pub fn all_for_num_committees(num_shards: NumPreshards, num_committees: u32) -> Vec<Self> {
+        // number of committees can never exceed number of shards
+        let num_committees = num_committees.clamp(1, num_shards.as_u32());
+        let shards_per_committee = num_shards.as_u32() / num_committees;
+        let shards_per_committee_rem = num_shards.as_u32() % num_committees;
+
+        let mut start = 0u32;
+        (0..num_committees)
+            .map(|i| {
+                // The remainder is spread over the first shard groups
+                let len = shards_per_committee + u32::from(i < shards_per_committee_rem);
+                let shard_group = Self::new(start, start + len - 1);
+                start += len;
+                shard_group
+            })
+            .collect()
+    }
+
     pub const fn len(&self) -> usize {
         (self.end_inclusive.as_u32() + 1 - self.start.as_u32()) as usize
     }
@@ -91,6 +111,16 @@ impl ShardGroup {
         self.as_range().contains(shard)
     }
 
+    /// Returns true if every shard in this shard group is also in the other shard group
+    pub fn is_subset_of(&self, other: &ShardGroup) -> bool {
+        other.start <= self.start && self.end_inclusive <= other.end_inclusive
+    }
+
+    /// Returns true if this shard group has at least one shard in common with the other shard group
+    pub fn overlaps(&self, other: &ShardGroup) -> bool {
+        self.start <= other.end_inclusive && other.start <= self.end_inclusive
+    }
+
     pub fn as_range(&self) -> RangeInclusive<Shard> {
         self.start..=self.end_inclusive
     }
@@ -139,6 +169,34 @@ mod tests {
         assert_eq!(ShardGroup::decode_from_u32(u32::MAX), None);
     }
 
+    #[test]
+    fn all_for_num_committees() {
+        for num_committees in 1..=64 {
+            let shard_groups = ShardGroup::all_for_num_committees(NumPreshards::P64, num_committees);
+            assert_eq!(shard_groups.len(), num_committees as usize);
+            assert_eq!(shard_groups.first().unwrap().start(), Shard::zero());
+            assert_eq!(shard_groups.last().unwrap().end(), Shard::from(63));
+            for shard_group in &shard_groups {
+                for shard in shard_group.shard_iter() {
+                    let address = *shard.to_substate_address_range(NumPreshards::P64).start();
+                    assert_eq!(address.to_shard_group(NumPreshards::P64, num_committees), *shard_group);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn subset_and_overlap() {
+        let sg = ShardGroup::new(16, 31);
+        assert!(sg.is_subset_of(&sg));
+        assert!(sg.is_subset_of(&ShardGroup::new(0, 31)));
+        assert!(!sg.is_subset_of(&ShardGroup::new(20, 63)));
+        assert!(sg.overlaps(&ShardGroup::new(31, 40)));
+        assert!(sg.overlaps(&ShardGroup::new(0, 16)));
+        assert!(!sg.overlaps(&ShardGroup::new(32, 63)));
+        assert!(!sg.overlaps(&ShardGroup::new(0, 15)));
+    }
+
     #[test]
     fn to_substate_address_range() {
         let sg = ShardGroup::new(0, 63);
@@ -0,0 +1,108 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{Display, Formatter};
+
+use crate::{NumPreshards, ShardGroup};
+
+/// Describes how a shard group relates to the shard groups of the previous epoch. Shard group boundaries change at
+/// epoch boundaries when the number of committees changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardGroupTransition {
+    /// The shard group exists unchanged in the previous epoch
+    Unchanged,
+    /// The shard group was split off from a larger shard group in the previous epoch
+    Split { from: ShardGroup },
+    /// The shard group is the union of two or more whole shard groups from the previous epoch
+    Merge { from: Vec<ShardGroup> },
+    /// The shard group contains parts of shard groups from the previous epoch. This occurs when the number of shards
+    /// is not divisible by the number of committees.
+    Rebalance { from: Vec<ShardGroup> },
+}
+
+impl ShardGroupTransition {
+    pub fn compute(
+        num_shards: NumPreshards,
+        prev_num_committees: u32,
+        num_committees: u32,
+        shard_group: ShardGroup,
+    ) -> Self {
+        if prev_num_committees == num_committees {
+            return Self::Unchanged;
+        }
+
+        let from = ShardGroup::all_for_num_committees(num_shards, prev_num_committees)
+            .into_iter()
+            .filter(|prev| prev.overlaps(&shard_group))
+            .collect::<Vec<_>>();
+
+        match from.as_slice() {
+            [prev] if *prev == shard_group => Self::Unchanged,
+            [prev] if shard_group.is_subset_of(prev) => Self::Split { from: *prev },
+            _ if from.iter().all(|prev| prev.is_subset_of(&shard_group)) => Self::Merge { from },
+            _ => Self::Rebalance { from },
+        }
+    }
+
+    pub fn is_unchanged(&self) -> bool {
+        matches!(self, Self::Unchanged)
+    }
+
+    /// Returns the shard groups from the previous epoch that hold the state for this shard group. This is empty if the
+    /// shard group is unchanged.
+    pub fn previous_shard_groups(&self) -> &[ShardGroup] {
+        match self {
+            Self::Unchanged => &[],
+            Self::Split { from } => std::slice::from_ref(from),
+            Self::Merge { from } | Self::Rebalance { from } => from,
+        }
+    }
+}
+
+impl Display for ShardGroupTransition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unchanged => write!(f, "Unchanged"),
+            Self::Split { from } => write!(f, "Split from {}", from),
+            Self::Merge { from } => write!(f, "Merge of {} shard group(s)", from.len()),
+            Self::Rebalance { from } => write!(f, "Rebalance of {} shard group(s)", from.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_detects_splits() {
+        let transition = ShardGroupTransition::compute(NumPreshards::P64, 2, 4, ShardGroup::new(16, 31));
+        assert_eq!(transition, ShardGroupTransition::Split {
+            from: ShardGroup::new(0, 31)
+        });
+    }
+
+    #[test]
+    fn it_detects_merges() {
+        let transition = ShardGroupTransition::compute(NumPreshards::P64, 4, 2, ShardGroup::new(32, 63));
+        assert_eq!(transition, ShardGroupTransition::Merge {
+            from: vec![ShardGroup::new(32, 47), ShardGroup::new(48, 63)]
+        });
+    }
+
+    #[test]
+    fn it_detects_rebalances() {
+        // 3 committees: [0, 21], [22, 42], [43, 63]
+        let transition = ShardGroupTransition::compute(NumPreshards::P64, 2, 3, ShardGroup::new(22, 42));
+        assert_eq!(transition, ShardGroupTransition::Rebalance {
+            from: vec![ShardGroup::new(0, 31), ShardGroup::new(32, 63)]
+        });
+    }
+
+    #[test]
+    fn it_detects_unchanged_shard_groups() {
+        let transition = ShardGroupTransition::compute(NumPreshards::P64, 4, 4, ShardGroup::new(0, 15));
+        assert!(transition.is_unchanged());
+        assert!(transition.previous_shard_groups().is_empty());
+    }
+}
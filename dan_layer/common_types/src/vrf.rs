@@ -0,0 +1,212 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A verifiable random function (VRF) over the Ristretto group. The proof is a Chaum-Pedersen proof that the output
+//! point and the public key share the same discrete log, in the style of ECVRF. It does not implement any of the
+//! RFC 9381 cipher suites and its proofs are not compatible with other ECVRF implementations.
+//!
+//! A validator proves the VRF output for an input using its secret key. The output is unique for the key and input, so
+//! the prover cannot grind it, and anyone holding the validator's public key can verify it.
+
+use blake2::{
+    digest::{consts::U64, Digest},
+    Blake2b,
+};
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+};
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey};
+use tari_crypto::{hash_domain, hashing::DomainSeparation, tari_utilities::ByteArray};
+
+use crate::hashing::vrf_output_hasher;
+
+hash_domain!(VrfHashDomain, "com.tari.dan.vrf", 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VrfProof {
+    gamma: [u8; 32],
+    challenge: [u8; 32],
+    response: [u8; 32],
+}
+
+impl VrfProof {
+    pub const BYTE_SIZE: usize = 96;
+
+    pub fn prove(secret_key: &PrivateKey, input: &[u8]) -> Self {
+        let secret = Scalar::from_bytes_mod_order(to_array(secret_key.as_bytes()));
+        let public_key = (secret * RISTRETTO_BASEPOINT_POINT).compress();
+        let h = hash_to_point(public_key.as_bytes(), input);
+        let gamma = secret * h;
+        let nonce =
+            Scalar::from_bytes_mod_order_wide(&hash_wide("Nonce", &[secret.as_bytes(), h.compress().as_bytes()]));
+        let challenge = calculate_challenge(
+            public_key.as_bytes(),
+            &h,
+            &gamma,
+            &(nonce * RISTRETTO_BASEPOINT_POINT),
+            &(nonce * h),
+        );
+        let response = nonce + challenge * secret;
+
+        Self {
+            gamma: gamma.compress().to_bytes(),
+            challenge: challenge.to_bytes(),
+            response: response.to_bytes(),
+        }
+    }
+
+    /// Verifies the proof for the given public key and input, returning the VRF output if the proof is valid
+    pub fn verify(&self, public_key: &PublicKey, input: &[u8]) -> Result<FixedHash, VrfError> {
+        let public_point = decompress(public_key.as_bytes())?;
+        let gamma = decompress(&self.gamma)?;
+        let challenge = canonical_scalar(self.challenge)?;
+        let response = canonical_scalar(self.response)?;
+
+        let h = hash_to_point(public_key.as_bytes(), input);
+        let u = response * RISTRETTO_BASEPOINT_POINT - challenge * public_point;
+        let v = response * h - challenge * gamma;
+        if calculate_challenge(public_key.as_bytes(), &h, &gamma, &u, &v) != challenge {
+            return Err(VrfError::InvalidProof);
+        }
+
+        Ok(self.output())
+    }
+
+    /// Returns the VRF output committed to by this proof. The output should only be trusted once the proof is verified.
+    pub fn output(&self) -> FixedHash {
+        vrf_output_hasher().chain(&self.gamma).result()
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTE_SIZE] {
+        let mut bytes = [0u8; Self::BYTE_SIZE];
+        bytes[..32].copy_from_slice(&self.gamma);
+        bytes[32..64].copy_from_slice(&self.challenge);
+        bytes[64..].copy_from_slice(&self.response);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VrfError> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return Err(VrfError::InvalidLength {
+                expected: Self::BYTE_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        Ok(Self {
+            gamma: to_array(&bytes[..32]),
+            challenge: to_array(&bytes[32..64]),
+            response: to_array(&bytes[64..]),
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VrfError {
+    #[error("Invalid VRF proof length: expected {expected} bytes but got {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("VRF proof contains an invalid group element")]
+    InvalidPoint,
+    #[error("VRF proof contains a non-canonical scalar")]
+    NonCanonicalScalar,
+    #[error("VRF proof is invalid")]
+    InvalidProof,
+}
+
+fn hash_to_point(public_key: &[u8], input: &[u8]) -> RistrettoPoint {
+    RistrettoPoint::from_uniform_bytes(&hash_wide("HashToPoint", &[public_key, input]))
+}
+
+fn calculate_challenge(
+    public_key: &[u8],
+    h: &RistrettoPoint,
+    gamma: &RistrettoPoint,
+    u: &RistrettoPoint,
+    v: &RistrettoPoint,
+) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&hash_wide("Challenge", &[
+        public_key,
+        h.compress().as_bytes(),
+        gamma.compress().as_bytes(),
+        u.compress().as_bytes(),
+        v.compress().as_bytes(),
+    ]))
+}
+
+fn hash_wide(label: &'static str, parts: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Blake2b::<U64>::new();
+    VrfHashDomain::add_domain_separation_tag(&mut hasher, label);
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut output = [0u8; 64];
+    output.copy_from_slice(&hasher.finalize());
+    output
+}
+
+fn decompress(bytes: &[u8]) -> Result<RistrettoPoint, VrfError> {
+    CompressedRistretto::from_slice(bytes)
+        .ok()
+        .and_then(|p| p.decompress())
+        .ok_or(VrfError::InvalidPoint)
+}
+
+fn canonical_scalar(bytes: [u8; 32]) -> Result<Scalar, VrfError> {
+    Option::from(Scalar::from_canonical_bytes(bytes)).ok_or(VrfError::NonCanonicalScalar)
+}
+
+fn to_array(bytes: &[u8]) -> [u8; 32] {
+    let mut array = [0u8; 32];
+    array.copy_from_slice(bytes);
+    array
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_crypto::keys::PublicKey as _;
+
+    use super::*;
+    use crate::crypto::create_key_pair_from_seed;
+
+    #[test]
+    fn it_proves_and_verifies() {
+        let (secret, public) = create_key_pair_from_seed(1);
+        let proof = VrfProof::prove(&secret, b"input");
+        let output = proof.verify(&public, b"input").unwrap();
+        assert_eq!(output, proof.output());
+
+        // The output is unique for the key and input
+        assert_eq!(VrfProof::prove(&secret, b"input").output(), output);
+        assert_ne!(VrfProof::prove(&secret, b"other").output(), output);
+    }
+
+    #[test]
+    fn it_rejects_the_wrong_key_or_input() {
+        let (secret, _) = create_key_pair_from_seed(1);
+        let (_, other_public) = create_key_pair_from_seed(2);
+        let proof = VrfProof::prove(&secret, b"input");
+        assert!(matches!(
+            proof.verify(&other_public, b"input"),
+            Err(VrfError::InvalidProof)
+        ));
+
+        let public = PublicKey::from_secret_key(&secret);
+        assert!(matches!(proof.verify(&public, b"other"), Err(VrfError::InvalidProof)));
+    }
+
+    #[test]
+    fn it_encodes_and_decodes() {
+        let (secret, public) = create_key_pair_from_seed(1);
+        let proof = VrfProof::prove(&secret, b"input");
+        let decoded = VrfProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        decoded.verify(&public, b"input").unwrap();
+
+        assert!(matches!(
+            VrfProof::from_bytes(&[0u8; 95]),
+            Err(VrfError::InvalidLength { .. })
+        ));
+    }
+}
@@ -2,26 +2,26 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_common::configuration::Network;
+use tari_common_types::types::FixedHash;
 use tari_crypto::{ristretto::RistrettoPublicKey, tari_utilities::ByteArray};
 use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
     DerivableFromPublicKey,
     ExtraFieldKey,
 };
-use tari_dan_storage::consensus_models::Block;
+use tari_dan_storage::consensus_models::{Block, QuorumCertificateVerificationError};
 use tari_epoch_manager::EpochManagerReader;
 
 use crate::{
-    hotstuff::{HotStuffError, HotstuffConfig, ProposalValidationError},
-    traits::{ConsensusSpec, LeaderStrategy, VoteSignatureService},
+    consensus_constants::ConsensusConstants,
+    hotstuff::{calculate_leader_vrf_input, HotStuffError, HotstuffConfig, ProposalValidationError},
+    traits::{ConsensusSpec, LeaderStrategy},
 };
 
 pub fn check_proposal<TConsensusSpec: ConsensusSpec>(
     block: &Block,
     committee_info: &CommitteeInfo,
     committee_for_block: &Committee<TConsensusSpec::Addr>,
-    vote_signing_service: &TConsensusSpec::SignatureService,
-    leader_strategy: &TConsensusSpec::LeaderStrategy,
     config: &HotstuffConfig,
 ) -> Result<(), HotStuffError> {
     // TODO: in order to do the base layer block has validation, we need to ensure that we have synced to the tip.
@@ -34,9 +34,8 @@ pub fn check_proposal<TConsensusSpec: ConsensusSpec>(
         check_dummy(block)?;
     }
     check_hash_and_height(block)?;
-    check_proposed_by_leader(leader_strategy, committee_for_block, block)?;
     check_signature(block)?;
-    check_quorum_certificate::<TConsensusSpec>(block, committee_for_block, committee_info, vote_signing_service)?;
+    check_quorum_certificate::<TConsensusSpec>(block, committee_for_block, committee_info)?;
     Ok(())
 }
 
@@ -136,9 +135,10 @@ pub fn check_hash_and_height(candidate_block: &Block) -> Result<(), ProposalVali
 pub fn check_proposed_by_leader<TAddr: DerivableFromPublicKey, TLeaderStrategy: LeaderStrategy<TAddr>>(
     leader_strategy: &TLeaderStrategy,
     local_committee: &Committee<TAddr>,
+    leader_seed: &FixedHash,
     candidate_block: &Block,
 ) -> Result<(), ProposalValidationError> {
-    let (leader, _) = leader_strategy.get_leader(local_committee, candidate_block.height());
+    let (leader, _) = leader_strategy.get_leader(local_committee, leader_seed, candidate_block.height());
     if !leader.eq_to_public_key(candidate_block.proposed_by()) {
         return Err(ProposalValidationError::NotLeader {
             proposed_by: candidate_block.proposed_by().to_string(),
@@ -173,11 +173,84 @@ pub fn check_signature(candidate_block: &Block) -> Result<(), ProposalValidation
     Ok(())
 }
 
+pub fn check_vrf_proof(candidate_block: &Block, leader_seed: &FixedHash) -> Result<(), ProposalValidationError> {
+    if candidate_block.is_dummy() || candidate_block.is_genesis() {
+        // Dummy and genesis blocks are not proposed by a validator
+        return Ok(());
+    }
+    let proof = candidate_block
+        .vrf_proof()
+        .ok_or(ProposalValidationError::MissingVrfProof {
+            block_id: *candidate_block.id(),
+            height: candidate_block.height(),
+        })?
+        .map_err(|e| ProposalValidationError::InvalidVrfProof {
+            block_id: *candidate_block.id(),
+            height: candidate_block.height(),
+            details: e.to_string(),
+        })?;
+    let input = calculate_leader_vrf_input(
+        candidate_block.network(),
+        candidate_block.epoch(),
+        candidate_block.shard_group(),
+        candidate_block.height(),
+        leader_seed,
+    );
+    proof
+        .verify(candidate_block.proposed_by(), input.as_slice())
+        .map_err(|e| ProposalValidationError::InvalidVrfProof {
+            block_id: *candidate_block.id(),
+            height: candidate_block.height(),
+            details: e.to_string(),
+        })?;
+    Ok(())
+}
+
+/// Checks that the block timestamp is no earlier than the justified block timestamp (within tolerance), is not
+/// before the median timestamp of recent blocks and is not too far ahead of the local clock.
+pub fn check_timestamp(
+    candidate_block: &Block,
+    justify_block: &Block,
+    median_timestamp: u64,
+    now: u64,
+    consensus_constants: &ConsensusConstants,
+) -> Result<(), ProposalValidationError> {
+    let timestamp = candidate_block.timestamp();
+    let min_timestamp = justify_block
+        .timestamp()
+        .saturating_sub(consensus_constants.block_timestamp_tolerance.as_secs());
+    if timestamp < min_timestamp {
+        return Err(ProposalValidationError::BlockTimestampBeforeJustify {
+            block_id: *candidate_block.id(),
+            timestamp,
+            justify_timestamp: justify_block.timestamp(),
+        });
+    }
+
+    if consensus_constants.block_timestamp_median_window > 0 && timestamp < median_timestamp {
+        return Err(ProposalValidationError::BlockTimestampBeforeMedian {
+            block_id: *candidate_block.id(),
+            timestamp,
+            median_timestamp,
+        });
+    }
+
+    let max_timestamp = now.saturating_add(consensus_constants.max_block_timestamp_future_drift.as_secs());
+    if timestamp > max_timestamp {
+        return Err(ProposalValidationError::BlockTimestampTooFarInFuture {
+            block_id: *candidate_block.id(),
+            timestamp,
+            max_timestamp,
+        });
+    }
+
+    Ok(())
+}
+
 pub fn check_quorum_certificate<TConsensusSpec: ConsensusSpec>(
     candidate_block: &Block,
     committee: &Committee<TConsensusSpec::Addr>,
     committee_info: &CommitteeInfo,
-    vote_signing_service: &TConsensusSpec::SignatureService,
 ) -> Result<(), HotStuffError> {
     let qc = candidate_block.justify();
     if qc.is_zero() {
@@ -194,36 +267,27 @@ pub fn check_quorum_certificate<TConsensusSpec: ConsensusSpec>(
         .into());
     }
 
-    if qc.signatures().is_empty() {
-        return Err(ProposalValidationError::QuorumWasNotReached { qc: *qc.id() }.into());
-    }
-
-    for signature in qc.signatures() {
-        if !committee.contains_public_key(signature.public_key()) {
-            return Err(ProposalValidationError::ValidatorNotInCommittee {
-                validator: signature.public_key().to_string(),
-                details: format!(
-                    "QC signed with validator {} that is not in committee {}",
-                    signature.public_key(),
-                    committee_info.shard_group()
-                ),
-            }
-            .into());
-        }
-    }
+    qc.verify(committee).map_err(|err| match err {
+        QuorumCertificateVerificationError::NoSigners { qc_id } |
+        QuorumCertificateVerificationError::QuorumNotReached { qc_id, .. } => {
+            ProposalValidationError::QuorumWasNotReached { qc: qc_id }
+        },
+        QuorumCertificateVerificationError::SignerNotInCommittee {
+            index, committee_size, ..
+        } => ProposalValidationError::ValidatorNotInCommittee {
+            validator: format!("signer #{}", index),
+            details: format!(
+                "QC signed by committee member #{} but committee {} only has {} members",
+                index,
+                committee_info.shard_group(),
+                committee_size
+            ),
+        },
+        QuorumCertificateVerificationError::InvalidSignature { qc_id } => {
+            ProposalValidationError::QCInvalidSignature { qc: qc_id }
+        },
+    })?;
 
-    for sign in qc.signatures() {
-        let message = vote_signing_service.create_message(qc.block_id(), &qc.decision());
-        if !sign.verify(message) {
-            return Err(ProposalValidationError::QCInvalidSignature { qc: *qc.id() }.into());
-        }
-    }
-
-    if committee_info.quorum_threshold() >
-        u32::try_from(qc.signatures().len()).map_err(|_| ProposalValidationError::QCConversionError)?
-    {
-        return Err(ProposalValidationError::QuorumWasNotReached { qc: *qc.id() }.into());
-    }
     Ok(())
 }
 
@@ -264,3 +328,105 @@ pub fn check_sidechain_id(candidate_block: &Block, config: &HotstuffConfig) -> R
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tari_common_types::types::PublicKey;
+    use tari_dan_common_types::{Epoch, NodeHeight, ShardGroup};
+    use tari_dan_storage::consensus_models::{BlockId, QuorumCertificate};
+
+    use super::*;
+
+    const JUSTIFY_TIMESTAMP: u64 = 1_000;
+    const NOW: u64 = 1_010;
+
+    fn create_block(timestamp: u64) -> Block {
+        let shard_group = ShardGroup::new(0, 63);
+        Block::create(
+            Network::LocalNet,
+            BlockId::zero(),
+            QuorumCertificate::genesis(Epoch(1), shard_group),
+            NodeHeight(1),
+            Epoch(1),
+            shard_group,
+            PublicKey::default(),
+            Default::default(),
+            FixedHash::zero(),
+            0,
+            Default::default(),
+            None,
+            timestamp,
+            0,
+            FixedHash::zero(),
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    fn constants() -> ConsensusConstants {
+        ConsensusConstants {
+            block_timestamp_median_window: 11,
+            block_timestamp_tolerance: Duration::from_secs(2),
+            max_block_timestamp_future_drift: Duration::from_secs(30),
+            ..ConsensusConstants::devnet()
+        }
+    }
+
+    #[test]
+    fn it_accepts_timestamps_within_bounds() {
+        let justify = create_block(JUSTIFY_TIMESTAMP);
+        for timestamp in [JUSTIFY_TIMESTAMP - 2, JUSTIFY_TIMESTAMP, NOW + 30] {
+            check_timestamp(
+                &create_block(timestamp),
+                &justify,
+                JUSTIFY_TIMESTAMP - 2,
+                NOW,
+                &constants(),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn it_rejects_timestamps_before_the_justify_block() {
+        let justify = create_block(JUSTIFY_TIMESTAMP);
+        let err = check_timestamp(&create_block(JUSTIFY_TIMESTAMP - 3), &justify, 0, NOW, &constants()).unwrap_err();
+        assert!(matches!(err, ProposalValidationError::BlockTimestampBeforeJustify {
+            timestamp: 997,
+            justify_timestamp: JUSTIFY_TIMESTAMP,
+            ..
+        }));
+    }
+
+    #[test]
+    fn it_rejects_timestamps_before_the_median() {
+        let justify = create_block(JUSTIFY_TIMESTAMP);
+        let candidate = create_block(JUSTIFY_TIMESTAMP);
+        let err = check_timestamp(&candidate, &justify, JUSTIFY_TIMESTAMP + 1, NOW, &constants()).unwrap_err();
+        assert!(matches!(err, ProposalValidationError::BlockTimestampBeforeMedian {
+            timestamp: JUSTIFY_TIMESTAMP,
+            median_timestamp: 1_001,
+            ..
+        }));
+
+        // The median is not checked when the window is disabled
+        let constants = ConsensusConstants {
+            block_timestamp_median_window: 0,
+            ..constants()
+        };
+        check_timestamp(&candidate, &justify, JUSTIFY_TIMESTAMP + 1, NOW, &constants).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_timestamps_too_far_in_the_future() {
+        let justify = create_block(JUSTIFY_TIMESTAMP);
+        let err = check_timestamp(&create_block(NOW + 31), &justify, 0, NOW, &constants()).unwrap_err();
+        assert!(matches!(err, ProposalValidationError::BlockTimestampTooFarInFuture {
+            timestamp: 1_041,
+            max_timestamp: 1_040,
+            ..
+        }));
+    }
+}
@@ -23,7 +23,9 @@
 use std::time::Duration;
 
 use tari_common::configuration::Network;
+use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{Epoch, NumPreshards};
+use tari_dan_storage::consensus_models::TransactionOrdering;
 
 #[derive(Clone, Debug)]
 pub struct ConsensusConstants {
@@ -43,9 +45,70 @@ pub struct ConsensusConstants {
     pub missed_proposal_recovery_threshold: u64,
     /// The maximum number of commands that a block may contain.
     pub max_block_size: usize,
+    /// The maximum size in bytes of a transaction, measured as the encoded size of the signed transaction. Larger
+    /// transactions are rejected by the mempool and aborted if proposed.
+    pub max_transaction_bytes: usize,
+    /// The maximum number of instructions, including fee instructions, that a transaction may contain. Transactions
+    /// with more instructions are rejected by the mempool and aborted if proposed.
+    pub max_instructions: usize,
     /// The value that fees are divided by to determine the amount of fees to burn. 0 means no fees are burned.
     pub fee_exhaust_divisor: u64,
+    /// The fee charged per byte of state for each epoch that a component, vault or key-value store entry is kept
+    /// alive. Substates whose rent has expired cannot be used as inputs until their rent is paid. 0 disables rent.
+    pub per_byte_rent_cost_per_epoch: u64,
+    /// The number of epochs of rent that a transaction pays for each substate that it creates.
+    pub initial_rent_epochs: u64,
     pub epochs_per_era: Epoch,
+    /// The number of epochs for which a transaction idempotency key is reserved for its signer. A second transaction
+    /// from the same signer with the same key is rejected within this window.
+    pub idempotency_key_window: Epoch,
+    /// The number of blocks at the start of an epoch in which the committee layout changed during which transactions
+    /// involving foreign shard groups are not prepared. This gives validators time to sync the shards that they gained
+    /// before cross-shard transactions resume. Validators do not vote for a block that prepares one of these
+    /// transactions within this window. 0 disables this.
+    ///
+    /// Cross-shard transactions that were already prepared before the layout changed and involve a shard group that no
+    /// longer exists can never receive its pledges, so they are aborted in the new epoch regardless of this setting.
+    pub committee_layout_switch_blocks: u64,
+    /// Proposals only carry transaction ids and validators request any transactions that they are missing from the
+    /// proposer. If the percentage of proposed transactions requested by peers reaches this threshold, the proposer
+    /// attaches the full transactions to its proposals instead. 0 disables this.
+    pub full_proposal_miss_rate_threshold: u64,
+    /// When transactions are executed relative to their sequencing in a block.
+    pub execution_mode: ExecutionMode,
+    /// The order in which a proposer selects new transactions from its pool. When a block is full, this decides which
+    /// transactions are proposed and, if transactions conflict, which of them acquires its locks first.
+    pub transaction_ordering: TransactionOrdering,
+    /// The number of blocks that a prepared or accepted transaction waits for the foreign proposal of another
+    /// involved shard group before requesting it directly from that shard group's committee, and between repeated
+    /// requests. 0 disables this.
+    pub foreign_proposal_request_interval: u64,
+    /// The number of blocks that a locally prepared transaction may hold its substate locks while waiting for other
+    /// shard groups to prepare it. Once the lease expires, the transaction is aborted and its locks are released.
+    /// 0 disables this.
+    pub substate_lock_lease_blocks: u64,
+    /// The number of blocks that a locally prepared transaction must have held its substate locks before it is
+    /// considered deadlocked with a conflicting transaction that a foreign shard group has prepared. The transaction
+    /// with the lowest id is then aborted. 0 disables deadlock detection.
+    pub cross_shard_deadlock_blocks: u64,
+    /// The number of most recent blocks whose median timestamp a proposed block timestamp may not precede. 0 disables
+    /// the median check.
+    pub block_timestamp_median_window: usize,
+    /// The amount of time that a proposed block timestamp may be earlier than the timestamp of the block it justifies.
+    pub block_timestamp_tolerance: Duration,
+    /// The amount of time that a proposed block timestamp may be ahead of the local clock.
+    pub max_block_timestamp_future_drift: Duration,
+    /// The number of blocks that a valid quorum certificate received from the local committee may be ahead of the
+    /// local view before the validator stops consensus, syncs and resumes from the synced height. 0 disables this.
+    pub fallen_behind_sync_threshold: u64,
+    /// If true, the voting power of each committee member is proportional to the stake locked by its base layer
+    /// registration and quorum certificates require more than two thirds of the committee's voting power. If false,
+    /// each member has one vote.
+    pub stake_weighted_voting: bool,
+    /// The hash of the genesis file that the network was created with, or None if the network uses the built-in
+    /// genesis state. Genesis blocks commit to this hash, so validators using a different genesis file cannot build
+    /// on the network's blocks.
+    pub genesis_hash: Option<FixedHash>,
 }
 
 impl ConsensusConstants {
@@ -61,8 +124,26 @@ impl ConsensusConstants {
             missed_proposal_evict_threshold: 5,
             missed_proposal_recovery_threshold: 5,
             max_block_size: 500,
+            max_transaction_bytes: 256 * 1024,
+            max_instructions: 256,
             fee_exhaust_divisor: 20, // 5%
+            per_byte_rent_cost_per_epoch: 0,
+            initial_rent_epochs: 100,
             epochs_per_era: Epoch(10),
+            idempotency_key_window: Epoch(10),
+            committee_layout_switch_blocks: 10,
+            full_proposal_miss_rate_threshold: 20,
+            execution_mode: ExecutionMode::Immediate,
+            transaction_ordering: TransactionOrdering::TransactionId,
+            foreign_proposal_request_interval: 3,
+            substate_lock_lease_blocks: 200,
+            cross_shard_deadlock_blocks: 10,
+            block_timestamp_median_window: 11,
+            block_timestamp_tolerance: Duration::from_secs(2),
+            max_block_timestamp_future_drift: Duration::from_secs(30),
+            fallen_behind_sync_threshold: 20,
+            stake_weighted_voting: false,
+            genesis_hash: None,
         }
     }
 }
@@ -77,3 +158,21 @@ impl From<Network> for ConsensusConstants {
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Transactions whose inputs are all local are executed when they are first proposed.
+    #[default]
+    Immediate,
+    /// All transactions are sequenced first: their local inputs are locked and pledged when they are first proposed,
+    /// and they are only executed, using the pledged inputs, once the block that sequenced them is committed. No
+    /// execution is wasted on proposals that are orphaned, at the cost of additional consensus rounds for transactions
+    /// that only involve the local shard group.
+    Deferred,
+}
+
+impl ExecutionMode {
+    pub fn is_deferred(&self) -> bool {
+        matches!(self, Self::Deferred)
+    }
+}
@@ -43,7 +43,7 @@ use tari_dan_storage::{
     StorageError,
 };
 use tari_engine_types::substate::SubstateId;
-use tari_transaction::TransactionId;
+use tari_transaction::{IdempotencyKey, TransactionId};
 
 use crate::tracing::TraceTimer;
 
@@ -63,7 +63,8 @@ pub struct BlockDecision {
     /// Contains newly-locked non-dummy blocks and the QC that justifies each block i.e. typically the parent block's
     /// QC
     pub locked_blocks: Vec<(Block, QuorumCertificate)>,
-    pub finalized_transactions: Vec<Vec<TransactionPoolRecord>>,
+    /// Contains newly-committed blocks and the transactions that were finalized in each block
+    pub committed_blocks: Vec<(Block, Vec<TransactionPoolRecord>)>,
     pub end_of_epoch: Option<Epoch>,
     pub high_qc: HighQc,
 }
@@ -81,6 +82,7 @@ pub struct ProposedBlockChangeSet {
     block_diff: Vec<SubstateChange>,
     state_tree_diffs: IndexMap<Shard, VersionedStateHashTreeDiff>,
     substate_locks: IndexMap<SubstateId, Vec<SubstateLock>>,
+    idempotency_keys: IndexMap<IdempotencyKey, TransactionId>,
     transaction_changes: IndexMap<TransactionId, TransactionChangeSet>,
     proposed_foreign_proposals: Vec<BlockId>,
     proposed_utxo_mints: Vec<SubstateId>,
@@ -96,6 +98,7 @@ impl ProposedBlockChangeSet {
             quorum_decision: None,
             block_diff: Vec::new(),
             substate_locks: IndexMap::new(),
+            idempotency_keys: IndexMap::new(),
             transaction_changes: IndexMap::new(),
             state_tree_diffs: IndexMap::new(),
             proposed_foreign_proposals: Vec::new(),
@@ -160,6 +163,7 @@ impl ProposedBlockChangeSet {
             );
             self.substate_locks.shrink_to(MEM_MAX_SUBSTATE_LOCK_SIZE);
         }
+        self.idempotency_keys.clear();
         self.proposed_foreign_proposals.clear();
         if self.proposed_foreign_proposals.capacity() > MEM_MAX_PROPOSED_FOREIGN_PROPOSALS_SIZE {
             debug!(
@@ -208,6 +212,11 @@ impl ProposedBlockChangeSet {
         self
     }
 
+    pub fn set_idempotency_keys(&mut self, idempotency_keys: IndexMap<IdempotencyKey, TransactionId>) -> &mut Self {
+        self.idempotency_keys = idempotency_keys;
+        self
+    }
+
     pub fn set_foreign_proposal_proposed_in(&mut self, foreign_proposal_block_id: BlockId) -> &mut Self {
         self.proposed_foreign_proposals.push(foreign_proposal_block_id);
         self
@@ -355,6 +364,9 @@ impl ProposedBlockChangeSet {
         // Save locks
         SubstateRecord::lock_all(tx, &self.block.block_id, &self.substate_locks)?;
 
+        // Save idempotency keys reserved by transactions sequenced in this block
+        tx.transaction_idempotency_keys_insert_all(&self.block.block_id, self.block.epoch, &self.idempotency_keys)?;
+
         for (transaction_id, change) in &self.transaction_changes {
             // Save any transaction executions for the block
             if let Some(ref execution) = change.execution {
@@ -12,6 +12,8 @@ use tari_common::configuration::Network;
 use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
+    hashing::{block_leader_seed_hasher, leader_seed_hasher, leader_vrf_input_hasher},
+    optional::Optional,
     shard::Shard,
     Epoch,
     NodeAddressable,
@@ -24,10 +26,13 @@ use tari_dan_storage::{
         BlockHeader,
         BlockId,
         EpochCheckpoint,
+        ExecutionContext,
+        HighQc,
         LeafBlock,
         PendingShardStateTreeDiff,
         QuorumCertificate,
         SubstateChange,
+        TransactionPoolRecord,
         ValidatorConsensusStats,
         VersionedStateHashTreeDiff,
     },
@@ -36,9 +41,11 @@ use tari_dan_storage::{
     StorageError,
 };
 use tari_engine_types::substate::SubstateDiff;
-use tari_state_tree::{Hash, JellyfishMerkleTree, StateTreeError};
+use tari_epoch_manager::EpochManagerReader;
+use tari_state_tree::{compute_merkle_root_for_hashes, Hash, JellyfishMerkleTree, StateTreeError};
 
 use crate::{
+    consensus_constants::ConsensusConstants,
     hotstuff::{
         substate_store::{ShardScopedTreeStoreReader, ShardedStateTree},
         HotStuffError,
@@ -60,6 +67,7 @@ pub fn calculate_last_dummy_block<TAddr: NodeAddressable, TLeaderStrategy: Leade
     qc: &QuorumCertificate,
     parent_merkle_root: FixedHash,
     leader_strategy: &TLeaderStrategy,
+    leader_seed: &FixedHash,
     local_committee: &Committee<TAddr>,
     parent_timestamp: u64,
     parent_base_layer_block_height: u64,
@@ -76,6 +84,7 @@ pub fn calculate_last_dummy_block<TAddr: NodeAddressable, TLeaderStrategy: Leade
         qc,
         parent_merkle_root,
         leader_strategy,
+        leader_seed,
         local_committee,
         parent_timestamp,
         parent_base_layer_block_height,
@@ -100,6 +109,7 @@ fn calculate_dummy_blocks<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrateg
     expected_parent_block_id: &BlockId,
     parent_merkle_root: FixedHash,
     leader_strategy: &TLeaderStrategy,
+    leader_seed: &FixedHash,
     local_committee: &Committee<TAddr>,
     parent_timestamp: u64,
     parent_base_layer_block_height: u64,
@@ -116,6 +126,7 @@ fn calculate_dummy_blocks<TAddr: NodeAddressable, TLeaderStrategy: LeaderStrateg
         qc,
         parent_merkle_root,
         leader_strategy,
+        leader_seed,
         local_committee,
         parent_timestamp,
         parent_base_layer_block_height,
@@ -139,6 +150,7 @@ pub fn calculate_dummy_blocks_from_justify<TAddr: NodeAddressable, TLeaderStrate
     candidate_block: &Block,
     justify_block: &Block,
     leader_strategy: &TLeaderStrategy,
+    leader_seed: &FixedHash,
     local_committee: &Committee<TAddr>,
 ) -> Vec<Block> {
     calculate_dummy_blocks(
@@ -152,6 +164,7 @@ pub fn calculate_dummy_blocks_from_justify<TAddr: NodeAddressable, TLeaderStrate
         candidate_block.parent(),
         *justify_block.state_merkle_root(),
         leader_strategy,
+        leader_seed,
         local_committee,
         justify_block.timestamp(),
         justify_block.base_layer_block_height(),
@@ -169,6 +182,7 @@ fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
     qc: &QuorumCertificate,
     parent_merkle_root: FixedHash,
     leader_strategy: &TLeaderStrategy,
+    leader_seed: &FixedHash,
     local_committee: &Committee<TAddr>,
     parent_timestamp: u64,
     parent_base_layer_block_height: u64,
@@ -201,7 +215,7 @@ fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
         if current_height == new_height {
             break;
         }
-        let (_, leader) = leader_strategy.get_leader(local_committee, current_height);
+        let (_, leader) = leader_strategy.get_leader(local_committee, leader_seed, current_height);
         let dummy_header = BlockHeader::dummy_block(
             network,
             parent_block_id,
@@ -229,6 +243,149 @@ fn with_dummy_blocks<TAddr, TLeaderStrategy, F>(
     }
 }
 
+/// Calculates the input to the VRF proof that the proposer includes in a block at the given height
+pub fn calculate_leader_vrf_input(
+    network: Network,
+    epoch: Epoch,
+    shard_group: ShardGroup,
+    height: NodeHeight,
+    leader_seed: &FixedHash,
+) -> FixedHash {
+    leader_vrf_input_hasher()
+        .chain(&network)
+        .chain(&epoch)
+        .chain(&shard_group)
+        .chain(&height)
+        .chain(leader_seed)
+        .result()
+}
+
+/// Calculates the seed used to select leaders in the given epoch from the VRF outputs of the last blocks committed in
+/// the previous epoch by the shard groups that the local shard group was formed from. The proposers of those blocks
+/// cannot influence their VRF outputs, which chain the seeds of the previous epoch. The outputs are ordered by shard
+/// group so that members that were in different shard groups in the previous epoch derive the same seed.
+pub fn calculate_epoch_leader_seed(epoch: Epoch, previous_checkpoints: &[EpochCheckpoint]) -> FixedHash {
+    let mut checkpoints = previous_checkpoints.iter().collect::<Vec<_>>();
+    checkpoints.sort_by_key(|checkpoint| checkpoint.block().shard_group());
+    let mut vrf_outputs = checkpoints
+        .into_iter()
+        .map(|checkpoint| {
+            checkpoint
+                .block()
+                .vrf_proof()
+                .and_then(Result::ok)
+                .map(|proof| proof.output())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    if vrf_outputs.is_empty() {
+        // There is no previous epoch in the first epoch
+        vrf_outputs.push(FixedHash::default());
+    }
+    vrf_outputs
+        .iter()
+        .fold(leader_seed_hasher().chain(&epoch), |hasher, vrf_output| {
+            hasher.chain(vrf_output)
+        })
+        .result()
+}
+
+/// Loads the checkpoints that the given shard groups created at the end of the epoch. Checkpoints of other shard groups
+/// are only available once they have been validated and their state has been handed over to us, missing checkpoints are
+/// omitted.
+pub fn get_source_checkpoints<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    epoch: Epoch,
+    source_shard_groups: &[ShardGroup],
+) -> Result<Vec<EpochCheckpoint>, StorageError> {
+    let mut checkpoints = Vec::with_capacity(source_shard_groups.len());
+    for shard_group in source_shard_groups {
+        if let Some(checkpoint) = EpochCheckpoint::get_for_shard_group(tx, epoch, *shard_group).optional()? {
+            checkpoints.push(checkpoint);
+        }
+    }
+    Ok(checkpoints)
+}
+
+/// Calculates the state merkle root of the genesis block for the shard group from the checkpoints of the shard groups
+/// that it was formed from. The root of each shard is taken from the checkpoint of the shard group that contained the
+/// shard in the previous epoch. Returns an error if the state of a shard has not been handed over.
+pub fn calculate_genesis_state_merkle_root(
+    epoch: Epoch,
+    shard_group: ShardGroup,
+    previous_checkpoints: &[EpochCheckpoint],
+) -> Result<Hash, HotStuffError> {
+    // An unchanged shard group continues from the state that was certified in the epoch-end block
+    if let [checkpoint] = previous_checkpoints {
+        if checkpoint.block().shard_group() == shard_group {
+            return Ok(*checkpoint.block().state_merkle_root());
+        }
+    }
+
+    let hashes = shard_group
+        .shard_iter()
+        .map(|shard| {
+            previous_checkpoints
+                .iter()
+                .find(|checkpoint| checkpoint.block().shard_group().contains(&shard))
+                .map(|checkpoint| checkpoint.get_shard_root(shard))
+                .ok_or(HotStuffError::StateHandoverIncomplete {
+                    epoch,
+                    shard_group,
+                    shard,
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let root = compute_merkle_root_for_hashes(hashes.into_iter().peekable())?;
+    Ok(root)
+}
+
+/// Calculates the seed used to select the leaders of the heights that follow the given block, which is the block that
+/// the next proposal justifies. The seed is derived from the VRF output of the block's proposer, so the leader for a
+/// height is not known until the block before it has been proposed. The VRF input of each block includes its own seed,
+/// so every VRF output in the chain depends on all of the outputs before it.
+pub fn calculate_block_leader_seed(epoch_leader_seed: &FixedHash, block: &Block) -> FixedHash {
+    // The genesis block has no proposer, so the heights that follow it are selected by the epoch seed alone
+    let vrf_output = block
+        .vrf_proof()
+        .and_then(Result::ok)
+        .map(|proof| proof.output())
+        .unwrap_or_default();
+    block_leader_seed_hasher()
+        .chain(epoch_leader_seed)
+        .chain(&vrf_output)
+        .result()
+}
+
+/// Loads the seed used to select the leaders of the heights in the epoch that follow the given block. Dummy blocks have
+/// no proposer, so the seed of the block that they extend is used.
+pub fn get_block_leader_seed<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    epoch: Epoch,
+    block: &Block,
+) -> Result<FixedHash, StorageError> {
+    if block.is_dummy() {
+        let justify_block = block.justify().get_block(tx)?;
+        return get_block_leader_seed(tx, epoch, &justify_block);
+    }
+    // The first blocks of an epoch are justified by the zero block, which is not part of the epoch, so the epoch is
+    // passed in rather than taken from the block
+    let epoch_leader_seed = Block::get_leader_seed(tx, epoch)?;
+    Ok(calculate_block_leader_seed(&epoch_leader_seed, block))
+}
+
+/// Loads the seed used to select the leader of the next proposal and of any dummy blocks before it. The next proposal
+/// is justified by the high QC, which is the block that proposal validation selects the leader with. The leaf block is
+/// not used because a proposal that reached only part of the committee leaves validators with different leaf blocks,
+/// which would select different leaders.
+pub fn get_high_qc_leader_seed<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    epoch: Epoch,
+) -> Result<FixedHash, StorageError> {
+    let high_qc_block = HighQc::get(tx, epoch)?.get_quorum_certificate(tx)?.get_block(tx)?;
+    get_block_leader_seed(tx, epoch, &high_qc_block)
+}
+
 pub fn calculate_state_merkle_root<'a, TTx: StateStoreReadTransaction, I: IntoIterator<Item = &'a SubstateChange>>(
     tx: &TTx,
     shard_group: ShardGroup,
@@ -266,7 +423,9 @@ where
     }
 
     let commit_block = blocks.pop().unwrap();
-    let qcs = blocks.into_iter().map(|b| b.into_justify()).collect();
+    let mut qcs = blocks.into_iter().map(|b| b.into_justify()).collect::<Vec<_>>();
+    // Include the QC for the commit block so that other shard groups can verify the checkpoint during state handover
+    qcs.push(QuorumCertificate::get_by_block_id(&**tx, commit_block.id())?);
 
     // Fetch the state roots of the shards in the shard group
     let mut shard_roots = IndexMap::with_capacity(shard_group.len());
@@ -290,6 +449,60 @@ where
     Ok(checkpoint)
 }
 
+/// Returns the median timestamp of the given block and up to `window - 1` of its ancestors.
+pub(crate) fn get_median_block_timestamp<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    block: &Block,
+    window: usize,
+) -> Result<u64, StorageError> {
+    let mut timestamps = block
+        .get_parent_chain(tx, window)?
+        .iter()
+        .map(|b| b.timestamp())
+        .collect::<Vec<_>>();
+    if timestamps.is_empty() {
+        return Ok(block.timestamp());
+    }
+    timestamps.sort_unstable();
+    Ok(timestamps[timestamps.len() / 2])
+}
+
+/// Returns true if transactions involving foreign shard groups may not be prepared in a block at the given height.
+/// Validators sync the shards that they gained at the start of an epoch in which the committee layout changed, so
+/// these transactions are deferred for the first `committee_layout_switch_blocks` blocks of the epoch. The proposer
+/// does not propose them and validators do not vote for a block that prepares them.
+pub(crate) async fn is_cross_shard_preparation_deferred<TEpochManager: EpochManagerReader>(
+    epoch_manager: &TEpochManager,
+    consensus_constants: &ConsensusConstants,
+    epoch: Epoch,
+    height: NodeHeight,
+) -> Result<bool, HotStuffError> {
+    if height.as_u64() > consensus_constants.committee_layout_switch_blocks {
+        return Ok(false);
+    }
+    Ok(epoch_manager.has_committee_layout_changed(epoch).await?)
+}
+
+/// Returns the context that a prepared transaction is executed with. This is the context recorded in the evidence by
+/// the first shard group with inputs, so that every involved shard group executes the transaction with the same
+/// values. The context of the current block is only used if no context was recorded.
+pub(crate) fn get_execution_context(
+    tx_rec: &TransactionPoolRecord,
+    block_execution_context: &ExecutionContext,
+) -> ExecutionContext {
+    match tx_rec.evidence().execution_context() {
+        Some(execution_context) => *execution_context,
+        None => {
+            warn!(
+                target: LOG_TARGET,
+                "No execution context recorded in the evidence for transaction {}. Using the context of the current block",
+                tx_rec.transaction_id(),
+            );
+            *block_execution_context
+        },
+    }
+}
+
 pub(crate) fn filter_diff_for_committee(committee_info: &CommitteeInfo, diff: &SubstateDiff) -> SubstateDiff {
     let mut filtered_diff = SubstateDiff::new();
     filtered_diff
@@ -315,18 +528,20 @@ pub(crate) fn get_next_block_height_and_leader<
     tx: &TTx,
     committee: &'a Committee<TAddr>,
     leader_strategy: &TLeaderStrategy,
+    leader_seed: &FixedHash,
     block_id: &BlockId,
     height: NodeHeight,
 ) -> Result<(NodeHeight, &'a TAddr, usize), HotStuffError> {
     let mut num_skipped = 0;
     let mut next_height = height;
-    let (mut leader_addr, mut leader_pk) = leader_strategy.get_leader_for_next_height(committee, next_height);
+    let (mut leader_addr, mut leader_pk) =
+        leader_strategy.get_leader_for_next_height(committee, leader_seed, next_height);
 
     while ValidatorConsensusStats::is_node_suspended(tx, block_id, leader_pk)? {
         debug!(target: LOG_TARGET, "Validator {} suspended for next height {}. Checking next validator", leader_addr, next_height + NodeHeight(1));
         next_height += NodeHeight(1);
         num_skipped += 1;
-        let (addr, pk) = leader_strategy.get_leader_for_next_height(committee, next_height);
+        let (addr, pk) = leader_strategy.get_leader_for_next_height(committee, leader_seed, next_height);
         leader_addr = addr;
         leader_pk = pk;
     }
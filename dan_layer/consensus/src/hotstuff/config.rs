@@ -11,4 +11,7 @@ pub struct HotstuffConfig {
     pub network: Network,
     pub sidechain_id: Option<RistrettoPublicKey>,
     pub consensus_constants: ConsensusConstants,
+    /// The number of state tree versions to keep for each shard. Older tree nodes are pruned once blocks are
+    /// committed. 0 keeps every historical version.
+    pub state_tree_retained_versions: u64,
 }
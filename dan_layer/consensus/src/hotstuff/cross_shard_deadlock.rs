@@ -0,0 +1,79 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashSet;
+
+use tari_dan_common_types::committee::CommitteeInfo;
+use tari_dan_storage::{
+    consensus_models::{TransactionPoolRecord, TransactionRecord},
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_transaction::TransactionId;
+
+/// Returns the id of a transaction that is deadlocked with the given locally prepared transaction, if any.
+///
+/// Two multi-shard transactions are deadlocked when each shard group has pledged (locked) the substates of one
+/// transaction while the other transaction waits for those substates. Locally, this means that `tx_rec` holds locks on
+/// substates that another transaction requires, so the other transaction cannot be prepared, while a foreign shard
+/// group that `tx_rec` is waiting for has already prepared the other transaction. Every involved shard group sees the
+/// same pair of transactions, so the transaction with the lowest id is chosen to be aborted: this function only
+/// returns a conflicting transaction if `tx_rec` has the lower id. The abort is propagated to the foreign shard group
+/// in the usual way i.e. by the foreign proposal containing the ABORT decision.
+///
+/// `new_transactions` are the transactions in the pool that have not yet been prepared by the local shard group. Read
+/// locks are not considered conflicting as the requested lock type of the other transaction is not known until it is
+/// executed.
+pub fn find_deadlocked_transaction<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    tx_rec: &TransactionPoolRecord,
+    new_transactions: &[TransactionPoolRecord],
+    local_committee_info: &CommitteeInfo,
+) -> Result<Option<TransactionId>, StorageError> {
+    // Foreign shard groups that have not yet prepared the transaction
+    let awaiting_shard_groups = tx_rec
+        .evidence()
+        .iter()
+        .filter(|(sg, evidence)| **sg != local_committee_info.shard_group() && !evidence.is_prepare_justified())
+        .map(|(sg, _)| *sg)
+        .collect::<HashSet<_>>();
+    if awaiting_shard_groups.is_empty() {
+        return Ok(None);
+    }
+
+    let locked_substates = tx
+        .substate_locks_get_locked_substates_for_transaction(tx_rec.transaction_id())?
+        .into_iter()
+        .filter(|lock| !lock.lock.substate_lock().is_read())
+        .map(|lock| lock.substate_id)
+        .collect::<HashSet<_>>();
+    if locked_substates.is_empty() {
+        return Ok(None);
+    }
+
+    for other in new_transactions {
+        // Only the transaction with the lowest id is aborted
+        if other.transaction_id() <= tx_rec.transaction_id() || !other.current_stage().is_new() {
+            continue;
+        }
+
+        let is_prepared_by_awaited_shard_group = other
+            .evidence()
+            .iter()
+            .any(|(sg, evidence)| awaiting_shard_groups.contains(sg) && evidence.is_prepare_justified());
+        if !is_prepared_by_awaited_shard_group {
+            continue;
+        }
+
+        let transaction = TransactionRecord::get(tx, other.transaction_id())?;
+        if transaction
+            .transaction()
+            .all_inputs_substate_ids_iter()
+            .any(|id| locked_substates.contains(id))
+        {
+            return Ok(Some(*other.transaction_id()));
+        }
+    }
+
+    Ok(None)
+}
@@ -3,7 +3,7 @@
 
 use tari_common_types::types::FixedHash;
 use tari_crypto::ristretto::RistrettoPublicKey;
-use tari_dan_common_types::{Epoch, NodeHeight, VersionedSubstateIdError};
+use tari_dan_common_types::{shard::Shard, Epoch, NodeHeight, NumPreshards, ShardGroup, VersionedSubstateIdError};
 use tari_dan_storage::{
     consensus_models::{BlockError, BlockId, LeafBlock, LockedBlock, QcId, TransactionPoolError},
     StorageError,
@@ -36,6 +36,8 @@ pub enum HotStuffError {
     EpochNotActive { epoch: Epoch, details: String },
     #[error("Not registered for current epoch {epoch}")]
     NotRegisteredForCurrentEpoch { epoch: Epoch },
+    #[error("Committee layout changed in epoch {epoch}. This validator gained {num_gained_shards} shard(s)")]
+    CommitteeLayoutChanged { epoch: Epoch, num_gained_shards: usize },
     #[error("Received vote from non-committee member. Epoch: {epoch}, Sender: {sender}, {context}")]
     ReceivedVoteFromNonCommitteeMember {
         epoch: Epoch,
@@ -106,6 +108,33 @@ pub enum HotStuffError {
     },
     #[error("Block building error: {0}")]
     BlockBuildingError(#[from] BlockError),
+    #[error(
+        "The genesis block for epoch {epoch} was created with {genesis_num_preshards} preshards but this node is \
+         configured with {configured_num_preshards}"
+    )]
+    NumPreshardsMismatch {
+        epoch: Epoch,
+        genesis_num_preshards: NumPreshards,
+        configured_num_preshards: NumPreshards,
+    },
+    #[error(
+        "The genesis block for epoch {epoch} was created from genesis file {genesis_hash:?} but the node is \
+         configured with genesis file {configured_genesis_hash:?}"
+    )]
+    GenesisHashMismatch {
+        epoch: Epoch,
+        genesis_hash: Option<FixedHash>,
+        configured_genesis_hash: Option<FixedHash>,
+    },
+    #[error(
+        "Cannot create the genesis block for {shard_group} in epoch {epoch}: the state of {shard} has not been handed \
+         over"
+    )]
+    StateHandoverIncomplete {
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        shard: Shard,
+    },
 }
 
 impl From<EpochManagerError> for HotStuffError {
@@ -154,6 +183,26 @@ pub enum ProposalValidationError {
         block_id: BlockId,
         details: String,
     },
+    #[error(
+        "Block {block_id} timestamp {timestamp} is earlier than its justified block timestamp {justify_timestamp}"
+    )]
+    BlockTimestampBeforeJustify {
+        block_id: BlockId,
+        timestamp: u64,
+        justify_timestamp: u64,
+    },
+    #[error("Block {block_id} timestamp {timestamp} is earlier than the median timestamp {median_timestamp}")]
+    BlockTimestampBeforeMedian {
+        block_id: BlockId,
+        timestamp: u64,
+        median_timestamp: u64,
+    },
+    #[error("Block {block_id} timestamp {timestamp} is later than the maximum allowed timestamp {max_timestamp}")]
+    BlockTimestampTooFarInFuture {
+        block_id: BlockId,
+        timestamp: u64,
+        max_timestamp: u64,
+    },
     #[error("Candidate block {candidate_block_height} is not higher than justify {justify_block_height}")]
     CandidateBlockNotHigherThanJustify {
         justify_block_height: NodeHeight,
@@ -194,6 +243,14 @@ pub enum ProposalValidationError {
     MissingSignature { block_id: BlockId, height: NodeHeight },
     #[error("Proposed block {block_id} {height} has invalid signature")]
     InvalidSignature { block_id: BlockId, height: NodeHeight },
+    #[error("Proposed block {block_id} {height} does not have a VRF proof")]
+    MissingVrfProof { block_id: BlockId, height: NodeHeight },
+    #[error("Proposed block {block_id} {height} has an invalid VRF proof: {details}")]
+    InvalidVrfProof {
+        block_id: BlockId,
+        height: NodeHeight,
+        details: String,
+    },
     #[error("QC has invalid signature: {qc}")]
     QCInvalidSignature { qc: QcId },
     #[error("Quorum was not reached: {qc}")]
@@ -2,7 +2,7 @@
 //    SPDX-License-Identifier: BSD-3-Clause
 
 use tari_dan_common_types::{Epoch, NodeHeight};
-use tari_dan_storage::consensus_models::{BlockId, LeafBlock};
+use tari_dan_storage::consensus_models::{BlockId, DoubleVoteEvidence, LeafBlock};
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum HotstuffEvent {
@@ -24,4 +24,6 @@ pub enum HotstuffEvent {
     },
     #[error("Parked block {block} is ready")]
     ParkedBlockReady { block: LeafBlock },
+    #[error("Double vote detected: {evidence}")]
+    DoubleVoteDetected { evidence: Box<DoubleVoteEvidence> },
 }
@@ -0,0 +1,176 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::{HashMap, HashSet};
+
+use log::*;
+use tari_dan_common_types::{committee::CommitteeInfo, Epoch, NodeHeight, ShardGroup};
+use tari_dan_storage::consensus_models::{TransactionPool, TransactionPoolRecord, TransactionPoolStage};
+use tari_epoch_manager::EpochManagerReader;
+use tari_transaction::TransactionId;
+
+use crate::{
+    hotstuff::{error::HotStuffError, HotstuffConfig},
+    messages::{ForeignProposalRequest, HotstuffMessage},
+    traits::{ConsensusSpec, OutboundMessaging},
+};
+
+const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::foreign_proposal_prefetcher";
+
+/// The maximum number of transactions that may be included in a single ForeignProposalRequest
+pub const MAX_FOREIGN_PROPOSAL_REQUEST_TRANSACTIONS: usize = 1000;
+
+/// Requests foreign proposals directly from foreign committees for transactions that the local shard group has
+/// prepared or accepted, but for which the evidence from one or more foreign shard groups is still outstanding. This
+/// allows a committee that missed the foreign proposal broadcast to make progress without waiting for it to be
+/// gossiped again. Received foreign proposals are stored and proposed in the same way as broadcast ones.
+pub struct ForeignProposalPrefetcher<TConsensusSpec: ConsensusSpec> {
+    config: HotstuffConfig,
+    store: TConsensusSpec::StateStore,
+    epoch_manager: TConsensusSpec::EpochManager,
+    outbound_messaging: TConsensusSpec::OutboundMessaging,
+    transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
+    /// The height at which each waiting transaction was first seen or last requested
+    last_requested: HashMap<TransactionId, NodeHeight>,
+}
+
+impl<TConsensusSpec> ForeignProposalPrefetcher<TConsensusSpec>
+where TConsensusSpec: ConsensusSpec
+{
+    pub fn new(
+        config: HotstuffConfig,
+        store: TConsensusSpec::StateStore,
+        epoch_manager: TConsensusSpec::EpochManager,
+        outbound_messaging: TConsensusSpec::OutboundMessaging,
+        transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
+    ) -> Self {
+        Self {
+            config,
+            store,
+            epoch_manager,
+            outbound_messaging,
+            transaction_pool,
+            last_requested: HashMap::new(),
+        }
+    }
+
+    pub async fn on_height_changed(
+        &mut self,
+        epoch: Epoch,
+        current_height: NodeHeight,
+        local_committee_info: &CommitteeInfo,
+    ) -> Result<(), HotStuffError> {
+        let interval = NodeHeight(self.config.consensus_constants.foreign_proposal_request_interval);
+        if interval.is_zero() {
+            return Ok(());
+        }
+
+        let pool = self.store.with_read_tx(|tx| self.transaction_pool.get_all(tx))?;
+
+        let mut requests = HashMap::<ShardGroup, HashSet<TransactionId>>::new();
+        let mut waiting = HashSet::with_capacity(pool.len());
+        for pool_tx in &pool {
+            let shard_groups = get_awaiting_foreign_shard_groups(pool_tx, local_committee_info);
+            if shard_groups.is_empty() {
+                continue;
+            }
+            let transaction_id = *pool_tx.transaction_id();
+            waiting.insert(transaction_id);
+
+            // Give the foreign committee time to broadcast the foreign proposal before requesting it
+            let last_requested = self.last_requested.entry(transaction_id).or_insert(current_height);
+            if current_height.saturating_sub(*last_requested) < interval {
+                continue;
+            }
+            *last_requested = current_height;
+
+            for shard_group in shard_groups {
+                requests.entry(shard_group).or_default().insert(transaction_id);
+            }
+        }
+
+        // Forget transactions that are no longer waiting for foreign evidence
+        self.last_requested.retain(|id, _| waiting.contains(id));
+
+        for (shard_group, transactions) in requests {
+            self.request_foreign_proposals(epoch, shard_group, transactions).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn request_foreign_proposals(
+        &mut self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        transactions: HashSet<TransactionId>,
+    ) -> Result<(), HotStuffError> {
+        let committees = self
+            .epoch_manager
+            .get_committees_by_shard_group(epoch, shard_group)
+            .await?;
+
+        // Request from f + 1 members so that at least one honest member responds
+        let addresses = committees
+            .values()
+            .flat_map(|committee| committee.select_n_random(committee.max_failures() + 1).cloned())
+            .collect::<Vec<_>>();
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 Requesting foreign proposals for {} transaction(s) from {} member(s) of shard group {}",
+            transactions.len(),
+            addresses.len(),
+            shard_group,
+        );
+
+        let transactions = transactions.into_iter().collect::<Vec<_>>();
+        for batch in transactions.chunks(MAX_FOREIGN_PROPOSAL_REQUEST_TRANSACTIONS) {
+            for address in &addresses {
+                self.outbound_messaging
+                    .send(
+                        address.clone(),
+                        HotstuffMessage::ForeignProposalRequest(ForeignProposalRequest {
+                            epoch,
+                            transactions: batch.iter().copied().collect(),
+                        }),
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the foreign shard groups that the transaction is waiting on. A transaction that has been LocalPrepared
+/// waits for the LocalPrepare of each foreign shard group with inputs, and a transaction that has been LocalAccepted
+/// waits for the LocalAccept of each foreign shard group.
+fn get_awaiting_foreign_shard_groups(
+    pool_tx: &TransactionPoolRecord,
+    local_committee_info: &CommitteeInfo,
+) -> Vec<ShardGroup> {
+    let local_shard_group = local_committee_info.shard_group();
+    let Some(local_evidence) = pool_tx.evidence().get(&local_shard_group) else {
+        return vec![];
+    };
+
+    let foreign_evidence = pool_tx
+        .evidence()
+        .iter()
+        .filter(|(sg, e)| **sg != local_shard_group && !e.substates().is_empty());
+
+    match pool_tx.committed_stage() {
+        TransactionPoolStage::LocalPrepared if local_evidence.prepare_qc().is_some() => foreign_evidence
+            // Output-only shard groups do not send a LocalPrepare
+            .filter(|(_, e)| !e.substates().values().all(|lock| lock.is_output()))
+            .filter(|(_, e)| !e.is_prepare_justified() && !e.is_accept_justified())
+            .map(|(sg, _)| *sg)
+            .collect(),
+        TransactionPoolStage::LocalAccepted if local_evidence.accept_qc().is_some() => foreign_evidence
+            .filter(|(_, e)| !e.is_accept_justified())
+            .map(|(sg, _)| *sg)
+            .collect(),
+        _ => vec![],
+    }
+}
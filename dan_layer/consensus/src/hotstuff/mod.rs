@@ -14,6 +14,7 @@ mod on_next_sync_view;
 mod on_propose;
 mod on_ready_to_vote_on_local_block;
 mod on_receive_foreign_proposal;
+mod on_receive_foreign_proposal_request;
 mod on_receive_local_proposal;
 mod on_receive_new_transaction;
 mod on_receive_new_view;
@@ -21,12 +22,17 @@ mod on_receive_request_missing_transactions;
 mod on_receive_vote;
 // mod on_sync_response;
 mod block_change_set;
+mod cross_shard_deadlock;
+mod foreign_proposal_prefetcher;
 mod foreign_proposal_processor;
 mod on_catch_up_sync;
 mod on_message_validate;
 mod pacemaker;
 mod pacemaker_handle;
+mod proposal_capacity;
+mod proposal_miss_rate;
 mod state_machine;
+mod state_recovery;
 pub mod substate_store;
 mod transaction_manager;
 mod vote_collector;
@@ -37,5 +43,7 @@ pub use config::HotstuffConfig;
 pub use current_view::*;
 pub use error::*;
 pub use event::*;
+pub use proposal_miss_rate::ProposalMissRate;
 pub use state_machine::*;
+pub use state_recovery::recover_consensus_state;
 pub use worker::*;
@@ -143,6 +143,7 @@ impl<TConsensusSpec: ConsensusSpec> OnSyncRequest<TConsensusSpec> {
                         HotstuffMessage::Proposal(ProposalMessage {
                             block,
                             foreign_proposals,
+                            transactions: vec![],
                         }),
                     )
                     .await
@@ -22,9 +22,13 @@ pub struct OnInboundMessage<TConsensusSpec: ConsensusSpec> {
 }
 
 impl<TConsensusSpec: ConsensusSpec> OnInboundMessage<TConsensusSpec> {
-    pub fn new(inbound_messaging: TConsensusSpec::InboundMessaging, hooks: TConsensusSpec::Hooks) -> Self {
+    pub fn new(
+        inbound_messaging: TConsensusSpec::InboundMessaging,
+        hooks: TConsensusSpec::Hooks,
+        fallen_behind_sync_threshold: u64,
+    ) -> Self {
         Self {
-            message_buffer: MessageBuffer::new(inbound_messaging),
+            message_buffer: MessageBuffer::new(inbound_messaging, fallen_behind_sync_threshold),
             hooks,
         }
     }
@@ -65,13 +69,15 @@ type EpochAndHeight = (Epoch, NodeHeight);
 pub struct MessageBuffer<TConsensusSpec: ConsensusSpec> {
     buffer: BTreeMap<EpochAndHeight, VecDeque<(TConsensusSpec::Addr, HotstuffMessage)>>,
     inbound_messaging: TConsensusSpec::InboundMessaging,
+    fallen_behind_sync_threshold: u64,
 }
 
 impl<TConsensusSpec: ConsensusSpec> MessageBuffer<TConsensusSpec> {
-    pub fn new(inbound_messaging: TConsensusSpec::InboundMessaging) -> Self {
+    pub fn new(inbound_messaging: TConsensusSpec::InboundMessaging, fallen_behind_sync_threshold: u64) -> Self {
         Self {
             buffer: BTreeMap::new(),
             inbound_messaging,
+            fallen_behind_sync_threshold,
         }
     }
 
@@ -100,6 +106,15 @@ impl<TConsensusSpec: ConsensusSpec> MessageBuffer<TConsensusSpec> {
                     info!(target: LOG_TARGET, "Discard message {} is for previous view {}/{}. Current view {}/{}", msg, e, h, current_epoch, next_height);
                     continue;
                 },
+                // Proposals that justify a block far ahead of our view are returned so that they can be validated and
+                // trigger a sync instead of waiting in the buffer
+                Some((epoch, height))
+                    if (epoch > current_epoch || height > next_height) &&
+                        self.is_far_ahead(&msg, current_epoch, current_height) =>
+                {
+                    info!(target: LOG_TARGET, "⚠️ Proposal {msg} is far ahead of the current view {current_epoch}/{next_height}");
+                    return Ok(Some((from, msg)));
+                },
                 // Buffer message for future epoch/height
                 Some((epoch, height)) if epoch > current_epoch || height > next_height => {
                     if msg.proposal().is_some() {
@@ -132,6 +147,19 @@ impl<TConsensusSpec: ConsensusSpec> MessageBuffer<TConsensusSpec> {
         self.buffer.clear();
     }
 
+    fn is_far_ahead(&self, msg: &HotstuffMessage, current_epoch: Epoch, current_height: NodeHeight) -> bool {
+        let Some(proposal) = msg.proposal() else {
+            return false;
+        };
+        is_qc_far_ahead(
+            proposal.block.justify().epoch(),
+            proposal.block.justify().block_height(),
+            current_epoch,
+            current_height,
+            self.fallen_behind_sync_threshold,
+        )
+    }
+
     fn push_to_buffer(&mut self, epoch: Epoch, height: NodeHeight, from: TConsensusSpec::Addr, msg: HotstuffMessage) {
         self.buffer.entry((epoch, height)).or_default().push_back((from, msg));
     }
@@ -147,6 +175,26 @@ pub struct NeedsSync<TAddr: NodeAddressable> {
     pub local_epoch: Epoch,
 }
 
+/// Returns true if a QC is more than `threshold` blocks ahead of the current view. Block heights start from zero in
+/// each epoch. A threshold of 0 always returns false.
+pub(crate) fn is_qc_far_ahead(
+    qc_epoch: Epoch,
+    qc_height: NodeHeight,
+    current_epoch: Epoch,
+    current_height: NodeHeight,
+    threshold: u64,
+) -> bool {
+    if threshold == 0 || qc_epoch < current_epoch {
+        return false;
+    }
+    let height_gap = if qc_epoch > current_epoch {
+        qc_height.as_u64()
+    } else {
+        qc_height.as_u64().saturating_sub(current_height.as_u64())
+    };
+    height_gap > threshold
+}
+
 fn msg_epoch_and_height(msg: &HotstuffMessage) -> Option<EpochAndHeight> {
     match msg {
         HotstuffMessage::Proposal(msg) => Some((msg.block.epoch(), msg.block.height())),
@@ -158,3 +206,33 @@ fn msg_epoch_and_height(msg: &HotstuffMessage) -> Option<EpochAndHeight> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qc_is_far_ahead_beyond_threshold_in_same_epoch() {
+        assert!(!is_qc_far_ahead(Epoch(1), NodeHeight(30), Epoch(1), NodeHeight(10), 20));
+        assert!(is_qc_far_ahead(Epoch(1), NodeHeight(31), Epoch(1), NodeHeight(10), 20));
+        assert!(!is_qc_far_ahead(Epoch(1), NodeHeight(5), Epoch(1), NodeHeight(10), 20));
+    }
+
+    #[test]
+    fn qc_in_later_epoch_is_measured_from_epoch_start() {
+        assert!(!is_qc_far_ahead(
+            Epoch(2),
+            NodeHeight(20),
+            Epoch(1),
+            NodeHeight(100),
+            20
+        ));
+        assert!(is_qc_far_ahead(Epoch(2), NodeHeight(21), Epoch(1), NodeHeight(100), 20));
+        assert!(!is_qc_far_ahead(Epoch(0), NodeHeight(100), Epoch(1), NodeHeight(0), 20));
+    }
+
+    #[test]
+    fn zero_threshold_disables_check() {
+        assert!(!is_qc_far_ahead(Epoch(2), NodeHeight(1000), Epoch(1), NodeHeight(0), 0));
+    }
+}
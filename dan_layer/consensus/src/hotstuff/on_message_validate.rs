@@ -21,7 +21,13 @@ use tokio::sync::broadcast;
 use super::config::HotstuffConfig;
 use crate::{
     block_validations,
-    hotstuff::{error::HotStuffError, HotstuffEvent, ProposalValidationError},
+    hotstuff::{
+        error::HotStuffError,
+        foreign_proposal_prefetcher::MAX_FOREIGN_PROPOSAL_REQUEST_TRANSACTIONS,
+        on_inbound_message::is_qc_far_ahead,
+        HotstuffEvent,
+        ProposalValidationError,
+    },
     messages::{ForeignProposalMessage, HotstuffMessage, MissingTransactionsRequest, ProposalMessage},
     tracing::TraceTimer,
     traits::{ConsensusSpec, OutboundMessaging},
@@ -33,13 +39,14 @@ pub struct OnMessageValidate<TConsensusSpec: ConsensusSpec> {
     config: HotstuffConfig,
     store: TConsensusSpec::StateStore,
     epoch_manager: TConsensusSpec::EpochManager,
-    leader_strategy: TConsensusSpec::LeaderStrategy,
-    vote_signing_service: TConsensusSpec::SignatureService,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
     tx_events: broadcast::Sender<HotstuffEvent>,
     /// Keep track of max 16 in-flight requests
     active_missing_transaction_requests: SimpleFixedArray<u32, 16>,
     current_request_id: u32,
+    /// The view of the QC that last triggered a sync. Proposals far ahead of the current view are discarded until it
+    /// is reached.
+    fallen_behind_sync_target: Option<(Epoch, NodeHeight)>,
 }
 
 impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
@@ -47,8 +54,6 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
         config: HotstuffConfig,
         store: TConsensusSpec::StateStore,
         epoch_manager: TConsensusSpec::EpochManager,
-        leader_strategy: TConsensusSpec::LeaderStrategy,
-        vote_signing_service: TConsensusSpec::SignatureService,
         outbound_messaging: TConsensusSpec::OutboundMessaging,
         tx_events: broadcast::Sender<HotstuffEvent>,
     ) -> Self {
@@ -56,17 +61,17 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
             config,
             store,
             epoch_manager,
-            leader_strategy,
-            vote_signing_service,
             outbound_messaging,
             tx_events,
             active_missing_transaction_requests: SimpleFixedArray::new(),
             current_request_id: 0,
+            fallen_behind_sync_target: None,
         }
     }
 
     pub async fn handle(
         &mut self,
+        current_epoch: Epoch,
         current_height: NodeHeight,
         local_committee_info: &CommitteeInfo,
         local_committee: &Committee<TConsensusSpec::Addr>,
@@ -76,6 +81,9 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
         let _timer = TraceTimer::debug(LOG_TARGET, "on_message_validate");
         match msg {
             HotstuffMessage::Proposal(msg) => {
+                if self.check_fallen_behind(current_epoch, current_height, &msg).await? {
+                    return Ok(MessageValidationResult::Discard);
+                }
                 self.process_local_proposal(current_height, from, local_committee_info, local_committee, msg)
             },
             HotstuffMessage::ForeignProposal(proposal) => {
@@ -96,6 +104,16 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
                     message: HotstuffMessage::MissingTransactionsResponse(msg),
                 })
             },
+            HotstuffMessage::ForeignProposalRequest(msg) => {
+                if msg.transactions.len() > MAX_FOREIGN_PROPOSAL_REQUEST_TRANSACTIONS {
+                    warn!(target: LOG_TARGET, "⚠️Peer requested foreign proposals for more than the maximum amount of transactions. Discarding message");
+                    return Ok(MessageValidationResult::Discard);
+                }
+                Ok(MessageValidationResult::Ready {
+                    from,
+                    message: HotstuffMessage::ForeignProposalRequest(msg),
+                })
+            },
             msg => Ok(MessageValidationResult::Ready { from, message: msg }),
         }
     }
@@ -129,6 +147,62 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
         req_id
     }
 
+    /// Returns a FallenBehind error if the proposal carries a valid QC that is far ahead of the current view. The
+    /// consensus worker then syncs and resumes from the synced height. Returns true if the proposal should be
+    /// discarded because a sync is already underway.
+    async fn check_fallen_behind(
+        &mut self,
+        current_epoch: Epoch,
+        current_height: NodeHeight,
+        proposal: &ProposalMessage,
+    ) -> Result<bool, HotStuffError> {
+        if self
+            .fallen_behind_sync_target
+            .is_some_and(|target| (current_epoch, current_height) >= target)
+        {
+            info!(target: LOG_TARGET, "🌐 Caught up to view {current_epoch}/{current_height}");
+            self.fallen_behind_sync_target = None;
+        }
+
+        let qc = proposal.block.justify();
+        if !is_qc_far_ahead(
+            qc.epoch(),
+            qc.block_height(),
+            current_epoch,
+            current_height,
+            self.config.consensus_constants.fallen_behind_sync_threshold,
+        ) {
+            return Ok(false);
+        }
+
+        if self.fallen_behind_sync_target.is_some() {
+            debug!(target: LOG_TARGET, "Discarding proposal {} while catching up", proposal.block);
+            return Ok(true);
+        }
+
+        // Only a QC signed by the committee for its epoch is evidence that we have fallen behind
+        let committee = match self.epoch_manager.get_local_committee(qc.epoch()).await {
+            Ok(committee) => committee,
+            Err(err) => {
+                warn!(target: LOG_TARGET, "Unable to get committee for epoch {} to check QC {}: {}", qc.epoch(), qc, err);
+                return Ok(false);
+            },
+        };
+        let committee_info = self.epoch_manager.get_local_committee_info(qc.epoch()).await?;
+        if let Err(err) =
+            block_validations::check_quorum_certificate::<TConsensusSpec>(&proposal.block, &committee, &committee_info)
+        {
+            warn!(target: LOG_TARGET, "❌ Proposal {} has an invalid QC: {}", proposal.block, err);
+            return Ok(false);
+        }
+
+        self.fallen_behind_sync_target = Some((qc.epoch(), qc.block_height()));
+        Err(HotStuffError::FallenBehind {
+            local_height: current_height,
+            qc_height: qc.block_height(),
+        })
+    }
+
     fn process_local_proposal(
         &mut self,
         current_height: NodeHeight,
@@ -189,6 +263,7 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
                     unparked_blocks.push(ProposalMessage {
                         block: unparked_block,
                         foreign_proposals,
+                        transactions: vec![],
                     });
                 }
 
@@ -208,14 +283,7 @@ impl<TConsensusSpec: ConsensusSpec> OnMessageValidate<TConsensusSpec> {
         committee_for_block: &Committee<TConsensusSpec::Addr>,
         committee_info: &CommitteeInfo,
     ) -> Result<(), HotStuffError> {
-        block_validations::check_proposal::<TConsensusSpec>(
-            block,
-            committee_info,
-            committee_for_block,
-            &self.vote_signing_service,
-            &self.leader_strategy,
-            &self.config,
-        )
+        block_validations::check_proposal::<TConsensusSpec>(block, committee_info, committee_for_block, &self.config)
     }
 
     fn handle_missing_transactions_local_block(
@@ -9,7 +9,12 @@ use tari_dan_storage::{
 };
 
 use crate::{
-    hotstuff::{get_next_block_height_and_leader, pacemaker_handle::PaceMakerHandle, HotStuffError},
+    hotstuff::{
+        get_high_qc_leader_seed,
+        get_next_block_height_and_leader,
+        pacemaker_handle::PaceMakerHandle,
+        HotStuffError,
+    },
     messages::{HotstuffMessage, NewViewMessage, VoteMessage},
     traits::{ConsensusSpec, OutboundMessaging},
 };
@@ -49,10 +54,14 @@ impl<TConsensusSpec: ConsensusSpec> OnNextSyncViewHandler<TConsensusSpec> {
 
         let (new_height, next_leader, leaf_block, high_qc, last_sent_vote) = self.store.with_read_tx(|tx| {
             let leaf_block = LeafBlock::get(tx, epoch)?;
+            // The next leader proposes a block justified by the high QC, so it is selected using the seed of the high
+            // QC block. Validators that did not receive the latest proposal have a different leaf block.
+            let leader_seed = get_high_qc_leader_seed(tx, epoch)?;
             let (next_height, next_leader, _) = get_next_block_height_and_leader(
                 tx,
                 local_committee,
                 &self.leader_strategy,
+                &leader_seed,
                 leaf_block.block_id(),
                 // Leader failure at current height, so we use the next height
                 current_height + NodeHeight(1),
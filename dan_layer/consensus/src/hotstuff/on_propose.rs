@@ -16,6 +16,7 @@ use tari_dan_common_types::{
     shard::Shard,
     Epoch,
     ExtraData,
+    ExtraFieldKey,
     NodeHeight,
     ToSubstateAddress,
     VersionedSubstateId,
@@ -29,6 +30,7 @@ use tari_dan_storage::{
         BurntUtxo,
         Command,
         Decision,
+        ExecutionContext,
         ForeignProposal,
         ForeignSendCounters,
         HighQc,
@@ -53,15 +55,21 @@ use tari_dan_storage::{
 };
 use tari_engine_types::{commit_result::RejectReason, substate::Substate};
 use tari_epoch_manager::EpochManagerReader;
-use tari_transaction::TransactionId;
+use tari_transaction::{Transaction, TransactionId};
 use tokio::task;
 
 use crate::{
     hotstuff::{
         block_change_set::ProposedBlockChangeSet,
+        calculate_leader_vrf_input,
         calculate_state_merkle_root,
+        cross_shard_deadlock::find_deadlocked_transaction,
         error::HotStuffError,
         filter_diff_for_committee,
+        get_block_leader_seed,
+        get_execution_context,
+        get_median_block_timestamp,
+        proposal_capacity::ProposalCapacity,
         substate_store::PendingSubstateStore,
         transaction_manager::{
             ConsensusTransactionManager,
@@ -71,6 +79,7 @@ use crate::{
             TransactionLockConflicts,
         },
         HotstuffConfig,
+        ProposalMissRate,
     },
     messages::{HotstuffMessage, ProposalMessage},
     tracing::TraceTimer,
@@ -95,6 +104,7 @@ pub struct OnPropose<TConsensusSpec: ConsensusSpec> {
     transaction_manager: ConsensusTransactionManager<TConsensusSpec::TransactionExecutor, TConsensusSpec::StateStore>,
     signing_service: TConsensusSpec::SignatureService,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
+    miss_rate: ProposalMissRate,
 }
 
 impl<TConsensusSpec> OnPropose<TConsensusSpec>
@@ -111,6 +121,7 @@ where TConsensusSpec: ConsensusSpec
         >,
         signing_service: TConsensusSpec::SignatureService,
         outbound_messaging: TConsensusSpec::OutboundMessaging,
+        miss_rate: ProposalMissRate,
     ) -> Self {
         Self {
             config,
@@ -120,6 +131,7 @@ where TConsensusSpec: ConsensusSpec
             transaction_manager,
             signing_service,
             outbound_messaging,
+            miss_rate,
         }
     }
 
@@ -132,6 +144,7 @@ where TConsensusSpec: ConsensusSpec
         local_committee_info: CommitteeInfo,
         leaf_block: LeafBlock,
         propose_epoch_end: bool,
+        defer_cross_shard_transactions: bool,
     ) -> Result<(), HotStuffError> {
         let _timer = TraceTimer::info(LOG_TARGET, "OnPropose");
         if let Some(last_proposed) = self.store.with_read_tx(|tx| LastProposed::get(tx)).optional()? {
@@ -154,60 +167,75 @@ where TConsensusSpec: ConsensusSpec
         let base_layer_block_hash = current_base_layer_block_hash;
         let base_layer_block_height = current_base_layer_block_height;
 
+        let propose_full = self
+            .miss_rate
+            .should_propose_full(self.config.consensus_constants.full_proposal_miss_rate_threshold);
         let on_propose = self.clone();
         let validator_node_pk = self.signing_service.public_key().clone();
-        let (next_block, foreign_proposals) = task::spawn_blocking(move || {
-            on_propose.store.with_write_tx(|tx| {
-                let high_qc = HighQc::get(&**tx, epoch)?;
-                let high_qc_cert = high_qc.get_quorum_certificate(&**tx)?;
-
-                info!(
-                    target: LOG_TARGET,
-                    "🌿 PROPOSE local block with parent {}. HighQC: {}",
-                    leaf_block,
-                    high_qc_cert,
-                );
-
-                let next_block = on_propose.build_next_block(
-                    tx,
-                    epoch,
-                    next_height,
-                    leaf_block,
-                    high_qc_cert,
-                    validator_node_pk,
-                    &local_committee_info,
-                    false,
-                    base_layer_block_height,
-                    base_layer_block_hash,
-                    propose_epoch_end,
-                )?;
-
-                let NextBlock {
-                    block: next_block,
-                    foreign_proposals,
-                    executed_transactions,
-                    lock_conflicts,
-                } = next_block;
-
-                lock_conflicts.save_for_block(tx, next_block.id())?;
-
-                // Add executions for this block
-                if !executed_transactions.is_empty() {
-                    debug!(
+        let (next_block, foreign_proposals, transactions) = task::spawn_blocking({
+            let local_committee = local_committee.clone();
+            move || {
+                on_propose.store.with_write_tx(|tx| {
+                    let high_qc = HighQc::get(&**tx, epoch)?;
+                    let high_qc_cert = high_qc.get_quorum_certificate(&**tx)?;
+
+                    info!(
                         target: LOG_TARGET,
-                        "Saving {} executed transaction(s) for block {}",
-                        executed_transactions.len(),
-                        next_block.id()
+                        "🌿 PROPOSE local block with parent {}. HighQC: {}",
+                        leaf_block,
+                        high_qc_cert,
                     );
-                }
-                for executed in executed_transactions.into_values() {
-                    executed.for_block(*next_block.id()).insert_if_required(tx)?;
-                }
 
-                next_block.as_last_proposed().set(tx)?;
+                    let next_block = on_propose.build_next_block(
+                        tx,
+                        epoch,
+                        next_height,
+                        leaf_block,
+                        high_qc_cert,
+                        validator_node_pk,
+                        &local_committee,
+                        &local_committee_info,
+                        false,
+                        base_layer_block_height,
+                        base_layer_block_hash,
+                        propose_epoch_end,
+                        defer_cross_shard_transactions,
+                    )?;
+
+                    let NextBlock {
+                        block: next_block,
+                        foreign_proposals,
+                        executed_transactions,
+                        lock_conflicts,
+                    } = next_block;
+
+                    lock_conflicts.save_for_block(tx, next_block.id())?;
+
+                    // Add executions for this block
+                    if !executed_transactions.is_empty() {
+                        debug!(
+                            target: LOG_TARGET,
+                            "Saving {} executed transaction(s) for block {}",
+                            executed_transactions.len(),
+                            next_block.id()
+                        );
+                    }
+                    for executed in executed_transactions.into_values() {
+                        executed.for_block(*next_block.id()).insert_if_required(tx)?;
+                    }
 
-                Ok::<_, HotStuffError>((next_block, foreign_proposals))
-            })
+                    next_block.as_last_proposed().set(tx)?;
+
+                    let transactions = if propose_full {
+                        let (transactions, _) = TransactionRecord::get_any(&**tx, new_transaction_ids(&next_block))?;
+                        transactions.into_iter().map(|rec| rec.into_transaction()).collect()
+                    } else {
+                        vec![]
+                    };
+
+                    Ok::<_, HotStuffError>((next_block, foreign_proposals, transactions))
+                })
+            }
         })
         .await??;
 
@@ -222,7 +250,12 @@ where TConsensusSpec: ConsensusSpec
             next_block.parent()
         );
 
-        self.broadcast_local_proposal(next_block, foreign_proposals, &local_committee_info)
+        self.miss_rate.record_proposed(
+            new_transaction_ids(&next_block).count(),
+            local_committee_info.num_shard_group_members().saturating_sub(1) as usize,
+        );
+
+        self.broadcast_local_proposal(next_block, foreign_proposals, transactions, &local_committee_info)
             .await?;
 
         Ok(())
@@ -232,16 +265,19 @@ where TConsensusSpec: ConsensusSpec
         &mut self,
         next_block: Block,
         foreign_proposals: Vec<ForeignProposal>,
+        transactions: Vec<Transaction>,
         local_committee_info: &CommitteeInfo,
     ) -> Result<(), HotStuffError> {
         info!(
             target: LOG_TARGET,
-            "🌿 Broadcasting local proposal {} to local committee",
+            "🌿 Broadcasting {} local proposal {} to local committee",
+            if transactions.is_empty() { "compact" } else { "full" },
             next_block,
         );
         let msg = HotstuffMessage::Proposal(ProposalMessage {
             block: next_block,
             foreign_proposals,
+            transactions,
         });
         // Broadcast to local and foreign committees
         self.outbound_messaging.send_self(msg.clone()).await?;
@@ -255,25 +291,30 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
-    /// Returns Ok(None) if the command cannot be sequenced yet due to lock conflicts.
+    /// Returns Ok(None) if the command cannot be sequenced yet due to lock conflicts, or because new transactions
+    /// involving foreign shard groups are deferred while the committee layout is switching.
     fn transaction_pool_record_to_command(
         &self,
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         start_of_chain_id: &LeafBlock,
+        execution_context: &ExecutionContext,
         mut tx_rec: TransactionPoolRecord,
         local_committee_info: &CommitteeInfo,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
         executed_transactions: &mut HashMap<TransactionId, TransactionExecution>,
         lock_conflicts: &mut TransactionLockConflicts,
+        defer_cross_shard_transactions: bool,
     ) -> Result<Option<Command>, HotStuffError> {
         match tx_rec.current_stage() {
             TransactionPoolStage::New => self.prepare_transaction(
                 start_of_chain_id,
+                execution_context,
                 &mut tx_rec,
                 local_committee_info,
                 substate_store,
                 executed_transactions,
                 lock_conflicts,
+                defer_cross_shard_transactions,
             ),
             // Leader thinks all local nodes have prepared
             TransactionPoolStage::Prepared => Ok(Some(Command::LocalPrepare(tx_rec.get_local_transaction_atom()))),
@@ -282,6 +323,7 @@ where TConsensusSpec: ConsensusSpec
             TransactionPoolStage::LocalPrepared => self.all_or_some_prepare_transaction(
                 tx,
                 start_of_chain_id,
+                execution_context,
                 local_committee_info,
                 &mut tx_rec,
                 substate_store,
@@ -373,7 +415,7 @@ where TConsensusSpec: ConsensusSpec
         Ok(())
     }
 
-    #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
     fn build_next_block(
         &self,
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
@@ -382,13 +424,15 @@ where TConsensusSpec: ConsensusSpec
         parent_block: LeafBlock,
         high_qc_certificate: QuorumCertificate,
         proposed_by: PublicKey,
+        local_committee: &Committee<TConsensusSpec::Addr>,
         local_committee_info: &CommitteeInfo,
         dont_propose_transactions: bool,
         base_layer_block_height: u64,
         base_layer_block_hash: FixedHash,
         propose_epoch_end: bool,
+        defer_cross_shard_transactions: bool,
     ) -> Result<NextBlock, HotStuffError> {
-        let max_block_size = self.config.consensus_constants.max_block_size;
+        let mut capacity = ProposalCapacity::new(self.config.consensus_constants.max_block_size);
 
         let justifies_parent = high_qc_certificate.block_id() == parent_block.block_id();
         let start_of_chain_block = if justifies_parent || high_qc_certificate.is_zero() {
@@ -401,13 +445,40 @@ where TConsensusSpec: ConsensusSpec
             high_qc_certificate.as_leaf_block()
         };
 
+        let justify_block = high_qc_certificate.get_block(tx)?;
+        // Never propose a timestamp earlier than the justified block or the median of recent blocks, even if the local
+        // clock is behind
+        let median_timestamp = get_median_block_timestamp(
+            tx,
+            &justify_block,
+            self.config.consensus_constants.block_timestamp_median_window,
+        )?;
+        let block_timestamp = EpochTime::now()
+            .as_u64()
+            .max(justify_block.timestamp())
+            .max(median_timestamp);
+        // Validators select the leader for this block using the seed of the block that it justifies
+        let leader_seed = get_block_leader_seed(tx, epoch, &justify_block)?;
+        let vrf_proof = self.signing_service.vrf_prove(calculate_leader_vrf_input(
+            self.config.network,
+            epoch,
+            local_committee_info.shard_group(),
+            next_height,
+            &leader_seed,
+        ));
+        // Transactions prepared in this block are executed with the timestamp and VRF output of this block, unless
+        // another shard group involved in the transaction has already recorded the context that it must be executed
+        // with
+        let execution_context = ExecutionContext::new(block_timestamp, vrf_proof.output());
+
         let mut total_leader_fee = 0;
 
         let foreign_proposals = if propose_epoch_end {
             vec![]
         } else {
-            ForeignProposal::get_all_new(tx, start_of_chain_block.block_id(), max_block_size / 4)?
+            ForeignProposal::get_all_new(tx, start_of_chain_block.block_id(), capacity.foreign_proposal_limit())?
         };
+        capacity.use_foreign_proposals(foreign_proposals.len());
 
         if !foreign_proposals.is_empty() {
             debug!(
@@ -417,16 +488,12 @@ where TConsensusSpec: ConsensusSpec
             );
         }
 
-        let burnt_utxos = if dont_propose_transactions || propose_epoch_end {
+        let burnt_utxos = if dont_propose_transactions || propose_epoch_end || capacity.mint_limit() == 0 {
             vec![]
         } else {
-            max_block_size
-                .checked_sub(foreign_proposals.len() * 4)
-                .filter(|n| *n > 0)
-                .map(|size| BurntUtxo::get_all_unproposed(tx, start_of_chain_block.block_id(), size))
-                .transpose()?
-                .unwrap_or_default()
+            BurntUtxo::get_all_unproposed(tx, start_of_chain_block.block_id(), capacity.mint_limit())?
         };
+        capacity.use_mints(burnt_utxos.len());
 
         if !burnt_utxos.is_empty() {
             debug!(
@@ -436,27 +503,34 @@ where TConsensusSpec: ConsensusSpec
             );
         }
 
-        let suspend_nodes = if dont_propose_transactions || propose_epoch_end {
+        let suspend_nodes = if dont_propose_transactions || propose_epoch_end || capacity.node_change_limit() == 0 {
             vec![]
         } else {
-            let num_suspended = ValidatorConsensusStats::count_number_suspended_nodes(tx)?;
-            let max_allowed_to_suspend =
-                u64::from(local_committee_info.quorum_threshold()).saturating_sub(num_suspended);
-
-            max_block_size
-                .checked_sub(foreign_proposals.len() * 4 + burnt_utxos.len())
-                .filter(|n| *n > 0)
-                .map(|size| {
-                    ValidatorConsensusStats::get_nodes_to_suspend(
-                        tx,
-                        start_of_chain_block.block_id(),
-                        self.config.consensus_constants.missed_proposal_suspend_threshold,
-                        size.min(max_allowed_to_suspend as usize),
-                    )
-                })
-                .transpose()?
-                .unwrap_or_default()
+            // Nodes may be suspended while the voting power of suspended nodes does not exceed the quorum voting power
+            let suspended_voting_power =
+                local_committee.sum_voting_power(&ValidatorConsensusStats::get_suspended_nodes(tx)?);
+            let mut max_voting_power_to_suspend = local_committee
+                .quorum_voting_power()
+                .saturating_sub(suspended_voting_power);
+
+            ValidatorConsensusStats::get_nodes_to_suspend(
+                tx,
+                start_of_chain_block.block_id(),
+                self.config.consensus_constants.missed_proposal_suspend_threshold,
+                capacity.node_change_limit(),
+            )?
+            .into_iter()
+            .filter(|public_key| {
+                let voting_power = local_committee.voting_power_of(public_key);
+                if voting_power > max_voting_power_to_suspend {
+                    return false;
+                }
+                max_voting_power_to_suspend -= voting_power;
+                true
+            })
+            .collect()
         };
+        capacity.use_node_changes(suspend_nodes.len());
 
         if !suspend_nodes.is_empty() {
             debug!(
@@ -466,16 +540,16 @@ where TConsensusSpec: ConsensusSpec
             )
         }
 
-        let resume_nodes = if dont_propose_transactions || propose_epoch_end {
+        let resume_nodes = if dont_propose_transactions || propose_epoch_end || capacity.node_change_limit() == 0 {
             vec![]
         } else {
-            max_block_size
-                .checked_sub(foreign_proposals.len() * 4 + burnt_utxos.len())
-                .filter(|n| *n > 0)
-                .map(|size| ValidatorConsensusStats::get_nodes_to_resume(tx, start_of_chain_block.block_id(), size))
-                .transpose()?
-                .unwrap_or_default()
+            ValidatorConsensusStats::get_nodes_to_resume(
+                tx,
+                start_of_chain_block.block_id(),
+                capacity.node_change_limit(),
+            )?
         };
+        capacity.use_node_changes(resume_nodes.len());
 
         if !resume_nodes.is_empty() {
             debug!(
@@ -487,17 +561,20 @@ where TConsensusSpec: ConsensusSpec
 
         let suspend_nodes_len = suspend_nodes.len();
 
-        let batch = if dont_propose_transactions || propose_epoch_end {
+        let mut batch = if dont_propose_transactions || propose_epoch_end || capacity.transaction_limit() == 0 {
             vec![]
         } else {
-            max_block_size
-                // Each foreign proposal is "heavier" than a transaction command
-                .checked_sub(foreign_proposals.len() * 4 + burnt_utxos.len() + suspend_nodes.len())
-                .filter(|n| *n > 0)
-                .map(|size| self.transaction_pool.get_batch_for_next_block(tx, size, start_of_chain_block.block_id()))
-                .transpose()?
-                .unwrap_or_default()
+            self.transaction_pool.get_batch_for_next_block(
+                tx,
+                capacity.transaction_limit(),
+                start_of_chain_block.block_id(),
+                self.config.consensus_constants.transaction_ordering,
+            )?
         };
+        capacity.use_transactions(batch.len());
+        // The ordering policy selects the transactions, but they are prepared in the order that validators evaluate the
+        // block commands so that conflicts within the block (e.g. reused idempotency keys) are resolved the same way
+        batch.sort_by(|a, b| a.transaction_id().cmp(b.transaction_id()));
 
         let mut commands = if propose_epoch_end {
             BTreeSet::from_iter([Command::EndEpoch])
@@ -581,11 +658,13 @@ where TConsensusSpec: ConsensusSpec
             if let Some(command) = self.transaction_pool_record_to_command(
                 tx,
                 &start_of_chain_block,
+                &execution_context,
                 transaction,
                 local_committee_info,
                 &mut substate_store,
                 &mut executed_transactions,
                 &mut lock_conflicts,
+                defer_cross_shard_transactions,
             )? {
                 total_leader_fee += command
                     .committing()
@@ -597,6 +676,17 @@ where TConsensusSpec: ConsensusSpec
         }
         timer.done();
 
+        if !dont_propose_transactions && !propose_epoch_end {
+            let aborts = self.get_stalled_transaction_aborts(
+                tx,
+                &start_of_chain_block,
+                next_height,
+                local_committee_info,
+                capacity.remaining(),
+            )?;
+            commands.extend(aborts.into_iter().map(Command::SomePrepare));
+        }
+
         // This relies on the UTXO commands being ordered after transaction commands
         for utxo in burnt_utxos {
             let id = VersionedSubstateId::new(utxo.substate_id.clone(), 0);
@@ -642,6 +732,12 @@ where TConsensusSpec: ConsensusSpec
         // Ensure that foreign indexes are canonically ordered
         foreign_indexes.sort_keys();
 
+        let mut extra_data = ExtraData::new();
+        extra_data.insert(
+            ExtraFieldKey::VrfProof,
+            vrf_proof.to_bytes().to_vec().try_into().expect("VRF proof is 96 bytes"),
+        );
+
         let mut next_block = Block::create(
             self.config.network,
             *parent_block.block_id(),
@@ -655,10 +751,10 @@ where TConsensusSpec: ConsensusSpec
             total_leader_fee,
             foreign_indexes,
             None,
-            EpochTime::now().as_u64(),
+            block_timestamp,
             base_layer_block_height,
             base_layer_block_hash,
-            ExtraData::new(),
+            extra_data,
         )?;
 
         let signature = self.signing_service.sign(next_block.id());
@@ -676,11 +772,13 @@ where TConsensusSpec: ConsensusSpec
     fn prepare_transaction(
         &self,
         parent_block: &LeafBlock,
+        execution_context: &ExecutionContext,
         tx_rec: &mut TransactionPoolRecord,
         local_committee_info: &CommitteeInfo,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
         executed_transactions: &mut HashMap<TransactionId, TransactionExecution>,
         lock_conflicts: &mut TransactionLockConflicts,
+        defer_cross_shard_transactions: bool,
     ) -> Result<Option<Command>, HotStuffError> {
         info!(
             target: LOG_TARGET,
@@ -694,6 +792,7 @@ where TConsensusSpec: ConsensusSpec
                 substate_store,
                 local_committee_info,
                 parent_block.epoch(),
+                execution_context,
                 *tx_rec.transaction_id(),
                 parent_block.block_id(),
             )
@@ -783,6 +882,14 @@ where TConsensusSpec: ConsensusSpec
                 Command::LocalOnly(atom)
             },
 
+            PreparedTransaction::MultiShard(_) if defer_cross_shard_transactions => {
+                info!(
+                    target: LOG_TARGET,
+                    "⏸️ Transaction {} involves foreign shard groups but the committee layout is switching. Deferring...",
+                    tx_rec.transaction_id(),
+                );
+                return Ok(None);
+            },
             PreparedTransaction::MultiShard(multishard) => {
                 match multishard.current_decision() {
                     Decision::Commit => {
@@ -818,6 +925,13 @@ where TConsensusSpec: ConsensusSpec
                     },
                 }
 
+                // Record the context that the transaction is executed with so that other involved shard groups can
+                // execute it with the same context
+                tx_rec
+                    .evidence_mut()
+                    .add_shard_group(local_committee_info.shard_group())
+                    .set_execution_context(*execution_context);
+
                 info!(
                     target: LOG_TARGET,
                     "🌍 Transaction involves foreign shard groups, proposing Prepare({}, {})",
@@ -833,10 +947,118 @@ where TConsensusSpec: ConsensusSpec
         Ok(Some(command))
     }
 
+    /// Returns ABORT atoms for locally prepared transactions that are still waiting for foreign shard groups to
+    /// prepare and either hold substate locks for which the lease expires at the given height, are deadlocked with
+    /// another transaction (see [find_deadlocked_transaction]), or were prepared with shard groups that no longer exist
+    /// after a committee layout change.
+    fn get_stalled_transaction_aborts(
+        &self,
+        tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
+        start_of_chain_block: &LeafBlock,
+        next_height: NodeHeight,
+        local_committee_info: &CommitteeInfo,
+        max: usize,
+    ) -> Result<Vec<TransactionAtom>, HotStuffError> {
+        let lease_blocks = self.config.consensus_constants.substate_lock_lease_blocks;
+        let deadlock_blocks = self.config.consensus_constants.cross_shard_deadlock_blocks;
+        if max == 0 {
+            return Ok(vec![]);
+        }
+
+        let pool = self.transaction_pool.get_all(tx)?;
+        // Loaded on demand for deadlock detection
+        let mut new_transactions = None;
+        let mut atoms = Vec::new();
+        for rec in &pool {
+            if atoms.len() >= max {
+                break;
+            }
+            if !rec.committed_stage().is_local_prepared() {
+                continue;
+            }
+            let Some(mut tx_rec) = self
+                .transaction_pool
+                .get(tx, start_of_chain_block, rec.transaction_id())
+                .optional()?
+            else {
+                continue;
+            };
+            if !tx_rec.current_stage().is_local_prepared() || tx_rec.is_ready() {
+                continue;
+            }
+
+            let reason = if tx_rec.has_shard_groups_outside_layout(local_committee_info) {
+                info!(
+                    target: LOG_TARGET,
+                    "🔀 Transaction {} was prepared before the committee layout changed, proposing SomePrepare(ABORT)",
+                    tx_rec.transaction_id(),
+                );
+                AbortReason::CommitteeLayoutChanged
+            } else if tx_rec.is_lock_lease_expired(tx, next_height, lease_blocks)? {
+                info!(
+                    target: LOG_TARGET,
+                    "⏳ Substate lock lease expired for transaction {} at height {}, proposing SomePrepare(ABORT)",
+                    tx_rec.transaction_id(),
+                    next_height,
+                );
+                AbortReason::LockLeaseExpired
+            } else if tx_rec.is_lock_lease_expired(tx, next_height, deadlock_blocks)? {
+                if new_transactions.is_none() {
+                    new_transactions = Some(self.get_new_transactions(tx, start_of_chain_block, &pool)?);
+                }
+                let new_transactions = new_transactions.as_deref().unwrap_or_default();
+                let Some(conflicting_transaction) =
+                    find_deadlocked_transaction(tx, &tx_rec, new_transactions, local_committee_info)?
+                else {
+                    continue;
+                };
+                info!(
+                    target: LOG_TARGET,
+                    "🔒 Transaction {} is deadlocked with transaction {} at height {}, proposing SomePrepare(ABORT)",
+                    tx_rec.transaction_id(),
+                    conflicting_transaction,
+                    next_height,
+                );
+                AbortReason::CrossShardDeadlock
+            } else {
+                continue;
+            };
+
+            tx_rec.set_local_decision(Decision::Abort(reason));
+            atoms.push(tx_rec.get_local_transaction_atom());
+        }
+
+        Ok(atoms)
+    }
+
+    /// Returns the transactions in the pool that have not yet been prepared as of the given leaf block.
+    fn get_new_transactions(
+        &self,
+        tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
+        start_of_chain_block: &LeafBlock,
+        pool: &[TransactionPoolRecord],
+    ) -> Result<Vec<TransactionPoolRecord>, HotStuffError> {
+        let mut new_transactions = Vec::new();
+        for rec in pool.iter().filter(|rec| rec.committed_stage().is_new()) {
+            let Some(rec) = self
+                .transaction_pool
+                .get(tx, start_of_chain_block, rec.transaction_id())
+                .optional()?
+            else {
+                continue;
+            };
+            if rec.current_stage().is_new() {
+                new_transactions.push(rec);
+            }
+        }
+        Ok(new_transactions)
+    }
+
     fn all_or_some_prepare_transaction(
         &self,
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         parent_block: &LeafBlock,
+        block_execution_context: &ExecutionContext,
         local_committee_info: &CommitteeInfo,
         tx_rec: &mut TransactionPoolRecord,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
@@ -847,8 +1069,14 @@ where TConsensusSpec: ConsensusSpec
             return Ok(Some(Command::SomePrepare(tx_rec.get_current_transaction_atom())));
         }
 
-        let mut execution =
-            self.execute_transaction(tx, &parent_block.block_id, parent_block.epoch, tx_rec.transaction_id())?;
+        let execution_context = get_execution_context(tx_rec, block_execution_context);
+        let mut execution = self.execute_transaction(
+            tx,
+            &parent_block.block_id,
+            parent_block.epoch,
+            &execution_context,
+            tx_rec.transaction_id(),
+        )?;
 
         // Try to lock all local outputs
         let local_outputs = execution
@@ -950,6 +1178,7 @@ where TConsensusSpec: ConsensusSpec
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         parent_block_id: &BlockId,
         current_epoch: Epoch,
+        execution_context: &ExecutionContext,
         transaction_id: &TransactionId,
     ) -> Result<TransactionExecution, HotStuffError> {
         let transaction = TransactionRecord::get(tx, transaction_id)?;
@@ -975,7 +1204,7 @@ where TConsensusSpec: ConsensusSpec
 
         let executed = self
             .transaction_manager
-            .execute(current_epoch, pledged)
+            .execute(current_epoch, execution_context, pledged)
             .map_err(|e| HotStuffError::TransactionExecutorError(e.to_string()))?;
 
         Ok(executed.into_execution())
@@ -992,3 +1221,13 @@ pub fn get_non_local_shards(diff: &[SubstateChange], local_committee_info: &Comm
         .filter(|shard| local_committee_info.shard_group().contains(shard))
         .collect()
 }
+
+/// Returns the ids of transactions that are proposed for the first time in the block. Validators may not have received
+/// these transactions yet.
+fn new_transaction_ids(block: &Block) -> impl Iterator<Item = &TransactionId> + '_ {
+    block
+        .commands()
+        .iter()
+        .filter_map(|cmd| cmd.local_only().or_else(|| cmd.prepare()))
+        .map(|atom| &atom.id)
+}
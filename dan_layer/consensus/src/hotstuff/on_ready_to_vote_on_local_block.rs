@@ -4,9 +4,10 @@
 use std::{collections::HashMap, num::NonZeroU64};
 
 use log::*;
+use tari_common_types::types::PublicKey;
 use tari_crypto::ristretto::RistrettoPublicKey;
 use tari_dan_common_types::{
-    committee::CommitteeInfo,
+    committee::{Committee, CommitteeInfo},
     optional::Optional,
     Epoch,
     ShardGroup,
@@ -22,6 +23,7 @@ use tari_dan_storage::{
         BlockTransactionExecution,
         Command,
         Decision,
+        ExecutionContext,
         ForeignProposalAtom,
         ForeignProposalStatus,
         HighQc,
@@ -43,6 +45,7 @@ use tari_dan_storage::{
         ValidatorConsensusStats,
     },
     StateStore,
+    StateStoreWriteTransaction,
 };
 use tari_engine_types::{commit_result::RejectReason, substate::Substate};
 use tokio::sync::broadcast;
@@ -51,10 +54,13 @@ use crate::{
     hotstuff::{
         block_change_set::{BlockDecision, ProposedBlockChangeSet},
         calculate_state_merkle_root,
+        create_epoch_checkpoint,
+        cross_shard_deadlock::find_deadlocked_transaction,
         error::HotStuffError,
         event::HotstuffEvent,
         filter_diff_for_committee,
         foreign_proposal_processor::process_foreign_block,
+        get_execution_context,
         substate_store::{PendingSubstateStore, ShardedStateTree},
         transaction_manager::{
             ConsensusTransactionManager,
@@ -107,7 +113,9 @@ where TConsensusSpec: ConsensusSpec
         tx: &mut <TConsensusSpec::StateStore as StateStore>::WriteTransaction<'_>,
         valid_block: &ValidBlock,
         local_committee_info: &CommitteeInfo,
+        local_committee: &Committee<TConsensusSpec::Addr>,
         can_propose_epoch_end: bool,
+        defer_cross_shard_transactions: bool,
         foreign_committee_infos: HashMap<ShardGroup, CommitteeInfo>,
         change_set: &mut ProposedBlockChangeSet,
     ) -> Result<BlockDecision, HotStuffError> {
@@ -131,7 +139,9 @@ where TConsensusSpec: ConsensusSpec
                 tx,
                 valid_block.block(),
                 local_committee_info,
+                local_committee,
                 can_propose_epoch_end,
+                defer_cross_shard_transactions,
                 &foreign_committee_infos,
                 change_set,
             )?;
@@ -140,11 +150,12 @@ where TConsensusSpec: ConsensusSpec
         }
 
         let mut locked_blocks = Vec::new();
-        let mut finalized_transactions = Vec::new();
+        let mut committed_blocks = Vec::new();
         let mut end_of_epoch = None;
         let mut maybe_high_qc = None;
 
         if change_set.is_accept() {
+            let local_committee_public_keys = local_committee.public_keys().cloned().collect::<Vec<_>>();
             // Update nodes
             let high_qc = valid_block.block().update_nodes(
                 tx,
@@ -155,19 +166,29 @@ where TConsensusSpec: ConsensusSpec
                     self.on_lock_block(tx, block)
                 },
                 |tx, last_exec, commit_block| {
-                    let committed = self.on_commit(tx, last_exec, commit_block, local_committee_info)?;
+                    let committed = self.on_commit(
+                        tx,
+                        last_exec,
+                        commit_block,
+                        local_committee_info,
+                        &local_committee_public_keys,
+                    )?;
                     if commit_block.is_epoch_end() {
                         end_of_epoch = Some(commit_block.epoch());
                     }
-                    if !committed.is_empty() {
-                        finalized_transactions.push(committed);
-                    }
+                    committed_blocks.push((commit_block.clone(), committed));
                     Ok(())
                 },
             )?;
 
             maybe_high_qc = Some(high_qc);
 
+            // The checkpoint is created in the same transaction as the commit of the epoch-end block so that a crash
+            // cannot leave the epoch ended without a checkpoint for the next genesis block
+            if let Some(epoch) = end_of_epoch {
+                create_epoch_checkpoint(tx, epoch, local_committee_info.shard_group())?;
+            }
+
             valid_block.block().as_last_voted().set(tx)?;
         }
 
@@ -188,7 +209,7 @@ where TConsensusSpec: ConsensusSpec
         Ok(BlockDecision {
             quorum_decision,
             locked_blocks,
-            finalized_transactions,
+            committed_blocks,
             end_of_epoch,
             high_qc,
         })
@@ -295,7 +316,9 @@ where TConsensusSpec: ConsensusSpec
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         block: &Block,
         local_committee_info: &CommitteeInfo,
+        local_committee: &Committee<TConsensusSpec::Addr>,
         can_propose_epoch_end: bool,
+        defer_cross_shard_transactions: bool,
         foreign_committee_infos: &HashMap<ShardGroup, CommitteeInfo>,
         proposed_block_change_set: &mut ProposedBlockChangeSet,
     ) -> Result<(), HotStuffError> {
@@ -304,8 +327,20 @@ where TConsensusSpec: ConsensusSpec
         let mut substate_store =
             PendingSubstateStore::new(tx, *block.parent(), self.config.consensus_constants.num_preshards);
         let mut total_leader_fee = 0;
+        if block.violates_epoch_end_lock(tx)? {
+            warn!(
+                target: LOG_TARGET,
+                "❌ NO VOTE: {} (block {})", NoVoteReason::EpochEndBlockLocked, block
+            );
+            proposed_block_change_set.no_vote(NoVoteReason::EpochEndBlockLocked);
+            return Ok(());
+        }
         let locked_block = LockedBlock::get(tx, block.epoch())?;
-        let mut suspended_in_this_block_count = 0u64;
+        // Transactions prepared in this block are executed with the timestamp and VRF output of this block, unless
+        // another shard group involved in the transaction has already recorded the context that it must be executed
+        // with
+        let execution_context = ExecutionContext::for_block(block);
+        let mut suspended_in_this_block_voting_power = 0u64;
 
         for cmd in block.commands() {
             match cmd {
@@ -316,6 +351,7 @@ where TConsensusSpec: ConsensusSpec
                         &locked_block,
                         atom,
                         local_committee_info,
+                        &execution_context,
                         &mut substate_store,
                         proposed_block_change_set,
                         &mut total_leader_fee,
@@ -331,6 +367,8 @@ where TConsensusSpec: ConsensusSpec
                         &locked_block,
                         atom,
                         local_committee_info,
+                        &execution_context,
+                        defer_cross_shard_transactions,
                         &mut substate_store,
                         proposed_block_change_set,
                     )? {
@@ -354,6 +392,7 @@ where TConsensusSpec: ConsensusSpec
                         &locked_block,
                         atom,
                         local_committee_info,
+                        &execution_context,
                         &mut substate_store,
                         proposed_block_change_set,
                     )? {
@@ -362,9 +401,14 @@ where TConsensusSpec: ConsensusSpec
                     }
                 },
                 Command::SomePrepare(atom) => {
-                    if let Some(reason) =
-                        self.evaluate_some_prepare_command(tx, block, &locked_block, atom, proposed_block_change_set)?
-                    {
+                    if let Some(reason) = self.evaluate_some_prepare_command(
+                        tx,
+                        block,
+                        &locked_block,
+                        atom,
+                        local_committee_info,
+                        proposed_block_change_set,
+                    )? {
                         proposed_block_change_set.no_vote(reason);
                         return Ok(());
                     }
@@ -450,11 +494,15 @@ where TConsensusSpec: ConsensusSpec
                         return Ok(());
                     }
 
-                    let num_suspended = ValidatorConsensusStats::count_number_suspended_nodes(tx)?;
-                    let max_allowed_to_suspend = u64::from(local_committee_info.quorum_threshold())
-                        .saturating_sub(num_suspended)
-                        .saturating_sub(suspended_in_this_block_count);
-                    if max_allowed_to_suspend == 0 {
+                    // The voting power of suspended nodes may not exceed the quorum voting power
+                    let suspended_voting_power =
+                        local_committee.sum_voting_power(&ValidatorConsensusStats::get_suspended_nodes(tx)?);
+                    let voting_power = local_committee.voting_power_of(&atom.public_key);
+                    let max_voting_power_to_suspend = local_committee
+                        .quorum_voting_power()
+                        .saturating_sub(suspended_voting_power)
+                        .saturating_sub(suspended_in_this_block_voting_power);
+                    if voting_power > max_voting_power_to_suspend {
                         warn!(
                             target: LOG_TARGET,
                             "❌ NO VOTE: {}", NoVoteReason::CannotSuspendNodeBelowQuorumThreshold
@@ -463,7 +511,7 @@ where TConsensusSpec: ConsensusSpec
                         proposed_block_change_set.no_vote(NoVoteReason::ShouldNotSuspendNode);
                         return Ok(());
                     }
-                    suspended_in_this_block_count += 1;
+                    suspended_in_this_block_voting_power += voting_power;
 
                     let stats = ValidatorConsensusStats::get_by_public_key(tx, block.epoch(), &atom.public_key)?;
                     if stats.missed_proposals < self.config.consensus_constants.missed_proposal_suspend_threshold {
@@ -509,7 +557,8 @@ where TConsensusSpec: ConsensusSpec
                         proposed_block_change_set.no_vote(NoVoteReason::ShouldNodeResumeNode);
                         return Ok(());
                     }
-                    suspended_in_this_block_count = suspended_in_this_block_count.saturating_sub(1);
+                    suspended_in_this_block_voting_power = suspended_in_this_block_voting_power
+                        .saturating_sub(local_committee.voting_power_of(&atom.public_key));
 
                     info!(
                         target: LOG_TARGET,
@@ -519,7 +568,9 @@ where TConsensusSpec: ConsensusSpec
                     proposed_block_change_set.add_resume_node(atom.public_key.clone());
                 },
                 Command::EndEpoch => {
-                    if !can_propose_epoch_end {
+                    // A validator that has not yet switched epochs still votes to end the epoch once an EndEpoch
+                    // block is locked
+                    if !can_propose_epoch_end && !locked_block.get_block(tx)?.is_epoch_end() {
                         warn!(
                             target: LOG_TARGET,
                             "❌ EpochEvent::End command received for block {} but it is not the next epoch",
@@ -578,11 +629,12 @@ where TConsensusSpec: ConsensusSpec
             return Ok(());
         }
 
-        let (diff, locks) = substate_store.into_parts();
+        let (diff, locks, idempotency_keys) = substate_store.into_parts();
         proposed_block_change_set
             .set_block_diff(diff)
             .set_state_tree_diffs(tree_diffs)
             .set_substate_locks(locks)
+            .set_idempotency_keys(idempotency_keys)
             .set_quorum_decision(QuorumDecision::Accept);
 
         Ok(())
@@ -596,6 +648,7 @@ where TConsensusSpec: ConsensusSpec
         locked_block: &LockedBlock,
         atom: &TransactionAtom,
         local_committee_info: &CommitteeInfo,
+        execution_context: &ExecutionContext,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
         proposed_block_change_set: &mut ProposedBlockChangeSet,
         total_leader_fee: &mut u64,
@@ -634,6 +687,7 @@ where TConsensusSpec: ConsensusSpec
                 substate_store,
                 local_committee_info,
                 block.epoch(),
+                execution_context,
                 *atom.id(),
                 block.id(),
             )
@@ -771,6 +825,8 @@ where TConsensusSpec: ConsensusSpec
         locked_block: &LockedBlock,
         atom: &TransactionAtom,
         local_committee_info: &CommitteeInfo,
+        execution_context: &ExecutionContext,
+        defer_cross_shard_transactions: bool,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
         proposed_block_change_set: &mut ProposedBlockChangeSet,
     ) -> Result<Option<NoVoteReason>, HotStuffError> {
@@ -814,6 +870,7 @@ where TConsensusSpec: ConsensusSpec
                 substate_store,
                 local_committee_info,
                 block.epoch(),
+                execution_context,
                 *atom.id(),
                 block.id(),
             )
@@ -829,6 +886,15 @@ where TConsensusSpec: ConsensusSpec
                 );
                 return Ok(Some(NoVoteReason::MultiShardProposedForLocalOnly));
             },
+            PreparedTransaction::MultiShard(_) if defer_cross_shard_transactions => {
+                warn!(
+                    target: LOG_TARGET,
+                    "❌ Multi-shard transaction {} proposed in block {} while cross-shard transactions are deferred after a committee layout change",
+                    atom.id(),
+                    block,
+                );
+                return Ok(Some(NoVoteReason::CrossShardTransactionDeferred));
+            },
             PreparedTransaction::MultiShard(multishard) => {
                 if multishard.current_decision() != atom.decision {
                     warn!(
@@ -878,6 +944,13 @@ where TConsensusSpec: ConsensusSpec
                         proposed_block_change_set.add_transaction_execution(execution)?;
                     },
                 }
+
+                // Record the context that the transaction is executed with so that other involved shard groups can
+                // execute it with the same context
+                tx_rec
+                    .evidence_mut()
+                    .add_shard_group(local_committee_info.shard_group())
+                    .set_execution_context(*execution_context);
             },
         }
 
@@ -951,6 +1024,28 @@ where TConsensusSpec: ConsensusSpec
             return Ok(Some(NoVoteReason::FeeDisagreement));
         }
 
+        // Foreign shard groups execute the transaction with the execution context in the evidence, so it must be the
+        // context that the local shard group recorded when preparing the transaction
+        let local_execution_context = tx_rec
+            .evidence()
+            .get(&block.shard_group())
+            .and_then(|e| e.execution_context());
+        let proposed_execution_context = atom
+            .evidence
+            .get(&block.shard_group())
+            .and_then(|e| e.execution_context());
+        if local_execution_context != proposed_execution_context {
+            warn!(
+                target: LOG_TARGET,
+                "❌ LocalPrepared execution context disagreement tx {} in block {}. Leader proposed {:?}, we recorded {:?}",
+                tx_rec.transaction_id(),
+                block,
+                proposed_execution_context,
+                local_execution_context,
+            );
+            return Ok(Some(NoVoteReason::ExecutionContextDisagreement));
+        }
+
         tx_rec.set_next_stage(TransactionPoolStage::LocalPrepared)?;
         proposed_block_change_set.set_next_transaction_update(tx_rec)?;
 
@@ -965,6 +1060,7 @@ where TConsensusSpec: ConsensusSpec
         locked_block: &LockedBlock,
         atom: &TransactionAtom,
         local_committee_info: &CommitteeInfo,
+        execution_context: &ExecutionContext,
         substate_store: &mut PendingSubstateStore<TConsensusSpec::StateStore>,
         proposed_block_change_set: &mut ProposedBlockChangeSet,
     ) -> Result<Option<NoVoteReason>, HotStuffError> {
@@ -1037,7 +1133,8 @@ where TConsensusSpec: ConsensusSpec
                 );
                 return Ok(Some(NoVoteReason::NotAllForeignInputPledges));
             }
-            let execution = self.execute_transaction(tx, block.id(), block.epoch(), transaction)?;
+            let execution_context = get_execution_context(&tx_rec, execution_context);
+            let execution = self.execute_transaction(tx, block.id(), block.epoch(), &execution_context, transaction)?;
             let mut execution = execution.into_transaction_execution();
 
             // TODO: check the diff is valid against the provided input evidence (correct locks etc).
@@ -1159,6 +1256,7 @@ where TConsensusSpec: ConsensusSpec
         block: &Block,
         locked_block: &LockedBlock,
         atom: &TransactionAtom,
+        local_committee_info: &CommitteeInfo,
         proposed_block_change_set: &mut ProposedBlockChangeSet,
     ) -> Result<Option<NoVoteReason>, HotStuffError> {
         if atom.decision.is_commit() {
@@ -1185,9 +1283,18 @@ where TConsensusSpec: ConsensusSpec
             return Ok(Some(NoVoteReason::TransactionNotInPool));
         };
 
+        let stalled_abort_reason = match atom.decision {
+            Decision::Abort(
+                reason @ (AbortReason::LockLeaseExpired |
+                AbortReason::CrossShardDeadlock |
+                AbortReason::CommitteeLayoutChanged),
+            ) => Some(reason),
+            _ => None,
+        };
+
         // If the local node would decide SomePrepare too, we should have already ABORTed due to foreign prepare abort
-        // or local input lock conflict
-        if tx_rec.current_decision().is_commit() {
+        // or local input lock conflict, unless the leader is aborting a stalled transaction
+        if stalled_abort_reason.is_none() && tx_rec.current_decision().is_commit() {
             warn!(
                 target: LOG_TARGET,
                 "❌ SomePrepare decision disagreement for transaction {} in block {}. Leader proposed ABORT, we decided COMMIT",
@@ -1227,12 +1334,129 @@ where TConsensusSpec: ConsensusSpec
             return Ok(Some(NoVoteReason::FeeDisagreement));
         }
 
+        if let Some(reason) = stalled_abort_reason {
+            let Some(reject_reason) = self.check_stalled_transaction_abort(
+                tx,
+                block,
+                locked_block,
+                &tx_rec,
+                reason,
+                local_committee_info,
+                proposed_block_change_set,
+            )?
+            else {
+                warn!(
+                    target: LOG_TARGET,
+                    "❌ SomePrepare stalled transaction disagreement for transaction {} in block {}. Leader proposed {}, but the transaction is not stalled (is_ready={})",
+                    tx_rec.transaction_id(),
+                    block,
+                    atom.decision,
+                    tx_rec.is_ready(),
+                );
+                return Ok(Some(NoVoteReason::DecisionDisagreement {
+                    local: tx_rec.current_decision(),
+                    remote: atom.decision,
+                }));
+            };
+
+            info!(
+                target: LOG_TARGET,
+                "⏳ Aborting stalled transaction {} in block {}: {}",
+                tx_rec.transaction_id(),
+                block,
+                reject_reason,
+            );
+            tx_rec.set_local_decision(atom.decision);
+            let mut transaction = TransactionRecord::get(tx, tx_rec.transaction_id())?;
+            transaction.set_abort_reason(reject_reason);
+            let exec = transaction.into_execution().expect("ABORT set above");
+            proposed_block_change_set.add_transaction_execution(exec)?;
+        }
+
         tx_rec.set_next_stage(TransactionPoolStage::SomePrepared)?;
         proposed_block_change_set.set_next_transaction_update(tx_rec)?;
 
         Ok(None)
     }
 
+    /// Checks that a locally prepared transaction that the leader proposed to ABORT is stalled for the given reason,
+    /// returning the reject reason for the ABORT execution if so.
+    fn check_stalled_transaction_abort(
+        &self,
+        tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
+        block: &Block,
+        locked_block: &LockedBlock,
+        tx_rec: &TransactionPoolRecord,
+        reason: AbortReason,
+        local_committee_info: &CommitteeInfo,
+        proposed_block_change_set: &ProposedBlockChangeSet,
+    ) -> Result<Option<RejectReason>, HotStuffError> {
+        // A transaction that is ready to continue has all the pledges it was waiting for
+        if tx_rec.is_ready() {
+            return Ok(None);
+        }
+
+        match reason {
+            AbortReason::CommitteeLayoutChanged => {
+                if !tx_rec.has_shard_groups_outside_layout(local_committee_info) {
+                    return Ok(None);
+                }
+                Ok(Some(RejectReason::CommitteeLayoutChanged(format!(
+                    "shard groups are not in the committee layout of epoch {} (aborted at height {})",
+                    block.epoch(),
+                    block.height()
+                ))))
+            },
+            AbortReason::LockLeaseExpired => {
+                let lease_blocks = self.config.consensus_constants.substate_lock_lease_blocks;
+                if !tx_rec.is_lock_lease_expired(tx, block.height(), lease_blocks)? {
+                    return Ok(None);
+                }
+                Ok(Some(RejectReason::LockLeaseExpired(format!(
+                    "locks were not released within {} blocks (expired at height {})",
+                    lease_blocks,
+                    block.height()
+                ))))
+            },
+            AbortReason::CrossShardDeadlock => {
+                let deadlock_blocks = self.config.consensus_constants.cross_shard_deadlock_blocks;
+                if !tx_rec.is_lock_lease_expired(tx, block.height(), deadlock_blocks)? {
+                    return Ok(None);
+                }
+
+                let mut new_transactions = Vec::new();
+                for rec in self.transaction_pool.get_all(tx)? {
+                    if !rec.committed_stage().is_new() {
+                        continue;
+                    }
+                    let Some(rec) = proposed_block_change_set.get_transaction(
+                        tx,
+                        locked_block,
+                        &block.as_leaf_block(),
+                        rec.transaction_id(),
+                    )?
+                    else {
+                        continue;
+                    };
+                    if rec.current_stage().is_new() {
+                        new_transactions.push(rec);
+                    }
+                }
+
+                let conflicting_transaction =
+                    find_deadlocked_transaction(tx, tx_rec, &new_transactions, local_committee_info)?;
+                Ok(conflicting_transaction.map(|id| {
+                    RejectReason::CrossShardDeadlock(format!(
+                        "deadlocked with transaction {} (aborted at height {})",
+                        id,
+                        block.height()
+                    ))
+                }))
+            },
+            _ => Ok(None),
+        }
+    }
+
     fn evaluate_local_accept_command(
         &self,
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
@@ -1669,6 +1893,7 @@ where TConsensusSpec: ConsensusSpec
         tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
         block_id: &BlockId,
         current_epoch: Epoch,
+        execution_context: &ExecutionContext,
         transaction: TransactionRecord,
     ) -> Result<BlockTransactionExecution, HotStuffError> {
         info!(
@@ -1688,7 +1913,7 @@ where TConsensusSpec: ConsensusSpec
 
         let executed = self
             .transaction_manager
-            .execute(current_epoch, pledged)
+            .execute(current_epoch, execution_context, pledged)
             .map_err(|e| HotStuffError::TransactionExecutorError(e.to_string()))?;
 
         Ok(executed.into_execution().for_block(*block_id))
@@ -1700,8 +1925,10 @@ where TConsensusSpec: ConsensusSpec
         last_executed: &LastExecuted,
         block: &Block,
         local_committee_info: &CommitteeInfo,
+        local_committee_public_keys: &[PublicKey],
     ) -> Result<Vec<TransactionPoolRecord>, HotStuffError> {
-        let committed_transactions = self.finalize_block(tx, block, local_committee_info)?;
+        let committed_transactions =
+            self.finalize_block(tx, block, local_committee_info, local_committee_public_keys)?;
         debug!(
             target: LOG_TARGET,
             "✅ COMMIT block {}, last executed height = {}",
@@ -1750,6 +1977,7 @@ where TConsensusSpec: ConsensusSpec
         tx: &mut <TConsensusSpec::StateStore as StateStore>::WriteTransaction<'_>,
         block: &Block,
         local_committee_info: &CommitteeInfo,
+        local_committee_public_keys: &[PublicKey],
     ) -> Result<Vec<TransactionPoolRecord>, HotStuffError> {
         if block.is_dummy() {
             block.increment_leader_failure_count(
@@ -1783,12 +2011,14 @@ where TConsensusSpec: ConsensusSpec
 
         // NOTE: this must happen before we commit the substate diff because the state transitions use this version
         let pending = block.remove_pending_tree_diff_and_return(tx)?;
-        let mut state_tree = ShardedStateTree::new(tx);
+        let mut state_tree = ShardedStateTree::new(tx).with_retained_versions(self.config.state_tree_retained_versions);
         state_tree.commit_diffs(pending)?;
         let tx = state_tree.into_transaction();
 
         let local_diff = diff.into_filtered(local_committee_info);
         block.commit_diff(tx, local_diff)?;
+        // Idempotency keys reserved in the block are now held by committed transactions
+        tx.transaction_idempotency_keys_set_committed(block.id())?;
 
         let finalized_transactions = self
             .transaction_pool
@@ -1817,7 +2047,9 @@ where TConsensusSpec: ConsensusSpec
             );
         }
 
-        block.justify().update_participation_shares(tx)?;
+        block
+            .justify()
+            .update_participation_shares(tx, local_committee_public_keys)?;
         block.clear_leader_failure_count(tx)?;
 
         Ok(finalized_transactions)
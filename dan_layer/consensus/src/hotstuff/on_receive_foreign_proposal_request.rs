@@ -0,0 +1,129 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashSet;
+
+use log::*;
+use tari_dan_common_types::{committee::CommitteeInfo, optional::Optional, Epoch};
+use tari_dan_storage::{
+    consensus_models::{Block, LeafBlock, LockedBlock, QuorumCertificate, TransactionPool},
+    StateStore,
+};
+use tari_transaction::TransactionId;
+
+use crate::{
+    hotstuff::error::HotStuffError,
+    messages::{ForeignProposalMessage, ForeignProposalRequest, HotstuffMessage},
+    tracing::TraceTimer,
+    traits::{ConsensusSpec, OutboundMessaging},
+};
+
+const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_foreign_proposal_request";
+
+pub struct OnReceiveForeignProposalRequest<TConsensusSpec: ConsensusSpec> {
+    store: TConsensusSpec::StateStore,
+    outbound_messaging: TConsensusSpec::OutboundMessaging,
+    transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
+}
+
+impl<TConsensusSpec> OnReceiveForeignProposalRequest<TConsensusSpec>
+where TConsensusSpec: ConsensusSpec
+{
+    pub fn new(
+        store: TConsensusSpec::StateStore,
+        outbound_messaging: TConsensusSpec::OutboundMessaging,
+        transaction_pool: TransactionPool<TConsensusSpec::StateStore>,
+    ) -> Self {
+        Self {
+            store,
+            outbound_messaging,
+            transaction_pool,
+        }
+    }
+
+    pub async fn handle(
+        &mut self,
+        from: TConsensusSpec::Addr,
+        msg: ForeignProposalRequest,
+        local_committee_info: &CommitteeInfo,
+    ) -> Result<(), HotStuffError> {
+        let _timer = TraceTimer::debug(LOG_TARGET, "OnReceiveForeignProposalRequest");
+        info!(
+            target: LOG_TARGET,
+            "{} requested foreign proposals for {} transaction(s) in epoch {}",
+            from,
+            msg.transactions.len(),
+            msg.epoch
+        );
+
+        let proposals = self.store.with_read_tx(|tx| {
+            self.get_locked_foreign_proposals(tx, msg.epoch, &msg.transactions, local_committee_info)
+        })?;
+
+        if proposals.is_empty() {
+            debug!(
+                target: LOG_TARGET,
+                "No locked foreign proposals found for the transaction(s) requested by {}", from
+            );
+            return Ok(());
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "🌐 Sending {} requested foreign proposal(s) to {}",
+            proposals.len(),
+            from
+        );
+        for proposal in proposals {
+            self.outbound_messaging
+                .send(from.clone(), HotstuffMessage::ForeignProposal(proposal))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the locked blocks that contain the LocalPrepare and/or LocalAccept commands for the requested
+    /// transactions, along with their pledges. Transactions that are no longer in the pool (i.e. finalised) or that
+    /// have not been prepared/accepted by the local shard group are ignored.
+    fn get_locked_foreign_proposals(
+        &self,
+        tx: &<TConsensusSpec::StateStore as StateStore>::ReadTransaction<'_>,
+        epoch: Epoch,
+        transaction_ids: &HashSet<TransactionId>,
+        local_committee_info: &CommitteeInfo,
+    ) -> Result<Vec<ForeignProposalMessage>, HotStuffError> {
+        let leaf = LeafBlock::get(tx, epoch)?;
+        let locked = LockedBlock::get(tx, epoch)?;
+
+        let mut qc_ids = HashSet::new();
+        for transaction_id in transaction_ids {
+            let Some(pool_tx) = self.transaction_pool.get(tx, &leaf, transaction_id).optional()? else {
+                continue;
+            };
+            let Some(evidence) = pool_tx.evidence().get(&local_committee_info.shard_group()) else {
+                continue;
+            };
+            qc_ids.extend(evidence.prepare_qc().copied());
+            qc_ids.extend(evidence.accept_qc().copied());
+        }
+
+        let mut proposals = Vec::with_capacity(qc_ids.len());
+        for qc_id in qc_ids {
+            let justify_qc = QuorumCertificate::get(tx, &qc_id)?;
+            let block = Block::get(tx, justify_qc.block_id())?;
+            // Only locked blocks are sent as foreign proposals
+            if block.height() > locked.height() {
+                continue;
+            }
+            let block_pledge = block.get_block_pledge(tx)?;
+            proposals.push(ForeignProposalMessage {
+                block,
+                justify_qc,
+                block_pledge,
+            });
+        }
+
+        Ok(proposals)
+    }
+}
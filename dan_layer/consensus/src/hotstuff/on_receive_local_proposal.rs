@@ -1,9 +1,13 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
 
 use log::*;
+use tari_crypto::tari_utilities::epoch_time::EpochTime;
 use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
     optional::Optional,
@@ -30,12 +34,18 @@ use tari_epoch_manager::EpochManagerReader;
 use tokio::{sync::broadcast, task};
 
 use crate::{
+    block_validations,
     hotstuff::{
         block_change_set::ProposedBlockChangeSet,
         calculate_dummy_blocks_from_justify,
-        create_epoch_checkpoint,
+        calculate_epoch_leader_seed,
+        calculate_genesis_state_merkle_root,
         error::HotStuffError,
+        get_block_leader_seed,
+        get_median_block_timestamp,
         get_next_block_height_and_leader,
+        get_source_checkpoints,
+        is_cross_shard_preparation_deferred,
         on_ready_to_vote_on_local_block::OnReadyToVoteOnLocalBlock,
         on_receive_foreign_proposal::OnReceiveForeignProposalHandler,
         pacemaker_handle::PaceMakerHandle,
@@ -49,7 +59,6 @@ use crate::{
     traits::{
         hooks::ConsensusHooks,
         ConsensusSpec,
-        LeaderStrategy,
         OutboundMessaging,
         ValidatorSignatureService,
         VoteSignatureService,
@@ -120,6 +129,8 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         msg: ProposalMessage,
     ) -> Result<(), HotStuffError> {
         let _timer = TraceTimer::debug(LOG_TARGET, "OnReceiveLocalProposalHandler");
+        let received_at = Instant::now();
+        self.hooks.on_proposal_received(&msg.block);
 
         // Do not trigger leader failures while processing a proposal.
         // Leader failures will be resumed after the proposal has been processed.
@@ -135,6 +146,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         let ProposalMessage {
             block,
             foreign_proposals,
+            ..
         } = msg;
 
         let maybe_valid_block = self.store.with_read_tx(|tx| {
@@ -219,6 +231,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                 local_committee,
                 valid_block,
                 foreign_committees,
+                received_at,
             )
             .await;
 
@@ -244,10 +257,18 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         local_committee: &Committee<TConsensusSpec::Addr>,
         valid_block: ValidBlock,
         foreign_committees: HashMap<ShardGroup, CommitteeInfo>,
+        received_at: Instant,
     ) -> Result<(), HotStuffError> {
         let em_epoch = self.epoch_manager.current_epoch().await?;
         let can_propose_epoch_end = em_epoch > current_epoch;
         let is_epoch_end = valid_block.block().is_epoch_end();
+        let defer_cross_shard_transactions = is_cross_shard_preparation_deferred(
+            &self.epoch_manager,
+            &self.config.consensus_constants,
+            valid_block.epoch(),
+            valid_block.height(),
+        )
+        .await?;
 
         let mut on_ready_to_vote_on_local_block = self.on_ready_to_vote_on_local_block.clone();
 
@@ -263,6 +284,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                 .unwrap_or_else(|| ProposedBlockChangeSet::new(valid_block.block().as_leaf_block()));
 
             let store = self.store.clone();
+            let local_committee = local_committee.clone();
 
             // Task closure
             move || {
@@ -271,7 +293,9 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                         tx,
                         &valid_block,
                         &local_committee_info,
+                        &local_committee,
                         can_propose_epoch_end,
+                        defer_cross_shard_transactions,
                         foreign_committees,
                         &mut change_set,
                     )?;
@@ -289,10 +313,13 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
         if block_decision.end_of_epoch.is_none() {
             if let Some(decision) = block_decision.quorum_decision {
                 let (next_height, next_leader_addr, num_skipped) = self.store.with_read_tx(|tx| {
+                    // The next proposal justifies this block, so the leader is selected using its seed
+                    let leader_seed = get_block_leader_seed(tx, valid_block.epoch(), valid_block.block())?;
                     get_next_block_height_and_leader(
                         tx,
                         local_committee,
                         &self.leader_strategy,
+                        &leader_seed,
                         valid_block.id(),
                         valid_block.height(),
                     )
@@ -315,6 +342,8 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                     )
                     .await?;
                 }
+                self.hooks
+                    .on_vote_sent(valid_block.block(), decision, received_at.elapsed());
             } else {
                 self.pacemaker.resume_leader_failure().await?;
             }
@@ -322,8 +351,15 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
 
         self.hooks
             .on_local_block_decide(&valid_block, block_decision.quorum_decision);
-        for t in block_decision.finalized_transactions.into_iter().flatten() {
-            self.hooks.on_transaction_finalized(&t.into_current_transaction_atom());
+        let now = EpochTime::now().as_u64();
+        for (block, finalized_transactions) in block_decision.committed_blocks {
+            let commit_latency = Duration::from_secs(now.saturating_sub(block.timestamp()));
+            self.hooks
+                .on_block_committed(&block, finalized_transactions.len(), commit_latency);
+            for t in finalized_transactions {
+                self.hooks
+                    .on_transaction_finalized(&t.into_current_transaction_atom(), &block);
+            }
         }
         self.propose_newly_locked_blocks(local_committee_info, block_decision.locked_blocks);
 
@@ -337,17 +373,43 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                 let next_shard_group = vn
                     .shard_key
                     .to_shard_group(self.config.consensus_constants.num_preshards, num_committees);
+                let shard_group = valid_block.block().shard_group();
                 self.store.with_write_tx(|tx| {
-                    // Generate checkpoint
-                    create_epoch_checkpoint(tx, epoch, local_committee_info.shard_group())?;
+                    cleanup_epoch(tx, epoch)?;
+
+                    if !next_shard_group.is_subset_of(&shard_group) {
+                        // The shard group was merged with (part of) another shard group. The genesis block is created
+                        // once the state of the shards handed over by the other shard group has been synced.
+                        info!(
+                            target: LOG_TARGET,
+                            "🔀 {shard_group} merges into {next_shard_group} in epoch {next_epoch}. Deferring genesis \
+                             until state handover is complete",
+                        );
+                        return Ok(());
+                    }
+
+                    if next_shard_group != shard_group {
+                        info!(
+                            target: LOG_TARGET,
+                            "🔀 {shard_group} splits into {next_shard_group} in epoch {next_epoch}",
+                        );
+                    }
+                    // The checkpoint was created when the epoch-end block was committed. This derives the same genesis
+                    // as members that join the shard group after syncing the checkpoint.
+                    let checkpoints = get_source_checkpoints(&**tx, epoch, &[shard_group])?;
+                    let state_merkle_root =
+                        calculate_genesis_state_merkle_root(next_epoch, next_shard_group, &checkpoints)?;
 
                     // Create the next genesis
                     let mut genesis = Block::genesis(
                         self.config.network,
                         next_epoch,
                         next_shard_group,
-                        *valid_block.block().state_merkle_root(),
+                        self.config.consensus_constants.num_preshards,
+                        state_merkle_root,
                         self.config.sidechain_id.clone(),
+                        calculate_epoch_leader_seed(next_epoch, &checkpoints),
+                        self.config.consensus_constants.genesis_hash,
                     );
                     info!(target: LOG_TARGET, "⭐️ Creating new genesis block {genesis}");
                     genesis.justify().insert(tx)?;
@@ -360,8 +422,6 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                     genesis.as_last_voted().set(tx)?;
                     genesis.justify().as_high_qc().set(tx)?;
 
-                    cleanup_epoch(tx, epoch)?;
-
                     Ok::<_, HotStuffError>(())
                 })?;
 
@@ -410,12 +470,13 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
             signature: vote.signature.clone(),
         };
 
+        // Record the vote before it is sent so that a vote that was sent is never lost if the node crashes
+        self.store.with_write_tx(|tx| last_sent_vote.set(tx))?;
+
         self.outbound_messaging
             .send(leader.clone(), HotstuffMessage::Vote(vote))
             .await?;
 
-        self.store.with_write_tx(|tx| last_sent_vote.set(tx))?;
-
         Ok(())
     }
 
@@ -459,12 +520,13 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
             last_vote: Some(vote),
         };
 
+        // Record the vote before it is sent so that a vote that was sent is never lost if the node crashes
+        self.store.with_write_tx(|tx| last_sent_vote.set(tx))?;
+
         self.outbound_messaging
             .send(leader.clone(), HotstuffMessage::NewView(message))
             .await?;
 
-        self.store.with_write_tx(|tx| last_sent_vote.set(tx))?;
-
         Ok(())
     }
 
@@ -479,7 +541,6 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
 
         task::spawn(propose_newly_locked_blocks_task::<TConsensusSpec>(
             self.epoch_manager.clone(),
-            self.leader_strategy.clone(),
             self.outbound_messaging.clone(),
             self.store.clone(),
             self.config.consensus_constants.num_preshards,
@@ -705,6 +766,17 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
             .into());
         };
 
+        // The candidate either extends the justify block or dummy blocks that extend it, so the leaders for the
+        // candidate and any dummy blocks are selected using the seed of the justify block
+        let leader_seed = get_block_leader_seed(tx, candidate_block.epoch(), &justify_block)?;
+        block_validations::check_proposed_by_leader(
+            &self.leader_strategy,
+            local_committee,
+            &leader_seed,
+            &candidate_block,
+        )?;
+        block_validations::check_vrf_proof(&candidate_block, &leader_seed)?;
+
         if candidate_block.justifies_parent() && !candidate_block.parent_exists(tx)? {
             return Err(ProposalValidationError::ParentNotFound {
                 proposed_by: candidate_block.proposed_by().to_string(),
@@ -735,6 +807,19 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
             .into());
         }
 
+        let median_timestamp = get_median_block_timestamp(
+            tx,
+            &justify_block,
+            self.config.consensus_constants.block_timestamp_median_window,
+        )?;
+        block_validations::check_timestamp(
+            &candidate_block,
+            &justify_block,
+            median_timestamp,
+            EpochTime::now().as_u64(),
+            &self.config.consensus_constants,
+        )?;
+
         // TODO: this is broken
         // self.check_foreign_indexes(
         //     tx,
@@ -752,6 +837,7 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
                 &candidate_block,
                 &justify_block,
                 &self.leader_strategy,
+                &leader_seed,
                 local_committee,
             );
 
@@ -796,7 +882,6 @@ impl<TConsensusSpec: ConsensusSpec> OnReceiveLocalProposalHandler<TConsensusSpec
 
 async fn propose_newly_locked_blocks_task<TConsensusSpec: ConsensusSpec>(
     epoch_manager: TConsensusSpec::EpochManager,
-    leader_strategy: TConsensusSpec::LeaderStrategy,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
     store: TConsensusSpec::StateStore,
     num_preshards: NumPreshards,
@@ -806,7 +891,6 @@ async fn propose_newly_locked_blocks_task<TConsensusSpec: ConsensusSpec>(
     let _timer = TraceTimer::debug(LOG_TARGET, "ProposeNewlyLockedBlocks").with_iterations(blocks.len());
     if let Err(err) = propose_newly_locked_blocks_task_inner::<TConsensusSpec>(
         epoch_manager,
-        leader_strategy,
         outbound_messaging,
         store,
         num_preshards,
@@ -821,7 +905,6 @@ async fn propose_newly_locked_blocks_task<TConsensusSpec: ConsensusSpec>(
 
 async fn propose_newly_locked_blocks_task_inner<TConsensusSpec: ConsensusSpec>(
     epoch_manager: TConsensusSpec::EpochManager,
-    leader_strategy: TConsensusSpec::LeaderStrategy,
     mut outbound_messaging: TConsensusSpec::OutboundMessaging,
     store: TConsensusSpec::StateStore,
     num_preshards: NumPreshards,
@@ -841,7 +924,10 @@ async fn propose_newly_locked_blocks_task_inner<TConsensusSpec: ConsensusSpec>(
         let local_committee = epoch_manager
             .get_committee_by_validator_public_key(block.epoch(), block.proposed_by().clone())
             .await?;
-        let leader_index = leader_strategy.calculate_leader(&local_committee, block.height()) as usize;
+        let leader_index = local_committee
+            .public_keys()
+            .position(|pk| pk == block.proposed_by())
+            .ok_or_else(|| HotStuffError::InvariantError("Block proposer not found in local committee".to_string()))?;
         let my_index = local_committee
             .addresses()
             .position(|addr| *addr == our_vn.address)
@@ -933,8 +1019,8 @@ async fn broadcast_foreign_proposal_if_required<TConsensusSpec: ConsensusSpec>(
         );
 
         // TODO: This message can be much larger than the default maximum for gossipsub (16KiB) For now, the limit is
-        //       increased. We also need to allow committees that are stuck on LocalAccept/LocalPrepare to request the
-        //       foreign proposal through messaging.
+        //       increased. Committees that miss this message request the foreign proposal directly (see
+        //       ForeignProposalPrefetcher).
         outbound_messaging
             .multicast(
                 shard_group,
@@ -1,14 +1,16 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::collections::HashSet;
+
 use log::*;
 use tari_dan_common_types::{committee::CommitteeInfo, Epoch};
 use tari_dan_storage::{
-    consensus_models::{TransactionPool, TransactionRecord},
+    consensus_models::{Block, TransactionPool, TransactionRecord},
     StateStore,
 };
 use tari_engine_types::commit_result::RejectReason;
-use tari_transaction::TransactionId;
+use tari_transaction::{Transaction, TransactionId};
 use tokio::sync::mpsc;
 
 use crate::{
@@ -53,8 +55,35 @@ where TConsensusSpec: ConsensusSpec
     ) -> Result<(), HotStuffError> {
         let _timer = TraceTimer::debug(LOG_TARGET, "OnReceiveRequestedTransactions");
         info!(target: LOG_TARGET, "Receiving {} requested transactions for block {} from {:?}", msg.transactions.len(), msg.block_id, from);
+        self.insert_transactions(current_epoch, msg.transactions, local_committee_info)
+    }
+
+    /// Adds the transactions attached to a full proposal. Only transactions that are included in the block are added.
+    pub fn process_proposed(
+        &mut self,
+        current_epoch: Epoch,
+        block: &Block,
+        transactions: Vec<Transaction>,
+        local_committee_info: &CommitteeInfo,
+    ) -> Result<(), HotStuffError> {
+        let _timer = TraceTimer::debug(LOG_TARGET, "OnReceiveProposedTransactions");
+        let block_transaction_ids = block.all_transaction_ids().collect::<HashSet<_>>();
+        let transactions = transactions
+            .into_iter()
+            .filter(|t| block_transaction_ids.contains(t.id()))
+            .collect::<Vec<_>>();
+        info!(target: LOG_TARGET, "Receiving {} transactions attached to proposal {}", transactions.len(), block);
+        self.insert_transactions(current_epoch, transactions, local_committee_info)
+    }
+
+    fn insert_transactions(
+        &mut self,
+        current_epoch: Epoch,
+        transactions: Vec<Transaction>,
+        local_committee_info: &CommitteeInfo,
+    ) -> Result<(), HotStuffError> {
         self.store.with_write_tx(|tx| {
-            let recs = TransactionRecord::get_any_or_build(&**tx, msg.transactions)?;
+            let recs = TransactionRecord::get_any_or_build(&**tx, transactions)?;
             let mut batch = Vec::with_capacity(recs.len());
             for transaction in recs {
                 if let Some(transaction_and_is_ready) =
@@ -73,7 +102,7 @@ where TConsensusSpec: ConsensusSpec
             self.tx_missing_transactions
                 .send(batch.iter().map(|(t, _)| *t.id()).collect())
                 .map_err(|_| HotStuffError::InternalChannelClosed {
-                    context: "process_requested",
+                    context: "insert_transactions",
                 })?;
             Ok(())
         })
@@ -11,13 +11,13 @@ use tari_dan_common_types::{
     NodeHeight,
 };
 use tari_dan_storage::{
-    consensus_models::{Block, BlockId, LeafBlock, QuorumCertificate},
+    consensus_models::{Block, BlockId, HighQc, LeafBlock, QuorumCertificate},
     StateStore,
 };
 
 use super::vote_collector::VoteCollector;
 use crate::{
-    hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle},
+    hotstuff::{error::HotStuffError, get_block_leader_seed, pacemaker_handle::PaceMakerHandle},
     messages::NewViewMessage,
     tracing::TraceTimer,
     traits::{ConsensusSpec, LeaderStrategy},
@@ -58,12 +58,15 @@ where TConsensusSpec: ConsensusSpec
         self.newview_message_counts.clear();
     }
 
+    /// Records the NEWVIEW and returns the voting power of the local committee members that sent a NEWVIEW for the
+    /// height and high QC.
     fn collect_new_views(
         &mut self,
         from: TConsensusSpec::Addr,
         new_height: NodeHeight,
         high_qc: &QuorumCertificate,
-    ) -> usize {
+        local_committee: &Committee<TConsensusSpec::Addr>,
+    ) -> u64 {
         self.newview_message_counts
             .retain(|(height, _), _| *height >= new_height);
         if self.newview_message_counts.len() <= 10 && self.newview_message_counts.capacity() > 10 {
@@ -74,7 +77,9 @@ where TConsensusSpec: ConsensusSpec
             .entry((new_height, *high_qc.block_id()))
             .or_default();
         entry.insert(from);
-        entry.len()
+        entry.iter().fold(0u64, |acc, addr| {
+            acc.saturating_add(local_committee.voting_power_of_member(addr))
+        })
     }
 
     #[allow(clippy::too_many_lines)]
@@ -103,7 +108,7 @@ where TConsensusSpec: ConsensusSpec
             return Ok(());
         }
 
-        self.store.with_read_tx(|tx| {
+        let leader_seed = self.store.with_read_tx(|tx| {
             self.validate_qc(&high_qc)?;
 
             if !Block::record_exists(tx, high_qc.block_id())? {
@@ -118,14 +123,23 @@ where TConsensusSpec: ConsensusSpec
                 });
             }
 
-            Ok(())
+            // The next proposal is justified by the higher of our high QC and the QC in the NEWVIEW, so the leader is
+            // selected using the seed of that block. This is the QC that the sender used to select us as the leader,
+            // unless we have a higher one.
+            let local_high_qc = HighQc::get(tx, current_epoch)?;
+            let justify_block = if high_qc.block_height() > local_high_qc.block_height() {
+                high_qc.get_block(tx)?
+            } else {
+                local_high_qc.get_quorum_certificate(tx)?.get_block(tx)?
+            };
+            Ok(get_block_leader_seed(tx, current_epoch, &justify_block)?)
         })?;
 
         // Check if we are the leader for the view after new_height. We'll set our local view height to the new_height
         // if quorum is reached and propose a block at new_height + 1.
         let (leader, _) = self
             .leader_strategy
-            .get_leader_for_next_height(local_committee, new_height);
+            .get_leader_for_next_height(local_committee, &leader_seed, new_height);
 
         if *leader != self.local_validator_addr {
             warn!(target: LOG_TARGET, "❌ NEWVIEW failed, leader is {} at {}. Our address is {}", leader, new_height, self.local_validator_addr);
@@ -134,12 +148,12 @@ where TConsensusSpec: ConsensusSpec
 
         // Are nodes requesting to create more than the minimum number of dummy blocks?
         let height_diff = high_qc.block_height().saturating_sub(new_height).as_u64();
-        if height_diff > u64::from(local_committee_info.quorum_threshold()) {
+        if height_diff > local_committee.quorum_threshold() as u64 {
             warn!(
                 target: LOG_TARGET,
                 "❌ Validator {from} sent NEWVIEW that attempts to create a larger than necessary number of dummy blocks. Expected requested {} < quorum threshold {}",
                 height_diff,
-                local_committee_info.quorum_threshold()
+                local_committee.quorum_threshold()
             );
             return Ok(());
         }
@@ -155,27 +169,29 @@ where TConsensusSpec: ConsensusSpec
         }
 
         // Take note of unique NEWVIEWs so that we can count them
-        let newview_count = self.collect_new_views(from, new_height, &high_qc);
+        let sender_voting_power = local_committee.voting_power_of_member(&from);
+        let newview_voting_power = self.collect_new_views(from, new_height, &high_qc, local_committee);
 
         let latest_high_qc = self.store.with_write_tx(|tx| {
             high_qc.save(tx)?;
             high_qc.update_high_qc(tx)
         })?;
 
-        let threshold = local_committee_info.quorum_threshold() as usize;
+        let threshold = local_committee.quorum_voting_power();
 
         info!(
             target: LOG_TARGET,
             "🌟 Received NEWVIEW (QUORUM: {}/{}) {} with high {}",
-            newview_count,
+            newview_voting_power,
             threshold,
             new_height,
             latest_high_qc,
         );
         // Once we have received enough (quorum) NEWVIEWS, we can create the dummy block(s) and propose the next block.
-        // Any subsequent NEWVIEWs for this height/view are ignored.
-        if newview_count == threshold {
-            info!(target: LOG_TARGET, "🌟✅ NEWVIEW height {} (high_qc: {}) has reached quorum ({}/{})", new_height, latest_high_qc, newview_count, threshold);
+        // Any subsequent NEWVIEWs for this height/view are ignored i.e. only the NEWVIEW that reaches the threshold
+        // proceeds.
+        if newview_voting_power >= threshold && newview_voting_power.saturating_sub(sender_voting_power) < threshold {
+            info!(target: LOG_TARGET, "🌟✅ NEWVIEW height {} (high_qc: {}) has reached quorum ({}/{})", new_height, latest_high_qc, newview_voting_power, threshold);
 
             self.pacemaker
                 .update_view(current_epoch, new_height, latest_high_qc.block_height())
@@ -5,7 +5,7 @@ use log::*;
 use tari_dan_storage::{consensus_models::TransactionRecord, StateStore};
 
 use crate::{
-    hotstuff::error::HotStuffError,
+    hotstuff::{error::HotStuffError, ProposalMissRate},
     messages::{HotstuffMessage, MissingTransactionsRequest, MissingTransactionsResponse},
     tracing::TraceTimer,
     traits::{ConsensusSpec, OutboundMessaging},
@@ -16,15 +16,21 @@ const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_request_mis
 pub struct OnReceiveRequestMissingTransactions<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
     outbound_messaging: TConsensusSpec::OutboundMessaging,
+    miss_rate: ProposalMissRate,
 }
 
 impl<TConsensusSpec> OnReceiveRequestMissingTransactions<TConsensusSpec>
 where TConsensusSpec: ConsensusSpec
 {
-    pub fn new(store: TConsensusSpec::StateStore, outbound_messaging: TConsensusSpec::OutboundMessaging) -> Self {
+    pub fn new(
+        store: TConsensusSpec::StateStore,
+        outbound_messaging: TConsensusSpec::OutboundMessaging,
+        miss_rate: ProposalMissRate,
+    ) -> Self {
         Self {
             store,
             outbound_messaging,
+            miss_rate,
         }
     }
 
@@ -44,6 +50,7 @@ where TConsensusSpec: ConsensusSpec
                 "Some requested transaction(s) not found: {}", missing.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
             )
         }
+        self.miss_rate.record_missed(txs.len());
 
         self.outbound_messaging
             .send(
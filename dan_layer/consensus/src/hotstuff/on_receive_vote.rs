@@ -1,48 +1,72 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use log::*;
 use tari_dan_common_types::{committee::CommitteeInfo, Epoch};
+use tari_dan_storage::consensus_models::BlockId;
 
 use super::vote_collector::VoteCollector;
 use crate::{
     hotstuff::{error::HotStuffError, pacemaker_handle::PaceMakerHandle},
     messages::VoteMessage,
     tracing::TraceTimer,
-    traits::ConsensusSpec,
+    traits::{hooks::ConsensusHooks, ConsensusSpec},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::on_receive_vote";
 
+/// Vote timings for blocks that do not reach quorum within this time are discarded
+const MAX_VOTE_COLLECTION_TIME: Duration = Duration::from_secs(5 * 60);
+
 pub struct OnReceiveVoteHandler<TConsensusSpec: ConsensusSpec> {
     pacemaker: PaceMakerHandle,
     vote_collector: VoteCollector<TConsensusSpec>,
+    hooks: TConsensusSpec::Hooks,
+    first_vote_received_at: HashMap<BlockId, Instant>,
 }
 
 impl<TConsensusSpec> OnReceiveVoteHandler<TConsensusSpec>
 where TConsensusSpec: ConsensusSpec
 {
-    pub fn new(pacemaker: PaceMakerHandle, vote_collector: VoteCollector<TConsensusSpec>) -> Self {
+    pub fn new(
+        pacemaker: PaceMakerHandle,
+        vote_collector: VoteCollector<TConsensusSpec>,
+        hooks: TConsensusSpec::Hooks,
+    ) -> Self {
         Self {
             vote_collector,
             pacemaker,
+            hooks,
+            first_vote_received_at: HashMap::new(),
         }
     }
 
     pub async fn handle(
-        &self,
+        &mut self,
         from: TConsensusSpec::Addr,
         current_epoch: Epoch,
         message: VoteMessage,
         local_committee_info: &CommitteeInfo,
     ) -> Result<(), HotStuffError> {
         let _timer = TraceTimer::info(LOG_TARGET, "OnReceiveVote");
+        let block_id = message.block_id;
+        let first_vote_received_at = *self.first_vote_received_at.entry(block_id).or_insert_with(Instant::now);
         match self
             .vote_collector
             .check_and_collect_vote(from, current_epoch, message, local_committee_info)
             .await
         {
-            Ok(Some((_, high_qc))) => {
+            Ok(Some((qc, high_qc))) => {
+                self.first_vote_received_at.remove(&block_id);
+                self.first_vote_received_at
+                    .retain(|_, received_at| received_at.elapsed() < MAX_VOTE_COLLECTION_TIME);
+                self.hooks.on_qc_formed(&qc, first_vote_received_at.elapsed());
+
                 // HighQc from votes will trigger a view-change in the proposer
                 // self.pacemaker
                 //     .update_view(high_qc.epoch(), high_qc.block_height(), high_qc.block_height())
@@ -0,0 +1,141 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+/// The block capacity used by a foreign proposal command. Each foreign proposal is "heavier" than a transaction
+/// command.
+pub const FOREIGN_PROPOSAL_WEIGHT: usize = 4;
+
+/// The share (1/n) of the block capacity reserved for each of the burnt UTXO mint and node suspend/resume lanes.
+const RESERVED_SHARE_DIVISOR: usize = 8;
+
+/// Allocates the capacity of a proposed block to the command lanes.
+///
+/// Protocol-critical (system) commands are allocated capacity ahead of user transactions, in the order foreign
+/// proposals, burnt UTXO mints (MintConfidentialOutput) and then node suspends/resumes. User transactions receive the
+/// remaining capacity, so a saturated mempool cannot starve system commands. EndEpoch is always proposed in a block on
+/// its own.
+///
+/// Foreign proposals are plentiful and heavy, so a share of the block is reserved for each of the mint and
+/// suspend/resume lanes that foreign proposals may not use. Once a lane has been allocated, any of its reserved
+/// capacity that was not used is released to the following lanes.
+#[derive(Debug, Clone)]
+pub struct ProposalCapacity {
+    remaining: usize,
+    reserved_mints: usize,
+    reserved_node_changes: usize,
+}
+
+impl ProposalCapacity {
+    pub fn new(max_block_size: usize) -> Self {
+        let reserved = max_block_size / RESERVED_SHARE_DIVISOR;
+        Self {
+            remaining: max_block_size,
+            reserved_mints: reserved,
+            reserved_node_changes: reserved,
+        }
+    }
+
+    /// The maximum number of foreign proposals that may be proposed.
+    pub fn foreign_proposal_limit(&self) -> usize {
+        self.remaining
+            .saturating_sub(self.reserved_mints + self.reserved_node_changes) /
+            FOREIGN_PROPOSAL_WEIGHT
+    }
+
+    pub fn use_foreign_proposals(&mut self, num_foreign_proposals: usize) {
+        self.use_capacity(num_foreign_proposals * FOREIGN_PROPOSAL_WEIGHT);
+    }
+
+    /// The maximum number of burnt UTXO mints that may be proposed.
+    pub fn mint_limit(&self) -> usize {
+        self.remaining.saturating_sub(self.reserved_node_changes)
+    }
+
+    pub fn use_mints(&mut self, num_mints: usize) {
+        self.reserved_mints = 0;
+        self.use_capacity(num_mints);
+    }
+
+    /// The maximum number of node suspends/resumes that may be proposed.
+    pub fn node_change_limit(&self) -> usize {
+        self.remaining.saturating_sub(self.reserved_mints)
+    }
+
+    pub fn use_node_changes(&mut self, num_node_changes: usize) {
+        self.reserved_node_changes = 0;
+        self.use_capacity(num_node_changes);
+    }
+
+    /// The maximum number of transaction commands that may be proposed. Capacity that is still reserved for system
+    /// commands is excluded.
+    pub fn transaction_limit(&self) -> usize {
+        self.remaining
+            .saturating_sub(self.reserved_mints + self.reserved_node_changes)
+    }
+
+    pub fn use_transactions(&mut self, num_transactions: usize) {
+        self.use_capacity(num_transactions);
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn use_capacity(&mut self, amount: usize) {
+        self.remaining = self.remaining.saturating_sub(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn foreign_proposals_cannot_starve_other_system_commands() {
+        let mut capacity = ProposalCapacity::new(500);
+        let limit = capacity.foreign_proposal_limit();
+        assert_eq!(limit, (500 - 62 - 62) / FOREIGN_PROPOSAL_WEIGHT);
+        capacity.use_foreign_proposals(limit);
+
+        assert_eq!(capacity.mint_limit(), 62);
+        capacity.use_mints(62);
+        assert_eq!(capacity.node_change_limit(), 62);
+        capacity.use_node_changes(62);
+
+        assert_eq!(capacity.transaction_limit(), 0);
+    }
+
+    #[test]
+    fn system_commands_are_allocated_before_transactions() {
+        let mut capacity = ProposalCapacity::new(100);
+        capacity.use_foreign_proposals(5);
+        // Mints may use the reserved capacity and all capacity not reserved for node changes
+        assert_eq!(capacity.mint_limit(), 100 - 20 - 12);
+        capacity.use_mints(50);
+        assert_eq!(capacity.node_change_limit(), 30);
+        capacity.use_node_changes(10);
+
+        assert_eq!(capacity.transaction_limit(), 20);
+        capacity.use_transactions(20);
+        assert_eq!(capacity.remaining(), 0);
+    }
+
+    #[test]
+    fn unused_reserved_capacity_is_released_to_transactions() {
+        let mut capacity = ProposalCapacity::new(100);
+        // Transactions cannot use reserved capacity before the system lanes are allocated
+        assert_eq!(capacity.transaction_limit(), 100 - 12 - 12);
+
+        capacity.use_foreign_proposals(0);
+        capacity.use_mints(0);
+        capacity.use_node_changes(0);
+        assert_eq!(capacity.transaction_limit(), 100);
+    }
+
+    #[test]
+    fn small_blocks_have_no_reserved_capacity() {
+        let capacity = ProposalCapacity::new(4);
+        assert_eq!(capacity.foreign_proposal_limit(), 1);
+        assert_eq!(capacity.transaction_limit(), 4);
+    }
+}
@@ -0,0 +1,118 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::{atomic, atomic::AtomicU64, Arc};
+
+/// Once this many transactions have been proposed to peers, both counters are halved so that the miss rate reflects
+/// recent proposals.
+const WINDOW_SIZE: u64 = 10_000;
+
+/// Tracks the fraction of transactions in this node's proposals that peers had to request because they did not have
+/// them. Proposals are compact (transaction ids only) by default and the proposer falls back to attaching full
+/// transactions when the miss rate is high.
+#[derive(Debug, Clone, Default)]
+pub struct ProposalMissRate {
+    proposed: Arc<AtomicU64>,
+    missed: Arc<AtomicU64>,
+}
+
+impl ProposalMissRate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that a proposal containing `num_transactions` new transactions was sent to `num_peers` peers.
+    pub(crate) fn record_proposed(&self, num_transactions: usize, num_peers: usize) {
+        let num_proposed = (num_transactions * num_peers) as u64;
+        if num_proposed == 0 {
+            return;
+        }
+        let proposed = self.proposed.fetch_add(num_proposed, atomic::Ordering::SeqCst) + num_proposed;
+        if proposed > WINDOW_SIZE {
+            self.proposed.store(proposed / 2, atomic::Ordering::SeqCst);
+            self.missed
+                .store(self.missed.load(atomic::Ordering::SeqCst) / 2, atomic::Ordering::SeqCst);
+        }
+    }
+
+    /// Records that a peer requested `num_transactions` transactions that it was missing.
+    pub(crate) fn record_missed(&self, num_transactions: usize) {
+        self.missed.fetch_add(num_transactions as u64, atomic::Ordering::SeqCst);
+    }
+
+    /// Returns the percentage of proposed transactions that were requested by peers.
+    pub fn miss_rate_percent(&self) -> u64 {
+        let proposed = self.proposed.load(atomic::Ordering::SeqCst);
+        if proposed == 0 {
+            return 0;
+        }
+        let missed = self.missed.load(atomic::Ordering::SeqCst);
+        (missed * 100 / proposed).min(100)
+    }
+
+    /// Returns true if proposals should include full transactions given the threshold percentage. A threshold of 0
+    /// disables full proposals.
+    pub fn should_propose_full(&self, threshold_percent: u64) -> bool {
+        threshold_percent > 0 && self.miss_rate_percent() >= threshold_percent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_proposes_full_transactions_from_the_threshold() {
+        let miss_rate = ProposalMissRate::new();
+        assert!(!miss_rate.should_propose_full(20));
+
+        // 10 transactions sent to 10 peers
+        miss_rate.record_proposed(10, 10);
+        miss_rate.record_missed(19);
+        assert_eq!(miss_rate.miss_rate_percent(), 19);
+        assert!(!miss_rate.should_propose_full(20));
+
+        miss_rate.record_missed(1);
+        assert_eq!(miss_rate.miss_rate_percent(), 20);
+        assert!(miss_rate.should_propose_full(20));
+        // A threshold of 0 disables full proposals
+        assert!(!miss_rate.should_propose_full(0));
+    }
+
+    #[test]
+    fn it_caps_the_miss_rate_at_100_percent() {
+        let miss_rate = ProposalMissRate::new();
+        miss_rate.record_proposed(1, 1);
+        // Peers may request the same transaction more than once
+        miss_rate.record_missed(3);
+        assert_eq!(miss_rate.miss_rate_percent(), 100);
+    }
+
+    #[test]
+    fn it_ignores_proposals_without_transactions_or_peers() {
+        let miss_rate = ProposalMissRate::new();
+        miss_rate.record_proposed(0, 10);
+        miss_rate.record_proposed(10, 0);
+        miss_rate.record_missed(1);
+        assert_eq!(miss_rate.miss_rate_percent(), 0);
+    }
+
+    #[test]
+    fn it_halves_the_counters_once_the_window_is_exceeded() {
+        let miss_rate = ProposalMissRate::new();
+        miss_rate.record_proposed(WINDOW_SIZE as usize, 1);
+        miss_rate.record_missed(5_000);
+        // The window is only exceeded after it is full
+        assert_eq!(miss_rate.proposed.load(atomic::Ordering::SeqCst), WINDOW_SIZE);
+        assert_eq!(miss_rate.miss_rate_percent(), 50);
+
+        miss_rate.record_proposed(2, 1);
+        assert_eq!(miss_rate.proposed.load(atomic::Ordering::SeqCst), (WINDOW_SIZE + 2) / 2);
+        assert_eq!(miss_rate.missed.load(atomic::Ordering::SeqCst), 2_500);
+
+        // Older proposals and misses carry half the weight of new ones
+        miss_rate.record_proposed(4_999, 1);
+        assert_eq!(miss_rate.proposed.load(atomic::Ordering::SeqCst), WINDOW_SIZE);
+        assert_eq!(miss_rate.miss_rate_percent(), 25);
+    }
+}
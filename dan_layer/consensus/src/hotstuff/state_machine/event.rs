@@ -11,6 +11,7 @@ use crate::hotstuff::HotStuffError;
 pub enum ConsensusStateEvent {
     RegisteredForEpoch { epoch: Epoch },
     NotRegisteredForEpoch { epoch: Epoch },
+    CommitteeLayoutChanged { epoch: Epoch },
     NeedSync,
     SyncComplete,
     Ready,
@@ -26,6 +27,7 @@ impl Display for ConsensusStateEvent {
         match self {
             RegisteredForEpoch { epoch } => write!(f, "Registered for epoch {}", epoch),
             NotRegisteredForEpoch { epoch } => write!(f, "Not registered for epoch {}", epoch),
+            CommitteeLayoutChanged { epoch } => write!(f, "Committee layout changed in epoch {}", epoch),
             NeedSync => write!(f, "Behind peers"),
             SyncComplete => write!(f, "Sync complete"),
             Ready => write!(f, "Ready"),
@@ -39,6 +39,10 @@ where TSpec: ConsensusSpec
                 info!(target: LOG_TARGET, "Not registered for current epoch ({err})");
                 Ok(ConsensusStateEvent::NotRegisteredForEpoch { epoch })
             },
+            Err(ref err @ HotStuffError::CommitteeLayoutChanged { epoch, .. }) => {
+                info!(target: LOG_TARGET, "🔀 Syncing gained shards ({err})");
+                Ok(ConsensusStateEvent::CommitteeLayoutChanged { epoch })
+            },
             Err(err @ HotStuffError::FallenBehind { .. }) => {
                 info!(target: LOG_TARGET, "⚠️ Behind peers, starting sync ({err})");
                 Ok(ConsensusStateEvent::NeedSync)
@@ -5,7 +5,7 @@ use std::marker::PhantomData;
 
 use crate::{
     hotstuff::{
-        state_machine::{check_sync::CheckSync, event::ConsensusStateEvent},
+        state_machine::{check_sync::CheckSync, event::ConsensusStateEvent, running::Running},
         ConsensusWorkerContext,
         HotStuffError,
     },
@@ -34,3 +34,9 @@ impl<TSpec> From<CheckSync<TSpec>> for Syncing<TSpec> {
         Self(PhantomData)
     }
 }
+
+impl<TSpec> From<Running<TSpec>> for Syncing<TSpec> {
+    fn from(_: Running<TSpec>) -> Self {
+        Self(PhantomData)
+    }
+}
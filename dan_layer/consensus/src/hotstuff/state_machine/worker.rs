@@ -87,6 +87,10 @@ where
             },
             (ConsensusState::Sleeping, ConsensusStateEvent::Resume) => ConsensusState::Idle(Idle::new()),
             (ConsensusState::Running(state), ConsensusStateEvent::NeedSync) => ConsensusState::CheckSync(state.into()),
+            // The state of the shards gained in the new layout is synced from the previous epoch's committees
+            (ConsensusState::Running(state), ConsensusStateEvent::CommitteeLayoutChanged { .. }) => {
+                ConsensusState::Syncing(state.into())
+            },
             (ConsensusState::Running(state), ConsensusStateEvent::NotRegisteredForEpoch { .. }) => {
                 ConsensusState::Idle(state.into())
             },
@@ -0,0 +1,139 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::Deref;
+
+use log::*;
+use tari_dan_common_types::{optional::Optional, Epoch};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        EpochCheckpoint,
+        HighQc,
+        LastExecuted,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        QuorumCertificate,
+    },
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+};
+
+use crate::hotstuff::{create_epoch_checkpoint, HotStuffError};
+
+const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::state_recovery";
+
+/// Repairs consensus bookkeeping that may have been left inconsistent by a crash. This must be called on startup before
+/// the genesis block for the epoch is created.
+///
+/// The bookkeeping is updated in the same transaction as the block and vote writes it corresponds to, so this only
+/// repairs state that was written by an older version of the node or a crash between independent writes:
+/// - a missing epoch checkpoint for the previous epoch when the epoch-end block has been committed
+/// - a locked block that is behind the last executed block
+/// - a leaf block that is missing or behind the locked block
+/// - a high QC that refers to a missing QC or block
+/// - a last voted block that is behind the last sent vote
+pub fn recover_consensus_state<TTx>(tx: &mut TTx, current_epoch: Epoch) -> Result<(), HotStuffError>
+where
+    TTx: StateStoreWriteTransaction + Deref,
+    TTx::Target: StateStoreReadTransaction,
+{
+    recover_epoch_checkpoint(tx, current_epoch)?;
+
+    let Some(mut locked) = LockedBlock::get(&**tx, current_epoch).optional()? else {
+        // The genesis block for this epoch has not been created yet, there is nothing to recover
+        return Ok(());
+    };
+
+    if let Some(last_executed) = LastExecuted::get(&**tx).optional()? {
+        if last_executed.epoch == current_epoch && last_executed.height > locked.height {
+            warn!(
+                target: LOG_TARGET,
+                "🩹 Locked block {} is behind last executed block {}. Updating locked block.",
+                locked,
+                last_executed.block_id
+            );
+            locked = Block::get(&**tx, &last_executed.block_id)?.as_locked_block();
+            locked.set(tx)?;
+        }
+    }
+
+    let leaf = LeafBlock::get(&**tx, current_epoch)?;
+    if leaf.height < locked.height || !Block::record_exists(&**tx, leaf.block_id())? {
+        warn!(
+            target: LOG_TARGET,
+            "🩹 Leaf block {} is missing or behind locked block {}. Resetting leaf block.",
+            leaf,
+            locked
+        );
+        LeafBlock {
+            block_id: locked.block_id,
+            height: locked.height,
+            epoch: locked.epoch,
+        }
+        .set(tx)?;
+    }
+
+    let high_qc = HighQc::get(&**tx, current_epoch)?;
+    let high_qc_exists = QuorumCertificate::get(&**tx, high_qc.qc_id()).optional()?.is_some() &&
+        Block::record_exists(&**tx, high_qc.block_id())?;
+    if !high_qc_exists {
+        let leaf = LeafBlock::get(&**tx, current_epoch)?;
+        let recovered_high_qc = Block::get(&**tx, leaf.block_id())?.justify().as_high_qc();
+        warn!(
+            target: LOG_TARGET,
+            "🩹 High QC {} refers to a missing QC or block. Resetting to {}.", high_qc, recovered_high_qc
+        );
+        recovered_high_qc.set(tx)?;
+    }
+
+    if let Some(last_sent_vote) = LastSentVote::get(&**tx).optional()? {
+        let last_voted = LastVoted::get(&**tx).optional()?;
+        let is_behind = last_voted.map_or(true, |v| v.height < last_sent_vote.block_height);
+        if last_sent_vote.epoch == current_epoch && is_behind {
+            if let Some(block) = Block::get(&**tx, &last_sent_vote.block_id).optional()? {
+                warn!(
+                    target: LOG_TARGET,
+                    "🩹 Last voted block is behind the last sent vote for {}. Updating last voted.", block
+                );
+                block.as_last_voted().set(tx)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn recover_epoch_checkpoint<TTx>(tx: &mut TTx, current_epoch: Epoch) -> Result<(), HotStuffError>
+where
+    TTx: StateStoreWriteTransaction + Deref,
+    TTx::Target: StateStoreReadTransaction,
+{
+    let Some(last_executed) = LastExecuted::get(&**tx).optional()? else {
+        return Ok(());
+    };
+    if last_executed.epoch >= current_epoch {
+        return Ok(());
+    }
+
+    let last_executed_block = Block::get(&**tx, &last_executed.block_id)?;
+    if !last_executed_block.is_epoch_end() {
+        return Ok(());
+    }
+
+    if EpochCheckpoint::get(&**tx, last_executed.epoch).optional()?.is_some() {
+        return Ok(());
+    }
+
+    warn!(
+        target: LOG_TARGET,
+        "🩹 Epoch {} ended at committed block {} but no checkpoint exists. Creating checkpoint.",
+        last_executed.epoch,
+        last_executed_block
+    );
+    create_epoch_checkpoint(tx, last_executed.epoch, last_executed_block.shard_group())?;
+
+    Ok(())
+}
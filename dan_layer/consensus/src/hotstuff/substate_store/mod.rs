@@ -5,8 +5,10 @@ mod error;
 mod pending_store;
 mod shard_state_store;
 mod sharded_state_tree;
+mod state_integrity;
 
 pub use error::*;
 pub use pending_store::*;
 pub use shard_state_store::*;
 pub use sharded_state_tree::*;
+pub use state_integrity::*;
@@ -7,6 +7,7 @@ use indexmap::IndexMap;
 use log::*;
 use tari_dan_common_types::{
     optional::Optional,
+    Epoch,
     LockIntent,
     NumPreshards,
     SubstateAddress,
@@ -22,7 +23,7 @@ use tari_dan_storage::{
     StateStoreReadTransaction,
 };
 use tari_engine_types::substate::{Substate, SubstateDiff, SubstateId};
-use tari_transaction::TransactionId;
+use tari_transaction::{IdempotencyKey, TransactionId};
 
 use super::error::SubstateStoreError;
 use crate::{
@@ -41,6 +42,8 @@ pub struct PendingSubstateStore<'a, 'tx, TStore: StateStore + 'a + 'tx> {
     /// Append only list of changes ordered oldest to newest
     diff: Vec<SubstateChange>,
     new_locks: IndexMap<SubstateId, Vec<SubstateLock>>,
+    /// Signer-bound idempotency keys reserved by transactions sequenced in this block
+    idempotency_keys: IndexMap<IdempotencyKey, TransactionId>,
     parent_block: BlockId,
     num_preshards: NumPreshards,
 }
@@ -53,6 +56,7 @@ impl<'a, 'tx, TStore: StateStore + 'a> PendingSubstateStore<'a, 'tx, TStore> {
             head: HashMap::new(),
             diff: Vec::new(),
             new_locks: IndexMap::new(),
+            idempotency_keys: IndexMap::new(),
             parent_block,
             num_preshards,
         }
@@ -571,8 +575,40 @@ impl<'a, 'tx, TStore: StateStore + 'a + 'tx> PendingSubstateStore<'a, 'tx, TStor
         &self.diff
     }
 
-    pub fn into_parts(self) -> (Vec<SubstateChange>, IndexMap<SubstateId, Vec<SubstateLock>>) {
-        (self.diff, self.new_locks)
+    /// Reserves the signer-bound idempotency key for a transaction sequenced in this block. If another transaction
+    /// reserved the key in this block, in a block in the parent chain or in a committed block since `since_epoch`, the
+    /// key is not reserved and that transaction's id is returned.
+    pub fn try_reserve_idempotency_key(
+        &mut self,
+        idempotency_key: IdempotencyKey,
+        transaction_id: TransactionId,
+        since_epoch: Epoch,
+    ) -> Result<Option<TransactionId>, SubstateStoreError> {
+        if let Some(existing_id) = self.idempotency_keys.get(&idempotency_key) {
+            if *existing_id != transaction_id {
+                return Ok(Some(*existing_id));
+            }
+            return Ok(None);
+        }
+
+        let existing_id = self
+            .store
+            .transaction_idempotency_keys_get(&idempotency_key, since_epoch, &self.parent_block)?
+            .filter(|id| *id != transaction_id);
+        if existing_id.is_none() {
+            self.idempotency_keys.insert(idempotency_key, transaction_id);
+        }
+        Ok(existing_id)
+    }
+
+    pub fn into_parts(
+        self,
+    ) -> (
+        Vec<SubstateChange>,
+        IndexMap<SubstateId, Vec<SubstateLock>>,
+        IndexMap<IdempotencyKey, TransactionId>,
+    ) {
+        (self.diff, self.new_locks, self.idempotency_keys)
     }
 }
 
@@ -47,6 +47,12 @@ impl<'a, TTx: StateStoreWriteTransaction> ShardScopedTreeStoreWriter<'a, TTx> {
             .map_err(|e| tari_state_tree::JmtStorageError::UnexpectedError(e.to_string()))
     }
 
+    pub fn prune_stale_nodes(&mut self, up_to_version: Version) -> Result<usize, tari_state_tree::JmtStorageError> {
+        self.tx
+            .state_tree_nodes_prune_stale(self.shard, up_to_version)
+            .map_err(|e| tari_state_tree::JmtStorageError::UnexpectedError(e.to_string()))
+    }
+
     pub fn transaction(&mut self) -> &mut TTx {
         self.tx
     }
@@ -73,9 +79,13 @@ impl<'a, TTx: StateStoreWriteTransaction> TreeStoreWriter<Version> for ShardScop
             .map_err(|e| tari_state_tree::JmtStorageError::UnexpectedError(e.to_string()))
     }
 
-    fn record_stale_tree_node(&mut self, node: StaleTreeNode) -> Result<(), tari_state_tree::JmtStorageError> {
+    fn record_stale_tree_node(
+        &mut self,
+        stale_since_version: Version,
+        node: StaleTreeNode,
+    ) -> Result<(), tari_state_tree::JmtStorageError> {
         self.tx
-            .state_tree_nodes_record_stale_tree_node(self.shard, node)
+            .state_tree_nodes_record_stale_tree_node(self.shard, stale_since_version, node)
             .map_err(|e| tari_state_tree::JmtStorageError::UnexpectedError(e.to_string()))
     }
 }
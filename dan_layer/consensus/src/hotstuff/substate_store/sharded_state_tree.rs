@@ -1,7 +1,7 @@
 //    Copyright 2024 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Deref};
 
 use indexmap::IndexMap;
 use log::debug;
@@ -34,6 +34,7 @@ pub struct ShardedStateTree<TTx> {
     tx: TTx,
     pending_diffs: HashMap<Shard, Vec<PendingShardStateTreeDiff>>,
     shard_tree_diffs: IndexMap<Shard, VersionedStateHashTreeDiff>,
+    retained_versions: u64,
 }
 
 impl<TTx> ShardedStateTree<TTx> {
@@ -42,6 +43,7 @@ impl<TTx> ShardedStateTree<TTx> {
             tx,
             pending_diffs: HashMap::new(),
             shard_tree_diffs: IndexMap::new(),
+            retained_versions: 0,
         }
     }
 
@@ -49,6 +51,15 @@ impl<TTx> ShardedStateTree<TTx> {
         Self { pending_diffs, ..self }
     }
 
+    /// Prune stale tree nodes once they are older than the given number of versions when committing diffs. If 0, all
+    /// historical versions are kept.
+    pub fn with_retained_versions(self, retained_versions: u64) -> Self {
+        Self {
+            retained_versions,
+            ..self
+        }
+    }
+
     pub fn transaction(&self) -> &TTx {
         &self.tx
     }
@@ -163,7 +174,11 @@ impl<TTx: StateStoreReadTransaction> ShardedStateTree<&TTx> {
     }
 }
 
-impl<TTx: StateStoreWriteTransaction> ShardedStateTree<&mut TTx> {
+impl<TTx> ShardedStateTree<&mut TTx>
+where
+    TTx: StateStoreWriteTransaction + Deref,
+    TTx::Target: StateStoreReadTransaction,
+{
     pub fn commit_diffs(
         &mut self,
         diffs: IndexMap<Shard, Vec<PendingShardStateTreeDiff>>,
@@ -190,7 +205,7 @@ impl<TTx: StateStoreWriteTransaction> ShardedStateTree<&mut TTx> {
                 "(shard={shard}) Recording stale tree node: {}",
                 stale_tree_node.as_node_key()
             );
-            store.record_stale_tree_node(stale_tree_node)?;
+            store.record_stale_tree_node(version, stale_tree_node)?;
         }
 
         for (key, node) in diff.new_nodes {
@@ -199,6 +214,23 @@ impl<TTx: StateStoreWriteTransaction> ShardedStateTree<&mut TTx> {
         }
 
         store.set_version(version)?;
+
+        if self.retained_versions > 0 {
+            // Nodes that became stale at or before this version are not part of any retained version
+            let up_to_version = version.saturating_sub(self.retained_versions);
+            if up_to_version > 0 {
+                let num_pruned = store.prune_stale_nodes(up_to_version)?;
+                if num_pruned > 0 {
+                    debug!(
+                        target: LOG_TARGET,
+                        "(shard={shard}) Pruned {num_pruned} stale tree node(s) up to v{up_to_version}"
+                    );
+                }
+                // Sanity check that pruning did not remove the root of the current version
+                SpreadPrefixStateTree::new(&mut store).get_root_hash(version)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,166 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeMap;
+
+use log::*;
+use tari_dan_common_types::{shard::Shard, SubstateAddress};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        LastExecuted,
+        ShardStateIntegrity,
+        StateIntegrityReport,
+        SubstateIntegrityMismatch,
+        SubstateIntegrityMismatchReason,
+        SubstateRecord,
+    },
+    StateStoreReadTransaction,
+};
+use tari_engine_types::substate::hash_substate;
+use tari_state_tree::{
+    compute_merkle_root_for_hashes,
+    memory_store::MemoryTreeStore,
+    SpreadPrefixStateTree,
+    SubstateTreeChange,
+    SPARSE_MERKLE_PLACEHOLDER_HASH,
+};
+
+use crate::hotstuff::substate_store::{ShardScopedTreeStoreReader, SubstateStoreError};
+
+const LOG_TARGET: &str = "tari::dan::consensus::substate_store::state_integrity";
+
+/// Recomputes the state merkle root of the last committed block from the substates in the state store and compares it
+/// to the block header. Shards whose stored state tree does not match the substates are checked substate by substate
+/// so that the offending substates can be reported.
+///
+/// This reads every live substate in the local shard group and should not be called on a hot path.
+pub fn check_state_integrity<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+) -> Result<StateIntegrityReport, SubstateStoreError> {
+    let last_executed = LastExecuted::get(tx)?;
+    let block = Block::get(tx, &last_executed.block_id)?;
+    let shard_group = block.shard_group();
+
+    let mut substates_by_shard = BTreeMap::<Shard, Vec<SubstateRecord>>::new();
+    let all_substates =
+        SubstateRecord::get_many_within_range(tx, SubstateAddress::zero()..=SubstateAddress::max(), &[])?;
+    for substate in all_substates {
+        if substate.is_destroyed() || !shard_group.contains(&substate.created_by_shard) {
+            continue;
+        }
+        substates_by_shard
+            .entry(substate.created_by_shard)
+            .or_default()
+            .push(substate);
+    }
+
+    let mut shards = Vec::with_capacity(shard_group.len());
+    for shard in shard_group.shard_iter() {
+        let substates = substates_by_shard.remove(&shard).unwrap_or_default();
+        shards.push(check_shard(tx, shard, &substates)?);
+    }
+
+    let computed_state_merkle_root = compute_merkle_root_for_hashes(shards.iter().map(|s| s.computed_root).peekable())?;
+
+    let report = StateIntegrityReport {
+        block_id: *block.id(),
+        epoch: block.epoch(),
+        height: block.height(),
+        expected_state_merkle_root: *block.state_merkle_root(),
+        computed_state_merkle_root,
+        shards,
+    };
+
+    if report.is_ok() {
+        info!(target: LOG_TARGET, "✅ State integrity check passed for block {}", block);
+    } else {
+        warn!(
+            target: LOG_TARGET,
+            "❌ State integrity check failed for block {}: {} shard(s) mismatched",
+            block,
+            report.mismatched_shards().count()
+        );
+    }
+
+    Ok(report)
+}
+
+fn check_shard<TTx: StateStoreReadTransaction>(
+    tx: &TTx,
+    shard: Shard,
+    substates: &[SubstateRecord],
+) -> Result<ShardStateIntegrity, SubstateStoreError> {
+    let mut mismatches = Vec::new();
+    let mut changes = Vec::with_capacity(substates.len());
+    for substate in substates {
+        let value_hash = hash_substate(substate.substate_value(), substate.version());
+        if value_hash != substate.state_hash {
+            mismatches.push(SubstateIntegrityMismatch {
+                substate_id: substate.substate_id().clone(),
+                version: substate.version(),
+                reason: SubstateIntegrityMismatchReason::StateHashMismatch {
+                    recorded: substate.state_hash,
+                    computed: value_hash,
+                },
+            });
+        }
+        changes.push(SubstateTreeChange::Up {
+            id: substate.substate_id().clone(),
+            value_hash,
+        });
+    }
+
+    let computed_root = if changes.is_empty() {
+        SPARSE_MERKLE_PLACEHOLDER_HASH
+    } else {
+        let mut mem_store = MemoryTreeStore::new();
+        SpreadPrefixStateTree::new(&mut mem_store).put_substate_changes(None, 1, changes)?
+    };
+
+    let state_tree_version = tx.state_tree_versions_get_latest(shard)?;
+    let mut store = ShardScopedTreeStoreReader::new(tx, shard);
+    let state_tree = SpreadPrefixStateTree::new(&mut store);
+    let (state_tree_root, state_tree_error) = match state_tree_version {
+        Some(version) => match state_tree.get_root_hash(version) {
+            Ok(root) => (Some(root), None),
+            Err(err) => (None, Some(err.to_string())),
+        },
+        None => (Some(SPARSE_MERKLE_PLACEHOLDER_HASH), None),
+    };
+
+    // Only look for the offending substates if the stored tree disagrees with the substates
+    if state_tree_root != Some(computed_root) {
+        for substate in substates {
+            let value_hash = hash_substate(substate.substate_value(), substate.version());
+            let reason = match state_tree_version {
+                Some(version) => match state_tree.get_proof(version, substate.substate_id()) {
+                    Ok((Some((in_tree, _, _)), _)) if in_tree == value_hash => continue,
+                    Ok((Some((in_tree, _, _)), _)) => SubstateIntegrityMismatchReason::StateTreeValueMismatch {
+                        in_tree,
+                        computed: value_hash,
+                    },
+                    Ok((None, _)) => SubstateIntegrityMismatchReason::MissingFromStateTree,
+                    // The tree is unreadable and this has already been reported for the shard
+                    Err(_) => continue,
+                },
+                None => SubstateIntegrityMismatchReason::MissingFromStateTree,
+            };
+            mismatches.push(SubstateIntegrityMismatch {
+                substate_id: substate.substate_id().clone(),
+                version: substate.version(),
+                reason,
+            });
+        }
+    }
+
+    Ok(ShardStateIntegrity {
+        shard,
+        num_substates: substates.len(),
+        state_tree_version,
+        state_tree_root,
+        state_tree_error,
+        computed_root,
+        mismatches,
+    })
+}
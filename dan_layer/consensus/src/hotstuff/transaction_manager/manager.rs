@@ -15,6 +15,7 @@ use tari_dan_common_types::{
     SubstateRequirement,
     ToSubstateAddress,
     VersionedSubstateId,
+    VersionedSubstateIdRef,
 };
 use tari_dan_storage::{
     consensus_models::{
@@ -22,6 +23,7 @@ use tari_dan_storage::{
         BlockTransactionExecution,
         Decision,
         ExecutedTransaction,
+        ExecutionContext,
         SubstateRequirementLockIntent,
         TransactionExecution,
         TransactionRecord,
@@ -37,9 +39,10 @@ use tari_transaction::{Transaction, TransactionId};
 
 use super::{PledgedTransaction, PreparedTransaction};
 use crate::{
+    consensus_constants::{ConsensusConstants, ExecutionMode},
     hotstuff::substate_store::{LockStatus, PendingSubstateStore},
     tracing::TraceTimer,
-    traits::{BlockTransactionExecutor, BlockTransactionExecutorError},
+    traits::{BlockTransactionExecutor, BlockTransactionExecutorError, ReadableSubstateStore},
 };
 
 const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::block_transaction_executor";
@@ -47,15 +50,23 @@ const LOG_TARGET: &str = "tari::dan::consensus::hotstuff::block_transaction_exec
 #[derive(Debug, Clone)]
 pub struct ConsensusTransactionManager<TExecutor, TStateStore> {
     executor: TExecutor,
+    execution_mode: ExecutionMode,
+    max_transaction_bytes: usize,
+    max_instructions: usize,
+    idempotency_key_window: Epoch,
     _store: PhantomData<TStateStore>,
 }
 
 impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
     ConsensusTransactionManager<TExecutor, TStateStore>
 {
-    pub fn new(executor: TExecutor) -> Self {
+    pub fn new(executor: TExecutor, consensus_constants: &ConsensusConstants) -> Self {
         Self {
             executor,
+            execution_mode: consensus_constants.execution_mode,
+            max_transaction_bytes: consensus_constants.max_transaction_bytes,
+            max_instructions: consensus_constants.max_instructions,
+            idempotency_key_window: consensus_constants.idempotency_key_window,
             _store: PhantomData,
         }
     }
@@ -92,9 +103,85 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
         Ok((resolved_substates, non_local_inputs))
     }
 
+    /// Returns the first local input whose rent has expired. An expired substate is inactive until its rent is paid,
+    /// so only a transaction that pays its rent may use it.
+    fn find_rent_expired_input(
+        store: &PendingSubstateStore<TStateStore>,
+        transaction: &Transaction,
+        local_versions: &IndexMap<SubstateRequirement, u32>,
+        current_epoch: Epoch,
+    ) -> Result<Option<VersionedSubstateId>, BlockTransactionExecutorError> {
+        for (req, version) in local_versions {
+            if transaction.pays_rent_for(req.substate_id()) {
+                continue;
+            }
+            let substate = store.get(VersionedSubstateIdRef::new(req.substate_id(), *version))?;
+            if substate.substate_value().is_rent_expired(current_epoch.as_u64()) {
+                return Ok(Some(VersionedSubstateId::new(req.substate_id().clone(), *version)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Prepares an ABORT for a transaction that is not executed. If all inputs are local, the transaction is aborted
+    /// locally, otherwise the ABORT is prepared with the foreign shard groups.
+    fn prepare_early_abort(
+        local_committee_info: &CommitteeInfo,
+        mut transaction: TransactionRecord,
+        outputs: HashSet<VersionedSubstateId>,
+        reason: RejectReason,
+    ) -> PreparedTransaction {
+        let transaction_id = *transaction.id();
+        let is_local_only = local_committee_info.includes_all_substate_addresses(
+            transaction
+                .transaction
+                .all_inputs_iter()
+                .map(|i| i.or_zero_version().to_substate_address()),
+        );
+        transaction.set_abort_reason(reason);
+        if is_local_only {
+            warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {} only contains local inputs. Will abort locally", transaction_id);
+            PreparedTransaction::new_local_early_abort(
+                transaction
+                    .into_execution()
+                    .expect("invariant: abort reason is set but into_execution is None"),
+            )
+        } else {
+            warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {} has foreign inputs. Will prepare ABORT", transaction_id);
+            PreparedTransaction::new_multishard(
+                transaction.into_execution(),
+                IndexMap::new(),
+                HashSet::new(),
+                outputs,
+                LockStatus::default(),
+            )
+        }
+    }
+
+    /// Returns a reason if the transaction exceeds the maximum instruction count or encoded size, otherwise None.
+    fn check_transaction_limits(&self, transaction: &Transaction) -> Option<String> {
+        let num_instructions = transaction.num_instructions();
+        if num_instructions > self.max_instructions {
+            return Some(format!(
+                "Transaction has {num_instructions} instructions which exceeds the maximum of {}",
+                self.max_instructions
+            ));
+        }
+
+        match transaction.encoded_size() {
+            Ok(size) if size > self.max_transaction_bytes => Some(format!(
+                "Transaction is {size} bytes which exceeds the maximum of {} bytes",
+                self.max_transaction_bytes
+            )),
+            Ok(_) => None,
+            Err(err) => Some(format!("Failed to encode transaction: {err}")),
+        }
+    }
+
     pub fn execute(
         &self,
         current_epoch: Epoch,
+        execution_context: &ExecutionContext,
         pledged_transaction: PledgedTransaction,
     ) -> Result<ExecutedTransaction, BlockTransactionExecutorError> {
         let resolved_inputs = pledged_transaction
@@ -112,9 +199,13 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
                     )
                 })
             .collect();
+        let transaction = pledged_transaction.transaction.into_transaction();
+        let random_beacon = execution_context.random_beacon(transaction.id());
         let executed = self.executor.execute(
-            pledged_transaction.transaction.into_transaction(),
+            transaction,
             current_epoch,
+            execution_context.timestamp(),
+            random_beacon,
             &resolved_inputs,
         )?;
 
@@ -126,6 +217,7 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
         store: &mut PendingSubstateStore<TStateStore>,
         transaction: Transaction,
         current_epoch: Epoch,
+        execution_context: &ExecutionContext,
         resolved_inputs: &HashMap<SubstateRequirement, Substate>,
         block_id: &BlockId,
     ) -> Result<TransactionExecution, BlockTransactionExecutorError> {
@@ -142,7 +234,14 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
             return Ok(execution.into_transaction_execution());
         }
 
-        let executed = self.executor.execute(transaction, current_epoch, resolved_inputs)?;
+        let random_beacon = execution_context.random_beacon(transaction.id());
+        let executed = self.executor.execute(
+            transaction,
+            current_epoch,
+            execution_context.timestamp(),
+            random_beacon,
+            resolved_inputs,
+        )?;
 
         Ok(executed.into_execution())
     }
@@ -153,6 +252,7 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
         store: &mut PendingSubstateStore<TStateStore>,
         local_committee_info: &CommitteeInfo,
         current_epoch: Epoch,
+        execution_context: &ExecutionContext,
         transaction_id: TransactionId,
         block_id: &BlockId,
     ) -> Result<PreparedTransaction, BlockTransactionExecutorError> {
@@ -163,48 +263,81 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
             TransactionReceiptAddress::from(transaction_id).into(),
             0,
         ));
+        // Reserved outputs are known before execution, so they are pledged along with the receipt
+        outputs.extend(
+            transaction
+                .transaction()
+                .reserved_output_addresses()
+                .map(|address| VersionedSubstateId::new(address.into(), 0)),
+        );
 
-        let (local_versions, non_local_inputs) = match self.resolve_local_versions(
-            store,
-            local_committee_info,
-            transaction.transaction(),
-        ) {
-            Ok(inputs) => inputs,
-            Err(err) => {
-                warn!(target: LOG_TARGET, "⚠️ PREPARE: failed to resolve local inputs: {err}");
-                // We only expect not found or down errors here. If we get any other error, this is fatal.
-                if !err.is_not_found_error() && !err.is_substate_down_error() {
-                    return Err(err);
-                }
-                let is_local_only = local_committee_info.includes_all_substate_addresses(
-                    transaction
-                        .transaction
-                        .all_inputs_iter()
-                        .map(|i| i.or_zero_version().to_substate_address()),
-                );
-                // TODO: consider sending Decision::Abort(AbortReason) in the block.
-                // Currently this message will differ depending on which involved shard is asked.
-                // e.g. local nodes will say "failed to lock inputs", foreign nodes will say "foreign shard abort"
-                transaction.set_abort_reason(RejectReason::OneOrMoreInputsNotFound(err.to_string()));
-                if is_local_only {
-                    warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {} only contains local inputs. Will abort locally", transaction_id);
-                    return Ok(PreparedTransaction::new_local_early_abort(
-                        transaction
-                            .into_execution()
-                            .expect("invariant: abort reason is set but into_execution is None"),
-                    ));
-                } else {
-                    warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {} has foreign inputs. Will prepare ABORT", transaction_id);
-                    return Ok(PreparedTransaction::new_multishard(
-                        transaction.into_execution(),
-                        IndexMap::new(),
-                        HashSet::new(),
+        // A transaction may not be sequenced after its validity window
+        if let Some(max_epoch) = transaction.transaction().max_epoch() {
+            if current_epoch > max_epoch {
+                warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {transaction_id} expired at epoch {max_epoch} (current epoch {current_epoch})");
+                return Ok(Self::prepare_early_abort(
+                    local_committee_info,
+                    transaction,
+                    outputs,
+                    RejectReason::InvalidTransaction(format!(
+                        "Current epoch ({current_epoch}) is greater than maximum epoch ({max_epoch}) for transaction"
+                    )),
+                ));
+            }
+        }
+
+        // Transactions over the network limits are aborted so that an oversized transaction cannot be committed by a
+        // leader that skipped mempool validation
+        if let Some(reason) = self.check_transaction_limits(transaction.transaction()) {
+            warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {transaction_id} exceeds network limits: {reason}");
+            return Ok(Self::prepare_early_abort(
+                local_committee_info,
+                transaction,
+                outputs,
+                RejectReason::InvalidTransaction(reason),
+            ));
+        }
+
+        // The first transaction sequenced with a signer-bound idempotency key reserves it for the idempotency window.
+        // Any other transaction with the same key is aborted, regardless of the order in which validators received
+        // them.
+        if let Some(idempotency_key) = transaction.transaction().signer_idempotency_key() {
+            let since_epoch = current_epoch.saturating_sub(self.idempotency_key_window);
+            if let Some(existing_id) =
+                store.try_reserve_idempotency_key(idempotency_key, transaction_id, since_epoch)?
+            {
+                warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {transaction_id} reuses the idempotency key of transaction {existing_id}");
+                return Ok(Self::prepare_early_abort(
+                    local_committee_info,
+                    transaction,
+                    outputs,
+                    RejectReason::InvalidTransaction(format!(
+                        "Idempotency key already used by transaction {existing_id}"
+                    )),
+                ));
+            }
+        }
+
+        let (local_versions, non_local_inputs) =
+            match self.resolve_local_versions(store, local_committee_info, transaction.transaction()) {
+                Ok(inputs) => inputs,
+                Err(err) => {
+                    warn!(target: LOG_TARGET, "⚠️ PREPARE: failed to resolve local inputs: {err}");
+                    // We only expect not found or down errors here. If we get any other error, this is fatal.
+                    if !err.is_not_found_error() && !err.is_substate_down_error() {
+                        return Err(err);
+                    }
+                    // TODO: consider sending Decision::Abort(AbortReason) in the block.
+                    // Currently this message will differ depending on which involved shard is asked.
+                    // e.g. local nodes will say "failed to lock inputs", foreign nodes will say "foreign shard abort"
+                    return Ok(Self::prepare_early_abort(
+                        local_committee_info,
+                        transaction,
                         outputs,
-                        LockStatus::default(),
+                        RejectReason::OneOrMoreInputsNotFound(err.to_string()),
                     ));
-                }
-            },
-        };
+                },
+            };
 
         if local_versions.is_empty() && non_local_inputs.is_empty() {
             // Mempool validations should have not sent the transaction to consensus
@@ -214,7 +347,19 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
             )));
         }
 
-        if non_local_inputs.is_empty() {
+        if let Some(id) =
+            Self::find_rent_expired_input(store, transaction.transaction(), &local_versions, current_epoch)?
+        {
+            warn!(target: LOG_TARGET, "⚠️ PREPARE: transaction {transaction_id} uses input {id} whose rent has expired");
+            return Ok(Self::prepare_early_abort(
+                local_committee_info,
+                transaction,
+                outputs,
+                RejectReason::InputRentExpired(format!("Rent for input {id} expired before epoch {current_epoch}")),
+            ));
+        }
+
+        if non_local_inputs.is_empty() && !self.execution_mode.is_deferred() {
             // CASE: All inputs are local and we can execute the transaction.
             //       Outputs may or may not be local
             let local_inputs = store.get_many(local_versions.iter().map(|(req, v)| (req.clone(), *v)))?;
@@ -222,6 +367,7 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
                 store,
                 transaction.into_transaction(),
                 current_epoch,
+                execution_context,
                 &local_inputs,
                 block_id,
             )?;
@@ -296,7 +442,8 @@ impl<TStateStore: StateStore, TExecutor: BlockTransactionExecutor<TStateStore>>
                 warn!(target: LOG_TARGET, "⚠️ PREPARE: Hard conflict when locking inputs: {err}");
                 transaction.set_abort_reason(RejectReason::FailedToLockInputs(err.to_string()));
             }
-            // CASE: Multishard transaction, not executed
+            // CASE: Multishard transaction or deferred execution, not executed. The transaction is executed using
+            //       the pledged inputs once all inputs have been prepared.
             Ok(PreparedTransaction::new_multishard(
                 transaction.into_execution(),
                 local_versions,
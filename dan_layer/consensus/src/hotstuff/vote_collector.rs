@@ -4,16 +4,34 @@
 use log::*;
 use tari_common::configuration::Network;
 use tari_common_types::types::FixedHash;
-use tari_dan_common_types::{committee::CommitteeInfo, optional::Optional, Epoch};
+use tari_dan_common_types::{
+    committee::{Committee, CommitteeInfo},
+    optional::Optional,
+    Epoch,
+};
 use tari_dan_storage::{
-    consensus_models::{Block, HighQc, QuorumCertificate, QuorumDecision, ValidatorSignature, Vote},
+    consensus_models::{
+        AggregateValidatorSignature,
+        Block,
+        BlockId,
+        DoubleVoteEvidence,
+        HighQc,
+        QuorumCertificate,
+        QuorumDecision,
+        SignedVote,
+        SignerBitmap,
+        Vote,
+    },
     global::models::ValidatorNode,
     StateStore,
+    StateStoreReadTransaction,
+    StorageError,
 };
 use tari_epoch_manager::EpochManagerReader;
+use tokio::sync::broadcast;
 
 use crate::{
-    hotstuff::error::HotStuffError,
+    hotstuff::{error::HotStuffError, HotstuffEvent},
     messages::VoteMessage,
     tracing::TraceTimer,
     traits::{ConsensusSpec, VoteSignatureService},
@@ -27,6 +45,7 @@ pub struct VoteCollector<TConsensusSpec: ConsensusSpec> {
     store: TConsensusSpec::StateStore,
     epoch_manager: TConsensusSpec::EpochManager,
     vote_signature_service: TConsensusSpec::SignatureService,
+    tx_events: broadcast::Sender<HotstuffEvent>,
 }
 
 impl<TConsensusSpec> VoteCollector<TConsensusSpec>
@@ -37,12 +56,14 @@ where TConsensusSpec: ConsensusSpec
         store: TConsensusSpec::StateStore,
         epoch_manager: TConsensusSpec::EpochManager,
         vote_signature_service: TConsensusSpec::SignatureService,
+        tx_events: broadcast::Sender<HotstuffEvent>,
     ) -> Self {
         Self {
             network,
             store,
             epoch_manager,
             vote_signature_service,
+            tx_events,
         }
     }
 
@@ -62,7 +83,8 @@ where TConsensusSpec: ConsensusSpec
 
         self.validate_vote_message(current_epoch, &message)?;
         let sender_vn = self.check_eligibility(from, &message, local_committee_info).await?;
-        let maybe_qc = self.collect_vote(message, local_committee_info, sender_vn)?;
+        let local_committee = self.epoch_manager.get_local_committee(message.epoch).await?;
+        let maybe_qc = self.collect_vote(message, &local_committee, sender_vn)?;
         if let Some((ref qc, ref high_qc)) = maybe_qc {
             if qc.id() == high_qc.qc_id() {
                 info!(target: LOG_TARGET, "🔥 New HIGH {}", qc);
@@ -109,20 +131,20 @@ where TConsensusSpec: ConsensusSpec
     fn collect_vote(
         &self,
         message: VoteMessage,
-        local_committee_info: &CommitteeInfo,
+        local_committee: &Committee<TConsensusSpec::Addr>,
         sender_vn: ValidatorNode<TConsensusSpec::Addr>,
     ) -> Result<Option<(QuorumCertificate, HighQc)>, HotStuffError> {
         self.store.with_write_tx(|tx| {
             let sender_leaf_hash = sender_vn.get_node_hash(self.network);
 
-            let exists = Vote {
+            let vote = Vote {
                 epoch: message.epoch,
                 block_id: message.block_id,
                 decision: message.decision,
                 sender_leaf_hash,
                 signature: message.signature,
-            }
-            .save(tx)?;
+            };
+            let exists = vote.save(tx)?;
 
             if exists {
                 warn!(
@@ -134,9 +156,17 @@ where TConsensusSpec: ConsensusSpec
                 return Ok(None);
             }
 
-            let count = Vote::count_for_block(&**tx, &message.block_id)?;
-            // We only generate the next high qc once when we have a quorum of votes. Any subsequent votes are not
-            // included in the QC.
+            if let Some(evidence) = self.find_double_vote(&**tx, &vote)? {
+                warn!(target: LOG_TARGET, "🚨 {} by {}", evidence, sender_vn.address);
+                let _ignore = self.tx_events.send(HotstuffEvent::DoubleVoteDetected {
+                    evidence: Box::new(evidence),
+                });
+            }
+
+            let voting_power = Self::get_voting_power_for_block(&**tx, &message.block_id, local_committee)?;
+            let quorum_threshold = local_committee.quorum_voting_power();
+            // We only generate the next high qc once when we have a quorum of votes i.e. the vote that reaches the
+            // threshold. Any subsequent votes are not included in the QC.
 
             info!(
                 target: LOG_TARGET,
@@ -145,17 +175,18 @@ where TConsensusSpec: ConsensusSpec
                 message.unverified_block_height,
                 message.block_id,
                 sender_vn.address,
-                count,
-                local_committee_info.quorum_threshold()
+                voting_power,
+                quorum_threshold
             );
-            if count != local_committee_info.quorum_threshold() as usize {
+            let sender_voting_power = local_committee.voting_power_of(&vote.signature.public_key);
+            if voting_power < quorum_threshold || voting_power.saturating_sub(sender_voting_power) >= quorum_threshold {
                 return Ok(None);
             }
 
             let Some(block) = Block::get(&**tx, &message.block_id).optional()? else {
                 warn!(
                     target: LOG_TARGET,
-                    "❌ Received {} votes for unknown block {}", count, message.block_id,
+                    "❌ Received {} voting power for unknown block {}", voting_power, message.block_id,
                 );
                 return Ok(None);
             };
@@ -165,26 +196,27 @@ where TConsensusSpec: ConsensusSpec
                     target: LOG_TARGET,
                     "🔥 Received vote for block {} from {} ({} of {}), but we already have a QC for this block ({})",
                     message.block_id,
-                    count,
                     sender_vn.address,
-                    local_committee_info.quorum_threshold(),
+                    voting_power,
+                    quorum_threshold,
                     existing_qc_for_block
                 );
                 return Ok(None);
             }
 
             let votes = block.get_votes(&**tx)?;
-            let Some(quorum_decision) = Self::calculate_threshold_decision(&votes, local_committee_info) else {
+            let Some(quorum_decision) = Self::calculate_threshold_decision(&votes, local_committee) else {
                 warn!(
                     target: LOG_TARGET,
                     "🔥 Received conflicting votes from replicas for block {} ({} of {}). Waiting for more votes.",
                     message.block_id,
-                    count,
-                    local_committee_info.quorum_threshold()
+                    voting_power,
+                    quorum_threshold
                 );
                 return Ok(None);
             };
 
+            // Signatures are aggregated in committee order so that the signers can be recorded as a bitmap
             let mut signatures = Vec::with_capacity(votes.len());
             let mut leaf_hashes = Vec::with_capacity(votes.len());
             for vote in votes {
@@ -192,12 +224,30 @@ where TConsensusSpec: ConsensusSpec
                     // We don't include votes that don't match the quorum decision
                     continue;
                 }
-                signatures.push(vote.signature);
+                let Some(index) = local_committee
+                    .public_keys()
+                    .position(|pk| *pk == vote.signature.public_key)
+                else {
+                    warn!(
+                        target: LOG_TARGET,
+                        "❓️ Vote for block {} signed by {} that is not in the local committee",
+                        vote.block_id,
+                        vote.signature.public_key
+                    );
+                    continue;
+                };
+                signatures.push((index, vote.signature));
                 leaf_hashes.push(vote.sender_leaf_hash);
             }
+            signatures.sort_by_key(|(index, _)| *index);
 
+            let message = self.vote_signature_service.create_message(block.id(), &quorum_decision);
             let vote_data = VoteData {
-                signatures,
+                signers: SignerBitmap::from_indexes(signatures.iter().map(|(index, _)| *index)),
+                signature: AggregateValidatorSignature::aggregate(
+                    message,
+                    &signatures.into_iter().map(|(_, s)| s).collect::<Vec<_>>(),
+                ),
                 leaf_hashes,
                 quorum_decision,
                 block,
@@ -209,21 +259,80 @@ where TConsensusSpec: ConsensusSpec
         })
     }
 
-    fn calculate_threshold_decision(votes: &[Vote], local_committee_info: &CommitteeInfo) -> Option<QuorumDecision> {
-        let mut count_accept = 0;
-        let mut count_reject = 0;
-        for vote in votes {
-            match vote.decision {
-                QuorumDecision::Accept => count_accept += 1,
-                QuorumDecision::Reject => count_reject += 1,
+    /// Returns evidence if the sender has already voted for a different block at the same height as the given vote.
+    /// Votes for blocks that we do not have cannot be checked.
+    fn find_double_vote<TTx: StateStoreReadTransaction>(
+        &self,
+        tx: &TTx,
+        vote: &Vote,
+    ) -> Result<Option<DoubleVoteEvidence>, StorageError> {
+        let Some(block) = Block::get(tx, &vote.block_id).optional()? else {
+            return Ok(None);
+        };
+
+        for other in Vote::get_by_sender(tx, vote.epoch, &vote.sender_leaf_hash)? {
+            if other.block_id == vote.block_id {
+                continue;
+            }
+            let Some(other_block) = Block::get(tx, &other.block_id).optional()? else {
+                continue;
+            };
+            if other_block.height() != block.height() {
+                continue;
+            }
+
+            let evidence = DoubleVoteEvidence::new(
+                SignedVote {
+                    block: other_block.header().clone(),
+                    decision: other.decision,
+                    signature: other.signature,
+                },
+                SignedVote {
+                    block: block.header().clone(),
+                    decision: vote.decision,
+                    signature: vote.signature.clone(),
+                },
+            );
+            if self.vote_signature_service.verify_double_vote_evidence(&evidence) {
+                return Ok(Some(evidence));
             }
         }
 
-        let threshold = local_committee_info.quorum_threshold() as usize;
-        if count_accept >= threshold {
+        Ok(None)
+    }
+
+    /// Returns the voting power of the local committee members that voted for the block. If all members have a voting
+    /// power of 1, this is the number of votes.
+    fn get_voting_power_for_block<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        block_id: &BlockId,
+        local_committee: &Committee<TConsensusSpec::Addr>,
+    ) -> Result<u64, StorageError> {
+        if !local_committee.is_weighted() {
+            return Ok(Vote::count_for_block(tx, block_id)? as u64);
+        }
+        let votes = Vote::get_for_block(tx, block_id)?;
+        Ok(local_committee.sum_voting_power(votes.iter().map(|vote| &vote.signature.public_key)))
+    }
+
+    fn calculate_threshold_decision(
+        votes: &[Vote],
+        local_committee: &Committee<TConsensusSpec::Addr>,
+    ) -> Option<QuorumDecision> {
+        let voting_power_for = |decision: QuorumDecision| {
+            local_committee.sum_voting_power(
+                votes
+                    .iter()
+                    .filter(|vote| vote.decision == decision)
+                    .map(|vote| &vote.signature.public_key),
+            )
+        };
+
+        let threshold = local_committee.quorum_voting_power();
+        if voting_power_for(QuorumDecision::Accept) >= threshold {
             return Some(QuorumDecision::Accept);
         }
-        if count_reject >= threshold {
+        if voting_power_for(QuorumDecision::Reject) >= threshold {
             return Some(QuorumDecision::Reject);
         }
 
@@ -255,7 +364,8 @@ where TConsensusSpec: ConsensusSpec
 
 fn create_qc(vote_data: VoteData) -> QuorumCertificate {
     let VoteData {
-        signatures,
+        signers,
+        signature,
         leaf_hashes,
         quorum_decision,
         block,
@@ -265,14 +375,16 @@ fn create_qc(vote_data: VoteData) -> QuorumCertificate {
         block.height(),
         block.epoch(),
         block.shard_group(),
-        signatures,
+        signers,
+        signature,
         leaf_hashes,
         quorum_decision,
     )
 }
 
 struct VoteData {
-    signatures: Vec<ValidatorSignature>,
+    signers: SignerBitmap,
+    signature: AggregateValidatorSignature,
     leaf_hashes: Vec<FixedHash>,
     quorum_decision: QuorumDecision,
     block: Block,
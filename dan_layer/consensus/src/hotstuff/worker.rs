@@ -4,6 +4,7 @@
 use std::{
     fmt::{Debug, Formatter},
     iter,
+    mem,
 };
 
 use log::*;
@@ -13,6 +14,7 @@ use tari_dan_common_types::{
     Epoch,
     NodeHeight,
     ShardGroup,
+    ShardGroupTransition,
 };
 use tari_dan_storage::{
     consensus_models::{
@@ -23,6 +25,7 @@ use tari_dan_storage::{
         ForeignProposal,
         HighQc,
         LeafBlock,
+        LockedBlock,
         TransactionPool,
         TransactionRecord,
     },
@@ -34,8 +37,14 @@ use tari_transaction::{Transaction, TransactionId};
 use tokio::sync::{broadcast, mpsc};
 
 use super::{
+    calculate_epoch_leader_seed,
+    calculate_genesis_state_merkle_root,
     calculate_last_dummy_block,
     config::HotstuffConfig,
+    get_block_leader_seed,
+    get_high_qc_leader_seed,
+    get_source_checkpoints,
+    is_cross_shard_preparation_deferred,
     on_receive_new_transaction::OnReceiveNewTransaction,
     ProposalValidationError,
 };
@@ -43,6 +52,7 @@ use crate::{
     hotstuff::{
         error::HotStuffError,
         event::HotstuffEvent,
+        foreign_proposal_prefetcher::ForeignProposalPrefetcher,
         on_catch_up_sync::OnCatchUpSync,
         on_catch_up_sync_request::OnSyncRequest,
         on_inbound_message::OnInboundMessage,
@@ -50,14 +60,17 @@ use crate::{
         on_next_sync_view::OnNextSyncViewHandler,
         on_propose::OnPropose,
         on_receive_foreign_proposal::OnReceiveForeignProposalHandler,
+        on_receive_foreign_proposal_request::OnReceiveForeignProposalRequest,
         on_receive_local_proposal::OnReceiveLocalProposalHandler,
         on_receive_new_view::OnReceiveNewViewHandler,
         on_receive_request_missing_transactions::OnReceiveRequestMissingTransactions,
         on_receive_vote::OnReceiveVoteHandler,
         pacemaker::PaceMaker,
         pacemaker_handle::PaceMakerHandle,
+        state_recovery::recover_consensus_state,
         transaction_manager::ConsensusTransactionManager,
         vote_collector::VoteCollector,
+        ProposalMissRate,
     },
     messages::{HotstuffMessage, ProposalMessage},
     tracing::TraceTimer,
@@ -80,6 +93,7 @@ pub struct HotstuffWorker<TConsensusSpec: ConsensusSpec> {
     on_next_sync_view: OnNextSyncViewHandler<TConsensusSpec>,
     on_receive_local_proposal: OnReceiveLocalProposalHandler<TConsensusSpec>,
     on_receive_foreign_proposal: OnReceiveForeignProposalHandler<TConsensusSpec>,
+    on_receive_foreign_proposal_request: OnReceiveForeignProposalRequest<TConsensusSpec>,
     on_receive_vote: OnReceiveVoteHandler<TConsensusSpec>,
     on_receive_new_view: OnReceiveNewViewHandler<TConsensusSpec>,
     on_receive_request_missing_txs: OnReceiveRequestMissingTransactions<TConsensusSpec>,
@@ -88,6 +102,7 @@ pub struct HotstuffWorker<TConsensusSpec: ConsensusSpec> {
     on_propose: OnPropose<TConsensusSpec>,
     on_sync_request: OnSyncRequest<TConsensusSpec>,
     on_catch_up_sync: OnCatchUpSync<TConsensusSpec>,
+    foreign_proposal_prefetcher: ForeignProposalPrefetcher<TConsensusSpec>,
 
     state_store: TConsensusSpec::StateStore,
     leader_strategy: TConsensusSpec::LeaderStrategy,
@@ -123,8 +138,11 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             state_store.clone(),
             epoch_manager.clone(),
             signing_service.clone(),
+            tx_events.clone(),
         );
-        let transaction_manager = ConsensusTransactionManager::new(transaction_executor.clone());
+        let transaction_manager =
+            ConsensusTransactionManager::new(transaction_executor.clone(), &config.consensus_constants);
+        let proposal_miss_rate = ProposalMissRate::new();
 
         Self {
             local_validator_addr: local_validator_addr.clone(),
@@ -134,13 +152,15 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             rx_new_transactions,
             rx_missing_transactions,
 
-            on_inbound_message: OnInboundMessage::new(inbound_messaging, hooks.clone()),
+            on_inbound_message: OnInboundMessage::new(
+                inbound_messaging,
+                hooks.clone(),
+                config.consensus_constants.fallen_behind_sync_threshold,
+            ),
             on_message_validate: OnMessageValidate::new(
                 config.clone(),
                 state_store.clone(),
                 epoch_manager.clone(),
-                leader_strategy.clone(),
-                signing_service.clone(),
                 outbound_messaging.clone(),
                 tx_events.clone(),
             ),
@@ -169,7 +189,12 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 epoch_manager.clone(),
                 pacemaker.clone_handle(),
             ),
-            on_receive_vote: OnReceiveVoteHandler::new(pacemaker.clone_handle(), vote_receiver.clone()),
+            on_receive_foreign_proposal_request: OnReceiveForeignProposalRequest::new(
+                state_store.clone(),
+                outbound_messaging.clone(),
+                transaction_pool.clone(),
+            ),
+            on_receive_vote: OnReceiveVoteHandler::new(pacemaker.clone_handle(), vote_receiver.clone(), hooks.clone()),
             on_receive_new_view: OnReceiveNewViewHandler::new(
                 local_validator_addr,
                 state_store.clone(),
@@ -180,6 +205,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             on_receive_request_missing_txs: OnReceiveRequestMissingTransactions::new(
                 state_store.clone(),
                 outbound_messaging.clone(),
+                proposal_miss_rate.clone(),
             ),
             on_receive_new_transaction: OnReceiveNewTransaction::new(
                 state_store.clone(),
@@ -188,13 +214,22 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 tx_missing_transactions,
             ),
             on_propose: OnPropose::new(
-                config,
+                config.clone(),
                 state_store.clone(),
                 epoch_manager.clone(),
                 transaction_pool.clone(),
                 transaction_manager,
                 signing_service,
                 outbound_messaging.clone(),
+                proposal_miss_rate,
+            ),
+
+            foreign_proposal_prefetcher: ForeignProposalPrefetcher::new(
+                config,
+                state_store.clone(),
+                epoch_manager.clone(),
+                outbound_messaging.clone(),
+                transaction_pool.clone(),
             ),
 
             on_sync_request: OnSyncRequest::new(state_store.clone(), outbound_messaging.clone()),
@@ -220,7 +255,13 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         let current_epoch = self.epoch_manager.current_epoch().await?;
         let local_committee_info = self.epoch_manager.get_local_committee_info(current_epoch).await?;
 
-        self.create_genesis_block_if_required(current_epoch, local_committee_info.shard_group())?;
+        self.state_store
+            .with_write_tx(|tx| recover_consensus_state(tx, current_epoch))?;
+        let transition = self
+            .epoch_manager
+            .get_local_shard_group_transition(current_epoch)
+            .await?;
+        self.create_genesis_block_if_required(current_epoch, local_committee_info.shard_group(), &transition)?;
 
         // Resume pacemaker from the last epoch/height
         let (current_height, high_qc) = self.state_store.with_read_tx(|tx| {
@@ -273,6 +314,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
 
             // Need to update local committee info if the epoch has changed
             if prev_epoch != current_epoch {
+                self.check_committee_layout(current_epoch).await?;
                 local_committee_info = self.epoch_manager.get_local_committee_info(current_epoch).await?;
                 local_committee = self.epoch_manager.get_local_committee(current_epoch).await?;
                 prev_epoch = current_epoch;
@@ -281,6 +323,14 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
             if current_height != prev_height {
                 self.hooks.on_pacemaker_height_changed(current_height);
                 prev_height = current_height;
+                if let Err(err) = self
+                    .foreign_proposal_prefetcher
+                    .on_height_changed(current_epoch, current_height, &local_committee_info)
+                    .await
+                {
+                    self.hooks.on_error(&err);
+                    error!(target: LOG_TARGET, "🚨Error requesting foreign proposals: {}", err);
+                }
             }
 
             debug!(
@@ -366,11 +416,31 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         local_committee_info: &CommitteeInfo,
         local_committee: &Committee<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
-        let (from, msg) = result?;
+        let (from, mut msg) = result?;
+
+        // Full proposals carry the transactions for the block, so add them before checking for missing transactions
+        if let HotstuffMessage::Proposal(proposal) = &mut msg {
+            if !proposal.transactions.is_empty() {
+                let transactions = mem::take(&mut proposal.transactions);
+                self.on_receive_new_transaction.process_proposed(
+                    current_epoch,
+                    &proposal.block,
+                    transactions,
+                    local_committee_info,
+                )?;
+            }
+        }
 
         match self
             .on_message_validate
-            .handle(current_height, local_committee_info, local_committee, from.clone(), msg)
+            .handle(
+                current_epoch,
+                current_height,
+                local_committee_info,
+                local_committee,
+                from.clone(),
+                msg,
+            )
             .await?
         {
             MessageValidationResult::Ready { from, message: msg } => {
@@ -564,6 +634,32 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         Ok(())
     }
 
+    /// Returns an error if this validator gained shards in the committee layout of the new epoch. Consensus is
+    /// restarted once the state of the gained shards has been synced from the committees of the previous epoch.
+    async fn check_committee_layout(&self, epoch: Epoch) -> Result<(), HotStuffError> {
+        if !self.epoch_manager.has_committee_layout_changed(epoch).await? {
+            return Ok(());
+        }
+
+        let transition = self.epoch_manager.get_local_shard_group_transition(epoch).await?;
+        let gained_shards = self.epoch_manager.get_local_gained_shards(epoch).await?;
+        info!(
+            target: LOG_TARGET,
+            "🔀 Committee layout changed in epoch {}: {}. Gained {} shard(s)",
+            epoch,
+            transition,
+            gained_shards.len()
+        );
+        if !gained_shards.is_empty() {
+            return Err(HotStuffError::CommitteeLayoutChanged {
+                epoch,
+                num_gained_shards: gained_shards.len(),
+            });
+        }
+
+        Ok(())
+    }
+
     async fn request_initial_catch_up_sync(&mut self, current_epoch: Epoch) -> Result<(), HotStuffError> {
         let mut committee = self.epoch_manager.get_local_committee(current_epoch).await?;
         committee.shuffle();
@@ -625,11 +721,15 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         local_committee_info: &CommitteeInfo,
         local_committee: &Committee<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
-        let leaf_block = self.state_store.with_read_tx(|tx| LeafBlock::get(tx, epoch))?;
+        let (leaf_block, leader_seed) = self.state_store.with_read_tx(|tx| {
+            let leaf_block = LeafBlock::get(tx, epoch)?;
+            let leader_seed = get_high_qc_leader_seed(tx, epoch)?;
+            Ok::<_, HotStuffError>((leaf_block, leader_seed))
+        })?;
         let next_height = leaf_block.height() + NodeHeight(1);
         if !self
             .leader_strategy
-            .is_leader(&self.local_validator_addr, local_committee, next_height)
+            .is_leader(&self.local_validator_addr, local_committee, &leader_seed, next_height)
         {
             debug!(
                 target: LOG_TARGET,
@@ -682,19 +782,23 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         local_committee_info: &CommitteeInfo,
         local_committee: &Committee<TConsensusSpec::Addr>,
     ) -> Result<(), HotStuffError> {
+        let (leaf_block, leader_seed) = self.state_store.with_read_tx(|tx| {
+            let leaf_block = LeafBlock::get(tx, epoch)?;
+            // The proposal and any dummy blocks needed to reach the next height are justified by the high QC, so the
+            // leader is selected using the seed of the high QC block
+            let leader_seed = get_high_qc_leader_seed(tx, epoch)?;
+            Ok::<_, HotStuffError>((leaf_block, leader_seed))
+        })?;
         let next_height = match forced_height {
             Some(height) => {
                 debug!(target: LOG_TARGET, "🔥 [force_beat] {} forced {height}", self.local_validator_addr);
                 height + NodeHeight(1)
             },
-            None => {
-                let leaf_block = self.state_store.with_read_tx(|tx| LeafBlock::get(tx, epoch))?;
-                leaf_block.height() + NodeHeight(1)
-            },
+            None => leaf_block.height() + NodeHeight(1),
         };
-        let is_leader = self
-            .leader_strategy
-            .is_leader(&self.local_validator_addr, local_committee, next_height);
+        let is_leader =
+            self.leader_strategy
+                .is_leader(&self.local_validator_addr, local_committee, &leader_seed, next_height);
 
         if !is_leader {
             debug!(
@@ -732,10 +836,13 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
     ) -> Result<(), HotStuffError> {
         let mut leaf_block = self.state_store.with_read_tx(|tx| LeafBlock::get(tx, epoch))?;
         if next_height > leaf_block.height + NodeHeight(1) {
-            let (high_qc, block) = self.state_store.with_read_tx(|tx| {
+            let (high_qc, block, leader_seed) = self.state_store.with_read_tx(|tx| {
                 let high_qc = HighQc::get(tx, epoch)?.get_quorum_certificate(tx)?;
                 let block = leaf_block.get_block(tx)?;
-                Ok::<_, HotStuffError>((high_qc, block))
+                // The dummy blocks and the proposal are justified by the high QC, so their leaders are selected using
+                // the seed of the high QC block
+                let leader_seed = get_block_leader_seed(tx, epoch, &high_qc.get_block(tx)?)?;
+                Ok::<_, HotStuffError>((high_qc, block, leader_seed))
             })?;
 
             info!(
@@ -753,6 +860,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 &high_qc,
                 *block.state_merkle_root(),
                 &self.leader_strategy,
+                &leader_seed,
                 local_committee,
                 block.timestamp(),
                 block.base_layer_block_height(),
@@ -763,7 +871,20 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         }
 
         let current_epoch = self.epoch_manager.current_epoch().await?;
-        let propose_epoch_end = current_epoch > epoch;
+        // Once an EndEpoch block is locked, only EndEpoch blocks may extend it
+        let is_epoch_end_locked = self
+            .state_store
+            .with_read_tx(|tx| LockedBlock::get(tx, epoch)?.get_block(tx))?
+            .is_epoch_end();
+        let propose_epoch_end = current_epoch > epoch || is_epoch_end_locked;
+        let defer_cross_shard_transactions = !propose_epoch_end &&
+            is_cross_shard_preparation_deferred(
+                &self.epoch_manager,
+                &self.config.consensus_constants,
+                epoch,
+                next_height,
+            )
+            .await?;
 
         self.on_propose
             .handle(
@@ -773,6 +894,7 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 *local_committee_info,
                 leaf_block,
                 propose_epoch_end,
+                defer_cross_shard_transactions,
             )
             .await?;
 
@@ -813,6 +935,12 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 "on_receive_foreign_proposal",
                 self.on_receive_foreign_proposal.handle(msg, local_committee_info).await,
             ),
+            HotstuffMessage::ForeignProposalRequest(msg) => log_err(
+                "on_receive_foreign_proposal_request",
+                self.on_receive_foreign_proposal_request
+                    .handle(from, msg, local_committee_info)
+                    .await,
+            ),
             HotstuffMessage::Vote(msg) => log_err(
                 "on_receive_vote",
                 self.on_receive_vote
@@ -875,16 +1003,57 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
         }
     }
 
-    fn create_genesis_block_if_required(&self, epoch: Epoch, shard_group: ShardGroup) -> Result<(), HotStuffError> {
+    fn create_genesis_block_if_required(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        transition: &ShardGroupTransition,
+    ) -> Result<(), HotStuffError> {
         self.state_store.with_write_tx(|tx| {
+            let num_preshards = self.config.consensus_constants.num_preshards;
+            let genesis_hash = self.config.consensus_constants.genesis_hash;
+            // The number of preshards and the genesis file are fixed at network genesis and cannot be changed on an
+            // existing chain
+            if let Some(existing_genesis) = tx.blocks_get_genesis_for_epoch(epoch).optional()? {
+                if let Some(genesis_num_preshards) = existing_genesis.num_preshards() {
+                    if genesis_num_preshards != num_preshards {
+                        return Err(HotStuffError::NumPreshardsMismatch {
+                            epoch,
+                            genesis_num_preshards,
+                            configured_num_preshards: num_preshards,
+                        });
+                    }
+                }
+                if existing_genesis.genesis_hash() != genesis_hash {
+                    return Err(HotStuffError::GenesisHashMismatch {
+                        epoch,
+                        genesis_hash: existing_genesis.genesis_hash(),
+                        configured_genesis_hash: genesis_hash,
+                    });
+                }
+                return Ok(());
+            }
+
             let previous_epoch = epoch.saturating_sub(Epoch(1));
-            let checkpoint = EpochCheckpoint::get(&**tx, previous_epoch).optional()?;
-            let state_merkle_root = checkpoint
-                .map(|cp| cp.compute_state_merkle_root())
-                .transpose()?
-                .unwrap_or_default();
+            // The genesis block is derived from the checkpoints of the shard groups that this shard group was formed
+            // from, so that every member derives the same genesis regardless of the shard group it was in previously
+            let source_shard_groups = if transition.is_unchanged() {
+                std::slice::from_ref(&shard_group)
+            } else {
+                transition.previous_shard_groups()
+            };
+            let checkpoints = get_source_checkpoints(&**tx, previous_epoch, source_shard_groups)?;
+            let is_first_epoch =
+                checkpoints.is_empty() && EpochCheckpoint::get(&**tx, previous_epoch).optional()?.is_none();
+            let leader_seed = calculate_epoch_leader_seed(epoch, &checkpoints);
+            let state_merkle_root = if is_first_epoch {
+                Default::default()
+            } else {
+                // Fails if the state of any shard has not been handed over yet
+                calculate_genesis_state_merkle_root(epoch, shard_group, &checkpoints)?
+            };
             // The parent for genesis blocks refer to this zero block
-            let mut zero_block = Block::zero_block(self.config.network, self.config.consensus_constants.num_preshards);
+            let mut zero_block = Block::zero_block(self.config.network, num_preshards);
             if !zero_block.exists(&**tx)? {
                 debug!(target: LOG_TARGET, "Creating zero block");
                 zero_block.justify().insert(tx)?;
@@ -897,8 +1066,11 @@ impl<TConsensusSpec: ConsensusSpec> HotstuffWorker<TConsensusSpec> {
                 self.config.network,
                 epoch,
                 shard_group,
+                num_preshards,
                 state_merkle_root,
                 self.config.sidechain_id.clone(),
+                leader_seed,
+                genesis_hash,
             );
             if !genesis.exists(&**tx)? {
                 info!(target: LOG_TARGET, "✨Creating genesis block {genesis}");
@@ -0,0 +1,16 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use tari_dan_common_types::Epoch;
+use tari_transaction::TransactionId;
+
+/// Requests the foreign proposals that contain the LocalPrepare/LocalAccept commands for the given transactions from
+/// a member of a foreign committee. The foreign committee responds with a ForeignProposal message for each block.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForeignProposalRequest {
+    pub epoch: Epoch,
+    pub transactions: HashSet<TransactionId>,
+}
@@ -7,7 +7,7 @@ use serde::Serialize;
 use tari_dan_common_types::Epoch;
 
 use super::{ForeignProposalMessage, MissingTransactionsResponse, NewViewMessage, ProposalMessage, VoteMessage};
-use crate::messages::{MissingTransactionsRequest, SyncRequestMessage, SyncResponseMessage};
+use crate::messages::{ForeignProposalRequest, MissingTransactionsRequest, SyncRequestMessage, SyncResponseMessage};
 
 // Serialize is implemented for the message logger
 #[derive(Debug, Clone, Serialize)]
@@ -15,6 +15,7 @@ pub enum HotstuffMessage {
     NewView(NewViewMessage),
     Proposal(ProposalMessage),
     ForeignProposal(ForeignProposalMessage),
+    ForeignProposalRequest(ForeignProposalRequest),
     Vote(VoteMessage),
     MissingTransactionsRequest(MissingTransactionsRequest),
     MissingTransactionsResponse(MissingTransactionsResponse),
@@ -29,6 +30,7 @@ impl HotstuffMessage {
             HotstuffMessage::NewView(_) => "NewView",
             HotstuffMessage::Proposal(_) => "Proposal",
             HotstuffMessage::ForeignProposal(_) => "ForeignProposal",
+            HotstuffMessage::ForeignProposalRequest(_) => "ForeignProposalRequest",
             HotstuffMessage::Vote(_) => "Vote",
             HotstuffMessage::MissingTransactionsRequest(_) => "MissingTransactionsRequest",
             HotstuffMessage::MissingTransactionsResponse(_) => "MissingTransactionsResponse",
@@ -42,6 +44,7 @@ impl HotstuffMessage {
             Self::NewView(msg) => msg.high_qc.epoch(),
             Self::Proposal(msg) => msg.block.epoch(),
             Self::ForeignProposal(msg) => msg.block.epoch(),
+            Self::ForeignProposalRequest(msg) => msg.epoch,
             Self::Vote(msg) => msg.epoch,
             Self::MissingTransactionsRequest(msg) => msg.epoch,
             Self::MissingTransactionsResponse(msg) => msg.epoch,
@@ -79,6 +82,12 @@ impl Display for HotstuffMessage {
                 )
             },
             HotstuffMessage::ForeignProposal(msg) => write!(f, "ForeignProposal({})", msg),
+            HotstuffMessage::ForeignProposalRequest(msg) => write!(
+                f,
+                "ForeignProposalRequest({} transaction(s), epoch: {})",
+                msg.transactions.len(),
+                msg.epoch
+            ),
             HotstuffMessage::Vote(msg) => write!(
                 f,
                 "Vote({}, {}, {})",
@@ -9,6 +9,9 @@ pub use new_view::*;
 mod proposal;
 pub use proposal::*;
 
+mod foreign_proposal_request;
+pub use foreign_proposal_request::*;
+
 mod vote;
 pub use vote::*;
 
@@ -11,11 +11,15 @@ use tari_dan_storage::consensus_models::{
     ForeignProposal,
     QuorumCertificate,
 };
+use tari_transaction::Transaction;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ProposalMessage {
     pub block: Block,
     pub foreign_proposals: Vec<ForeignProposal>,
+    /// The full transactions for new transactions in the block. This is empty for compact proposals, in which case
+    /// validators request any transactions that they are missing from the proposer.
+    pub transactions: Vec<Transaction>,
 }
 
 impl Display for ProposalMessage {
@@ -1,14 +1,29 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::time::Duration;
+
 use tari_dan_common_types::NodeHeight;
-use tari_dan_storage::consensus_models::{QuorumDecision, TransactionAtom, ValidBlock};
+use tari_dan_storage::consensus_models::{Block, QuorumCertificate, QuorumDecision, TransactionAtom, ValidBlock};
 use tari_transaction::TransactionId;
 
 use crate::{hotstuff::HotStuffError, messages::HotstuffMessage};
 
+/// Callbacks for consensus events. This allows embedders to collect telemetry without changes to the consensus code.
+/// Hooks are called on the consensus worker task and should not block.
 pub trait ConsensusHooks {
+    /// Called when a local proposal is received, before it is validated
+    fn on_proposal_received(&mut self, block: &Block);
     fn on_local_block_decide(&mut self, block: &ValidBlock, decision: Option<QuorumDecision>);
+    /// Called after a vote has been sent to the next leader. The processing time is measured from when the proposal was
+    /// received.
+    fn on_vote_sent(&mut self, block: &Block, decision: QuorumDecision, processing_time: Duration);
+    /// Called when this node, as leader, aggregates a quorum of votes into a new QC. The collection time is measured
+    /// from the first vote received for the block.
+    fn on_qc_formed(&mut self, qc: &QuorumCertificate, collection_time: Duration);
+    /// Called when a block is committed. The commit latency is measured from the block timestamp and has a resolution
+    /// of seconds.
+    fn on_block_committed(&mut self, block: &Block, num_finalized_transactions: usize, commit_latency: Duration);
 
     fn on_block_validation_failed<E: ToString>(&mut self, err: &E);
     fn on_message_received(&mut self, message: &HotstuffMessage);
@@ -19,7 +34,9 @@ pub trait ConsensusHooks {
     fn on_needs_sync(&mut self, local_height: NodeHeight, remote_qc_height: NodeHeight);
 
     fn on_transaction_ready(&mut self, tx_id: &TransactionId);
-    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom);
+    /// Called for each transaction that is finalized in a committed block. This is called after `on_block_committed`
+    /// for the block.
+    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom, committed_block: &Block);
 }
 
 #[derive(Debug, Clone)]
@@ -38,12 +55,36 @@ impl<T> OptionalHooks<T> {
 }
 
 impl<T: ConsensusHooks> ConsensusHooks for OptionalHooks<T> {
+    fn on_proposal_received(&mut self, block: &Block) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_proposal_received(block);
+        }
+    }
+
     fn on_local_block_decide(&mut self, block: &ValidBlock, decision: Option<QuorumDecision>) {
         if let Some(inner) = self.inner.as_mut() {
             inner.on_local_block_decide(block, decision);
         }
     }
 
+    fn on_vote_sent(&mut self, block: &Block, decision: QuorumDecision, processing_time: Duration) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_vote_sent(block, decision, processing_time);
+        }
+    }
+
+    fn on_qc_formed(&mut self, qc: &QuorumCertificate, collection_time: Duration) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_qc_formed(qc, collection_time);
+        }
+    }
+
+    fn on_block_committed(&mut self, block: &Block, num_finalized_transactions: usize, commit_latency: Duration) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.on_block_committed(block, num_finalized_transactions, commit_latency);
+        }
+    }
+
     fn on_block_validation_failed<E: ToString>(&mut self, err: &E) {
         if let Some(inner) = self.inner.as_mut() {
             inner.on_block_validation_failed(err);
@@ -86,9 +127,9 @@ impl<T: ConsensusHooks> ConsensusHooks for OptionalHooks<T> {
         }
     }
 
-    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom) {
+    fn on_transaction_finalized(&mut self, transaction: &TransactionAtom, committed_block: &Block) {
         if let Some(inner) = self.inner.as_mut() {
-            inner.on_transaction_finalized(transaction);
+            inner.on_transaction_finalized(transaction, committed_block);
         }
     }
 }
@@ -103,8 +144,16 @@ impl<T> From<T> for OptionalHooks<T> {
 pub struct NoopHooks;
 
 impl ConsensusHooks for NoopHooks {
+    fn on_proposal_received(&mut self, _block: &Block) {}
+
     fn on_local_block_decide(&mut self, _block: &ValidBlock, _decision: Option<QuorumDecision>) {}
 
+    fn on_vote_sent(&mut self, _block: &Block, _decision: QuorumDecision, _processing_time: Duration) {}
+
+    fn on_qc_formed(&mut self, _qc: &QuorumCertificate, _collection_time: Duration) {}
+
+    fn on_block_committed(&mut self, _block: &Block, _num_finalized_transactions: usize, _commit_latency: Duration) {}
+
     fn on_block_validation_failed<E: ToString>(&mut self, _: &E) {}
 
     fn on_message_received(&mut self, _message: &HotstuffMessage) {}
@@ -119,5 +168,5 @@ impl ConsensusHooks for NoopHooks {
 
     fn on_transaction_ready(&mut self, _tx_id: &TransactionId) {}
 
-    fn on_transaction_finalized(&mut self, _transaction: &TransactionAtom) {}
+    fn on_transaction_finalized(&mut self, _transaction: &TransactionAtom, _committed_block: &Block) {}
 }
@@ -20,15 +20,25 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{FixedHash, PublicKey};
 use tari_dan_common_types::{committee::Committee, NodeHeight};
 
+/// Selects the leader for a height. The leader seed is derived from the VRF output of the block that the proposal for
+/// the height justifies (see `get_block_leader_seed`), so leaders are only known one block in advance.
 pub trait LeaderStrategy<TAddr> {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, height: NodeHeight) -> u32;
+    fn calculate_leader(&self, committee: &Committee<TAddr>, leader_seed: &FixedHash, height: NodeHeight) -> u32;
 
-    fn is_leader(&self, validator_addr: &TAddr, committee: &Committee<TAddr>, height: NodeHeight) -> bool
-    where TAddr: PartialEq {
-        let position = self.calculate_leader(committee, height);
+    fn is_leader(
+        &self,
+        validator_addr: &TAddr,
+        committee: &Committee<TAddr>,
+        leader_seed: &FixedHash,
+        height: NodeHeight,
+    ) -> bool
+    where
+        TAddr: PartialEq,
+    {
+        let position = self.calculate_leader(committee, leader_seed, height);
         if let Some((addr, _)) = committee.members.get(position as usize) {
             addr == validator_addr
         } else {
@@ -40,16 +50,22 @@ pub trait LeaderStrategy<TAddr> {
         &self,
         validator_addr: &TAddr,
         committee: &Committee<TAddr>,
+        leader_seed: &FixedHash,
         height: NodeHeight,
     ) -> bool
     where
         TAddr: PartialEq,
     {
-        self.is_leader(validator_addr, committee, height + NodeHeight(1))
+        self.is_leader(validator_addr, committee, leader_seed, height + NodeHeight(1))
     }
 
-    fn get_leader<'b>(&self, committee: &'b Committee<TAddr>, height: NodeHeight) -> (&'b TAddr, &'b PublicKey) {
-        let index = self.calculate_leader(committee, height);
+    fn get_leader<'b>(
+        &self,
+        committee: &'b Committee<TAddr>,
+        leader_seed: &FixedHash,
+        height: NodeHeight,
+    ) -> (&'b TAddr, &'b PublicKey) {
+        let index = self.calculate_leader(committee, leader_seed, height);
         let (addr, pk) = committee.members.get(index as usize).unwrap();
         (addr, pk)
     }
@@ -57,8 +73,9 @@ pub trait LeaderStrategy<TAddr> {
     fn get_leader_for_next_height<'b>(
         &self,
         committee: &'b Committee<TAddr>,
+        leader_seed: &FixedHash,
         height: NodeHeight,
     ) -> (&'b TAddr, &'b PublicKey) {
-        self.get_leader(committee, height + NodeHeight(1))
+        self.get_leader(committee, leader_seed, height + NodeHeight(1))
     }
 }
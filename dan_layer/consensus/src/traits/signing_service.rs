@@ -2,18 +2,28 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_common_types::types::{FixedHash, PublicKey};
-use tari_dan_common_types::hashing::vote_signature_hasher;
-use tari_dan_storage::consensus_models::{BlockId, QuorumDecision, ValidatorSchnorrSignature, ValidatorSignature};
+use tari_dan_common_types::vrf::VrfProof;
+use tari_dan_storage::consensus_models::{
+    create_vote_message,
+    BlockId,
+    DoubleVoteEvidence,
+    QuorumDecision,
+    ValidatorSchnorrSignature,
+    ValidatorSignature,
+};
 
 pub trait ValidatorSignatureService {
     fn sign<M: AsRef<[u8]>>(&self, message: M) -> ValidatorSchnorrSignature;
 
+    /// Proves the VRF output for the input using the validator's secret key
+    fn vrf_prove<M: AsRef<[u8]>>(&self, input: M) -> VrfProof;
+
     fn public_key(&self) -> &PublicKey;
 }
 
 pub trait VoteSignatureService: ValidatorSignatureService {
     fn create_message(&self, block_id: &BlockId, decision: &QuorumDecision) -> FixedHash {
-        vote_signature_hasher().chain(block_id).chain(decision).result()
+        create_vote_message(block_id, decision)
     }
 
     fn sign_vote(&self, block_id: &BlockId, decision: &QuorumDecision) -> ValidatorSignature {
@@ -23,4 +33,12 @@ pub trait VoteSignatureService: ValidatorSignatureService {
     }
 
     fn verify(&self, signature: &ValidatorSignature, block_id: &BlockId, decision: &QuorumDecision) -> bool;
+
+    /// Returns true if the evidence is well-formed and both of its votes are correctly signed
+    fn verify_double_vote_evidence(&self, evidence: &DoubleVoteEvidence) -> bool {
+        evidence.validate().is_ok() &&
+            [&evidence.first, &evidence.second]
+                .into_iter()
+                .all(|vote| self.verify(&vote.signature, vote.block.id(), &vote.decision))
+    }
 }
@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 
+use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{optional::IsNotFoundError, Epoch, SubstateRequirement};
 use tari_dan_storage::{consensus_models::ExecutedTransaction, StateStore, StorageError};
 use tari_engine_types::substate::Substate;
@@ -59,6 +60,8 @@ pub trait BlockTransactionExecutor<TStateStore: StateStore> {
         &self,
         transaction: Transaction,
         current_epoch: Epoch,
+        current_timestamp: u64,
+        random_beacon: FixedHash,
         resolved_inputs: &HashMap<SubstateRequirement, Substate>,
     ) -> Result<ExecutedTransaction, BlockTransactionExecutorError>;
 }
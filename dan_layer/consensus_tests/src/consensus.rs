@@ -8,11 +8,24 @@
 //! Use `Test::builder().debug_sql("/tmp/test{}.db")...` to create a database file for each validator
 //! where {} is replaced with the node address.
 
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use tari_common_types::types::PrivateKey;
-use tari_consensus::hotstuff::HotStuffError;
-use tari_dan_common_types::{optional::Optional, Epoch, LockIntent, NodeHeight, SubstateRequirement};
+use tari_consensus::{consensus_constants::ExecutionMode, hotstuff::HotStuffError, messages::HotstuffMessage};
+use tari_dan_common_types::{
+    optional::Optional,
+    Epoch,
+    LockIntent,
+    NodeHeight,
+    SubstateRequirement,
+    VersionedSubstateId,
+};
 use tari_dan_storage::{
     consensus_models::{
         AbortReason,
@@ -20,14 +33,15 @@ use tari_dan_storage::{
         Command,
         Decision,
         SubstateRequirementLockIntent,
+        TransactionOrdering,
         TransactionRecord,
         VersionedSubstateIdLockIntent,
     },
     StateStore,
     StateStoreReadTransaction,
 };
-use tari_engine_types::commit_result::RejectReason;
-use tari_transaction::Transaction;
+use tari_engine_types::{commit_result::RejectReason, execution_failure::ExecutionFailure};
+use tari_transaction::{IdempotencyKey, Transaction};
 
 use crate::support::{
     build_transaction_from,
@@ -88,6 +102,61 @@ async fn single_transaction() {
     test.assert_clean_shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn single_transaction_deferred_execution() {
+    setup_logger();
+    let mut test = Test::builder()
+        .modify_consensus_constants(|config_mut| {
+            config_mut.execution_mode = ExecutionMode::Deferred;
+        })
+        .add_committee(0, vec!["1", "2"])
+        .start()
+        .await;
+    let (tx1, _, _) = test.send_transaction_to_all(Decision::Commit, 1, 1, 1).await;
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height >= NodeHeight(20) {
+            panic!("Not all transaction committed after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_committed();
+
+    // The transaction is sequenced and only executed once its inputs are pledged, so it is never proposed as LocalOnly
+    test.get_validator(&TestAddress::new("1"))
+        .state_store
+        .with_read_tx(|tx| {
+            let mut block = tx.blocks_get_tip(Epoch(1), test.get_validator(&TestAddress::new("1")).shard_group)?;
+            let mut has_all_prepare = false;
+            loop {
+                block = block.get_parent(tx)?;
+                if block.id().is_zero() {
+                    break;
+                }
+
+                for cmd in block.commands() {
+                    assert!(!matches!(cmd, Command::LocalOnly(_)));
+                    has_all_prepare |= matches!(cmd, Command::AllPrepare(_));
+                }
+            }
+            assert!(has_all_prepare);
+            Ok::<_, HotStuffError>(())
+        })
+        .unwrap();
+    test.assert_all_validators_have_decision(tx1.id(), Decision::Commit)
+        .await;
+
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn single_transaction_multi_vn() {
     setup_logger();
@@ -192,6 +261,40 @@ async fn propose_blocks_with_queued_up_transactions_until_all_committed() {
     test.assert_clean_shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn propose_blocks_with_shuffled_transaction_ordering_until_all_committed() {
+    setup_logger();
+    let mut test = Test::builder()
+        .modify_consensus_constants(|config_mut| {
+            config_mut.transaction_ordering = TransactionOrdering::Shuffled;
+            // Only some of the queued transactions fit in each block
+            config_mut.max_block_size = 4;
+        })
+        .add_committee(0, vec!["1", "2", "3"])
+        .start()
+        .await;
+    for _ in 0..10 {
+        test.send_transaction_to_all(Decision::Commit, 1, 5, 1).await;
+    }
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height > NodeHeight(30) {
+            panic!("Not all transaction committed after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_committed();
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn propose_blocks_with_new_transactions_until_all_committed() {
     setup_logger();
@@ -412,7 +515,7 @@ async fn foreign_shard_group_decides_to_abort() {
     // non-byzantine nodes MUST have the same decision given the same pledges. However, this does test that is it not
     // possible for others to COMMIT without all committees agreeing to COMMIT.
     let mut tx2 = tx1.clone();
-    tx2.set_abort_reason(RejectReason::ExecutionFailure("Test aborted".to_string()));
+    tx2.set_abort_reason(RejectReason::ExecutionFailure(ExecutionFailure::other("Test aborted")));
 
     test.create_execution_at_destination_for_transaction(
         TestVnDestination::Committee(0),
@@ -853,6 +956,76 @@ async fn single_shard_input_conflict() {
     log::info!("total messages sent: {}", test.network().total_messages_sent());
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn single_shard_duplicate_idempotency_key() {
+    setup_logger();
+    let mut test = Test::builder().add_committee(0, vec!["1", "2"]).start().await;
+
+    let mut inputs = test.create_substates_on_vns(TestVnDestination::All, 3);
+    let idempotency_key = IdempotencyKey::new([1u8; 32]);
+    let other_signer = PrivateKey::from(1u64);
+
+    let build = |input: VersionedSubstateId, secret: &PrivateKey| {
+        let tx = Transaction::builder()
+            .add_input(input.clone())
+            .with_idempotency_key(Some(idempotency_key))
+            .sign(secret)
+            .build();
+        let tx = TransactionRecord::new(tx);
+        test.add_execution_at_destination(TestVnDestination::All, ExecuteSpec {
+            transaction: tx.transaction().clone(),
+            decision: Decision::Commit,
+            fee: 1,
+            inputs: vec![VersionedSubstateIdLockIntent::write(input, true).into()],
+            new_outputs: vec![],
+        });
+        tx
+    };
+    let tx1 = build(inputs.pop().unwrap(), &PrivateKey::default());
+    // Same signer and key as tx1
+    let tx2 = build(inputs.pop().unwrap(), &PrivateKey::default());
+    // Same key as tx1 but bound to a different signer
+    let tx3 = build(inputs.pop().unwrap(), &other_signer);
+
+    for tx in [&tx1, &tx2, &tx3] {
+        test.network()
+            .send_transaction(TestVnDestination::All, tx.clone())
+            .await;
+    }
+
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        let leaf1 = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf1.height > NodeHeight(30) {
+            panic!("Not all transaction committed after {} blocks", leaf1.height,);
+        }
+    }
+
+    // Both transactions are proposed in the same block, which prepares them in transaction id order
+    let (first, second) = if tx1.id() < tx2.id() {
+        (&tx1, &tx2)
+    } else {
+        (&tx2, &tx1)
+    };
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_have_decision(first.id(), Decision::Commit)
+        .await;
+    test.assert_all_validators_have_decision(second.id(), Decision::Abort(AbortReason::InvalidTransaction))
+        .await;
+    test.assert_all_validators_have_decision(tx3.id(), Decision::Commit)
+        .await;
+
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn epoch_change() {
     setup_logger();
@@ -890,17 +1063,22 @@ async fn epoch_change() {
         .state_store
         .with_read_tx(|tx| {
             let mut block = tx.blocks_get_tip(Epoch(1), test.get_validator(&TestAddress::new("1")).shard_group)?;
-            loop {
-                block = block.get_parent(tx)?;
-                if block.id().is_zero() {
-                    break;
-                }
+            let mut num_epoch_end_blocks = 0;
+            while !block.id().is_zero() {
                 if block.is_epoch_end() {
-                    return Ok::<_, HotStuffError>(());
+                    num_epoch_end_blocks += 1;
                 }
+                block = block.get_parent(tx)?;
             }
 
-            panic!("No epoch end block found");
+            assert!(num_epoch_end_blocks > 0, "No epoch end block found");
+            // The first EndEpoch block is committed once its EndEpoch child is certified, so the chain only needs one
+            // more EndEpoch block to carry that certificate
+            assert!(
+                num_epoch_end_blocks <= 3,
+                "Expected at most 3 epoch end blocks but found {num_epoch_end_blocks}"
+            );
+            Ok::<_, HotStuffError>(())
         })
         .unwrap();
 
@@ -976,6 +1154,57 @@ async fn leader_failure_node_goes_down() {
     test.assert_clean_shutdown_except(&[failure_node]).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn leader_failure_proposal_delivered_to_part_of_the_committee() {
+    setup_logger();
+    let mut test = Test::builder()
+        // Allow enough time for leader failures
+        .with_test_timeout(Duration::from_secs(60))
+        .modify_consensus_constants(|config_mut| {
+            // Prevent suspends
+            config_mut.missed_proposal_suspend_threshold = 10;
+            config_mut.pacemaker_block_time = Duration::from_secs(2);
+        })
+        .with_message_filter(Box::new(|_: &TestAddress, to: &TestAddress, msg: &HotstuffMessage| {
+            // The proposal for height 3 only reaches half of the committee, so it cannot be certified and the
+            // validators have different leaf blocks when the view changes
+            match msg {
+                HotstuffMessage::Proposal(proposal) if proposal.block.height() == NodeHeight(3) => {
+                    *to != TestAddress::new("3") && *to != TestAddress::new("4")
+                },
+                _ => true,
+            }
+        }))
+        .add_committee(0, vec!["1", "2", "3", "4"])
+        .start()
+        .await;
+
+    for _ in 0..10 {
+        test.send_transaction_to_all(Decision::Commit, 1, 2, 1).await;
+    }
+
+    test.network().start();
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        let (_, _, _, committed_height) = test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        if committed_height > NodeHeight(50) {
+            panic!("Not all transaction committed after {} blocks", committed_height);
+        }
+    }
+
+    // Every validator sent its NEWVIEW to the same leader, so the view change completed
+    assert!(test.network().total_messages_filtered() > 0);
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_committed();
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn leader_failure_node_goes_down_and_gets_suspended() {
     setup_logger();
@@ -1116,6 +1345,452 @@ async fn foreign_block_distribution() {
     test.assert_clean_shutdown().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn foreign_proposals_are_requested_if_broadcast_is_missed() {
+    setup_logger();
+    let is_requested = Arc::new(AtomicBool::new(false));
+    let is_requested_filter = is_requested.clone();
+    let mut test = Test::builder()
+        // Allow enough time for leader failures while foreign proposals are missing
+        .with_test_timeout(Duration::from_secs(60))
+        .modify_consensus_constants(|config_mut| {
+            // Prevent suspends
+            config_mut.missed_proposal_suspend_threshold = 10;
+            config_mut.pacemaker_block_time = Duration::from_secs(2);
+            config_mut.foreign_proposal_request_interval = 1;
+        })
+        .with_message_filter(Box::new(move |_: &TestAddress, _: &TestAddress, msg: &HotstuffMessage| {
+            match msg {
+                // Drop all broadcast foreign proposals until a committee requests them
+                HotstuffMessage::ForeignProposal(_) => is_requested_filter.load(Ordering::SeqCst),
+                HotstuffMessage::ForeignProposalRequest(_) => {
+                    is_requested_filter.store(true, Ordering::SeqCst);
+                    true
+                },
+                _ => true,
+            }
+        }))
+        .add_committee(0, vec!["1", "2", "3"])
+        .add_committee(1, vec!["4", "5", "6"])
+        .start()
+        .await;
+    for _ in 0..10 {
+        test.send_transaction_to_all(Decision::Commit, 1, 5, 1).await;
+    }
+
+    test.network().start();
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        let leaf1 = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        let leaf2 = test.get_validator(&TestAddress::new("4")).get_leaf_block();
+        if leaf1.height > NodeHeight(100) || leaf2.height > NodeHeight(100) {
+            panic!(
+                "Not all transaction committed after {}/{} blocks",
+                leaf1.height, leaf2.height
+            );
+        }
+    }
+
+    assert!(is_requested.load(Ordering::SeqCst));
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn stalled_multishard_transaction_aborts_when_lock_lease_expires() {
+    setup_logger();
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .modify_consensus_constants(|config_mut| {
+            // Prevent suspends
+            config_mut.missed_proposal_suspend_threshold = 10;
+            config_mut.pacemaker_block_time = Duration::from_secs(2);
+            // Foreign proposals never arrive
+            config_mut.foreign_proposal_request_interval = 0;
+            config_mut.substate_lock_lease_blocks = 5;
+        })
+        .with_message_filter(Box::new(|_: &TestAddress, _: &TestAddress, msg: &HotstuffMessage| {
+            !matches!(msg, HotstuffMessage::ForeignProposal(_))
+        }))
+        .add_committee(0, vec!["1", "2", "3"])
+        .add_committee(1, vec!["4", "5", "6"])
+        .start()
+        .await;
+    let (tx1, _, _) = test.send_transaction_to_all(Decision::Commit, 1, 2, 1).await;
+
+    test.network().start();
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        let leaf1 = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        let leaf2 = test.get_validator(&TestAddress::new("4")).get_leaf_block();
+        if leaf1.height > NodeHeight(50) || leaf2.height > NodeHeight(50) {
+            panic!("Transaction not aborted after {}/{} blocks", leaf1.height, leaf2.height);
+        }
+    }
+
+    test.assert_all_validators_have_decision(tx1.id(), Decision::Abort(AbortReason::LockLeaseExpired))
+        .await;
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn cross_shard_deadlock_aborts_the_lower_id_transaction() {
+    setup_logger();
+    let is_foreign_proposal_enabled = Arc::new(AtomicBool::new(false));
+    let mut test = Test::builder()
+        .with_test_timeout(Duration::from_secs(60))
+        .modify_consensus_constants(|config_mut| {
+            // Prevent suspends
+            config_mut.missed_proposal_suspend_threshold = 10;
+            config_mut.pacemaker_block_time = Duration::from_secs(2);
+            config_mut.foreign_proposal_request_interval = 1;
+            config_mut.cross_shard_deadlock_blocks = 3;
+        })
+        .with_message_filter(Box::new({
+            let is_foreign_proposal_enabled = is_foreign_proposal_enabled.clone();
+            move |_: &TestAddress, _: &TestAddress, msg: &HotstuffMessage| match msg {
+                // Each shard group prepares its own transaction before it learns that the other shard group did
+                HotstuffMessage::ForeignProposal(_) => is_foreign_proposal_enabled.load(Ordering::SeqCst),
+                _ => true,
+            }
+        }))
+        .add_committee(0, vec!["1", "2", "3"])
+        .add_committee(1, vec!["4", "5", "6"])
+        .start()
+        .await;
+
+    let id0 = test
+        .create_substates_on_vns(TestVnDestination::Committee(0), 1)
+        .pop()
+        .unwrap();
+    let id1 = test
+        .create_substates_on_vns(TestVnDestination::Committee(1), 1)
+        .pop()
+        .unwrap();
+
+    let mut transactions = [1u64, 2].map(|signer| {
+        let transaction = Transaction::builder()
+            .add_input(SubstateRequirement::unversioned(id0.substate_id().clone()))
+            .add_input(SubstateRequirement::unversioned(id1.substate_id().clone()))
+            .sign(&PrivateKey::from(signer))
+            .build();
+        TransactionRecord::new(transaction)
+    });
+    transactions.sort_by(|a, b| a.id().cmp(b.id()));
+    let [lower, higher] = transactions;
+
+    for transaction in [&lower, &higher] {
+        test.add_execution_at_destination(TestVnDestination::All, ExecuteSpec {
+            transaction: transaction.transaction().clone(),
+            decision: Decision::Commit,
+            fee: 1,
+            inputs: vec![
+                VersionedSubstateIdLockIntent::write(id0.clone(), false).into(),
+                VersionedSubstateIdLockIntent::write(id1.clone(), false).into(),
+            ],
+            new_outputs: vec![],
+        });
+    }
+
+    // Shard group 0 locks its substate for the lower id transaction and shard group 1 for the higher id transaction
+    test.send_transaction_to_destination(TestVnDestination::Committee(0), lower.clone())
+        .await;
+    test.send_transaction_to_destination(TestVnDestination::Committee(1), higher.clone())
+        .await;
+
+    test.network().start();
+    test.start_epoch(Epoch(1)).await;
+
+    let has_locks = |test: &Test, addr: &str, transaction: &TransactionRecord| {
+        test.get_validator(&TestAddress::new(addr))
+            .state_store
+            .with_read_tx(|tx| tx.substate_locks_get_locked_substates_for_transaction(transaction.id()))
+            .map(|locks| !locks.is_empty())
+            .unwrap()
+    };
+    loop {
+        let (_, _, _, committed_height) = test.on_block_committed().await;
+        if has_locks(&test, "1", &lower) && has_locks(&test, "4", &higher) {
+            break;
+        }
+        if committed_height > NodeHeight(20) {
+            panic!("Transactions not locally prepared after {} blocks", committed_height);
+        }
+    }
+
+    // Each transaction now waits for a substate that the other transaction locked in the other shard group
+    test.send_transaction_to_destination(TestVnDestination::Committee(1), lower.clone())
+        .await;
+    test.send_transaction_to_destination(TestVnDestination::Committee(0), higher.clone())
+        .await;
+    is_foreign_proposal_enabled.store(true, Ordering::SeqCst);
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        let leaf1 = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        let leaf2 = test.get_validator(&TestAddress::new("4")).get_leaf_block();
+        if leaf1.height > NodeHeight(50) || leaf2.height > NodeHeight(50) {
+            panic!("Deadlock not resolved after {}/{} blocks", leaf1.height, leaf2.height);
+        }
+    }
+
+    test.assert_all_validators_have_decision(higher.id(), Decision::Commit)
+        .await;
+    test.with_all_validators(|v| {
+        let decision = v
+            .state_store
+            .with_read_tx(|tx| TransactionRecord::get(tx, lower.id()))
+            .unwrap()
+            .final_decision();
+        assert!(
+            decision.is_some_and(|d| d.is_abort()),
+            "Expected validator {} to ABORT transaction {} but decision is {:?}",
+            v.address,
+            lower.id(),
+            decision
+        );
+    });
+    // The shard group that holds the lower id transaction detects the deadlock
+    for addr in ["1", "2", "3"] {
+        let transaction = test
+            .get_validator(&TestAddress::new(addr))
+            .state_store
+            .with_read_tx(|tx| TransactionRecord::get(tx, lower.id()))
+            .unwrap();
+        assert!(
+            matches!(transaction.abort_reason(), Some(RejectReason::CrossShardDeadlock(_))),
+            "Expected validator {} to abort with a cross-shard deadlock but reason is {:?}",
+            addr,
+            transaction.abort_reason()
+        );
+    }
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn cross_shard_transaction_is_not_prepared_while_the_committee_layout_switches() {
+    setup_logger();
+    const SWITCH_BLOCKS: u64 = 6;
+    let early_prepare_proposed = Arc::new(AtomicBool::new(false));
+    let mut test = Test::builder()
+        // Allow enough time for leader failures
+        .with_test_timeout(Duration::from_secs(60))
+        .modify_consensus_constants(|config_mut| {
+            // Prevent suspends
+            config_mut.missed_proposal_suspend_threshold = 10;
+            config_mut.pacemaker_block_time = Duration::from_secs(2);
+            config_mut.committee_layout_switch_blocks = SWITCH_BLOCKS;
+        })
+        // Validator 1 does not know that the layout changed, so it proposes the transaction within the switch window
+        .with_committee_layout_change(Epoch(1), vec!["1"])
+        .with_message_filter(Box::new({
+            let early_prepare_proposed = early_prepare_proposed.clone();
+            move |_: &TestAddress, _: &TestAddress, msg: &HotstuffMessage| {
+                if let HotstuffMessage::Proposal(proposal) = msg {
+                    if proposal.block.height().as_u64() <= SWITCH_BLOCKS &&
+                        proposal.block.commands().iter().any(|cmd| matches!(cmd, Command::Prepare(_)))
+                    {
+                        early_prepare_proposed.store(true, Ordering::SeqCst);
+                    }
+                }
+                true
+            }
+        }))
+        .add_committee(0, vec!["1", "2", "3"])
+        .add_committee(1, vec!["4", "5", "6"])
+        .start()
+        .await;
+    let (tx1, _, _) = test.send_transaction_to_all(Decision::Commit, 1, 2, 1).await;
+
+    test.network().start();
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+
+        let leaf1 = test.get_validator(&TestAddress::new("2")).get_leaf_block();
+        let leaf2 = test.get_validator(&TestAddress::new("4")).get_leaf_block();
+        if leaf1.height > NodeHeight(50) || leaf2.height > NodeHeight(50) {
+            panic!(
+                "Transaction not committed after {}/{} blocks",
+                leaf1.height, leaf2.height
+            );
+        }
+    }
+
+    assert!(early_prepare_proposed.load(Ordering::SeqCst));
+    // The other validators did not vote for the block that prepared the transaction within the switch window
+    for addr in ["2", "4"] {
+        let validator = test.get_validator(&TestAddress::new(addr));
+        validator
+            .state_store
+            .with_read_tx(|tx| {
+                let mut block = tx.blocks_get_tip(Epoch(1), validator.shard_group)?;
+                let mut prepared_at = None;
+                while !block.id().is_zero() {
+                    if block
+                        .commands()
+                        .iter()
+                        .any(|cmd| matches!(cmd, Command::Prepare(atom) if atom.id() == tx1.id()))
+                    {
+                        prepared_at = Some(block.height());
+                    }
+                    block = block.get_parent(tx)?;
+                }
+
+                let prepared_at = prepared_at.expect("Transaction was not prepared");
+                assert!(
+                    prepared_at.as_u64() > SWITCH_BLOCKS,
+                    "Transaction prepared at height {prepared_at} within the switch window"
+                );
+                Ok::<_, HotStuffError>(())
+            })
+            .unwrap();
+    }
+
+    test.assert_all_validators_have_decision(tx1.id(), Decision::Commit)
+        .await;
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transaction_aborted_if_sequenced_after_max_epoch() {
+    setup_logger();
+    let mut test = Test::builder().add_committee(0, vec!["1", "2"]).start().await;
+    let inputs = test.create_substates_on_vns(TestVnDestination::All, 1);
+    let tx = Transaction::builder()
+        .with_inputs(inputs.iter().cloned().map(Into::into))
+        // Only valid before the first epoch
+        .with_max_epoch(Some(Epoch(0)))
+        .sign(&PrivateKey::default())
+        .build();
+    let tx = TransactionRecord::new(tx);
+
+    test.send_transaction_to_destination(TestVnDestination::All, tx.clone())
+        .await;
+
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height >= NodeHeight(10) {
+            panic!("Transaction not finalized after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_have_decision(tx.id(), Decision::Abort(AbortReason::InvalidTransaction))
+        .await;
+
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transaction_aborted_if_it_exceeds_max_instructions() {
+    setup_logger();
+    let mut test = Test::builder()
+        .modify_consensus_constants(|config_mut| {
+            config_mut.max_instructions = 1;
+        })
+        .add_committee(0, vec!["1", "2"])
+        .start()
+        .await;
+    let inputs = test.create_substates_on_vns(TestVnDestination::All, 1);
+    let tx = Transaction::builder()
+        .with_inputs(inputs.iter().cloned().map(Into::into))
+        .drop_all_proofs_in_workspace()
+        .drop_all_proofs_in_workspace()
+        .sign(&PrivateKey::default())
+        .build();
+    let tx = TransactionRecord::new(tx);
+
+    test.send_transaction_to_destination(TestVnDestination::All, tx.clone())
+        .await;
+
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height >= NodeHeight(10) {
+            panic!("Transaction not finalized after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_have_decision(tx.id(), Decision::Abort(AbortReason::InvalidTransaction))
+        .await;
+
+    test.assert_clean_shutdown().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn transaction_aborted_if_an_input_rent_has_expired() {
+    setup_logger();
+    let mut test = Test::builder().add_committee(0, vec!["1", "2"]).start().await;
+    // Rent is only paid for the genesis epoch
+    let inputs = test.create_substates_on_vns_with_rent(TestVnDestination::All, 1, Some(0));
+    let tx = Transaction::builder()
+        .with_inputs(inputs.iter().cloned().map(Into::into))
+        .sign(&PrivateKey::default())
+        .build();
+    let tx = TransactionRecord::new(tx);
+
+    test.send_transaction_to_destination(TestVnDestination::All, tx.clone())
+        .await;
+
+    test.start_epoch(Epoch(1)).await;
+
+    loop {
+        test.on_block_committed().await;
+
+        if test.is_transaction_pool_empty() {
+            break;
+        }
+        let leaf = test.get_validator(&TestAddress::new("1")).get_leaf_block();
+        if leaf.height >= NodeHeight(10) {
+            panic!("Transaction not finalized after {} blocks", leaf.height);
+        }
+    }
+
+    test.assert_all_validators_at_same_height().await;
+    test.assert_all_validators_have_decision(tx.id(), Decision::Abort(AbortReason::InputRentExpired))
+        .await;
+
+    test.assert_clean_shutdown().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn single_shard_unversioned_inputs() {
     setup_logger();
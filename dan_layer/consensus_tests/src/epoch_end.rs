@@ -0,0 +1,176 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, ExtraData, NodeHeight, PeerAddress, ShardGroup};
+use tari_dan_storage::{
+    consensus_models::{
+        AggregateValidatorSignature,
+        Block,
+        BlockId,
+        Command,
+        LastExecuted,
+        LockedBlock,
+        QuorumCertificate,
+        QuorumDecision,
+        SignerBitmap,
+    },
+    StateStore,
+    StorageError,
+};
+use tari_state_store_sqlite::SqliteStateStore;
+
+use crate::support::{logging::setup_logger, TEST_NUM_PRESHARDS};
+
+type TestStore = SqliteStateStore<PeerAddress>;
+type TestWriteTx<'a> = <TestStore as StateStore>::WriteTransaction<'a>;
+
+#[test]
+fn it_commits_an_epoch_end_block_after_two_chained_epoch_end_qcs() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let block2 = add_block(&mut tx, &block1, BTreeSet::new());
+    let epoch_end1 = add_block(&mut tx, &block2, epoch_end_commands());
+    let epoch_end2 = add_block(&mut tx, &epoch_end1, epoch_end_commands());
+    let mut committed = vec![];
+    for block in [&block1, &block2, &epoch_end1, &epoch_end2] {
+        update_nodes(&mut tx, block, &mut committed);
+    }
+
+    // The first QC over an EndEpoch child locks the EndEpoch block, but does not commit it
+    assert_eq!(LockedBlock::get(&*tx, Epoch(1)).unwrap().block_id, *epoch_end1.id());
+    assert!(!committed.contains(epoch_end1.id()));
+
+    // The second chained QC commits it, together with the blocks before it
+    let epoch_end3 = add_block(&mut tx, &epoch_end2, epoch_end_commands());
+    update_nodes(&mut tx, &epoch_end3, &mut committed);
+    assert_eq!(committed, [*block1.id(), *block2.id(), *epoch_end1.id()]);
+    assert_eq!(LastExecuted::get(&*tx).unwrap().block_id, *epoch_end1.id());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_rejects_non_epoch_end_blocks_after_an_epoch_end_block_is_locked() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let epoch_end1 = add_block(&mut tx, &block1, epoch_end_commands());
+    let block2 = create_block(&epoch_end1, BTreeSet::new());
+    // Nothing is locked yet, so the epoch may still continue
+    assert!(!block2.violates_epoch_end_lock(&*tx).unwrap());
+
+    let epoch_end2 = add_block(&mut tx, &epoch_end1, epoch_end_commands());
+    let mut committed = vec![];
+    for block in [&block1, &epoch_end1, &epoch_end2] {
+        update_nodes(&mut tx, block, &mut committed);
+    }
+    assert_eq!(LockedBlock::get(&*tx, Epoch(1)).unwrap().block_id, *epoch_end1.id());
+
+    let block3 = create_block(&epoch_end2, BTreeSet::new());
+    assert!(block3.violates_epoch_end_lock(&*tx).unwrap());
+    let epoch_end3 = create_block(&epoch_end2, epoch_end_commands());
+    assert!(!epoch_end3.violates_epoch_end_lock(&*tx).unwrap());
+    tx.rollback().unwrap();
+}
+
+fn create_store() -> TestStore {
+    SqliteStateStore::connect(":memory:").unwrap()
+}
+
+fn epoch_end_commands() -> BTreeSet<Command> {
+    [Command::EndEpoch].into_iter().collect()
+}
+
+/// Processes the QC of the block in the same way as a validator that receives the block, recording the blocks that
+/// are committed
+fn update_nodes(tx: &mut TestWriteTx<'_>, block: &Block, committed: &mut Vec<BlockId>) {
+    block
+        .update_nodes(
+            tx,
+            |_, _, _, _| Ok::<_, StorageError>(()),
+            |_, _, block| {
+                committed.push(*block.id());
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+/// Creates the genesis block for the epoch in the same way as the hotstuff worker
+fn create_genesis(tx: &mut TestWriteTx<'_>, epoch: Epoch) -> Block {
+    let zero_block = Block::zero_block(Default::default(), TEST_NUM_PRESHARDS);
+    zero_block.justify().insert(tx).unwrap();
+    zero_block.insert(tx).unwrap();
+
+    let genesis = Block::genesis(
+        Default::default(),
+        epoch,
+        ShardGroup::all_shards(TEST_NUM_PRESHARDS),
+        TEST_NUM_PRESHARDS,
+        FixedHash::zero(),
+        None,
+        FixedHash::zero(),
+        None,
+    );
+    genesis.justify().save(tx).unwrap();
+    genesis.insert(tx).unwrap();
+    genesis.set_as_justified(tx).unwrap();
+    genesis.as_locked_block().set(tx).unwrap();
+    genesis.as_leaf_block().set(tx).unwrap();
+    genesis.as_last_executed().set(tx).unwrap();
+    genesis.as_last_voted().set(tx).unwrap();
+    genesis.justify().as_high_qc().set(tx).unwrap();
+    genesis
+}
+
+/// Adds a block that extends and justifies the parent block
+fn add_block(tx: &mut TestWriteTx<'_>, parent: &Block, commands: BTreeSet<Command>) -> Block {
+    let block = create_block(parent, commands);
+    block.justify().save(tx).unwrap();
+    block.insert(tx).unwrap();
+    block
+}
+
+fn create_block(parent: &Block, commands: BTreeSet<Command>) -> Block {
+    Block::create(
+        Default::default(),
+        *parent.id(),
+        create_qc(parent),
+        parent.height() + NodeHeight(1),
+        parent.epoch(),
+        parent.shard_group(),
+        Default::default(),
+        commands,
+        FixedHash::zero(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        ExtraData::default(),
+    )
+    .unwrap()
+}
+
+fn create_qc(block: &Block) -> QuorumCertificate {
+    QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        block.shard_group(),
+        SignerBitmap::default(),
+        AggregateValidatorSignature::default(),
+        vec![],
+        QuorumDecision::Accept,
+    )
+}
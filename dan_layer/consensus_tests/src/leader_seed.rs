@@ -0,0 +1,165 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use rand::rngs::OsRng;
+use tari_common_types::types::{FixedHash, PrivateKey};
+use tari_consensus::hotstuff::get_block_leader_seed;
+use tari_crypto::keys::SecretKey;
+use tari_dan_common_types::{vrf::VrfProof, Epoch, ExtraData, ExtraFieldKey, NodeHeight, PeerAddress, ShardGroup};
+use tari_dan_storage::{
+    consensus_models::{
+        AggregateValidatorSignature,
+        Block,
+        BlockHeader,
+        QuorumCertificate,
+        QuorumDecision,
+        SignerBitmap,
+    },
+    StateStore,
+};
+use tari_state_store_sqlite::SqliteStateStore;
+
+use crate::support::{logging::setup_logger, TEST_NUM_PRESHARDS};
+
+type TestStore = SqliteStateStore<PeerAddress>;
+type TestWriteTx<'a> = <TestStore as StateStore>::WriteTransaction<'a>;
+
+#[test]
+fn it_derives_a_different_seed_after_each_proposed_block() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1), FixedHash::from([1u8; 32]));
+    let block1 = add_block(&mut tx, &genesis, b"block1");
+    let block2 = add_block(&mut tx, &block1, b"block2");
+
+    let genesis_seed = get_block_leader_seed(&*tx, Epoch(1), &genesis).unwrap();
+    let block1_seed = get_block_leader_seed(&*tx, Epoch(1), &block1).unwrap();
+    let block2_seed = get_block_leader_seed(&*tx, Epoch(1), &block2).unwrap();
+    assert_ne!(genesis_seed, block1_seed);
+    assert_ne!(block1_seed, block2_seed);
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_uses_the_epoch_seed_for_the_zero_block() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1), FixedHash::from([1u8; 32]));
+    let zero_block = genesis.justify().get_block(&*tx).unwrap();
+
+    assert_eq!(
+        get_block_leader_seed(&*tx, Epoch(1), &zero_block).unwrap(),
+        get_block_leader_seed(&*tx, Epoch(1), &genesis).unwrap()
+    );
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_uses_the_seed_of_the_justified_block_for_dummy_blocks() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1), FixedHash::from([1u8; 32]));
+    let block1 = add_block(&mut tx, &genesis, b"block1");
+    let qc = create_qc(&block1);
+    qc.save(&mut tx).unwrap();
+    let dummy = Block::new(
+        BlockHeader::dummy_block(
+            Default::default(),
+            *block1.id(),
+            Default::default(),
+            block1.height() + NodeHeight(1),
+            *qc.id(),
+            block1.epoch(),
+            block1.shard_group(),
+            *block1.state_merkle_root(),
+            block1.timestamp(),
+            block1.base_layer_block_height(),
+            *block1.base_layer_block_hash(),
+        ),
+        qc,
+        BTreeSet::new(),
+    );
+
+    assert_eq!(
+        get_block_leader_seed(&*tx, Epoch(1), &dummy).unwrap(),
+        get_block_leader_seed(&*tx, Epoch(1), &block1).unwrap()
+    );
+    tx.rollback().unwrap();
+}
+
+fn create_store() -> TestStore {
+    SqliteStateStore::connect(":memory:").unwrap()
+}
+
+fn create_genesis(tx: &mut TestWriteTx<'_>, epoch: Epoch, leader_seed: FixedHash) -> Block {
+    let zero_block = Block::zero_block(Default::default(), TEST_NUM_PRESHARDS);
+    zero_block.justify().insert(tx).unwrap();
+    zero_block.insert(tx).unwrap();
+
+    let genesis = Block::genesis(
+        Default::default(),
+        epoch,
+        ShardGroup::all_shards(TEST_NUM_PRESHARDS),
+        TEST_NUM_PRESHARDS,
+        FixedHash::zero(),
+        None,
+        leader_seed,
+        None,
+    );
+    genesis.justify().save(tx).unwrap();
+    genesis.insert(tx).unwrap();
+    genesis
+}
+
+/// Adds a block that extends and justifies the parent block, with a VRF proof for the given input
+fn add_block(tx: &mut TestWriteTx<'_>, parent: &Block, vrf_input: &[u8]) -> Block {
+    let vrf_proof = VrfProof::prove(&PrivateKey::random(&mut OsRng), vrf_input);
+    let mut extra_data = ExtraData::new();
+    extra_data.insert(
+        ExtraFieldKey::VrfProof,
+        vrf_proof.to_bytes().to_vec().try_into().unwrap(),
+    );
+    let block = Block::create(
+        Default::default(),
+        *parent.id(),
+        create_qc(parent),
+        parent.height() + NodeHeight(1),
+        parent.epoch(),
+        parent.shard_group(),
+        Default::default(),
+        BTreeSet::new(),
+        FixedHash::zero(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        extra_data,
+    )
+    .unwrap();
+    block.justify().save(tx).unwrap();
+    block.insert(tx).unwrap();
+    block
+}
+
+fn create_qc(block: &Block) -> QuorumCertificate {
+    QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        block.shard_group(),
+        SignerBitmap::default(),
+        AggregateValidatorSignature::default(),
+        vec![],
+        QuorumDecision::Accept,
+    )
+}
@@ -3,6 +3,13 @@
 #[cfg(test)]
 mod consensus;
 #[cfg(test)]
+mod epoch_end;
+#[cfg(test)]
+mod leader_seed;
+#[cfg(test)]
+mod shard_group_handover;
+mod state_recovery;
+#[cfg(test)]
 mod substate_store;
 #[cfg(test)]
 mod support;
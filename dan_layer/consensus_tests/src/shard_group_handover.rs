@@ -0,0 +1,208 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use rand::rngs::OsRng;
+use tari_common_types::types::{FixedHash, PrivateKey};
+use tari_consensus::hotstuff::{
+    calculate_epoch_leader_seed,
+    calculate_genesis_state_merkle_root,
+    get_source_checkpoints,
+    HotStuffError,
+};
+use tari_crypto::keys::SecretKey;
+use tari_dan_common_types::{
+    shard::Shard,
+    vrf::VrfProof,
+    Epoch,
+    ExtraData,
+    ExtraFieldKey,
+    NodeHeight,
+    PeerAddress,
+    ShardGroup,
+};
+use tari_dan_storage::{
+    consensus_models::{
+        AggregateValidatorSignature,
+        Block,
+        Command,
+        EpochCheckpoint,
+        QuorumCertificate,
+        QuorumDecision,
+        SignerBitmap,
+    },
+    StateStore,
+};
+use tari_state_store_sqlite::SqliteStateStore;
+
+use crate::support::{logging::setup_logger, TEST_NUM_PRESHARDS};
+
+type TestStore = SqliteStateStore<PeerAddress>;
+
+#[test]
+fn it_derives_the_same_genesis_for_each_shard_group_after_a_split() {
+    setup_logger();
+    let parent = ShardGroup::new(0, 63);
+    let children = [ShardGroup::new(0, 31), ShardGroup::new(32, 63)];
+    let checkpoint = create_checkpoint(parent, &[(Shard::from(3), [1u8; 32]), (Shard::from(40), [2u8; 32])]);
+
+    // A member of the parent shard group created the checkpoint at the end of the epoch
+    let member_store = create_store();
+    member_store.with_write_tx(|tx| checkpoint.save(tx)).unwrap();
+    // A member that joined in the next epoch synced the checkpoint during state handover
+    let joined_store = create_store();
+    joined_store.with_write_tx(|tx| checkpoint.save_handover(tx)).unwrap();
+
+    let genesis_ids = children.map(|child| {
+        let member_genesis = create_next_genesis(&member_store, child, &[parent]).unwrap();
+        let joined_genesis = create_next_genesis(&joined_store, child, &[parent]).unwrap();
+        assert_eq!(member_genesis.id(), joined_genesis.id());
+        *member_genesis.id()
+    });
+    assert_ne!(genesis_ids[0], genesis_ids[1]);
+}
+
+#[test]
+fn it_derives_the_same_genesis_for_members_of_a_merged_shard_group() {
+    setup_logger();
+    let sources = [ShardGroup::new(0, 31), ShardGroup::new(32, 63)];
+    let merged = ShardGroup::new(0, 63);
+    let checkpoints = [
+        create_checkpoint(sources[0], &[(Shard::from(3), [1u8; 32])]),
+        create_checkpoint(sources[1], &[(Shard::from(40), [2u8; 32])]),
+    ];
+
+    // Each member keeps the checkpoint of its previous shard group and the checkpoint handed over by the other
+    let stores = [(&checkpoints[0], &checkpoints[1]), (&checkpoints[1], &checkpoints[0])].map(|(own, handover)| {
+        let store = create_store();
+        store
+            .with_write_tx(|tx| {
+                own.save(tx)?;
+                handover.save_handover(tx)
+            })
+            .unwrap();
+        store
+    });
+
+    let genesis_blocks = stores
+        .iter()
+        .map(|store| create_next_genesis(store, merged, &sources).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(genesis_blocks[0].id(), genesis_blocks[1].id());
+    assert_eq!(
+        genesis_blocks[0].state_merkle_root(),
+        genesis_blocks[1].state_merkle_root()
+    );
+
+    // The seed depends on the VRF outputs of both source shard groups
+    let merged_seed = calculate_epoch_leader_seed(Epoch(2), &checkpoints);
+    for checkpoint in &checkpoints {
+        assert_ne!(
+            merged_seed,
+            calculate_epoch_leader_seed(Epoch(2), std::slice::from_ref(checkpoint))
+        );
+    }
+}
+
+#[test]
+fn it_does_not_create_the_genesis_of_a_merged_shard_group_before_state_handover() {
+    setup_logger();
+    let sources = [ShardGroup::new(0, 31), ShardGroup::new(32, 63)];
+    let store = create_store();
+    store
+        .with_write_tx(|tx| create_checkpoint(sources[0], &[]).save(tx))
+        .unwrap();
+
+    let err = create_next_genesis(&store, ShardGroup::new(0, 63), &sources).unwrap_err();
+    assert!(matches!(
+        err,
+        HotStuffError::StateHandoverIncomplete { shard, .. } if shard == Shard::from(32)
+    ));
+}
+
+fn create_store() -> TestStore {
+    SqliteStateStore::connect(":memory:").unwrap()
+}
+
+/// Creates the genesis block of the shard group in epoch 2 from the checkpoints that the source shard groups created at
+/// the end of epoch 1
+fn create_next_genesis(
+    store: &TestStore,
+    shard_group: ShardGroup,
+    source_shard_groups: &[ShardGroup],
+) -> Result<Block, HotStuffError> {
+    store.with_read_tx(|tx| {
+        let checkpoints = get_source_checkpoints(tx, Epoch(1), source_shard_groups)?;
+        let state_merkle_root = calculate_genesis_state_merkle_root(Epoch(2), shard_group, &checkpoints)?;
+        Ok(Block::genesis(
+            Default::default(),
+            Epoch(2),
+            shard_group,
+            TEST_NUM_PRESHARDS,
+            state_merkle_root,
+            None,
+            calculate_epoch_leader_seed(Epoch(2), &checkpoints),
+            None,
+        ))
+    })
+}
+
+/// Creates the checkpoint for an epoch-end block of the shard group in epoch 1, including the QC that certifies it
+fn create_checkpoint(shard_group: ShardGroup, shard_roots: &[(Shard, [u8; 32])]) -> EpochCheckpoint {
+    let genesis = Block::genesis(
+        Default::default(),
+        Epoch(1),
+        shard_group,
+        TEST_NUM_PRESHARDS,
+        FixedHash::zero(),
+        None,
+        FixedHash::zero(),
+        None,
+    );
+    let vrf_proof = VrfProof::prove(&PrivateKey::random(&mut OsRng), shard_group.to_string().as_bytes());
+    let mut extra_data = ExtraData::new();
+    extra_data.insert(
+        ExtraFieldKey::VrfProof,
+        vrf_proof.to_bytes().to_vec().try_into().unwrap(),
+    );
+    let epoch_end = Block::create(
+        Default::default(),
+        *genesis.id(),
+        create_qc(&genesis),
+        genesis.height() + NodeHeight(1),
+        Epoch(1),
+        shard_group,
+        Default::default(),
+        [Command::EndEpoch].into_iter().collect::<BTreeSet<_>>(),
+        FixedHash::zero(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        extra_data,
+    )
+    .unwrap();
+
+    let shard_roots = shard_roots
+        .iter()
+        .map(|(shard, root)| (*shard, FixedHash::from(*root)))
+        .collect();
+    let qc = create_qc(&epoch_end);
+    EpochCheckpoint::new(epoch_end, vec![qc], shard_roots)
+}
+
+fn create_qc(block: &Block) -> QuorumCertificate {
+    QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        block.shard_group(),
+        SignerBitmap::default(),
+        AggregateValidatorSignature::default(),
+        vec![],
+        QuorumDecision::Accept,
+    )
+}
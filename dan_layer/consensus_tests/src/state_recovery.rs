@@ -0,0 +1,314 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use tari_common_types::types::FixedHash;
+use tari_consensus::hotstuff::recover_consensus_state;
+use tari_dan_common_types::{optional::Optional, Epoch, ExtraData, NodeHeight, PeerAddress, ShardGroup};
+use tari_dan_storage::{
+    consensus_models::{
+        AggregateValidatorSignature,
+        Block,
+        BlockDiff,
+        BlockId,
+        Command,
+        EpochCheckpoint,
+        HighQc,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        QuorumCertificate,
+        QuorumDecision,
+        SignerBitmap,
+        ValidatorSignature,
+    },
+    StateStore,
+    StateStoreWriteTransaction,
+};
+use tari_state_store_sqlite::SqliteStateStore;
+
+use crate::support::{logging::setup_logger, TEST_NUM_PRESHARDS};
+
+type TestStore = SqliteStateStore<PeerAddress>;
+type TestWriteTx<'a> = <TestStore as StateStore>::WriteTransaction<'a>;
+
+#[test]
+fn it_creates_a_missing_checkpoint_for_a_committed_epoch_end() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let block2 = add_block(&mut tx, &block1, BTreeSet::new());
+    let epoch_end = add_block(&mut tx, &block2, [Command::EndEpoch].into_iter().collect());
+    // The epoch-end block is committed once it has been certified by the blocks that extend it
+    add_block(&mut tx, &epoch_end, BTreeSet::new());
+    for block in [&block1, &block2, &epoch_end] {
+        block.commit_diff(&mut tx, BlockDiff::empty(*block.id())).unwrap();
+    }
+    epoch_end.as_last_executed().set(&mut tx).unwrap();
+    assert!(EpochCheckpoint::get(&*tx, Epoch(1)).optional().unwrap().is_none());
+
+    recover_consensus_state(&mut tx, Epoch(2)).unwrap();
+
+    let checkpoint = EpochCheckpoint::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(checkpoint.block().id(), epoch_end.id());
+    assert!(checkpoint.block_qc().is_some());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_does_not_create_a_checkpoint_if_the_epoch_did_not_end() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    block1.commit_diff(&mut tx, BlockDiff::empty(*block1.id())).unwrap();
+    block1.as_last_executed().set(&mut tx).unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(2)).unwrap();
+
+    assert!(EpochCheckpoint::get(&*tx, Epoch(1)).optional().unwrap().is_none());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_moves_the_locked_block_up_to_the_last_executed_block() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let block2 = add_block(&mut tx, &block1, BTreeSet::new());
+    block2.as_leaf_block().set(&mut tx).unwrap();
+    // Executed block 2 but crashed before the locked block was updated
+    block2.as_last_executed().set(&mut tx).unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let locked = LockedBlock::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(locked.block_id, *block2.id());
+    assert_eq!(locked.height, block2.height());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_resets_a_missing_leaf_block_to_the_locked_block() {
+    setup_logger();
+    let store = create_store();
+    // Foreign keys prevent records from referring to missing blocks and QCs, which is the state being recovered from
+    store.foreign_keys_off().unwrap();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    block1.as_locked_block().set(&mut tx).unwrap();
+    LeafBlock {
+        block_id: BlockId::new(FixedHash::from([1u8; 32])),
+        height: NodeHeight(5),
+        epoch: Epoch(1),
+    }
+    .set(&mut tx)
+    .unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let leaf = LeafBlock::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(leaf, block1.as_leaf_block());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_resets_a_leaf_block_behind_the_locked_block() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let block2 = add_block(&mut tx, &block1, BTreeSet::new());
+    block2.as_locked_block().set(&mut tx).unwrap();
+    block1.as_leaf_block().set(&mut tx).unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let leaf = LeafBlock::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(leaf, block2.as_leaf_block());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_resets_a_dangling_high_qc_to_the_justify_of_the_leaf_block() {
+    setup_logger();
+    let store = create_store();
+    // Foreign keys prevent records from referring to missing blocks and QCs, which is the state being recovered from
+    store.foreign_keys_off().unwrap();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    let block2 = add_block(&mut tx, &block1, BTreeSet::new());
+    block2.as_leaf_block().set(&mut tx).unwrap();
+    // A QC for block 2 that was never stored
+    let dangling_qc = create_qc(&block2);
+    assert!(QuorumCertificate::get(&*tx, dangling_qc.id())
+        .optional()
+        .unwrap()
+        .is_none());
+    dangling_qc.as_high_qc().set(&mut tx).unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let high_qc = HighQc::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(high_qc, block2.justify().as_high_qc());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_resets_a_high_qc_for_a_missing_block() {
+    setup_logger();
+    let store = create_store();
+    // Foreign keys prevent records from referring to missing blocks and QCs, which is the state being recovered from
+    store.foreign_keys_off().unwrap();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    block1.as_leaf_block().set(&mut tx).unwrap();
+    // The QC is stored but the block it certifies is not
+    let missing_block = create_block(&block1, BTreeSet::new());
+    let dangling_qc = create_qc(&missing_block);
+    dangling_qc.save(&mut tx).unwrap();
+    dangling_qc.as_high_qc().set(&mut tx).unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let high_qc = HighQc::get(&*tx, Epoch(1)).unwrap();
+    assert_eq!(high_qc, block1.justify().as_high_qc());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_moves_last_voted_up_to_the_last_sent_vote() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+    let block1 = add_block(&mut tx, &genesis, BTreeSet::new());
+    block1.as_leaf_block().set(&mut tx).unwrap();
+    // The vote for block 1 was sent but the node crashed before last voted was updated
+    LastSentVote {
+        epoch: Epoch(1),
+        block_id: *block1.id(),
+        block_height: block1.height(),
+        decision: QuorumDecision::Accept,
+        signature: ValidatorSignature::new(Default::default(), Default::default()),
+    }
+    .set(&mut tx)
+    .unwrap();
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    let last_voted = LastVoted::get(&*tx).unwrap();
+    assert_eq!(last_voted.block_id, *block1.id());
+    assert_eq!(last_voted.height, block1.height());
+    tx.rollback().unwrap();
+}
+
+#[test]
+fn it_leaves_consistent_state_unchanged() {
+    setup_logger();
+    let store = create_store();
+    let mut tx = store.create_write_tx().unwrap();
+
+    let genesis = create_genesis(&mut tx, Epoch(1));
+
+    recover_consensus_state(&mut tx, Epoch(1)).unwrap();
+
+    assert_eq!(LockedBlock::get(&*tx, Epoch(1)).unwrap().block_id, *genesis.id());
+    assert_eq!(LeafBlock::get(&*tx, Epoch(1)).unwrap(), genesis.as_leaf_block());
+    assert_eq!(HighQc::get(&*tx, Epoch(1)).unwrap(), genesis.justify().as_high_qc());
+    assert_eq!(LastVoted::get(&*tx).unwrap().block_id, *genesis.id());
+    tx.rollback().unwrap();
+}
+
+fn create_store() -> TestStore {
+    SqliteStateStore::connect(":memory:").unwrap()
+}
+
+/// Creates the genesis block for the epoch in the same way as the hotstuff worker
+fn create_genesis(tx: &mut TestWriteTx<'_>, epoch: Epoch) -> Block {
+    let zero_block = Block::zero_block(Default::default(), TEST_NUM_PRESHARDS);
+    zero_block.justify().insert(tx).unwrap();
+    zero_block.insert(tx).unwrap();
+
+    let mut genesis = Block::genesis(
+        Default::default(),
+        epoch,
+        ShardGroup::all_shards(TEST_NUM_PRESHARDS),
+        TEST_NUM_PRESHARDS,
+        FixedHash::zero(),
+        None,
+        FixedHash::zero(),
+        None,
+    );
+    genesis.justify().save(tx).unwrap();
+    genesis.insert(tx).unwrap();
+    genesis.set_as_justified(tx).unwrap();
+    genesis.as_locked_block().set(tx).unwrap();
+    genesis.as_leaf_block().set(tx).unwrap();
+    genesis.as_last_executed().set(tx).unwrap();
+    genesis.as_last_voted().set(tx).unwrap();
+    genesis.justify().as_high_qc().set(tx).unwrap();
+    genesis
+}
+
+/// Adds a block that extends and justifies the parent block
+fn add_block(tx: &mut TestWriteTx<'_>, parent: &Block, commands: BTreeSet<Command>) -> Block {
+    let block = create_block(parent, commands);
+    block.justify().save(tx).unwrap();
+    block.insert(tx).unwrap();
+    block
+}
+
+fn create_block(parent: &Block, commands: BTreeSet<Command>) -> Block {
+    Block::create(
+        Default::default(),
+        *parent.id(),
+        create_qc(parent),
+        parent.height() + NodeHeight(1),
+        parent.epoch(),
+        parent.shard_group(),
+        Default::default(),
+        commands,
+        FixedHash::zero(),
+        0,
+        Default::default(),
+        None,
+        0,
+        0,
+        FixedHash::zero(),
+        ExtraData::default(),
+    )
+    .unwrap()
+}
+
+fn create_qc(block: &Block) -> QuorumCertificate {
+    QuorumCertificate::new(
+        *block.id(),
+        block.height(),
+        block.epoch(),
+        block.shard_group(),
+        SignerBitmap::default(),
+        AggregateValidatorSignature::default(),
+        vec![],
+        QuorumDecision::Accept,
+    )
+}
@@ -241,10 +241,14 @@ fn new_substate_value(seed: u8) -> SubstateValue {
     ComponentHeader {
         template_address: Default::default(),
         module_name: "".to_string(),
+        previous_template_address: None,
         owner_key: None,
         owner_rule: Default::default(),
         access_rules: Default::default(),
         entity_id: [seed; EntityId::LENGTH].into(),
+        non_reentrant: false,
+        fee_sponsor: None,
+        rent_paid_until: None,
         body: ComponentBody {
             state: tari_bor::Value::Null,
         },
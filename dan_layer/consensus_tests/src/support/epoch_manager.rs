@@ -1,7 +1,10 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use async_trait::async_trait;
 use tari_common_types::types::{FixedHash, PublicKey};
@@ -68,6 +71,7 @@ impl TestEpochManager {
                 start_epoch: our_validator_node.start_epoch,
                 fee_claim_public_key: PublicKey::default(),
                 sidechain_id: None,
+                stake: 0,
             });
         } else {
             copy.our_validator_node = Some(ValidatorNode {
@@ -78,6 +82,7 @@ impl TestEpochManager {
                 start_epoch: Epoch(0),
                 fee_claim_public_key: PublicKey::default(),
                 sidechain_id: None,
+                stake: 0,
             });
         }
         copy
@@ -107,6 +112,13 @@ impl TestEpochManager {
         }
     }
 
+    /// Marks the committee layout as changed in the given epoch. The given validators are not aware of the change.
+    pub async fn set_committee_layout_changed(&self, epoch: Epoch, unaware_validators: Vec<TestAddress>) {
+        let mut state = self.state_lock().await;
+        state.committee_layout_changed_epochs.insert(epoch);
+        state.committee_layout_unaware_validators.extend(unaware_validators);
+    }
+
     pub async fn all_validators(&self) -> Vec<(TestAddress, ShardGroup, SubstateAddress, PublicKey, u64, Epoch)> {
         self.state_lock()
             .await
@@ -178,6 +190,7 @@ impl EpochManagerReader for TestEpochManager {
             start_epoch,
             fee_claim_public_key: PublicKey::default(),
             sidechain_id,
+            stake: 0,
         })
     }
 
@@ -233,6 +246,15 @@ impl EpochManagerReader for TestEpochManager {
         Ok(self.inner.lock().await.committees.len() as u32)
     }
 
+    async fn has_committee_layout_changed(&self, epoch: Epoch) -> Result<bool, EpochManagerError> {
+        let state = self.state_lock().await;
+        let is_unaware = self
+            .our_validator_node
+            .as_ref()
+            .is_some_and(|vn| state.committee_layout_unaware_validators.contains(&vn.address));
+        Ok(!is_unaware && state.committee_layout_changed_epochs.contains(&epoch))
+    }
+
     async fn get_committees(
         &self,
         _epoch: Epoch,
@@ -325,6 +347,7 @@ impl EpochManagerReader for TestEpochManager {
             start_epoch: *start_epoch,
             fee_claim_public_key: PublicKey::default(),
             sidechain_id: sidechain_id.clone(),
+            stake: 0,
         })
     }
 
@@ -348,6 +371,8 @@ pub struct TestEpochManagerState {
     pub validator_shards: HashMap<TestAddress, (ShardGroup, SubstateAddress, PublicKey, Option<PublicKey>, u64, Epoch)>,
     pub committees: HashMap<ShardGroup, Committee<TestAddress>>,
     pub address_shard: HashMap<TestAddress, ShardGroup>,
+    pub committee_layout_changed_epochs: HashSet<Epoch>,
+    pub committee_layout_unaware_validators: HashSet<TestAddress>,
 }
 
 impl Default for TestEpochManagerState {
@@ -360,6 +385,8 @@ impl Default for TestEpochManagerState {
             is_epoch_active: false,
             committees: HashMap::new(),
             address_shard: HashMap::new(),
+            committee_layout_changed_epochs: HashSet::new(),
+            committee_layout_unaware_validators: HashSet::new(),
         }
     }
 }
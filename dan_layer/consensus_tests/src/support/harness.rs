@@ -12,7 +12,7 @@ use itertools::Itertools;
 use log::info;
 use tari_common::configuration::Network;
 use tari_consensus::{
-    consensus_constants::ConsensusConstants,
+    consensus_constants::{ConsensusConstants, ExecutionMode},
     hotstuff::{HotstuffConfig, HotstuffEvent},
 };
 use tari_dan_common_types::{
@@ -25,7 +25,15 @@ use tari_dan_common_types::{
     VersionedSubstateId,
 };
 use tari_dan_storage::{
-    consensus_models::{BlockId, Decision, QcId, SubstateRecord, SubstateRequirementLockIntent, TransactionRecord},
+    consensus_models::{
+        BlockId,
+        Decision,
+        QcId,
+        SubstateRecord,
+        SubstateRequirementLockIntent,
+        TransactionOrdering,
+        TransactionRecord,
+    },
     StateStore,
     StateStoreReadTransaction,
     StorageError,
@@ -141,6 +149,16 @@ impl Test {
     }
 
     pub fn create_substates_on_vns(&self, dest: TestVnDestination, num: usize) -> Vec<VersionedSubstateId> {
+        self.create_substates_on_vns_with_rent(dest, num, None)
+    }
+
+    /// Creates substates whose rent is paid until the given epoch
+    pub fn create_substates_on_vns_with_rent(
+        &self,
+        dest: TestVnDestination,
+        num: usize,
+        rent_paid_until: Option<u64>,
+    ) -> Vec<VersionedSubstateId> {
         assert!(
             num <= u8::MAX as usize,
             "Creating more than 255 substates is not supported"
@@ -167,7 +185,10 @@ impl Test {
         let substates = substate_ids
             .iter()
             .map(|id| {
-                let value = make_test_component(id.substate_id().as_component_address().unwrap().entity_id());
+                let mut value = make_test_component(id.substate_id().as_component_address().unwrap().entity_id());
+                if let Some(epoch) = rent_paid_until {
+                    value.set_rent_paid_until(epoch);
+                }
                 SubstateRecord::new(
                     id.substate_id.clone(),
                     id.version,
@@ -510,6 +531,7 @@ pub struct TestBuilder {
     debug_sql_file: Option<String>,
     message_filter: Option<MessageFilter>,
     failure_nodes: Vec<TestAddress>,
+    committee_layout_change: Option<(Epoch, Vec<TestAddress>)>,
     config: HotstuffConfig,
 }
 
@@ -522,9 +544,11 @@ impl TestBuilder {
             debug_sql_file: None,
             message_filter: None,
             failure_nodes: Vec::new(),
+            committee_layout_change: None,
             config: HotstuffConfig {
                 network: Network::LocalNet,
                 sidechain_id: None,
+                state_tree_retained_versions: 10,
                 consensus_constants: ConsensusConstants {
                     base_layer_confirmations: 0,
                     committee_size: 10,
@@ -536,8 +560,26 @@ impl TestBuilder {
                     missed_proposal_evict_threshold: 10,
                     missed_proposal_recovery_threshold: 5,
                     max_block_size: 500,
+                    max_transaction_bytes: 256 * 1024,
+                    max_instructions: 256,
                     fee_exhaust_divisor: 20,
+                    per_byte_rent_cost_per_epoch: 0,
+                    initial_rent_epochs: 0,
                     epochs_per_era: Epoch(10),
+                    idempotency_key_window: Epoch(10),
+                    committee_layout_switch_blocks: 0,
+                    full_proposal_miss_rate_threshold: 20,
+                    execution_mode: ExecutionMode::Immediate,
+                    transaction_ordering: TransactionOrdering::TransactionId,
+                    foreign_proposal_request_interval: 3,
+                    substate_lock_lease_blocks: 0,
+                    cross_shard_deadlock_blocks: 0,
+                    block_timestamp_median_window: 11,
+                    block_timestamp_tolerance: Duration::from_secs(2),
+                    max_block_timestamp_future_drift: Duration::from_secs(30),
+                    fallen_behind_sync_threshold: 20,
+                    stake_weighted_voting: false,
+                    genesis_hash: None,
                 },
             },
         }
@@ -585,6 +627,13 @@ impl TestBuilder {
         self
     }
 
+    /// Reports a committee layout change in the given epoch to all validators except the given unaware validators
+    pub fn with_committee_layout_change(mut self, epoch: Epoch, unaware_validators: Vec<&'static str>) -> Self {
+        let unaware_validators = unaware_validators.into_iter().map(TestAddress::new).collect();
+        self.committee_layout_change = Some((epoch, unaware_validators));
+        self
+    }
+
     pub fn with_message_filter(mut self, message_filter: MessageFilter) -> Self {
         self.message_filter = Some(message_filter);
         self
@@ -656,6 +705,11 @@ impl TestBuilder {
         let (tx_epoch_events, _) = broadcast::channel(10);
         let epoch_manager = TestEpochManager::new(tx_epoch_events);
         epoch_manager.add_committees(committees).await;
+        if let Some((epoch, unaware_validators)) = self.committee_layout_change {
+            epoch_manager
+                .set_committee_layout_changed(epoch, unaware_validators)
+                .await;
+        }
         let shutdown = Shutdown::new();
         let (channels, validators) = Self::build_validators(
             &leader_strategy,
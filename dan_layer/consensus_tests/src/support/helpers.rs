@@ -64,10 +64,14 @@ pub fn make_test_component(entity_id: EntityId) -> SubstateValue {
     SubstateValue::Component(ComponentHeader {
         template_address: Default::default(),
         module_name: "Test".to_string(),
+        previous_template_address: None,
         owner_key: None,
         owner_rule: Default::default(),
         access_rules: Default::default(),
         entity_id,
+        non_reentrant: false,
+        fee_sponsor: None,
+        rent_paid_until: None,
         body: ComponentBody {
             state: tari_bor::Value::Null,
         },
@@ -1,6 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use tari_common_types::types::FixedHash;
 use tari_consensus::traits::LeaderStrategy;
 use tari_dan_common_types::{committee::Committee, NodeAddressable, NodeHeight};
 
@@ -13,7 +14,7 @@ impl RoundRobinLeaderStrategy {
 }
 
 impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for RoundRobinLeaderStrategy {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, height: NodeHeight) -> u32 {
+    fn calculate_leader(&self, committee: &Committee<TAddr>, _leader_seed: &FixedHash, height: NodeHeight) -> u32 {
         (height.as_u64() % committee.members.len() as u64) as u32
     }
 }
@@ -33,11 +34,13 @@ mod tests {
         let strategy = RoundRobinLeaderStrategy::new();
         let committee = Committee::from_iter([new_member("1"), new_member("2"), new_member("3")]);
 
-        let (addr, _) = strategy.get_leader(&committee, NodeHeight(1));
+        let seed = FixedHash::zero();
+
+        let (addr, _) = strategy.get_leader(&committee, &seed, NodeHeight(1));
         assert_eq!(addr, "2");
-        let (addr, _) = strategy.get_leader(&committee, NodeHeight(2));
+        let (addr, _) = strategy.get_leader(&committee, &seed, NodeHeight(2));
         assert_eq!(addr, "3");
-        let (addr, _) = strategy.get_leader(&committee, NodeHeight(3));
+        let (addr, _) = strategy.get_leader(&committee, &seed, NodeHeight(3));
         assert_eq!(addr, "1");
     }
 }
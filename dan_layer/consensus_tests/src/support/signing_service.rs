@@ -4,6 +4,7 @@
 use rand::rngs::OsRng;
 use tari_common_types::types::{PrivateKey, PublicKey};
 use tari_consensus::traits::{ValidatorSignatureService, VoteSignatureService};
+use tari_dan_common_types::vrf::VrfProof;
 use tari_dan_storage::consensus_models::{BlockId, QuorumDecision, ValidatorSchnorrSignature, ValidatorSignature};
 
 use super::{helpers, TestAddress};
@@ -31,6 +32,10 @@ impl ValidatorSignatureService for TestVoteSignatureService {
         ValidatorSchnorrSignature::sign(&self.secret_key, message, &mut OsRng).unwrap()
     }
 
+    fn vrf_prove<M: AsRef<[u8]>>(&self, input: M) -> VrfProof {
+        VrfProof::prove(&self.secret_key, input.as_ref())
+    }
+
     fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
@@ -9,6 +9,7 @@ use tari_dan_storage::consensus_models::{Decision, TransactionRecord, VersionedS
 use tari_engine_types::{
     commit_result::{ExecuteResult, FinalizeResult, RejectReason, TransactionResult},
     component::{ComponentBody, ComponentHeader},
+    execution_failure::ExecutionFailure,
     fees::{FeeBreakdown, FeeReceipt},
     substate::{Substate, SubstateDiff, SubstateId},
     transaction_receipt::{TransactionReceipt, TransactionReceiptAddress},
@@ -21,7 +22,7 @@ use crate::support::{committee_number_to_shard_group, helpers::random_substate_i
 pub fn build_transaction_from(tx: Transaction, decision: Decision) -> TransactionRecord {
     let mut tx = TransactionRecord::new(tx);
     if decision.is_abort() {
-        tx.set_abort_reason(RejectReason::ExecutionFailure("Test aborted".to_string()));
+        tx.set_abort_reason(RejectReason::ExecutionFailure(ExecutionFailure::other("Test aborted")));
     }
     tx
 }
@@ -58,6 +59,7 @@ pub fn create_execution_result_for_transaction(
                 Substate::new(output.versioned_substate_id().version, ComponentHeader {
                     template_address: Default::default(),
                     module_name: "Test".to_string(),
+                    previous_template_address: None,
                     owner_key: Default::default(),
                     owner_rule: Default::default(),
                     access_rules: Default::default(),
@@ -67,6 +69,9 @@ pub fn create_execution_result_for_transaction(
                         .as_component_address()
                         .unwrap()
                         .entity_id(),
+                    non_reentrant: false,
+                    fee_sponsor: None,
+                    rent_paid_until: None,
                     body: ComponentBody { state },
                 }),
             )
@@ -82,15 +87,16 @@ pub fn create_execution_result_for_transaction(
                     total_fee_payment: fee.try_into().unwrap(),
                     total_fees_paid: fee.try_into().unwrap(),
                     cost_breakdown: FeeBreakdown::default(),
+                    instruction_cost_breakdown: vec![],
                 },
             }),
         );
 
         TransactionResult::Accept(diff)
     } else {
-        TransactionResult::Reject(RejectReason::ExecutionFailure(
-            "Transaction was set to ABORT in test".to_string(),
-        ))
+        TransactionResult::Reject(RejectReason::ExecutionFailure(ExecutionFailure::other(
+            "Transaction was set to ABORT in test",
+        )))
     };
 
     let result = ExecuteResult {
@@ -103,9 +109,11 @@ pub fn create_execution_result_for_transaction(
                 total_fee_payment: fee.try_into().unwrap(),
                 total_fees_paid: fee.try_into().unwrap(),
                 cost_breakdown: FeeBreakdown::default(),
+                instruction_cost_breakdown: vec![],
             },
         ),
         execution_time: Duration::from_secs(0),
+        execution_trace: None,
     };
 
     result
@@ -138,7 +146,7 @@ pub fn build_transaction(decision: Decision, inputs: Vec<SubstateRequirement>) -
         .build();
     let mut tx = TransactionRecord::new(tx);
     if decision.is_abort() {
-        tx.set_abort_reason(RejectReason::ExecutionFailure("Test aborted".to_string()));
+        tx.set_abort_reason(RejectReason::ExecutionFailure(ExecutionFailure::other("Test aborted")));
     }
     tx
 }
@@ -3,6 +3,7 @@
 
 use std::{collections::HashMap, iter};
 
+use tari_common_types::types::FixedHash;
 use tari_consensus::traits::{BlockTransactionExecutor, BlockTransactionExecutorError};
 use tari_dan_common_types::{Epoch, LockIntent, SubstateRequirement, VersionedSubstateId};
 use tari_dan_engine::state_store::{memory::MemoryStateStore, new_memory_store, StateWriter};
@@ -57,6 +58,8 @@ impl<TStateStore: StateStore> BlockTransactionExecutor<TStateStore> for TestBloc
         &self,
         transaction: Transaction,
         current_epoch: Epoch,
+        current_timestamp: u64,
+        random_beacon: FixedHash,
         resolved_inputs: &HashMap<SubstateRequirement, Substate>,
     ) -> Result<ExecutedTransaction, BlockTransactionExecutorError> {
         let id = *transaction.id();
@@ -72,6 +75,24 @@ impl<TStateStore: StateStore> BlockTransactionExecutor<TStateStore> for TestBloc
             VirtualSubstateId::CurrentEpoch,
             VirtualSubstate::CurrentEpoch(current_epoch.as_u64()),
         );
+        virtual_substates.insert(
+            VirtualSubstateId::CurrentTimestamp,
+            VirtualSubstate::CurrentTimestamp(current_timestamp),
+        );
+        virtual_substates.insert(
+            VirtualSubstateId::RandomBeacon,
+            VirtualSubstate::random_beacon(&random_beacon),
+        );
+        virtual_substates.insert(
+            VirtualSubstateId::RandomSeed,
+            VirtualSubstate::random_seed(
+                current_epoch.as_u64(),
+                &random_beacon,
+                resolved_inputs
+                    .iter()
+                    .map(|(req, substate)| (req.substate_id(), substate)),
+            ),
+        );
 
         let spec = self
             .store
@@ -4,7 +4,7 @@
 use std::io;
 
 use tari_bor::encode_into_std_writer;
-use tari_engine_types::fees::FeeSource;
+use tari_engine_types::{fees::FeeSource, logs::LogEntry};
 
 use super::FeeTable;
 use crate::runtime::{RuntimeModule, RuntimeModuleError, StateTracker};
@@ -34,7 +34,32 @@ impl RuntimeModule for FeeModule {
         Ok(())
     }
 
+    fn on_log(&self, track: &StateTracker, log: &LogEntry) -> Result<(), RuntimeModuleError> {
+        let (num_logs, log_size) = track.num_logs_and_size();
+        let max_logs = self.fee_table.max_logs_per_transaction();
+        if num_logs >= max_logs {
+            return Err(RuntimeModuleError::TooManyLogs { max: max_logs });
+        }
+        let size = log_size + log.size();
+        let max_size = self.fee_table.max_log_bytes_per_transaction();
+        if size > max_size {
+            return Err(RuntimeModuleError::LogSizeExceeded { size, max: max_size });
+        }
+        Ok(())
+    }
+
+    fn on_instruction_end(&self, track: &StateTracker) -> Result<(), RuntimeModuleError> {
+        let (num_events, num_logs) = track.num_instruction_events_and_logs();
+        track.add_fee_charge(FeeSource::Logs, num_logs as u64 * self.fee_table.per_log_cost());
+        track.add_fee_charge(FeeSource::Events, num_events as u64 * self.fee_table.per_event_cost());
+        Ok(())
+    }
+
     fn on_before_finalize(&self, track: &StateTracker) -> Result<(), RuntimeModuleError> {
+        if self.fee_table.is_rent_enabled() {
+            track.fund_created_substates_rent(self.fee_table.initial_rent_epochs())?;
+        }
+
         let total_storage = track.with_substates_to_persist(|changes| {
             let mut counter = ByteCounter::new();
             for substate in changes.values() {
@@ -51,12 +76,27 @@ impl RuntimeModule for FeeModule {
             self.fee_table.per_byte_storage_cost() * total_storage as u64 / STORAGE_COST_REDUCTION_DIVISOR,
         );
 
-        track.add_fee_charge(FeeSource::Logs, track.num_logs() as u64 * self.fee_table.per_log_cost());
-
-        track.add_fee_charge(
-            FeeSource::Events,
-            track.num_events() as u64 * self.fee_table.per_event_cost(),
-        );
+        // Rent is charged for the final size of each substate that was funded in this transaction
+        let rent_payments = track.rent_payments();
+        if !rent_payments.is_empty() {
+            let total_rent = track.with_substates_to_persist(|changes| {
+                let mut total = 0u64;
+                for (substate_id, epochs) in &rent_payments {
+                    let Some(substate) = changes.get(substate_id) else {
+                        continue;
+                    };
+                    let mut counter = ByteCounter::new();
+                    encode_into_std_writer(substate, &mut counter)?;
+                    total = total.saturating_add(
+                        (counter.get() as u64)
+                            .saturating_mul(*epochs)
+                            .saturating_mul(self.fee_table.per_byte_rent_cost_per_epoch()),
+                    );
+                }
+                Ok::<_, RuntimeModuleError>(total)
+            })?;
+            track.add_fee_charge(FeeSource::Rent, total_rent);
+        }
 
         Ok(())
     }
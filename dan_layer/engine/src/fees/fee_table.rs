@@ -7,15 +7,31 @@ pub struct FeeTable {
     pub per_byte_storage_cost: u64,
     pub per_event_cost: u64,
     pub per_log_cost: u64,
+    /// The maximum number of log entries a transaction may emit
+    pub max_logs_per_transaction: usize,
+    /// The maximum total size in bytes of the log entries a transaction may emit
+    pub max_log_bytes_per_transaction: usize,
+    /// The rent charged per byte of state for each epoch. Rent is not charged if this is zero. Components, vaults and
+    /// key-value store entries pay rent, see [SubstateValue::pays_rent](tari_engine_types::substate::SubstateValue).
+    pub per_byte_rent_cost_per_epoch: u64,
+    /// The number of epochs of rent that is charged when a substate that pays rent is created
+    pub initial_rent_epochs: u64,
 }
 
 impl FeeTable {
+    pub const DEFAULT_MAX_LOGS_PER_TRANSACTION: usize = 1000;
+    pub const DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION: usize = 64 * 1024;
+
     pub fn zero_rated() -> Self {
         Self {
             per_module_call_cost: 0,
             per_byte_storage_cost: 0,
             per_event_cost: 0,
             per_log_cost: 0,
+            max_logs_per_transaction: Self::DEFAULT_MAX_LOGS_PER_TRANSACTION,
+            max_log_bytes_per_transaction: Self::DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION,
+            per_byte_rent_cost_per_epoch: 0,
+            initial_rent_epochs: 0,
         }
     }
 
@@ -34,4 +50,24 @@ impl FeeTable {
     pub fn per_log_cost(&self) -> u64 {
         self.per_log_cost
     }
+
+    pub fn max_logs_per_transaction(&self) -> usize {
+        self.max_logs_per_transaction
+    }
+
+    pub fn max_log_bytes_per_transaction(&self) -> usize {
+        self.max_log_bytes_per_transaction
+    }
+
+    pub fn per_byte_rent_cost_per_epoch(&self) -> u64 {
+        self.per_byte_rent_cost_per_epoch
+    }
+
+    pub fn initial_rent_epochs(&self) -> u64 {
+        self.initial_rent_epochs
+    }
+
+    pub fn is_rent_enabled(&self) -> bool {
+        self.per_byte_rent_cost_per_epoch > 0
+    }
 }
@@ -7,6 +7,7 @@ pub mod function_definitions;
 pub mod runtime;
 pub mod state_store;
 pub mod template;
+pub mod trace;
 pub mod traits;
 pub mod transaction;
 pub mod wasm;
@@ -4,7 +4,7 @@
 use std::fmt::{Display, Formatter};
 
 use tari_template_lib::{
-    args::{ComponentAction, VaultAction},
+    args::{ComponentAction, KeyValueStoreAction, VaultAction},
     auth::ResourceAuthAction,
     models::ComponentAddress,
 };
@@ -42,6 +42,12 @@ impl From<VaultAction> for ActionIdent {
     }
 }
 
+impl From<KeyValueStoreAction> for ActionIdent {
+    fn from(action: KeyValueStoreAction) -> Self {
+        Self::Native(NativeAction::KeyValueStore(action))
+    }
+}
+
 impl Display for ActionIdent {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,6 +67,7 @@ pub enum NativeAction {
     Component(ComponentAction),
     Resource(ResourceAuthAction),
     Vault(VaultAction),
+    KeyValueStore(KeyValueStoreAction),
 }
 
 impl Display for NativeAction {
@@ -69,6 +76,7 @@ impl Display for NativeAction {
             NativeAction::Component(action) => write!(f, "component.call_method.{:?}", action),
             NativeAction::Resource(action) => write!(f, "resource.{:?}", action),
             NativeAction::Vault(action) => write!(f, "vault.{:?}", action),
+            NativeAction::KeyValueStore(action) => write!(f, "key_value_store.{:?}", action),
         }
     }
 }
@@ -2,7 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use tari_engine_types::{substate::SubstateId, TemplateAddress};
-use tari_template_lib::models::ComponentAddress;
+use tari_template_lib::models::{ComponentAddress, ResourceAddress};
 
 #[derive(Debug, Clone)]
 pub struct AllocatedAddress {
@@ -34,3 +34,11 @@ impl TryFrom<AllocatedAddress> for ComponentAddress {
         value.address.try_into()
     }
 }
+
+impl TryFrom<AllocatedAddress> for ResourceAddress {
+    type Error = SubstateId;
+
+    fn try_from(value: AllocatedAddress) -> Result<Self, Self::Error> {
+        value.address.try_into()
+    }
+}
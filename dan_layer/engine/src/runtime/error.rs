@@ -28,6 +28,7 @@ use tari_common_types::types::PublicKey;
 use tari_dan_common_types::{optional::IsNotFoundError, Epoch};
 use tari_engine_types::{
     entity_id_provider::EntityIdProviderError,
+    execution_failure::{ExecutionError, ExecutionFailure},
     id_provider::IdProviderError,
     indexed_value::IndexedValueError,
     lock::LockId,
@@ -40,6 +41,7 @@ use tari_template_lib::models::{
     Amount,
     BucketId,
     ComponentAddress,
+    KeyValueStoreId,
     NonFungibleId,
     ProofId,
     ResourceAddress,
@@ -123,6 +125,12 @@ pub enum RuntimeError {
         resource_address: ResourceAddress,
         nf_id: NonFungibleId,
     },
+    #[error("Invalid patch for the mutable data of non-fungible {nf_id} of resource {resource_address}: {details}")]
+    InvalidNonFungibleDataPatch {
+        resource_address: ResourceAddress,
+        nf_id: NonFungibleId,
+        details: String,
+    },
     #[error("Bucket not found with id {bucket_id}")]
     BucketNotFound { bucket_id: BucketId },
     #[error("Proof not found with id {proof_id}")]
@@ -157,6 +165,31 @@ pub enum RuntimeError {
     AccessDeniedAuthHook { action_ident: ActionIdent, details: String },
     #[error("Access Denied: You must be the owner to perform this action: {action}")]
     AccessDeniedOwnerRequired { action: ActionIdent },
+    #[error("Soulbound resource {resource_address} cannot be moved out of its vault, it can only be burnt")]
+    SoulboundResourceTransfer { resource_address: ResourceAddress },
+    #[error("Minting {amount} of {resource_address} exceeds its max supply of {max_supply} (supply {total_supply})")]
+    MaxSupplyExceeded {
+        resource_address: ResourceAddress,
+        amount: Amount,
+        total_supply: Amount,
+        max_supply: Amount,
+    },
+    #[error("Component {component_address} is non-reentrant and is already on the call stack")]
+    ReentrantCall { component_address: ComponentAddress },
+    #[error("Rent for substate {id} expired after epoch {paid_until} (current epoch: {current_epoch})")]
+    SubstateRentExpired {
+        id: SubstateId,
+        paid_until: u64,
+        current_epoch: u64,
+    },
+    #[error("Component {component_address} does not match the interface for method {method}: {details}")]
+    InterfaceMismatch {
+        component_address: ComponentAddress,
+        method: String,
+        details: String,
+    },
+    #[error("Resource {address} is frozen: {action} is not permitted")]
+    ResourceFrozen { address: SubstateId, action: ActionIdent },
     #[error("Invalid method address rule for {template_name}: {details}")]
     InvalidMethodAccessRule { template_name: String, details: String },
     #[error("Runtime module error: {0}")]
@@ -187,6 +220,18 @@ pub enum RuntimeError {
     TransactionReceiptNotFound,
     #[error("Component already exists {address}")]
     ComponentAlreadyExists { address: ComponentAddress },
+    #[error("Resource already exists {address}")]
+    ResourceAlreadyExists { address: ResourceAddress },
+    #[error("Cannot destroy component {address} because vault {vault_id} is not empty")]
+    CannotDestroyComponentWithNonEmptyVault {
+        address: ComponentAddress,
+        vault_id: VaultId,
+    },
+    #[error("Cannot destroy component {address} because it owns key-value store {store_id}")]
+    CannotDestroyComponentWithKeyValueStore {
+        address: ComponentAddress,
+        store_id: KeyValueStoreId,
+    },
     #[error("Cross-template call function error of function '{function}' on template '{template_address}': {details}")]
     CrossTemplateCallFunctionError {
         template_address: TemplateAddress,
@@ -238,6 +283,8 @@ pub enum RuntimeError {
     DuplicateBucket { bucket_id: BucketId },
     #[error("Duplicate proof {proof_id}")]
     DuplicateProof { proof_id: ProofId },
+    #[error("Operation {op} is not supported on composite proof {proof_id}")]
+    InvalidOpCompositeProof { op: &'static str, proof_id: ProofId },
 
     #[error("Address allocation not found with id {id}")]
     AddressAllocationNotFound { id: u32 },
@@ -246,6 +293,10 @@ pub enum RuntimeError {
 
     #[error("Invalid event topic {topic}")]
     InvalidEventTopic { topic: String },
+    #[error("Too many indexed event fields: {count} given but at most {max} are permitted")]
+    TooManyIndexedEventFields { count: usize, max: usize },
+    #[error("Event field '{field}' cannot be indexed: {details}")]
+    InvalidIndexedEventField { field: String, details: String },
 
     #[error("Numeric conversion error: {details}")]
     NumericConversionError { details: String },
@@ -260,6 +311,108 @@ impl RuntimeError {
     pub fn state_db_error<T: Display>(err: T) -> Self {
         RuntimeError::StateDbError(anyhow!("{}", err))
     }
+
+    pub fn to_execution_failure(&self) -> ExecutionFailure {
+        ExecutionFailure::new(self.to_execution_error(), self.to_string())
+    }
+
+    /// Returns the structured representation of this error that is included in the reject reason of a failed
+    /// transaction
+    pub fn to_execution_error(&self) -> ExecutionError {
+        match self {
+            RuntimeError::SubstateNotFound { id } => ExecutionError::SubstateNotFound { id: id.clone() },
+            RuntimeError::RootSubstateNotFound { address } | RuntimeError::ReferencedSubstateNotFound { address } => {
+                ExecutionError::SubstateNotFound { id: address.clone() }
+            },
+            RuntimeError::SubstateOutOfScope { address } => ExecutionError::SubstateOutOfScope { id: address.clone() },
+            RuntimeError::SubstateNotOwned {
+                address,
+                requested_owner,
+            } => ExecutionError::SubstateNotOwned {
+                id: address.clone(),
+                requested_owner: (**requested_owner).clone(),
+            },
+            RuntimeError::ComponentNotFound { address } => ExecutionError::ComponentNotFound { address: *address },
+            RuntimeError::VaultNotFound { vault_id } => ExecutionError::VaultNotFound { vault_id: *vault_id },
+            RuntimeError::ResourceNotFound { resource_address } => ExecutionError::ResourceNotFound {
+                resource_address: *resource_address,
+            },
+            RuntimeError::NonFungibleNotFound {
+                resource_address,
+                nft_id,
+            } => ExecutionError::NonFungibleNotFound {
+                resource_address: *resource_address,
+                nft_id: nft_id.clone(),
+            },
+            RuntimeError::TemplateNotFound { template_address } => ExecutionError::TemplateNotFound {
+                template_address: *template_address,
+            },
+            RuntimeError::InvalidArgument { argument, reason } => ExecutionError::InvalidArgument {
+                argument: (*argument).to_string(),
+                reason: reason.clone(),
+            },
+            RuntimeError::InvalidAmount { amount, reason } => ExecutionError::InvalidAmount {
+                amount: *amount,
+                reason: reason.clone(),
+            },
+            RuntimeError::AccessDenied { action_ident } |
+            RuntimeError::AccessDeniedAuthHook { action_ident, .. } |
+            RuntimeError::AccessDeniedOwnerRequired { action: action_ident } => ExecutionError::AccessDenied {
+                action: action_ident.to_string(),
+            },
+            RuntimeError::AccessDeniedSetComponentState { .. } => ExecutionError::AccessDenied {
+                action: "SetComponentState".to_string(),
+            },
+            RuntimeError::ResourceError(err) => ExecutionError::ResourceError {
+                details: err.to_string(),
+            },
+            RuntimeError::MaxSupplyExceeded {
+                resource_address,
+                amount,
+                total_supply,
+                max_supply,
+            } => ExecutionError::MaxSupplyExceeded {
+                resource_address: *resource_address,
+                amount: *amount,
+                total_supply: *total_supply,
+                max_supply: *max_supply,
+            },
+            RuntimeError::ResourceFrozen { address, action } => ExecutionError::ResourceFrozen {
+                address: address.clone(),
+                action: action.to_string(),
+            },
+            RuntimeError::BucketNotEmpty { bucket_id } => ExecutionError::BucketNotEmpty { bucket_id: *bucket_id },
+            RuntimeError::InsufficientFeesPaid {
+                required_fee,
+                fees_paid,
+            } => ExecutionError::InsufficientFeesPaid {
+                required_fee: *required_fee,
+                fees_paid: *fees_paid,
+            },
+            RuntimeError::ReentrantCall { component_address } => ExecutionError::ReentrantCall {
+                component_address: *component_address,
+            },
+            RuntimeError::SubstateRentExpired {
+                id,
+                paid_until,
+                current_epoch,
+            } => ExecutionError::SubstateRentExpired {
+                id: id.clone(),
+                paid_until: *paid_until,
+                current_epoch: *current_epoch,
+            },
+            RuntimeError::MaxCallDepthExceeded { max_depth } => ExecutionError::MaxCallDepthExceeded {
+                max_depth: *max_depth as u64,
+            },
+            RuntimeError::OrphanedSubstate { address } => ExecutionError::OrphanedSubstates {
+                substates: vec![address.to_string()],
+            },
+            RuntimeError::OrphanedSubstates { substates } => ExecutionError::OrphanedSubstates {
+                substates: substates.clone(),
+            },
+            _ => ExecutionError::Other,
+        }
+    }
 }
 
 impl IsNotFoundError for RuntimeError {
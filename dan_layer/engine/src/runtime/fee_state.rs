@@ -8,6 +8,10 @@ use tari_template_lib::models::{Amount, VaultId};
 pub struct FeeState {
     pub fee_payments: Vec<(ResourceContainer, VaultId)>,
     pub fee_charges: FeeBreakdown,
+    /// The fee charges of each executed instruction in execution order
+    pub instruction_fee_charges: Vec<FeeBreakdown>,
+    /// The fee charges of the instruction that is currently executing, if any
+    pub current_instruction: Option<InstructionFeeState>,
 }
 
 impl FeeState {
@@ -23,3 +27,12 @@ impl FeeState {
         self.fee_payments.iter().map(|(resx, _)| resx.amount()).sum()
     }
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct InstructionFeeState {
+    pub fee_charges: FeeBreakdown,
+    /// The number of events emitted before the instruction started
+    pub num_events_before: usize,
+    /// The number of logs emitted before the instruction started
+    pub num_logs_before: usize,
+}
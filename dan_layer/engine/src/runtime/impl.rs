@@ -20,7 +20,7 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use log::{warn, *};
 use tari_common::configuration::Network;
@@ -31,20 +31,30 @@ use tari_engine_types::{
     base_layer_hashing::ownership_proof_hasher64,
     commit_result::{FinalizeResult, RejectReason, TransactionResult},
     component::ComponentHeader,
-    confidential::{get_commitment_factory, get_range_proof_service, ConfidentialClaim, ConfidentialOutput},
+    confidential::{
+        get_commitment_factory,
+        get_range_proof_service,
+        ConfidentialClaim,
+        ConfidentialOutput,
+        ConfidentialProofBatch,
+    },
     entity_id_provider::EntityIdProvider,
-    events::Event,
-    indexed_value::IndexedValue,
+    events::{indexed_field_value, Event},
+    indexed_value::{IndexedValue, IndexedWellKnownTypes},
+    instruction::Instruction,
     instruction_result::InstructionResult,
+    key_value_store::{KeyValueEntry, KeyValueEntryAddress, KeyValueStoreContainer},
     lock::LockFlag,
     logs::LogEntry,
+    named_address::{new_named_component_address, new_named_resource_address},
+    non_fungible,
     resource::Resource,
     resource_container::ResourceContainer,
     substate::{SubstateId, SubstateValue},
     vault::Vault,
     TemplateAddress,
 };
-use tari_template_abi::{TemplateDef, Type};
+use tari_template_abi::{FunctionDef, TemplateDef, Type};
 use tari_template_builtin::{ACCOUNT_NFT_TEMPLATE_ADDRESS, ACCOUNT_TEMPLATE_ADDRESS};
 use tari_template_lib::{
     args,
@@ -55,6 +65,7 @@ use tari_template_lib::{
         BuiltinTemplateAction,
         CallAction,
         CallFunctionArg,
+        CallInterfaceMethodArg,
         CallMethodArg,
         CallerContextAction,
         ComponentAction,
@@ -65,6 +76,8 @@ use tari_template_lib::{
         CreateResourceArg,
         GenerateRandomAction,
         InvokeResult,
+        KeyValueStoreAction,
+        KeyValueStoreRef,
         LogLevel,
         MintResourceArg,
         NonFungibleAction,
@@ -74,7 +87,9 @@ use tari_template_lib::{
         RecallResourceArg,
         ResourceAction,
         ResourceGetNonFungibleArg,
+        ResourcePatchNonFungibleDataArg,
         ResourceRef,
+        ResourceUpdateMetadataArg,
         ResourceUpdateNonFungibleDataArg,
         VaultAction,
         VaultCreateProofByFungibleAmountArg,
@@ -82,9 +97,19 @@ use tari_template_lib::{
         VaultWithdrawArg,
         WorkspaceAction,
     },
-    auth::{AuthHook, AuthHookCaller, ComponentAccessRules, OwnerRule, ResourceAccessRules, ResourceAuthAction},
+    auth::{
+        AuthHook,
+        AuthHookCaller,
+        ComponentAccessRules,
+        ComponentSnapshot,
+        OwnerRule,
+        ResourceAccessRules,
+        ResourceAuthAction,
+    },
+    component::FeeSponsor,
     constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, XTR},
     crypto::RistrettoPublicKeyBytes,
+    events::MAX_INDEXED_EVENT_FIELDS,
     models::{
         Amount,
         BucketId,
@@ -93,7 +118,9 @@ use tari_template_lib::{
         Metadata,
         NonFungible,
         NonFungibleAddress,
+        NonFungibleId,
         NotAuthorized,
+        ProofId,
         ResourceAddress,
         VaultId,
         VaultRef,
@@ -125,6 +152,9 @@ const LOG_TARGET: &str = "tari::dan::engine::runtime::impl";
 const STANDARD_TOPIC_PREFIX: &str = "std.";
 const VAULT_DEPOSIT_TOPIC: &str = "std.vault.deposit";
 const VAULT_WITHDRAW_TOPIC: &str = "std.vault.withdraw";
+const RESOURCE_METADATA_UPDATED_TOPIC: &str = "std.resource.metadata_updated";
+const RESOURCE_FROZEN_TOPIC: &str = "std.resource.frozen";
+const RESOURCE_UNFROZEN_TOPIC: &str = "std.resource.unfrozen";
 
 #[derive(Clone)]
 pub struct RuntimeInterfaceImpl<TTemplateProvider> {
@@ -132,6 +162,7 @@ pub struct RuntimeInterfaceImpl<TTemplateProvider> {
     template_provider: Arc<TTemplateProvider>,
     entity_id_provider: EntityIdProvider,
     transaction_signer_public_key: RistrettoPublicKey,
+    reserved_output_addresses: Vec<ComponentAddress>,
     modules: Vec<Arc<dyn RuntimeModule>>,
     max_call_depth: usize,
     network: Network,
@@ -142,6 +173,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         tracker: StateTracker,
         template_provider: Arc<TTemplateProvider>,
         signer_public_key: RistrettoPublicKey,
+        reserved_output_addresses: Vec<ComponentAddress>,
         entity_id_provider: EntityIdProvider,
         modules: Vec<Arc<dyn RuntimeModule>>,
         max_call_depth: usize,
@@ -152,6 +184,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
             template_provider,
             entity_id_provider,
             transaction_signer_public_key: signer_public_key,
+            reserved_output_addresses,
             modules,
             max_call_depth,
             network,
@@ -174,6 +207,20 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         Ok(())
     }
 
+    fn invoke_modules_on_instruction_start(&self, instruction: &Instruction) -> Result<(), RuntimeError> {
+        for module in &self.modules {
+            module.on_instruction_start(&self.tracker, instruction)?;
+        }
+        Ok(())
+    }
+
+    fn invoke_modules_on_instruction_end(&self) -> Result<(), RuntimeError> {
+        for module in &self.modules {
+            module.on_instruction_end(&self.tracker)?;
+        }
+        Ok(())
+    }
+
     fn invoke_modules_on_before_finalize(&self) -> Result<(), RuntimeError> {
         for module in &self.modules {
             module.on_before_finalize(&self.tracker)?;
@@ -266,6 +313,60 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         Ok(())
     }
 
+    /// Checks that the caller may update the mutable data of the non-fungible and applies `update` to its contents
+    fn update_non_fungible_data<F>(
+        &self,
+        resource_address: ResourceAddress,
+        id: NonFungibleId,
+        op: &'static str,
+        update: F,
+    ) -> Result<(), RuntimeError>
+    where
+        F: FnOnce(&mut non_fungible::NonFungible) -> Result<(), RuntimeError>,
+    {
+        let (maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
+            let resource_lock = state_mut.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Read)?;
+
+            let resource = state_mut.get_resource(&resource_lock)?;
+
+            state_mut.authorization().check_resource_access_rules(
+                ResourceAuthAction::UpdateNonFungibleData,
+                resource.as_ownership(),
+                resource.access_rules(),
+            )?;
+
+            let auth_hook = resource.auth_hook().cloned();
+            let auth_caller = state_mut.get_auth_caller()?;
+
+            state_mut.unlock_substate(resource_lock)?;
+            Ok::<_, RuntimeError>((auth_hook, auth_caller))
+        })?;
+
+        if let Some(auth_hook) = maybe_auth_hook {
+            self.invoke_resource_access_hook(auth_hook, auth_caller, ResourceAuthAction::UpdateNonFungibleData)?;
+        }
+
+        self.tracker.write_with(|state| {
+            let addr = NonFungibleAddress::new(resource_address, id);
+            let locked = state.lock_substate(&SubstateId::NonFungible(addr.clone()), LockFlag::Write)?;
+
+            let nft = state.get_non_fungible_mut(&locked)?;
+
+            let contents = nft
+                .contents_mut()
+                .ok_or_else(|| RuntimeError::InvalidOpNonFungibleBurnt {
+                    op,
+                    resource_address,
+                    nf_id: addr.id().clone(),
+                })?;
+            update(contents)?;
+
+            state.unlock_substate(locked)?;
+
+            Ok(())
+        })
+    }
+
     fn invoke_resource_access_hook(
         &self,
         auth_hook: AuthHook,
@@ -292,13 +393,19 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
             return Ok(());
         }
 
+        // The caller component is locked while the hook is invoked, so we provide the hook with a snapshot of the
+        // caller's state.
         let caller = auth_caller
             .component()
-            .map(|component| self.load_component(component))
+            .map(|component| self.load_component(component).map(|header| (*component, header)))
             .transpose()?;
 
-        if let Some(caller) = caller {
-            auth_caller.with_component_state(caller.into_component().state);
+        if let Some((address, caller)) = caller {
+            auth_caller.with_component_snapshot(ComponentSnapshot::new(
+                address,
+                caller.template_address,
+                caller.into_component().state,
+            ));
         }
 
         // The signature of a call back is (action: ResourceAuthAction, auth_caller: AuthCaller)
@@ -422,11 +529,18 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         Ok(id)
     }
 
-    fn emit_event(&self, topic: String, payload: Metadata) -> Result<(), RuntimeError> {
+    fn emit_event(
+        &self,
+        topic: String,
+        mut payload: Metadata,
+        data: Option<tari_bor::Value>,
+        indexed_fields: Vec<String>,
+    ) -> Result<(), RuntimeError> {
         // forbid template users to emit events that can be confused with the ones emitted by the engine
         if topic.starts_with(STANDARD_TOPIC_PREFIX) {
             return Err(RuntimeError::InvalidEventTopic { topic });
         }
+        add_indexed_event_fields(&mut payload, data.as_ref(), indexed_fields)?;
 
         self.invoke_modules_on_runtime_call("emit_event")?;
 
@@ -442,15 +556,23 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         let tx_hash = self.entity_id_provider.transaction_hash();
         let template_address = self.tracker.get_template_address()?;
 
-        let event = Event::new(substate_id, template_address, tx_hash, topic, payload);
+        let mut event = Event::new(substate_id, template_address, tx_hash, topic, payload);
+        if let Some(data) = data {
+            event = event.with_data(data);
+        }
         log::log!(target: "tari::dan::engine::runtime", log::Level::Debug, "{}", event.to_string());
         self.tracker.add_event(event);
         Ok(())
     }
 
-    fn emit_log(&self, level: LogLevel, message: String) -> Result<(), RuntimeError> {
+    fn emit_log(&self, level: LogLevel, message: String, fields: Metadata) -> Result<(), RuntimeError> {
         self.invoke_modules_on_runtime_call("emit_log")?;
 
+        let log = LogEntry::new(level, message).with_fields(fields);
+        for module in &self.modules {
+            module.on_log(&self.tracker, &log)?;
+        }
+
         let log_level = match level {
             LogLevel::Error => log::Level::Error,
             LogLevel::Warn => log::Level::Warn,
@@ -459,8 +581,8 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         };
 
         // eprintln!("{}: {}", log_level, message);
-        log::log!(target: "tari::dan::engine::runtime", log_level, "{}", message);
-        self.tracker.add_log(LogEntry::new(level, message));
+        log::log!(target: "tari::dan::engine::runtime", log_level, "{}", log);
+        self.tracker.add_log(log);
         Ok(())
     }
 
@@ -470,7 +592,57 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
     }
 
     fn lock_component(&self, address: &ComponentAddress, lock_flag: LockFlag) -> Result<LockedSubstate, RuntimeError> {
-        self.tracker.lock_substate(&SubstateId::Component(*address), lock_flag)
+        self.tracker.write_with(|state| {
+            if state.load_component(address)?.non_reentrant && state.is_component_on_call_stack(address) {
+                return Err(RuntimeError::ReentrantCall {
+                    component_address: *address,
+                });
+            }
+            state.lock_substate(&SubstateId::Component(*address), lock_flag)
+        })
+    }
+
+    fn pay_sponsored_fee(&self, component_address: &ComponentAddress, amount: Amount) -> Result<(), RuntimeError> {
+        self.invoke_modules_on_runtime_call("pay_sponsored_fee")?;
+
+        if amount.is_negative() {
+            return Err(RuntimeError::InvalidArgument {
+                argument: "amount",
+                reason: "Sponsored fee amount must be positive".to_string(),
+            });
+        }
+
+        self.tracker.write_with(|state| {
+            let vault_id = state
+                .load_component(component_address)?
+                .fee_sponsor
+                .as_ref()
+                .map(|sponsor| sponsor.vault_id)
+                .ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "component_address",
+                    reason: format!("Component {} does not sponsor fees", component_address),
+                })?;
+
+            let vault_lock = state.lock_substate(&SubstateId::Vault(vault_id), LockFlag::Write)?;
+            let resource_address = *state.get_vault(&vault_lock)?.resource_address();
+            if resource_address != XTR {
+                return Err(RuntimeError::InvalidArgument {
+                    argument: "fee_sponsor",
+                    reason: format!(
+                        "Fees can only be paid using XTR, however the fee vault contained resource {}",
+                        resource_address
+                    ),
+                });
+            }
+
+            let withdrawn = state.get_vault_mut(&vault_lock)?.withdraw(amount)?;
+            let mut container = ResourceContainer::confidential(XTR, None, Amount::zero());
+            container.deposit(withdrawn)?;
+            state.pay_fee(container, vault_id)?;
+
+            state.unlock_substate(vault_lock)?;
+            Ok(())
+        })
     }
 
     fn caller_context_invoke(
@@ -519,6 +691,40 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 let allocation = state.new_address_allocation(address)?;
                 Ok(InvokeResult::encode(&allocation)?)
             }),
+            CallerContextAction::AllocateNamedComponentAddress => self.tracker.write_with(|state| {
+                let name: String = args.assert_one_arg()?;
+                validate_address_name(&name)?;
+                let template_address = *state.current_template()?.0;
+                let address =
+                    new_named_component_address(&template_address, &self.transaction_signer_public_key, &name);
+                let allocation = state.new_address_allocation(address)?;
+                Ok(InvokeResult::encode(&allocation)?)
+            }),
+            CallerContextAction::AllocateNamedResourceAddress => self.tracker.write_with(|state| {
+                let name: String = args.assert_one_arg()?;
+                validate_address_name(&name)?;
+                let template_address = *state.current_template()?.0;
+                let address = new_named_resource_address(&template_address, &self.transaction_signer_public_key, &name);
+                let allocation = state.new_address_allocation(address)?;
+                Ok(InvokeResult::encode(&allocation)?)
+            }),
+            CallerContextAction::AllocateReservedComponentAddress => self.tracker.write_with(|state| {
+                let index: u32 = args.assert_one_arg()?;
+                let address = self
+                    .reserved_output_addresses
+                    .get(index as usize)
+                    .copied()
+                    .ok_or_else(|| RuntimeError::InvalidArgument {
+                        argument: "index",
+                        reason: format!(
+                            "Reserved output index {} out of range. The transaction reserves {} output(s)",
+                            index,
+                            self.reserved_output_addresses.len()
+                        ),
+                    })?;
+                let allocation = state.new_address_allocation(address)?;
+                Ok(InvokeResult::encode(&allocation)?)
+            }),
         }
     }
 
@@ -552,11 +758,16 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     owner_rule,
                     access_rules,
                     address_allocation,
+                    non_reentrant,
+                    fee_sponsor,
                 } = args.assert_one_arg()?;
 
                 let template_addr = self.tracker.get_template_address()?;
                 let template_def = self.get_template_def(&template_addr)?;
                 validate_component_access_rule_methods(&access_rules, &template_def)?;
+                if let Some(ref fee_sponsor) = fee_sponsor {
+                    validate_fee_sponsor(fee_sponsor, &template_def, &encoded_state)?;
+                }
 
                 let owner_key = match owner_rule {
                     OwnerRule::OwnedBySigner => {
@@ -565,6 +776,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     OwnerRule::None => None,
                     OwnerRule::ByAccessRule(_) => None,
                     OwnerRule::ByPublicKey(key) => Some(key),
+                    OwnerRule::OwnedByComponent(_) => None,
                 };
 
                 let component_address = self.tracker.new_component(
@@ -573,6 +785,8 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     owner_rule,
                     access_rules,
                     address_allocation,
+                    non_reentrant,
+                    fee_sponsor,
                 )?;
                 Ok(InvokeResult::encode(&component_address)?)
             },
@@ -708,6 +922,49 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                 Ok(InvokeResult::unit())
             },
+            ComponentAction::SetOwnerRule => {
+                let component_address =
+                    component_ref
+                        .as_component_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "component_ref",
+                            reason: "SetOwnerRule component action requires a component address".to_string(),
+                        })?;
+
+                let owner_rule: OwnerRule = args.assert_one_arg()?;
+
+                self.tracker.write_with(|state| {
+                    let component_lock = state
+                        .current_call_scope()?
+                        .get_current_component_lock()
+                        .cloned()
+                        .ok_or(RuntimeError::NotInComponentContext {
+                            action: ComponentAction::SetOwnerRule.into(),
+                        })?;
+                    // Only the current component may be updated, see SetAccessRules
+                    if *component_lock.address() != component_address {
+                        return Err(RuntimeError::LockError(LockError::SubstateNotLocked {
+                            address: SubstateId::Component(component_address),
+                        }));
+                    }
+                    let component = state.get_component(&component_lock)?;
+                    state
+                        .authorization()
+                        .require_ownership(ComponentAction::SetOwnerRule, component.as_ownership())?;
+
+                    state.modify_component_with(&component_lock, |component| {
+                        if owner_rule == component.owner_rule {
+                            return false;
+                        }
+                        component.set_owner_rule(owner_rule);
+                        true
+                    })?;
+
+                    Ok::<_, RuntimeError>(())
+                })?;
+
+                Ok(InvokeResult::unit())
+            },
             ComponentAction::GetTemplateAddress => {
                 let component_address =
                     component_ref
@@ -719,17 +976,157 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                 args.assert_no_args("Component::GetTemplateAddress")?;
 
-                // The template can never change so we'll just fetch the component
-                self.tracker.read_with(|state| {
-                    let substate = state.store().get_unmodified_substate(&component_address.into())?;
-                    let component = substate
-                        .substate_value()
-                        .component()
-                        .ok_or(RuntimeError::ComponentNotFound {
-                            address: component_address,
+                // The template may have changed in this transaction if the component was migrated
+                let template_address = self
+                    .tracker
+                    .write_with(|state| state.get_template_for_component(&component_address))?;
+
+                Ok(InvokeResult::encode(&template_address)?)
+            },
+            ComponentAction::Destroy => {
+                let component_address =
+                    component_ref
+                        .as_component_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "component_ref",
+                            reason: "Destroy component action requires a component address".to_string(),
+                        })?;
+                args.assert_no_args("ComponentAction::Destroy")?;
+
+                self.tracker.write_with(|state| {
+                    let component_lock = state
+                        .current_call_scope()?
+                        .get_current_component_lock()
+                        .cloned()
+                        .ok_or(RuntimeError::NotInComponentContext {
+                            action: ComponentAction::Destroy.into(),
+                        })?;
+                    // A component can only destroy itself
+                    if *component_lock.address() != component_address {
+                        return Err(RuntimeError::LockError(LockError::SubstateNotLocked {
+                            address: SubstateId::Component(component_address),
+                        }));
+                    }
+                    let component = state.get_component(&component_lock)?;
+                    state
+                        .authorization()
+                        .require_ownership(ComponentAction::Destroy, component.as_ownership())?;
+
+                    // The component is removed when the call frame is popped, so that the method can still update its
+                    // state (e.g. emptying its vaults) after calling destroy.
+                    state.current_call_scope_mut()?.mark_component_destroyed();
+
+                    Ok::<_, RuntimeError>(())
+                })?;
+
+                Ok(InvokeResult::unit())
+            },
+            ComponentAction::Migrate => {
+                let component_address =
+                    component_ref
+                        .as_component_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "component_ref",
+                            reason: "Migrate component action requires a component address".to_string(),
+                        })?;
+                let new_template_address: TemplateAddress = args.assert_one_arg()?;
+
+                // The component is locked for the duration of the migration. This fails if the component is currently
+                // executing a method i.e. a component cannot migrate itself.
+                let (component_lock, old_template_address, old_state, access_rules) =
+                    self.tracker.write_with(|state| {
+                        let component_lock =
+                            state.lock_substate(&SubstateId::Component(component_address), LockFlag::Write)?;
+                        let component = state.get_component(&component_lock)?;
+                        state
+                            .authorization()
+                            .require_ownership(ComponentAction::Migrate, component.as_ownership())?;
+
+                        Ok::<_, RuntimeError>((
+                            component_lock,
+                            component.template_address,
+                            component.state().clone(),
+                            component.access_rules().clone(),
+                        ))
+                    })?;
+
+                if old_template_address == new_template_address {
+                    return Err(RuntimeError::InvalidArgument {
+                        argument: "template_address",
+                        reason: format!(
+                            "Component {} already uses template {}",
+                            component_address, new_template_address
+                        ),
+                    });
+                }
+
+                let template_def = self.get_template_def(&new_template_address)?;
+                let module_name = template_def.template_name().to_string();
+                validate_migrate_function(&template_def)?;
+                validate_component_access_rule_methods(&access_rules, &template_def)?;
+
+                let result = self.invoke_template_function(&new_template_address, "migrate", args![old_state])?;
+                let new_state = result.indexed.into_value();
+
+                self.tracker.write_with(|state| {
+                    state.modify_component_with(&component_lock, |component| {
+                        component.previous_template_address = Some(component.template_address);
+                        component.template_address = new_template_address;
+                        component.module_name = module_name;
+                        component.body.set(new_state);
+                        true
+                    })?;
+                    state.unlock_substate(component_lock)
+                })?;
+
+                Ok(InvokeResult::unit())
+            },
+            ComponentAction::GetVaultBalance => {
+                let component_address =
+                    component_ref
+                        .as_component_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "component_ref",
+                            reason: "GetVaultBalance component action requires a component address".to_string(),
                         })?;
+                let resource_address: ResourceAddress = args.assert_one_arg()?;
+
+                self.tracker.write_with(|state| {
+                    let current_lock = state
+                        .current_call_scope()?
+                        .get_current_component_lock()
+                        .filter(|l| *l.address() == component_address)
+                        .cloned();
+                    let is_already_locked = current_lock.is_some();
+                    let component_lock = match current_lock {
+                        Some(lock) => lock,
+                        None => state.lock_substate(&SubstateId::Component(component_address), LockFlag::Read)?,
+                    };
+
+                    state.authorization().check_component_balance_query(&component_lock)?;
+
+                    let component = state.get_component(&component_lock)?;
+                    let indexed = IndexedWellKnownTypes::from_value(component.state())?;
+                    let mut balance = Amount::zero();
+                    for vault_id in indexed.vault_ids() {
+                        let vault_lock = state.lock_substate(&SubstateId::Vault(*vault_id), LockFlag::Read)?;
+                        let vault = state.get_vault(&vault_lock)?;
+                        if *vault.resource_address() == resource_address {
+                            balance = balance
+                                .checked_add(vault.balance())
+                                .ok_or(RuntimeError::InvalidAmount {
+                                    amount: balance,
+                                    reason: "Vault balance overflow".to_string(),
+                                })?;
+                        }
+                        state.unlock_substate(vault_lock)?;
+                    }
+
+                    if !is_already_locked {
+                        state.unlock_substate(component_lock)?;
+                    }
 
-                    Ok(InvokeResult::encode(&component.template_address)?)
+                    Ok(InvokeResult::encode(&balance)?)
                 })
             },
         }
@@ -779,7 +1176,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         Some(to_ristretto_public_key_bytes(&self.transaction_signer_public_key))
                     },
                     OwnerRule::ByPublicKey(key) => Some(*key),
-                    OwnerRule::None | OwnerRule::ByAccessRule(_) => None,
+                    OwnerRule::None | OwnerRule::ByAccessRule(_) | OwnerRule::OwnedByComponent(_) => None,
                 };
 
                 let maybe_view_key = arg
@@ -791,13 +1188,46 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         reason: format!("Invalid view key: {}", e),
                     })?;
 
+                if arg.max_supply.map_or(false, |max| max.is_negative()) {
+                    return Err(RuntimeError::InvalidArgument {
+                        argument: "CreateResourceArg",
+                        reason: "Max supply must not be negative".to_string(),
+                    });
+                }
+
+                if arg.is_soulbound && arg.resource_type.is_confidential() {
+                    return Err(RuntimeError::InvalidArgument {
+                        argument: "CreateResourceArg",
+                        reason: "Confidential resources cannot be soulbound".to_string(),
+                    });
+                }
+
+                if let Some(royalty) = arg.royalty.as_ref() {
+                    if !arg.resource_type.is_non_fungible() {
+                        return Err(RuntimeError::InvalidArgument {
+                            argument: "CreateResourceArg",
+                            reason: "Royalties can only be set for non-fungible resources".to_string(),
+                        });
+                    }
+                    if royalty.percentage > 100 {
+                        return Err(RuntimeError::InvalidArgument {
+                            argument: "CreateResourceArg",
+                            reason: format!("Royalty percentage must be at most 100, got {}", royalty.percentage),
+                        });
+                    }
+                }
+
                 // Check that auth hook is valid
                 if let Some(hook) = arg.authorize_hook.as_ref() {
                     self.check_resource_auth_hook(hook)?;
                 }
 
                 self.tracker.write_with(|state| {
-                    let resource = Resource::new(
+                    if let Some(royalty) = arg.royalty.as_ref() {
+                        state.validate_royalty_vault(royalty)?;
+                    }
+
+                    let mut resource = Resource::new(
                         arg.resource_type,
                         owner_key,
                         arg.owner_rule,
@@ -806,8 +1236,24 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         maybe_view_key,
                         arg.authorize_hook,
                     );
-
-                    let resource_address = state.id_provider()?.new_resource_address()?;
+                    resource.set_royalty(arg.royalty);
+                    resource.set_soulbound(arg.is_soulbound);
+                    resource.set_max_supply(arg.max_supply);
+
+                    let resource_address = match arg.address_allocation {
+                        Some(address_allocation) => {
+                            let addr = state.take_allocated_address(address_allocation.id())?;
+                            addr.try_into()
+                                .map_err(|address| RuntimeError::AddressAllocationTypeMismatch { address })?
+                        },
+                        None => state.id_provider()?.new_resource_address()?,
+                    };
+                    // Allocated addresses are deterministic, so the resource may already exist
+                    if state.substate_exists(&SubstateId::Resource(resource_address))? {
+                        return Err(RuntimeError::ResourceAlreadyExists {
+                            address: resource_address,
+                        });
+                    }
                     state.new_substate(resource_address, resource)?;
                     let resource_lock =
                         state.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Write)?;
@@ -883,6 +1329,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         resource.as_ownership(),
                         resource.access_rules(),
                     )?;
+                    check_resource_not_frozen(&resource_lock, resource, ResourceAuthAction::Mint)?;
 
                     let auth_caller = state_mut.get_auth_caller()?;
                     Ok::<_, RuntimeError>((resource_lock, resource.auth_hook().cloned(), auth_caller))
@@ -913,7 +1360,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         })?;
                 let arg: RecallResourceArg = args.assert_one_arg()?;
 
-                let (maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
+                let (maybe_auth_hook, is_soulbound, auth_caller) = self.tracker.write_with(|state_mut| {
                     let resource_lock =
                         state_mut.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Read)?;
 
@@ -926,10 +1373,11 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     )?;
 
                     let auth_hook = resource.auth_hook().cloned();
+                    let is_soulbound = resource.is_soulbound();
                     let auth_caller = state_mut.get_auth_caller()?;
 
                     state_mut.unlock_substate(resource_lock)?;
-                    Ok::<_, RuntimeError>((auth_hook, auth_caller))
+                    Ok::<_, RuntimeError>((auth_hook, is_soulbound, auth_caller))
                 })?;
 
                 if let Some(auth_hook) = maybe_auth_hook {
@@ -943,6 +1391,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
+                    if is_soulbound {
+                        state.get_bucket_mut(bucket_id)?.set_burn_only();
+                    }
 
                     state.unlock_substate(vault_lock)?;
 
@@ -989,63 +1440,130 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                             reason: "UpdateNonFungibleData resource action requires a resource address".to_string(),
                         })?;
                 let arg: ResourceUpdateNonFungibleDataArg = args.assert_one_arg()?;
+                self.update_non_fungible_data(resource_address, arg.id, "UpdateNonFungibleData", |contents| {
+                    contents.set_mutable_data(arg.data);
+                    Ok(())
+                })?;
+                Ok(InvokeResult::unit())
+            },
+            ResourceAction::PatchNonFungibleData => {
+                let resource_address =
+                    resource_ref
+                        .as_resource_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "resource_ref",
+                            reason: "PatchNonFungibleData resource action requires a resource address".to_string(),
+                        })?;
+                let arg: ResourcePatchNonFungibleDataArg = args.assert_one_arg()?;
+                let nf_id = arg.id.clone();
+                self.update_non_fungible_data(resource_address, arg.id, "PatchNonFungibleData", |contents| {
+                    contents
+                        .merge_mutable_data(arg.patch)
+                        .map_err(|e| RuntimeError::InvalidNonFungibleDataPatch {
+                            resource_address,
+                            nf_id,
+                            details: e.to_string(),
+                        })
+                })?;
+                Ok(InvokeResult::unit())
+            },
+            ResourceAction::UpdateAccessRules => {
+                let resource_address =
+                    resource_ref
+                        .as_resource_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "resource_ref",
+                            reason: "UpdateAccessRules resource action requires a resource address".to_string(),
+                        })?;
+                let access_rules: ResourceAccessRules = args.assert_one_arg()?;
 
-                let (maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
+                let (resource_lock, maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
                     let resource_lock =
-                        state_mut.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Read)?;
+                        state_mut.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Write)?;
+
+                    let resource = state_mut.get_resource(&resource_lock)?;
+
+                    state_mut
+                        .authorization()
+                        .require_ownership(ResourceAuthAction::UpdateAccessRules, resource.as_ownership())?;
+
+                    let auth_caller = state_mut.get_auth_caller()?;
+                    Ok::<_, RuntimeError>((resource_lock, resource.auth_hook().cloned(), auth_caller))
+                })?;
+
+                if let Some(auth_hook) = maybe_auth_hook {
+                    self.invoke_resource_access_hook(auth_hook, auth_caller, ResourceAuthAction::UpdateAccessRules)?;
+                }
+
+                self.tracker.write_with(|state| {
+                    let resource_mut = state.get_resource_mut(&resource_lock)?;
+                    resource_mut.set_access_rules(access_rules);
+                    state.unlock_substate(resource_lock)?;
+
+                    Ok(InvokeResult::unit())
+                })
+            },
+            ResourceAction::UpdateResourceMetadata => {
+                let resource_address =
+                    resource_ref
+                        .as_resource_address()
+                        .ok_or_else(|| RuntimeError::InvalidArgument {
+                            argument: "resource_ref",
+                            reason: "UpdateResourceMetadata resource action requires a resource address".to_string(),
+                        })?;
+                let arg: ResourceUpdateMetadataArg = args.assert_one_arg()?;
+
+                let (resource_lock, maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
+                    let resource_lock =
+                        state_mut.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Write)?;
 
                     let resource = state_mut.get_resource(&resource_lock)?;
 
                     state_mut.authorization().check_resource_access_rules(
-                        ResourceAuthAction::UpdateNonFungibleData,
+                        ResourceAuthAction::UpdateMetadata,
                         resource.as_ownership(),
                         resource.access_rules(),
                     )?;
 
-                    let auth_hook = resource.auth_hook().cloned();
                     let auth_caller = state_mut.get_auth_caller()?;
-
-                    state_mut.unlock_substate(resource_lock)?;
-                    Ok::<_, RuntimeError>((auth_hook, auth_caller))
+                    Ok::<_, RuntimeError>((resource_lock, resource.auth_hook().cloned(), auth_caller))
                 })?;
 
                 if let Some(auth_hook) = maybe_auth_hook {
-                    self.invoke_resource_access_hook(
-                        auth_hook,
-                        auth_caller,
-                        ResourceAuthAction::UpdateNonFungibleData,
-                    )?;
+                    self.invoke_resource_access_hook(auth_hook, auth_caller, ResourceAuthAction::UpdateMetadata)?;
                 }
 
                 self.tracker.write_with(|state| {
-                    let addr = NonFungibleAddress::new(resource_address, arg.id);
-                    let locked = state.lock_substate(&SubstateId::NonFungible(addr.clone()), LockFlag::Write)?;
-
-                    let nft = state.get_non_fungible_mut(&locked)?;
-
-                    let contents = nft
-                        .contents_mut()
-                        .ok_or_else(|| RuntimeError::InvalidOpNonFungibleBurnt {
-                            op: "UpdateNonFungibleData",
-                            resource_address,
-                            nf_id: addr.id().clone(),
-                        })?;
-                    contents.set_mutable_data(arg.data);
+                    let resource_mut = state.get_resource_mut(&resource_lock)?;
+                    resource_mut.set_metadata(arg.metadata.clone());
+                    state.unlock_substate(resource_lock)?;
 
-                    state.unlock_substate(locked)?;
+                    // Let indexers know that the resource description has changed
+                    let tx_hash = self.entity_id_provider.transaction_hash();
+                    let (template_address, _) = state.current_template()?;
+                    let event = Event::new(
+                        Some(SubstateId::Resource(resource_address)),
+                        *template_address,
+                        tx_hash,
+                        RESOURCE_METADATA_UPDATED_TOPIC.to_string(),
+                        arg.metadata,
+                    );
+                    debug!(target: LOG_TARGET, "Emitted resource event {}", event);
+                    state.push_event(event);
 
                     Ok(InvokeResult::unit())
                 })
             },
-            ResourceAction::UpdateAccessRules => {
+            ResourceAction::Freeze | ResourceAction::Unfreeze => {
                 let resource_address =
                     resource_ref
                         .as_resource_address()
                         .ok_or_else(|| RuntimeError::InvalidArgument {
                             argument: "resource_ref",
-                            reason: "UpdateAccessRules resource action requires a resource address".to_string(),
+                            reason: format!("{:?} resource action requires a resource address", action),
                         })?;
-                let access_rules: ResourceAccessRules = args.assert_one_arg()?;
+                args.assert_no_args("ResourceAction::Freeze/Unfreeze")?;
+                let is_freeze = matches!(action, ResourceAction::Freeze);
 
                 let (resource_lock, maybe_auth_hook, auth_caller) = self.tracker.write_with(|state_mut| {
                     let resource_lock =
@@ -1053,23 +1571,42 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                     let resource = state_mut.get_resource(&resource_lock)?;
 
-                    state_mut
-                        .authorization()
-                        .require_ownership(ResourceAuthAction::UpdateAccessRules, resource.as_ownership())?;
+                    state_mut.authorization().check_resource_access_rules(
+                        ResourceAuthAction::Freeze,
+                        resource.as_ownership(),
+                        resource.access_rules(),
+                    )?;
 
                     let auth_caller = state_mut.get_auth_caller()?;
                     Ok::<_, RuntimeError>((resource_lock, resource.auth_hook().cloned(), auth_caller))
                 })?;
 
                 if let Some(auth_hook) = maybe_auth_hook {
-                    self.invoke_resource_access_hook(auth_hook, auth_caller, ResourceAuthAction::UpdateAccessRules)?;
+                    self.invoke_resource_access_hook(auth_hook, auth_caller, ResourceAuthAction::Freeze)?;
                 }
 
                 self.tracker.write_with(|state| {
                     let resource_mut = state.get_resource_mut(&resource_lock)?;
-                    resource_mut.set_access_rules(access_rules);
+                    resource_mut.set_frozen(is_freeze);
                     state.unlock_substate(resource_lock)?;
 
+                    let topic = if is_freeze {
+                        RESOURCE_FROZEN_TOPIC
+                    } else {
+                        RESOURCE_UNFROZEN_TOPIC
+                    };
+                    let tx_hash = self.entity_id_provider.transaction_hash();
+                    let (template_address, _) = state.current_template()?;
+                    let event = Event::new(
+                        Some(SubstateId::Resource(resource_address)),
+                        *template_address,
+                        tx_hash,
+                        topic.to_string(),
+                        Metadata::new(),
+                    );
+                    debug!(target: LOG_TARGET, "Emitted resource event {}", event);
+                    state.push_event(event);
+
                     Ok(InvokeResult::unit())
                 })
             },
@@ -1172,6 +1709,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                             resource.as_ownership(),
                             resource.access_rules(),
                         )?;
+                        check_resource_not_frozen(&resource_lock, resource, ResourceAuthAction::Deposit)?;
 
                         let auth_caller = state_mut.get_auth_caller()?;
                         Ok::<_, RuntimeError>((vault_lock, resource_lock, resource.auth_hook().cloned(), auth_caller))
@@ -1190,6 +1728,14 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                             locked_amount: bucket.locked_amount(),
                         });
                     }
+                    // Soulbound tokens that have left their vault can only be burnt
+                    if bucket.is_burn_only() {
+                        return Err(RuntimeError::SoulboundResourceTransfer {
+                            resource_address: *bucket.resource_address(),
+                        });
+                    }
+
+                    state_mut.record_royalty_deposit(vault_id, &resource_lock, &bucket)?;
 
                     // Emit a builtin event for the deposit
                     self.emit_vault_events(
@@ -1233,6 +1779,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                             resource.as_ownership(),
                             resource.access_rules(),
                         )?;
+                        check_resource_not_frozen(&resource_lock, resource, ResourceAuthAction::Withdraw)?;
 
                         let auth_caller = state_mut.get_auth_caller()?;
                         Ok::<_, RuntimeError>((vault_lock, resource_lock, resource.auth_hook().cloned(), auth_caller))
@@ -1245,6 +1792,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 self.tracker.write_with(|state| {
                     let resource = state.get_resource(&resource_lock)?;
                     let maybe_view_key = resource.view_key().cloned();
+                    let is_soulbound = resource.is_soulbound();
 
                     let vault_mut = state.get_vault_mut(&vault_lock)?;
                     let (resource_container, amount) = match arg {
@@ -1262,7 +1810,10 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         },
                         VaultWithdrawArg::Confidential { proof } => {
                             let amount = proof.revealed_input_amount();
-                            let container = vault_mut.withdraw_confidential(*proof, maybe_view_key.as_ref())?;
+                            let mut batch = ConfidentialProofBatch::new();
+                            let container =
+                                vault_mut.withdraw_confidential(*proof, maybe_view_key.as_ref(), &mut batch)?;
+                            state.defer_confidential_proofs(batch);
                             (container, amount)
                         },
                     };
@@ -1279,6 +1830,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource_container)?;
+                    if is_soulbound {
+                        state.get_bucket_mut(bucket_id)?.set_burn_only();
+                    }
 
                     state.unlock_substate(vault_lock)?;
                     state.unlock_substate(resource_lock)?;
@@ -1344,6 +1898,39 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     Ok(result)
                 })
             },
+            VaultAction::GetNonFungibleIdsPaged => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "vault action requires a vault id".to_string(),
+                })?;
+                args.assert_n_args::<u32>(2)?;
+                let offset: u32 = args.get(0)?;
+                let limit: u32 = args.get(1)?;
+
+                self.tracker.write_with(|state| {
+                    let vault_lock = state.lock_substate(&SubstateId::Vault(vault_id), LockFlag::Read)?;
+                    let non_fungible_ids = state
+                        .get_vault(&vault_lock)?
+                        .get_non_fungible_ids_paged(offset as usize, limit as usize);
+                    let result = InvokeResult::encode(&non_fungible_ids)?;
+                    state.unlock_substate(vault_lock)?;
+                    Ok(result)
+                })
+            },
+            VaultAction::GetNonFungibleCount => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "vault action requires a vault id".to_string(),
+                })?;
+                args.assert_no_args("Vault::GetNonFungibleCount")?;
+
+                self.tracker.write_with(|state| {
+                    let vault_lock = state.lock_substate(&SubstateId::Vault(vault_id), LockFlag::Read)?;
+                    let count = state.get_vault(&vault_lock)?.get_non_fungible_count();
+                    state.unlock_substate(vault_lock)?;
+                    Ok(InvokeResult::encode(&(count as u32))?)
+                })
+            },
             VaultAction::GetCommitmentCount => {
                 let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
                     argument: "vault_ref",
@@ -1383,6 +1970,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                             resource.as_ownership(),
                             resource.access_rules(),
                         )?;
+                        check_resource_not_frozen(&resource_lock, resource, ResourceAuthAction::Withdraw)?;
 
                         let auth_caller = state_mut.get_auth_caller()?;
                         Ok::<_, RuntimeError>((vault_lock, resource_lock, resource.auth_hook().cloned(), auth_caller))
@@ -1397,7 +1985,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let view_key = resource.view_key().cloned();
 
                     let vault_mut = state.get_vault_mut(&vault_lock)?;
-                    let resource_container = vault_mut.reveal_confidential(arg.proof, view_key.as_ref())?;
+                    let mut batch = ConfidentialProofBatch::new();
+                    let resource_container = vault_mut.reveal_confidential(arg.proof, view_key.as_ref(), &mut batch)?;
+                    state.defer_confidential_proofs(batch);
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource_container)?;
 
@@ -1451,10 +2041,12 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         let withdrawn = vault_mut.withdraw(arg.amount)?;
                         container.deposit(withdrawn)?;
                     }
+                    let mut batch = ConfidentialProofBatch::new();
                     if let Some(proof) = arg.proof {
-                        let revealed = vault_mut.reveal_confidential(proof, view_key.as_ref())?;
+                        let revealed = vault_mut.reveal_confidential(proof, view_key.as_ref(), &mut batch)?;
                         container.deposit(revealed)?;
                     }
+                    state.defer_confidential_proofs(batch);
                     if container.amount().is_zero() {
                         return Err(RuntimeError::InvalidArgument {
                             argument: "TakeFeesArg",
@@ -1686,8 +2278,31 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 self.tracker.write_with(|state| {
                     let bucket = state.get_bucket_mut(bucket_id)?;
                     let resource = bucket.take(amount)?;
+                    let is_burn_only = bucket.is_burn_only();
+                    let bucket_id = state.id_provider()?.new_bucket_id();
+                    state.new_bucket(bucket_id, resource)?;
+                    if is_burn_only {
+                        state.get_bucket_mut(bucket_id)?.set_burn_only();
+                    }
+                    Ok(InvokeResult::encode(&bucket_id)?)
+                })
+            },
+            BucketAction::TakeNonFungibles => {
+                let bucket_id = bucket_ref.bucket_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "bucket_ref",
+                    reason: "TakeNonFungibles bucket action requires a bucket id".to_string(),
+                })?;
+                let ids: BTreeSet<NonFungibleId> = args.assert_one_arg()?;
+
+                self.tracker.write_with(|state| {
+                    let bucket = state.get_bucket_mut(bucket_id)?;
+                    let resource = bucket.take_non_fungibles(&ids)?;
+                    let is_burn_only = bucket.is_burn_only();
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
+                    if is_burn_only {
+                        state.get_bucket_mut(bucket_id)?.set_burn_only();
+                    }
                     Ok(InvokeResult::encode(&bucket_id)?)
                 })
             },
@@ -1704,7 +2319,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let resource = state.get_resource(&resource_lock)?;
                     let view_key = resource.view_key().cloned();
                     let bucket_mut = state.get_bucket_mut(bucket_id)?;
-                    let resource = bucket_mut.take_confidential(proof, view_key.as_ref())?;
+                    let mut batch = ConfidentialProofBatch::new();
+                    let resource = bucket_mut.take_confidential(proof, view_key.as_ref(), &mut batch)?;
+                    state.defer_confidential_proofs(batch);
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
                     state.unlock_substate(resource_lock)?;
@@ -1737,7 +2354,9 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     let resource = state.get_resource(&resource_lock)?;
                     let view_key = resource.view_key().cloned();
                     let bucket = state.get_bucket_mut(bucket_id)?;
-                    let resource = bucket.reveal_confidential(proof, view_key.as_ref())?;
+                    let mut batch = ConfidentialProofBatch::new();
+                    let resource = bucket.reveal_confidential(proof, view_key.as_ref(), &mut batch)?;
+                    state.defer_confidential_proofs(batch);
                     let bucket_id = state.id_provider()?.new_bucket_id();
                     state.new_bucket(bucket_id, resource)?;
                     state.unlock_substate(resource_lock)?;
@@ -1897,7 +2516,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 })?;
                 args.assert_no_args("Proof.GetAmount")?;
                 self.tracker.write_with(|state| {
-                    let proof = state.get_proof(proof_id)?;
+                    let proof = state.get_single_proof(proof_id, "Proof.GetAmount")?;
                     Ok(InvokeResult::encode(&proof.amount())?)
                 })
             },
@@ -1908,7 +2527,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 })?;
                 args.assert_no_args("Proof.GetResourceAddress")?;
                 self.tracker.write_with(|state| {
-                    let proof = state.get_proof(proof_id)?;
+                    let proof = state.get_single_proof(proof_id, "Proof.GetResourceAddress")?;
                     Ok(InvokeResult::encode(proof.resource_address())?)
                 })
             },
@@ -1921,7 +2540,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 args.assert_no_args("Proof.GetResourceType")?;
 
                 self.tracker.write_with(|state| {
-                    let proof = state.get_proof(proof_id)?;
+                    let proof = state.get_single_proof(proof_id, "Proof.GetResourceType")?;
                     Ok(InvokeResult::encode(&proof.resource_type())?)
                 })
             },
@@ -1934,7 +2553,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 args.assert_no_args("Proof.GetNonFungibles")?;
 
                 self.tracker.write_with(|state| {
-                    let proof = state.get_proof(proof_id)?;
+                    let proof = state.get_single_proof(proof_id, "Proof.GetNonFungibles")?;
                     let nfts = proof.non_fungible_token_ids();
                     Ok(InvokeResult::encode(&nfts)?)
                 })
@@ -1970,6 +2589,21 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                     Ok(InvokeResult::unit())
                 })
             },
+            ProofAction::Combine => {
+                let proof_id = proof_ref.proof_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "proof_ref",
+                    reason: "Combine proof action requires a proof id".to_string(),
+                })?;
+                let others: Vec<ProofId> = args.assert_one_arg()?;
+                let mut proof_ids = vec![proof_id];
+                proof_ids.extend(others);
+
+                self.tracker.write_with(|state| {
+                    let composite_proof_id = state.id_provider()?.new_proof_id();
+                    state.combine_proofs(composite_proof_id, &proof_ids)?;
+                    Ok(InvokeResult::encode(&composite_proof_id)?)
+                })
+            },
             ProofAction::Drop => {
                 let proof_id = proof_ref.proof_id().ok_or_else(|| RuntimeError::InvalidArgument {
                     argument: "proof_ref",
@@ -2058,6 +2692,51 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                         }));
                     }
 
+                    Ok(InvokeResult::unit())
+                })
+            },
+            WorkspaceAction::CreateProofFromBucket => {
+                let key: Vec<u8> = args.get(0)?;
+
+                let value = self.tracker.get_from_workspace(&key)?;
+                let bucket_id = *value
+                    .bucket_ids()
+                    .first()
+                    .ok_or_else(|| RuntimeError::InvalidArgument {
+                        argument: "key",
+                        reason: format!("Workspace item '{}' is not a bucket", String::from_utf8_lossy(&key)),
+                    })?;
+
+                self.tracker.write_with(|state| {
+                    let resource_address = *state.get_bucket(bucket_id)?.resource_address();
+                    let resource_lock = state.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Read)?;
+                    let resource = state.get_resource(&resource_lock)?;
+
+                    state.authorization().check_resource_access_rules(
+                        ResourceAuthAction::Withdraw,
+                        resource.as_ownership(),
+                        resource.access_rules(),
+                    )?;
+                    let has_auth_hook = resource.auth_hook().is_some();
+                    state.unlock_substate(resource_lock)?;
+
+                    // There is no calling template or component to pass to the hook at the transaction level
+                    if has_auth_hook {
+                        return Err(RuntimeError::InvalidArgument {
+                            argument: "key",
+                            reason: format!(
+                                "Resource {} has an authorization hook, proofs of it must be created from within a \
+                                 template",
+                                resource_address
+                            ),
+                        });
+                    }
+
+                    let locked_funds = state.get_bucket_mut(bucket_id)?.lock_all()?;
+                    let proof_id = state.new_proof_id();
+                    state.new_proof(proof_id, locked_funds)?;
+                    state.set_last_instruction_output(IndexedValue::from_type(&proof_id)?);
+
                     Ok(InvokeResult::unit())
                 })
             },
@@ -2120,6 +2799,103 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         }
     }
 
+    fn key_value_store_invoke(
+        &self,
+        store_ref: KeyValueStoreRef,
+        action: KeyValueStoreAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError> {
+        self.invoke_modules_on_runtime_call("key_value_store_invoke")?;
+        debug!(target: LOG_TARGET, "KeyValueStore invoke: {} {:?}", store_ref, action);
+
+        // Only the owner of the store may modify its entries
+        if action.requires_write_access() {
+            if let Some(store_id) = store_ref.as_store_id() {
+                self.tracker
+                    .read_with(|state| state.check_component_scope(&store_id.into(), action))?;
+            }
+        }
+
+        let get_entry_id = |key: &[u8]| {
+            let store_id = store_ref.as_store_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                argument: "store_ref",
+                reason: format!("{:?} action requires a key-value store id", action),
+            })?;
+            Ok::<_, RuntimeError>(SubstateId::KeyValueEntry(KeyValueEntryAddress::new(store_id, key)))
+        };
+
+        match action {
+            KeyValueStoreAction::Create => {
+                args.assert_no_args("KeyValueStoreAction::Create")?;
+                self.tracker.write_with(|state| {
+                    let store_id = state.id_provider()?.new_key_value_store_id()?;
+                    state.new_substate(store_id, KeyValueStoreContainer::new())?;
+                    debug!(target: LOG_TARGET, "Created key-value store {}", store_id);
+                    Ok(InvokeResult::encode(&store_id)?)
+                })
+            },
+            KeyValueStoreAction::Get => {
+                args.assert_n_args::<Vec<u8>>(1)?;
+                let key: Vec<u8> = args.get(0)?;
+                let entry_id = get_entry_id(&key)?;
+                self.tracker.write_with(|state| {
+                    // Entries that have not been created (or were not provided as inputs) do not exist
+                    if !state.substate_exists(&entry_id)? {
+                        return Ok(InvokeResult::encode(&Option::<tari_bor::Value>::None)?);
+                    }
+                    let lock = state.lock_substate(&entry_id, LockFlag::Read)?;
+                    let value = state.get_key_value_entry(&lock)?.value().cloned();
+                    state.unlock_substate(lock)?;
+                    Ok(InvokeResult::encode(&value)?)
+                })
+            },
+            KeyValueStoreAction::Insert => {
+                args.assert_n_args::<tari_bor::Value>(2)?;
+                let key: Vec<u8> = args.get(0)?;
+                let value: tari_bor::Value = args.get(1)?;
+                let entry_id = get_entry_id(&key)?;
+                let next_state = IndexedWellKnownTypes::from_value(&value)?;
+
+                self.tracker.write_with(|state| {
+                    if !state.substate_exists(&entry_id)? {
+                        state.validate_component_state(None, &next_state)?;
+                        state.new_substate(entry_id, KeyValueEntry::new(key, value))?;
+                        return Ok(InvokeResult::encode(&Option::<tari_bor::Value>::None)?);
+                    }
+
+                    let lock = state.lock_substate(&entry_id, LockFlag::Write)?;
+                    let previous = state.get_key_value_entry(&lock)?.value().cloned();
+                    let previous_state = previous.as_ref().map(IndexedWellKnownTypes::from_value).transpose()?;
+                    state.validate_component_state(previous_state.as_ref(), &next_state)?;
+                    state.get_key_value_entry_mut(&lock)?.replace(value);
+                    state.unlock_substate(lock)?;
+                    Ok(InvokeResult::encode(&previous)?)
+                })
+            },
+            KeyValueStoreAction::Remove => {
+                args.assert_n_args::<Vec<u8>>(1)?;
+                let key: Vec<u8> = args.get(0)?;
+                let entry_id = get_entry_id(&key)?;
+                self.tracker.write_with(|state| {
+                    if !state.substate_exists(&entry_id)? {
+                        return Ok(InvokeResult::encode(&Option::<tari_bor::Value>::None)?);
+                    }
+
+                    let lock = state.lock_substate(&entry_id, LockFlag::Write)?;
+                    let previous = state.get_key_value_entry(&lock)?.value().cloned();
+                    if let Some(ref value) = previous {
+                        // Removing a value must not orphan any substates that it owns (e.g. vaults)
+                        let previous_state = IndexedWellKnownTypes::from_value(value)?;
+                        state.validate_component_state(Some(&previous_state), &IndexedWellKnownTypes::new())?;
+                        state.get_key_value_entry_mut(&lock)?.remove();
+                    }
+                    state.unlock_substate(lock)?;
+                    Ok(InvokeResult::encode(&previous)?)
+                })
+            },
+        }
+    }
+
     fn consensus_invoke(&self, action: ConsensusAction) -> Result<InvokeResult, RuntimeError> {
         self.invoke_modules_on_runtime_call("consensus_invoke")?;
         match action {
@@ -2127,6 +2903,14 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 let epoch = self.tracker.get_current_epoch()?;
                 Ok(InvokeResult::encode(&epoch)?)
             },
+            ConsensusAction::GetCurrentTimestamp => {
+                let timestamp = self.tracker.get_current_timestamp()?;
+                Ok(InvokeResult::encode(&timestamp)?)
+            },
+            ConsensusAction::GetRandomBeacon => {
+                let beacon = self.tracker.get_random_beacon()?;
+                Ok(InvokeResult::encode(&beacon)?)
+            },
         }
     }
 
@@ -2168,6 +2952,27 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
 
                 self.invoke_component_method(&component_address, &method, args)?
             },
+            CallAction::CallInterfaceMethod => {
+                let CallInterfaceMethodArg {
+                    component_address,
+                    signature,
+                    args,
+                } = args.assert_one_arg()?;
+
+                let template_address = self
+                    .tracker
+                    .write_with(|state| state.get_template_for_component(&component_address))?;
+                let template_def = self.get_template_def(&template_address)?;
+                validate_interface_method(&template_def, &signature).map_err(|details| {
+                    RuntimeError::InterfaceMismatch {
+                        component_address,
+                        method: signature.name.clone(),
+                        details,
+                    }
+                })?;
+
+                self.invoke_component_method(&component_address, &signature.name, args)?
+            },
         };
 
         Ok(InvokeResult::from_value(exec_result.indexed.into_value()))
@@ -2226,18 +3031,21 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
                 encrypted_data: unclaimed_output.encrypted_data,
                 minimum_value_promise: 0,
                 viewable_balance: None,
+                encrypted_memo: None,
             })),
             Amount::zero(),
         );
 
         // If a withdraw proof is provided, we execute it and deposit back into the resource
         // This allows some funds to be revealed and/or reblinded within a single instruction
+        let mut batch = ConfidentialProofBatch::new();
         if let Some(proof) = withdraw_proof {
-            let withdraw = resource.withdraw_confidential(proof, None)?;
+            let withdraw = resource.withdraw_confidential(proof, None, &mut batch)?;
             resource.deposit(withdraw)?;
         }
 
         self.tracker.write_with(|state| {
+            state.defer_confidential_proofs(batch);
             let bucket_id = state.new_bucket_id();
             state.new_bucket(bucket_id, resource)?;
             state.set_last_instruction_output(IndexedValue::from_type(&bucket_id)?);
@@ -2259,6 +3067,40 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
         Ok(())
     }
 
+    fn pay_rent(&self, substate_id: &SubstateId, epochs: u64) -> Result<(), RuntimeError> {
+        self.invoke_modules_on_runtime_call("pay_rent")?;
+
+        if epochs == 0 {
+            return Err(RuntimeError::InvalidArgument {
+                argument: "epochs",
+                reason: "Rent must be paid for at least one epoch".to_string(),
+            });
+        }
+
+        self.tracker.write_with(|state| {
+            let paid_until = state.top_up_rent(substate_id, epochs)?;
+            debug!(target: LOG_TARGET, "Rent for substate {} paid until epoch {}", substate_id, paid_until);
+            Ok(())
+        })
+    }
+
+    fn begin_instruction(&self, instruction: &Instruction) -> Result<(), RuntimeError> {
+        self.tracker.begin_instruction_fees();
+        self.invoke_modules_on_instruction_start(instruction)?;
+        Ok(())
+    }
+
+    fn end_instruction(&self) -> Result<(), RuntimeError> {
+        // If the fee module is present, this will add the event and log fees of the instruction
+        self.invoke_modules_on_instruction_end()?;
+        self.tracker.end_instruction_fees();
+        Ok(())
+    }
+
+    fn collect_royalties(&self) -> Result<(), RuntimeError> {
+        self.tracker.write_with(|state| state.collect_royalties())
+    }
+
     fn set_fee_checkpoint(&self) -> Result<(), RuntimeError> {
         if self.tracker.total_fee_payments() < self.tracker.total_fee_charges() {
             return Err(RuntimeError::InsufficientFeesPaid {
@@ -2304,7 +3146,8 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>> RuntimeInte
     }
 
     fn validate_finalized(&self) -> Result<(), RuntimeError> {
-        self.tracker.read_with(|state| {
+        self.tracker.write_with(|state| {
+            state.verify_deferred_confidential_proofs()?;
             state.validate_finalized()?;
             Ok(())
         })
@@ -2358,3 +3201,181 @@ fn validate_component_access_rule_methods(
     }
     Ok(())
 }
+
+/// A fee sponsor policy must be a `fn(&self, method: String) -> Amount` method and the fee vault must be part of the
+/// component's state
+fn validate_fee_sponsor(
+    fee_sponsor: &FeeSponsor,
+    template_def: &TemplateDef,
+    state: &tari_bor::Value,
+) -> Result<(), RuntimeError> {
+    let func = template_def
+        .get_function(&fee_sponsor.policy_method)
+        .ok_or_else(|| RuntimeError::InvalidArgument {
+            argument: "fee_sponsor",
+            reason: format!("Fee policy method '{}' not found", fee_sponsor.policy_method),
+        })?;
+
+    let is_valid_signature = !func.is_mut &&
+        func.arguments.len() == 2 &&
+        func.arguments[0].name == "self" &&
+        matches!(func.arguments[1].arg_type, Type::String) &&
+        matches!(func.output.other(), Some("Amount"));
+    if !is_valid_signature {
+        return Err(RuntimeError::InvalidArgument {
+            argument: "fee_sponsor",
+            reason: format!(
+                "Fee policy method '{}' must have the signature fn(&self, method: String) -> Amount",
+                fee_sponsor.policy_method
+            ),
+        });
+    }
+
+    let indexed = IndexedWellKnownTypes::from_value(state)?;
+    if !indexed.vault_ids().contains(&fee_sponsor.vault_id) {
+        return Err(RuntimeError::InvalidArgument {
+            argument: "fee_sponsor",
+            reason: format!("Fee vault {} is not owned by the component", fee_sponsor.vault_id),
+        });
+    }
+
+    Ok(())
+}
+
+/// Names used to derive addresses must be non-empty and at most 64 bytes
+fn validate_address_name(name: &str) -> Result<(), RuntimeError> {
+    const MAX_NAME_LEN: usize = 64;
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(RuntimeError::InvalidArgument {
+            argument: "name",
+            reason: format!("Address name must be between 1 and {MAX_NAME_LEN} bytes long"),
+        });
+    }
+    Ok(())
+}
+
+/// A template that components can migrate to must export a `migrate(old_state)` function that returns its state
+fn validate_migrate_function(template_def: &TemplateDef) -> Result<(), RuntimeError> {
+    let template_name = template_def.template_name();
+    let func = template_def
+        .get_function("migrate")
+        .ok_or_else(|| RuntimeError::InvalidArgument {
+            argument: "template_address",
+            reason: format!("Template {} does not export a migrate function", template_name),
+        })?;
+
+    if func.arguments.len() != 1 || func.arguments[0].name == "self" {
+        return Err(RuntimeError::InvalidArgument {
+            argument: "template_address",
+            reason: format!(
+                "Migrate function of template {} must be a function that takes the old state as its only argument",
+                template_name
+            ),
+        });
+    }
+
+    if func.output.other() != Some(template_name) {
+        return Err(RuntimeError::InvalidArgument {
+            argument: "template_address",
+            reason: format!("Migrate function of template {template_name} must return {template_name}"),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the template has a method that matches the signature declared by an interface
+fn validate_interface_method(template_def: &TemplateDef, signature: &FunctionDef) -> Result<(), String> {
+    let func = template_def.get_function(&signature.name).ok_or_else(|| {
+        format!(
+            "Template {} does not have a method named {}",
+            template_def.template_name(),
+            signature.name
+        )
+    })?;
+
+    if func.arguments.first().map_or(true, |arg| arg.name != "self") {
+        return Err(format!("{} is a function, not a method", signature.name));
+    }
+
+    if func.is_mut != signature.is_mut {
+        return Err(format!(
+            "Expected {} to be {}mutable but the template declares it {}mutable",
+            signature.name,
+            if signature.is_mut { "" } else { "im" },
+            if func.is_mut { "" } else { "im" },
+        ));
+    }
+
+    let expected_args = signature.arguments.iter().map(|a| &a.arg_type).collect::<Vec<_>>();
+    let actual_args = func.arguments.iter().map(|a| &a.arg_type).collect::<Vec<_>>();
+    if expected_args != actual_args {
+        return Err(format!(
+            "Expected arguments ({}) but the template declares ({})",
+            display_types(&expected_args),
+            display_types(&actual_args)
+        ));
+    }
+
+    if func.output != signature.output {
+        return Err(format!(
+            "Expected return type {} but the template declares {}",
+            signature.output, func.output
+        ));
+    }
+
+    Ok(())
+}
+
+/// Adds the values of the indexed fields of a typed event payload to the event's search keys
+fn add_indexed_event_fields(
+    payload: &mut Metadata,
+    data: Option<&tari_bor::Value>,
+    indexed_fields: Vec<String>,
+) -> Result<(), RuntimeError> {
+    if indexed_fields.len() > MAX_INDEXED_EVENT_FIELDS {
+        return Err(RuntimeError::TooManyIndexedEventFields {
+            count: indexed_fields.len(),
+            max: MAX_INDEXED_EVENT_FIELDS,
+        });
+    }
+
+    for field in indexed_fields {
+        let invalid_field = |details: &str| RuntimeError::InvalidIndexedEventField {
+            field: field.clone(),
+            details: details.to_string(),
+        };
+        let value = data
+            .and_then(|data| data.as_map())
+            .and_then(|entries| entries.iter().find(|(k, _)| k.as_text() == Some(field.as_str())))
+            .map(|(_, v)| v)
+            .ok_or_else(|| invalid_field("the typed payload of the event does not contain the field"))?;
+        let value =
+            indexed_field_value(value).ok_or_else(|| invalid_field("only scalar and address fields can be indexed"))?;
+        if payload.contains_key(&field) {
+            return Err(invalid_field("the event already has a search key with this name"));
+        }
+        payload.insert(field, value);
+    }
+
+    Ok(())
+}
+
+fn display_types(types: &[&Type]) -> String {
+    types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Frozen resources cannot be minted, withdrawn or deposited until the resource is unfrozen
+fn check_resource_not_frozen(
+    resource_lock: &LockedSubstate,
+    resource: &Resource,
+    action: ResourceAuthAction,
+) -> Result<(), RuntimeError> {
+    if resource.is_frozen() {
+        return Err(RuntimeError::ResourceFrozen {
+            address: resource_lock.address().clone(),
+            action: action.into(),
+        });
+    }
+    Ok(())
+}
@@ -111,6 +111,10 @@ impl LockedSubstates {
         Ok(LockedSubstate::new(addr.clone(), lock_id, lock_flag))
     }
 
+    pub fn is_locked(&self, addr: &SubstateId) -> bool {
+        self.locks.contains_key(addr)
+    }
+
     fn next_id(&mut self) -> Result<LockId, LockError> {
         let id = self.id_counter;
         self.id_counter = self
@@ -226,6 +230,8 @@ pub enum LockError {
         requested_lock: LockFlag,
         lock_state: LockState,
     },
+    #[error("Substate {address} is locked")]
+    SubstateIsLocked { address: SubstateId },
     #[error("Multiple write locks requested for substate {address}")]
     MultipleWriteLockRequested { address: SubstateId },
     #[error("Lock for {address} does not have the required access. Requested: {requested}, Actual: {actual}")]
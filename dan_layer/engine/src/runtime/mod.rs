@@ -53,16 +53,17 @@ mod workspace;
 
 use std::{fmt::Debug, sync::Arc};
 
-use tari_bor::decode_exact;
+use tari_bor::{decode_exact, encode, to_value};
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::Epoch;
 use tari_engine_types::{
     commit_result::FinalizeResult,
     component::ComponentHeader,
     confidential::ConfidentialClaim,
-    indexed_value::IndexedValue,
+    indexed_value::{IndexedValue, IndexedWellKnownTypes},
+    instruction::Instruction,
     lock::LockFlag,
-    substate::SubstateValue,
+    substate::{SubstateId, SubstateValue},
 };
 use tari_template_lib::{
     args::{
@@ -77,6 +78,8 @@ use tari_template_lib::{
         ConsensusAction,
         GenerateRandomAction,
         InvokeResult,
+        KeyValueStoreAction,
+        KeyValueStoreRef,
         LogLevel,
         NonFungibleAction,
         ProofAction,
@@ -87,7 +90,7 @@ use tari_template_lib::{
         WorkspaceAction,
     },
     invoke_args,
-    models::{ComponentAddress, EntityId, Metadata, NonFungibleAddress, VaultRef},
+    models::{Amount, ComponentAddress, EntityId, Metadata, NonFungibleAddress, VaultRef},
 };
 pub use tracker::StateTracker;
 
@@ -95,14 +98,22 @@ use crate::runtime::{locking::LockedSubstate, scope::PushCallFrame};
 
 pub trait RuntimeInterface: Send + Sync {
     fn next_entity_id(&self) -> Result<EntityId, RuntimeError>;
-    fn emit_event(&self, topic: String, payload: Metadata) -> Result<(), RuntimeError>;
+    fn emit_event(
+        &self,
+        topic: String,
+        payload: Metadata,
+        data: Option<tari_bor::Value>,
+        indexed_fields: Vec<String>,
+    ) -> Result<(), RuntimeError>;
 
-    fn emit_log(&self, level: LogLevel, message: String) -> Result<(), RuntimeError>;
+    fn emit_log(&self, level: LogLevel, message: String, fields: Metadata) -> Result<(), RuntimeError>;
 
     fn load_component(&self, address: &ComponentAddress) -> Result<ComponentHeader, RuntimeError>;
 
     fn lock_component(&self, address: &ComponentAddress, lock_flag: LockFlag) -> Result<LockedSubstate, RuntimeError>;
 
+    fn pay_sponsored_fee(&self, component_address: &ComponentAddress, amount: Amount) -> Result<(), RuntimeError>;
+
     fn get_substate(&self, lock: &LockedSubstate) -> Result<SubstateValue, RuntimeError>;
     fn component_invoke(
         &self,
@@ -147,6 +158,13 @@ pub trait RuntimeInterface: Send + Sync {
         args: EngineArgs,
     ) -> Result<InvokeResult, RuntimeError>;
 
+    fn key_value_store_invoke(
+        &self,
+        store_ref: KeyValueStoreRef,
+        action: KeyValueStoreAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError>;
+
     fn consensus_invoke(&self, action: ConsensusAction) -> Result<InvokeResult, RuntimeError>;
 
     fn generate_random_invoke(&self, action: GenerateRandomAction) -> Result<InvokeResult, RuntimeError>;
@@ -159,6 +177,13 @@ pub trait RuntimeInterface: Send + Sync {
 
     fn claim_validator_fees(&self, epoch: Epoch, validator_public_key: PublicKey) -> Result<(), RuntimeError>;
 
+    fn pay_rent(&self, substate_id: &SubstateId, epochs: u64) -> Result<(), RuntimeError>;
+
+    fn begin_instruction(&self, instruction: &Instruction) -> Result<(), RuntimeError>;
+    fn end_instruction(&self) -> Result<(), RuntimeError>;
+
+    fn collect_royalties(&self) -> Result<(), RuntimeError>;
+
     fn set_fee_checkpoint(&self) -> Result<(), RuntimeError>;
     fn reset_to_fee_checkpoint(&self) -> Result<(), RuntimeError>;
     fn finalize(&self) -> Result<FinalizeResult, RuntimeError>;
@@ -187,22 +212,94 @@ pub struct Runtime {
     interface: Arc<dyn RuntimeInterface>,
 }
 
+/// Arguments resolved from the workspace and literal values, ready to be passed to a template call
+pub(crate) struct ResolvedArgs {
+    pub values: Vec<tari_bor::Value>,
+    /// The substates referenced by the arguments, including those referenced by nested workspace arguments
+    pub scope: IndexedWellKnownTypes,
+}
+
 impl Runtime {
-    pub(crate) fn resolve_args(&self, args: Vec<Arg>) -> Result<Vec<tari_bor::Value>, RuntimeError> {
-        let mut resolved = Vec::with_capacity(args.len());
+    pub(crate) fn resolve_args(&self, args: Vec<Arg>) -> Result<ResolvedArgs, RuntimeError> {
+        let mut values = Vec::with_capacity(args.len());
+        let mut nested = Vec::new();
         for arg in args {
-            match arg {
-                Arg::Workspace(key) => {
-                    let value = self
-                        .interface
-                        .workspace_invoke(WorkspaceAction::Get, invoke_args![key].into())?;
-                    resolved.push(value.into_value()?);
-                },
-                Arg::Literal(v) => resolved.push(decode_exact(&v)?),
-            }
+            let mut value = self.resolve_arg(arg)?;
+            self.resolve_nested_args(&mut value, &mut nested)?;
+            values.push(value);
         }
-        Ok(resolved)
+        let scope = values
+            .iter()
+            .chain(&nested)
+            .map(IndexedWellKnownTypes::from_value)
+            .collect::<Result<_, _>>()?;
+        Ok(ResolvedArgs { values, scope })
+    }
+
+    fn resolve_arg(&self, arg: Arg) -> Result<tari_bor::Value, RuntimeError> {
+        match arg {
+            Arg::Workspace(key) => {
+                let value = self
+                    .interface
+                    .workspace_invoke(WorkspaceAction::Get, invoke_args![key].into())?;
+                Ok(value.into_value()?)
+            },
+            Arg::Literal(v) => Ok(decode_exact(&v)?),
+        }
+    }
+
+    /// Resolves workspace references in any list of args contained in the value (e.g. args that a template passes on
+    /// to another call). The resolved values are replaced with literals and added to `resolved` so that the referenced
+    /// substates are brought into scope at this call site.
+    fn resolve_nested_args(
+        &self,
+        value: &mut tari_bor::Value,
+        resolved: &mut Vec<tari_bor::Value>,
+    ) -> Result<(), RuntimeError> {
+        match value {
+            tari_bor::Value::Array(items) => {
+                if let Some(args) = as_nested_args(items) {
+                    for (item, arg) in items.iter_mut().zip(args) {
+                        if matches!(arg, Arg::Workspace(_)) {
+                            let value = self.resolve_arg(arg)?;
+                            *item = to_value(&Arg::Literal(encode(&value)?))?;
+                            resolved.push(value);
+                        }
+                    }
+                    return Ok(());
+                }
+                for item in items {
+                    self.resolve_nested_args(item, resolved)?;
+                }
+            },
+            tari_bor::Value::Map(entries) => {
+                for (_, value) in entries {
+                    self.resolve_nested_args(value, resolved)?;
+                }
+            },
+            tari_bor::Value::Tag(_, value) => {
+                self.resolve_nested_args(value, resolved)?;
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+}
+
+/// Returns the args if every item in the list is an encoded [Arg]
+fn as_nested_args(items: &[tari_bor::Value]) -> Option<Vec<Arg>> {
+    if items.is_empty() {
+        return None;
     }
+    items
+        .iter()
+        .map(|item| match item.as_map()?.as_slice() {
+            [(tari_bor::Value::Text(variant), _)] if variant == "Workspace" || variant == "Literal" => {
+                item.deserialized().ok()
+            },
+            _ => None,
+        })
+        .collect()
 }
 
 impl Runtime {
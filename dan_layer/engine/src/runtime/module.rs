@@ -1,7 +1,9 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use crate::runtime::StateTracker;
+use tari_engine_types::{instruction::Instruction, logs::LogEntry};
+
+use crate::runtime::{RuntimeError, StateTracker};
 
 pub trait RuntimeModule: Send + Sync {
     fn on_initialize(&self, _track: &StateTracker) -> Result<(), RuntimeModuleError> {
@@ -12,6 +14,22 @@ pub trait RuntimeModule: Send + Sync {
         Ok(())
     }
 
+    fn on_log(&self, _track: &StateTracker, _log: &LogEntry) -> Result<(), RuntimeModuleError> {
+        Ok(())
+    }
+
+    fn on_instruction_start(
+        &self,
+        _track: &StateTracker,
+        _instruction: &Instruction,
+    ) -> Result<(), RuntimeModuleError> {
+        Ok(())
+    }
+
+    fn on_instruction_end(&self, _track: &StateTracker) -> Result<(), RuntimeModuleError> {
+        Ok(())
+    }
+
     fn on_before_finalize(&self, _track: &StateTracker) -> Result<(), RuntimeModuleError> {
         Ok(())
     }
@@ -21,4 +39,16 @@ pub trait RuntimeModule: Send + Sync {
 pub enum RuntimeModuleError {
     #[error("BOR error: {0}")]
     Bor(#[from] tari_bor::BorError),
+    #[error("Transaction exceeded the maximum of {max} log entries")]
+    TooManyLogs { max: usize },
+    #[error("Transaction logs of {size} bytes exceed the maximum of {max} bytes")]
+    LogSizeExceeded { size: usize, max: usize },
+    #[error("Runtime error: {0}")]
+    Runtime(Box<RuntimeError>),
+}
+
+impl From<RuntimeError> for RuntimeModuleError {
+    fn from(err: RuntimeError) -> Self {
+        Self::Runtime(Box::new(err))
+    }
 }
@@ -27,6 +27,7 @@ pub struct CallScope {
     proof_scope: IndexSet<ProofId>,
     bucket_scope: IndexSet<BucketId>,
     auth_scope: AuthorizationScope,
+    is_component_destroyed: bool,
 }
 
 impl CallScope {
@@ -41,6 +42,7 @@ impl CallScope {
             proof_scope: IndexSet::new(),
             bucket_scope: IndexSet::new(),
             auth_scope: AuthorizationScope::new(vec![]),
+            is_component_destroyed: false,
         }
     }
 
@@ -114,6 +116,12 @@ impl CallScope {
         self.auth_scope_mut().add_proof(proof_id);
     }
 
+    /// Removes the proof from the scope, including every authorization that has been granted by it
+    pub fn remove_proof_from_scope(&mut self, proof_id: &ProofId) -> bool {
+        while self.auth_scope_mut().remove_proof(proof_id).is_some() {}
+        self.proof_scope.swap_remove(proof_id)
+    }
+
     pub fn remove_lock_from_scope(&mut self, lock_id: LockId) -> Result<(), RuntimeError> {
         if !self.lock_scope.swap_remove(&lock_id) {
             return Err(RuntimeError::LockError(LockError::LockIdNotFound { lock_id }));
@@ -125,6 +133,14 @@ impl CallScope {
         self.component_lock.as_ref()
     }
 
+    pub fn mark_component_destroyed(&mut self) {
+        self.is_component_destroyed = true;
+    }
+
+    pub fn is_component_destroyed(&self) -> bool {
+        self.is_component_destroyed
+    }
+
     pub fn owned_nodes(&self) -> &IndexSet<SubstateId> {
         &self.owned
     }
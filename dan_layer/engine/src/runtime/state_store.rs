@@ -25,6 +25,8 @@ use crate::{
 pub struct WorkingStateStore {
     // This must be ordered deterministically since we use this to create the substate diff
     new_substates: IndexMap<SubstateId, SubstateValue>,
+    // Substates that existed before the transaction and have been removed, with their last version
+    deleted_substates: IndexMap<SubstateId, u32>,
 
     loaded_substates: HashMap<SubstateId, SubstateValue>,
     locked_substates: LockedSubstates,
@@ -36,6 +38,7 @@ impl WorkingStateStore {
     pub fn new(state_store: ReadOnlyMemoryStateStore) -> Self {
         Self {
             new_substates: IndexMap::new(),
+            deleted_substates: IndexMap::new(),
             loaded_substates: HashMap::new(),
             locked_substates: Default::default(),
             state_store,
@@ -130,12 +133,41 @@ impl WorkingStateStore {
     }
 
     pub fn exists(&self, id: &SubstateId) -> Result<bool, RuntimeError> {
+        if self.deleted_substates.contains_key(id) {
+            return Ok(false);
+        }
         let exists = self.new_substates.contains_key(id) ||
             self.loaded_substates.contains_key(id) ||
             self.state_store.exists(id)?;
         Ok(exists)
     }
 
+    /// Removes an unlocked substate. If the substate existed before the transaction, it is recorded so that it is
+    /// downed in the substate diff.
+    pub fn delete(&mut self, id: &SubstateId) -> Result<(), RuntimeError> {
+        if !self.exists(id)? {
+            return Err(RuntimeError::SubstateNotFound { id: id.clone() });
+        }
+        if self.locked_substates.is_locked(id) {
+            return Err(LockError::SubstateIsLocked { address: id.clone() }.into());
+        }
+        self.new_substates.shift_remove(id);
+        self.loaded_substates.remove(id);
+        if let Some(existing) = self.state_store.get_state(id).optional()? {
+            self.deleted_substates.insert(id.clone(), existing.version());
+        }
+        Ok(())
+    }
+
+    /// Returns the ids of substates that existed before the transaction and have been loaded but not changed
+    pub fn loaded_substate_ids(&self) -> impl Iterator<Item = &SubstateId> + '_ {
+        self.loaded_substates.keys()
+    }
+
+    pub fn deleted_substates(&self) -> &IndexMap<SubstateId, u32> {
+        &self.deleted_substates
+    }
+
     pub fn insert(&mut self, id: SubstateId, value: SubstateValue) -> Result<(), RuntimeError> {
         if self.exists(&id)? {
             return Err(RuntimeError::DuplicateSubstate { address: id });
@@ -145,6 +177,9 @@ impl WorkingStateStore {
     }
 
     fn load(&mut self, id: &SubstateId) -> Result<(), RuntimeError> {
+        if self.deleted_substates.contains_key(id) {
+            return Err(RuntimeError::SubstateNotFound { id: id.clone() });
+        }
         if self.new_substates.contains_key(id) {
             return Ok(());
         }
@@ -169,6 +204,20 @@ impl WorkingStateStore {
         &self.new_substates
     }
 
+    /// Sets the rent of each substate created in this transaction that pays rent and has not been funded yet, returning
+    /// the ids of the funded substates
+    pub fn set_created_substates_rent(&mut self, paid_until: u64) -> Result<Vec<SubstateId>, RuntimeError> {
+        let mut funded = Vec::new();
+        for (id, substate) in &mut self.new_substates {
+            if !substate.pays_rent() || substate.rent_paid_until().is_some() || self.state_store.exists(id)? {
+                continue;
+            }
+            substate.set_rent_paid_until(paid_until);
+            funded.push(id.clone());
+        }
+        Ok(funded)
+    }
+
     pub fn new_vaults(&self) -> impl Iterator<Item = (VaultId, &Vault)> + '_ {
         self.new_substates
             .iter()
@@ -191,6 +240,19 @@ impl WorkingStateStore {
         })
     }
 
+    /// Get a component without loading or locking it
+    pub fn get_component(&self, address: &ComponentAddress) -> Result<&ComponentHeader, RuntimeError> {
+        let addr = SubstateId::Component(*address);
+        let substate = match self.get_ref(&addr) {
+            Ok(substate) => substate,
+            Err(_) => self.get_unmodified_substate(&addr)?.substate_value(),
+        };
+        substate.component().ok_or_else(|| RuntimeError::InvariantError {
+            function: "get_component",
+            details: format!("Substate at address {} is not a component", addr),
+        })
+    }
+
     pub(super) fn get_unmodified_substate(&self, address: &SubstateId) -> Result<&Substate, RuntimeError> {
         self.state_store
             .get_state(address)
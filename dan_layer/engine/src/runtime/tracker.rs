@@ -22,6 +22,7 @@
 
 use std::{
     convert::TryFrom,
+    mem,
     sync::{Arc, Mutex, RwLock},
 };
 
@@ -33,7 +34,7 @@ use tari_engine_types::{
     component::{ComponentBody, ComponentHeader},
     confidential::UnclaimedConfidentialOutput,
     events::Event,
-    fees::FeeSource,
+    fees::{FeeBreakdown, FeeSource},
     indexed_value::{IndexedValue, IndexedWellKnownTypes},
     lock::LockFlag,
     logs::LogEntry,
@@ -43,6 +44,7 @@ use tari_engine_types::{
 };
 use tari_template_lib::{
     auth::{ComponentAccessRules, OwnerRule},
+    component::FeeSponsor,
     crypto::RistrettoPublicKeyBytes,
     models::{AddressAllocation, Amount, BucketId, ComponentAddress, Metadata, UnclaimedConfidentialOutputAddress},
     Hash,
@@ -50,6 +52,7 @@ use tari_template_lib::{
 
 use crate::{
     runtime::{
+        fee_state::InstructionFeeState,
         locking::LockedSubstate,
         scope::{CallScope, PushCallFrame},
         working_state::WorkingState,
@@ -89,10 +92,19 @@ impl StateTracker {
         self.read_with(|state| state.get_current_epoch())
     }
 
+    pub fn get_current_timestamp(&self) -> Result<u64, RuntimeError> {
+        self.read_with(|state| state.get_current_timestamp())
+    }
+
+    pub fn get_random_beacon(&self) -> Result<Hash, RuntimeError> {
+        self.read_with(|state| state.get_random_beacon())
+    }
+
     pub fn get_pseudorandom_bytes(&self, length: usize) -> Result<Vec<u8>, RuntimeError> {
         self.read_with(|state| {
+            let seed = state.get_random_seed();
             let id_provider = state.id_provider()?;
-            let bytes = id_provider.get_random_bytes(length)?;
+            let bytes = id_provider.get_random_bytes(&seed, length)?;
             Ok(bytes)
         })
     }
@@ -117,6 +129,25 @@ impl StateTracker {
         self.read_with(|state| state.logs().len())
     }
 
+    /// Returns the number of log entries emitted by the transaction and their total size in bytes
+    pub fn num_logs_and_size(&self) -> (usize, usize) {
+        self.read_with(|state| (state.logs().len(), state.logs().iter().map(LogEntry::size).sum()))
+    }
+
+    pub fn fund_created_substates_rent(&self, epochs: u64) -> Result<(), RuntimeError> {
+        self.write_with(|state| state.fund_created_substates_rent(epochs))
+    }
+
+    pub fn rent_payments(&self) -> Vec<(SubstateId, u64)> {
+        self.read_with(|state| {
+            state
+                .rent_payments()
+                .iter()
+                .map(|(id, epochs)| (id.clone(), *epochs))
+                .collect()
+        })
+    }
+
     pub fn get_template_address(&self) -> Result<TemplateAddress, RuntimeError> {
         self.read_with(|state| state.current_template().map(|(a, _)| *a))
     }
@@ -155,6 +186,8 @@ impl StateTracker {
         owner_rule: OwnerRule,
         access_rules: ComponentAccessRules,
         address_allocation: Option<AddressAllocation<ComponentAddress>>,
+        non_reentrant: bool,
+        fee_sponsor: Option<FeeSponsor>,
     ) -> Result<ComponentAddress, RuntimeError> {
         self.write_with(|state| {
             let (template_address, module_name) =
@@ -173,10 +206,14 @@ impl StateTracker {
             let component = ComponentHeader {
                 template_address,
                 module_name: module_name.clone(),
+                previous_template_address: None,
                 owner_key,
                 access_rules,
                 owner_rule,
                 entity_id: component_address.entity_id(),
+                non_reentrant,
+                fee_sponsor,
+                rent_paid_until: None,
                 body: component,
             };
             let substate_id = SubstateId::Component(component_address);
@@ -268,7 +305,83 @@ impl StateTracker {
 
         self.write_with(|state| {
             debug!(target: LOG_TARGET, "Add fee: source: {:?}, amount: {}", source, amount);
-            state.fee_state_mut().fee_charges.insert(source, amount);
+            let fee_state = state.fee_state_mut();
+            fee_state.fee_charges.insert(source, amount);
+            if let Some(instruction) = fee_state.current_instruction.as_mut() {
+                instruction.fee_charges.insert(source, amount);
+            }
+        })
+    }
+
+    /// Starts recording the fee charges for an instruction
+    pub fn begin_instruction_fees(&self) {
+        self.write_with(|state| {
+            let instruction = InstructionFeeState {
+                fee_charges: FeeBreakdown::default(),
+                num_events_before: state.events().len(),
+                num_logs_before: state.logs().len(),
+            };
+            state.fee_state_mut().current_instruction = Some(instruction);
+        })
+    }
+
+    /// Stops recording the fee charges for the current instruction and adds them to the per-instruction breakdown
+    pub fn end_instruction_fees(&self) {
+        self.write_with(|state| {
+            let fee_state = state.fee_state_mut();
+            if let Some(instruction) = fee_state.current_instruction.take() {
+                fee_state.instruction_fee_charges.push(instruction.fee_charges);
+            }
+        })
+    }
+
+    /// Returns the fee charges of the current instruction so far
+    pub fn current_instruction_fee_charges(&self) -> FeeBreakdown {
+        self.read_with(|state| {
+            state
+                .fee_state()
+                .current_instruction
+                .as_ref()
+                .map(|instruction| instruction.fee_charges.clone())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns the fee charges for the transaction so far
+    pub fn fee_charges(&self) -> FeeBreakdown {
+        self.read_with(|state| state.fee_state().fee_charges.clone())
+    }
+
+    /// Returns the ids of substates that have been read but not changed, and the ids of substates that have been
+    /// created, changed or deleted in the transaction so far
+    pub fn substate_accesses(&self) -> (Vec<SubstateId>, Vec<SubstateId>) {
+        self.read_with(|state| {
+            let store = state.store();
+            let reads = store.loaded_substate_ids().cloned().collect();
+            let writes = store
+                .mutated_substates()
+                .keys()
+                .chain(store.deleted_substates().keys())
+                .cloned()
+                .collect();
+            (reads, writes)
+        })
+    }
+
+    /// Returns the number of events and logs emitted by the current instruction
+    pub fn num_instruction_events_and_logs(&self) -> (usize, usize) {
+        self.read_with(|state| {
+            state
+                .fee_state()
+                .current_instruction
+                .as_ref()
+                .map(|instruction| {
+                    (
+                        state.events().len().saturating_sub(instruction.num_events_before),
+                        state.logs().len().saturating_sub(instruction.num_logs_before),
+                    )
+                })
+                .unwrap_or_default()
         })
     }
 
@@ -292,7 +405,7 @@ impl StateTracker {
 
         let result = match result {
             Ok(substate_diff) => TransactionResult::Accept(substate_diff),
-            Err(err) => TransactionResult::Reject(RejectReason::ExecutionFailure(err.to_string())),
+            Err(err) => TransactionResult::Reject(RejectReason::ExecutionFailure(err.to_execution_failure())),
         };
 
         let finalized = FinalizeResult::new(
@@ -320,9 +433,11 @@ impl StateTracker {
         let mut checkpoint = self.fee_checkpoint.lock().unwrap();
         if let Some(checkpoint) = checkpoint.take() {
             self.write_with(|state| {
-                let fee_state = state.fee_state().clone();
+                let mut fee_state = state.fee_state().clone();
                 *state = checkpoint;
-                // Preserve fee state across resets
+                // Preserve fee charges across resets. Fee payments made after the checkpoint are discarded along with
+                // the vault withdrawals that funded them.
+                fee_state.fee_payments = mem::take(&mut state.fee_state_mut().fee_payments);
                 *state.fee_state_mut() = fee_state;
             });
             Ok(())
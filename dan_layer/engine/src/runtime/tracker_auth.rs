@@ -1,15 +1,19 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_template_lib::auth::{
-    AccessRule,
-    OwnerRule,
-    Ownership,
-    RequireRule,
-    ResourceAccessRules,
-    ResourceAuthAction,
-    RestrictedAccessRule,
-    RuleRequirement,
+use tari_template_lib::{
+    args::ComponentAction,
+    auth::{
+        AccessRule,
+        OwnerRule,
+        Ownership,
+        RequireRule,
+        ResourceAccessRules,
+        ResourceAuthAction,
+        RestrictedAccessRule,
+        RuleRequirement,
+    },
+    models::ComponentAddress,
 };
 
 use crate::runtime::{
@@ -32,7 +36,9 @@ impl<'a> Authorization<'a> {
     pub fn check_component_access_rules(&self, method: &str, locked: &LockedSubstate) -> Result<(), RuntimeError> {
         let component = self.state.get_component(locked)?;
         let scope = self.state.current_call_scope()?.auth_scope();
-        if check_ownership(self.state, scope, component.as_ownership())? {
+        // The current call frame belongs to the component being called, so the caller is in the previous frame
+        let caller = self.state.calling_component();
+        if check_ownership(self.state, scope, component.as_ownership(), caller)? {
             // Owner can call any component method
             return Ok(());
         }
@@ -70,6 +76,27 @@ impl<'a> Authorization<'a> {
         }
     }
 
+    /// Checks that the current component (or signer) may query the vault balances of the locked component. A
+    /// component may always query its own balances.
+    pub fn check_component_balance_query(&self, locked: &LockedSubstate) -> Result<(), RuntimeError> {
+        let component = self.state.get_component(locked)?;
+        let scope = self.state.current_call_scope()?.auth_scope();
+        let caller = self.state.current_component()?;
+        if caller.is_some_and(|caller| *locked.address() == caller) ||
+            check_ownership(self.state, scope, component.as_ownership(), caller)?
+        {
+            return Ok(());
+        }
+
+        if !check_access_rule(self.state, scope, component.access_rules().get_balance_query_rule())? {
+            return Err(RuntimeError::AccessDenied {
+                action_ident: ComponentAction::GetVaultBalance.into(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn check_resource_access_rules(
         &self,
         action: ResourceAuthAction,
@@ -80,7 +107,9 @@ impl<'a> Authorization<'a> {
 
         // Check ownership.
         // A resource is only recallable by explicit access rules
-        if !action.is_recall() && check_ownership(self.state, scope, resource_ownership)? {
+        if !action.is_recall() &&
+            check_ownership(self.state, scope, resource_ownership, self.state.current_component()?)?
+        {
             // Owner can invoke any resource method
             return Ok(());
         }
@@ -100,17 +129,32 @@ impl<'a> Authorization<'a> {
         action: A,
         ownership: Ownership<'_>,
     ) -> Result<(), RuntimeError> {
-        if !check_ownership(self.state, self.state.current_call_scope()?.auth_scope(), ownership)? {
+        let scope = self.state.current_call_scope()?.auth_scope();
+        if !check_ownership(self.state, scope, ownership, self.state.current_component()?)? {
             return Err(RuntimeError::AccessDeniedOwnerRequired { action: action.into() });
         }
         Ok(())
     }
 }
 
+/// The maximum number of owning components that are followed when checking ownership
+const MAX_OWNERSHIP_DEPTH: usize = 8;
+
 fn check_ownership(
     state: &WorkingState,
     scope: &AuthorizationScope,
     ownership: Ownership<'_>,
+    caller: Option<ComponentAddress>,
+) -> Result<bool, RuntimeError> {
+    check_ownership_with_depth(state, scope, ownership, caller, 0)
+}
+
+fn check_ownership_with_depth(
+    state: &WorkingState,
+    scope: &AuthorizationScope,
+    ownership: Ownership<'_>,
+    caller: Option<ComponentAddress>,
+    depth: usize,
 ) -> Result<bool, RuntimeError> {
     match ownership.owner_rule {
         OwnerRule::OwnedBySigner => {
@@ -130,6 +174,17 @@ fn check_ownership(
             let owner_proof = key.to_non_fungible_address();
             Ok(key == owner_key && scope.virtual_proofs().contains(&owner_proof))
         },
+        OwnerRule::OwnedByComponent(owner) => {
+            if caller == Some(*owner) {
+                return Ok(true);
+            }
+            // Otherwise, the owner of the owning component is also an owner
+            if depth >= MAX_OWNERSHIP_DEPTH {
+                return Ok(false);
+            }
+            let owner_component = state.store().get_component(owner)?;
+            check_ownership_with_depth(state, scope, owner_component.as_ownership(), caller, depth + 1)
+        },
     }
 }
 
@@ -168,6 +223,7 @@ fn check_restricted_access_rule(
             }
             Ok(true)
         },
+        RestrictedAccessRule::Not(rule) => Ok(!check_restricted_access_rule(state, scope, rule)?),
     }
 }
 
@@ -196,6 +252,26 @@ fn check_require_rule(
 
             Ok(true)
         },
+        RequireRule::Threshold {
+            threshold,
+            requirements,
+        } => {
+            let mut num_satisfied = 0u32;
+            for (i, requirement) in requirements.iter().enumerate() {
+                if num_satisfied >= *threshold {
+                    break;
+                }
+                // Duplicate requirements only count once
+                if requirements[..i].contains(requirement) {
+                    continue;
+                }
+                if check_requirement(state, scope, requirement)? {
+                    num_satisfied += 1;
+                }
+            }
+
+            Ok(num_satisfied >= *threshold)
+        },
     }
 }
 
@@ -217,7 +293,11 @@ fn check_requirement(
             for proof_id in scope.proofs() {
                 let proof = state.get_proof(*proof_id)?;
 
-                if resx == proof.resource_address() {
+                if proof
+                    .locked_resources()
+                    .iter()
+                    .any(|locked| resx == locked.resource_address())
+                {
                     return Ok(true);
                 }
             }
@@ -231,9 +311,10 @@ fn check_requirement(
             for proof_id in scope.proofs() {
                 let proof = state.get_proof(*proof_id)?;
 
-                if addr.resource_address() == proof.resource_address() &&
-                    proof.non_fungible_token_ids().contains(addr.id())
-                {
+                if proof.locked_resources().iter().any(|locked| {
+                    addr.resource_address() == locked.resource_address() &&
+                        locked.non_fungible_token_ids().contains(addr.id())
+                }) {
                     return Ok(true);
                 }
             }
@@ -247,5 +328,9 @@ fn check_requirement(
             let (current, _) = state.current_template()?;
             Ok(current == address)
         },
+        RuleRequirement::BeforeEpoch(epoch) => Ok(state.get_current_epoch()?.as_u64() < *epoch),
+        RuleRequirement::AfterEpoch(epoch) => Ok(state.get_current_epoch()?.as_u64() >= *epoch),
+        RuleRequirement::BeforeTimestamp(timestamp) => Ok(state.get_current_timestamp()? < *timestamp),
+        RuleRequirement::AfterTimestamp(timestamp) => Ok(state.get_current_timestamp()? >= *timestamp),
     }
 }
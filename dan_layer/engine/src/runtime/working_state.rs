@@ -15,11 +15,13 @@ use tari_dan_common_types::{optional::Optional, Epoch};
 use tari_engine_types::{
     bucket::Bucket,
     component::ComponentHeader,
+    confidential::ConfidentialProofBatch,
     events::Event,
     fee_claim::{FeeClaim, FeeClaimAddress},
     fees::FeeReceipt,
     id_provider::{IdProvider, ObjectIds},
     indexed_value::{IndexedValue, IndexedWellKnownTypes},
+    key_value_store::KeyValueEntry,
     lock::LockFlag,
     logs::LogEntry,
     non_fungible::NonFungibleContainer,
@@ -35,6 +37,7 @@ use tari_engine_types::{
 use tari_template_lib::{
     args::{MintArg, ResourceDiscriminator},
     constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+    crypto::RistrettoPublicKeyBytes,
     models::{
         AddressAllocation,
         Amount,
@@ -42,10 +45,12 @@ use tari_template_lib::{
         ComponentAddress,
         NonFungibleAddress,
         ProofId,
+        ResourceAddress,
         UnclaimedConfidentialOutputAddress,
         VaultId,
     },
     prelude::{AuthHookCaller, PUBLIC_IDENTITY_RESOURCE_ADDRESS},
+    resource::{ResourceType, RoyaltyConfig},
     Hash,
 };
 
@@ -77,6 +82,7 @@ pub(super) struct WorkingState {
     address_allocation_id: u32,
     proofs: HashMap<ProofId, Proof>,
     object_ids: ObjectIds,
+    deferred_confidential_proofs: ConfidentialProofBatch,
 
     store: WorkingStateStore,
 
@@ -89,6 +95,10 @@ pub(super) struct WorkingState {
     initial_call_scope: CallScope,
 
     fee_state: FeeState,
+    /// Deposits that royalties may be owed for or collected from, see [WorkingState::collect_royalties]
+    royalty_deposits: Vec<RoyaltyDeposit>,
+    /// Number of epochs of rent funded for each substate in this transaction
+    rent_payments: IndexMap<SubstateId, u64>,
 }
 
 impl WorkingState {
@@ -104,6 +114,7 @@ impl WorkingState {
             logs: Vec::new(),
             buckets: HashMap::new(),
             proofs: HashMap::new(),
+            deferred_confidential_proofs: ConfidentialProofBatch::new(),
             address_allocation_id: 0,
             address_allocations: HashMap::new(),
 
@@ -118,6 +129,8 @@ impl WorkingState {
             initial_call_scope,
             fee_state: FeeState::new(),
             object_ids: ObjectIds::new(1000),
+            royalty_deposits: Vec::new(),
+            rent_payments: IndexMap::new(),
         }
     }
 
@@ -151,7 +164,24 @@ impl WorkingState {
 
     pub fn lock_substate(&mut self, addr: &SubstateId, lock_flag: LockFlag) -> Result<LockedSubstate, RuntimeError> {
         let lock_id = self.store.try_lock(addr, lock_flag)?;
-        Ok(LockedSubstate::new(addr.clone(), lock_id, lock_flag))
+        let locked = LockedSubstate::new(addr.clone(), lock_id, lock_flag);
+
+        // A substate whose rent has expired is inactive until its rent is topped up. The current epoch is only
+        // required (and may only be available) if the substate pays rent.
+        let (_, substate) = self.store.get_locked_substate(lock_id)?;
+        if let Some(paid_until) = substate.rent_paid_until() {
+            let current_epoch = self.get_current_epoch()?.as_u64();
+            if current_epoch > paid_until {
+                self.unlock_substate(locked)?;
+                return Err(RuntimeError::SubstateRentExpired {
+                    id: addr.clone(),
+                    paid_until,
+                    current_epoch,
+                });
+            }
+        }
+
+        Ok(locked)
     }
 
     pub fn unlock_substate(&mut self, lock: LockedSubstate) -> Result<(), RuntimeError> {
@@ -242,6 +272,30 @@ impl WorkingState {
         Ok(non_fungible)
     }
 
+    pub fn get_key_value_entry(&self, locked: &LockedSubstate) -> Result<&KeyValueEntry, RuntimeError> {
+        let (address, value) = self.store.get_locked_substate(locked.lock_id())?;
+        let entry = value
+            .as_key_value_entry()
+            .ok_or_else(|| RuntimeError::LockSubstateMismatch {
+                lock_id: locked.lock_id(),
+                address,
+                expected_type: "KeyValueEntry",
+            })?;
+        Ok(entry)
+    }
+
+    pub fn get_key_value_entry_mut(&mut self, locked: &LockedSubstate) -> Result<&mut KeyValueEntry, RuntimeError> {
+        let (address, value) = self.store.get_locked_substate_mut(locked.lock_id())?;
+        let entry = value
+            .as_key_value_entry_mut()
+            .ok_or_else(|| RuntimeError::LockSubstateMismatch {
+                lock_id: locked.lock_id(),
+                address,
+                expected_type: "KeyValueEntry",
+            })?;
+        Ok(entry)
+    }
+
     pub fn claim_confidential_output(&mut self, addr: &UnclaimedConfidentialOutputAddress) -> Result<(), RuntimeError> {
         if self.claimed_confidential_outputs.contains(addr) {
             return Err(RuntimeError::ConfidentialOutputAlreadyClaimed { address: *addr });
@@ -309,6 +363,43 @@ impl WorkingState {
         Ok(Epoch(*epoch))
     }
 
+    pub fn get_current_timestamp(&self) -> Result<u64, RuntimeError> {
+        let address = VirtualSubstateId::CurrentTimestamp;
+        let current_timestamp =
+            self.virtual_substates
+                .get(&address)
+                .ok_or_else(|| RuntimeError::VirtualSubstateNotFound {
+                    address: address.clone(),
+                })?;
+        let VirtualSubstate::CurrentTimestamp(timestamp) = current_timestamp else {
+            return Err(RuntimeError::VirtualSubstateNotFound { address });
+        };
+        Ok(*timestamp)
+    }
+
+    pub fn get_random_beacon(&self) -> Result<Hash, RuntimeError> {
+        let address = VirtualSubstateId::RandomBeacon;
+        let random_beacon =
+            self.virtual_substates
+                .get(&address)
+                .ok_or_else(|| RuntimeError::VirtualSubstateNotFound {
+                    address: address.clone(),
+                })?;
+        let VirtualSubstate::RandomBeacon(beacon) = random_beacon else {
+            return Err(RuntimeError::VirtualSubstateNotFound { address });
+        };
+        Ok(*beacon)
+    }
+
+    /// Returns the consensus-provided random seed for this transaction. If no seed was provided (e.g. for dry runs),
+    /// the zero hash is used, in which case random values are derived from the transaction hash alone.
+    pub fn get_random_seed(&self) -> Hash {
+        match self.virtual_substates.get(&VirtualSubstateId::RandomSeed) {
+            Some(VirtualSubstate::RandomSeed(seed)) => *seed,
+            _ => Hash::default(),
+        }
+    }
+
     pub(super) fn validate_finalized(&self) -> Result<(), RuntimeError> {
         if !self.buckets.is_empty() {
             return Err(TransactionCommitError::DanglingBuckets {
@@ -324,6 +415,13 @@ impl WorkingState {
             .into());
         }
 
+        if !self.deferred_confidential_proofs.is_empty() {
+            return Err(RuntimeError::InvariantError {
+                function: "validate_finalized",
+                details: "Deferred confidential proofs have not been verified".to_string(),
+            });
+        }
+
         if !self.address_allocations.is_empty() {
             return Err(TransactionCommitError::DanglingAddressAllocations {
                 count: self.address_allocations.len(),
@@ -359,12 +457,34 @@ impl WorkingState {
         Ok(())
     }
 
+    /// Defers verification of confidential range and balance proofs so that all of the proofs in the transaction are
+    /// verified in a single batch
+    pub fn defer_confidential_proofs(&mut self, batch: ConfidentialProofBatch) {
+        self.deferred_confidential_proofs.append(batch);
+    }
+
+    pub(super) fn verify_deferred_confidential_proofs(&mut self) -> Result<(), RuntimeError> {
+        let batch = mem::take(&mut self.deferred_confidential_proofs);
+        if !batch.is_empty() {
+            debug!(target: LOG_TARGET, "Verifying {} deferred confidential proof(s)", batch.len());
+            batch.verify()?;
+        }
+        Ok(())
+    }
+
     pub fn get_proof(&self, proof_id: ProofId) -> Result<&Proof, RuntimeError> {
         self.proofs
             .get(&proof_id)
             .ok_or(RuntimeError::ProofNotFound { proof_id })
     }
 
+    /// Returns the locked resource of a proof that is not a composite proof
+    pub fn get_single_proof(&self, proof_id: ProofId, op: &'static str) -> Result<&LockedResource, RuntimeError> {
+        self.get_proof(proof_id)?
+            .as_single()
+            .ok_or(RuntimeError::InvalidOpCompositeProof { op, proof_id })
+    }
+
     pub fn proof_exists(&self, proof_id: ProofId) -> bool {
         self.proofs.contains_key(&proof_id)
     }
@@ -447,20 +567,51 @@ impl WorkingState {
             .ok_or(RuntimeError::ProofNotFound { proof_id })?;
 
         // Unlock funds
-        match *proof.container() {
-            ContainerRef::Bucket(bucket_id) => {
-                self.buckets
-                    .get_mut(&bucket_id)
-                    .ok_or(RuntimeError::BucketNotFound { bucket_id })?
-                    .unlock(proof)?;
-            },
-            ContainerRef::Vault(vault_id) => {
-                let vault_lock = self.lock_substate(&SubstateId::Vault(vault_id), LockFlag::Write)?;
-                self.get_vault_mut(&vault_lock)?.unlock(proof)?;
-                self.unlock_substate(vault_lock)?;
-            },
+        for locked in proof.into_locked_resources() {
+            match *locked.container() {
+                ContainerRef::Bucket(bucket_id) => {
+                    self.buckets
+                        .get_mut(&bucket_id)
+                        .ok_or(RuntimeError::BucketNotFound { bucket_id })?
+                        .unlock(locked)?;
+                },
+                ContainerRef::Vault(vault_id) => {
+                    let vault_lock = self.lock_substate(&SubstateId::Vault(vault_id), LockFlag::Write)?;
+                    self.get_vault_mut(&vault_lock)?.unlock(locked)?;
+                    self.unlock_substate(vault_lock)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the given proofs with a single composite proof of all of their resources. The given proofs must be in
+    /// scope and are consumed, so they are removed from every call scope and the workspace.
+    pub fn combine_proofs(&mut self, proof_id: ProofId, proof_ids: &[ProofId]) -> Result<(), RuntimeError> {
+        let mut proofs = Vec::with_capacity(proof_ids.len());
+        for id in proof_ids {
+            if !self.current_call_scope()?.is_proof_in_scope(*id) {
+                return Err(RuntimeError::ProofNotFound { proof_id: *id });
+            }
+            let proof = self
+                .proofs
+                .remove(id)
+                .ok_or(RuntimeError::ProofNotFound { proof_id: *id })?;
+            proofs.push(proof);
+
+            for frame in &mut self.call_frames {
+                frame.scope_mut().remove_proof_from_scope(id);
+            }
+            self.initial_call_scope.remove_proof_from_scope(id);
+            self.workspace.remove_proof(id);
         }
 
+        debug!(target: LOG_TARGET, "New composite proof {} from {} proof(s)", proof_id, proofs.len());
+        if self.proofs.insert(proof_id, Proof::combine(proofs)).is_some() {
+            return Err(RuntimeError::DuplicateProof { proof_id });
+        }
+        self.current_call_scope_mut()?.add_proof_to_scope(proof_id);
         Ok(())
     }
 
@@ -539,7 +690,11 @@ impl WorkingState {
                     target: LOG_TARGET,
                     "Minting confidential tokens on resource: {}", resource_address
                 );
-                ResourceContainer::mint_confidential(resource_address, *proof, resource.view_key())?
+                let mut batch = ConfidentialProofBatch::new();
+                let container =
+                    ResourceContainer::mint_confidential(resource_address, *proof, resource.view_key(), &mut batch)?;
+                self.defer_confidential_proofs(batch);
+                container
             },
         };
 
@@ -554,7 +709,22 @@ impl WorkingState {
                 }
                 .into());
             }
-            resource_mut.increase_total_supply(resource_container.amount());
+            let amount = resource_container.amount();
+            if let Some(max_supply) = resource_mut.max_supply() {
+                let total_supply = resource_mut.total_supply();
+                if total_supply
+                    .checked_add(amount)
+                    .map_or(true, |new_total| new_total > max_supply)
+                {
+                    return Err(RuntimeError::MaxSupplyExceeded {
+                        resource_address,
+                        amount,
+                        total_supply,
+                        max_supply,
+                    });
+                }
+            }
+            resource_mut.increase_total_supply(amount);
         }
 
         Ok(resource_container)
@@ -622,6 +792,13 @@ impl WorkingState {
                     });
                 }
 
+                if tokens.is_empty() {
+                    return Err(RuntimeError::InvalidArgument {
+                        argument: "resource",
+                        reason: "At least one non-fungible id must be recalled".to_string(),
+                    });
+                }
+
                 vault_mut.withdraw_non_fungibles(&tokens)?
             },
             ResourceDiscriminator::Confidential {
@@ -724,6 +901,228 @@ impl WorkingState {
             .ok_or(RuntimeError::AddressAllocationNotFound { id })
     }
 
+    /// Checks that the royalty vault of a new resource exists and holds a fungible resource that royalties can be
+    /// paid in
+    pub fn validate_royalty_vault(&mut self, royalty: &RoyaltyConfig) -> Result<(), RuntimeError> {
+        let vault_substate_id = SubstateId::Vault(royalty.vault_id);
+        if !self.substate_exists(&vault_substate_id)? {
+            return Err(RuntimeError::InvalidArgument {
+                argument: "CreateResourceArg",
+                reason: format!("Royalty vault {} does not exist", royalty.vault_id),
+            });
+        }
+        let vault_lock = self.lock_substate(&vault_substate_id, LockFlag::Read)?;
+        let resource_type = self.get_vault(&vault_lock)?.resource_type();
+        self.unlock_substate(vault_lock)?;
+        if !resource_type.is_fungible() {
+            return Err(RuntimeError::InvalidArgument {
+                argument: "CreateResourceArg",
+                reason: format!("Royalty vault {} must hold a fungible resource", royalty.vault_id),
+            });
+        }
+        Ok(())
+    }
+
+    /// Records a deposit into the vault `vault_id` that royalties may be owed for or collected from. Fungible deposits
+    /// are payments that royalties are collected from and deposits of non-fungibles that have a royalty are transfers
+    /// that royalties are owed for.
+    pub fn record_royalty_deposit(
+        &mut self,
+        vault_id: VaultId,
+        resource_lock: &LockedSubstate,
+        bucket: &Bucket,
+    ) -> Result<(), RuntimeError> {
+        let payment = match bucket.resource_type() {
+            ResourceType::Fungible if bucket.amount().is_positive() => Some(bucket.amount()),
+            ResourceType::NonFungible if self.get_resource(resource_lock)?.royalty().is_some() => None,
+            _ => return Ok(()),
+        };
+
+        // The current component can only deposit into a vault that it owns, unless the vault was created in the
+        // current scope. The owners of new vaults are resolved once the components that hold them are known.
+        let component_address = match self.current_call_scope()?.get_current_component_lock() {
+            Some(component_lock) => {
+                let component = self.get_component(component_lock)?;
+                if component.contains_substate(&SubstateId::Vault(vault_id))? {
+                    component_lock.address().as_component_address()
+                } else {
+                    None
+                }
+            },
+            None => None,
+        };
+
+        self.royalty_deposits.push(RoyaltyDeposit {
+            vault_id,
+            component_address,
+            resource_address: *bucket.resource_address(),
+            payment,
+        });
+        Ok(())
+    }
+
+    /// Extends the rent of a substate by `epochs` epochs, starting from the current epoch if the rent has expired.
+    /// Returns the last epoch that the rent is paid for.
+    pub fn top_up_rent(&mut self, substate_id: &SubstateId, epochs: u64) -> Result<u64, RuntimeError> {
+        let current_epoch = self.get_current_epoch()?.as_u64();
+        // The substate is locked directly because an expired substate may only be locked to top up its rent
+        let lock_id = self.store.try_lock(substate_id, LockFlag::Write)?;
+        let (_, substate) = self.store.get_locked_substate(lock_id)?;
+        let Some(paid_until) = substate.rent_paid_until() else {
+            self.store.try_unlock(lock_id)?;
+            return Err(RuntimeError::InvalidArgument {
+                argument: "substate_id",
+                reason: format!("Substate {} is not subject to rent", substate_id),
+            });
+        };
+        let paid_until = paid_until.max(current_epoch).saturating_add(epochs);
+        self.store.mutate_locked_substate_with(lock_id, |_, substate| {
+            substate.set_rent_paid_until(paid_until);
+            Ok(Some(()))
+        })?;
+        self.store.try_unlock(lock_id)?;
+
+        *self.rent_payments.entry(substate_id.clone()).or_default() += epochs;
+        Ok(paid_until)
+    }
+
+    /// Funds `epochs` epochs of rent for each substate created in this transaction that pays rent
+    pub fn fund_created_substates_rent(&mut self, epochs: u64) -> Result<(), RuntimeError> {
+        let paid_until = self.get_current_epoch()?.as_u64().saturating_add(epochs);
+        for substate_id in self.store.set_created_substates_rent(paid_until)? {
+            *self.rent_payments.entry(substate_id).or_default() += epochs;
+        }
+        Ok(())
+    }
+
+    pub fn rent_payments(&self) -> &IndexMap<SubstateId, u64> {
+        &self.rent_payments
+    }
+
+    /// Collects the royalties owed for non-fungibles that were deposited into components that are not owned by the
+    /// resource owner. The royalty is taken from each payment in the currency of the royalty vault that was deposited
+    /// in this transaction into a component other than the receiving component, regardless of whether the payment
+    /// was made before or after the non-fungibles were withdrawn. Components that are owned by the resource owner
+    /// neither owe nor pay royalties. A payment is only charged once, for the first royalty that it is subject to.
+    pub fn collect_royalties(&mut self) -> Result<(), RuntimeError> {
+        let deposits = mem::take(&mut self.royalty_deposits);
+        if deposits.iter().all(|deposit| deposit.is_payment()) {
+            return Ok(());
+        }
+
+        let new_vault_owners = self.get_new_vault_owners()?;
+        let owner_of = |deposit: &RoyaltyDeposit| {
+            deposit
+                .component_address
+                .or_else(|| new_vault_owners.get(&deposit.vault_id).copied())
+        };
+
+        let mut royalty_resources = Vec::new();
+        for deposit in deposits.iter().filter(|deposit| !deposit.is_payment()) {
+            if !royalty_resources.contains(&deposit.resource_address) {
+                royalty_resources.push(deposit.resource_address);
+            }
+        }
+
+        let mut is_charged = vec![false; deposits.len()];
+        for resource_address in royalty_resources {
+            let resource_lock = self.lock_substate(&SubstateId::Resource(resource_address), LockFlag::Read)?;
+            let resource = self.get_resource(&resource_lock)?;
+            let maybe_royalty = resource.royalty().copied();
+            let resource_owner_key = resource.owner_key().copied();
+            self.unlock_substate(resource_lock)?;
+            let Some(royalty) = maybe_royalty else {
+                continue;
+            };
+
+            let mut receivers = Vec::new();
+            for deposit in deposits
+                .iter()
+                .filter(|deposit| !deposit.is_payment() && deposit.resource_address == resource_address)
+            {
+                let component_address = owner_of(deposit);
+                if !self.is_component_owned_by(component_address, resource_owner_key.as_ref())? {
+                    receivers.push((deposit.vault_id, component_address));
+                }
+            }
+            if receivers.is_empty() {
+                continue;
+            }
+
+            let royalty_vault_lock = self.lock_substate(&SubstateId::Vault(royalty.vault_id), LockFlag::Write)?;
+            let currency = *self.get_vault(&royalty_vault_lock)?.resource_address();
+            for (i, deposit) in deposits.iter().enumerate() {
+                let Some(amount) = deposit.payment else {
+                    continue;
+                };
+                if is_charged[i] || deposit.resource_address != currency || deposit.vault_id == royalty.vault_id {
+                    continue;
+                }
+                let component_address = owner_of(deposit);
+                // Deposits into the receiving components (e.g. change) are not payments for the non-fungibles
+                let is_receiver = receivers.iter().any(|(vault_id, receiver)| {
+                    *vault_id == deposit.vault_id || (receiver.is_some() && *receiver == component_address)
+                });
+                if is_receiver || self.is_component_owned_by(component_address, resource_owner_key.as_ref())? {
+                    continue;
+                }
+
+                is_charged[i] = true;
+                let royalty_amount = Amount((i128::from(amount.value()) * i128::from(royalty.percentage) / 100) as i64);
+                if !royalty_amount.is_positive() {
+                    continue;
+                }
+                debug!(
+                    target: LOG_TARGET,
+                    "Collecting royalty of {} {} from vault {} for resource {}",
+                    royalty_amount,
+                    currency,
+                    deposit.vault_id,
+                    resource_address
+                );
+                let payment_vault_lock = self.lock_substate(&SubstateId::Vault(deposit.vault_id), LockFlag::Write)?;
+                let royalty_container = self.get_vault_mut(&payment_vault_lock)?.withdraw(royalty_amount)?;
+                self.unlock_substate(payment_vault_lock)?;
+                self.get_vault_mut(&royalty_vault_lock)?
+                    .resource_container_mut()
+                    .deposit(royalty_container)?;
+            }
+            self.unlock_substate(royalty_vault_lock)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the component that holds each vault in the components that were created or changed in this transaction
+    fn get_new_vault_owners(&self) -> Result<HashMap<VaultId, ComponentAddress>, RuntimeError> {
+        let mut owners = HashMap::new();
+        for (id, substate) in self.store.mutated_substates() {
+            let (Some(component_address), Some(component)) = (id.as_component_address(), substate.component()) else {
+                continue;
+            };
+            let indexed = IndexedWellKnownTypes::from_value(component.state())?;
+            owners.extend(
+                indexed
+                    .vault_ids()
+                    .iter()
+                    .map(|vault_id| (*vault_id, component_address)),
+            );
+        }
+        Ok(owners)
+    }
+
+    fn is_component_owned_by(
+        &mut self,
+        component_address: Option<ComponentAddress>,
+        owner_key: Option<&RistrettoPublicKeyBytes>,
+    ) -> Result<bool, RuntimeError> {
+        let (Some(component_address), Some(owner_key)) = (component_address, owner_key) else {
+            return Ok(false);
+        };
+        let component = self.store.load_component(&component_address)?;
+        Ok(component.owner_key.as_ref() == Some(owner_key))
+    }
+
     pub fn pay_fee(&mut self, resource: ResourceContainer, return_vault: VaultId) -> Result<(), RuntimeError> {
         self.fee_state.fee_payments.push((resource, return_vault));
         Ok(())
@@ -792,6 +1191,14 @@ impl WorkingState {
                     });
                 }
             }
+            // Key-value stores can never be removed because their entries would be orphaned
+            for existing_store in prev_state.key_value_store_ids() {
+                if !next_state.key_value_store_ids().contains(existing_store) {
+                    return Err(RuntimeError::OrphanedSubstate {
+                        address: (*existing_store).into(),
+                    });
+                }
+            }
         }
 
         // Check that no vaults are duplicated
@@ -896,6 +1303,7 @@ impl WorkingState {
                 total_fee_payment,
                 total_fees_paid: fee_resource.amount(),
                 cost_breakdown: mem::take(&mut self.fee_state.fee_charges),
+                instruction_cost_breakdown: mem::take(&mut self.fee_state.instruction_fee_charges),
             },
         })
     }
@@ -939,6 +1347,10 @@ impl WorkingState {
         self.object_ids.next_bucket_id()
     }
 
+    pub fn new_proof_id(&mut self) -> ProofId {
+        self.object_ids.next_proof_id()
+    }
+
     /// Returns the component that is currently in scope (if any)
     pub fn current_component(&self) -> Result<Option<ComponentAddress>, RuntimeError> {
         let frame = self.call_frames.last().ok_or(RuntimeError::NoActiveCallFrame)?;
@@ -948,6 +1360,27 @@ impl WorkingState {
             .and_then(|lock| lock.address().as_component_address()))
     }
 
+    /// Returns the component that called into the current call frame, if any
+    pub fn calling_component(&self) -> Option<ComponentAddress> {
+        let num_frames = self.call_frames.len();
+        let frame = self.call_frames.get(num_frames.checked_sub(2)?)?;
+        frame
+            .scope()
+            .get_current_component_lock()
+            .and_then(|lock| lock.address().as_component_address())
+    }
+
+    /// Returns true if the component is locked by any of the active call frames
+    pub fn is_component_on_call_stack(&self, address: &ComponentAddress) -> bool {
+        self.call_frames.iter().any(|frame| {
+            frame
+                .scope()
+                .get_current_component_lock()
+                .and_then(|lock| lock.address().as_component_address()) ==
+                Some(*address)
+        })
+    }
+
     pub fn get_auth_caller(&self) -> Result<AuthHookCaller, RuntimeError> {
         let frame = self.call_frames.last().ok_or(RuntimeError::NoActiveCallFrame)?;
         let (template, _) = frame.current_template();
@@ -988,6 +1421,17 @@ impl WorkingState {
         // Unlock the component
         if let Some(component_lock) = scope.get_current_component_lock() {
             self.unlock_substate(component_lock.clone())?;
+            if scope.is_component_destroyed() {
+                let address =
+                    component_lock
+                        .address()
+                        .as_component_address()
+                        .ok_or_else(|| RuntimeError::InvariantError {
+                            function: "pop_frame",
+                            details: format!("Component lock address {} is not a component", component_lock.address()),
+                        })?;
+                self.destroy_component(address)?;
+            }
         }
 
         if !scope.lock_scope().is_empty() {
@@ -1009,6 +1453,37 @@ impl WorkingState {
         Ok(())
     }
 
+    /// Removes the component and its vaults from the state. The component may not own any key-value stores and all of
+    /// its vaults must be empty.
+    fn destroy_component(&mut self, address: ComponentAddress) -> Result<(), RuntimeError> {
+        let component = self.store.load_component(&address)?;
+        let indexed = IndexedWellKnownTypes::from_value(component.state())?;
+
+        if let Some(store_id) = indexed.key_value_store_ids().first() {
+            return Err(RuntimeError::CannotDestroyComponentWithKeyValueStore {
+                address,
+                store_id: *store_id,
+            });
+        }
+
+        for vault_id in indexed.vault_ids() {
+            let vault_lock = self.lock_substate(&SubstateId::Vault(*vault_id), LockFlag::Read)?;
+            let is_empty = self.get_vault(&vault_lock)?.is_empty();
+            self.unlock_substate(vault_lock)?;
+            if !is_empty {
+                return Err(RuntimeError::CannotDestroyComponentWithNonEmptyVault {
+                    address,
+                    vault_id: *vault_id,
+                });
+            }
+            self.store.delete(&SubstateId::Vault(*vault_id))?;
+        }
+
+        self.store.delete(&SubstateId::Component(address))?;
+        debug!(target: LOG_TARGET, "Destroyed component {}", address);
+        Ok(())
+    }
+
     pub fn base_call_scope(&self) -> &CallScope {
         &self.initial_call_scope
     }
@@ -1141,6 +1616,10 @@ impl WorkingState {
             substate_diff.up(address, new_substate);
         }
 
+        for (address, version) in self.store.deleted_substates() {
+            substate_diff.down(address.clone(), *version);
+        }
+
         // Special case: unclaimed confidential outputs are downed without being upped if claimed
         for claimed in &self.claimed_confidential_outputs {
             substate_diff.down(SubstateId::UnclaimedConfidentialOutput(*claimed), 0);
@@ -1191,3 +1670,21 @@ impl WorkingState {
         Ok(())
     }
 }
+
+/// A deposit that royalties may be owed for or collected from. Royalties are collected once all instructions have
+/// run, so that they do not depend on the order in which non-fungibles and payments are exchanged.
+#[derive(Debug, Clone)]
+struct RoyaltyDeposit {
+    vault_id: VaultId,
+    /// The component that owns the vault, if it is known when the deposit is made
+    component_address: Option<ComponentAddress>,
+    resource_address: ResourceAddress,
+    /// The amount of a fungible payment, or None for a deposit of non-fungibles that have a royalty
+    payment: Option<Amount>,
+}
+
+impl RoyaltyDeposit {
+    fn is_payment(&self) -> bool {
+        self.payment.is_some()
+    }
+}
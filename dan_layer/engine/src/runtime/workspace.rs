@@ -57,6 +57,10 @@ impl Workspace {
         self.variables.insert(key, value);
     }
 
+    pub fn remove_proof(&mut self, proof_id: &ProofId) -> bool {
+        self.proofs.remove(proof_id)
+    }
+
     pub fn drain_all_proofs(&mut self) -> HashSet<ProofId> {
         mem::take(&mut self.proofs)
     }
@@ -22,12 +22,14 @@
 
 use wasmer::InstantiationError;
 
-use crate::wasm::WasmExecutionError;
+use crate::wasm::{WasmBackendKind, WasmExecutionError, WasmValidationError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TemplateLoaderError {
     #[error(transparent)]
     WasmModuleError(#[from] WasmExecutionError),
+    #[error("Template module failed validation: {0}")]
+    ValidationError(#[from] WasmValidationError),
     #[error(transparent)]
     CompileError(#[from] wasmer::CompileError),
     #[error(transparent)]
@@ -36,6 +38,8 @@ pub enum TemplateLoaderError {
     ExportError(#[from] wasmer::ExportError),
     #[error("Runtime error: {0}")]
     RuntimeError(#[from] wasmer::RuntimeError),
+    #[error("The {backend} WASM backend is not enabled in this build")]
+    BackendNotEnabled { backend: WasmBackendKind },
 }
 
 impl From<wasmer::InstantiationError> for TemplateLoaderError {
@@ -0,0 +1,5 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod trace_module;
+pub use trace_module::TraceModule;
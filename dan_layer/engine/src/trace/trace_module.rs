@@ -0,0 +1,113 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{collections::HashSet, mem, sync::Mutex};
+
+use tari_engine_types::{
+    execution_trace::{ExecutionTrace, ExecutionTraceEntry},
+    fees::{FeeBreakdown, FeeSource},
+    instruction::Instruction,
+    substate::SubstateId,
+};
+
+use crate::runtime::{RuntimeModule, RuntimeModuleError, StateTracker};
+
+/// Records an [ExecutionTrace] for a transaction. This module must be added after the fee module so that the fees
+/// charged by the fee module are included in the trace.
+#[derive(Debug, Default)]
+pub struct TraceModule {
+    state: Mutex<TraceState>,
+}
+
+#[derive(Debug, Default)]
+struct TraceState {
+    trace: ExecutionTrace,
+    num_instructions: u32,
+    seen_reads: HashSet<SubstateId>,
+    seen_writes: HashSet<SubstateId>,
+}
+
+impl TraceModule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the trace recorded so far, leaving an empty trace
+    pub fn take_trace(&self) -> ExecutionTrace {
+        mem::take(&mut self.state.lock().unwrap().trace)
+    }
+
+    fn with_state<F: FnOnce(&mut TraceState)>(&self, f: F) {
+        f(&mut self.state.lock().unwrap())
+    }
+}
+
+impl TraceState {
+    fn record_substate_accesses(&mut self, track: &StateTracker) {
+        let (mut reads, writes) = track.substate_accesses();
+        // Loaded substates are not ordered
+        reads.sort();
+        for substate_id in reads {
+            if self.seen_reads.insert(substate_id.clone()) {
+                self.trace.push(ExecutionTraceEntry::SubstateRead { substate_id });
+            }
+        }
+        for substate_id in writes {
+            if self.seen_writes.insert(substate_id.clone()) {
+                self.trace.push(ExecutionTraceEntry::SubstateWrite { substate_id });
+            }
+        }
+    }
+
+    fn record_fee_charges<F: Fn(&FeeSource) -> bool>(&mut self, charges: &FeeBreakdown, filter: F) {
+        for (source, amount) in charges.iter().filter(|(source, _)| filter(source)) {
+            self.trace.push(ExecutionTraceEntry::FeeCharge {
+                source: *source,
+                amount: *amount,
+            });
+        }
+    }
+}
+
+impl RuntimeModule for TraceModule {
+    fn on_runtime_call(&self, _track: &StateTracker, call: &'static str) -> Result<(), RuntimeModuleError> {
+        self.with_state(|state| {
+            state
+                .trace
+                .push(ExecutionTraceEntry::EngineCall { call: call.to_string() })
+        });
+        Ok(())
+    }
+
+    fn on_instruction_start(&self, _track: &StateTracker, instruction: &Instruction) -> Result<(), RuntimeModuleError> {
+        self.with_state(|state| {
+            state.trace.push(ExecutionTraceEntry::Instruction {
+                index: state.num_instructions,
+                instruction: instruction.to_string(),
+            });
+            state.num_instructions += 1;
+        });
+        Ok(())
+    }
+
+    fn on_instruction_end(&self, track: &StateTracker) -> Result<(), RuntimeModuleError> {
+        let charges = track.current_instruction_fee_charges();
+        self.with_state(|state| {
+            state.record_substate_accesses(track);
+            state.record_fee_charges(&charges, |_| true);
+        });
+        Ok(())
+    }
+
+    fn on_before_finalize(&self, track: &StateTracker) -> Result<(), RuntimeModuleError> {
+        let charges = track.fee_charges();
+        self.with_state(|state| {
+            state.record_substate_accesses(track);
+            // Instruction fees have already been recorded, only transaction-wide fees are left
+            state.record_fee_charges(&charges, |source| {
+                matches!(source, FeeSource::Initial | FeeSource::Storage | FeeSource::Rent)
+            });
+        });
+        Ok(())
+    }
+}
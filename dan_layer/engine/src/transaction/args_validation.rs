@@ -0,0 +1,68 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_bor::Value;
+use tari_template_abi::{FunctionDef, Type};
+
+use crate::transaction::TransactionError;
+
+/// Checks that the resolved arguments of a call match the arity and types declared in the function's ABI. Template
+/// specific types (`Type::Other`) are not known to the engine and are left to the template to decode.
+pub fn validate_args(function_def: &FunctionDef, args: &[Value]) -> Result<(), TransactionError> {
+    let invalid_args = |details: String| TransactionError::InvalidArguments {
+        function: function_def.name.clone(),
+        details,
+    };
+
+    if args.len() != function_def.arguments.len() {
+        return Err(invalid_args(format!(
+            "expected {} argument(s) but got {}",
+            function_def.arguments.len(),
+            args.len()
+        )));
+    }
+
+    for (arg_def, arg) in function_def.arguments.iter().zip(args) {
+        if !is_value_of_type(arg, &arg_def.arg_type) {
+            return Err(invalid_args(format!(
+                "argument '{}' is not a valid {}",
+                arg_def.name, arg_def.arg_type
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_value_of_type(value: &Value, ty: &Type) -> bool {
+    match ty {
+        Type::Unit => value.is_null(),
+        Type::Bool => value.is_bool(),
+        Type::I8 => is_integer_in_range(value, i8::MIN.into(), i8::MAX.into()),
+        Type::I16 => is_integer_in_range(value, i16::MIN.into(), i16::MAX.into()),
+        Type::I32 => is_integer_in_range(value, i32::MIN.into(), i32::MAX.into()),
+        Type::I64 => is_integer_in_range(value, i64::MIN.into(), i64::MAX.into()),
+        Type::U8 => is_integer_in_range(value, 0, u8::MAX.into()),
+        Type::U16 => is_integer_in_range(value, 0, u16::MAX.into()),
+        Type::U32 => is_integer_in_range(value, 0, u32::MAX.into()),
+        Type::U64 => is_integer_in_range(value, 0, u64::MAX.into()),
+        // Integers that do not fit in 64 bits are encoded as tagged big numbers
+        Type::I128 | Type::U128 => value.is_integer() || value.is_tag(),
+        Type::String => value.is_text(),
+        Type::Vec(inner) => match value {
+            Value::Array(items) => items.iter().all(|item| is_value_of_type(item, inner)),
+            Value::Bytes(_) => **inner == Type::U8,
+            _ => false,
+        },
+        // The ABI describes both tuples and vecs of tuples as a tuple, so only the outer array can be checked
+        Type::Tuple(_) => value.is_array(),
+        Type::Other { .. } => true,
+    }
+}
+
+fn is_integer_in_range(value: &Value, min: i128, max: i128) -> bool {
+    value
+        .as_integer()
+        .map(i128::from)
+        .map_or(false, |v| (min..=max).contains(&v))
+}
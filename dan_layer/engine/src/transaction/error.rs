@@ -20,7 +20,11 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_engine_types::indexed_value::IndexedValueError;
+use tari_engine_types::{
+    commit_result::RejectReason,
+    execution_failure::{ExecutionError, ExecutionFailure},
+    indexed_value::IndexedValueError,
+};
 use tari_template_lib::models::TemplateAddress;
 
 use crate::{runtime::RuntimeError, wasm::WasmExecutionError};
@@ -43,6 +47,44 @@ pub enum TransactionError {
     ValueVisitorError(#[from] IndexedValueError),
     #[error("Function {name} not found")]
     FunctionNotFound { name: String },
+    #[error("Invalid arguments for function {function}: {details}")]
+    InvalidArguments { function: String, details: String },
     #[error("Invariant error: {details}")]
     InvariantError { details: String },
+    #[error("{error}")]
+    InstructionFailed { index: u32, error: Box<TransactionError> },
+}
+
+impl TransactionError {
+    pub fn into_reject_reason(self) -> RejectReason {
+        match self {
+            Self::InvalidArguments { function, details } => RejectReason::InvalidArguments { function, details },
+            Self::InstructionFailed { index, error } => match error.into_reject_reason() {
+                RejectReason::ExecutionFailure(failure) => {
+                    RejectReason::ExecutionFailure(failure.with_instruction_index(index))
+                },
+                reason => reason,
+            },
+            err => RejectReason::ExecutionFailure(err.to_execution_failure()),
+        }
+    }
+
+    pub fn to_execution_failure(&self) -> ExecutionFailure {
+        let error = match self {
+            Self::WasmExecutionError(err) => err.to_execution_error(),
+            Self::RuntimeError(err) => err.to_execution_error(),
+            Self::TemplateNotFound { address } => ExecutionError::TemplateNotFound {
+                template_address: *address,
+            },
+            Self::FailedToLoadTemplate { .. } => ExecutionError::WasmError {
+                details: self.to_string(),
+            },
+            Self::FunctionNotFound { name } => ExecutionError::FunctionNotFound { name: name.clone() },
+            Self::InstructionFailed { index, error } => {
+                return error.to_execution_failure().with_instruction_index(*index)
+            },
+            _ => ExecutionError::Other,
+        };
+        ExecutionFailure::new(error, self.to_string())
+    }
 }
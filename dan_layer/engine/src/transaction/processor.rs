@@ -46,7 +46,7 @@ use tari_template_lib::{
     auth::OwnerRule,
     crypto::RistrettoPublicKeyBytes,
     invoke_args,
-    models::{Bucket, ComponentAddress, NonFungibleAddress},
+    models::{Amount, Bucket, ComponentAddress, Metadata, NonFungibleAddress},
     prelude::{AccessRules, TemplateAddress},
 };
 use tari_transaction::Transaction;
@@ -57,6 +57,7 @@ use crate::{
         scope::{CallScope, PushCallFrame},
         AuthParams,
         AuthorizationScope,
+        ResolvedArgs,
         Runtime,
         RuntimeInterfaceImpl,
         RuntimeModule,
@@ -64,9 +65,7 @@ use crate::{
     },
     state_store::memory::ReadOnlyMemoryStateStore,
     template::LoadedTemplate,
-    traits::Invokable,
-    transaction::TransactionError,
-    wasm::WasmProcess,
+    transaction::{args_validation::validate_args, TransactionError},
 };
 
 const LOG_TARGET: &str = "tari::dan::engine::instruction_processor";
@@ -138,6 +137,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             tracker,
             template_provider.clone(),
             transaction_signer_public_key,
+            transaction.reserved_output_addresses().collect(),
             entity_id_provider,
             modules,
             MAX_CALL_DEPTH,
@@ -148,36 +148,47 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         let transaction_hash = transaction.hash();
 
         let (fee_instructions, instructions) = transaction.into_instructions();
+        // Instructions are indexed in execution order, fee instructions first
+        let num_fee_instructions = fee_instructions.len() as u32;
 
-        let fee_exec_results = Self::process_instructions(&template_provider, &runtime, fee_instructions);
+        let fee_exec_results = Self::process_instructions(&template_provider, &runtime, 0, fee_instructions);
 
         let fee_exec_result = match fee_exec_results {
             Ok(execution_results) => {
                 // Checkpoint the tracker state after the fee instructions have been executed in case of transaction
                 // failure.
                 if let Err(err) = runtime.interface().set_fee_checkpoint() {
-                    let mut finalize =
-                        FinalizeResult::new_rejected(transaction_hash, RejectReason::ExecutionFailure(err.to_string()));
+                    let mut finalize = FinalizeResult::new_rejected(
+                        transaction_hash,
+                        RejectReason::ExecutionFailure(err.to_execution_failure()),
+                    );
                     finalize.execution_results = execution_results;
                     return Ok(ExecuteResult {
                         finalize,
                         execution_time: timer.elapsed(),
+                        execution_trace: None,
                     });
                 }
                 execution_results
             },
             Err(err) => {
                 return Ok(ExecuteResult {
-                    finalize: FinalizeResult::new_rejected(
-                        transaction_hash,
-                        RejectReason::ExecutionFailure(err.to_string()),
-                    ),
+                    finalize: FinalizeResult::new_rejected(transaction_hash, err.into_reject_reason()),
                     execution_time: timer.elapsed(),
+                    execution_trace: None,
                 });
             },
         };
 
-        let instruction_result = Self::process_instructions(&*template_provider, &runtime, instructions);
+        let instruction_result =
+            Self::process_instructions(&*template_provider, &runtime, num_fee_instructions, instructions).and_then(
+                |execution_results| {
+                    // Royalties are collected once all instructions have run so that they do not depend on the order
+                    // in which non-fungibles and payments are exchanged
+                    runtime.interface().collect_royalties()?;
+                    Ok(execution_results)
+                },
+            );
 
         match instruction_result {
             Ok(execution_results) => {
@@ -190,6 +201,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                 Ok(ExecuteResult {
                     finalize,
                     execution_time: timer.elapsed(),
+                    execution_trace: None,
                 })
             },
             // This can happen e.g if you have dangling buckets after running the instructions
@@ -206,11 +218,12 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                         .accept()
                         .cloned()
                         .expect("The fee transaction should be there"),
-                    RejectReason::ExecutionFailure(err.to_string()),
+                    err.into_reject_reason(),
                 );
                 Ok(ExecuteResult {
                     finalize,
                     execution_time: timer.elapsed(),
+                    execution_trace: None,
                 })
             },
         }
@@ -219,11 +232,19 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
     fn process_instructions(
         template_provider: &TTemplateProvider,
         runtime: &Runtime,
+        start_index: u32,
         instructions: Vec<Instruction>,
     ) -> Result<Vec<InstructionResult>, TransactionError> {
-        let result: Result<_, _> = instructions
-            .into_iter()
-            .map(|instruction| Self::process_instruction(template_provider, runtime, instruction))
+        let result: Result<_, _> = (start_index..)
+            .zip(instructions)
+            .map(|(index, instruction)| {
+                Self::run_instruction(template_provider, runtime, instruction).map_err(|error| {
+                    TransactionError::InstructionFailed {
+                        index,
+                        error: Box::new(error),
+                    }
+                })
+            })
             .collect();
 
         // check that the finalized state is valid
@@ -234,6 +255,17 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         result
     }
 
+    fn run_instruction(
+        template_provider: &TTemplateProvider,
+        runtime: &Runtime,
+        instruction: Instruction,
+    ) -> Result<InstructionResult, TransactionError> {
+        runtime.interface().begin_instruction(&instruction)?;
+        let result = Self::process_instruction(template_provider, runtime, instruction);
+        runtime.interface().end_instruction()?;
+        result
+    }
+
     fn process_instruction(
         template_provider: &TTemplateProvider,
         runtime: &Runtime,
@@ -263,7 +295,10 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                 component_address,
                 method,
                 args,
-            } => Self::call_method(template_provider, runtime, &component_address, &method, args),
+            } => {
+                Self::pay_sponsored_fee(template_provider, runtime, &component_address, &method)?;
+                Self::call_method(template_provider, runtime, &component_address, &method, args)
+            },
             // Basically names an output on the workspace so that you can refer to it as an
             // Arg::Variable
             Instruction::PutLastInstructionOutputOnWorkspace { key } => {
@@ -275,7 +310,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                 Ok(InstructionResult::empty())
             },
             Instruction::EmitLog { level, message } => {
-                runtime.interface().emit_log(level, message)?;
+                runtime.interface().emit_log(level, message, Metadata::new())?;
                 Ok(InstructionResult::empty())
             },
             Instruction::ClaimBurn { claim } => {
@@ -303,6 +338,16 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
                 )?;
                 Ok(InstructionResult::empty())
             },
+            Instruction::CreateProofFromBucket { key } => {
+                runtime
+                    .interface()
+                    .workspace_invoke(WorkspaceAction::CreateProofFromBucket, invoke_args![key].into())?;
+                Ok(InstructionResult::empty())
+            },
+            Instruction::PayRent { substate_id, epochs } => {
+                runtime.interface().pay_rent(&substate_id, epochs)?;
+                Ok(InstructionResult::empty())
+            },
         }
     }
 
@@ -376,11 +421,10 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             args.push(arg![Literal(none)]);
         }
 
-        let args = runtime.resolve_args(args)?;
-        let arg_scope = args
-            .iter()
-            .map(IndexedWellKnownTypes::from_value)
-            .collect::<Result<_, _>>()?;
+        let ResolvedArgs {
+            values: args,
+            scope: arg_scope,
+        } = runtime.resolve_args(args)?;
 
         runtime.interface().push_call_frame(PushCallFrame::Static {
             template_address: ACCOUNT_TEMPLATE_ADDRESS,
@@ -421,11 +465,11 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             }
         })?;
 
-        let args = runtime.resolve_args(args)?;
-        let arg_scope = args
-            .iter()
-            .map(IndexedWellKnownTypes::from_value)
-            .collect::<Result<_, _>>()?;
+        let ResolvedArgs {
+            values: args,
+            scope: arg_scope,
+        } = runtime.resolve_args(args)?;
+        validate_args(&function_def, &args)?;
 
         runtime.interface().push_call_frame(PushCallFrame::Static {
             template_address: *template_address,
@@ -443,6 +487,33 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         Ok(result)
     }
 
+    /// If the component sponsors fees, asks its fee policy how much to pay for calling `method` and pays that amount
+    /// from the component's fee vault.
+    fn pay_sponsored_fee(
+        template_provider: &TTemplateProvider,
+        runtime: &Runtime,
+        component_address: &ComponentAddress,
+        method: &str,
+    ) -> Result<(), TransactionError> {
+        let component = runtime.interface().load_component(component_address)?;
+        let Some(fee_sponsor) = component.fee_sponsor else {
+            return Ok(());
+        };
+
+        let result = Self::call_method(
+            template_provider,
+            runtime,
+            component_address,
+            &fee_sponsor.policy_method,
+            args![method],
+        )?;
+        let amount = result.decode::<Amount>()?;
+        if !amount.is_zero() {
+            runtime.interface().pay_sponsored_fee(component_address, amount)?;
+        }
+        Ok(())
+    }
+
     pub fn call_method(
         template_provider: &TTemplateProvider,
         runtime: &Runtime,
@@ -477,11 +548,15 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
 
         let component_lock = runtime.interface().lock_component(component_address, lock_flag)?;
 
-        let args = runtime.resolve_args(args)?;
-        let arg_scope = args
-            .iter()
-            .map(IndexedWellKnownTypes::from_value)
-            .collect::<Result<_, _>>()?;
+        let ResolvedArgs {
+            values: args,
+            scope: arg_scope,
+        } = runtime.resolve_args(args)?;
+
+        let mut final_args = Vec::with_capacity(args.len() + 1);
+        final_args.push(to_value(component_address)?);
+        final_args.extend(args);
+        validate_args(&function_def, &final_args)?;
 
         let component_scope = IndexedWellKnownTypes::from_value(component.state())?;
 
@@ -499,10 +574,6 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
             .interface()
             .check_component_access_rules(method, &component_lock)?;
 
-        let mut final_args = Vec::with_capacity(args.len() + 1);
-        final_args.push(to_value(component_address)?);
-        final_args.extend(args);
-
         let result = Self::invoke_template(template, template_provider, runtime.clone(), function_def, final_args)?;
 
         runtime.interface().validate_return_value(&result.indexed)?;
@@ -519,11 +590,7 @@ impl<TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + 'static> T
         args: Vec<tari_bor::Value>,
     ) -> Result<InstructionResult, TransactionError> {
         let result = match module {
-            LoadedTemplate::Wasm(loaded) => {
-                let mut store = loaded.create_store();
-                let mut process = WasmProcess::init(&mut store, loaded, runtime)?;
-                process.invoke(&mut store, &function_def, args)?
-            },
+            LoadedTemplate::Wasm(loaded) => loaded.invoke(runtime, &function_def, args)?,
             LoadedTemplate::Flow(flow_factory) => {
                 flow_factory.run_new_instance(
                     Arc::new(template_provider.clone()),
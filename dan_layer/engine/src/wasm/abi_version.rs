@@ -0,0 +1,72 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_abi::{TemplateDef, ABI_VERSION};
+
+use crate::wasm::WasmExecutionError;
+
+/// The ABI version of templates that were compiled before the ABI version was embedded in templates
+pub const LEGACY_ABI_VERSION: u32 = 1;
+
+/// The template ABI versions that this engine can load and the changes that each version introduced. When a new
+/// version is added, templates compiled with the previous versions continue to be loaded as long as their entries
+/// remain in this table.
+const ABI_COMPATIBILITY_TABLE: &[(u32, &str)] = &[(1, "TemplateDef::V1 definition with CBOR encoded engine calls")];
+
+/// Checks that the engine supports the ABI version of a template
+pub fn check_abi_version(template_abi_version: u32) -> Result<(), WasmExecutionError> {
+    if ABI_COMPATIBILITY_TABLE
+        .iter()
+        .any(|(version, _)| *version == template_abi_version)
+    {
+        return Ok(());
+    }
+
+    if template_abi_version > ABI_VERSION {
+        return Err(WasmExecutionError::AbiVersionTooNew {
+            template_abi_version,
+            engine_abi_version: ABI_VERSION,
+        });
+    }
+
+    Err(WasmExecutionError::AbiVersionUnsupported {
+        template_abi_version,
+        supported_versions: ABI_COMPATIBILITY_TABLE
+            .iter()
+            .map(|(version, _)| version.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    })
+}
+
+/// Decodes the template definition of a template using the encoding of its ABI version
+pub fn decode_template_def(template_abi_version: u32, data: &[u8]) -> Result<TemplateDef, WasmExecutionError> {
+    check_abi_version(template_abi_version)?;
+    match template_abi_version {
+        1 => tari_bor::decode(data).map_err(WasmExecutionError::AbiDecodeError),
+        _ => unreachable!(
+            "ABI version {} is in the compatibility table but has no decoder",
+            template_abi_version
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_accepts_supported_versions() {
+        check_abi_version(LEGACY_ABI_VERSION).unwrap();
+        check_abi_version(ABI_VERSION).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_unsupported_versions() {
+        let err = check_abi_version(ABI_VERSION + 1).unwrap_err();
+        assert!(matches!(err, WasmExecutionError::AbiVersionTooNew { .. }));
+
+        let err = check_abi_version(0).unwrap_err();
+        assert!(matches!(err, WasmExecutionError::AbiVersionUnsupported { .. }));
+    }
+}
@@ -0,0 +1,82 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use tari_engine_types::instruction_result::InstructionResult;
+use tari_template_abi::FunctionDef;
+
+use crate::{
+    runtime::Runtime,
+    template::{LoadedTemplate, TemplateLoaderError},
+    wasm::{
+        module::{WasmerBackend, MEMORY_PAGE_LIMIT},
+        validation::validate_deterministic_module,
+        LoadedWasmTemplate,
+        WasmExecutionError,
+    },
+};
+
+/// The maximum amount of fuel that a single template invocation may consume
+pub const MAX_EXECUTION_FUEL: u64 = 100_000_000;
+
+/// A WASM execution backend that compiles template code into modules that the engine can invoke.
+///
+/// Backends receive code that has already passed validation. They must limit template memory to
+/// `MEMORY_PAGE_LIMIT` pages and trap once an invocation has consumed `MAX_EXECUTION_FUEL`.
+pub trait WasmBackend {
+    /// Compiles the template code and loads the template definition that it exports
+    fn compile_template(&self, code: &[u8]) -> Result<LoadedWasmTemplate, TemplateLoaderError>;
+}
+
+/// A template module compiled by a [`WasmBackend`]
+pub trait WasmBackendModule: Debug + Send + Sync {
+    /// Instantiates the module and invokes a function of the template. Each invocation uses a new instance.
+    fn invoke(
+        &self,
+        template: &LoadedWasmTemplate,
+        runtime: Runtime,
+        func_def: &FunctionDef,
+        args: Vec<tari_bor::Value>,
+    ) -> Result<InstructionResult, WasmExecutionError>;
+}
+
+/// Selects the backend used to compile and execute templates.
+///
+/// The backends charge different amounts of fuel for the same instructions, so a template invocation that comes close
+/// to the fuel limit may succeed on one backend and fail on the other. Every validator in a network should use the
+/// same backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WasmBackendKind {
+    /// The wasmer backend with the cranelift compiler
+    #[default]
+    Wasmer,
+    /// The wasmtime backend, available when the engine is built with the `wasmtime` feature
+    Wasmtime,
+}
+
+impl WasmBackendKind {
+    /// Validates the template code and compiles it using this backend
+    pub fn load_template(self, code: &[u8]) -> Result<LoadedTemplate, TemplateLoaderError> {
+        validate_deterministic_module(code, MEMORY_PAGE_LIMIT)?;
+        let template = match self {
+            WasmBackendKind::Wasmer => WasmerBackend.compile_template(code)?,
+            #[cfg(feature = "wasmtime")]
+            WasmBackendKind::Wasmtime => super::wasmtime_backend::WasmtimeBackend.compile_template(code)?,
+            #[cfg(not(feature = "wasmtime"))]
+            WasmBackendKind::Wasmtime => return Err(TemplateLoaderError::BackendNotEnabled { backend: self }),
+        };
+        Ok(template.into())
+    }
+}
+
+impl Display for WasmBackendKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WasmBackendKind::Wasmer => write!(f, "wasmer"),
+            WasmBackendKind::Wasmtime => write!(f, "wasmtime"),
+        }
+    }
+}
@@ -26,7 +26,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use tari_template_abi::{TemplateDef, ABI_TEMPLATE_DEF_GLOBAL_NAME};
+use tari_template_abi::{TemplateDef, ABI_TEMPLATE_DEF_GLOBAL_NAME, ABI_VERSION_GLOBAL_NAME};
 use wasmer::{
     AsStoreMut,
     AsStoreRef,
@@ -41,7 +41,7 @@ use wasmer::{
 
 use crate::{
     runtime::RuntimeError,
-    wasm::{mem_writer::MemWriter, WasmExecutionError},
+    wasm::{abi_version, WasmExecutionError},
 };
 
 #[derive(Clone)]
@@ -89,10 +89,33 @@ impl<T: Send + 'static> WasmEnv<T> {
         self.last_engine_error.lock().unwrap().take()
     }
 
+    /// Returns the ABI version exported by the template, or the legacy version if the template does not export one
+    pub(super) fn load_abi_version<S: AsStoreMut>(
+        &self,
+        store: &mut S,
+        instance: &Instance,
+    ) -> Result<u32, WasmExecutionError> {
+        let global = match instance.exports.get_global(ABI_VERSION_GLOBAL_NAME) {
+            Ok(global) => global,
+            Err(ExportError::Missing(_)) => return Ok(abi_version::LEGACY_ABI_VERSION),
+            Err(err) => return Err(err.into()),
+        };
+        let ptr = global
+            .get(store)
+            .i32()
+            .ok_or(WasmExecutionError::ExportError(ExportError::IncompatibleType))? as u32;
+
+        let view = self.get_memory()?.view(store);
+        let mut buf = [0u8; 4];
+        view.read(u64::from(ptr), &mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
     pub(super) fn load_abi<S: AsStoreMut>(
         &self,
         store: &mut S,
         instance: &Instance,
+        abi_version: u32,
     ) -> Result<TemplateDef, WasmExecutionError> {
         let ptr = instance
             .exports
@@ -103,17 +126,18 @@ impl<T: Send + 'static> WasmEnv<T> {
 
         // Load ABI from memory
         let data = self.read_memory_with_embedded_len(store, ptr)?;
-        let decoded = tari_bor::decode(&data).map_err(WasmExecutionError::AbiDecodeError)?;
-        Ok(decoded)
+        abi_version::decode_template_def(abi_version, &data)
     }
 
-    pub(super) fn memory_writer<'a, S: AsStoreMut>(
+    pub(super) fn write_to_memory<S: AsStoreRef>(
         &self,
-        store: &'a mut S,
+        store: &mut S,
         ptr: WasmPtr<u8>,
-    ) -> Result<MemWriter<'a>, WasmExecutionError> {
+        data: &[u8],
+    ) -> Result<(), WasmExecutionError> {
         let view = self.get_memory()?.view(store);
-        Ok(MemWriter::new(ptr, view))
+        view.write(u64::from(ptr.offset()), data)?;
+        Ok(())
     }
 
     pub(super) fn read_memory_with_embedded_len<S: AsStoreRef>(
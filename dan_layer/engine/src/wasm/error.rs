@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use tari_bor::BorError;
-use tari_engine_types::indexed_value::IndexedValueError;
+use tari_engine_types::{execution_failure::ExecutionError, indexed_value::IndexedValueError};
 use wasmer::{ExportError, InstantiationError, MemoryAccessError};
 
 use crate::runtime::RuntimeError;
@@ -45,6 +45,16 @@ pub enum WasmExecutionError {
     MaxMemorySizeExceeded,
     #[error("Failed to decode ABI: {0:?}")]
     AbiDecodeError(BorError),
+    #[error("Template ABI version {template_abi_version} is newer than the engine ABI version {engine_abi_version}")]
+    AbiVersionTooNew {
+        template_abi_version: u32,
+        engine_abi_version: u32,
+    },
+    #[error("Template ABI version {template_abi_version} is not supported (supported: {supported_versions})")]
+    AbiVersionUnsupported {
+        template_abi_version: u32,
+        supported_versions: String,
+    },
     #[error("Unexpected ABI function {name}")]
     UnexpectedAbiFunction { name: String },
     #[error("Encoding error: {0}")]
@@ -52,8 +62,12 @@ pub enum WasmExecutionError {
     #[error("Panic! {message}")]
     Panic {
         message: String,
-        runtime_error: wasmer::RuntimeError,
+        runtime_error: anyhow::Error,
     },
+    #[error("Execution ran out of fuel")]
+    OutOfFuel,
+    #[error("WASM backend error: {0}")]
+    BackendError(#[from] anyhow::Error),
     #[error("Value visitor error: {0}")]
     ValueVisitorError(#[from] IndexedValueError),
     #[error("Template version parsing error: {0}")]
@@ -64,6 +78,21 @@ pub enum WasmExecutionError {
         template_version: String,
     },
 }
+
+impl WasmExecutionError {
+    pub fn to_execution_error(&self) -> ExecutionError {
+        match self {
+            Self::RuntimeError(err) => err.to_execution_error(),
+            Self::Panic { message, .. } => ExecutionError::Panic {
+                message: message.clone(),
+            },
+            err => ExecutionError::WasmError {
+                details: err.to_string(),
+            },
+        }
+    }
+}
+
 impl From<wasmer::InstantiationError> for WasmExecutionError {
     fn from(value: InstantiationError) -> Self {
         Self::InstantiationError(Box::new(value))
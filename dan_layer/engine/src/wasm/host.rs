@@ -0,0 +1,207 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Handling of calls between the engine and templates that is shared by all WASM backends. Backends are responsible
+//! for moving data in and out of template memory, everything else goes through these functions so that templates
+//! behave identically regardless of the backend that executes them.
+
+use serde::{de::DeserializeOwned, Serialize};
+use tari_bor::{decode_exact, encode, encode_with_len_to_writer, encoded_len};
+use tari_engine_types::{indexed_value::IndexedValue, instruction_result::InstructionResult};
+use tari_template_abi::{CallInfo, EngineOp, FunctionDef};
+use tari_template_lib::{
+    args::{
+        BucketInvokeArg,
+        BuiltinTemplateInvokeArg,
+        CallInvokeArg,
+        CallerContextInvokeArg,
+        ComponentInvokeArg,
+        ConsensusInvokeArg,
+        EmitEventArg,
+        EmitLogArg,
+        GenerateRandomInvokeArg,
+        KeyValueStoreInvokeArg,
+        LogLevel,
+        NonFungibleInvokeArg,
+        ProofInvokeArg,
+        ResourceInvokeArg,
+        VaultInvokeArg,
+        WorkspaceInvokeArg,
+    },
+    models::Metadata,
+    AbiContext,
+};
+
+use super::version::are_versions_compatible;
+use crate::{
+    runtime::{Runtime, RuntimeError},
+    wasm::{LoadedWasmTemplate, WasmExecutionError},
+};
+
+const LOG_TARGET: &str = "tari::dan::engine::wasm::host";
+pub const ENGINE_TARI_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The encoded response to an engine call
+pub(super) struct EngineCallResponse {
+    encoded_with_len: Vec<u8>,
+}
+
+impl EngineCallResponse {
+    /// The length to allocate in template memory for the response. `tari_alloc` reserves space for the length prefix,
+    /// so it is not included.
+    pub fn alloc_len(&self) -> Result<u32, WasmExecutionError> {
+        u32::try_from(self.encoded_with_len.len() - 4).map_err(|_| WasmExecutionError::MemoryAllocationTooLarge)
+    }
+
+    /// The encoded response prefixed with its length, as the template expects to read it
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.encoded_with_len
+    }
+}
+
+/// Invokes the engine operation requested by a template
+pub(super) fn invoke_engine_op(
+    runtime: &mut Runtime,
+    op: EngineOp,
+    arg: &[u8],
+) -> Result<EngineCallResponse, WasmExecutionError> {
+    log::debug!(target: LOG_TARGET, "Engine call: {:?}", op);
+
+    match op {
+        EngineOp::EmitLog => handle(runtime, arg, |env, arg: EmitLogArg| {
+            env.interface().emit_log(arg.level, arg.message, arg.fields)
+        }),
+        EngineOp::ComponentInvoke => handle(runtime, arg, |env, arg: ComponentInvokeArg| {
+            env.interface()
+                .component_invoke(arg.component_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::ResourceInvoke => handle(runtime, arg, |env, arg: ResourceInvokeArg| {
+            env.interface()
+                .resource_invoke(arg.resource_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::VaultInvoke => handle(runtime, arg, |env, arg: VaultInvokeArg| {
+            env.interface().vault_invoke(arg.vault_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::BucketInvoke => handle(runtime, arg, |env, arg: BucketInvokeArg| {
+            env.interface()
+                .bucket_invoke(arg.bucket_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::WorkspaceInvoke => handle(runtime, arg, |env, arg: WorkspaceInvokeArg| {
+            env.interface().workspace_invoke(arg.action, arg.args.into())
+        }),
+        EngineOp::NonFungibleInvoke => handle(runtime, arg, |env, arg: NonFungibleInvokeArg| {
+            env.interface()
+                .non_fungible_invoke(arg.address, arg.action, arg.args.into())
+        }),
+        EngineOp::KeyValueStoreInvoke => handle(runtime, arg, |env, arg: KeyValueStoreInvokeArg| {
+            env.interface()
+                .key_value_store_invoke(arg.store_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::GenerateUniqueId => handle(runtime, arg, |env, _arg: ()| env.interface().generate_uuid()),
+        EngineOp::ConsensusInvoke => handle(runtime, arg, |env, arg: ConsensusInvokeArg| {
+            env.interface().consensus_invoke(arg.action)
+        }),
+        EngineOp::CallerContextInvoke => handle(runtime, arg, |env, arg: CallerContextInvokeArg| {
+            env.interface().caller_context_invoke(arg.action, arg.args.into())
+        }),
+        EngineOp::GenerateRandomInvoke => handle(runtime, arg, |env, arg: GenerateRandomInvokeArg| {
+            env.interface().generate_random_invoke(arg.action)
+        }),
+        EngineOp::EmitEvent => handle(runtime, arg, |env, arg: EmitEventArg| {
+            env.interface()
+                .emit_event(arg.topic, arg.payload, arg.data, arg.indexed_fields)
+        }),
+        EngineOp::CallInvoke => handle(runtime, arg, |env, arg: CallInvokeArg| {
+            env.interface().call_invoke(arg.action, arg.args.into())
+        }),
+        EngineOp::ProofInvoke => handle(runtime, arg, |env, arg: ProofInvokeArg| {
+            log::debug!(target: LOG_TARGET, "proof action = {:?}", arg.action);
+            env.interface().proof_invoke(arg.proof_ref, arg.action, arg.args.into())
+        }),
+        EngineOp::BuiltinTemplateInvoke => handle(runtime, arg, |env, arg: BuiltinTemplateInvokeArg| {
+            env.interface().builtin_template_invoke(arg.action)
+        }),
+    }
+}
+
+fn handle<T, U, E>(
+    runtime: &mut Runtime,
+    arg: &[u8],
+    f: fn(&mut Runtime, T) -> Result<U, E>,
+) -> Result<EngineCallResponse, WasmExecutionError>
+where
+    T: DeserializeOwned,
+    U: Serialize,
+    WasmExecutionError: From<E>,
+{
+    let decoded = decode_exact(arg).map_err(WasmExecutionError::EngineArgDecodeFailed)?;
+    let resp = f(runtime, decoded)?;
+    let mut encoded_with_len = Vec::with_capacity(encoded_len(&resp)? + 4);
+    encode_with_len_to_writer(&mut encoded_with_len, &resp)?;
+    Ok(EngineCallResponse { encoded_with_len })
+}
+
+/// Reports a failed engine call. The template receives a null pointer and panics, so a runtime error is returned to be
+/// reported as the cause of the failure instead of the panic.
+pub(super) fn handle_engine_call_error(runtime: &Runtime, err: WasmExecutionError) -> Option<RuntimeError> {
+    if let Err(err) =
+        runtime
+            .interface()
+            .emit_log(LogLevel::Error, format!("Execution error: {}", err), Metadata::new())
+    {
+        log::error!(target: LOG_TARGET, "Error emitting log: {}", err);
+    }
+
+    log::error!(target: LOG_TARGET, "{}", err);
+    match err {
+        WasmExecutionError::RuntimeError(e) => Some(e),
+        _ => None,
+    }
+}
+
+/// Encodes the call to a template function that is passed to the template's main function
+pub(super) fn encode_call_info(
+    func_def: &FunctionDef,
+    args: Vec<tari_bor::Value>,
+) -> Result<Vec<u8>, WasmExecutionError> {
+    let call_info = CallInfo {
+        abi_context: encode(&AbiContext {})?,
+        func_name: func_def.name.clone(),
+        args,
+    };
+    Ok(encode(&call_info)?)
+}
+
+/// Decodes the value returned by a template function
+pub(super) fn decode_return_value(
+    runtime: &Runtime,
+    func_def: &FunctionDef,
+    raw: &[u8],
+) -> Result<InstructionResult, WasmExecutionError> {
+    let value = IndexedValue::from_raw(raw)?;
+
+    runtime.interface().set_last_instruction_output(value.clone())?;
+
+    Ok(InstructionResult {
+        indexed: value,
+        return_type: func_def.output.clone(),
+    })
+}
+
+/// Determine if the version of the template_lib crate in the WASM is valid.
+/// This is just a placeholder that logs the result, as we don't manage version incompatibilities yet
+pub(super) fn validate_template_tari_version(module: &LoadedWasmTemplate) -> Result<(), WasmExecutionError> {
+    let template_tari_version = module.template_def().tari_version();
+
+    if are_versions_compatible(template_tari_version, ENGINE_TARI_VERSION)? {
+        log::debug!(target: LOG_TARGET, "The Tari version in the template WASM (\"{}\") is compatible with the one used in the engine", template_tari_version);
+    } else {
+        log::error!(target: LOG_TARGET, "The Tari version in the template WASM (\"{}\") is incompatible with the one used in the engine (\"{}\")", template_tari_version, ENGINE_TARI_VERSION);
+        return Err(WasmExecutionError::TemplateVersionMismatch {
+            engine_version: ENGINE_TARI_VERSION.to_owned(),
+            template_version: template_tari_version.to_owned(),
+        });
+    }
+
+    Ok(())
+}
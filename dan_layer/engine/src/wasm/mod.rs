@@ -1,15 +1,21 @@
 // Copyright 2022 The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+mod abi_version;
+
+mod backend;
+pub use backend::{WasmBackend, WasmBackendKind, WasmBackendModule, MAX_EXECUTION_FUEL};
+
 pub mod compile;
 
 mod error;
 pub use error::{WasmError, WasmExecutionError};
 
 mod environment;
+mod host;
 
 mod module;
-pub use module::{LoadedWasmTemplate, WasmModule};
+pub use module::{LoadedWasmTemplate, WasmModule, WasmerBackend, WasmerModule};
 
 mod metering;
 mod process;
@@ -17,5 +23,12 @@ mod process;
 pub use process::WasmProcess;
 
 mod limiting_tunable;
-mod mem_writer;
+mod validation;
+pub use validation::WasmValidationError;
+
 mod version;
+
+#[cfg(feature = "wasmtime")]
+mod wasmtime_backend;
+#[cfg(feature = "wasmtime")]
+pub use wasmtime_backend::{WasmtimeBackend, WasmtimeModule};
@@ -22,6 +22,7 @@
 
 use std::{fmt, fmt::Formatter, sync::Arc};
 
+use tari_engine_types::instruction_result::InstructionResult;
 use tari_template_abi::{FunctionDef, TemplateDef, ABI_TEMPLATE_DEF_GLOBAL_NAME};
 use wasmer::{
     imports,
@@ -43,22 +44,76 @@ use wasmer::{
 };
 
 use crate::{
+    runtime::Runtime,
     template::{LoadedTemplate, TemplateLoaderError, TemplateModuleLoader},
-    wasm::{environment::WasmEnv, limiting_tunable::LimitingTunables, metering, WasmExecutionError},
+    traits::Invokable,
+    wasm::{
+        backend::{WasmBackend, WasmBackendKind, WasmBackendModule, MAX_EXECUTION_FUEL},
+        environment::WasmEnv,
+        limiting_tunable::LimitingTunables,
+        metering,
+        WasmExecutionError,
+        WasmProcess,
+    },
 };
 
+pub(super) const MEMORY_PAGE_LIMIT: Pages = Pages(32); // 2MiB = 32 * 65,536
+
 pub type MainFunction = TypedFunction<(WasmPtr<u8>, u32), WasmPtr<u8>>;
 #[derive(Debug, Clone)]
 pub struct WasmModule {
     code: Vec<u8>,
+    backend: WasmBackendKind,
 }
 
 impl WasmModule {
     pub fn from_code(code: Vec<u8>) -> Self {
-        Self { code }
+        Self {
+            code,
+            backend: WasmBackendKind::default(),
+        }
+    }
+
+    /// Sets the backend used to compile and execute the template
+    pub fn with_backend(mut self, backend: WasmBackendKind) -> Self {
+        self.backend = backend;
+        self
     }
 
     pub fn load_template_from_code(code: &[u8]) -> Result<LoadedTemplate, TemplateLoaderError> {
+        WasmBackendKind::default().load_template(code)
+    }
+
+    pub fn code(&self) -> &[u8] {
+        &self.code
+    }
+}
+
+impl TemplateModuleLoader for WasmModule {
+    fn load_template(&self) -> Result<LoadedTemplate, TemplateLoaderError> {
+        self.backend.load_template(&self.code)
+    }
+}
+
+/// Compiles templates with wasmer using the cranelift compiler
+pub struct WasmerBackend;
+
+impl WasmerBackend {
+    fn create_engine() -> Engine {
+        let base = BaseTunables::for_target(&Target::default());
+        let tunables = LimitingTunables::new(base, MEMORY_PAGE_LIMIT);
+        let mut compiler = Cranelift::new();
+        compiler.opt_level(CraneliftOptLevel::Speed).canonicalize_nans(true);
+        compiler.push_middleware(Arc::new(metering::middleware(MAX_EXECUTION_FUEL)));
+        let mut engine = Engine::from(compiler);
+        engine.set_tunables(tunables);
+
+        engine
+    }
+}
+
+impl WasmBackend for WasmerBackend {
+    fn compile_template(&self, code: &[u8]) -> Result<LoadedWasmTemplate, TemplateLoaderError> {
         let engine = Self::create_engine();
         let module = wasmer::Module::new(&engine, code)?;
         let mut store = Store::new(engine);
@@ -74,68 +129,79 @@ impl WasmModule {
         let mut env = WasmEnv::new(());
         let memory = instance.exports.get_memory("memory")?.clone();
         env.set_memory(memory);
-        let template = env.load_abi(&mut store, &instance)?;
+        let abi_version = env.load_abi_version(&mut store, &instance)?;
+        let template = env.load_abi(&mut store, &instance, abi_version)?;
         let main_fn = format!("{}_main", template.template_name());
         validate_instance(&mut store, &instance, &main_fn)?;
 
         let engine = store.engine().clone();
 
-        Ok(LoadedWasmTemplate::new(template, module, engine, code.len()).into())
-    }
-
-    pub fn code(&self) -> &[u8] {
-        &self.code
+        Ok(LoadedWasmTemplate::new(
+            template,
+            abi_version,
+            WasmerModule { module, engine },
+            code.len(),
+        ))
     }
+}
 
-    fn create_engine() -> Engine {
-        const MEMORY_PAGE_LIMIT: Pages = Pages(32); // 2MiB = 32 * 65,536
-        let base = BaseTunables::for_target(&Target::default());
-        let tunables = LimitingTunables::new(base, MEMORY_PAGE_LIMIT);
-        let mut compiler = Cranelift::new();
-        compiler.opt_level(CraneliftOptLevel::Speed).canonicalize_nans(true);
-        // TODO: Configure metering limit
-        compiler.push_middleware(Arc::new(metering::middleware(100_000_000)));
-        let mut engine = Engine::from(compiler);
-        engine.set_tunables(tunables);
+/// A template module compiled by the wasmer backend
+pub struct WasmerModule {
+    module: wasmer::Module,
+    engine: Engine,
+}
 
-        engine
+impl fmt::Debug for WasmerModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmerModule").field("module", &self.module).finish()
     }
 }
 
-impl TemplateModuleLoader for WasmModule {
-    fn load_template(&self) -> Result<LoadedTemplate, TemplateLoaderError> {
-        Self::load_template_from_code(&self.code)
+impl WasmBackendModule for WasmerModule {
+    fn invoke(
+        &self,
+        template: &LoadedWasmTemplate,
+        runtime: Runtime,
+        func_def: &FunctionDef,
+        args: Vec<tari_bor::Value>,
+    ) -> Result<InstructionResult, WasmExecutionError> {
+        let mut store = Store::new(self.engine.clone());
+        let mut process = WasmProcess::init(&mut store, template.clone(), &self.module, runtime)?;
+        process.invoke(&mut store, func_def, args)
     }
 }
 
 #[derive(Clone)]
 pub struct LoadedWasmTemplate {
     template_def: Arc<TemplateDef>,
-    module: wasmer::Module,
-    engine: Engine,
+    abi_version: u32,
+    module: Arc<dyn WasmBackendModule>,
     code_size: usize,
 }
 
 impl LoadedWasmTemplate {
-    pub fn new(template_def: TemplateDef, module: wasmer::Module, engine: Engine, code_size: usize) -> Self {
+    pub fn new<M: WasmBackendModule + 'static>(
+        template_def: TemplateDef,
+        abi_version: u32,
+        module: M,
+        code_size: usize,
+    ) -> Self {
         Self {
             template_def: Arc::new(template_def),
-            module,
-            engine,
+            abi_version,
+            module: Arc::new(module),
             code_size,
         }
     }
 
-    pub fn wasm_module(&self) -> &wasmer::Module {
-        &self.module
-    }
-
-    pub fn engine(&self) -> &Engine {
-        &self.engine
-    }
-
-    pub fn create_store(&self) -> Store {
-        Store::new(self.engine.clone())
+    /// Invokes a function of the template using the backend that compiled it
+    pub fn invoke(
+        &self,
+        runtime: Runtime,
+        func_def: &FunctionDef,
+        args: Vec<tari_bor::Value>,
+    ) -> Result<InstructionResult, WasmExecutionError> {
+        self.module.invoke(self, runtime, func_def, args)
     }
 
     pub fn template_name(&self) -> &str {
@@ -146,6 +212,11 @@ impl LoadedWasmTemplate {
         &self.template_def
     }
 
+    /// Returns the ABI version that the template was compiled with
+    pub fn abi_version(&self) -> u32 {
+        self.abi_version
+    }
+
     pub fn find_func_by_name(&self, function_name: &str) -> Option<&FunctionDef> {
         self.template_def.functions().iter().find(|f| f.name == *function_name)
     }
@@ -159,6 +230,7 @@ impl fmt::Debug for LoadedWasmTemplate {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("LoadedWasmTemplate")
             .field("template_name", &self.template_name())
+            .field("abi_version", &self.abi_version)
             .field("code_size", &self.code_size())
             .field("main", &"<main func>")
             .field("module", &self.module)
@@ -20,47 +20,25 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use serde::{de::DeserializeOwned, Serialize};
-use tari_bor::{decode_exact, encode, encode_into_writer, encode_with_len_to_writer, encoded_len};
-use tari_engine_types::{indexed_value::IndexedValue, instruction_result::InstructionResult};
-use tari_template_abi::{CallInfo, EngineOp, FunctionDef};
-use tari_template_lib::{
-    args::{
-        BucketInvokeArg,
-        BuiltinTemplateInvokeArg,
-        CallInvokeArg,
-        CallerContextInvokeArg,
-        ComponentInvokeArg,
-        ConsensusInvokeArg,
-        EmitEventArg,
-        EmitLogArg,
-        GenerateRandomInvokeArg,
-        LogLevel,
-        NonFungibleInvokeArg,
-        ProofInvokeArg,
-        ResourceInvokeArg,
-        VaultInvokeArg,
-        WorkspaceInvokeArg,
-    },
-    AbiContext,
-};
-use wasmer::{imports, AsStoreMut, Function, FunctionEnv, FunctionEnvMut, Instance, Store, StoreMut, WasmPtr};
+use tari_engine_types::instruction_result::InstructionResult;
+use tari_template_abi::{EngineOp, FunctionDef};
+use wasmer::{imports, AsStoreMut, Function, FunctionEnv, FunctionEnvMut, Instance, Store, WasmPtr};
 
-use super::version::are_versions_compatible;
 use crate::{
     runtime::Runtime,
     traits::Invokable,
     wasm::{
         environment::{AllocPtr, WasmEnv},
         error::WasmExecutionError,
+        host,
         module::MainFunction,
         LoadedWasmTemplate,
     },
 };
 
 const LOG_TARGET: &str = "tari::dan::engine::wasm::process";
-pub const ENGINE_TARI_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Executes a template using the wasmer backend
 pub struct WasmProcess {
     module: LoadedWasmTemplate,
     env: WasmEnv<Runtime>,
@@ -68,8 +46,13 @@ pub struct WasmProcess {
 }
 
 impl WasmProcess {
-    pub fn init(store: &mut Store, module: LoadedWasmTemplate, state: Runtime) -> Result<Self, WasmExecutionError> {
-        Self::validate_template_tari_version(&module)?;
+    pub fn init(
+        store: &mut Store,
+        module: LoadedWasmTemplate,
+        wasm_module: &wasmer::Module,
+        state: Runtime,
+    ) -> Result<Self, WasmExecutionError> {
+        host::validate_template_tari_version(&module)?;
 
         let mut env = WasmEnv::new(state);
         let fn_env = FunctionEnv::new(store, env.clone());
@@ -82,7 +65,7 @@ impl WasmProcess {
                 "on_panic" => Function::new_typed_with_env(store,&fn_env, on_panic_handler),
             }
         };
-        let instance = Instance::new(store, module.wasm_module(), &imports)?;
+        let instance = Instance::new(store, wasm_module, &imports)?;
         let memory = instance.exports.get_memory("memory")?.clone();
         let mem_alloc = instance.exports.get_typed_function(store, "tari_alloc")?;
         fn_env
@@ -96,20 +79,14 @@ impl WasmProcess {
         Ok(Self { module, env, instance })
     }
 
-    fn alloc_and_write<T: Serialize, S: AsStoreMut>(
-        &self,
-        store: &mut S,
-        val: &T,
-    ) -> Result<AllocPtr, WasmExecutionError> {
-        let len = encoded_len(val).unwrap();
-        let len = u32::try_from(len).map_err(|_| WasmExecutionError::MemoryAllocationTooLarge)?;
+    fn alloc_and_write<S: AsStoreMut>(&self, store: &mut S, data: &[u8]) -> Result<AllocPtr, WasmExecutionError> {
+        let len = u32::try_from(data.len()).map_err(|_| WasmExecutionError::MemoryAllocationTooLarge)?;
 
         let ptr = self.env.alloc(store, len)?;
         if ptr.is_null() {
             return Err(WasmExecutionError::MemoryAllocationFailed);
         }
-        let mut writer = self.env.memory_writer(store, ptr)?;
-        encode_into_writer(val, &mut writer).unwrap();
+        self.env.write_to_memory(store, ptr, data)?;
 
         Ok(AllocPtr::new(ptr.offset(), len))
     }
@@ -137,122 +114,19 @@ impl WasmProcess {
             },
         };
 
-        log::debug!(target: LOG_TARGET, "Engine call: {:?}", op);
-
-        let result = match op {
-            EngineOp::EmitLog => Self::handle(store, env_mut, arg, |env, arg: EmitLogArg| {
-                env.interface().emit_log(arg.level, arg.message)
-            }),
-            EngineOp::ComponentInvoke => Self::handle(store, env_mut, arg, |env, arg: ComponentInvokeArg| {
-                env.interface()
-                    .component_invoke(arg.component_ref, arg.action, arg.args.into())
-            }),
-            EngineOp::ResourceInvoke => Self::handle(store, env_mut, arg, |env, arg: ResourceInvokeArg| {
-                env.interface()
-                    .resource_invoke(arg.resource_ref, arg.action, arg.args.into())
-            }),
-            EngineOp::VaultInvoke => Self::handle(store, env_mut, arg, |env, arg: VaultInvokeArg| {
-                env.interface().vault_invoke(arg.vault_ref, arg.action, arg.args.into())
-            }),
-            EngineOp::BucketInvoke => Self::handle(store, env_mut, arg, |env, arg: BucketInvokeArg| {
-                env.interface()
-                    .bucket_invoke(arg.bucket_ref, arg.action, arg.args.into())
-            }),
-            EngineOp::WorkspaceInvoke => Self::handle(store, env_mut, arg, |env, arg: WorkspaceInvokeArg| {
-                env.interface().workspace_invoke(arg.action, arg.args.into())
-            }),
-            EngineOp::NonFungibleInvoke => Self::handle(store, env_mut, arg, |env, arg: NonFungibleInvokeArg| {
-                env.interface()
-                    .non_fungible_invoke(arg.address, arg.action, arg.args.into())
-            }),
-            EngineOp::GenerateUniqueId => {
-                Self::handle(store, env_mut, arg, |env, _arg: ()| env.interface().generate_uuid())
-            },
-            EngineOp::ConsensusInvoke => Self::handle(store, env_mut, arg, |env, arg: ConsensusInvokeArg| {
-                env.interface().consensus_invoke(arg.action)
-            }),
-            EngineOp::CallerContextInvoke => Self::handle(store, env_mut, arg, |env, arg: CallerContextInvokeArg| {
-                env.interface().caller_context_invoke(arg.action, arg.args.into())
-            }),
-            EngineOp::GenerateRandomInvoke => Self::handle(store, env_mut, arg, |env, arg: GenerateRandomInvokeArg| {
-                env.interface().generate_random_invoke(arg.action)
-            }),
-            EngineOp::EmitEvent => Self::handle(store, env_mut, arg, |env, arg: EmitEventArg| {
-                env.interface().emit_event(arg.topic, arg.payload)
-            }),
-            EngineOp::CallInvoke => Self::handle(store, env_mut, arg, |env, arg: CallInvokeArg| {
-                env.interface().call_invoke(arg.action, arg.args.into())
-            }),
-            EngineOp::ProofInvoke => Self::handle(store, env_mut, arg, |env, arg: ProofInvokeArg| {
-                log::debug!(target: LOG_TARGET, "proof action = {:?}", arg.action);
-                env.interface().proof_invoke(arg.proof_ref, arg.action, arg.args.into())
-            }),
-            EngineOp::BuiltinTemplateInvoke => {
-                Self::handle(store, env_mut, arg, |env, arg: BuiltinTemplateInvokeArg| {
-                    env.interface().builtin_template_invoke(arg.action)
-                })
-            },
-        };
+        let result = host::invoke_engine_op(env_mut.state_mut(), op, &arg).and_then(|response| {
+            let ptr = env_mut.alloc(&mut store, response.alloc_len()?)?;
+            env_mut.write_to_memory(&mut store, ptr, response.as_bytes())?;
+            Ok(ptr)
+        });
 
         result.unwrap_or_else(|err| {
-            if let Err(err) = env
-                .data()
-                .state()
-                .interface()
-                .emit_log(LogLevel::Error, format!("Execution error: {}", err))
-            {
-                log::error!(target: LOG_TARGET, "Error emitting log: {}", err);
-            }
-
-            log::error!(target: LOG_TARGET, "{}", err);
-            if let WasmExecutionError::RuntimeError(e) = err {
-                env.data_mut().set_last_engine_error(e);
+            if let Some(err) = host::handle_engine_call_error(env.data().state(), err) {
+                env.data_mut().set_last_engine_error(err);
             }
             WasmPtr::null()
         })
     }
-
-    pub fn handle<T, U, E>(
-        mut store: StoreMut,
-        env_mut: &mut WasmEnv<Runtime>,
-        args: Vec<u8>,
-        f: fn(&mut Runtime, T) -> Result<U, E>,
-    ) -> Result<WasmPtr<u8>, WasmExecutionError>
-    where
-        T: DeserializeOwned,
-        U: Serialize,
-        WasmExecutionError: From<E>,
-    {
-        let decoded = decode_exact(&args).map_err(WasmExecutionError::EngineArgDecodeFailed)?;
-        let resp = f(env_mut.state_mut(), decoded)?;
-        let len = encoded_len(&resp)?;
-        let ptr = env_mut.alloc(&mut store, len as u32)?;
-        let mut writer = env_mut.memory_writer(&mut store, ptr)?;
-        encode_with_len_to_writer(&mut writer, &resp)?;
-        Ok(ptr)
-    }
-
-    fn encoded_abi_context(&self) -> Vec<u8> {
-        encode(&AbiContext {}).unwrap()
-    }
-
-    /// Determine if the version of the template_lib crate in the WASM is valid.
-    /// This is just a placeholder that logs the result, as we don't manage version incompatibilities yet
-    fn validate_template_tari_version(module: &LoadedWasmTemplate) -> Result<(), WasmExecutionError> {
-        let template_tari_version = module.template_def().tari_version();
-
-        if are_versions_compatible(template_tari_version, ENGINE_TARI_VERSION)? {
-            log::debug!(target: LOG_TARGET, "The Tari version in the template WASM (\"{}\") is compatible with the one used in the engine", template_tari_version);
-        } else {
-            log::error!(target: LOG_TARGET, "The Tari version in the template WASM (\"{}\") is incompatible with the one used in the engine (\"{}\")", template_tari_version, ENGINE_TARI_VERSION);
-            return Err(WasmExecutionError::TemplateVersionMismatch {
-                engine_version: ENGINE_TARI_VERSION.to_owned(),
-                template_version: template_tari_version.to_owned(),
-            });
-        }
-
-        Ok(())
-    }
 }
 
 impl Invokable<Store> for WasmProcess {
@@ -264,11 +138,7 @@ impl Invokable<Store> for WasmProcess {
         func_def: &FunctionDef,
         args: Vec<tari_bor::Value>,
     ) -> Result<InstructionResult, Self::Error> {
-        let call_info = CallInfo {
-            abi_context: self.encoded_abi_context(),
-            func_name: func_def.name.clone(),
-            args,
-        };
+        let call_info = host::encode_call_info(func_def, args)?;
 
         let main_name = format!("{}_main", self.module.template_name());
         let func: MainFunction = self.instance.exports.get_typed_function(store, &main_name)?;
@@ -288,7 +158,7 @@ impl Invokable<Store> for WasmProcess {
                 if let Some(message) = self.env.take_last_panic_message() {
                     return Err(WasmExecutionError::Panic {
                         message,
-                        runtime_error: err,
+                        runtime_error: err.into(),
                     });
                 }
                 eprintln!("Error calling function: {}", err);
@@ -299,17 +169,7 @@ impl Invokable<Store> for WasmProcess {
         // Read response from memory
         let raw = self.env.read_memory_with_embedded_len(store, ptr.offset())?;
 
-        let value = IndexedValue::from_raw(&raw)?;
-
-        self.env
-            .state()
-            .interface()
-            .set_last_instruction_output(value.clone())?;
-
-        Ok(InstructionResult {
-            indexed: value,
-            return_type: func_def.output.clone(),
-        })
+        host::decode_return_value(self.env.state(), func_def, &raw)
     }
 }
 
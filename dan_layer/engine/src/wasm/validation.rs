@@ -0,0 +1,197 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Validation of template WASM modules before they are compiled. Every validator must execute a template identically,
+//! so modules that use instructions or host functions whose results may differ between machines are rejected.
+
+use wasmer::{
+    wasmparser::{BinaryReaderError, Operator, Parser, Payload, TypeRef},
+    Pages,
+};
+
+/// The host functions that the engine provides to templates
+const PERMITTED_IMPORTS: &[(&str, &str)] = &[("env", "tari_engine"), ("env", "debug"), ("env", "on_panic")];
+
+#[derive(Debug, thiserror::Error)]
+pub enum WasmValidationError {
+    #[error("Failed to parse WASM module: {0}")]
+    ParseError(#[from] BinaryReaderError),
+    #[error("Import {module}.{name} is not permitted")]
+    ImportNotPermitted { module: String, name: String },
+    #[error("Floating point instruction {instruction} in function {function_index} is not permitted")]
+    FloatingPointInstruction { function_index: u32, instruction: String },
+    #[error("Memory {kind} of {pages} pages exceeds the limit of {limit} pages")]
+    MemoryLimitExceeded { kind: &'static str, pages: u64, limit: u32 },
+    #[error("Unsupported memory: {details}")]
+    UnsupportedMemory { details: &'static str },
+}
+
+/// Checks that the module only uses deterministic instructions, only imports the engine's host functions and that its
+/// memory cannot grow beyond `memory_page_limit`.
+///
+/// Floating point arithmetic is rejected. Instructions that only load, store, compare or convert floating point values
+/// are permitted because the CBOR codec used by all templates requires them, and the compiler canonicalizes NaNs so
+/// that their results are identical on every machine. A memory without a declared maximum is permitted because the
+/// engine caps it at `memory_page_limit`.
+pub fn validate_deterministic_module(code: &[u8], memory_page_limit: Pages) -> Result<(), WasmValidationError> {
+    let mut function_index = 0u32;
+    for payload in Parser::new(0).parse_all(code) {
+        match payload? {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import?;
+                    let is_permitted = matches!(import.ty, TypeRef::Func(_)) &&
+                        PERMITTED_IMPORTS.contains(&(import.module, import.name));
+                    if !is_permitted {
+                        return Err(WasmValidationError::ImportNotPermitted {
+                            module: import.module.to_string(),
+                            name: import.name.to_string(),
+                        });
+                    }
+                    // Imported functions precede the module's own functions in the function index space
+                    function_index += 1;
+                }
+            },
+            Payload::MemorySection(reader) => {
+                for memory in reader {
+                    let memory = memory?;
+                    if memory.memory64 {
+                        return Err(WasmValidationError::UnsupportedMemory {
+                            details: "64-bit memories are not supported",
+                        });
+                    }
+                    if memory.shared {
+                        return Err(WasmValidationError::UnsupportedMemory {
+                            details: "shared memories are not supported",
+                        });
+                    }
+                    check_memory_pages("minimum", memory.initial, memory_page_limit)?;
+                    if let Some(maximum) = memory.maximum {
+                        check_memory_pages("maximum", maximum, memory_page_limit)?;
+                    }
+                }
+            },
+            Payload::CodeSectionEntry(body) => {
+                for op in body.get_operators_reader()? {
+                    let op = op?;
+                    if is_float_arithmetic(&op) {
+                        return Err(WasmValidationError::FloatingPointInstruction {
+                            function_index,
+                            instruction: format!("{:?}", op),
+                        });
+                    }
+                }
+                function_index += 1;
+            },
+            _ => {},
+        }
+    }
+
+    Ok(())
+}
+
+fn check_memory_pages(kind: &'static str, pages: u64, limit: Pages) -> Result<(), WasmValidationError> {
+    if pages > u64::from(limit.0) {
+        return Err(WasmValidationError::MemoryLimitExceeded {
+            kind,
+            pages,
+            limit: limit.0,
+        });
+    }
+    Ok(())
+}
+
+fn is_float_arithmetic(op: &Operator) -> bool {
+    matches!(
+        op,
+        Operator::F32Add |
+            Operator::F32Sub |
+            Operator::F32Mul |
+            Operator::F32Div |
+            Operator::F32Min |
+            Operator::F32Max |
+            Operator::F32Sqrt |
+            Operator::F32Ceil |
+            Operator::F32Floor |
+            Operator::F32Trunc |
+            Operator::F32Nearest |
+            Operator::F64Add |
+            Operator::F64Sub |
+            Operator::F64Mul |
+            Operator::F64Div |
+            Operator::F64Min |
+            Operator::F64Max |
+            Operator::F64Sqrt |
+            Operator::F64Ceil |
+            Operator::F64Floor |
+            Operator::F64Trunc |
+            Operator::F64Nearest
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: Pages = Pages(32);
+
+    fn validate(wat: &str) -> Result<(), WasmValidationError> {
+        let code = wasmer::wat2wasm(wat.as_bytes()).unwrap();
+        validate_deterministic_module(&code, LIMIT)
+    }
+
+    #[test]
+    fn it_accepts_deterministic_modules() {
+        validate(
+            r#"(module
+                (import "env" "debug" (func $debug (param i32 i32)))
+                (memory 17)
+                (func (param f32) (result f64) local.get 0 f64.promote_f32)
+                (func (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn it_rejects_float_arithmetic() {
+        let err = validate(
+            r#"(module
+                (import "env" "debug" (func $debug (param i32 i32)))
+                (func (param i32) (result i32) local.get 0)
+                (func (param f64 f64) (result f64) local.get 0 local.get 1 f64.div))"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, WasmValidationError::FloatingPointInstruction {
+            function_index: 2,
+            ..
+        }));
+    }
+
+    #[test]
+    fn it_rejects_unknown_imports() {
+        let err = validate(
+            r#"(module (import "wasi_snapshot_preview1" "clock_time_get" (func (param i32 i64 i32) (result i32))))"#,
+        )
+        .unwrap_err();
+        assert!(matches!(err, WasmValidationError::ImportNotPermitted { .. }));
+
+        let err = validate(r#"(module (import "env" "memory" (memory 1)))"#).unwrap_err();
+        assert!(matches!(err, WasmValidationError::ImportNotPermitted { .. }));
+    }
+
+    #[test]
+    fn it_rejects_memory_exceeding_the_limit() {
+        let err = validate("(module (memory 1 1000))").unwrap_err();
+        assert!(matches!(err, WasmValidationError::MemoryLimitExceeded {
+            kind: "maximum",
+            pages: 1000,
+            ..
+        }));
+
+        let err = validate("(module (memory 33))").unwrap_err();
+        assert!(matches!(err, WasmValidationError::MemoryLimitExceeded {
+            kind: "minimum",
+            ..
+        }));
+    }
+}
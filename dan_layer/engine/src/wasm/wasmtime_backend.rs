@@ -0,0 +1,418 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A WASM backend that compiles templates with wasmtime.
+//!
+//! Execution is limited using wasmtime's fuel metering, which charges one unit of fuel for most instructions. This
+//! differs from the per-instruction costs of the wasmer metering middleware.
+
+use std::fmt;
+
+use tari_engine_types::instruction_result::InstructionResult;
+use tari_template_abi::{EngineOp, FunctionDef, TemplateDef, ABI_TEMPLATE_DEF_GLOBAL_NAME, ABI_VERSION_GLOBAL_NAME};
+use wasmer::ExportError;
+use wasmtime::{
+    AsContext,
+    AsContextMut,
+    Caller,
+    Config,
+    Engine,
+    Extern,
+    Instance,
+    Linker,
+    Memory,
+    Module,
+    OptLevel,
+    Store,
+    StoreLimits,
+    StoreLimitsBuilder,
+    Trap,
+    TypedFunc,
+};
+
+use crate::{
+    runtime::{Runtime, RuntimeError},
+    template::TemplateLoaderError,
+    wasm::{
+        abi_version,
+        backend::{WasmBackend, WasmBackendModule, MAX_EXECUTION_FUEL},
+        host,
+        module::MEMORY_PAGE_LIMIT,
+        LoadedWasmTemplate,
+        WasmExecutionError,
+    },
+};
+
+const LOG_TARGET: &str = "tari::dan::engine::wasm::wasmtime";
+
+type MainFunction = TypedFunc<(u32, u32), u32>;
+
+/// Compiles templates with wasmtime using the cranelift compiler
+pub struct WasmtimeBackend;
+
+impl WasmtimeBackend {
+    fn create_engine() -> Result<Engine, WasmExecutionError> {
+        let mut config = Config::new();
+        config
+            .consume_fuel(true)
+            .cranelift_opt_level(OptLevel::Speed)
+            .cranelift_nan_canonicalization(true);
+        Ok(Engine::new(&config)?)
+    }
+
+    fn load_abi(engine: &Engine, module: &Module) -> Result<(TemplateDef, u32), WasmExecutionError> {
+        let mut store = create_store(engine, ())?;
+        let mut linker = Linker::new(engine);
+        linker
+            .func_wrap("env", "tari_engine", |_op: i32, _arg_ptr: u32, _arg_len: u32| 0u32)?
+            .func_wrap("env", "debug", |_arg_ptr: u32, _arg_len: u32| {})?
+            .func_wrap(
+                "env",
+                "on_panic",
+                |_msg_ptr: u32, _msg_len: u32, _line: i32, _col: i32| {},
+            )?;
+        let instance = linker.instantiate(&mut store, module)?;
+        let memory = get_memory(&mut store, &instance)?;
+
+        let abi_version = load_abi_version(&mut store, &instance, memory)?;
+        let ptr = get_i32_global(&mut store, &instance, ABI_TEMPLATE_DEF_GLOBAL_NAME)?;
+        let data = read_memory_with_embedded_len(&store, memory, ptr)?;
+        let template = abi_version::decode_template_def(abi_version, &data)?;
+
+        let main_fn = format!("{}_main", template.template_name());
+        validate_instance(&mut store, &instance, &main_fn)?;
+
+        Ok((template, abi_version))
+    }
+}
+
+impl WasmBackend for WasmtimeBackend {
+    fn compile_template(&self, code: &[u8]) -> Result<LoadedWasmTemplate, TemplateLoaderError> {
+        let engine = Self::create_engine()?;
+        let module = Module::new(&engine, code).map_err(WasmExecutionError::BackendError)?;
+        let (template, abi_version) = Self::load_abi(&engine, &module)?;
+
+        Ok(LoadedWasmTemplate::new(
+            template,
+            abi_version,
+            WasmtimeModule { engine, module },
+            code.len(),
+        ))
+    }
+}
+
+/// A template module compiled by the wasmtime backend
+pub struct WasmtimeModule {
+    engine: Engine,
+    module: Module,
+}
+
+impl WasmBackendModule for WasmtimeModule {
+    fn invoke(
+        &self,
+        template: &LoadedWasmTemplate,
+        runtime: Runtime,
+        func_def: &FunctionDef,
+        args: Vec<tari_bor::Value>,
+    ) -> Result<InstructionResult, WasmExecutionError> {
+        host::validate_template_tari_version(template)?;
+
+        let mut store = create_store(&self.engine, runtime)?;
+        let mut linker = Linker::new(&self.engine);
+        linker
+            .func_wrap("env", "tari_engine", tari_engine_entrypoint)?
+            .func_wrap("env", "debug", debug_handler::<Runtime>)?
+            .func_wrap("env", "on_panic", on_panic_handler::<Runtime>)?;
+        let instance = linker.instantiate(&mut store, &self.module)?;
+        let memory = get_memory(&mut store, &instance)?;
+        let mem_alloc = instance.get_typed_func(&mut store, "tari_alloc")?;
+        store.data_mut().memory = Some(memory);
+        store.data_mut().mem_alloc = Some(mem_alloc);
+
+        let call_info = host::encode_call_info(func_def, args)?;
+        let call_info_len = u32::try_from(call_info.len()).map_err(|_| WasmExecutionError::MemoryAllocationTooLarge)?;
+        let call_info_ptr = alloc(&mut store, call_info_len)?;
+        write_to_memory(&mut store, memory, call_info_ptr, &call_info)?;
+
+        let main_name = format!("{}_main", template.template_name());
+        let func: MainFunction = instance.get_typed_func(&mut store, &main_name)?;
+        let ptr = match func.call(&mut store, (call_info_ptr, call_info_len)) {
+            Ok(ptr) => ptr,
+            Err(err) => {
+                if let Some(err) = store.data_mut().last_engine_error.take() {
+                    return Err(WasmExecutionError::RuntimeError(err));
+                }
+                if let Some(message) = store.data_mut().last_panic.take() {
+                    return Err(WasmExecutionError::Panic {
+                        message,
+                        runtime_error: err,
+                    });
+                }
+                if err.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+                    return Err(WasmExecutionError::OutOfFuel);
+                }
+                return Err(err.into());
+            },
+        };
+
+        let raw = read_memory_with_embedded_len(&store, memory, ptr)?;
+
+        host::decode_return_value(&store.data().state, func_def, &raw)
+    }
+}
+
+impl fmt::Debug for WasmtimeModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmtimeModule")
+            .field("name", &self.module.name())
+            .finish()
+    }
+}
+
+/// The host state of a wasmtime store
+struct WasmtimeEnv<T> {
+    state: T,
+    limits: StoreLimits,
+    memory: Option<Memory>,
+    mem_alloc: Option<TypedFunc<u32, u32>>,
+    last_panic: Option<String>,
+    last_engine_error: Option<RuntimeError>,
+}
+
+impl<T> WasmtimeEnv<T> {
+    fn get_memory(&self) -> Result<Memory, WasmExecutionError> {
+        self.memory.ok_or(WasmExecutionError::MemoryNotSet)
+    }
+}
+
+fn create_store<T>(engine: &Engine, state: T) -> Result<Store<WasmtimeEnv<T>>, WasmExecutionError> {
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MEMORY_PAGE_LIMIT.bytes().0)
+        .build();
+    let mut store = Store::new(engine, WasmtimeEnv {
+        state,
+        limits,
+        memory: None,
+        mem_alloc: None,
+        last_panic: None,
+        last_engine_error: None,
+    });
+    store.limiter(|env| &mut env.limits);
+    store.set_fuel(MAX_EXECUTION_FUEL)?;
+    Ok(store)
+}
+
+fn tari_engine_entrypoint(mut caller: Caller<'_, WasmtimeEnv<Runtime>>, op: i32, arg_ptr: u32, arg_len: u32) -> u32 {
+    let op = match EngineOp::from_i32(op) {
+        Some(op) => op,
+        None => {
+            log::error!(target: LOG_TARGET, "Invalid opcode: {}", op);
+            return 0;
+        },
+    };
+
+    let arg = match caller
+        .data()
+        .get_memory()
+        .and_then(|memory| read_from_memory(&caller, memory, arg_ptr, arg_len))
+    {
+        Ok(arg) => arg,
+        Err(err) => {
+            log::error!(target: LOG_TARGET, "Failed to read from memory: {}", err);
+            return 0;
+        },
+    };
+
+    let result = host::invoke_engine_op(&mut caller.data_mut().state, op, &arg).and_then(|response| {
+        let memory = caller.data().get_memory()?;
+        let ptr = alloc(&mut caller, response.alloc_len()?)?;
+        write_to_memory(&mut caller, memory, ptr, response.as_bytes())?;
+        Ok(ptr)
+    });
+
+    result.unwrap_or_else(|err| {
+        if let Some(err) = host::handle_engine_call_error(&caller.data().state, err) {
+            caller.data_mut().last_engine_error = Some(err);
+        }
+        0
+    })
+}
+
+fn debug_handler<T>(caller: Caller<'_, WasmtimeEnv<T>>, arg_ptr: u32, arg_len: u32) {
+    const WASM_DEBUG_LOG_TARGET: &str = "tari::dan::wasm";
+
+    match caller
+        .data()
+        .get_memory()
+        .and_then(|memory| read_from_memory(&caller, memory, arg_ptr, arg_len))
+    {
+        Ok(arg) => {
+            eprintln!("DEBUG: {}", String::from_utf8_lossy(&arg));
+        },
+        Err(err) => {
+            log::error!(target: WASM_DEBUG_LOG_TARGET, "Failed to read from memory: {}", err);
+        },
+    }
+}
+
+fn on_panic_handler<T>(mut caller: Caller<'_, WasmtimeEnv<T>>, msg_ptr: u32, msg_len: i32, line: i32, col: i32) {
+    const WASM_DEBUG_LOG_TARGET: &str = "tari::dan::wasm";
+
+    let message = match caller
+        .data()
+        .get_memory()
+        .and_then(|memory| read_from_memory(&caller, memory, msg_ptr, msg_len as u32))
+    {
+        Ok(msg) => {
+            let msg = String::from_utf8_lossy(&msg);
+            eprintln!("📣 PANIC: ({}:{}) {}", line, col, msg);
+            log::error!(target: WASM_DEBUG_LOG_TARGET, "📣 PANIC: ({}:{}) {}", line, col, msg);
+            msg.to_string()
+        },
+        Err(err) => {
+            log::error!(
+                target: WASM_DEBUG_LOG_TARGET,
+                "📣 PANIC: WASM template panicked but did not provide a valid memory pointer to on_panic \
+                 callback: {}",
+                err
+            );
+            format!(
+                "WASM panicked but did not provide a valid message pointer to on_panic callback: {}",
+                err
+            )
+        },
+    };
+    caller.data_mut().last_panic = Some(message);
+}
+
+fn alloc<T>(mut store: impl AsContextMut<Data = WasmtimeEnv<T>>, len: u32) -> Result<u32, WasmExecutionError> {
+    let mem_alloc = store
+        .as_context()
+        .data()
+        .mem_alloc
+        .clone()
+        .ok_or(WasmExecutionError::MissingAbiFunction { function: "tari_alloc" })?;
+    let ptr = mem_alloc.call(&mut store, len)?;
+    if ptr == 0 {
+        return Err(WasmExecutionError::MemoryAllocationFailed);
+    }
+
+    Ok(ptr)
+}
+
+/// Reads from template memory. As in the wasmer backend, the range must end before the end of the memory.
+fn read_from_memory(store: impl AsContext, memory: Memory, ptr: u32, len: u32) -> Result<Vec<u8>, WasmExecutionError> {
+    let data = memory.data(&store);
+    let mem_size = data.len() as u64;
+    let ptr_plus_len = ptr.checked_add(len).ok_or(WasmExecutionError::MaxMemorySizeExceeded)?;
+    if u64::from(ptr) >= mem_size || u64::from(ptr_plus_len) >= mem_size {
+        return Err(WasmExecutionError::MemoryPointerOutOfRange {
+            size: mem_size,
+            pointer: u64::from(ptr),
+            len: u64::from(len),
+        });
+    }
+    Ok(data[ptr as usize..ptr_plus_len as usize].to_vec())
+}
+
+/// Reads data that is prefixed with its length from template memory. As in the wasmer backend, data that extends
+/// past the end of the memory is truncated.
+fn read_memory_with_embedded_len(
+    store: impl AsContext,
+    memory: Memory,
+    offset: u32,
+) -> Result<Vec<u8>, WasmExecutionError> {
+    let data = memory.data(&store);
+    let start = offset as usize + 4;
+    let len_bytes = data
+        .get(offset as usize..start)
+        .ok_or(WasmExecutionError::MemoryPointerOutOfRange {
+            size: data.len() as u64,
+            pointer: u64::from(offset),
+            len: 4,
+        })?;
+    let len = u32::from_le_bytes(len_bytes.try_into().expect("slice is 4 bytes long")) as usize;
+    let end = start.saturating_add(len).min(data.len());
+    Ok(data.get(start..end).unwrap_or_default().to_vec())
+}
+
+fn write_to_memory(
+    mut store: impl AsContextMut,
+    memory: Memory,
+    ptr: u32,
+    data: &[u8],
+) -> Result<(), WasmExecutionError> {
+    let memory_data = memory.data_mut(&mut store);
+    let mem_size = memory_data.len() as u64;
+    let dest = (ptr as usize)
+        .checked_add(data.len())
+        .and_then(|end| memory_data.get_mut(ptr as usize..end))
+        .ok_or(WasmExecutionError::MemoryPointerOutOfRange {
+            size: mem_size,
+            pointer: u64::from(ptr),
+            len: data.len() as u64,
+        })?;
+    dest.copy_from_slice(data);
+    Ok(())
+}
+
+fn get_memory<T>(store: &mut Store<T>, instance: &Instance) -> Result<Memory, WasmExecutionError> {
+    instance
+        .get_memory(store, "memory")
+        .ok_or_else(|| WasmExecutionError::ExportError(ExportError::Missing("memory".to_string())))
+}
+
+fn get_i32_global<T>(store: &mut Store<T>, instance: &Instance, name: &str) -> Result<u32, WasmExecutionError> {
+    let global = instance
+        .get_global(&mut *store, name)
+        .ok_or_else(|| WasmExecutionError::ExportError(ExportError::Missing(name.to_string())))?;
+    let value = global
+        .get(&mut *store)
+        .i32()
+        .ok_or(WasmExecutionError::ExportError(ExportError::IncompatibleType))?;
+    Ok(value as u32)
+}
+
+/// Returns the ABI version exported by the template, or the legacy version if the template does not export one
+fn load_abi_version<T>(store: &mut Store<T>, instance: &Instance, memory: Memory) -> Result<u32, WasmExecutionError> {
+    match instance.get_export(&mut *store, ABI_VERSION_GLOBAL_NAME) {
+        None => return Ok(abi_version::LEGACY_ABI_VERSION),
+        Some(Extern::Global(_)) => {},
+        Some(_) => return Err(WasmExecutionError::ExportError(ExportError::IncompatibleType)),
+    }
+    let ptr = get_i32_global(store, instance, ABI_VERSION_GLOBAL_NAME)?;
+    let data = memory.data(&*store);
+    let version_bytes =
+        data.get(ptr as usize..ptr as usize + 4)
+            .ok_or(WasmExecutionError::MemoryPointerOutOfRange {
+                size: data.len() as u64,
+                pointer: u64::from(ptr),
+                len: 4,
+            })?;
+    Ok(u32::from_le_bytes(
+        version_bytes.try_into().expect("slice is 4 bytes long"),
+    ))
+}
+
+fn validate_instance<T>(store: &mut Store<T>, instance: &Instance, main_fn: &str) -> Result<(), WasmExecutionError> {
+    fn is_func_permitted(name: &str) -> bool {
+        name.ends_with("_main") || name == "tari_alloc" || name == "tari_free"
+    }
+
+    // Enforce that only permitted functions are allowed
+    let unexpected_abi_func = instance.exports(&mut *store).find_map(|export| {
+        let name = export.name();
+        (export.into_func().is_some() && !is_func_permitted(name)).then(|| name.to_string())
+    });
+
+    if let Some(name) = unexpected_abi_func {
+        return Err(WasmExecutionError::UnexpectedAbiFunction { name });
+    }
+
+    get_i32_global(store, instance, ABI_TEMPLATE_DEF_GLOBAL_NAME)?;
+
+    // Check that the main function exists
+    let _main: MainFunction = instance.get_typed_func(&mut *store, main_fn)?;
+
+    Ok(())
+}
@@ -3,6 +3,7 @@
 use std::collections::{BTreeMap, HashMap};
 
 use tari_dan_engine::runtime::{ActionIdent, RuntimeError};
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
 use tari_template_lib::{
     args,
     args::ComponentAction,
@@ -100,6 +101,70 @@ mod component_access_rules {
         });
     }
 
+    #[test]
+    fn it_denies_component_methods_to_negated_requirements() {
+        let mut test = TemplateTest::new(["tests/templates/access_rules"]);
+
+        let (owner_proof, _, owner_key) = test.create_owner_proof();
+        let (user_proof, _, user_key) = test.create_owner_proof();
+        let (banned1_proof, _, banned1_key) = test.create_owner_proof();
+        let (banned2_proof, _, banned2_key) = test.create_owner_proof();
+
+        let access_rules_template = test.get_template_address("AccessRulesTest");
+
+        // Anyone may call set_value unless they hold one of the banned badges
+        let set_value_rule = rule!(not(any_of(
+            non_fungible(banned1_proof.clone()),
+            non_fungible(banned2_proof.clone())
+        )));
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "with_configured_rules", args![
+                    // Owner
+                    OwnerRule::OwnedBySigner,
+                    // Component
+                    ComponentAccessRules::new()
+                        .add_method_rule("set_value", set_value_rule)
+                        .default(AccessRule::DenyAll),
+                    // Resource
+                    ResourceAccessRules::deny_all(),
+                    // Badge recall rule
+                    AccessRule::DenyAll,
+                ])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof],
+        );
+
+        let component_address = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(component_address, "set_value", args![1])
+                .sign(&user_key)
+                .build(),
+            vec![user_proof],
+        );
+
+        for (proof, key) in [(banned1_proof, banned1_key), (banned2_proof, banned2_key)] {
+            let reason = test.execute_expect_failure(
+                Transaction::builder()
+                    .call_method(component_address, "set_value", args![2])
+                    .sign(&key)
+                    .build(),
+                vec![proof],
+            );
+
+            assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+                component_address,
+                method: "set_value".to_string(),
+            });
+        }
+    }
+
     #[test]
     fn it_allows_owner_to_update_component_access_rules() {
         let mut test = TemplateTest::new(["tests/templates/access_rules"]);
@@ -222,6 +287,195 @@ mod component_access_rules {
             action: ComponentAction::SetAccessRules.into(),
         });
     }
+
+    #[test]
+    fn it_requires_a_threshold_of_proofs() {
+        let mut test = TemplateTest::new(["tests/templates/access_rules"]);
+        let (proof1, _, key1) = test.create_owner_proof();
+        let (proof2, _, _) = test.create_owner_proof();
+        let (proof3, _, _) = test.create_owner_proof();
+        let access_rules_template = test.get_template_address("AccessRulesTest");
+
+        let component_rules = ComponentAccessRules::new().add_method_rule(
+            "set_value",
+            rule!(threshold(
+                2,
+                non_fungible(proof1.clone()),
+                non_fungible(proof2.clone()),
+                non_fungible(proof3.clone())
+            )),
+        );
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "with_configured_rules", args![
+                    // Owner
+                    OwnerRule::None,
+                    // Component
+                    component_rules,
+                    // Resource
+                    ResourceAccessRules::new(),
+                    // Badge recall rule
+                    AccessRule::DenyAll,
+                ])
+                .sign(&key1)
+                .build(),
+            vec![proof1.clone()],
+        );
+
+        let component_address = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        // One of three is not enough
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(component_address, "set_value", args![1])
+                .sign(&key1)
+                .build(),
+            vec![proof1.clone()],
+        );
+        assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+            component_address,
+            method: "set_value".to_string(),
+        });
+
+        // Two of three
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(component_address, "set_value", args![1])
+                .sign(&key1)
+                .build(),
+            vec![proof1, proof3],
+        );
+    }
+
+    #[test]
+    fn it_restricts_component_methods_by_epoch_and_timestamp() {
+        let mut test = TemplateTest::new(["tests/templates/access_rules"]);
+        let (owner_proof, _, owner_key) = test.create_owner_proof();
+        let access_rules_template = test.get_template_address("AccessRulesTest");
+
+        let component_rules = ComponentAccessRules::new()
+            .add_method_rule("set_value", rule!(before_epoch(10)))
+            .add_method_rule("get_value", rule!(all_of(after_epoch(5), after_timestamp(1000))));
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "with_configured_rules", args![
+                    // Owner
+                    OwnerRule::None,
+                    // Component
+                    component_rules,
+                    // Resource
+                    ResourceAccessRules::new(),
+                    // Badge recall rule
+                    AccessRule::DenyAll,
+                ])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+
+        let component_address = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        test.call_method::<()>(component_address, "set_value", args![1], vec![]);
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(component_address, "get_value", args![])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+        assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+            component_address,
+            method: "get_value".to_string(),
+        });
+
+        test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(10));
+        test.set_virtual_substate(
+            VirtualSubstateId::CurrentTimestamp,
+            VirtualSubstate::CurrentTimestamp(1000),
+        );
+
+        let value: u32 = test.call_method(component_address, "get_value", args![], vec![]);
+        assert_eq!(value, 1);
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(component_address, "set_value", args![2])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof],
+        );
+        assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+            component_address,
+            method: "set_value".to_string(),
+        });
+    }
+
+    #[test]
+    fn it_delegates_ownership_to_the_owning_component() {
+        let mut test = TemplateTest::new(["tests/templates/registry"]);
+        let (owner_proof, _, owner_key) = test.create_owner_proof();
+        let (user_proof, _, user_key) = test.create_owner_proof();
+        let registry_template = test.get_template_address("Registry");
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(registry_template, "new", args![])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+        let registry = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_method(registry, "create_child", args![])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+        let child = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        // The registry is the owner of the child
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(registry, "set_child_value", args![child, 1])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+        let value: u32 = test.call_method(child, "get_value", args![], vec![owner_proof.clone()]);
+        assert_eq!(value, 1);
+
+        // The owner of the registry is also an owner of the child
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(child, "set_value", args![2])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof],
+        );
+
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(child, "set_value", args![3])
+                .sign(&user_key)
+                .build(),
+            vec![user_proof],
+        );
+        assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+            component_address: child,
+            method: "set_value".to_string(),
+        });
+    }
 }
 
 mod resource_access_rules {
@@ -577,6 +831,74 @@ mod resource_access_rules {
         );
     }
 
+    #[test]
+    fn it_allows_resource_access_with_combined_proofs() {
+        let mut test = TemplateTest::new(["tests/templates/access_rules"]);
+
+        let (owner_proof, _, owner_key) = test.create_owner_proof();
+        let (user_account, user_proof, user_key) = test.create_empty_account();
+
+        let access_rules_template = test.get_template_address("AccessRulesTest");
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "using_badge_rules", args![])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+
+        let component_address = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+        let vault: VaultId = test.extract_component_value(component_address, "$.badges");
+        let badge_resource = *test
+            .read_only_state_store()
+            .get_vault(&vault)
+            .unwrap()
+            .resource_address();
+
+        // Give the user a withdraw and deposit badge
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(component_address, "take_badge_by_name", args!["withdraw"])
+                .put_last_instruction_output_on_workspace("withdraw_perm")
+                .call_method(component_address, "take_badge_by_name", args!["deposit"])
+                .put_last_instruction_output_on_workspace("deposit_perm")
+                .call_method(user_account, "deposit", args![Workspace("withdraw_perm")])
+                .call_method(user_account, "deposit", args![Workspace("deposit_perm")])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof],
+        );
+
+        // A single composite proof of both badges authorizes the withdraw and the deposit
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(user_account, "create_proof_by_non_fungible_ids", args![
+                    badge_resource,
+                    vec![NonFungibleId::from_string("withdraw")]
+                ])
+                .put_last_instruction_output_on_workspace("withdraw_proof")
+                .call_method(user_account, "create_proof_by_non_fungible_ids", args![
+                    badge_resource,
+                    vec![NonFungibleId::from_string("deposit")]
+                ])
+                .put_last_instruction_output_on_workspace("deposit_proof")
+                .call_method(component_address, "take_tokens_using_combined_proofs", args![
+                    Workspace("withdraw_proof"),
+                    Workspace("deposit_proof"),
+                    Amount(10)
+                ])
+                .put_last_instruction_output_on_workspace("result")
+                .call_method(user_account, "deposit", args![Workspace("result.0")])
+                .drop_all_proofs_in_workspace()
+                .sign(&user_key)
+                .build(),
+            vec![user_proof],
+        );
+    }
+
     #[test]
     fn it_locks_resources_used_in_proofs() {
         let mut test = TemplateTest::new(["tests/templates/access_rules"]);
@@ -726,18 +1048,13 @@ mod resource_access_rules {
                     args![badge_resource],
                 )
                 .put_last_instruction_output_on_workspace("proof")
-                // This is quite interesting: we have to pass in the proof to call_component_with_args_using_proof to bring it into scope so that the cross template call can use it.
-                // Another way would be for the arguments to resolve recursively at the "base" call site, rather than resolving workspace args in invoke_call. This requires indexing the Args type.
-                .call_function(
-                    cross_call_template,
-                    "call_component_with_args_using_proof",
-                    args![
-                        component_address,
-                        "take_tokens_using_proof",
-                        Workspace("proof"),
-                        args![Workspace("proof"), Amount(10)],
-                    ],
-                )
+                // The nested workspace proof is resolved at the base call site, bringing it into scope for the cross
+                // template call
+                .call_function(cross_call_template, "call_component_with_args", args![
+                    component_address,
+                    "take_tokens_using_proof",
+                    args![Workspace("proof"), Amount(10)],
+                ])
                 .put_last_instruction_output_on_workspace("tokens")
                 .call_method(owner_account, "deposit", args![Workspace("tokens")])
                 .drop_all_proofs_in_workspace()
@@ -824,12 +1141,7 @@ mod resource_access_rules {
                     ],
                 )
                 .put_last_instruction_output_on_workspace("badges")
-                // TODO: this perhaps should be a native instruction
-                .call_function(
-                    access_rules_template,
-                    "create_proof_from_bucket",
-                    args![Workspace("badges")],
-                )
+                .create_proof_from_bucket("badges")
                 .put_last_instruction_output_on_workspace("proof")
                 .call_method(
                     component_address,
@@ -855,30 +1167,20 @@ mod resource_access_rules {
         // User can take tokens, using a proof obtained from a bucket
         test.execute_expect_success(
             Transaction::builder()
-                .call_method(
-                    user_account,
-                    "withdraw_many_non_fungibles",
-                    args![
-                        badge_resource,
-                        vec![
-                            NonFungibleId::from_string("withdraw"),
-                            NonFungibleId::from_string("deposit")
-                        ]
-                    ],
-                )
+                .call_method(user_account, "withdraw_many_non_fungibles", args![
+                    badge_resource,
+                    vec![
+                        NonFungibleId::from_string("withdraw"),
+                        NonFungibleId::from_string("deposit")
+                    ]
+                ])
                 .put_last_instruction_output_on_workspace("badges")
-                // TODO: this perhaps should be a native instruction
-                .call_function(
-                    access_rules_template,
-                    "create_proof_from_bucket",
-                    args![Workspace("badges")],
-                )
+                .create_proof_from_bucket("badges")
                 .put_last_instruction_output_on_workspace("proof")
-                .call_method(
-                    component_address,
-                    "take_tokens_using_proof",
-                    args![Workspace("proof"), Amount(10)],
-                )
+                .call_method(component_address, "take_tokens_using_proof", args![
+                    Workspace("proof"),
+                    Amount(10)
+                ])
                 .put_last_instruction_output_on_workspace("tokens")
                 .call_method(user_account, "deposit", args![Workspace("tokens")])
                 .drop_all_proofs_in_workspace()
@@ -1019,6 +1321,76 @@ mod resource_access_rules {
         });
     }
 
+    #[test]
+    fn it_allows_auth_hook_to_read_caller_component_state() {
+        let mut test = TemplateTest::new(["tests/templates/access_rules"]);
+
+        let (owner_account, owner_proof, owner_key) = test.create_empty_account();
+
+        let access_rules_template = test.get_template_address("AccessRulesTest");
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "with_caller_state_auth_hook", args![])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+
+        let tokens_resource = result
+            .finalize
+            .result
+            .accept()
+            .unwrap()
+            .up_iter()
+            .filter_map(|(addr, s)| s.substate_value().as_resource().map(|r| (addr, r)))
+            .filter(|(_, r)| r.resource_type().is_fungible())
+            .map(|(addr, _)| addr.as_resource_address().unwrap())
+            .next()
+            .unwrap();
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(access_rules_template, "with_auth_hook", args![true, "valid_auth_hook"])
+                .call_function(access_rules_template, "with_auth_hook", args![false, "valid_auth_hook"])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+
+        let allowed_component = result.finalize.execution_results[0]
+            .decode::<ComponentAddress>()
+            .unwrap();
+        let denied_component = result.finalize.execution_results[1]
+            .decode::<ComponentAddress>()
+            .unwrap();
+
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(allowed_component, "mint_tokens_of", args![tokens_resource, Amount(10)])
+                .put_last_instruction_output_on_workspace("tokens")
+                .call_method(owner_account, "deposit", args![Workspace("tokens")])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof.clone()],
+        );
+
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(denied_component, "mint_tokens_of", args![tokens_resource, Amount(10)])
+                .put_last_instruction_output_on_workspace("tokens")
+                .call_method(owner_account, "deposit", args![Workspace("tokens")])
+                .sign(&owner_key)
+                .build(),
+            vec![owner_proof],
+        );
+
+        assert_reject_reason(reason, RuntimeError::AccessDeniedAuthHook {
+            action_ident: ResourceAuthAction::Mint.into(),
+            details: format!("Panic! Caller {} is not allowed to mint", denied_component),
+        });
+    }
+
     #[test]
     fn it_disallows_hook_that_writes_to_caller_component() {
         let mut test = TemplateTest::new(["tests/templates/access_rules"]);
@@ -1,20 +1,36 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_crypto::{keys::PublicKey, ristretto::RistrettoPublicKey, tari_utilities::ByteArray};
+use tari_crypto::{
+    keys::PublicKey,
+    ristretto::{RistrettoPublicKey, RistrettoSecretKey},
+    tari_utilities::ByteArray,
+};
 use tari_dan_engine::runtime::{ActionIdent, RuntimeError};
-use tari_engine_types::instruction::Instruction;
+use tari_engine_types::{
+    instruction::Instruction,
+    virtual_substate::{VirtualSubstate, VirtualSubstateId},
+};
 use tari_template_lib::{
     args,
-    auth::AccessRule,
+    auth::{AccessRule, RequireRule, RestrictedAccessRule, RuleRequirement},
     constants::XTR,
-    models::{Amount, ComponentAddress, ResourceAddress},
-    prelude::AccessRules,
+    events::account::{
+        DepositEvent,
+        FeePaidEvent,
+        WithdrawEvent,
+        DEPOSIT_EVENT_TOPIC,
+        FEE_PAID_EVENT_TOPIC,
+        WITHDRAW_EVENT_TOPIC,
+    },
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress},
+    prelude::{AccessRules, Metadata},
     rule,
 };
 use tari_template_test_tooling::{
     support::assert_error::{assert_access_denied_for_action, assert_reject_reason},
     test_faucet_component,
+    SubstateType,
     TemplateTest,
 };
 use tari_transaction::Transaction;
@@ -303,3 +319,439 @@ fn custom_access_rules() {
         vec![user2_account_proof],
     );
 }
+
+struct SessionKeyAccount {
+    account: ComponentAddress,
+    owner_proof: NonFungibleAddress,
+    owner_key: RistrettoSecretKey,
+    session_proof: NonFungibleAddress,
+    session_key: RistrettoSecretKey,
+}
+
+/// Creates a funded account from which a session key, in addition to the owner key, may withdraw
+fn create_account_with_session_key(test: &mut TemplateTest) -> SessionKeyAccount {
+    let (owner_proof, owner_public_key, owner_secret_key) = test.create_owner_proof();
+    let (session_proof, _, session_secret_key) = test.create_owner_proof();
+
+    let access_rules = AccessRules::new()
+        .add_method_rule("balance", rule!(allow_all))
+        .add_method_rule("deposit", rule!(allow_all))
+        .add_method_rule("get_spending_limit", rule!(allow_all))
+        .default(rule!(any_of(
+            non_fungible(owner_proof.clone()),
+            non_fungible(session_proof.clone())
+        )));
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(test_faucet_component(), "take_free_coins", args![])
+            .put_last_instruction_output_on_workspace("bucket")
+            .create_account_with_custom_rules(owner_public_key, None, Some(access_rules), Some("bucket"))
+            .sign(&owner_secret_key)
+            .build(),
+        vec![owner_proof.clone()],
+    );
+    SessionKeyAccount {
+        account: result.finalize.execution_results[2].decode().unwrap(),
+        owner_proof,
+        owner_key: owner_secret_key,
+        session_proof,
+        session_key: session_secret_key,
+    }
+}
+
+fn withdraw_transaction(
+    account: ComponentAddress,
+    receiver: ComponentAddress,
+    amount: Amount,
+    key: &RistrettoSecretKey,
+) -> Transaction {
+    Transaction::builder()
+        .call_method(account, "withdraw", args![XTR, amount])
+        .put_last_instruction_output_on_workspace("b")
+        .call_method(receiver, "deposit", args![Workspace("b")])
+        .sign(key)
+        .build()
+}
+
+#[test]
+fn spending_limits() {
+    let mut template_test = TemplateTest::new(Vec::<&str>::new());
+    let SessionKeyAccount {
+        account,
+        owner_proof,
+        owner_key,
+        session_proof,
+        session_key,
+    } = create_account_with_session_key(&mut template_test);
+    let (receiver, _, _) = template_test.create_empty_account();
+
+    let set_limit = |key: &RistrettoSecretKey| {
+        Transaction::builder()
+            .call_method(account, "set_spending_limit", args![XTR, Amount(100), 10u64])
+            .sign(key)
+            .build()
+    };
+
+    // The session key may withdraw but cannot change the spending limits
+    let reason = template_test.execute_expect_failure(set_limit(&session_key), vec![session_proof.clone()]);
+    assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+        component_address: account,
+        method: "set_spending_limit".to_string(),
+    });
+    template_test.execute_expect_success(set_limit(&owner_key), vec![owner_proof.clone()]);
+
+    template_test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(5));
+    template_test.execute_expect_success(withdraw_transaction(account, receiver, Amount(60), &session_key), vec![
+        session_proof.clone(),
+    ]);
+    let reason = template_test
+        .execute_expect_failure(withdraw_transaction(account, receiver, Amount(50), &session_key), vec![
+            session_proof.clone(),
+        ]);
+    assert_reject_reason(
+        reason,
+        format!(
+            "Spending limit of 100 for resource {} exceeded: 60 withdrawn since epoch 0, 50 requested",
+            XTR
+        ),
+    );
+
+    // The limit applies again from the start of the next window
+    template_test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(10));
+    template_test.execute_expect_success(withdraw_transaction(account, receiver, Amount(50), &session_key), vec![
+        session_proof.clone(),
+    ]);
+    let balance: Amount = template_test.call_method(receiver, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(110));
+
+    template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(account, "remove_spending_limit", args![XTR])
+            .sign(&owner_key)
+            .build(),
+        vec![owner_proof],
+    );
+    template_test.execute_expect_success(
+        withdraw_transaction(account, receiver, Amount(500), &session_key),
+        vec![session_proof],
+    );
+}
+
+#[test]
+fn allowances() {
+    let mut template_test = TemplateTest::new(Vec::<&str>::new());
+    let (owner_account, owner_proof, owner_key) = template_test.create_funded_account();
+    let (spender_account, spender_proof, spender_key) = template_test.create_funded_account();
+
+    // The spender holds a badge that is approved to withdraw from the owner account
+    let nft_template = template_test.get_template_address("AccountNonFungible");
+    let result = template_test.execute_expect_success(
+        Transaction::builder()
+            .call_function(nft_template, "create", args![spender_proof.clone()])
+            .sign(&spender_key)
+            .build(),
+        vec![spender_proof.clone()],
+    );
+    let nft_component: ComponentAddress = result.finalize.execution_results[0].decode().unwrap();
+    let badge_resource: ResourceAddress = template_test.call_method(
+        nft_component,
+        "non_fungible_token_get_resource_address",
+        args![],
+        vec![],
+    );
+    let badge_id = NonFungibleId::from_string("spender");
+    template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(nft_component, "mint_specific", args![badge_id, Metadata::new()])
+            .put_last_instruction_output_on_workspace("badge")
+            .call_method(spender_account, "deposit", args![Workspace("badge")])
+            .sign(&spender_key)
+            .build(),
+        vec![spender_proof.clone()],
+    );
+    let spender_token = NonFungibleAddress::new(badge_resource, badge_id);
+
+    let approve = |amount: Amount| {
+        Transaction::builder()
+            .call_method(owner_account, "approve", args![XTR, amount, spender_token])
+            .sign(&owner_key)
+            .build()
+    };
+    let withdraw_from_allowance = |amount: Amount| {
+        Transaction::builder()
+            .call_method(spender_account, "create_proof_for_resource", args![badge_resource])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(owner_account, "withdraw_from_allowance", args![
+                Workspace("proof"),
+                spender_token,
+                XTR,
+                amount
+            ])
+            .put_last_instruction_output_on_workspace("funds")
+            .call_method(spender_account, "deposit", args![Workspace("funds")])
+            .drop_all_proofs_in_workspace()
+            .sign(&spender_key)
+            .build()
+    };
+
+    let reason = template_test.execute_expect_failure(withdraw_from_allowance(Amount(10)), vec![spender_proof.clone()]);
+    assert_reject_reason(reason, format!("No allowance for resource {}", XTR));
+
+    // The owner does not need to sign the withdrawals from the allowance
+    template_test.execute_expect_success(approve(Amount(100)), vec![owner_proof.clone()]);
+    template_test.execute_expect_success(withdraw_from_allowance(Amount(60)), vec![spender_proof.clone()]);
+    let allowance: Amount = template_test.call_method(owner_account, "allowance", args![XTR, spender_token], vec![]);
+    assert_eq!(allowance, Amount(40));
+
+    let reason = template_test.execute_expect_failure(withdraw_from_allowance(Amount(50)), vec![spender_proof.clone()]);
+    assert_reject_reason(
+        reason,
+        format!("Withdraw amount 50 exceeds the allowance 40 of {}", spender_token),
+    );
+
+    // Approving a zero amount revokes the allowance
+    template_test.execute_expect_success(approve(Amount(0)), vec![owner_proof]);
+    let allowance: Amount = template_test.call_method(owner_account, "allowance", args![XTR, spender_token], vec![]);
+    assert_eq!(allowance, Amount(0));
+    let reason = template_test.execute_expect_failure(withdraw_from_allowance(Amount(10)), vec![spender_proof]);
+    assert_reject_reason(reason, format!("No allowance for resource {}", XTR));
+}
+
+#[test]
+fn transfer_many() {
+    let mut template_test = TemplateTest::new(Vec::<&str>::new());
+    let (sender, sender_proof, sender_key) = template_test.create_funded_account();
+    let (alice, _, _) = template_test.create_empty_account();
+    let (bob, _, _) = template_test.create_empty_account();
+
+    let transfer_many = |transfers: Vec<(ResourceAddress, Amount, ComponentAddress)>| {
+        Transaction::builder()
+            .call_method(sender, "transfer_many", args![transfers])
+            .sign(&sender_key)
+            .build()
+    };
+
+    template_test.execute_expect_success(
+        transfer_many(vec![
+            (XTR, Amount(100), alice),
+            (XTR, Amount(200), bob),
+            (XTR, Amount(50), alice),
+        ]),
+        vec![sender_proof.clone()],
+    );
+    let balance: Amount = template_test.call_method(alice, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(150));
+    let balance: Amount = template_test.call_method(bob, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(200));
+
+    let reason =
+        template_test.execute_expect_failure(transfer_many(vec![(XTR, Amount(10), sender)]), vec![sender_proof]);
+    assert_reject_reason(reason, "Cannot transfer to the account itself");
+}
+
+#[test]
+fn deposit_filter() {
+    let mut template_test = TemplateTest::new(Vec::<&str>::new());
+    let (account, owner_proof, owner_key) = template_test.create_funded_account();
+    let (sender, sender_proof, sender_key) = template_test.create_funded_account();
+    let faucet: ComponentAddress = template_test.call_function(
+        "TestFaucet",
+        "mint_with_symbol",
+        args![Amount(1_000_000), "SPAM"],
+        vec![],
+    );
+    let spam_resource = template_test
+        .get_previous_output_address(SubstateType::Resource)
+        .as_resource_address()
+        .unwrap();
+
+    let set_deposit_filter = |allowed_resources: Vec<ResourceAddress>, deny_unknown: bool| {
+        Transaction::builder()
+            .call_method(account, "set_deposit_filter", args![allowed_resources, deny_unknown])
+            .sign(&owner_key)
+            .build()
+    };
+    let send_spam = Transaction::builder()
+        .call_method(faucet, "take_free_coins", args![])
+        .put_last_instruction_output_on_workspace("spam")
+        .call_method(account, "deposit", args![Workspace("spam")])
+        .sign(&sender_key)
+        .build();
+    let send_xtr = withdraw_transaction(sender, account, Amount(10), &sender_key);
+
+    // Resources for which the account does not have a vault are rejected
+    template_test.execute_expect_success(set_deposit_filter(vec![], true), vec![owner_proof.clone()]);
+    let reason = template_test.execute_expect_failure(send_spam.clone(), vec![sender_proof.clone()]);
+    assert_reject_reason(
+        reason,
+        format!(
+            "Deposits of resource {} are not accepted by this account",
+            spam_resource
+        ),
+    );
+    template_test.execute_expect_success(send_xtr.clone(), vec![sender_proof.clone()]);
+
+    // Only allowed resources are accepted
+    template_test.execute_expect_success(set_deposit_filter(vec![spam_resource], false), vec![owner_proof]);
+    template_test.execute_expect_success(send_spam, vec![sender_proof.clone()]);
+    let reason = template_test.execute_expect_failure(send_xtr, vec![sender_proof]);
+    assert_reject_reason(
+        reason,
+        format!("Deposits of resource {} are not accepted by this account", XTR),
+    );
+}
+
+#[test]
+fn social_recovery() {
+    let mut template_test = TemplateTest::new(Vec::<&str>::new());
+    let (account, owner_proof, owner_key) = template_test.create_funded_account();
+    let (receiver, _, _) = template_test.create_empty_account();
+    let (guardian1_account, guardian1_proof, guardian1_key) = template_test.create_funded_account();
+    let (guardian2_account, guardian2_proof, guardian2_key) = template_test.create_funded_account();
+    let (new_owner_proof, _, new_owner_key) = template_test.create_owner_proof();
+
+    // The first guardian mints a guardian badge for each guardian
+    let nft_template = template_test.get_template_address("AccountNonFungible");
+    let result = template_test.execute_expect_success(
+        Transaction::builder()
+            .call_function(nft_template, "create", args![guardian1_proof.clone()])
+            .sign(&guardian1_key)
+            .build(),
+        vec![guardian1_proof.clone()],
+    );
+    let nft_component: ComponentAddress = result.finalize.execution_results[0].decode().unwrap();
+    let badge_resource: ResourceAddress = template_test.call_method(
+        nft_component,
+        "non_fungible_token_get_resource_address",
+        args![],
+        vec![],
+    );
+    let guardian1 = NonFungibleAddress::new(badge_resource, NonFungibleId::from_u32(1));
+    let guardian2 = NonFungibleAddress::new(badge_resource, NonFungibleId::from_u32(2));
+    template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(nft_component, "mint_specific", args![guardian1.id(), Metadata::new()])
+            .put_last_instruction_output_on_workspace("badge1")
+            .call_method(guardian1_account, "deposit", args![Workspace("badge1")])
+            .call_method(nft_component, "mint_specific", args![guardian2.id(), Metadata::new()])
+            .put_last_instruction_output_on_workspace("badge2")
+            .call_method(guardian2_account, "deposit", args![Workspace("badge2")])
+            .sign(&guardian1_key)
+            .build(),
+        vec![guardian1_proof.clone()],
+    );
+
+    template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(account, "set_guardians", args![vec![guardian1, guardian2], 2u32, 5u64])
+            .sign(&owner_key)
+            .build(),
+        vec![owner_proof.clone()],
+    );
+
+    let approve_recovery = |guardian_account: ComponentAddress, guardian_key: &RistrettoSecretKey| {
+        Transaction::builder()
+            .call_method(guardian_account, "create_proof_for_resource", args![badge_resource])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(account, "approve_recovery", args![Workspace("proof"), new_owner_proof])
+            .drop_all_proofs_in_workspace()
+            .sign(guardian_key)
+            .build()
+    };
+    let complete_recovery = Transaction::builder()
+        .call_method(account, "complete_recovery", args![new_owner_proof])
+        .sign(&guardian1_key)
+        .build();
+
+    // The owner may cancel a pending recovery
+    template_test.execute_expect_success(approve_recovery(guardian1_account, &guardian1_key), vec![
+        guardian1_proof.clone(),
+    ]);
+    let reason = template_test.execute_expect_failure(complete_recovery.clone(), vec![guardian1_proof.clone()]);
+    assert_reject_reason(
+        reason,
+        format!("Recovery to {} has not been approved by 2 guardians", new_owner_proof),
+    );
+    template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(account, "cancel_recovery", args![])
+            .sign(&owner_key)
+            .build(),
+        vec![owner_proof.clone()],
+    );
+
+    template_test.execute_expect_success(approve_recovery(guardian1_account, &guardian1_key), vec![
+        guardian1_proof.clone(),
+    ]);
+    template_test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(1));
+    template_test.execute_expect_success(approve_recovery(guardian2_account, &guardian2_key), vec![
+        guardian2_proof,
+    ]);
+    let reason = template_test.execute_expect_failure(complete_recovery.clone(), vec![guardian1_proof.clone()]);
+    assert_reject_reason(
+        reason,
+        format!("Recovery to {} may not be completed before epoch 6", new_owner_proof),
+    );
+
+    template_test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(6));
+    template_test.execute_expect_success(complete_recovery, vec![guardian1_proof]);
+
+    // Only the new owner may withdraw
+    let reason = template_test
+        .execute_expect_failure(withdraw_transaction(account, receiver, Amount(10), &owner_key), vec![
+            owner_proof,
+        ]);
+    assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+        component_address: account,
+        method: "withdraw".to_string(),
+    });
+    template_test.execute_expect_success(
+        withdraw_transaction(account, receiver, Amount(10), &new_owner_key),
+        vec![new_owner_proof],
+    );
+}
+
+#[test]
+fn it_emits_typed_balance_events() {
+    let mut test = TemplateTest::new::<_, &str>([]);
+    test.enable_fees();
+
+    let (sender, sender_proof, sender_key) = test.create_funded_account();
+    let (receiver, _, _) = test.create_empty_account();
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(sender, Amount(1000))
+            .call_method(sender, "withdraw", args![XTR, Amount(100)])
+            .put_last_instruction_output_on_workspace("b")
+            .call_method(receiver, "deposit", args![Workspace("b")])
+            .sign(&sender_key)
+            .build(),
+        vec![sender_proof],
+    );
+
+    let events = &result.finalize.events;
+    let event = events.iter().find(|e| e.topic() == WITHDRAW_EVENT_TOPIC).unwrap();
+    assert_eq!(event.substate_id(), Some(sender.into()));
+    assert_eq!(event.decode_data::<WithdrawEvent>().unwrap().unwrap(), WithdrawEvent {
+        resource_address: XTR,
+        amount: Amount(100),
+    });
+    assert_eq!(event.get_payload("resource").unwrap(), XTR.to_string());
+    assert_eq!(event.get_payload("amount").unwrap(), "100");
+
+    let event = events.iter().find(|e| e.topic() == DEPOSIT_EVENT_TOPIC).unwrap();
+    assert_eq!(event.substate_id(), Some(receiver.into()));
+    assert_eq!(event.decode_data::<DepositEvent>().unwrap().unwrap(), DepositEvent {
+        resource_address: XTR,
+        amount: Amount(100),
+    });
+    assert_eq!(event.get_payload("resource").unwrap(), XTR.to_string());
+
+    let event = events.iter().find(|e| e.topic() == FEE_PAID_EVENT_TOPIC).unwrap();
+    assert_eq!(event.substate_id(), Some(sender.into()));
+    assert_eq!(event.decode_data::<FeePaidEvent>().unwrap().unwrap(), FeePaidEvent {
+        amount: Amount(1000),
+    });
+}
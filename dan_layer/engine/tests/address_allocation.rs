@@ -1,8 +1,12 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_engine::runtime::TransactionCommitError;
-use tari_template_lib::{args, models::ComponentAddress};
+use tari_dan_engine::runtime::{RuntimeError, TransactionCommitError};
+use tari_engine_types::named_address::{new_named_component_address, new_named_resource_address};
+use tari_template_lib::{
+    args,
+    models::{ComponentAddress, ResourceAddress},
+};
 use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
 use tari_transaction::Transaction;
 
@@ -50,3 +54,65 @@ fn it_fails_if_allocation_is_not_used() {
 
     assert_reject_reason(reason, TransactionCommitError::DanglingAddressAllocations { count: 1 });
 }
+
+#[test]
+fn it_creates_components_at_reserved_addresses() {
+    let mut test = TemplateTest::new(["tests/templates/address_allocation"]);
+    let template_addr = test.get_template_address("AddressAllocationTest");
+
+    let transaction = Transaction::builder()
+        .with_reserved_outputs(2)
+        .call_function(template_addr, "create_reserved", args![1u32])
+        .sign(test.get_test_secret_key())
+        .build();
+    let reserved = transaction.reserved_output_addresses().collect::<Vec<_>>();
+    assert_eq!(reserved.len(), 2);
+
+    let result = test.execute_expect_success(transaction, vec![]);
+    let actual = result
+        .finalize
+        .result
+        .accept()
+        .unwrap()
+        .up_iter()
+        .find_map(|(k, _)| k.as_component_address())
+        .unwrap();
+    assert_eq!(actual, reserved[1]);
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .with_reserved_outputs(2)
+            .call_function(template_addr, "create_reserved", args![2u32])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "Reserved output index 2 out of range");
+}
+
+#[test]
+fn it_creates_substates_at_named_addresses() {
+    let mut test = TemplateTest::new(["tests/templates/address_allocation"]);
+    let template_addr = test.get_template_address("AddressAllocationTest");
+
+    let (component, resource): (ComponentAddress, ResourceAddress) =
+        test.call_function("AddressAllocationTest", "create_named", args!["pool"], vec![]);
+    assert_eq!(
+        component,
+        new_named_component_address(&template_addr, test.get_test_public_key(), "pool")
+    );
+    assert_eq!(
+        resource,
+        new_named_resource_address(&template_addr, test.get_test_public_key(), "pool")
+    );
+
+    // The same name cannot be used twice by the same creator
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(template_addr, "create_named", args!["pool"])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, RuntimeError::ResourceAlreadyExists { address: resource });
+}
@@ -0,0 +1,192 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_template_builtin::AMM_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    models::{Amount, ComponentAddress, NonFungibleAddress, ResourceAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, SubstateType, TemplateTest};
+use tari_transaction::Transaction;
+
+struct AmmTest {
+    test: TemplateTest,
+    pool: ComponentAddress,
+    a_resource: ResourceAddress,
+    b_resource: ResourceAddress,
+    lp_resource: ResourceAddress,
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+impl AmmTest {
+    fn setup(fee: u16) -> Self {
+        let mut test = TemplateTest::new(Vec::<&str>::new());
+        let (account, proof, key) = test.create_funded_account();
+        let (a_faucet, a_resource) = create_faucet(&mut test, "A");
+        let (b_faucet, b_resource) = create_faucet(&mut test, "B");
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_method(a_faucet, "take_free_coins", args![])
+                .put_last_instruction_output_on_workspace("a")
+                .call_method(b_faucet, "take_free_coins", args![])
+                .put_last_instruction_output_on_workspace("b")
+                .call_method(account, "deposit", args![Workspace("a")])
+                .call_method(account, "deposit", args![Workspace("b")])
+                .call_function(AMM_TEMPLATE_ADDRESS, "new", args![a_resource, b_resource, fee])
+                .sign(&key)
+                .build(),
+            vec![proof.clone()],
+        );
+        let pool: ComponentAddress = result.finalize.execution_results[6].decode().unwrap();
+        let lp_resource: ResourceAddress = test.extract_component_value(pool, "$.lp_resource");
+
+        Self {
+            test,
+            pool,
+            a_resource,
+            b_resource,
+            lp_resource,
+            account,
+            proof,
+            key,
+        }
+    }
+
+    fn add_liquidity_transaction(&self, a_amount: Amount, b_amount: Amount) -> Transaction {
+        Transaction::builder()
+            .call_method(self.account, "withdraw", args![self.a_resource, a_amount])
+            .put_last_instruction_output_on_workspace("a")
+            .call_method(self.account, "withdraw", args![self.b_resource, b_amount])
+            .put_last_instruction_output_on_workspace("b")
+            .call_method(self.pool, "add_liquidity", args![Workspace("a"), Workspace("b")])
+            .put_last_instruction_output_on_workspace("liquidity")
+            .call_method(self.account, "deposit", args![Workspace("liquidity.0")])
+            .call_method(self.account, "deposit", args![Workspace("liquidity.1")])
+            .call_method(self.account, "deposit", args![Workspace("liquidity.2")])
+            .sign(&self.key)
+            .build()
+    }
+
+    fn add_liquidity(&mut self, a_amount: Amount, b_amount: Amount) {
+        let transaction = self.add_liquidity_transaction(a_amount, b_amount);
+        self.test.execute_expect_success(transaction, vec![self.proof.clone()]);
+    }
+
+    fn swap_transaction(&self, input_resource: ResourceAddress, amount: Amount, min_output: Amount) -> Transaction {
+        Transaction::builder()
+            .call_method(self.account, "withdraw", args![input_resource, amount])
+            .put_last_instruction_output_on_workspace("input")
+            .call_method(self.pool, "swap", args![Workspace("input"), min_output])
+            .put_last_instruction_output_on_workspace("output")
+            .call_method(self.account, "deposit", args![Workspace("output")])
+            .sign(&self.key)
+            .build()
+    }
+
+    fn balance(&mut self, resource: ResourceAddress) -> Amount {
+        self.test.call_method(self.account, "balance", args![resource], vec![])
+    }
+
+    fn reserves(&mut self) -> (Amount, Amount) {
+        self.test.call_method(self.pool, "reserves", args![], vec![])
+    }
+}
+
+fn create_faucet(test: &mut TemplateTest, symbol: &str) -> (ComponentAddress, ResourceAddress) {
+    let faucet: ComponentAddress = test.call_function(
+        "TestFaucet",
+        "mint_with_symbol",
+        args![Amount(1_000_000), symbol],
+        vec![],
+    );
+    let resource = test
+        .get_previous_output_address(SubstateType::Resource)
+        .as_resource_address()
+        .unwrap();
+    (faucet, resource)
+}
+
+#[test]
+fn it_adds_and_removes_liquidity() {
+    let mut amm = AmmTest::setup(30);
+
+    // The first provider sets the price and receives sqrt(a * b) LP tokens
+    amm.add_liquidity(Amount(400), Amount(100));
+    let lp_resource = amm.lp_resource;
+    assert_eq!(amm.balance(lp_resource), Amount(200));
+
+    // Subsequent providers receive LP tokens in proportion to the reserves, the excess B is returned
+    amm.add_liquidity(Amount(200), Amount(100));
+    assert_eq!(amm.balance(lp_resource), Amount(300));
+    assert_eq!(amm.reserves(), (Amount(600), Amount(150)));
+    let b_resource = amm.b_resource;
+    assert_eq!(amm.balance(b_resource), Amount(850));
+
+    amm.test.execute_expect_success(
+        Transaction::builder()
+            .call_method(amm.account, "withdraw", args![lp_resource, Amount(150)])
+            .put_last_instruction_output_on_workspace("lp")
+            .call_method(amm.pool, "remove_liquidity", args![Workspace("lp")])
+            .put_last_instruction_output_on_workspace("funds")
+            .call_method(amm.account, "deposit", args![Workspace("funds.0")])
+            .call_method(amm.account, "deposit", args![Workspace("funds.1")])
+            .sign(&amm.key)
+            .build(),
+        vec![amm.proof.clone()],
+    );
+
+    assert_eq!(amm.reserves(), (Amount(300), Amount(75)));
+    let a_resource = amm.a_resource;
+    assert_eq!(amm.balance(a_resource), Amount(700));
+    assert_eq!(amm.balance(b_resource), Amount(925));
+    let lp_supply: Amount = amm.test.call_method(amm.pool, "lp_total_supply", args![], vec![]);
+    assert_eq!(lp_supply, Amount(150));
+}
+
+#[test]
+fn it_swaps_with_fee() {
+    let mut amm = AmmTest::setup(30);
+    amm.add_liquidity(Amount(500), Amount(500));
+    let a_resource = amm.a_resource;
+    let b_resource = amm.b_resource;
+
+    // 100 A less the 0.3% fee is 99, which returns 500 * 99 / (500 + 99) = 82 B
+    let quote: Amount = amm
+        .test
+        .call_method(amm.pool, "quote", args![a_resource, Amount(100)], vec![]);
+    assert_eq!(quote, Amount(82));
+
+    let reason = amm
+        .test
+        .execute_expect_failure(amm.swap_transaction(a_resource, Amount(100), Amount(83)), vec![amm
+            .proof
+            .clone()]);
+    assert_reject_reason(reason, "Swap output 82 is less than the minimum output 83");
+
+    let transaction = amm.swap_transaction(a_resource, Amount(100), Amount(82));
+    let result = amm.test.execute_expect_success(transaction, vec![amm.proof.clone()]);
+    assert!(result.finalize.events.iter().any(|e| e.topic() == "swap"));
+
+    assert_eq!(amm.reserves(), (Amount(600), Amount(418)));
+    assert_eq!(amm.balance(a_resource), Amount(400));
+    assert_eq!(amm.balance(b_resource), Amount(582));
+}
+
+#[test]
+fn it_rejects_invalid_pools() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (_, resource) = create_faucet(&mut test, "A");
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(AMM_TEMPLATE_ADDRESS, "new", args![resource, resource, 30u16])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "The resources of the pair must be different");
+}
@@ -0,0 +1,243 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
+use tari_template_builtin::{ACCOUNT_NFT_TEMPLATE_ADDRESS, AUCTION_TEMPLATE_ADDRESS};
+use tari_template_lib::{
+    args,
+    constants::XTR,
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress},
+    prelude::Metadata,
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, SubstateType, TemplateTest};
+use tari_transaction::Transaction;
+
+/// Mirrors the AuctionStatus type of the auction template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum AuctionStatus {
+    Open,
+    Sold { buyer: ComponentAddress, price: Amount },
+    Unsold,
+}
+
+struct Party {
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+impl Party {
+    fn new(test: &mut TemplateTest) -> Self {
+        let (account, proof, key) = test.create_funded_account();
+        Self { account, proof, key }
+    }
+}
+
+/// Mints a single NFT into the seller account, returning the NFT resource
+fn mint_nft(test: &mut TemplateTest, seller: &Party) -> ResourceAddress {
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_function(ACCOUNT_NFT_TEMPLATE_ADDRESS, "create", args![seller.proof])
+            .sign(&seller.key)
+            .build(),
+        vec![seller.proof.clone()],
+    );
+    let nft_component: ComponentAddress = result.finalize.execution_results[0].decode().unwrap();
+
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(nft_component, "mint_specific", args![
+                NonFungibleId::from_u32(1),
+                Metadata::new()
+            ])
+            .put_last_instruction_output_on_workspace("nft")
+            .call_method(seller.account, "deposit", args![Workspace("nft")])
+            .sign(&seller.key)
+            .build(),
+        vec![seller.proof.clone()],
+    );
+    test.extract_component_value(nft_component, "$.resource_address")
+}
+
+fn create_english_auction(test: &mut TemplateTest, seller: &Party, nft_resource: ResourceAddress) -> ComponentAddress {
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(seller.account, "withdraw", args![nft_resource, Amount(1)])
+            .put_last_instruction_output_on_workspace("lot")
+            .call_function(AUCTION_TEMPLATE_ADDRESS, "new_english", args![
+                seller.account,
+                Workspace("lot"),
+                XTR,
+                Amount(100),
+                Amount(10),
+                10u64
+            ])
+            .sign(&seller.key)
+            .build(),
+        vec![seller.proof.clone()],
+    );
+    result.finalize.execution_results[2].decode().unwrap()
+}
+
+fn bid_transaction(auction: ComponentAddress, bidder: &Party, amount: Amount) -> Transaction {
+    Transaction::builder()
+        .call_method(bidder.account, "withdraw", args![XTR, amount])
+        .put_last_instruction_output_on_workspace("bid")
+        .call_method(auction, "bid", args![bidder.account, Workspace("bid")])
+        .sign(&bidder.key)
+        .build()
+}
+
+fn settle_transaction(test: &TemplateTest, auction: ComponentAddress) -> Transaction {
+    Transaction::builder()
+        .call_method(auction, "settle", args![])
+        .sign(test.get_test_secret_key())
+        .build()
+}
+
+fn balance(test: &mut TemplateTest, account: ComponentAddress, resource: ResourceAddress) -> Amount {
+    test.call_method(account, "balance", args![resource], vec![])
+}
+
+#[test]
+fn it_sells_the_lot_to_the_highest_bidder() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let seller = Party::new(&mut test);
+    let bidder1 = Party::new(&mut test);
+    let bidder2 = Party::new(&mut test);
+    let nft_resource = mint_nft(&mut test, &seller);
+    let auction = create_english_auction(&mut test, &seller, nft_resource);
+    let seller_balance = balance(&mut test, seller.account, XTR);
+    let bidder1_balance = balance(&mut test, bidder1.account, XTR);
+    let bidder2_balance = balance(&mut test, bidder2.account, XTR);
+
+    let reason = test.execute_expect_failure(bid_transaction(auction, &bidder1, Amount(50)), vec![bidder1
+        .proof
+        .clone()]);
+    assert_reject_reason(reason, "Bid 50 is less than the required bid 100");
+
+    test.execute_expect_success(bid_transaction(auction, &bidder1, Amount(100)), vec![bidder1
+        .proof
+        .clone()]);
+    assert_eq!(balance(&mut test, bidder1.account, XTR), bidder1_balance - Amount(100));
+
+    let reason = test.execute_expect_failure(bid_transaction(auction, &bidder2, Amount(105)), vec![bidder2
+        .proof
+        .clone()]);
+    assert_reject_reason(reason, "Bid 105 is less than the required bid 110");
+
+    // The outbid bidder is refunded
+    test.execute_expect_success(bid_transaction(auction, &bidder2, Amount(120)), vec![bidder2
+        .proof
+        .clone()]);
+    assert_eq!(balance(&mut test, bidder1.account, XTR), bidder1_balance);
+
+    let reason = test.execute_expect_failure(settle_transaction(&test, auction), vec![]);
+    assert_reject_reason(reason, "Auction is open until epoch 10");
+
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(10));
+    let reason = test.execute_expect_failure(bid_transaction(auction, &bidder1, Amount(200)), vec![bidder1
+        .proof
+        .clone()]);
+    assert_reject_reason(reason, "Auction ended at epoch 10");
+
+    test.execute_expect_success(settle_transaction(&test, auction), vec![]);
+
+    let status: AuctionStatus = test.call_method(auction, "status", args![], vec![]);
+    assert_eq!(status, AuctionStatus::Sold {
+        buyer: bidder2.account,
+        price: Amount(120),
+    });
+    assert_eq!(balance(&mut test, bidder2.account, nft_resource), Amount(1));
+    assert_eq!(balance(&mut test, bidder2.account, XTR), bidder2_balance - Amount(120));
+    assert_eq!(balance(&mut test, seller.account, XTR), seller_balance + Amount(120));
+}
+
+#[test]
+fn it_returns_an_unsold_lot_to_the_seller() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let seller = Party::new(&mut test);
+    let nft_resource = mint_nft(&mut test, &seller);
+    let auction = create_english_auction(&mut test, &seller, nft_resource);
+    assert_eq!(balance(&mut test, seller.account, nft_resource), Amount(0));
+
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(10));
+    test.execute_expect_success(settle_transaction(&test, auction), vec![]);
+
+    let status: AuctionStatus = test.call_method(auction, "status", args![], vec![]);
+    assert_eq!(status, AuctionStatus::Unsold);
+    assert_eq!(balance(&mut test, seller.account, nft_resource), Amount(1));
+
+    let reason = test.execute_expect_failure(settle_transaction(&test, auction), vec![]);
+    assert_reject_reason(reason, "Auction has already been settled");
+}
+
+#[test]
+fn it_sells_at_the_current_price_in_a_dutch_auction() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let seller = Party::new(&mut test);
+    let buyer = Party::new(&mut test);
+
+    let faucet: ComponentAddress = test.call_function(
+        "TestFaucet",
+        "mint_with_symbol",
+        args![Amount(1_000_000), "LOT"],
+        vec![],
+    );
+    let lot_resource = test
+        .get_previous_output_address(SubstateType::Resource)
+        .as_resource_address()
+        .unwrap();
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(faucet, "take_free_coins", args![])
+            .put_last_instruction_output_on_workspace("lot")
+            .call_function(AUCTION_TEMPLATE_ADDRESS, "new_dutch", args![
+                seller.account,
+                Workspace("lot"),
+                XTR,
+                Amount(1000),
+                Amount(200),
+                8u64
+            ])
+            .sign(&seller.key)
+            .build(),
+        vec![seller.proof.clone()],
+    );
+    let auction: ComponentAddress = result.finalize.execution_results[2].decode().unwrap();
+    let seller_balance = balance(&mut test, seller.account, XTR);
+    let buyer_balance = balance(&mut test, buyer.account, XTR);
+
+    // The price falls from 1000 to 200 over 8 epochs
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(4));
+    let price: Amount = test.call_method(auction, "current_price", args![], vec![]);
+    assert_eq!(price, Amount(600));
+
+    let buy_transaction = |amount: Amount| {
+        Transaction::builder()
+            .call_method(buyer.account, "withdraw", args![XTR, amount])
+            .put_last_instruction_output_on_workspace("payment")
+            .call_method(auction, "buy", args![buyer.account, Workspace("payment")])
+            .put_last_instruction_output_on_workspace("change")
+            .call_method(buyer.account, "deposit", args![Workspace("change")])
+            .sign(&buyer.key)
+            .build()
+    };
+
+    let reason = test.execute_expect_failure(buy_transaction(Amount(500)), vec![buyer.proof.clone()]);
+    assert_reject_reason(reason, "Payment 500 is less than the current price 600");
+
+    test.execute_expect_success(buy_transaction(Amount(700)), vec![buyer.proof.clone()]);
+
+    let status: AuctionStatus = test.call_method(auction, "status", args![], vec![]);
+    assert_eq!(status, AuctionStatus::Sold {
+        buyer: buyer.account,
+        price: Amount(600),
+    });
+    assert_eq!(balance(&mut test, buyer.account, lot_resource), Amount(1000));
+    assert_eq!(balance(&mut test, buyer.account, XTR), buyer_balance - Amount(600));
+    assert_eq!(balance(&mut test, seller.account, XTR), seller_balance + Amount(600));
+}
@@ -11,7 +11,10 @@ use tari_template_lib::{
     models::{ComponentAddress, TemplateAddress},
     prelude::{Amount, ResourceAddress},
 };
-use tari_template_test_tooling::{support::assert_error::assert_access_denied_for_action, TemplateTest};
+use tari_template_test_tooling::{
+    support::assert_error::{assert_access_denied_for_action, assert_reject_reason},
+    TemplateTest,
+};
 use tari_transaction::Transaction;
 
 struct ComposabilityTest {
@@ -368,3 +371,22 @@ fn it_fails_when_surpassing_recursion_limit() {
     // CallInvoke" we should be able to assert a more specific error cause
     assert!(matches!(reason, RejectReason::ExecutionFailure(_)));
 }
+
+#[test]
+fn it_calls_components_through_an_interface() {
+    let mut test = TemplateTest::new(vec!["tests/templates/interface", "tests/templates/state"]);
+    let state: ComponentAddress = test.call_function("State", "new", args![], vec![]);
+
+    let value: u32 = test.call_function("InterfaceCaller", "set_and_get", args![state, 123u32], vec![]);
+    assert_eq!(value, 123);
+
+    let interface_template = test.get_template_address("InterfaceCaller");
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(interface_template, "get_mismatched", args![state])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "does not match the interface for method get");
+}
@@ -0,0 +1,252 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
+use tari_template_builtin::ESCROW_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    constants::XTR,
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+/// Mirrors the EscrowStatus type of the escrow template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum EscrowStatus {
+    AwaitingDeposit,
+    Funded { dispute_deadline_epoch: u64 },
+    Disputed,
+    Released,
+    Refunded,
+    Closed,
+}
+
+struct Party {
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+struct EscrowTest {
+    test: TemplateTest,
+    escrow: ComponentAddress,
+    badges: ResourceAddress,
+    buyer: Party,
+    seller: Party,
+    arbiter: Party,
+}
+
+impl EscrowTest {
+    fn setup() -> Self {
+        let mut test = TemplateTest::new(Vec::<&str>::new());
+        let (account, proof, key) = test.create_funded_account();
+        let buyer = Party { account, proof, key };
+        let (account, proof, key) = test.create_empty_account();
+        let seller = Party { account, proof, key };
+        let (account, proof, key) = test.create_empty_account();
+        let arbiter = Party { account, proof, key };
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(ESCROW_TEMPLATE_ADDRESS, "new", args![XTR, Amount(100), 5u64, true])
+                .put_last_instruction_output_on_workspace("escrow")
+                .call_method(buyer.account, "deposit", args![Workspace("escrow.1")])
+                .sign(&buyer.key)
+                .build(),
+            vec![buyer.proof.clone()],
+        );
+        let escrow: ComponentAddress = result.finalize.execution_results[0].get_value("$.0").unwrap().unwrap();
+        let badges: ResourceAddress = test.extract_component_value(escrow, "$.badges");
+
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(buyer.account, "withdraw_non_fungible", args![
+                    badges,
+                    NonFungibleId::from_string("seller")
+                ])
+                .put_last_instruction_output_on_workspace("seller_badge")
+                .call_method(seller.account, "deposit", args![Workspace("seller_badge")])
+                .call_method(buyer.account, "withdraw_non_fungible", args![
+                    badges,
+                    NonFungibleId::from_string("arbiter")
+                ])
+                .put_last_instruction_output_on_workspace("arbiter_badge")
+                .call_method(arbiter.account, "deposit", args![Workspace("arbiter_badge")])
+                .sign(&buyer.key)
+                .build(),
+            vec![buyer.proof.clone()],
+        );
+
+        Self {
+            test,
+            escrow,
+            badges,
+            buyer,
+            seller,
+            arbiter,
+        }
+    }
+
+    fn deposit(&mut self) {
+        let buyer = &self.buyer;
+        self.test.execute_expect_success(
+            Transaction::builder()
+                .call_method(buyer.account, "create_proof_for_resource", args![self.badges])
+                .put_last_instruction_output_on_workspace("proof")
+                .call_method(buyer.account, "withdraw", args![XTR, Amount(100)])
+                .put_last_instruction_output_on_workspace("funds")
+                .call_method(self.escrow, "deposit", args![Workspace("proof"), Workspace("funds")])
+                .drop_all_proofs_in_workspace()
+                .sign(&buyer.key)
+                .build(),
+            vec![buyer.proof.clone()],
+        );
+    }
+
+    /// Builds a transaction in which the party calls a method on the escrow with their badge proof, depositing any
+    /// returned funds into their account
+    fn party_call(&self, party: &Party, method: &str, returns_funds: bool) -> Transaction {
+        let mut builder = Transaction::builder()
+            .call_method(party.account, "create_proof_for_resource", args![self.badges])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(self.escrow, method, args![Workspace("proof")]);
+        if returns_funds {
+            builder =
+                builder
+                    .put_last_instruction_output_on_workspace("funds")
+                    .call_method(party.account, "deposit", args![Workspace("funds")]);
+        }
+        builder.drop_all_proofs_in_workspace().sign(&party.key).build()
+    }
+
+    fn status(&mut self) -> EscrowStatus {
+        self.test.call_method(self.escrow, "status", args![], vec![])
+    }
+
+    fn balance(&mut self, account: ComponentAddress) -> Amount {
+        self.test.call_method(account, "balance", args![XTR], vec![])
+    }
+}
+
+#[test]
+fn it_releases_funds_to_the_seller() {
+    let mut escrow = EscrowTest::setup();
+    escrow.deposit();
+    assert_eq!(escrow.status(), EscrowStatus::Funded {
+        dispute_deadline_epoch: 5
+    });
+
+    // The seller cannot release the funds
+    let reason = escrow
+        .test
+        .execute_expect_failure(escrow.party_call(&escrow.seller, "release", false), vec![escrow
+            .seller
+            .proof
+            .clone()]);
+    assert_reject_reason(reason, "Proof does not contain the buyer badge");
+
+    escrow
+        .test
+        .execute_expect_success(escrow.party_call(&escrow.buyer, "release", false), vec![escrow
+            .buyer
+            .proof
+            .clone()]);
+    escrow
+        .test
+        .execute_expect_success(escrow.party_call(&escrow.seller, "claim_payment", true), vec![escrow
+            .seller
+            .proof
+            .clone()]);
+
+    assert_eq!(escrow.status(), EscrowStatus::Closed);
+    let seller_account = escrow.seller.account;
+    assert_eq!(escrow.balance(seller_account), Amount(100));
+}
+
+#[test]
+fn it_allows_the_seller_to_claim_after_the_dispute_window() {
+    let mut escrow = EscrowTest::setup();
+    escrow.deposit();
+
+    let reason = escrow
+        .test
+        .execute_expect_failure(escrow.party_call(&escrow.seller, "claim_payment", true), vec![escrow
+            .seller
+            .proof
+            .clone()]);
+    assert_reject_reason(reason, "Dispute window is open until epoch 5");
+
+    escrow
+        .test
+        .set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(5));
+
+    // The buyer can no longer dispute
+    let reason = escrow
+        .test
+        .execute_expect_failure(escrow.party_call(&escrow.buyer, "dispute", false), vec![escrow
+            .buyer
+            .proof
+            .clone()]);
+    assert_reject_reason(reason, "Dispute window closed at epoch 5");
+
+    escrow
+        .test
+        .execute_expect_success(escrow.party_call(&escrow.seller, "claim_payment", true), vec![escrow
+            .seller
+            .proof
+            .clone()]);
+    let seller_account = escrow.seller.account;
+    assert_eq!(escrow.balance(seller_account), Amount(100));
+}
+
+#[test]
+fn it_refunds_the_buyer_when_the_arbiter_resolves_a_dispute() {
+    let mut escrow = EscrowTest::setup();
+    escrow.deposit();
+    let buyer_account = escrow.buyer.account;
+    let balance_after_deposit = escrow.balance(buyer_account);
+
+    escrow
+        .test
+        .execute_expect_success(escrow.party_call(&escrow.buyer, "dispute", false), vec![escrow
+            .buyer
+            .proof
+            .clone()]);
+    assert_eq!(escrow.status(), EscrowStatus::Disputed);
+
+    // The dispute blocks the seller from claiming, even after the dispute window
+    escrow
+        .test
+        .set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(10));
+    let reason = escrow
+        .test
+        .execute_expect_failure(escrow.party_call(&escrow.seller, "claim_payment", true), vec![escrow
+            .seller
+            .proof
+            .clone()]);
+    assert_reject_reason(reason, "Cannot claim payment from escrow with status Disputed");
+
+    let arbiter = &escrow.arbiter;
+    escrow.test.execute_expect_success(
+        Transaction::builder()
+            .call_method(arbiter.account, "create_proof_for_resource", args![escrow.badges])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(escrow.escrow, "resolve", args![Workspace("proof"), false])
+            .drop_all_proofs_in_workspace()
+            .sign(&arbiter.key)
+            .build(),
+        vec![arbiter.proof.clone()],
+    );
+    assert_eq!(escrow.status(), EscrowStatus::Refunded);
+
+    escrow
+        .test
+        .execute_expect_success(escrow.party_call(&escrow.buyer, "claim_refund", true), vec![escrow
+            .buyer
+            .proof
+            .clone()]);
+    assert_eq!(escrow.balance(buyer_account), balance_after_deposit + Amount(100));
+}
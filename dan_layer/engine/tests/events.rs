@@ -37,6 +37,92 @@ fn basic_emit_event() {
     );
 }
 
+#[test]
+fn emit_typed_event() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Transfer {
+        amount: Amount,
+        memo: String,
+    }
+
+    let mut template_test = TemplateTest::new(vec!["tests/templates/events"]);
+    let result = template_test
+        .execute_and_commit(
+            vec![Instruction::CallFunction {
+                template_address: template_test.get_template_address("EventEmitter"),
+                function: "emit_typed".to_string(),
+                args: args![Amount(123), "coffee"],
+            }],
+            vec![],
+        )
+        .expect("Failed to emit typed event");
+    assert!(result.finalize.is_accept());
+
+    let event = &result.finalize.events[0];
+    assert_eq!(event.topic(), "transfer");
+    assert_eq!(event.get_payload("memo").unwrap(), "coffee");
+    let transfer = event.decode_data::<Transfer>().unwrap().unwrap();
+    assert_eq!(transfer.amount, Amount(123));
+    assert_eq!(transfer.memo, "coffee");
+}
+
+#[test]
+fn emit_indexed_event() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/events"]);
+    let result = template_test
+        .execute_and_commit(
+            vec![Instruction::CallFunction {
+                template_address: template_test.get_template_address("EventEmitter"),
+                function: "emit_indexed".to_string(),
+                args: args![Amount(123), "coffee", vec!["amount", "memo"]],
+            }],
+            vec![],
+        )
+        .expect("Failed to emit indexed event");
+    assert!(result.finalize.is_accept());
+
+    let event = &result.finalize.events[0];
+    assert_eq!(event.get_payload("amount").unwrap(), "123");
+    assert_eq!(event.get_payload("memo").unwrap(), "coffee");
+    assert!(event.data().is_some());
+}
+
+#[test]
+fn it_rejects_invalid_indexed_event_fields() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/events"]);
+    let event_emitter_template = template_test.get_template_address("EventEmitter");
+    let (_, _, private_key) = template_test.create_funded_account();
+
+    let reason = template_test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(event_emitter_template, "emit_indexed", args![
+                Amount(123),
+                "coffee",
+                vec!["sender"]
+            ])
+            .sign(&private_key)
+            .build(),
+        [].into(),
+    );
+    assert_reject_reason(reason, RuntimeError::InvalidIndexedEventField {
+        field: "sender".to_string(),
+        details: "the typed payload of the event does not contain the field".to_string(),
+    });
+
+    let reason = template_test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(event_emitter_template, "emit_indexed", args![
+                Amount(123),
+                "coffee",
+                vec!["amount", "memo", "amount", "memo", "amount"]
+            ])
+            .sign(&private_key)
+            .build(),
+        [].into(),
+    );
+    assert_reject_reason(reason, RuntimeError::TooManyIndexedEventFields { count: 5, max: 4 });
+}
+
 #[test]
 fn cannot_use_standard_topic() {
     let mut template_test = TemplateTest::new(vec!["tests/templates/events"]);
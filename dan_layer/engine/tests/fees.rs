@@ -3,7 +3,13 @@
 
 use std::iter;
 
-use tari_engine_types::{commit_result::RejectReason, instruction::Instruction};
+use tari_dan_engine::{fees::FeeTable, runtime::RuntimeError};
+use tari_engine_types::{
+    commit_result::RejectReason,
+    fees::{FeeBreakdown, FeeSource},
+    instruction::Instruction,
+    virtual_substate::{VirtualSubstate, VirtualSubstateId},
+};
 use tari_template_lib::{
     args,
     constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
@@ -403,3 +409,274 @@ fn dangling_bucket_pay_fees() {
     assert!(!payment.total_fees_paid().is_zero());
     assert_eq!(orig_balance - new_balance, payment.total_fees_paid());
 }
+
+#[test]
+fn it_records_fees_for_each_instruction() {
+    let mut test = TemplateTest::new(["tests/templates/state"]);
+
+    let (account, owner_token, private_key) = test.create_funded_account();
+
+    test.enable_fees();
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("State"), "new", args![])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+    test.disable_fees();
+
+    let receipt = result.finalize.fee_receipt;
+    // One fee instruction followed by one instruction
+    assert_eq!(receipt.instruction_cost_breakdown.len(), 2);
+    let charge_for = |breakdown: &FeeBreakdown, source: FeeSource| {
+        breakdown
+            .iter()
+            .find(|(s, _)| **s == source)
+            .map(|(_, amount)| *amount)
+            .unwrap_or(0)
+    };
+    let create_component = &receipt.instruction_cost_breakdown[1];
+    assert!(charge_for(create_component, FeeSource::RuntimeCall) > 0);
+    // The component-created event
+    assert_eq!(
+        charge_for(create_component, FeeSource::Events),
+        test.fee_table().per_event_cost()
+    );
+    // Storage is charged for the transaction as a whole
+    assert_eq!(charge_for(create_component, FeeSource::Storage), 0);
+
+    let instructions_total = receipt
+        .instruction_cost_breakdown
+        .iter()
+        .map(|b| b.get_total())
+        .sum::<u64>();
+    assert!(instructions_total < receipt.cost_breakdown.get_total());
+    assert_eq!(
+        receipt.to_cost_breakdown().instructions.len(),
+        receipt.instruction_cost_breakdown.len()
+    );
+}
+
+#[test]
+fn component_sponsors_fees() {
+    let mut test = TemplateTest::new(["tests/templates/fee_sponsor"]);
+    let template_addr = test.get_template_address("SponsoredCounter");
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(test_faucet_component(), "take_free_coins", args![])
+            .put_last_instruction_output_on_workspace("bucket")
+            .call_function(template_addr, "new", args![Workspace("bucket")])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    let counter = result.finalize.execution_results[2]
+        .decode::<ComponentAddress>()
+        .unwrap();
+    let orig_balance: Amount = test.call_method(counter, "fee_balance", args![], vec![]);
+
+    // The caller does not hold any XTR
+    let (_, owner_token, private_key) = test.create_empty_account();
+
+    test.enable_fees();
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(counter, "increase", args![])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    let fees = result.expect_fees_paid_in_full();
+
+    // The policy does not sponsor calls to set
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(counter, "set", args![123u32])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+    assert!(matches!(reason, RejectReason::FeesNotPaid(_)));
+    test.disable_fees();
+
+    let value: u32 = test.call_method(counter, "get", args![], vec![]);
+    assert_eq!(value, 1);
+    let new_balance: Amount = test.call_method(counter, "fee_balance", args![], vec![]);
+    assert_eq!(new_balance, orig_balance - fees.total_fees_charged());
+}
+
+#[test]
+fn it_rejects_an_invalid_fee_policy() {
+    let mut test = TemplateTest::new(["tests/templates/fee_sponsor"]);
+    let template_addr = test.get_template_address("SponsoredCounter");
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(test_faucet_component(), "take_free_coins", args![])
+            .put_last_instruction_output_on_workspace("bucket")
+            .call_function(template_addr, "with_invalid_policy", args![Workspace("bucket")])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "must have the signature fn(&self, method: String) -> Amount");
+}
+
+#[test]
+fn it_charges_rent_and_rejects_calls_to_expired_components() {
+    let mut test = TemplateTest::new(["tests/templates/state"]);
+    test.set_fee_table(FeeTable {
+        per_byte_rent_cost_per_epoch: 1,
+        initial_rent_epochs: 2,
+        ..test.fee_table().clone()
+    });
+
+    let (account, owner_token, private_key) = test.create_funded_account();
+
+    test.enable_fees();
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("State"), "new", args![])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    let component_address = result.finalize.execution_results[0]
+        .decode::<ComponentAddress>()
+        .unwrap();
+    let charged_rent = |breakdown: &FeeBreakdown| {
+        breakdown
+            .iter()
+            .find(|(s, _)| **s == FeeSource::Rent)
+            .map(|(_, amount)| *amount)
+            .unwrap_or(0)
+    };
+    assert!(charged_rent(&result.finalize.fee_receipt.cost_breakdown) > 0);
+
+    let header = test.read_only_state_store().get_component(component_address).unwrap();
+    assert_eq!(header.rent_paid_until, Some(2));
+
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(3));
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_method(component_address, "set", args![1u32])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    assert_reject_reason(reason, RuntimeError::SubstateRentExpired {
+        id: component_address.into(),
+        paid_until: 2,
+        current_epoch: 3,
+    });
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .pay_rent(component_address, 5)
+            .call_method(component_address, "set", args![1u32])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+    assert!(charged_rent(&result.finalize.fee_receipt.cost_breakdown) > 0);
+    test.disable_fees();
+
+    let header = test.read_only_state_store().get_component(component_address).unwrap();
+    assert_eq!(header.rent_paid_until, Some(8));
+}
+
+#[test]
+fn it_charges_rent_for_key_value_entries_and_vaults() {
+    let mut test = TemplateTest::new(["tests/templates/key_value_store", "tests/templates/resource"]);
+    test.set_fee_table(FeeTable {
+        per_byte_rent_cost_per_epoch: 1,
+        initial_rent_epochs: 2,
+        ..test.fee_table().clone()
+    });
+
+    let (account, owner_token, private_key) = test.create_funded_account();
+
+    test.enable_fees();
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("KeyValueStoreTest"), "new", args![])
+            .call_function(test.get_template_address("ResourceTest"), "new", args![])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    let diff = result.finalize.result.accept().unwrap();
+    let vaults = diff
+        .up_iter()
+        .filter_map(|(_, substate)| substate.substate_value().as_vault())
+        .collect::<Vec<_>>();
+    assert!(!vaults.is_empty());
+    for vault in vaults {
+        assert_eq!(vault.rent_paid_until(), Some(2));
+    }
+    let component_address = result.finalize.execution_results[0]
+        .decode::<ComponentAddress>()
+        .unwrap();
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_method(component_address, "insert", args!["a", 1u64])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    let (entry_id, entry) = result
+        .finalize
+        .result
+        .accept()
+        .unwrap()
+        .up_iter()
+        .find_map(|(id, substate)| {
+            substate
+                .substate_value()
+                .as_key_value_entry()
+                .map(|entry| (id.clone(), entry.clone()))
+        })
+        .unwrap();
+    assert_eq!(entry.rent_paid_until(), Some(2));
+
+    // Paying the rent of the component does not keep the entries of its store alive
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(3));
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .pay_rent(component_address, 5)
+            .call_method(component_address, "get", args!["a"])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+    assert_reject_reason(reason, RuntimeError::SubstateRentExpired {
+        id: entry_id.clone(),
+        paid_until: 2,
+        current_epoch: 3,
+    });
+
+    test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .pay_rent(component_address, 5)
+            .pay_rent(entry_id.clone(), 5)
+            .call_method(component_address, "get", args!["a"])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+    test.disable_fees();
+
+    let entry = test.read_only_state_store().get_substate(&entry_id).unwrap();
+    assert_eq!(entry.substate_value().rent_paid_until(), Some(8));
+}
@@ -0,0 +1,98 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_engine::{fees::FeeTable, runtime::RuntimeModuleError};
+use tari_engine_types::logs::LogLevel;
+use tari_template_lib::{
+    args,
+    models::{Amount, Metadata},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+#[test]
+fn it_emits_logs_with_structured_fields() {
+    let mut test = TemplateTest::new(["tests/templates/logging"]);
+    let fields = Metadata::from([("user", "alice"), ("amount", "123")]);
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_function(test.get_template_address("Logger"), "log_with_fields", args![
+                "transfer", fields
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let log = result
+        .finalize
+        .logs
+        .iter()
+        .find(|log| log.message == "transfer")
+        .unwrap();
+    assert_eq!(log.level, LogLevel::Warn);
+    assert_eq!(log.fields.get("user").unwrap(), "alice");
+    assert_eq!(log.fields.get("amount").unwrap(), "123");
+}
+
+fn test_with_log_limits(max_logs_per_transaction: usize, max_log_bytes_per_transaction: usize) -> TemplateTest {
+    let mut test = TemplateTest::new(["tests/templates/logging"]);
+    test.set_fee_table(FeeTable {
+        max_logs_per_transaction,
+        max_log_bytes_per_transaction,
+        ..test.fee_table().clone()
+    });
+    test
+}
+
+#[test]
+fn it_rejects_transactions_that_emit_too_many_logs() {
+    let mut test = test_with_log_limits(20, FeeTable::DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION);
+    let (account, owner_token, private_key) = test.create_funded_account();
+    test.enable_fees();
+
+    test.execute_expect_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("Logger"), "log_many", args![5u32])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token.clone()],
+    );
+
+    let result = test.execute_and_commit_on_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("Logger"), "log_many", args![100u32])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+
+    let reason = result.expect_transaction_failure();
+    result.expect_finalization_success();
+    assert_reject_reason(reason, RuntimeModuleError::TooManyLogs { max: 20 });
+}
+
+#[test]
+fn it_rejects_transactions_that_exceed_the_log_size_limit() {
+    let mut test = test_with_log_limits(FeeTable::DEFAULT_MAX_LOGS_PER_TRANSACTION, 1024);
+    let (account, owner_token, private_key) = test.create_funded_account();
+    test.enable_fees();
+
+    let result = test.execute_and_commit_on_success(
+        Transaction::builder()
+            .fee_transaction_pay_from_component(account, Amount(1000))
+            .call_function(test.get_template_address("Logger"), "log_message", args![
+                "x".repeat(2000)
+            ])
+            .sign(&private_key)
+            .build(),
+        vec![owner_token],
+    );
+
+    let reason = result.expect_transaction_failure();
+    result.expect_finalization_success();
+    assert_reject_reason(reason, "exceed the maximum of 1024 bytes");
+}
@@ -0,0 +1,57 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_engine::runtime::RuntimeError;
+use tari_template_lib::{args, args::ComponentAction, models::ComponentAddress};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+#[test]
+fn it_migrates_a_component_to_a_new_template() {
+    let mut test = TemplateTest::new([
+        "tests/templates/migration/counter_v1",
+        "tests/templates/migration/counter_v2",
+    ]);
+    let v1_address = test.get_template_address("CounterV1");
+    let v2_address = test.get_template_address("CounterV2");
+
+    let counter: ComponentAddress = test.call_function("CounterV1", "new", args![], vec![]);
+    test.call_method::<()>(counter, "increase", args![], vec![]);
+
+    // Only the owner may migrate the component
+    let (proof, _, key) = test.create_owner_proof();
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(v2_address, "upgrade", args![counter, v2_address])
+            .sign(&key)
+            .build(),
+        vec![proof],
+    );
+    assert_reject_reason(reason, RuntimeError::AccessDeniedOwnerRequired {
+        action: ComponentAction::Migrate.into(),
+    });
+
+    let proof = test.get_test_proof();
+    test.call_function::<()>("CounterV2", "upgrade", args![counter, v2_address], vec![proof]);
+
+    let header = test.read_only_state_store().get_component(counter).unwrap();
+    assert_eq!(header.template_address, v2_address);
+    assert_eq!(header.previous_template_address, Some(v1_address));
+    assert_eq!(header.module_name, "CounterV2");
+
+    // The component now uses the methods and state of the new template
+    test.call_method::<()>(counter, "increase", args![], vec![]);
+    let value: u64 = test.call_method(counter, "value", args![], vec![]);
+    assert_eq!(value, 11);
+
+    // CounterV1 has no migrate function so the component cannot be migrated back
+    let proof = test.get_test_proof();
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(v2_address, "upgrade", args![counter, v1_address])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![proof],
+    );
+    assert_reject_reason(reason, "does not export a migrate function");
+}
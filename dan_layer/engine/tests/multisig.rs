@@ -0,0 +1,257 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_template_builtin::MULTISIG_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    args::Arg,
+    constants::XTR,
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress, TemplateAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+/// Mirrors the ProposalAction type of the multisig template
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum ProposalAction {
+    Transfer {
+        resource: ResourceAddress,
+        amount: Amount,
+        destination: ComponentAddress,
+    },
+    CallMethod {
+        component: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    },
+    CallFunction {
+        template: TemplateAddress,
+        function: String,
+        args: Vec<Arg>,
+    },
+    UpdateSigners {
+        num_new_signers: u32,
+        revoke: Vec<NonFungibleId>,
+        threshold: u32,
+    },
+}
+
+struct Signer {
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+fn setup(test: &mut TemplateTest, num_signers: u32) -> (ComponentAddress, ResourceAddress, Vec<Signer>) {
+    let signers = (0..num_signers)
+        .map(|_| {
+            let (account, proof, key) = test.create_funded_account();
+            Signer { account, proof, key }
+        })
+        .collect::<Vec<_>>();
+    let creator = &signers[0];
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_function(MULTISIG_TEMPLATE_ADDRESS, "create", args![num_signers, 2u32])
+            .put_last_instruction_output_on_workspace("multisig")
+            .call_method(creator.account, "deposit", args![Workspace("multisig.1")])
+            .sign(&creator.key)
+            .build(),
+        vec![creator.proof.clone()],
+    );
+    let multisig: ComponentAddress = result.finalize.execution_results[0].get_value("$.0").unwrap().unwrap();
+    let badge_resource: ResourceAddress = test.extract_component_value(multisig, "$.signer_badge");
+
+    // Distribute the badges to the other signers
+    let mut builder = Transaction::builder();
+    for (i, signer) in signers.iter().enumerate().skip(1) {
+        let badge = format!("badge{}", i);
+        builder = builder
+            .call_method(creator.account, "withdraw_non_fungible", args![
+                badge_resource,
+                NonFungibleId::from_u32(i as u32)
+            ])
+            .put_last_instruction_output_on_workspace(badge.clone())
+            .call_method(signer.account, "deposit", args![Workspace(badge)]);
+    }
+    test.execute_expect_success(builder.sign(&creator.key).build(), vec![creator.proof.clone()]);
+
+    // Fund the multisig
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(creator.account, "withdraw", args![XTR, Amount(100)])
+            .put_last_instruction_output_on_workspace("funds")
+            .call_method(multisig, "deposit", args![Workspace("funds")])
+            .sign(&creator.key)
+            .build(),
+        vec![creator.proof.clone()],
+    );
+
+    (multisig, badge_resource, signers)
+}
+
+fn propose(
+    test: &mut TemplateTest,
+    multisig: ComponentAddress,
+    badge_resource: ResourceAddress,
+    signer: &Signer,
+    action: ProposalAction,
+) -> u64 {
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(signer.account, "create_proof_for_resource", args![badge_resource])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(multisig, "propose", args![Workspace("proof"), action])
+            .drop_all_proofs_in_workspace()
+            .sign(&signer.key)
+            .build(),
+        vec![signer.proof.clone()],
+    );
+    result.finalize.execution_results[2].decode().unwrap()
+}
+
+fn approve_transaction(
+    multisig: ComponentAddress,
+    badge_resource: ResourceAddress,
+    signer: &Signer,
+    proposal_id: u64,
+) -> Transaction {
+    Transaction::builder()
+        .call_method(signer.account, "create_proof_for_resource", args![badge_resource])
+        .put_last_instruction_output_on_workspace("proof")
+        .call_method(multisig, "approve", args![proposal_id, Workspace("proof")])
+        .drop_all_proofs_in_workspace()
+        .sign(&signer.key)
+        .build()
+}
+
+#[test]
+fn it_executes_a_proposal_once_the_threshold_is_reached() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (multisig, badge_resource, signers) = setup(&mut test, 3);
+    let (receiver, _, _) = test.create_empty_account();
+
+    let proposal_id = propose(
+        &mut test,
+        multisig,
+        badge_resource,
+        &signers[0],
+        ProposalAction::Transfer {
+            resource: XTR,
+            amount: Amount(40),
+            destination: receiver,
+        },
+    );
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(multisig, "execute", args![proposal_id])
+            .sign(&signers[2].key)
+            .build(),
+        vec![signers[2].proof.clone()],
+    );
+    assert_reject_reason(reason, "has 1 of 2 required approvals");
+
+    test.execute_expect_success(
+        approve_transaction(multisig, badge_resource, &signers[1], proposal_id),
+        vec![signers[1].proof.clone()],
+    );
+
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(multisig, "execute", args![proposal_id])
+            .sign(&signers[2].key)
+            .build(),
+        vec![signers[2].proof.clone()],
+    );
+
+    let balance: Amount = test.call_method(multisig, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(60));
+    let balance: Amount = test.call_method(receiver, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(40));
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(multisig, "execute", args![proposal_id])
+            .sign(&signers[2].key)
+            .build(),
+        vec![signers[2].proof.clone()],
+    );
+    assert_reject_reason(reason, "has already been executed");
+}
+
+#[test]
+fn it_rotates_the_signer_set() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (multisig, badge_resource, signers) = setup(&mut test, 3);
+    let (new_signer_account, new_signer_proof, new_signer_key) = test.create_empty_account();
+
+    // Replace signer 1 with a new signer
+    let proposal_id = propose(
+        &mut test,
+        multisig,
+        badge_resource,
+        &signers[0],
+        ProposalAction::UpdateSigners {
+            num_new_signers: 1,
+            revoke: vec![NonFungibleId::from_u32(1)],
+            threshold: 2,
+        },
+    );
+    test.execute_expect_success(
+        approve_transaction(multisig, badge_resource, &signers[2], proposal_id),
+        vec![signers[2].proof.clone()],
+    );
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(multisig, "execute", args![proposal_id])
+            .put_last_instruction_output_on_workspace("badges")
+            .call_method(new_signer_account, "deposit", args![Workspace("badges")])
+            .sign(&signers[0].key)
+            .build(),
+        vec![signers[0].proof.clone()],
+    );
+
+    let proposal_id = propose(
+        &mut test,
+        multisig,
+        badge_resource,
+        &signers[0],
+        ProposalAction::Transfer {
+            resource: XTR,
+            amount: Amount(10),
+            destination: new_signer_account,
+        },
+    );
+
+    // The revoked signer can no longer approve proposals
+    let reason = test.execute_expect_failure(
+        approve_transaction(multisig, badge_resource, &signers[1], proposal_id),
+        vec![signers[1].proof.clone()],
+    );
+    assert_reject_reason(reason, "Proof does not contain a signer badge");
+
+    let new_signer = Signer {
+        account: new_signer_account,
+        proof: new_signer_proof,
+        key: new_signer_key,
+    };
+    test.execute_expect_success(
+        approve_transaction(multisig, badge_resource, &new_signer, proposal_id),
+        vec![new_signer.proof.clone()],
+    );
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(multisig, "execute", args![proposal_id])
+            .sign(&new_signer.key)
+            .build(),
+        vec![new_signer.proof.clone()],
+    );
+
+    let balance: Amount = test.call_method(new_signer_account, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(10));
+}
@@ -0,0 +1,258 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_dan_engine::runtime::ActionIdent;
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
+use tari_template_builtin::NAME_SERVICE_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    constants::XTR,
+    crypto::RistrettoPublicKeyBytes,
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress},
+};
+use tari_template_test_tooling::{
+    support::assert_error::{assert_access_denied_for_action, assert_reject_reason},
+    TemplateTest,
+};
+use tari_transaction::Transaction;
+
+/// Mirrors the NameTarget type of the name service template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum NameTarget {
+    Component(ComponentAddress),
+    PublicKey(RistrettoPublicKeyBytes),
+}
+
+/// Mirrors the NameRecord type of the name service template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct NameRecord {
+    target: NameTarget,
+    badge: NonFungibleId,
+    expiry_epoch: u64,
+}
+
+struct Party {
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+struct NameServiceTest {
+    test: TemplateTest,
+    name_service: ComponentAddress,
+    badge_resource: ResourceAddress,
+}
+
+impl NameServiceTest {
+    /// Creates a name service owned by the first returned party, along with a second party
+    fn setup() -> (Self, Party, Party) {
+        let mut test = TemplateTest::new(Vec::<&str>::new());
+        let (account, proof, key) = test.create_funded_account();
+        let alice = Party { account, proof, key };
+        let (account, proof, key) = test.create_funded_account();
+        let bob = Party { account, proof, key };
+
+        // The registration costs 10 XTR and lasts for 10 epochs
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_function(NAME_SERVICE_TEMPLATE_ADDRESS, "new", args![XTR, Amount(10), 10u64])
+                .sign(&alice.key)
+                .build(),
+            vec![alice.proof.clone()],
+        );
+        let name_service: ComponentAddress = result.finalize.execution_results[0].decode().unwrap();
+        let badge_resource = test.extract_component_value(name_service, "$.badge_resource");
+
+        let ns = Self {
+            test,
+            name_service,
+            badge_resource,
+        };
+        (ns, alice, bob)
+    }
+
+    fn register_transaction(&self, party: &Party, name: &str, fee: Amount) -> Transaction {
+        Transaction::builder()
+            .call_method(party.account, "withdraw", args![XTR, fee])
+            .put_last_instruction_output_on_workspace("payment")
+            .call_method(self.name_service, "register", args![
+                name,
+                NameTarget::Component(party.account),
+                Workspace("payment")
+            ])
+            .put_last_instruction_output_on_workspace("badge")
+            .call_method(party.account, "deposit", args![Workspace("badge")])
+            .sign(&party.key)
+            .build()
+    }
+
+    fn register(&mut self, party: &Party, name: &str) {
+        let transaction = self.register_transaction(party, name, Amount(10));
+        self.test.execute_expect_success(transaction, vec![party.proof.clone()]);
+    }
+
+    fn set_target_transaction(&self, party: &Party, name: &str, target: NameTarget) -> Transaction {
+        Transaction::builder()
+            .call_method(party.account, "create_proof_for_resource", args![self.badge_resource])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(self.name_service, "set_target", args![Workspace("proof"), name, target])
+            .drop_all_proofs_in_workspace()
+            .sign(&party.key)
+            .build()
+    }
+
+    fn renew_transaction(&self, party: &Party, name: &str) -> Transaction {
+        Transaction::builder()
+            .call_method(party.account, "create_proof_for_resource", args![self.badge_resource])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(party.account, "withdraw", args![XTR, Amount(10)])
+            .put_last_instruction_output_on_workspace("payment")
+            .call_method(self.name_service, "renew", args![
+                Workspace("proof"),
+                name,
+                Workspace("payment")
+            ])
+            .drop_all_proofs_in_workspace()
+            .sign(&party.key)
+            .build()
+    }
+
+    fn resolve(&mut self, name: &str) -> Option<NameTarget> {
+        self.test.call_method(self.name_service, "resolve", args![name], vec![])
+    }
+
+    fn get_record(&mut self, name: &str) -> NameRecord {
+        let record: Option<NameRecord> = self
+            .test
+            .call_method(self.name_service, "get_record", args![name], vec![]);
+        record.unwrap()
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        self.test
+            .set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(epoch));
+    }
+}
+
+#[test]
+fn it_registers_and_resolves_names() {
+    let (mut ns, alice, bob) = NameServiceTest::setup();
+
+    let transaction = ns.register_transaction(&alice, "Alice", Amount(10));
+    let reason = ns.test.execute_expect_failure(transaction, vec![alice.proof.clone()]);
+    assert_reject_reason(
+        reason,
+        "Name 'Alice' may only contain lowercase letters, digits and hyphens",
+    );
+
+    let transaction = ns.register_transaction(&alice, "alice", Amount(5));
+    let reason = ns.test.execute_expect_failure(transaction, vec![alice.proof.clone()]);
+    assert_reject_reason(reason, "Payment does not match the registration fee");
+
+    ns.register(&alice, "alice");
+    ns.register(&bob, "bob");
+    assert_eq!(ns.resolve("alice"), Some(NameTarget::Component(alice.account)));
+    assert_eq!(ns.resolve("bob"), Some(NameTarget::Component(bob.account)));
+    assert_eq!(ns.resolve("carol"), None);
+
+    let transaction = ns.register_transaction(&bob, "alice", Amount(10));
+    let reason = ns.test.execute_expect_failure(transaction, vec![bob.proof.clone()]);
+    assert_reject_reason(reason, "Name 'alice' is registered until epoch 10");
+
+    // Only the badge holder may change the target
+    let public_key = RistrettoPublicKeyBytes::from_bytes(&[1u8; 32]).unwrap();
+    let transaction = ns.set_target_transaction(&bob, "alice", NameTarget::PublicKey(public_key));
+    let reason = ns.test.execute_expect_failure(transaction, vec![bob.proof.clone()]);
+    assert_reject_reason(reason, "Proof does not contain the badge of name 'alice'");
+
+    let transaction = ns.set_target_transaction(&alice, "alice", NameTarget::PublicKey(public_key));
+    ns.test.execute_expect_success(transaction, vec![alice.proof.clone()]);
+    assert_eq!(ns.resolve("alice"), Some(NameTarget::PublicKey(public_key)));
+}
+
+#[test]
+fn it_transfers_names_with_the_badge() {
+    let (mut ns, alice, bob) = NameServiceTest::setup();
+    ns.register(&alice, "alice");
+    let record = ns.get_record("alice");
+
+    ns.test.execute_expect_success(
+        Transaction::builder()
+            .call_method(alice.account, "withdraw_non_fungible", args![
+                ns.badge_resource,
+                record.badge
+            ])
+            .put_last_instruction_output_on_workspace("badge")
+            .call_method(bob.account, "deposit", args![Workspace("badge")])
+            .sign(&alice.key)
+            .build(),
+        vec![alice.proof.clone()],
+    );
+
+    let transaction = ns.set_target_transaction(&bob, "alice", NameTarget::Component(bob.account));
+    ns.test.execute_expect_success(transaction, vec![bob.proof.clone()]);
+    assert_eq!(ns.resolve("alice"), Some(NameTarget::Component(bob.account)));
+
+    let transaction = ns.renew_transaction(&alice, "alice");
+    let reason = ns.test.execute_expect_failure(transaction, vec![alice.proof.clone()]);
+    assert_reject_reason(reason, "Proof does not contain the badge of name 'alice'");
+}
+
+#[test]
+fn it_expires_and_renews_names() {
+    let (mut ns, alice, bob) = NameServiceTest::setup();
+    ns.register(&alice, "alice");
+    let first_badge = ns.get_record("alice").badge;
+
+    ns.set_epoch(5);
+    let transaction = ns.renew_transaction(&alice, "alice");
+    ns.test.execute_expect_success(transaction, vec![alice.proof.clone()]);
+    assert_eq!(ns.get_record("alice").expiry_epoch, 20);
+
+    ns.set_epoch(20);
+    assert_eq!(ns.resolve("alice"), None);
+
+    // Once expired, the name may be registered by anyone and the previous badge no longer owns it
+    ns.register(&bob, "alice");
+    let record = ns.get_record("alice");
+    assert_ne!(record.badge, first_badge);
+    assert_eq!(record.expiry_epoch, 30);
+    assert_eq!(ns.resolve("alice"), Some(NameTarget::Component(bob.account)));
+
+    let transaction = ns.set_target_transaction(&alice, "alice", NameTarget::Component(alice.account));
+    let reason = ns.test.execute_expect_failure(transaction, vec![alice.proof.clone()]);
+    assert_reject_reason(reason, "Proof does not contain the badge of name 'alice'");
+}
+
+#[test]
+fn it_restricts_fee_withdrawal_to_the_owner() {
+    let (mut ns, alice, bob) = NameServiceTest::setup();
+    ns.register(&alice, "alice");
+    ns.register(&bob, "bob");
+
+    let name_service = ns.name_service;
+    let withdraw_fees = |party: &Party| {
+        Transaction::builder()
+            .call_method(name_service, "withdraw_fees", args![Amount(20)])
+            .put_last_instruction_output_on_workspace("fees")
+            .call_method(party.account, "deposit", args![Workspace("fees")])
+            .sign(&party.key)
+            .build()
+    };
+
+    let reason = ns
+        .test
+        .execute_expect_failure(withdraw_fees(&bob), vec![bob.proof.clone()]);
+    assert_access_denied_for_action(reason, ActionIdent::ComponentCallMethod {
+        component_address: name_service,
+        method: "withdraw_fees".to_string(),
+    });
+
+    let balance: Amount = ns.test.call_method(alice.account, "balance", args![XTR], vec![]);
+    ns.test
+        .execute_expect_success(withdraw_fees(&alice), vec![alice.proof.clone()]);
+    let new_balance: Amount = ns.test.call_method(alice.account, "balance", args![XTR], vec![]);
+    assert_eq!(new_balance, balance + Amount(20));
+}
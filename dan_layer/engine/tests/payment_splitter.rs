@@ -0,0 +1,88 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_builtin::PAYMENT_SPLITTER_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    constants::XTR,
+    models::{Amount, ComponentAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+fn new_splitter_transaction(test: &TemplateTest, beneficiaries: Vec<(ComponentAddress, u32)>) -> Transaction {
+    Transaction::builder()
+        .call_function(PAYMENT_SPLITTER_TEMPLATE_ADDRESS, "new", args![XTR, beneficiaries])
+        .sign(test.get_test_secret_key())
+        .build()
+}
+
+fn claimable(test: &mut TemplateTest, splitter: ComponentAddress, beneficiary: ComponentAddress) -> Amount {
+    test.call_method(splitter, "claimable", args![beneficiary], vec![])
+}
+
+#[test]
+fn it_splits_payments_by_weight() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (payer, payer_proof, payer_key) = test.create_funded_account();
+    let (alice, _, _) = test.create_empty_account();
+    let (bob, _, _) = test.create_empty_account();
+    let (carol, _, _) = test.create_empty_account();
+    let transaction = new_splitter_transaction(&test, vec![(alice, 1), (bob, 2), (carol, 3)]);
+    let result = test.execute_expect_success(transaction, vec![]);
+    let splitter: ComponentAddress = result.finalize.execution_results[0].decode().unwrap();
+
+    let pay = |amount: Amount| {
+        Transaction::builder()
+            .call_method(payer, "withdraw", args![XTR, amount])
+            .put_last_instruction_output_on_workspace("funds")
+            .call_method(splitter, "deposit", args![Workspace("funds")])
+            .call_method(splitter, "distribute", args![])
+            .sign(&payer_key)
+            .build()
+    };
+
+    // The remainder of 100 / 6 is carried over to the next distribution
+    test.execute_expect_success(pay(Amount(100)), vec![payer_proof.clone()]);
+    assert_eq!(claimable(&mut test, splitter, alice), Amount(16));
+    assert_eq!(claimable(&mut test, splitter, bob), Amount(33));
+    assert_eq!(claimable(&mut test, splitter, carol), Amount(50));
+    let undistributed: Amount = test.call_method(splitter, "undistributed_balance", args![], vec![]);
+    assert_eq!(undistributed, Amount(1));
+
+    test.execute_expect_success(pay(Amount(5)), vec![payer_proof.clone()]);
+    assert_eq!(claimable(&mut test, splitter, alice), Amount(17));
+    assert_eq!(claimable(&mut test, splitter, bob), Amount(35));
+    assert_eq!(claimable(&mut test, splitter, carol), Amount(53));
+
+    // Anyone may claim on behalf of a beneficiary, the funds are deposited into the beneficiary account
+    let claim = Transaction::builder()
+        .call_method(splitter, "claim", args![bob])
+        .sign(&payer_key)
+        .build();
+    test.execute_expect_success(claim.clone(), vec![payer_proof.clone()]);
+    let balance: Amount = test.call_method(bob, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(35));
+    assert_eq!(claimable(&mut test, splitter, bob), Amount(0));
+
+    let reason = test.execute_expect_failure(claim, vec![payer_proof]);
+    assert_reject_reason(reason, format!("Beneficiary {} has nothing to claim", bob));
+}
+
+#[test]
+fn it_rejects_invalid_beneficiaries() {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (alice, _, _) = test.create_empty_account();
+
+    let transaction = new_splitter_transaction(&test, vec![]);
+    let reason = test.execute_expect_failure(transaction, vec![]);
+    assert_reject_reason(reason, "At least one beneficiary is required");
+
+    let transaction = new_splitter_transaction(&test, vec![(alice, 1), (alice, 2)]);
+    let reason = test.execute_expect_failure(transaction, vec![]);
+    assert_reject_reason(reason, format!("Beneficiary {} is listed more than once", alice));
+
+    let transaction = new_splitter_transaction(&test, vec![(alice, 0)]);
+    let reason = test.execute_expect_failure(transaction, vec![]);
+    assert_reject_reason(reason, format!("Weight of beneficiary {} must be positive", alice));
+}
@@ -86,3 +86,87 @@ fn it_recalls_all_resource_types() {
     let confidential_balance = result.finalize.execution_results[6].decode::<Amount>().unwrap();
     assert_eq!(confidential_balance, Amount(6));
 }
+
+#[test]
+fn it_recalls_many_non_fungibles_in_one_action() {
+    let mut test = TemplateTest::new(["tests/templates/recall"]);
+    let recall_template = test.get_template_address("Recall");
+    let (account, _, _) = test.create_empty_account();
+
+    let (mut initial_supply, _, _) = generate_confidential_proof(Amount(1000), None);
+    initial_supply.output_revealed_amount = Amount(1000);
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_function(recall_template, "new", args![initial_supply])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let recall_component = result.finalize.execution_results[0].get_value("$.0").unwrap().unwrap();
+    let non_fungible_resource: ResourceAddress =
+        result.finalize.execution_results[0].get_value("$.2").unwrap().unwrap();
+
+    let ids = (1..=5).map(NonFungibleId::from_u32).collect::<Vec<_>>();
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(recall_component, "withdraw_non_fungibles", args![ids])
+            .put_last_instruction_output_on_workspace("badges")
+            .call_method(account, "deposit", args![Workspace("badges")])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let vaults: BTreeMap<ResourceAddress, VaultId> = test.extract_component_value(account, "$.vaults");
+    let non_fungible_vault = vaults[&non_fungible_resource];
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(recall_component, "recall_non_fungibles", args![non_fungible_vault, [
+                NonFungibleId::from_u32(1),
+                NonFungibleId::from_u32(3),
+                NonFungibleId::from_u32(5)
+            ]])
+            .call_method(account, "balance", args![non_fungible_resource])
+            .call_method(recall_component, "get_balances", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let account_balance = result.finalize.execution_results[1].decode::<Amount>().unwrap();
+    assert_eq!(account_balance, Amount(2));
+    let (_, recalled_balance, _) = result.finalize.execution_results[2]
+        .decode::<(Amount, Amount, Amount)>()
+        .unwrap();
+    assert_eq!(recalled_balance, Amount(8));
+
+    // Recalling is atomic, so none of the tokens are recalled if one of them is not in the vault
+    test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(recall_component, "recall_non_fungibles", args![non_fungible_vault, [
+                NonFungibleId::from_u32(2),
+                NonFungibleId::from_u32(3)
+            ]])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let empty_ids: [NonFungibleId; 0] = [];
+    test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(recall_component, "recall_non_fungibles", args![
+                non_fungible_vault,
+                empty_ids
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let account_balance: Amount = test.call_method(account, "balance", args![non_fungible_resource], vec![]);
+    assert_eq!(account_balance, Amount(2));
+}
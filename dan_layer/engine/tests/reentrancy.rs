@@ -1,7 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_dan_engine::runtime::{LockError, LockState};
+use tari_dan_engine::runtime::{LockError, LockState, RuntimeError};
 use tari_engine_types::lock::LockFlag;
 use tari_template_lib::{
     args,
@@ -116,3 +116,32 @@ fn it_prevents_multiple_mutable_access_to_component() {
         address: reentrancy.into(),
     });
 }
+
+#[test]
+fn it_prevents_any_reentrant_access_to_non_reentrant_component() {
+    let mut test = TemplateTest::new(["tests/templates/reentrancy"]);
+
+    let reentrancy: ComponentAddress = test.call_function("Reentrancy", "new_non_reentrant", args![], vec![]);
+
+    // Calls that do not re-enter the component are unaffected
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(reentrancy, "assert_is_allowed", args![])
+            .call_method(reentrancy, "assert_is_allowed", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(reentrancy, "reentrant_access_immutable", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    assert_reject_reason(reason, RuntimeError::ReentrantCall {
+        component_address: reentrancy,
+    });
+}
@@ -1,8 +1,22 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_template_lib::{args, models::ComponentAddress};
-use tari_template_test_tooling::{support::confidential::generate_confidential_proof, TemplateTest};
+use tari_dan_engine::runtime::RuntimeError;
+use tari_engine_types::substate::SubstateId;
+use tari_template_lib::{
+    args,
+    auth::ResourceAuthAction,
+    models::{Amount, ComponentAddress, Metadata, NonFungibleId, ResourceAddress},
+    resource::TOKEN_SYMBOL,
+};
+use tari_template_test_tooling::{
+    support::{
+        assert_error::{assert_access_denied_for_action, assert_reject_reason},
+        confidential::generate_confidential_proof,
+    },
+    TemplateTest,
+};
+use tari_transaction::Transaction;
 
 #[test]
 fn fungible_join() {
@@ -18,6 +32,35 @@ fn non_fungible_join() {
     test.call_method::<()>(component, "non_fungible_join", args![], vec![]);
 }
 
+#[test]
+fn fungible_split() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+    test.call_method::<()>(component, "fungible_split", args![], vec![]);
+}
+
+#[test]
+fn non_fungible_split() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+    test.call_method::<()>(component, "non_fungible_split", args![], vec![]);
+}
+
+#[test]
+fn non_fungible_ids_paged() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+
+    let count: u32 = test.call_method(component, "non_fungible_count", args![], vec![]);
+    assert_eq!(count, 2);
+    let ids: Vec<NonFungibleId> = test.call_method(component, "non_fungible_ids_paged", args![0u32, 1u32], vec![]);
+    assert_eq!(ids, vec![NonFungibleId::from_u64(1)]);
+    let ids: Vec<NonFungibleId> = test.call_method(component, "non_fungible_ids_paged", args![1u32, 10u32], vec![]);
+    assert_eq!(ids, vec![NonFungibleId::from_u64(2)]);
+    let ids: Vec<NonFungibleId> = test.call_method(component, "non_fungible_ids_paged", args![2u32, 10u32], vec![]);
+    assert!(ids.is_empty());
+}
+
 #[test]
 fn confidential_join() {
     let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
@@ -25,3 +68,170 @@ fn confidential_join() {
     let (output, _, _) = generate_confidential_proof(1000.into(), None);
     test.call_method::<()>(component, "confidential_join", args![output], vec![]);
 }
+
+#[test]
+fn update_metadata() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+    let resource_address: ResourceAddress = test.call_method(component, "fungible_resource_address", args![], vec![]);
+
+    let mut metadata = Metadata::new();
+    metadata.insert(TOKEN_SYMBOL, "TST2");
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(component, "update_fungible_metadata", args![metadata])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert!(result
+        .finalize
+        .events
+        .iter()
+        .any(|e| e.topic() == "std.resource.metadata_updated"));
+
+    let resource = test.read_only_state_store().get_resource(&resource_address).unwrap();
+    assert_eq!(resource.token_symbol(), Some("TST2"));
+
+    // Metadata updates are denied by default for anyone but the owner
+    let (_, _, other_secret) = test.create_empty_account();
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "update_non_fungible_metadata", args![Metadata::new()])
+            .sign(&other_secret)
+            .build(),
+        vec![],
+    );
+    assert_access_denied_for_action(reason, ResourceAuthAction::UpdateMetadata);
+}
+
+#[test]
+fn freeze_and_unfreeze() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+    let resource_address: ResourceAddress = test.call_method(component, "fungible_resource_address", args![], vec![]);
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(component, "freeze_fungible", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert!(result
+        .finalize
+        .events
+        .iter()
+        .any(|e| e.topic() == "std.resource.frozen"));
+    let resource = test.read_only_state_store().get_resource(&resource_address).unwrap();
+    assert!(resource.is_frozen());
+
+    // Withdrawals are halted while the resource is frozen
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "fungible_join", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, RuntimeError::ResourceFrozen {
+        address: SubstateId::Resource(resource_address),
+        action: ResourceAuthAction::Withdraw.into(),
+    });
+
+    test.execute_expect_success(
+        Transaction::builder()
+            .call_method(component, "unfreeze_fungible", args![])
+            .call_method(component, "fungible_join", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+
+    // Freezing is denied by default for anyone but the owner
+    let (_, _, other_secret) = test.create_empty_account();
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "freeze_non_fungible", args![])
+            .sign(&other_secret)
+            .build(),
+        vec![],
+    );
+    assert_access_denied_for_action(reason, ResourceAuthAction::Freeze);
+}
+
+#[test]
+fn soulbound() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "soulbound_transfer", args![])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "cannot be moved out of its vault");
+
+    test.call_method::<()>(component, "soulbound_burn", args![], vec![]);
+}
+
+#[test]
+fn max_supply() {
+    let mut test = TemplateTest::new(vec!["tests/templates/resource"]);
+    let component: ComponentAddress = test.call_function("ResourceTest", "new", args![], vec![]);
+
+    test.call_method::<()>(
+        component,
+        "mint_fungible_with_max_supply",
+        args![Amount(100), Amount(60), Amount(40)],
+        vec![],
+    );
+
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "mint_fungible_with_max_supply", args![
+                Amount(100),
+                Amount(60),
+                Amount(41)
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "exceeds its max supply of 100");
+
+    // The initial supply is also subject to the max supply
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "mint_fungible_with_max_supply", args![
+                Amount(100),
+                Amount(101),
+                Amount(0)
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "exceeds its max supply of 100");
+
+    // Revealed amounts of confidential mints count towards the total supply
+    test.call_method::<()>(
+        component,
+        "mint_confidential_with_max_supply",
+        args![Amount(100), Amount(100)],
+        vec![],
+    );
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "mint_confidential_with_max_supply", args![
+                Amount(100),
+                Amount(101)
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "exceeds its max supply of 100");
+}
@@ -0,0 +1,177 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{
+    args,
+    models::{Amount, ComponentAddress, NonFungibleId, ObjectKey, ResourceAddress, VaultId},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::{Transaction, TransactionBuilder};
+
+struct RoyaltyTest {
+    test: TemplateTest,
+    collection: ComponentAddress,
+    market: ComponentAddress,
+    payment_resource: ResourceAddress,
+    reseller: ComponentAddress,
+}
+
+/// Creates a collection with a 10% royalty and sells NFT 1 to a reseller, who uses it to open a market of their own
+fn setup() -> RoyaltyTest {
+    let mut test = TemplateTest::new(["tests/templates/royalty"]);
+    let template_address = test.get_template_address("RoyaltyMarket");
+    let collection: ComponentAddress = test.call_function("RoyaltyMarket", "new_collection", args![10u8], vec![]);
+    let payment_resource: ResourceAddress = test.call_method(collection, "payment_resource", args![], vec![]);
+
+    let (reseller, reseller_proof, reseller_key) = test.create_empty_account();
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(collection, "take_coins", args![Amount(1000)])
+            .put_last_instruction_output_on_workspace("payment")
+            .call_method(collection, "buy", args![
+                NonFungibleId::from_u64(1),
+                Workspace("payment")
+            ])
+            .put_last_instruction_output_on_workspace("nft")
+            .call_function(template_address, "new_market", args![
+                Workspace("nft"),
+                payment_resource
+            ])
+            .sign(&reseller_key)
+            .build(),
+        vec![reseller_proof],
+    );
+    let market = result.finalize.execution_results[4]
+        .decode::<ComponentAddress>()
+        .unwrap();
+
+    RoyaltyTest {
+        test,
+        collection,
+        market,
+        payment_resource,
+        reseller,
+    }
+}
+
+/// Creates an account for a buyer and runs the purchase, depositing NFT 1 into the buyer's account
+fn buy_with<F>(test: &mut TemplateTest, collection: ComponentAddress, purchase: F) -> ComponentAddress
+where F: FnOnce(TransactionBuilder) -> TransactionBuilder {
+    let (buyer, buyer_proof, buyer_key) = test.create_empty_account();
+    let builder = Transaction::builder()
+        .call_method(collection, "take_coins", args![Amount(1000)])
+        .put_last_instruction_output_on_workspace("payment");
+    test.execute_expect_success(
+        purchase(builder)
+            .put_last_instruction_output_on_workspace("nft")
+            .call_method(buyer, "deposit", args![Workspace("nft")])
+            .sign(&buyer_key)
+            .build(),
+        vec![buyer_proof],
+    );
+    buyer
+}
+
+#[test]
+fn it_collects_royalties_on_resales() {
+    let RoyaltyTest {
+        mut test,
+        collection,
+        market,
+        ..
+    } = setup();
+
+    // The first sale is made by the resource owner, so no royalty is collected
+    let proceeds: Amount = test.call_method(collection, "proceeds_balance", args![], vec![]);
+    assert_eq!(proceeds, Amount(1000));
+    let royalties: Amount = test.call_method(collection, "royalties_balance", args![], vec![]);
+    assert_eq!(royalties, Amount::zero());
+
+    // The resale attracts a 10% royalty
+    buy_with(&mut test, collection, |builder| {
+        builder.call_method(market, "buy", args![NonFungibleId::from_u64(1), Workspace("payment")])
+    });
+
+    let proceeds: Amount = test.call_method(market, "proceeds_balance", args![], vec![]);
+    assert_eq!(proceeds, Amount(900));
+    let royalties: Amount = test.call_method(collection, "royalties_balance", args![], vec![]);
+    assert_eq!(royalties, Amount(100));
+}
+
+#[test]
+fn it_collects_royalties_if_the_payment_is_made_before_the_nft_is_withdrawn() {
+    let RoyaltyTest {
+        mut test,
+        collection,
+        market,
+        ..
+    } = setup();
+
+    buy_with(&mut test, collection, |builder| {
+        builder
+            .call_method(market, "pay", args![Workspace("payment")])
+            .call_method(market, "release", args![NonFungibleId::from_u64(1)])
+    });
+
+    let proceeds: Amount = test.call_method(market, "proceeds_balance", args![], vec![]);
+    assert_eq!(proceeds, Amount(900));
+    let royalties: Amount = test.call_method(collection, "royalties_balance", args![], vec![]);
+    assert_eq!(royalties, Amount(100));
+}
+
+#[test]
+fn it_collects_royalties_if_the_payment_is_made_to_a_different_component() {
+    let RoyaltyTest {
+        mut test,
+        collection,
+        market,
+        payment_resource,
+        reseller,
+    } = setup();
+
+    // The seller is paid into their account rather than into the market that holds the NFT
+    buy_with(&mut test, collection, |builder| {
+        builder
+            .call_method(reseller, "deposit", args![Workspace("payment")])
+            .call_method(market, "release", args![NonFungibleId::from_u64(1)])
+    });
+
+    let balance: Amount = test.call_method(reseller, "balance", args![payment_resource], vec![]);
+    assert_eq!(balance, Amount(900));
+    let royalties: Amount = test.call_method(collection, "royalties_balance", args![], vec![]);
+    assert_eq!(royalties, Amount(100));
+    let proceeds: Amount = test.call_method(market, "proceeds_balance", args![], vec![]);
+    assert_eq!(proceeds, Amount::zero());
+}
+
+#[test]
+fn it_rejects_a_royalty_vault_that_is_not_valid() {
+    let RoyaltyTest { mut test, market, .. } = setup();
+    let template_address = test.get_template_address("RoyaltyMarket");
+
+    let missing_vault = VaultId::new(ObjectKey::from_array([1u8; ObjectKey::LENGTH]));
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(template_address, "new_collection_with_royalty_vault", args![
+                10u8,
+                missing_vault
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, format!("Royalty vault {} does not exist", missing_vault));
+
+    // Royalties cannot be paid into a vault of non-fungibles
+    let nft_vault: VaultId = test.call_method(market, "nft_vault_id", args![], vec![]);
+    let reason = test.execute_expect_failure(
+        Transaction::builder()
+            .call_function(template_address, "new_collection_with_royalty_vault", args![
+                10u8, nft_vault
+            ])
+            .sign(test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_reject_reason(reason, "must hold a fungible resource");
+}
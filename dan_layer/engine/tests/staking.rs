@@ -0,0 +1,304 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
+use tari_template_builtin::STAKING_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    arg,
+    args,
+    args::Arg,
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, ResourceAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, SubstateType, TemplateTest};
+use tari_transaction::Transaction;
+
+/// Mirrors the StakePosition type of the staking template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct StakePosition {
+    amount: Amount,
+    rewards: Amount,
+    unlock_epoch: u64,
+}
+
+struct Party {
+    account: ComponentAddress,
+    proof: NonFungibleAddress,
+    key: RistrettoSecretKey,
+}
+
+struct StakingTest {
+    test: TemplateTest,
+    pool: ComponentAddress,
+    stake_resource: ResourceAddress,
+    reward_resource: ResourceAddress,
+    position_badge: ResourceAddress,
+    admin_badge: ResourceAddress,
+}
+
+impl StakingTest {
+    /// Creates a pool that emits 100 rewards per epoch and locks stakes for 5 epochs, funded with 1000 rewards. The
+    /// first party holds the admin badge and both parties hold 1000 stake tokens.
+    fn setup() -> (Self, Party, Party) {
+        let mut test = TemplateTest::new(Vec::<&str>::new());
+        let (account, proof, key) = test.create_funded_account();
+        let owner = Party { account, proof, key };
+        let (account, proof, key) = test.create_funded_account();
+        let staker = Party { account, proof, key };
+        let (stake_faucet, stake_resource) = create_faucet(&mut test, "STK");
+        let (reward_faucet, reward_resource) = create_faucet(&mut test, "RWD");
+
+        let result = test.execute_expect_success(
+            Transaction::builder()
+                .call_method(stake_faucet, "take_free_coins", args![])
+                .put_last_instruction_output_on_workspace("owner_stake")
+                .call_method(owner.account, "deposit", args![Workspace("owner_stake")])
+                .call_method(stake_faucet, "take_free_coins", args![])
+                .put_last_instruction_output_on_workspace("staker_stake")
+                .call_method(staker.account, "deposit", args![Workspace("staker_stake")])
+                .call_function(STAKING_TEMPLATE_ADDRESS, "new", args![
+                    stake_resource,
+                    reward_resource,
+                    Amount(100),
+                    5u64
+                ])
+                .put_last_instruction_output_on_workspace("pool")
+                .call_method(owner.account, "deposit", args![Workspace("pool.1")])
+                .sign(&owner.key)
+                .build(),
+            vec![owner.proof.clone()],
+        );
+        let pool: ComponentAddress = result.finalize.execution_results[6].get_value("$.0").unwrap().unwrap();
+        let position_badge = test.extract_component_value(pool, "$.position_badge");
+        let admin_badge = test.extract_component_value(pool, "$.admin_badge");
+
+        test.execute_expect_success(
+            Transaction::builder()
+                .call_method(reward_faucet, "take_free_coins", args![])
+                .put_last_instruction_output_on_workspace("rewards")
+                .call_method(pool, "fund", args![Workspace("rewards")])
+                .sign(&owner.key)
+                .build(),
+            vec![owner.proof.clone()],
+        );
+
+        let staking = Self {
+            test,
+            pool,
+            stake_resource,
+            reward_resource,
+            position_badge,
+            admin_badge,
+        };
+        (staking, owner, staker)
+    }
+
+    fn stake(&mut self, party: &Party, amount: Amount) {
+        self.test.execute_expect_success(
+            Transaction::builder()
+                .call_method(party.account, "withdraw", args![self.stake_resource, amount])
+                .put_last_instruction_output_on_workspace("stake")
+                .call_method(self.pool, "stake", args![Workspace("stake")])
+                .put_last_instruction_output_on_workspace("badge")
+                .call_method(party.account, "deposit", args![Workspace("badge")])
+                .sign(&party.key)
+                .build(),
+            vec![party.proof.clone()],
+        );
+    }
+
+    fn claim_rewards_transaction(&self, party: &Party) -> Transaction {
+        Transaction::builder()
+            .call_method(party.account, "create_proof_for_resource", args![self.position_badge])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(self.pool, "claim_rewards", args![Workspace("proof")])
+            .put_last_instruction_output_on_workspace("rewards")
+            .call_method(party.account, "deposit", args![Workspace("rewards")])
+            .drop_all_proofs_in_workspace()
+            .sign(&party.key)
+            .build()
+    }
+
+    /// Builds a transaction that unstakes the position, optionally claiming its rewards first
+    fn unstake_transaction(&self, party: &Party, position_id: u64, claim_rewards: bool) -> Transaction {
+        let mut builder = Transaction::builder();
+        if claim_rewards {
+            builder = builder
+                .call_method(party.account, "create_proof_for_resource", args![self.position_badge])
+                .put_last_instruction_output_on_workspace("proof")
+                .call_method(self.pool, "claim_rewards", args![Workspace("proof")])
+                .put_last_instruction_output_on_workspace("rewards")
+                .call_method(party.account, "deposit", args![Workspace("rewards")])
+                .drop_all_proofs_in_workspace();
+        }
+        builder
+            .call_method(party.account, "withdraw_non_fungible", args![
+                self.position_badge,
+                NonFungibleId::from_u64(position_id)
+            ])
+            .put_last_instruction_output_on_workspace("badge")
+            .call_method(self.pool, "unstake", args![Workspace("badge")])
+            .put_last_instruction_output_on_workspace("stake")
+            .call_method(party.account, "deposit", args![Workspace("stake")])
+            .sign(&party.key)
+            .build()
+    }
+
+    /// Builds a transaction that calls an admin method on the pool with a proof of the given badge resource, depositing
+    /// any returned tokens into the party's account
+    fn admin_transaction(
+        &self,
+        party: &Party,
+        badge: ResourceAddress,
+        method: &str,
+        method_args: Vec<Arg>,
+        returns_tokens: bool,
+    ) -> Transaction {
+        let mut call_args = vec![arg!(Workspace("proof"))];
+        call_args.extend(method_args);
+        let mut builder = Transaction::builder()
+            .call_method(party.account, "create_proof_for_resource", args![badge])
+            .put_last_instruction_output_on_workspace("proof")
+            .call_method(self.pool, method, call_args);
+        if returns_tokens {
+            builder = builder.put_last_instruction_output_on_workspace("tokens").call_method(
+                party.account,
+                "deposit",
+                args![Workspace("tokens")],
+            );
+        }
+        builder.drop_all_proofs_in_workspace().sign(&party.key).build()
+    }
+
+    fn balance(&mut self, party: &Party, resource: ResourceAddress) -> Amount {
+        self.test.call_method(party.account, "balance", args![resource], vec![])
+    }
+
+    fn get_position(&mut self, position_id: u64) -> Option<StakePosition> {
+        self.test
+            .call_method(self.pool, "get_position", args![position_id], vec![])
+    }
+
+    fn set_epoch(&mut self, epoch: u64) {
+        self.test
+            .set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(epoch));
+    }
+}
+
+fn create_faucet(test: &mut TemplateTest, symbol: &str) -> (ComponentAddress, ResourceAddress) {
+    let faucet: ComponentAddress = test.call_function(
+        "TestFaucet",
+        "mint_with_symbol",
+        args![Amount(1_000_000), symbol],
+        vec![],
+    );
+    let resource = test
+        .get_previous_output_address(SubstateType::Resource)
+        .as_resource_address()
+        .unwrap();
+    (faucet, resource)
+}
+
+#[test]
+fn it_emits_rewards_in_proportion_to_stake() {
+    let (mut staking, owner, staker) = StakingTest::setup();
+    staking.stake(&owner, Amount(100));
+    staking.stake(&staker, Amount(300));
+
+    let transaction = staking.claim_rewards_transaction(&owner);
+    let reason = staking
+        .test
+        .execute_expect_failure(transaction, vec![owner.proof.clone()]);
+    assert_reject_reason(reason, "There are no rewards to claim");
+
+    // 200 rewards are emitted over two epochs
+    staking.set_epoch(2);
+    let transaction = staking.claim_rewards_transaction(&owner);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![owner.proof.clone()]);
+    let transaction = staking.claim_rewards_transaction(&staker);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![staker.proof.clone()]);
+
+    let reward_resource = staking.reward_resource;
+    assert_eq!(staking.balance(&owner, reward_resource), Amount(50));
+    assert_eq!(staking.balance(&staker, reward_resource), Amount(150));
+    let remaining: Amount = staking
+        .test
+        .call_method(staking.pool, "remaining_rewards", args![], vec![]);
+    assert_eq!(remaining, Amount(800));
+
+    // Emission stops once the funded rewards run out
+    staking.set_epoch(100);
+    let transaction = staking.claim_rewards_transaction(&staker);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![staker.proof.clone()]);
+    assert_eq!(staking.balance(&staker, reward_resource), Amount(750));
+}
+
+#[test]
+fn it_locks_stakes_until_the_unlock_epoch() {
+    let (mut staking, owner, _) = StakingTest::setup();
+    staking.stake(&owner, Amount(100));
+
+    staking.set_epoch(3);
+    let transaction = staking.unstake_transaction(&owner, 0, true);
+    let reason = staking
+        .test
+        .execute_expect_failure(transaction, vec![owner.proof.clone()]);
+    assert_reject_reason(reason, "Position 0 is locked until epoch 5");
+
+    staking.set_epoch(5);
+    let transaction = staking.unstake_transaction(&owner, 0, false);
+    let reason = staking
+        .test
+        .execute_expect_failure(transaction, vec![owner.proof.clone()]);
+    assert_reject_reason(reason, "Position 0 has unclaimed rewards");
+
+    let transaction = staking.unstake_transaction(&owner, 0, true);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![owner.proof.clone()]);
+
+    let (stake_resource, reward_resource) = (staking.stake_resource, staking.reward_resource);
+    assert_eq!(staking.balance(&owner, stake_resource), Amount(1000));
+    assert_eq!(staking.balance(&owner, reward_resource), Amount(500));
+    assert_eq!(staking.get_position(0), None);
+}
+
+#[test]
+fn it_allows_the_admin_to_slash_and_unlock() {
+    let (mut staking, owner, staker) = StakingTest::setup();
+    staking.stake(&staker, Amount(300));
+    let (admin_badge, position_badge) = (staking.admin_badge, staking.position_badge);
+
+    let transaction = staking.admin_transaction(&staker, position_badge, "slash", args![0u64, Amount(100)], true);
+    let reason = staking
+        .test
+        .execute_expect_failure(transaction, vec![staker.proof.clone()]);
+    assert_reject_reason(reason, "Proof of resource did not match");
+
+    let transaction = staking.admin_transaction(&owner, admin_badge, "slash", args![0u64, Amount(100)], true);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![owner.proof.clone()]);
+    let stake_resource = staking.stake_resource;
+    assert_eq!(staking.balance(&owner, stake_resource), Amount(1100));
+    assert_eq!(staking.get_position(0).unwrap().amount, Amount(200));
+
+    staking.set_epoch(1);
+    let transaction = staking.admin_transaction(&owner, admin_badge, "unlock", args![0u64], false);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![owner.proof.clone()]);
+
+    let transaction = staking.unstake_transaction(&staker, 0, true);
+    staking
+        .test
+        .execute_expect_success(transaction, vec![staker.proof.clone()]);
+    assert_eq!(staking.balance(&staker, stake_resource), Amount(900));
+}
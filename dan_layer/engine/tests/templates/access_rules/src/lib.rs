@@ -114,6 +114,28 @@ mod access_rules_template {
             .create()
         }
 
+        pub fn with_caller_state_auth_hook() -> Component<AccessRulesTest> {
+            let badges = create_badge_resource(rule!(deny_all));
+
+            let address_alloc = CallerContext::allocate_component_address(None);
+
+            let tokens = ResourceBuilder::fungible()
+                .mintable(rule!(allow_all))
+                .with_authorization_hook(*address_alloc.address(), "caller_state_auth_hook")
+                .initial_supply(1000);
+
+            Component::new(Self {
+                value: 0,
+                tokens: Vault::from_bucket(tokens),
+                badges: Vault::from_bucket(badges),
+                allowed: true,
+                attack_component: None,
+            })
+            .with_address_allocation(address_alloc)
+            .with_access_rules(ComponentAccessRules::new().default(rule!(allow_all)))
+            .create()
+        }
+
         pub fn using_badge_rules() -> Component<AccessRulesTest> {
             let badges = create_badge_resource(rule!(allow_all));
 
@@ -193,6 +215,18 @@ mod access_rules_template {
             }
         }
 
+        /// Only allows minting by AccessRulesTest components that are allowed in their own state
+        pub fn caller_state_auth_hook(&self, action: ResourceAuthAction, caller: AuthHookCaller) {
+            if !matches!(action, ResourceAuthAction::Mint) {
+                return;
+            }
+            let snapshot = caller.component_snapshot().expect("Mint must be called by a component");
+            let caller_state = snapshot.decode_state::<AccessRulesTest>();
+            if !caller_state.allowed {
+                panic!("Caller {} is not allowed to mint", snapshot.address());
+            }
+        }
+
         pub fn malicious_auth_hook_set_state(&self, action: ResourceAuthAction, caller: AuthHookCaller) {
             debug!("malicious_auth_hook_set_state: action = {:?}", action);
             let caller = caller.component().unwrap();
@@ -276,6 +310,17 @@ mod access_rules_template {
             }
         }
 
+        pub fn take_tokens_using_combined_proofs(
+            &mut self,
+            withdraw_proof: Proof,
+            deposit_proof: Proof,
+            amount: Amount,
+        ) -> (Bucket, Proof) {
+            let proof = Proof::combine(vec![withdraw_proof, deposit_proof]);
+            let tokens = proof.authorize_with(|| self.tokens.withdraw(amount));
+            (tokens, proof)
+        }
+
         pub fn get_nft_data_using_proof(&self, proof: Proof) -> Vec<Metadata> {
             let nfts = proof.get_non_fungibles();
             let manager = ResourceManager::get(proof.resource_address());
@@ -303,10 +348,6 @@ mod access_rules_template {
             ResourceManager::get(self.tokens.resource_address()).set_access_rules(access_rules);
         }
 
-        pub fn create_proof_from_bucket(bucket: Bucket) -> Proof {
-            bucket.create_proof()
-        }
-
         pub fn mint_resource(resource: ResourceAddress) -> Bucket {
             let manager = ResourceManager::get(resource);
             match manager.resource_type() {
@@ -316,6 +357,10 @@ mod access_rules_template {
             }
         }
 
+        pub fn mint_tokens_of(&self, resource: ResourceAddress, amount: Amount) -> Bucket {
+            ResourceManager::get(resource).mint_fungible(amount)
+        }
+
         pub fn mint_more_tokens(&mut self, amount: Amount) -> Bucket {
             ResourceManager::get(self.tokens.resource_address()).mint_fungible(amount)
         }
@@ -19,6 +19,23 @@ mod template {
             )
         }
 
+        pub fn create_reserved(index: u32) -> Component<Self> {
+            let allocation = CallerContext::allocate_reserved_component_address(index);
+            Component::new(Self {}).with_address_allocation(allocation).create()
+        }
+
+        pub fn create_named(name: String) -> (Component<Self>, ResourceAddress) {
+            let resource_allocation = CallerContext::allocate_named_resource_address(name.clone());
+            let resource = ResourceBuilder::fungible()
+                .with_address_allocation(resource_allocation)
+                .build();
+            let allocation = CallerContext::allocate_named_component_address(name);
+            (
+                Component::new(Self {}).with_address_allocation(allocation).create(),
+                resource,
+            )
+        }
+
         pub fn drop_allocation() {
             let _allocation = CallerContext::allocate_component_address(None);
         }
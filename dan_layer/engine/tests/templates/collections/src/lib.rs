@@ -0,0 +1,51 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod template {
+    use super::*;
+
+    pub struct CollectionsTest {
+        vaults: IndexMap<String, Vault>,
+        values: IndexSet<u64>,
+    }
+
+    impl CollectionsTest {
+        pub fn new() -> Component<Self> {
+            let mut vaults = IndexMap::default();
+            vaults.insert(
+                "zebra".to_string(),
+                Vault::from_bucket(ResourceBuilder::fungible().initial_supply(200)),
+            );
+            vaults.insert(
+                "apple".to_string(),
+                Vault::from_bucket(ResourceBuilder::fungible().initial_supply(100)),
+            );
+
+            Component::new(Self {
+                vaults,
+                values: IndexSet::default(),
+            })
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        pub fn vault_names(&self) -> Vec<String> {
+            self.vaults.keys().cloned().collect()
+        }
+
+        pub fn balance_of(&self, name: String) -> Amount {
+            self.vaults.get(&name).map(|v| v.balance()).unwrap_or_default()
+        }
+
+        pub fn insert_value(&mut self, value: u64) -> bool {
+            self.values.insert(value)
+        }
+
+        pub fn values(&self) -> Vec<u64> {
+            self.values.iter().copied().collect()
+        }
+    }
+}
@@ -26,11 +26,47 @@ use tari_template_lib::prelude::*;
 mod template {
     use super::*;
 
-    pub struct ComponentManagerTest;
+    pub struct ComponentManagerTest {
+        vault: Vault,
+    }
 
     impl ComponentManagerTest {
+        pub fn new() -> Component<Self> {
+            let tokens = ResourceBuilder::fungible().initial_supply(1000);
+            Component::new(Self {
+                vault: Vault::from_bucket(tokens),
+            })
+            .create()
+        }
+
+        pub fn with_balance_query_rule(rule: AccessRule) -> Component<Self> {
+            let tokens = ResourceBuilder::fungible().initial_supply(1000);
+            Component::new(Self {
+                vault: Vault::from_bucket(tokens),
+            })
+            .with_access_rules(ComponentAccessRules::allow_all().balance_query(rule))
+            .create()
+        }
+
+        pub fn resource_address(&self) -> ResourceAddress {
+            self.vault.resource_address()
+        }
+
+        pub fn get_vault_balance_of(&self, component_address: ComponentAddress, resource: ResourceAddress) -> Amount {
+            ComponentManager::get(component_address).get_vault_balance(resource)
+        }
+
         pub fn get_template_address_for_component(component_address: ComponentAddress) -> TemplateAddress {
             ComponentManager::get(component_address).get_template_address()
         }
+
+        pub fn destroy(&mut self) {
+            ComponentManager::current().destroy();
+        }
+
+        pub fn withdraw_all_and_destroy(&mut self) -> Bucket {
+            ComponentManager::current().destroy();
+            self.vault.withdraw_all()
+        }
     }
 }
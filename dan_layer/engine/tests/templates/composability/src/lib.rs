@@ -129,14 +129,5 @@ mod composability {
         ) -> tari_bor::Value {
             ComponentManager::get(component_address).call(method_name, args)
         }
-
-        pub fn call_component_with_args_using_proof(
-            component_address: ComponentAddress,
-            method_name: String,
-            _proof: Proof,
-            args: Vec<tari_template_lib::args::Arg>,
-        ) -> tari_bor::Value {
-            ComponentManager::get(component_address).call(method_name, args)
-        }
     }
 }
@@ -32,5 +32,13 @@ mod consensus_template {
         pub fn current_epoch() -> u64 {
             Consensus::current_epoch()    
         }
+
+        pub fn timestamp() -> u64 {
+            Consensus::timestamp()
+        }
+
+        pub fn random_beacon() -> Hash {
+            Consensus::random_beacon()
+        }
     }
 }
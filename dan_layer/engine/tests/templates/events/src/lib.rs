@@ -24,6 +24,12 @@ use std::collections::HashMap;
 
 use tari_template_lib::prelude::*;
 
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Transfer {
+    pub amount: Amount,
+    pub memo: String,
+}
+
 #[template]
 mod event {
     use super::*;
@@ -36,5 +42,14 @@ mod event {
             let payload = [("my", "event")];
             emit_event(topic, payload);
         }
+
+        pub fn emit_typed(amount: Amount, memo: String) {
+            emit_typed_event("transfer", [("memo", memo.clone())], &Transfer { amount, memo });
+        }
+
+        pub fn emit_indexed(amount: Amount, memo: String, indexed_fields: Vec<String>) {
+            let indexed_fields = indexed_fields.iter().map(|f| f.as_str()).collect::<Vec<_>>();
+            emit_indexed_event("transfer", &indexed_fields, &Transfer { amount, memo });
+        }
     }
 }
@@ -0,0 +1,59 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod sponsored_counter_template {
+    use super::*;
+
+    pub struct SponsoredCounter {
+        fees: Vault,
+        value: u32,
+    }
+
+    impl SponsoredCounter {
+        pub fn new(fee_bucket: Bucket) -> Component<Self> {
+            let fees = Vault::from_bucket(fee_bucket);
+            let fee_vault_id = fees.vault_id();
+            Component::new(Self { fees, value: 0 })
+                .with_access_rules(AccessRules::allow_all())
+                .with_fee_sponsor(fee_vault_id, "fee_policy")
+                .create()
+        }
+
+        pub fn with_invalid_policy(fee_bucket: Bucket) -> Component<Self> {
+            let fees = Vault::from_bucket(fee_bucket);
+            let fee_vault_id = fees.vault_id();
+            Component::new(Self { fees, value: 0 })
+                .with_access_rules(AccessRules::allow_all())
+                .with_fee_sponsor(fee_vault_id, "set")
+                .create()
+        }
+
+        /// Sponsors calls to `increase` only
+        pub fn fee_policy(&self, method: String) -> Amount {
+            if method == "increase" {
+                Amount(1000)
+            } else {
+                Amount::zero()
+            }
+        }
+
+        pub fn increase(&mut self) {
+            self.value += 1;
+        }
+
+        pub fn set(&mut self, value: u32) {
+            self.value = value;
+        }
+
+        pub fn get(&self) -> u32 {
+            self.value
+        }
+
+        pub fn fee_balance(&self) -> Amount {
+            self.fees.balance()
+        }
+    }
+}
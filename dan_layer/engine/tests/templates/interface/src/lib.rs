@@ -0,0 +1,36 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+/// The methods of the State test template
+#[interface]
+pub trait State {
+    fn get(&self) -> u32;
+    fn set(&mut self, value: u32);
+}
+
+/// Does not match the State test template, `get` returns a u32
+#[interface]
+pub trait MismatchedState {
+    fn get(&self) -> String;
+}
+
+#[template]
+mod interface_template {
+    use super::*;
+
+    pub struct InterfaceCaller {}
+
+    impl InterfaceCaller {
+        pub fn set_and_get(state: ComponentAddress, value: u32) -> u32 {
+            let mut state = StateInterface::new(state);
+            state.set(value);
+            state.get()
+        }
+
+        pub fn get_mismatched(state: ComponentAddress) -> String {
+            MismatchedStateInterface::new(state).get()
+        }
+    }
+}
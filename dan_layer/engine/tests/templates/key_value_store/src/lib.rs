@@ -0,0 +1,35 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod key_value_store_template {
+    use super::*;
+
+    pub struct KeyValueStoreTest {
+        map: KeyValueStore<String, u64>,
+    }
+
+    impl KeyValueStoreTest {
+        pub fn new() -> Component<Self> {
+            Component::new(Self {
+                map: KeyValueStore::new(),
+            })
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        pub fn get(&self, key: String) -> Option<u64> {
+            self.map.get(&key)
+        }
+
+        pub fn insert(&mut self, key: String, value: u64) -> Option<u64> {
+            self.map.insert(key, value)
+        }
+
+        pub fn remove(&mut self, key: String) -> Option<u64> {
+            self.map.remove(&key)
+        }
+    }
+}
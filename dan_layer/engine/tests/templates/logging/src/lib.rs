@@ -0,0 +1,27 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{args::LogLevel, engine::engine, prelude::*};
+
+#[template]
+mod logging {
+    use super::*;
+
+    pub struct Logger {}
+
+    impl Logger {
+        pub fn log_with_fields(message: String, fields: Metadata) {
+            engine().emit_structured_log(LogLevel::Warn, message, fields);
+        }
+
+        pub fn log_many(count: u32) {
+            for i in 0..count {
+                info!("log entry {}", i);
+            }
+        }
+
+        pub fn log_message(message: String) {
+            info!(message);
+        }
+    }
+}
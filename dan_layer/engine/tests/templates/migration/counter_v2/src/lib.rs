@@ -0,0 +1,43 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+/// The state of the CounterV1 template that is migrated from
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CounterV1State {
+    pub value: u32,
+}
+
+#[template]
+mod template {
+    use super::*;
+
+    pub struct CounterV2 {
+        value: u64,
+        step: u64,
+    }
+
+    impl CounterV2 {
+        /// Migrates a CounterV1 component to this template
+        pub fn upgrade(component: ComponentAddress, template_address: TemplateAddress) {
+            ComponentManager::get(component).migrate(template_address);
+        }
+
+        /// Called by the engine with the state of the component being migrated
+        pub fn migrate(old_state: CounterV1State) -> CounterV2 {
+            Self {
+                value: u64::from(old_state.value),
+                step: 10,
+            }
+        }
+
+        pub fn increase(&mut self) {
+            self.value += self.step;
+        }
+
+        pub fn value(&self) -> u64 {
+            self.value
+        }
+    }
+}
@@ -19,6 +19,8 @@
 //   SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+use std::collections::BTreeMap;
+
 use tari_template_lib::prelude::*;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -97,6 +99,11 @@ mod sparkle_nft_template {
             });
         }
 
+        pub fn patch_sparkle(&mut self, id: NonFungibleId, field: String, value: u32) {
+            let mut nft = ResourceManager::get(self.address).get_non_fungible(&id);
+            nft.patch_mutable_data(&BTreeMap::from([(field, value)]));
+        }
+
         fn with_sparkle_mut<F: FnOnce(&mut Sparkle)>(&self, id: NonFungibleId, f: F) {
             let resource_manager = ResourceManager::get(self.address);
             let mut nft = resource_manager.get_non_fungible(&id);
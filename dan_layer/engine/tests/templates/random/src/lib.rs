@@ -28,6 +28,7 @@ mod random_template {
 
     pub struct RandomTest {
         random: u32,
+        random_u64: u64,
         random_bytes: Vec<u8>,
         random_long_bytes: Vec<u8>,
     }
@@ -35,10 +36,12 @@ mod random_template {
     impl RandomTest {
         pub fn create() -> Component<Self> {
             let random: u32 = rand::random_u32();
+            let random_u64 = rand::random_u64();
             let random_bytes = rand::random_bytes(32);
             let random_long_bytes = rand::random_bytes(300);
             Component::new(Self {
                 random,
+                random_u64,
                 random_bytes,
                 random_long_bytes,
             })
@@ -50,6 +53,10 @@ mod random_template {
             self.random
         }
 
+        pub fn get_random_u64(&self) -> u64 {
+            self.random_u64
+        }
+
         pub fn get_random_bytes(&self) -> Vec<u8> {
             self.random_bytes.clone()
         }
@@ -59,6 +59,10 @@ mod template {
             (fungible, non_fungible, confidential)
         }
 
+        pub fn withdraw_non_fungibles(&mut self, ids: BTreeSet<NonFungibleId>) -> Bucket {
+            self.non_fungible.withdraw_non_fungibles(ids)
+        }
+
         pub fn recall_fungible_all(&mut self, vault_id: VaultId) {
             let bucket = ResourceManager::get(self.fungible.resource_address()).recall_fungible_all(vault_id);
             match bucket.resource_type() {
@@ -0,0 +1,49 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+/// A factory component that owns and administers the child components that it creates
+#[template]
+mod registry_template {
+    use super::*;
+
+    pub struct Registry {
+        value: u32,
+        children: Vec<ComponentAddress>,
+    }
+
+    impl Registry {
+        pub fn new() -> Component<Self> {
+            Component::new(Self {
+                value: 0,
+                children: vec![],
+            })
+            .create()
+        }
+
+        pub fn create_child(&mut self) -> ComponentAddress {
+            let registry = CallerContext::current_component_address();
+            let child = Component::new(Self {
+                value: 0,
+                children: vec![],
+            })
+            .with_owner_rule(OwnerRule::OwnedByComponent(registry))
+            .create();
+            self.children.push(*child.address());
+            *child.address()
+        }
+
+        pub fn set_child_value(&self, child: ComponentAddress, value: u32) {
+            ComponentManager::get(child).invoke("set_value", args![value]);
+        }
+
+        pub fn set_value(&mut self, value: u32) {
+            self.value = value;
+        }
+
+        pub fn get_value(&self) -> u32 {
+            self.value
+        }
+    }
+}
@@ -30,21 +30,30 @@ mod template {
         fungible: Vault,
         non_fungible: Vault,
         confidential: Vault,
+        soulbound: Vault,
     }
 
     impl ResourceTest {
         pub fn new() -> Component<Self> {
-            let fungible = ResourceBuilder::fungible().initial_supply(1000);
+            let fungible = ResourceBuilder::fungible()
+                .with_token_symbol("TST")
+                .update_metadata(rule!(allow_all))
+                .initial_supply(1000);
             let non_fungible = ResourceBuilder::non_fungible()
                 .initial_supply([NonFungibleId::from_u64(1), NonFungibleId::from_u64(2)]);
             let confidential = ResourceBuilder::confidential()
                 .mintable(rule!(allow_all))
                 .initial_supply(ConfidentialOutputStatement::mint_revealed(1000));
+            let soulbound = ResourceBuilder::non_fungible()
+                .soulbound()
+                .burnable(rule!(allow_all))
+                .initial_supply([NonFungibleId::from_u64(1), NonFungibleId::from_u64(2)]);
 
             Component::new(Self {
                 fungible: Vault::from_bucket(fungible),
                 non_fungible: Vault::from_bucket(non_fungible),
                 confidential: Vault::from_bucket(confidential),
+                soulbound: Vault::from_bucket(soulbound),
             })
             .with_access_rules(AccessRules::allow_all())
             .create()
@@ -66,6 +75,32 @@ mod template {
             self.non_fungible.deposit(joined);
         }
 
+        pub fn fungible_split(&self) {
+            let bucket = self.fungible.withdraw(100);
+            let (b1, b2) = bucket.split(Amount(30));
+            assert_eq!(b1.amount(), 30);
+            assert_eq!(b2.amount(), 70);
+            self.fungible.deposit(b1.join(b2));
+        }
+
+        pub fn non_fungible_split(&self) {
+            let bucket = self
+                .non_fungible
+                .withdraw_non_fungibles([NonFungibleId::from_u64(1), NonFungibleId::from_u64(2)]);
+            let (b1, b2) = bucket.split_non_fungibles([NonFungibleId::from_u64(2)]);
+            assert_eq!(b1.get_non_fungible_ids(), vec![NonFungibleId::from_u64(2)]);
+            assert_eq!(b2.get_non_fungible_ids(), vec![NonFungibleId::from_u64(1)]);
+            self.non_fungible.deposit(b1.join(b2));
+        }
+
+        pub fn non_fungible_ids_paged(&self, offset: u32, limit: u32) -> Vec<NonFungibleId> {
+            self.non_fungible.get_non_fungible_ids_paged(offset, limit)
+        }
+
+        pub fn non_fungible_count(&self) -> u32 {
+            self.non_fungible.non_fungible_count()
+        }
+
         pub fn confidential_join(&self, output: ConfidentialOutputStatement) {
             let commitments = ResourceManager::get(self.confidential.resource_address()).mint_confidential(output);
             let b1 = self
@@ -80,5 +115,60 @@ mod template {
             self.confidential.deposit(joined);
             assert_eq!(self.confidential.commitment_count(), 1);
         }
+
+        pub fn fungible_resource_address(&self) -> ResourceAddress {
+            self.fungible.resource_address()
+        }
+
+        pub fn update_fungible_metadata(&self, metadata: Metadata) {
+            ResourceManager::get(self.fungible.resource_address()).update_metadata(metadata);
+        }
+
+        pub fn update_non_fungible_metadata(&self, metadata: Metadata) {
+            ResourceManager::get(self.non_fungible.resource_address()).update_metadata(metadata);
+        }
+
+        pub fn freeze_fungible(&self) {
+            ResourceManager::get(self.fungible.resource_address()).freeze();
+        }
+
+        pub fn unfreeze_fungible(&self) {
+            ResourceManager::get(self.fungible.resource_address()).unfreeze();
+        }
+
+        pub fn freeze_non_fungible(&self) {
+            ResourceManager::get(self.non_fungible.resource_address()).freeze();
+        }
+
+        pub fn soulbound_transfer(&self) {
+            let other_vault = Vault::new_empty(self.soulbound.resource_address());
+            other_vault.deposit(self.soulbound.withdraw_non_fungible(NonFungibleId::from_u64(1)));
+        }
+
+        pub fn soulbound_burn(&self) {
+            self.soulbound.withdraw_non_fungible(NonFungibleId::from_u64(1)).burn();
+            assert_eq!(self.soulbound.balance(), 1);
+        }
+
+        pub fn mint_fungible_with_max_supply(&self, max_supply: Amount, initial_supply: Amount, mint_amount: Amount) {
+            let bucket = ResourceBuilder::fungible()
+                .mintable(rule!(allow_all))
+                .burnable(rule!(allow_all))
+                .with_max_supply(max_supply)
+                .initial_supply(initial_supply);
+            let minted = ResourceManager::get(bucket.resource_address()).mint_fungible(mint_amount);
+            bucket.join(minted).burn();
+        }
+
+        pub fn mint_confidential_with_max_supply(&self, max_supply: Amount, mint_amount: Amount) {
+            let resource_address = ResourceBuilder::confidential()
+                .mintable(rule!(allow_all))
+                .burnable(rule!(allow_all))
+                .with_max_supply(max_supply)
+                .build();
+            ResourceManager::get(resource_address)
+                .mint_confidential(ConfidentialOutputStatement::mint_revealed(mint_amount))
+                .burn();
+        }
     }
 }
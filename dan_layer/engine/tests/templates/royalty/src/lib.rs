@@ -0,0 +1,106 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod template {
+    use super::*;
+
+    pub struct RoyaltyMarket {
+        nfts: Vault,
+        proceeds: Vault,
+        royalties: Option<Vault>,
+        coins: Option<Vault>,
+    }
+
+    impl RoyaltyMarket {
+        /// Creates a collection of NFTs with a royalty, along with the coins that are used to pay for them
+        pub fn new_collection(percentage: u8) -> Component<Self> {
+            let coins = ResourceBuilder::fungible()
+                .with_token_symbol("PAY")
+                .initial_supply(1_000_000);
+            let royalties = Vault::new_empty(coins.resource_address());
+            let nfts = ResourceBuilder::non_fungible()
+                .with_royalty(percentage, royalties.vault_id())
+                .initial_supply([NonFungibleId::from_u64(1), NonFungibleId::from_u64(2)]);
+
+            Component::new(Self {
+                nfts: Vault::from_bucket(nfts),
+                proceeds: Vault::new_empty(coins.resource_address()),
+                royalties: Some(royalties),
+                coins: Some(Vault::from_bucket(coins)),
+            })
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        /// Creates a collection of NFTs whose royalties are paid into the given vault
+        pub fn new_collection_with_royalty_vault(percentage: u8, vault_id: VaultId) -> Component<Self> {
+            let coins = ResourceBuilder::fungible()
+                .with_token_symbol("PAY")
+                .initial_supply(1_000_000);
+            let nfts = ResourceBuilder::non_fungible()
+                .with_royalty(percentage, vault_id)
+                .initial_supply([NonFungibleId::from_u64(1)]);
+
+            Component::new(Self {
+                nfts: Vault::from_bucket(nfts),
+                proceeds: Vault::new_empty(coins.resource_address()),
+                royalties: None,
+                coins: Some(Vault::from_bucket(coins)),
+            })
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        /// Creates a market that resells the given NFTs
+        pub fn new_market(nfts: Bucket, payment_resource: ResourceAddress) -> Component<Self> {
+            Component::new(Self {
+                nfts: Vault::from_bucket(nfts),
+                proceeds: Vault::new_empty(payment_resource),
+                royalties: None,
+                coins: None,
+            })
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        pub fn payment_resource(&self) -> ResourceAddress {
+            self.proceeds.resource_address()
+        }
+
+        pub fn take_coins(&self, amount: Amount) -> Bucket {
+            self.coins
+                .as_ref()
+                .expect("No coins in this component")
+                .withdraw(amount)
+        }
+
+        pub fn buy(&self, id: NonFungibleId, payment: Bucket) -> Bucket {
+            let nft = self.nfts.withdraw_non_fungible(id);
+            self.proceeds.deposit(payment);
+            nft
+        }
+
+        pub fn pay(&self, payment: Bucket) {
+            self.proceeds.deposit(payment);
+        }
+
+        pub fn release(&self, id: NonFungibleId) -> Bucket {
+            self.nfts.withdraw_non_fungible(id)
+        }
+
+        pub fn nft_vault_id(&self) -> VaultId {
+            self.nfts.vault_id()
+        }
+
+        pub fn proceeds_balance(&self) -> Amount {
+            self.proceeds.balance()
+        }
+
+        pub fn royalties_balance(&self) -> Amount {
+            self.royalties.as_ref().map(|v| v.balance()).unwrap_or_default()
+        }
+    }
+}
@@ -19,7 +19,7 @@
 //   SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
-use std::iter;
+use std::{collections::BTreeMap, iter};
 
 use tari_dan_engine::{
     template::{TemplateLoaderError, TemplateModuleLoader},
@@ -27,6 +27,7 @@ use tari_dan_engine::{
 };
 use tari_engine_types::{
     commit_result::{FinalizeResult, RejectReason},
+    execution_failure::ExecutionError,
     instruction::Instruction,
     substate::SubstateId,
     virtual_substate::{VirtualSubstate, VirtualSubstateId},
@@ -35,11 +36,18 @@ use tari_engine_types::{
 use tari_template_builtin::{ACCOUNT_NFT_TEMPLATE_ADDRESS, ACCOUNT_TEMPLATE_ADDRESS};
 use tari_template_lib::{
     args,
+    args::ComponentAction,
+    auth::AccessRule,
     crypto::RistrettoPublicKeyBytes,
     models::{Amount, ComponentAddress, NonFungibleAddress},
     prelude::{NonFungibleId, ResourceAddress},
+    Hash,
+};
+use tari_template_test_tooling::{
+    support::assert_error::{assert_access_denied_for_action, assert_reject_reason},
+    SubstateType,
+    TemplateTest,
 };
-use tari_template_test_tooling::{support::assert_error::assert_reject_reason, SubstateType, TemplateTest};
 use tari_transaction::Transaction;
 use tari_transaction_manifest::ManifestValue;
 use tari_utilities::hex::to_hex;
@@ -239,9 +247,11 @@ fn test_engine_errors() {
     // Check that the engine error is captured in the execution result rather than the WASM panic message (Panic! Engine
     // call returned null for op VaultInvoke)
     assert_eq!(
-        reason,
+        reason.message,
         "Runtime error: Substate 'resource_7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b7b' not found"
     );
+    assert!(matches!(reason.error, ExecutionError::SubstateNotFound { .. }));
+    assert!(reason.instruction_index.is_some());
 }
 
 #[test]
@@ -292,6 +302,87 @@ fn test_get_template_address() {
     assert_eq!(addr, template_test.get_template_address("Account"));
 }
 
+#[test]
+fn test_destroy_component() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/component_manager"]);
+    let (account, _, _) = template_test.create_empty_account();
+    let owner_proof = template_test.get_test_proof();
+
+    let component: ComponentAddress = template_test.call_function("ComponentManagerTest", "new", args![], vec![]);
+
+    // The vault still holds funds
+    let reason = template_test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "destroy", args![])
+            .sign(template_test.get_test_secret_key())
+            .build(),
+        vec![owner_proof.clone()],
+    );
+    assert_reject_reason(reason, "Cannot destroy component");
+
+    // Only the owner may destroy the component
+    let (_, _, other_secret) = template_test.create_empty_account();
+    template_test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(component, "withdraw_all_and_destroy", args![])
+            .put_last_instruction_output_on_workspace("tokens")
+            .call_method(account, "deposit", args![Workspace("tokens")])
+            .sign(&other_secret)
+            .build(),
+        vec![],
+    );
+
+    let result = template_test.execute_expect_success(
+        Transaction::builder()
+            .call_method(component, "withdraw_all_and_destroy", args![])
+            .put_last_instruction_output_on_workspace("tokens")
+            .call_method(account, "deposit", args![Workspace("tokens")])
+            .sign(template_test.get_test_secret_key())
+            .build(),
+        vec![owner_proof],
+    );
+
+    let diff = result.finalize.result.accept().unwrap();
+    assert!(diff.down_iter().any(|(id, _)| *id == SubstateId::Component(component)));
+    assert!(diff.down_iter().any(|(id, _)| id.is_vault()));
+    assert!(!diff.up_iter().any(|(id, _)| *id == SubstateId::Component(component)));
+}
+
+#[test]
+fn test_get_vault_balance_of_another_component() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/component_manager"]);
+
+    let private: ComponentAddress = template_test.call_function(
+        "ComponentManagerTest",
+        "with_balance_query_rule",
+        args![AccessRule::DenyAll],
+        vec![],
+    );
+    let public: ComponentAddress = template_test.call_function(
+        "ComponentManagerTest",
+        "with_balance_query_rule",
+        args![AccessRule::AllowAll],
+        vec![],
+    );
+    let resource: ResourceAddress = template_test.call_method(public, "resource_address", args![], vec![]);
+
+    // A component may always query its own balance
+    let balance: Amount = template_test.call_method(private, "get_vault_balance_of", args![private, resource], vec![]);
+    assert_eq!(balance, 1000);
+
+    let balance: Amount = template_test.call_method(private, "get_vault_balance_of", args![public, resource], vec![]);
+    assert_eq!(balance, 1000);
+
+    let reason = template_test.execute_expect_failure(
+        Transaction::builder()
+            .call_method(public, "get_vault_balance_of", args![private, resource])
+            .sign(template_test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    assert_access_denied_for_action(reason, ComponentAction::GetVaultBalance);
+}
+
 #[test]
 fn test_caller_context() {
     let mut template_test = TemplateTest::new(vec!["tests/templates/caller_context"]);
@@ -312,6 +403,9 @@ fn test_random() {
     let value: u32 = template_test.call_method(component_address, "get_random", args![], vec![]);
     assert_ne!(value, 0);
 
+    let value: u64 = template_test.call_method(component_address, "get_random_u64", args![], vec![]);
+    assert_ne!(value, 0);
+
     let value: Vec<u8> = template_test.call_method(component_address, "get_random_bytes", args![], vec![]);
     assert_eq!(value.len(), 32);
     assert_ne!(value, vec![0; 32]);
@@ -321,6 +415,67 @@ fn test_random() {
     assert_ne!(value, vec![0; 300]);
 }
 
+#[test]
+fn test_key_value_store() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/key_value_store"]);
+    let component_address: ComponentAddress = template_test.call_function("KeyValueStoreTest", "new", args![], vec![]);
+
+    let value: Option<u64> = template_test.call_method(component_address, "get", args!["a"], vec![]);
+    assert_eq!(value, None);
+
+    let previous: Option<u64> = template_test.call_method(component_address, "insert", args!["a", 1u64], vec![]);
+    assert_eq!(previous, None);
+    let value: Option<u64> = template_test.call_method(component_address, "get", args!["a"], vec![]);
+    assert_eq!(value, Some(1));
+
+    let previous: Option<u64> = template_test.call_method(component_address, "insert", args!["a", 2u64], vec![]);
+    assert_eq!(previous, Some(1));
+
+    let previous: Option<u64> = template_test.call_method(component_address, "remove", args!["a"], vec![]);
+    assert_eq!(previous, Some(2));
+    let value: Option<u64> = template_test.call_method(component_address, "get", args!["a"], vec![]);
+    assert_eq!(value, None);
+
+    // Each entry is stored in its own substate, so the component state only holds the store id
+    let mut num_entries = 0usize;
+    template_test
+        .read_only_state_store()
+        .with_substates(|s| {
+            if s.substate_value().as_key_value_entry().is_some() {
+                num_entries += 1;
+            }
+        })
+        .unwrap();
+    assert_eq!(num_entries, 1);
+}
+
+#[test]
+fn test_ordered_collections_in_component_state() {
+    let mut template_test = TemplateTest::new(vec!["tests/templates/collections"]);
+    let result = template_test.execute_expect_success(
+        Transaction::builder()
+            .call_function(template_test.get_template_address("CollectionsTest"), "new", args![])
+            .sign(template_test.get_test_secret_key())
+            .build(),
+        vec![],
+    );
+    let diff = result.finalize.result.accept().unwrap();
+    let component_address = diff.up_iter().find_map(|(id, _)| id.as_component_address()).unwrap();
+    // Vaults held in the map are indexed like any other vault in the component state
+    assert_eq!(diff.up_iter().filter(|(id, _)| id.is_vault()).count(), 2);
+
+    let names: Vec<String> = template_test.call_method(component_address, "vault_names", args![], vec![]);
+    assert_eq!(names, ["zebra", "apple"]);
+    let balance: Amount = template_test.call_method(component_address, "balance_of", args!["apple"], vec![]);
+    assert_eq!(balance, 100);
+
+    for value in [3u64, 1, 2, 1] {
+        template_test.call_method::<bool>(component_address, "insert_value", args![value], vec![]);
+    }
+    let values: Vec<u64> = template_test.call_method(component_address, "values", args![], vec![]);
+    assert_eq!(values, [3, 1, 2]);
+}
+
 #[test]
 fn test_errors_on_infinite_loop() {
     let mut test = TemplateTest::new(vec!["tests/templates/infinity_loop"]);
@@ -354,11 +509,15 @@ mod errors {
             )
             .unwrap();
         match result.finalize.result.full_reject().unwrap() {
-            RejectReason::ExecutionFailure(message) => {
+            RejectReason::ExecutionFailure(failure) => {
+                assert_eq!(failure.error, ExecutionError::Panic {
+                    message: "This error message should be included in the execution result".to_string()
+                });
                 assert_eq!(
-                    message,
+                    failure.message,
                     "Panic! This error message should be included in the execution result"
                 );
+                assert_eq!(failure.instruction_index, Some(0));
             },
             reason => panic!("Unexpected transaction reject reason: {}", reason),
         }
@@ -382,14 +541,57 @@ mod errors {
             .unwrap();
         println!("{:?}", result.finalize.result);
         match result.finalize.result.full_reject().unwrap() {
-            RejectReason::ExecutionFailure(message) => {
-                assert!(message.starts_with(
+            RejectReason::ExecutionFailure(failure) => {
+                assert!(failure.message.starts_with(
                     "Panic! failed to decode argument at position 0 for function 'please_pass_invalid_args':"
                 ),);
             },
             reason => panic!("Unexpected failure reason: {}", reason),
         }
     }
+
+    #[test]
+    fn args_not_matching_abi() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/state"]);
+        let template_address = template_test.get_template_address("State");
+
+        let reason = template_test.execute_expect_failure(
+            Transaction::builder()
+                .call_function(template_address, "create_multiple", args![])
+                .sign(&Default::default())
+                .build(),
+            vec![],
+        );
+        assert_eq!(reason, RejectReason::InvalidArguments {
+            function: "create_multiple".to_string(),
+            details: "expected 1 argument(s) but got 0".to_string(),
+        });
+
+        let reason = template_test.execute_expect_failure(
+            Transaction::builder()
+                .call_function(template_address, "create_multiple", args!["ten"])
+                .sign(&Default::default())
+                .build(),
+            vec![],
+        );
+        assert_eq!(reason, RejectReason::InvalidArguments {
+            function: "create_multiple".to_string(),
+            details: "argument 'n' is not a valid U32".to_string(),
+        });
+
+        let component: ComponentAddress = template_test.call_function("State", "new", args![], vec![]);
+        let reason = template_test.execute_expect_failure(
+            Transaction::builder()
+                .call_method(component, "set", args![-1i64])
+                .sign(&Default::default())
+                .build(),
+            vec![],
+        );
+        assert_eq!(reason, RejectReason::InvalidArguments {
+            function: "set".to_string(),
+            details: "argument 'value' is not a valid U32".to_string(),
+        });
+    }
 }
 
 mod consensus {
@@ -409,6 +611,36 @@ mod consensus {
         let result: u64 = template_test.call_function("TestConsensus", "current_epoch", args![], vec![]);
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn timestamp() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/consensus"]);
+
+        // the default value for the timestamp in the mocks is 0
+        let result: u64 = template_test.call_function("TestConsensus", "timestamp", args![], vec![]);
+        assert_eq!(result, 0);
+
+        template_test.set_virtual_substate(
+            VirtualSubstateId::CurrentTimestamp,
+            VirtualSubstate::CurrentTimestamp(1_700_000_000),
+        );
+        let result: u64 = template_test.call_function("TestConsensus", "timestamp", args![], vec![]);
+        assert_eq!(result, 1_700_000_000);
+    }
+
+    #[test]
+    fn random_beacon() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/consensus"]);
+
+        // the default value for the random beacon in the mocks is the zero hash
+        let result: Hash = template_test.call_function("TestConsensus", "random_beacon", args![], vec![]);
+        assert_eq!(result, Hash::default());
+
+        let beacon = Hash::from_array([1u8; 32]);
+        template_test.set_virtual_substate(VirtualSubstateId::RandomBeacon, VirtualSubstate::RandomBeacon(beacon));
+        let result: Hash = template_test.call_function("TestConsensus", "random_beacon", args![], vec![]);
+        assert_eq!(result, beacon);
+    }
 }
 
 mod fungible {
@@ -710,6 +942,55 @@ mod basic_nft {
         assert!(err.to_string().contains("Not enough brightness remaining"));
     }
 
+    #[test]
+    fn patch_nft_mutable_data() {
+        let (mut template_test, (account_address, account_owner), nft_component, nft_resx) = setup();
+
+        let vars = [
+            ("account", account_address.into()),
+            ("nft", nft_component.into()),
+            ("nft_id", ManifestValue::NonFungibleId(NonFungibleId::from_u32(123))),
+        ];
+
+        template_test
+            .execute_and_commit_manifest(
+                r#"
+            let account = var!["account"];
+            let sparkle_nft = var!["nft"];
+            let sparkle_nft_id = var!["nft_id"];
+
+            let nft_bucket = sparkle_nft.mint_specific(sparkle_nft_id);
+            account.deposit(nft_bucket);
+
+            sparkle_nft.patch_sparkle(sparkle_nft_id, "colour", 7u32);
+            sparkle_nft.patch_sparkle(sparkle_nft_id, "brightness", 5u32);
+        "#,
+                vars,
+                vec![account_owner],
+            )
+            .unwrap();
+
+        let nft_address =
+            NonFungibleAddress::new(nft_resx.as_resource_address().unwrap(), NonFungibleId::from_u32(123));
+        let nft = template_test
+            .read_only_state_store()
+            .get_substate(&SubstateId::NonFungible(nft_address))
+            .unwrap()
+            .into_substate_value()
+            .into_non_fungible()
+            .unwrap();
+
+        let data = nft
+            .contents()
+            .unwrap()
+            .decode_mutable_data::<BTreeMap<String, u32>>()
+            .unwrap();
+        assert_eq!(
+            data,
+            BTreeMap::from([("brightness".to_string(), 5), ("colour".to_string(), 7)])
+        );
+    }
+
     #[test]
     fn mint_specific_id() {
         let (mut template_test, (account_address, account_owner), nft_component, nft_resx) = setup();
@@ -0,0 +1,86 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+#[cfg(not(feature = "wasmtime"))]
+#[test]
+fn it_fails_to_load_templates_if_the_wasmtime_backend_is_not_enabled() {
+    use tari_dan_engine::{
+        template::{TemplateLoaderError, TemplateModuleLoader},
+        wasm::{WasmBackendKind, WasmModule},
+    };
+    use tari_template_builtin::{get_template_builtin, ACCOUNT_TEMPLATE_ADDRESS};
+
+    let err = WasmModule::from_code(get_template_builtin(&ACCOUNT_TEMPLATE_ADDRESS).to_vec())
+        .with_backend(WasmBackendKind::Wasmtime)
+        .load_template()
+        .unwrap_err();
+    assert!(matches!(err, TemplateLoaderError::BackendNotEnabled {
+        backend: WasmBackendKind::Wasmtime
+    }));
+}
+
+#[cfg(feature = "wasmtime")]
+mod wasmtime {
+    use tari_dan_engine::wasm::{WasmBackendKind, WasmExecutionError};
+    use tari_engine_types::commit_result::RejectReason;
+    use tari_template_lib::{args, models::ComponentAddress};
+    use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+    use tari_transaction::Transaction;
+
+    fn new_test(template_paths: Vec<&str>) -> TemplateTest {
+        TemplateTest::new_with_wasm_backend(WasmBackendKind::Wasmtime, template_paths)
+    }
+
+    #[test]
+    fn it_calls_a_template_function() {
+        let mut test = new_test(vec!["tests/templates/hello_world"]);
+        let result: String = test.call_function("HelloWorld", "greet", args![], vec![]);
+
+        assert_eq!(result, "Hello World!");
+    }
+
+    #[test]
+    fn it_calls_the_engine_from_a_template() {
+        let mut test = new_test(vec!["tests/templates/state"]);
+
+        let component: ComponentAddress = test.call_function("State", "new", args![], vec![]);
+        test.call_method::<()>(component, "set", args![20u32], vec![]);
+        let value: u32 = test.call_method(component, "get", args![], vec![]);
+
+        assert_eq!(value, 20);
+    }
+
+    #[test]
+    fn it_reports_template_panics() {
+        let mut test = new_test(vec!["tests/templates/errors"]);
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_function(test.get_template_address("Errors"), "panic", args![])
+                .sign(test.get_test_secret_key())
+                .build(),
+            vec![],
+        );
+
+        match reason {
+            RejectReason::ExecutionFailure(failure) => assert_eq!(
+                failure.message,
+                "Panic! This error message should be included in the execution result"
+            ),
+            reason => panic!("Unexpected transaction reject reason: {}", reason),
+        }
+    }
+
+    #[test]
+    fn it_runs_out_of_fuel_on_an_infinite_loop() {
+        let mut test = new_test(vec!["tests/templates/infinity_loop"]);
+        let reason = test.execute_expect_failure(
+            Transaction::builder()
+                .call_function(test.get_template_address("InfinityLoopTest"), "infinity_loop", args![])
+                .sign(test.get_test_secret_key())
+                .build(),
+            vec![],
+        );
+
+        assert_reject_reason(reason, WasmExecutionError::OutOfFuel);
+    }
+}
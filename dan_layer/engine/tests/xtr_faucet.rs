@@ -0,0 +1,110 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::Deserialize;
+use tari_crypto::ristretto::RistrettoSecretKey;
+use tari_engine_types::virtual_substate::{VirtualSubstate, VirtualSubstateId};
+use tari_template_builtin::FAUCET_TEMPLATE_ADDRESS;
+use tari_template_lib::{
+    args,
+    constants::XTR,
+    models::{Amount, ComponentAddress},
+};
+use tari_template_test_tooling::{support::assert_error::assert_reject_reason, TemplateTest};
+use tari_transaction::Transaction;
+
+/// Mirrors the ClaimRecord type of the faucet template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+struct ClaimRecord {
+    last_claim_epoch: u64,
+    total_claimed: Amount,
+}
+
+/// Creates a faucet funded with 500 XTR that hands out at most 100 per key every epoch
+fn setup() -> (TemplateTest, ComponentAddress) {
+    let mut test = TemplateTest::new(Vec::<&str>::new());
+    let (account, owner_proof, owner_key) = test.create_funded_account();
+
+    let result = test.execute_expect_success(
+        Transaction::builder()
+            .call_method(account, "withdraw", args![XTR, Amount(500)])
+            .put_last_instruction_output_on_workspace("funds")
+            .call_function(FAUCET_TEMPLATE_ADDRESS, "new", args![
+                Workspace("funds"),
+                Amount(100),
+                1u64
+            ])
+            .sign(&owner_key)
+            .build(),
+        vec![owner_proof],
+    );
+    let faucet = result.finalize.execution_results[2].decode().unwrap();
+    (test, faucet)
+}
+
+fn claim_transaction(
+    faucet: ComponentAddress,
+    account: ComponentAddress,
+    amount: Amount,
+    key: &RistrettoSecretKey,
+) -> Transaction {
+    Transaction::builder()
+        .call_method(faucet, "take", args![amount])
+        .put_last_instruction_output_on_workspace("claim")
+        .call_method(account, "deposit", args![Workspace("claim")])
+        .sign(key)
+        .build()
+}
+
+#[test]
+fn it_limits_claims_per_key() {
+    let (mut test, faucet) = setup();
+    let (account, proof, key) = test.create_empty_account();
+
+    test.execute_expect_success(
+        claim_transaction(faucet, account, Amount(50), &key),
+        vec![proof.clone()],
+    );
+    let record: Option<ClaimRecord> = test.call_method(faucet, "get_claim", args![proof], vec![]);
+    assert_eq!(
+        record,
+        Some(ClaimRecord {
+            last_claim_epoch: 0,
+            total_claimed: Amount(50)
+        })
+    );
+
+    let reason = test.execute_expect_failure(
+        claim_transaction(faucet, account, Amount(50), &key),
+        vec![proof.clone()],
+    );
+    assert_reject_reason(reason, format!("{} may claim again from epoch 1", proof));
+
+    // Another key is not affected by the cooldown
+    let (other_account, other_proof, other_key) = test.create_empty_account();
+    test.execute_expect_success(claim_transaction(faucet, other_account, Amount(100), &other_key), vec![
+        other_proof.clone(),
+    ]);
+
+    test.set_virtual_substate(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(1));
+    let reason = test.execute_expect_failure(claim_transaction(faucet, account, Amount(200), &key), vec![
+        proof.clone()
+    ]);
+    assert_reject_reason(reason, "Claim amount 200 exceeds the maximum of 100");
+
+    test.execute_expect_success(
+        claim_transaction(faucet, account, Amount(50), &key),
+        vec![proof.clone()],
+    );
+    let record: Option<ClaimRecord> = test.call_method(faucet, "get_claim", args![proof], vec![]);
+    assert_eq!(
+        record,
+        Some(ClaimRecord {
+            last_claim_epoch: 1,
+            total_claimed: Amount(100)
+        })
+    );
+
+    let balance: Amount = test.call_method(account, "balance", args![XTR], vec![]);
+    assert_eq!(balance, Amount(100));
+}
@@ -164,6 +164,8 @@ impl From<ParsedArg<'_>> for Arg {
                 SubstateId::NonFungibleIndex(v) => arg!(v),
                 SubstateId::TransactionReceipt(v) => arg!(v),
                 SubstateId::FeeClaim(v) => arg!(v),
+                SubstateId::KeyValueStore(v) => arg!(v),
+                SubstateId::KeyValueEntry(v) => arg!(v),
             },
             ParsedArg::TemplateAddress(v) => arg!(v),
             ParsedArg::UnsignedInteger(v) => arg!(v),
@@ -201,6 +203,8 @@ fn convert_to_cbor(value: json::Value) -> tari_bor::Value {
                     SubstateId::NonFungibleIndex(id) => to_value(&id).unwrap(),
                     SubstateId::TransactionReceipt(id) => to_value(&id).unwrap(),
                     SubstateId::FeeClaim(id) => to_value(&id).unwrap(),
+                    SubstateId::KeyValueStore(id) => to_value(&id).unwrap(),
+                    SubstateId::KeyValueEntry(id) => to_value(&id).unwrap(),
                 },
                 ParsedArg::TemplateAddress(address) => to_value(&address).unwrap(),
                 ParsedArg::UnsignedInteger(i) => tari_bor::Value::Integer(i.into()),
@@ -30,7 +30,8 @@ use tari_template_lib::{
 };
 
 use crate::{
-    proof::{ContainerRef, LockedResource, Proof},
+    confidential::ConfidentialProofBatch,
+    proof::{ContainerRef, LockedResource},
     resource_container::{ResourceContainer, ResourceError},
 };
 
@@ -38,6 +39,9 @@ use crate::{
 pub struct Bucket {
     bucket_id: BucketId,
     resource_container: ResourceContainer,
+    /// Buckets holding soulbound tokens that were withdrawn from a vault cannot be deposited, only burnt
+    #[serde(default)]
+    is_burn_only: bool,
 }
 
 impl Bucket {
@@ -45,9 +49,18 @@ impl Bucket {
         Self {
             bucket_id,
             resource_container: resource,
+            is_burn_only: false,
         }
     }
 
+    pub fn is_burn_only(&self) -> bool {
+        self.is_burn_only
+    }
+
+    pub fn set_burn_only(&mut self) {
+        self.is_burn_only = true;
+    }
+
     pub fn amount(&self) -> Amount {
         self.resource_container.amount()
     }
@@ -84,24 +97,32 @@ impl Bucket {
         self.resource_container.withdraw(amount)
     }
 
+    pub fn take_non_fungibles(&mut self, ids: &BTreeSet<NonFungibleId>) -> Result<ResourceContainer, ResourceError> {
+        self.resource_container.withdraw_by_ids(ids)
+    }
+
     pub fn take_confidential(
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.withdraw_confidential(proof, view_key)
+        self.resource_container.withdraw_confidential(proof, view_key, batch)
     }
 
     pub fn join(&mut self, other: Bucket) -> Result<(), ResourceError> {
-        self.resource_container.deposit(other.resource_container)
+        self.resource_container.deposit(other.resource_container)?;
+        self.is_burn_only |= other.is_burn_only;
+        Ok(())
     }
 
     pub fn reveal_confidential(
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.reveal_confidential(proof, view_key)
+        self.resource_container.reveal_confidential(proof, view_key, batch)
     }
 
     pub fn lock_all(&mut self) -> Result<LockedResource, ResourceError> {
@@ -128,7 +149,7 @@ impl Bucket {
         ))
     }
 
-    pub fn unlock(&mut self, proof: Proof) -> Result<(), ResourceError> {
-        self.resource_container.unlock(proof.into_resource_container())
+    pub fn unlock(&mut self, locked: LockedResource) -> Result<(), ResourceError> {
+        self.resource_container.unlock(locked.into_resource_container())
     }
 }
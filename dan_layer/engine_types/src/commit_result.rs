@@ -32,6 +32,8 @@ use ts_rs::TS;
 
 use crate::{
     events::Event,
+    execution_failure::ExecutionFailure,
+    execution_trace::ExecutionTrace,
     fees::FeeReceipt,
     instruction_result::InstructionResult,
     logs::LogEntry,
@@ -46,6 +48,9 @@ pub struct ExecuteResult {
     pub finalize: FinalizeResult,
     #[cfg_attr(feature = "ts", ts(type = "{secs: number, nanos: number}"))]
     pub execution_time: Duration,
+    /// The execution trace, if tracing was enabled for the executor
+    #[serde(default)]
+    pub execution_trace: Option<ExecutionTrace>,
 }
 
 impl ExecuteResult {
@@ -53,6 +58,7 @@ impl ExecuteResult {
         Self {
             finalize: FinalizeResult::new_rejected(transaction_hash, reason),
             execution_time: Duration::default(),
+            execution_trace: None,
         }
     }
 
@@ -288,12 +294,17 @@ impl Display for TransactionResult {
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
 pub enum RejectReason {
     InvalidTransaction(String),
-    ExecutionFailure(String),
+    ExecutionFailure(ExecutionFailure),
     OneOrMoreInputsNotFound(String),
     FailedToLockInputs(String),
     FailedToLockOutputs(String),
     ForeignShardGroupDecidedToAbort { start_shard: u32, end_shard: u32 },
     FeesNotPaid(String),
+    InvalidArguments { function: String, details: String },
+    LockLeaseExpired(String),
+    CrossShardDeadlock(String),
+    CommitteeLayoutChanged(String),
+    InputRentExpired(String),
     Unknown,
 }
 
@@ -301,7 +312,7 @@ impl Display for RejectReason {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             RejectReason::InvalidTransaction(msg) => write!(f, "Invalid transaction: {}", msg),
-            RejectReason::ExecutionFailure(msg) => write!(f, "Execution failure: {}", msg),
+            RejectReason::ExecutionFailure(failure) => write!(f, "Execution failure: {}", failure),
             RejectReason::OneOrMoreInputsNotFound(msg) => write!(f, "One or more inputs not found: {}", msg),
             RejectReason::FailedToLockInputs(msg) => write!(f, "Failed to lock inputs: {}", msg),
             RejectReason::FailedToLockOutputs(msg) => write!(f, "Failed to lock outputs: {}", msg),
@@ -309,6 +320,13 @@ impl Display for RejectReason {
                 write!(f, "Foreign shard group ({start_shard}-{end_shard}) decided to abort")
             },
             RejectReason::FeesNotPaid(msg) => write!(f, "Fee not paid: {}", msg),
+            RejectReason::InvalidArguments { function, details } => {
+                write!(f, "Invalid arguments for function {}: {}", function, details)
+            },
+            RejectReason::LockLeaseExpired(msg) => write!(f, "Substate lock lease expired: {}", msg),
+            RejectReason::CrossShardDeadlock(msg) => write!(f, "Cross-shard deadlock: {}", msg),
+            RejectReason::CommitteeLayoutChanged(msg) => write!(f, "Committee layout changed: {}", msg),
+            RejectReason::InputRentExpired(msg) => write!(f, "Input rent expired: {}", msg),
             RejectReason::Unknown => write!(f, "<unknown reject reason - this is not valid>"),
         }
     }
@@ -24,9 +24,11 @@ use serde::{Deserialize, Serialize};
 use tari_common_types::types::PublicKey;
 use tari_template_lib::{
     auth::{ComponentAccessRules, OwnerRule, Ownership},
+    component::FeeSponsor,
     crypto::RistrettoPublicKeyBytes,
     models::{EntityId, ObjectKey, TemplateAddress},
     prelude::ComponentAddress,
+    Hash,
 };
 #[cfg(feature = "ts")]
 use ts_rs::TS;
@@ -54,6 +56,19 @@ pub fn new_component_address_from_public_key(
     ComponentAddress::new(key)
 }
 
+/// Derives the address of the component at `index` of the output addresses reserved by a transaction.
+///
+/// Reserved addresses only depend on the transaction hash, so validators can determine (and pledge) the shards of these
+/// outputs before the transaction is executed.
+pub fn new_reserved_component_address(transaction_hash: &Hash, index: u32) -> ComponentAddress {
+    let address = hasher32(EngineHashDomainLabel::ReservedComponentAddress)
+        .chain(transaction_hash)
+        .chain(&index)
+        .result();
+    let key = ObjectKey::from_array(address.leading_bytes());
+    ComponentAddress::new(key)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
 pub struct ComponentHeader {
@@ -61,12 +76,27 @@ pub struct ComponentHeader {
     #[cfg_attr(feature = "ts", ts(type = "Uint8Array"))]
     pub template_address: TemplateAddress,
     pub module_name: String,
+    /// The template address that the component was migrated from, if it has been migrated
+    #[serde(default, with = "serde_with::hex::option")]
+    #[cfg_attr(feature = "ts", ts(type = "Uint8Array | null"))]
+    pub previous_template_address: Option<TemplateAddress>,
     #[serde(with = "serde_with::hex::option")]
     #[cfg_attr(feature = "ts", ts(type = "string | null"))]
     pub owner_key: Option<RistrettoPublicKeyBytes>,
     pub owner_rule: OwnerRule,
     pub access_rules: ComponentAccessRules,
     pub entity_id: EntityId,
+    /// If true, the component may not be called while one of its methods is already on the call stack
+    #[serde(default)]
+    pub non_reentrant: bool,
+    /// The vault and policy used to pay fees on behalf of callers, if the component sponsors fees
+    #[serde(default)]
+    pub fee_sponsor: Option<FeeSponsor>,
+    /// The last epoch that the component's state rent has been paid for. Components created while rent is not charged
+    /// have no rent epoch and never expire. See [SubstateValue::rent_paid_until](crate::substate::SubstateValue).
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub rent_paid_until: Option<u64>,
     // TODO: Split the state from the header
     pub body: ComponentBody,
 }
@@ -100,6 +130,11 @@ impl ComponentHeader {
         self
     }
 
+    pub fn set_owner_rule(&mut self, owner_rule: OwnerRule) -> &mut Self {
+        self.owner_rule = owner_rule;
+        self
+    }
+
     pub fn contains_substate(&self, address: &SubstateId) -> Result<bool, IndexedValueError> {
         let found = IndexedWellKnownTypes::value_contains_substate(self.state(), address)?;
         Ok(found)
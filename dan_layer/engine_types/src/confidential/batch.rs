@@ -0,0 +1,184 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeMap;
+
+use tari_common_types::types::{Commitment, PrivateKey, PublicKey, Signature};
+use tari_crypto::{
+    extended_range_proof::{ExtendedRangeProofService, Statement},
+    keys::{PublicKey as _, SecretKey},
+    ristretto::bulletproofs_plus::RistrettoAggregatedPublicStatement,
+};
+
+use super::get_range_proof_service;
+use crate::{
+    hashing::{hasher32, hasher64, EngineHashDomainLabel},
+    resource_container::ResourceError,
+};
+
+/// Range proofs and balance proofs of confidential statements that are yet to be verified. Verifying many proofs
+/// together is substantially faster than verifying each of them individually, so validation collects the proofs into a
+/// batch and the caller verifies the batch once all of the proofs in a transaction are known.
+#[derive(Debug, Clone, Default)]
+pub struct ConfidentialProofBatch {
+    range_proofs: Vec<RangeProofEntry>,
+    balance_proofs: Vec<BalanceProofEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct RangeProofEntry {
+    range_proof: Vec<u8>,
+    statements: Vec<(Commitment, u64)>,
+}
+
+#[derive(Debug, Clone)]
+struct BalanceProofEntry {
+    public_excess: PublicKey,
+    signature: Signature,
+    challenge: [u8; 64],
+}
+
+impl ConfidentialProofBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an aggregated range proof over the given commitments and their minimum value promises
+    pub fn add_range_proof(&mut self, range_proof: Vec<u8>, statements: Vec<(Commitment, u64)>) {
+        self.range_proofs.push(RangeProofEntry {
+            range_proof,
+            statements,
+        });
+    }
+
+    /// Adds a balance proof signature over the public excess, where `challenge` is the uniform challenge bytes
+    pub fn add_balance_proof(&mut self, public_excess: PublicKey, signature: Signature, challenge: [u8; 64]) {
+        self.balance_proofs.push(BalanceProofEntry {
+            public_excess,
+            signature,
+            challenge,
+        });
+    }
+
+    pub fn append(&mut self, other: ConfidentialProofBatch) {
+        self.range_proofs.extend(other.range_proofs);
+        self.balance_proofs.extend(other.balance_proofs);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.range_proofs.is_empty() && self.balance_proofs.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.range_proofs.len() + self.balance_proofs.len()
+    }
+
+    /// Verifies all of the proofs in the batch
+    pub fn verify(&self) -> Result<(), ResourceError> {
+        self.verify_range_proofs()?;
+        self.verify_balance_proofs()
+    }
+
+    fn verify_range_proofs(&self) -> Result<(), ResourceError> {
+        // A range proof service verifies proofs of a single aggregation factor, so the proofs are batched per factor
+        let mut by_agg_factor = BTreeMap::<_, (Vec<_>, Vec<_>)>::new();
+        for entry in &self.range_proofs {
+            let statements = entry
+                .statements
+                .iter()
+                .map(|(commitment, minimum_value_promise)| Statement {
+                    commitment: commitment.clone(),
+                    minimum_value_promise: *minimum_value_promise,
+                })
+                .collect::<Vec<_>>();
+            let agg_factor = statements.len();
+            let public_statement = RistrettoAggregatedPublicStatement::init(statements).map_err(|e| {
+                ResourceError::InvalidConfidentialProof {
+                    details: format!("Invalid range proof statement: {}", e),
+                }
+            })?;
+            let (proofs, statements) = by_agg_factor.entry(agg_factor).or_default();
+            proofs.push(&entry.range_proof);
+            statements.push(public_statement);
+        }
+
+        for (agg_factor, (proofs, statements)) in by_agg_factor {
+            get_range_proof_service(agg_factor)
+                .verify_batch(proofs, statements.iter().collect())
+                .map_err(|e| ResourceError::InvalidConfidentialProof {
+                    details: format!("Invalid range proof: {}", e),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    fn verify_balance_proofs(&self) -> Result<(), ResourceError> {
+        if self.balance_proofs.len() <= 1 {
+            return self.verify_balance_proofs_individually();
+        }
+
+        // Each signature satisfies s.G = R + e.P. Rather than checking each one, check a random linear combination
+        // sum(w.s).G = sum(w.R) + sum(w.e.P) in a single multiscalar multiplication. The weights are derived from a
+        // hash of every proof in the batch so that they cannot be chosen to cancel out an invalid signature.
+        let seed = self
+            .balance_proofs
+            .iter()
+            .fold(
+                hasher32(EngineHashDomainLabel::ConfidentialProofBatch),
+                |hasher, entry| {
+                    hasher
+                        .chain(&entry.public_excess)
+                        .chain(entry.signature.get_public_nonce())
+                        .chain(entry.signature.get_signature())
+                        .chain(entry.challenge.as_slice())
+                },
+            )
+            .result();
+
+        let mut signature_sum = PrivateKey::default();
+        let mut scalars = Vec::with_capacity(self.balance_proofs.len() * 2);
+        let mut points = Vec::with_capacity(self.balance_proofs.len() * 2);
+        for (i, entry) in self.balance_proofs.iter().enumerate() {
+            let weight = uniform_scalar(
+                &hasher64(EngineHashDomainLabel::ConfidentialProofBatch)
+                    .chain(&seed)
+                    .chain(&(i as u64))
+                    .result(),
+            );
+            let challenge = uniform_scalar(&entry.challenge);
+
+            signature_sum = signature_sum + &weight * entry.signature.get_signature();
+            scalars.push(&weight * &challenge);
+            points.push(entry.public_excess.clone());
+            scalars.push(weight);
+            points.push(entry.signature.get_public_nonce().clone());
+        }
+
+        if PublicKey::from_secret_key(&signature_sum) != PublicKey::batch_mul(&scalars, &points) {
+            // Find the invalid proof so that the error is the same as when verifying individually
+            return self.verify_balance_proofs_individually();
+        }
+
+        Ok(())
+    }
+
+    fn verify_balance_proofs_individually(&self) -> Result<(), ResourceError> {
+        for entry in &self.balance_proofs {
+            if !entry
+                .signature
+                .verify_raw_uniform(&entry.public_excess, &entry.challenge)
+            {
+                return Err(ResourceError::InvalidBalanceProof {
+                    details: "Balance proof was invalid".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn uniform_scalar(bytes: &[u8; 64]) -> PrivateKey {
+    PrivateKey::from_uniform_bytes(bytes)
+        .expect("INVARIANT VIOLATION: RistrettoSecretKey::from_uniform_bytes and hash output length mismatch")
+}
@@ -1,6 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+mod batch;
 mod claim;
 mod elgamal;
 mod proof;
@@ -9,11 +10,11 @@ mod validation;
 mod value_lookup_table;
 mod withdraw;
 
+pub use batch::*;
 pub use claim::*;
 pub use elgamal::*;
 pub use proof::*;
 pub use unclaimed::*;
 pub use validation::*;
 pub use value_lookup_table::*;
-pub(crate) use withdraw::validate_confidential_withdraw;
-pub use withdraw::{ConfidentialOutput, ValidatedConfidentialWithdrawProof};
+pub use withdraw::{validate_confidential_withdraw, ConfidentialOutput, ValidatedConfidentialWithdrawProof};
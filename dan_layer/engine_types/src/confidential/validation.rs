@@ -4,16 +4,15 @@
 use tari_common_types::types::{Commitment, PrivateKey, PublicKey};
 use tari_crypto::{
     commitment::HomomorphicCommitmentFactory,
-    extended_range_proof::{ExtendedRangeProofService, Statement},
     keys::{PublicKey as _, SecretKey},
-    ristretto::{bulletproofs_plus::RistrettoAggregatedPublicStatement, RistrettoSecretKey},
+    ristretto::RistrettoSecretKey,
     tari_utilities::ByteArray,
 };
-use tari_template_lib::models::{Amount, ConfidentialOutputStatement, ViewableBalanceProof};
+use tari_template_lib::models::{Amount, ConfidentialOutputStatement, ConfidentialStatement, ViewableBalanceProof};
 
-use super::{challenges, get_commitment_factory, get_range_proof_service};
+use super::{challenges, get_commitment_factory};
 use crate::{
-    confidential::{elgamal::ElgamalVerifiableBalance, ConfidentialOutput},
+    confidential::{elgamal::ElgamalVerifiableBalance, ConfidentialOutput, ConfidentialProofBatch},
     resource_container::ResourceError,
 };
 
@@ -25,9 +24,12 @@ pub struct ValidatedConfidentialProof {
     pub change_revealed_amount: Amount,
 }
 
+/// Validates the confidential output statement. The range proof is added to `batch` and is only verified once the
+/// batch is verified.
 pub fn validate_confidential_proof(
     proof: &ConfidentialOutputStatement,
     view_key: Option<&PublicKey>,
+    batch: &mut ConfidentialProofBatch,
 ) -> Result<ValidatedConfidentialProof, ResourceError> {
     if proof.output_revealed_amount.is_negative() || proof.change_revealed_amount.is_negative() {
         return Err(ResourceError::InvalidConfidentialProof {
@@ -56,6 +58,7 @@ pub fn validate_confidential_proof(
                 view_key,
                 statement.viewable_balance_proof.as_ref(),
             )?;
+            validate_encrypted_memo(statement)?;
 
             Ok(ConfidentialOutput {
                 commitment: output_commitment,
@@ -63,6 +66,7 @@ pub fn validate_confidential_proof(
                 encrypted_data: statement.encrypted_data.clone(),
                 minimum_value_promise: statement.minimum_value_promise,
                 viewable_balance,
+                encrypted_memo: statement.encrypted_memo.clone(),
             })
         })
         .transpose()?;
@@ -85,6 +89,7 @@ pub fn validate_confidential_proof(
 
             let viewable_balance =
                 validate_elgamal_verifiable_balance_proof(&commitment, view_key, stmt.viewable_balance_proof.as_ref())?;
+            validate_encrypted_memo(stmt)?;
 
             Ok(ConfidentialOutput {
                 commitment,
@@ -92,6 +97,7 @@ pub fn validate_confidential_proof(
                 encrypted_data: stmt.encrypted_data.clone(),
                 minimum_value_promise: stmt.minimum_value_promise,
                 viewable_balance,
+                encrypted_memo: stmt.encrypted_memo.clone(),
             })
         })
         .transpose()?;
@@ -105,7 +111,7 @@ pub fn validate_confidential_proof(
             });
         }
     } else {
-        validate_bullet_proof(proof)?;
+        validate_bullet_proof(proof, batch)?;
     }
 
     Ok(ValidatedConfidentialProof {
@@ -219,7 +225,26 @@ pub fn validate_elgamal_verifiable_balance_proof(
     }))
 }
 
-fn validate_bullet_proof(proof: &ConfidentialOutputStatement) -> Result<(), ResourceError> {
+fn validate_encrypted_memo(statement: &ConfidentialStatement) -> Result<(), ResourceError> {
+    let Some(memo) = statement.encrypted_memo.as_ref() else {
+        return Ok(());
+    };
+    if memo.len() > ConfidentialStatement::MAX_ENCRYPTED_MEMO_SIZE {
+        return Err(ResourceError::InvalidConfidentialProof {
+            details: format!(
+                "Encrypted memo is {} bytes which exceeds the maximum of {} bytes",
+                memo.len(),
+                ConfidentialStatement::MAX_ENCRYPTED_MEMO_SIZE
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn validate_bullet_proof(
+    proof: &ConfidentialOutputStatement,
+    batch: &mut ConfidentialProofBatch,
+) -> Result<(), ResourceError> {
     let statements = proof
         .output_statement
         .iter()
@@ -230,10 +255,7 @@ fn validate_bullet_proof(proof: &ConfidentialOutputStatement) -> Result<(), Reso
                     details: "Invalid commitment".to_string(),
                 }
             })?;
-            Ok(Statement {
-                commitment,
-                minimum_value_promise: stmt.minimum_value_promise,
-            })
+            Ok((commitment, stmt.minimum_value_promise))
         })
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -249,14 +271,7 @@ fn validate_bullet_proof(proof: &ConfidentialOutputStatement) -> Result<(), Reso
         });
     }
 
-    let public_statement = RistrettoAggregatedPublicStatement::init(statements).unwrap();
-
-    let proofs = vec![&proof.range_proof];
-    get_range_proof_service(agg_factor)
-        .verify_batch(proofs, vec![&public_statement])
-        .map_err(|e| ResourceError::InvalidConfidentialProof {
-            details: format!("Invalid range proof: {}", e),
-        })?;
+    batch.add_range_proof(proof.range_proof.clone(), statements);
 
     Ok(())
 }
@@ -9,7 +9,7 @@ use tari_template_lib::{
     models::{Amount, ConfidentialWithdrawProof, EncryptedData},
 };
 
-use super::{challenges, get_commitment_factory, validate_confidential_proof};
+use super::{challenges, get_commitment_factory, validate_confidential_proof, ConfidentialProofBatch};
 use crate::{confidential::elgamal::ElgamalVerifiableBalance, resource_container::ResourceError};
 
 #[derive(Debug, Clone)]
@@ -44,14 +44,20 @@ pub struct ConfidentialOutput {
     #[cfg_attr(feature = "ts", ts(type = "number"))]
     pub minimum_value_promise: u64,
     pub viewable_balance: Option<ElgamalVerifiableBalance>,
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "Array<number> | null"))]
+    pub encrypted_memo: Option<Vec<u8>>,
 }
 
-pub(crate) fn validate_confidential_withdraw<'a, I: IntoIterator<Item = &'a Commitment>>(
+/// Validates the confidential withdraw proof. The range proof and balance proof are added to `batch` and are only
+/// verified once the batch is verified.
+pub fn validate_confidential_withdraw<'a, I: IntoIterator<Item = &'a Commitment>>(
     inputs: I,
     view_key: Option<&PublicKey>,
     withdraw_proof: ConfidentialWithdrawProof,
+    batch: &mut ConfidentialProofBatch,
 ) -> Result<ValidatedConfidentialWithdrawProof, ResourceError> {
-    let validated_proof = validate_confidential_proof(&withdraw_proof.output_proof, view_key)?;
+    let validated_proof = validate_confidential_proof(&withdraw_proof.output_proof, view_key, batch)?;
 
     let input_revealed_amount = withdraw_proof.input_revealed_amount;
     // We expect the revealed amount to be excluded from the output commitment.
@@ -121,11 +127,7 @@ pub(crate) fn validate_confidential_withdraw<'a, I: IntoIterator<Item = &'a Comm
         total_output_revealed_amount,
     );
 
-    if !balance_proof.verify_raw_uniform(&public_excess, &message) {
-        return Err(ResourceError::InvalidBalanceProof {
-            details: "Balance proof was invalid".to_string(),
-        });
-    }
+    batch.add_balance_proof(public_excess, balance_proof, message);
 
     Ok(ValidatedConfidentialWithdrawProof {
         output: validated_proof.output,
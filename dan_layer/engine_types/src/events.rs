@@ -22,15 +22,17 @@
 
 use std::fmt::Display;
 
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tari_bor::{BorError, FromTagAndValue};
 use tari_template_lib::{
     models::{Metadata, TemplateAddress},
     Hash,
 };
+use tari_utilities::hex::to_hex;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-use crate::{serde_with, substate::SubstateId};
+use crate::{indexed_value::WellKnownTariValue, serde_with, substate::SubstateId};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
@@ -45,7 +47,12 @@ pub struct Event {
     topic: String,
     // NOTE: We need to use an ordered map here. HashMaps are unordered, so when we pledge this state the hash
     // resulting hash may differ.
+    /// String key fields that indexers can search events by
     payload: Metadata,
+    /// The typed (CBOR) payload of the event, if any
+    #[serde(default, with = "serde_with::cbor_value::option")]
+    #[cfg_attr(feature = "ts", ts(type = "any"))]
+    data: Option<tari_bor::Value>,
 }
 
 impl Event {
@@ -62,9 +69,15 @@ impl Event {
             tx_hash,
             topic,
             payload,
+            data: None,
         }
     }
 
+    pub fn with_data(mut self, data: tari_bor::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
     pub fn substate_id(&self) -> Option<SubstateId> {
         self.substate_id.clone()
     }
@@ -96,6 +109,37 @@ impl Event {
     pub fn into_payload(self) -> Metadata {
         self.payload
     }
+
+    pub fn data(&self) -> Option<&tari_bor::Value> {
+        self.data.as_ref()
+    }
+
+    pub fn decode_data<T: DeserializeOwned>(&self) -> Result<Option<T>, BorError> {
+        self.data.as_ref().map(tari_bor::from_value).transpose()
+    }
+}
+
+/// Returns the string form of a typed event payload field that is added to the event's search keys, or `None` if the
+/// value cannot be indexed. Only scalar values and addresses can be indexed.
+pub fn indexed_field_value(value: &tari_bor::Value) -> Option<String> {
+    match value {
+        tari_bor::Value::Text(text) => Some(text.clone()),
+        tari_bor::Value::Bool(b) => Some(b.to_string()),
+        tari_bor::Value::Integer(i) => Some(i128::from(*i).to_string()),
+        tari_bor::Value::Bytes(bytes) => Some(to_hex(bytes)),
+        tari_bor::Value::Tag(tag, inner) => match WellKnownTariValue::try_from_tag_and_value(*tag, inner).ok()? {
+            WellKnownTariValue::ComponentAddress(addr) => Some(addr.to_string()),
+            WellKnownTariValue::ResourceAddress(addr) => Some(addr.to_string()),
+            WellKnownTariValue::TransactionReceiptAddress(addr) => Some(addr.to_string()),
+            WellKnownTariValue::NonFungibleAddress(addr) => Some(addr.to_string()),
+            WellKnownTariValue::VaultId(id) => Some(id.to_string()),
+            WellKnownTariValue::KeyValueStoreId(id) => Some(id.to_string()),
+            WellKnownTariValue::FeeClaim(addr) => Some(addr.to_string()),
+            WellKnownTariValue::UnclaimedConfidentialOutputAddress(addr) => Some(addr.to_string()),
+            WellKnownTariValue::BucketId(_) | WellKnownTariValue::ProofId(_) | WellKnownTariValue::Metadata(_) => None,
+        },
+        _ => None,
+    }
 }
 
 impl Display for Event {
@@ -0,0 +1,255 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{Display, Formatter};
+
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize,
+    Deserializer,
+    Serialize,
+};
+use tari_template_lib::models::{Amount, BucketId, ComponentAddress, NonFungibleId, ResourceAddress, VaultId};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use crate::{substate::SubstateId, TemplateAddress};
+
+/// A structured description of why a transaction failed to execute
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct ExecutionFailure {
+    /// A stable numeric code for the error. See [ExecutionError::code].
+    pub code: u32,
+    /// The index of the instruction that failed, counting fee instructions first. This is None if the failure did not
+    /// occur while executing an instruction, for example when the final state is invalid.
+    pub instruction_index: Option<u32>,
+    pub error: ExecutionError,
+    /// A human-readable description of the failure
+    pub message: String,
+}
+
+impl ExecutionFailure {
+    pub fn new<T: Into<String>>(error: ExecutionError, message: T) -> Self {
+        Self {
+            code: error.code(),
+            instruction_index: None,
+            error,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a failure for an error that has no structured representation
+    pub fn other<T: Into<String>>(message: T) -> Self {
+        Self::new(ExecutionError::Other, message)
+    }
+
+    pub fn with_instruction_index(mut self, instruction_index: u32) -> Self {
+        self.instruction_index = Some(instruction_index);
+        self
+    }
+}
+
+impl Display for ExecutionFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(index) = self.instruction_index {
+            write!(f, " (instruction {})", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Execution failures were previously recorded as a plain string. This allows results that were stored before
+/// failures were structured to be read.
+impl<'de> Deserialize<'de> for ExecutionFailure {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Structured {
+            code: u32,
+            instruction_index: Option<u32>,
+            error: ExecutionError,
+            message: String,
+        }
+
+        struct ExecutionFailureVisitor;
+
+        impl<'de> Visitor<'de> for ExecutionFailureVisitor {
+            type Value = ExecutionFailure;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("an execution failure or a failure message")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(ExecutionFailure::other(v))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let s = Structured::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(ExecutionFailure {
+                    code: s.code,
+                    instruction_index: s.instruction_index,
+                    error: s.error,
+                    message: s.message,
+                })
+            }
+        }
+
+        deserializer.deserialize_any(ExecutionFailureVisitor)
+    }
+}
+
+/// The engine error that caused an execution failure. Errors that clients are unlikely to handle are reported as
+/// [ExecutionError::Other] and are only described by the failure message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub enum ExecutionError {
+    Other,
+    /// The template panicked
+    Panic {
+        message: String,
+    },
+    SubstateNotFound {
+        id: SubstateId,
+    },
+    SubstateOutOfScope {
+        id: SubstateId,
+    },
+    SubstateNotOwned {
+        id: SubstateId,
+        requested_owner: SubstateId,
+    },
+    ComponentNotFound {
+        address: ComponentAddress,
+    },
+    VaultNotFound {
+        vault_id: VaultId,
+    },
+    ResourceNotFound {
+        resource_address: ResourceAddress,
+    },
+    NonFungibleNotFound {
+        resource_address: ResourceAddress,
+        nft_id: NonFungibleId,
+    },
+    TemplateNotFound {
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        template_address: TemplateAddress,
+    },
+    FunctionNotFound {
+        name: String,
+    },
+    InvalidArgument {
+        argument: String,
+        reason: String,
+    },
+    InvalidAmount {
+        amount: Amount,
+        reason: String,
+    },
+    /// The caller did not have permission to perform the action
+    AccessDenied {
+        action: String,
+    },
+    /// A resource operation failed, e.g. insufficient balance in a vault or bucket
+    ResourceError {
+        details: String,
+    },
+    MaxSupplyExceeded {
+        resource_address: ResourceAddress,
+        amount: Amount,
+        total_supply: Amount,
+        max_supply: Amount,
+    },
+    ResourceFrozen {
+        address: SubstateId,
+        action: String,
+    },
+    InsufficientFeesPaid {
+        required_fee: Amount,
+        fees_paid: Amount,
+    },
+    ReentrantCall {
+        component_address: ComponentAddress,
+    },
+    SubstateRentExpired {
+        id: SubstateId,
+        paid_until: u64,
+        current_epoch: u64,
+    },
+    MaxCallDepthExceeded {
+        max_depth: u64,
+    },
+    /// Substates were created but are not owned by anything once the transaction completes
+    OrphanedSubstates {
+        substates: Vec<String>,
+    },
+    /// A bucket was dropped while it still contained funds
+    BucketNotEmpty {
+        bucket_id: BucketId,
+    },
+    /// The template could not be loaded or executed by the WASM runtime
+    WasmError {
+        details: String,
+    },
+}
+
+impl ExecutionError {
+    /// Returns a stable numeric code for the error. Codes are never reused so that clients can rely on them across
+    /// versions.
+    pub fn code(&self) -> u32 {
+        match self {
+            Self::Other => 0,
+            Self::Panic { .. } => 1,
+            Self::SubstateNotFound { .. } => 100,
+            Self::SubstateOutOfScope { .. } => 101,
+            Self::SubstateNotOwned { .. } => 102,
+            Self::ComponentNotFound { .. } => 103,
+            Self::VaultNotFound { .. } => 104,
+            Self::ResourceNotFound { .. } => 105,
+            Self::NonFungibleNotFound { .. } => 106,
+            Self::TemplateNotFound { .. } => 107,
+            Self::FunctionNotFound { .. } => 108,
+            Self::InvalidArgument { .. } => 200,
+            Self::InvalidAmount { .. } => 201,
+            Self::AccessDenied { .. } => 300,
+            Self::ResourceError { .. } => 400,
+            Self::MaxSupplyExceeded { .. } => 401,
+            Self::ResourceFrozen { .. } => 402,
+            Self::BucketNotEmpty { .. } => 403,
+            Self::InsufficientFeesPaid { .. } => 500,
+            Self::ReentrantCall { .. } => 600,
+            Self::SubstateRentExpired { .. } => 601,
+            Self::MaxCallDepthExceeded { .. } => 602,
+            Self::OrphanedSubstates { .. } => 603,
+            Self::WasmError { .. } => 700,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_deserializes_legacy_string_failures() {
+        let failure: ExecutionFailure = serde_json::from_str(r#""Panic! oops""#).unwrap();
+        assert_eq!(failure, ExecutionFailure::other("Panic! oops"));
+    }
+
+    #[test]
+    fn it_roundtrips_structured_failures() {
+        let failure = ExecutionFailure::new(
+            ExecutionError::FunctionNotFound {
+                name: "foo".to_string(),
+            },
+            "Function foo not found",
+        )
+        .with_instruction_index(2);
+        let json = serde_json::to_string(&failure).unwrap();
+        let decoded: ExecutionFailure = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, failure);
+        assert_eq!(decoded.code, 108);
+    }
+}
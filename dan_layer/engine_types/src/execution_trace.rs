@@ -0,0 +1,84 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use crate::{fees::FeeSource, substate::SubstateId};
+
+/// A structured record of what happened while executing a transaction. Traces are intended for debugging and are not
+/// part of the transaction result that validators agree on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct ExecutionTrace {
+    pub entries: Vec<ExecutionTraceEntry>,
+    /// True if entries were dropped because the trace exceeded the maximum number of entries
+    pub is_truncated: bool,
+}
+
+impl ExecutionTrace {
+    /// The maximum number of entries recorded for a single transaction
+    pub const MAX_ENTRIES: usize = 10_000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, entry: ExecutionTraceEntry) {
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.is_truncated = true;
+            return;
+        }
+        self.entries.push(entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Display for ExecutionTrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        if self.is_truncated {
+            writeln!(f, "... (truncated after {} entries)", self.entries.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub enum ExecutionTraceEntry {
+    /// An instruction started executing. The index counts fee instructions first.
+    Instruction { index: u32, instruction: String },
+    /// The engine was called by a template or instruction
+    EngineCall { call: String },
+    /// A substate was read for the first time in the transaction
+    SubstateRead { substate_id: SubstateId },
+    /// A substate was created or changed for the first time in the transaction
+    SubstateWrite { substate_id: SubstateId },
+    /// A fee was charged
+    FeeCharge { source: FeeSource, amount: u64 },
+}
+
+impl Display for ExecutionTraceEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Instruction { index, instruction } => write!(f, "[{}] {}", index, instruction),
+            Self::EngineCall { call } => write!(f, "  call {}", call),
+            Self::SubstateRead { substate_id } => write!(f, "  read {}", substate_id),
+            Self::SubstateWrite { substate_id } => write!(f, "  write {}", substate_id),
+            Self::FeeCharge { source, amount } => write!(f, "  fee {:?}: {}", source, amount),
+        }
+    }
+}
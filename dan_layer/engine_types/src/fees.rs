@@ -20,6 +20,10 @@ pub struct FeeReceipt {
     pub total_fees_paid: Amount,
     /// Breakdown of fee costs
     pub cost_breakdown: FeeBreakdown,
+    /// Breakdown of the fee costs of each instruction in execution order, fee instructions first. Storage costs are
+    /// charged for the transaction as a whole and only appear in `cost_breakdown`.
+    #[serde(default)]
+    pub instruction_cost_breakdown: Vec<FeeBreakdown>,
 }
 
 impl FeeReceipt {
@@ -27,6 +31,7 @@ impl FeeReceipt {
         FeeCostBreakdown {
             total_fees_charged: self.total_fees_charged(),
             breakdown: self.cost_breakdown.clone(),
+            instructions: self.instruction_cost_breakdown.clone(),
         }
     }
 
@@ -72,6 +77,7 @@ pub enum FeeSource {
     Storage,
     Events,
     Logs,
+    Rent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -108,6 +114,9 @@ impl FeeBreakdown {
 pub struct FeeCostBreakdown {
     pub total_fees_charged: Amount,
     pub breakdown: FeeBreakdown,
+    /// The fee costs of each instruction in execution order, fee instructions first
+    #[serde(default)]
+    pub instructions: Vec<FeeBreakdown>,
 }
 
 #[derive(Debug)]
@@ -183,6 +183,15 @@ pub enum EngineHashDomainLabel {
     QuorumCertificate,
     SubstateValue,
     ViewKey,
+    EventPayload,
+    KeyValueEntry,
+    RandomSeed,
+    RandomBytes,
+    ConfidentialProofBatch,
+    IdempotencyKey,
+    ReservedComponentAddress,
+    NamedComponentAddress,
+    NamedResourceAddress,
 }
 
 impl EngineHashDomainLabel {
@@ -206,6 +215,15 @@ impl EngineHashDomainLabel {
             Self::QuorumCertificate => "QuorumCertificate",
             Self::SubstateValue => "SubstateValue",
             Self::ViewKey => "ViewKey",
+            Self::EventPayload => "EventPayload",
+            Self::KeyValueEntry => "KeyValueEntry",
+            Self::RandomSeed => "RandomSeed",
+            Self::RandomBytes => "RandomBytes",
+            Self::ConfidentialProofBatch => "ConfidentialProofBatch",
+            Self::IdempotencyKey => "IdempotencyKey",
+            Self::ReservedComponentAddress => "ReservedComponentAddress",
+            Self::NamedComponentAddress => "NamedComponentAddress",
+            Self::NamedResourceAddress => "NamedResourceAddress",
         }
     }
 }
@@ -10,6 +10,7 @@ use tari_template_lib::{
         ComponentAddress,
         ComponentKey,
         EntityId,
+        KeyValueStoreId,
         ObjectKey,
         ProofId,
         ResourceAddress,
@@ -78,6 +79,11 @@ impl<'a> IdProvider<'a> {
         Ok(v)
     }
 
+    pub fn new_key_value_store_id(&self) -> Result<KeyValueStoreId, IdProviderError> {
+        let id = KeyValueStoreId::new(self.next_object_key()?);
+        Ok(id)
+    }
+
     pub fn new_bucket_id(&self) -> BucketId {
         self.object_ids.next_bucket_id()
     }
@@ -96,10 +102,15 @@ impl<'a> IdProvider<'a> {
         Ok(id.into_array())
     }
 
-    pub fn get_random_bytes(&self, len: usize) -> Result<Vec<u8>, IdProviderError> {
+    /// Returns `len` pseudorandom bytes. The bytes are derived from the given seed and the transaction hash, so a
+    /// transaction cannot predict them unless it can predict the seed.
+    pub fn get_random_bytes(&self, seed: &Hash, len: usize) -> Result<Vec<u8>, IdProviderError> {
         let mut result = Vec::with_capacity(len);
         while result.len() < len {
-            let bytes = self.new_uuid()?;
+            let bytes = hasher32(EngineHashDomainLabel::RandomBytes)
+                .chain(seed)
+                .chain(&self.new_uuid()?)
+                .result();
             let remaining = len - result.len();
             let end = bytes.len().min(remaining);
             result.extend_from_slice(&bytes[..end]);
@@ -200,7 +211,7 @@ mod tests {
         let id_provider = IdProvider::new(EntityId::default(), Hash::default(), &object_ids);
         const CASES: [usize; 7] = [0, 4, 32, 33, 64, 65, 129];
         for len in CASES {
-            let b = id_provider.get_random_bytes(len).unwrap();
+            let b = id_provider.get_random_bytes(&Hash::default(), len).unwrap();
             assert_eq!(b.len(), len);
             if len > 0 {
                 assert!(b.iter().any(|&x| x != 0));
@@ -9,6 +9,7 @@ use tari_template_lib::{
     models::{
         BinaryTag,
         BucketId,
+        KeyValueStoreId,
         NonFungibleAddressContents,
         ObjectKey,
         ProofId,
@@ -67,6 +68,7 @@ impl IndexedValue {
             .chain(self.indexed.resource_addresses.iter().map(|a| (*a).into()))
             .chain(self.indexed.non_fungible_addresses.iter().map(|a| a.clone().into()))
             .chain(self.indexed.vault_ids.iter().map(|a| (*a).into()))
+            .chain(self.indexed.key_value_store_ids.iter().map(|a| (*a).into()))
     }
 
     pub fn well_known_types(&self) -> &IndexedWellKnownTypes {
@@ -97,6 +99,10 @@ impl IndexedValue {
         &self.indexed.vault_ids
     }
 
+    pub fn key_value_store_ids(&self) -> &[KeyValueStoreId] {
+        &self.indexed.key_value_store_ids
+    }
+
     pub fn metadata(&self) -> &[Metadata] {
         &self.indexed.metadata
     }
@@ -138,6 +144,8 @@ pub struct IndexedWellKnownTypes {
     non_fungible_addresses: Vec<NonFungibleAddress>,
     #[serde(with = "serde_with::hex::vec")]
     vault_ids: Vec<VaultId>,
+    #[serde(default, with = "serde_with::hex::vec")]
+    key_value_store_ids: Vec<KeyValueStoreId>,
     metadata: Vec<Metadata>,
     unclaimed_confidential_output_address: Vec<UnclaimedConfidentialOutputAddress>,
 }
@@ -152,6 +160,7 @@ impl IndexedWellKnownTypes {
             transaction_receipt_addresses: vec![],
             non_fungible_addresses: vec![],
             vault_ids: vec![],
+            key_value_store_ids: vec![],
             metadata: vec![],
             unclaimed_confidential_output_address: vec![],
         }
@@ -173,6 +182,7 @@ impl IndexedWellKnownTypes {
             transaction_receipt_addresses: visitor.transaction_receipt_addresses,
             non_fungible_addresses: visitor.non_fungible_addresses,
             vault_ids: visitor.vault_ids,
+            key_value_store_ids: visitor.key_value_store_ids,
             metadata: visitor.metadata,
             unclaimed_confidential_output_address: visitor.unclaimed_confidential_output_addresses,
         })
@@ -200,6 +210,9 @@ impl IndexedWellKnownTypes {
                     WellKnownTariValue::VaultId(addr) => {
                         found = *address == addr;
                     },
+                    WellKnownTariValue::KeyValueStoreId(id) => {
+                        found = *address == id;
+                    },
                     WellKnownTariValue::FeeClaim(addr) => {
                         found = *address == addr;
                     },
@@ -229,6 +242,7 @@ impl IndexedWellKnownTypes {
             .chain(self.resource_addresses.iter().map(|a| (*a).into()))
             .chain(self.non_fungible_addresses.iter().map(|a| a.clone().into()))
             .chain(self.vault_ids.iter().map(|a| (*a).into()))
+            .chain(self.key_value_store_ids.iter().map(|a| (*a).into()))
             .chain(self.unclaimed_confidential_output_address.iter().map(|a| (*a).into()))
     }
 
@@ -256,6 +270,10 @@ impl IndexedWellKnownTypes {
         &self.vault_ids
     }
 
+    pub fn key_value_store_ids(&self) -> &[KeyValueStoreId] {
+        &self.key_value_store_ids
+    }
+
     pub fn metadata(&self) -> &[Metadata] {
         &self.metadata
     }
@@ -272,6 +290,7 @@ impl IndexedWellKnownTypes {
             ),
             non_fungible_addresses: diff_vec(&self.non_fungible_addresses, &other.non_fungible_addresses),
             vault_ids: diff_vec(&self.vault_ids, &other.vault_ids),
+            key_value_store_ids: diff_vec(&self.key_value_store_ids, &other.key_value_store_ids),
             metadata: diff_vec(&self.metadata, &other.metadata),
             unclaimed_confidential_output_address: diff_vec(
                 &self.unclaimed_confidential_output_address,
@@ -298,6 +317,7 @@ impl FromIterator<IndexedWellKnownTypes> for IndexedWellKnownTypes {
                 .extend(value.transaction_receipt_addresses);
             indexed.non_fungible_addresses.extend(value.non_fungible_addresses);
             indexed.vault_ids.extend(value.vault_ids);
+            indexed.key_value_store_ids.extend(value.key_value_store_ids);
             indexed.metadata.extend(value.metadata);
             indexed
                 .unclaimed_confidential_output_address
@@ -315,6 +335,7 @@ pub enum WellKnownTariValue {
     BucketId(BucketId),
     Metadata(Metadata),
     VaultId(VaultId),
+    KeyValueStoreId(KeyValueStoreId),
     FeeClaim(FeeClaimAddress),
     ProofId(ProofId),
     UnclaimedConfidentialOutputAddress(UnclaimedConfidentialOutputAddress),
@@ -355,6 +376,10 @@ impl FromTagAndValue for WellKnownTariValue {
                 let vault_id: ObjectKey = value.deserialized().map_err(BorError::from)?;
                 Ok(Self::VaultId(vault_id.into()))
             },
+            BinaryTag::KeyValueStoreId => {
+                let store_id: ObjectKey = value.deserialized().map_err(BorError::from)?;
+                Ok(Self::KeyValueStoreId(store_id.into()))
+            },
             BinaryTag::FeeClaim => {
                 let value: Hash = value.deserialized().map_err(BorError::from)?;
                 Ok(Self::FeeClaim(value.into()))
@@ -380,6 +405,7 @@ pub struct IndexedValueVisitor {
     transaction_receipt_addresses: Vec<TransactionReceiptAddress>,
     non_fungible_addresses: Vec<NonFungibleAddress>,
     vault_ids: Vec<VaultId>,
+    key_value_store_ids: Vec<KeyValueStoreId>,
     metadata: Vec<Metadata>,
     unclaimed_confidential_output_addresses: Vec<UnclaimedConfidentialOutputAddress>,
 }
@@ -394,6 +420,7 @@ impl IndexedValueVisitor {
             transaction_receipt_addresses: vec![],
             non_fungible_addresses: vec![],
             vault_ids: vec![],
+            key_value_store_ids: vec![],
             metadata: vec![],
             unclaimed_confidential_output_addresses: vec![],
         }
@@ -423,6 +450,9 @@ impl ValueVisitor<WellKnownTariValue> for IndexedValueVisitor {
             WellKnownTariValue::VaultId(vault_id) => {
                 self.vault_ids.push(vault_id);
             },
+            WellKnownTariValue::KeyValueStoreId(store_id) => {
+                self.key_value_store_ids.push(store_id);
+            },
             WellKnownTariValue::Metadata(metadata) => {
                 self.metadata.push(metadata);
             },
@@ -15,7 +15,7 @@ use tari_template_lib::{
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-use crate::{confidential::ConfidentialClaim, serde_with};
+use crate::{confidential::ConfidentialClaim, serde_with, substate::SubstateId};
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
@@ -68,6 +68,19 @@ pub enum Instruction {
         resource_address: ResourceAddress,
         min_amount: Amount,
     },
+    /// Locks the bucket on the workspace under `key` and outputs a proof of its contents
+    CreateProofFromBucket {
+        key: Vec<u8>,
+    },
+    /// Extends the rent of a component, vault or key-value store entry by the given number of epochs. The rent is
+    /// charged as a transaction fee.
+    PayRent {
+        #[serde(with = "serde_with::string")]
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        substate_id: SubstateId,
+        #[cfg_attr(feature = "ts", ts(type = "number"))]
+        epochs: u64,
+    },
 }
 
 impl Display for Instruction {
@@ -149,6 +162,12 @@ impl Display for Instruction {
                     key, resource_address, min_amount
                 )
             },
+            Self::CreateProofFromBucket { key } => {
+                write!(f, "CreateProofFromBucket {{ key: {:?} }}", key)
+            },
+            Self::PayRent { substate_id, epochs } => {
+                write!(f, "PayRent {{ substate_id: {}, epochs: {} }}", substate_id, epochs)
+            },
         }
     }
 }
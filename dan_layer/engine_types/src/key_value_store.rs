@@ -0,0 +1,142 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{fmt, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use tari_bor::BorError;
+use tari_template_lib::{
+    models::{KeyValueStoreId, ObjectKey},
+    Hash,
+};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use crate::{
+    hashing::{hasher32, EngineHashDomainLabel},
+    serde_with,
+};
+
+/// The address of a single entry in a key-value store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct KeyValueEntryAddress {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    store_id: KeyValueStoreId,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    key_hash: Hash,
+}
+
+impl KeyValueEntryAddress {
+    pub fn new(store_id: KeyValueStoreId, key: &[u8]) -> Self {
+        let key_hash = hasher32(EngineHashDomainLabel::KeyValueEntry)
+            .chain(&store_id)
+            .chain(&key)
+            .result();
+        Self { store_id, key_hash }
+    }
+
+    pub fn store_id(&self) -> &KeyValueStoreId {
+        &self.store_id
+    }
+
+    pub fn key_hash(&self) -> &Hash {
+        &self.key_hash
+    }
+
+    pub fn to_object_key(&self) -> ObjectKey {
+        ObjectKey::new(self.store_id.entity_id(), self.key_hash.trailing_bytes().into())
+    }
+}
+
+impl Display for KeyValueEntryAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "kventry_{}_{}", self.store_id.as_object_key(), self.key_hash)
+    }
+}
+
+impl FromStr for KeyValueEntryAddress {
+    type Err = KeyValueEntryAddressParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // kventry_{store_id}_{key_hash}
+        let s = s.strip_prefix("kventry_").unwrap_or(s);
+        let (store_id, key_hash) = s.split_once('_').ok_or(KeyValueEntryAddressParseError)?;
+        let store_id = KeyValueStoreId::from_hex(store_id).map_err(|_| KeyValueEntryAddressParseError)?;
+        let key_hash = Hash::from_hex(key_hash).map_err(|_| KeyValueEntryAddressParseError)?;
+        Ok(Self { store_id, key_hash })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid key-value entry address string")]
+pub struct KeyValueEntryAddressParseError;
+
+/// The root substate of a key-value store. The entries are stored in their own substates so that they can be loaded
+/// and locked independently.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct KeyValueStoreContainer {}
+
+impl KeyValueStoreContainer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// A single entry in a key-value store. A removed entry keeps its substate with no value, so that it can be
+/// re-inserted later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct KeyValueEntry {
+    #[cfg_attr(feature = "ts", ts(type = "Array<number>"))]
+    key: Vec<u8>,
+    #[cfg_attr(feature = "ts", ts(type = "any"))]
+    #[serde(with = "serde_with::cbor_value::option")]
+    value: Option<tari_bor::Value>,
+    /// The last epoch that the entry's rent has been paid for. The state of a key-value store is held in its entries,
+    /// so each entry pays rent for its own size. Entries created while rent is not charged never expire.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    rent_paid_until: Option<u64>,
+}
+
+impl KeyValueEntry {
+    pub fn new(key: Vec<u8>, value: tari_bor::Value) -> Self {
+        Self {
+            key,
+            value: Some(value),
+            rent_paid_until: None,
+        }
+    }
+
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn value(&self) -> Option<&tari_bor::Value> {
+        self.value.as_ref()
+    }
+
+    pub fn decode_value<T: serde::de::DeserializeOwned>(&self) -> Result<Option<T>, BorError> {
+        self.value.as_ref().map(tari_bor::from_value).transpose()
+    }
+
+    /// Sets the value of the entry, returning the previous value if any
+    pub fn replace(&mut self, value: tari_bor::Value) -> Option<tari_bor::Value> {
+        self.value.replace(value)
+    }
+
+    /// Removes the value of the entry, returning it if any
+    pub fn remove(&mut self) -> Option<tari_bor::Value> {
+        self.value.take()
+    }
+
+    pub fn rent_paid_until(&self) -> Option<u64> {
+        self.rent_paid_until
+    }
+
+    pub fn set_rent_paid_until(&mut self, epoch: u64) {
+        self.rent_paid_until = Some(epoch);
+    }
+}
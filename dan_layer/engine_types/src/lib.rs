@@ -7,14 +7,18 @@ pub mod commit_result;
 pub mod component;
 pub mod confidential;
 pub mod events;
+pub mod execution_failure;
+pub mod execution_trace;
 pub mod fee_claim;
 pub mod fees;
 pub mod hashing;
 pub mod indexed_value;
 pub mod instruction;
 pub mod instruction_result;
+pub mod key_value_store;
 pub mod lock;
 pub mod logs;
+pub mod named_address;
 pub mod non_fungible;
 pub mod non_fungible_index;
 pub mod proof;
@@ -24,6 +24,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 pub use tari_template_lib::args::LogLevel;
+use tari_template_lib::models::Metadata;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
@@ -32,16 +33,41 @@ use ts_rs::TS;
 pub struct LogEntry {
     pub message: String,
     pub level: LogLevel,
+    /// Structured key-value fields of the log entry
+    #[serde(default)]
+    pub fields: Metadata,
 }
 
 impl LogEntry {
     pub fn new(level: LogLevel, message: String) -> Self {
-        Self { message, level }
+        Self {
+            message,
+            level,
+            fields: Metadata::new(),
+        }
+    }
+
+    pub fn with_fields(mut self, fields: Metadata) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// The number of bytes of the message and fields, used to limit the size of the logs of a transaction
+    pub fn size(&self) -> usize {
+        self.message.len() +
+            self.fields
+                .iter()
+                .map(|(key, value)| key.len() + value.len())
+                .sum::<usize>()
     }
 }
 
 impl Display for LogEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}", self.level, self.message)
+        write!(f, "{} {}", self.level, self.message)?;
+        for (key, value) in self.fields.iter() {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,78 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Derivation of well-known component and resource addresses.
+//!
+//! A named address only depends on the template that creates the substate, the public key of the creator (the signer
+//! of the creating transaction) and a name chosen by the template. This allows clients to compute the address of a
+//! deployment before it exists. The engine rejects the creation of a named substate that already exists.
+
+use tari_common_types::types::PublicKey;
+use tari_template_lib::models::{ComponentAddress, ObjectKey, ResourceAddress, TemplateAddress};
+
+use crate::hashing::{hasher32, EngineHashDomainLabel};
+
+/// Derives the address of the component named `name` created by `creator` using the template at `template_address`
+pub fn new_named_component_address(
+    template_address: &TemplateAddress,
+    creator: &PublicKey,
+    name: &str,
+) -> ComponentAddress {
+    ComponentAddress::new(named_object_key(
+        EngineHashDomainLabel::NamedComponentAddress,
+        template_address,
+        creator,
+        name,
+    ))
+}
+
+/// Derives the address of the resource named `name` created by `creator` using the template at `template_address`
+pub fn new_named_resource_address(
+    template_address: &TemplateAddress,
+    creator: &PublicKey,
+    name: &str,
+) -> ResourceAddress {
+    ResourceAddress::new(named_object_key(
+        EngineHashDomainLabel::NamedResourceAddress,
+        template_address,
+        creator,
+        name,
+    ))
+}
+
+fn named_object_key(
+    label: EngineHashDomainLabel,
+    template_address: &TemplateAddress,
+    creator: &PublicKey,
+    name: &str,
+) -> ObjectKey {
+    let hash = hasher32(label)
+        .chain(template_address)
+        .chain(creator)
+        .chain(name)
+        .result();
+    ObjectKey::from_array(hash.leading_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_crypto::keys::PublicKey as _;
+
+    use super::*;
+
+    #[test]
+    fn it_derives_distinct_addresses_for_each_name_and_creator() {
+        let template_address = TemplateAddress::default();
+        let (_, creator1) = PublicKey::random_keypair(&mut rand::rngs::OsRng);
+        let (_, creator2) = PublicKey::random_keypair(&mut rand::rngs::OsRng);
+
+        let addr = new_named_component_address(&template_address, &creator1, "pool");
+        assert_eq!(addr, new_named_component_address(&template_address, &creator1, "pool"));
+        assert_ne!(addr, new_named_component_address(&template_address, &creator1, "pool2"));
+        assert_ne!(addr, new_named_component_address(&template_address, &creator2, "pool"));
+        assert_ne!(
+            *addr.as_object_key(),
+            *new_named_resource_address(&template_address, &creator1, "pool").as_object_key()
+        );
+    }
+}
@@ -79,4 +79,108 @@ impl NonFungible {
     pub fn set_mutable_data(&mut self, mutable_data: tari_bor::Value) {
         self.mutable_data = mutable_data;
     }
+
+    /// Merges the fields of `patch` into the mutable data. Fields in the patch replace the fields with the same key,
+    /// maps that are in both are merged recursively and all other fields are left unchanged.
+    pub fn merge_mutable_data(&mut self, patch: tari_bor::Value) -> Result<(), NonFungibleDataPatchError> {
+        let tari_bor::Value::Map(patch) = patch else {
+            return Err(NonFungibleDataPatchError::PatchNotAMap);
+        };
+        let tari_bor::Value::Map(mutable_data) = &mut self.mutable_data else {
+            return Err(NonFungibleDataPatchError::MutableDataNotAMap);
+        };
+        merge_maps(mutable_data, patch);
+        Ok(())
+    }
+}
+
+fn merge_maps(target: &mut Vec<(tari_bor::Value, tari_bor::Value)>, patch: Vec<(tari_bor::Value, tari_bor::Value)>) {
+    for (key, value) in patch {
+        match target.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => match (existing, value) {
+                (tari_bor::Value::Map(existing), tari_bor::Value::Map(value)) => merge_maps(existing, value),
+                (existing, value) => *existing = value,
+            },
+            None => target.push((key, value)),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NonFungibleDataPatchError {
+    #[error("The patch is not a map of fields")]
+    PatchNotAMap,
+    #[error("The mutable data is not a map of fields")]
+    MutableDataNotAMap,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use tari_bor::to_value;
+
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Data {
+        name: String,
+        level: u32,
+        stats: Stats,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Stats {
+        strength: u32,
+        speed: u32,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct LevelUp {
+        level: u32,
+        stats: SpeedUp,
+    }
+
+    #[derive(Debug, Serialize)]
+    struct SpeedUp {
+        speed: u32,
+    }
+
+    #[test]
+    fn it_merges_the_patch_into_the_mutable_data() {
+        let data = Data {
+            name: "hero".to_string(),
+            level: 1,
+            stats: Stats { strength: 5, speed: 3 },
+        };
+        let mut nft = NonFungible::new(tari_bor::Value::Null, to_value(&data).unwrap());
+
+        nft.merge_mutable_data(
+            to_value(&LevelUp {
+                level: 2,
+                stats: SpeedUp { speed: 4 },
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(nft.decode_mutable_data::<Data>().unwrap(), Data {
+            name: "hero".to_string(),
+            level: 2,
+            stats: Stats { strength: 5, speed: 4 },
+        });
+    }
+
+    #[test]
+    fn it_rejects_patches_that_are_not_maps() {
+        let data = to_value(&Stats { strength: 1, speed: 1 }).unwrap();
+        let mut nft = NonFungible::new(tari_bor::Value::Null, data);
+        let err = nft.merge_mutable_data(to_value(&123u32).unwrap()).unwrap_err();
+        assert!(matches!(err, NonFungibleDataPatchError::PatchNotAMap));
+
+        let mut nft = NonFungible::new(tari_bor::Value::Null, tari_bor::Value::Null);
+        let err = nft
+            .merge_mutable_data(to_value(&SpeedUp { speed: 1 }).unwrap())
+            .unwrap_err();
+        assert!(matches!(err, NonFungibleDataPatchError::MutableDataNotAMap));
+    }
 }
@@ -29,38 +29,43 @@ use tari_template_lib::{
 
 use crate::resource_container::ResourceContainer;
 
+/// A proof of one or more locked resources. A proof created from a single vault or bucket locks one resource, a
+/// composite proof created by combining proofs locks the resources of all of its constituent proofs.
 #[derive(Debug, Clone)]
 pub struct Proof {
-    locked: LockedResource,
+    locked: Vec<LockedResource>,
 }
 
 impl Proof {
     pub fn new(locked: LockedResource) -> Self {
-        Self { locked }
+        Self { locked: vec![locked] }
     }
 
-    pub fn amount(&self) -> Amount {
-        self.locked.amount()
+    /// Combines the given proofs into a composite proof of all of the resources they lock
+    pub fn combine<I: IntoIterator<Item = Proof>>(proofs: I) -> Self {
+        Self {
+            locked: proofs.into_iter().flat_map(|p| p.locked).collect(),
+        }
     }
 
-    pub fn resource_address(&self) -> &ResourceAddress {
-        self.locked.resource_address()
+    pub fn is_composite(&self) -> bool {
+        self.locked.len() > 1
     }
 
-    pub fn non_fungible_token_ids(&self) -> &BTreeSet<NonFungibleId> {
-        self.locked.non_fungible_token_ids()
+    /// Returns the locked resource of the proof, or None if this is a composite proof
+    pub fn as_single(&self) -> Option<&LockedResource> {
+        match self.locked.as_slice() {
+            [locked] => Some(locked),
+            _ => None,
+        }
     }
 
-    pub fn resource_type(&self) -> ResourceType {
-        self.locked.resource_type()
-    }
-
-    pub fn container(&self) -> &ContainerRef {
-        &self.locked.container
+    pub fn locked_resources(&self) -> &[LockedResource] {
+        &self.locked
     }
 
-    pub fn into_resource_container(self) -> ResourceContainer {
-        self.locked.into_resource_container()
+    pub fn into_locked_resources(self) -> Vec<LockedResource> {
+        self.locked
     }
 }
 
@@ -26,7 +26,7 @@ use tari_template_lib::{
     auth::{AuthHook, OwnerRule, Ownership, ResourceAccessRules},
     crypto::RistrettoPublicKeyBytes,
     models::{Amount, Metadata},
-    resource::{ResourceType, TOKEN_SYMBOL},
+    resource::{ResourceType, RoyaltyConfig, TOKEN_SYMBOL},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +46,14 @@ pub struct Resource {
     #[cfg_attr(feature = "ts", ts(type = "string | null"))]
     view_key: Option<PublicKey>,
     auth_hook: Option<AuthHook>,
+    #[serde(default)]
+    is_frozen: bool,
+    #[serde(default)]
+    royalty: Option<RoyaltyConfig>,
+    #[serde(default)]
+    is_soulbound: bool,
+    #[serde(default)]
+    max_supply: Option<Amount>,
 }
 
 impl Resource {
@@ -67,6 +75,10 @@ impl Resource {
             total_supply: 0.into(),
             view_key,
             auth_hook,
+            is_frozen: false,
+            royalty: None,
+            is_soulbound: false,
+            max_supply: None,
         }
     }
 
@@ -136,10 +148,49 @@ impl Resource {
         self.total_supply
     }
 
+    /// Returns the maximum total supply of the resource, if one was set on creation
+    pub fn max_supply(&self) -> Option<Amount> {
+        self.max_supply
+    }
+
+    pub fn set_max_supply(&mut self, max_supply: Option<Amount>) {
+        self.max_supply = max_supply;
+    }
+
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
     }
 
+    pub fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata;
+    }
+
+    /// Returns true if the resource has been frozen, in which case no tokens can be minted, withdrawn or deposited
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen
+    }
+
+    pub fn set_frozen(&mut self, is_frozen: bool) {
+        self.is_frozen = is_frozen;
+    }
+
+    pub fn royalty(&self) -> Option<&RoyaltyConfig> {
+        self.royalty.as_ref()
+    }
+
+    pub fn set_royalty(&mut self, royalty: Option<RoyaltyConfig>) {
+        self.royalty = royalty;
+    }
+
+    /// Returns true if tokens of the resource cannot be moved out of the vault they were first deposited into
+    pub fn is_soulbound(&self) -> bool {
+        self.is_soulbound
+    }
+
+    pub fn set_soulbound(&mut self, is_soulbound: bool) {
+        self.is_soulbound = is_soulbound;
+    }
+
     pub fn token_symbol(&self) -> Option<&str> {
         self.metadata.get(TOKEN_SYMBOL).map(|s| s.as_str())
     }
@@ -27,7 +27,12 @@ use tari_template_lib::{
 use ts_rs::TS;
 
 use crate::{
-    confidential::{validate_confidential_proof, validate_confidential_withdraw, ConfidentialOutput},
+    confidential::{
+        validate_confidential_proof,
+        validate_confidential_withdraw,
+        ConfidentialOutput,
+        ConfidentialProofBatch,
+    },
     substate::SubstateId,
 };
 
@@ -93,6 +98,7 @@ impl ResourceContainer {
         address: ResourceAddress,
         proof: ConfidentialOutputStatement,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
         if proof.change_statement.is_some() {
             return Err(ResourceError::InvalidConfidentialMintWithChange);
@@ -102,7 +108,7 @@ impl ResourceContainer {
                 details: "Change revealed amount must be zero for minting".to_string(),
             });
         }
-        let validated_proof = validate_confidential_proof(&proof, view_key)?;
+        let validated_proof = validate_confidential_proof(&proof, view_key, batch)?;
         assert!(
             validated_proof.change_output.is_none(),
             "invariant failed: validate_confidential_proof returned change with no change in input proof"
@@ -185,6 +191,15 @@ impl ResourceContainer {
         }
     }
 
+    /// Returns up to `limit` non-fungible ids, in order, skipping the first `offset` ids
+    pub fn non_fungible_token_ids_paged(&self, offset: usize, limit: usize) -> impl Iterator<Item = &NonFungibleId> {
+        self.non_fungible_token_ids().iter().skip(offset).take(limit)
+    }
+
+    pub fn non_fungible_count(&self) -> usize {
+        self.non_fungible_token_ids().len()
+    }
+
     pub fn into_non_fungible_ids(self) -> Option<BTreeSet<NonFungibleId>> {
         match self {
             ResourceContainer::NonFungible { token_ids, .. } => Some(token_ids),
@@ -367,6 +382,7 @@ impl ResourceContainer {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
         match self {
             ResourceContainer::Fungible { .. } => Err(ResourceError::OperationNotAllowed(
@@ -401,7 +417,7 @@ impl ResourceContainer {
                     })
                     .collect::<Result<Vec<_>, ResourceError>>()?;
 
-                let validated_proof = validate_confidential_withdraw(&inputs, view_key, proof)?;
+                let validated_proof = validate_confidential_withdraw(&inputs, view_key, proof, batch)?;
 
                 // Withdraw revealed amount
                 if validated_proof.input_revealed_amount > *revealed_amount {
@@ -506,8 +522,9 @@ impl ResourceContainer {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.withdraw_confidential(proof, view_key)
+        self.withdraw_confidential(proof, view_key, batch)
     }
 
     /// Returns all confidential commitments. If the resource is not confidential, None is returned.
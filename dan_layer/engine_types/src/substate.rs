@@ -31,6 +31,7 @@ use tari_common_types::types::FixedHash;
 use tari_template_lib::{
     models::{
         ComponentAddress,
+        KeyValueStoreId,
         NonFungibleAddress,
         NonFungibleIndexAddress,
         ObjectKey,
@@ -49,6 +50,7 @@ use crate::{
     confidential::UnclaimedConfidentialOutput,
     fee_claim::{FeeClaim, FeeClaimAddress},
     hashing::{hasher32, substate_value_hasher32, EngineHashDomainLabel},
+    key_value_store::{KeyValueEntry, KeyValueEntryAddress, KeyValueStoreContainer},
     non_fungible::NonFungibleContainer,
     non_fungible_index::NonFungibleIndex,
     resource::Resource,
@@ -118,6 +120,8 @@ pub enum SubstateId {
     NonFungibleIndex(#[serde(with = "serde_with::string")] NonFungibleIndexAddress),
     TransactionReceipt(#[serde(with = "serde_with::string")] TransactionReceiptAddress),
     FeeClaim(#[serde(with = "serde_with::string")] FeeClaimAddress),
+    KeyValueStore(#[serde(with = "serde_with::string")] KeyValueStoreId),
+    KeyValueEntry(#[serde(with = "serde_with::string")] KeyValueEntryAddress),
 }
 
 impl SubstateId {
@@ -142,6 +146,20 @@ impl SubstateId {
         }
     }
 
+    pub fn as_key_value_store_id(&self) -> Option<KeyValueStoreId> {
+        match self {
+            Self::KeyValueStore(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn as_key_value_entry_address(&self) -> Option<&KeyValueEntryAddress> {
+        match self {
+            Self::KeyValueEntry(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
     pub fn as_unclaimed_confidential_output_address(&self) -> Option<UnclaimedConfidentialOutputAddress> {
         match self {
             Self::UnclaimedConfidentialOutput(address) => Some(*address),
@@ -156,7 +174,9 @@ impl SubstateId {
             SubstateId::Resource(_) |
             SubstateId::Vault(_) |
             SubstateId::NonFungibleIndex(_) |
-            SubstateId::NonFungible(_) => true,
+            SubstateId::NonFungible(_) |
+            SubstateId::KeyValueStore(_) |
+            SubstateId::KeyValueEntry(_) => true,
             SubstateId::UnclaimedConfidentialOutput(_) |
             SubstateId::TransactionReceipt(_) |
             SubstateId::FeeClaim(_) => false,
@@ -198,6 +218,8 @@ impl SubstateId {
             SubstateId::UnclaimedConfidentialOutput(addr) => *addr.as_object_key(),
             SubstateId::TransactionReceipt(addr) => *addr.as_object_key(),
             SubstateId::FeeClaim(addr) => *addr.as_object_key(),
+            SubstateId::KeyValueStore(id) => *id.as_object_key(),
+            SubstateId::KeyValueEntry(addr) => addr.to_object_key(),
         }
     }
 
@@ -230,8 +252,12 @@ impl SubstateId {
 
     pub fn is_root(&self) -> bool {
         // A component is a "root" substate i.e. it may not have a parent node. NOTE: this concept isn't well-defined
-        // right now, this is simply used to prevent components being detected as dangling.
-        matches!(self, Self::Component(_) | Self::NonFungibleIndex(_))
+        // right now, this is simply used to prevent components being detected as dangling. Key-value entries are owned
+        // by their store, which is itself owned by a component.
+        matches!(
+            self,
+            Self::Component(_) | Self::NonFungibleIndex(_) | Self::KeyValueEntry(_)
+        )
     }
 
     pub fn is_public_key_identity(&self) -> bool {
@@ -254,6 +280,14 @@ impl SubstateId {
         matches!(self, Self::NonFungibleIndex(_))
     }
 
+    pub fn is_key_value_store(&self) -> bool {
+        matches!(self, Self::KeyValueStore(_))
+    }
+
+    pub fn is_key_value_entry(&self) -> bool {
+        matches!(self, Self::KeyValueEntry(_))
+    }
+
     pub fn is_layer1_commitment(&self) -> bool {
         matches!(self, Self::UnclaimedConfidentialOutput(_))
     }
@@ -309,6 +343,18 @@ impl From<FeeClaimAddress> for SubstateId {
     }
 }
 
+impl From<KeyValueStoreId> for SubstateId {
+    fn from(id: KeyValueStoreId) -> Self {
+        Self::KeyValueStore(id)
+    }
+}
+
+impl From<KeyValueEntryAddress> for SubstateId {
+    fn from(address: KeyValueEntryAddress) -> Self {
+        Self::KeyValueEntry(address)
+    }
+}
+
 impl From<TransactionReceiptAddress> for SubstateId {
     fn from(address: TransactionReceiptAddress) -> Self {
         Self::TransactionReceipt(address)
@@ -326,6 +372,17 @@ impl TryFrom<SubstateId> for ComponentAddress {
     }
 }
 
+impl TryFrom<SubstateId> for ResourceAddress {
+    type Error = SubstateId;
+
+    fn try_from(value: SubstateId) -> Result<Self, Self::Error> {
+        match value {
+            SubstateId::Resource(addr) => Ok(addr),
+            _ => Err(value),
+        }
+    }
+}
+
 impl Display for SubstateId {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -337,6 +394,8 @@ impl Display for SubstateId {
             SubstateId::UnclaimedConfidentialOutput(commitment_address) => write!(f, "{}", commitment_address),
             SubstateId::TransactionReceipt(addr) => write!(f, "{}", addr),
             SubstateId::FeeClaim(addr) => write!(f, "{}", addr),
+            SubstateId::KeyValueStore(id) => write!(f, "{}", id),
+            SubstateId::KeyValueEntry(addr) => write!(f, "{}", addr),
         }
     }
 }
@@ -388,6 +447,15 @@ impl FromStr for SubstateId {
                 let addr = Hash::from_hex(addr).map_err(|_| InvalidSubstateIdFormat(addr.to_string()))?;
                 Ok(SubstateId::FeeClaim(addr.into()))
             },
+            Some(("kvstore", addr)) => {
+                let id = KeyValueStoreId::from_hex(addr).map_err(|_| InvalidSubstateIdFormat(s.to_string()))?;
+                Ok(SubstateId::KeyValueStore(id))
+            },
+            Some(("kventry", rest)) => {
+                // kventry_{store_id}_{key_hash}
+                let addr = KeyValueEntryAddress::from_str(rest).map_err(|_| InvalidSubstateIdFormat(s.to_string()))?;
+                Ok(SubstateId::KeyValueEntry(addr))
+            },
             Some(_) | None => Err(InvalidSubstateIdFormat(s.to_string())),
         }
     }
@@ -417,6 +485,8 @@ impl_partial_eq!(UnclaimedConfidentialOutputAddress, UnclaimedConfidentialOutput
 impl_partial_eq!(NonFungibleAddress, NonFungible);
 impl_partial_eq!(TransactionReceiptAddress, TransactionReceipt);
 impl_partial_eq!(FeeClaimAddress, FeeClaim);
+impl_partial_eq!(KeyValueStoreId, KeyValueStore);
+impl_partial_eq!(KeyValueEntryAddress, KeyValueEntry);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
@@ -429,6 +499,8 @@ pub enum SubstateValue {
     UnclaimedConfidentialOutput(UnclaimedConfidentialOutput),
     TransactionReceipt(TransactionReceipt),
     FeeClaim(FeeClaim),
+    KeyValueStore(KeyValueStoreContainer),
+    KeyValueEntry(KeyValueEntry),
 }
 
 impl SubstateValue {
@@ -579,6 +651,20 @@ impl SubstateValue {
         }
     }
 
+    pub fn as_key_value_entry(&self) -> Option<&KeyValueEntry> {
+        match self {
+            SubstateValue::KeyValueEntry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn as_key_value_entry_mut(&mut self) -> Option<&mut KeyValueEntry> {
+        match self {
+            SubstateValue::KeyValueEntry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
     pub fn as_unclaimed_confidential_output(&self) -> Option<&UnclaimedConfidentialOutput> {
         match self {
             SubstateValue::UnclaimedConfidentialOutput(output) => Some(output),
@@ -586,6 +672,44 @@ impl SubstateValue {
         }
     }
 
+    /// Returns true if the substate pays rent for its state when rent is charged. Components, vaults and key-value
+    /// store entries hold state that grows with use, other substates are bounded by the substates that own them.
+    pub fn pays_rent(&self) -> bool {
+        matches!(
+            self,
+            SubstateValue::Component(_) | SubstateValue::Vault(_) | SubstateValue::KeyValueEntry(_)
+        )
+    }
+
+    /// Returns the last epoch that the substate's rent has been paid for, or None if the substate does not pay rent or
+    /// was created while rent was not charged
+    pub fn rent_paid_until(&self) -> Option<u64> {
+        match self {
+            SubstateValue::Component(component) => component.rent_paid_until,
+            SubstateValue::Vault(vault) => vault.rent_paid_until(),
+            SubstateValue::KeyValueEntry(entry) => entry.rent_paid_until(),
+            _ => None,
+        }
+    }
+
+    /// Sets the last epoch that the substate's rent has been paid for. This has no effect if the substate does not pay
+    /// rent.
+    pub fn set_rent_paid_until(&mut self, epoch: u64) {
+        match self {
+            SubstateValue::Component(component) => component.rent_paid_until = Some(epoch),
+            SubstateValue::Vault(vault) => vault.set_rent_paid_until(epoch),
+            SubstateValue::KeyValueEntry(entry) => entry.set_rent_paid_until(epoch),
+            _ => {},
+        }
+    }
+
+    /// Returns true if the substate's rent ran out before the given epoch. A substate with expired rent is inactive and
+    /// cannot be used by a transaction until its rent is topped up.
+    pub fn is_rent_expired(&self, current_epoch: u64) -> bool {
+        self.rent_paid_until()
+            .is_some_and(|paid_until| current_epoch > paid_until)
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         encode(self).unwrap()
     }
@@ -631,6 +755,18 @@ impl From<FeeClaim> for SubstateValue {
     }
 }
 
+impl From<KeyValueStoreContainer> for SubstateValue {
+    fn from(store: KeyValueStoreContainer) -> Self {
+        Self::KeyValueStore(store)
+    }
+}
+
+impl From<KeyValueEntry> for SubstateValue {
+    fn from(entry: KeyValueEntry) -> Self {
+        Self::KeyValueEntry(entry)
+    }
+}
+
 impl From<TransactionReceipt> for SubstateValue {
     fn from(tx_receipt: TransactionReceipt) -> Self {
         Self::TransactionReceipt(tx_receipt)
@@ -649,6 +785,8 @@ impl Display for SubstateValue {
             SubstateValue::UnclaimedConfidentialOutput(commitment) => write!(f, "{:?}", commitment),
             SubstateValue::TransactionReceipt(tx_receipt) => write!(f, "{:?}", tx_receipt),
             SubstateValue::FeeClaim(fee_claim) => write!(f, "{:?}", fee_claim),
+            SubstateValue::KeyValueStore(store) => write!(f, "{:?}", store),
+            SubstateValue::KeyValueEntry(entry) => write!(f, "{:?}", entry),
         }
     }
 }
@@ -769,6 +907,11 @@ mod tests {
             check("feeclaim_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff");
             check("txreceipt_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff");
             check("commitment_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff");
+            check("kvstore_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff");
+            check(
+                "kventry_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab5ffffffff_\
+                 7f19c3fe5fa13ff66a0d379fe5f9e3508acbd338db6bedd7350d8d565b2c5d32",
+            );
         }
     }
 }
@@ -34,8 +34,8 @@ use ts_rs::TS;
 
 use crate::{
     bucket::Bucket,
-    confidential::{ConfidentialOutput, ElgamalVerifiableBalance, ValueLookupTable},
-    proof::{ContainerRef, LockedResource, Proof},
+    confidential::{ConfidentialOutput, ConfidentialProofBatch, ElgamalVerifiableBalance, ValueLookupTable},
+    proof::{ContainerRef, LockedResource},
     resource_container::{ResourceContainer, ResourceError},
 };
 
@@ -43,15 +43,29 @@ use crate::{
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
 pub struct Vault {
     resource_container: ResourceContainer,
+    /// The last epoch that the vault's rent has been paid for. Vaults created while rent is not charged have no rent
+    /// epoch and never expire.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    rent_paid_until: Option<u64>,
 }
 
 impl Vault {
     pub fn new(resource: ResourceContainer) -> Self {
         Self {
             resource_container: resource,
+            rent_paid_until: None,
         }
     }
 
+    pub fn rent_paid_until(&self) -> Option<u64> {
+        self.rent_paid_until
+    }
+
+    pub fn set_rent_paid_until(&mut self, epoch: u64) {
+        self.rent_paid_until = Some(epoch);
+    }
+
     pub fn deposit(&mut self, bucket: Bucket) -> Result<(), ResourceError> {
         self.resource_container.deposit(bucket.into_resource())?;
         Ok(())
@@ -72,8 +86,9 @@ impl Vault {
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.withdraw_confidential(proof, view_key)
+        self.resource_container.withdraw_confidential(proof, view_key, batch)
     }
 
     pub fn recall_all(&mut self) -> Result<ResourceContainer, ResourceError> {
@@ -97,6 +112,13 @@ impl Vault {
         self.resource_container.locked_amount()
     }
 
+    /// Returns true if the vault holds no funds, including locked funds and confidential commitments
+    pub fn is_empty(&self) -> bool {
+        self.balance().is_zero() &&
+            self.locked_balance().is_zero() &&
+            self.resource_container.number_of_confidential_commitments() == 0
+    }
+
     pub fn get_commitment_count(&self) -> u32 {
         self.resource_container.get_commitment_count()
     }
@@ -142,12 +164,24 @@ impl Vault {
         self.resource_container.non_fungible_token_ids()
     }
 
+    pub fn get_non_fungible_ids_paged(&self, offset: usize, limit: usize) -> Vec<NonFungibleId> {
+        self.resource_container
+            .non_fungible_token_ids_paged(offset, limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_non_fungible_count(&self) -> usize {
+        self.resource_container.non_fungible_count()
+    }
+
     pub fn reveal_confidential(
         &mut self,
         proof: ConfidentialWithdrawProof,
         view_key: Option<&PublicKey>,
+        batch: &mut ConfidentialProofBatch,
     ) -> Result<ResourceContainer, ResourceError> {
-        self.resource_container.reveal_confidential(proof, view_key)
+        self.resource_container.reveal_confidential(proof, view_key, batch)
     }
 
     pub fn resource_container_mut(&mut self) -> &mut ResourceContainer {
@@ -173,7 +207,7 @@ impl Vault {
         Ok(LockedResource::new(ContainerRef::Vault(vault_id), locked_resource))
     }
 
-    pub fn unlock(&mut self, proof: Proof) -> Result<(), ResourceError> {
-        self.resource_container.unlock(proof.into_resource_container())
+    pub fn unlock(&mut self, locked: LockedResource) -> Result<(), ResourceError> {
+        self.resource_container.unlock(locked.into_resource_container())
     }
 }
@@ -8,14 +8,22 @@ use std::{
 };
 
 use serde::{Deserialize, Serialize};
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_template_lib::Hash;
 
-use crate::fee_claim::FeeClaim;
+use crate::{
+    fee_claim::FeeClaim,
+    hashing::{hasher32, EngineHashDomainLabel},
+    substate::{Substate, SubstateId},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum VirtualSubstateId {
     CurrentEpoch,
     UnclaimedValidatorFee { epoch: u64, address: PublicKey },
+    RandomSeed,
+    CurrentTimestamp,
+    RandomBeacon,
 }
 
 impl Display for VirtualSubstateId {
@@ -29,6 +37,9 @@ impl Display for VirtualSubstateId {
                     epoch, address
                 )
             },
+            VirtualSubstateId::RandomSeed => write!(f, "Virtual(RandomSeed)"),
+            VirtualSubstateId::CurrentTimestamp => write!(f, "Virtual(CurrentTimestamp)"),
+            VirtualSubstateId::RandomBeacon => write!(f, "Virtual(RandomBeacon)"),
         }
     }
 }
@@ -37,6 +48,38 @@ impl Display for VirtualSubstateId {
 pub enum VirtualSubstate {
     CurrentEpoch(u64),
     UnclaimedValidatorFee(FeeClaim),
+    RandomSeed(Hash),
+    /// Unix timestamp in seconds
+    CurrentTimestamp(u64),
+    /// Per-transaction entropy derived from the VRF output of the block that prepared the transaction
+    RandomBeacon(Hash),
+}
+
+impl VirtualSubstate {
+    /// Derives the random seed for a transaction from the random beacon of the transaction and the input substates
+    /// that were agreed on by consensus. The beacon is only known once the transaction has been prepared in a block, so
+    /// the sender cannot choose inputs that result in a favourable seed. Every committee involved in the transaction
+    /// executes it with the same beacon and inputs, so all of them derive the same seed.
+    pub fn random_seed<'a, I>(epoch: u64, random_beacon: &FixedHash, inputs: I) -> Self
+    where I: IntoIterator<Item = (&'a SubstateId, &'a Substate)> {
+        let mut inputs = inputs.into_iter().collect::<Vec<_>>();
+        inputs.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut hasher = hasher32(EngineHashDomainLabel::RandomSeed)
+            .chain(&epoch)
+            .chain(random_beacon);
+        for (id, substate) in inputs {
+            hasher = hasher
+                .chain(id)
+                .chain(&substate.version())
+                .chain(&substate.to_value_hash());
+        }
+        Self::RandomSeed(hasher.result())
+    }
+
+    pub fn random_beacon(beacon: &FixedHash) -> Self {
+        Self::RandomBeacon(Hash::try_from(beacon.as_bytes()).expect("FixedHash is always 32 bytes"))
+    }
 }
 
 // Developer note: this struct has two non-functional purposes:
@@ -85,3 +128,49 @@ impl FromIterator<(VirtualSubstateId, VirtualSubstate)> for VirtualSubstates {
         Self(iter.into_iter().collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tari_template_lib::models::Amount;
+
+    use super::*;
+    use crate::fee_claim::FeeClaimAddress;
+
+    fn fee_claim_input(epoch: u64) -> (SubstateId, Substate) {
+        let address = FeeClaimAddress::from_addr(epoch, [0u8; 32]);
+        let claim = FeeClaim {
+            epoch,
+            validator_public_key: PublicKey::default(),
+            amount: Amount::new(100),
+        };
+        (address.into(), Substate::new(0, claim))
+    }
+
+    fn random_seed(epoch: u64, random_beacon: &FixedHash, inputs: &[(SubstateId, Substate)]) -> Hash {
+        let VirtualSubstate::RandomSeed(seed) =
+            VirtualSubstate::random_seed(epoch, random_beacon, inputs.iter().map(|(id, s)| (id, s)))
+        else {
+            panic!("Expected random seed");
+        };
+        seed
+    }
+
+    #[test]
+    fn random_seed_does_not_depend_on_input_order() {
+        let inputs = [fee_claim_input(1), fee_claim_input(2)];
+        let reversed = [fee_claim_input(2), fee_claim_input(1)];
+        let beacon = FixedHash::zero();
+        let a = random_seed(1, &beacon, &inputs);
+        assert_eq!(a, random_seed(1, &beacon, &reversed));
+        assert_ne!(a, random_seed(2, &beacon, &inputs));
+    }
+
+    #[test]
+    fn random_seed_depends_on_the_random_beacon() {
+        let inputs = [fee_claim_input(1)];
+        assert_ne!(
+            random_seed(1, &FixedHash::zero(), &inputs),
+            random_seed(1, &FixedHash::from([1u8; 32]), &inputs)
+        );
+    }
+}
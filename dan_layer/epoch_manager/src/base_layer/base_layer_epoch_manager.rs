@@ -20,12 +20,15 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{cmp, collections::HashMap, mem, num::NonZeroU32};
+use std::{cmp, collections::HashMap, mem, num::NonZeroU32, time::Duration};
 
 use log::*;
 use tari_base_node_client::{grpc::GrpcBaseNodeClient, types::BaseLayerConsensusConstants, BaseNodeClient};
 use tari_common_types::types::{FixedHash, PublicKey};
-use tari_core::{blocks::BlockHeader, transactions::transaction_components::ValidatorNodeRegistration};
+use tari_core::{
+    blocks::BlockHeader,
+    transactions::{tari_amount::MicroMinotari, transaction_components::ValidatorNodeRegistration},
+};
 use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
     optional::Optional,
@@ -40,7 +43,11 @@ use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
 use tari_utilities::{byte_array::ByteArray, hex::Hex};
 use tokio::sync::{broadcast, oneshot};
 
-use crate::{base_layer::config::EpochManagerConfig, error::EpochManagerError, EpochManagerEvent};
+use crate::{
+    base_layer::{config::EpochManagerConfig, ValidatorAllowlist},
+    error::EpochManagerError,
+    EpochManagerEvent,
+};
 
 const LOG_TARGET: &str = "tari::dan::epoch_manager::base_layer";
 
@@ -57,6 +64,7 @@ pub struct BaseLayerEpochManager<TGlobalStore, TBaseNodeClient> {
     current_shard_key: Option<SubstateAddress>,
     base_layer_consensus_constants: Option<BaseLayerConsensusConstants>,
     is_initial_base_layer_sync_complete: bool,
+    validator_allowlist: Option<ValidatorAllowlist>,
 }
 
 impl<TAddr: NodeAddressable + DerivableFromPublicKey>
@@ -82,6 +90,7 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
             current_shard_key: None,
             base_layer_consensus_constants: None,
             is_initial_base_layer_sync_complete: false,
+            validator_allowlist: None,
         }
     }
 
@@ -102,6 +111,29 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         self.last_block_of_current_epoch = metadata
             .get_metadata(MetadataKey::EpochManagerLastBlockOfCurrentEpoch)?
             .unwrap_or(Default::default());
+
+        if let Some(config) = self.config.validator_allowlist.clone() {
+            info!(target: LOG_TARGET, "Loading validator allowlist from {}", config.path.display());
+            let applied = metadata.get_metadata(MetadataKey::EpochManagerValidatorAllowlist)?;
+            let min_version = metadata
+                .get_metadata(MetadataKey::EpochManagerValidatorAllowlistVersion)?
+                .unwrap_or(0);
+            let allowlist = ValidatorAllowlist::load(config, applied, min_version)?;
+            info!(
+                target: LOG_TARGET,
+                "📋️ Validator allowlist version {} loaded with {} validator(s). Highest accepted version: {}",
+                allowlist.version(),
+                allowlist.len(),
+                allowlist.highest_version()
+            );
+            metadata.set_metadata(MetadataKey::EpochManagerValidatorAllowlist, allowlist.active())?;
+            metadata.set_metadata(
+                MetadataKey::EpochManagerValidatorAllowlistVersion,
+                &allowlist.highest_version(),
+            )?;
+            tx.commit()?;
+            self.validator_allowlist = Some(allowlist);
+        }
         Ok(())
     }
 
@@ -122,9 +154,12 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         // extract and store in database the MMR of the epoch's validator nodes
         let epoch_header = self.base_node_client.get_header_by_hash(block_hash).await?;
 
+        // Pick up a new allowlist before the epoch starts, in case it has not been reloaded since it changed
+        self.reload_validator_allowlist();
         // persist the epoch data including the validator node set
         self.insert_current_epoch(epoch, epoch_header)?;
         self.update_base_layer_consensus_constants(base_layer_constants)?;
+        self.apply_validator_allowlist(epoch)?;
         self.assign_validators_for_epoch(epoch)?;
 
         Ok(())
@@ -145,6 +180,7 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
                 vn.shard_key.to_shard_group(self.config.num_preshards, num_committees),
                 self.config.validator_node_sidechain_id.as_ref(),
                 epoch,
+                self.voting_power_of(&vn),
             )?;
         }
 
@@ -153,6 +189,141 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         Ok(())
     }
 
+    /// Activates the allowlist for the epoch and applies it to the registered validators. This is done at the start of
+    /// each epoch so that changes to the allowlist take effect at the same time for all validators. The registrations
+    /// of validators that are not in the allowlist are held rather than removed, and are restored with a start epoch
+    /// of the current epoch once a later allowlist admits them.
+    fn apply_validator_allowlist(&mut self, epoch: Epoch) -> Result<(), EpochManagerError> {
+        let Some(ref mut allowlist) = self.validator_allowlist else {
+            return Ok(());
+        };
+        if allowlist.activate_for_epoch(epoch) {
+            info!(
+                target: LOG_TARGET,
+                "📋️ Validator allowlist version {} with {} validator(s) is active from epoch {}",
+                allowlist.version(),
+                allowlist.len(),
+                epoch
+            );
+        }
+
+        let mut tx = self.global_db.create_transaction()?;
+        let held = self
+            .global_db
+            .metadata(&mut tx)
+            .get_metadata::<Vec<ValidatorNode<TAddr>>>(MetadataKey::EpochManagerHeldValidatorRegistrations)?
+            .unwrap_or_default();
+        let (restored, mut held) = held
+            .into_iter()
+            .partition::<Vec<_>, _>(|vn| allowlist.is_allowed(&vn.public_key));
+
+        let mut validator_nodes = self.global_db.validator_nodes(&mut tx);
+        for vn in restored {
+            let has_registered_again = validator_nodes
+                .get_by_public_key(epoch, &vn.public_key, vn.sidechain_id.as_ref())
+                .optional()?
+                .is_some();
+            if has_registered_again {
+                continue;
+            }
+            info!(
+                target: LOG_TARGET,
+                "📋️ Validator {} is in the validator allowlist (version {}). Restoring its registration for epoch {}",
+                vn.public_key,
+                allowlist.version(),
+                epoch
+            );
+            validator_nodes.insert_validator_node(
+                vn.address,
+                vn.public_key,
+                vn.shard_key,
+                vn.registered_at_base_height,
+                cmp::max(vn.start_epoch, epoch),
+                vn.fee_claim_public_key,
+                vn.sidechain_id,
+                vn.stake,
+            )?;
+        }
+
+        let vns = validator_nodes.get_all_within_epoch(epoch, self.config.validator_node_sidechain_id.as_ref())?;
+        for vn in vns {
+            if allowlist.is_allowed(&vn.public_key) {
+                continue;
+            }
+            warn!(
+                target: LOG_TARGET,
+                "🚫 Validator {} is not in the validator allowlist (version {}). Holding its registration before epoch \
+                 {}",
+                vn.public_key,
+                allowlist.version(),
+                epoch
+            );
+            validator_nodes.remove(vn.public_key.clone(), vn.sidechain_id.clone())?;
+            // A validator that registers again while it is held replaces its previous registration
+            held.retain(|held_vn| held_vn.public_key != vn.public_key);
+            held.push(vn);
+        }
+
+        let mut metadata = self.global_db.metadata(&mut tx);
+        metadata.set_metadata(MetadataKey::EpochManagerHeldValidatorRegistrations, &held)?;
+        metadata.set_metadata(MetadataKey::EpochManagerValidatorAllowlist, allowlist.active())?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Reloads the validator allowlist if it has changed. A new allowlist is pending until its activation epoch. If
+    /// the new allowlist is invalid, it is ignored.
+    pub fn reload_validator_allowlist(&mut self) {
+        let Some(ref mut allowlist) = self.validator_allowlist else {
+            return;
+        };
+        match allowlist.reload_if_changed(self.current_epoch) {
+            Ok(true) => {
+                info!(
+                    target: LOG_TARGET,
+                    "📋️ Validator allowlist version {} with {} validator(s) accepted. It will be active from epoch {}",
+                    allowlist.latest().version,
+                    allowlist.latest().public_keys.len(),
+                    allowlist.latest().activation_epoch
+                );
+                if let Err(err) = self.persist_validator_allowlist_version() {
+                    error!(target: LOG_TARGET, "Failed to persist the validator allowlist version: {}", err);
+                }
+            },
+            Ok(false) => {},
+            Err(err) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to reload validator allowlist, keeping version {}: {}",
+                    allowlist.highest_version(),
+                    err
+                );
+            },
+        }
+    }
+
+    fn persist_validator_allowlist_version(&self) -> Result<(), EpochManagerError> {
+        let Some(ref allowlist) = self.validator_allowlist else {
+            return Ok(());
+        };
+        let mut tx = self.global_db.create_transaction()?;
+        self.global_db.metadata(&mut tx).set_metadata(
+            MetadataKey::EpochManagerValidatorAllowlistVersion,
+            &allowlist.highest_version(),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn validator_allowlist_reload_interval(&self) -> Option<Duration> {
+        self.config.validator_allowlist.as_ref().map(|c| c.reload_interval)
+    }
+
+    fn voting_power_of(&self, vn: &ValidatorNode<TAddr>) -> u64 {
+        calculate_voting_power(vn.stake, self.config.stake_weighted_voting)
+    }
+
     pub async fn base_layer_consensus_constants(&self) -> Result<&BaseLayerConsensusConstants, EpochManagerError> {
         Ok(self
             .base_layer_consensus_constants
@@ -190,6 +361,7 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         &mut self,
         block_height: u64,
         registration: ValidatorNodeRegistration,
+        value: MicroMinotari,
     ) -> Result<(), EpochManagerError> {
         if registration.sidechain_id() != self.config.validator_node_sidechain_id.as_ref() {
             return Err(EpochManagerError::ValidatorNodeRegistrationSidechainIdMismatch {
@@ -198,6 +370,19 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
             });
         }
 
+        if let Some(ref allowlist) = self.validator_allowlist {
+            if !allowlist.is_allowed(registration.public_key()) {
+                info!(
+                    target: LOG_TARGET,
+                    "Validator {} registered at height {} is not in the validator allowlist (version {}). Its \
+                     registration will be held at the start of the epoch unless the allowlist admits it",
+                    registration.public_key(),
+                    block_height,
+                    allowlist.version()
+                );
+            }
+        }
+
         let constants = self.base_layer_consensus_constants().await?;
         let next_epoch = constants.height_to_epoch(block_height) + Epoch(1);
 
@@ -223,6 +408,7 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
             next_epoch,
             registration.claim_public_key().clone(),
             registration.sidechain_id().cloned(),
+            value.as_u64(),
         )?;
 
         if *registration.public_key() == self.node_public_key {
@@ -260,6 +446,17 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         info!(target: LOG_TARGET, "Remove validator node({}) registration", public_key);
 
         let mut tx = self.global_db.create_transaction()?;
+        if self.validator_allowlist.is_some() {
+            let mut metadata = self.global_db.metadata(&mut tx);
+            let mut held = metadata
+                .get_metadata::<Vec<ValidatorNode<TAddr>>>(MetadataKey::EpochManagerHeldValidatorRegistrations)?
+                .unwrap_or_default();
+            let num_held = held.len();
+            held.retain(|vn| vn.public_key != public_key);
+            if held.len() != num_held {
+                metadata.set_metadata(MetadataKey::EpochManagerHeldValidatorRegistrations, &held)?;
+            }
+        }
         self.global_db
             .validator_nodes(&mut tx)
             .remove(public_key, sidechain_id)?;
@@ -508,9 +705,12 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
         substate_address: SubstateAddress,
     ) -> Result<Committee<TAddr>, EpochManagerError> {
         let result = self.get_committee_vns_from_shard_key(epoch, substate_address)?;
-        Ok(Committee::new(
-            result.into_iter().map(|v| (v.address, v.public_key)).collect(),
-        ))
+        let mut committee = Committee::with_capacity(result.len());
+        for vn in result {
+            committee.set_voting_power(vn.public_key.clone(), self.voting_power_of(&vn));
+            committee.members.push((vn.address, vn.public_key));
+        }
+        Ok(committee)
     }
 
     pub fn get_number_of_committees(&self, epoch: Epoch) -> Result<u32, EpochManagerError> {
@@ -667,6 +867,16 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey>
     }
 }
 
+/// Returns the voting power of a validator that registered with the given stake. A validator always has at least one
+/// vote so that validators registered without a stake are able to take part in consensus.
+fn calculate_voting_power(stake: u64, stake_weighted_voting: bool) -> u64 {
+    if stake_weighted_voting {
+        cmp::max(1, stake)
+    } else {
+        1
+    }
+}
+
 fn calculate_num_committees(num_vns: u64, committee_size: NonZeroU32) -> u32 {
     // Number of committees is proportional to the number of validators available.
     // We cap the number of committees to u32::MAX (for a committee_size of 10 that's over 42 billion validators)
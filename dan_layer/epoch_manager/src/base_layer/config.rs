@@ -1,7 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::num::NonZeroU32;
+use std::{num::NonZeroU32, path::PathBuf, time::Duration};
 
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::NumPreshards;
@@ -12,4 +12,18 @@ pub struct EpochManagerConfig {
     pub committee_size: NonZeroU32,
     pub validator_node_sidechain_id: Option<PublicKey>,
     pub num_preshards: NumPreshards,
+    /// If true, committee members are given a voting power equal to the stake locked by their registration
+    pub stake_weighted_voting: bool,
+    /// If set, only validators in the signed allowlist are admitted into committees
+    pub validator_allowlist: Option<ValidatorAllowlistConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatorAllowlistConfig {
+    /// The path to the signed allowlist JSON file
+    pub path: PathBuf,
+    /// The public key of the authority that signs the allowlist
+    pub authority_public_key: PublicKey,
+    /// How often the file is checked for changes
+    pub reload_interval: Duration,
 }
@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use log::{error, info, trace};
 use tari_base_node_client::grpc::GrpcBaseNodeClient;
 use tari_common_types::types::PublicKey;
@@ -30,6 +32,7 @@ use tari_shutdown::ShutdownSignal;
 use tokio::{
     sync::{broadcast, mpsc::Receiver, oneshot},
     task::JoinHandle,
+    time::{self, MissedTickBehavior},
 };
 
 use crate::{
@@ -77,6 +80,12 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey + 'static>
         // first, load initial state
         self.inner.load_initial_state().await?;
 
+        let reload_interval = self.inner.validator_allowlist_reload_interval();
+        // The interval is only polled if a validator allowlist is configured. Loading the allowlist rejects a zero
+        // interval.
+        let mut allowlist_reload = time::interval(reload_interval.unwrap_or(Duration::from_secs(60)));
+        allowlist_reload.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
         loop {
             tokio::select! {
                 req = self.rx_request.recv() => {
@@ -88,6 +97,9 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey + 'static>
                         }
                     }
                 },
+                _ = allowlist_reload.tick(), if reload_interval.is_some() => {
+                    self.inner.reload_validator_allowlist();
+                },
                 _ = shutdown.wait() => {
                     dbg!("Shutting down epoch manager");
                     break;
@@ -194,12 +206,12 @@ impl<TAddr: NodeAddressable + DerivableFromPublicKey + 'static>
             EpochManagerRequest::AddValidatorNodeRegistration {
                 block_height,
                 registration,
-                value: _value,
+                value,
                 reply,
             } => handle(
                 reply,
                 self.inner
-                    .add_validator_node_registration(block_height, registration)
+                    .add_validator_node_registration(block_height, registration, value)
                     .await,
                 context,
             ),
@@ -4,7 +4,7 @@
 use tari_base_node_client::BaseNodeClientError;
 use tari_dan_storage_sqlite::error::SqliteStorageError;
 
-use crate::EpochManagerError;
+use crate::{base_layer::ValidatorAllowlistError, EpochManagerError};
 
 impl From<BaseNodeClientError> for EpochManagerError {
     fn from(e: BaseNodeClientError) -> Self {
@@ -17,3 +17,9 @@ impl From<SqliteStorageError> for EpochManagerError {
         Self::SqlLiteStorageError(anyhow::Error::from(e))
     }
 }
+
+impl From<ValidatorAllowlistError> for EpochManagerError {
+    fn from(e: ValidatorAllowlistError) -> Self {
+        Self::ValidatorAllowlistError(anyhow::Error::from(e))
+    }
+}
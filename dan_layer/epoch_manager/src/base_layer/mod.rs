@@ -8,10 +8,12 @@ mod error;
 mod handle;
 mod initializer;
 mod types;
+mod validator_allowlist;
 
 pub use base_layer_epoch_manager::BaseLayerEpochManager;
-pub use config::EpochManagerConfig;
+pub use config::{EpochManagerConfig, ValidatorAllowlistConfig};
 pub use epoch_manager_service::EpochManagerService;
 pub use handle::EpochManagerHandle;
 pub use initializer::spawn_service;
 pub use types::*;
+pub use validator_allowlist::{SignedValidatorAllowlist, ValidatorAllowlist, ValidatorAllowlistError};
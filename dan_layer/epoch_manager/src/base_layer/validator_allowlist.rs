@@ -0,0 +1,460 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey, Signature};
+use tari_dan_common_types::{hashing::validator_allowlist_hasher, Epoch};
+
+use crate::base_layer::config::ValidatorAllowlistConfig;
+
+/// A list of validator public keys that are admitted into committees in a permissioned network. The list is signed by
+/// the network's allowlist authority.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedValidatorAllowlist {
+    /// Must be incremented each time the list is changed. A list with a lower version than a list that has already
+    /// been accepted is rejected, so that an old list cannot be replayed.
+    pub version: u64,
+    /// The epoch from which the list is in effect. Every validator applies the list at the start of this epoch, so the
+    /// list must be distributed before the epoch begins.
+    pub activation_epoch: Epoch,
+    pub public_keys: BTreeSet<PublicKey>,
+    pub signature: Signature,
+}
+
+impl SignedValidatorAllowlist {
+    pub fn sign(
+        authority_secret_key: &PrivateKey,
+        version: u64,
+        activation_epoch: Epoch,
+        public_keys: BTreeSet<PublicKey>,
+    ) -> Self {
+        let message = create_allowlist_message(version, activation_epoch, &public_keys);
+        Self {
+            version,
+            activation_epoch,
+            public_keys,
+            signature: Signature::sign(authority_secret_key, message, &mut OsRng).expect("sign is infallible"),
+        }
+    }
+
+    pub fn verify(&self, authority_public_key: &PublicKey) -> bool {
+        let message = create_allowlist_message(self.version, self.activation_epoch, &self.public_keys);
+        self.signature.verify(authority_public_key, message)
+    }
+
+    pub fn contains(&self, public_key: &PublicKey) -> bool {
+        self.public_keys.contains(public_key)
+    }
+}
+
+fn create_allowlist_message(version: u64, activation_epoch: Epoch, public_keys: &BTreeSet<PublicKey>) -> FixedHash {
+    validator_allowlist_hasher()
+        .chain(&version)
+        .chain(&activation_epoch)
+        .chain(public_keys)
+        .result()
+}
+
+/// The validator allowlist loaded from the configured file. The file is reloaded when it changes. A new list is held
+/// as pending until its activation epoch, so that all validators switch to it at the same epoch.
+#[derive(Debug)]
+pub struct ValidatorAllowlist {
+    config: ValidatorAllowlistConfig,
+    active: SignedValidatorAllowlist,
+    /// Lists that have been accepted but are not yet active, by version
+    pending: BTreeMap<u64, SignedValidatorAllowlist>,
+    last_modified: Option<SystemTime>,
+}
+
+impl ValidatorAllowlist {
+    /// Loads the allowlist file. `applied` is the list that was active when the node last stopped and `min_version` is
+    /// the highest version that the node has accepted. A file with a lower version is rejected. If the node has not
+    /// applied a list before, the list in the file is active from the start.
+    pub fn load(
+        config: ValidatorAllowlistConfig,
+        applied: Option<SignedValidatorAllowlist>,
+        min_version: u64,
+    ) -> Result<Self, ValidatorAllowlistError> {
+        if config.reload_interval.is_zero() {
+            return Err(ValidatorAllowlistError::InvalidReloadInterval);
+        }
+        let last_modified = get_last_modified(&config.path)?;
+        let allowlist = read_allowlist(&config.path, &config.authority_public_key)?;
+        if allowlist.version < min_version {
+            return Err(ValidatorAllowlistError::VersionRollback {
+                current: min_version,
+                new: allowlist.version,
+            });
+        }
+
+        // A list signed by a previous authority is replaced by the list in the file
+        let applied = applied.filter(|applied| applied.verify(&config.authority_public_key));
+        let mut pending = BTreeMap::new();
+        let active = match applied {
+            Some(applied) if applied.version < allowlist.version => {
+                pending.insert(allowlist.version, allowlist);
+                applied
+            },
+            Some(applied) if applied != allowlist => {
+                return Err(ValidatorAllowlistError::ConflictingVersion {
+                    version: allowlist.version,
+                });
+            },
+            _ => allowlist,
+        };
+
+        Ok(Self {
+            config,
+            active,
+            pending,
+            last_modified,
+        })
+    }
+
+    pub fn is_allowed(&self, public_key: &PublicKey) -> bool {
+        self.active.contains(public_key)
+    }
+
+    /// The list that is in effect for the current epoch
+    pub fn active(&self) -> &SignedValidatorAllowlist {
+        &self.active
+    }
+
+    pub fn version(&self) -> u64 {
+        self.active.version
+    }
+
+    /// The list with the highest version that has been accepted, which may be pending
+    pub fn latest(&self) -> &SignedValidatorAllowlist {
+        self.pending
+            .last_key_value()
+            .map_or(&self.active, |(_, allowlist)| allowlist)
+    }
+
+    pub fn highest_version(&self) -> u64 {
+        self.latest().version
+    }
+
+    pub fn len(&self) -> usize {
+        self.active.public_keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.public_keys.is_empty()
+    }
+
+    /// Activates the newest pending list whose activation epoch has been reached. Returns true if the active list
+    /// changed.
+    pub fn activate_for_epoch(&mut self, epoch: Epoch) -> bool {
+        let Some(version) = self
+            .pending
+            .iter()
+            .rev()
+            .find(|(_, allowlist)| allowlist.activation_epoch <= epoch)
+            .map(|(version, _)| *version)
+        else {
+            return false;
+        };
+
+        let still_pending = self.pending.split_off(&(version + 1));
+        let mut activated = std::mem::replace(&mut self.pending, still_pending);
+        self.active = activated.remove(&version).expect("version is in the pending lists");
+        true
+    }
+
+    /// Reloads the allowlist if the file has been modified since it was last loaded. Returns true if a new list was
+    /// accepted, in which case it is pending until its activation epoch. A new list must activate after
+    /// `current_epoch`, because validators that have already started that epoch cannot apply it deterministically.
+    /// If the new list is invalid, it is ignored and an error is returned.
+    pub fn reload_if_changed(&mut self, current_epoch: Epoch) -> Result<bool, ValidatorAllowlistError> {
+        let last_modified = get_last_modified(&self.config.path)?;
+        if last_modified.is_some() && last_modified == self.last_modified {
+            return Ok(false);
+        }
+
+        let allowlist = read_allowlist(&self.config.path, &self.config.authority_public_key)?;
+        // Record the modified time even if the list is rejected so that the same file is not reloaded repeatedly
+        self.last_modified = last_modified;
+        let latest = self.latest();
+        if allowlist.version < latest.version {
+            return Err(ValidatorAllowlistError::VersionRollback {
+                current: latest.version,
+                new: allowlist.version,
+            });
+        }
+        if allowlist.version == latest.version {
+            if *latest == allowlist {
+                return Ok(false);
+            }
+            return Err(ValidatorAllowlistError::ConflictingVersion {
+                version: allowlist.version,
+            });
+        }
+        if allowlist.activation_epoch <= current_epoch {
+            return Err(ValidatorAllowlistError::ActivationEpochPassed {
+                version: allowlist.version,
+                activation_epoch: allowlist.activation_epoch,
+                current_epoch,
+            });
+        }
+
+        self.pending.insert(allowlist.version, allowlist);
+        Ok(true)
+    }
+}
+
+fn get_last_modified(path: &Path) -> Result<Option<SystemTime>, ValidatorAllowlistError> {
+    let metadata = fs::metadata(path).map_err(|source| ValidatorAllowlistError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    // Not all platforms support modified times, in which case the file is reloaded at each interval
+    Ok(metadata.modified().ok())
+}
+
+fn read_allowlist(
+    path: &Path,
+    authority_public_key: &PublicKey,
+) -> Result<SignedValidatorAllowlist, ValidatorAllowlistError> {
+    let contents = fs::read_to_string(path).map_err(|source| ValidatorAllowlistError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let allowlist = serde_json::from_str::<SignedValidatorAllowlist>(&contents).map_err(|source| {
+        ValidatorAllowlistError::Malformed {
+            path: path.to_path_buf(),
+            source,
+        }
+    })?;
+    if !allowlist.verify(authority_public_key) {
+        return Err(ValidatorAllowlistError::InvalidSignature {
+            path: path.to_path_buf(),
+            version: allowlist.version,
+        });
+    }
+    Ok(allowlist)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ValidatorAllowlistError {
+    #[error("Failed to read validator allowlist {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("Validator allowlist {} is malformed: {source}", path.display())]
+    Malformed { path: PathBuf, source: serde_json::Error },
+    #[error("Validator allowlist {} (version {version}) is not signed by the allowlist authority", path.display())]
+    InvalidSignature { path: PathBuf, version: u64 },
+    #[error("Validator allowlist version {new} is lower than the accepted version {current}")]
+    VersionRollback { current: u64, new: u64 },
+    #[error("Validator allowlist version {version} differs from the list that was accepted with the same version")]
+    ConflictingVersion { version: u64 },
+    #[error(
+        "Validator allowlist version {version} activates in {activation_epoch}, which is not after the current \
+         {current_epoch}"
+    )]
+    ActivationEpochPassed {
+        version: u64,
+        activation_epoch: Epoch,
+        current_epoch: Epoch,
+    },
+    #[error("Validator allowlist reload interval must be greater than zero")]
+    InvalidReloadInterval,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tari_dan_common_types::crypto::create_key_pair_from_seed;
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    fn create_allowlist(
+        authority_secret: &PrivateKey,
+        version: u64,
+        activation_epoch: u64,
+        seeds: &[u8],
+    ) -> SignedValidatorAllowlist {
+        let public_keys = seeds.iter().map(|seed| create_key_pair_from_seed(*seed).1).collect();
+        SignedValidatorAllowlist::sign(authority_secret, version, Epoch(activation_epoch), public_keys)
+    }
+
+    fn write_allowlist(file: &NamedTempFile, allowlist: &SignedValidatorAllowlist) {
+        fs::write(file.path(), serde_json::to_string(allowlist).unwrap()).unwrap();
+    }
+
+    fn create_config(file: &NamedTempFile, authority_public_key: PublicKey) -> ValidatorAllowlistConfig {
+        ValidatorAllowlistConfig {
+            path: file.path().to_path_buf(),
+            authority_public_key,
+            reload_interval: Duration::from_secs(30),
+        }
+    }
+
+    /// Writes the list and clears the last modified time so that the reload does not depend on the file system's
+    /// timestamp resolution
+    fn replace_allowlist(
+        allowlist: &mut ValidatorAllowlist,
+        file: &NamedTempFile,
+        new_allowlist: &SignedValidatorAllowlist,
+    ) {
+        write_allowlist(file, new_allowlist);
+        allowlist.last_modified = None;
+    }
+
+    #[test]
+    fn it_verifies_the_authority_signature() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let allowlist = create_allowlist(&authority_secret, 1, 0, &[2, 3, 4]);
+        assert!(allowlist.verify(&authority_public));
+        assert!(allowlist.contains(&create_key_pair_from_seed(2).1));
+        assert!(!allowlist.contains(&create_key_pair_from_seed(5).1));
+
+        let (_, other_public) = create_key_pair_from_seed(9);
+        assert!(!allowlist.verify(&other_public));
+    }
+
+    #[test]
+    fn it_rejects_a_modified_list() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let mut allowlist = create_allowlist(&authority_secret, 1, 0, &[2, 3, 4]);
+        allowlist.public_keys.insert(create_key_pair_from_seed(5).1);
+        assert!(!allowlist.verify(&authority_public));
+
+        let mut allowlist = create_allowlist(&authority_secret, 1, 0, &[2, 3, 4]);
+        allowlist.version = 2;
+        assert!(!allowlist.verify(&authority_public));
+
+        let mut allowlist = create_allowlist(&authority_secret, 1, 0, &[2, 3, 4]);
+        allowlist.activation_epoch = Epoch(10);
+        assert!(!allowlist.verify(&authority_public));
+    }
+
+    #[test]
+    fn it_roundtrips_through_json() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let allowlist = create_allowlist(&authority_secret, 3, 5, &[2, 3]);
+        let json = serde_json::to_string(&allowlist).unwrap();
+        let decoded = serde_json::from_str::<SignedValidatorAllowlist>(&json).unwrap();
+        assert_eq!(decoded.version, 3);
+        assert_eq!(decoded.activation_epoch, Epoch(5));
+        assert!(decoded.verify(&authority_public));
+    }
+
+    #[test]
+    fn it_activates_a_new_list_at_its_activation_epoch() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        write_allowlist(&file, &create_allowlist(&authority_secret, 1, 0, &[2, 3]));
+        let mut allowlist = ValidatorAllowlist::load(create_config(&file, authority_public), None, 0).unwrap();
+
+        replace_allowlist(
+            &mut allowlist,
+            &file,
+            &create_allowlist(&authority_secret, 2, 5, &[2, 4]),
+        );
+        assert!(allowlist.reload_if_changed(Epoch(3)).unwrap());
+        assert_eq!(allowlist.version(), 1);
+        assert_eq!(allowlist.highest_version(), 2);
+        assert!(allowlist.is_allowed(&create_key_pair_from_seed(3).1));
+
+        assert!(!allowlist.activate_for_epoch(Epoch(4)));
+        assert_eq!(allowlist.version(), 1);
+
+        assert!(allowlist.activate_for_epoch(Epoch(5)));
+        assert_eq!(allowlist.version(), 2);
+        assert!(!allowlist.is_allowed(&create_key_pair_from_seed(3).1));
+        assert!(allowlist.is_allowed(&create_key_pair_from_seed(4).1));
+    }
+
+    #[test]
+    fn it_activates_the_newest_list_that_has_reached_its_activation_epoch() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        write_allowlist(&file, &create_allowlist(&authority_secret, 1, 0, &[2]));
+        let mut allowlist = ValidatorAllowlist::load(create_config(&file, authority_public), None, 0).unwrap();
+
+        for (version, activation_epoch) in [(2, 5), (3, 6), (4, 9)] {
+            replace_allowlist(
+                &mut allowlist,
+                &file,
+                &create_allowlist(&authority_secret, version, activation_epoch, &[2]),
+            );
+            assert!(allowlist.reload_if_changed(Epoch(1)).unwrap());
+        }
+
+        assert!(allowlist.activate_for_epoch(Epoch(7)));
+        assert_eq!(allowlist.version(), 3);
+        assert_eq!(allowlist.highest_version(), 4);
+    }
+
+    #[test]
+    fn it_rejects_a_list_that_activates_in_the_current_epoch() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        write_allowlist(&file, &create_allowlist(&authority_secret, 1, 0, &[2]));
+        let mut allowlist = ValidatorAllowlist::load(create_config(&file, authority_public), None, 0).unwrap();
+
+        replace_allowlist(&mut allowlist, &file, &create_allowlist(&authority_secret, 2, 3, &[3]));
+        let err = allowlist.reload_if_changed(Epoch(3)).unwrap_err();
+        assert!(matches!(err, ValidatorAllowlistError::ActivationEpochPassed { .. }));
+        assert_eq!(allowlist.highest_version(), 1);
+    }
+
+    #[test]
+    fn it_rejects_a_version_lower_than_the_accepted_version() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        write_allowlist(&file, &create_allowlist(&authority_secret, 2, 0, &[2]));
+
+        let err = ValidatorAllowlist::load(create_config(&file, authority_public.clone()), None, 3).unwrap_err();
+        assert!(matches!(err, ValidatorAllowlistError::VersionRollback {
+            current: 3,
+            new: 2
+        }));
+
+        let mut allowlist = ValidatorAllowlist::load(create_config(&file, authority_public), None, 2).unwrap();
+        replace_allowlist(&mut allowlist, &file, &create_allowlist(&authority_secret, 1, 5, &[3]));
+        let err = allowlist.reload_if_changed(Epoch(1)).unwrap_err();
+        assert!(matches!(err, ValidatorAllowlistError::VersionRollback {
+            current: 2,
+            new: 1
+        }));
+    }
+
+    #[test]
+    fn it_keeps_the_applied_list_active_until_a_newer_list_activates() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        let applied = create_allowlist(&authority_secret, 1, 0, &[2]);
+        write_allowlist(&file, &create_allowlist(&authority_secret, 2, 4, &[3]));
+
+        let mut allowlist = ValidatorAllowlist::load(create_config(&file, authority_public), Some(applied), 1).unwrap();
+        assert_eq!(allowlist.version(), 1);
+        assert!(allowlist.is_allowed(&create_key_pair_from_seed(2).1));
+
+        assert!(allowlist.activate_for_epoch(Epoch(4)));
+        assert!(allowlist.is_allowed(&create_key_pair_from_seed(3).1));
+    }
+
+    #[test]
+    fn it_rejects_a_zero_reload_interval() {
+        let (authority_secret, authority_public) = create_key_pair_from_seed(1);
+        let file = NamedTempFile::new().unwrap();
+        write_allowlist(&file, &create_allowlist(&authority_secret, 1, 0, &[2]));
+        let mut config = create_config(&file, authority_public);
+        config.reload_interval = Duration::ZERO;
+
+        let err = ValidatorAllowlist::load(config, None, 0).unwrap_err();
+        assert!(matches!(err, ValidatorAllowlistError::InvalidReloadInterval));
+    }
+}
@@ -44,6 +44,8 @@ pub enum EpochManagerError {
         actual: Option<String>,
         expected: Option<String>,
     },
+    #[error("Validator allowlist error: {0}")]
+    ValidatorAllowlistError(anyhow::Error),
 }
 
 impl EpochManagerError {
@@ -26,9 +26,11 @@ use async_trait::async_trait;
 use tari_common_types::types::{FixedHash, PublicKey};
 use tari_dan_common_types::{
     committee::{Committee, CommitteeInfo},
+    shard::Shard,
     Epoch,
     NodeAddressable,
     ShardGroup,
+    ShardGroupTransition,
     SubstateAddress,
 };
 use tari_dan_storage::global::models::ValidatorNode;
@@ -119,6 +121,49 @@ pub trait EpochManagerReader: Send + Sync {
         shards: ShardGroup,
     ) -> Result<HashMap<ShardGroup, Committee<Self::Addr>>, EpochManagerError>;
 
+    /// Returns true if the number of committees, and therefore the shard group boundaries, in the given epoch differs
+    /// from the previous epoch
+    async fn has_committee_layout_changed(&self, epoch: Epoch) -> Result<bool, EpochManagerError> {
+        if epoch.is_zero() {
+            return Ok(false);
+        }
+        let prev_num_committees = self.get_num_committees(epoch.saturating_sub(Epoch(1))).await?;
+        let num_committees = self.get_num_committees(epoch).await?;
+        Ok(prev_num_committees != num_committees)
+    }
+
+    /// Returns the shards of the local shard group in the given epoch that were not in the local shard group in the
+    /// previous epoch. The state of these shards must be synced from the previous epoch's committees before this
+    /// validator can participate in consensus for them.
+    async fn get_local_gained_shards(&self, epoch: Epoch) -> Result<Vec<Shard>, EpochManagerError> {
+        let shard_group = self.get_local_committee_info(epoch).await?.shard_group();
+        let prev_epoch = epoch.saturating_sub(Epoch(1));
+        if epoch.is_zero() || !self.is_this_validator_registered_for_epoch(prev_epoch).await? {
+            return Ok(shard_group.shard_iter().collect());
+        }
+        let prev_shard_group = self.get_local_committee_info(prev_epoch).await?.shard_group();
+        Ok(shard_group
+            .shard_iter()
+            .filter(|shard| !prev_shard_group.contains(shard))
+            .collect())
+    }
+
+    /// Returns how the local shard group in the given epoch was formed from the shard groups of the previous epoch,
+    /// i.e. whether it was split from a larger shard group or merged from smaller ones.
+    async fn get_local_shard_group_transition(&self, epoch: Epoch) -> Result<ShardGroupTransition, EpochManagerError> {
+        if epoch.is_zero() {
+            return Ok(ShardGroupTransition::Unchanged);
+        }
+        let local_info = self.get_local_committee_info(epoch).await?;
+        let prev_num_committees = self.get_num_committees(epoch.saturating_sub(Epoch(1))).await?;
+        Ok(ShardGroupTransition::compute(
+            local_info.num_preshards(),
+            prev_num_committees,
+            local_info.num_committees(),
+            local_info.shard_group(),
+        ))
+    }
+
     async fn get_local_committee(&self, epoch: Epoch) -> Result<Committee<Self::Addr>, EpochManagerError> {
         let validator = self.get_our_validator_node(epoch).await?;
         let committee = self.get_committee_for_substate(epoch, validator.shard_key).await?;
@@ -154,9 +199,10 @@ pub trait EpochManagerReader: Send + Sync {
         self.get_committee_for_substate(current_epoch, shard).await
     }
 
-    async fn get_local_threshold_for_epoch(&self, epoch: Epoch) -> Result<usize, EpochManagerError> {
-        let committee = self.get_local_committee_info(epoch).await?;
-        Ok(committee.quorum_threshold() as usize)
+    /// Returns the voting power required for the local committee to reach quorum in the given epoch
+    async fn get_local_threshold_for_epoch(&self, epoch: Epoch) -> Result<u64, EpochManagerError> {
+        let committee = self.get_local_committee(epoch).await?;
+        Ok(committee.quorum_voting_power())
     }
 
     async fn is_this_validator_registered_for_epoch(&self, epoch: Epoch) -> Result<bool, EpochManagerError> {
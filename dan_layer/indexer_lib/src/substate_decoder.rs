@@ -68,6 +68,14 @@ pub fn find_related_substates(substate: &Substate) -> Result<Vec<SubstateId>, In
             let substate_address = SubstateId::NonFungible(index.referenced_address().clone());
             Ok(vec![substate_address])
         },
+        SubstateValue::KeyValueEntry(entry) => {
+            // Look inside the entry value for substate references
+            let Some(value) = entry.value() else {
+                return Ok(vec![]);
+            };
+            let value = IndexedWellKnownTypes::from_value(value)?;
+            Ok(value.referenced_substates().collect())
+        },
         SubstateValue::Vault(vault) => Ok(vec![SubstateId::Resource(*vault.resource_address())]),
         SubstateValue::Resource(resource) => Ok(resource
             .auth_hook()
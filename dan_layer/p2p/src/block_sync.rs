@@ -5,7 +5,7 @@ use crate::{
     proto,
     proto::{
         consensus::{Block, QuorumCertificate},
-        rpc::{sync_blocks_response::SyncData, QuorumCertificates, SubstateUpdate, Transactions},
+        rpc::{sync_blocks_response::SyncData, QuorumCertificates, SubstateUpdate, SyncBlocksPageEnd, Transactions},
         transaction::Transaction,
     },
 };
@@ -45,4 +45,11 @@ impl proto::rpc::SyncBlocksResponse {
             _ => None,
         }
     }
+
+    pub fn page_end(&self) -> Option<&SyncBlocksPageEnd> {
+        match self.sync_data {
+            Some(SyncData::PageEnd(ref page_end)) => Some(page_end),
+            _ => None,
+        }
+    }
 }
@@ -27,9 +27,10 @@ use std::{
 
 use anyhow::anyhow;
 use tari_bor::{decode_exact, encode};
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{PrivateKey, PublicKey};
 use tari_consensus::messages::{
     ForeignProposalMessage,
+    ForeignProposalRequest,
     FullBlock,
     HotstuffMessage,
     MissingTransactionsRequest,
@@ -55,6 +56,7 @@ use tari_dan_storage::{
     consensus_models,
     consensus_models::{
         AbortReason,
+        AggregateValidatorSignature,
         BlockId,
         Command,
         Decision,
@@ -68,6 +70,7 @@ use tari_dan_storage::{
         QuorumCertificate,
         QuorumDecision,
         ResumeNodeAtom,
+        SignerBitmap,
         SubstateDestroyed,
         SubstatePledge,
         SubstatePledges,
@@ -93,6 +96,9 @@ impl From<&HotstuffMessage> for proto::consensus::HotStuffMessage {
             HotstuffMessage::ForeignProposal(msg) => {
                 proto::consensus::hot_stuff_message::Message::ForeignProposal(msg.into())
             },
+            HotstuffMessage::ForeignProposalRequest(msg) => {
+                proto::consensus::hot_stuff_message::Message::ForeignProposalRequest(msg.into())
+            },
             HotstuffMessage::Vote(msg) => proto::consensus::hot_stuff_message::Message::Vote(msg.into()),
             HotstuffMessage::MissingTransactionsRequest(msg) => {
                 proto::consensus::hot_stuff_message::Message::RequestMissingTransactions(msg.into())
@@ -122,6 +128,9 @@ impl TryFrom<proto::consensus::HotStuffMessage> for HotstuffMessage {
             proto::consensus::hot_stuff_message::Message::ForeignProposal(msg) => {
                 HotstuffMessage::ForeignProposal(msg.try_into()?)
             },
+            proto::consensus::hot_stuff_message::Message::ForeignProposalRequest(msg) => {
+                HotstuffMessage::ForeignProposalRequest(msg.try_into()?)
+            },
             proto::consensus::hot_stuff_message::Message::Vote(msg) => HotstuffMessage::Vote(msg.try_into()?),
             proto::consensus::hot_stuff_message::Message::RequestMissingTransactions(msg) => {
                 HotstuffMessage::MissingTransactionsRequest(msg.try_into()?)
@@ -173,6 +182,7 @@ impl From<&ProposalMessage> for proto::consensus::ProposalMessage {
         Self {
             block: Some((&value.block).into()),
             foreign_proposals: value.foreign_proposals.iter().map(Into::into).collect(),
+            transactions: value.transactions.iter().map(Into::into).collect(),
         }
     }
 }
@@ -188,6 +198,11 @@ impl TryFrom<proto::consensus::ProposalMessage> for ProposalMessage {
                 .into_iter()
                 .map(TryInto::try_into)
                 .collect::<Result<_, _>>()?,
+            transactions: value
+                .transactions
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -424,6 +439,32 @@ impl TryFrom<proto::consensus::MissingTransactionsRequest> for MissingTransactio
         })
     }
 }
+//---------------------------------- ForeignProposalRequest --------------------------------------------//
+
+impl From<&ForeignProposalRequest> for proto::consensus::ForeignProposalRequest {
+    fn from(msg: &ForeignProposalRequest) -> Self {
+        Self {
+            epoch: msg.epoch.as_u64(),
+            transaction_ids: msg.transactions.iter().map(|tx_id| tx_id.as_bytes().to_vec()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::consensus::ForeignProposalRequest> for ForeignProposalRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::ForeignProposalRequest) -> Result<Self, Self::Error> {
+        Ok(ForeignProposalRequest {
+            epoch: Epoch(value.epoch),
+            transactions: value
+                .transaction_ids
+                .into_iter()
+                .map(|tx_id| tx_id.try_into())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 //---------------------------------- MissingTransactionsResponse --------------------------------------------//
 
 impl From<&MissingTransactionsResponse> for proto::consensus::MissingTransactionsResponse {
@@ -817,6 +858,10 @@ impl From<AbortReason> for DecisionReason {
             AbortReason::ForeignShardGroupDecidedToAbort => Self::ForeignShardGroupDecidedToAbort,
             AbortReason::FeesNotPaid => Self::FeesNotPaid,
             AbortReason::EarlyAbort => Self::EarlyAbort,
+            AbortReason::LockLeaseExpired => Self::LockLeaseExpired,
+            AbortReason::CrossShardDeadlock => Self::CrossShardDeadlock,
+            AbortReason::CommitteeLayoutChanged => Self::CommitteeLayoutChanged,
+            AbortReason::InputRentExpired => Self::InputRentExpired,
         }
     }
 }
@@ -838,6 +883,10 @@ impl From<DecisionReason> for AbortReason {
             DecisionReason::ForeignShardGroupDecidedToAbort => Self::ForeignShardGroupDecidedToAbort,
             DecisionReason::FeesNotPaid => Self::FeesNotPaid,
             DecisionReason::EarlyAbort => Self::EarlyAbort,
+            DecisionReason::LockLeaseExpired => Self::LockLeaseExpired,
+            DecisionReason::CrossShardDeadlock => Self::CrossShardDeadlock,
+            DecisionReason::CommitteeLayoutChanged => Self::CommitteeLayoutChanged,
+            DecisionReason::InputRentExpired => Self::InputRentExpired,
         }
     }
 }
@@ -882,7 +931,8 @@ impl From<&QuorumCertificate> for proto::consensus::QuorumCertificate {
             block_height: source.block_height().as_u64(),
             epoch: source.epoch().as_u64(),
             shard_group: source.shard_group().encode_as_u32(),
-            signatures: source.signatures().iter().map(Into::into).collect(),
+            signers: source.signers().as_bytes().to_vec(),
+            signature: Some(source.aggregate_signature().into()),
             leaf_hashes: source.leaf_hashes().iter().map(|h| h.to_vec()).collect(),
             decision: i32::from(source.decision().as_u8()),
         }
@@ -900,11 +950,11 @@ impl TryFrom<proto::consensus::QuorumCertificate> for QuorumCertificate {
             NodeHeight(value.block_height),
             Epoch(value.epoch),
             shard_group,
+            SignerBitmap::from_bytes(value.signers),
             value
-                .signatures
-                .into_iter()
-                .map(TryInto::try_into)
-                .collect::<Result<_, _>>()?,
+                .signature
+                .ok_or_else(|| anyhow!("QC signature is missing"))?
+                .try_into()?,
             value
                 .leaf_hashes
                 .into_iter()
@@ -916,6 +966,31 @@ impl TryFrom<proto::consensus::QuorumCertificate> for QuorumCertificate {
     }
 }
 
+// -------------------------------- AggregateValidatorSignature -------------------------------- //
+
+impl From<&AggregateValidatorSignature> for proto::consensus::AggregateValidatorSignature {
+    fn from(value: &AggregateValidatorSignature) -> Self {
+        Self {
+            public_nonces: value.public_nonces().iter().map(|n| n.as_bytes().to_vec()).collect(),
+            signature: value.signature().as_bytes().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::consensus::AggregateValidatorSignature> for AggregateValidatorSignature {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::AggregateValidatorSignature) -> Result<Self, Self::Error> {
+        let public_nonces = value
+            .public_nonces
+            .iter()
+            .map(|n| PublicKey::from_canonical_bytes(n).map_err(anyhow::Error::msg))
+            .collect::<Result<_, _>>()?;
+        let signature = PrivateKey::from_canonical_bytes(&value.signature).map_err(anyhow::Error::msg)?;
+        Ok(Self::new(public_nonces, signature))
+    }
+}
+
 // -------------------------------- ValidatorMetadata -------------------------------- //
 
 impl From<ValidatorMetadata> for proto::consensus::ValidatorMetadata {
@@ -43,7 +43,7 @@ use tari_template_lib::{
     },
     prelude::AccessRules,
 };
-use tari_transaction::{Transaction, UnsignedTransaction};
+use tari_transaction::{IdempotencyKey, Transaction, UnsignedTransaction};
 
 use crate::{
     proto::{
@@ -142,12 +142,19 @@ impl TryFrom<proto::transaction::UnsignedTransaction> for UnsignedTransaction {
             .collect::<Result<_, _>>()?;
         let min_epoch = request.min_epoch.map(|epoch| Epoch(epoch.epoch));
         let max_epoch = request.max_epoch.map(|epoch| Epoch(epoch.epoch));
+        let idempotency_key = Some(request.idempotency_key)
+            .filter(|k| !k.is_empty())
+            .map(|k| IdempotencyKey::try_from(k.as_slice()))
+            .transpose()
+            .map_err(|_| anyhow!("Invalid idempotency key length"))?;
         Ok(Self {
             fee_instructions,
             instructions,
             inputs,
             min_epoch,
             max_epoch,
+            idempotency_key,
+            num_reserved_outputs: request.num_reserved_outputs,
         })
     }
 }
@@ -170,6 +177,11 @@ impl From<&UnsignedTransaction> for proto::transaction::UnsignedTransaction {
             inputs,
             min_epoch,
             max_epoch,
+            idempotency_key: transaction
+                .idempotency_key()
+                .map(|k| k.as_bytes().to_vec())
+                .unwrap_or_default(),
+            num_reserved_outputs: transaction.num_reserved_outputs(),
         }
     }
 }
@@ -253,6 +265,11 @@ impl TryFrom<proto::transaction::Instruction> for Instruction {
                     min_amount: Amount::new(request.min_amount),
                 }
             },
+            InstructionType::CreateProofFromBucket => Instruction::CreateProofFromBucket { key: request.key },
+            InstructionType::PayRent => Instruction::PayRent {
+                substate_id: SubstateId::from_bytes(&request.pay_rent_substate_id)?,
+                epochs: request.pay_rent_epochs,
+            },
         };
 
         Ok(instruction)
@@ -334,6 +351,15 @@ impl From<Instruction> for proto::transaction::Instruction {
                 result.resource_address = resource_address.as_bytes().to_vec();
                 result.min_amount = min_amount.0
             },
+            Instruction::CreateProofFromBucket { key } => {
+                result.instruction_type = InstructionType::CreateProofFromBucket as i32;
+                result.key = key;
+            },
+            Instruction::PayRent { substate_id, epochs } => {
+                result.instruction_type = InstructionType::PayRent as i32;
+                result.pay_rent_substate_id = substate_id.to_bytes();
+                result.pay_rent_epochs = epochs;
+            },
         }
         result
     }
@@ -548,6 +574,7 @@ impl TryFrom<proto::transaction::ConfidentialStatement> for ConfidentialStatemen
                 .map_err(|len| anyhow!("Invalid length ({len}) of encrypted_value bytes"))?,
             minimum_value_promise: val.minimum_value_promise,
             viewable_balance_proof: val.viewable_balance_proof.map(TryInto::try_into).transpose()?,
+            encrypted_memo: Some(val.encrypted_memo).filter(|v| !v.is_empty()),
         })
     }
 }
@@ -560,6 +587,7 @@ impl From<ConfidentialStatement> for proto::transaction::ConfidentialStatement {
             encrypted_value: val.encrypted_data.as_ref().to_vec(),
             minimum_value_promise: val.minimum_value_promise,
             viewable_balance_proof: val.viewable_balance_proof.map(Into::into),
+            encrypted_memo: val.encrypted_memo.unwrap_or_default(),
         }
     }
 }
@@ -257,7 +257,8 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress>
         self.state_store.with_read_tx(|tx| {
             let mut store = ShardScopedTreeStoreReader::new(tx, shard);
             let state_tree = SpreadPrefixStateTree::new(&mut store);
-            let root_hash = state_tree.get_root_hash(version)?;
+            // Walk the synced tree to ensure that every node is present and hashes correctly, not just the root
+            let root_hash = state_tree.check_consistency(version)?;
             Ok(root_hash)
         })
     }
@@ -327,15 +328,54 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress>
         Ok(committees)
     }
 
-    fn validate_checkpoint(&self, checkpoint: &EpochCheckpoint) -> Result<(), CommsRpcConsensusSyncError> {
-        // TODO: validate checkpoint
+    /// Saves the checkpoint if we do not have a checkpoint for the epoch yet. If we already have one, it belongs to the
+    /// shard group that we were in during that epoch and is kept.
+    fn save_checkpoint_if_required(&self, checkpoint: &EpochCheckpoint) -> Result<(), CommsRpcConsensusSyncError> {
+        self.state_store.with_write_tx(|tx| {
+            if EpochCheckpoint::get(&**tx, checkpoint.block().epoch())
+                .optional()?
+                .is_none()
+            {
+                checkpoint.save(tx)?;
+            }
+            Ok(())
+        })
+    }
 
-        if !checkpoint.block().is_epoch_end() {
+    /// Validates that the checkpoint was created by the committee of the shard group at the end of the epoch. The block
+    /// ID commits to the state merkle root and VRF proof of the block, so a QC for the block signed by the committee
+    /// certifies the values that the genesis block of the next epoch is derived from.
+    fn validate_checkpoint(
+        &self,
+        checkpoint: &EpochCheckpoint,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        committee: &Committee<PeerAddress>,
+    ) -> Result<(), CommsRpcConsensusSyncError> {
+        let block = checkpoint.block();
+        if !block.is_epoch_end() {
             return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow!(
                 "Checkpoint block is not an Epoch End block"
             )));
         }
 
+        if block.epoch() != epoch || block.shard_group() != shard_group {
+            return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow!(
+                "Checkpoint block {block} is not for {shard_group} in epoch {epoch}"
+            )));
+        }
+
+        let qc = checkpoint.block_qc().ok_or_else(|| {
+            CommsRpcConsensusSyncError::InvalidResponse(anyhow!("Checkpoint does not contain a QC for block {block}"))
+        })?;
+        if qc.epoch() != epoch || qc.shard_group() != shard_group || !qc.decision().is_accept() {
+            return Err(CommsRpcConsensusSyncError::InvalidResponse(anyhow!(
+                "Checkpoint QC {qc} does not accept block {block}"
+            )));
+        }
+        qc.verify(committee)
+            .map_err(|err| CommsRpcConsensusSyncError::InvalidResponse(anyhow!("Checkpoint QC is invalid: {err}")))?;
+
         // Sanity check that the calculated merkle root matches the provided shard roots
         // Note this allows us to use each of the provided shard MRs assuming we trust the provided block that has been
         // signed by a BFT majority of registered VNs
@@ -380,17 +420,31 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress> + Send + Sync + 'static
 
     async fn sync(&mut self) -> Result<(), Self::Error> {
         let current_epoch = self.epoch_manager.current_epoch().await?;
+        let local_shard_group = self
+            .epoch_manager
+            .get_local_committee_info(current_epoch)
+            .await?
+            .shard_group();
+        let prev_epoch = current_epoch.saturating_sub(Epoch(1));
         let prev_epoch_committees = self.get_sync_committees(current_epoch).await?;
         let our_vn = self.epoch_manager.get_our_validator_node(current_epoch).await?;
 
         let mut last_error = None;
         // Sync data from each committee in range of the committee we're joining.
-        // NOTE: we don't have to worry about substates in address range because shard boundaries are fixed.
+        // NOTE: we don't have to worry about substates in address range because shard boundaries are fixed. If the
+        // committee layout changed, the shard groups of the previous epoch may only partially overlap ours, so only our
+        // shards are synced.
         for (shard_group, mut committee) in prev_epoch_committees {
+            // Checkpoint QCs are verified against the committee in committee order
+            let source_committee = committee.clone();
             committee.shuffle();
-            for shard in shard_group.shard_iter() {
+            let mut synced_checkpoint = None;
+            for shard in shard_group
+                .shard_iter()
+                .filter(|shard| local_shard_group.contains(shard))
+            {
                 let mut remaining_members = committee.len();
-                info!(target: LOG_TARGET, "🛜Syncing state for {shard} and {}", current_epoch.saturating_sub(Epoch(1)));
+                info!(target: LOG_TARGET, "🛜Syncing state for {shard} and {prev_epoch}");
                 for (addr, public_key) in &committee {
                     remaining_members = remaining_members.saturating_sub(1);
                     if our_vn.public_key == *public_key {
@@ -436,8 +490,19 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress> + Send + Sync + 'static
                     };
                     info!(target: LOG_TARGET, "🛜 Checkpoint: {checkpoint}");
 
-                    self.validate_checkpoint(&checkpoint)?;
-                    self.state_store.with_write_tx(|tx| checkpoint.save(tx))?;
+                    if let Err(err) = self.validate_checkpoint(&checkpoint, prev_epoch, shard_group, &source_committee)
+                    {
+                        warn!(
+                            target: LOG_TARGET,
+                            "⚠️Invalid checkpoint from {addr}: {err}. Attempting another peer if available"
+                        );
+                        if remaining_members == 0 {
+                            return Err(err);
+                        }
+                        last_error = Some(err);
+                        continue;
+                    }
+                    self.save_checkpoint_if_required(&checkpoint)?;
 
                     match self.start_state_sync(&mut client, shard, &checkpoint).await {
                         Ok(current_version) => {
@@ -462,6 +527,7 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress> + Send + Sync + 'static
                                 continue;
                             }
 
+                            synced_checkpoint = Some(checkpoint);
                             info!(target: LOG_TARGET, "🛜Synced state for {shard} to v{} with root {state_root}", current_version.unwrap_or(0));
                         },
                         Err(err) => {
@@ -480,6 +546,13 @@ where TConsensusSpec: ConsensusSpec<Addr = PeerAddress> + Send + Sync + 'static
                     break;
                 }
             }
+
+            // The state of every shard that the shard group hands over to us has been synced and verified against its
+            // checkpoint, which is kept to derive the genesis block of our shard group
+            if let Some(checkpoint) = synced_checkpoint {
+                self.state_store.with_write_tx(|tx| checkpoint.save_handover(tx))?;
+                info!(target: LOG_TARGET, "🔀 State handover from {shard_group} in {prev_epoch} complete");
+            }
         }
 
         if let Some(err) = last_error {
@@ -0,0 +1,131 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Column families used by the state store. Each table is stored in its own column family. Tables that are queried by
+//! something other than their primary key have additional index column families whose values are primary keys.
+
+/// Per-table insertion counters, used to order records in the same way as SQLite's auto-increment ids
+pub const SEQUENCES: &str = "sequences";
+
+pub const LAST_SENT_VOTE: &str = "last_sent_vote";
+pub const LAST_VOTED: &str = "last_voted";
+pub const LAST_EXECUTED: &str = "last_executed";
+pub const LAST_PROPOSED: &str = "last_proposed";
+pub const LOCKED_BLOCK: &str = "locked_block";
+pub const LEAF_BLOCKS: &str = "leaf_blocks";
+pub const HIGH_QCS: &str = "high_qcs";
+
+pub const BLOCKS: &str = "blocks";
+pub const BLOCKS_BY_PARENT: &str = "blocks_by_parent";
+pub const BLOCKS_BY_HEIGHT: &str = "blocks_by_height";
+pub const COMMITTED_BLOCKS: &str = "committed_blocks";
+pub const DIAGNOSTIC_DELETED_BLOCKS: &str = "diagnostic_deleted_blocks";
+pub const PARKED_BLOCKS: &str = "parked_blocks";
+pub const MISSING_TRANSACTIONS: &str = "missing_transactions";
+pub const MISSING_TRANSACTIONS_BY_TRANSACTION: &str = "missing_transactions_by_transaction";
+pub const BLOCK_DIFFS: &str = "block_diffs";
+pub const BLOCK_DIFFS_BY_SUBSTATE: &str = "block_diffs_by_substate";
+
+pub const QUORUM_CERTIFICATES: &str = "quorum_certificates";
+pub const QUORUM_CERTIFICATES_BY_BLOCK: &str = "quorum_certificates_by_block";
+pub const VOTES: &str = "votes";
+pub const VOTES_BY_SENDER: &str = "votes_by_sender";
+
+pub const FOREIGN_PROPOSALS: &str = "foreign_proposals";
+pub const FOREIGN_SEND_COUNTERS: &str = "foreign_send_counters";
+pub const FOREIGN_RECEIVE_COUNTERS: &str = "foreign_receive_counters";
+pub const FOREIGN_PARKED_BLOCKS: &str = "foreign_parked_blocks";
+pub const FOREIGN_MISSING_TRANSACTIONS: &str = "foreign_missing_transactions";
+pub const FOREIGN_MISSING_TRANSACTIONS_BY_TRANSACTION: &str = "foreign_missing_transactions_by_transaction";
+pub const FOREIGN_SUBSTATE_PLEDGES: &str = "foreign_substate_pledges";
+
+pub const TRANSACTIONS: &str = "transactions";
+pub const TRANSACTIONS_BY_CREATED_AT: &str = "transactions_by_created_at";
+pub const TRANSACTION_IDEMPOTENCY_KEYS: &str = "transaction_idempotency_keys";
+pub const TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK: &str = "transaction_idempotency_keys_by_block";
+pub const TRANSACTION_EXECUTIONS: &str = "transaction_executions";
+pub const TRANSACTION_EXECUTIONS_BY_BLOCK: &str = "transaction_executions_by_block";
+pub const TRANSACTION_POOL: &str = "transaction_pool";
+pub const TRANSACTION_POOL_STATE_UPDATES: &str = "transaction_pool_state_updates";
+pub const TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK: &str = "transaction_pool_state_updates_by_block";
+pub const LOCK_CONFLICTS: &str = "lock_conflicts";
+
+pub const SUBSTATES: &str = "substates";
+pub const SUBSTATES_BY_ID: &str = "substates_by_id";
+pub const SUBSTATES_BY_SEQ: &str = "substates_by_seq";
+pub const SUBSTATES_BY_TRANSACTION: &str = "substates_by_transaction";
+pub const SUBSTATE_LOCKS: &str = "substate_locks";
+pub const SUBSTATE_LOCKS_BY_TRANSACTION: &str = "substate_locks_by_transaction";
+pub const SUBSTATE_LOCKS_BY_BLOCK: &str = "substate_locks_by_block";
+pub const STATE_TRANSITIONS: &str = "state_transitions";
+pub const PENDING_STATE_TREE_DIFFS: &str = "pending_state_tree_diffs";
+pub const STATE_TREE: &str = "state_tree";
+pub const STATE_TREE_STALE: &str = "state_tree_stale";
+pub const STATE_TREE_SHARD_VERSIONS: &str = "state_tree_shard_versions";
+
+pub const EPOCH_CHECKPOINTS: &str = "epoch_checkpoints";
+pub const EPOCH_HANDOVER_CHECKPOINTS: &str = "epoch_handover_checkpoints";
+pub const BURNT_UTXOS: &str = "burnt_utxos";
+pub const VALIDATOR_EPOCH_STATS: &str = "validator_epoch_stats";
+pub const SUSPENDED_NODES: &str = "suspended_nodes";
+pub const DIAGNOSTICS_NO_VOTES: &str = "diagnostics_no_votes";
+
+pub const ALL: &[&str] = &[
+    SEQUENCES,
+    LAST_SENT_VOTE,
+    LAST_VOTED,
+    LAST_EXECUTED,
+    LAST_PROPOSED,
+    LOCKED_BLOCK,
+    LEAF_BLOCKS,
+    HIGH_QCS,
+    BLOCKS,
+    BLOCKS_BY_PARENT,
+    BLOCKS_BY_HEIGHT,
+    COMMITTED_BLOCKS,
+    DIAGNOSTIC_DELETED_BLOCKS,
+    PARKED_BLOCKS,
+    MISSING_TRANSACTIONS,
+    MISSING_TRANSACTIONS_BY_TRANSACTION,
+    BLOCK_DIFFS,
+    BLOCK_DIFFS_BY_SUBSTATE,
+    QUORUM_CERTIFICATES,
+    QUORUM_CERTIFICATES_BY_BLOCK,
+    VOTES,
+    VOTES_BY_SENDER,
+    FOREIGN_PROPOSALS,
+    FOREIGN_SEND_COUNTERS,
+    FOREIGN_RECEIVE_COUNTERS,
+    FOREIGN_PARKED_BLOCKS,
+    FOREIGN_MISSING_TRANSACTIONS,
+    FOREIGN_MISSING_TRANSACTIONS_BY_TRANSACTION,
+    FOREIGN_SUBSTATE_PLEDGES,
+    TRANSACTIONS,
+    TRANSACTIONS_BY_CREATED_AT,
+    TRANSACTION_IDEMPOTENCY_KEYS,
+    TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK,
+    TRANSACTION_EXECUTIONS,
+    TRANSACTION_EXECUTIONS_BY_BLOCK,
+    TRANSACTION_POOL,
+    TRANSACTION_POOL_STATE_UPDATES,
+    TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK,
+    LOCK_CONFLICTS,
+    SUBSTATES,
+    SUBSTATES_BY_ID,
+    SUBSTATES_BY_SEQ,
+    SUBSTATES_BY_TRANSACTION,
+    SUBSTATE_LOCKS,
+    SUBSTATE_LOCKS_BY_TRANSACTION,
+    SUBSTATE_LOCKS_BY_BLOCK,
+    STATE_TRANSITIONS,
+    PENDING_STATE_TREE_DIFFS,
+    STATE_TREE,
+    STATE_TREE_STALE,
+    STATE_TREE_SHARD_VERSIONS,
+    EPOCH_CHECKPOINTS,
+    EPOCH_HANDOVER_CHECKPOINTS,
+    BURNT_UTXOS,
+    VALIDATOR_EPOCH_STATS,
+    SUSPENDED_NODES,
+    DIAGNOSTICS_NO_VOTES,
+];
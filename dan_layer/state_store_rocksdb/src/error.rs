@@ -0,0 +1,49 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_storage::StorageError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RocksDbStorageError {
+    #[error("Could not open database: {source}")]
+    OpenError { source: rocksdb::Error },
+    #[error("RocksDB error during operation {operation}: {source}")]
+    RocksDbError {
+        source: rocksdb::Error,
+        operation: &'static str,
+    },
+    #[error("Column family {name} does not exist")]
+    MissingColumnFamily { name: &'static str },
+    #[error("Malformed DB data in {operation}: {details}")]
+    MalformedDbData { operation: &'static str, details: String },
+    #[error("Database inconsistency for operation {operation}: {details}")]
+    DbInconsistency { operation: &'static str, details: String },
+    #[error("[{operation}] Not all {items} were found: {details}")]
+    NotAllItemsFound {
+        items: &'static str,
+        operation: &'static str,
+        details: String,
+    },
+}
+
+impl From<RocksDbStorageError> for StorageError {
+    fn from(source: RocksDbStorageError) -> Self {
+        match source {
+            RocksDbStorageError::OpenError { .. } => StorageError::ConnectionError {
+                reason: source.to_string(),
+            },
+            RocksDbStorageError::RocksDbError { .. } => StorageError::QueryError {
+                reason: source.to_string(),
+            },
+            RocksDbStorageError::MalformedDbData { operation, details } => StorageError::DecodingError {
+                operation,
+                item: "record",
+                details,
+            },
+            other => StorageError::General {
+                details: other.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Key encoding. Integers are encoded as big-endian so that the lexicographic ordering of keys matches their numeric
+//! ordering. Variable length components (substate ids) are length-prefixed so that a key prefix never matches a
+//! different, longer component.
+
+use tari_engine_types::substate::SubstateId;
+
+#[derive(Debug, Clone, Default)]
+pub struct KeyBuilder(Vec<u8>);
+
+impl KeyBuilder {
+    pub fn new() -> Self {
+        Self(Vec::with_capacity(64))
+    }
+
+    pub fn bytes<T: AsRef<[u8]>>(mut self, bytes: T) -> Self {
+        self.0.extend_from_slice(bytes.as_ref());
+        self
+    }
+
+    pub fn u64(mut self, n: u64) -> Self {
+        self.0.extend_from_slice(&n.to_be_bytes());
+        self
+    }
+
+    pub fn u32(mut self, n: u32) -> Self {
+        self.0.extend_from_slice(&n.to_be_bytes());
+        self
+    }
+
+    pub fn substate_id(mut self, substate_id: &SubstateId) -> Self {
+        let s = substate_id.to_string();
+        self.0.extend_from_slice(&(s.len() as u32).to_be_bytes());
+        self.0.extend_from_slice(s.as_bytes());
+        self
+    }
+
+    pub fn build(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+/// Returns the smallest key that is greater than all keys that start with the given prefix, or None if no such key
+/// exists (i.e. the prefix is empty or all 0xff bytes).
+pub fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+    while let Some(last) = successor.pop() {
+        if last < u8::MAX {
+            successor.push(last + 1);
+            return Some(successor);
+        }
+    }
+    None
+}
+
+pub fn decode_u64(bytes: &[u8]) -> Option<u64> {
+    let bytes = bytes.get(..8)?;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    Some(u64::from_be_bytes(buf))
+}
@@ -0,0 +1,15 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod columns;
+mod error;
+mod keys;
+mod models;
+mod reader;
+mod serialization;
+mod store;
+mod writer;
+
+pub use reader::RocksDbStateStoreReadTransaction;
+pub use store::RocksDbStateStore;
+pub use writer::RocksDbStateStoreWriteTransaction;
@@ -0,0 +1,441 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Records as they are stored in the database. Records are JSON encoded and contain the columns of the equivalent
+//! SQLite table that are needed to load the consensus models.
+
+use std::collections::{BTreeSet, HashMap};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_dan_common_types::{
+    shard::Shard,
+    Epoch,
+    NodeHeight,
+    ShardGroup,
+    SubstateAddress,
+    SubstateLockType,
+    VersionedSubstateId,
+};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockHeader,
+        BlockId,
+        BlockPledge,
+        BlockTransactionExecution,
+        BurntUtxo,
+        Command,
+        Decision,
+        Evidence,
+        ForeignProposal,
+        ForeignProposalStatus,
+        LeaderFee,
+        QcId,
+        QuorumCertificate,
+        QuorumDecision,
+        SubstateChange,
+        SubstateRecord,
+        TransactionPoolRecord,
+        TransactionRecord,
+        ValidatorSignature,
+        VersionedSubstateIdLockIntent,
+    },
+    StorageError,
+};
+use tari_engine_types::{
+    commit_result::{ExecuteResult, RejectReason},
+    substate::{Substate, SubstateId, SubstateValue},
+};
+use tari_state_tree::{Hash, StateHashTreeDiff, TreeNode, Version};
+use tari_transaction::{Transaction, TransactionId};
+use time::PrimitiveDateTime;
+
+use crate::serialization::parse_from_string;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastSentVoteRow {
+    pub epoch: Epoch,
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub decision: QuorumDecision,
+    pub signature: ValidatorSignature,
+}
+
+/// Used for the last voted, last executed, last proposed, leaf and locked block bookkeeping records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRefRow {
+    pub block_id: BlockId,
+    pub height: NodeHeight,
+    pub epoch: Epoch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighQcRow {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub epoch: Epoch,
+    pub qc_id: QcId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountersRow {
+    pub counters: HashMap<Shard, u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockRow {
+    pub header: BlockHeader,
+    pub commands: BTreeSet<Command>,
+    pub is_committed: bool,
+    pub is_justified: bool,
+    pub block_time: Option<u64>,
+    pub created_at: PrimitiveDateTime,
+    pub seq: u64,
+}
+
+impl BlockRow {
+    pub fn into_block(self, justify: QuorumCertificate) -> Block {
+        let header = self.header;
+        Block::load(
+            *header.id(),
+            header.network(),
+            *header.parent(),
+            justify,
+            header.height(),
+            header.epoch(),
+            header.shard_group(),
+            header.proposed_by().clone(),
+            *header.state_merkle_root(),
+            self.commands,
+            *header.command_merkle_root(),
+            header.total_leader_fee(),
+            header.is_dummy(),
+            self.is_justified,
+            self.is_committed,
+            header.foreign_indexes().clone(),
+            header.signature().cloned(),
+            self.created_at,
+            self.block_time,
+            header.timestamp(),
+            header.base_layer_block_height(),
+            *header.base_layer_block_hash(),
+            header.extra_data().clone(),
+        )
+    }
+
+    pub fn is_self_parented(&self) -> bool {
+        self.header.id() == self.header.parent()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParkedBlockRow {
+    pub block: Block,
+    pub foreign_proposals: Vec<ForeignProposal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockDiffRow {
+    pub block_id: BlockId,
+    pub transaction_id: TransactionId,
+    pub id: VersionedSubstateId,
+    pub shard: Shard,
+    /// The substate value for UP changes, None for DOWN changes
+    pub substate: Option<Substate>,
+}
+
+impl BlockDiffRow {
+    pub fn from_change(block_id: BlockId, change: &SubstateChange) -> Self {
+        Self {
+            block_id,
+            transaction_id: change.transaction_id(),
+            id: change.versioned_substate_id().clone(),
+            shard: change.shard(),
+            substate: change.substate().cloned(),
+        }
+    }
+
+    pub fn into_change(self) -> SubstateChange {
+        match self.substate {
+            Some(substate) => SubstateChange::Up {
+                id: self.id,
+                shard: self.shard,
+                transaction_id: self.transaction_id,
+                substate,
+            },
+            None => SubstateChange::Down {
+                id: self.id,
+                shard: self.shard,
+                transaction_id: self.transaction_id,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignProposalRow {
+    pub block: Block,
+    pub block_pledge: BlockPledge,
+    pub justify_qc_id: QcId,
+    pub proposed_in_block: Option<BlockId>,
+    pub proposed_in_block_height: Option<NodeHeight>,
+    pub status: ForeignProposalStatus,
+}
+
+impl ForeignProposalRow {
+    pub fn into_proposal(self, justify_qc: QuorumCertificate) -> ForeignProposal {
+        ForeignProposal {
+            block: self.block,
+            block_pledge: self.block_pledge,
+            justify_qc,
+            proposed_by_block: self.proposed_in_block,
+            status: self.status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionRow {
+    pub transaction: Transaction,
+    pub result: Option<ExecuteResult>,
+    pub resolved_inputs: Option<Vec<VersionedSubstateIdLockIntent>>,
+    pub resulting_outputs: Option<Vec<VersionedSubstateIdLockIntent>>,
+    pub final_decision: Option<Decision>,
+    pub abort_details: Option<RejectReason>,
+    pub outcome: Option<String>,
+    pub finalized_at: Option<PrimitiveDateTime>,
+    pub created_at: PrimitiveDateTime,
+    pub seq: u64,
+}
+
+impl TransactionRow {
+    pub fn into_record(self) -> TransactionRecord {
+        let finalized_time = self
+            .finalized_at
+            .map(|t| t.assume_utc() - self.created_at.assume_utc())
+            .map(|d| d.try_into().unwrap_or_default());
+
+        TransactionRecord::load(
+            self.transaction,
+            self.result,
+            self.resolved_inputs,
+            self.final_decision,
+            finalized_time,
+            self.resulting_outputs,
+            self.abort_details,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionIdempotencyKeyRow {
+    pub transaction_id: TransactionId,
+    pub block_id: BlockId,
+    pub is_committed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionExecutionRow {
+    pub block_id: BlockId,
+    pub transaction_id: TransactionId,
+    pub result: ExecuteResult,
+    pub resolved_inputs: Vec<VersionedSubstateIdLockIntent>,
+    pub resulting_outputs: Vec<VersionedSubstateIdLockIntent>,
+    pub abort_reason: Option<RejectReason>,
+}
+
+impl From<TransactionExecutionRow> for BlockTransactionExecution {
+    fn from(row: TransactionExecutionRow) -> Self {
+        BlockTransactionExecution::new(
+            row.block_id,
+            row.transaction_id,
+            row.result,
+            row.resolved_inputs,
+            row.resulting_outputs,
+            row.abort_reason,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPoolRow {
+    pub transaction_id: TransactionId,
+    pub original_decision: Decision,
+    pub local_decision: Option<Decision>,
+    pub remote_decision: Option<Decision>,
+    pub evidence: Evidence,
+    pub transaction_fee: u64,
+    pub leader_fee: Option<LeaderFee>,
+    pub stage: String,
+    /// The stage of the last pending update. This is only used for counting and is not given to
+    /// TransactionPoolRecord::load.
+    pub pending_stage: Option<String>,
+    pub is_ready: bool,
+    pub confirm_stage: Option<String>,
+    pub updated_at: PrimitiveDateTime,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl TransactionPoolRow {
+    pub fn try_convert(
+        self,
+        update: Option<TransactionPoolStateUpdateRow>,
+    ) -> Result<TransactionPoolRecord, StorageError> {
+        let mut evidence = self.evidence;
+        let mut pending_stage = None;
+        let mut local_decision = self.local_decision;
+        let mut is_ready = self.is_ready;
+        let mut remote_decision = self.remote_decision;
+        let mut leader_fee = self.leader_fee;
+        let mut transaction_fee = self.transaction_fee;
+
+        if let Some(update) = update {
+            evidence = update.evidence;
+            is_ready = update.is_ready;
+            pending_stage = Some(parse_from_string(&update.stage)?);
+            local_decision = Some(update.local_decision);
+            remote_decision = update.remote_decision;
+            leader_fee = update.leader_fee;
+            transaction_fee = update.transaction_fee;
+        }
+
+        Ok(TransactionPoolRecord::load(
+            self.transaction_id,
+            evidence,
+            transaction_fee,
+            leader_fee,
+            parse_from_string(&self.stage)?,
+            pending_stage,
+            self.original_decision,
+            local_decision,
+            remote_decision,
+            is_ready,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPoolStateUpdateRow {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub transaction_id: TransactionId,
+    pub stage: String,
+    pub evidence: Evidence,
+    pub is_ready: bool,
+    pub local_decision: Decision,
+    pub transaction_fee: u64,
+    pub leader_fee: Option<LeaderFee>,
+    pub remote_decision: Option<Decision>,
+    pub is_applied: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockConflictRow {
+    pub block_id: BlockId,
+    pub transaction_id: TransactionId,
+    pub depends_on_tx: TransactionId,
+    pub lock_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstateRow {
+    pub record: SubstateRecord,
+    pub seq: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Transition {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransitionRow {
+    pub epoch: Epoch,
+    pub shard: Shard,
+    pub seq: u64,
+    pub substate_address: SubstateAddress,
+    pub substate_id: SubstateId,
+    pub version: u32,
+    pub transition: Transition,
+    pub state_hash: Option<FixedHash>,
+    pub state_version: Version,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubstateLockRow {
+    pub block_id: BlockId,
+    pub substate_id: SubstateId,
+    pub version: u32,
+    pub transaction_id: TransactionId,
+    pub lock_type: SubstateLockType,
+    pub is_local_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingStateTreeDiffRow {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub shard: Shard,
+    pub version: Version,
+    pub diff: StateHashTreeDiff<Version>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTreeNodeRow {
+    pub node: TreeNode<Version>,
+    pub stale_since_version: Option<Version>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpochCheckpointRow {
+    pub commit_block: Block,
+    pub qcs: Vec<QuorumCertificate>,
+    pub shard_roots: IndexMap<Shard, Hash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForeignSubstatePledgeRow {
+    pub substate_id: SubstateId,
+    pub version: u32,
+    pub shard_group: ShardGroup,
+    pub lock_type: SubstateLockType,
+    pub substate_value: Option<SubstateValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BurntUtxoRow {
+    pub utxo: BurntUtxo,
+    pub proposed_in_block_height: Option<NodeHeight>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorEpochStatsRow {
+    pub epoch: Epoch,
+    pub public_key: PublicKey,
+    pub participation_shares: u64,
+    pub missed_proposals: u64,
+    pub missed_proposals_capped: u64,
+    pub total_missed_proposals: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedNodeRow {
+    pub public_key: PublicKey,
+    pub epoch: Epoch,
+    pub suspended_in_block: BlockId,
+    pub suspended_in_block_height: NodeHeight,
+    pub resumed_in_block: Option<BlockId>,
+    pub resumed_in_block_height: Option<NodeHeight>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoVoteRow {
+    pub block_id: BlockId,
+    pub block_height: NodeHeight,
+    pub reason_code: String,
+    pub reason_text: String,
+    pub created_at: PrimitiveDateTime,
+}
@@ -0,0 +1,2419 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    ops::RangeInclusive,
+};
+
+use log::*;
+use rocksdb::{ColumnFamily, DBAccess, DBRawIteratorWithThreadMode, Direction, SnapshotWithThreadMode, TransactionDB};
+use serde::{de::DeserializeOwned, Serialize};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_dan_common_types::{
+    shard::Shard,
+    Epoch,
+    NodeAddressable,
+    NodeHeight,
+    ShardGroup,
+    SubstateAddress,
+    SubstateRequirement,
+    ToSubstateAddress,
+    VersionedSubstateId,
+};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockDiff,
+        BlockId,
+        BlockTransactionExecution,
+        BurntUtxo,
+        EpochCheckpoint,
+        ForeignProposal,
+        ForeignProposalAtom,
+        ForeignProposalStatus,
+        ForeignReceiveCounters,
+        ForeignSendCounters,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockedBlock,
+        LockedSubstateValue,
+        PendingShardStateTreeDiff,
+        QcId,
+        QuorumCertificate,
+        StateTransition,
+        StateTransitionId,
+        SubstateChange,
+        SubstateCreatedProof,
+        SubstateData,
+        SubstateDestroyedProof,
+        SubstateLock,
+        SubstatePledge,
+        SubstatePledges,
+        SubstateRecord,
+        SubstateUpdate,
+        TransactionOrdering,
+        TransactionPoolConfirmedStage,
+        TransactionPoolRecord,
+        TransactionPoolStage,
+        TransactionRecord,
+        ValidatorConsensusStats,
+        ValidatorEpochStats,
+        VersionedSubstateIdLockIntent,
+        Vote,
+    },
+    Ordering,
+    StateStoreReadTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateId;
+use tari_state_tree::{Node, NodeKey, Version};
+use tari_transaction::{IdempotencyKey, TransactionId};
+use tari_utilities::{hex::Hex, ByteArray};
+
+use crate::{
+    columns,
+    error::RocksDbStorageError,
+    keys::{decode_u64, prefix_successor, KeyBuilder},
+    models,
+    models::Transition,
+    serialization::{deserialize_json, deserialize_try_from},
+};
+
+const LOG_TARGET: &str = "tari::dan::storage::rocksdb::reader";
+
+/// The maximum number of blocks walked when collecting the blocks between two blocks
+const MAX_BLOCK_WALK: usize = 1000;
+
+pub(crate) enum DbReader<'a> {
+    /// Read transactions read from a consistent snapshot of the database
+    Snapshot(SnapshotWithThreadMode<'a, TransactionDB>),
+    /// Write transactions read their own uncommitted writes
+    Transaction(rocksdb::Transaction<'a, TransactionDB>),
+}
+
+pub struct RocksDbStateStoreReadTransaction<'a, TAddr> {
+    db: &'a TransactionDB,
+    reader: DbReader<'a>,
+    _addr: PhantomData<TAddr>,
+}
+
+impl<'a, TAddr> RocksDbStateStoreReadTransaction<'a, TAddr> {
+    pub(crate) fn new(db: &'a TransactionDB, reader: DbReader<'a>) -> Self {
+        Self {
+            db,
+            reader,
+            _addr: PhantomData,
+        }
+    }
+
+    pub(crate) fn cf(&self, name: &'static str) -> Result<&'a ColumnFamily, RocksDbStorageError> {
+        self.db
+            .cf_handle(name)
+            .ok_or(RocksDbStorageError::MissingColumnFamily { name })
+    }
+
+    pub(crate) fn transaction(&self) -> &rocksdb::Transaction<'a, TransactionDB> {
+        match &self.reader {
+            DbReader::Transaction(tx) => tx,
+            DbReader::Snapshot(_) => unreachable!("transaction() called on a read-only transaction"),
+        }
+    }
+
+    pub(crate) fn into_transaction(self) -> rocksdb::Transaction<'a, TransactionDB> {
+        match self.reader {
+            DbReader::Transaction(tx) => tx,
+            DbReader::Snapshot(_) => unreachable!("into_transaction() called on a read-only transaction"),
+        }
+    }
+
+    pub(crate) fn get_raw(&self, cf_name: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        let cf = self.cf(cf_name)?;
+        let value = match &self.reader {
+            DbReader::Snapshot(snapshot) => snapshot.get_cf(cf, key),
+            DbReader::Transaction(tx) => tx.get_cf(cf, key),
+        }
+        .map_err(|source| RocksDbStorageError::RocksDbError {
+            source,
+            operation: "get",
+        })?;
+        Ok(value)
+    }
+
+    pub(crate) fn get<T: DeserializeOwned>(
+        &self,
+        cf_name: &'static str,
+        key: &[u8],
+    ) -> Result<Option<T>, StorageError> {
+        self.get_raw(cf_name, key)?
+            .map(|value| deserialize_json(&value))
+            .transpose()
+    }
+
+    pub(crate) fn exists(&self, cf_name: &'static str, key: &[u8]) -> Result<bool, StorageError> {
+        Ok(self.get_raw(cf_name, key)?.is_some())
+    }
+
+    /// Iterates over all keys that start with the given prefix, in the given direction. If `start` is given, iteration
+    /// starts at the first key >= start (forward) or the last key <= start (reverse). Iteration stops when the callback
+    /// returns false.
+    pub(crate) fn iterate<F>(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+        start: Option<&[u8]>,
+        direction: Direction,
+        f: F,
+    ) -> Result<(), StorageError>
+    where
+        F: FnMut(&[u8], &[u8]) -> Result<bool, StorageError>,
+    {
+        let cf = self.cf(cf_name)?;
+        match &self.reader {
+            DbReader::Snapshot(snapshot) => scan(snapshot.raw_iterator_cf(cf), prefix, start, direction, f),
+            DbReader::Transaction(tx) => scan(tx.raw_iterator_cf(cf), prefix, start, direction, f),
+        }
+    }
+
+    pub(crate) fn entries<T: DeserializeOwned>(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, T)>, StorageError> {
+        let mut entries = Vec::new();
+        self.iterate(cf_name, prefix, None, Direction::Forward, |key, value| {
+            entries.push((key.to_vec(), deserialize_json(value)?));
+            Ok(true)
+        })?;
+        Ok(entries)
+    }
+
+    pub(crate) fn values<T: DeserializeOwned>(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+    ) -> Result<Vec<T>, StorageError> {
+        let mut values = Vec::new();
+        self.iterate(cf_name, prefix, None, Direction::Forward, |_, value| {
+            values.push(deserialize_json(value)?);
+            Ok(true)
+        })?;
+        Ok(values)
+    }
+
+    pub(crate) fn keys(&self, cf_name: &'static str, prefix: &[u8]) -> Result<Vec<Vec<u8>>, StorageError> {
+        let mut keys = Vec::new();
+        self.iterate(cf_name, prefix, None, Direction::Forward, |key, _| {
+            keys.push(key.to_vec());
+            Ok(true)
+        })?;
+        Ok(keys)
+    }
+
+    pub(crate) fn raw_entries(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StorageError> {
+        let mut entries = Vec::new();
+        self.iterate(cf_name, prefix, None, Direction::Forward, |key, value| {
+            entries.push((key.to_vec(), value.to_vec()));
+            Ok(true)
+        })?;
+        Ok(entries)
+    }
+
+    /// Returns the first (forward) or last (reverse) value with the given prefix
+    pub(crate) fn first_value<T: DeserializeOwned>(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<Option<T>, StorageError> {
+        let mut found = None;
+        self.iterate(cf_name, prefix, None, direction, |_, value| {
+            found = Some(deserialize_json(value)?);
+            Ok(false)
+        })?;
+        Ok(found)
+    }
+
+    pub(crate) fn first_key(
+        &self,
+        cf_name: &'static str,
+        prefix: &[u8],
+        direction: Direction,
+    ) -> Result<Option<Vec<u8>>, StorageError> {
+        let mut found = None;
+        self.iterate(cf_name, prefix, None, direction, |key, _| {
+            found = Some(key.to_vec());
+            Ok(false)
+        })?;
+        Ok(found)
+    }
+
+    pub(crate) fn count(&self, cf_name: &'static str, prefix: &[u8]) -> Result<u64, StorageError> {
+        let mut count = 0;
+        self.iterate(cf_name, prefix, None, Direction::Forward, |_, _| {
+            count += 1;
+            Ok(true)
+        })?;
+        Ok(count)
+    }
+
+    pub(crate) fn has_prefix(&self, cf_name: &'static str, prefix: &[u8]) -> Result<bool, StorageError> {
+        Ok(self.first_key(cf_name, prefix, Direction::Forward)?.is_some())
+    }
+
+    pub(crate) fn get_block_row(&self, block_id: &BlockId) -> Result<Option<models::BlockRow>, StorageError> {
+        self.get(columns::BLOCKS, block_id.as_ref())
+    }
+
+    pub(crate) fn get_block_height(&self, block_id: &BlockId) -> Result<Option<NodeHeight>, StorageError> {
+        Ok(self.get_block_row(block_id)?.map(|row| row.header.height()))
+    }
+
+    fn load_block(&self, row: models::BlockRow, operation: &'static str) -> Result<Block, StorageError> {
+        let qc = self
+            .get::<QuorumCertificate>(columns::QUORUM_CERTIFICATES, row.header.justify_id().as_ref())?
+            .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                operation,
+                details: format!(
+                    "block {} references non-existent quorum certificate {}",
+                    row.header.id(),
+                    row.header.justify_id()
+                ),
+            })?;
+        Ok(row.into_block(qc))
+    }
+
+    fn load_foreign_proposal(
+        &self,
+        row: models::ForeignProposalRow,
+        operation: &'static str,
+    ) -> Result<ForeignProposal, StorageError> {
+        let justify_qc = self
+            .get::<QuorumCertificate>(columns::QUORUM_CERTIFICATES, row.justify_qc_id.as_ref())?
+            .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                operation,
+                details: format!(
+                    "foreign proposal {} references non-existent quorum certificate {}",
+                    row.block.id(),
+                    row.justify_qc_id
+                ),
+            })?;
+        Ok(row.into_proposal(justify_qc))
+    }
+
+    /// Returns the blocks from the end_block (inclusive) back to the start_block (inclusive) by following the parent
+    /// links. As in the SQLite implementation, it is the callers responsibility to ensure that the start block is an
+    /// ancestor of the end block. Failing this, all block ids back to genesis (up to the limit) are returned.
+    pub(crate) fn get_block_ids_between(
+        &self,
+        start_block: &BlockId,
+        end_block: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<BlockId>, StorageError> {
+        debug!(target: LOG_TARGET, "get_block_ids_between: start: {start_block}, end: {end_block}");
+        Ok(self
+            .walk_parents(end_block, start_block, limit)?
+            .into_iter()
+            .map(|row| *row.header.id())
+            .collect())
+    }
+
+    pub(crate) fn get_block_ids_with_commands_between(
+        &self,
+        start_block: &BlockId,
+        end_block: &BlockId,
+    ) -> Result<Vec<BlockId>, StorageError> {
+        Ok(self
+            .walk_parents(end_block, start_block, MAX_BLOCK_WALK)?
+            .into_iter()
+            .filter(|row| !row.header.is_dummy() && !row.commands.is_empty())
+            .map(|row| *row.header.id())
+            .collect())
+    }
+
+    fn walk_parents(
+        &self,
+        from_block: &BlockId,
+        stop_at: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<models::BlockRow>, StorageError> {
+        let mut rows = Vec::new();
+        let mut current = *from_block;
+        while rows.len() < limit {
+            let Some(row) = self.get_block_row(&current)? else {
+                break;
+            };
+            let parent = *row.header.parent();
+            let is_last = current == *stop_at || parent.is_zero() || row.is_self_parented();
+            rows.push(row);
+            if is_last {
+                break;
+            }
+            current = parent;
+        }
+        Ok(rows)
+    }
+
+    pub(crate) fn get_commit_block(&self) -> Result<LeafBlock, StorageError> {
+        let block_id = self
+            .first_value::<BlockId>(columns::COMMITTED_BLOCKS, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "get_commit_block: committed block",
+                key: "latest".to_string(),
+            })?;
+        let row = self
+            .get_block_row(&block_id)?
+            .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                operation: "get_commit_block",
+                details: format!("committed block {block_id} does not exist"),
+            })?;
+        Ok(LeafBlock {
+            block_id,
+            height: row.header.height(),
+            epoch: row.header.epoch(),
+        })
+    }
+
+    pub(crate) fn get_current_locked_block(&self) -> Result<LockedBlock, StorageError> {
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LOCKED_BLOCK, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "get_current_locked_block: locked block",
+                key: "latest".to_string(),
+            })?;
+        Ok(LockedBlock {
+            height: row.height,
+            block_id: row.block_id,
+            epoch: row.epoch,
+        })
+    }
+
+    /// Returns the latest unapplied pool state update for each of the given transactions (or all transactions if None)
+    /// in the blocks between the given blocks.
+    pub(crate) fn get_transaction_atom_state_updates_between_blocks<'i, I>(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_ids: I,
+    ) -> Result<HashMap<TransactionId, models::TransactionPoolStateUpdateRow>, StorageError>
+    where
+        I: IntoIterator<Item = &'i TransactionId>,
+    {
+        let mut transaction_ids = transaction_ids.into_iter().peekable();
+        if transaction_ids.peek().is_none() {
+            return Ok(HashMap::new());
+        }
+
+        // Blocks without commands may change pending transaction state because they justify a
+        // block that proposes a change. So we cannot only use blocks that have commands.
+        let applicable_block_ids = self
+            .get_block_ids_between(from_block_id, to_block_id, MAX_BLOCK_WALK)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        debug!(
+            target: LOG_TARGET,
+            "get_transaction_atom_state_updates_between_blocks: from_block_id={}, to_block_id={}, len(applicable_block_ids)={}",
+            from_block_id,
+            to_block_id,
+            applicable_block_ids.len());
+
+        if applicable_block_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut updates = HashMap::new();
+        for transaction_id in transaction_ids {
+            let mut latest = None::<models::TransactionPoolStateUpdateRow>;
+            self.iterate(
+                columns::TRANSACTION_POOL_STATE_UPDATES,
+                transaction_id.as_ref(),
+                None,
+                Direction::Forward,
+                |_, value| {
+                    let update = deserialize_json::<models::TransactionPoolStateUpdateRow>(value)?;
+                    if update.is_applied || !applicable_block_ids.contains(&update.block_id) {
+                        return Ok(true);
+                    }
+                    if latest.as_ref().map_or(true, |l| update.block_height >= l.block_height) {
+                        latest = Some(update);
+                    }
+                    Ok(true)
+                },
+            )?;
+            if let Some(update) = latest {
+                updates.insert(*transaction_id, update);
+            }
+        }
+
+        Ok(updates)
+    }
+
+    fn get_substate_row(&self, address: &SubstateAddress) -> Result<Option<models::SubstateRow>, StorageError> {
+        self.get(columns::SUBSTATES, address.as_ref())
+    }
+
+    fn get_substate_by_version(
+        &self,
+        substate_id: &SubstateId,
+        version: u32,
+    ) -> Result<Option<SubstateRecord>, StorageError> {
+        let key = KeyBuilder::new().substate_id(substate_id).u32(version).build();
+        let Some(address) = self.get_raw(columns::SUBSTATES_BY_ID, &key)? else {
+            return Ok(None);
+        };
+        let address = deserialize_try_from::<SubstateAddress>(&address)?;
+        Ok(self.get_substate_row(&address)?.map(|row| row.record))
+    }
+
+    fn get_substate_max_version(&self, substate_id: &SubstateId) -> Result<Option<SubstateRecord>, StorageError> {
+        let prefix = KeyBuilder::new().substate_id(substate_id).build();
+        let mut address = None;
+        self.iterate(
+            columns::SUBSTATES_BY_ID,
+            &prefix,
+            None,
+            Direction::Reverse,
+            |_, value| {
+                address = Some(deserialize_try_from::<SubstateAddress>(value)?);
+                Ok(false)
+            },
+        )?;
+        match address {
+            Some(address) => Ok(self.get_substate_row(&address)?.map(|row| row.record)),
+            None => Ok(None),
+        }
+    }
+
+    fn get_substates_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut addresses = Vec::new();
+        self.iterate(
+            columns::SUBSTATES_BY_TRANSACTION,
+            transaction_id.as_ref(),
+            None,
+            Direction::Forward,
+            |_, value| {
+                addresses.push(deserialize_try_from::<SubstateAddress>(value)?);
+                Ok(true)
+            },
+        )?;
+
+        addresses
+            .iter()
+            .map(|address| {
+                self.get_substate_row(address)?.map(|row| row.record).ok_or_else(|| {
+                    RocksDbStorageError::DbInconsistency {
+                        operation: "get_substates_for_transaction",
+                        details: format!("substate index references non-existent substate {address}"),
+                    }
+                    .into()
+                })
+            })
+            .collect()
+    }
+
+    fn filtered_block_rows(
+        &self,
+        filter_index: Option<usize>,
+        filter: Option<String>,
+    ) -> Result<Vec<models::BlockRow>, StorageError> {
+        let mut rows = self.values::<models::BlockRow>(columns::BLOCKS, &[])?;
+
+        let Some(filter) = filter.filter(|f| !f.is_empty()) else {
+            return Ok(rows);
+        };
+        let Some(filter_index) = filter_index else {
+            return Ok(rows);
+        };
+
+        let parse_filter = || filter.parse::<u64>().map_err(|_| StorageError::InvalidIntegerCast);
+        match filter_index {
+            0 => rows.retain(|row| row.header.id().to_string().contains(&filter)),
+            1 => {
+                let epoch = parse_filter()?;
+                rows.retain(|row| row.header.epoch().as_u64() == epoch);
+            },
+            2 => {
+                let height = parse_filter()?;
+                rows.retain(|row| row.header.height().as_u64() == height);
+            },
+            4 => {
+                let command_count = parse_filter()?;
+                rows.retain(|row| row.commands.len() as u64 >= command_count);
+            },
+            5 => {
+                let total_leader_fee = parse_filter()?;
+                rows.retain(|row| row.header.total_leader_fee() >= total_leader_fee);
+            },
+            7 => rows.retain(|row| row.header.proposed_by().to_hex().contains(&filter)),
+            _ => {},
+        }
+
+        Ok(rows)
+    }
+
+    /// Used in tests, therefore not used in consensus and not part of the trait
+    pub fn transactions_count(&self) -> Result<u64, StorageError> {
+        self.count(columns::TRANSACTIONS, &[])
+    }
+
+    pub fn substates_count(&self) -> Result<u64, StorageError> {
+        self.count(columns::SUBSTATES, &[])
+    }
+
+    pub fn blocks_get_tip(&self, epoch: Epoch, shard_group: ShardGroup) -> Result<Block, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let mut tip = None;
+        self.iterate(
+            columns::BLOCKS_BY_HEIGHT,
+            &prefix,
+            None,
+            Direction::Reverse,
+            |key, _| {
+                let block_id = deserialize_try_from::<BlockId>(&key[16..])?;
+                let row = self
+                    .get_block_row(&block_id)?
+                    .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                        operation: "blocks_get_tip",
+                        details: format!("block index references non-existent block {block_id}"),
+                    })?;
+                if row.header.shard_group() == shard_group {
+                    tip = Some(row);
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        let row = tip.ok_or_else(|| StorageError::NotFound {
+            item: "blocks_get_tip: Block",
+            key: format!("epoch={epoch}, shard_group={shard_group}"),
+        })?;
+        self.load_block(row, "blocks_get_tip")
+    }
+}
+
+impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStoreReadTransaction
+    for RocksDbStateStoreReadTransaction<'tx, TAddr>
+{
+    type Addr = TAddr;
+
+    fn last_sent_vote_get(&self) -> Result<LastSentVote, StorageError> {
+        let row = self
+            .first_value::<models::LastSentVoteRow>(columns::LAST_SENT_VOTE, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "last_sent_vote",
+                key: "latest".to_string(),
+            })?;
+
+        Ok(LastSentVote {
+            epoch: row.epoch,
+            block_id: row.block_id,
+            block_height: row.block_height,
+            decision: row.decision,
+            signature: row.signature,
+        })
+    }
+
+    fn last_voted_get(&self) -> Result<LastVoted, StorageError> {
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LAST_VOTED, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "last_voted",
+                key: "latest".to_string(),
+            })?;
+
+        Ok(LastVoted {
+            block_id: row.block_id,
+            height: row.height,
+            epoch: row.epoch,
+        })
+    }
+
+    fn last_executed_get(&self) -> Result<LastExecuted, StorageError> {
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LAST_EXECUTED, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "last_executed",
+                key: "latest".to_string(),
+            })?;
+
+        Ok(LastExecuted {
+            height: row.height,
+            block_id: row.block_id,
+            epoch: row.epoch,
+        })
+    }
+
+    fn last_proposed_get(&self) -> Result<LastProposed, StorageError> {
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LAST_PROPOSED, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "last_proposed",
+                key: "latest".to_string(),
+            })?;
+
+        Ok(LastProposed {
+            height: row.height,
+            block_id: row.block_id,
+            epoch: row.epoch,
+        })
+    }
+
+    fn locked_block_get(&self, epoch: Epoch) -> Result<LockedBlock, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LOCKED_BLOCK, &prefix, Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "locked_block",
+                key: epoch.to_string(),
+            })?;
+
+        Ok(LockedBlock {
+            height: row.height,
+            block_id: row.block_id,
+            epoch: row.epoch,
+        })
+    }
+
+    fn leaf_block_get(&self, epoch: Epoch) -> Result<LeafBlock, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let row = self
+            .first_value::<models::BlockRefRow>(columns::LEAF_BLOCKS, &prefix, Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "leaf_block",
+                key: epoch.to_string(),
+            })?;
+
+        Ok(LeafBlock {
+            block_id: row.block_id,
+            height: row.height,
+            epoch: row.epoch,
+        })
+    }
+
+    fn high_qc_get(&self, epoch: Epoch) -> Result<HighQc, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let row = self
+            .first_value::<models::HighQcRow>(columns::HIGH_QCS, &prefix, Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "high_qc",
+                key: epoch.to_string(),
+            })?;
+
+        Ok(HighQc {
+            block_id: row.block_id,
+            block_height: row.block_height,
+            epoch: row.epoch,
+            qc_id: row.qc_id,
+        })
+    }
+
+    fn foreign_proposals_get_any<'a, I: IntoIterator<Item = &'a BlockId>>(
+        &self,
+        block_ids: I,
+    ) -> Result<Vec<ForeignProposal>, StorageError> {
+        let mut proposals = Vec::new();
+        for block_id in block_ids {
+            let Some(row) = self.get::<models::ForeignProposalRow>(columns::FOREIGN_PROPOSALS, block_id.as_ref())?
+            else {
+                continue;
+            };
+            proposals.push(self.load_foreign_proposal(row, "foreign_proposals_get_any")?);
+        }
+        Ok(proposals)
+    }
+
+    fn foreign_proposals_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        self.exists(columns::FOREIGN_PROPOSALS, block_id.as_ref())
+    }
+
+    fn foreign_proposals_has_unconfirmed(&self, epoch: Epoch) -> Result<bool, StorageError> {
+        let mut has_unconfirmed = false;
+        self.iterate(columns::FOREIGN_PROPOSALS, &[], None, Direction::Forward, |_, value| {
+            let row = deserialize_json::<models::ForeignProposalRow>(value)?;
+            if row.block.epoch() == epoch && row.status != ForeignProposalStatus::Confirmed {
+                has_unconfirmed = true;
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+        Ok(has_unconfirmed)
+    }
+
+    fn foreign_proposals_get_all_new(
+        &self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<ForeignProposal>, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::NotFound {
+                item: "foreign_proposals_get_all_new: Block",
+                key: block_id.to_string(),
+            });
+        }
+
+        let locked = self.get_current_locked_block()?;
+        let pending_block_ids = self
+            .get_block_ids_with_commands_between(&locked.block_id, block_id)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut rows = Vec::new();
+        self.iterate(columns::FOREIGN_PROPOSALS, &[], None, Direction::Forward, |_, value| {
+            if rows.len() >= limit {
+                return Ok(false);
+            }
+            let row = deserialize_json::<models::ForeignProposalRow>(value)?;
+            if row.block.epoch() != locked.epoch {
+                return Ok(true);
+            }
+            let is_new = match row.proposed_in_block {
+                None => true,
+                Some(proposed_in_block) => {
+                    !pending_block_ids.contains(&proposed_in_block) &&
+                        row.proposed_in_block_height.is_some_and(|h| h > locked.height)
+                },
+            };
+            if is_new {
+                rows.push(row);
+            }
+            Ok(true)
+        })?;
+
+        rows.into_iter()
+            .map(|row| self.load_foreign_proposal(row, "foreign_proposals_get_all_new"))
+            .collect()
+    }
+
+    fn foreign_proposal_get_all_pending(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+    ) -> Result<Vec<ForeignProposalAtom>, StorageError> {
+        let block_ids = self.get_block_ids_with_commands_between(from_block_id, to_block_id)?;
+
+        let mut atoms = Vec::new();
+        for block_id in block_ids {
+            let Some(row) = self.get_block_row(&block_id)? else {
+                continue;
+            };
+            atoms.extend(
+                row.commands
+                    .iter()
+                    .filter_map(|command| command.foreign_proposal().cloned()),
+            );
+        }
+        Ok(atoms)
+    }
+
+    fn foreign_send_counters_get(&self, block_id: &BlockId) -> Result<ForeignSendCounters, StorageError> {
+        let row = self
+            .get::<models::CountersRow>(columns::FOREIGN_SEND_COUNTERS, block_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "foreign_send_counters",
+                key: block_id.to_string(),
+            })?;
+
+        Ok(ForeignSendCounters { counters: row.counters })
+    }
+
+    fn foreign_receive_counters_get(&self) -> Result<ForeignReceiveCounters, StorageError> {
+        let row = self
+            .first_value::<models::CountersRow>(columns::FOREIGN_RECEIVE_COUNTERS, &[], Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "foreign_receive_counters",
+                key: "latest".to_string(),
+            })?;
+
+        Ok(ForeignReceiveCounters { counters: row.counters })
+    }
+
+    fn transactions_get(&self, tx_id: &TransactionId) -> Result<TransactionRecord, StorageError> {
+        let row = self
+            .get::<models::TransactionRow>(columns::TRANSACTIONS, tx_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "transaction",
+                key: tx_id.to_string(),
+            })?;
+
+        Ok(row.into_record())
+    }
+
+    fn transactions_exists(&self, tx_id: &TransactionId) -> Result<bool, StorageError> {
+        self.exists(columns::TRANSACTIONS, tx_id.as_ref())
+    }
+
+    fn transactions_get_any<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &self,
+        tx_ids: I,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        let mut transactions = Vec::new();
+        for tx_id in tx_ids {
+            if let Some(row) = self.get::<models::TransactionRow>(columns::TRANSACTIONS, tx_id.as_ref())? {
+                transactions.push(row.into_record());
+            }
+        }
+        Ok(transactions)
+    }
+
+    fn transactions_get_paginated(
+        &self,
+        limit: u64,
+        offset: u64,
+        asc_desc_created_at: Option<Ordering>,
+    ) -> Result<Vec<TransactionRecord>, StorageError> {
+        let direction = match asc_desc_created_at {
+            Some(Ordering::Descending) => Direction::Reverse,
+            Some(Ordering::Ascending) | None => Direction::Forward,
+        };
+
+        let mut tx_ids = Vec::new();
+        let mut skipped = 0;
+        self.iterate(columns::TRANSACTIONS_BY_CREATED_AT, &[], None, direction, |_, value| {
+            if tx_ids.len() as u64 >= limit {
+                return Ok(false);
+            }
+            if skipped < offset {
+                skipped += 1;
+                return Ok(true);
+            }
+            tx_ids.push(deserialize_try_from::<TransactionId>(value)?);
+            Ok(true)
+        })?;
+
+        tx_ids.iter().map(|tx_id| self.transactions_get(tx_id)).collect()
+    }
+
+    fn transaction_idempotency_keys_get(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        since_epoch: Epoch,
+        from_block_id: &BlockId,
+    ) -> Result<Option<TransactionId>, StorageError> {
+        let commit_block = self.get_commit_block()?;
+        let block_ids = self
+            .get_block_ids_with_commands_between(commit_block.block_id(), from_block_id)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let start = KeyBuilder::new()
+            .bytes(idempotency_key)
+            .u64(since_epoch.as_u64())
+            .build();
+        let mut transaction_id = None;
+        self.iterate(
+            columns::TRANSACTION_IDEMPOTENCY_KEYS,
+            idempotency_key.as_ref(),
+            Some(&start),
+            Direction::Forward,
+            |_, value| {
+                let row = deserialize_json::<models::TransactionIdempotencyKeyRow>(value)?;
+                if row.is_committed || block_ids.contains(&row.block_id) {
+                    transaction_id = Some(row.transaction_id);
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+        Ok(transaction_id)
+    }
+
+    fn transaction_executions_get(
+        &self,
+        tx_id: &TransactionId,
+        block: &BlockId,
+    ) -> Result<BlockTransactionExecution, StorageError> {
+        let index_key = KeyBuilder::new().bytes(block).bytes(tx_id).build();
+        let seq = self
+            .get_raw(columns::TRANSACTION_EXECUTIONS_BY_BLOCK, &index_key)?
+            .and_then(|seq| decode_u64(&seq))
+            .ok_or_else(|| StorageError::NotFound {
+                item: "transaction_execution",
+                key: format!("transaction_id={tx_id}, block_id={block}"),
+            })?;
+
+        let key = KeyBuilder::new().bytes(tx_id).u64(seq).build();
+        let row = self
+            .get::<models::TransactionExecutionRow>(columns::TRANSACTION_EXECUTIONS, &key)?
+            .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                operation: "transaction_executions_get",
+                details: format!("execution index references non-existent execution for transaction {tx_id}"),
+            })?;
+
+        Ok(row.into())
+    }
+
+    fn transaction_executions_get_pending_for_block(
+        &self,
+        tx_id: &TransactionId,
+        from_block_id: &BlockId,
+    ) -> Result<BlockTransactionExecution, StorageError> {
+        if !self.blocks_exists(from_block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "transaction_executions_get_pending_for_block: Block {} does not exist",
+                    from_block_id
+                ),
+            });
+        }
+
+        let commit_block = self.get_commit_block()?;
+        let block_ids = self
+            .get_block_ids_between(commit_block.block_id(), from_block_id, MAX_BLOCK_WALK)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // Get the last execution in the given blocks
+        let mut execution = None;
+        self.iterate(
+            columns::TRANSACTION_EXECUTIONS,
+            tx_id.as_ref(),
+            None,
+            Direction::Reverse,
+            |_, value| {
+                let row = deserialize_json::<models::TransactionExecutionRow>(value)?;
+                if block_ids.contains(&row.block_id) {
+                    execution = Some(row);
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        if let Some(execution) = execution {
+            return Ok(execution.into());
+        }
+
+        // Otherwise look for executions after the commit block
+        self.iterate(
+            columns::TRANSACTION_EXECUTIONS,
+            tx_id.as_ref(),
+            None,
+            Direction::Reverse,
+            |_, value| {
+                let row = deserialize_json::<models::TransactionExecutionRow>(value)?;
+                if self.get_block_row(&row.block_id)?.is_some_and(|b| b.is_committed) {
+                    execution = Some(row);
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        execution.map(Into::into).ok_or_else(|| StorageError::NotFound {
+            item: "transaction_execution",
+            key: format!("transaction_id={tx_id}, from_block_id={from_block_id}"),
+        })
+    }
+
+    fn blocks_get(&self, block_id: &BlockId) -> Result<Block, StorageError> {
+        let row = self.get_block_row(block_id)?.ok_or_else(|| StorageError::NotFound {
+            item: "block",
+            key: block_id.to_string(),
+        })?;
+
+        self.load_block(row, "blocks_get")
+    }
+
+    fn blocks_get_all_ids_by_height(&self, epoch: Epoch, height: NodeHeight) -> Result<Vec<BlockId>, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).u64(height.as_u64()).build();
+        self.keys(columns::BLOCKS_BY_HEIGHT, &prefix)?
+            .iter()
+            .map(|key| deserialize_try_from(&key[16..]))
+            .collect()
+    }
+
+    fn blocks_get_genesis_for_epoch(&self, epoch: Epoch) -> Result<Block, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).u64(0).build();
+        // The zero block is the parent of all genesis blocks
+        let block_id = self
+            .keys(columns::BLOCKS_BY_HEIGHT, &prefix)?
+            .iter()
+            .map(|key| deserialize_try_from::<BlockId>(&key[16..]))
+            .find(|id| id.as_ref().map_or(true, |id| !id.is_zero()))
+            .transpose()?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "blocks_get_genesis_for_epoch: Block",
+                key: epoch.to_string(),
+            })?;
+
+        self.blocks_get(&block_id)
+    }
+
+    fn blocks_get_last_n_in_epoch(&self, n: usize, epoch: Epoch) -> Result<Vec<Block>, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let mut rows = Vec::with_capacity(n);
+        self.iterate(
+            columns::BLOCKS_BY_HEIGHT,
+            &prefix,
+            None,
+            Direction::Reverse,
+            |key, _| {
+                if rows.len() >= n {
+                    return Ok(false);
+                }
+                let block_id = deserialize_try_from::<BlockId>(&key[16..])?;
+                if let Some(row) = self.get_block_row(&block_id)? {
+                    if row.is_committed {
+                        rows.push(row);
+                    }
+                }
+                Ok(true)
+            },
+        )?;
+
+        rows.into_iter()
+            // Order from lowest to highest height
+            .rev()
+            .map(|row| self.load_block(row, "blocks_get_last_n_in_epoch"))
+            .collect()
+    }
+
+    fn blocks_get_all_between(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+        start_block_height: NodeHeight,
+        end_block_height: NodeHeight,
+        include_dummy_blocks: bool,
+        limit: u64,
+    ) -> Result<Vec<Block>, StorageError> {
+        if start_block_height > end_block_height {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "Start block height {start_block_height} must be less than end block height {end_block_height}"
+                ),
+            });
+        }
+
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let start = KeyBuilder::new()
+            .u64(epoch.as_u64())
+            .u64(start_block_height.as_u64())
+            .build();
+        let mut rows = Vec::new();
+        self.iterate(
+            columns::BLOCKS_BY_HEIGHT,
+            &prefix,
+            Some(&start),
+            Direction::Forward,
+            |key, _| {
+                if rows.len() as u64 >= limit {
+                    return Ok(false);
+                }
+                let height = decode_u64(&key[8..]).unwrap_or_default();
+                if height > end_block_height.as_u64() {
+                    return Ok(false);
+                }
+                let block_id = deserialize_try_from::<BlockId>(&key[16..])?;
+                if let Some(row) = self.get_block_row(&block_id)? {
+                    if row.header.shard_group() == shard_group && (include_dummy_blocks || !row.header.is_dummy()) {
+                        rows.push(row);
+                    }
+                }
+                Ok(true)
+            },
+        )?;
+
+        rows.into_iter()
+            .map(|row| self.load_block(row, "blocks_get_all_between"))
+            .collect()
+    }
+
+    fn blocks_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        self.exists(columns::BLOCKS, block_id.as_ref())
+    }
+
+    fn blocks_is_ancestor(&self, descendant: &BlockId, ancestor: &BlockId) -> Result<bool, StorageError> {
+        if !self.blocks_exists(descendant)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_is_ancestor: descendant block {} does not exist", descendant),
+            });
+        }
+
+        if !self.blocks_exists(ancestor)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_is_ancestor: ancestor block {} does not exist", ancestor),
+            });
+        }
+
+        // TODO: this walks all the way to genesis for every query - can optimise though it's low priority for now
+        let mut current = *descendant;
+        let is_ancestor = loop {
+            if current == *ancestor {
+                break true;
+            }
+            let Some(row) = self.get_block_row(&current)? else {
+                break false;
+            };
+            // Stop at the zero block (or any self referencing block)
+            if row.is_self_parented() {
+                break false;
+            }
+            current = *row.header.parent();
+        };
+
+        debug!(target: LOG_TARGET, "blocks_is_ancestor: is_ancestor: {}", is_ancestor);
+
+        Ok(is_ancestor)
+    }
+
+    fn blocks_get_all_by_parent(&self, parent_id: &BlockId) -> Result<Vec<Block>, StorageError> {
+        self.blocks_get_ids_by_parent(parent_id)?
+            .iter()
+            .map(|block_id| {
+                let row = self
+                    .get_block_row(block_id)?
+                    .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                        operation: "blocks_get_all_by_parent",
+                        details: format!("block index references non-existent block {block_id}"),
+                    })?;
+                self.load_block(row, "blocks_get_all_by_parent")
+            })
+            .collect()
+    }
+
+    fn blocks_get_ids_by_parent(&self, parent_id: &BlockId) -> Result<Vec<BlockId>, StorageError> {
+        self.keys(columns::BLOCKS_BY_PARENT, parent_id.as_ref())?
+            .iter()
+            .map(|key| deserialize_try_from::<BlockId>(&key[32..]))
+            // Exclude the genesis block
+            .filter(|id| id.as_ref().map_or(true, |id| id != parent_id))
+            .collect()
+    }
+
+    fn blocks_get_parent_chain(&self, block_id: &BlockId, limit: usize) -> Result<Vec<Block>, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_get_parent_chain: descendant block {} does not exist", block_id),
+            });
+        }
+
+        let mut rows = Vec::new();
+        let mut current = *block_id;
+        while rows.len() < limit {
+            let Some(row) = self.get_block_row(&current)? else {
+                break;
+            };
+            let is_self_parented = row.is_self_parented();
+            current = *row.header.parent();
+            rows.push(row);
+            if is_self_parented {
+                break;
+            }
+        }
+
+        rows.into_iter()
+            .map(|row| self.load_block(row, "blocks_get_parent_chain"))
+            .collect()
+    }
+
+    fn blocks_get_pending_transactions(&self, block_id: &BlockId) -> Result<Vec<TransactionId>, StorageError> {
+        let Some(parked) = self.get::<models::ParkedBlockRow>(columns::PARKED_BLOCKS, block_id.as_ref())? else {
+            return Ok(Vec::new());
+        };
+
+        let prefix = KeyBuilder::new()
+            .u64(parked.block.height().as_u64())
+            .bytes(block_id)
+            .build();
+        self.keys(columns::MISSING_TRANSACTIONS, &prefix)?
+            .iter()
+            .map(|key| deserialize_try_from(&key[prefix.len()..]))
+            .collect()
+    }
+
+    fn blocks_get_total_leader_fee_for_epoch(
+        &self,
+        epoch: Epoch,
+        validator_public_key: &PublicKey,
+    ) -> Result<u64, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let mut total_fee = 0u64;
+        self.iterate(
+            columns::BLOCKS_BY_HEIGHT,
+            &prefix,
+            None,
+            Direction::Forward,
+            |key, _| {
+                let block_id = deserialize_try_from::<BlockId>(&key[16..])?;
+                if let Some(row) = self.get_block_row(&block_id)? {
+                    if row.header.proposed_by() == validator_public_key {
+                        total_fee = total_fee
+                            .checked_add(row.header.total_leader_fee())
+                            .expect("total fee overflows u64");
+                    }
+                }
+                Ok(true)
+            },
+        )?;
+
+        Ok(total_fee)
+    }
+
+    fn blocks_get_any_with_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        validator_public_key: Option<&PublicKey>,
+    ) -> Result<Vec<Block>, StorageError> {
+        let start = KeyBuilder::new().u64(epoch_range.start().as_u64()).build();
+        let mut rows = Vec::new();
+        self.iterate(
+            columns::BLOCKS_BY_HEIGHT,
+            &[],
+            Some(&start),
+            Direction::Forward,
+            |key, _| {
+                let epoch = decode_u64(key).unwrap_or_default();
+                if epoch > epoch_range.end().as_u64() {
+                    return Ok(false);
+                }
+                let block_id = deserialize_try_from::<BlockId>(&key[16..])?;
+                if let Some(row) = self.get_block_row(&block_id)? {
+                    if validator_public_key.map_or(true, |pk| row.header.proposed_by() == pk) {
+                        rows.push(row);
+                    }
+                }
+                Ok(true)
+            },
+        )?;
+
+        rows.into_iter()
+            .map(|row| self.load_block(row, "blocks_get_any_with_epoch_range"))
+            .collect()
+    }
+
+    fn blocks_get_paginated(
+        &self,
+        limit: u64,
+        offset: u64,
+        filter_index: Option<usize>,
+        filter: Option<String>,
+        ordering_index: Option<usize>,
+        ordering: Option<Ordering>,
+    ) -> Result<Vec<Block>, StorageError> {
+        let mut rows = self.filtered_block_rows(filter_index, filter)?;
+
+        rows.sort_by(|a, b| match ordering_index {
+            Some(0) => a.header.id().as_ref().cmp(b.header.id().as_ref()),
+            Some(1) => a.header.epoch().cmp(&b.header.epoch()),
+            Some(4) => a.commands.len().cmp(&b.commands.len()),
+            Some(5) => a.header.total_leader_fee().cmp(&b.header.total_leader_fee()),
+            Some(6) => a.block_time.cmp(&b.block_time),
+            Some(7) => a.created_at.cmp(&b.created_at),
+            Some(8) => a.header.proposed_by().as_bytes().cmp(b.header.proposed_by().as_bytes()),
+            _ => a
+                .header
+                .epoch()
+                .cmp(&b.header.epoch())
+                .then_with(|| a.header.height().cmp(&b.header.height())),
+        });
+        if !matches!(ordering, Some(Ordering::Ascending)) {
+            rows.reverse();
+        }
+
+        rows.into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(limit).unwrap_or(usize::MAX))
+            .map(|row| self.load_block(row, "blocks_get_paginated"))
+            .collect()
+    }
+
+    fn blocks_get_count(&self) -> Result<i64, StorageError> {
+        let count = self.count(columns::BLOCKS, &[])?;
+        Ok(i64::try_from(count).unwrap_or(i64::MAX))
+    }
+
+    fn filtered_blocks_get_count(
+        &self,
+        filter_index: Option<usize>,
+        filter: Option<String>,
+    ) -> Result<i64, StorageError> {
+        let count = self.filtered_block_rows(filter_index, filter)?.len();
+        Ok(i64::try_from(count).unwrap_or(i64::MAX))
+    }
+
+    fn blocks_max_height(&self) -> Result<NodeHeight, StorageError> {
+        let mut max_height = 0;
+        self.iterate(columns::BLOCKS_BY_HEIGHT, &[], None, Direction::Forward, |key, _| {
+            max_height = max_height.max(decode_u64(&key[8..]).unwrap_or_default());
+            Ok(true)
+        })?;
+
+        Ok(NodeHeight(max_height))
+    }
+
+    fn block_diffs_get(&self, block_id: &BlockId) -> Result<BlockDiff, StorageError> {
+        let changes = self
+            .values::<models::BlockDiffRow>(columns::BLOCK_DIFFS, block_id.as_ref())?
+            .into_iter()
+            .map(models::BlockDiffRow::into_change)
+            .collect();
+
+        Ok(BlockDiff {
+            block_id: *block_id,
+            changes,
+        })
+    }
+
+    fn block_diffs_get_last_change_for_substate(
+        &self,
+        block_id: &BlockId,
+        substate_id: &SubstateId,
+    ) -> Result<SubstateChange, StorageError> {
+        let commit_block = self.get_commit_block()?;
+        let block_ids = self
+            .get_block_ids_with_commands_between(commit_block.block_id(), block_id)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let prefix = KeyBuilder::new().substate_id(substate_id).build();
+        let mut diff_key = None;
+        self.iterate(
+            columns::BLOCK_DIFFS_BY_SUBSTATE,
+            &prefix,
+            None,
+            Direction::Reverse,
+            |key, value| {
+                let diff_block_id = deserialize_try_from::<BlockId>(value)?;
+                if block_ids.contains(&diff_block_id) {
+                    diff_key = Some(
+                        KeyBuilder::new()
+                            .bytes(diff_block_id)
+                            .bytes(&key[prefix.len()..])
+                            .build(),
+                    );
+                    return Ok(false);
+                }
+                Ok(true)
+            },
+        )?;
+
+        let diff = diff_key
+            .map(|key| self.get::<models::BlockDiffRow>(columns::BLOCK_DIFFS, &key))
+            .transpose()?
+            .flatten()
+            .ok_or_else(|| StorageError::NotFound {
+                item: "block_diffs_get_last_change_for_substate: BlockDiff",
+                key: substate_id.to_string(),
+            })?;
+
+        Ok(diff.into_change())
+    }
+
+    fn quorum_certificates_get(&self, qc_id: &QcId) -> Result<QuorumCertificate, StorageError> {
+        self.get(columns::QUORUM_CERTIFICATES, qc_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "quorum_certificate",
+                key: qc_id.to_string(),
+            })
+    }
+
+    fn quorum_certificates_get_all<'a, I: IntoIterator<Item = &'a QcId>>(
+        &self,
+        qc_ids: I,
+    ) -> Result<Vec<QuorumCertificate>, StorageError> {
+        let qc_ids = qc_ids.into_iter().collect::<HashSet<_>>();
+        let qcs = qc_ids
+            .iter()
+            .filter_map(|qc_id| {
+                self.get::<QuorumCertificate>(columns::QUORUM_CERTIFICATES, qc_id.as_ref())
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if qcs.len() != qc_ids.len() {
+            return Err(RocksDbStorageError::NotAllItemsFound {
+                items: "QCs",
+                operation: "quorum_certificates_get_all",
+                details: format!(
+                    "quorum_certificates_get_all: expected {} quorum certificates, got {}",
+                    qc_ids.len(),
+                    qcs.len()
+                ),
+            }
+            .into());
+        }
+
+        Ok(qcs)
+    }
+
+    fn quorum_certificates_get_by_block_id(&self, block_id: &BlockId) -> Result<QuorumCertificate, StorageError> {
+        let qc_id = self
+            .first_value::<QcId>(
+                columns::QUORUM_CERTIFICATES_BY_BLOCK,
+                block_id.as_ref(),
+                Direction::Forward,
+            )?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "quorum_certificate",
+                key: block_id.to_string(),
+            })?;
+
+        self.quorum_certificates_get(&qc_id)
+    }
+
+    fn transaction_pool_get_for_blocks(
+        &self,
+        from_block_id: &BlockId,
+        to_block_id: &BlockId,
+        transaction_id: &TransactionId,
+    ) -> Result<TransactionPoolRecord, StorageError> {
+        if !self.blocks_exists(from_block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "transaction_pool_get_for_blocks: Block {} does not exist",
+                    from_block_id
+                ),
+            });
+        }
+
+        if !self.blocks_exists(to_block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("transaction_pool_get_for_blocks: Block {} does not exist", to_block_id),
+            });
+        }
+
+        let mut updates =
+            self.get_transaction_atom_state_updates_between_blocks(from_block_id, to_block_id, Some(transaction_id))?;
+
+        debug!(
+            target: LOG_TARGET,
+            "transaction_pool_get: from_block_id={}, to_block_id={}, transaction_id={}, updates={}",
+            from_block_id,
+            to_block_id,
+            transaction_id,
+            updates.len(),
+        );
+
+        let rec = self
+            .get::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, transaction_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "transaction_pool",
+                key: transaction_id.to_string(),
+            })?;
+
+        rec.try_convert(updates.remove(transaction_id))
+    }
+
+    fn transaction_pool_exists(&self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        self.exists(columns::TRANSACTION_POOL, transaction_id.as_ref())
+    }
+
+    fn transaction_pool_get_all(&self) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        // TODO: need to get the updates - this is just used in JRPC so it doesnt matter too much
+        self.values::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, &[])?
+            .into_iter()
+            .map(|tx| tx.try_convert(None))
+            .collect()
+    }
+
+    fn transaction_pool_get_many_ready(
+        &self,
+        max_txs: usize,
+        block_id: &BlockId,
+        ordering: TransactionOrdering,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("transaction_pool_get_many_ready: block {block_id} does not exist"),
+            });
+        }
+
+        let new_stage = TransactionPoolStage::New.to_string();
+        let all_ready = self
+            .values::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, &[])?
+            .into_iter()
+            .filter(|rec| rec.is_ready);
+
+        let (mut ready_txs, mut new_txs) = all_ready.partition::<Vec<_>, _>(|rec| rec.stage != new_stage);
+
+        debug!(
+            target: LOG_TARGET,
+            "🛢️ transaction_pool_get_many_ready: block_id={}, in progress ready_txs={}",
+            block_id,
+            ready_txs.len()
+        );
+
+        let new_limit = max_txs.saturating_sub(ready_txs.len());
+        if new_limit > 0 {
+            // Records are stored keyed by transaction id, so they are already in TransactionId order
+            let max_candidates = match ordering {
+                TransactionOrdering::TransactionId => new_limit,
+                TransactionOrdering::ArrivalTime => {
+                    new_txs.sort_by_key(|rec| rec.created_at);
+                    new_limit
+                },
+                // The oldest candidates are shuffled below
+                TransactionOrdering::Shuffled => {
+                    new_txs.sort_by_key(|rec| rec.created_at);
+                    TransactionOrdering::max_shuffle_candidates(new_limit)
+                },
+            };
+
+            let mut candidates = Vec::with_capacity(max_candidates.min(new_txs.len()));
+            for rec in new_txs {
+                if candidates.len() >= max_candidates {
+                    break;
+                }
+                // Filter out any transactions that are in lock conflict
+                if self.has_prefix(columns::LOCK_CONFLICTS, rec.transaction_id.as_ref())? {
+                    continue;
+                }
+                candidates.push(rec);
+            }
+            if ordering.is_shuffled() {
+                candidates.sort_by_cached_key(|rec| TransactionOrdering::shuffle_key(block_id, &rec.transaction_id));
+                candidates.truncate(new_limit);
+            }
+            let num_new = candidates.len();
+            ready_txs.extend(candidates);
+
+            debug!(
+                target: LOG_TARGET,
+                "🛢️ transaction_pool_get_many_ready: block_id={}, new ready_txs={}, total ready_txs={}",
+                block_id,
+                num_new,
+                ready_txs.len()
+            );
+        }
+
+        if ready_txs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fetch all applicable block ids between the locked block and the given block
+        let locked = self.get_current_locked_block()?;
+
+        let mut updates = self.get_transaction_atom_state_updates_between_blocks(
+            &locked.block_id,
+            block_id,
+            ready_txs.iter().map(|rec| &rec.transaction_id),
+        )?;
+
+        debug!(
+            target: LOG_TARGET,
+            "transaction_pool_get_many_ready: locked.block_id={}, leaf.block_id={}, len(ready_txs)={}, updates={}",
+            locked.block_id,
+            block_id,
+            ready_txs.len(),
+            updates.len()
+        );
+
+        ready_txs
+            .into_iter()
+            .map(|rec| {
+                let maybe_update = updates.remove(&rec.transaction_id);
+                rec.try_convert(maybe_update)
+            })
+            // Filter only Ok where is_ready == true (after update) or Err
+            .filter(|result| result.as_ref().map_or(true, |rec| rec.is_ready()))
+            .take(max_txs)
+            .collect()
+    }
+
+    fn transaction_pool_count(
+        &self,
+        stage: Option<TransactionPoolStage>,
+        is_ready: Option<bool>,
+        confirmed_stage: Option<Option<TransactionPoolConfirmedStage>>,
+    ) -> Result<usize, StorageError> {
+        let stage = stage.map(|s| s.to_string());
+        let confirmed_stage = confirmed_stage.map(|s| s.map(|s| s.to_string()));
+
+        let mut count = 0;
+        self.iterate(columns::TRANSACTION_POOL, &[], None, Direction::Forward, |_, value| {
+            let rec = deserialize_json::<models::TransactionPoolRow>(value)?;
+            if let Some(ref stage) = stage {
+                let current_stage = rec.pending_stage.as_ref().unwrap_or(&rec.stage);
+                if current_stage != stage {
+                    return Ok(true);
+                }
+            }
+            if is_ready.is_some_and(|is_ready| rec.is_ready != is_ready) {
+                return Ok(true);
+            }
+            if confirmed_stage
+                .as_ref()
+                .is_some_and(|confirmed_stage| rec.confirm_stage != *confirmed_stage)
+            {
+                return Ok(true);
+            }
+            count += 1;
+            Ok(true)
+        })?;
+
+        Ok(count)
+    }
+
+    fn transactions_fetch_involved_shards(
+        &self,
+        transaction_ids: HashSet<TransactionId>,
+    ) -> Result<HashSet<SubstateAddress>, StorageError> {
+        let mut shards = HashSet::new();
+        let mut num_found = 0;
+        for tx_id in &transaction_ids {
+            let Some(row) = self.get::<models::TransactionRow>(columns::TRANSACTIONS, tx_id.as_ref())? else {
+                continue;
+            };
+            num_found += 1;
+            shards.extend(
+                row.resolved_inputs
+                    .iter()
+                    .flatten()
+                    .map(|input| input.versioned_substate_id().to_substate_address()),
+            );
+        }
+
+        if num_found != transaction_ids.len() {
+            return Err(RocksDbStorageError::NotAllItemsFound {
+                items: "Transactions",
+                operation: "transactions_fetch_involved_shards",
+                details: format!(
+                    "transactions_fetch_involved_shards: expected {} transactions, got {}",
+                    transaction_ids.len(),
+                    num_found
+                ),
+            }
+            .into());
+        }
+
+        Ok(shards)
+    }
+
+    fn votes_get_by_block_and_sender(
+        &self,
+        block_id: &BlockId,
+        sender_leaf_hash: &FixedHash,
+    ) -> Result<Vote, StorageError> {
+        let key = KeyBuilder::new().bytes(block_id).bytes(sender_leaf_hash).build();
+        self.get(columns::VOTES, &key)?.ok_or_else(|| StorageError::NotFound {
+            item: "vote",
+            key: format!("block_id={block_id}, sender_leaf_hash={sender_leaf_hash}"),
+        })
+    }
+
+    fn votes_count_for_block(&self, block_id: &BlockId) -> Result<u64, StorageError> {
+        self.count(columns::VOTES, block_id.as_ref())
+    }
+
+    fn votes_get_for_block(&self, block_id: &BlockId) -> Result<Vec<Vote>, StorageError> {
+        self.values(columns::VOTES, block_id.as_ref())
+    }
+
+    fn votes_get_by_sender(&self, epoch: Epoch, sender_leaf_hash: &FixedHash) -> Result<Vec<Vote>, StorageError> {
+        let prefix = KeyBuilder::new().u64(epoch.as_u64()).bytes(sender_leaf_hash).build();
+        self.keys(columns::VOTES_BY_SENDER, &prefix)?
+            .iter()
+            .filter_map(|key| {
+                let vote_key = KeyBuilder::new()
+                    .bytes(&key[prefix.len()..])
+                    .bytes(sender_leaf_hash)
+                    .build();
+                self.get::<Vote>(columns::VOTES, &vote_key).transpose()
+            })
+            .collect()
+    }
+
+    fn substates_get(&self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
+        self.get_substate_row(address)?
+            .map(|row| row.record)
+            .ok_or_else(|| StorageError::NotFound {
+                item: "substate",
+                key: address.to_string(),
+            })
+    }
+
+    fn substates_get_any(
+        &self,
+        substate_ids: &HashSet<SubstateRequirement>,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = Vec::with_capacity(substate_ids.len());
+        for id in substate_ids {
+            let substate = match id.version() {
+                Some(v) => self.get_substate_by_version(id.substate_id(), v)?,
+                // Select the max known version
+                None => self.get_substate_max_version(id.substate_id())?,
+            };
+            substates.extend(substate);
+        }
+        Ok(substates)
+    }
+
+    fn substates_get_any_max_version<'a, I: IntoIterator<Item = &'a SubstateId>>(
+        &self,
+        substate_ids: I,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = Vec::new();
+        for substate_id in substate_ids {
+            substates.extend(self.get_substate_max_version(substate_id)?);
+        }
+        Ok(substates)
+    }
+
+    fn substates_get_max_version_for_substate(&self, substate_id: &SubstateId) -> Result<(u32, bool), StorageError> {
+        let substate = self
+            .get_substate_max_version(substate_id)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "Substate (substates_get_max_version_for_substate)",
+                key: substate_id.to_string(),
+            })?;
+
+        Ok((substate.version, substate.is_destroyed()))
+    }
+
+    fn substates_any_exist<I: IntoIterator<Item = S>, S: Borrow<VersionedSubstateId>>(
+        &self,
+        addresses: I,
+    ) -> Result<bool, StorageError> {
+        for id in addresses {
+            if self.exists(columns::SUBSTATES, id.borrow().to_substate_address().as_ref())? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn substates_exists_for_transaction(&self, transaction_id: &TransactionId) -> Result<bool, StorageError> {
+        self.has_prefix(columns::SUBSTATES_BY_TRANSACTION, transaction_id.as_ref())
+    }
+
+    fn substates_get_n_after(&self, n: usize, after: &SubstateAddress) -> Result<Vec<SubstateRecord>, StorageError> {
+        let start = self.get_substate_row(after)?.ok_or_else(|| StorageError::NotFound {
+            item: "substate",
+            key: after.to_string(),
+        })?;
+
+        let start_key = KeyBuilder::new().u64(start.seq + 1).build();
+        let mut addresses = Vec::with_capacity(n);
+        self.iterate(
+            columns::SUBSTATES_BY_SEQ,
+            &[],
+            Some(&start_key),
+            Direction::Forward,
+            |_, value| {
+                if addresses.len() >= n {
+                    return Ok(false);
+                }
+                addresses.push(deserialize_try_from::<SubstateAddress>(value)?);
+                Ok(true)
+            },
+        )?;
+
+        addresses.iter().map(|address| self.substates_get(address)).collect()
+    }
+
+    fn substates_get_many_within_range(
+        &self,
+        start: &SubstateAddress,
+        end: &SubstateAddress,
+        exclude: &[SubstateAddress],
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = Vec::new();
+        self.iterate(
+            columns::SUBSTATES,
+            &[],
+            Some(start.as_ref()),
+            Direction::Forward,
+            |key, value| {
+                if key > end.as_ref() {
+                    return Ok(false);
+                }
+                if exclude.iter().any(|address| address.as_ref() == key) {
+                    return Ok(true);
+                }
+                substates.push(deserialize_json::<models::SubstateRow>(value)?.record);
+                Ok(true)
+            },
+        )?;
+
+        Ok(substates)
+    }
+
+    fn substates_get_many_by_created_transaction(
+        &self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = self.get_substates_for_transaction(tx_id)?;
+        substates.retain(|s| s.created_by_transaction == *tx_id);
+        Ok(substates)
+    }
+
+    fn substates_get_many_by_destroyed_transaction(
+        &self,
+        tx_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        let mut substates = self.get_substates_for_transaction(tx_id)?;
+        substates.retain(|s| s.destroyed().is_some_and(|d| d.by_transaction == *tx_id));
+        Ok(substates)
+    }
+
+    fn substates_get_all_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<SubstateRecord>, StorageError> {
+        self.get_substates_for_transaction(transaction_id)
+    }
+
+    fn substate_locks_get_locked_substates_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<LockedSubstateValue>, StorageError> {
+        let lock_keys = self
+            .raw_entries(columns::SUBSTATE_LOCKS_BY_TRANSACTION, transaction_id.as_ref())?
+            .into_iter()
+            .map(|(_, lock_key)| lock_key);
+
+        let mut locked = Vec::new();
+        for lock_key in lock_keys {
+            let lock = self
+                .get::<models::SubstateLockRow>(columns::SUBSTATE_LOCKS, &lock_key)?
+                .ok_or_else(|| RocksDbStorageError::DbInconsistency {
+                    operation: "substate_locks_get_locked_substates_for_transaction",
+                    details: format!("lock index references non-existent lock for transaction {transaction_id}"),
+                })?;
+            let substate = self.get_substate_by_version(&lock.substate_id, lock.version)?;
+            locked.push(LockedSubstateValue {
+                locked_by_block: lock.block_id,
+                substate_id: lock.substate_id,
+                lock: SubstateLock::new(lock.transaction_id, lock.version, lock.lock_type, lock.is_local_only),
+                value: substate.map(|r| r.into_substate_value()),
+            });
+        }
+
+        Ok(locked)
+    }
+
+    fn substate_locks_get_latest_for_substate(&self, substate_id: &SubstateId) -> Result<SubstateLock, StorageError> {
+        // TODO: this may return an invalid lock if:
+        // 1. the proposer links the parent block to the locked block instead of the previous tip
+        // 2. if there are any inactive locks that were not removed from previous uncommitted blocks.
+
+        let prefix = KeyBuilder::new().substate_id(substate_id).build();
+        let lock = self
+            .first_value::<models::SubstateLockRow>(columns::SUBSTATE_LOCKS, &prefix, Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "substate_lock",
+                key: substate_id.to_string(),
+            })?;
+
+        Ok(SubstateLock::new(
+            lock.transaction_id,
+            lock.version,
+            lock.lock_type,
+            lock.is_local_only,
+        ))
+    }
+
+    fn pending_state_tree_diffs_get_all_up_to_commit_block(
+        &self,
+        block_id: &BlockId,
+    ) -> Result<HashMap<Shard, Vec<PendingShardStateTreeDiff>>, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::NotFound {
+                item: "pending_state_tree_diffs_get_all_up_to_commit_block: Block",
+                key: block_id.to_string(),
+            });
+        }
+
+        // Get the last committed block
+        let commit_block = self.get_commit_block()?;
+
+        // Block may modify state with zero commands because the justify a block that changes state
+        let block_ids = self.get_block_ids_between(commit_block.block_id(), block_id, MAX_BLOCK_WALK)?;
+
+        let mut diff_recs = Vec::new();
+        for block_id in block_ids {
+            diff_recs.extend(
+                self.values::<models::PendingStateTreeDiffRow>(columns::PENDING_STATE_TREE_DIFFS, block_id.as_ref())?,
+            );
+        }
+        diff_recs.sort_by_key(|diff| diff.block_height);
+
+        let mut diffs = HashMap::new();
+        for diff in diff_recs {
+            diffs
+                .entry(diff.shard)
+                .or_insert_with(Vec::new)
+                .push(PendingShardStateTreeDiff::load(diff.version, diff.diff));
+        }
+
+        Ok(diffs)
+    }
+
+    fn state_transitions_get_n_after(
+        &self,
+        n: usize,
+        id: StateTransitionId,
+        end_epoch: Epoch,
+    ) -> Result<Vec<StateTransition>, StorageError> {
+        debug!(target: LOG_TARGET, "state_transitions_get_n_after: {id}, end_epoch:{end_epoch}");
+
+        let prefix = KeyBuilder::new().u32(id.shard().as_u32()).build();
+        let start = KeyBuilder::new().u32(id.shard().as_u32()).u64(id.seq() + 1).build();
+        let mut transitions = Vec::with_capacity(n);
+        self.iterate(
+            columns::STATE_TRANSITIONS,
+            &prefix,
+            Some(&start),
+            Direction::Forward,
+            |_, value| {
+                if transitions.len() >= n {
+                    return Ok(false);
+                }
+                let transition = deserialize_json::<models::StateTransitionRow>(value)?;
+                if transition.epoch < end_epoch {
+                    transitions.push(transition);
+                }
+                Ok(true)
+            },
+        )?;
+
+        transitions
+            .into_iter()
+            .map(|t| {
+                let substate =
+                    self.get_substate_row(&t.substate_address)?
+                        .ok_or_else(|| StorageError::DataInconsistency {
+                            details: format!("substate entry does not exist for transition {}", t.seq),
+                        })?;
+                let substate = substate.record;
+
+                let update = match t.transition {
+                    Transition::Up => SubstateUpdate::Create(SubstateCreatedProof {
+                        substate: SubstateData {
+                            substate_id: substate.substate_id,
+                            version: substate.version,
+                            substate_value: substate.substate_value,
+                            created_by_transaction: substate.created_by_transaction,
+                        },
+                    }),
+                    Transition::Down => {
+                        let destroyed = substate.destroyed().ok_or_else(|| StorageError::DataInconsistency {
+                            details: format!(
+                                "State transition for substate {}:{} is DOWN but the substate is not destroyed",
+                                substate.substate_id, substate.version
+                            ),
+                        })?;
+
+                        SubstateUpdate::Destroy(SubstateDestroyedProof {
+                            destroyed_by_transaction: destroyed.by_transaction,
+                            substate_id: substate.substate_id,
+                            version: substate.version,
+                        })
+                    },
+                };
+
+                Ok(StateTransition {
+                    id: StateTransitionId::new(t.epoch, t.shard, t.seq),
+                    update,
+                })
+            })
+            .collect()
+    }
+
+    fn state_transitions_get_last_id(&self, shard: Shard) -> Result<StateTransitionId, StorageError> {
+        let prefix = KeyBuilder::new().u32(shard.as_u32()).build();
+        let transition = self
+            .first_value::<models::StateTransitionRow>(columns::STATE_TRANSITIONS, &prefix, Direction::Reverse)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "state_transition",
+                key: shard.to_string(),
+            })?;
+
+        Ok(StateTransitionId::new(transition.epoch, shard, transition.seq))
+    }
+
+    fn state_tree_nodes_get(&self, shard: Shard, key: &NodeKey) -> Result<Node<Version>, StorageError> {
+        let db_key = KeyBuilder::new().u32(shard.as_u32()).bytes(key.to_string()).build();
+        let row = self
+            .get_raw(columns::STATE_TREE, &db_key)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "state_tree_node",
+                key: key.to_string(),
+            })?;
+
+        let row =
+            serde_json::from_slice::<models::StateTreeNodeRow>(&row).map_err(|e| StorageError::DataInconsistency {
+                details: format!("Failed to deserialize state tree node: {}", e),
+            })?;
+
+        Ok(row.node.into_node())
+    }
+
+    fn state_tree_versions_get_latest(&self, shard: Shard) -> Result<Option<Version>, StorageError> {
+        let key = KeyBuilder::new().u32(shard.as_u32()).build();
+        Ok(self
+            .get_raw(columns::STATE_TREE_SHARD_VERSIONS, &key)?
+            .and_then(|v| decode_u64(&v)))
+    }
+
+    fn epoch_checkpoint_get(&self, epoch: Epoch) -> Result<EpochCheckpoint, StorageError> {
+        let key = KeyBuilder::new().u64(epoch.as_u64()).build();
+        let checkpoint = self
+            .get::<models::EpochCheckpointRow>(columns::EPOCH_CHECKPOINTS, &key)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "epoch_checkpoint",
+                key: epoch.to_string(),
+            })?;
+
+        Ok(EpochCheckpoint::new(
+            checkpoint.commit_block,
+            checkpoint.qcs,
+            checkpoint.shard_roots,
+        ))
+    }
+
+    fn epoch_handover_checkpoints_get(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+    ) -> Result<EpochCheckpoint, StorageError> {
+        let key = KeyBuilder::new()
+            .u64(epoch.as_u64())
+            .u32(shard_group.encode_as_u32())
+            .build();
+        let checkpoint = self
+            .get::<models::EpochCheckpointRow>(columns::EPOCH_HANDOVER_CHECKPOINTS, &key)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "epoch_handover_checkpoint",
+                key: format!("{epoch}, {shard_group}"),
+            })?;
+
+        Ok(EpochCheckpoint::new(
+            checkpoint.commit_block,
+            checkpoint.qcs,
+            checkpoint.shard_roots,
+        ))
+    }
+
+    fn foreign_substate_pledges_exists_for_address<T: ToSubstateAddress>(
+        &self,
+        transaction_id: &TransactionId,
+        address: T,
+    ) -> Result<bool, StorageError> {
+        let key = KeyBuilder::new()
+            .bytes(transaction_id)
+            .bytes(address.to_substate_address())
+            .build();
+        self.exists(columns::FOREIGN_SUBSTATE_PLEDGES, &key)
+    }
+
+    fn foreign_substate_pledges_get_all_by_transaction_id(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<SubstatePledges, StorageError> {
+        let recs = self
+            .values::<models::ForeignSubstatePledgeRow>(columns::FOREIGN_SUBSTATE_PLEDGES, transaction_id.as_ref())?;
+
+        #[allow(clippy::mutable_key_type)]
+        let mut pledges = SubstatePledges::with_capacity(recs.len());
+        for pledge in recs {
+            let id = VersionedSubstateId::new(pledge.substate_id, pledge.version);
+            let lock_intent = VersionedSubstateIdLockIntent::new(id, pledge.lock_type, true);
+            let pledge = SubstatePledge::try_create(lock_intent.clone(), pledge.substate_value).ok_or_else(|| {
+                StorageError::DataInconsistency {
+                    details: format!("Invalid input substate pledge for {lock_intent}"),
+                }
+            })?;
+            pledges.insert(pledge);
+        }
+
+        Ok(pledges)
+    }
+
+    fn burnt_utxos_get(&self, substate_id: &SubstateId) -> Result<BurntUtxo, StorageError> {
+        let row = self
+            .get::<models::BurntUtxoRow>(columns::BURNT_UTXOS, substate_id.to_string().as_bytes())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "burnt_utxo",
+                key: substate_id.to_string(),
+            })?;
+
+        Ok(row.utxo)
+    }
+
+    fn burnt_utxos_get_all_unproposed(
+        &self,
+        leaf_block: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<BurntUtxo>, StorageError> {
+        if !self.blocks_exists(leaf_block)? {
+            return Err(StorageError::NotFound {
+                item: "Block",
+                key: leaf_block.to_string(),
+            });
+        }
+
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let locked_block = self.get_current_locked_block()?;
+        let exclude_block_ids = self
+            .get_block_ids_with_commands_between(&locked_block.block_id, leaf_block)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let mut burnt_utxos = Vec::new();
+        self.iterate(columns::BURNT_UTXOS, &[], None, Direction::Forward, |_, value| {
+            if burnt_utxos.len() >= limit {
+                return Ok(false);
+            }
+            let row = deserialize_json::<models::BurntUtxoRow>(value)?;
+            let is_unproposed = match row.utxo.proposed_in_block {
+                None => true,
+                Some(ref proposed_in_block) => {
+                    !exclude_block_ids.contains(proposed_in_block) &&
+                        row.proposed_in_block_height.is_some_and(|h| h > locked_block.height)
+                },
+            };
+            if is_unproposed {
+                burnt_utxos.push(row.utxo);
+            }
+            Ok(true)
+        })?;
+
+        Ok(burnt_utxos)
+    }
+
+    fn burnt_utxos_count(&self) -> Result<u64, StorageError> {
+        self.count(columns::BURNT_UTXOS, &[])
+    }
+
+    fn foreign_parked_blocks_exists(&self, block_id: &BlockId) -> Result<bool, StorageError> {
+        self.exists(columns::FOREIGN_PARKED_BLOCKS, block_id.as_ref())
+    }
+
+    fn validator_epoch_stats_get(
+        &self,
+        epoch: Epoch,
+        public_key: &PublicKey,
+    ) -> Result<ValidatorConsensusStats, StorageError> {
+        let key = KeyBuilder::new()
+            .u64(epoch.as_u64())
+            .bytes(public_key.as_bytes())
+            .build();
+        let row = self
+            .get::<models::ValidatorEpochStatsRow>(columns::VALIDATOR_EPOCH_STATS, &key)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "validator_epoch_stats",
+                key: format!("epoch={epoch}, public_key={public_key}"),
+            })?;
+
+        Ok(ValidatorConsensusStats {
+            missed_proposals: row.missed_proposals,
+            participation_shares: row.participation_shares,
+            total_missed_proposals: row.total_missed_proposals,
+        })
+    }
+
+    fn validator_epoch_stats_get_all_in_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorEpochStats>, StorageError> {
+        let start = KeyBuilder::new().u64(epoch_range.start().as_u64()).build();
+        let mut stats = Vec::new();
+        self.iterate(
+            columns::VALIDATOR_EPOCH_STATS,
+            &[],
+            Some(&start),
+            Direction::Forward,
+            |key, value| {
+                let epoch = decode_u64(key).unwrap_or_default();
+                if epoch > epoch_range.end().as_u64() {
+                    return Ok(false);
+                }
+                let row = deserialize_json::<models::ValidatorEpochStatsRow>(value)?;
+                if public_key.map_or(true, |pk| row.public_key == *pk) {
+                    stats.push(ValidatorEpochStats {
+                        epoch: row.epoch,
+                        public_key: row.public_key,
+                        stats: ValidatorConsensusStats {
+                            missed_proposals: row.missed_proposals,
+                            participation_shares: row.participation_shares,
+                            total_missed_proposals: row.total_missed_proposals,
+                        },
+                    });
+                }
+                Ok(true)
+            },
+        )?;
+
+        Ok(stats)
+    }
+
+    fn validator_epoch_stats_get_nodes_to_suspend(
+        &self,
+        block_id: &BlockId,
+        min_missed_proposals: u64,
+        limit: usize,
+    ) -> Result<Vec<PublicKey>, StorageError> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let commit_block = self.get_commit_block()?;
+
+        let block_ids = self
+            .get_block_ids_between(commit_block.block_id(), block_id, MAX_BLOCK_WALK)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let suspended = self
+            .values::<models::SuspendedNodeRow>(columns::SUSPENDED_NODES, &[])?
+            .into_iter()
+            // Already suspended in uncommitted blocks or in committed blocks
+            .filter(|node| {
+                block_ids.contains(&node.suspended_in_block) || node.suspended_in_block_height < commit_block.height()
+            })
+            .map(|node| node.public_key)
+            .collect::<HashSet<_>>();
+
+        let prefix = KeyBuilder::new().u64(commit_block.epoch().as_u64()).build();
+        let pks = self
+            .values::<models::ValidatorEpochStatsRow>(columns::VALIDATOR_EPOCH_STATS, &prefix)?
+            .into_iter()
+            .filter(|stats| stats.missed_proposals >= min_missed_proposals && !suspended.contains(&stats.public_key))
+            .take(limit)
+            .map(|stats| stats.public_key)
+            .collect();
+
+        Ok(pks)
+    }
+
+    fn validator_epoch_stats_get_nodes_to_resume(
+        &self,
+        block_id: &BlockId,
+        limit: usize,
+    ) -> Result<Vec<PublicKey>, StorageError> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+
+        let commit_block = self.get_commit_block()?;
+
+        let block_ids = self
+            .get_block_ids_between(commit_block.block_id(), block_id, MAX_BLOCK_WALK)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        // Must be suspended
+        let suspended = self
+            .values::<models::SuspendedNodeRow>(columns::SUSPENDED_NODES, &[])?
+            .into_iter()
+            .filter(|node| match (&node.resumed_in_block, node.resumed_in_block_height) {
+                (Some(resumed_in_block), Some(resumed_in_block_height)) => {
+                    !block_ids.contains(resumed_in_block) || resumed_in_block_height < commit_block.height()
+                },
+                _ => true,
+            })
+            .map(|node| node.public_key)
+            .collect::<HashSet<_>>();
+
+        let prefix = KeyBuilder::new().u64(commit_block.epoch().as_u64()).build();
+        let pks = self
+            .values::<models::ValidatorEpochStatsRow>(columns::VALIDATOR_EPOCH_STATS, &prefix)?
+            .into_iter()
+            .filter(|stats| stats.missed_proposals_capped == 0 && suspended.contains(&stats.public_key))
+            .take(limit)
+            .map(|stats| stats.public_key)
+            .collect();
+
+        Ok(pks)
+    }
+
+    fn suspended_nodes_is_suspended(&self, block_id: &BlockId, public_key: &PublicKey) -> Result<bool, StorageError> {
+        if !self.blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("block {} not found", block_id),
+            });
+        }
+
+        let commit_block = self.get_commit_block()?;
+        let block_ids = self.get_block_ids_between(commit_block.block_id(), block_id, MAX_BLOCK_WALK)?;
+
+        let Some(node) = self.get::<models::SuspendedNodeRow>(columns::SUSPENDED_NODES, public_key.as_bytes())? else {
+            return Ok(false);
+        };
+
+        Ok(node
+            .resumed_in_block
+            .map_or(true, |resumed_in_block| !block_ids.contains(&resumed_in_block)))
+    }
+
+    fn suspended_nodes_count(&self) -> Result<u64, StorageError> {
+        self.count(columns::SUSPENDED_NODES, &[])
+    }
+
+    fn suspended_nodes_get_all(&self) -> Result<Vec<PublicKey>, StorageError> {
+        Ok(self
+            .values::<models::SuspendedNodeRow>(columns::SUSPENDED_NODES, &[])?
+            .into_iter()
+            .map(|row| row.public_key)
+            .collect())
+    }
+}
+
+fn scan<D, F>(
+    mut iter: DBRawIteratorWithThreadMode<'_, D>,
+    prefix: &[u8],
+    start: Option<&[u8]>,
+    direction: Direction,
+    mut f: F,
+) -> Result<(), StorageError>
+where
+    D: DBAccess,
+    F: FnMut(&[u8], &[u8]) -> Result<bool, StorageError>,
+{
+    match (direction, start) {
+        (Direction::Forward, start) => iter.seek(start.unwrap_or(prefix)),
+        (Direction::Reverse, Some(start)) => iter.seek_for_prev(start),
+        (Direction::Reverse, None) => match prefix_successor(prefix) {
+            Some(successor) => {
+                iter.seek_for_prev(&successor);
+                // seek_for_prev positions the iterator on the successor itself if that key exists
+                if iter.valid() && iter.key() == Some(successor.as_slice()) {
+                    iter.prev();
+                }
+            },
+            None => iter.seek_to_last(),
+        },
+    }
+
+    while iter.valid() {
+        let (Some(key), Some(value)) = (iter.key(), iter.value()) else {
+            break;
+        };
+        if !key.starts_with(prefix) {
+            break;
+        }
+        if !f(key, value)? {
+            break;
+        }
+        match direction {
+            Direction::Forward => iter.next(),
+            Direction::Reverse => iter.prev(),
+        }
+    }
+
+    iter.status().map_err(|source| RocksDbStorageError::RocksDbError {
+        source,
+        operation: "iterate",
+    })?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{any::type_name, fmt, str::FromStr};
+
+use serde::Serialize;
+use tari_dan_storage::StorageError;
+
+pub fn serialize_json<T: Serialize + ?Sized>(t: &T) -> Result<Vec<u8>, StorageError> {
+    serde_json::to_vec(t).map_err(|e| StorageError::EncodingError {
+        operation: "serialize_json",
+        item: type_name::<T>(),
+        details: e.to_string(),
+    })
+}
+
+pub fn deserialize_json<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, StorageError> {
+    serde_json::from_slice(bytes).map_err(|e| StorageError::DecodingError {
+        operation: "deserialize_json",
+        item: type_name::<T>(),
+        details: e.to_string(),
+    })
+}
+
+pub fn deserialize_try_from<'a, T>(bytes: &'a [u8]) -> Result<T, StorageError>
+where
+    T: TryFrom<&'a [u8]>,
+    T::Error: fmt::Debug,
+{
+    T::try_from(bytes).map_err(|e| StorageError::DecodingError {
+        operation: "deserialize_try_from",
+        item: type_name::<T>(),
+        details: format!("{e:?}"),
+    })
+}
+
+pub fn parse_from_string<T>(s: &str) -> Result<T, StorageError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    s.parse().map_err(|e| StorageError::DecodingError {
+        operation: "parse_from_string",
+        item: type_name::<T>(),
+        details: format!("Cannot parse string '{s}: {e}'"),
+    })
+}
@@ -0,0 +1,108 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use log::log;
+use rocksdb::{ColumnFamilyDescriptor, Options, TransactionDB, TransactionDBOptions};
+use serde::{de::DeserializeOwned, Serialize};
+use tari_dan_common_types::NodeAddressable;
+use tari_dan_storage::{StateStore, StorageError};
+
+use crate::{
+    columns,
+    error::RocksDbStorageError,
+    reader::{DbReader, RocksDbStateStoreReadTransaction},
+    writer::RocksDbStateStoreWriteTransaction,
+};
+
+const LOG_TARGET: &str = "tari::dan::storage::rocksdb::state_store";
+
+pub struct RocksDbStateStore<TAddr> {
+    db: Arc<TransactionDB>,
+    /// RocksDB transactions are optimistic about conflicting writes. Consensus expects write transactions to be
+    /// serialized (as they are in SQLite), so only one write transaction may be open at a time.
+    write_lock: Arc<Mutex<()>>,
+    _addr: PhantomData<TAddr>,
+}
+
+impl<TAddr> RocksDbStateStore<TAddr> {
+    /// Opens the database at the given path, creating it and any missing column families if necessary.
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let column_families = columns::ALL
+            .iter()
+            .map(|name| ColumnFamilyDescriptor::new(*name, Options::default()));
+
+        let db = TransactionDB::open_cf_descriptors(&options, &TransactionDBOptions::default(), path, column_families)
+            .map_err(|source| RocksDbStorageError::OpenError { source })?;
+
+        Ok(Self {
+            db: Arc::new(db),
+            write_lock: Arc::new(Mutex::new(())),
+            _addr: PhantomData,
+        })
+    }
+}
+
+// Manually implement the Debug implementation because `TransactionDB` does not implement the Debug trait
+impl<TAddr> fmt::Debug for RocksDbStateStore<TAddr> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RocksDbStateStore")
+    }
+}
+
+impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for RocksDbStateStore<TAddr> {
+    type Addr = TAddr;
+    type ReadTransaction<'a>
+        = RocksDbStateStoreReadTransaction<'a, Self::Addr>
+    where TAddr: 'a;
+    type WriteTransaction<'a>
+        = RocksDbStateStoreWriteTransaction<'a, Self::Addr>
+    where TAddr: 'a;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        Ok(RocksDbStateStoreReadTransaction::new(
+            &self.db,
+            DbReader::Snapshot(self.db.snapshot()),
+        ))
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        let timer = Instant::now();
+        let guard = self.write_lock.lock().unwrap();
+        let tx = RocksDbStateStoreReadTransaction::new(&self.db, DbReader::Transaction(self.db.transaction()));
+        let tx = RocksDbStateStoreWriteTransaction::new(tx, guard);
+        let elapsed = timer.elapsed();
+        let level = if elapsed > Duration::from_secs(1) {
+            log::Level::Warn
+        } else {
+            log::Level::Trace
+        };
+        log!(
+            target: LOG_TARGET,
+            level,
+            "Write transaction obtained in {:?}", elapsed
+        );
+        Ok(tx)
+    }
+}
+
+impl<TAddr> Clone for RocksDbStateStore<TAddr> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            write_lock: self.write_lock.clone(),
+            _addr: PhantomData,
+        }
+    }
+}
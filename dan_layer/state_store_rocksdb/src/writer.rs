@@ -0,0 +1,2006 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    cmp,
+    collections::{hash_map::Entry, HashMap, HashSet},
+    iter::Peekable,
+    ops::Deref,
+    sync::MutexGuard,
+};
+
+use indexmap::IndexMap;
+use log::*;
+use rocksdb::Direction;
+use serde::{de::DeserializeOwned, Serialize};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{
+    optional::Optional,
+    shard::Shard,
+    Epoch,
+    NodeAddressable,
+    NodeHeight,
+    ShardGroup,
+    SubstateLockType,
+    ToSubstateAddress,
+};
+use tari_dan_storage::{
+    consensus_models::{
+        Block,
+        BlockId,
+        BlockTransactionExecution,
+        BurntUtxo,
+        Decision,
+        EpochCheckpoint,
+        Evidence,
+        ForeignParkedProposal,
+        ForeignProposal,
+        ForeignProposalStatus,
+        ForeignReceiveCounters,
+        ForeignSendCounters,
+        HighQc,
+        LastExecuted,
+        LastProposed,
+        LastSentVote,
+        LastVoted,
+        LeafBlock,
+        LockConflict,
+        LockedBlock,
+        NoVoteReason,
+        PendingShardStateTreeDiff,
+        QcId,
+        QuorumCertificate,
+        SubstateChange,
+        SubstateDestroyed,
+        SubstateLock,
+        SubstatePledge,
+        SubstatePledges,
+        SubstateWrite,
+        TransactionPoolConfirmedStage,
+        TransactionPoolRecord,
+        TransactionPoolStage,
+        TransactionPoolStatusUpdate,
+        TransactionRecord,
+        ValidatorStatsUpdate,
+        VersionedStateHashTreeDiff,
+        Vote,
+    },
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+    StorageError,
+};
+use tari_engine_types::substate::SubstateId;
+use tari_state_tree::{Node, NodeKey, StaleTreeNode, TreeNode, Version};
+use tari_transaction::{IdempotencyKey, TransactionId};
+use tari_utilities::ByteArray;
+use time::{OffsetDateTime, PrimitiveDateTime};
+
+use crate::{
+    columns,
+    error::RocksDbStorageError,
+    keys::{decode_u64, KeyBuilder},
+    models,
+    models::Transition,
+    reader::RocksDbStateStoreReadTransaction,
+    serialization::{deserialize_try_from, serialize_json},
+};
+
+const LOG_TARGET: &str = "tari::dan::storage";
+
+pub struct RocksDbStateStoreWriteTransaction<'a, TAddr> {
+    /// None indicates if the transaction has been explicitly committed/rolled back
+    transaction: Option<RocksDbStateStoreReadTransaction<'a, TAddr>>,
+    _guard: MutexGuard<'a, ()>,
+}
+
+impl<'a, TAddr: NodeAddressable + Serialize + DeserializeOwned> RocksDbStateStoreWriteTransaction<'a, TAddr> {
+    pub(crate) fn new(transaction: RocksDbStateStoreReadTransaction<'a, TAddr>, guard: MutexGuard<'a, ()>) -> Self {
+        Self {
+            transaction: Some(transaction),
+            _guard: guard,
+        }
+    }
+
+    fn tx(&self) -> &RocksDbStateStoreReadTransaction<'a, TAddr> {
+        self.transaction.as_ref().unwrap()
+    }
+
+    fn put_raw(&self, cf_name: &'static str, key: &[u8], value: &[u8]) -> Result<(), StorageError> {
+        let tx = self.tx();
+        tx.transaction()
+            .put_cf(tx.cf(cf_name)?, key, value)
+            .map_err(|source| RocksDbStorageError::RocksDbError {
+                source,
+                operation: "put",
+            })?;
+        Ok(())
+    }
+
+    fn put<T: Serialize + ?Sized>(&self, cf_name: &'static str, key: &[u8], value: &T) -> Result<(), StorageError> {
+        self.put_raw(cf_name, key, &serialize_json(value)?)
+    }
+
+    fn delete(&self, cf_name: &'static str, key: &[u8]) -> Result<(), StorageError> {
+        let tx = self.tx();
+        tx.transaction()
+            .delete_cf(tx.cf(cf_name)?, key)
+            .map_err(|source| RocksDbStorageError::RocksDbError {
+                source,
+                operation: "delete",
+            })?;
+        Ok(())
+    }
+
+    fn delete_prefix(&self, cf_name: &'static str, prefix: &[u8]) -> Result<usize, StorageError> {
+        let keys = self.tx().keys(cf_name, prefix)?;
+        for key in &keys {
+            self.delete(cf_name, key)?;
+        }
+        Ok(keys.len())
+    }
+
+    /// Returns the next value of the named sequence. Sequences start at 1.
+    fn next_seq(&self, name: &'static str) -> Result<u64, StorageError> {
+        let seq = self
+            .tx()
+            .get_raw(columns::SEQUENCES, name.as_bytes())?
+            .and_then(|v| decode_u64(&v))
+            .unwrap_or(0) +
+            1;
+        self.put_raw(columns::SEQUENCES, name.as_bytes(), &seq.to_be_bytes())?;
+        Ok(seq)
+    }
+
+    fn require_block_height(&self, block_id: &BlockId, operation: &str) -> Result<NodeHeight, StorageError> {
+        self.tx()
+            .get_block_height(block_id)?
+            .ok_or_else(|| StorageError::QueryError {
+                reason: format!("{operation}: Block {block_id} does not exist"),
+            })
+    }
+
+    fn update_block_row<F: FnOnce(&mut models::BlockRow)>(&self, block_id: &BlockId, f: F) -> Result<(), StorageError> {
+        let Some(mut row) = self.tx().get_block_row(block_id)? else {
+            return Ok(());
+        };
+        f(&mut row);
+        self.put(columns::BLOCKS, block_id.as_ref(), &row)
+    }
+
+    fn update_foreign_proposal<F: FnOnce(&mut models::ForeignProposalRow)>(
+        &self,
+        block_id: &BlockId,
+        f: F,
+    ) -> Result<(), StorageError> {
+        let Some(mut row) = self
+            .tx()
+            .get::<models::ForeignProposalRow>(columns::FOREIGN_PROPOSALS, block_id.as_ref())?
+        else {
+            return Ok(());
+        };
+        f(&mut row);
+        self.put(columns::FOREIGN_PROPOSALS, block_id.as_ref(), &row)
+    }
+
+    /// Appends a bookkeeping record. Records are keyed by epoch (if given) and insertion order so that the latest
+    /// record is the last one in the column family.
+    fn bookkeeping_insert<T: Serialize>(
+        &self,
+        cf_name: &'static str,
+        epoch: Option<Epoch>,
+        value: &T,
+    ) -> Result<(), StorageError> {
+        let seq = self.next_seq(cf_name)?;
+        let key = match epoch {
+            Some(epoch) => KeyBuilder::new().u64(epoch.as_u64()).u64(seq).build(),
+            None => KeyBuilder::new().u64(seq).build(),
+        };
+        self.put(cf_name, &key, value)
+    }
+
+    fn bookkeeping_remove(
+        &self,
+        cf_name: &'static str,
+        block_id: &BlockId,
+        height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        let entries = self.tx().entries::<models::BlockRefRow>(cf_name, &[])?;
+        for (key, row) in entries {
+            if row.block_id == *block_id && row.height == height {
+                self.delete(cf_name, &key)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parked_blocks_remove(&mut self, block_id: &BlockId) -> Result<(Block, Vec<ForeignProposal>), StorageError> {
+        let parked_block = self
+            .tx()
+            .get::<models::ParkedBlockRow>(columns::PARKED_BLOCKS, block_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "parked_blocks",
+                key: block_id.to_string(),
+            })?;
+
+        self.delete(columns::PARKED_BLOCKS, block_id.as_ref())?;
+
+        Ok((parked_block.block, parked_block.foreign_proposals))
+    }
+
+    fn parked_blocks_insert(
+        &mut self,
+        block: &Block,
+        foreign_proposals: &[ForeignProposal],
+    ) -> Result<(), StorageError> {
+        if self.tx().blocks_exists(block.id())? {
+            return Err(StorageError::QueryError {
+                reason: format!("Cannot park block {} that already exists in blocks table", block.id()),
+            });
+        }
+
+        // check if block already exists in parked_blocks
+        if self.tx().exists(columns::PARKED_BLOCKS, block.id().as_ref())? {
+            return Ok(());
+        }
+
+        let row = models::ParkedBlockRow {
+            block: block.clone(),
+            foreign_proposals: foreign_proposals.to_vec(),
+        };
+        self.put(columns::PARKED_BLOCKS, block.id().as_ref(), &row)
+    }
+
+    fn transaction_insert_row(&self, row: &models::TransactionRow) -> Result<(), StorageError> {
+        let tx_id = row.transaction.id();
+        self.put(columns::TRANSACTIONS, tx_id.as_ref(), row)?;
+        self.put_raw(
+            columns::TRANSACTIONS_BY_CREATED_AT,
+            &KeyBuilder::new().u64(row.seq).build(),
+            tx_id.as_ref(),
+        )
+    }
+
+    fn new_transaction_row(&self, rec: &TransactionRecord) -> Result<models::TransactionRow, StorageError> {
+        let finalized_at = rec
+            .finalized_time()
+            .map(|t| {
+                let now = OffsetDateTime::now_utc().saturating_sub(t.try_into()?);
+                Ok(PrimitiveDateTime::new(now.date(), now.time()))
+            })
+            .transpose()
+            .map_err(|e: time::error::ConversionRange| StorageError::QueryError {
+                reason: format!("Cannot convert finalize time into PrimitiveDateTime: {e}"),
+            })?;
+
+        Ok(models::TransactionRow {
+            transaction: rec.transaction().clone(),
+            result: rec.execution_result().cloned(),
+            resolved_inputs: rec.resolved_inputs().map(|i| i.to_vec()),
+            resulting_outputs: rec.resulting_outputs().map(|o| o.to_vec()),
+            final_decision: rec.final_decision(),
+            abort_details: rec.abort_reason().cloned(),
+            outcome: None,
+            finalized_at,
+            created_at: now(),
+            seq: self.next_seq(columns::TRANSACTIONS)?,
+        })
+    }
+
+    fn remove_pool_state_updates(&self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        let updates = self.tx().entries::<models::TransactionPoolStateUpdateRow>(
+            columns::TRANSACTION_POOL_STATE_UPDATES,
+            transaction_id.as_ref(),
+        )?;
+        for (key, update) in updates {
+            self.delete(columns::TRANSACTION_POOL_STATE_UPDATES, &key)?;
+            let index_key = KeyBuilder::new().bytes(update.block_id).bytes(&key).build();
+            self.delete(columns::TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK, &index_key)?;
+        }
+        Ok(())
+    }
+
+    fn substate_locks_delete(&self, lock_key: &[u8]) -> Result<(), StorageError> {
+        let Some(lock) = self
+            .tx()
+            .get::<models::SubstateLockRow>(columns::SUBSTATE_LOCKS, lock_key)?
+        else {
+            return Ok(());
+        };
+        let seq = &lock_key[lock_key.len() - 8..];
+        self.delete(columns::SUBSTATE_LOCKS, lock_key)?;
+        self.delete(
+            columns::SUBSTATE_LOCKS_BY_TRANSACTION,
+            &KeyBuilder::new().bytes(lock.transaction_id).bytes(seq).build(),
+        )?;
+        self.delete(
+            columns::SUBSTATE_LOCKS_BY_BLOCK,
+            &KeyBuilder::new().bytes(lock.block_id).bytes(seq).build(),
+        )
+    }
+}
+
+impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStoreWriteTransaction
+    for RocksDbStateStoreWriteTransaction<'tx, TAddr>
+{
+    type Addr = TAddr;
+
+    fn commit(&mut self) -> Result<(), StorageError> {
+        // Take so that we mark this transaction as complete in the drop impl
+        self.transaction
+            .take()
+            .unwrap()
+            .into_transaction()
+            .commit()
+            .map_err(|source| RocksDbStorageError::RocksDbError {
+                source,
+                operation: "commit",
+            })?;
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> Result<(), StorageError> {
+        // Take so that we mark this transaction as complete in the drop impl
+        self.transaction
+            .take()
+            .unwrap()
+            .into_transaction()
+            .rollback()
+            .map_err(|source| RocksDbStorageError::RocksDbError {
+                source,
+                operation: "rollback",
+            })?;
+        Ok(())
+    }
+
+    fn blocks_insert(&mut self, block: &Block) -> Result<(), StorageError> {
+        let block_id = block.id();
+        if self.tx().blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("blocks_insert: Block {block_id} already exists"),
+            });
+        }
+
+        let block_time = self
+            .tx()
+            .get_block_row(block.justify().block_id())?
+            .map(|justify| block.timestamp().saturating_sub(justify.header.timestamp()));
+
+        let row = models::BlockRow {
+            header: block.header().clone(),
+            commands: block.commands().clone(),
+            is_committed: false,
+            is_justified: block.is_justified(),
+            block_time,
+            created_at: now(),
+            seq: self.next_seq(columns::BLOCKS)?,
+        };
+
+        self.put(columns::BLOCKS, block_id.as_ref(), &row)?;
+        self.put_raw(
+            columns::BLOCKS_BY_PARENT,
+            &KeyBuilder::new().bytes(block.parent()).bytes(block_id).build(),
+            &[],
+        )?;
+        self.put_raw(
+            columns::BLOCKS_BY_HEIGHT,
+            &KeyBuilder::new()
+                .u64(block.epoch().as_u64())
+                .u64(block.height().as_u64())
+                .bytes(block_id)
+                .build(),
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    fn blocks_delete(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let row = self
+            .tx()
+            .get_block_row(block_id)?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "blocks",
+                key: block_id.to_string(),
+            })?;
+
+        self.put(columns::DIAGNOSTIC_DELETED_BLOCKS, block_id.as_ref(), &row)?;
+        self.delete(columns::BLOCKS, block_id.as_ref())?;
+        self.delete(
+            columns::BLOCKS_BY_PARENT,
+            &KeyBuilder::new().bytes(row.header.parent()).bytes(block_id).build(),
+        )?;
+        self.delete(
+            columns::BLOCKS_BY_HEIGHT,
+            &KeyBuilder::new()
+                .u64(row.header.epoch().as_u64())
+                .u64(row.header.height().as_u64())
+                .bytes(block_id)
+                .build(),
+        )?;
+
+        Ok(())
+    }
+
+    fn blocks_set_flags(
+        &mut self,
+        block_id: &BlockId,
+        is_committed: Option<bool>,
+        is_justified: Option<bool>,
+    ) -> Result<(), StorageError> {
+        let Some(mut row) = self.tx().get_block_row(block_id)? else {
+            return Ok(());
+        };
+
+        if let Some(is_committed) = is_committed {
+            if is_committed && !row.is_committed {
+                let seq = self.next_seq(columns::COMMITTED_BLOCKS)?;
+                self.put(columns::COMMITTED_BLOCKS, &KeyBuilder::new().u64(seq).build(), block_id)?;
+            }
+            if !is_committed && row.is_committed {
+                let entries = self.tx().entries::<BlockId>(columns::COMMITTED_BLOCKS, &[])?;
+                for (key, id) in entries {
+                    if id == *block_id {
+                        self.delete(columns::COMMITTED_BLOCKS, &key)?;
+                    }
+                }
+            }
+            row.is_committed = is_committed;
+        }
+        if let Some(is_justified) = is_justified {
+            row.is_justified = is_justified;
+        }
+
+        self.put(columns::BLOCKS, block_id.as_ref(), &row)
+    }
+
+    fn block_diffs_insert(&mut self, block_id: &BlockId, changes: &[SubstateChange]) -> Result<(), StorageError> {
+        for change in changes {
+            let seq = self.next_seq(columns::BLOCK_DIFFS)?;
+            let row = models::BlockDiffRow::from_change(*block_id, change);
+            self.put(
+                columns::BLOCK_DIFFS,
+                &KeyBuilder::new().bytes(block_id).u64(seq).build(),
+                &row,
+            )?;
+            self.put_raw(
+                columns::BLOCK_DIFFS_BY_SUBSTATE,
+                &KeyBuilder::new()
+                    .substate_id(change.versioned_substate_id().substate_id())
+                    .u64(seq)
+                    .build(),
+                block_id.as_ref(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn block_diffs_remove(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let diffs = self
+            .tx()
+            .entries::<models::BlockDiffRow>(columns::BLOCK_DIFFS, block_id.as_ref())?;
+        for (key, diff) in diffs {
+            let seq = &key[key.len() - 8..];
+            self.delete(
+                columns::BLOCK_DIFFS_BY_SUBSTATE,
+                &KeyBuilder::new().substate_id(diff.id.substate_id()).bytes(seq).build(),
+            )?;
+            self.delete(columns::BLOCK_DIFFS, &key)?;
+        }
+
+        Ok(())
+    }
+
+    fn quorum_certificates_insert(&mut self, qc: &QuorumCertificate) -> Result<(), StorageError> {
+        if self.tx().exists(columns::QUORUM_CERTIFICATES, qc.id().as_ref())? {
+            return Err(StorageError::QueryError {
+                reason: format!("quorum_certificates_insert: QC {} already exists", qc.id()),
+            });
+        }
+
+        self.put(columns::QUORUM_CERTIFICATES, qc.id().as_ref(), qc)?;
+        self.put(
+            columns::QUORUM_CERTIFICATES_BY_BLOCK,
+            &KeyBuilder::new().bytes(qc.block_id()).bytes(qc.id()).build(),
+            qc.id(),
+        )?;
+
+        Ok(())
+    }
+
+    fn quorum_certificates_set_shares_processed(&mut self, qc_id: &QcId) -> Result<(), StorageError> {
+        let mut json = self
+            .tx()
+            .get::<serde_json::Value>(columns::QUORUM_CERTIFICATES, qc_id.as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "quorum_certificate",
+                key: qc_id.to_string(),
+            })?;
+
+        json["is_shares_processed"] = serde_json::Value::Bool(true);
+
+        self.put(columns::QUORUM_CERTIFICATES, qc_id.as_ref(), &json)
+    }
+
+    fn last_sent_vote_set(&mut self, last_sent_vote: &LastSentVote) -> Result<(), StorageError> {
+        let row = models::LastSentVoteRow {
+            epoch: last_sent_vote.epoch,
+            block_id: last_sent_vote.block_id,
+            block_height: last_sent_vote.block_height,
+            decision: last_sent_vote.decision,
+            signature: last_sent_vote.signature.clone(),
+        };
+        self.bookkeeping_insert(columns::LAST_SENT_VOTE, None, &row)
+    }
+
+    fn last_voted_set(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        let row = models::BlockRefRow {
+            block_id: last_voted.block_id,
+            height: last_voted.height,
+            epoch: last_voted.epoch,
+        };
+        self.bookkeeping_insert(columns::LAST_VOTED, None, &row)
+    }
+
+    fn last_votes_unset(&mut self, last_voted: &LastVoted) -> Result<(), StorageError> {
+        self.bookkeeping_remove(columns::LAST_VOTED, &last_voted.block_id, last_voted.height)
+    }
+
+    fn last_executed_set(&mut self, last_exec: &LastExecuted) -> Result<(), StorageError> {
+        let row = models::BlockRefRow {
+            block_id: last_exec.block_id,
+            height: last_exec.height,
+            epoch: last_exec.epoch,
+        };
+        self.bookkeeping_insert(columns::LAST_EXECUTED, None, &row)
+    }
+
+    fn last_proposed_set(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        let row = models::BlockRefRow {
+            block_id: last_proposed.block_id,
+            height: last_proposed.height,
+            epoch: last_proposed.epoch,
+        };
+        self.bookkeeping_insert(columns::LAST_PROPOSED, None, &row)
+    }
+
+    fn last_proposed_unset(&mut self, last_proposed: &LastProposed) -> Result<(), StorageError> {
+        self.bookkeeping_remove(columns::LAST_PROPOSED, &last_proposed.block_id, last_proposed.height)
+    }
+
+    fn leaf_block_set(&mut self, leaf_node: &LeafBlock) -> Result<(), StorageError> {
+        let row = models::BlockRefRow {
+            block_id: leaf_node.block_id,
+            height: leaf_node.height,
+            epoch: leaf_node.epoch,
+        };
+        self.bookkeeping_insert(columns::LEAF_BLOCKS, Some(leaf_node.epoch), &row)
+    }
+
+    fn locked_block_set(&mut self, locked_block: &LockedBlock) -> Result<(), StorageError> {
+        let row = models::BlockRefRow {
+            block_id: locked_block.block_id,
+            height: locked_block.height,
+            epoch: locked_block.epoch,
+        };
+        self.bookkeeping_insert(columns::LOCKED_BLOCK, Some(locked_block.epoch), &row)
+    }
+
+    fn high_qc_set(&mut self, high_qc: &HighQc) -> Result<(), StorageError> {
+        let row = models::HighQcRow {
+            block_id: high_qc.block_id,
+            block_height: high_qc.block_height(),
+            epoch: high_qc.epoch(),
+            qc_id: high_qc.qc_id,
+        };
+        self.bookkeeping_insert(columns::HIGH_QCS, Some(high_qc.epoch()), &row)
+    }
+
+    fn foreign_proposals_upsert(
+        &mut self,
+        foreign_proposal: &ForeignProposal,
+        proposed_in_block: Option<BlockId>,
+    ) -> Result<(), StorageError> {
+        let block_id = foreign_proposal.block().id();
+
+        if !self.tx().foreign_proposals_exists(block_id)? {
+            let row = models::ForeignProposalRow {
+                block: foreign_proposal.block().clone(),
+                block_pledge: foreign_proposal.block_pledge().clone(),
+                justify_qc_id: *foreign_proposal.justify_qc().id(),
+                proposed_in_block: None,
+                proposed_in_block_height: None,
+                status: ForeignProposalStatus::New,
+            };
+            self.put(columns::FOREIGN_PROPOSALS, block_id.as_ref(), &row)?;
+        }
+
+        if let Some(proposed_in_block) = proposed_in_block {
+            self.foreign_proposals_set_proposed_in(block_id, &proposed_in_block)?;
+        }
+
+        Ok(())
+    }
+
+    fn foreign_proposals_delete(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        self.delete(columns::FOREIGN_PROPOSALS, block_id.as_ref())
+    }
+
+    fn foreign_proposals_delete_in_epoch(&mut self, epoch: Epoch) -> Result<(), StorageError> {
+        let proposals = self
+            .tx()
+            .entries::<models::ForeignProposalRow>(columns::FOREIGN_PROPOSALS, &[])?;
+        for (key, row) in proposals {
+            if row.block.epoch() == epoch {
+                self.delete(columns::FOREIGN_PROPOSALS, &key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn foreign_proposals_set_status(
+        &mut self,
+        block_id: &BlockId,
+        status: ForeignProposalStatus,
+    ) -> Result<(), StorageError> {
+        self.update_foreign_proposal(block_id, |row| row.status = status)
+    }
+
+    fn foreign_proposals_set_proposed_in(
+        &mut self,
+        block_id: &BlockId,
+        proposed_in_block: &BlockId,
+    ) -> Result<(), StorageError> {
+        let proposed_in_block_height = self.tx().get_block_height(proposed_in_block)?;
+        self.update_foreign_proposal(block_id, |row| {
+            row.proposed_in_block = Some(*proposed_in_block);
+            row.proposed_in_block_height = proposed_in_block_height;
+            row.status = ForeignProposalStatus::Proposed;
+        })
+    }
+
+    fn foreign_proposals_clear_proposed_in(&mut self, proposed_in_block: &BlockId) -> Result<(), StorageError> {
+        let proposals = self
+            .tx()
+            .entries::<models::ForeignProposalRow>(columns::FOREIGN_PROPOSALS, &[])?;
+        for (key, mut row) in proposals {
+            if row.proposed_in_block.as_ref() != Some(proposed_in_block) {
+                continue;
+            }
+            row.proposed_in_block = None;
+            row.proposed_in_block_height = None;
+            row.status = ForeignProposalStatus::New;
+            self.put(columns::FOREIGN_PROPOSALS, &key, &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn foreign_send_counters_set(
+        &mut self,
+        foreign_send_counter: &ForeignSendCounters,
+        block_id: &BlockId,
+    ) -> Result<(), StorageError> {
+        let row = models::CountersRow {
+            counters: foreign_send_counter.counters.clone(),
+        };
+        self.put(columns::FOREIGN_SEND_COUNTERS, block_id.as_ref(), &row)
+    }
+
+    fn foreign_receive_counters_set(
+        &mut self,
+        foreign_receive_counter: &ForeignReceiveCounters,
+    ) -> Result<(), StorageError> {
+        let row = models::CountersRow {
+            counters: foreign_receive_counter.counters.clone(),
+        };
+        self.bookkeeping_insert(columns::FOREIGN_RECEIVE_COUNTERS, None, &row)
+    }
+
+    fn transactions_insert(&mut self, tx_rec: &TransactionRecord) -> Result<(), StorageError> {
+        if self.tx().transactions_exists(tx_rec.id())? {
+            return Err(StorageError::QueryError {
+                reason: format!("transactions_insert: Transaction {} already exists", tx_rec.id()),
+            });
+        }
+
+        let row = self.new_transaction_row(tx_rec)?;
+        self.transaction_insert_row(&row)
+    }
+
+    fn transactions_update(&mut self, transaction_rec: &TransactionRecord) -> Result<(), StorageError> {
+        let transaction = transaction_rec.transaction();
+        let mut row = self
+            .tx()
+            .get::<models::TransactionRow>(columns::TRANSACTIONS, transaction.id().as_ref())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "transaction",
+                key: transaction.id().to_string(),
+            })?;
+
+        row.transaction = transaction.clone();
+        row.result = transaction_rec.execution_result().cloned();
+        row.resulting_outputs = transaction_rec.resulting_outputs().map(|o| o.to_vec());
+        row.resolved_inputs = transaction_rec.resolved_inputs().map(|i| i.to_vec());
+        row.final_decision = transaction_rec.final_decision();
+        row.finalized_at = transaction_rec.final_decision().map(|_| now());
+        row.abort_details = transaction_rec.abort_reason.clone();
+
+        self.put(columns::TRANSACTIONS, transaction.id().as_ref(), &row)
+    }
+
+    fn transactions_save_all<'a, I: IntoIterator<Item = &'a TransactionRecord>>(
+        &mut self,
+        txs: I,
+    ) -> Result<(), StorageError> {
+        for rec in txs {
+            if self.tx().transactions_exists(rec.id())? {
+                continue;
+            }
+            let mut row = self.new_transaction_row(rec)?;
+            // Matches the SQLite implementation, which does not save these fields
+            row.final_decision = None;
+            row.finalized_at = None;
+            row.abort_details = None;
+            self.transaction_insert_row(&row)?;
+        }
+
+        Ok(())
+    }
+
+    fn transactions_finalize_all<'a, I: IntoIterator<Item = &'a TransactionPoolRecord>>(
+        &mut self,
+        block_id: BlockId,
+        transactions: I,
+    ) -> Result<(), StorageError> {
+        if !self.tx().blocks_exists(&block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "transactions_finalize_all: Cannot finalize transactions for non-existent block {}",
+                    block_id
+                ),
+            });
+        }
+
+        for rec in transactions {
+            let exec = self
+                .tx()
+                .transaction_executions_get_pending_for_block(rec.transaction_id(), &block_id)
+                .optional()?
+                .ok_or_else(|| StorageError::DataInconsistency {
+                    details: format!(
+                        "transactions_finalize_all: No pending execution for transaction {}",
+                        rec.transaction_id()
+                    ),
+                })?;
+
+            let Some(mut row) = self
+                .tx()
+                .get::<models::TransactionRow>(columns::TRANSACTIONS, rec.transaction_id().as_ref())?
+            else {
+                continue;
+            };
+
+            row.resolved_inputs = Some(exec.resolved_inputs().to_vec());
+            row.resulting_outputs = Some(exec.resulting_outputs().to_vec());
+            row.result = Some(exec.result().clone());
+            row.final_decision = Some(rec.current_decision());
+            row.abort_details = exec.abort_reason().cloned();
+            row.outcome = Some(exec.result().finalize.result.to_string());
+            row.finalized_at = Some(now());
+
+            self.put(columns::TRANSACTIONS, rec.transaction_id().as_ref(), &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_idempotency_keys_insert_all<'a, I: IntoIterator<Item = (&'a IdempotencyKey, &'a TransactionId)>>(
+        &mut self,
+        block_id: &BlockId,
+        epoch: Epoch,
+        idempotency_keys: I,
+    ) -> Result<(), StorageError> {
+        for (idempotency_key, transaction_id) in idempotency_keys {
+            let seq = self.next_seq(columns::TRANSACTION_IDEMPOTENCY_KEYS)?;
+            let key = KeyBuilder::new()
+                .bytes(idempotency_key)
+                .u64(epoch.as_u64())
+                .u64(seq)
+                .build();
+            let row = models::TransactionIdempotencyKeyRow {
+                transaction_id: *transaction_id,
+                block_id: *block_id,
+                is_committed: false,
+            };
+            self.put(columns::TRANSACTION_IDEMPOTENCY_KEYS, &key, &row)?;
+            self.put_raw(
+                columns::TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK,
+                &KeyBuilder::new().bytes(block_id).u64(seq).build(),
+                &key,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_idempotency_keys_set_committed(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let entries = self
+            .tx()
+            .raw_entries(columns::TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK, block_id.as_ref())?;
+        for (_, key) in entries {
+            let Some(mut row) = self
+                .tx()
+                .get::<models::TransactionIdempotencyKeyRow>(columns::TRANSACTION_IDEMPOTENCY_KEYS, &key)?
+            else {
+                continue;
+            };
+            row.is_committed = true;
+            self.put(columns::TRANSACTION_IDEMPOTENCY_KEYS, &key, &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_idempotency_keys_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let entries = self
+            .tx()
+            .raw_entries(columns::TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK, block_id.as_ref())?;
+        for (index_key, key) in entries {
+            self.delete(columns::TRANSACTION_IDEMPOTENCY_KEYS, &key)?;
+            self.delete(columns::TRANSACTION_IDEMPOTENCY_KEYS_BY_BLOCK, &index_key)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_executions_insert_or_ignore(
+        &mut self,
+        transaction_execution: &BlockTransactionExecution,
+    ) -> Result<bool, StorageError> {
+        let block_id = transaction_execution.block_id();
+        let transaction_id = transaction_execution.transaction_id();
+
+        let index_key = KeyBuilder::new().bytes(block_id).bytes(transaction_id).build();
+        if self.tx().exists(columns::TRANSACTION_EXECUTIONS_BY_BLOCK, &index_key)? {
+            return Ok(false);
+        }
+
+        let row = models::TransactionExecutionRow {
+            block_id: *block_id,
+            transaction_id: *transaction_id,
+            result: transaction_execution.result().clone(),
+            resolved_inputs: transaction_execution.resolved_inputs().to_vec(),
+            resulting_outputs: transaction_execution.resulting_outputs().to_vec(),
+            abort_reason: transaction_execution.abort_reason().cloned(),
+        };
+
+        let seq = self.next_seq(columns::TRANSACTION_EXECUTIONS)?;
+        self.put(
+            columns::TRANSACTION_EXECUTIONS,
+            &KeyBuilder::new().bytes(transaction_id).u64(seq).build(),
+            &row,
+        )?;
+        self.put_raw(columns::TRANSACTION_EXECUTIONS_BY_BLOCK, &index_key, &seq.to_be_bytes())?;
+
+        Ok(true)
+    }
+
+    fn transaction_executions_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let entries = self
+            .tx()
+            .raw_entries(columns::TRANSACTION_EXECUTIONS_BY_BLOCK, block_id.as_ref())?;
+        for (index_key, seq) in entries {
+            let transaction_id = &index_key[block_id.as_ref().len()..];
+            self.delete(
+                columns::TRANSACTION_EXECUTIONS,
+                &KeyBuilder::new().bytes(transaction_id).bytes(&seq).build(),
+            )?;
+            self.delete(columns::TRANSACTION_EXECUTIONS_BY_BLOCK, &index_key)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_insert_new(
+        &mut self,
+        tx_id: TransactionId,
+        decision: Decision,
+        is_ready: bool,
+    ) -> Result<(), StorageError> {
+        if self.tx().transaction_pool_exists(&tx_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("transaction_pool_insert: Transaction {tx_id} already exists in the pool"),
+            });
+        }
+
+        let now = now();
+        let row = models::TransactionPoolRow {
+            transaction_id: tx_id,
+            original_decision: decision,
+            local_decision: None,
+            remote_decision: None,
+            evidence: Evidence::default(),
+            transaction_fee: 0,
+            leader_fee: None,
+            stage: TransactionPoolStage::New.to_string(),
+            pending_stage: None,
+            is_ready,
+            confirm_stage: None,
+            updated_at: now,
+            created_at: now,
+        };
+
+        self.put(columns::TRANSACTION_POOL, tx_id.as_ref(), &row)
+    }
+
+    fn transaction_pool_add_pending_update(
+        &mut self,
+        block_id: &BlockId,
+        update: &TransactionPoolStatusUpdate,
+    ) -> Result<(), StorageError> {
+        let block_height = self.require_block_height(block_id, "transaction_pool_add_pending_update")?;
+        let transaction_id = update.transaction_id();
+
+        let row = models::TransactionPoolStateUpdateRow {
+            block_id: *block_id,
+            block_height,
+            transaction_id: *transaction_id,
+            stage: update.stage().to_string(),
+            evidence: update.evidence().clone(),
+            is_ready: update.is_ready(),
+            local_decision: update.decision(),
+            transaction_fee: update.transaction_fee(),
+            leader_fee: update.leader_fee().cloned(),
+            remote_decision: update.remote_decision(),
+            is_applied: false,
+        };
+
+        let seq = self.next_seq(columns::TRANSACTION_POOL_STATE_UPDATES)?;
+        let update_key = KeyBuilder::new().bytes(transaction_id).u64(seq).build();
+        self.put(columns::TRANSACTION_POOL_STATE_UPDATES, &update_key, &row)?;
+        self.put_raw(
+            columns::TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK,
+            &KeyBuilder::new().bytes(block_id).bytes(&update_key).build(),
+            &[],
+        )?;
+
+        // Set is_ready and pending_stage to the updated values. This allows has_uncommitted_transactions to return an
+        // accurate value without querying records in the updates table.
+        if let Some(mut pool_row) = self
+            .tx()
+            .get::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, transaction_id.as_ref())?
+        {
+            pool_row.is_ready = update.is_ready_now();
+            pool_row.pending_stage = Some(update.stage().to_string());
+            self.put(columns::TRANSACTION_POOL, transaction_id.as_ref(), &pool_row)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_remove(&mut self, transaction_id: &TransactionId) -> Result<(), StorageError> {
+        if !self.tx().transaction_pool_exists(transaction_id)? {
+            return Err(StorageError::NotFound {
+                item: "transaction",
+                key: transaction_id.to_string(),
+            });
+        }
+
+        self.delete(columns::TRANSACTION_POOL, transaction_id.as_ref())?;
+        self.remove_pool_state_updates(transaction_id)
+    }
+
+    fn transaction_pool_remove_all<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        transaction_ids: I,
+    ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
+        let transaction_ids = transaction_ids.into_iter().collect::<HashSet<_>>();
+
+        let mut txs = Vec::with_capacity(transaction_ids.len());
+        for transaction_id in &transaction_ids {
+            if let Some(row) = self
+                .tx()
+                .get::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, transaction_id.as_ref())?
+            {
+                txs.push(row);
+            }
+        }
+
+        if txs.len() != transaction_ids.len() {
+            return Err(RocksDbStorageError::NotAllItemsFound {
+                items: "Transactions",
+                operation: "transaction_pool_remove_all",
+                details: format!(
+                    "Found {} transactions, but {} were queried",
+                    txs.len(),
+                    transaction_ids.len()
+                ),
+            }
+            .into());
+        }
+
+        for transaction_id in transaction_ids {
+            self.delete(columns::TRANSACTION_POOL, transaction_id.as_ref())?;
+            self.remove_pool_state_updates(transaction_id)?;
+        }
+
+        txs.into_iter().map(|tx| tx.try_convert(None)).collect()
+    }
+
+    fn transaction_pool_confirm_all_transitions(&mut self, new_locked_block: &LockedBlock) -> Result<(), StorageError> {
+        let update_keys = self.tx().keys(
+            columns::TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK,
+            new_locked_block.block_id().as_ref(),
+        )?;
+
+        let mut updates = Vec::with_capacity(update_keys.len());
+        for index_key in update_keys {
+            let update_key = &index_key[new_locked_block.block_id().as_ref().len()..];
+            let Some(update) = self
+                .tx()
+                .get::<models::TransactionPoolStateUpdateRow>(columns::TRANSACTION_POOL_STATE_UPDATES, update_key)?
+            else {
+                continue;
+            };
+            if !update.is_applied {
+                updates.push((update_key.to_vec(), update));
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "transaction_pool_confirm_all_transitions: new_locked_block={}, {} updates",  new_locked_block, updates.len()
+        );
+
+        for (update_key, update) in &updates {
+            if update.block_height <= new_locked_block.height() {
+                let mut applied = update.clone();
+                applied.is_applied = true;
+                self.put(columns::TRANSACTION_POOL_STATE_UPDATES, update_key, &applied)?;
+            }
+        }
+
+        for (_, update) in updates {
+            let Some(mut row) = self
+                .tx()
+                .get::<models::TransactionPoolRow>(columns::TRANSACTION_POOL, update.transaction_id.as_ref())?
+            else {
+                continue;
+            };
+
+            let confirm_stage = match update.stage.as_str() {
+                "LocalPrepared" => Some(TransactionPoolConfirmedStage::ConfirmedPrepared.to_string()),
+                "LocalAccepted" => Some(TransactionPoolConfirmedStage::ConfirmedAccepted.to_string()),
+                _ => None,
+            };
+
+            row.stage = update.stage;
+            row.local_decision = Some(update.local_decision);
+            row.transaction_fee = update.transaction_fee;
+            // Only update if Some. This isn't technically necessary since leader fee should be in every update.
+            if let Some(leader_fee) = update.leader_fee {
+                row.leader_fee = Some(leader_fee);
+            }
+            row.evidence = update.evidence;
+            row.is_ready = update.is_ready;
+            if let Some(confirm_stage) = confirm_stage {
+                row.confirm_stage = Some(confirm_stage);
+            }
+            row.remote_decision = update.remote_decision;
+            row.updated_at = now();
+
+            self.put(columns::TRANSACTION_POOL, update.transaction_id.as_ref(), &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn transaction_pool_state_updates_remove_any_by_block_id(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<(), StorageError> {
+        let index_keys = self
+            .tx()
+            .keys(columns::TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK, block_id.as_ref())?;
+        for index_key in index_keys {
+            self.delete(
+                columns::TRANSACTION_POOL_STATE_UPDATES,
+                &index_key[block_id.as_ref().len()..],
+            )?;
+            self.delete(columns::TRANSACTION_POOL_STATE_UPDATES_BY_BLOCK, &index_key)?;
+        }
+
+        Ok(())
+    }
+
+    fn missing_transactions_insert<'a, IMissing: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        block: &Block,
+        foreign_proposals: &[ForeignProposal],
+        missing_transaction_ids: IMissing,
+    ) -> Result<(), StorageError> {
+        self.parked_blocks_insert(block, foreign_proposals)?;
+
+        let height = block.height().as_u64();
+        for tx_id in missing_transaction_ids {
+            self.put_raw(
+                columns::MISSING_TRANSACTIONS,
+                &KeyBuilder::new().u64(height).bytes(block.id()).bytes(tx_id).build(),
+                &[],
+            )?;
+            self.put_raw(
+                columns::MISSING_TRANSACTIONS_BY_TRANSACTION,
+                &KeyBuilder::new().bytes(tx_id).u64(height).bytes(block.id()).build(),
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn missing_transactions_remove(
+        &mut self,
+        height: NodeHeight,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<(Block, Vec<ForeignProposal>)>, StorageError> {
+        let prefix = KeyBuilder::new().bytes(transaction_id).u64(height.as_u64()).build();
+        let Some(key) = self.tx().first_key(
+            columns::MISSING_TRANSACTIONS_BY_TRANSACTION,
+            &prefix,
+            Direction::Forward,
+        )?
+        else {
+            return Ok(None);
+        };
+        let block_id = deserialize_try_from::<BlockId>(&key[prefix.len()..])?;
+
+        // Remove the transaction for all blocks
+        let entries = self
+            .tx()
+            .keys(columns::MISSING_TRANSACTIONS_BY_TRANSACTION, transaction_id.as_ref())?;
+        for key in entries {
+            let suffix = &key[transaction_id.as_ref().len()..];
+            self.delete(
+                columns::MISSING_TRANSACTIONS,
+                &KeyBuilder::new().bytes(suffix).bytes(transaction_id).build(),
+            )?;
+            self.delete(columns::MISSING_TRANSACTIONS_BY_TRANSACTION, &key)?;
+        }
+
+        let block_prefix = KeyBuilder::new().u64(height.as_u64()).bytes(block_id).build();
+        if self.tx().has_prefix(columns::MISSING_TRANSACTIONS, &block_prefix)? {
+            return Ok(None);
+        }
+
+        // delete all entries that are for previous heights
+        let end = KeyBuilder::new().u64(height.as_u64()).build();
+        let mut stale = Vec::new();
+        self.tx().iterate(
+            columns::MISSING_TRANSACTIONS,
+            &[],
+            None,
+            Direction::Forward,
+            |key, _| {
+                if key >= end.as_slice() {
+                    return Ok(false);
+                }
+                stale.push(key.to_vec());
+                Ok(true)
+            },
+        )?;
+        for key in stale {
+            // height (8) ++ block_id (32) ++ transaction_id (32)
+            let (height_and_block, tx_id) = key.split_at(40);
+            self.delete(
+                columns::MISSING_TRANSACTIONS_BY_TRANSACTION,
+                &KeyBuilder::new().bytes(tx_id).bytes(height_and_block).build(),
+            )?;
+            self.delete(columns::MISSING_TRANSACTIONS, &key)?;
+        }
+
+        let block = self.parked_blocks_remove(&block_id)?;
+        Ok(Some(block))
+    }
+
+    fn foreign_parked_blocks_insert(&mut self, park_block: &ForeignParkedProposal) -> Result<(), StorageError> {
+        let block_id = park_block.block().id();
+        if self.tx().foreign_parked_blocks_exists(block_id)? {
+            return Err(StorageError::QueryError {
+                reason: format!("foreign_parked_blocks_insert: Block {block_id} is already parked"),
+            });
+        }
+
+        let proposal = ForeignProposal::new(
+            park_block.block().clone(),
+            park_block.block_pledge().clone(),
+            park_block.justify_qc().clone(),
+        );
+        self.put(columns::FOREIGN_PARKED_BLOCKS, block_id.as_ref(), &proposal)
+    }
+
+    fn foreign_parked_blocks_insert_missing_transactions<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        park_block_id: &BlockId,
+        missing_transaction_ids: I,
+    ) -> Result<(), StorageError> {
+        if !self.tx().foreign_parked_blocks_exists(park_block_id)? {
+            return Err(StorageError::NotFound {
+                item: "foreign_parked_blocks",
+                key: park_block_id.to_string(),
+            });
+        }
+
+        for tx_id in missing_transaction_ids {
+            self.put_raw(
+                columns::FOREIGN_MISSING_TRANSACTIONS,
+                &KeyBuilder::new().bytes(park_block_id).bytes(tx_id).build(),
+                &[],
+            )?;
+            self.put_raw(
+                columns::FOREIGN_MISSING_TRANSACTIONS_BY_TRANSACTION,
+                &KeyBuilder::new().bytes(tx_id).bytes(park_block_id).build(),
+                &[],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn foreign_parked_blocks_remove_all_by_transaction(
+        &mut self,
+        transaction_id: &TransactionId,
+    ) -> Result<Vec<ForeignParkedProposal>, StorageError> {
+        let keys = self.tx().keys(
+            columns::FOREIGN_MISSING_TRANSACTIONS_BY_TRANSACTION,
+            transaction_id.as_ref(),
+        )?;
+
+        if keys.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut removed_block_ids = Vec::with_capacity(keys.len());
+        for key in keys {
+            let block_id = deserialize_try_from::<BlockId>(&key[transaction_id.as_ref().len()..])?;
+            self.delete(
+                columns::FOREIGN_MISSING_TRANSACTIONS,
+                &KeyBuilder::new().bytes(block_id).bytes(transaction_id).build(),
+            )?;
+            self.delete(columns::FOREIGN_MISSING_TRANSACTIONS_BY_TRANSACTION, &key)?;
+            removed_block_ids.push(block_id);
+        }
+
+        let mut blocks = Vec::new();
+        for block_id in removed_block_ids {
+            // If there are still missing transactions for the parked block, it remains parked
+            if self
+                .tx()
+                .has_prefix(columns::FOREIGN_MISSING_TRANSACTIONS, block_id.as_ref())?
+            {
+                continue;
+            }
+            let Some(proposal) = self
+                .tx()
+                .get::<ForeignProposal>(columns::FOREIGN_PARKED_BLOCKS, block_id.as_ref())?
+            else {
+                continue;
+            };
+            self.delete(columns::FOREIGN_PARKED_BLOCKS, block_id.as_ref())?;
+            blocks.push(ForeignParkedProposal::new(proposal));
+        }
+
+        Ok(blocks)
+    }
+
+    fn votes_insert(&mut self, vote: &Vote) -> Result<(), StorageError> {
+        let key = KeyBuilder::new()
+            .bytes(vote.block_id)
+            .bytes(vote.sender_leaf_hash)
+            .build();
+        if self.tx().exists(columns::VOTES, &key)? {
+            return Err(StorageError::QueryError {
+                reason: format!("votes_insert: vote {} already exists", vote.calculate_hash()),
+            });
+        }
+
+        self.put(columns::VOTES, &key, vote)?;
+        self.put_raw(
+            columns::VOTES_BY_SENDER,
+            &KeyBuilder::new()
+                .u64(vote.epoch.as_u64())
+                .bytes(vote.sender_leaf_hash)
+                .bytes(vote.block_id)
+                .build(),
+            &[],
+        )
+    }
+
+    fn votes_delete_all(&mut self) -> Result<(), StorageError> {
+        self.delete_prefix(columns::VOTES, &[])?;
+        self.delete_prefix(columns::VOTES_BY_SENDER, &[])?;
+        Ok(())
+    }
+
+    fn substate_locks_insert_all<'a, I: IntoIterator<Item = (&'a SubstateId, &'a Vec<SubstateLock>)>>(
+        &mut self,
+        block_id: &BlockId,
+        locks: I,
+    ) -> Result<(), StorageError> {
+        for (id, locks) in locks {
+            for lock in locks {
+                let seq = self.next_seq(columns::SUBSTATE_LOCKS)?;
+                let lock_key = KeyBuilder::new().substate_id(id).u64(seq).build();
+                let row = models::SubstateLockRow {
+                    block_id: *block_id,
+                    substate_id: id.clone(),
+                    version: lock.version(),
+                    transaction_id: *lock.transaction_id(),
+                    lock_type: lock.substate_lock(),
+                    is_local_only: lock.is_local_only(),
+                };
+                self.put(columns::SUBSTATE_LOCKS, &lock_key, &row)?;
+                self.put_raw(
+                    columns::SUBSTATE_LOCKS_BY_TRANSACTION,
+                    &KeyBuilder::new().bytes(lock.transaction_id()).u64(seq).build(),
+                    &lock_key,
+                )?;
+                self.put_raw(
+                    columns::SUBSTATE_LOCKS_BY_BLOCK,
+                    &KeyBuilder::new().bytes(block_id).u64(seq).build(),
+                    &lock_key,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn substate_locks_remove_many_for_transactions<'a, I: Iterator<Item = &'a TransactionId>>(
+        &mut self,
+        mut transaction_ids: Peekable<I>,
+    ) -> Result<(), StorageError> {
+        if transaction_ids.peek().is_none() {
+            return Ok(());
+        }
+
+        for transaction_id in transaction_ids {
+            let entries = self
+                .tx()
+                .raw_entries(columns::SUBSTATE_LOCKS_BY_TRANSACTION, transaction_id.as_ref())?;
+            for (_, lock_key) in entries {
+                self.substate_locks_delete(&lock_key)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn substate_locks_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        let entries = self
+            .tx()
+            .raw_entries(columns::SUBSTATE_LOCKS_BY_BLOCK, block_id.as_ref())?;
+        for (_, lock_key) in entries {
+            self.substate_locks_delete(&lock_key)?;
+        }
+
+        Ok(())
+    }
+
+    fn substates_write_many(&mut self, writes: &[SubstateWrite]) -> Result<(), StorageError> {
+        if writes.is_empty() {
+            return Ok(());
+        }
+
+        for write in writes {
+            let SubstateWrite::Create(substate) = write else {
+                continue;
+            };
+            if substate.is_destroyed() {
+                return Err(StorageError::QueryError {
+                    reason: format!(
+                        "calling substates_write_many with a destroyed SubstateRecord is not valid. substate_id = {}",
+                        substate.substate_id
+                    ),
+                });
+            }
+
+            let address = substate.to_substate_address();
+            if self.tx().exists(columns::SUBSTATES, address.as_ref())? {
+                return Err(StorageError::QueryError {
+                    reason: format!("substates_write_many: substate {address} already exists"),
+                });
+            }
+
+            let seq = self.next_seq(columns::SUBSTATES)?;
+            let row = models::SubstateRow {
+                record: substate.clone(),
+                seq,
+            };
+            self.put(columns::SUBSTATES, address.as_ref(), &row)?;
+            self.put_raw(
+                columns::SUBSTATES_BY_ID,
+                &KeyBuilder::new()
+                    .substate_id(&substate.substate_id)
+                    .u32(substate.version)
+                    .build(),
+                address.as_ref(),
+            )?;
+            self.put_raw(
+                columns::SUBSTATES_BY_SEQ,
+                &KeyBuilder::new().u64(seq).build(),
+                address.as_ref(),
+            )?;
+            self.put_raw(
+                columns::SUBSTATES_BY_TRANSACTION,
+                &KeyBuilder::new()
+                    .bytes(substate.created_by_transaction)
+                    .u64(seq)
+                    .build(),
+                address.as_ref(),
+            )?;
+        }
+
+        // Substates are destroyed after all substates are created, since a substate may be created and destroyed in
+        // the same batch.
+        for write in writes {
+            let SubstateWrite::Destroy {
+                versioned_substate_id,
+                shard,
+                epoch,
+                destroyed_by_block,
+                destroyed_by_transaction,
+                destroyed_justify,
+            } = write
+            else {
+                continue;
+            };
+
+            let address = versioned_substate_id.to_substate_address();
+            let Some(mut row) = self
+                .tx()
+                .get::<models::SubstateRow>(columns::SUBSTATES, address.as_ref())?
+            else {
+                continue;
+            };
+
+            row.record.destroyed = Some(SubstateDestroyed {
+                by_transaction: *destroyed_by_transaction,
+                justify: *destroyed_justify,
+                by_block: *destroyed_by_block,
+                at_epoch: *epoch,
+                by_shard: *shard,
+            });
+            self.put(columns::SUBSTATES, address.as_ref(), &row)?;
+            let seq = self.next_seq(columns::SUBSTATES_BY_TRANSACTION)?;
+            self.put_raw(
+                columns::SUBSTATES_BY_TRANSACTION,
+                &KeyBuilder::new().bytes(destroyed_by_transaction).u64(seq).build(),
+                address.as_ref(),
+            )?;
+        }
+
+        // The next transition sequence number and the state tree version are fetched once for each shard. This
+        // means that we MUST do the state tree updates before writing substates.
+        let mut shard_transitions = HashMap::<Shard, (u64, Version)>::new();
+        for write in writes {
+            let (shard, epoch, versioned_substate_id, transition, state_hash) = match write {
+                SubstateWrite::Create(substate) => (
+                    substate.created_by_shard,
+                    substate.created_at_epoch,
+                    substate.to_versioned_substate_id(),
+                    Transition::Up,
+                    Some(substate.state_hash),
+                ),
+                SubstateWrite::Destroy {
+                    versioned_substate_id,
+                    shard,
+                    epoch,
+                    ..
+                } => (*shard, *epoch, versioned_substate_id.clone(), Transition::Down, None),
+            };
+
+            let (next_seq, state_version) = match shard_transitions.entry(shard) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let seq = self
+                        .tx()
+                        .state_transitions_get_last_id(shard)
+                        .optional()?
+                        .map_or(1, |id| id.seq() + 1);
+                    let version = self.tx().state_tree_versions_get_latest(shard)?;
+                    entry.insert((seq, version.unwrap_or(0)))
+                },
+            };
+
+            let row = models::StateTransitionRow {
+                epoch,
+                shard,
+                seq: *next_seq,
+                substate_address: versioned_substate_id.to_substate_address(),
+                substate_id: versioned_substate_id.substate_id.clone(),
+                version: versioned_substate_id.version,
+                transition,
+                state_hash,
+                state_version: *state_version,
+            };
+            self.put(
+                columns::STATE_TRANSITIONS,
+                &KeyBuilder::new().u32(shard.as_u32()).u64(*next_seq).build(),
+                &row,
+            )?;
+            *next_seq += 1;
+        }
+
+        Ok(())
+    }
+
+    fn foreign_substate_pledges_save(
+        &mut self,
+        transaction_id: &TransactionId,
+        shard_group: ShardGroup,
+        pledges: &SubstatePledges,
+    ) -> Result<(), StorageError> {
+        for pledge in pledges {
+            let (substate_id, lock_type, substate_value) = match pledge {
+                SubstatePledge::Input {
+                    substate_id,
+                    is_write,
+                    substate,
+                } => {
+                    let lock_type = if *is_write {
+                        SubstateLockType::Write
+                    } else {
+                        SubstateLockType::Read
+                    };
+                    (substate_id, lock_type, Some(substate.clone()))
+                },
+                SubstatePledge::Output { substate_id } => (substate_id, SubstateLockType::Output, None),
+            };
+
+            let key = KeyBuilder::new()
+                .bytes(transaction_id)
+                .bytes(substate_id.to_substate_address())
+                .build();
+            if self.tx().exists(columns::FOREIGN_SUBSTATE_PLEDGES, &key)? {
+                continue;
+            }
+
+            let row = models::ForeignSubstatePledgeRow {
+                substate_id: substate_id.substate_id().clone(),
+                version: substate_id.version(),
+                shard_group,
+                lock_type,
+                substate_value,
+            };
+            self.put(columns::FOREIGN_SUBSTATE_PLEDGES, &key, &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn foreign_substate_pledges_remove_many<'a, I: IntoIterator<Item = &'a TransactionId>>(
+        &mut self,
+        transaction_ids: I,
+    ) -> Result<(), StorageError> {
+        let mut num_deleted = 0;
+        for transaction_id in transaction_ids {
+            num_deleted += self.delete_prefix(columns::FOREIGN_SUBSTATE_PLEDGES, transaction_id.as_ref())?;
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Deleted {num_deleted} foreign substate pledges",
+        );
+
+        Ok(())
+    }
+
+    fn pending_state_tree_diffs_insert(
+        &mut self,
+        block_id: BlockId,
+        shard: Shard,
+        diff: &VersionedStateHashTreeDiff,
+    ) -> Result<(), StorageError> {
+        let block_height = self.require_block_height(&block_id, "pending_state_tree_diffs_insert")?;
+        let row = models::PendingStateTreeDiffRow {
+            block_id,
+            block_height,
+            shard,
+            version: diff.version,
+            diff: diff.diff.clone(),
+        };
+
+        let seq = self.next_seq(columns::PENDING_STATE_TREE_DIFFS)?;
+        self.put(
+            columns::PENDING_STATE_TREE_DIFFS,
+            &KeyBuilder::new().bytes(block_id).u64(seq).build(),
+            &row,
+        )
+    }
+
+    fn pending_state_tree_diffs_remove_by_block(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        self.delete_prefix(columns::PENDING_STATE_TREE_DIFFS, block_id.as_ref())?;
+        Ok(())
+    }
+
+    fn pending_state_tree_diffs_remove_and_return_by_block(
+        &mut self,
+        block_id: &BlockId,
+    ) -> Result<IndexMap<Shard, Vec<PendingShardStateTreeDiff>>, StorageError> {
+        let mut diff_recs = self
+            .tx()
+            .entries::<models::PendingStateTreeDiffRow>(columns::PENDING_STATE_TREE_DIFFS, block_id.as_ref())?;
+        diff_recs.sort_by_key(|(_, diff)| diff.block_height);
+
+        let mut diffs = IndexMap::new();
+        for (key, diff) in diff_recs {
+            self.delete(columns::PENDING_STATE_TREE_DIFFS, &key)?;
+            diffs
+                .entry(diff.shard)
+                .or_insert_with(Vec::new)
+                .push(PendingShardStateTreeDiff::load(diff.version, diff.diff));
+        }
+
+        Ok(diffs)
+    }
+
+    fn state_tree_nodes_insert(&mut self, shard: Shard, key: NodeKey, node: Node<Version>) -> Result<(), StorageError> {
+        let db_key = KeyBuilder::new().u32(shard.as_u32()).bytes(key.to_string()).build();
+        if self.tx().exists(columns::STATE_TREE, &db_key)? {
+            return Err(StorageError::QueryError {
+                reason: format!("state_tree_nodes_insert: node {key} already exists in shard {shard}"),
+            });
+        }
+
+        let row = models::StateTreeNodeRow {
+            node: TreeNode::new_latest(node),
+            stale_since_version: None,
+        };
+        self.put(columns::STATE_TREE, &db_key, &row)
+    }
+
+    fn state_tree_nodes_record_stale_tree_node(
+        &mut self,
+        shard: Shard,
+        stale_since_version: Version,
+        node: StaleTreeNode,
+    ) -> Result<(), StorageError> {
+        // Stale nodes are kept so that recent versions can still be read, until they are pruned
+        let key = node.as_node_key().to_string();
+        let db_key = KeyBuilder::new().u32(shard.as_u32()).bytes(&key).build();
+        let mut row = self
+            .tx()
+            .get::<models::StateTreeNodeRow>(columns::STATE_TREE, &db_key)?
+            .filter(|row| row.stale_since_version.is_none())
+            .ok_or_else(|| StorageError::NotFound {
+                item: "state_tree_node",
+                key: key.clone(),
+            })?;
+
+        row.stale_since_version = Some(stale_since_version);
+        self.put(columns::STATE_TREE, &db_key, &row)?;
+        self.put_raw(
+            columns::STATE_TREE_STALE,
+            &KeyBuilder::new()
+                .u32(shard.as_u32())
+                .u64(stale_since_version)
+                .bytes(&key)
+                .build(),
+            &[],
+        )
+    }
+
+    fn state_tree_nodes_prune_stale(&mut self, shard: Shard, up_to_version: Version) -> Result<usize, StorageError> {
+        let prefix = KeyBuilder::new().u32(shard.as_u32()).build();
+        let mut stale_keys = Vec::new();
+        self.tx().iterate(
+            columns::STATE_TREE_STALE,
+            &prefix,
+            None,
+            Direction::Forward,
+            |key, _| {
+                let version = decode_u64(&key[prefix.len()..]).unwrap_or_default();
+                if version > up_to_version {
+                    return Ok(false);
+                }
+                stale_keys.push(key.to_vec());
+                Ok(true)
+            },
+        )?;
+
+        for stale_key in &stale_keys {
+            // shard (4) ++ version (8) ++ node key
+            let node_key = &stale_key[prefix.len() + 8..];
+            self.delete(
+                columns::STATE_TREE,
+                &KeyBuilder::new().bytes(&prefix).bytes(node_key).build(),
+            )?;
+            self.delete(columns::STATE_TREE_STALE, stale_key)?;
+        }
+
+        Ok(stale_keys.len())
+    }
+
+    fn state_tree_shard_versions_set(&mut self, shard: Shard, version: Version) -> Result<(), StorageError> {
+        self.put_raw(
+            columns::STATE_TREE_SHARD_VERSIONS,
+            &KeyBuilder::new().u32(shard.as_u32()).build(),
+            &version.to_be_bytes(),
+        )
+    }
+
+    fn epoch_checkpoint_save(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError> {
+        let key = KeyBuilder::new().u64(checkpoint.block().epoch().as_u64()).build();
+        if self.tx().exists(columns::EPOCH_CHECKPOINTS, &key)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "epoch_checkpoint_save: checkpoint for epoch {} already exists",
+                    checkpoint.block().epoch()
+                ),
+            });
+        }
+
+        let row = models::EpochCheckpointRow {
+            commit_block: checkpoint.block().clone(),
+            qcs: checkpoint.qcs().to_vec(),
+            shard_roots: checkpoint.shard_roots().clone(),
+        };
+        self.put(columns::EPOCH_CHECKPOINTS, &key, &row)
+    }
+
+    fn epoch_handover_checkpoints_insert(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError> {
+        let epoch = checkpoint.block().epoch();
+        let shard_group = checkpoint.block().shard_group();
+        let key = KeyBuilder::new()
+            .u64(epoch.as_u64())
+            .u32(shard_group.encode_as_u32())
+            .build();
+        if self.tx().exists(columns::EPOCH_HANDOVER_CHECKPOINTS, &key)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "epoch_handover_checkpoints_insert: checkpoint for {shard_group} in epoch {epoch} already exists"
+                ),
+            });
+        }
+
+        let row = models::EpochCheckpointRow {
+            commit_block: checkpoint.block().clone(),
+            qcs: checkpoint.qcs().to_vec(),
+            shard_roots: checkpoint.shard_roots().clone(),
+        };
+        self.put(columns::EPOCH_HANDOVER_CHECKPOINTS, &key, &row)
+    }
+
+    fn burnt_utxos_insert(&mut self, burnt_utxo: &BurntUtxo) -> Result<(), StorageError> {
+        let key = burnt_utxo.substate_id.to_string();
+        if self.tx().exists(columns::BURNT_UTXOS, key.as_bytes())? {
+            return Err(StorageError::QueryError {
+                reason: format!("burnt_utxos_insert: burnt UTXO {key} already exists"),
+            });
+        }
+
+        let row = models::BurntUtxoRow {
+            utxo: BurntUtxo {
+                substate_id: burnt_utxo.substate_id.clone(),
+                substate_value: burnt_utxo.substate_value.clone(),
+                proposed_in_block: None,
+                base_layer_block_height: burnt_utxo.base_layer_block_height,
+            },
+            proposed_in_block_height: None,
+        };
+        self.put(columns::BURNT_UTXOS, key.as_bytes(), &row)
+    }
+
+    fn burnt_utxos_set_proposed_block(
+        &mut self,
+        substate_id: &SubstateId,
+        proposed_in_block: &BlockId,
+    ) -> Result<(), StorageError> {
+        let key = substate_id.to_string();
+        let mut row = self
+            .tx()
+            .get::<models::BurntUtxoRow>(columns::BURNT_UTXOS, key.as_bytes())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "burnt_utxo",
+                key: substate_id.to_string(),
+            })?;
+
+        row.utxo.proposed_in_block = Some(*proposed_in_block);
+        row.proposed_in_block_height = self.tx().get_block_height(proposed_in_block)?;
+        self.put(columns::BURNT_UTXOS, key.as_bytes(), &row)
+    }
+
+    fn burnt_utxos_clear_proposed_block(&mut self, proposed_in_block: &BlockId) -> Result<(), StorageError> {
+        let utxos = self.tx().entries::<models::BurntUtxoRow>(columns::BURNT_UTXOS, &[])?;
+        for (key, mut row) in utxos {
+            if row.utxo.proposed_in_block.as_ref() != Some(proposed_in_block) {
+                continue;
+            }
+            row.utxo.proposed_in_block = None;
+            row.proposed_in_block_height = None;
+            self.put(columns::BURNT_UTXOS, &key, &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn burnt_utxos_delete(&mut self, substate_id: &SubstateId) -> Result<(), StorageError> {
+        let key = substate_id.to_string();
+        if !self.tx().exists(columns::BURNT_UTXOS, key.as_bytes())? {
+            return Err(StorageError::NotFound {
+                item: "burnt_utxo",
+                key,
+            });
+        }
+
+        self.delete(columns::BURNT_UTXOS, key.as_bytes())
+    }
+
+    fn lock_conflicts_insert_all<'a, I: IntoIterator<Item = (&'a TransactionId, &'a Vec<LockConflict>)>>(
+        &mut self,
+        block_id: &BlockId,
+        conflicts: I,
+    ) -> Result<(), StorageError> {
+        for (tx_id, conflicts) in conflicts {
+            for conflict in conflicts {
+                let row = models::LockConflictRow {
+                    block_id: *block_id,
+                    transaction_id: *tx_id,
+                    depends_on_tx: conflict.transaction_id,
+                    lock_type: conflict.requested_lock.to_string(),
+                };
+                let seq = self.next_seq(columns::LOCK_CONFLICTS)?;
+                self.put(
+                    columns::LOCK_CONFLICTS,
+                    &KeyBuilder::new().bytes(tx_id).u64(seq).build(),
+                    &row,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validator_epoch_stats_updates<'a, I: IntoIterator<Item = ValidatorStatsUpdate<'a>>>(
+        &mut self,
+        epoch: Epoch,
+        updates: I,
+    ) -> Result<(), StorageError> {
+        for update in updates {
+            let key = KeyBuilder::new()
+                .u64(epoch.as_u64())
+                .bytes(update.public_key().as_bytes())
+                .build();
+            let existing = self
+                .tx()
+                .get::<models::ValidatorEpochStatsRow>(columns::VALIDATOR_EPOCH_STATS, &key)?;
+
+            let row = match existing {
+                Some(mut row) => {
+                    row.participation_shares += update.participation_shares_increment();
+                    match update.missed_proposal_change() {
+                        Some(0) => {
+                            row.missed_proposals = 0;
+                            row.missed_proposals_capped = 0;
+                        },
+                        Some(n) => {
+                            // NOTE: n can be negative
+                            let missed_proposals = cmp::max(row.missed_proposals as i64 + n, 0);
+                            let missed_proposals_capped = update
+                                .max_total_missed_proposals()
+                                .min(cmp::max(row.missed_proposals_capped as i64 + n, 0));
+                            row.missed_proposals = missed_proposals as u64;
+                            row.missed_proposals_capped = missed_proposals_capped.max(0) as u64;
+                            row.total_missed_proposals += n.max(0) as u64;
+                        },
+                        None => {},
+                    }
+                    row
+                },
+                None => {
+                    let leader_failure_inc = update.missed_proposal_change().map_or(0i64, |set| set.max(0)) as u64;
+                    models::ValidatorEpochStatsRow {
+                        epoch,
+                        public_key: update.public_key().clone(),
+                        participation_shares: update.participation_shares_increment(),
+                        missed_proposals: leader_failure_inc,
+                        missed_proposals_capped: leader_failure_inc,
+                        total_missed_proposals: leader_failure_inc,
+                    }
+                },
+            };
+
+            self.put(columns::VALIDATOR_EPOCH_STATS, &key, &row)?;
+        }
+
+        Ok(())
+    }
+
+    fn suspended_nodes_insert(
+        &mut self,
+        public_key: &PublicKey,
+        suspended_in_block: BlockId,
+    ) -> Result<(), StorageError> {
+        let Some(block) = self.tx().get_block_row(&suspended_in_block)? else {
+            return Ok(());
+        };
+
+        let row = models::SuspendedNodeRow {
+            public_key: public_key.clone(),
+            epoch: block.header.epoch(),
+            suspended_in_block,
+            suspended_in_block_height: block.header.height(),
+            resumed_in_block: None,
+            resumed_in_block_height: None,
+        };
+        self.put(columns::SUSPENDED_NODES, public_key.as_bytes(), &row)
+    }
+
+    fn suspended_nodes_mark_for_removal(
+        &mut self,
+        public_key: &PublicKey,
+        resumed_in_block: BlockId,
+    ) -> Result<(), StorageError> {
+        let Some(mut row) = self
+            .tx()
+            .get::<models::SuspendedNodeRow>(columns::SUSPENDED_NODES, public_key.as_bytes())?
+        else {
+            return Ok(());
+        };
+
+        row.resumed_in_block = Some(resumed_in_block);
+        row.resumed_in_block_height = self.tx().get_block_height(&resumed_in_block)?;
+        self.put(columns::SUSPENDED_NODES, public_key.as_bytes(), &row)
+    }
+
+    fn suspended_nodes_delete(&mut self, public_key: &PublicKey) -> Result<(), StorageError> {
+        if !self.tx().exists(columns::SUSPENDED_NODES, public_key.as_bytes())? {
+            return Err(StorageError::NotFound {
+                item: "suspended_node",
+                key: public_key.to_string(),
+            });
+        }
+
+        self.delete(columns::SUSPENDED_NODES, public_key.as_bytes())
+    }
+
+    fn diagnostics_add_no_vote(&mut self, block_id: BlockId, reason: NoVoteReason) -> Result<(), StorageError> {
+        let block_height = self.require_block_height(&block_id, "diagnostics_add_no_vote")?;
+        let row = models::NoVoteRow {
+            block_id,
+            block_height,
+            reason_code: reason.as_code_str().to_string(),
+            reason_text: reason.to_string(),
+            created_at: now(),
+        };
+
+        let seq = self.next_seq(columns::DIAGNOSTICS_NO_VOTES)?;
+        self.put(columns::DIAGNOSTICS_NO_VOTES, &KeyBuilder::new().u64(seq).build(), &row)
+    }
+}
+
+impl<'a, TAddr> Deref for RocksDbStateStoreWriteTransaction<'a, TAddr> {
+    type Target = RocksDbStateStoreReadTransaction<'a, TAddr>;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.as_ref().unwrap()
+    }
+}
+
+impl<TAddr> Drop for RocksDbStateStoreWriteTransaction<'_, TAddr> {
+    fn drop(&mut self) {
+        if self.transaction.is_some() {
+            warn!(
+                target: LOG_TARGET,
+                "Shard store write transaction was not committed/rolled back"
+            );
+        }
+    }
+}
+
+fn now() -> PrimitiveDateTime {
+    let now = OffsetDateTime::now_utc();
+    PrimitiveDateTime::new(now.date(), now.time())
+}
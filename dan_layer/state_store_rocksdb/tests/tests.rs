@@ -0,0 +1,246 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use rand::{rngs::OsRng, RngCore};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_storage::{
+    consensus_models::{Block, Command, Decision, TransactionAtom, TransactionPoolStage, TransactionPoolStatusUpdate},
+    StateStore,
+    StateStoreReadTransaction,
+    StateStoreWriteTransaction,
+};
+use tari_state_store_rocksdb::RocksDbStateStore;
+use tari_transaction::TransactionId;
+use tari_utilities::epoch_time::EpochTime;
+use tempfile::TempDir;
+
+fn create_db() -> (TempDir, RocksDbStateStore<String>) {
+    let dir = tempfile::tempdir().unwrap();
+    let db = RocksDbStateStore::connect(dir.path()).unwrap();
+    (dir, db)
+}
+
+fn create_tx_atom() -> TransactionAtom {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    TransactionAtom {
+        id: TransactionId::new(bytes),
+        decision: Decision::Commit,
+        evidence: Default::default(),
+        transaction_fee: 0,
+        leader_fee: None,
+    }
+}
+
+mod confirm_all_transitions {
+    use tari_dan_common_types::{ExtraData, NumPreshards, ShardGroup};
+
+    use super::*;
+
+    #[test]
+    fn it_sets_pending_stage_to_stage() {
+        let (_dir, db) = create_db();
+        let mut tx = db.create_write_tx().unwrap();
+
+        let atom1 = create_tx_atom();
+        let atom2 = create_tx_atom();
+        let atom3 = create_tx_atom();
+
+        let network = Default::default();
+        let zero_block = Block::zero_block(network, NumPreshards::P64);
+        zero_block.insert(&mut tx).unwrap();
+        let block1 = Block::create(
+            network,
+            *zero_block.id(),
+            zero_block.justify().clone(),
+            NodeHeight(1),
+            Epoch(0),
+            ShardGroup::all_shards(NumPreshards::P64),
+            Default::default(),
+            // Need to have a command in, otherwise this block will not be included internally in the query because it
+            // cannot cause a state change without any commands
+            [Command::Prepare(atom1.clone())].into_iter().collect(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            EpochTime::now().as_u64(),
+            0,
+            FixedHash::zero(),
+            ExtraData::default(),
+        )
+        .unwrap();
+        block1.insert(&mut tx).unwrap();
+
+        tx.transaction_pool_insert_new(atom1.id, atom1.decision, true).unwrap();
+        tx.transaction_pool_insert_new(atom2.id, atom2.decision, true).unwrap();
+        tx.transaction_pool_insert_new(atom3.id, atom3.decision, true).unwrap();
+        let block_id = *block1.id();
+
+        let transactions = tx.transaction_pool_get_all().unwrap();
+        let mut tx_1 = transactions
+            .iter()
+            .find(|tx| *tx.transaction_id() == atom1.id)
+            .unwrap()
+            .clone();
+        let mut tx_2 = transactions
+            .iter()
+            .find(|tx| *tx.transaction_id() == atom2.id)
+            .unwrap()
+            .clone();
+        let mut tx_3 = transactions
+            .iter()
+            .find(|tx| *tx.transaction_id() == atom3.id)
+            .unwrap()
+            .clone();
+
+        tx_1.set_next_stage(TransactionPoolStage::Prepared).unwrap();
+        tx_1.set_next_stage(TransactionPoolStage::LocalPrepared).unwrap();
+
+        tx_2.set_next_stage(TransactionPoolStage::Prepared).unwrap();
+        tx_3.set_next_stage(TransactionPoolStage::Prepared).unwrap();
+
+        tx.transaction_pool_add_pending_update(&block_id, &TransactionPoolStatusUpdate::new(tx_1, true))
+            .unwrap();
+        tx.transaction_pool_add_pending_update(&block_id, &TransactionPoolStatusUpdate::new(tx_2, true))
+            .unwrap();
+        tx.transaction_pool_add_pending_update(&block_id, &TransactionPoolStatusUpdate::new(tx_3, true))
+            .unwrap();
+
+        let rec = tx
+            .transaction_pool_get_for_blocks(zero_block.id(), &block_id, &atom1.id)
+            .unwrap();
+        assert!(rec.committed_stage().is_new());
+        assert!(rec.pending_stage().unwrap().is_local_prepared());
+
+        let rec = tx
+            .transaction_pool_get_for_blocks(zero_block.id(), &block_id, &atom2.id)
+            .unwrap();
+        assert!(rec.committed_stage().is_new());
+        assert!(rec.pending_stage().unwrap().is_prepared());
+
+        tx.transaction_pool_confirm_all_transitions(&block1.as_locked_block())
+            .unwrap();
+
+        let rec = tx
+            .transaction_pool_get_for_blocks(zero_block.id(), &block_id, &atom1.id)
+            .unwrap();
+        assert!(rec.committed_stage().is_local_prepared());
+        assert!(rec.pending_stage().is_none());
+
+        let rec = tx
+            .transaction_pool_get_for_blocks(zero_block.id(), &block_id, &atom2.id)
+            .unwrap();
+        assert_eq!(rec.committed_stage(), TransactionPoolStage::Prepared);
+        assert_eq!(rec.pending_stage(), None);
+
+        let rec = tx
+            .transaction_pool_get_for_blocks(zero_block.id(), &block_id, &atom3.id)
+            .unwrap();
+        assert_eq!(rec.committed_stage(), TransactionPoolStage::Prepared);
+        assert_eq!(rec.pending_stage(), None);
+
+        tx.rollback().unwrap();
+    }
+}
+
+mod persistence {
+    use tari_dan_common_types::NumPreshards;
+
+    use super::*;
+
+    #[test]
+    fn it_persists_committed_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let zero_block = Block::zero_block(Default::default(), NumPreshards::P64);
+        let atom = create_tx_atom();
+
+        {
+            let db = RocksDbStateStore::<String>::connect(dir.path()).unwrap();
+            let mut tx = db.create_write_tx().unwrap();
+            zero_block.insert(&mut tx).unwrap();
+            tx.transaction_pool_insert_new(atom.id, atom.decision, true).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = RocksDbStateStore::<String>::connect(dir.path()).unwrap();
+        let tx = db.create_read_tx().unwrap();
+        assert!(tx.blocks_exists(zero_block.id()).unwrap());
+        assert!(tx.transaction_pool_exists(&atom.id).unwrap());
+    }
+
+    #[test]
+    fn it_discards_rolled_back_writes() {
+        let (_dir, db) = create_db();
+        let atom = create_tx_atom();
+
+        let mut tx = db.create_write_tx().unwrap();
+        tx.transaction_pool_insert_new(atom.id, atom.decision, true).unwrap();
+        assert!(tx.transaction_pool_exists(&atom.id).unwrap());
+        tx.rollback().unwrap();
+
+        let tx = db.create_read_tx().unwrap();
+        assert!(!tx.transaction_pool_exists(&atom.id).unwrap());
+    }
+}
+
+mod validator_epoch_stats {
+    use tari_common_types::types::PublicKey;
+    use tari_crypto::keys::PublicKey as _;
+    use tari_dan_storage::consensus_models::{ValidatorAggregateStats, ValidatorStatsUpdate};
+
+    use super::*;
+
+    #[test]
+    fn it_aggregates_persisted_stats_across_epochs() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, pk1) = PublicKey::random_keypair(&mut OsRng);
+        let (_, pk2) = PublicKey::random_keypair(&mut OsRng);
+
+        {
+            let db = RocksDbStateStore::<String>::connect(dir.path()).unwrap();
+            let mut tx = db.create_write_tx().unwrap();
+            tx.validator_epoch_stats_updates(Epoch(1), [
+                ValidatorStatsUpdate::new(&pk1).add_missed_proposal(),
+                ValidatorStatsUpdate::new(&pk2).increment_participation_share(),
+            ])
+            .unwrap();
+            // Resetting the missed proposals does not change the total
+            tx.validator_epoch_stats_updates(Epoch(1), [ValidatorStatsUpdate::new(&pk1).reset_missed_proposals()])
+                .unwrap();
+            tx.validator_epoch_stats_updates(Epoch(2), [
+                ValidatorStatsUpdate::new(&pk1)
+                    .add_missed_proposal()
+                    .increment_participation_share(),
+                ValidatorStatsUpdate::new(&pk2).increment_participation_share(),
+            ])
+            .unwrap();
+            tx.validator_epoch_stats_updates(Epoch(3), [ValidatorStatsUpdate::new(&pk1).add_missed_proposal()])
+                .unwrap();
+            tx.commit().unwrap();
+        }
+
+        let db = RocksDbStateStore::<String>::connect(dir.path()).unwrap();
+        let tx = db.create_read_tx().unwrap();
+        let stats = ValidatorAggregateStats::get(&tx, Epoch(1)..=Epoch(2), &pk1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.num_epochs, 2);
+        assert_eq!(stats.first_epoch, Epoch(1));
+        assert_eq!(stats.last_epoch, Epoch(2));
+        assert_eq!(stats.total_missed_proposals, 2);
+        assert_eq!(stats.participation_shares, 1);
+
+        let all = ValidatorAggregateStats::get_all(&tx, Epoch(0)..=Epoch(3)).unwrap();
+        assert_eq!(all.len(), 2);
+        let stats = all.iter().find(|s| s.public_key == pk2).unwrap();
+        assert_eq!(stats.num_epochs, 2);
+        assert_eq!(stats.participation_shares, 2);
+        assert_eq!(stats.total_missed_proposals, 0);
+
+        assert!(ValidatorAggregateStats::get(&tx, Epoch(4)..=Epoch(10), &pk1)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -287,11 +287,30 @@ create table transactions
     abort_details     text      NULL,
     min_epoch         BIGINT    NULL,
     max_epoch         BIGINT    NULL,
+    idempotency_key   text      NULL,
+    num_reserved_outputs integer NOT NULL DEFAULT 0,
     created_at        timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 create unique index transactions_uniq_idx_id on transactions (transaction_id);
 
+create table transaction_idempotency_keys
+(
+    id              integer   not null primary key AUTOINCREMENT,
+    -- The idempotency key bound to the signer of the transaction
+    idempotency_key text      not null,
+    transaction_id  text      not null,
+    -- The block that first sequenced the transaction
+    block_id        text      not null,
+    epoch           bigint    not null,
+    -- Set when the block is committed
+    is_committed    boolean   not null default '0',
+    created_at      timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+create index transaction_idempotency_keys_idx_idempotency_key_epoch on transaction_idempotency_keys (idempotency_key, epoch);
+create index transaction_idempotency_keys_idx_block_id on transaction_idempotency_keys (block_id);
+
 create table lock_conflicts
 (
     id             integer   not null primary key AUTOINCREMENT,
@@ -378,6 +397,7 @@ create table votes
 );
 
 CREATE INDEX votes_idx_block_id ON votes (block_id);
+CREATE INDEX votes_idx_epoch_sender_leaf_hash ON votes (epoch, sender_leaf_hash);
 
 CREATE TABLE foreign_proposals
 (
@@ -439,14 +459,18 @@ CREATE TABLE burnt_utxos
 
 CREATE TABLE state_tree
 (
-    id    integer not NULL primary key AUTOINCREMENT,
-    shard int     not NULL,
-    key   text    not NULL,
-    node  text    not NULL
+    id                  integer not NULL primary key AUTOINCREMENT,
+    shard               int     not NULL,
+    key                 text    not NULL,
+    node                text    not NULL,
+    -- The version from which the node is no longer part of the tree. NULL if the node is part of the current tree.
+    stale_since_version bigint  NULL
 );
 
 -- Scoping by shard
 CREATE INDEX state_tree_idx_shard_key on state_tree (shard);
+-- Pruning stale nodes
+CREATE INDEX state_tree_idx_shard_stale_since_version on state_tree (shard, stale_since_version);
 -- Duplicate keys are not allowed
 -- CREATE UNIQUE INDEX state_tree_uniq_idx_key on state_tree (shard, key);
 
@@ -485,6 +509,20 @@ CREATE TABLE epoch_checkpoints
     created_at   timestamp not NULL DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Validated checkpoints of the shard groups that handed over shards to the local shard group
+CREATE TABLE epoch_handover_checkpoints
+(
+    id           integer   not NULL primary key AUTOINCREMENT,
+    epoch        bigint    not NULL,
+    shard_group  integer   not NULL,
+    commit_block text      not NULL,
+    qcs          text      not NULL,
+    shard_roots  text      not NULL,
+    created_at   timestamp not NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX epoch_handover_checkpoints_uniq_idx_epoch_shard_group on epoch_handover_checkpoints (epoch, shard_group);
+
 -- An append-only store of state transitions
 CREATE TABLE state_transitions
 (
@@ -515,10 +553,12 @@ CREATE TABLE validator_epoch_stats
     participation_shares    bigint    not NULL DEFAULT '0',
     missed_proposals        bigint    not NULL DEFAULT '0',
     missed_proposals_capped bigint    not NULL DEFAULT '0',
+    total_missed_proposals  bigint    not NULL DEFAULT '0',
     created_at              timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
 CREATE UNIQUE INDEX participation_shares_uniq_idx_epoch_public_key on validator_epoch_stats (epoch, public_key);
+CREATE INDEX validator_epoch_stats_idx_public_key_epoch on validator_epoch_stats (public_key, epoch);
 
 CREATE TABLE suspended_nodes
 (
@@ -546,6 +586,21 @@ CREATE TABLE diagnostics_no_votes
     created_at   timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
+-- Blocks and quorum certificates that have been moved from the state store into an epoch archive
+CREATE TABLE archived_blocks
+(
+    block_id   text      not NULL primary key,
+    epoch      bigint    not NULL,
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE archived_quorum_certificates
+(
+    qc_id      text      not NULL primary key,
+    epoch      bigint    not NULL,
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 create table diagnostic_deleted_blocks
 (
     id                      integer   not null primary key AUTOINCREMENT,
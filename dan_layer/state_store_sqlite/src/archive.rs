@@ -0,0 +1,348 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use diesel::{
+    connection::SimpleConnection,
+    sql_query,
+    sql_types::{BigInt, Text},
+    Connection,
+    QueryableByName,
+    RunQueryDsl,
+    SqliteConnection,
+};
+use diesel_migrations::MigrationHarness;
+use log::*;
+use tari_dan_common_types::Epoch;
+
+use crate::{error::SqliteStorageError, store::MIGRATIONS};
+
+const LOG_TARGET: &str = "tari::dan::storage::sqlite::archive";
+
+/// Summary of the data that was newly copied from the hot state store into an epoch archive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpochArchiveStats {
+    pub num_blocks: usize,
+    pub num_quorum_certificates: usize,
+    pub num_block_diffs: usize,
+}
+
+impl EpochArchiveStats {
+    pub fn is_empty(&self) -> bool {
+        self.num_blocks == 0 && self.num_quorum_certificates == 0 && self.num_block_diffs == 0
+    }
+}
+
+/// Returns the path of the archive database for the given epoch
+pub fn epoch_archive_path<P: AsRef<Path>>(archive_dir: P, epoch: Epoch) -> PathBuf {
+    archive_dir.as_ref().join(format!("epoch_{}.db", epoch.as_u64()))
+}
+
+#[derive(QueryableByName)]
+struct EpochRow {
+    #[diesel(sql_type = BigInt)]
+    epoch: i64,
+}
+
+/// Returns all epochs before `before_epoch` that still have committed blocks in the hot state store, excluding the zero
+/// block.
+pub(crate) fn get_archivable_epochs(
+    conn: &mut SqliteConnection,
+    before_epoch: Epoch,
+) -> Result<Vec<Epoch>, SqliteStorageError> {
+    let rows = sql_query(
+        "SELECT DISTINCT epoch FROM blocks
+         WHERE epoch < ? AND is_committed = 1 AND block_id != parent_block_id
+         ORDER BY epoch ASC",
+    )
+    .bind::<BigInt, _>(before_epoch.as_u64() as i64)
+    .get_results::<EpochRow>(conn)
+    .map_err(|source| SqliteStorageError::DieselError {
+        source,
+        operation: "get_archivable_epochs",
+    })?;
+
+    Ok(rows.into_iter().map(|r| Epoch(r.epoch as u64)).collect())
+}
+
+/// Moves the committed blocks, quorum certificates and block diffs for the epoch into a separate archive database.
+///
+/// Rows that are still referenced by data in the hot store (e.g. the parent of the first block in the next epoch) are
+/// copied to the archive but are kept in the hot store. They are removed on a subsequent call once they are no
+/// longer referenced. Archiving an epoch more than once is idempotent.
+///
+/// This must be called with a connection that is not in a transaction.
+pub(crate) fn archive_epoch(
+    conn: &mut SqliteConnection,
+    archive_dir: &Path,
+    epoch: Epoch,
+) -> Result<EpochArchiveStats, SqliteStorageError> {
+    let path = epoch_archive_path(archive_dir, epoch);
+    create_archive_db(&path)?;
+
+    // Foreign keys cannot be changed within a transaction. The archive does not contain the parents of the first
+    // blocks in the epoch, and blocks kept in the hot store may reference archived parents.
+    execute(conn, "PRAGMA foreign_keys = OFF", "archive_epoch: disable foreign keys")?;
+    let result = sql_query("ATTACH DATABASE ? AS archive")
+        .bind::<Text, _>(path.to_string_lossy())
+        .execute(conn)
+        .map_err(|source| SqliteStorageError::DieselError {
+            source,
+            operation: "archive_epoch: attach",
+        })
+        .and_then(|_| {
+            let result = execute(conn, "BEGIN TRANSACTION", "archive_epoch: begin").and_then(|_| {
+                match move_epoch_to_archive(conn, epoch) {
+                    Ok(stats) => execute(conn, "COMMIT", "archive_epoch: commit").map(|_| stats),
+                    Err(err) => {
+                        if let Err(rollback_err) = execute(conn, "ROLLBACK", "archive_epoch: rollback") {
+                            error!(target: LOG_TARGET, "Failed to roll back epoch archive: {}", rollback_err);
+                        }
+                        Err(err)
+                    },
+                }
+            });
+            let detach = execute(conn, "DETACH DATABASE archive", "archive_epoch: detach");
+            result.and_then(|stats| detach.map(|_| stats))
+        });
+    let fk_on = execute(conn, "PRAGMA foreign_keys = ON", "archive_epoch: enable foreign keys");
+    let stats = result?;
+    fk_on?;
+
+    if !stats.is_empty() {
+        info!(
+            target: LOG_TARGET,
+            "🗄️ Archived {} block(s), {} QC(s) and {} block diff(s) for epoch {} to {}",
+            stats.num_blocks,
+            stats.num_quorum_certificates,
+            stats.num_block_diffs,
+            epoch,
+            path.display()
+        );
+    }
+
+    Ok(stats)
+}
+
+/// Opens an existing epoch archive as a read-only connection
+pub(crate) fn open_archive_read_only(path: &Path) -> Result<SqliteConnection, SqliteStorageError> {
+    if !path.exists() {
+        return Err(SqliteStorageError::ArchiveError {
+            operation: "open_archive_read_only",
+            details: format!("Epoch archive {} does not exist", path.display()),
+        });
+    }
+    let mut conn = SqliteConnection::establish(&format!("file:{}?mode=ro", path.display()))?;
+    execute(&mut conn, "PRAGMA query_only = ON", "open_archive_read_only")?;
+    Ok(conn)
+}
+
+/// Read-only access to the epoch archives of a state store. The archive for an epoch is ATTACHed read-only to a
+/// separate in-memory connection when it is first queried, because databases cannot be attached while a transaction is
+/// open on the state store connection. Only one archive is attached at a time, so unqualified table names resolve to
+/// the archive tables and the state store queries can be used unchanged.
+pub(crate) struct EpochArchiveReader {
+    archive_dir: PathBuf,
+    attached: Mutex<AttachedArchive>,
+}
+
+struct AttachedArchive {
+    connection: SqliteConnection,
+    epoch: Option<Epoch>,
+}
+
+impl EpochArchiveReader {
+    pub fn new(archive_dir: PathBuf) -> Result<Self, SqliteStorageError> {
+        let connection = SqliteConnection::establish(":memory:")?;
+        Ok(Self {
+            archive_dir,
+            attached: Mutex::new(AttachedArchive {
+                connection,
+                epoch: None,
+            }),
+        })
+    }
+
+    /// Runs the query against the archive for the epoch
+    pub fn with_archive<F, R>(&self, epoch: Epoch, query: F) -> Result<R, SqliteStorageError>
+    where F: FnOnce(&mut SqliteConnection) -> Result<R, SqliteStorageError> {
+        let mut attached = self.attached.lock().unwrap();
+        if attached.epoch != Some(epoch) {
+            if attached.epoch.take().is_some() {
+                execute(
+                    &mut attached.connection,
+                    "DETACH DATABASE archive",
+                    "epoch_archive_reader: detach",
+                )?;
+            }
+            let path = epoch_archive_path(&self.archive_dir, epoch);
+            if !path.exists() {
+                return Err(SqliteStorageError::ArchiveError {
+                    operation: "epoch_archive_reader",
+                    details: format!("Epoch archive {} does not exist", path.display()),
+                });
+            }
+            sql_query("ATTACH DATABASE ? AS archive")
+                .bind::<Text, _>(format!("file:{}?mode=ro", path.display()))
+                .execute(&mut attached.connection)
+                .map_err(|source| SqliteStorageError::DieselError {
+                    source,
+                    operation: "epoch_archive_reader: attach",
+                })?;
+            attached.epoch = Some(epoch);
+        }
+
+        query(&mut attached.connection)
+    }
+}
+
+fn create_archive_db(path: &Path) -> Result<(), SqliteStorageError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| SqliteStorageError::ArchiveError {
+            operation: "create_archive_db",
+            details: format!("Failed to create archive directory {}: {}", parent.display(), e),
+        })?;
+    }
+
+    // The archive has the same schema as the state store so that the same queries can be used to read from it
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())?;
+    conn.run_pending_migrations(MIGRATIONS)
+        .map_err(|source| SqliteStorageError::MigrationError { source })?;
+    Ok(())
+}
+
+fn move_epoch_to_archive(conn: &mut SqliteConnection, epoch: Epoch) -> Result<EpochArchiveStats, SqliteStorageError> {
+    let epoch = epoch.as_u64() as i64;
+
+    // Copy everything for the epoch into the archive, including QCs from the previous epoch that justify blocks in
+    // this epoch so that every archived block can be loaded along with its justify QC.
+    let num_blocks = execute_with_epoch(
+        conn,
+        "INSERT OR IGNORE INTO archive.blocks SELECT * FROM main.blocks WHERE epoch = ?1 AND is_committed = 1",
+        epoch,
+        "archive_epoch: copy blocks",
+    )?;
+    let num_quorum_certificates = execute_with_epoch(
+        conn,
+        "INSERT OR IGNORE INTO archive.quorum_certificates SELECT * FROM main.quorum_certificates
+         WHERE epoch = ?1 OR qc_id IN (SELECT qc_id FROM main.blocks WHERE epoch = ?1 AND is_committed = 1)",
+        epoch,
+        "archive_epoch: copy quorum certificates",
+    )?;
+    let num_block_diffs = execute_with_epoch(
+        conn,
+        "INSERT OR IGNORE INTO archive.block_diffs SELECT * FROM main.block_diffs
+         WHERE block_id IN (SELECT block_id FROM main.blocks WHERE epoch = ?1 AND is_committed = 1)",
+        epoch,
+        "archive_epoch: copy block diffs",
+    )?;
+
+    // Blocks that are still referenced by data in the hot store are kept until they are no longer referenced. The zero
+    // block is its own parent and is never removed.
+    execute(
+        conn,
+        "CREATE TEMP TABLE archived_block_ids (block_id text NOT NULL PRIMARY KEY)",
+        "archive_epoch: create temp table",
+    )?;
+    execute_with_epoch(
+        conn,
+        "INSERT INTO temp.archived_block_ids SELECT b.block_id FROM main.blocks b
+         WHERE b.epoch = ?1 AND b.is_committed = 1 AND b.block_id != b.parent_block_id
+           AND NOT EXISTS (SELECT 1 FROM main.blocks c
+                           WHERE c.parent_block_id = b.block_id AND (c.epoch != ?1 OR c.is_committed = 0))
+           AND NOT EXISTS (SELECT 1 FROM main.substate_locks l WHERE l.block_id = b.block_id)
+           AND NOT EXISTS (SELECT 1 FROM main.transaction_pool_state_updates u WHERE u.block_id = b.block_id)
+           AND NOT EXISTS (SELECT 1 FROM main.pending_state_tree_diffs d WHERE d.block_id = b.block_id)
+           AND NOT EXISTS (SELECT 1 FROM main.foreign_proposals f WHERE f.proposed_in_block = b.block_id)
+           AND NOT EXISTS (SELECT 1 FROM main.burnt_utxos u WHERE u.proposed_in_block = b.block_id)",
+        epoch,
+        "archive_epoch: collect block ids",
+    )?;
+
+    // Record where removed blocks and QCs can be found so that reads can fall back to the archive
+    execute_with_epoch(
+        conn,
+        "INSERT OR IGNORE INTO main.archived_blocks (block_id, epoch) SELECT block_id, ?1 FROM temp.archived_block_ids",
+        epoch,
+        "archive_epoch: index archived blocks",
+    )?;
+    execute_with_epoch(
+        conn,
+        "INSERT OR IGNORE INTO main.archived_quorum_certificates (qc_id, epoch)
+         SELECT qc_id, ?1 FROM archive.quorum_certificates",
+        epoch,
+        "archive_epoch: index archived quorum certificates",
+    )?;
+
+    execute(
+        conn,
+        "DELETE FROM main.block_diffs WHERE block_id IN (SELECT block_id FROM temp.archived_block_ids)",
+        "archive_epoch: delete block diffs",
+    )?;
+    // Foreign keys are off so ON DELETE CASCADE does not apply. Always keep the latest bookkeeping record.
+    for table in [
+        "leaf_blocks",
+        "high_qcs",
+        "locked_block",
+        "last_executed",
+        "last_sent_vote",
+    ] {
+        execute(
+            conn,
+            &format!(
+                "DELETE FROM main.{table} WHERE block_id IN (SELECT block_id FROM temp.archived_block_ids) AND id < \
+                 (SELECT MAX(id) FROM main.{table})"
+            ),
+            "archive_epoch: delete bookkeeping",
+        )?;
+    }
+    execute(
+        conn,
+        "DELETE FROM main.blocks WHERE block_id IN (SELECT block_id FROM temp.archived_block_ids)",
+        "archive_epoch: delete blocks",
+    )?;
+    execute(
+        conn,
+        "DROP TABLE temp.archived_block_ids",
+        "archive_epoch: drop temp table",
+    )?;
+
+    // QCs from earlier epochs are only removed once they are no longer referenced and have been archived
+    execute_with_epoch(
+        conn,
+        "DELETE FROM main.quorum_certificates WHERE epoch <= ?1
+           AND qc_id IN (SELECT qc_id FROM main.archived_quorum_certificates)
+           AND qc_id NOT IN (SELECT qc_id FROM main.blocks)
+           AND qc_id NOT IN (SELECT qc_id FROM main.high_qcs)
+           AND qc_id NOT IN (SELECT justify_qc_id FROM main.foreign_proposals)",
+        epoch,
+        "archive_epoch: delete quorum certificates",
+    )?;
+
+    Ok(EpochArchiveStats {
+        num_blocks,
+        num_quorum_certificates,
+        num_block_diffs,
+    })
+}
+
+fn execute(conn: &mut SqliteConnection, sql: &str, operation: &'static str) -> Result<(), SqliteStorageError> {
+    conn.batch_execute(sql)
+        .map_err(|source| SqliteStorageError::DieselError { source, operation })
+}
+
+fn execute_with_epoch(
+    conn: &mut SqliteConnection,
+    sql: &str,
+    epoch: i64,
+    operation: &'static str,
+) -> Result<usize, SqliteStorageError> {
+    sql_query(sql)
+        .bind::<BigInt, _>(epoch)
+        .execute(conn)
+        .map_err(|source| SqliteStorageError::DieselError { source, operation })
+}
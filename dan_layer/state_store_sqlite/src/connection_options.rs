@@ -0,0 +1,90 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{fmt, fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Options that are applied to the SQLite connection when the state store is opened
+#[derive(Debug, Clone)]
+pub struct SqliteConnectionOptions {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    /// How long a statement waits for a lock held by another connection before failing. Zero fails immediately.
+    pub busy_timeout: Duration,
+    /// The maximum size of the page cache in KiB. None uses the SQLite default.
+    pub cache_size_kib: Option<u32>,
+}
+
+impl SqliteConnectionOptions {
+    pub(crate) fn to_pragmas(&self) -> String {
+        let mut pragmas = format!(
+            "PRAGMA journal_mode = {}; PRAGMA synchronous = {}; PRAGMA busy_timeout = {};",
+            self.journal_mode,
+            self.synchronous,
+            self.busy_timeout.as_millis()
+        );
+        if let Some(cache_size_kib) = self.cache_size_kib {
+            // A negative cache size is in KiB rather than pages
+            pragmas.push_str(&format!(" PRAGMA cache_size = -{};", cache_size_kib));
+        }
+        pragmas
+    }
+}
+
+impl Default for SqliteConnectionOptions {
+    fn default() -> Self {
+        Self {
+            // WAL allows readers to continue while a write transaction is in progress
+            journal_mode: SqliteJournalMode::Wal,
+            // NORMAL is durable in WAL mode, except for the last transactions before a power loss
+            synchronous: SqliteSynchronous::Normal,
+            busy_timeout: Duration::from_secs(5),
+            cache_size_kib: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqliteJournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+    Off,
+}
+
+impl Display for SqliteJournalMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delete => write!(f, "DELETE"),
+            Self::Truncate => write!(f, "TRUNCATE"),
+            Self::Persist => write!(f, "PERSIST"),
+            Self::Memory => write!(f, "MEMORY"),
+            Self::Wal => write!(f, "WAL"),
+            Self::Off => write!(f, "OFF"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqliteSynchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl Display for SqliteSynchronous {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Off => write!(f, "OFF"),
+            Self::Normal => write!(f, "NORMAL"),
+            Self::Full => write!(f, "FULL"),
+            Self::Extra => write!(f, "EXTRA"),
+        }
+    }
+}
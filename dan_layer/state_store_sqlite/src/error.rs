@@ -35,6 +35,8 @@ pub enum SqliteStorageError {
         operation: &'static str,
         details: String,
     },
+    #[error("Epoch archive error for operation {operation}: {details}")]
+    ArchiveError { operation: &'static str, details: String },
 }
 
 impl From<SqliteStorageError> for StorageError {
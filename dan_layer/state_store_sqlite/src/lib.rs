@@ -1,6 +1,8 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+mod archive;
+mod connection_options;
 mod error;
 mod reader;
 mod schema;
@@ -11,4 +13,6 @@ mod store;
 // mod tree_store;
 mod writer;
 
+pub use archive::{epoch_archive_path, EpochArchiveStats};
+pub use connection_options::{SqliteConnectionOptions, SqliteJournalMode, SqliteSynchronous};
 pub use store::SqliteStateStore;
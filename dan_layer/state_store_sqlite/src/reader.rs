@@ -72,11 +72,13 @@ use tari_dan_storage::{
         SubstatePledge,
         SubstatePledges,
         SubstateRecord,
+        TransactionOrdering,
         TransactionPoolConfirmedStage,
         TransactionPoolRecord,
         TransactionPoolStage,
         TransactionRecord,
         ValidatorConsensusStats,
+        ValidatorEpochStats,
         VersionedSubstateIdLockIntent,
         Vote,
     },
@@ -86,10 +88,11 @@ use tari_dan_storage::{
 };
 use tari_engine_types::substate::SubstateId;
 use tari_state_tree::{Node, NodeKey, TreeNode, Version};
-use tari_transaction::TransactionId;
+use tari_transaction::{IdempotencyKey, TransactionId};
 use tari_utilities::{hex::Hex, ByteArray};
 
 use crate::{
+    archive::EpochArchiveReader,
     error::SqliteStorageError,
     serialization::{deserialize_hex_try_from, deserialize_json, parse_from_string, serialize_hex},
     sql_models,
@@ -100,13 +103,15 @@ const LOG_TARGET: &str = "tari::dan::storage::state_store_sqlite::reader";
 
 pub struct SqliteStateStoreReadTransaction<'a, TAddr> {
     transaction: SqliteTransaction<'a>,
+    archive: Option<&'a EpochArchiveReader>,
     _addr: PhantomData<TAddr>,
 }
 
 impl<'a, TAddr> SqliteStateStoreReadTransaction<'a, TAddr> {
-    pub(crate) fn new(transaction: SqliteTransaction<'a>) -> Self {
+    pub(crate) fn new(transaction: SqliteTransaction<'a>, archive: Option<&'a EpochArchiveReader>) -> Self {
         Self {
             transaction,
+            archive,
             _addr: PhantomData,
         }
     }
@@ -122,6 +127,32 @@ impl<'a, TAddr> SqliteStateStoreReadTransaction<'a, TAddr> {
     pub(crate) fn rollback(self) -> Result<(), SqliteStorageError> {
         self.transaction.rollback()
     }
+
+    /// Runs the query against the epoch archive of a record that is no longer in the state store. `archived_epoch`
+    /// looks up the epoch of the archive in the state store and returns a not found error if the record has not been
+    /// archived.
+    fn query_archive<T, FEpoch, FQuery>(
+        &self,
+        operation: &'static str,
+        archived_epoch: FEpoch,
+        query: FQuery,
+    ) -> Result<T, SqliteStorageError>
+    where
+        FEpoch: FnOnce(&mut SqliteConnection) -> diesel::QueryResult<i64>,
+        FQuery: FnOnce(&mut SqliteConnection) -> diesel::QueryResult<T>,
+    {
+        let Some(archive) = self.archive else {
+            return Err(SqliteStorageError::DieselError {
+                operation,
+                source: diesel::NotFound,
+            });
+        };
+        let epoch = archived_epoch(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError { operation, source })?;
+        archive.with_archive(Epoch(epoch as u64), |conn| {
+            query(conn).map_err(|source| SqliteStorageError::DieselError { operation, source })
+        })
+    }
 }
 
 impl<'a, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'a> SqliteStateStoreReadTransaction<'a, TAddr> {
@@ -723,6 +754,37 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
         Ok(exists > 0)
     }
 
+    fn transaction_idempotency_keys_get(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        since_epoch: Epoch,
+        from_block_id: &BlockId,
+    ) -> Result<Option<TransactionId>, StorageError> {
+        use crate::schema::transaction_idempotency_keys;
+
+        let commit_block = self.get_commit_block()?;
+        let block_ids = self.get_block_ids_with_commands_between(commit_block.block_id(), from_block_id)?;
+
+        let transaction_id = transaction_idempotency_keys::table
+            .select(transaction_idempotency_keys::transaction_id)
+            .filter(transaction_idempotency_keys::idempotency_key.eq(serialize_hex(idempotency_key)))
+            .filter(transaction_idempotency_keys::epoch.ge(since_epoch.as_u64() as i64))
+            .filter(
+                transaction_idempotency_keys::is_committed
+                    .eq(true)
+                    .or(transaction_idempotency_keys::block_id.eq_any(block_ids)),
+            )
+            .order_by(transaction_idempotency_keys::id.asc())
+            .first::<String>(self.connection())
+            .optional()
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_idempotency_keys_get",
+                source: e,
+            })?;
+
+        transaction_id.as_deref().map(deserialize_hex_try_from).transpose()
+    }
+
     fn transactions_get_any<'a, I: IntoIterator<Item = &'a TransactionId>>(
         &self,
         tx_ids: I,
@@ -852,17 +914,33 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
     }
 
     fn blocks_get(&self, block_id: &BlockId) -> Result<Block, StorageError> {
-        use crate::schema::{blocks, quorum_certificates};
-
-        let (block, qc) = blocks::table
-            .left_join(quorum_certificates::table.on(blocks::qc_id.eq(quorum_certificates::qc_id)))
-            .select((blocks::all_columns, quorum_certificates::all_columns.nullable()))
-            .filter(blocks::block_id.eq(serialize_hex(block_id)))
-            .first::<(sql_models::Block, Option<sql_models::QuorumCertificate>)>(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
+        use crate::schema::{archived_blocks, blocks, quorum_certificates};
+
+        let block_id_hex = serialize_hex(block_id);
+        let query = |conn: &mut SqliteConnection| {
+            blocks::table
+                .left_join(quorum_certificates::table.on(blocks::qc_id.eq(quorum_certificates::qc_id)))
+                .select((blocks::all_columns, quorum_certificates::all_columns.nullable()))
+                .filter(blocks::block_id.eq(&block_id_hex))
+                .first::<(sql_models::Block, Option<sql_models::QuorumCertificate>)>(conn)
+        };
+        let (block, qc) = match query(self.connection()) {
+            // Blocks from old epochs may have been moved to an epoch archive
+            Err(diesel::NotFound) => self.query_archive(
+                "blocks_get",
+                |conn| {
+                    archived_blocks::table
+                        .select(archived_blocks::epoch)
+                        .filter(archived_blocks::block_id.eq(&block_id_hex))
+                        .first(conn)
+                },
+                query,
+            )?,
+            result => result.map_err(|e| SqliteStorageError::DieselError {
                 operation: "blocks_get",
                 source: e,
-            })?;
+            })?,
+        };
 
         let qc = qc.ok_or_else(|| SqliteStorageError::DbInconsistency {
             operation: "blocks_get",
@@ -902,6 +980,8 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
             .select((blocks::all_columns, quorum_certificates::all_columns.nullable()))
             .filter(blocks::epoch.eq(epoch.as_u64() as i64))
             .filter(blocks::height.eq(0))
+            // The zero block is the parent of all genesis blocks
+            .filter(blocks::block_id.ne(serialize_hex(BlockId::zero())))
             .first::<(sql_models::Block, Option<sql_models::QuorumCertificate>)>(self.connection())
             .map_err(|e| SqliteStorageError::DieselError {
                 operation: "blocks_get_genesis_for_epoch",
@@ -1461,16 +1541,32 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
     }
 
     fn quorum_certificates_get(&self, qc_id: &QcId) -> Result<QuorumCertificate, StorageError> {
-        use crate::schema::quorum_certificates;
-
-        let qc_json = quorum_certificates::table
-            .select(quorum_certificates::json)
-            .filter(quorum_certificates::qc_id.eq(serialize_hex(qc_id)))
-            .first::<String>(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
+        use crate::schema::{archived_quorum_certificates, quorum_certificates};
+
+        let qc_id_hex = serialize_hex(qc_id);
+        let query = |conn: &mut SqliteConnection| {
+            quorum_certificates::table
+                .select(quorum_certificates::json)
+                .filter(quorum_certificates::qc_id.eq(&qc_id_hex))
+                .first::<String>(conn)
+        };
+        let qc_json = match query(self.connection()) {
+            // QCs from old epochs may have been moved to an epoch archive
+            Err(diesel::NotFound) => self.query_archive(
+                "quorum_certificates_get",
+                |conn| {
+                    archived_quorum_certificates::table
+                        .select(archived_quorum_certificates::epoch)
+                        .filter(archived_quorum_certificates::qc_id.eq(&qc_id_hex))
+                        .first(conn)
+                },
+                query,
+            )?,
+            result => result.map_err(|e| SqliteStorageError::DieselError {
                 operation: "quorum_certificates_get",
                 source: e,
-            })?;
+            })?,
+        };
 
         deserialize_json(&qc_json)
     }
@@ -1605,6 +1701,7 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
         &self,
         max_txs: usize,
         block_id: &BlockId,
+        ordering: TransactionOrdering,
     ) -> Result<Vec<TransactionPoolRecord>, StorageError> {
         use crate::schema::{lock_conflicts, transaction_pool};
 
@@ -1634,19 +1731,51 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
 
         let new_limit = max_txs.saturating_sub(ready_txs.len());
         if new_limit > 0 {
-            let new_txs = transaction_pool::table
+            let query = transaction_pool::table
                 .filter(transaction_pool::stage.eq(TransactionPoolStage::New.to_string()))
                 .filter(transaction_pool::is_ready.eq(true))
                 // Filter out any transactions that are in lock conflict
                 .filter(transaction_pool::transaction_id.ne_all(lock_conflicts::table.select(lock_conflicts::transaction_id)))
-                .order_by(transaction_pool::transaction_id.asc())
-                .limit(new_limit as i64)
+                .into_boxed();
+
+            let query = match ordering {
+                TransactionOrdering::TransactionId => query
+                    .order_by(transaction_pool::transaction_id.asc())
+                    .limit(new_limit as i64),
+                TransactionOrdering::ArrivalTime => query
+                    .order_by((
+                        transaction_pool::created_at.asc(),
+                        transaction_pool::transaction_id.asc(),
+                    ))
+                    .limit(new_limit as i64),
+                // The shuffle key cannot be computed in SQL, so the oldest candidates are loaded and sorted below
+                TransactionOrdering::Shuffled => query
+                    .order_by((
+                        transaction_pool::created_at.asc(),
+                        transaction_pool::transaction_id.asc(),
+                    ))
+                    .limit(TransactionOrdering::max_shuffle_candidates(new_limit) as i64),
+            };
+
+            let mut new_txs = query
                 .get_results::<sql_models::TransactionPoolRecord>(self.connection())
                 .map_err(|e| SqliteStorageError::DieselError {
                     operation: "transaction_pool_get_many_ready",
                     source: e,
                 })?;
 
+            if ordering.is_shuffled() {
+                let mut keyed = new_txs
+                    .into_iter()
+                    .map(|rec| {
+                        let transaction_id = deserialize_hex_try_from(&rec.transaction_id)?;
+                        Ok((TransactionOrdering::shuffle_key(block_id, &transaction_id), rec))
+                    })
+                    .collect::<Result<Vec<_>, StorageError>>()?;
+                keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+                new_txs = keyed.into_iter().take(new_limit).map(|(_, rec)| rec).collect();
+            }
+
             debug!(
                 target: LOG_TARGET,
                 "🛢️ transaction_pool_get_many_ready: block_id={}, new ready_txs={}, total ready_txs={}",
@@ -1828,6 +1957,21 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
         votes.into_iter().map(Vote::try_from).collect()
     }
 
+    fn votes_get_by_sender(&self, epoch: Epoch, sender_leaf_hash: &FixedHash) -> Result<Vec<Vote>, StorageError> {
+        use crate::schema::votes;
+
+        let votes = votes::table
+            .filter(votes::epoch.eq(epoch.as_u64() as i64))
+            .filter(votes::sender_leaf_hash.eq(serialize_hex(sender_leaf_hash)))
+            .get_results::<sql_models::Vote>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "votes_get_by_sender",
+                source: e,
+            })?;
+
+        votes.into_iter().map(Vote::try_from).collect()
+    }
+
     fn substates_get(&self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError> {
         use crate::schema::substates;
 
@@ -2316,6 +2460,25 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
         checkpoint.try_into()
     }
 
+    fn epoch_handover_checkpoints_get(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+    ) -> Result<EpochCheckpoint, StorageError> {
+        use crate::schema::epoch_handover_checkpoints;
+
+        let checkpoint = epoch_handover_checkpoints::table
+            .filter(epoch_handover_checkpoints::epoch.eq(epoch.as_u64() as i64))
+            .filter(epoch_handover_checkpoints::shard_group.eq(shard_group.encode_as_u32() as i32))
+            .first::<sql_models::EpochHandoverCheckpoint>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "epoch_handover_checkpoints_get",
+                source: e,
+            })?;
+
+        checkpoint.try_into()
+    }
+
     fn foreign_substate_pledges_exists_for_address<T: ToSubstateAddress>(
         &self,
         transaction_id: &TransactionId,
@@ -2460,14 +2623,15 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
     ) -> Result<ValidatorConsensusStats, StorageError> {
         use crate::schema::validator_epoch_stats;
 
-        let (participation_shares, missed_proposals) = validator_epoch_stats::table
+        let (participation_shares, missed_proposals, total_missed_proposals) = validator_epoch_stats::table
             .select((
                 validator_epoch_stats::participation_shares,
                 validator_epoch_stats::missed_proposals,
+                validator_epoch_stats::total_missed_proposals,
             ))
             .filter(validator_epoch_stats::public_key.eq(public_key.to_hex()))
             .filter(validator_epoch_stats::epoch.eq(epoch.as_u64() as i64))
-            .get_result::<(i64, i64)>(self.connection())
+            .get_result::<(i64, i64, i64)>(self.connection())
             .map_err(|e| SqliteStorageError::DieselError {
                 operation: "validator_epoch_stats_get",
                 source: e,
@@ -2484,9 +2648,43 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
                 .map_err(|_| StorageError::DataInconsistency {
                     details: "validator_epoch_stats_get: participation_shares is negative".to_string(),
                 })?,
+            total_missed_proposals: total_missed_proposals
+                .try_into()
+                .map_err(|_| StorageError::DataInconsistency {
+                    details: "validator_epoch_stats_get: total_missed_proposals is negative".to_string(),
+                })?,
         })
     }
 
+    fn validator_epoch_stats_get_all_in_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorEpochStats>, StorageError> {
+        use crate::schema::validator_epoch_stats;
+
+        let mut query = validator_epoch_stats::table
+            .filter(
+                validator_epoch_stats::epoch
+                    .between(epoch_range.start().as_u64() as i64, epoch_range.end().as_u64() as i64),
+            )
+            .into_boxed();
+
+        if let Some(pk) = public_key {
+            query = query.filter(validator_epoch_stats::public_key.eq(pk.to_hex()));
+        }
+
+        let rows = query
+            .order_by(validator_epoch_stats::epoch.asc())
+            .get_results::<sql_models::ValidatorEpochStats>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "validator_epoch_stats_get_all_in_epoch_range",
+                source: e,
+            })?;
+
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
     fn validator_epoch_stats_get_nodes_to_suspend(
         &self,
         block_id: &BlockId,
@@ -2624,6 +2822,28 @@ impl<'tx, TAddr: NodeAddressable + Serialize + DeserializeOwned + 'tx> StateStor
 
         Ok(count as u64)
     }
+
+    fn suspended_nodes_get_all(&self) -> Result<Vec<PublicKey>, StorageError> {
+        use crate::schema::suspended_nodes;
+
+        let pks = suspended_nodes::table
+            .select(suspended_nodes::public_key)
+            .get_results::<String>(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "suspended_nodes_get_all",
+                source: e,
+            })?;
+
+        pks.iter()
+            .map(|s| {
+                PublicKey::from_hex(s).map_err(|e| StorageError::DecodingError {
+                    operation: "suspended_nodes_get_all",
+                    item: "public key",
+                    details: format!("Failed to decode public key: {e}"),
+                })
+            })
+            .collect()
+    }
 }
 
 #[derive(QueryableByName)]
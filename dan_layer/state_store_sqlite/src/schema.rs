@@ -1,5 +1,21 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    archived_blocks (block_id) {
+        block_id -> Text,
+        epoch -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    archived_quorum_certificates (qc_id) {
+        qc_id -> Text,
+        epoch -> BigInt,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     block_diffs (id) {
         id -> Integer,
@@ -110,6 +126,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    epoch_handover_checkpoints (id) {
+        id -> Integer,
+        epoch -> BigInt,
+        shard_group -> Integer,
+        commit_block -> Text,
+        qcs -> Text,
+        shard_roots -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     foreign_missing_transactions (id) {
         id -> Integer,
@@ -361,6 +389,7 @@ diesel::table! {
         shard -> Integer,
         key -> Text,
         node -> Text,
+        stale_since_version -> Nullable<BigInt>,
     }
 }
 
@@ -437,6 +466,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    transaction_idempotency_keys (id) {
+        id -> Integer,
+        idempotency_key -> Text,
+        transaction_id -> Text,
+        block_id -> Text,
+        epoch -> BigInt,
+        is_committed -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     transaction_pool (id) {
         id -> Integer,
@@ -520,6 +561,8 @@ diesel::table! {
         abort_details -> Nullable<Text>,
         min_epoch -> Nullable<BigInt>,
         max_epoch -> Nullable<BigInt>,
+        idempotency_key -> Nullable<Text>,
+        num_reserved_outputs -> Integer,
         created_at -> Timestamp,
     }
 }
@@ -532,6 +575,7 @@ diesel::table! {
         participation_shares -> BigInt,
         missed_proposals -> BigInt,
         missed_proposals_capped -> BigInt,
+        total_missed_proposals -> BigInt,
         created_at -> Timestamp,
     }
 }
@@ -552,12 +596,15 @@ diesel::table! {
 diesel::joinable!(foreign_missing_transactions -> foreign_parked_blocks (parked_block_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    archived_blocks,
+    archived_quorum_certificates,
     block_diffs,
     blocks,
     burnt_utxos,
     diagnostic_deleted_blocks,
     diagnostics_no_votes,
     epoch_checkpoints,
+    epoch_handover_checkpoints,
     foreign_missing_transactions,
     foreign_parked_blocks,
     foreign_proposals,
@@ -583,6 +630,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     substates,
     suspended_nodes,
     transaction_executions,
+    transaction_idempotency_keys,
     transaction_pool,
     transaction_pool_history,
     transaction_pool_state_updates,
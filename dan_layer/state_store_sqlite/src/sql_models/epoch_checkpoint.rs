@@ -28,3 +28,26 @@ impl TryFrom<EpochCheckpoint> for consensus_models::EpochCheckpoint {
         Ok(Self::new(commit_block, qcs, shard_roots))
     }
 }
+
+#[derive(Debug, Clone, Queryable)]
+pub struct EpochHandoverCheckpoint {
+    pub id: i32,
+    pub epoch: i64,
+    pub shard_group: i32,
+    pub commit_block: String,
+    pub qcs: String,
+    pub shard_roots: String,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl TryFrom<EpochHandoverCheckpoint> for consensus_models::EpochCheckpoint {
+    type Error = StorageError;
+
+    fn try_from(value: EpochHandoverCheckpoint) -> Result<Self, Self::Error> {
+        let commit_block = deserialize_json(&value.commit_block)?;
+        let qcs = deserialize_json(&value.qcs)?;
+        let shard_roots = deserialize_json(&value.shard_roots)?;
+
+        Ok(Self::new(commit_block, qcs, shard_roots))
+    }
+}
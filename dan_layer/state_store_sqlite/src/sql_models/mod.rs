@@ -18,6 +18,7 @@ mod substate_lock;
 mod transaction;
 mod transaction_execution;
 mod transaction_pool;
+mod validator_epoch_stats;
 mod vote;
 
 pub use block::*;
@@ -37,4 +38,5 @@ pub use substate_lock::*;
 pub use transaction::*;
 pub use transaction_execution::*;
 pub use transaction_pool::*;
+pub use validator_epoch_stats::*;
 pub use vote::*;
@@ -9,7 +9,7 @@ use tari_dan_storage::{consensus_models, consensus_models::Decision, StorageErro
 use tari_transaction::UnsignedTransaction;
 use time::PrimitiveDateTime;
 
-use crate::serialization::deserialize_json;
+use crate::serialization::{deserialize_hex_try_from, deserialize_json};
 
 #[derive(Debug, Clone, Queryable)]
 pub struct Transaction {
@@ -30,6 +30,8 @@ pub struct Transaction {
     pub abort_details: Option<String>,
     pub min_epoch: Option<i64>,
     pub max_epoch: Option<i64>,
+    pub idempotency_key: Option<String>,
+    pub num_reserved_outputs: i32,
     pub created_at: PrimitiveDateTime,
 }
 
@@ -46,6 +48,11 @@ impl TryFrom<Transaction> for tari_transaction::Transaction {
         let filled_inputs = deserialize_json(&value.filled_inputs)?;
         let min_epoch = value.min_epoch.map(|epoch| Epoch(epoch as u64));
         let max_epoch = value.max_epoch.map(|epoch| Epoch(epoch as u64));
+        let idempotency_key = value
+            .idempotency_key
+            .as_deref()
+            .map(deserialize_hex_try_from)
+            .transpose()?;
 
         Ok(Self::new(
             UnsignedTransaction {
@@ -54,6 +61,8 @@ impl TryFrom<Transaction> for tari_transaction::Transaction {
                 inputs,
                 min_epoch,
                 max_epoch,
+                idempotency_key,
+                num_reserved_outputs: value.num_reserved_outputs as u32,
             },
             signatures,
         )
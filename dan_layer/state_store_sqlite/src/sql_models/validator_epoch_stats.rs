@@ -0,0 +1,48 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use diesel::Queryable;
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::Epoch;
+use tari_dan_storage::{consensus_models, StorageError};
+use tari_utilities::hex::Hex;
+use time::PrimitiveDateTime;
+
+#[derive(Debug, Clone, Queryable)]
+pub struct ValidatorEpochStats {
+    pub id: i32,
+    pub epoch: i64,
+    pub public_key: String,
+    pub participation_shares: i64,
+    pub missed_proposals: i64,
+    pub missed_proposals_capped: i64,
+    pub total_missed_proposals: i64,
+    pub created_at: PrimitiveDateTime,
+}
+
+impl TryFrom<ValidatorEpochStats> for consensus_models::ValidatorEpochStats {
+    type Error = StorageError;
+
+    fn try_from(value: ValidatorEpochStats) -> Result<Self, Self::Error> {
+        let public_key = PublicKey::from_hex(&value.public_key).map_err(|e| StorageError::DecodingError {
+            operation: "try_from",
+            item: "validator_epoch_stats",
+            details: format!("Failed to decode public key: {e}"),
+        })?;
+        let to_u64 = |n: i64, field: &str| {
+            u64::try_from(n).map_err(|_| StorageError::DataInconsistency {
+                details: format!("validator_epoch_stats: {field} is negative"),
+            })
+        };
+
+        Ok(Self {
+            epoch: Epoch(value.epoch as u64),
+            public_key,
+            stats: consensus_models::ValidatorConsensusStats {
+                missed_proposals: to_u64(value.missed_proposals, "missed_proposals")?,
+                participation_shares: to_u64(value.participation_shares, "participation_shares")?,
+                total_missed_proposals: to_u64(value.total_missed_proposals, "total_missed_proposals")?,
+            },
+        })
+    }
+}
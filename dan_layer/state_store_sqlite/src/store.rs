@@ -4,18 +4,22 @@
 use std::{
     fmt,
     marker::PhantomData,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
-use diesel::{sql_query, Connection, RunQueryDsl, SqliteConnection};
+use diesel::{connection::SimpleConnection, sql_query, Connection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use log::log;
 use serde::{de::DeserializeOwned, Serialize};
-use tari_dan_common_types::NodeAddressable;
+use tari_dan_common_types::{Epoch, NodeAddressable};
 use tari_dan_storage::{StateStore, StorageError};
 
 use crate::{
+    archive,
+    archive::{EpochArchiveReader, EpochArchiveStats},
+    connection_options::SqliteConnectionOptions,
     error::SqliteStorageError,
     reader::SqliteStateStoreReadTransaction,
     sqlite_transaction::SqliteTransaction,
@@ -24,16 +28,30 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::storage::sqlite::state_store";
 
+pub(crate) const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
+
 pub struct SqliteStateStore<TAddr> {
     connection: Arc<Mutex<SqliteConnection>>,
+    archive: Option<Arc<EpochArchiveReader>>,
     _addr: PhantomData<TAddr>,
 }
 
 impl<TAddr> SqliteStateStore<TAddr> {
     pub fn connect(url: &str) -> Result<Self, StorageError> {
+        Self::connect_with_options(url, &SqliteConnectionOptions::default())
+    }
+
+    pub fn connect_with_options(url: &str, options: &SqliteConnectionOptions) -> Result<Self, StorageError> {
         let mut connection = SqliteConnection::establish(url).map_err(SqliteStorageError::from)?;
 
-        const MIGRATIONS: EmbeddedMigrations = embed_migrations!("./migrations");
+        // Set the pragmas before running migrations so that the busy timeout applies to them
+        connection
+            .batch_execute(&options.to_pragmas())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "set connection pragmas",
+            })?;
+
         connection
             .run_pending_migrations(MIGRATIONS)
             .map_err(|source| SqliteStorageError::MigrationError { source })?;
@@ -47,6 +65,7 @@ impl<TAddr> SqliteStateStore<TAddr> {
 
         Ok(Self {
             connection: Arc::new(Mutex::new(connection)),
+            archive: None,
             _addr: PhantomData,
         })
     }
@@ -60,6 +79,42 @@ impl<TAddr> SqliteStateStore<TAddr> {
             })?;
         Ok(())
     }
+
+    /// Reads blocks and quorum certificates that have been archived from the epoch archives in `archive_dir`
+    pub fn with_epoch_archive<P: Into<PathBuf>>(mut self, archive_dir: P) -> Result<Self, StorageError> {
+        self.archive = Some(Arc::new(EpochArchiveReader::new(archive_dir.into())?));
+        Ok(self)
+    }
+
+    /// Opens the archive for an epoch in read-only mode. The returned store can be queried using read transactions
+    /// like the hot state store, but only contains the archived blocks, quorum certificates and block diffs for the
+    /// epoch.
+    pub fn open_epoch_archive<P: AsRef<Path>>(archive_dir: P, epoch: Epoch) -> Result<Self, StorageError> {
+        let path = archive::epoch_archive_path(archive_dir, epoch);
+        let connection = archive::open_archive_read_only(&path)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            archive: None,
+            _addr: PhantomData,
+        })
+    }
+
+    /// Returns the epochs before `before_epoch` that have committed blocks in the hot state store
+    pub fn get_archivable_epochs(&self, before_epoch: Epoch) -> Result<Vec<Epoch>, StorageError> {
+        let epochs = archive::get_archivable_epochs(&mut self.connection.lock().unwrap(), before_epoch)?;
+        Ok(epochs)
+    }
+
+    /// Moves the committed blocks, quorum certificates and block diffs for the epoch into an archive database in
+    /// `archive_dir`. Other transactions wait until archiving is complete.
+    pub fn archive_epoch<P: AsRef<Path>>(
+        &self,
+        archive_dir: P,
+        epoch: Epoch,
+    ) -> Result<EpochArchiveStats, StorageError> {
+        let stats = archive::archive_epoch(&mut self.connection.lock().unwrap(), archive_dir.as_ref(), epoch)?;
+        Ok(stats)
+    }
 }
 
 // Manually implement the Debug implementation because `SqliteConnection` does not implement the Debug trait
@@ -80,13 +135,13 @@ impl<TAddr: NodeAddressable + Serialize + DeserializeOwned> StateStore for Sqlit
 
     fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
         let tx = SqliteTransaction::begin(self.connection.lock().unwrap())?;
-        Ok(SqliteStateStoreReadTransaction::new(tx))
+        Ok(SqliteStateStoreReadTransaction::new(tx, self.archive.as_deref()))
     }
 
     fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
         let timer = Instant::now();
         let tx = SqliteTransaction::begin(self.connection.lock().unwrap())?;
-        let tx = SqliteStateStoreWriteTransaction::new(tx);
+        let tx = SqliteStateStoreWriteTransaction::new(tx, self.archive.as_deref());
         let elapsed = timer.elapsed();
         let level = if elapsed > Duration::from_secs(1) {
             log::Level::Warn
@@ -106,6 +161,7 @@ impl<TAddr> Clone for SqliteStateStore<TAddr> {
     fn clone(&self) -> Self {
         Self {
             connection: self.connection.clone(),
+            archive: self.archive.clone(),
             _addr: PhantomData,
         }
     }
@@ -1,7 +1,12 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::{cmp, iter::Peekable, ops::Deref};
+use std::{
+    cmp,
+    collections::{hash_map::Entry, HashMap},
+    iter::Peekable,
+    ops::Deref,
+};
 
 use diesel::{
     dsl,
@@ -28,7 +33,6 @@ use tari_dan_common_types::{
     ShardGroup,
     SubstateLockType,
     ToSubstateAddress,
-    VersionedSubstateId,
 };
 use tari_dan_storage::{
     consensus_models::{
@@ -59,7 +63,7 @@ use tari_dan_storage::{
         SubstateLock,
         SubstatePledge,
         SubstatePledges,
-        SubstateRecord,
+        SubstateWrite,
         TransactionPoolConfirmedStage,
         TransactionPoolRecord,
         TransactionPoolStage,
@@ -75,11 +79,12 @@ use tari_dan_storage::{
 };
 use tari_engine_types::substate::SubstateId;
 use tari_state_tree::{Node, NodeKey, StaleTreeNode, TreeNode, Version};
-use tari_transaction::TransactionId;
+use tari_transaction::{IdempotencyKey, TransactionId};
 use tari_utilities::{hex::Hex, ByteArray};
 use time::{OffsetDateTime, PrimitiveDateTime};
 
 use crate::{
+    archive::EpochArchiveReader,
     error::SqliteStorageError,
     reader::SqliteStateStoreReadTransaction,
     serialization::{deserialize_json, serialize_hex, serialize_json},
@@ -89,15 +94,19 @@ use crate::{
 
 const LOG_TARGET: &str = "tari::dan::storage";
 
+/// The maximum number of rows inserted in a single statement. This keeps the number of bound parameters within SQLite's
+/// limit.
+const MAX_ROWS_PER_BATCH_INSERT: usize = 500;
+
 pub struct SqliteStateStoreWriteTransaction<'a, TAddr> {
     /// None indicates if the transaction has been explicitly committed/rolled back
     transaction: Option<SqliteStateStoreReadTransaction<'a, TAddr>>,
 }
 
 impl<'a, TAddr: NodeAddressable> SqliteStateStoreWriteTransaction<'a, TAddr> {
-    pub fn new(transaction: SqliteTransaction<'a>) -> Self {
+    pub(crate) fn new(transaction: SqliteTransaction<'a>, archive: Option<&'a EpochArchiveReader>) -> Self {
         Self {
-            transaction: Some(SqliteStateStoreReadTransaction::new(transaction)),
+            transaction: Some(SqliteStateStoreReadTransaction::new(transaction, archive)),
         }
     }
 
@@ -830,6 +839,8 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
             transactions::abort_details.eq(tx_rec.abort_reason().map(serialize_json).transpose()?),
             transactions::min_epoch.eq(transaction.min_epoch().map(|e| e.as_u64() as i64)),
             transactions::max_epoch.eq(transaction.max_epoch().map(|e| e.as_u64() as i64)),
+            transactions::idempotency_key.eq(transaction.idempotency_key().map(serialize_hex)),
+            transactions::num_reserved_outputs.eq(transaction.num_reserved_outputs() as i32),
         );
 
         diesel::insert_into(transactions::table)
@@ -992,6 +1003,71 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
         Ok(())
     }
 
+    fn transaction_idempotency_keys_insert_all<'a, I: IntoIterator<Item = (&'a IdempotencyKey, &'a TransactionId)>>(
+        &mut self,
+        block_id: &BlockId,
+        epoch: Epoch,
+        idempotency_keys: I,
+    ) -> Result<(), StorageError> {
+        use crate::schema::transaction_idempotency_keys;
+
+        let block_id = serialize_hex(block_id);
+        let values = idempotency_keys
+            .into_iter()
+            .map(|(idempotency_key, transaction_id)| {
+                (
+                    transaction_idempotency_keys::idempotency_key.eq(serialize_hex(idempotency_key)),
+                    transaction_idempotency_keys::transaction_id.eq(serialize_hex(transaction_id)),
+                    transaction_idempotency_keys::block_id.eq(block_id.clone()),
+                    transaction_idempotency_keys::epoch.eq(epoch.as_u64() as i64),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        diesel::insert_into(transaction_idempotency_keys::table)
+            .values(values)
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_idempotency_keys_insert_all",
+                source: e,
+            })?;
+
+        Ok(())
+    }
+
+    fn transaction_idempotency_keys_set_committed(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        use crate::schema::transaction_idempotency_keys;
+
+        diesel::update(transaction_idempotency_keys::table)
+            .filter(transaction_idempotency_keys::block_id.eq(serialize_hex(block_id)))
+            .set(transaction_idempotency_keys::is_committed.eq(true))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_idempotency_keys_set_committed",
+                source: e,
+            })?;
+
+        Ok(())
+    }
+
+    fn transaction_idempotency_keys_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError> {
+        use crate::schema::transaction_idempotency_keys;
+
+        diesel::delete(transaction_idempotency_keys::table)
+            .filter(transaction_idempotency_keys::block_id.eq(serialize_hex(block_id)))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "transaction_idempotency_keys_remove_any_by_block_id",
+                source: e,
+            })?;
+
+        Ok(())
+    }
+
     fn transaction_executions_insert_or_ignore(
         &mut self,
         transaction_execution: &BlockTransactionExecution,
@@ -1602,135 +1678,146 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
         Ok(())
     }
 
-    fn substates_create(&mut self, substate: &SubstateRecord) -> Result<(), StorageError> {
+    fn substates_write_many(&mut self, writes: &[SubstateWrite]) -> Result<(), StorageError> {
         use crate::schema::{state_transitions, substates};
 
-        if substate.is_destroyed() {
-            return Err(StorageError::QueryError {
-                reason: format!(
-                    "calling substates_create with a destroyed SubstateRecord is not valid. substate_id = {}",
-                    substate.substate_id
-                ),
-            });
+        if writes.is_empty() {
+            return Ok(());
         }
 
-        let values = (
-            substates::address.eq(serialize_hex(substate.to_substate_address())),
-            substates::substate_id.eq(substate.substate_id.to_string()),
-            substates::version.eq(substate.version as i32),
-            substates::data.eq(serialize_json(&substate.substate_value)?),
-            substates::state_hash.eq(serialize_hex(substate.state_hash)),
-            substates::created_by_transaction.eq(serialize_hex(substate.created_by_transaction)),
-            substates::created_justify.eq(serialize_hex(substate.created_justify)),
-            substates::created_block.eq(serialize_hex(substate.created_block)),
-            substates::created_height.eq(substate.created_height.as_u64() as i64),
-            substates::created_at_epoch.eq(substate.created_at_epoch.as_u64() as i64),
-            substates::created_by_shard.eq(substate.created_by_shard.as_u32() as i32),
-        );
-
-        diesel::insert_into(substates::table)
-            .values(values)
-            .execute(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_create",
-                source: e,
-            })?;
-
-        let seq = state_transitions::table
-            .select(dsl::max(state_transitions::seq))
-            .filter(state_transitions::shard.eq(substate.created_by_shard.as_u32() as i32))
-            .first::<Option<i64>>(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_create",
-                source: e,
-            })?;
-        let next_seq = seq.map(|s| s + 1).unwrap_or(1);
-
-        // This means that we MUST do the state tree updates before inserting substates
-        let version = self.state_tree_versions_get_latest(substate.created_by_shard)?;
-        let values = (
-            state_transitions::seq.eq(next_seq),
-            state_transitions::epoch.eq(substate.created_at_epoch.as_u64() as i64),
-            state_transitions::shard.eq(substate.created_by_shard.as_u32() as i32),
-            state_transitions::substate_address.eq(serialize_hex(substate.to_substate_address())),
-            state_transitions::substate_id.eq(substate.substate_id.to_string()),
-            state_transitions::version.eq(substate.version as i32),
-            state_transitions::transition.eq("UP"),
-            state_transitions::state_hash.eq(serialize_hex(substate.state_hash)),
-            state_transitions::state_version.eq(version.unwrap_or(0) as i64),
-        );
+        let mut new_substates = Vec::new();
+        for write in writes {
+            let SubstateWrite::Create(substate) = write else {
+                continue;
+            };
+            if substate.is_destroyed() {
+                return Err(StorageError::QueryError {
+                    reason: format!(
+                        "calling substates_write_many with a destroyed SubstateRecord is not valid. substate_id = {}",
+                        substate.substate_id
+                    ),
+                });
+            }
 
-        diesel::insert_into(state_transitions::table)
-            .values(values)
-            .execute(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_create",
-                source: e,
-            })?;
+            new_substates.push((
+                substates::address.eq(serialize_hex(substate.to_substate_address())),
+                substates::substate_id.eq(substate.substate_id.to_string()),
+                substates::version.eq(substate.version as i32),
+                substates::data.eq(serialize_json(&substate.substate_value)?),
+                substates::state_hash.eq(serialize_hex(substate.state_hash)),
+                substates::created_by_transaction.eq(serialize_hex(substate.created_by_transaction)),
+                substates::created_justify.eq(serialize_hex(substate.created_justify)),
+                substates::created_block.eq(serialize_hex(substate.created_block)),
+                substates::created_height.eq(substate.created_height.as_u64() as i64),
+                substates::created_at_epoch.eq(substate.created_at_epoch.as_u64() as i64),
+                substates::created_by_shard.eq(substate.created_by_shard.as_u32() as i32),
+            ));
+        }
 
-        Ok(())
-    }
+        for chunk in new_substates.chunks(MAX_ROWS_PER_BATCH_INSERT) {
+            diesel::insert_into(substates::table)
+                .values(chunk)
+                .execute(self.connection())
+                .map_err(|e| SqliteStorageError::DieselError {
+                    operation: "substates_write_many (insert substates)",
+                    source: e,
+                })?;
+        }
 
-    fn substates_down(
-        &mut self,
-        versioned_substate_id: VersionedSubstateId,
-        shard: Shard,
-        epoch: Epoch,
-        destroyed_block_height: NodeHeight,
-        destroyed_transaction_id: &TransactionId,
-        destroyed_qc_id: &QcId,
-    ) -> Result<(), StorageError> {
-        use crate::schema::{state_transitions, substates};
+        // Substates are destroyed after all substates are created, since a substate may be created and destroyed in
+        // the same batch. The update statement is prepared once and reused for each substate.
+        for write in writes {
+            let SubstateWrite::Destroy {
+                versioned_substate_id,
+                shard,
+                epoch,
+                destroyed_by_block,
+                destroyed_by_transaction,
+                destroyed_justify,
+            } = write
+            else {
+                continue;
+            };
 
-        let changes = (
-            substates::destroyed_at.eq(diesel::dsl::now),
-            substates::destroyed_by_transaction.eq(Some(serialize_hex(destroyed_transaction_id))),
-            substates::destroyed_by_block.eq(Some(destroyed_block_height.as_u64() as i64)),
-            substates::destroyed_at_epoch.eq(Some(epoch.as_u64() as i64)),
-            substates::destroyed_by_shard.eq(Some(shard.as_u32() as i32)),
-            substates::destroyed_justify.eq(Some(serialize_hex(destroyed_qc_id))),
-        );
+            let changes = (
+                substates::destroyed_at.eq(diesel::dsl::now),
+                substates::destroyed_by_transaction.eq(Some(serialize_hex(destroyed_by_transaction))),
+                substates::destroyed_by_block.eq(Some(destroyed_by_block.as_u64() as i64)),
+                substates::destroyed_at_epoch.eq(Some(epoch.as_u64() as i64)),
+                substates::destroyed_by_shard.eq(Some(shard.as_u32() as i32)),
+                substates::destroyed_justify.eq(Some(serialize_hex(destroyed_justify))),
+            );
 
-        let address = versioned_substate_id.to_substate_address();
+            diesel::update(substates::table)
+                .filter(substates::address.eq(serialize_hex(versioned_substate_id.to_substate_address())))
+                .set(changes)
+                .execute(self.connection())
+                .map_err(|e| SqliteStorageError::DieselError {
+                    operation: "substates_write_many (update substates)",
+                    source: e,
+                })?;
+        }
 
-        diesel::update(substates::table)
-            .filter(substates::address.eq(serialize_hex(address)))
-            .set(changes)
-            .execute(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_down (update substates)",
-                source: e,
-            })?;
+        // The next transition sequence number and the state tree version are fetched once for each shard. This
+        // means that we MUST do the state tree updates before writing substates.
+        let mut shard_transitions = HashMap::<Shard, (i64, Version)>::new();
+        let mut transitions = Vec::with_capacity(writes.len());
+        for write in writes {
+            let (shard, epoch, versioned_substate_id, transition, state_hash) = match write {
+                SubstateWrite::Create(substate) => (
+                    substate.created_by_shard,
+                    substate.created_at_epoch,
+                    substate.to_versioned_substate_id(),
+                    "UP".to_string(),
+                    Some(serialize_hex(substate.state_hash)),
+                ),
+                SubstateWrite::Destroy {
+                    versioned_substate_id,
+                    shard,
+                    epoch,
+                    ..
+                } => (*shard, *epoch, versioned_substate_id.clone(), "DOWN".to_string(), None),
+            };
 
-        let seq = state_transitions::table
-            .select(dsl::max(state_transitions::seq))
-            .filter(state_transitions::shard.eq(shard.as_u32() as i32))
-            .first::<Option<i64>>(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_down (get max seq)",
-                source: e,
-            })?;
-        let next_seq = seq.map(|s| s + 1).unwrap_or(1);
+            let (next_seq, state_version) = match shard_transitions.entry(shard) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let seq = state_transitions::table
+                        .select(dsl::max(state_transitions::seq))
+                        .filter(state_transitions::shard.eq(shard.as_u32() as i32))
+                        .first::<Option<i64>>(self.connection())
+                        .map_err(|e| SqliteStorageError::DieselError {
+                            operation: "substates_write_many (get max seq)",
+                            source: e,
+                        })?;
+                    let version = self.state_tree_versions_get_latest(shard)?;
+                    entry.insert((seq.map(|s| s + 1).unwrap_or(1), version.unwrap_or(0)))
+                },
+            };
 
-        let version = self.state_tree_versions_get_latest(shard)?;
-        let values = (
-            state_transitions::seq.eq(next_seq),
-            state_transitions::epoch.eq(epoch.as_u64() as i64),
-            state_transitions::shard.eq(shard.as_u32() as i32),
-            state_transitions::substate_address.eq(serialize_hex(address)),
-            state_transitions::substate_id.eq(versioned_substate_id.substate_id.to_string()),
-            state_transitions::version.eq(versioned_substate_id.version as i32),
-            state_transitions::transition.eq("DOWN"),
-            state_transitions::state_version.eq(version.unwrap_or(0) as i64),
-        );
+            transitions.push((
+                state_transitions::seq.eq(*next_seq),
+                state_transitions::epoch.eq(epoch.as_u64() as i64),
+                state_transitions::shard.eq(shard.as_u32() as i32),
+                state_transitions::substate_address.eq(serialize_hex(versioned_substate_id.to_substate_address())),
+                state_transitions::substate_id.eq(versioned_substate_id.substate_id.to_string()),
+                state_transitions::version.eq(versioned_substate_id.version as i32),
+                state_transitions::transition.eq(transition),
+                state_transitions::state_hash.eq(state_hash),
+                state_transitions::state_version.eq(*state_version as i64),
+            ));
+            *next_seq += 1;
+        }
 
-        diesel::insert_into(state_transitions::table)
-            .values(values)
-            .execute(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "substates_down(insert into state_transitions)",
-                source: e,
-            })?;
+        for chunk in transitions.chunks(MAX_ROWS_PER_BATCH_INSERT) {
+            diesel::insert_into(state_transitions::table)
+                .values(chunk)
+                .execute(self.connection())
+                .map_err(|e| SqliteStorageError::DieselError {
+                    operation: "substates_write_many (insert state_transitions)",
+                    source: e,
+                })?;
+        }
 
         Ok(())
     }
@@ -1918,24 +2005,18 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
     fn state_tree_nodes_record_stale_tree_node(
         &mut self,
         shard: Shard,
+        stale_since_version: Version,
         node: StaleTreeNode,
     ) -> Result<(), StorageError> {
         use crate::schema::state_tree;
 
-        //   let num_effected = diesel::update(state_tree::table)
-        //             .filter(state_tree::shard.eq(shard.as_u32() as i32))
-        //             .filter(state_tree::key.eq(key.to_string()))
-        //             .set(state_tree::is_stale.eq(true))
-        //             .execute(self.connection())
-        //             .map_err(|e| SqliteStorageError::DieselError {
-        //                 operation: "state_tree_nodes_mark_stale_tree_node",
-        //                 source: e,
-        //             })?;
-
+        // Stale nodes are kept so that recent versions can still be read, until they are pruned
         let key = node.as_node_key();
-        let num_effected = diesel::delete(state_tree::table)
+        let num_effected = diesel::update(state_tree::table)
             .filter(state_tree::shard.eq(shard.as_u32() as i32))
             .filter(state_tree::key.eq(key.to_string()))
+            .filter(state_tree::stale_since_version.is_null())
+            .set(state_tree::stale_since_version.eq(stale_since_version as i64))
             .execute(self.connection())
             .map_err(|e| SqliteStorageError::DieselError {
                 operation: "state_tree_nodes_mark_stale_tree_node",
@@ -1952,6 +2033,21 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
         Ok(())
     }
 
+    fn state_tree_nodes_prune_stale(&mut self, shard: Shard, up_to_version: Version) -> Result<usize, StorageError> {
+        use crate::schema::state_tree;
+
+        let num_pruned = diesel::delete(state_tree::table)
+            .filter(state_tree::shard.eq(shard.as_u32() as i32))
+            .filter(state_tree::stale_since_version.le(up_to_version as i64))
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "state_tree_nodes_prune_stale",
+                source: e,
+            })?;
+
+        Ok(num_pruned)
+    }
+
     fn state_tree_shard_versions_set(&mut self, shard: Shard, version: Version) -> Result<(), StorageError> {
         use crate::schema::state_tree_shard_versions;
 
@@ -1995,6 +2091,28 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
         Ok(())
     }
 
+    fn epoch_handover_checkpoints_insert(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError> {
+        use crate::schema::epoch_handover_checkpoints;
+
+        let values = (
+            epoch_handover_checkpoints::epoch.eq(checkpoint.block().epoch().as_u64() as i64),
+            epoch_handover_checkpoints::shard_group.eq(checkpoint.block().shard_group().encode_as_u32() as i32),
+            epoch_handover_checkpoints::commit_block.eq(serialize_json(checkpoint.block())?),
+            epoch_handover_checkpoints::qcs.eq(serialize_json(checkpoint.qcs())?),
+            epoch_handover_checkpoints::shard_roots.eq(serialize_json(checkpoint.shard_roots())?),
+        );
+
+        diesel::insert_into(epoch_handover_checkpoints::table)
+            .values(values)
+            .execute(self.connection())
+            .map_err(|e| SqliteStorageError::DieselError {
+                operation: "epoch_handover_checkpoints_insert",
+                source: e,
+            })?;
+
+        Ok(())
+    }
+
     fn burnt_utxos_insert(&mut self, burnt_utxo: &BurntUtxo) -> Result<(), StorageError> {
         use crate::schema::burnt_utxos;
 
@@ -2120,59 +2238,6 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
         Ok(())
     }
 
-    fn validator_epoch_stats_add_participation_share(&mut self, qc_id: &QcId) -> Result<(), StorageError> {
-        use crate::schema::{quorum_certificates, validator_epoch_stats};
-
-        let qc_id = serialize_hex(qc_id);
-        let qc_json = quorum_certificates::table
-            .select(quorum_certificates::json)
-            .filter(quorum_certificates::qc_id.eq(&qc_id))
-            .filter(quorum_certificates::is_shares_processed.eq(false))
-            .first::<String>(self.connection())
-            .optional()
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "validator_epoch_stats_add_participation_share",
-                source: e,
-            })?;
-        let Some(qc_json) = qc_json else {
-            return Ok(());
-        };
-
-        let qc = deserialize_json::<QuorumCertificate>(&qc_json)?;
-        let epoch = qc.epoch().as_u64() as i64;
-
-        for sig in qc.signatures() {
-            let values = (
-                validator_epoch_stats::epoch.eq(epoch),
-                validator_epoch_stats::public_key.eq(serialize_hex(sig.public_key().as_bytes())),
-                validator_epoch_stats::participation_shares.eq(1),
-            );
-
-            diesel::insert_into(validator_epoch_stats::table)
-                .values(values)
-                .on_conflict((validator_epoch_stats::epoch, validator_epoch_stats::public_key))
-                .do_update()
-                .set(validator_epoch_stats::participation_shares.eq(validator_epoch_stats::participation_shares + 1))
-                .execute(self.connection())
-                .map_err(|e| SqliteStorageError::DieselError {
-                    operation: "validator_epoch_stats_add_participation_share",
-                    source: e,
-                })?;
-        }
-
-        // Mark QC shares as processed
-        diesel::update(quorum_certificates::table)
-            .filter(quorum_certificates::qc_id.eq(qc_id))
-            .set(quorum_certificates::is_shares_processed.eq(true))
-            .execute(self.connection())
-            .map_err(|e| SqliteStorageError::DieselError {
-                operation: "validator_epoch_stats_add_participation_share",
-                source: e,
-            })?;
-
-        Ok(())
-    }
-
     fn validator_epoch_stats_updates<'a, I: IntoIterator<Item = ValidatorStatsUpdate<'a>>>(
         &mut self,
         epoch: Epoch,
@@ -2188,10 +2253,11 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
                     validator_epoch_stats::participation_shares,
                     validator_epoch_stats::missed_proposals,
                     validator_epoch_stats::missed_proposals_capped,
+                    validator_epoch_stats::total_missed_proposals,
                 ))
                 .filter(validator_epoch_stats::epoch.eq(epoch))
                 .filter(validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())))
-                .first::<(i64, i64, i64)>(self.connection())
+                .first::<(i64, i64, i64, i64)>(self.connection())
                 .optional()
                 .map_err(|e| SqliteStorageError::DieselError {
                     operation: "validator_epoch_stats_updates",
@@ -2199,61 +2265,68 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
                 })?;
 
             match existing {
-                Some((participation_shares, missed_proposals, missed_proposals_capped)) => match update
-                    .missed_proposal_change()
-                {
-                    Some(0) => {
-                        diesel::update(validator_epoch_stats::table)
-                            .filter(validator_epoch_stats::epoch.eq(epoch))
-                            .filter(validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())))
-                            .set((
-                                validator_epoch_stats::participation_shares
-                                    .eq(participation_shares + update.participation_shares_increment() as i64),
-                                validator_epoch_stats::missed_proposals.eq(0),
-                                validator_epoch_stats::missed_proposals_capped.eq(0),
-                            ))
-                            .execute(self.connection())
-                            .map_err(|e| SqliteStorageError::DieselError {
-                                operation: "validator_epoch_stats_updates",
-                                source: e,
-                            })?;
-                    },
-                    Some(n) => {
-                        // NOTE: n can be negative
-                        let missed_proposal_count = cmp::max(missed_proposals + n, 0);
-                        let capped_missed_proposal_count = update
-                            .max_total_missed_proposals()
-                            .min(cmp::max(missed_proposals_capped + n, 0));
-                        diesel::update(validator_epoch_stats::table)
-                            .filter(validator_epoch_stats::epoch.eq(epoch))
-                            .filter(validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())))
-                            .set((
-                                validator_epoch_stats::participation_shares
-                                    .eq(participation_shares + update.participation_shares_increment() as i64),
-                                validator_epoch_stats::missed_proposals.eq(missed_proposal_count),
-                                validator_epoch_stats::missed_proposals_capped.eq(capped_missed_proposal_count),
-                            ))
-                            .execute(self.connection())
-                            .map_err(|e| SqliteStorageError::DieselError {
-                                operation: "validator_epoch_stats_updates",
-                                source: e,
-                            })?;
-                    },
-
-                    None => {
-                        diesel::update(validator_epoch_stats::table)
-                            .filter(validator_epoch_stats::epoch.eq(epoch))
-                            .filter(validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())))
-                            .set(
-                                validator_epoch_stats::participation_shares
-                                    .eq(participation_shares + update.participation_shares_increment() as i64),
-                            )
-                            .execute(self.connection())
-                            .map_err(|e| SqliteStorageError::DieselError {
-                                operation: "validator_epoch_stats_updates",
-                                source: e,
-                            })?;
-                    },
+                Some((participation_shares, missed_proposals, missed_proposals_capped, total_missed_proposals)) => {
+                    match update.missed_proposal_change() {
+                        Some(0) => {
+                            diesel::update(validator_epoch_stats::table)
+                                .filter(validator_epoch_stats::epoch.eq(epoch))
+                                .filter(
+                                    validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())),
+                                )
+                                .set((
+                                    validator_epoch_stats::participation_shares
+                                        .eq(participation_shares + update.participation_shares_increment() as i64),
+                                    validator_epoch_stats::missed_proposals.eq(0),
+                                    validator_epoch_stats::missed_proposals_capped.eq(0),
+                                ))
+                                .execute(self.connection())
+                                .map_err(|e| SqliteStorageError::DieselError {
+                                    operation: "validator_epoch_stats_updates",
+                                    source: e,
+                                })?;
+                        },
+                        Some(n) => {
+                            // NOTE: n can be negative
+                            let missed_proposal_count = cmp::max(missed_proposals + n, 0);
+                            let capped_missed_proposal_count = update
+                                .max_total_missed_proposals()
+                                .min(cmp::max(missed_proposals_capped + n, 0));
+                            diesel::update(validator_epoch_stats::table)
+                                .filter(validator_epoch_stats::epoch.eq(epoch))
+                                .filter(
+                                    validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())),
+                                )
+                                .set((
+                                    validator_epoch_stats::participation_shares
+                                        .eq(participation_shares + update.participation_shares_increment() as i64),
+                                    validator_epoch_stats::missed_proposals.eq(missed_proposal_count),
+                                    validator_epoch_stats::missed_proposals_capped.eq(capped_missed_proposal_count),
+                                    validator_epoch_stats::total_missed_proposals.eq(total_missed_proposals + n.max(0)),
+                                ))
+                                .execute(self.connection())
+                                .map_err(|e| SqliteStorageError::DieselError {
+                                    operation: "validator_epoch_stats_updates",
+                                    source: e,
+                                })?;
+                        },
+
+                        None => {
+                            diesel::update(validator_epoch_stats::table)
+                                .filter(validator_epoch_stats::epoch.eq(epoch))
+                                .filter(
+                                    validator_epoch_stats::public_key.eq(serialize_hex(update.public_key().as_bytes())),
+                                )
+                                .set(
+                                    validator_epoch_stats::participation_shares
+                                        .eq(participation_shares + update.participation_shares_increment() as i64),
+                                )
+                                .execute(self.connection())
+                                .map_err(|e| SqliteStorageError::DieselError {
+                                    operation: "validator_epoch_stats_updates",
+                                    source: e,
+                                })?;
+                        },
+                    }
                 },
                 None => {
                     let leader_failure_inc = update.missed_proposal_change().map_or(0i64, |set| set.max(0));
@@ -2263,6 +2336,7 @@ impl<'tx, TAddr: NodeAddressable + 'tx> StateStoreWriteTransaction for SqliteSta
                         validator_epoch_stats::participation_shares.eq(update.participation_shares_increment() as i64),
                         validator_epoch_stats::missed_proposals.eq(leader_failure_inc),
                         validator_epoch_stats::missed_proposals_capped.eq(leader_failure_inc),
+                        validator_epoch_stats::total_missed_proposals.eq(leader_failure_inc),
                     );
 
                     diesel::insert_into(validator_epoch_stats::table)
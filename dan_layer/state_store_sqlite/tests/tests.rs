@@ -143,3 +143,170 @@ mod confirm_all_transitions {
         tx.rollback().unwrap();
     }
 }
+
+mod validator_epoch_stats {
+    use tari_common_types::types::PublicKey;
+    use tari_crypto::keys::PublicKey as _;
+    use tari_dan_storage::consensus_models::{ValidatorAggregateStats, ValidatorStatsUpdate};
+
+    use super::*;
+
+    #[test]
+    fn it_aggregates_stats_across_epochs() {
+        let db = create_db();
+        let mut tx = db.create_write_tx().unwrap();
+        let (_, pk1) = PublicKey::random_keypair(&mut OsRng);
+        let (_, pk2) = PublicKey::random_keypair(&mut OsRng);
+
+        tx.validator_epoch_stats_updates(Epoch(1), [
+            ValidatorStatsUpdate::new(&pk1).add_missed_proposal(),
+            ValidatorStatsUpdate::new(&pk2).increment_participation_share(),
+        ])
+        .unwrap();
+        // Resetting the missed proposals does not change the total
+        tx.validator_epoch_stats_updates(Epoch(1), [ValidatorStatsUpdate::new(&pk1).reset_missed_proposals()])
+            .unwrap();
+        tx.validator_epoch_stats_updates(Epoch(2), [
+            ValidatorStatsUpdate::new(&pk1)
+                .add_missed_proposal()
+                .increment_participation_share(),
+            ValidatorStatsUpdate::new(&pk2).increment_participation_share(),
+        ])
+        .unwrap();
+        tx.validator_epoch_stats_updates(Epoch(3), [ValidatorStatsUpdate::new(&pk1).add_missed_proposal()])
+            .unwrap();
+
+        let stats = ValidatorAggregateStats::get(&*tx, Epoch(1)..=Epoch(2), &pk1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats.num_epochs, 2);
+        assert_eq!(stats.first_epoch, Epoch(1));
+        assert_eq!(stats.last_epoch, Epoch(2));
+        assert_eq!(stats.total_missed_proposals, 2);
+        assert_eq!(stats.participation_shares, 1);
+
+        let all = ValidatorAggregateStats::get_all(&*tx, Epoch(0)..=Epoch(3)).unwrap();
+        assert_eq!(all.len(), 2);
+        let stats = all.iter().find(|s| s.public_key == pk2).unwrap();
+        assert_eq!(stats.num_epochs, 2);
+        assert_eq!(stats.participation_shares, 2);
+        assert_eq!(stats.total_missed_proposals, 0);
+
+        assert!(ValidatorAggregateStats::get(&*tx, Epoch(4)..=Epoch(10), &pk1)
+            .unwrap()
+            .is_none());
+
+        tx.rollback().unwrap();
+    }
+}
+
+mod epoch_archive {
+    use tari_dan_common_types::{ExtraData, NumPreshards, ShardGroup};
+    use tari_dan_storage::consensus_models::{
+        AggregateValidatorSignature,
+        QuorumCertificate,
+        QuorumDecision,
+        SignerBitmap,
+    };
+
+    use super::*;
+
+    fn create_block(parent: &Block, height: NodeHeight, epoch: Epoch) -> Block {
+        Block::create(
+            Default::default(),
+            *parent.id(),
+            parent.justify().clone(),
+            height,
+            epoch,
+            parent.shard_group(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            None,
+            EpochTime::now().as_u64(),
+            0,
+            FixedHash::zero(),
+            ExtraData::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_moves_committed_blocks_to_a_queryable_archive() {
+        let archive_dir = tempfile::tempdir().unwrap();
+        let db = create_db().with_epoch_archive(archive_dir.path()).unwrap();
+        let mut tx = db.create_write_tx().unwrap();
+
+        let network = Default::default();
+        let zero_block = Block::zero_block(network, NumPreshards::P64);
+        tx.quorum_certificates_insert(zero_block.justify()).unwrap();
+        zero_block.insert(&mut tx).unwrap();
+        let genesis = Block::genesis(
+            network,
+            Epoch(1),
+            ShardGroup::all_shards(NumPreshards::P64),
+            NumPreshards::P64,
+            FixedHash::zero(),
+            None,
+            FixedHash::zero(),
+            None,
+        );
+        tx.quorum_certificates_insert(genesis.justify()).unwrap();
+        genesis.insert(&mut tx).unwrap();
+        let block1 = create_block(&genesis, NodeHeight(1), Epoch(1));
+        block1.insert(&mut tx).unwrap();
+        let block2 = create_block(&block1, NodeHeight(2), Epoch(2));
+        block2.insert(&mut tx).unwrap();
+        for block in [&genesis, &block1, &block2] {
+            tx.blocks_set_flags(block.id(), Some(true), None).unwrap();
+        }
+        // A QC for the epoch that is not referenced by any block
+        let qc = QuorumCertificate::new(
+            *block1.id(),
+            block1.height(),
+            block1.epoch(),
+            block1.shard_group(),
+            SignerBitmap::default(),
+            AggregateValidatorSignature::default(),
+            vec![],
+            QuorumDecision::Accept,
+        );
+        tx.quorum_certificates_insert(&qc).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(db.get_archivable_epochs(Epoch(2)).unwrap(), vec![Epoch(1)]);
+        let stats = db.archive_epoch(archive_dir.path(), Epoch(1)).unwrap();
+        assert_eq!(stats.num_blocks, 2);
+        assert_eq!(stats.num_quorum_certificates, 2);
+
+        {
+            let tx = db.create_read_tx().unwrap();
+            assert!(!tx.blocks_exists(genesis.id()).unwrap());
+            // Kept because it is the parent of a block in the next epoch
+            assert!(tx.blocks_exists(block1.id()).unwrap());
+            assert!(tx.blocks_exists(zero_block.id()).unwrap());
+            // Archived records can still be read through the state store
+            let block = tx.blocks_get(genesis.id()).unwrap();
+            assert_eq!(block.justify().id(), genesis.justify().id());
+            assert_eq!(tx.quorum_certificates_get(qc.id()).unwrap().id(), qc.id());
+        }
+
+        // Archiving again is idempotent
+        let stats = db.archive_epoch(archive_dir.path(), Epoch(1)).unwrap();
+        assert!(stats.is_empty());
+
+        let archive = SqliteStateStore::<String>::open_epoch_archive(archive_dir.path(), Epoch(1)).unwrap();
+        let tx = archive.create_read_tx().unwrap();
+        let block = tx.blocks_get(genesis.id()).unwrap();
+        assert_eq!(block.id(), genesis.id());
+        assert_eq!(block.justify().id(), genesis.justify().id());
+        assert!(tx.blocks_exists(block1.id()).unwrap());
+        drop(tx);
+
+        let mut tx = archive.create_write_tx().unwrap();
+        tx.blocks_set_flags(genesis.id(), Some(false), None).unwrap_err();
+        tx.rollback().unwrap();
+    }
+}
@@ -3,7 +3,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::jellyfish::{JmtStorageError, Node, NodeKey};
+use crate::jellyfish::{JmtStorageError, Node, NodeKey, Version};
 
 /// Implementers are able to read nodes from a tree store.
 pub trait TreeStoreReader<P> {
@@ -17,8 +17,13 @@ pub trait TreeStoreWriter<P> {
     fn insert_node(&mut self, key: NodeKey, node: Node<P>) -> Result<(), JmtStorageError>;
 
     /// Marks the given tree part for a (potential) future removal by an arbitrary external pruning
-    /// process.
-    fn record_stale_tree_node(&mut self, part: StaleTreeNode) -> Result<(), JmtStorageError>;
+    /// process. The part is no longer referenced by the tree from `stale_since_version` onwards, but is still required
+    /// to read earlier versions.
+    fn record_stale_tree_node(
+        &mut self,
+        stale_since_version: Version,
+        part: StaleTreeNode,
+    ) -> Result<(), JmtStorageError>;
 }
 
 /// Implementers are able to read and write nodes to a tree store.
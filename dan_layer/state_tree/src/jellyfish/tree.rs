@@ -610,6 +610,29 @@ impl<'a, R: 'a + TreeStoreReader<P>, P: Clone> JellyfishMerkleTree<'a, R, P> {
         self.get_root_node(version).map(|n| n.leaf_count())
     }
 
+    /// Walks the tree at the given version, checking that every node exists and hashes to the value committed to by its
+    /// parent. Returns the root hash if the tree is consistent.
+    pub fn check_consistency(&self, version: Version) -> Result<Hash, JmtStorageError> {
+        let root_node_key = NodeKey::new_empty_path(version);
+        let root = self.reader.get_node(&root_node_key)?;
+        self.check_consistency_impl(&root_node_key, &root)?;
+        Ok(root.hash())
+    }
+
+    fn check_consistency_impl(&self, key: &NodeKey, node: &Node<P>) -> Result<(), JmtStorageError> {
+        if let Node::Internal(internal_node) = node {
+            for (child_nibble, child) in internal_node.children_sorted() {
+                let child_key = key.gen_child_node_key(child.version, *child_nibble);
+                let child_node = self.reader.get_node(&child_key)?;
+                if child_node.hash() != child.hash {
+                    return Err(JmtStorageError::InconsistentState);
+                }
+                self.check_consistency_impl(&child_key, &child_node)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_all_nodes_referenced(&self, key: NodeKey) -> Result<Vec<NodeKey>, JmtStorageError> {
         let mut out_keys = vec![];
         self.get_all_nodes_referenced_impl(key, &mut out_keys)?;
@@ -755,7 +778,8 @@ mod tests {
         }
 
         for a in diff.stale_node_index_batch {
-            mem.record_stale_tree_node(StaleTreeNode::Node(a.node_key)).unwrap();
+            mem.record_stale_tree_node(a.stale_since_version, StaleTreeNode::Node(a.node_key))
+                .unwrap();
         }
         mem.clear_stale_nodes();
 
@@ -773,7 +797,8 @@ mod tests {
             mem.insert_node(k, v).unwrap();
         }
         for a in diff.stale_node_index_batch {
-            mem.record_stale_tree_node(StaleTreeNode::Node(a.node_key)).unwrap();
+            mem.record_stale_tree_node(a.stale_since_version, StaleTreeNode::Node(a.node_key))
+                .unwrap();
         }
         mem.clear_stale_nodes();
         let jmt = JellyfishMerkleTree::new(&mem);
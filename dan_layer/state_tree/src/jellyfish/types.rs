@@ -163,6 +163,85 @@ impl SparseMerkleProof {
     pub fn siblings(&self) -> &[Hash] {
         &self.siblings
     }
+
+    // SOURCE: https://github.com/aptos-labs/aptos-core/blob/1.0.4/types/src/proof/definition.rs#L239
+    /// If `element_value_hash` is present, verifies an element whose key is `element_key` and value hash is
+    /// `element_value_hash` exists in the Sparse Merkle Tree using the provided proof. Otherwise verifies the proof is
+    /// a valid non-inclusion proof that shows this key doesn't exist in the tree.
+    pub fn verify(
+        &self,
+        expected_root_hash: &Hash,
+        element_key: &LeafKey,
+        element_value_hash: Option<&Hash>,
+    ) -> Result<(), SparseMerkleProofError> {
+        let num_bits = element_key.bytes.as_slice().len() * 8;
+        if self.siblings.len() > num_bits {
+            return Err(SparseMerkleProofError::TooManySiblings {
+                num_siblings: self.siblings.len(),
+            });
+        }
+
+        match (element_value_hash, &self.leaf) {
+            (Some(value_hash), Some(leaf)) => {
+                // This is an inclusion proof, so the key and value hash provided in the proof should match
+                // element_key and element_value_hash. `siblings` should prove the route from the leaf node to the
+                // root.
+                if element_key != leaf.key() {
+                    return Err(SparseMerkleProofError::KeyMismatch);
+                }
+                if value_hash != leaf.value_hash() {
+                    return Err(SparseMerkleProofError::ValueHashMismatch);
+                }
+            },
+            (Some(_), None) => return Err(SparseMerkleProofError::ExpectedInclusionProof),
+            (None, Some(leaf)) => {
+                // This is a non-inclusion proof. The proof intends to show that if a leaf node representing
+                // `element_key` is inserted, it will break a currently existing leaf node represented by
+                // `proof_key` into a branch. `siblings` should prove the route from that leaf node to the root.
+                if element_key == leaf.key() {
+                    return Err(SparseMerkleProofError::ExpectedNonInclusionProof);
+                }
+                let common_prefix_bits_len = element_key
+                    .iter_bits()
+                    .zip(leaf.key().iter_bits())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if common_prefix_bits_len < self.siblings.len() {
+                    return Err(SparseMerkleProofError::KeyPrefixTooShort);
+                }
+            },
+            (None, None) => {
+                // This is a non-inclusion proof. The proof intends to show that if a leaf node representing
+                // `element_key` is inserted, it will show up at a currently empty position. `sibling` should prove
+                // the route from this empty position to the root.
+            },
+        }
+
+        let current_hash = self
+            .leaf
+            .as_ref()
+            .map_or(SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash());
+        let actual_root_hash = self
+            .siblings
+            .iter()
+            .zip(element_key.iter_bits().rev().skip(num_bits - self.siblings.len()))
+            .fold(current_hash, |hash, (sibling_hash, bit)| {
+                if bit {
+                    SparseMerkleInternalNode::new(*sibling_hash, hash).hash()
+                } else {
+                    SparseMerkleInternalNode::new(hash, *sibling_hash).hash()
+                }
+            });
+
+        if actual_root_hash != *expected_root_hash {
+            return Err(SparseMerkleProofError::RootHashMismatch {
+                expected: *expected_root_hash,
+                actual: actual_root_hash,
+            });
+        }
+
+        Ok(())
+    }
 }
 
 /// A proof that can be used to authenticate an element in a Sparse Merkle Tree given trusted root
@@ -1231,6 +1310,25 @@ impl<P: Clone> Node<P> {
     }
 }
 
+/// Error returned when a sparse merkle proof fails to verify.
+#[derive(Debug, thiserror::Error)]
+pub enum SparseMerkleProofError {
+    #[error("Sparse merkle proof has more siblings ({num_siblings}) than there are bits in the key")]
+    TooManySiblings { num_siblings: usize },
+    #[error("Key in proof does not match the expected key")]
+    KeyMismatch,
+    #[error("Value hash in proof does not match the expected value hash")]
+    ValueHashMismatch,
+    #[error("Expected an inclusion proof but found a non-inclusion proof")]
+    ExpectedInclusionProof,
+    #[error("Expected a non-inclusion proof but found an inclusion proof")]
+    ExpectedNonInclusionProof,
+    #[error("Key in non-inclusion proof does not share enough prefix bits with the expected key")]
+    KeyPrefixTooShort,
+    #[error("Root hash mismatch: expected {expected}, actual {actual}")]
+    RootHashMismatch { expected: Hash, actual: Hash },
+}
+
 // INITIAL-MODIFICATION: we propagate usage of our own error enum (instead of `std::io::ErrorKind`
 // used by Aptos) to allow for no-std build.
 /// Error originating from underlying storage failure / inconsistency.
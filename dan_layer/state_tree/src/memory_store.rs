@@ -3,12 +3,21 @@
 
 use std::{collections::HashMap, fmt, fmt::Debug};
 
-use crate::jellyfish::{JmtStorageError, Node, NodeKey, StaleTreeNode, TreeNode, TreeStoreReader, TreeStoreWriter};
+use crate::jellyfish::{
+    JmtStorageError,
+    Node,
+    NodeKey,
+    StaleTreeNode,
+    TreeNode,
+    TreeStoreReader,
+    TreeStoreWriter,
+    Version,
+};
 
 #[derive(Debug, Default)]
 pub struct MemoryTreeStore<P> {
     pub nodes: HashMap<NodeKey, TreeNode<P>>,
-    pub stale_nodes: Vec<StaleTreeNode>,
+    pub stale_nodes: Vec<(Version, StaleTreeNode)>,
 }
 
 impl<P> MemoryTreeStore<P> {
@@ -20,10 +29,26 @@ impl<P> MemoryTreeStore<P> {
     }
 
     pub fn clear_stale_nodes(&mut self) {
-        for stale in self.stale_nodes.drain(..) {
+        for (_, stale) in self.stale_nodes.drain(..) {
             self.nodes.remove(stale.as_node_key());
         }
     }
+
+    /// Removes all nodes that have been stale since `up_to_version` or earlier. Versions before `up_to_version` can no
+    /// longer be read after this. Returns the number of nodes removed.
+    pub fn prune_stale_nodes(&mut self, up_to_version: Version) -> usize {
+        let mut num_pruned = 0;
+        self.stale_nodes.retain(|(stale_since_version, stale)| {
+            if *stale_since_version > up_to_version {
+                return true;
+            }
+            if self.nodes.remove(stale.as_node_key()).is_some() {
+                num_pruned += 1;
+            }
+            false
+        });
+        num_pruned
+    }
 }
 
 impl<P: Clone> TreeStoreReader<P> for MemoryTreeStore<P> {
@@ -42,8 +67,12 @@ impl<P> TreeStoreWriter<P> for MemoryTreeStore<P> {
         Ok(())
     }
 
-    fn record_stale_tree_node(&mut self, stale: StaleTreeNode) -> Result<(), JmtStorageError> {
-        self.stale_nodes.push(stale);
+    fn record_stale_tree_node(
+        &mut self,
+        stale_since_version: Version,
+        stale: StaleTreeNode,
+    ) -> Result<(), JmtStorageError> {
+        self.stale_nodes.push((stale_since_version, stale));
         Ok(())
     }
 }
@@ -58,8 +87,8 @@ impl<P: Debug> fmt::Display for MemoryTreeStore<P> {
             writeln!(f, "    {}: {:?}", key, node)?;
         }
         writeln!(f, "  Stale Nodes:")?;
-        for stale in &self.stale_nodes {
-            writeln!(f, "    {}", stale.as_node_key())?;
+        for (stale_since_version, stale) in &self.stale_nodes {
+            writeln!(f, "    {} (since v{})", stale.as_node_key(), stale_since_version)?;
         }
         Ok(())
     }
@@ -5,7 +5,16 @@ use std::collections::{HashMap, VecDeque};
 
 use log::debug;
 
-use crate::{JmtStorageError, Node, NodeKey, StaleTreeNode, StateHashTreeDiff, TreeStoreReader, TreeStoreWriter};
+use crate::{
+    JmtStorageError,
+    Node,
+    NodeKey,
+    StaleTreeNode,
+    StateHashTreeDiff,
+    TreeStoreReader,
+    TreeStoreWriter,
+    Version,
+};
 
 const LOG_TARGET: &str = "tari::dan::consensus::sharded_state_tree";
 
@@ -70,7 +79,12 @@ impl<'s, S, P> TreeStoreWriter<P> for StagedTreeStore<'s, S, P> {
         Ok(())
     }
 
-    fn record_stale_tree_node(&mut self, stale: StaleTreeNode) -> Result<(), JmtStorageError> {
+    fn record_stale_tree_node(
+        &mut self,
+        _stale_since_version: Version,
+        stale: StaleTreeNode,
+    ) -> Result<(), JmtStorageError> {
+        // The diff is versioned as a whole, so the version is recorded when the diff is committed
         // Prune staged tree nodes immediately from preceding_pending_state.
         let mut remove_queue = VecDeque::new();
         remove_queue.push_front(stale.as_node_key().clone());
@@ -8,7 +8,7 @@ use tari_engine_types::substate::SubstateId;
 
 use crate::{
     error::StateTreeError,
-    jellyfish::{Hash, JellyfishMerkleTree, SparseMerkleProofExt, TreeStore, Version},
+    jellyfish::{Hash, JellyfishMerkleTree, SparseMerkleProof, SparseMerkleProofExt, TreeStore, Version},
     key_mapper::{DbKeyMapper, HashIdentityKeyMapper, SpreadPrefixKeyMapper},
     memory_store::MemoryTreeStore,
     Node,
@@ -54,6 +54,14 @@ impl<'a, S: TreeStoreReader<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a,
         let root_hash = smt.get_root_hash(version)?;
         Ok(root_hash)
     }
+
+    /// Checks that every node of the tree at the given version is present in the store and matches the hash that its
+    /// parent commits to. Returns the root hash if the tree is consistent.
+    pub fn check_consistency(&self, version: Version) -> Result<Hash, StateTreeError> {
+        let smt = JellyfishMerkleTree::new(self.store);
+        let root_hash = smt.check_consistency(version)?;
+        Ok(root_hash)
+    }
 }
 
 impl<'a, S: TreeStore<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a, S, M> {
@@ -76,11 +84,11 @@ impl<'a, S: TreeStore<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a, S, M>
         changes: I,
     ) -> Result<Hash, StateTreeError> {
         let (root_hash, update_batch) = self.calculate_substate_changes(current_version, next_version, changes)?;
-        self.commit_diff(update_batch)?;
+        self.commit_diff(next_version, update_batch)?;
         Ok(root_hash)
     }
 
-    pub fn commit_diff(&mut self, diff: StateHashTreeDiff<Version>) -> Result<(), StateTreeError> {
+    pub fn commit_diff(&mut self, version: Version, diff: StateHashTreeDiff<Version>) -> Result<(), StateTreeError> {
         for (key, node) in diff.new_nodes {
             log::debug!("Inserting node: {}", key);
             self.store.insert_node(key, node)?;
@@ -88,7 +96,7 @@ impl<'a, S: TreeStore<Version>, M: DbKeyMapper<SubstateId>> StateTree<'a, S, M>
 
         for stale_tree_node in diff.stale_tree_nodes {
             log::debug!("Recording stale tree node: {}", stale_tree_node.as_node_key());
-            self.store.record_stale_tree_node(stale_tree_node)?;
+            self.store.record_stale_tree_node(version, stale_tree_node)?;
         }
 
         Ok(())
@@ -109,8 +117,10 @@ impl<'a, S: TreeStore<()>, M: DbKeyMapper<Hash>> StateTree<'a, S, M> {
         }
 
         for stale_tree_node in update_result.stale_node_index_batch {
-            self.store
-                .record_stale_tree_node(StaleTreeNode::Node(stale_tree_node.node_key))?;
+            self.store.record_stale_tree_node(
+                stale_tree_node.stale_since_version,
+                StaleTreeNode::Node(stale_tree_node.node_key),
+            )?;
         }
 
         Ok(root_hash)
@@ -209,3 +219,20 @@ pub fn compute_merkle_root_for_hashes<I: Iterator<Item = Hash>>(
     let (hash, _) = root_tree.compute_update_batch(None, 1, hashes)?;
     Ok(hash)
 }
+
+/// Returns a proof that `hash` is (or is not) included in the merkle root computed by `compute_merkle_root_for_hashes`
+/// for the same hashes.
+pub fn compute_merkle_proof_for_hashes<I: Iterator<Item = Hash>>(
+    mut hashes: Peekable<I>,
+    hash: &Hash,
+) -> Result<SparseMerkleProof, StateTreeError> {
+    if hashes.peek().is_none() {
+        return Ok(SparseMerkleProof::new(None, vec![]));
+    }
+    let mut mem_store = MemoryTreeStore::new();
+    RootStateTree::new(&mut mem_store).put_changes(None, 1, hashes)?;
+    let jmt = JellyfishMerkleTree::<_, ()>::new(&mem_store);
+    let key = HashIdentityKeyMapper::map_to_leaf_key(hash);
+    let (_, proof) = jmt.get_with_proof(key.as_ref(), 1)?;
+    Ok(proof)
+}
@@ -5,7 +5,17 @@
 use std::collections::HashSet;
 
 use itertools::Itertools;
-use tari_state_tree::{memory_store::MemoryTreeStore, StaleTreeNode, Version, SPARSE_MERKLE_PLACEHOLDER_HASH};
+use tari_state_tree::{
+    compute_merkle_proof_for_hashes,
+    compute_merkle_root_for_hashes,
+    memory_store::MemoryTreeStore,
+    Hash,
+    LeafKey,
+    SpreadPrefixStateTree,
+    StaleTreeNode,
+    Version,
+    SPARSE_MERKLE_PLACEHOLDER_HASH,
+};
 
 use crate::support::{change, HashTreeTester};
 mod support;
@@ -154,7 +164,7 @@ fn records_stale_tree_node_keys() {
         .tree_store
         .stale_nodes
         .iter()
-        .map(|stale_part| {
+        .map(|(_, stale_part)| {
             let StaleTreeNode::Node(key) = stale_part else {
                 panic!("expected only single node removals");
             };
@@ -182,3 +192,55 @@ fn serialized_keys_are_strictly_increasing() {
     let max_previous_key = previous_keys.iter().max().unwrap();
     assert!(min_next_key > max_previous_key);
 }
+
+#[test]
+fn prunes_stale_nodes_up_to_version() {
+    let mut tester = HashTreeTester::new_empty();
+    tester.put_substate_changes(vec![change(4, Some(30))]);
+    tester.put_substate_changes(vec![change(3, Some(70))]);
+    tester.put_substate_changes(vec![change(3, Some(80))]);
+    let hash_v4 = tester.put_substate_changes(vec![change(4, Some(40))]);
+
+    let num_pruned = tester.tree_store.prune_stale_nodes(2);
+    assert!(num_pruned > 0);
+
+    // Versions from the pruning point onwards are intact
+    let state_tree = SpreadPrefixStateTree::new(&mut tester.tree_store);
+    state_tree.check_consistency(2).unwrap();
+    assert_eq!(state_tree.check_consistency(4).unwrap(), hash_v4);
+    // Earlier versions can no longer be read
+    assert!(state_tree.check_consistency(1).is_err());
+
+    // Pruning again has no effect
+    assert_eq!(tester.tree_store.prune_stale_nodes(2), 0);
+}
+
+#[test]
+fn proofs_for_hashes_verify_against_merkle_root() {
+    let hashes = (1u8..=20).map(|n| Hash::from([n; 32])).collect::<Vec<_>>();
+    let root = compute_merkle_root_for_hashes(hashes.iter().copied().peekable()).unwrap();
+
+    for hash in &hashes {
+        let proof = compute_merkle_proof_for_hashes(hashes.iter().copied().peekable(), hash).unwrap();
+        proof.verify(&root, &LeafKey::new(*hash), Some(hash)).unwrap();
+        // The proof does not verify against a different root
+        proof
+            .verify(&SPARSE_MERKLE_PLACEHOLDER_HASH, &LeafKey::new(*hash), Some(hash))
+            .unwrap_err();
+    }
+
+    // Non-inclusion
+    let missing = Hash::from([0xaa; 32]);
+    let proof = compute_merkle_proof_for_hashes(hashes.iter().copied().peekable(), &missing).unwrap();
+    proof.verify(&root, &LeafKey::new(missing), None).unwrap();
+    proof.verify(&root, &LeafKey::new(missing), Some(&missing)).unwrap_err();
+}
+
+#[test]
+fn proof_for_single_hash_verifies() {
+    let hash = Hash::from([1u8; 32]);
+    let root = compute_merkle_root_for_hashes(std::iter::once(hash).peekable()).unwrap();
+    let proof = compute_merkle_proof_for_hashes(std::iter::once(hash).peekable(), &hash).unwrap();
+    assert!(proof.siblings().is_empty());
+    proof.verify(&root, &LeafKey::new(hash), Some(&hash)).unwrap();
+}
@@ -0,0 +1,229 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use blake2::{digest::consts::U64, Blake2b};
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{PrivateKey, PublicKey};
+use tari_crypto::keys::{PublicKey as _, SecretKey};
+use tari_dan_common_types::{hasher::tari_hasher, hashing::TariDanConsensusHashDomain, serde_with};
+use tari_engine_types::hashing::TariHasher64;
+
+use crate::consensus_models::{ValidatorSchnorrSignature, ValidatorSignature};
+
+/// Validator vote signatures over the same message, aggregated into a single signature.
+///
+/// Each vote is a Schnorr signature `s_i.G = R_i + e_i.P_i`. The aggregate keeps every public nonce `R_i` but replaces
+/// the individual signature scalars with a single `s = sum(z_i.s_i)` where each weight `z_i` commits to every signer
+/// and nonce. This does not require any interaction between signers and is verified with a single multiscalar
+/// multiplication. The signer public keys are not included and must be provided by the verifier in signer order.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct AggregateValidatorSignature {
+    #[cfg_attr(feature = "ts", ts(type = "Array<string>"))]
+    public_nonces: Vec<PublicKey>,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    signature: PrivateKey,
+}
+
+impl AggregateValidatorSignature {
+    pub fn new(public_nonces: Vec<PublicKey>, signature: PrivateKey) -> Self {
+        Self {
+            public_nonces,
+            signature,
+        }
+    }
+
+    /// Aggregates the signatures, which must all sign the given message. The signatures must be in signer order.
+    pub fn aggregate<M: AsRef<[u8]>>(message: M, signatures: &[ValidatorSignature]) -> Self {
+        let message = message.as_ref();
+        let public_keys = signatures.iter().map(|s| &s.public_key).collect::<Vec<_>>();
+        let public_nonces = signatures
+            .iter()
+            .map(|s| s.signature.get_public_nonce().clone())
+            .collect::<Vec<_>>();
+        let weights = calculate_weights(message, &public_keys, &public_nonces);
+
+        let signature = signatures
+            .iter()
+            .zip(weights)
+            .fold(PrivateKey::default(), |acc, (s, weight)| {
+                acc + &weight * s.signature.get_signature()
+            });
+
+        Self {
+            public_nonces,
+            signature,
+        }
+    }
+
+    /// Verifies the aggregate signature for the message and signer public keys, given in signer order.
+    pub fn verify<M: AsRef<[u8]>>(&self, message: M, public_keys: &[&PublicKey]) -> bool {
+        if public_keys.is_empty() || public_keys.len() != self.public_nonces.len() {
+            return false;
+        }
+        let message = message.as_ref();
+        let weights = calculate_weights(message, public_keys, &self.public_nonces);
+
+        // s.G = sum(z_i.R_i) + sum(z_i.e_i.P_i)
+        let mut scalars = Vec::with_capacity(public_keys.len() * 2);
+        let mut points = Vec::with_capacity(public_keys.len() * 2);
+        for ((public_key, public_nonce), weight) in public_keys.iter().zip(&self.public_nonces).zip(weights) {
+            let challenge = ValidatorSchnorrSignature::construct_domain_separated_challenge::<_, Blake2b<U64>>(
+                public_nonce,
+                public_key,
+                message,
+            );
+            scalars.push(&weight * &uniform_scalar(challenge.as_ref()));
+            points.push((*public_key).clone());
+            scalars.push(weight);
+            points.push(public_nonce.clone());
+        }
+
+        PublicKey::from_secret_key(&self.signature) == PublicKey::batch_mul(&scalars, &points)
+    }
+
+    pub fn num_signatures(&self) -> usize {
+        self.public_nonces.len()
+    }
+
+    pub fn public_nonces(&self) -> &[PublicKey] {
+        &self.public_nonces
+    }
+
+    pub fn signature(&self) -> &PrivateKey {
+        &self.signature
+    }
+}
+
+/// The committee members that signed a quorum certificate, as a bitmap of their indexes in the committee.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct SignerBitmap(
+    #[serde(with = "serde_with::hex")]
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    Vec<u8>,
+);
+
+impl SignerBitmap {
+    pub fn from_indexes<I: IntoIterator<Item = usize>>(indexes: I) -> Self {
+        let mut bitmap = Vec::new();
+        for index in indexes {
+            let byte = index / 8;
+            if bitmap.len() <= byte {
+                bitmap.resize(byte + 1, 0);
+            }
+            bitmap[byte] |= 1 << (index % 8);
+        }
+        Self(bitmap)
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.0.get(index / 8).is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+
+    /// Returns the signer indexes in ascending order
+    pub fn indexes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.0.len() * 8).filter(|i| self.contains(*i))
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn calculate_weights(message: &[u8], public_keys: &[&PublicKey], public_nonces: &[PublicKey]) -> Vec<PrivateKey> {
+    let seed = tari_hasher::<TariDanConsensusHashDomain>("AggregateSignatureSeed")
+        .chain(message)
+        .chain(public_keys)
+        .chain(public_nonces)
+        .result();
+
+    (0..public_keys.len() as u64)
+        .map(|i| {
+            uniform_scalar(
+                &TariHasher64::new_with_label::<TariDanConsensusHashDomain>("AggregateSignatureWeight")
+                    .chain(&seed)
+                    .chain(&i)
+                    .result(),
+            )
+        })
+        .collect()
+}
+
+fn uniform_scalar(bytes: &[u8]) -> PrivateKey {
+    PrivateKey::from_uniform_bytes(bytes)
+        .expect("INVARIANT VIOLATION: RistrettoSecretKey::from_uniform_bytes and hash output length mismatch")
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_common_types::crypto::create_key_pair_from_seed;
+
+    use super::*;
+
+    fn sign_all(message: &[u8], seeds: &[u8]) -> (Vec<ValidatorSignature>, Vec<PublicKey>) {
+        seeds
+            .iter()
+            .map(|seed| {
+                let (secret, public) = create_key_pair_from_seed(*seed);
+                (ValidatorSignature::sign(&secret, message), public)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn it_verifies_aggregated_signatures() {
+        let (signatures, public_keys) = sign_all(b"vote", &[1, 2, 3, 4]);
+        let aggregate = AggregateValidatorSignature::aggregate(b"vote", &signatures);
+        let public_keys = public_keys.iter().collect::<Vec<_>>();
+        assert!(aggregate.verify(b"vote", &public_keys));
+
+        assert!(!aggregate.verify(b"other", &public_keys));
+        assert!(!aggregate.verify(b"vote", &public_keys[..3]));
+        let reordered = [public_keys[1], public_keys[0], public_keys[2], public_keys[3]];
+        assert!(!aggregate.verify(b"vote", &reordered));
+    }
+
+    #[test]
+    fn it_rejects_an_invalid_signature() {
+        let (mut signatures, public_keys) = sign_all(b"vote", &[1, 2, 3]);
+        let (secret, _) = create_key_pair_from_seed(4);
+        signatures[1] = ValidatorSignature::new(
+            signatures[1].public_key.clone(),
+            ValidatorSignature::sign(&secret, b"vote").signature,
+        );
+        let aggregate = AggregateValidatorSignature::aggregate(b"vote", &signatures);
+        assert!(!aggregate.verify(b"vote", &public_keys.iter().collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn it_encodes_signer_indexes() {
+        let bitmap = SignerBitmap::from_indexes([0, 3, 9]);
+        assert_eq!(bitmap.as_bytes(), &[0b0000_1001, 0b0000_0010]);
+        assert_eq!(bitmap.indexes().collect::<Vec<_>>(), vec![0, 3, 9]);
+        assert_eq!(bitmap.count(), 3);
+        assert!(!bitmap.contains(8));
+        assert!(SignerBitmap::default().is_empty());
+    }
+}
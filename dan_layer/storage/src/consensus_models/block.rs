@@ -19,6 +19,7 @@ use tari_dan_common_types::{
     optional::Optional,
     serde_with,
     shard::Shard,
+    vrf::{VrfError, VrfProof},
     Epoch,
     ExtraData,
     ExtraFieldKey,
@@ -48,6 +49,7 @@ use super::{
     SubstateDestroyedProof,
     SubstatePledge,
     SubstateRecord,
+    SubstateWrite,
     TransactionAtom,
     ValidatorSchnorrSignature,
     ValidatorStatsUpdate,
@@ -56,6 +58,7 @@ use crate::{
     consensus_models::{
         block_header::{compute_command_merkle_root, BlockHeader},
         Command,
+        CommandMerkleProof,
         LastExecuted,
         LastProposed,
         LastVoted,
@@ -214,10 +217,26 @@ impl Block {
         network: Network,
         epoch: Epoch,
         shard_group: ShardGroup,
+        num_preshards: NumPreshards,
         state_merkle_root: FixedHash,
         sidechain_id: Option<RistrettoPublicKey>,
+        leader_seed: FixedHash,
+        genesis_hash: Option<FixedHash>,
     ) -> Self {
         let mut extra_data = ExtraData::new();
+        extra_data.insert(
+            ExtraFieldKey::LeaderSeed,
+            leader_seed.to_vec().try_into().expect("FixedHash is 32 bytes"),
+        );
+        extra_data.insert(
+            ExtraFieldKey::NumPreshards,
+            num_preshards
+                .as_u32()
+                .to_le_bytes()
+                .to_vec()
+                .try_into()
+                .expect("u32 is 4 bytes"),
+        );
         if let Some(sidechain_id) = sidechain_id {
             extra_data.insert(
                 ExtraFieldKey::SidechainId,
@@ -228,6 +247,12 @@ impl Block {
                     .expect("RistrettoPublicKey is 32 bytes"),
             );
         }
+        if let Some(genesis_hash) = genesis_hash {
+            extra_data.insert(
+                ExtraFieldKey::GenesisHash,
+                genesis_hash.to_vec().try_into().expect("FixedHash is 32 bytes"),
+            );
+        }
 
         Self::create(
             network,
@@ -410,6 +435,30 @@ impl Block {
         compute_command_merkle_root(&self.commands)
     }
 
+    /// Returns a proof that the given command is included in this block's command merkle root. The command must be
+    /// contained in the block for the proof to verify.
+    pub fn command_merkle_proof(&self, command: &Command) -> Result<CommandMerkleProof, BlockError> {
+        let proof = CommandMerkleProof::generate(&self.commands, command)?;
+        Ok(proof)
+    }
+
+    /// Returns a proof of the command that finalises the given transaction in this block, or None if the transaction
+    /// is not finalised in this block.
+    pub fn finalising_command_merkle_proof(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> Result<Option<CommandMerkleProof>, BlockError> {
+        let Some(command) = self
+            .commands
+            .iter()
+            .find(|cmd| cmd.finalising().is_some_and(|atom| atom.id() == transaction_id))
+        else {
+            return Ok(None);
+        };
+
+        self.command_merkle_proof(command).map(Some)
+    }
+
     pub fn commands(&self) -> &BTreeSet<Command> {
         &self.commands
     }
@@ -465,6 +514,37 @@ impl Block {
     pub fn extra_data(&self) -> &ExtraData {
         self.header.extra_data()
     }
+
+    /// Returns the leader seed of this block's epoch, from which the seed for each height is derived. This is only
+    /// recorded in genesis blocks.
+    pub fn leader_seed(&self) -> Option<FixedHash> {
+        self.extra_data()
+            .get(&ExtraFieldKey::LeaderSeed)
+            .and_then(|seed| FixedHash::try_from(&**seed).ok())
+    }
+
+    /// Returns the number of preshards that the network was configured with. This is only recorded in genesis blocks.
+    pub fn num_preshards(&self) -> Option<NumPreshards> {
+        self.extra_data()
+            .get(&ExtraFieldKey::NumPreshards)
+            .and_then(|bytes| <[u8; 4]>::try_from(&**bytes).ok())
+            .and_then(|bytes| NumPreshards::try_from(u32::from_le_bytes(bytes)).ok())
+    }
+
+    /// Returns the hash of the genesis file that the network was created with. This is only recorded in genesis blocks
+    /// of networks that use a genesis file.
+    pub fn genesis_hash(&self) -> Option<FixedHash> {
+        self.extra_data()
+            .get(&ExtraFieldKey::GenesisHash)
+            .and_then(|hash| FixedHash::try_from(&**hash).ok())
+    }
+
+    /// Returns the VRF proof included by the proposer, if any
+    pub fn vrf_proof(&self) -> Option<Result<VrfProof, VrfError>> {
+        self.extra_data()
+            .get(&ExtraFieldKey::VrfProof)
+            .map(|proof| VrfProof::from_bytes(proof))
+    }
 }
 
 impl Block {
@@ -500,6 +580,12 @@ impl Block {
         )
     }
 
+    /// Returns the leader seed of the given epoch, which is recorded in the genesis block for the epoch
+    pub fn get_leader_seed<TTx: StateStoreReadTransaction>(tx: &TTx, epoch: Epoch) -> Result<FixedHash, StorageError> {
+        let genesis = tx.blocks_get_genesis_for_epoch(epoch)?;
+        Ok(genesis.leader_seed().unwrap_or_default())
+    }
+
     pub fn get_last_n_in_epoch<TTx: StateStoreReadTransaction>(
         tx: &TTx,
         n: usize,
@@ -637,44 +723,41 @@ impl Block {
             block_diff.remove(tx)?;
         }
 
-        for change in block_diff.into_changes() {
-            match change {
+        let writes = block_diff
+            .into_changes()
+            .into_iter()
+            .map(|change| match change {
                 SubstateChange::Up {
                     id,
                     shard,
                     transaction_id,
                     substate,
-                } => {
-                    SubstateRecord::new(
-                        id.substate_id,
-                        id.version,
-                        substate.into_substate_value(),
-                        shard,
-                        self.epoch(),
-                        self.height(),
-                        *self.id(),
-                        transaction_id,
-                        *self.justify().id(),
-                    )
-                    .create(tx)?;
-                },
+                } => SubstateWrite::Create(SubstateRecord::new(
+                    id.substate_id,
+                    id.version,
+                    substate.into_substate_value(),
+                    shard,
+                    self.epoch(),
+                    self.height(),
+                    *self.id(),
+                    transaction_id,
+                    *self.justify().id(),
+                )),
                 SubstateChange::Down {
                     id,
                     transaction_id,
                     shard,
-                } => {
-                    SubstateRecord::destroy(
-                        tx,
-                        id,
-                        shard,
-                        self.epoch(),
-                        self.height(),
-                        self.justify().id(),
-                        &transaction_id,
-                    )?;
+                } => SubstateWrite::Destroy {
+                    versioned_substate_id: id,
+                    shard,
+                    epoch: self.epoch(),
+                    destroyed_by_block: self.height(),
+                    destroyed_by_transaction: transaction_id,
+                    destroyed_justify: *self.justify().id(),
                 },
-            }
-        }
+            })
+            .collect::<Vec<_>>();
+        SubstateRecord::write_all(tx, &writes)?;
 
         tx.blocks_set_flags(self.id(), Some(true), None)
     }
@@ -841,6 +924,15 @@ impl Block {
         // b'' <- b*.justify.node i.e. the (possibly new) justified block
         let justified_node = self.justify().get_block(&**tx)?;
 
+        // Epoch-end lock rule: an EndEpoch block is locked as soon as a direct EndEpoch child justifies it
+        if self.is_epoch_end() && justified_node.is_epoch_end() && self.parent() == justified_node.id() {
+            let current_locked = LockedBlock::get(&**tx, self.epoch())?;
+            if justified_node.height() > current_locked.height {
+                on_locked_block_recurse(tx, &current_locked, &justified_node, self.justify(), &mut on_lock_block)?;
+                justified_node.as_locked_block().set(tx)?;
+            }
+        }
+
         // b' <- b''.justify.node
         let prepared_node = justified_node.justify().get_block(&**tx)?;
 
@@ -862,7 +954,27 @@ impl Block {
 
         // b <- b'.justify.node
         let commit_node = prepared_node.justify().block_id();
-        if justified_node.parent() == prepared_node.id() && prepared_node.parent() == commit_node {
+        if justified_node.is_epoch_end() &&
+            prepared_node.is_epoch_end() &&
+            justified_node.parent() == prepared_node.id()
+        {
+            // Epoch-end commit rule: an EndEpoch block is committed once a direct EndEpoch child is certified. Every
+            // validator that voted for the child locked the EndEpoch block, so no conflicting block can be certified.
+            // This ends the epoch a round earlier than the 3-chain rule and does not require three consecutive
+            // certified blocks, which may take many dummy blocks to achieve while validators switch epochs. It is
+            // checked first because a direct chain also forms a 3-chain that would only commit the parent of b'.
+            debug!(
+                target: LOG_TARGET,
+                "✅ Block {} {} commits EndEpoch block {} certified by EndEpoch child {}",
+                self.height(),
+                self.id(),
+                prepared_node.id(),
+                justified_node.id(),
+            );
+            let last_executed = LastExecuted::get(&**tx)?;
+            on_commit_block_recurse(tx, &last_executed, &prepared_node, &mut on_commit)?;
+            prepared_node.as_last_executed().set(tx)?;
+        } else if justified_node.parent() == prepared_node.id() && prepared_node.parent() == commit_node {
             debug!(
                 target: LOG_TARGET,
                 "✅ Block {} {} forms a 3-chain b'' = {}, b' = {}, b = {}",
@@ -926,6 +1038,16 @@ impl Block {
         Ok(false)
     }
 
+    /// Returns true if an EndEpoch block is locked in this block's epoch and this block is not an EndEpoch block. Once
+    /// an EndEpoch block is locked, the epoch can only end, so only EndEpoch blocks may extend it.
+    pub fn violates_epoch_end_lock<TTx: StateStoreReadTransaction>(&self, tx: &TTx) -> Result<bool, StorageError> {
+        if self.is_epoch_end() {
+            return Ok(false);
+        }
+        let locked = LockedBlock::get(tx, self.epoch())?.get_block(tx)?;
+        Ok(locked.is_epoch_end())
+    }
+
     pub fn save_foreign_send_counters<TTx>(&self, tx: &mut TTx) -> Result<(), StorageError>
     where
         TTx: StateStoreWriteTransaction + Deref,
@@ -1166,6 +1288,7 @@ where
     tx.substate_locks_remove_any_by_block_id(block_id)?;
     tx.transaction_pool_state_updates_remove_any_by_block_id(block_id)?;
     tx.transaction_executions_remove_any_by_block_id(block_id)?;
+    tx.transaction_idempotency_keys_remove_any_by_block_id(block_id)?;
     tx.foreign_proposals_clear_proposed_in(block_id)?;
     tx.burnt_utxos_clear_proposed_block(block_id)?;
 
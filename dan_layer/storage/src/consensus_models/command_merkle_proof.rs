@@ -0,0 +1,149 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_state_tree::{
+    compute_merkle_proof_for_hashes,
+    LeafKey,
+    SparseMerkleLeafNode,
+    SparseMerkleProof,
+    SparseMerkleProofError,
+    StateTreeError,
+};
+use tari_transaction::TransactionId;
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use crate::consensus_models::{BlockHeader, BlockId, Command};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandMerkleProofError {
+    #[error("Block header hash does not match block id {block_id}")]
+    InvalidBlockHeader { block_id: BlockId },
+    #[error("Command {command} does not finalise transaction {transaction_id}")]
+    CommandDoesNotFinaliseTransaction {
+        command: Command,
+        transaction_id: TransactionId,
+    },
+    #[error("Invalid merkle proof: {0}")]
+    InvalidProof(#[from] SparseMerkleProofError),
+}
+
+/// A proof that a command is included in a block. The proof is verified against the command merkle root committed to
+/// in the block header, so given a trusted block header, an external verifier can check that the command (e.g. the
+/// commit of a transaction) was included in that block without the rest of the block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct CommandMerkleProof {
+    command: Command,
+    /// Sibling hashes on the path from the command leaf to the root, ordered from the bottom level to the root level.
+    #[cfg_attr(feature = "ts", ts(type = "Array<string>"))]
+    siblings: Vec<FixedHash>,
+}
+
+impl CommandMerkleProof {
+    /// Generates the inclusion proof for a command in the given set of commands.
+    pub(crate) fn generate<'a, I: IntoIterator<Item = &'a Command>>(
+        commands: I,
+        command: &Command,
+    ) -> Result<Self, StateTreeError> {
+        let hashes = commands.into_iter().map(|cmd| cmd.hash()).peekable();
+        let proof = compute_merkle_proof_for_hashes(hashes, &command.hash())?;
+        Ok(Self {
+            command: command.clone(),
+            siblings: proof.siblings().to_vec(),
+        })
+    }
+
+    pub fn command(&self) -> &Command {
+        &self.command
+    }
+
+    pub fn siblings(&self) -> &[FixedHash] {
+        &self.siblings
+    }
+
+    /// Verifies that the command is included in the given command merkle root.
+    pub fn verify(&self, command_merkle_root: &FixedHash) -> Result<(), CommandMerkleProofError> {
+        // Command leaves are keyed by the command hash, and the value hash is the command hash
+        let hash = self.command.hash();
+        let leaf_key = LeafKey::new(hash);
+        let proof = SparseMerkleProof::new(
+            Some(SparseMerkleLeafNode::new(leaf_key.clone(), hash)),
+            self.siblings.clone(),
+        );
+        proof.verify(command_merkle_root, &leaf_key, Some(&hash))?;
+        Ok(())
+    }
+
+    /// Verifies that the block header hashes to its block id and that the command is included in the header's command
+    /// merkle root.
+    pub fn verify_against_header(&self, header: &BlockHeader) -> Result<(), CommandMerkleProofError> {
+        if header.calculate_hash() != *header.id().hash() {
+            return Err(CommandMerkleProofError::InvalidBlockHeader { block_id: *header.id() });
+        }
+        self.verify(header.command_merkle_root())
+    }
+
+    /// Verifies that the command finalises (COMMITs or ABORTs) the given transaction and that it is included in the
+    /// given block header. The decision is available from the transaction atom of the command.
+    ///
+    /// This only proves that the block proposes to finalise the transaction. It does not prove that the block was
+    /// committed, so callers must check that the header is part of the committed chain (e.g. from the quorum
+    /// certificates that justify it) before treating the transaction as finalised.
+    pub fn verify_transaction_finalised_in_header(
+        &self,
+        header: &BlockHeader,
+        transaction_id: &TransactionId,
+    ) -> Result<(), CommandMerkleProofError> {
+        if self.command.finalising().map(|atom| atom.id()) != Some(transaction_id) {
+            return Err(CommandMerkleProofError::CommandDoesNotFinaliseTransaction {
+                command: self.command.clone(),
+                transaction_id: *transaction_id,
+            });
+        }
+        self.verify_against_header(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::consensus_models::{block_header::compute_command_merkle_root, Decision, Evidence, TransactionAtom};
+
+    fn accept(n: u8) -> Command {
+        Command::AllAccept(TransactionAtom {
+            id: TransactionId::new([n; 32]),
+            decision: Decision::Commit,
+            evidence: Evidence::default(),
+            transaction_fee: 0,
+            leader_fee: None,
+        })
+    }
+
+    #[test]
+    fn proofs_verify_against_command_merkle_root() {
+        let commands = (1..=10).map(accept).collect::<BTreeSet<_>>();
+        let root = compute_command_merkle_root(&commands).unwrap();
+
+        for command in &commands {
+            let proof = CommandMerkleProof::generate(&commands, command).unwrap();
+            proof.verify(&root).unwrap();
+        }
+
+        // A command that is not in the block does not verify
+        let mut proof = CommandMerkleProof::generate(&commands, &accept(1)).unwrap();
+        proof.command = accept(11);
+        proof.verify(&root).unwrap_err();
+
+        // A proof does not verify against another block's root
+        let other_root = compute_command_merkle_root(&[accept(1), accept(2)]).unwrap();
+        CommandMerkleProof::generate(&commands, &accept(1))
+            .unwrap()
+            .verify(&other_root)
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,96 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{Epoch, NodeHeight};
+
+use crate::consensus_models::{BlockHeader, QuorumDecision, ValidatorSignature};
+
+/// A vote signed by a validator, together with the header of the block that was voted for so that the epoch and
+/// height of the vote can be verified without access to the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedVote {
+    pub block: BlockHeader,
+    pub decision: QuorumDecision,
+    pub signature: ValidatorSignature,
+}
+
+/// Evidence that a validator signed votes for two different blocks at the same epoch and height. An honest validator
+/// votes at most once per height, so this is sufficient to penalize the signer. The vote signatures must be verified
+/// separately against the validator's registered public key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoubleVoteEvidence {
+    pub first: SignedVote,
+    pub second: SignedVote,
+}
+
+impl DoubleVoteEvidence {
+    pub fn new(first: SignedVote, second: SignedVote) -> Self {
+        Self { first, second }
+    }
+
+    pub fn public_key(&self) -> &PublicKey {
+        self.first.signature.public_key()
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.first.block.epoch()
+    }
+
+    pub fn height(&self) -> NodeHeight {
+        self.first.block.height()
+    }
+
+    /// Checks that the two votes were signed by the same validator for two different, well-formed blocks at the same
+    /// epoch and height
+    pub fn validate(&self) -> Result<(), DoubleVoteEvidenceError> {
+        for vote in [&self.first, &self.second] {
+            if vote.block.calculate_hash() != *vote.block.id().hash() {
+                return Err(DoubleVoteEvidenceError::InvalidBlockHeader {
+                    block_id: vote.block.id().to_string(),
+                });
+            }
+        }
+        if self.first.signature.public_key() != self.second.signature.public_key() {
+            return Err(DoubleVoteEvidenceError::DifferentSigners);
+        }
+        if self.first.block.id() == self.second.block.id() {
+            return Err(DoubleVoteEvidenceError::SameBlock);
+        }
+        if self.first.block.epoch() != self.second.block.epoch() ||
+            self.first.block.height() != self.second.block.height()
+        {
+            return Err(DoubleVoteEvidenceError::DifferentHeights);
+        }
+        Ok(())
+    }
+}
+
+impl Display for DoubleVoteEvidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DoubleVote({} voted for {} and {} in epoch {} at height {})",
+            self.public_key(),
+            self.first.block.id(),
+            self.second.block.id(),
+            self.epoch(),
+            self.height()
+        )
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DoubleVoteEvidenceError {
+    #[error("Block header {block_id} does not match its hash")]
+    InvalidBlockHeader { block_id: String },
+    #[error("Votes were signed by different validators")]
+    DifferentSigners,
+    #[error("Votes are for the same block")]
+    SameBlock,
+    #[error("Votes are for blocks at different epochs or heights")]
+    DifferentHeights,
+}
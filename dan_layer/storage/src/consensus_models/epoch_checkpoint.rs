@@ -1,10 +1,10 @@
 //    Copyright 2024 The Tari Project
 //    SPDX-License-Identifier: BSD-3-Clause
 
-use std::fmt::Display;
+use std::{fmt::Display, ops::Deref};
 
 use indexmap::IndexMap;
-use tari_dan_common_types::{shard::Shard, Epoch};
+use tari_dan_common_types::{optional::Optional, shard::Shard, Epoch, ShardGroup};
 use tari_state_tree::{compute_merkle_root_for_hashes, Hash, StateTreeError, SPARSE_MERKLE_PLACEHOLDER_HASH};
 
 use crate::{
@@ -57,6 +57,11 @@ impl EpochCheckpoint {
             .peekable();
         compute_merkle_root_for_hashes(hashes)
     }
+
+    /// Returns the QC in this checkpoint that certifies the checkpoint block, if any
+    pub fn block_qc(&self) -> Option<&QuorumCertificate> {
+        self.linked_qcs.iter().find(|qc| qc.block_id() == self.block.id())
+    }
 }
 
 impl EpochCheckpoint {
@@ -64,9 +69,42 @@ impl EpochCheckpoint {
         tx.epoch_checkpoint_get(epoch)
     }
 
+    /// Returns the checkpoint of the given shard group at the end of the epoch. This is our own checkpoint if we were
+    /// in that shard group, otherwise the checkpoint that was validated when the shard group handed over state to
+    /// us.
+    pub fn get_for_shard_group<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+    ) -> Result<Self, StorageError> {
+        if let Some(checkpoint) = Self::get(tx, epoch).optional()? {
+            if checkpoint.block().shard_group() == shard_group {
+                return Ok(checkpoint);
+            }
+        }
+        tx.epoch_handover_checkpoints_get(epoch, shard_group)
+    }
+
     pub fn save<TTx: StateStoreWriteTransaction>(&self, tx: &mut TTx) -> Result<(), StorageError> {
         tx.epoch_checkpoint_save(self)
     }
+
+    /// Saves this checkpoint as the checkpoint of a shard group that handed over state to us, if it has not been saved
+    /// already
+    pub fn save_handover<TTx>(&self, tx: &mut TTx) -> Result<(), StorageError>
+    where
+        TTx: StateStoreWriteTransaction + Deref,
+        TTx::Target: StateStoreReadTransaction,
+    {
+        let exists = (**tx)
+            .epoch_handover_checkpoints_get(self.block().epoch(), self.block().shard_group())
+            .optional()?
+            .is_some();
+        if !exists {
+            tx.epoch_handover_checkpoints_insert(self)?;
+        }
+        Ok(())
+    }
 }
 
 impl Display for EpochCheckpoint {
@@ -16,7 +16,7 @@ use tari_dan_common_types::{
 };
 use tari_engine_types::serde_with;
 
-use crate::consensus_models::{QcId, VersionedSubstateIdLockIntent};
+use crate::consensus_models::{ExecutionContext, QcId, VersionedSubstateIdLockIntent};
 
 const LOG_TARGET: &str = "tari::dan::consensus_models::evidence";
 
@@ -164,7 +164,18 @@ impl Evidence {
         self.evidence.len()
     }
 
-    /// Add or update shard groups, substates and locks into Evidence. Existing prepare/accept QC IDs are not changed.
+    /// Returns the execution context recorded by the first shard group that has inputs, which every involved shard
+    /// group executes the transaction with. Returns None if that shard group has not prepared the transaction yet.
+    pub fn execution_context(&self) -> Option<&ExecutionContext> {
+        self.evidence
+            .iter()
+            .filter(|(_, e)| e.substates.values().any(|lock| !lock.is_output()))
+            .min_by_key(|(sg, _)| *sg)
+            .and_then(|(_, e)| e.execution_context.as_ref())
+    }
+
+    /// Add or update shard groups, substates and locks into Evidence. Existing prepare/accept QC IDs and execution
+    /// contexts are not changed.
     pub fn update(&mut self, other: &Evidence) -> &mut Self {
         for (sg, evidence) in other.iter() {
             let evidence_mut = self.evidence.entry(*sg).or_default();
@@ -172,6 +183,9 @@ impl Evidence {
                 .substates
                 .extend(evidence.substates.iter().map(|(addr, lock)| (*addr, *lock)));
             evidence_mut.sort_substates();
+            if evidence_mut.execution_context.is_none() {
+                evidence_mut.execution_context = evidence.execution_context;
+            }
         }
         self.evidence.sort_keys();
         self
@@ -211,6 +225,8 @@ pub struct ShardGroupEvidence {
     prepare_qc: Option<QcId>,
     #[cfg_attr(feature = "ts", ts(type = "string | null"))]
     accept_qc: Option<QcId>,
+    #[serde(default)]
+    execution_context: Option<ExecutionContext>,
 }
 
 impl ShardGroupEvidence {
@@ -233,6 +249,23 @@ impl ShardGroupEvidence {
         &self.substates
     }
 
+    pub fn prepare_qc(&self) -> Option<&QcId> {
+        self.prepare_qc.as_ref()
+    }
+
+    pub fn accept_qc(&self) -> Option<&QcId> {
+        self.accept_qc.as_ref()
+    }
+
+    pub fn execution_context(&self) -> Option<&ExecutionContext> {
+        self.execution_context.as_ref()
+    }
+
+    pub fn set_execution_context(&mut self, execution_context: ExecutionContext) -> &mut Self {
+        self.execution_context = Some(execution_context);
+        self
+    }
+
     pub fn sort_substates(&mut self) {
         self.substates.sort_keys();
     }
@@ -330,4 +363,45 @@ mod tests {
             SubstateLockType::Output
         );
     }
+
+    #[test]
+    fn it_uses_the_execution_context_of_the_first_shard_group_with_inputs() {
+        let output_sg = ShardGroup::new(0, 1);
+        let input_sg1 = ShardGroup::new(2, 3);
+        let input_sg2 = ShardGroup::new(4, 5);
+
+        let mut evidence = Evidence::empty();
+        evidence
+            .add_shard_group(output_sg)
+            .insert(seed_substate_address(1), SubstateLockType::Output)
+            .set_execution_context(ExecutionContext::new(1, Default::default()));
+        evidence
+            .add_shard_group(input_sg2)
+            .insert(seed_substate_address(3), SubstateLockType::Write)
+            .set_execution_context(ExecutionContext::new(3, Default::default()));
+        // The first shard group with inputs has not prepared the transaction yet
+        let mut foreign_evidence = Evidence::empty();
+        foreign_evidence
+            .add_shard_group(input_sg1)
+            .insert(seed_substate_address(2), SubstateLockType::Read);
+        evidence.update(&foreign_evidence);
+        assert_eq!(evidence.execution_context(), None);
+
+        foreign_evidence
+            .add_shard_group(input_sg1)
+            .set_execution_context(ExecutionContext::new(2, Default::default()));
+        // A recorded execution context is never replaced
+        foreign_evidence
+            .add_shard_group(input_sg2)
+            .set_execution_context(ExecutionContext::new(4, Default::default()));
+        evidence.update(&foreign_evidence);
+        assert_eq!(
+            evidence.execution_context(),
+            Some(&ExecutionContext::new(2, Default::default()))
+        );
+        assert_eq!(
+            evidence.get(&input_sg2).unwrap().execution_context(),
+            Some(&ExecutionContext::new(3, Default::default()))
+        );
+    }
 }
@@ -0,0 +1,67 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::hashing::random_beacon_hasher;
+use tari_transaction::TransactionId;
+
+use crate::consensus_models::Block;
+
+/// The consensus values that a transaction is executed with. Each shard group records the values of the block that
+/// prepares the transaction in its evidence. Every shard group involved in the transaction then executes it with the
+/// values recorded by the first shard group that has inputs, so that all of them derive the same result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct ExecutionContext {
+    /// Unix timestamp in seconds
+    #[cfg_attr(feature = "ts", ts(type = "number"))]
+    pub timestamp: u64,
+    /// The VRF output of the block proposer
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub vrf_output: FixedHash,
+}
+
+impl ExecutionContext {
+    pub fn new(timestamp: u64, vrf_output: FixedHash) -> Self {
+        Self { timestamp, vrf_output }
+    }
+
+    pub fn for_block(block: &Block) -> Self {
+        let vrf_output = block
+            .vrf_proof()
+            .and_then(|proof| proof.ok())
+            .map(|proof| proof.output())
+            .unwrap_or_default();
+        Self::new(block.timestamp(), vrf_output)
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn vrf_output(&self) -> &FixedHash {
+        &self.vrf_output
+    }
+
+    /// Returns the random beacon for the given transaction. The VRF output is only known once the proposer has
+    /// prepared the transaction, so it cannot be predicted by the sender. The proposer cannot choose its VRF output.
+    pub fn random_beacon(&self, transaction_id: &TransactionId) -> FixedHash {
+        random_beacon_hasher()
+            .chain(&self.vrf_output)
+            .chain(transaction_id)
+            .result()
+    }
+}
+
+impl Display for ExecutionContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timestamp: {}, vrf_output: {}", self.timestamp, self.vrf_output)
+    }
+}
@@ -1,15 +1,19 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+mod aggregate_signature;
 mod block;
 mod block_diff;
 mod block_header;
 mod block_pledges;
 mod burnt_utxo;
 mod command;
+mod command_merkle_proof;
+mod double_vote_evidence;
 mod epoch_checkpoint;
 mod evidence;
 mod executed_transaction;
+mod execution_context;
 mod foreign_parked_proposal;
 mod foreign_proposal;
 mod foreign_receive_counters;
@@ -27,6 +31,7 @@ mod locked_block;
 mod no_vote;
 mod quorum;
 mod quorum_certificate;
+mod state_integrity;
 mod state_transition;
 mod state_tree_diff;
 mod substate;
@@ -35,6 +40,7 @@ mod substate_lock;
 mod transaction;
 mod transaction_decision;
 mod transaction_execution;
+mod transaction_ordering;
 mod transaction_pool;
 mod transaction_pool_status_update;
 mod validated_block;
@@ -42,15 +48,19 @@ mod validator_stats;
 mod vote;
 mod vote_signature;
 
+pub use aggregate_signature::*;
 pub use block::*;
 pub use block_diff::*;
 pub use block_header::*;
 pub use block_pledges::*;
 pub use burnt_utxo::*;
 pub use command::*;
+pub use command_merkle_proof::*;
+pub use double_vote_evidence::*;
 pub use epoch_checkpoint::*;
 pub use evidence::*;
 pub use executed_transaction::*;
+pub use execution_context::*;
 pub use foreign_parked_proposal::*;
 pub use foreign_proposal::*;
 pub use foreign_receive_counters::*;
@@ -68,6 +78,7 @@ pub use locked_block::*;
 pub use no_vote::*;
 pub use quorum::*;
 pub use quorum_certificate::*;
+pub use state_integrity::*;
 pub use state_transition::*;
 pub use state_tree_diff::*;
 pub use substate::*;
@@ -76,6 +87,7 @@ pub use substate_lock::*;
 pub use transaction::*;
 pub use transaction_decision::*;
 pub use transaction_execution::*;
+pub use transaction_ordering::*;
 pub use transaction_pool::*;
 pub use transaction_pool_status_update::*;
 pub use validated_block::*;
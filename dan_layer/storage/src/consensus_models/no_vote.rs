@@ -18,6 +18,8 @@ pub enum NoVoteReason {
     DecisionDisagreement { local: Decision, remote: Decision },
     #[error("Fee disagreement")]
     FeeDisagreement,
+    #[error("Execution context disagreement")]
+    ExecutionContextDisagreement,
     #[error("Leader fee disagreement")]
     LeaderFeeDisagreement,
     #[error("Total leader fee disagreement")]
@@ -28,6 +30,8 @@ pub enum NoVoteReason {
     LocalOnlyProposedForMultiShard,
     #[error("Multi shard proposed for local only")]
     MultiShardProposedForLocalOnly,
+    #[error("Multi shard proposed while cross-shard transactions are deferred after a committee layout change")]
+    CrossShardTransactionDeferred,
     #[error("Not all inputs prepared")]
     NotAllInputsPrepared,
     #[error("Foreign proposal command in block missing")]
@@ -48,6 +52,8 @@ pub enum NoVoteReason {
     NotEndOfEpoch,
     #[error("The node is not at the end of the epoch and other commands are present")]
     EndOfEpochWithOtherCommands,
+    #[error("An EndEpoch block is locked and the block is not an EndEpoch block")]
+    EpochEndBlockLocked,
     #[error("The state Merkle root does not match")]
     StateMerkleRootMismatch,
     #[error("The command Merkle root does not match")]
@@ -74,10 +80,12 @@ impl NoVoteReason {
             Self::TransactionNotInPool => "TransactionNotInPool",
             Self::DecisionDisagreement { .. } => "DecisionDisagreement",
             Self::FeeDisagreement => "FeeDisagreement",
+            Self::ExecutionContextDisagreement => "ExecutionContextDisagreement",
             Self::LeaderFeeDisagreement => "LeaderFeeDisagreement",
             Self::NoLeaderFee => "NoLeaderFee",
             Self::LocalOnlyProposedForMultiShard => "LocalOnlyProposedForMultiShard",
             Self::MultiShardProposedForLocalOnly => "MultiShardProposedForLocalOnly",
+            Self::CrossShardTransactionDeferred => "CrossShardTransactionDeferred",
             Self::NotAllInputsPrepared => "NotAllInputsPrepared",
             Self::ForeignProposalCommandInBlockMissing => "ForeignProposalCommandInBlockMissing",
             Self::ForeignProposalAlreadyProposed => "ForeignProposalAlreadyProposed",
@@ -88,6 +96,7 @@ impl NoVoteReason {
             Self::MintConfidentialOutputStoreFailed => "MintConfidentialOutputStoreFailed",
             Self::NotEndOfEpoch => "NotEndOfEpoch",
             Self::EndOfEpochWithOtherCommands => "EndOfEpochWithOtherCommands",
+            Self::EpochEndBlockLocked => "EpochEndBlockLocked",
             Self::TotalLeaderFeeDisagreement => "TotalLeaderFeeDisagreement",
             Self::StateMerkleRootMismatch => "StateMerkleRootMismatch",
             Self::CommandMerkleRootMismatch => "CommandMerkleRootMismatch",
@@ -5,8 +5,9 @@ use std::{fmt::Display, ops::Deref};
 
 use log::*;
 use serde::{Deserialize, Serialize};
-use tari_common_types::types::{FixedHash, FixedHashSizeError};
+use tari_common_types::types::{FixedHash, FixedHashSizeError, PublicKey};
 use tari_dan_common_types::{
+    committee::Committee,
     hashing::quorum_certificate_hasher,
     optional::Optional,
     serde_with,
@@ -17,13 +18,15 @@ use tari_dan_common_types::{
 
 use crate::{
     consensus_models::{
+        create_vote_message,
+        AggregateValidatorSignature,
         Block,
         BlockId,
         HighQc,
         LastVoted,
         LeafBlock,
         QuorumDecision,
-        ValidatorSignature,
+        SignerBitmap,
         ValidatorStatsUpdate,
     },
     StateStoreReadTransaction,
@@ -47,7 +50,8 @@ pub struct QuorumCertificate {
     block_height: NodeHeight,
     epoch: Epoch,
     shard_group: ShardGroup,
-    signatures: Vec<ValidatorSignature>,
+    signers: SignerBitmap,
+    signature: AggregateValidatorSignature,
     #[serde(with = "serde_with::hex::vec")]
     #[cfg_attr(feature = "ts", ts(type = "Array<string>"))]
     leaf_hashes: Vec<FixedHash>,
@@ -61,7 +65,8 @@ impl QuorumCertificate {
         block_height: NodeHeight,
         epoch: Epoch,
         shard_group: ShardGroup,
-        signatures: Vec<ValidatorSignature>,
+        signers: SignerBitmap,
+        signature: AggregateValidatorSignature,
         mut leaf_hashes: Vec<FixedHash>,
         decision: QuorumDecision,
     ) -> Self {
@@ -72,7 +77,8 @@ impl QuorumCertificate {
             block_height,
             epoch,
             shard_group,
-            signatures,
+            signers,
+            signature,
             leaf_hashes,
             decision,
             is_shares_processed: false,
@@ -87,7 +93,8 @@ impl QuorumCertificate {
             NodeHeight::zero(),
             epoch,
             shard_group,
-            vec![],
+            SignerBitmap::default(),
+            AggregateValidatorSignature::default(),
             vec![],
             QuorumDecision::Accept,
         )
@@ -99,7 +106,8 @@ impl QuorumCertificate {
             .chain(&self.shard_group)
             .chain(&self.block_id)
             .chain(&self.block_height)
-            .chain(&self.signatures)
+            .chain(&self.signers)
+            .chain(&self.signature)
             .chain(&self.leaf_hashes)
             .chain(&self.decision)
             .result()
@@ -128,8 +136,17 @@ impl QuorumCertificate {
         &self.leaf_hashes
     }
 
-    pub fn signatures(&self) -> &[ValidatorSignature] {
-        &self.signatures
+    /// Returns the committee members that signed this QC, by their index in the committee
+    pub fn signers(&self) -> &SignerBitmap {
+        &self.signers
+    }
+
+    pub fn num_signers(&self) -> usize {
+        self.signers.count()
+    }
+
+    pub fn aggregate_signature(&self) -> &AggregateValidatorSignature {
+        &self.signature
     }
 
     pub fn block_height(&self) -> NodeHeight {
@@ -170,6 +187,65 @@ impl QuorumCertificate {
     }
 }
 
+impl QuorumCertificate {
+    /// Returns the message that each signer of this QC signed
+    pub fn vote_message(&self) -> FixedHash {
+        create_vote_message(&self.block_id, &self.decision)
+    }
+
+    /// Returns the public keys of the committee members that signed this QC, or an error if a signer is not in the
+    /// committee. The committee must be the one that produced this QC, in committee order.
+    pub fn get_signer_public_keys<'a, TAddr: PartialEq>(
+        &self,
+        committee: &'a Committee<TAddr>,
+    ) -> Result<Vec<&'a PublicKey>, QuorumCertificateVerificationError> {
+        if let Some(index) = self.signers.indexes().find(|i| *i >= committee.len()) {
+            return Err(QuorumCertificateVerificationError::SignerNotInCommittee {
+                qc_id: self.qc_id,
+                index,
+                committee_size: committee.len(),
+            });
+        }
+
+        Ok(committee
+            .public_keys()
+            .enumerate()
+            .filter(|(i, _)| self.signers.contains(*i))
+            .map(|(_, pk)| pk)
+            .collect())
+    }
+
+    /// Verifies that this QC was signed by a quorum of the given committee snapshot. The signers must hold more than
+    /// two thirds of the committee's voting power. This does not require a state store, so can be used by any
+    /// service that knows the validator set for the QC's epoch and shard group.
+    pub fn verify<TAddr: PartialEq>(
+        &self,
+        committee: &Committee<TAddr>,
+    ) -> Result<(), QuorumCertificateVerificationError> {
+        if self.signers.is_empty() {
+            return Err(QuorumCertificateVerificationError::NoSigners { qc_id: self.qc_id });
+        }
+
+        let signer_public_keys = self.get_signer_public_keys(committee)?;
+        let voting_power = committee.sum_voting_power(signer_public_keys.iter().copied());
+        let quorum_threshold = committee.quorum_voting_power();
+        if voting_power < quorum_threshold {
+            return Err(QuorumCertificateVerificationError::QuorumNotReached {
+                qc_id: self.qc_id,
+                num_signers: signer_public_keys.len(),
+                voting_power,
+                quorum_threshold,
+            });
+        }
+
+        if !self.signature.verify(self.vote_message(), &signer_public_keys) {
+            return Err(QuorumCertificateVerificationError::InvalidSignature { qc_id: self.qc_id });
+        }
+
+        Ok(())
+    }
+}
+
 impl QuorumCertificate {
     pub fn get<TTx: StateStoreReadTransaction>(tx: &TTx, qc_id: &QcId) -> Result<Self, StorageError> {
         tx.quorum_certificates_get(qc_id)
@@ -242,9 +318,12 @@ impl QuorumCertificate {
         Ok(high_qc)
     }
 
+    /// Increments the participation shares of the signers of this QC. The committee public keys must be in committee
+    /// order.
     pub fn update_participation_shares<TTx: StateStoreWriteTransaction>(
         &self,
         tx: &mut TTx,
+        committee_public_keys: &[PublicKey],
     ) -> Result<(), StorageError> {
         if self.is_shares_processed {
             return Ok(());
@@ -252,11 +331,14 @@ impl QuorumCertificate {
 
         tx.validator_epoch_stats_updates(
             self.epoch,
-            self.signatures.iter().map(|s| s.public_key()).map(|pk| {
-                ValidatorStatsUpdate::new(pk)
-                    .increment_participation_share()
-                    .decrement_missed_proposal()
-            }),
+            self.signers
+                .indexes()
+                .filter_map(|i| committee_public_keys.get(i))
+                .map(|pk| {
+                    ValidatorStatsUpdate::new(pk)
+                        .increment_participation_share()
+                        .decrement_missed_proposal()
+                }),
         )?;
         tx.quorum_certificates_set_shares_processed(&self.qc_id)?;
 
@@ -280,12 +362,12 @@ impl Display for QuorumCertificate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Qc(block: {} {}, qc_id: {}, epoch: {}, {} signatures)",
+            "Qc(block: {} {}, qc_id: {}, epoch: {}, {} signers)",
             self.block_height,
             self.block_id,
             self.qc_id,
             self.epoch,
-            self.signatures.len()
+            self.num_signers()
         )
     }
 }
@@ -342,3 +424,131 @@ impl Display for QcId {
         Display::fmt(&self.0, f)
     }
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum QuorumCertificateVerificationError {
+    #[error("QC {qc_id} has no signers")]
+    NoSigners { qc_id: QcId },
+    #[error("QC {qc_id} was signed by committee member #{index} but the committee only has {committee_size} members")]
+    SignerNotInCommittee {
+        qc_id: QcId,
+        index: usize,
+        committee_size: usize,
+    },
+    #[error(
+        "QC {qc_id} has {num_signers} signer(s) with a voting power of {voting_power} but the quorum threshold is \
+         {quorum_threshold}"
+    )]
+    QuorumNotReached {
+        qc_id: QcId,
+        num_signers: usize,
+        voting_power: u64,
+        quorum_threshold: u64,
+    },
+    #[error("QC {qc_id} has an invalid aggregate signature")]
+    InvalidSignature { qc_id: QcId },
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_common_types::types::PrivateKey;
+    use tari_dan_common_types::{crypto::create_key_pair_from_seed, NumPreshards};
+
+    use super::*;
+    use crate::consensus_models::ValidatorSignature;
+
+    fn create_committee(size: u8) -> (Committee<u8>, Vec<PrivateKey>) {
+        let (members, secrets) = (0..size)
+            .map(|i| {
+                let (secret, public) = create_key_pair_from_seed(i + 1);
+                ((i, public), secret)
+            })
+            .unzip();
+        (Committee::new(members), secrets)
+    }
+
+    fn create_qc(secrets: &[PrivateKey], signers: &[usize]) -> QuorumCertificate {
+        let block_id = BlockId::new(FixedHash::from([1u8; 32]));
+        let message = create_vote_message(&block_id, &QuorumDecision::Accept);
+        let signatures = signers
+            .iter()
+            .map(|i| ValidatorSignature::sign(&secrets[*i], message))
+            .collect::<Vec<_>>();
+        QuorumCertificate::new(
+            block_id,
+            NodeHeight(1),
+            Epoch(1),
+            ShardGroup::all_shards(NumPreshards::P64),
+            SignerBitmap::from_indexes(signers.iter().copied()),
+            AggregateValidatorSignature::aggregate(message, &signatures),
+            vec![],
+            QuorumDecision::Accept,
+        )
+    }
+
+    #[test]
+    fn it_verifies_a_qc_signed_by_a_quorum() {
+        let (committee, secrets) = create_committee(4);
+        assert_eq!(committee.quorum_threshold(), 3);
+        create_qc(&secrets, &[0, 1, 3]).verify(&committee).unwrap();
+        create_qc(&secrets, &[0, 1, 2, 3]).verify(&committee).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_a_qc_without_a_quorum() {
+        let (committee, secrets) = create_committee(4);
+        let err = create_qc(&secrets, &[0, 1]).verify(&committee).unwrap_err();
+        assert!(matches!(err, QuorumCertificateVerificationError::QuorumNotReached {
+            num_signers: 2,
+            voting_power: 2,
+            quorum_threshold: 3,
+            ..
+        }));
+        let err = create_qc(&secrets, &[]).verify(&committee).unwrap_err();
+        assert!(matches!(err, QuorumCertificateVerificationError::NoSigners { .. }));
+    }
+
+    #[test]
+    fn it_weights_signers_by_voting_power() {
+        let (committee, secrets) = create_committee(4);
+        let public_keys = committee.public_keys().cloned().collect::<Vec<_>>();
+        let committee = committee
+            .with_voting_power(public_keys[0].clone(), 10)
+            .with_voting_power(public_keys[1].clone(), 20)
+            .with_voting_power(public_keys[2].clone(), 30)
+            .with_voting_power(public_keys[3].clone(), 40);
+        assert_eq!(committee.quorum_voting_power(), 67);
+
+        // Three of four members is not enough if they hold less than two thirds of the voting power
+        let err = create_qc(&secrets, &[0, 1, 2]).verify(&committee).unwrap_err();
+        assert!(matches!(err, QuorumCertificateVerificationError::QuorumNotReached {
+            num_signers: 3,
+            voting_power: 60,
+            quorum_threshold: 67,
+            ..
+        }));
+        create_qc(&secrets, &[2, 3]).verify(&committee).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_a_qc_signed_by_a_different_committee() {
+        let (committee, _) = create_committee(4);
+        let (larger_committee, secrets) = create_committee(5);
+        let err = create_qc(&secrets, &[0, 1, 4]).verify(&committee).unwrap_err();
+        assert!(matches!(
+            err,
+            QuorumCertificateVerificationError::SignerNotInCommittee {
+                index: 4,
+                committee_size: 4,
+                ..
+            }
+        ));
+
+        let other_committee = larger_committee.into_iter().skip(1).collect::<Committee<_>>();
+        let err = create_qc(&secrets, &[0, 1, 2, 3]).verify(&other_committee).unwrap_err();
+        assert!(matches!(
+            err,
+            QuorumCertificateVerificationError::InvalidSignature { .. }
+        ));
+    }
+}
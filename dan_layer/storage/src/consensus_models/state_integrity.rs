@@ -0,0 +1,180 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::{shard::Shard, Epoch, NodeHeight};
+use tari_engine_types::substate::SubstateId;
+
+use crate::consensus_models::BlockId;
+
+/// The result of recomputing the state merkle root from the stored substates and comparing it to the state merkle root
+/// of the last committed block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct StateIntegrityReport {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub block_id: BlockId,
+    pub epoch: Epoch,
+    pub height: NodeHeight,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub expected_state_merkle_root: FixedHash,
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub computed_state_merkle_root: FixedHash,
+    pub shards: Vec<ShardStateIntegrity>,
+}
+
+impl StateIntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.expected_state_merkle_root == self.computed_state_merkle_root && self.shards.iter().all(|s| s.is_ok())
+    }
+
+    pub fn mismatched_shards(&self) -> impl Iterator<Item = &ShardStateIntegrity> + '_ {
+        self.shards.iter().filter(|s| !s.is_ok())
+    }
+}
+
+impl Display for StateIntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "State integrity at block {} (epoch {}, height {}): {}",
+            self.block_id,
+            self.epoch,
+            self.height,
+            if self.is_ok() { "OK" } else { "MISMATCH" }
+        )?;
+        writeln!(f, "  expected state merkle root: {}", self.expected_state_merkle_root)?;
+        writeln!(f, "  computed state merkle root: {}", self.computed_state_merkle_root)?;
+        for shard in &self.shards {
+            write!(f, "{}", shard)?;
+        }
+        Ok(())
+    }
+}
+
+/// Integrity check result for a single shard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct ShardStateIntegrity {
+    pub shard: Shard,
+    pub num_substates: usize,
+    #[cfg_attr(feature = "ts", ts(type = "number | null"))]
+    pub state_tree_version: Option<u64>,
+    /// The root hash of the stored state tree, or None if the stored tree could not be read
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub state_tree_root: Option<FixedHash>,
+    pub state_tree_error: Option<String>,
+    /// The root hash computed from the stored substates
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub computed_root: FixedHash,
+    pub mismatches: Vec<SubstateIntegrityMismatch>,
+}
+
+impl ShardStateIntegrity {
+    pub fn is_ok(&self) -> bool {
+        self.state_tree_root == Some(self.computed_root) && self.mismatches.is_empty()
+    }
+}
+
+impl Display for ShardStateIntegrity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "  {}: {} substate(s), tree version ", self.shard, self.num_substates)?;
+        match self.state_tree_version {
+            Some(v) => write!(f, "{}", v)?,
+            None => write!(f, "<none>")?,
+        }
+        writeln!(f, ": {}", if self.is_ok() { "OK" } else { "MISMATCH" })?;
+        if !self.is_ok() {
+            match self.state_tree_root {
+                Some(root) => writeln!(f, "    stored root:   {}", root)?,
+                None => writeln!(
+                    f,
+                    "    stored root:   <error: {}>",
+                    self.state_tree_error.as_deref().unwrap_or("unknown")
+                )?,
+            }
+            writeln!(f, "    computed root: {}", self.computed_root)?;
+        }
+        for mismatch in &self.mismatches {
+            writeln!(f, "    {}", mismatch)?;
+        }
+        Ok(())
+    }
+}
+
+/// A stored substate that does not match the state tree or its own recorded state hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub struct SubstateIntegrityMismatch {
+    pub substate_id: SubstateId,
+    pub version: u32,
+    pub reason: SubstateIntegrityMismatchReason,
+}
+
+impl Display for SubstateIntegrityMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} {}", self.substate_id, self.version, self.reason)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+pub enum SubstateIntegrityMismatchReason {
+    /// The recorded state hash does not match the hash of the stored substate value
+    StateHashMismatch {
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        recorded: FixedHash,
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        computed: FixedHash,
+    },
+    /// The substate is not present in the stored state tree
+    MissingFromStateTree,
+    /// The stored state tree commits to a different value for the substate
+    StateTreeValueMismatch {
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        in_tree: FixedHash,
+        #[cfg_attr(feature = "ts", ts(type = "string"))]
+        computed: FixedHash,
+    },
+}
+
+impl Display for SubstateIntegrityMismatchReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StateHashMismatch { recorded, computed } => {
+                write!(
+                    f,
+                    "recorded state hash {} does not match computed {}",
+                    recorded, computed
+                )
+            },
+            Self::MissingFromStateTree => write!(f, "missing from state tree"),
+            Self::StateTreeValueMismatch { in_tree, computed } => {
+                write!(
+                    f,
+                    "state tree value hash {} does not match computed {}",
+                    in_tree, computed
+                )
+            },
+        }
+    }
+}
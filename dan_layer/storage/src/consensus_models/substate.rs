@@ -177,8 +177,15 @@ impl SubstateRecord {
     }
 
     pub fn create<TTx: StateStoreWriteTransaction>(&self, tx: &mut TTx) -> Result<(), StorageError> {
-        tx.substates_create(self)?;
-        Ok(())
+        tx.substates_write_many(&[SubstateWrite::Create(self.clone())])
+    }
+
+    /// Writes all substate creates and destroys in a single batch. State transitions are recorded in the order given.
+    pub fn write_all<TTx: StateStoreWriteTransaction>(
+        tx: &mut TTx,
+        writes: &[SubstateWrite],
+    ) -> Result<(), StorageError> {
+        tx.substates_write_many(writes)
     }
 
     pub fn exists<TTx: StateStoreReadTransaction>(tx: &TTx, id: &VersionedSubstateId) -> Result<bool, StorageError> {
@@ -319,17 +326,31 @@ impl SubstateRecord {
         destroyed_justify: &QcId,
         destroyed_by_transaction: &TransactionId,
     ) -> Result<(), StorageError> {
-        tx.substates_down(
+        tx.substates_write_many(&[SubstateWrite::Destroy {
             versioned_substate_id,
             shard,
             epoch,
             destroyed_by_block,
-            destroyed_by_transaction,
-            destroyed_justify,
-        )
+            destroyed_by_transaction: *destroyed_by_transaction,
+            destroyed_justify: *destroyed_justify,
+        }])
     }
 }
 
+/// A substate create or destroy that is written to the store when a block is committed
+#[derive(Debug, Clone)]
+pub enum SubstateWrite {
+    Create(SubstateRecord),
+    Destroy {
+        versioned_substate_id: VersionedSubstateId,
+        shard: Shard,
+        epoch: Epoch,
+        destroyed_by_block: NodeHeight,
+        destroyed_by_transaction: TransactionId,
+        destroyed_justify: QcId,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct SubstateCreatedProof {
     pub substate: SubstateData,
@@ -185,11 +185,13 @@ impl TransactionRecord {
             // then use abort_details (which should have been set in this case).
             let exec_result = self.execution_result.as_ref().filter(|r| r.finalize.result.is_reject());
             let execution_time = exec_result.as_ref().map(|r| r.execution_time).unwrap_or_default();
+            let execution_trace = exec_result.and_then(|r| r.execution_trace.clone());
             ExecuteResult {
                 finalize: exec_result.map(|r| r.finalize.clone()).unwrap_or_else(|| {
                     FinalizeResult::new_rejected(self.transaction.id().into_array().into(), reason.clone())
                 }),
                 execution_time,
+                execution_trace,
             }
         } else {
             // If there's no abort reason or execution result, return None here
@@ -217,8 +219,9 @@ impl TransactionRecord {
             } else {
                 // Only use rejected results for the transaction. If execution ACCEPTed but the final decision is ABORT,
                 // then use abort_details (which should have been set in this case).
-                let exec_result = self.execution_result.filter(|r| r.finalize.result.is_reject());
+                let mut exec_result = self.execution_result.filter(|r| r.finalize.result.is_reject());
                 let execution_time = exec_result.as_ref().map(|r| r.execution_time).unwrap_or_default();
+                let execution_trace = exec_result.as_mut().and_then(|r| r.execution_trace.take());
                 Some(ExecuteResult {
                     finalize: exec_result.map(|r| r.finalize).unwrap_or_else(|| {
                         FinalizeResult::new_rejected(
@@ -228,6 +231,7 @@ impl TransactionRecord {
                         )
                     }),
                     execution_time,
+                    execution_trace,
                 })
             }
         })
@@ -48,6 +48,10 @@ pub enum AbortReason {
     ForeignShardGroupDecidedToAbort,
     FeesNotPaid,
     EarlyAbort,
+    LockLeaseExpired,
+    CrossShardDeadlock,
+    CommitteeLayoutChanged,
+    InputRentExpired,
 }
 
 impl From<&RejectReason> for AbortReason {
@@ -61,6 +65,11 @@ impl From<&RejectReason> for AbortReason {
             RejectReason::FailedToLockOutputs(_) => Self::LockOutputsFailed,
             RejectReason::ForeignShardGroupDecidedToAbort { .. } => Self::ForeignShardGroupDecidedToAbort,
             RejectReason::FeesNotPaid(_) => Self::FeesNotPaid,
+            RejectReason::InvalidArguments { .. } => Self::ExecutionFailure,
+            RejectReason::LockLeaseExpired(_) => Self::LockLeaseExpired,
+            RejectReason::CrossShardDeadlock(_) => Self::CrossShardDeadlock,
+            RejectReason::CommitteeLayoutChanged(_) => Self::CommitteeLayoutChanged,
+            RejectReason::InputRentExpired(_) => Self::InputRentExpired,
         }
     }
 }
@@ -0,0 +1,49 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::hashing::transaction_ordering_hasher;
+use tari_transaction::TransactionId;
+
+use crate::consensus_models::BlockId;
+
+/// The policy used by a proposer to select and order new transactions from the transaction pool. Transactions that are
+/// already in progress are always proposed before new transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionOrdering {
+    /// Transactions are ordered by transaction id. Since the transaction id is chosen by the submitter, a submitter
+    /// can grind their transaction to the front of the pool.
+    #[default]
+    TransactionId,
+    /// Transactions are ordered by the time that they arrived in the proposer's pool (first come, first served).
+    ArrivalTime,
+    /// The oldest transactions in the pool are ordered by the hash of the transaction id and the block that the
+    /// proposal builds on, which spreads transactions that arrive together across positions. This is a proposer policy
+    /// only: voters do not verify it, a proposer can still choose which transactions to propose, and a submitter who
+    /// knows the block being extended can grind the transaction id for a better position.
+    Shuffled,
+}
+
+/// The number of candidates per available slot that are loaded from the pool for the `Shuffled` ordering. Candidates
+/// are taken in arrival order, so a transaction is not delayed indefinitely by newer transactions with better keys.
+const SHUFFLE_CANDIDATES_PER_SLOT: usize = 4;
+
+impl TransactionOrdering {
+    pub fn is_shuffled(&self) -> bool {
+        matches!(self, Self::Shuffled)
+    }
+
+    /// Returns the maximum number of candidate transactions to load from the pool for the `Shuffled` ordering when
+    /// `num_slots` new transactions can be proposed.
+    pub fn max_shuffle_candidates(num_slots: usize) -> usize {
+        num_slots.saturating_mul(SHUFFLE_CANDIDATES_PER_SLOT)
+    }
+
+    /// Returns the sort key of a transaction for the `Shuffled` ordering, seeded by the given block id.
+    pub fn shuffle_key(block_id: &BlockId, transaction_id: &TransactionId) -> FixedHash {
+        transaction_ordering_hasher()
+            .chain(block_id)
+            .chain(transaction_id)
+            .result()
+    }
+}
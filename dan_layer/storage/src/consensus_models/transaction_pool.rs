@@ -3,6 +3,7 @@
 
 use std::{
     clone::Clone,
+    collections::HashSet,
     fmt::{Display, Formatter},
     marker::PhantomData,
     num::NonZeroU64,
@@ -14,7 +15,9 @@ use serde::Serialize;
 use tari_dan_common_types::{
     committee::CommitteeInfo,
     optional::{IsNotFoundError, Optional},
+    NodeHeight,
     NumPreshards,
+    ShardGroup,
     ToSubstateAddress,
 };
 use tari_engine_types::transaction_receipt::TransactionReceiptAddress;
@@ -22,6 +25,8 @@ use tari_transaction::TransactionId;
 
 use crate::{
     consensus_models::{
+        AbortReason,
+        Block,
         BlockId,
         BlockTransactionExecution,
         Decision,
@@ -32,6 +37,7 @@ use crate::{
         QcId,
         TransactionAtom,
         TransactionExecution,
+        TransactionOrdering,
         TransactionRecord,
     },
     StateStore,
@@ -71,6 +77,15 @@ impl<TStateStore: StateStore> TransactionPool<TStateStore> {
         Ok(rec)
     }
 
+    /// Returns all transactions in the pool as of the locked block. Pending (unlocked) updates are not applied.
+    pub fn get_all(
+        &self,
+        tx: &TStateStore::ReadTransaction<'_>,
+    ) -> Result<Vec<TransactionPoolRecord>, TransactionPoolError> {
+        let recs = tx.transaction_pool_get_all()?;
+        Ok(recs)
+    }
+
     pub fn exists(
         &self,
         tx: &TStateStore::ReadTransaction<'_>,
@@ -108,8 +123,9 @@ impl<TStateStore: StateStore> TransactionPool<TStateStore> {
         tx: &TStateStore::ReadTransaction<'_>,
         max: usize,
         block_id: &BlockId,
+        ordering: TransactionOrdering,
     ) -> Result<Vec<TransactionPoolRecord>, TransactionPoolError> {
-        let recs = tx.transaction_pool_get_many_ready(max, block_id)?;
+        let recs = tx.transaction_pool_get_many_ready(max, block_id, ordering)?;
         Ok(recs)
     }
 
@@ -382,7 +398,14 @@ impl TransactionPoolRecord {
             TransactionPoolStage::AllPrepared | TransactionPoolStage::SomePrepared => true,
             TransactionPoolStage::LocalAccepted => match self.current_decision() {
                 Decision::Commit => self.evidence.all_addresses_accepted(),
-                // If we have decided to abort, we can continue if all input addresses are justified
+                // If we have decided to abort, we can continue if all input addresses are justified. If our substate
+                // lock lease expired, the transaction is deadlocked or the foreign shard groups no longer exist after a
+                // committee layout change, we abort without waiting for the foreign shard groups.
+                Decision::Abort(
+                    AbortReason::LockLeaseExpired |
+                    AbortReason::CrossShardDeadlock |
+                    AbortReason::CommitteeLayoutChanged,
+                ) => true,
                 Decision::Abort(_) => self.evidence.all_inputs_prepared(),
             },
             TransactionPoolStage::AllAccepted |
@@ -702,6 +725,50 @@ impl TransactionPoolRecord {
         Ok(exec)
     }
 
+    /// Returns the height of the earliest block that locked substates for this transaction, or None if the transaction
+    /// does not hold any substate locks.
+    pub fn get_earliest_lock_height<TTx: StateStoreReadTransaction>(
+        &self,
+        tx: &TTx,
+    ) -> Result<Option<NodeHeight>, TransactionPoolError> {
+        let locks = tx.substate_locks_get_locked_substates_for_transaction(self.transaction_id())?;
+        let block_ids = locks.iter().map(|lock| lock.locked_by_block).collect::<HashSet<_>>();
+        let mut earliest = None;
+        for block_id in block_ids {
+            let height = Block::get(tx, &block_id)?.height();
+            earliest = Some(earliest.map_or(height, |h: NodeHeight| h.min(height)));
+        }
+        Ok(earliest)
+    }
+
+    /// Returns true if the substate locks held by this transaction were acquired at least `lease_blocks` blocks before
+    /// `height`. Lock leases are disabled if `lease_blocks` is zero.
+    pub fn is_lock_lease_expired<TTx: StateStoreReadTransaction>(
+        &self,
+        tx: &TTx,
+        height: NodeHeight,
+        lease_blocks: u64,
+    ) -> Result<bool, TransactionPoolError> {
+        if lease_blocks == 0 {
+            return Ok(false);
+        }
+        let Some(lock_height) = self.get_earliest_lock_height(tx)? else {
+            return Ok(false);
+        };
+        Ok(height.saturating_sub(lock_height) >= NodeHeight(lease_blocks))
+    }
+
+    /// Returns true if the evidence refers to a shard group that is not part of the committee layout of the given
+    /// committee. This is the case for a transaction that was prepared before the committee layout changed, whose
+    /// pledges can no longer be provided by the shard groups it was waiting on.
+    pub fn has_shard_groups_outside_layout(&self, committee_info: &CommitteeInfo) -> bool {
+        let shard_groups =
+            ShardGroup::all_for_num_committees(committee_info.num_preshards(), committee_info.num_committees());
+        self.evidence
+            .shard_groups_iter()
+            .any(|shard_group| !shard_groups.contains(shard_group))
+    }
+
     pub fn involves_committee(&self, committee_info: &CommitteeInfo) -> bool {
         self.evidence.contains(&committee_info.shard_group())
     }
@@ -877,4 +944,38 @@ mod tests {
             );
         }
     }
+
+    mod has_shard_groups_outside_layout {
+        use super::*;
+
+        fn create_record_with_shard_groups(shard_groups: &[ShardGroup]) -> TransactionPoolRecord {
+            let mut evidence = Evidence::empty();
+            for shard_group in shard_groups {
+                evidence.add_shard_group(*shard_group);
+            }
+            TransactionPoolRecord {
+                transaction_id: TransactionId::new([0; 32]),
+                original_decision: Decision::Commit,
+                evidence,
+                transaction_fee: 0,
+                leader_fee: None,
+                stage: TransactionPoolStage::LocalPrepared,
+                pending_stage: None,
+                local_decision: None,
+                remote_decision: None,
+                is_ready: false,
+            }
+        }
+
+        #[test]
+        fn it_detects_shard_groups_from_a_previous_layout() {
+            let prepared = create_record_with_shard_groups(&[ShardGroup::new(0, 31), ShardGroup::new(32, 63)]);
+
+            let unchanged = CommitteeInfo::new(NumPreshards::P64, 3, 2, ShardGroup::new(0, 31));
+            assert!(!prepared.has_shard_groups_outside_layout(&unchanged));
+
+            let split = CommitteeInfo::new(NumPreshards::P64, 3, 4, ShardGroup::new(0, 15));
+            assert!(prepared.has_shard_groups_outside_layout(&split));
+        }
+    }
 }
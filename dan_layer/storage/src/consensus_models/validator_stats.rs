@@ -1,6 +1,8 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
 use tari_common_types::types::PublicKey;
 use tari_dan_common_types::Epoch;
 
@@ -71,8 +73,13 @@ impl<'a> ValidatorStatsUpdate<'a> {
 
 #[derive(Debug, Clone)]
 pub struct ValidatorConsensusStats {
+    /// The number of missed proposals that count towards suspension. This is decremented as the validator participates
+    /// and reset when it proposes a block.
     pub missed_proposals: u64,
     pub participation_shares: u64,
+    /// The total number of proposals missed in the epoch. Unlike `missed_proposals`, this is never decremented or
+    /// reset.
+    pub total_missed_proposals: u64,
 }
 
 impl ValidatorConsensusStats {
@@ -128,4 +135,84 @@ impl ValidatorConsensusStats {
     pub fn count_number_suspended_nodes<TTx: StateStoreReadTransaction>(tx: &TTx) -> Result<u64, StorageError> {
         tx.suspended_nodes_count()
     }
+
+    pub fn get_suspended_nodes<TTx: StateStoreReadTransaction>(tx: &TTx) -> Result<Vec<PublicKey>, StorageError> {
+        tx.suspended_nodes_get_all()
+    }
+}
+
+/// The stats recorded for a validator in an epoch. Stats are kept per epoch and are not removed when the epoch ends.
+#[derive(Debug, Clone)]
+pub struct ValidatorEpochStats {
+    pub epoch: Epoch,
+    pub public_key: PublicKey,
+    pub stats: ValidatorConsensusStats,
+}
+
+impl ValidatorEpochStats {
+    /// Returns the per-epoch stats for all epochs in the range, ordered by epoch. If a public key is given, only the
+    /// stats for that validator are returned.
+    pub fn get_all_in_epoch_range<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: Option<&PublicKey>,
+    ) -> Result<Vec<Self>, StorageError> {
+        tx.validator_epoch_stats_get_all_in_epoch_range(epoch_range, public_key)
+    }
+}
+
+/// The stats of a validator summed over a range of epochs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorAggregateStats {
+    pub public_key: PublicKey,
+    /// The number of epochs in the range in which the validator has recorded stats
+    pub num_epochs: u64,
+    pub first_epoch: Epoch,
+    pub last_epoch: Epoch,
+    pub participation_shares: u64,
+    pub total_missed_proposals: u64,
+}
+
+impl ValidatorAggregateStats {
+    /// Returns the aggregated stats of a validator over the epoch range, or None if the validator has no stats in the
+    /// range.
+    pub fn get<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: &PublicKey,
+    ) -> Result<Option<Self>, StorageError> {
+        let stats = ValidatorEpochStats::get_all_in_epoch_range(tx, epoch_range, Some(public_key))?;
+        Ok(Self::aggregate(stats).pop())
+    }
+
+    /// Returns the aggregated stats of all validators that have stats in the epoch range, ordered by public key.
+    pub fn get_all<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        epoch_range: RangeInclusive<Epoch>,
+    ) -> Result<Vec<Self>, StorageError> {
+        let stats = ValidatorEpochStats::get_all_in_epoch_range(tx, epoch_range, None)?;
+        Ok(Self::aggregate(stats))
+    }
+
+    fn aggregate<I: IntoIterator<Item = ValidatorEpochStats>>(epoch_stats: I) -> Vec<Self> {
+        let mut aggregates = BTreeMap::<PublicKey, Self>::new();
+        for epoch_stats in epoch_stats {
+            let aggregate = aggregates
+                .entry(epoch_stats.public_key.clone())
+                .or_insert_with(|| Self {
+                    public_key: epoch_stats.public_key,
+                    num_epochs: 0,
+                    first_epoch: epoch_stats.epoch,
+                    last_epoch: epoch_stats.epoch,
+                    participation_shares: 0,
+                    total_missed_proposals: 0,
+                });
+            aggregate.num_epochs += 1;
+            aggregate.first_epoch = aggregate.first_epoch.min(epoch_stats.epoch);
+            aggregate.last_epoch = aggregate.last_epoch.max(epoch_stats.epoch);
+            aggregate.participation_shares += epoch_stats.stats.participation_shares;
+            aggregate.total_missed_proposals += epoch_stats.stats.total_missed_proposals;
+        }
+        aggregates.into_values().collect()
+    }
 }
@@ -75,6 +75,14 @@ impl Vote {
         tx.votes_get_for_block(block_id)
     }
 
+    pub fn get_by_sender<TTx: StateStoreReadTransaction>(
+        tx: &TTx,
+        epoch: Epoch,
+        sender_leaf_hash: &FixedHash,
+    ) -> Result<Vec<Self>, StorageError> {
+        tx.votes_get_by_sender(epoch, sender_leaf_hash)
+    }
+
     pub fn delete_all<TTx: StateStoreWriteTransaction>(tx: &mut TTx) -> Result<(), StorageError> {
         tx.votes_delete_all()
     }
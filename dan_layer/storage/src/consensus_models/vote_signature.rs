@@ -3,14 +3,22 @@
 
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
-use tari_common_types::types::{PrivateKey, PublicKey};
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey};
 use tari_core::transactions::transaction_components::ValidatorNodeHashDomain;
 use tari_crypto::{keys::PublicKey as _, signatures::SchnorrSignature};
+use tari_dan_common_types::hashing::vote_signature_hasher;
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
+use crate::consensus_models::{BlockId, QuorumDecision};
+
 pub type ValidatorSchnorrSignature = SchnorrSignature<PublicKey, PrivateKey, ValidatorNodeHashDomain>;
 
+/// Returns the message that validators sign when voting on a block
+pub fn create_vote_message(block_id: &BlockId, decision: &QuorumDecision) -> FixedHash {
+    vote_signature_hasher().chain(block_id).chain(decision).result()
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
 pub struct ValidatorSignature {
@@ -86,6 +86,7 @@ pub trait GlobalDbAdapter: AtomicDb + Send + Sync + Clone {
         start_epoch: Epoch,
         fee_claim_public_key: PublicKey,
         sidechain_id: Option<PublicKey>,
+        stake: u64,
     ) -> Result<(), Self::Error>;
 
     fn remove_validator_node(
@@ -144,6 +145,7 @@ pub trait GlobalDbAdapter: AtomicDb + Send + Sync + Clone {
         shard_group: ShardGroup,
         sidechain_id: Option<&PublicKey>,
         epoch: Epoch,
+        voting_power: u64,
     ) -> Result<(), Self::Error>;
 
     fn validator_nodes_get_by_substate_range(
@@ -62,6 +62,9 @@ pub enum MetadataKey {
     EpochManagerLastSyncedEpoch,
     EpochManagerFeeClaimPublicKey,
     EpochManagerLastBlockOfCurrentEpoch,
+    EpochManagerValidatorAllowlist,
+    EpochManagerValidatorAllowlistVersion,
+    EpochManagerHeldValidatorRegistrations,
 }
 
 impl MetadataKey {
@@ -79,6 +82,9 @@ impl MetadataKey {
             MetadataKey::EpochManagerLastSyncedEpoch => b"epoch_manager.last_synced_epoch",
             MetadataKey::EpochManagerFeeClaimPublicKey => b"epoch_manager.fee_claim_public_key",
             MetadataKey::EpochManagerLastBlockOfCurrentEpoch => b"epoch_manager.last_block_of_current_epoch",
+            MetadataKey::EpochManagerValidatorAllowlist => b"epoch_manager.validator_allowlist",
+            MetadataKey::EpochManagerValidatorAllowlistVersion => b"epoch_manager.validator_allowlist_version",
+            MetadataKey::EpochManagerHeldValidatorRegistrations => b"epoch_manager.held_validator_registrations",
         }
     }
 }
@@ -15,6 +15,8 @@ pub struct ValidatorNode<TAddr> {
     pub start_epoch: Epoch,
     pub fee_claim_public_key: PublicKey,
     pub sidechain_id: Option<PublicKey>,
+    /// The amount of Minotari (in micro-Minotari) locked by the base layer registration
+    pub stake: u64,
 }
 
 impl<TAddr: NodeAddressable> ValidatorNode<TAddr> {
@@ -46,6 +46,7 @@ impl<'a, 'tx, TGlobalDbAdapter: GlobalDbAdapter> ValidatorNodeDb<'a, 'tx, TGloba
         start_epoch: Epoch,
         fee_claim_public_key: PublicKey,
         sidechain_id: Option<PublicKey>,
+        stake: u64,
     ) -> Result<(), TGlobalDbAdapter::Error> {
         self.backend
             .insert_validator_node(
@@ -57,6 +58,7 @@ impl<'a, 'tx, TGlobalDbAdapter: GlobalDbAdapter> ValidatorNodeDb<'a, 'tx, TGloba
                 start_epoch,
                 fee_claim_public_key,
                 sidechain_id,
+                stake,
             )
             .map_err(TGlobalDbAdapter::Error::into)
     }
@@ -146,9 +148,17 @@ impl<'a, 'tx, TGlobalDbAdapter: GlobalDbAdapter> ValidatorNodeDb<'a, 'tx, TGloba
         shard_group: ShardGroup,
         sidechain_id: Option<&PublicKey>,
         epoch: Epoch,
+        voting_power: u64,
     ) -> Result<(), TGlobalDbAdapter::Error> {
         self.backend
-            .validator_nodes_set_committee_shard(self.tx, substate_address, shard_group, sidechain_id, epoch)
+            .validator_nodes_set_committee_shard(
+                self.tx,
+                substate_address,
+                shard_group,
+                sidechain_id,
+                epoch,
+                voting_power,
+            )
             .map_err(TGlobalDbAdapter::Error::into)
     }
 }
@@ -24,7 +24,7 @@ use tari_dan_common_types::{
 };
 use tari_engine_types::substate::SubstateId;
 use tari_state_tree::{Node, NodeKey, StaleTreeNode, Version};
-use tari_transaction::TransactionId;
+use tari_transaction::{IdempotencyKey, TransactionId};
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
@@ -62,12 +62,15 @@ use crate::{
         SubstateLock,
         SubstatePledges,
         SubstateRecord,
+        SubstateWrite,
+        TransactionOrdering,
         TransactionPoolConfirmedStage,
         TransactionPoolRecord,
         TransactionPoolStage,
         TransactionPoolStatusUpdate,
         TransactionRecord,
         ValidatorConsensusStats,
+        ValidatorEpochStats,
         ValidatorStatsUpdate,
         VersionedStateHashTreeDiff,
         Vote,
@@ -153,6 +156,14 @@ pub trait StateStoreReadTransaction: Sized {
         offset: u64,
         asc_desc_created_at: Option<Ordering>,
     ) -> Result<Vec<TransactionRecord>, StorageError>;
+    /// Returns the transaction that holds the given signer-bound idempotency key, if it was recorded in or after
+    /// `since_epoch` in a committed block or in a block between the commit block and `from_block_id` (inclusive).
+    fn transaction_idempotency_keys_get(
+        &self,
+        idempotency_key: &IdempotencyKey,
+        since_epoch: Epoch,
+        from_block_id: &BlockId,
+    ) -> Result<Option<TransactionId>, StorageError>;
 
     fn transaction_executions_get(
         &self,
@@ -241,6 +252,7 @@ pub trait StateStoreReadTransaction: Sized {
         &self,
         max_txs: usize,
         block_id: &BlockId,
+        ordering: TransactionOrdering,
     ) -> Result<Vec<TransactionPoolRecord>, StorageError>;
     fn transaction_pool_count(
         &self,
@@ -262,6 +274,7 @@ pub trait StateStoreReadTransaction: Sized {
     ) -> Result<Vote, StorageError>;
     fn votes_count_for_block(&self, block_id: &BlockId) -> Result<u64, StorageError>;
     fn votes_get_for_block(&self, block_id: &BlockId) -> Result<Vec<Vote>, StorageError>;
+    fn votes_get_by_sender(&self, epoch: Epoch, sender_leaf_hash: &FixedHash) -> Result<Vec<Vote>, StorageError>;
     //---------------------------------- Substates --------------------------------------------//
     fn substates_get(&self, address: &SubstateAddress) -> Result<SubstateRecord, StorageError>;
     fn substates_get_any(
@@ -328,6 +341,11 @@ pub trait StateStoreReadTransaction: Sized {
 
     // -------------------------------- Epoch checkpoint -------------------------------- //
     fn epoch_checkpoint_get(&self, epoch: Epoch) -> Result<EpochCheckpoint, StorageError>;
+    fn epoch_handover_checkpoints_get(
+        &self,
+        epoch: Epoch,
+        shard_group: ShardGroup,
+    ) -> Result<EpochCheckpoint, StorageError>;
 
     // -------------------------------- Foreign Substate Pledges -------------------------------- //
     fn foreign_substate_pledges_exists_for_address<T: ToSubstateAddress>(
@@ -359,6 +377,11 @@ pub trait StateStoreReadTransaction: Sized {
         epoch: Epoch,
         public_key: &PublicKey,
     ) -> Result<ValidatorConsensusStats, StorageError>;
+    fn validator_epoch_stats_get_all_in_epoch_range(
+        &self,
+        epoch_range: RangeInclusive<Epoch>,
+        public_key: Option<&PublicKey>,
+    ) -> Result<Vec<ValidatorEpochStats>, StorageError>;
     fn validator_epoch_stats_get_nodes_to_suspend(
         &self,
         block_id: &BlockId,
@@ -373,6 +396,7 @@ pub trait StateStoreReadTransaction: Sized {
     // -------------------------------- SuspendedNodes -------------------------------- //
     fn suspended_nodes_is_suspended(&self, block_id: &BlockId, public_key: &PublicKey) -> Result<bool, StorageError>;
     fn suspended_nodes_count(&self) -> Result<u64, StorageError>;
+    fn suspended_nodes_get_all(&self) -> Result<Vec<PublicKey>, StorageError>;
 }
 
 pub trait StateStoreWriteTransaction {
@@ -452,6 +476,14 @@ pub trait StateStoreWriteTransaction {
         block_id: BlockId,
         transaction: I,
     ) -> Result<(), StorageError>;
+    fn transaction_idempotency_keys_insert_all<'a, I: IntoIterator<Item = (&'a IdempotencyKey, &'a TransactionId)>>(
+        &mut self,
+        block_id: &BlockId,
+        epoch: Epoch,
+        idempotency_keys: I,
+    ) -> Result<(), StorageError>;
+    fn transaction_idempotency_keys_set_committed(&mut self, block_id: &BlockId) -> Result<(), StorageError>;
+    fn transaction_idempotency_keys_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError>;
     // -------------------------------- Transaction Executions -------------------------------- //
     fn transaction_executions_insert_or_ignore(
         &mut self,
@@ -529,16 +561,9 @@ pub trait StateStoreWriteTransaction {
 
     fn substate_locks_remove_any_by_block_id(&mut self, block_id: &BlockId) -> Result<(), StorageError>;
 
-    fn substates_create(&mut self, substate: &SubstateRecord) -> Result<(), StorageError>;
-    fn substates_down(
-        &mut self,
-        versioned_substate_id: VersionedSubstateId,
-        shard: Shard,
-        epoch: Epoch,
-        destroyed_block_height: NodeHeight,
-        destroyed_transaction_id: &TransactionId,
-        destroyed_qc_id: &QcId,
-    ) -> Result<(), StorageError>;
+    /// Creates and destroys substates and records their state transitions in the order given. The state tree updates
+    /// for the substates MUST be committed first because the transitions record the current state tree version.
+    fn substates_write_many(&mut self, writes: &[SubstateWrite]) -> Result<(), StorageError>;
 
     // -------------------------------- Foreign pledges -------------------------------- //
 
@@ -574,12 +599,18 @@ pub trait StateStoreWriteTransaction {
     fn state_tree_nodes_record_stale_tree_node(
         &mut self,
         shard: Shard,
+        stale_since_version: Version,
         node: StaleTreeNode,
     ) -> Result<(), StorageError>;
+    /// Deletes all nodes in the shard that have been stale since `up_to_version` or earlier, returning the number of
+    /// nodes deleted.
+    fn state_tree_nodes_prune_stale(&mut self, shard: Shard, up_to_version: Version) -> Result<usize, StorageError>;
     fn state_tree_shard_versions_set(&mut self, shard: Shard, version: Version) -> Result<(), StorageError>;
 
     // -------------------------------- Epoch checkpoint -------------------------------- //
     fn epoch_checkpoint_save(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError>;
+    /// Inserts the validated checkpoint of a shard group that handed over shards to the local shard group
+    fn epoch_handover_checkpoints_insert(&mut self, checkpoint: &EpochCheckpoint) -> Result<(), StorageError>;
 
     // -------------------------------- BurntUtxo -------------------------------- //
     fn burnt_utxos_insert(&mut self, burnt_utxo: &BurntUtxo) -> Result<(), StorageError>;
@@ -599,7 +630,6 @@ pub trait StateStoreWriteTransaction {
     ) -> Result<(), StorageError>;
 
     // -------------------------------- ParticipationShares -------------------------------- //
-    fn validator_epoch_stats_add_participation_share(&mut self, qc_id: &QcId) -> Result<(), StorageError>;
     fn validator_epoch_stats_updates<'a, I: IntoIterator<Item = ValidatorStatsUpdate<'a>>>(
         &mut self,
         epoch: Epoch,
@@ -0,0 +1,5 @@
+ALTER TABLE committees
+    DROP COLUMN voting_power;
+
+ALTER TABLE validator_nodes
+    DROP COLUMN stake;
@@ -0,0 +1,5 @@
+ALTER TABLE validator_nodes
+    ADD COLUMN stake BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE committees
+    ADD COLUMN voting_power BIGINT NOT NULL DEFAULT 1;
@@ -370,6 +370,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
         start_epoch: Epoch,
         fee_claim_public_key: PublicKey,
         sidechain_id: Option<PublicKey>,
+        stake: u64,
     ) -> Result<(), Self::Error> {
         use crate::global::schema::validator_nodes;
         let addr = serialize_json(&address)?;
@@ -383,6 +384,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 validator_nodes::start_epoch.eq(start_epoch.as_u64() as i64),
                 validator_nodes::fee_claim_public_key.eq(ByteArray::as_bytes(&fee_claim_public_key)),
                 validator_nodes::sidechain_id.eq(sidechain_id.as_ref().map(|id| id.as_bytes()).unwrap_or(&[0u8; 32])),
+                validator_nodes::stake.eq(stake as i64),
             ))
             .execute(tx.connection())
             .map_err(|source| SqliteStorageError::DieselError {
@@ -436,6 +438,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 validator_nodes::fee_claim_public_key,
                 validator_nodes::address,
                 validator_nodes::sidechain_id,
+                validator_nodes::stake,
             ))
             .filter(validator_nodes::start_epoch.le(epoch.as_u64() as i64))
             .filter(validator_nodes::address.eq(serialize_json(address)?))
@@ -562,25 +565,26 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 committees::shard_end,
                 validator_nodes::address,
                 validator_nodes::public_key,
+                committees::voting_power,
             ))
             .filter(committees::epoch.eq(epoch.as_u64() as i64))
             .filter(validator_nodes::sidechain_id.eq(db_sidechain_id))
-            .load::<(i32, i32, String, Vec<u8>)>(tx.connection())
+            .load::<(i32, i32, String, Vec<u8>, i64)>(tx.connection())
             .map_err(|source| SqliteStorageError::DieselError {
                 source,
                 operation: "validator_nodes_get_committees".to_string(),
             })?;
 
         let mut committees = HashMap::new();
-        for (shard_start, shard_end, address, public_key) in results {
+        for (shard_start, shard_end, address, public_key, voting_power) in results {
             let addr = DbValidatorNode::try_parse_address(&address)?;
             let pk = PublicKey::from_canonical_bytes(&public_key)
                 .map_err(|_| SqliteStorageError::MalformedDbData("Invalid public key".to_string()))?;
-            committees
+            let committee = committees
                 .entry(ShardGroup::new(shard_start as u32, shard_end as u32))
-                .or_insert_with(Committee::empty)
-                .members
-                .push((addr, pk));
+                .or_insert_with(Committee::empty);
+            committee.members.push((addr, pk.clone()));
+            committee.set_voting_power(pk, voting_power as u64);
         }
 
         Ok(committees)
@@ -593,6 +597,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
         shard_group: ShardGroup,
         sidechain_id: Option<&PublicKey>,
         epoch: Epoch,
+        voting_power: u64,
     ) -> Result<(), Self::Error> {
         use crate::global::schema::{committees, validator_nodes};
         let db_sidechain_id = sidechain_id.map(|id| id.as_bytes()).unwrap_or(&[0u8; 32]);
@@ -616,6 +621,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 committees::epoch.eq(epoch.as_u64() as i64),
                 committees::shard_start.eq(shard_group.start().as_u32() as i32),
                 committees::shard_end.eq(shard_group.end().as_u32() as i32),
+                committees::voting_power.eq(voting_power as i64),
             ))
             .execute(tx.connection())
             .map_err(|source| SqliteStorageError::DieselError {
@@ -645,7 +651,8 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 validator_nodes::start_epoch,
                 validator_nodes::fee_claim_public_key,
                 validator_nodes::address,
-                validator_nodes::sidechain_id
+                validator_nodes::sidechain_id,
+                validator_nodes::stake,
             ))
             .filter(validator_nodes::start_epoch.le(epoch.as_u64() as i64))
             // SQLite compares BLOB types using memcmp which, IIRC, compares bytes "left to right"/big-endian which is
@@ -676,8 +683,9 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
             .left_join(committees::table.on(committees::validator_node_id.eq(validator_nodes::id)))
             .select((validator_nodes::all_columns, committees::all_columns.nullable()))
             .filter(committees::epoch.eq(epoch.as_u64() as i64))
-            .filter(committees::shard_start.le(shard_group.start().as_u32() as i32))
-            .filter(committees::shard_end.ge(shard_group.end().as_u32() as i32))
+            // Every committee that overlaps the shard group e.g. the committees that a merged shard group came from
+            .filter(committees::shard_start.le(shard_group.end().as_u32() as i32))
+            .filter(committees::shard_end.ge(shard_group.start().as_u32() as i32))
             .get_results::<(DbValidatorNode, Option<DbCommittee>)>(tx.connection())
             .map_err(|source| SqliteStorageError::DieselError {
                 source,
@@ -690,15 +698,13 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 .entry(committee.as_shard_group())
                 .or_insert_with(|| Committee::empty());
 
-            validators.members.push((
-                DbValidatorNode::try_parse_address(&vn.address)?,
-                PublicKey::from_canonical_bytes(&vn.public_key).map_err(|_| {
-                    SqliteStorageError::MalformedDbData(format!(
-                        "Invalid public key in validator node record id={}",
-                        vn.id
-                    ))
-                })?,
-            ));
+            let public_key = PublicKey::from_canonical_bytes(&vn.public_key).map_err(|_| {
+                SqliteStorageError::MalformedDbData(format!("Invalid public key in validator node record id={}", vn.id))
+            })?;
+            validators
+                .members
+                .push((DbValidatorNode::try_parse_address(&vn.address)?, public_key.clone()));
+            validators.set_voting_power(public_key, committee.voting_power as u64);
         }
 
         Ok(committees)
@@ -723,6 +729,7 @@ impl<TAddr: NodeAddressable> GlobalDbAdapter for SqliteGlobalDbAdapter<TAddr> {
                 validator_nodes::fee_claim_public_key,
                 validator_nodes::address,
                 validator_nodes::sidechain_id,
+                validator_nodes::stake,
             ))
             .filter(validator_nodes::start_epoch.le(epoch.as_u64() as i64))
             .filter(validator_nodes::sidechain_id.eq(db_sidechain_id))
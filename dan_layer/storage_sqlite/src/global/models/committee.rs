@@ -32,6 +32,7 @@ pub struct DbCommittee {
     pub epoch: i64,
     pub shard_start: i32,
     pub shard_end: i32,
+    pub voting_power: i64,
 }
 
 impl DbCommittee {
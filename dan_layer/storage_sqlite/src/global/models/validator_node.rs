@@ -41,6 +41,7 @@ pub struct DbValidatorNode {
     pub fee_claim_public_key: Vec<u8>,
     pub address: String,
     pub sidechain_id: Vec<u8>,
+    pub stake: i64,
 }
 impl<TAddr: NodeAddressable> TryFrom<DbValidatorNode> for ValidatorNode<TAddr> {
     type Error = SqliteStorageError;
@@ -72,6 +73,7 @@ impl<TAddr: NodeAddressable> TryFrom<DbValidatorNode> for ValidatorNode<TAddr> {
                     ))
                 })?)
             },
+            stake: vn.stake as u64,
         })
     }
 }
@@ -21,6 +21,7 @@ diesel::table! {
         epoch -> BigInt,
         shard_start -> Integer,
         shard_end -> Integer,
+        voting_power -> BigInt,
     }
 }
 
@@ -66,6 +67,7 @@ diesel::table! {
         fee_claim_public_key -> Binary,
         address -> Text,
         sidechain_id -> Binary,
+        stake -> BigInt,
     }
 }
 
@@ -54,6 +54,7 @@ fn insert_vn_with_public_key(
             start_epoch,
             public_key,
             sidechain_id,
+            0,
         )
         .unwrap()
 }
@@ -63,9 +64,25 @@ fn set_committee_shard_group(
     public_key: &PublicKey,
     shard_group: ShardGroup,
     epoch: Epoch,
+) {
+    set_committee_shard_group_with_voting_power(validator_nodes, public_key, shard_group, epoch, 1);
+}
+
+fn set_committee_shard_group_with_voting_power(
+    validator_nodes: &mut ValidatorNodeDb<'_, '_, SqliteGlobalDbAdapter<PeerAddress>>,
+    public_key: &PublicKey,
+    shard_group: ShardGroup,
+    epoch: Epoch,
+    voting_power: u64,
 ) {
     validator_nodes
-        .set_committee_shard(derived_substate_address(public_key), shard_group, None, epoch)
+        .set_committee_shard(
+            derived_substate_address(public_key),
+            shard_group,
+            None,
+            epoch,
+            voting_power,
+        )
         .unwrap();
 }
 
@@ -97,3 +114,68 @@ fn change_committee_shard_group() {
         .unwrap();
     assert_eq!(vns.get(&ShardGroup::new(4, 5)).unwrap().len(), 2);
 }
+
+#[test]
+fn committees_include_voting_power() {
+    let db = create_db();
+    let mut tx = db.create_transaction().unwrap();
+    let mut validator_nodes = db.validator_nodes(&mut tx);
+    let pk1 = new_public_key();
+    let pk2 = new_public_key();
+    insert_vn_with_public_key(&mut validator_nodes, pk1.clone(), Epoch(0), None);
+    insert_vn_with_public_key(&mut validator_nodes, pk2.clone(), Epoch(0), None);
+    set_committee_shard_group_with_voting_power(&mut validator_nodes, &pk1, ShardGroup::new(0, 63), Epoch(0), 100);
+    set_committee_shard_group(&mut validator_nodes, &pk2, ShardGroup::new(0, 63), Epoch(0));
+
+    let committees = validator_nodes
+        .get_committees_for_shard_group(Epoch(0), ShardGroup::new(0, 63))
+        .unwrap();
+    let committee = committees.get(&ShardGroup::new(0, 63)).unwrap();
+    assert_eq!(committee.voting_power_of(&pk1), 100);
+    assert_eq!(committee.voting_power_of(&pk2), 1);
+    assert_eq!(committee.total_voting_power(), 101);
+
+    let committees = validator_nodes.get_committees(Epoch(0), None).unwrap();
+    let committee = committees.get(&ShardGroup::new(0, 63)).unwrap();
+    assert_eq!(committee.voting_power_of(&pk1), 100);
+}
+
+#[test]
+fn committees_for_shard_group_include_overlapping_committees() {
+    let db = create_db();
+    let mut tx = db.create_transaction().unwrap();
+    let mut validator_nodes = db.validator_nodes(&mut tx);
+    let pk1 = new_public_key();
+    let pk2 = new_public_key();
+    let pk3 = new_public_key();
+    insert_vn_with_public_key(&mut validator_nodes, pk1.clone(), Epoch(0), None);
+    insert_vn_with_public_key(&mut validator_nodes, pk2.clone(), Epoch(0), None);
+    insert_vn_with_public_key(&mut validator_nodes, pk3.clone(), Epoch(0), None);
+    set_committee_shard_group(&mut validator_nodes, &pk1, ShardGroup::new(0, 15), Epoch(0));
+    set_committee_shard_group(&mut validator_nodes, &pk2, ShardGroup::new(16, 31), Epoch(0));
+    set_committee_shard_group(&mut validator_nodes, &pk3, ShardGroup::new(32, 63), Epoch(0));
+
+    // The committees that a merged shard group was formed from
+    let committees = validator_nodes
+        .get_committees_for_shard_group(Epoch(0), ShardGroup::new(0, 31))
+        .unwrap();
+    assert_eq!(committees.len(), 2);
+    assert!(committees
+        .get(&ShardGroup::new(0, 15))
+        .unwrap()
+        .contains_public_key(&pk1));
+    assert!(committees
+        .get(&ShardGroup::new(16, 31))
+        .unwrap()
+        .contains_public_key(&pk2));
+
+    // The committee that a split shard group was formed from
+    let committees = validator_nodes
+        .get_committees_for_shard_group(Epoch(0), ShardGroup::new(40, 47))
+        .unwrap();
+    assert_eq!(committees.len(), 1);
+    assert!(committees
+        .get(&ShardGroup::new(32, 63))
+        .unwrap()
+        .contains_public_key(&pk3));
+}
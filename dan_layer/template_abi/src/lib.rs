@@ -43,3 +43,10 @@ pub use types::*;
 
 /// The name of the global export that defines the template definition
 pub const ABI_TEMPLATE_DEF_GLOBAL_NAME: &str = "_ABI_TEMPLATE_DEF";
+
+/// The name of the global export that defines the ABI version the template was compiled with
+pub const ABI_VERSION_GLOBAL_NAME: &str = "_ABI_VERSION";
+
+/// The ABI version of templates compiled with this crate. This must be incremented whenever the template definition or
+/// the encoding of engine calls changes in a way that is incompatible with previously compiled templates.
+pub const ABI_VERSION: u32 = 1;
@@ -11,7 +11,18 @@ use std::{
     process::Command,
 };
 
-const TEMPLATE_BUILTINS: &[&str] = &["templates/account", "templates/account_nfts", "templates/faucet"];
+const TEMPLATE_BUILTINS: &[&str] = &[
+    "templates/account",
+    "templates/account_nfts",
+    "templates/faucet",
+    "templates/multisig",
+    "templates/escrow",
+    "templates/amm",
+    "templates/auction",
+    "templates/name_service",
+    "templates/payment_splitter",
+    "templates/staking",
+];
 
 fn main() -> Result<(), Box<dyn Error>> {
     // Rebuild templates if abi or lib changes
@@ -28,6 +28,27 @@ pub const ACCOUNT_NFT_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_
 pub const FAUCET_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
     1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
 ]);
+pub const MULTISIG_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+]);
+pub const ESCROW_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,
+]);
+pub const AMM_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,
+]);
+pub const AUCTION_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5,
+]);
+pub const NAME_SERVICE_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
+]);
+pub const PAYMENT_SPLITTER_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,
+]);
+pub const STAKING_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8,
+]);
 
 pub fn get_template_builtin(address: &TemplateAddress) -> &'static [u8] {
     try_get_template_builtin(address).unwrap_or_else(|| panic!("Unknown builtin template address {address}"))
@@ -51,6 +72,34 @@ pub fn all_builtin_templates() -> impl Iterator<Item = (TemplateAddress, &'stati
             FAUCET_TEMPLATE_ADDRESS,
             include_bytes!("../templates/faucet/faucet.wasm").as_slice(),
         ),
+        (
+            MULTISIG_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/multisig/multisig.wasm").as_slice(),
+        ),
+        (
+            ESCROW_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/escrow/escrow.wasm").as_slice(),
+        ),
+        (
+            AMM_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/amm/amm.wasm").as_slice(),
+        ),
+        (
+            AUCTION_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/auction/auction.wasm").as_slice(),
+        ),
+        (
+            NAME_SERVICE_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/name_service/name_service.wasm").as_slice(),
+        ),
+        (
+            PAYMENT_SPLITTER_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/payment_splitter/payment_splitter.wasm").as_slice(),
+        ),
+        (
+            STAKING_TEMPLATE_ADDRESS,
+            include_bytes!("../templates/staking/staking.wasm").as_slice(),
+        ),
     ]
     .into_iter()
 }
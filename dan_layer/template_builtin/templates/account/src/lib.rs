@@ -20,8 +20,134 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_template_abi::rust::collections::BTreeMap;
-use tari_template_lib::prelude::*;
+use serde::{Deserialize, Serialize};
+use tari_template_abi::rust::collections::{BTreeMap, BTreeSet};
+use tari_template_lib::{
+    events::account::{
+        DepositEvent,
+        FeePaidEvent,
+        WithdrawEvent,
+        ACCOUNT_EVENT_INDEXED_FIELDS,
+        DEPOSIT_EVENT_TOPIC,
+        FEE_PAID_EVENT_INDEXED_FIELDS,
+        FEE_PAID_EVENT_TOPIC,
+        WITHDRAW_EVENT_TOPIC,
+    },
+    prelude::*,
+};
+
+/// Limits the amount of a resource that may be withdrawn from the account within each window of epochs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    /// The maximum amount that may be withdrawn within a window
+    pub amount: Amount,
+    /// The length of a window in epochs. Windows start at multiples of this length.
+    pub window_epochs: u64,
+    /// The first epoch of the window in which `spent` was withdrawn
+    pub window_start_epoch: u64,
+    /// The amount withdrawn within the current window
+    pub spent: Amount,
+}
+
+impl SpendingLimit {
+    fn window_start(&self, epoch: u64) -> u64 {
+        epoch - epoch % self.window_epochs
+    }
+
+    /// Records the withdrawal of `amount` in the current epoch, panicking if it exceeds the limit
+    fn spend(&mut self, resource: ResourceAddress, amount: Amount, current_epoch: u64) {
+        let window_start_epoch = self.window_start(current_epoch);
+        if window_start_epoch != self.window_start_epoch {
+            self.window_start_epoch = window_start_epoch;
+            self.spent = Amount::zero();
+        }
+        let spent = self.spent + amount;
+        assert!(
+            spent <= self.amount,
+            "Spending limit of {} for resource {} exceeded: {} withdrawn since epoch {}, {} requested",
+            self.amount,
+            resource,
+            self.spent,
+            self.window_start_epoch,
+            amount
+        );
+        self.spent = spent;
+    }
+}
+
+/// Restricts the resources that may be deposited into the account, e.g. to block spam tokens and NFTs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositFilter {
+    /// If not empty, only these resources may be deposited
+    pub allowed_resources: BTreeSet<ResourceAddress>,
+    /// If true, resources for which the account does not yet have a vault may not be deposited
+    pub deny_unknown: bool,
+}
+
+impl DepositFilter {
+    fn is_allowed(&self, resource: &ResourceAddress, is_known: bool) -> bool {
+        if self.allowed_resources.contains(resource) {
+            return true;
+        }
+        self.allowed_resources.is_empty() && (is_known || !self.deny_unknown)
+    }
+}
+
+/// The guardians that may recover the account if the owner loses their key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryConfig {
+    pub guardians: BTreeSet<NonFungibleAddress>,
+    /// The number of guardians that must approve a recovery
+    pub threshold: u32,
+    /// The number of epochs after a recovery is approved by the threshold of guardians before it may be completed,
+    /// during which the owner may cancel it
+    pub delay_epochs: u64,
+}
+
+/// A proposal by guardians to rotate the owner of the account to a new owner token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryProposal {
+    pub approvals: BTreeSet<NonFungibleAddress>,
+    /// The epoch in which the threshold of approvals was reached
+    pub approved_epoch: Option<u64>,
+}
+
+/// Methods that may only be called by the owner of the account
+const OWNER_METHODS: [&str; 5] = [
+    "set_spending_limit",
+    "remove_spending_limit",
+    "set_deposit_filter",
+    "set_guardians",
+    "cancel_recovery",
+];
+
+fn default_access_rules(owner_token: NonFungibleAddress) -> AccessRules {
+    AccessRules::new()
+        .add_method_rule("balance", rule!(allow_all))
+        .add_method_rule("get_balances", rule!(allow_all))
+        .add_method_rule("deposit", rule!(allow_all))
+        .add_method_rule("deposit_all", rule!(allow_all))
+        .add_method_rule("get_non_fungible_ids", rule!(allow_all))
+        .add_method_rule("get_non_fungible_ids_paged", rule!(allow_all))
+        .add_method_rule("get_non_fungible_count", rule!(allow_all))
+        .add_method_rule("get_spending_limit", rule!(allow_all))
+        // Spenders and guardians are authorized by the proof of their token
+        .add_method_rule("withdraw_from_allowance", rule!(allow_all))
+        .add_method_rule("allowance", rule!(allow_all))
+        .add_method_rule("get_deposit_filter", rule!(allow_all))
+        .add_method_rule("approve_recovery", rule!(allow_all))
+        .add_method_rule("complete_recovery", rule!(allow_all))
+        .add_method_rule("get_recovery_config", rule!(allow_all))
+        .add_method_rule("get_recovery_proposals", rule!(allow_all))
+        // By defaul, only the owner of the token will be able to withdraw funds from the account
+        .default(rule!(non_fungible(owner_token)))
+}
+
+fn with_owner_methods(access_rules: AccessRules, rule: AccessRule) -> AccessRules {
+    OWNER_METHODS.iter().fold(access_rules, |rules, method| {
+        rules.add_method_rule(*method, rule.clone())
+    })
+}
 
 #[template]
 mod account_template {
@@ -30,6 +156,21 @@ mod account_template {
     pub struct Account {
         // TODO: Lazy key value map/store
         vaults: BTreeMap<ResourceAddress, Vault>,
+        #[serde(default)]
+        spending_limits: BTreeMap<ResourceAddress, SpendingLimit>,
+        /// The amount of each resource that each spender token has been approved to withdraw
+        #[serde(default)]
+        allowances: BTreeMap<ResourceAddress, BTreeMap<NonFungibleAddress, Amount>>,
+        #[serde(default)]
+        deposit_filter: DepositFilter,
+        /// The token of the owner, which is rotated by a recovery
+        #[serde(default)]
+        owner_token: Option<NonFungibleAddress>,
+        #[serde(default)]
+        recovery: Option<RecoveryConfig>,
+        /// The recovery proposals by the new owner token
+        #[serde(default)]
+        recovery_proposals: BTreeMap<NonFungibleAddress, RecoveryProposal>,
     }
 
     impl Account {
@@ -44,16 +185,10 @@ mod account_template {
                 OwnerRule::ByPublicKey(public_key)
             );
 
-            let access_rules = access_rules.unwrap_or(
-                AccessRules::new()
-                    .add_method_rule("balance", rule!(allow_all))
-                    .add_method_rule("get_balances", rule!(allow_all))
-                    .add_method_rule("deposit", rule!(allow_all))
-                    .add_method_rule("deposit_all", rule!(allow_all))
-                    .add_method_rule("get_non_fungible_ids", rule!(allow_all))
-                    // By defaul, only the owner of the token will be able to withdraw funds from the account
-                    .default(rule!(non_fungible(public_key_token)))
-            );
+            let access_rules = access_rules.unwrap_or_else(|| default_access_rules(public_key_token.clone()));
+            // Owner methods are denied to anyone but the owner, who bypasses the access rules, so that they cannot be
+            // called by any other key that is allowed to withdraw
+            let access_rules = with_owner_methods(access_rules, rule!(deny_all));
 
             // add the funds from the (optional) bucket
             let mut vaults = BTreeMap::new();
@@ -61,7 +196,15 @@ mod account_template {
                 vaults.insert(b.resource_address(), Vault::from_bucket(b));
             }
 
-            Component::new(Self { vaults })
+            Component::new(Self {
+                vaults,
+                spending_limits: BTreeMap::new(),
+                allowances: BTreeMap::new(),
+                deposit_filter: DepositFilter::default(),
+                owner_token: Some(public_key_token),
+                recovery: None,
+                recovery_proposals: BTreeMap::new(),
+            })
                 .with_access_rules(access_rules)
                 .with_public_key_address(public_key)
                 .with_owner_rule(owner_rule)
@@ -82,11 +225,11 @@ mod account_template {
 
         // #[access_rule(requires(owner_badge))]
         pub fn withdraw(&mut self, resource: ResourceAddress, amount: Amount) -> Bucket {
-            // TODO: clean up hashmap api in emit_event
-            emit_event("withdraw", [
-                ("amount", amount.to_string()),
-                ("resource", resource.to_string()),
-            ]);
+            emit_indexed_event(WITHDRAW_EVENT_TOPIC, ACCOUNT_EVENT_INDEXED_FIELDS, &WithdrawEvent {
+                resource_address: resource,
+                amount,
+            });
+            self.record_spend(resource, amount);
             let v = self.get_vault_mut(resource);
             v.withdraw(amount)
         }
@@ -97,6 +240,7 @@ mod account_template {
                 ("id", nf_id.to_string()),
                 ("resource", resource.to_string()),
             ]);
+            self.record_spend(resource, Amount(1));
             let v = self.get_vault_mut(resource);
             v.withdraw_non_fungibles([nf_id])
         }
@@ -109,6 +253,7 @@ mod account_template {
                     nf_ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(","),
                 ),
             ]);
+            self.record_spend(resource, Amount::new(nf_ids.len() as i64));
             let v = self.get_vault_mut(resource);
             v.withdraw_non_fungibles(nf_ids)
         }
@@ -124,17 +269,41 @@ mod account_template {
                 ("resource", resource.to_string()),
             ]);
 
+            self.record_confidential_spend(resource, &withdraw_proof);
             let v = self.get_vault_mut(resource);
             v.withdraw_confidential(withdraw_proof)
         }
 
+        /// Withdraws each amount of the resource and deposits it into the destination component, e.g. for a batch of
+        /// payouts. Destinations must implement a `deposit` method.
+        pub fn transfer_many(&mut self, transfers: Vec<(ResourceAddress, Amount, ComponentAddress)>) {
+            let this = CallerContext::current_component_address();
+            for (resource, amount, destination) in transfers {
+                assert_ne!(destination, this, "Cannot transfer to the account itself");
+                emit_event("transfer", [
+                    ("resource", resource.to_string()),
+                    ("amount", amount.to_string()),
+                    ("destination", destination.to_string()),
+                ]);
+                self.record_spend(resource, amount);
+                let bucket = self.get_vault_mut(resource).withdraw(amount);
+                ComponentManager::get(destination).invoke("deposit", args![bucket]);
+            }
+        }
+
         // #[access_rules(allow_all)]
         pub fn deposit(&mut self, bucket: Bucket) {
-            emit_event("deposit", [
-                ("amount", bucket.amount().to_string()),
-                ("resource", bucket.resource_address().to_string()),
-            ]);
+            emit_indexed_event(DEPOSIT_EVENT_TOPIC, ACCOUNT_EVENT_INDEXED_FIELDS, &DepositEvent {
+                resource_address: bucket.resource_address(),
+                amount: bucket.amount(),
+            });
             let resource_address = bucket.resource_address();
+            assert!(
+                self.deposit_filter
+                    .is_allowed(&resource_address, self.vaults.contains_key(&resource_address)),
+                "Deposits of resource {} are not accepted by this account",
+                resource_address
+            );
             let vault_mut = self
                 .vaults
                 .entry(resource_address)
@@ -148,12 +317,184 @@ mod account_template {
             }
         }
 
+        /// Sets the resources that may be deposited into the account. If `allowed_resources` is not empty, only those
+        /// resources are accepted. If `deny_unknown` is true, resources for which the account does not yet have a
+        /// vault are rejected. Only the account owner may call this method.
+        pub fn set_deposit_filter(&mut self, allowed_resources: Vec<ResourceAddress>, deny_unknown: bool) {
+            emit_event("set_deposit_filter", [
+                (
+                    "allowed_resources",
+                    allowed_resources
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ),
+                ("deny_unknown", deny_unknown.to_string()),
+            ]);
+            self.deposit_filter = DepositFilter {
+                allowed_resources: allowed_resources.into_iter().collect(),
+                deny_unknown,
+            };
+        }
+
+        pub fn get_deposit_filter(&self) -> DepositFilter {
+            self.deposit_filter.clone()
+        }
+
+        /// Sets the guardians that may recover the account, replacing any previous guardians. Only the account owner
+        /// may call this method.
+        ///
+        /// When guardians are first set, the account becomes owned by itself so that a recovery can rotate the owner,
+        /// and its access rules are replaced by the default rules of the owner token. The owner methods then require
+        /// the owner token.
+        pub fn set_guardians(&mut self, guardians: Vec<NonFungibleAddress>, threshold: u32, delay_epochs: u64) {
+            let guardians = guardians.into_iter().collect::<BTreeSet<_>>();
+            assert!(
+                threshold > 0 && threshold as usize <= guardians.len(),
+                "Threshold must be between 1 and the number of guardians ({})",
+                guardians.len()
+            );
+            emit_event("set_guardians", [
+                (
+                    "guardians",
+                    guardians.iter().map(ToString::to_string).collect::<Vec<_>>().join(","),
+                ),
+                ("threshold", threshold.to_string()),
+                ("delay_epochs", delay_epochs.to_string()),
+            ]);
+
+            if self.recovery.is_none() {
+                let owner_token = self.owner_token();
+                let component = ComponentManager::current();
+                component.set_access_rules(with_owner_methods(
+                    default_access_rules(owner_token.clone()),
+                    rule!(non_fungible(owner_token)),
+                ));
+                component.set_owner_rule(OwnerRule::OwnedByComponent(component.component_address()));
+            }
+            self.recovery = Some(RecoveryConfig {
+                guardians,
+                threshold,
+                delay_epochs,
+            });
+            // Approvals by previous guardians no longer count
+            self.recovery_proposals.clear();
+        }
+
+        /// Approves the recovery of the account to the new owner token by the guardians in `guardian_proof`. Once the
+        /// threshold of guardians has approved, the recovery may be completed after the recovery delay.
+        pub fn approve_recovery(&mut self, guardian_proof: Proof, new_owner_token: NonFungibleAddress) {
+            let recovery = self.get_recovery_config().expect("Account has no guardians");
+            let _access = guardian_proof.authorize();
+            let resource = guardian_proof.resource_address();
+            let ids = guardian_proof.get_non_fungibles();
+            let guardians = recovery
+                .guardians
+                .iter()
+                .filter(|g| *g.resource_address() == resource && ids.contains(g.id()))
+                .cloned()
+                .collect::<Vec<_>>();
+            assert!(!guardians.is_empty(), "Proof does not contain a guardian badge");
+
+            let proposal = self
+                .recovery_proposals
+                .entry(new_owner_token.clone())
+                .or_insert_with(|| RecoveryProposal {
+                    approvals: BTreeSet::new(),
+                    approved_epoch: None,
+                });
+            for guardian in guardians {
+                emit_event("approve_recovery", [
+                    ("guardian", guardian.to_string()),
+                    ("new_owner_token", new_owner_token.to_string()),
+                ]);
+                proposal.approvals.insert(guardian);
+            }
+            if proposal.approved_epoch.is_none() && proposal.approvals.len() >= recovery.threshold as usize {
+                proposal.approved_epoch = Some(Consensus::current_epoch());
+            }
+        }
+
+        /// Rotates the owner of the account to the new owner token once the recovery has been approved by the
+        /// threshold of guardians and the recovery delay has passed. The access rules are replaced by the default
+        /// rules of the new owner token.
+        pub fn complete_recovery(&mut self, new_owner_token: NonFungibleAddress) {
+            let recovery = self.get_recovery_config().expect("Account has no guardians");
+            let approved_epoch = self
+                .recovery_proposals
+                .get(&new_owner_token)
+                .and_then(|p| p.approved_epoch)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Recovery to {} has not been approved by {} guardians",
+                        new_owner_token, recovery.threshold
+                    )
+                });
+            let unlock_epoch = approved_epoch + recovery.delay_epochs;
+            assert!(
+                Consensus::current_epoch() >= unlock_epoch,
+                "Recovery to {} may not be completed before epoch {}",
+                new_owner_token,
+                unlock_epoch
+            );
+
+            emit_event("complete_recovery", [
+                ("old_owner_token", self.owner_token().to_string()),
+                ("new_owner_token", new_owner_token.to_string()),
+            ]);
+            ComponentManager::current().set_access_rules(with_owner_methods(
+                default_access_rules(new_owner_token.clone()),
+                rule!(non_fungible(new_owner_token.clone())),
+            ));
+            self.owner_token = Some(new_owner_token);
+            self.recovery_proposals.clear();
+        }
+
+        /// Cancels all pending recoveries. Only the account owner may call this method.
+        pub fn cancel_recovery(&mut self) {
+            emit_event("cancel_recovery", [(
+                "num_proposals",
+                self.recovery_proposals.len().to_string(),
+            )]);
+            self.recovery_proposals.clear();
+        }
+
+        pub fn get_recovery_config(&self) -> Option<RecoveryConfig> {
+            self.recovery.clone()
+        }
+
+        pub fn get_recovery_proposals(&self) -> BTreeMap<NonFungibleAddress, RecoveryProposal> {
+            self.recovery_proposals.clone()
+        }
+
+        fn owner_token(&self) -> NonFungibleAddress {
+            self.owner_token
+                .clone()
+                .expect("The owner token of the account is unknown")
+        }
+
         // #[access_rules(require(owner_badge))]
         pub fn get_non_fungible_ids(&self, resource: ResourceAddress) -> Vec<NonFungibleId> {
             let v = self.get_vault(resource);
             v.get_non_fungible_ids()
         }
 
+        pub fn get_non_fungible_ids_paged(
+            &self,
+            resource: ResourceAddress,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<NonFungibleId> {
+            let v = self.get_vault(resource);
+            v.get_non_fungible_ids_paged(offset, limit)
+        }
+
+        pub fn get_non_fungible_count(&self, resource: ResourceAddress) -> u32 {
+            let v = self.get_vault(resource);
+            v.non_fungible_count()
+        }
+
         fn get_vault(&self, resource: ResourceAddress) -> &Vault {
             self.vaults
                 .get(&resource)
@@ -170,11 +511,151 @@ mod account_template {
             self.vaults.iter().map(|(k, v)| (*k, v.balance())).collect()
         }
 
+        /// Limits the amount of the resource that may be withdrawn within each window of `window_epochs` epochs.
+        /// Only the account owner may call this method. The amount already withdrawn in the current window is kept
+        /// when an existing limit is replaced.
+        pub fn set_spending_limit(&mut self, resource: ResourceAddress, amount: Amount, window_epochs: u64) {
+            assert!(!amount.is_negative(), "Spending limit must not be negative");
+            assert!(window_epochs > 0, "Spending limit window must be at least one epoch");
+            emit_event("set_spending_limit", [
+                ("resource", resource.to_string()),
+                ("amount", amount.to_string()),
+                ("window_epochs", window_epochs.to_string()),
+            ]);
+
+            let current_epoch = Consensus::current_epoch();
+            let mut limit = SpendingLimit {
+                amount,
+                window_epochs,
+                window_start_epoch: 0,
+                spent: Amount::zero(),
+            };
+            limit.window_start_epoch = limit.window_start(current_epoch);
+            if let Some(existing) = self.spending_limits.get(&resource) {
+                if existing.window_start(current_epoch) == existing.window_start_epoch {
+                    limit.spent = existing.spent;
+                }
+            }
+            self.spending_limits.insert(resource, limit);
+        }
+
+        /// Removes the spending limit of the resource. Only the account owner may call this method.
+        pub fn remove_spending_limit(&mut self, resource: ResourceAddress) {
+            emit_event("remove_spending_limit", [("resource", resource.to_string())]);
+            self.spending_limits.remove(&resource);
+        }
+
+        pub fn get_spending_limit(&self, resource: ResourceAddress) -> Option<SpendingLimit> {
+            self.spending_limits.get(&resource).copied()
+        }
+
+        /// Approves the holder of `spender_token` to withdraw up to `amount` of the resource from the account,
+        /// replacing any previous allowance. Approving a zero amount revokes the allowance.
+        pub fn approve(&mut self, resource: ResourceAddress, amount: Amount, spender_token: NonFungibleAddress) {
+            assert!(!amount.is_negative(), "Allowance must not be negative");
+            emit_event("approve", [
+                ("resource", resource.to_string()),
+                ("spender", spender_token.to_string()),
+                ("amount", amount.to_string()),
+            ]);
+
+            let allowances = self.allowances.entry(resource).or_default();
+            if amount.is_zero() {
+                allowances.remove(&spender_token);
+                if allowances.is_empty() {
+                    self.allowances.remove(&resource);
+                }
+            } else {
+                allowances.insert(spender_token, amount);
+            }
+        }
+
+        /// Withdraws `amount` of the resource from the allowance of the spender token contained in `spender_proof`.
+        /// Withdrawals from an allowance count towards the spending limit of the resource.
+        pub fn withdraw_from_allowance(
+            &mut self,
+            spender_proof: Proof,
+            spender_token: NonFungibleAddress,
+            resource: ResourceAddress,
+            amount: Amount,
+        ) -> Bucket {
+            spender_proof.assert_resource(*spender_token.resource_address());
+            let _access = spender_proof.authorize();
+            assert!(
+                spender_proof.get_non_fungibles().contains(spender_token.id()),
+                "Proof does not contain the spender token {}",
+                spender_token
+            );
+            assert!(amount.is_positive(), "Withdraw amount must be positive");
+
+            let allowances = self
+                .allowances
+                .get_mut(&resource)
+                .unwrap_or_else(|| panic!("No allowance for resource {}", resource));
+            let allowance = allowances
+                .get_mut(&spender_token)
+                .unwrap_or_else(|| panic!("{} has no allowance for resource {}", spender_token, resource));
+            assert!(
+                amount <= *allowance,
+                "Withdraw amount {} exceeds the allowance {} of {}",
+                amount,
+                allowance,
+                spender_token
+            );
+            *allowance -= amount;
+            let remaining = *allowance;
+            if remaining.is_zero() {
+                allowances.remove(&spender_token);
+                if allowances.is_empty() {
+                    self.allowances.remove(&resource);
+                }
+            }
+
+            emit_event("withdraw_from_allowance", [
+                ("resource", resource.to_string()),
+                ("spender", spender_token.to_string()),
+                ("amount", amount.to_string()),
+                ("remaining", remaining.to_string()),
+            ]);
+            self.record_spend(resource, amount);
+            self.get_vault_mut(resource).withdraw(amount)
+        }
+
+        /// Returns the amount of the resource that the spender token may withdraw
+        pub fn allowance(&self, resource: ResourceAddress, spender_token: NonFungibleAddress) -> Amount {
+            self.allowances
+                .get(&resource)
+                .and_then(|allowances| allowances.get(&spender_token))
+                .copied()
+                .unwrap_or_else(Amount::zero)
+        }
+
+        fn record_spend(&mut self, resource: ResourceAddress, amount: Amount) {
+            if let Some(limit) = self.spending_limits.get_mut(&resource) {
+                limit.spend(resource, amount, Consensus::current_epoch());
+            }
+        }
+
+        /// Only the revealed amount of a confidential withdrawal is known, so confidential outputs cannot be withdrawn
+        /// while the resource has a spending limit
+        fn record_confidential_spend(&mut self, resource: ResourceAddress, proof: &ConfidentialWithdrawProof) {
+            if !self.spending_limits.contains_key(&resource) {
+                return;
+            }
+            assert!(
+                proof.output_proof.output_statement.is_none(),
+                "Confidential outputs cannot be withdrawn while resource {} has a spending limit",
+                resource
+            );
+            self.record_spend(resource, proof.output_proof.output_revealed_amount);
+        }
+
         pub fn reveal_confidential(&mut self, resource: ResourceAddress, proof: ConfidentialWithdrawProof) -> Bucket {
             emit_event("reveal_confidential", [
                 ("num_inputs", proof.inputs.len().to_string()),
                 ("resource", resource.to_string()),
             ]);
+            self.record_confidential_spend(resource, &proof);
             let v = self.get_vault_mut(resource);
             v.reveal_confidential(proof)
         }
@@ -191,7 +672,9 @@ mod account_template {
 
         /// Pay fees from previously revealed confidential resource.
         pub fn pay_fee(&mut self, amount: Amount) {
-            emit_event("pay_fee", [("amount", amount.to_string())]);
+            emit_indexed_event(FEE_PAID_EVENT_TOPIC, FEE_PAID_EVENT_INDEXED_FIELDS, &FeePaidEvent {
+                amount,
+            });
             self.get_vault_mut(CONFIDENTIAL_TARI_RESOURCE_ADDRESS).pay_fee(amount);
         }
 
@@ -0,0 +1,244 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A constant-product automated market maker (x * y = k) for a pair of fungible resources.
+//!
+//! Liquidity providers deposit both resources in the ratio of the current reserves and receive LP tokens representing
+//! their share of the pool. Swaps are charged a fee, expressed in basis points, which remains in the pool and accrues
+//! to the LP token holders. Only the pool component may mint or burn its LP tokens.
+
+use tari_template_lib::prelude::*;
+
+/// The fee denominator, fees are expressed in basis points (e.g. 30 represents 0.3%)
+const FEE_DENOMINATOR: i128 = 10_000;
+/// The maximum fee that a pool may charge (10%)
+const MAX_FEE: u16 = 1_000;
+
+#[template]
+mod amm_template {
+    use super::*;
+
+    pub struct AmmPool {
+        a_vault: Vault,
+        b_vault: Vault,
+        lp_resource: ResourceAddress,
+        fee: u16,
+    }
+
+    impl AmmPool {
+        /// Creates a new, empty pool for the resource pair A - B. The fee is charged on every swap, in basis points.
+        pub fn new(a_resource: ResourceAddress, b_resource: ResourceAddress, fee: u16) -> Component<AmmPool> {
+            assert_ne!(a_resource, b_resource, "The resources of the pair must be different");
+            assert_fungible(a_resource);
+            assert_fungible(b_resource);
+            assert!(
+                fee <= MAX_FEE,
+                "Fee {} exceeds the maximum of {} basis points",
+                fee,
+                MAX_FEE
+            );
+
+            let address_alloc = CallerContext::allocate_component_address(None);
+            let pool_rule = rule!(component(*address_alloc.address()));
+            let lp_resource = ResourceBuilder::fungible()
+                .with_token_symbol("LP")
+                .mintable(pool_rule.clone())
+                .burnable(pool_rule)
+                .build();
+
+            Component::new(Self {
+                a_vault: Vault::new_empty(a_resource),
+                b_vault: Vault::new_empty(b_resource),
+                lp_resource,
+                fee,
+            })
+            .with_address_allocation(address_alloc)
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        /// Deposits liquidity into the pool and returns the minted LP tokens, followed by any A and B that were not
+        /// needed to match the current reserve ratio.
+        pub fn add_liquidity(&mut self, mut a_bucket: Bucket, mut b_bucket: Bucket) -> (Bucket, Bucket, Bucket) {
+            assert_eq!(
+                a_bucket.resource_address(),
+                self.a_vault.resource_address(),
+                "Invalid A resource"
+            );
+            assert_eq!(
+                b_bucket.resource_address(),
+                self.b_vault.resource_address(),
+                "Invalid B resource"
+            );
+            let a_amount = to_i128(a_bucket.amount());
+            let b_amount = to_i128(b_bucket.amount());
+            assert!(
+                a_amount > 0 && b_amount > 0,
+                "Both resources must be provided to add liquidity"
+            );
+
+            let lp_supply = to_i128(self.lp_total_supply());
+            let (a_used, b_used, lp_amount) = if lp_supply == 0 {
+                // The first provider sets the price
+                (a_amount, b_amount, isqrt(a_amount * b_amount))
+            } else {
+                let a_reserve = to_i128(self.a_vault.balance());
+                let b_reserve = to_i128(self.b_vault.balance());
+                let lp_for_a = a_amount * lp_supply / a_reserve;
+                let lp_for_b = b_amount * lp_supply / b_reserve;
+                if lp_for_a <= lp_for_b {
+                    let b_used = div_ceil(a_amount * b_reserve, a_reserve).min(b_amount);
+                    (a_amount, b_used, lp_for_a)
+                } else {
+                    let a_used = div_ceil(b_amount * a_reserve, b_reserve).min(a_amount);
+                    (a_used, b_amount, lp_for_b)
+                }
+            };
+            assert!(lp_amount > 0, "Liquidity provided is too small");
+
+            let a_used = to_amount(a_used);
+            let b_used = to_amount(b_used);
+            let lp_amount = to_amount(lp_amount);
+            self.a_vault.deposit(a_bucket.take(a_used));
+            self.b_vault.deposit(b_bucket.take(b_used));
+
+            emit_event("add_liquidity", [
+                ("a_amount", a_used.to_string()),
+                ("b_amount", b_used.to_string()),
+                ("lp_amount", lp_amount.to_string()),
+            ]);
+
+            let lp_bucket = ResourceManager::get(self.lp_resource).mint_fungible(lp_amount);
+            (lp_bucket, a_bucket, b_bucket)
+        }
+
+        /// Burns the LP tokens and returns the provider's share of the A and B reserves
+        pub fn remove_liquidity(&mut self, lp_bucket: Bucket) -> (Bucket, Bucket) {
+            assert_eq!(lp_bucket.resource_address(), self.lp_resource, "Invalid LP resource");
+            let lp_amount = to_i128(lp_bucket.amount());
+            assert!(lp_amount > 0, "LP bucket is empty");
+
+            let lp_supply = to_i128(self.lp_total_supply());
+            let a_amount = to_amount(lp_amount * to_i128(self.a_vault.balance()) / lp_supply);
+            let b_amount = to_amount(lp_amount * to_i128(self.b_vault.balance()) / lp_supply);
+            assert!(
+                a_amount.is_positive() && b_amount.is_positive(),
+                "LP amount is too small to withdraw liquidity"
+            );
+            lp_bucket.burn();
+
+            emit_event("remove_liquidity", [
+                ("a_amount", a_amount.to_string()),
+                ("b_amount", b_amount.to_string()),
+                ("lp_amount", lp_amount.to_string()),
+            ]);
+
+            (self.a_vault.withdraw(a_amount), self.b_vault.withdraw(b_amount))
+        }
+
+        /// Swaps the input bucket for the other resource of the pair. Fails if fewer than `min_output` tokens would be
+        /// returned.
+        pub fn swap(&mut self, input_bucket: Bucket, min_output: Amount) -> Bucket {
+            let input_resource = input_bucket.resource_address();
+            let input_amount = input_bucket.amount();
+            let output_amount = self.quote(input_resource, input_amount);
+            assert!(output_amount.is_positive(), "Swap output amount is zero");
+            assert!(
+                output_amount >= min_output,
+                "Swap output {} is less than the minimum output {}",
+                output_amount,
+                min_output
+            );
+
+            let (input_vault, output_vault) = if input_resource == self.a_vault.resource_address() {
+                (&mut self.a_vault, &mut self.b_vault)
+            } else {
+                (&mut self.b_vault, &mut self.a_vault)
+            };
+            input_vault.deposit(input_bucket);
+            let output_bucket = output_vault.withdraw(output_amount);
+
+            emit_event("swap", [
+                ("input_resource", input_resource.to_string()),
+                ("input_amount", input_amount.to_string()),
+                ("output_resource", output_bucket.resource_address().to_string()),
+                ("output_amount", output_amount.to_string()),
+            ]);
+
+            output_bucket
+        }
+
+        /// Returns the amount of the other resource that would be received when swapping `input_amount` of
+        /// `input_resource`
+        pub fn quote(&self, input_resource: ResourceAddress, input_amount: Amount) -> Amount {
+            let (input_reserve, output_reserve) = if input_resource == self.a_vault.resource_address() {
+                (self.a_vault.balance(), self.b_vault.balance())
+            } else if input_resource == self.b_vault.resource_address() {
+                (self.b_vault.balance(), self.a_vault.balance())
+            } else {
+                panic!("Resource {} is not in the pool", input_resource);
+            };
+            assert!(input_amount.is_positive(), "Swap input amount must be positive");
+            assert!(
+                input_reserve.is_positive() && output_reserve.is_positive(),
+                "The pool has no liquidity"
+            );
+
+            let effective_input = to_i128(input_amount) * (FEE_DENOMINATOR - i128::from(self.fee)) / FEE_DENOMINATOR;
+            let output_reserve = to_i128(output_reserve);
+            to_amount(output_reserve * effective_input / (to_i128(input_reserve) + effective_input))
+        }
+
+        pub fn resources(&self) -> (ResourceAddress, ResourceAddress) {
+            (self.a_vault.resource_address(), self.b_vault.resource_address())
+        }
+
+        pub fn reserves(&self) -> (Amount, Amount) {
+            (self.a_vault.balance(), self.b_vault.balance())
+        }
+
+        pub fn lp_resource(&self) -> ResourceAddress {
+            self.lp_resource
+        }
+
+        pub fn lp_total_supply(&self) -> Amount {
+            ResourceManager::get(self.lp_resource).total_supply()
+        }
+
+        pub fn fee(&self) -> u16 {
+            self.fee
+        }
+    }
+}
+
+fn assert_fungible(resource: ResourceAddress) {
+    let resource_type = ResourceManager::get(resource).resource_type();
+    assert!(resource_type.is_fungible(), "Resource {} is not fungible", resource);
+}
+
+fn to_i128(amount: Amount) -> i128 {
+    i128::from(amount.value())
+}
+
+fn to_amount(value: i128) -> Amount {
+    Amount::new(i64::try_from(value).expect("Amount overflow"))
+}
+
+fn div_ceil(numerator: i128, denominator: i128) -> i128 {
+    (numerator + denominator - 1) / denominator
+}
+
+/// Integer square root (floor) using Newton's method
+fn isqrt(value: i128) -> i128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
@@ -0,0 +1,295 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! An auction of a lot of fungible or non-fungible tokens, paid for in a fungible resource.
+//!
+//! In an English auction, bidders place increasing bids that are held by the auction. A bidder that is outbid is
+//! refunded to their account immediately. Once the end epoch is reached, anyone may settle the auction, which sends the
+//! lot to the highest bidder and the winning bid to the seller.
+//!
+//! In a Dutch auction, the price falls linearly from the start price to the reserve price over the duration of the
+//! auction, and the first buyer to pay the current price receives the lot. If the lot is not sold by the end epoch, it
+//! is returned to the seller on settlement.
+
+use serde::{Deserialize, Serialize};
+use tari_template_lib::prelude::*;
+
+/// The address of the builtin account template. Proceeds and refunds are only deposited into accounts, so that a
+/// bidder cannot block the auction with a component that rejects deposits.
+const ACCOUNT_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([0; 32]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bid {
+    pub bidder: ComponentAddress,
+    pub amount: Amount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuctionKind {
+    English {
+        min_bid: Amount,
+        min_increment: Amount,
+        highest_bid: Option<Bid>,
+    },
+    Dutch {
+        start_price: Amount,
+        reserve_price: Amount,
+        start_epoch: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuctionStatus {
+    Open,
+    Sold { buyer: ComponentAddress, price: Amount },
+    Unsold,
+}
+
+#[template]
+mod auction_template {
+    use super::*;
+
+    pub struct Auction {
+        seller: ComponentAddress,
+        lot: Vault,
+        bids: Vault,
+        kind: AuctionKind,
+        end_epoch: u64,
+        status: AuctionStatus,
+    }
+
+    impl Auction {
+        /// Creates an English auction for the lot. Bids must be at least `min_bid`, and each bid must exceed the
+        /// previous bid by at least `min_increment`. The proceeds are deposited into the seller account.
+        pub fn new_english(
+            seller: ComponentAddress,
+            lot: Bucket,
+            bid_resource: ResourceAddress,
+            min_bid: Amount,
+            min_increment: Amount,
+            end_epoch: u64,
+        ) -> Component<Auction> {
+            assert!(min_bid.is_positive(), "Minimum bid must be positive");
+            assert!(!min_increment.is_negative(), "Minimum increment must not be negative");
+            Self::create(seller, lot, bid_resource, end_epoch, AuctionKind::English {
+                min_bid,
+                min_increment,
+                highest_bid: None,
+            })
+        }
+
+        /// Creates a Dutch auction for the lot. The price falls linearly from `start_price` at the current epoch to
+        /// `reserve_price` at the end epoch. The proceeds are deposited into the seller account.
+        pub fn new_dutch(
+            seller: ComponentAddress,
+            lot: Bucket,
+            bid_resource: ResourceAddress,
+            start_price: Amount,
+            reserve_price: Amount,
+            end_epoch: u64,
+        ) -> Component<Auction> {
+            assert!(reserve_price.is_positive(), "Reserve price must be positive");
+            assert!(
+                start_price >= reserve_price,
+                "Start price must not be less than the reserve price"
+            );
+            Self::create(seller, lot, bid_resource, end_epoch, AuctionKind::Dutch {
+                start_price,
+                reserve_price,
+                start_epoch: Consensus::current_epoch(),
+            })
+        }
+
+        fn create(
+            seller: ComponentAddress,
+            lot: Bucket,
+            bid_resource: ResourceAddress,
+            end_epoch: u64,
+            kind: AuctionKind,
+        ) -> Component<Auction> {
+            assert_is_account(seller);
+            assert!(lot.amount().is_positive(), "Lot must not be empty");
+            assert!(
+                matches!(
+                    ResourceManager::get(bid_resource).resource_type(),
+                    ResourceType::Fungible | ResourceType::Confidential
+                ),
+                "Bid resource {} is not fungible nor confidential",
+                bid_resource
+            );
+            assert!(
+                end_epoch > Consensus::current_epoch(),
+                "End epoch {} has already passed",
+                end_epoch
+            );
+
+            Component::new(Self {
+                seller,
+                lot: Vault::from_bucket(lot),
+                bids: Vault::new_empty(bid_resource),
+                kind,
+                end_epoch,
+                status: AuctionStatus::Open,
+            })
+            // Funds are only ever sent to the seller and bidder accounts
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        /// Places a bid in an English auction on behalf of the bidder account. The previous highest bidder is
+        /// refunded.
+        pub fn bid(&mut self, bidder: ComponentAddress, bid: Bucket) {
+            self.assert_open();
+            assert_is_account(bidder);
+            assert_eq!(
+                bid.resource_address(),
+                self.bids.resource_address(),
+                "Invalid bid resource"
+            );
+
+            let amount = bid.amount();
+            let AuctionKind::English {
+                min_bid,
+                min_increment,
+                highest_bid,
+            } = &mut self.kind
+            else {
+                panic!("Bids can only be placed in an English auction");
+            };
+            let required = match *highest_bid {
+                Some(highest_bid) => highest_bid.amount + (*min_increment).max(Amount(1)),
+                None => *min_bid,
+            };
+            assert!(
+                amount >= required,
+                "Bid {} is less than the required bid {}",
+                amount,
+                required
+            );
+
+            let previous_bid = highest_bid.replace(Bid { bidder, amount });
+            if let Some(previous_bid) = previous_bid {
+                let refund = self.bids.withdraw(previous_bid.amount);
+                deposit_into(previous_bid.bidder, refund);
+                emit_event("refund", [
+                    ("bidder", previous_bid.bidder.to_string()),
+                    ("amount", previous_bid.amount.to_string()),
+                ]);
+            }
+            self.bids.deposit(bid);
+
+            emit_event("bid", [("bidder", bidder.to_string()), ("amount", amount.to_string())]);
+        }
+
+        /// Buys the lot in a Dutch auction at the current price on behalf of the buyer account, returning any change
+        pub fn buy(&mut self, buyer: ComponentAddress, mut payment: Bucket) -> Bucket {
+            self.assert_open();
+            assert_is_account(buyer);
+            assert_eq!(
+                payment.resource_address(),
+                self.bids.resource_address(),
+                "Invalid payment resource"
+            );
+            let price = self.current_price();
+            assert!(
+                payment.amount() >= price,
+                "Payment {} is less than the current price {}",
+                payment.amount(),
+                price
+            );
+
+            deposit_into(self.seller, payment.take(price));
+            deposit_into(buyer, self.lot.withdraw_all());
+            self.status = AuctionStatus::Sold { buyer, price };
+            emit_event("buy", [("buyer", buyer.to_string()), ("price", price.to_string())]);
+
+            payment
+        }
+
+        /// Settles the auction once the end epoch is reached. The lot is sent to the highest bidder and the winning
+        /// bid to the seller, or the lot is returned to the seller if it was not sold.
+        pub fn settle(&mut self) {
+            assert_eq!(self.status, AuctionStatus::Open, "Auction has already been settled");
+            assert!(
+                Consensus::current_epoch() >= self.end_epoch,
+                "Auction is open until epoch {}",
+                self.end_epoch
+            );
+
+            let winning_bid = match self.kind {
+                AuctionKind::English { highest_bid, .. } => highest_bid,
+                AuctionKind::Dutch { .. } => None,
+            };
+            match winning_bid {
+                Some(Bid { bidder, amount }) => {
+                    deposit_into(bidder, self.lot.withdraw_all());
+                    deposit_into(self.seller, self.bids.withdraw_all());
+                    self.status = AuctionStatus::Sold {
+                        buyer: bidder,
+                        price: amount,
+                    };
+                    emit_event("settle", [("buyer", bidder.to_string()), ("price", amount.to_string())]);
+                },
+                None => {
+                    deposit_into(self.seller, self.lot.withdraw_all());
+                    self.status = AuctionStatus::Unsold;
+                    emit_event("settle", [("buyer", "none".to_string())]);
+                },
+            }
+        }
+
+        /// Returns the current price of a Dutch auction
+        pub fn current_price(&self) -> Amount {
+            let AuctionKind::Dutch {
+                start_price,
+                reserve_price,
+                start_epoch,
+            } = self.kind
+            else {
+                panic!("Only Dutch auctions have a current price");
+            };
+            let elapsed = Consensus::current_epoch().saturating_sub(start_epoch);
+            let duration = self.end_epoch - start_epoch;
+            let discount = (start_price - reserve_price).value() * elapsed.min(duration) as i64 / duration as i64;
+            start_price - Amount(discount)
+        }
+
+        pub fn highest_bid(&self) -> Option<Bid> {
+            match self.kind {
+                AuctionKind::English { highest_bid, .. } => highest_bid,
+                AuctionKind::Dutch { .. } => None,
+            }
+        }
+
+        pub fn status(&self) -> AuctionStatus {
+            self.status
+        }
+
+        pub fn end_epoch(&self) -> u64 {
+            self.end_epoch
+        }
+
+        fn assert_open(&self) {
+            assert_eq!(self.status, AuctionStatus::Open, "Auction is closed");
+            assert!(
+                Consensus::current_epoch() < self.end_epoch,
+                "Auction ended at epoch {}",
+                self.end_epoch
+            );
+        }
+    }
+}
+
+fn assert_is_account(address: ComponentAddress) {
+    assert_eq!(
+        ComponentManager::get(address).get_template_address(),
+        ACCOUNT_TEMPLATE_ADDRESS,
+        "Component {} is not an account",
+        address
+    );
+}
+
+fn deposit_into(account: ComponentAddress, bucket: Bucket) {
+    ComponentManager::get(account).invoke("deposit", args![bucket]);
+}
@@ -0,0 +1,194 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A conditional payment between a buyer and a seller, with an optional arbiter. Each party is identified by a badge
+//! issued by the escrow.
+//!
+//! The buyer deposits the agreed amount, which opens a dispute window of a number of epochs. The buyer may release the
+//! funds to the seller at any time, and the seller may refund the buyer at any time. If the buyer raises a dispute
+//! within the window, the arbiter decides who receives the funds. Otherwise, once the window has passed, the seller may
+//! claim the payment.
+
+use serde::{Deserialize, Serialize};
+use tari_template_lib::prelude::*;
+
+const BUYER_BADGE: &str = "buyer";
+const SELLER_BADGE: &str = "seller";
+const ARBITER_BADGE: &str = "arbiter";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscrowStatus {
+    AwaitingDeposit,
+    Funded { dispute_deadline_epoch: u64 },
+    Disputed,
+    Released,
+    Refunded,
+    Closed,
+}
+
+#[template]
+mod escrow_template {
+    use super::*;
+
+    pub struct Escrow {
+        badges: ResourceAddress,
+        has_arbiter: bool,
+        amount: Amount,
+        dispute_window_epochs: u64,
+        funds: Vault,
+        status: EscrowStatus,
+    }
+
+    impl Escrow {
+        /// Creates a new escrow for `amount` of `resource`. Returns the escrow and a bucket with the buyer, seller
+        /// and (optionally) arbiter badges, which should be distributed to each party.
+        pub fn new(
+            resource: ResourceAddress,
+            amount: Amount,
+            dispute_window_epochs: u64,
+            has_arbiter: bool,
+        ) -> (Component<Escrow>, Bucket) {
+            assert!(amount.is_positive(), "Escrow amount must be positive");
+
+            let mut badge_names = vec![BUYER_BADGE, SELLER_BADGE];
+            if has_arbiter {
+                badge_names.push(ARBITER_BADGE);
+            }
+            let badges = ResourceBuilder::non_fungible()
+                .with_token_symbol("ESCROW")
+                .initial_supply(badge_names.into_iter().map(NonFungibleId::from_string));
+
+            let component = Component::new(Self {
+                badges: badges.resource_address(),
+                has_arbiter,
+                amount,
+                dispute_window_epochs,
+                funds: Vault::new_empty(resource),
+                status: EscrowStatus::AwaitingDeposit,
+            })
+            // Authorization is enforced by badge proofs
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create();
+
+            (component, badges)
+        }
+
+        /// Deposits the escrowed funds and opens the dispute window. Requires the buyer badge.
+        pub fn deposit(&mut self, proof: Proof, funds: Bucket) {
+            self.assert_party(&proof, BUYER_BADGE);
+            assert_eq!(
+                self.status,
+                EscrowStatus::AwaitingDeposit,
+                "Escrow has already been funded"
+            );
+            assert_eq!(funds.amount(), self.amount, "Deposit must be exactly {}", self.amount);
+
+            self.funds.deposit(funds);
+            let dispute_deadline_epoch = Consensus::current_epoch() + self.dispute_window_epochs;
+            self.status = EscrowStatus::Funded { dispute_deadline_epoch };
+            emit_event("deposit", [(
+                "dispute_deadline_epoch",
+                dispute_deadline_epoch.to_string(),
+            )]);
+        }
+
+        /// Releases the funds to the seller. Requires the buyer badge.
+        pub fn release(&mut self, proof: Proof) {
+            self.assert_party(&proof, BUYER_BADGE);
+            self.assert_pending();
+            self.status = EscrowStatus::Released;
+            emit_event("release", [("amount", self.amount.to_string())]);
+        }
+
+        /// Refunds the funds to the buyer. Requires the seller badge.
+        pub fn refund(&mut self, proof: Proof) {
+            self.assert_party(&proof, SELLER_BADGE);
+            self.assert_pending();
+            self.status = EscrowStatus::Refunded;
+            emit_event("refund", [("amount", self.amount.to_string())]);
+        }
+
+        /// Raises a dispute before the dispute window closes. Requires the buyer badge.
+        pub fn dispute(&mut self, proof: Proof) {
+            self.assert_party(&proof, BUYER_BADGE);
+            assert!(self.has_arbiter, "Escrow has no arbiter to resolve a dispute");
+            match self.status {
+                EscrowStatus::Funded { dispute_deadline_epoch } => {
+                    assert!(
+                        Consensus::current_epoch() < dispute_deadline_epoch,
+                        "Dispute window closed at epoch {}",
+                        dispute_deadline_epoch
+                    );
+                },
+                status => panic!("Cannot dispute escrow with status {:?}", status),
+            }
+            self.status = EscrowStatus::Disputed;
+            emit_event("dispute", [("amount", self.amount.to_string())]);
+        }
+
+        /// Resolves a dispute in favour of the seller or the buyer. Requires the arbiter badge.
+        pub fn resolve(&mut self, proof: Proof, release_to_seller: bool) {
+            self.assert_party(&proof, ARBITER_BADGE);
+            assert_eq!(self.status, EscrowStatus::Disputed, "Escrow is not disputed");
+            self.status = if release_to_seller {
+                EscrowStatus::Released
+            } else {
+                EscrowStatus::Refunded
+            };
+            emit_event("resolve", [("release_to_seller", release_to_seller.to_string())]);
+        }
+
+        /// Withdraws the funds once released, or once the dispute window has passed without a dispute. Requires the
+        /// seller badge.
+        pub fn claim_payment(&mut self, proof: Proof) -> Bucket {
+            self.assert_party(&proof, SELLER_BADGE);
+            match self.status {
+                EscrowStatus::Released => {},
+                EscrowStatus::Funded { dispute_deadline_epoch } => {
+                    assert!(
+                        Consensus::current_epoch() >= dispute_deadline_epoch,
+                        "Dispute window is open until epoch {}",
+                        dispute_deadline_epoch
+                    );
+                },
+                status => panic!("Cannot claim payment from escrow with status {:?}", status),
+            }
+            self.status = EscrowStatus::Closed;
+            self.funds.withdraw_all()
+        }
+
+        /// Withdraws the funds once refunded. Requires the buyer badge.
+        pub fn claim_refund(&mut self, proof: Proof) -> Bucket {
+            self.assert_party(&proof, BUYER_BADGE);
+            assert_eq!(self.status, EscrowStatus::Refunded, "Escrow has not been refunded");
+            self.status = EscrowStatus::Closed;
+            self.funds.withdraw_all()
+        }
+
+        pub fn status(&self) -> EscrowStatus {
+            self.status
+        }
+
+        pub fn badge_resource(&self) -> ResourceAddress {
+            self.badges
+        }
+
+        fn assert_party(&self, proof: &Proof, badge: &str) {
+            proof.assert_resource(self.badges);
+            let _access = proof.authorize();
+            assert!(
+                proof.get_non_fungibles().contains(&NonFungibleId::from_string(badge)),
+                "Proof does not contain the {} badge",
+                badge
+            );
+        }
+
+        fn assert_pending(&self) {
+            assert!(
+                matches!(self.status, EscrowStatus::Funded { .. } | EscrowStatus::Disputed),
+                "Escrow is not funded"
+            );
+        }
+    }
+}
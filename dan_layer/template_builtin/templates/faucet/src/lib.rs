@@ -1,8 +1,31 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+//! A faucet that hands out test XTR. Claims are tracked per public key of the transaction signer, so that each key may
+//! claim at most `max_claim` once every `cooldown_epochs` epochs.
+
+use serde::{Deserialize, Serialize};
 use tari_template_lib::prelude::*;
 
+/// The default maximum amount of a single claim
+const DEFAULT_MAX_CLAIM: Amount = Amount::new(1_000_000_000);
+/// The default number of epochs that a key must wait between claims
+const DEFAULT_COOLDOWN_EPOCHS: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimRecord {
+    pub last_claim_epoch: u64,
+    pub total_claimed: Amount,
+}
+
+fn default_max_claim() -> Amount {
+    DEFAULT_MAX_CLAIM
+}
+
+fn default_cooldown_epochs() -> u64 {
+    DEFAULT_COOLDOWN_EPOCHS
+}
+
 #[template]
 mod template {
     use tari_template_lib::crypto::BalanceProofSignature;
@@ -11,24 +34,100 @@ mod template {
 
     pub struct XtrFaucet {
         vault: Vault,
+        /// The claims of each public key token. The bootstrapped faucet has no claims store, so it is created on the
+        /// first claim.
+        #[serde(default)]
+        claims: Option<KeyValueStore<NonFungibleAddress, ClaimRecord>>,
+        #[serde(default = "default_max_claim")]
+        max_claim: Amount,
+        #[serde(default = "default_cooldown_epochs")]
+        cooldown_epochs: u64,
     }
 
     impl XtrFaucet {
-        pub fn take(&self, amount: Amount) -> Bucket {
+        /// Creates a faucet that hands out the funds, at most `max_claim` per key every `cooldown_epochs` epochs
+        pub fn new(funds: Bucket, max_claim: Amount, cooldown_epochs: u64) -> Component<XtrFaucet> {
+            assert!(max_claim.is_positive(), "Maximum claim must be positive");
+            Component::new(Self {
+                vault: Vault::from_bucket(funds),
+                claims: Some(KeyValueStore::new()),
+                max_claim,
+                cooldown_epochs,
+            })
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        pub fn take(&mut self, amount: Amount) -> Bucket {
+            self.record_claim(amount);
             debug!("Withdrawing {} coins from faucet", amount);
             self.vault.withdraw(amount)
         }
 
         pub fn take_confidential(
-            &self,
+            &mut self,
             amount: Amount,
             output: ConfidentialOutputStatement,
             balance_proof: BalanceProofSignature,
         ) -> Bucket {
+            self.record_claim(amount);
             // Withdraws revealed funds into the given confidential output
             let proof = ConfidentialWithdrawProof::revealed_to_confidential(amount, output, balance_proof);
             debug!("Withdrawing {} coins from faucet into confidential output", amount);
             self.vault.withdraw_confidential(proof)
         }
+
+        pub fn get_claim(&self, public_key_token: NonFungibleAddress) -> Option<ClaimRecord> {
+            self.claims.as_ref().and_then(|claims| claims.get(&public_key_token))
+        }
+
+        pub fn max_claim(&self) -> Amount {
+            self.max_claim
+        }
+
+        pub fn cooldown_epochs(&self) -> u64 {
+            self.cooldown_epochs
+        }
+
+        /// Records a claim by the transaction signer, panicking if it exceeds the maximum claim or the signer is still
+        /// in their cooldown period
+        fn record_claim(&mut self, amount: Amount) {
+            assert!(
+                amount <= self.max_claim,
+                "Claim amount {} exceeds the maximum of {}",
+                amount,
+                self.max_claim
+            );
+            let claimant = NonFungibleAddress::from_public_key(CallerContext::transaction_signer_public_key());
+            let current_epoch = Consensus::current_epoch();
+            let claims = self.claims.get_or_insert_with(KeyValueStore::new);
+
+            let record = match claims.get(&claimant) {
+                Some(mut record) => {
+                    let next_claim_epoch = record.last_claim_epoch + self.cooldown_epochs;
+                    assert!(
+                        current_epoch >= next_claim_epoch,
+                        "{} may claim again from epoch {}",
+                        claimant,
+                        next_claim_epoch
+                    );
+                    record.last_claim_epoch = current_epoch;
+                    record.total_claimed += amount;
+                    record
+                },
+                None => ClaimRecord {
+                    last_claim_epoch: current_epoch,
+                    total_claimed: amount,
+                },
+            };
+            claims.insert(claimant.clone(), record);
+
+            emit_event("claim", [
+                ("claimant", claimant.to_string()),
+                ("amount", amount.to_string()),
+                ("epoch", current_epoch.to_string()),
+            ]);
+        }
     }
 }
@@ -0,0 +1,256 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! An m-of-n multisig account. Each signer holds a badge (non-fungible) issued by the account. Any signer may create
+//! a proposal, which is executed once it has been approved by at least `threshold` of the current signers.
+
+use serde::{Deserialize, Serialize};
+use tari_template_abi::rust::collections::{BTreeMap, BTreeSet};
+use tari_template_lib::{args::Arg, prelude::*};
+
+/// An action that is executed by the multisig account once a proposal has been approved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProposalAction {
+    /// Withdraw funds from the account and deposit them into another component (typically an account)
+    Transfer {
+        resource: ResourceAddress,
+        amount: Amount,
+        destination: ComponentAddress,
+    },
+    /// Call a method on a component. The return value of the call is returned from `execute`.
+    CallMethod {
+        component: ComponentAddress,
+        method: String,
+        args: Vec<Arg>,
+    },
+    /// Call a template function. The return value of the call is returned from `execute`.
+    CallFunction {
+        template: TemplateAddress,
+        function: String,
+        args: Vec<Arg>,
+    },
+    /// Rotate the signer set: mint `num_new_signers` new badges (returned from `execute` as an optional bucket),
+    /// revoke the given badges and set a new threshold
+    UpdateSigners {
+        num_new_signers: u32,
+        revoke: Vec<NonFungibleId>,
+        threshold: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Proposal {
+    pub action: ProposalAction,
+    pub approvals: BTreeSet<NonFungibleId>,
+    pub is_executed: bool,
+}
+
+#[template]
+mod multisig_template {
+    use super::*;
+
+    pub struct Multisig {
+        vaults: BTreeMap<ResourceAddress, Vault>,
+        signer_badge: ResourceAddress,
+        signers: BTreeSet<NonFungibleId>,
+        next_signer_id: u32,
+        threshold: u32,
+        proposals: BTreeMap<u64, Proposal>,
+        next_proposal_id: u64,
+    }
+
+    impl Multisig {
+        /// Creates a new multisig account with `num_signers` signers. Returns the account and a bucket containing a
+        /// badge for each signer, which should be distributed to the signers.
+        pub fn create(num_signers: u32, threshold: u32) -> (Component<Multisig>, Bucket) {
+            assert_valid_threshold(threshold, num_signers);
+
+            let address_alloc = CallerContext::allocate_component_address(None);
+            let badges = ResourceBuilder::non_fungible()
+                .with_token_symbol("MSIG")
+                .mintable(rule!(component(*address_alloc.address())))
+                .initial_supply((0..num_signers).map(NonFungibleId::from_u32));
+
+            let component = Component::new(Self {
+                vaults: BTreeMap::new(),
+                signer_badge: badges.resource_address(),
+                signers: (0..num_signers).map(NonFungibleId::from_u32).collect(),
+                next_signer_id: num_signers,
+                threshold,
+                proposals: BTreeMap::new(),
+                next_proposal_id: 0,
+            })
+            .with_address_allocation(address_alloc)
+            // Authorization is enforced by signer badge proofs
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create();
+
+            (component, badges)
+        }
+
+        pub fn deposit(&mut self, bucket: Bucket) {
+            emit_event("deposit", [
+                ("amount", bucket.amount().to_string()),
+                ("resource", bucket.resource_address().to_string()),
+            ]);
+            let resource_address = bucket.resource_address();
+            self.vaults
+                .entry(resource_address)
+                .or_insert_with(|| Vault::new_empty(resource_address))
+                .deposit(bucket);
+        }
+
+        pub fn balance(&self, resource: ResourceAddress) -> Amount {
+            self.vaults
+                .get(&resource)
+                .map(|v| v.balance())
+                .unwrap_or_else(Amount::zero)
+        }
+
+        /// Creates a new proposal. The proposer's approval is included.
+        pub fn propose(&mut self, proof: Proof, action: ProposalAction) -> u64 {
+            let approvals = self.authorized_signers(&proof);
+            assert!(!approvals.is_empty(), "Proof does not contain a signer badge");
+
+            let id = self.next_proposal_id;
+            self.next_proposal_id += 1;
+            self.proposals.insert(id, Proposal {
+                action,
+                approvals,
+                is_executed: false,
+            });
+            emit_event("propose", [("proposal_id", id.to_string())]);
+            id
+        }
+
+        pub fn approve(&mut self, proposal_id: u64, proof: Proof) {
+            let signers = self.authorized_signers(&proof);
+            assert!(!signers.is_empty(), "Proof does not contain a signer badge");
+
+            let proposal = self.get_proposal_mut(proposal_id);
+            assert!(
+                !proposal.is_executed,
+                "Proposal {} has already been executed",
+                proposal_id
+            );
+            proposal.approvals.extend(signers);
+            emit_event("approve", [("proposal_id", proposal_id.to_string())]);
+        }
+
+        /// Executes an approved proposal. Anyone may execute a proposal once it has been approved by the threshold
+        /// of signers.
+        pub fn execute(&mut self, proposal_id: u64) -> tari_bor::Value {
+            let num_approvals = self.num_approvals(proposal_id);
+            assert!(
+                num_approvals >= self.threshold,
+                "Proposal {} has {} of {} required approvals",
+                proposal_id,
+                num_approvals,
+                self.threshold
+            );
+
+            let proposal = self.get_proposal_mut(proposal_id);
+            assert!(
+                !proposal.is_executed,
+                "Proposal {} has already been executed",
+                proposal_id
+            );
+            proposal.is_executed = true;
+            let action = proposal.action.clone();
+            emit_event("execute", [("proposal_id", proposal_id.to_string())]);
+
+            match action {
+                ProposalAction::Transfer {
+                    resource,
+                    amount,
+                    destination,
+                } => {
+                    let bucket = self
+                        .vaults
+                        .get_mut(&resource)
+                        .unwrap_or_else(|| panic!("No vault for resource {}", resource))
+                        .withdraw(amount);
+                    ComponentManager::get(destination).invoke("deposit", args![bucket]);
+                    tari_bor::Value::Null
+                },
+                ProposalAction::CallMethod {
+                    component,
+                    method,
+                    args,
+                } => ComponentManager::get(component).call(method, args),
+                ProposalAction::CallFunction {
+                    template,
+                    function,
+                    args,
+                } => TemplateManager::get(template).call(function, args),
+                ProposalAction::UpdateSigners {
+                    num_new_signers,
+                    revoke,
+                    threshold,
+                } => {
+                    for id in &revoke {
+                        self.signers.remove(id);
+                    }
+                    let manager = ResourceManager::get(self.signer_badge);
+                    let mut badges = Vec::with_capacity(num_new_signers as usize);
+                    for _ in 0..num_new_signers {
+                        let id = NonFungibleId::from_u32(self.next_signer_id);
+                        self.next_signer_id += 1;
+                        badges.push(manager.mint_non_fungible(id.clone(), &Metadata::new(), &()));
+                        self.signers.insert(id);
+                    }
+                    assert_valid_threshold(threshold, self.signers.len() as u32);
+                    self.threshold = threshold;
+                    let badges = badges.into_iter().reduce(|a, b| a.join(b));
+                    tari_bor::to_value(&badges).expect("Failed to encode badges bucket")
+                },
+            }
+        }
+
+        /// Returns the number of approvals from current signers for the given proposal
+        pub fn num_approvals(&self, proposal_id: u64) -> u32 {
+            let proposal = self
+                .proposals
+                .get(&proposal_id)
+                .unwrap_or_else(|| panic!("Proposal {} not found", proposal_id));
+            proposal.approvals.intersection(&self.signers).count() as u32
+        }
+
+        pub fn get_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+            self.proposals.get(&proposal_id).cloned()
+        }
+
+        pub fn signer_badge(&self) -> ResourceAddress {
+            self.signer_badge
+        }
+
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        fn authorized_signers(&self, proof: &Proof) -> BTreeSet<NonFungibleId> {
+            proof.assert_resource(self.signer_badge);
+            let _access = proof.authorize();
+            proof
+                .get_non_fungibles()
+                .into_iter()
+                .filter(|id| self.signers.contains(id))
+                .collect()
+        }
+
+        fn get_proposal_mut(&mut self, proposal_id: u64) -> &mut Proposal {
+            self.proposals
+                .get_mut(&proposal_id)
+                .unwrap_or_else(|| panic!("Proposal {} not found", proposal_id))
+        }
+    }
+}
+
+fn assert_valid_threshold(threshold: u32, num_signers: u32) {
+    assert!(
+        threshold > 0 && threshold <= num_signers,
+        "Threshold must be between 1 and the number of signers ({})",
+        num_signers
+    );
+}
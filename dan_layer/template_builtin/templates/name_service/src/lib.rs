@@ -0,0 +1,216 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A registry of human-readable names that resolve to a component address or a public key.
+//!
+//! Registering a name requires paying the registration fee, and returns a badge NFT that represents ownership of the
+//! name. The badge holder may renew the name, or change the target that it resolves to. Names are transferred by
+//! transferring the badge. Once a registration expires, the name no longer resolves and may be registered again, in
+//! which case a new badge is issued and the previous badge no longer grants ownership of the name.
+
+use serde::{Deserialize, Serialize};
+use tari_template_abi::rust::collections::BTreeMap;
+use tari_template_lib::prelude::*;
+
+/// The maximum length of a name, in characters
+const MAX_NAME_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameTarget {
+    Component(ComponentAddress),
+    PublicKey(RistrettoPublicKeyBytes),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NameRecord {
+    pub target: NameTarget,
+    pub badge: NonFungibleId,
+    pub expiry_epoch: u64,
+}
+
+#[template]
+mod name_service_template {
+    use super::*;
+
+    pub struct NameService {
+        names: BTreeMap<String, NameRecord>,
+        badge_resource: ResourceAddress,
+        next_badge_id: u64,
+        fees: Vault,
+        registration_fee: Amount,
+        registration_period_epochs: u64,
+    }
+
+    impl NameService {
+        /// Creates a new name registry. Each registration or renewal costs `registration_fee` of `fee_resource` and
+        /// lasts for `registration_period_epochs`. Only the transaction signer may withdraw the collected fees.
+        pub fn new(
+            fee_resource: ResourceAddress,
+            registration_fee: Amount,
+            registration_period_epochs: u64,
+        ) -> Component<NameService> {
+            assert!(registration_fee.is_positive(), "Registration fee must be positive");
+            assert!(registration_period_epochs > 0, "Registration period must be positive");
+
+            let address_alloc = CallerContext::allocate_component_address(None);
+            let badge_resource = ResourceBuilder::non_fungible()
+                .with_token_symbol("NAME")
+                .mintable(rule!(component(*address_alloc.address())))
+                .build();
+
+            let owner_token = NonFungibleAddress::from_public_key(CallerContext::transaction_signer_public_key());
+            Component::new(Self {
+                names: BTreeMap::new(),
+                badge_resource,
+                next_badge_id: 0,
+                fees: Vault::new_empty(fee_resource),
+                registration_fee,
+                registration_period_epochs,
+            })
+            .with_address_allocation(address_alloc)
+            // Name ownership is enforced by badge proofs
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(
+                AccessRules::new()
+                    .add_method_rule("withdraw_fees", rule!(non_fungible(owner_token)))
+                    .default(rule!(allow_all)),
+            )
+            .create()
+        }
+
+        /// Registers a name that is not currently registered, returning the badge that represents ownership of the
+        /// name
+        pub fn register(&mut self, name: String, target: NameTarget, payment: Bucket) -> Bucket {
+            assert_valid_name(&name);
+            let current_epoch = Consensus::current_epoch();
+            if let Some(record) = self.names.get(&name) {
+                assert!(
+                    record.expiry_epoch <= current_epoch,
+                    "Name '{}' is registered until epoch {}",
+                    name,
+                    record.expiry_epoch
+                );
+            }
+            self.collect_fee(payment);
+
+            let badge = NonFungibleId::from_u64(self.next_badge_id);
+            self.next_badge_id += 1;
+            let expiry_epoch = current_epoch + self.registration_period_epochs;
+            self.names.insert(name.clone(), NameRecord {
+                target,
+                badge: badge.clone(),
+                expiry_epoch,
+            });
+
+            emit_event("register", [
+                ("name", name.clone()),
+                ("badge", badge.to_string()),
+                ("expiry_epoch", expiry_epoch.to_string()),
+            ]);
+
+            let mut metadata = Metadata::new();
+            metadata.insert("name", name);
+            ResourceManager::get(self.badge_resource).mint_non_fungible(badge, &metadata, &{})
+        }
+
+        /// Extends the registration of the name by one registration period. Requires the badge of the name.
+        pub fn renew(&mut self, proof: Proof, name: String, payment: Bucket) {
+            self.assert_owner(&proof, &name);
+            self.collect_fee(payment);
+
+            let current_epoch = Consensus::current_epoch();
+            let record = self.names.get_mut(&name).unwrap();
+            record.expiry_epoch = record.expiry_epoch.max(current_epoch) + self.registration_period_epochs;
+
+            emit_event("renew", [
+                ("name", name),
+                ("expiry_epoch", record.expiry_epoch.to_string()),
+            ]);
+        }
+
+        /// Changes the target that the name resolves to. Requires the badge of the name.
+        pub fn set_target(&mut self, proof: Proof, name: String, target: NameTarget) {
+            self.assert_owner(&proof, &name);
+            let record = self.names.get_mut(&name).unwrap();
+            record.target = target;
+
+            emit_event("set_target", [("name", name)]);
+        }
+
+        /// Returns the target of the name, or None if the name is not registered or has expired
+        pub fn resolve(&self, name: String) -> Option<NameTarget> {
+            self.names
+                .get(&name)
+                .filter(|record| record.expiry_epoch > Consensus::current_epoch())
+                .map(|record| record.target)
+        }
+
+        /// Returns the registration record of the name, including expired registrations
+        pub fn get_record(&self, name: String) -> Option<NameRecord> {
+            self.names.get(&name).cloned()
+        }
+
+        pub fn withdraw_fees(&mut self, amount: Amount) -> Bucket {
+            self.fees.withdraw(amount)
+        }
+
+        pub fn badge_resource(&self) -> ResourceAddress {
+            self.badge_resource
+        }
+
+        pub fn registration_fee(&self) -> Amount {
+            self.registration_fee
+        }
+
+        pub fn registration_period_epochs(&self) -> u64 {
+            self.registration_period_epochs
+        }
+
+        fn collect_fee(&mut self, payment: Bucket) {
+            assert_eq!(
+                payment.resource_address(),
+                self.fees.resource_address(),
+                "Invalid payment resource"
+            );
+            assert_eq!(
+                payment.amount(),
+                self.registration_fee,
+                "Payment does not match the registration fee"
+            );
+            self.fees.deposit(payment);
+        }
+
+        fn assert_owner(&self, proof: &Proof, name: &str) {
+            let record = self
+                .names
+                .get(name)
+                .unwrap_or_else(|| panic!("Name '{}' is not registered", name));
+            proof.assert_resource(self.badge_resource);
+            let _access = proof.authorize();
+            assert!(
+                proof.get_non_fungibles().contains(&record.badge),
+                "Proof does not contain the badge of name '{}'",
+                name
+            );
+        }
+    }
+}
+
+fn assert_valid_name(name: &str) {
+    assert!(
+        !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+        "Name must be between 1 and {} characters",
+        MAX_NAME_LENGTH
+    );
+    assert!(
+        name.bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-'),
+        "Name '{}' may only contain lowercase letters, digits and hyphens",
+        name
+    );
+    assert!(
+        !name.starts_with('-') && !name.ends_with('-'),
+        "Name '{}' must not start or end with a hyphen",
+        name
+    );
+}
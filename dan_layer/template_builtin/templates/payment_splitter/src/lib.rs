@@ -0,0 +1,160 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Splits incoming payments of a resource among a fixed set of beneficiary accounts, in proportion to their weights.
+//!
+//! Anyone may deposit funds into the splitter, e.g. as royalties or team payouts. Deposited funds are held until
+//! `distribute` is called, which allocates the undistributed balance to each beneficiary's claim vault. Any remainder
+//! that cannot be evenly divided is carried over to the next distribution. Claimed funds are always deposited into the
+//! beneficiary account, so anyone may trigger a claim on behalf of a beneficiary.
+
+use serde::{Deserialize, Serialize};
+use tari_template_lib::prelude::*;
+
+/// The address of the builtin account template. Beneficiaries must be accounts, so that a beneficiary cannot block
+/// claims with a component that rejects deposits.
+const ACCOUNT_TEMPLATE_ADDRESS: TemplateAddress = TemplateAddress::from_array([0; 32]);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Beneficiary {
+    pub account: ComponentAddress,
+    pub weight: u32,
+    pub claims: Vault,
+}
+
+#[template]
+mod payment_splitter_template {
+    use super::*;
+
+    pub struct PaymentSplitter {
+        undistributed: Vault,
+        beneficiaries: Vec<Beneficiary>,
+        total_weight: u64,
+    }
+
+    impl PaymentSplitter {
+        /// Creates a payment splitter for `resource` between the beneficiary accounts, each with a relative weight
+        pub fn new(
+            resource: ResourceAddress,
+            beneficiaries: Vec<(ComponentAddress, u32)>,
+        ) -> Component<PaymentSplitter> {
+            assert!(
+                matches!(
+                    ResourceManager::get(resource).resource_type(),
+                    ResourceType::Fungible | ResourceType::Confidential
+                ),
+                "Resource {} is not fungible nor confidential",
+                resource
+            );
+            assert!(!beneficiaries.is_empty(), "At least one beneficiary is required");
+
+            let mut total_weight = 0u64;
+            let mut splitter_beneficiaries = Vec::<Beneficiary>::with_capacity(beneficiaries.len());
+            for (account, weight) in beneficiaries {
+                assert!(weight > 0, "Weight of beneficiary {} must be positive", account);
+                assert!(
+                    splitter_beneficiaries.iter().all(|b| b.account != account),
+                    "Beneficiary {} is listed more than once",
+                    account
+                );
+                assert_is_account(account);
+                total_weight += u64::from(weight);
+                splitter_beneficiaries.push(Beneficiary {
+                    account,
+                    weight,
+                    claims: Vault::new_empty(resource),
+                });
+            }
+
+            Component::new(Self {
+                undistributed: Vault::new_empty(resource),
+                beneficiaries: splitter_beneficiaries,
+                total_weight,
+            })
+            // Funds are only ever sent to the beneficiary accounts
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create()
+        }
+
+        /// Deposits funds to be split among the beneficiaries on the next distribution
+        pub fn deposit(&mut self, funds: Bucket) {
+            assert_eq!(
+                funds.resource_address(),
+                self.undistributed.resource_address(),
+                "Invalid deposit resource"
+            );
+            funds.assert_contains_no_confidential_funds();
+            emit_event("deposit", [("amount", funds.amount().to_string())]);
+            self.undistributed.deposit(funds);
+        }
+
+        /// Allocates the undistributed balance to the claim vault of each beneficiary in proportion to their weight
+        pub fn distribute(&mut self) {
+            let balance = i128::from(self.undistributed.balance().value());
+            let total_weight = i128::from(self.total_weight);
+            let mut distributed = Amount::zero();
+            for beneficiary in &mut self.beneficiaries {
+                let share = Amount::new((balance * i128::from(beneficiary.weight) / total_weight) as i64);
+                if share.is_positive() {
+                    beneficiary.claims.deposit(self.undistributed.withdraw(share));
+                    distributed += share;
+                }
+            }
+            assert!(distributed.is_positive(), "There are no funds to distribute");
+
+            emit_event("distribute", [
+                ("amount", distributed.to_string()),
+                ("remainder", self.undistributed.balance().to_string()),
+            ]);
+        }
+
+        /// Deposits all funds allocated to the beneficiary into their account
+        pub fn claim(&mut self, beneficiary: ComponentAddress) {
+            let claims = &mut self.get_beneficiary_mut(beneficiary).claims;
+            let amount = claims.balance();
+            assert!(amount.is_positive(), "Beneficiary {} has nothing to claim", beneficiary);
+            let funds = claims.withdraw(amount);
+            ComponentManager::get(beneficiary).invoke("deposit", args![funds]);
+
+            emit_event("claim", [
+                ("beneficiary", beneficiary.to_string()),
+                ("amount", amount.to_string()),
+            ]);
+        }
+
+        /// Returns the amount allocated to the beneficiary that has not yet been claimed
+        pub fn claimable(&self, beneficiary: ComponentAddress) -> Amount {
+            self.beneficiaries
+                .iter()
+                .find(|b| b.account == beneficiary)
+                .map(|b| b.claims.balance())
+                .unwrap_or_else(|| panic!("{} is not a beneficiary", beneficiary))
+        }
+
+        pub fn undistributed_balance(&self) -> Amount {
+            self.undistributed.balance()
+        }
+
+        /// Returns each beneficiary account with its weight
+        pub fn beneficiaries(&self) -> Vec<(ComponentAddress, u32)> {
+            self.beneficiaries.iter().map(|b| (b.account, b.weight)).collect()
+        }
+
+        fn get_beneficiary_mut(&mut self, account: ComponentAddress) -> &mut Beneficiary {
+            self.beneficiaries
+                .iter_mut()
+                .find(|b| b.account == account)
+                .unwrap_or_else(|| panic!("{} is not a beneficiary", account))
+        }
+    }
+}
+
+fn assert_is_account(address: ComponentAddress) {
+    assert_eq!(
+        ComponentManager::get(address).get_template_address(),
+        ACCOUNT_TEMPLATE_ADDRESS,
+        "Component {} is not an account",
+        address
+    );
+}
@@ -0,0 +1,330 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A staking pool in which stakers lock a fungible resource and earn rewards in another (or the same) resource.
+//!
+//! Rewards are funded into a reward vault and emitted at a fixed amount per epoch, shared between the stake positions
+//! in proportion to their stake. Emission stops when the funded rewards run out, and no rewards are emitted while
+//! nothing is staked. Each stake position is represented by a position badge, which is required to claim rewards and
+//! is burnt when unstaking once the lock period has passed. The holder of the admin badge may change the emission,
+//! slash a position or unlock it before the end of its lock period.
+
+use serde::{Deserialize, Serialize};
+use tari_template_abi::rust::collections::BTreeMap;
+use tari_template_lib::prelude::*;
+
+const ADMIN_BADGE: &str = "admin";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakePosition {
+    pub amount: Amount,
+    pub rewards: Amount,
+    pub unlock_epoch: u64,
+}
+
+#[template]
+mod staking_template {
+    use super::*;
+
+    pub struct StakingPool {
+        stake_vault: Vault,
+        reward_vault: Vault,
+        admin_badge: ResourceAddress,
+        position_badge: ResourceAddress,
+        positions: BTreeMap<u64, StakePosition>,
+        next_position_id: u64,
+        total_staked: Amount,
+        /// Rewards that have been emitted to positions but not yet claimed
+        allocated_rewards: Amount,
+        emission_per_epoch: Amount,
+        lock_epochs: u64,
+        last_update_epoch: u64,
+    }
+
+    impl StakingPool {
+        /// Creates a new staking pool. Stakes are locked for `lock_epochs` and `emission_per_epoch` rewards are
+        /// emitted each epoch. Returns the pool and the admin badge.
+        pub fn new(
+            stake_resource: ResourceAddress,
+            reward_resource: ResourceAddress,
+            emission_per_epoch: Amount,
+            lock_epochs: u64,
+        ) -> (Component<StakingPool>, Bucket) {
+            assert_fungible(stake_resource);
+            assert_fungible(reward_resource);
+            assert!(!emission_per_epoch.is_negative(), "Emission must not be negative");
+
+            let admin_badge = ResourceBuilder::non_fungible()
+                .with_token_symbol("STAKE_ADMIN")
+                .initial_supply([NonFungibleId::from_string(ADMIN_BADGE)]);
+
+            let address_alloc = CallerContext::allocate_component_address(None);
+            let pool_rule = rule!(component(*address_alloc.address()));
+            let position_badge = ResourceBuilder::non_fungible()
+                .with_token_symbol("STAKE")
+                .mintable(pool_rule.clone())
+                .burnable(pool_rule)
+                .build();
+
+            let component = Component::new(Self {
+                stake_vault: Vault::new_empty(stake_resource),
+                reward_vault: Vault::new_empty(reward_resource),
+                admin_badge: admin_badge.resource_address(),
+                position_badge,
+                positions: BTreeMap::new(),
+                next_position_id: 0,
+                total_staked: Amount::zero(),
+                allocated_rewards: Amount::zero(),
+                emission_per_epoch,
+                lock_epochs,
+                last_update_epoch: Consensus::current_epoch(),
+            })
+            .with_address_allocation(address_alloc)
+            // Authorization is enforced by badge proofs
+            .with_owner_rule(OwnerRule::None)
+            .with_access_rules(AccessRules::allow_all())
+            .create();
+
+            (component, admin_badge)
+        }
+
+        /// Adds rewards to be emitted to stakers
+        pub fn fund(&mut self, rewards: Bucket) {
+            assert_eq!(
+                rewards.resource_address(),
+                self.reward_vault.resource_address(),
+                "Invalid reward resource"
+            );
+            rewards.assert_contains_no_confidential_funds();
+            // Rewards for past epochs are emitted from the previous balance
+            self.update_rewards();
+            emit_event("fund", [("amount", rewards.amount().to_string())]);
+            self.reward_vault.deposit(rewards);
+        }
+
+        /// Stakes the tokens in a new position, returning the position badge
+        pub fn stake(&mut self, tokens: Bucket) -> Bucket {
+            assert_eq!(
+                tokens.resource_address(),
+                self.stake_vault.resource_address(),
+                "Invalid stake resource"
+            );
+            tokens.assert_contains_no_confidential_funds();
+            let amount = tokens.amount();
+            assert!(amount.is_positive(), "Stake amount must be positive");
+            self.update_rewards();
+
+            let position_id = self.next_position_id;
+            self.next_position_id += 1;
+            let unlock_epoch = Consensus::current_epoch() + self.lock_epochs;
+            self.positions.insert(position_id, StakePosition {
+                amount,
+                rewards: Amount::zero(),
+                unlock_epoch,
+            });
+            self.total_staked += amount;
+            self.stake_vault.deposit(tokens);
+
+            emit_event("stake", [
+                ("position", position_id.to_string()),
+                ("amount", amount.to_string()),
+                ("unlock_epoch", unlock_epoch.to_string()),
+            ]);
+
+            ResourceManager::get(self.position_badge).mint_non_fungible(
+                NonFungibleId::from_u64(position_id),
+                &Metadata::new(),
+                &{},
+            )
+        }
+
+        /// Withdraws the rewards of all positions in the proof
+        pub fn claim_rewards(&mut self, proof: Proof) -> Bucket {
+            let position_ids = self.get_position_ids(&proof);
+            self.update_rewards();
+
+            let mut total = Amount::zero();
+            for position_id in position_ids {
+                let position = self.get_position_mut(position_id);
+                total += position.rewards;
+                position.rewards = Amount::zero();
+            }
+            assert!(total.is_positive(), "There are no rewards to claim");
+            self.allocated_rewards -= total;
+
+            emit_event("claim_rewards", [("amount", total.to_string())]);
+            self.reward_vault.withdraw(total)
+        }
+
+        /// Burns the position badges and returns the staked tokens. The lock period of each position must have passed
+        /// and its rewards must have been claimed.
+        pub fn unstake(&mut self, badges: Bucket) -> Bucket {
+            assert_eq!(
+                badges.resource_address(),
+                self.position_badge,
+                "Invalid position badge resource"
+            );
+            self.update_rewards();
+
+            let current_epoch = Consensus::current_epoch();
+            let mut total = Amount::zero();
+            for id in badges.get_non_fungible_ids() {
+                let position_id = to_position_id(&id);
+                let position = self.get_position_mut(position_id);
+                assert!(
+                    current_epoch >= position.unlock_epoch,
+                    "Position {} is locked until epoch {}",
+                    position_id,
+                    position.unlock_epoch
+                );
+                assert!(
+                    position.rewards.is_zero(),
+                    "Position {} has unclaimed rewards",
+                    position_id
+                );
+                total += position.amount;
+                self.positions.remove(&position_id);
+            }
+            assert!(total.is_positive(), "There is nothing to unstake");
+            badges.burn();
+            self.total_staked -= total;
+
+            emit_event("unstake", [("amount", total.to_string())]);
+            self.stake_vault.withdraw(total)
+        }
+
+        /// Slashes `amount` from the position's stake, returning the slashed tokens. Requires the admin badge.
+        pub fn slash(&mut self, proof: Proof, position_id: u64, amount: Amount) -> Bucket {
+            self.assert_admin(&proof);
+            assert!(amount.is_positive(), "Slash amount must be positive");
+            self.update_rewards();
+
+            let position = self.get_position_mut(position_id);
+            assert!(
+                amount <= position.amount,
+                "Slash amount {} exceeds the stake {} of position {}",
+                amount,
+                position.amount,
+                position_id
+            );
+            position.amount -= amount;
+            self.total_staked -= amount;
+
+            emit_event("slash", [
+                ("position", position_id.to_string()),
+                ("amount", amount.to_string()),
+            ]);
+            self.stake_vault.withdraw(amount)
+        }
+
+        /// Unlocks the position before the end of its lock period. Requires the admin badge.
+        pub fn unlock(&mut self, proof: Proof, position_id: u64) {
+            self.assert_admin(&proof);
+            let current_epoch = Consensus::current_epoch();
+            let position = self.get_position_mut(position_id);
+            position.unlock_epoch = position.unlock_epoch.min(current_epoch);
+
+            emit_event("unlock", [("position", position_id.to_string())]);
+        }
+
+        /// Sets the amount of rewards emitted per epoch. Requires the admin badge.
+        pub fn set_emission(&mut self, proof: Proof, emission_per_epoch: Amount) {
+            self.assert_admin(&proof);
+            assert!(!emission_per_epoch.is_negative(), "Emission must not be negative");
+            // Rewards up to now are emitted at the previous rate
+            self.update_rewards();
+            self.emission_per_epoch = emission_per_epoch;
+
+            emit_event("set_emission", [("emission_per_epoch", emission_per_epoch.to_string())]);
+        }
+
+        /// Returns the position, including the rewards emitted to it up to the last update of the pool
+        pub fn get_position(&self, position_id: u64) -> Option<StakePosition> {
+            self.positions.get(&position_id).copied()
+        }
+
+        pub fn total_staked(&self) -> Amount {
+            self.total_staked
+        }
+
+        /// Returns the funded rewards that have not yet been emitted
+        pub fn remaining_rewards(&self) -> Amount {
+            self.reward_vault.balance() - self.allocated_rewards
+        }
+
+        pub fn emission_per_epoch(&self) -> Amount {
+            self.emission_per_epoch
+        }
+
+        pub fn position_badge(&self) -> ResourceAddress {
+            self.position_badge
+        }
+
+        pub fn admin_badge(&self) -> ResourceAddress {
+            self.admin_badge
+        }
+
+        /// Emits the rewards for the epochs since the last update to the positions, in proportion to their stake
+        fn update_rewards(&mut self) {
+            let current_epoch = Consensus::current_epoch();
+            if current_epoch <= self.last_update_epoch {
+                return;
+            }
+            let elapsed = current_epoch - self.last_update_epoch;
+            self.last_update_epoch = current_epoch;
+            if self.total_staked.is_zero() {
+                return;
+            }
+
+            let emission = i128::from(self.emission_per_epoch.value()) * i128::from(elapsed);
+            let reward = emission.min(i128::from(self.remaining_rewards().value()));
+            let total_staked = i128::from(self.total_staked.value());
+            for position in self.positions.values_mut() {
+                let share = Amount::new((reward * i128::from(position.amount.value()) / total_staked) as i64);
+                position.rewards += share;
+                self.allocated_rewards += share;
+            }
+        }
+
+        fn get_position_ids(&self, proof: &Proof) -> Vec<u64> {
+            proof.assert_resource(self.position_badge);
+            let _access = proof.authorize();
+            proof.get_non_fungibles().iter().map(to_position_id).collect()
+        }
+
+        fn get_position_mut(&mut self, position_id: u64) -> &mut StakePosition {
+            self.positions
+                .get_mut(&position_id)
+                .unwrap_or_else(|| panic!("Position {} does not exist", position_id))
+        }
+
+        fn assert_admin(&self, proof: &Proof) {
+            proof.assert_resource(self.admin_badge);
+            let _access = proof.authorize();
+            assert!(
+                proof
+                    .get_non_fungibles()
+                    .contains(&NonFungibleId::from_string(ADMIN_BADGE)),
+                "Proof does not contain the admin badge"
+            );
+        }
+    }
+}
+
+fn assert_fungible(resource: ResourceAddress) {
+    assert!(
+        matches!(
+            ResourceManager::get(resource).resource_type(),
+            ResourceType::Fungible | ResourceType::Confidential
+        ),
+        "Resource {} is not fungible nor confidential",
+        resource
+    );
+}
+
+fn to_position_id(id: &NonFungibleId) -> u64 {
+    match id {
+        NonFungibleId::Uint64(position_id) => *position_id,
+        _ => panic!("Invalid position badge {}", id),
+    }
+}
@@ -23,9 +23,12 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
-use tari_template_abi::rust::{
-    fmt::{Display, Formatter},
-    str::FromStr,
+use tari_template_abi::{
+    rust::{
+        fmt::{Display, Formatter},
+        str::FromStr,
+    },
+    FunctionDef,
 };
 #[cfg(feature = "ts")]
 use ts_rs::TS;
@@ -33,6 +36,7 @@ use ts_rs::TS;
 use crate::{
     args::Arg,
     auth::{AuthHook, OwnerRule, ResourceAccessRules},
+    component::FeeSponsor,
     crypto::{PedersonCommitmentBytes, RistrettoPublicKeyBytes},
     models::{
         AddressAllocation,
@@ -40,6 +44,7 @@ use crate::{
         BucketId,
         ComponentAddress,
         ConfidentialWithdrawProof,
+        KeyValueStoreId,
         Metadata,
         NonFungibleAddress,
         NonFungibleId,
@@ -49,7 +54,7 @@ use crate::{
         VaultRef,
     },
     prelude::{ComponentAccessRules, ConfidentialOutputStatement, TemplateAddress},
-    resource::ResourceType,
+    resource::{ResourceType, RoyaltyConfig},
     template::BuiltinTemplate,
 };
 
@@ -60,6 +65,9 @@ use crate::{
 pub struct EmitLogArg {
     pub message: String,
     pub level: LogLevel,
+    /// Structured key-value fields of the log entry
+    #[serde(default)]
+    pub fields: Metadata,
 }
 
 /// All the possible log levels
@@ -126,7 +134,11 @@ pub enum ComponentAction {
     GetState,
     SetState,
     SetAccessRules,
+    SetOwnerRule,
     GetTemplateAddress,
+    Destroy,
+    Migrate,
+    GetVaultBalance,
 }
 
 /// Encapsulates all the ways that a component can be referenced
@@ -161,6 +173,10 @@ pub struct CreateComponentArg {
     pub owner_rule: OwnerRule,
     pub access_rules: ComponentAccessRules,
     pub address_allocation: Option<AddressAllocation<ComponentAddress>>,
+    #[serde(default)]
+    pub non_reentrant: bool,
+    #[serde(default)]
+    pub fee_sponsor: Option<FeeSponsor>,
 }
 
 // -------------------------------- Events -------------------------------- //
@@ -169,7 +185,14 @@ pub struct CreateComponentArg {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EmitEventArg {
     pub topic: String,
+    /// String key fields of the event that indexers can search by
     pub payload: Metadata,
+    /// The typed payload of the event
+    #[serde(default)]
+    pub data: Option<tari_bor::Value>,
+    /// The names of the fields of the typed payload that the engine should add to the event's search keys
+    #[serde(default)]
+    pub indexed_fields: Vec<String>,
 }
 
 // -------------------------------- Resource -------------------------------- //
@@ -224,6 +247,10 @@ pub enum ResourceAction {
     GetResourceType,
     GetNonFungible,
     UpdateAccessRules,
+    UpdateResourceMetadata,
+    Freeze,
+    Unfreeze,
+    PatchNonFungibleData,
 }
 
 /// All the possible minting operation types
@@ -260,6 +287,14 @@ pub struct CreateResourceArg {
     pub mint_arg: Option<MintArg>,
     pub view_key: Option<RistrettoPublicKeyBytes>,
     pub authorize_hook: Option<AuthHook>,
+    #[serde(default)]
+    pub royalty: Option<RoyaltyConfig>,
+    #[serde(default)]
+    pub is_soulbound: bool,
+    #[serde(default)]
+    pub max_supply: Option<Amount>,
+    #[serde(default)]
+    pub address_allocation: Option<AddressAllocation<ResourceAddress>>,
 }
 
 /// A resource minting operation argument
@@ -281,6 +316,20 @@ pub struct ResourceUpdateNonFungibleDataArg {
     pub data: tari_bor::Value,
 }
 
+/// A non-fungible mutable data patch operation argument. The fields of `patch` are merged into the existing mutable
+/// data of the token, leaving fields that are not in the patch unchanged.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourcePatchNonFungibleDataArg {
+    pub id: NonFungibleId,
+    pub patch: tari_bor::Value,
+}
+
+/// A resource metadata update operation argument
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResourceUpdateMetadataArg {
+    pub metadata: Metadata,
+}
+
 /// A convenience enum that allows to specify resource types
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ResourceDiscriminator {
@@ -323,6 +372,8 @@ pub enum VaultAction {
     GetLockedBalance,
     GetResourceAddress,
     GetNonFungibleIds,
+    GetNonFungibleIdsPaged,
+    GetNonFungibleCount,
     GetCommitmentCount,
     ConfidentialReveal,
     PayFee,
@@ -342,6 +393,8 @@ impl VaultAction {
                 GetLockedBalance |
                 GetResourceAddress |
                 GetNonFungibleIds |
+                GetNonFungibleIdsPaged |
+                GetNonFungibleCount |
                 GetCommitmentCount |
                 GetNonFungibles
         )
@@ -422,6 +475,7 @@ pub enum BucketAction {
     GetResourceType,
     GetAmount,
     Take,
+    TakeNonFungibles,
     TakeConfidential,
     Join,
     RevealConfidential,
@@ -448,6 +502,7 @@ pub enum WorkspaceAction {
     ListBuckets,
     DropAllProofs,
     AssertBucketContains,
+    CreateProofFromBucket,
 }
 
 /// A workspace operation argument
@@ -474,6 +529,56 @@ pub enum NonFungibleAction {
     GetMutableData,
 }
 
+// -------------------------------- KeyValueStore -------------------------------- //
+
+/// A key-value store operation argument
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyValueStoreInvokeArg {
+    pub store_ref: KeyValueStoreRef,
+    pub action: KeyValueStoreAction,
+    pub args: Vec<Vec<u8>>,
+}
+
+/// The possible actions that can be performed on key-value stores
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum KeyValueStoreAction {
+    Create,
+    Get,
+    Insert,
+    Remove,
+}
+
+impl KeyValueStoreAction {
+    pub fn requires_write_access(&self) -> bool {
+        matches!(self, Self::Insert | Self::Remove)
+    }
+}
+
+/// Encapsulates all the ways that a key-value store can be referenced
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum KeyValueStoreRef {
+    KeyValueStore,
+    Ref(KeyValueStoreId),
+}
+
+impl KeyValueStoreRef {
+    pub fn as_store_id(&self) -> Option<KeyValueStoreId> {
+        match self {
+            KeyValueStoreRef::KeyValueStore => None,
+            KeyValueStoreRef::Ref(id) => Some(*id),
+        }
+    }
+}
+
+impl Display for KeyValueStoreRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyValueStoreRef::KeyValueStore => write!(f, "KeyValueStore"),
+            KeyValueStoreRef::Ref(id) => write!(f, "Ref({})", id),
+        }
+    }
+}
+
 // -------------------------------- Consensus -------------------------------- //
 
 /// A consensus operation argument
@@ -486,6 +591,8 @@ pub struct ConsensusInvokeArg {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ConsensusAction {
     GetCurrentEpoch,
+    GetCurrentTimestamp,
+    GetRandomBeacon,
 }
 
 // -------------------------------- GenerateRandom -------------------------------- //
@@ -517,6 +624,9 @@ pub enum CallerContextAction {
     GetCallerPublicKey,
     GetComponentAddress,
     AllocateNewComponentAddress,
+    AllocateReservedComponentAddress,
+    AllocateNamedComponentAddress,
+    AllocateNamedResourceAddress,
 }
 
 // -------------------------------- CallInvoke -------------------------------- //
@@ -535,6 +645,8 @@ pub enum CallAction {
     CallFunction,
     /// Call to a component's method
     CallMethod,
+    /// Call to a component's method through an interface. The engine checks the method signature before calling it.
+    CallInterfaceMethod,
 }
 
 /// A template's function call operation argument
@@ -553,6 +665,15 @@ pub struct CallMethodArg {
     pub args: Vec<Arg>,
 }
 
+/// A component's method call through an interface operation argument
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CallInterfaceMethodArg {
+    pub component_address: ComponentAddress,
+    /// The signature of the method as declared by the interface
+    pub signature: FunctionDef,
+    pub args: Vec<Arg>,
+}
+
 // -------------------------------- ProofInvoke -------------------------------- //
 
 /// A proof-related operation argument
@@ -605,6 +726,7 @@ pub enum ProofAction {
     Authorize,
     DropAuthorize,
     Drop,
+    Combine,
 }
 
 /// An argument to represent a proof of a vault's fungible amount
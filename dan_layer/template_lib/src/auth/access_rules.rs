@@ -2,7 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use serde::{Deserialize, Serialize};
-use tari_template_abi::rust::collections::BTreeMap;
+use tari_template_abi::rust::{boxed::Box, collections::BTreeMap, ops::Not};
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
@@ -37,6 +37,29 @@ impl AccessRule {
             (Self::Restricted(rule), Self::DenyAll) | (Self::DenyAll, Self::Restricted(rule)) => Self::Restricted(rule),
         }
     }
+
+    /// Returns a rule that is satisfied only if all of the given rules are satisfied. An empty set of rules allows all.
+    pub fn all_of<I: IntoIterator<Item = Self>>(rules: I) -> Self {
+        rules.into_iter().fold(Self::AllowAll, Self::and)
+    }
+
+    /// Returns a rule that is satisfied if any of the given rules are satisfied. An empty set of rules denies all.
+    pub fn any_of<I: IntoIterator<Item = Self>>(rules: I) -> Self {
+        rules.into_iter().fold(Self::DenyAll, Self::or)
+    }
+}
+
+impl Not for AccessRule {
+    type Output = Self;
+
+    /// Returns a rule that is satisfied only if this rule is not satisfied
+    fn not(self) -> Self {
+        match self {
+            Self::AllowAll => Self::DenyAll,
+            Self::DenyAll => Self::AllowAll,
+            Self::Restricted(rule) => Self::Restricted(!rule),
+        }
+    }
 }
 
 /// An enum that represents the possible ways to restrict access to components or resources
@@ -46,6 +69,8 @@ pub enum RestrictedAccessRule {
     Require(RequireRule),
     AnyOf(Vec<RestrictedAccessRule>),
     AllOf(Vec<RestrictedAccessRule>),
+    /// Satisfied only when the inner rule is not satisfied
+    Not(Box<RestrictedAccessRule>),
 }
 
 impl RestrictedAccessRule {
@@ -58,6 +83,17 @@ impl RestrictedAccessRule {
     }
 }
 
+impl Not for RestrictedAccessRule {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        match self {
+            Self::Not(rule) => *rule,
+            rule => Self::Not(Box::new(rule)),
+        }
+    }
+}
+
 /// Specifies a requirement for a [RequireRule].
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
@@ -70,6 +106,14 @@ pub enum RuleRequirement {
     ScopedToComponent(ComponentAddress),
     /// Requires execution within a specific template
     ScopedToTemplate(#[cfg_attr(feature = "ts", ts(type = "Uint8Array"))] TemplateAddress),
+    /// Requires the current epoch to be before (less than) the given epoch
+    BeforeEpoch(#[cfg_attr(feature = "ts", ts(type = "number"))] u64),
+    /// Requires the current epoch to be at or after the given epoch
+    AfterEpoch(#[cfg_attr(feature = "ts", ts(type = "number"))] u64),
+    /// Requires the consensus timestamp (seconds since the Unix epoch) to be before (less than) the given timestamp
+    BeforeTimestamp(#[cfg_attr(feature = "ts", ts(type = "number"))] u64),
+    /// Requires the consensus timestamp (seconds since the Unix epoch) to be at or after the given timestamp
+    AfterTimestamp(#[cfg_attr(feature = "ts", ts(type = "number"))] u64),
 }
 
 impl From<ResourceAddress> for RuleRequirement {
@@ -103,6 +147,11 @@ pub enum RequireRule {
     Require(RuleRequirement),
     AnyOf(Vec<RuleRequirement>),
     AllOf(Vec<RuleRequirement>),
+    /// Satisfied when at least `threshold` of the distinct requirements are met (e.g. 2-of-3 multisig badges)
+    Threshold {
+        threshold: u32,
+        requirements: Vec<RuleRequirement>,
+    },
 }
 
 /// Information needed to specify access rules to methods of a component
@@ -112,6 +161,9 @@ pub struct ComponentAccessRules {
     #[cfg_attr(feature = "ts", ts(type = "Record<string, AccessRule>"))]
     method_access: BTreeMap<String, AccessRule>,
     default: AccessRule,
+    // Components created before this rule existed do not allow other components to query their balances
+    #[serde(default = "deny_all_rule")]
+    balance_query: AccessRule,
 }
 
 impl ComponentAccessRules {
@@ -121,6 +173,7 @@ impl ComponentAccessRules {
         Self {
             method_access: BTreeMap::new(),
             default: AccessRule::DenyAll,
+            balance_query: AccessRule::DenyAll,
         }
     }
 
@@ -130,6 +183,7 @@ impl ComponentAccessRules {
         Self {
             method_access: BTreeMap::new(),
             default: AccessRule::AllowAll,
+            balance_query: AccessRule::DenyAll,
         }
     }
 
@@ -145,11 +199,23 @@ impl ComponentAccessRules {
         self
     }
 
+    /// Set up the rule that determines which callers may query the vault balances of the component.
+    /// By default, only the component itself and its owner may do so.
+    pub fn balance_query(mut self, rule: AccessRule) -> Self {
+        self.balance_query = rule;
+        self
+    }
+
     /// Return the access rule of a particular method in the component
     pub fn get_method_access_rule(&self, name: &str) -> &AccessRule {
         self.method_access.get(name).unwrap_or(&self.default)
     }
 
+    /// Return the access rule for querying the vault balances of the component
+    pub fn get_balance_query_rule(&self) -> &AccessRule {
+        &self.balance_query
+    }
+
     /// Return an iterator over the access rules of all methods
     pub fn method_access_rules_iter(&self) -> impl Iterator<Item = (&String, &AccessRule)> {
         self.method_access.iter()
@@ -172,6 +238,8 @@ pub enum ResourceAuthAction {
     Deposit,
     UpdateNonFungibleData,
     UpdateAccessRules,
+    UpdateMetadata,
+    Freeze,
 }
 
 impl ResourceAuthAction {
@@ -190,13 +258,18 @@ pub struct ResourceAccessRules {
     withdrawable: AccessRule,
     depositable: AccessRule,
     update_non_fungible_data: AccessRule,
+    // Resources created before this rule existed cannot have their metadata updated
+    #[serde(default = "deny_all_rule")]
+    update_metadata: AccessRule,
+    #[serde(default = "deny_all_rule")]
+    freezable: AccessRule,
 }
 
 impl ResourceAccessRules {
     /// Builds a new set of access rules for a resource.
     ///
     /// By default:
-    /// * Minting, burning and recalling are disabled for all users
+    /// * Minting, burning, recalling, freezing and metadata updates are disabled for all users
     /// * Withdrawals, deposits and non-fungible data updates are allowed for all users
     pub fn new() -> Self {
         Self {
@@ -208,6 +281,8 @@ impl ResourceAccessRules {
             withdrawable: AccessRule::AllowAll,
             depositable: AccessRule::AllowAll,
             update_non_fungible_data: AccessRule::AllowAll,
+            update_metadata: AccessRule::DenyAll,
+            freezable: AccessRule::DenyAll,
         }
     }
 
@@ -220,6 +295,8 @@ impl ResourceAccessRules {
             withdrawable: AccessRule::DenyAll,
             depositable: AccessRule::DenyAll,
             update_non_fungible_data: AccessRule::DenyAll,
+            update_metadata: AccessRule::DenyAll,
+            freezable: AccessRule::DenyAll,
         }
     }
 
@@ -260,6 +337,19 @@ impl ResourceAccessRules {
         self
     }
 
+    /// Sets up who can update the metadata of the resource (e.g. the token symbol or image URL)
+    pub fn update_metadata(mut self, rule: AccessRule) -> Self {
+        self.update_metadata = rule;
+        self
+    }
+
+    /// Sets up who can freeze and unfreeze the resource.
+    /// While frozen, no tokens of the resource can be minted, withdrawn or deposited
+    pub fn freezable(mut self, rule: AccessRule) -> Self {
+        self.freezable = rule;
+        self
+    }
+
     /// Returns a reference to the access rule for the specified action
     pub fn get_access_rule(&self, action: &ResourceAuthAction) -> &AccessRule {
         match action {
@@ -269,6 +359,8 @@ impl ResourceAccessRules {
             ResourceAuthAction::Withdraw => &self.withdrawable,
             ResourceAuthAction::Deposit => &self.depositable,
             ResourceAuthAction::UpdateNonFungibleData => &self.update_non_fungible_data,
+            ResourceAuthAction::UpdateMetadata => &self.update_metadata,
+            ResourceAuthAction::Freeze => &self.freezable,
             // Only owner can do this
             ResourceAuthAction::UpdateAccessRules => &AccessRule::DenyAll,
         }
@@ -281,6 +373,10 @@ impl Default for ResourceAccessRules {
     }
 }
 
+fn deny_all_rule() -> AccessRule {
+    AccessRule::DenyAll
+}
+
 #[macro_export]
 macro_rules! rule {
     (allow_all) => {
@@ -302,8 +398,14 @@ macro_rules! __restricted_access_rule {
     (all_of($($tail:tt)*)) => {
         RestrictedAccessRule::AllOf($crate::__build_vec!(@ {__restricted_access_rule} $($tail)*))
     };
-    ($a:ident($b:expr)) => {
-        RestrictedAccessRule::Require($crate::__require_rule!($a($b)))
+    (not($($tail:tt)*)) => {
+        RestrictedAccessRule::Not(Box::new($crate::__restricted_access_rule!($($tail)*)))
+    };
+    (threshold($($tail:tt)*)) => {
+        RestrictedAccessRule::Require($crate::__require_rule!(threshold($($tail)*)))
+    };
+    ($a:ident($($b:tt)*)) => {
+        RestrictedAccessRule::Require($crate::__require_rule!($a($($b)*)))
     };
 }
 
@@ -315,8 +417,14 @@ macro_rules! __require_rule {
     (all_of($($tail:tt)*)) => {
         RequireRule::AllOf($crate::__build_vec!(@ {__rule_requirement} $($tail)*))
     };
-    ($a:ident($b:expr)) => {
-        RequireRule::Require($crate::__rule_requirement!($a($b)))
+    (threshold($m:expr, $($tail:tt)*)) => {
+        RequireRule::Threshold {
+            threshold: $m,
+            requirements: $crate::__build_vec!(@ {__rule_requirement} $($tail)*),
+        }
+    };
+    ($a:ident($($b:tt)*)) => {
+        RequireRule::Require($crate::__rule_requirement!($a($($b)*)))
     };
 }
 
@@ -334,33 +442,45 @@ macro_rules! __rule_requirement {
     (template($x: expr)) => {
         RuleRequirement::ScopedToTemplate($x)
     };
+    (before_epoch($x: expr)) => {
+        RuleRequirement::BeforeEpoch($x)
+    };
+    (after_epoch($x: expr)) => {
+        RuleRequirement::AfterEpoch($x)
+    };
+    (before_timestamp($x: expr)) => {
+        RuleRequirement::BeforeTimestamp($x)
+    };
+    (after_timestamp($x: expr)) => {
+        RuleRequirement::AfterTimestamp($x)
+    };
 }
 
 #[macro_export]
 macro_rules! __build_vec {
     () => (Vec::new());
 
-    (@ {$item_fn:ident} $a:ident($b:expr), $($tail:tt)*) => {{
+    (@ {$item_fn:ident} $a:ident($($b:tt)*), $($tail:tt)*) => {{
         let mut items = Vec::with_capacity(1 + $crate::__expr_counter!($($tail)*));
-        $crate::__build_vec_inner!(@ { items, $item_fn } $a($b), $($tail)*);
+        $crate::__build_vec_inner!(@ { items, $item_fn } $a($($b)*), $($tail)*);
         items
     }};
 
-    (@ {$item_fn:ident} $a:ident($b:expr) $(,)?) => {{
+    (@ {$item_fn:ident} $a:ident($($b:tt)*) $(,)?) => {{
         let mut items = Vec::new();
-        $crate::__build_vec_inner!(@ { items, $item_fn } $a($b),);
+        $crate::__build_vec_inner!(@ { items, $item_fn } $a($($b)*),);
         items
     }};
 }
 
 #[macro_export]
 macro_rules! __build_vec_inner {
-    (@ { $this:ident, $item_fn:ident } $a:ident($e:expr), $($tail:tt)*) => {
-        $crate::args::__push(&mut $this, $crate::$item_fn!($a($e)));
+    (@ { $this:ident, $item_fn:ident } $a:ident($($e:tt)*), $($tail:tt)*) => {
+        $crate::args::__push(&mut $this, $crate::$item_fn!($a($($e)*)));
         $crate::__build_vec_inner!(@ {$this, $item_fn } $($tail)*);
     };
-    (@ { $this:ident, $item_fn:ident } $a:ident($e:expr) $(,)*) => {
-        $crate::args::__push(&mut $this, $crate::$item_fn!($a($e)));
+    (@ { $this:ident, $item_fn:ident } $a:ident($($e:tt)*) $(,)*) => {
+        $crate::args::__push(&mut $this, $crate::$item_fn!($a($($e)*)));
     };
 }
 
@@ -435,6 +555,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_builds_negated_and_nested_access_rules() {
+        let resource_address = ResourceAddress::new(ObjectKey::default());
+        let component_address = ComponentAddress::new(ObjectKey::default());
+        let require_resource =
+            RestrictedAccessRule::Require(RequireRule::Require(RuleRequirement::Resource(resource_address)));
+        let require_component = RestrictedAccessRule::Require(RequireRule::Require(
+            RuleRequirement::ScopedToComponent(component_address),
+        ));
+
+        let rule = rule!(not(resource(resource_address)));
+        assert_eq!(
+            rule,
+            AccessRule::Restricted(RestrictedAccessRule::Not(Box::new(require_resource.clone())))
+        );
+        assert_eq!(
+            !rule,
+            access_rule_from_requirement(RuleRequirement::Resource(resource_address))
+        );
+
+        let rule = rule!(all_of(
+            component(component_address),
+            not(any_of(resource(resource_address), before_epoch(10)))
+        ));
+        assert_eq!(
+            rule,
+            AccessRule::Restricted(RestrictedAccessRule::AllOf(vec![
+                require_component.clone(),
+                RestrictedAccessRule::Not(Box::new(RestrictedAccessRule::AnyOf(vec![
+                    require_resource.clone(),
+                    RestrictedAccessRule::Require(RequireRule::Require(RuleRequirement::BeforeEpoch(10))),
+                ]))),
+            ]))
+        );
+
+        assert_eq!(!rule!(allow_all), AccessRule::DenyAll);
+        assert_eq!(AccessRule::all_of([]), AccessRule::AllowAll);
+        assert_eq!(AccessRule::any_of([]), AccessRule::DenyAll);
+        assert_eq!(
+            AccessRule::any_of([rule!(resource(resource_address)), rule!(component(component_address))]),
+            AccessRule::Restricted(require_resource.or(require_component))
+        );
+    }
+
     fn access_rule_from_requirement(requirement: RuleRequirement) -> AccessRule {
         AccessRule::Restricted(RestrictedAccessRule::Require(RequireRule::Require(requirement)))
     }
@@ -1,6 +1,8 @@
 //   Copyright 2024 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use serde::de::DeserializeOwned;
+use tari_bor::from_value;
 use tari_template_abi::rust::fmt;
 
 use crate::models::{ComponentAddress, TemplateAddress};
@@ -31,11 +33,12 @@ impl fmt::Display for AuthHook {
     }
 }
 
+/// The caller of a resource auth hook
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct AuthHookCaller {
     component_address: Option<ComponentAddress>,
     template_address: TemplateAddress,
-    component_state: Option<tari_bor::Value>,
+    component_snapshot: Option<ComponentSnapshot>,
 }
 
 impl AuthHookCaller {
@@ -43,17 +46,23 @@ impl AuthHookCaller {
         Self {
             component_address,
             template_address,
-            component_state: None,
+            component_snapshot: None,
         }
     }
 
-    pub fn with_component_state(&mut self, component_state: tari_bor::Value) -> &mut Self {
-        self.component_state = Some(component_state);
+    pub fn with_component_snapshot(&mut self, component_snapshot: ComponentSnapshot) -> &mut Self {
+        self.component_snapshot = Some(component_snapshot);
         self
     }
 
+    /// Returns a read-only snapshot of the calling component, if the caller is a component. The calling component is
+    /// locked for the duration of the hook, so this should be used instead of loading the component state.
+    pub fn component_snapshot(&self) -> Option<&ComponentSnapshot> {
+        self.component_snapshot.as_ref()
+    }
+
     pub fn component_state(&self) -> Option<&tari_bor::Value> {
-        self.component_state.as_ref()
+        self.component_snapshot.as_ref().map(|snapshot| snapshot.state())
     }
 
     pub fn component(&self) -> Option<&ComponentAddress> {
@@ -64,3 +73,38 @@ impl AuthHookCaller {
         &self.template_address
     }
 }
+
+/// A read-only copy of a component's state taken when an auth hook is invoked
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ComponentSnapshot {
+    address: ComponentAddress,
+    template_address: TemplateAddress,
+    state: tari_bor::Value,
+}
+
+impl ComponentSnapshot {
+    pub fn new(address: ComponentAddress, template_address: TemplateAddress, state: tari_bor::Value) -> Self {
+        Self {
+            address,
+            template_address,
+            state,
+        }
+    }
+
+    pub fn address(&self) -> &ComponentAddress {
+        &self.address
+    }
+
+    pub fn template_address(&self) -> &TemplateAddress {
+        &self.template_address
+    }
+
+    pub fn state(&self) -> &tari_bor::Value {
+        &self.state
+    }
+
+    /// Decodes the component state into the given type. Panics if the state cannot be decoded.
+    pub fn decode_state<T: DeserializeOwned>(&self) -> T {
+        from_value(&self.state).expect("Failed to decode component snapshot state")
+    }
+}
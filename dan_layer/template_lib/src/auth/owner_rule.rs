@@ -1,7 +1,7 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use crate::{auth::AccessRule, crypto::RistrettoPublicKeyBytes};
+use crate::{auth::AccessRule, crypto::RistrettoPublicKeyBytes, models::ComponentAddress};
 
 /// Data that is needed to represent ownership of a value (resource or component method).
 /// Owners are the only ones allowed to update the values's access rules after creation
@@ -24,6 +24,9 @@ pub enum OwnerRule {
     None,
     ByAccessRule(AccessRule),
     ByPublicKey(#[cfg_attr(feature = "ts", ts(type = "Array<number>"))] RistrettoPublicKeyBytes),
+    /// Owned by another component. The owner is authorized when the owning component is the caller, or when the
+    /// caller is authorized as the owner of the owning component.
+    OwnedByComponent(ComponentAddress),
 }
 
 impl OwnerRule {
@@ -33,4 +36,11 @@ impl OwnerRule {
             _ => None,
         }
     }
+
+    pub fn owned_by_component(&self) -> Option<&ComponentAddress> {
+        match self {
+            OwnerRule::OwnedByComponent(address) => Some(address),
+            _ => None,
+        }
+    }
 }
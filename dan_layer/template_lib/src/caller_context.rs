@@ -8,7 +8,7 @@ use tari_template_abi::{call_engine, EngineOp};
 use crate::{
     args::{CallerContextAction, CallerContextInvokeArg, InvokeResult},
     crypto::RistrettoPublicKeyBytes,
-    models::{AddressAllocation, ComponentAddress},
+    models::{AddressAllocation, ComponentAddress, ResourceAddress},
 };
 
 /// Allows a template to access information about the current instruction's caller
@@ -49,4 +49,42 @@ impl CallerContext {
         resp.decode()
             .expect("Failed to decode AddressAllocation<ComponentAddress>")
     }
+
+    /// Allocates a component address that is derived from the current template address, the public key of the
+    /// transaction signer and `name`. The address can be computed ahead of time (see
+    /// `tari_engine_types::named_address`) and the engine fails the transaction if the component already exists.
+    pub fn allocate_named_component_address<T: Into<String>>(name: T) -> AddressAllocation<ComponentAddress> {
+        let resp: InvokeResult = call_engine(EngineOp::CallerContextInvoke, &CallerContextInvokeArg {
+            action: CallerContextAction::AllocateNamedComponentAddress,
+            args: invoke_args![name.into()],
+        });
+
+        resp.decode()
+            .expect("Failed to decode AddressAllocation<ComponentAddress>")
+    }
+
+    /// Allocates a resource address that is derived from the current template address, the public key of the
+    /// transaction signer and `name`. Use it with the `with_address_allocation` method of the resource builders.
+    pub fn allocate_named_resource_address<T: Into<String>>(name: T) -> AddressAllocation<ResourceAddress> {
+        let resp: InvokeResult = call_engine(EngineOp::CallerContextInvoke, &CallerContextInvokeArg {
+            action: CallerContextAction::AllocateNamedResourceAddress,
+            args: invoke_args![name.into()],
+        });
+
+        resp.decode()
+            .expect("Failed to decode AddressAllocation<ResourceAddress>")
+    }
+
+    /// Allocates the component address at `index` of the addresses reserved by the transaction. Reserved addresses are
+    /// derived from the transaction hash and pledged by validators before execution.
+    /// It will panic if the transaction does not reserve an output at `index`.
+    pub fn allocate_reserved_component_address(index: u32) -> AddressAllocation<ComponentAddress> {
+        let resp: InvokeResult = call_engine(EngineOp::CallerContextInvoke, &CallerContextInvokeArg {
+            action: CallerContextAction::AllocateReservedComponentAddress,
+            args: invoke_args![index],
+        });
+
+        resp.decode()
+            .expect("Failed to decode AddressAllocation<ComponentAddress>")
+    }
 }
@@ -0,0 +1,68 @@
+//  Copyright 2024 The Tari Project
+//  SPDX-License-Identifier: BSD-3-Clause
+
+//! Ordered collections that can be used in component state
+//!
+//! [`IndexMap`] and [`IndexSet`] keep their entries in insertion order and provide O(1) lookups by key. They are
+//! encoded as CBOR maps and arrays respectively, so the engine indexes any vaults, buckets, components or resources
+//! that they contain (including map keys) in the same way as the other types in the component state. Use
+//! [`BTreeMap`] and [`BTreeSet`] if entries should instead be ordered by key.
+//!
+//! # Costs
+//!
+//! These collections are stored inline in the component state. Reading or modifying an entry does not make any engine
+//! calls, but the whole collection is loaded whenever the component is called and is charged per byte of storage (and
+//! rent, if enabled) each time the component state is written. For large collections where only a few entries are
+//! accessed per call, a [`KeyValueStore`](crate::models::KeyValueStore) is cheaper since each entry is stored and
+//! charged separately.
+//!
+//! # Example
+//! ```
+//! use tari_template_lib::collections::IndexMap;
+//!
+//! let mut balances = IndexMap::<String, u64>::default();
+//! balances.insert("b".to_string(), 2);
+//! balances.insert("a".to_string(), 1);
+//! assert_eq!(balances.keys().collect::<Vec<_>>(), ["b", "a"]);
+//! ```
+
+pub use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// An insertion-ordered map. Construct one with `IndexMap::default()`.
+pub type IndexMap<K, V> = indexmap::IndexMap<K, V, DeterministicState>;
+
+/// An insertion-ordered set. Construct one with `IndexSet::default()`.
+pub type IndexSet<T> = indexmap::IndexSet<T, DeterministicState>;
+
+/// Hasher state used by the collections in this module. The standard library's randomly seeded hasher would make the
+/// amount of work done by a lookup differ between validators, so a fixed hash function is used instead.
+pub type DeterministicState = BuildHasherDefault<FnvHasher>;
+
+/// A 64-bit FNV-1a hasher
+#[derive(Debug, Clone, Copy)]
+pub struct FnvHasher(u64);
+
+impl FnvHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use crate::models::VaultId;
+
+/// Registers a component as a fee sponsor. When a transaction calls a method of the component directly, the engine
+/// first calls the policy method (`fn(&self, method: String) -> Amount`) and pays the returned amount of fees from
+/// the fee vault on behalf of the caller.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct FeeSponsor {
+    #[cfg_attr(feature = "ts", ts(type = "string"))]
+    pub vault_id: VaultId,
+    pub policy_method: String,
+}
+
+impl FeeSponsor {
+    pub fn new<S: Into<String>>(vault_id: VaultId, policy_method: S) -> Self {
+        Self {
+            vault_id,
+            policy_method: policy_method.into(),
+        }
+    }
+}
@@ -6,9 +6,10 @@ use std::marker::PhantomData;
 use crate::{
     auth::{ComponentAccessRules, OwnerRule},
     caller_context::CallerContext,
+    component::FeeSponsor,
     crypto::RistrettoPublicKeyBytes,
     engine,
-    models::{AddressAllocation, ComponentAddress},
+    models::{AddressAllocation, ComponentAddress, VaultId},
 };
 
 /// Utility for building components inside templates
@@ -18,6 +19,8 @@ pub struct ComponentBuilder<T> {
     access_rules: ComponentAccessRules,
     public_key_address: Option<RistrettoPublicKeyBytes>,
     address_allocation: Option<AddressAllocation<ComponentAddress>>,
+    non_reentrant: bool,
+    fee_sponsor: Option<FeeSponsor>,
 }
 
 impl<T> ComponentBuilder<T> {
@@ -29,6 +32,8 @@ impl<T> ComponentBuilder<T> {
             access_rules: ComponentAccessRules::new(),
             public_key_address: None,
             address_allocation: None,
+            non_reentrant: false,
+            fee_sponsor: None,
         }
     }
 
@@ -55,6 +60,22 @@ impl<T> ComponentBuilder<T> {
         self.access_rules = access_rules;
         self
     }
+
+    /// Prevents the component from being called while one of its methods is already executing in the same
+    /// transaction, e.g. by a nested call from another component that it has called.
+    pub fn non_reentrant(mut self) -> Self {
+        self.non_reentrant = true;
+        self
+    }
+
+    /// Pays the fees of transactions that call the component's methods from the given XTR vault. Before each method
+    /// call made directly by a transaction, the engine calls `policy_method` (`fn(&self, method: String) -> Amount`)
+    /// and pays the returned amount of fees from the vault. Returning zero declines to sponsor the call.
+    /// The vault must be part of the component's state.
+    pub fn with_fee_sponsor<S: Into<String>>(mut self, vault_id: VaultId, policy_method: S) -> Self {
+        self.fee_sponsor = Some(FeeSponsor::new(vault_id, policy_method));
+        self
+    }
 }
 
 impl<T: serde::Serialize> ComponentBuilder<T> {
@@ -70,7 +91,14 @@ impl<T: serde::Serialize> ComponentBuilder<T> {
             .map(|pk| CallerContext::allocate_component_address(Some(pk)))
             .or(self.address_allocation);
 
-        let address = engine().create_component(self.component, self.owner_rule, self.access_rules, address_allocation);
+        let address = engine().create_component(
+            self.component,
+            self.owner_rule,
+            self.access_rules,
+            address_allocation,
+            self.non_reentrant,
+            self.fee_sponsor,
+        );
         Component::from_address(address)
     }
 }
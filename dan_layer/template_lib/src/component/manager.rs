@@ -22,12 +22,13 @@
 
 use serde::{de::DeserializeOwned, Serialize};
 use tari_bor::{from_value, to_value};
-use tari_template_abi::{call_engine, EngineOp};
+use tari_template_abi::{call_engine, EngineOp, FunctionDef};
 
 use crate::{
     args::{
         Arg,
         CallAction,
+        CallInterfaceMethodArg,
         CallInvokeArg,
         CallMethodArg,
         ComponentAction,
@@ -35,9 +36,9 @@ use crate::{
         ComponentRef,
         InvokeResult,
     },
-    auth::ComponentAccessRules,
+    auth::{ComponentAccessRules, OwnerRule},
     caller_context::CallerContext,
-    models::{ComponentAddress, TemplateAddress},
+    models::{Amount, ComponentAddress, ResourceAddress, TemplateAddress},
 };
 
 /// Utility for managing components inside templates
@@ -84,6 +85,23 @@ impl ComponentManager {
             .expect("failed to decode component call result from engine")
     }
 
+    /// Calls a method of another component through an interface generated by the `#[interface]` macro. The engine
+    /// checks that the component's template has a method matching `signature` before calling it.
+    pub fn call_interface<R: DeserializeOwned>(&self, signature: FunctionDef, args: Vec<Arg>) -> R {
+        let result = call_engine::<_, InvokeResult>(EngineOp::CallInvoke, &CallInvokeArg {
+            action: CallAction::CallInterfaceMethod,
+            args: invoke_args![CallInterfaceMethodArg {
+                component_address: self.address,
+                signature,
+                args,
+            }],
+        });
+
+        result
+            .decode()
+            .expect("failed to decode component interface call result from engine")
+    }
+
     /// Calls a method of another component. The called method must return a unit type.
     /// Equivalent to [`call::<_, ()>(method, args)`](ComponentManager::call).
     pub fn invoke<T: Into<String>>(&self, method: T, args: Vec<Arg>) {
@@ -122,6 +140,16 @@ impl ComponentManager {
         });
     }
 
+    /// Updates the owner rule of the component, which determines who may update its access rules and bypass them.
+    /// It will panic if the caller is not the owner of the component.
+    pub fn set_owner_rule(&self, owner_rule: OwnerRule) {
+        call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
+            component_ref: ComponentRef::Ref(self.address),
+            action: ComponentAction::SetOwnerRule,
+            args: invoke_args![owner_rule],
+        });
+    }
+
     /// Returns the template address of the component that is being managed
     pub fn get_template_address(&self) -> TemplateAddress {
         let result = call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
@@ -135,6 +163,42 @@ impl ComponentManager {
             .expect("failed to decode component template address from engine")
     }
 
+    /// Destroys the component once the current method call returns. Only the owner of the component may destroy it and
+    /// all of its vaults must be empty at that point, otherwise the transaction will fail.
+    /// It will panic if it is not called from within a method of the component.
+    pub fn destroy(self) {
+        call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
+            component_ref: ComponentRef::Ref(self.address),
+            action: ComponentAction::Destroy,
+            args: invoke_args![],
+        });
+    }
+
+    /// Migrates the component to another template. The `migrate` function of the new template is called with the
+    /// current component state and the state it returns replaces it. Only the owner of the component may migrate it
+    /// and it cannot be called from within a method of the component.
+    pub fn migrate(&self, template_address: TemplateAddress) {
+        call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
+            component_ref: ComponentRef::Ref(self.address),
+            action: ComponentAction::Migrate,
+            args: invoke_args![template_address],
+        });
+    }
+
+    /// Returns the total balance of the given resource held in the vaults of the component. Unless called by the
+    /// component itself or its owner, the component's balance query access rule must allow the caller.
+    pub fn get_vault_balance(&self, resource_address: ResourceAddress) -> Amount {
+        let result = call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
+            component_ref: ComponentRef::Ref(self.address),
+            action: ComponentAction::GetVaultBalance,
+            args: invoke_args![resource_address],
+        });
+
+        result
+            .decode()
+            .expect("failed to decode component vault balance from engine")
+    }
+
     pub fn component_address(&self) -> ComponentAddress {
         self.address
     }
@@ -27,3 +27,6 @@ pub use manager::*;
 
 mod instance;
 pub use instance::*;
+
+mod fee_sponsor;
+pub use fee_sponsor::*;
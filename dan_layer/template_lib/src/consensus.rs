@@ -22,10 +22,13 @@
 
 use tari_template_abi::{call_engine, EngineOp};
 
-use crate::args::{ConsensusAction, ConsensusInvokeArg, InvokeResult};
+use crate::{
+    args::{ConsensusAction, ConsensusInvokeArg, InvokeResult},
+    Hash,
+};
 
 /// The Consensus module provides access to data about the current state of the
-/// chain. Currently, it only exposes the epoch via `current_epoch`.
+/// chain, namely the current epoch, timestamp and random beacon.
 pub struct Consensus {}
 
 impl Consensus {
@@ -37,4 +40,28 @@ impl Consensus {
         resp.decode()
             .expect("Consensus GetCurrentEpoch returned invalid resource type")
     }
+
+    /// Returns the current timestamp as seconds since the Unix epoch. This is the timestamp of the block that prepared
+    /// the transaction, as recorded by the first shard group with inputs, so every validator in every shard group
+    /// executing the transaction observes the same value. It is coarse-grained and lags behind wall-clock time, so it
+    /// should only be used for time locks and expirations.
+    pub fn timestamp() -> u64 {
+        let resp: InvokeResult = call_engine(EngineOp::ConsensusInvoke, &ConsensusInvokeArg {
+            action: ConsensusAction::GetCurrentTimestamp,
+        });
+        resp.decode()
+            .expect("Consensus GetCurrentTimestamp returned invalid timestamp")
+    }
+
+    /// Returns the random beacon for the transaction. The beacon is derived from the transaction id and the VRF output
+    /// of the block that prepared the transaction, as recorded by the first shard group with inputs. It cannot be
+    /// predicted by the sender of the transaction or chosen by the proposer, and every validator in every shard group
+    /// executing the transaction observes the same value. The beacon is not known when a transaction is dry run, so
+    /// dry runs of transactions that call this function fail.
+    pub fn random_beacon() -> Hash {
+        let resp: InvokeResult = call_engine(EngineOp::ConsensusInvoke, &ConsensusInvokeArg {
+            action: ConsensusAction::GetRandomBeacon,
+        });
+        resp.decode().expect("Consensus GetRandomBeacon returned invalid hash")
+    }
 }
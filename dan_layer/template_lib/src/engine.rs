@@ -27,10 +27,10 @@ use tari_template_abi::{call_engine, EngineOp};
 use crate::{
     args::{ComponentAction, ComponentInvokeArg, ComponentRef, CreateComponentArg, EmitLogArg, InvokeResult, LogLevel},
     auth::OwnerRule,
-    component::ComponentManager,
+    component::{ComponentManager, FeeSponsor},
     context::Context,
     get_context,
-    models::{AddressAllocation, ComponentAddress},
+    models::{AddressAllocation, ComponentAddress, Metadata},
     prelude::ComponentAccessRules,
 };
 
@@ -56,6 +56,8 @@ impl TariEngine {
         owner_rule: OwnerRule,
         access_rules: ComponentAccessRules,
         address_allocation: Option<AddressAllocation<ComponentAddress>>,
+        non_reentrant: bool,
+        fee_sponsor: Option<FeeSponsor>,
     ) -> ComponentAddress {
         let encoded_state = to_value(&initial_state).unwrap();
 
@@ -66,7 +68,9 @@ impl TariEngine {
                 encoded_state,
                 owner_rule,
                 access_rules,
-                address_allocation
+                address_allocation,
+                non_reentrant,
+                fee_sponsor,
             }],
         });
 
@@ -74,9 +78,15 @@ impl TariEngine {
     }
 
     pub fn emit_log<T: Into<String>>(&self, level: LogLevel, msg: T) {
+        self.emit_structured_log(level, msg, Metadata::new());
+    }
+
+    /// Emits a log message along with key-value fields that can be read by machines without parsing the message
+    pub fn emit_structured_log<T: Into<String>>(&self, level: LogLevel, msg: T, fields: Metadata) {
         call_engine::<_, ()>(EngineOp::EmitLog, &EmitLogArg {
             level,
             message: msg.into(),
+            fields,
         });
     }
 
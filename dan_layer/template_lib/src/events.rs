@@ -22,14 +22,54 @@
 
 //! A wrapper for engine calls related to events
 
+pub mod account;
+
+use serde::Serialize;
 use tari_template_abi::{call_engine, EngineOp};
 
 use crate::{args::EmitEventArg, models::Metadata};
 
-/// Requests the engine to emit an event that will be permanently recorded in the transaction result
+/// The maximum number of fields of a typed event payload that may be marked as indexed
+pub const MAX_INDEXED_EVENT_FIELDS: usize = 4;
+
+/// Requests the engine to emit an event that will be permanently recorded in the transaction result.
+/// The payload can be any serializable type and is recorded as CBOR. The indexed keys are recorded as strings so that
+/// indexers can search for events by them.
+pub fn emit_typed_event<T: Into<String>, K: Into<Metadata>, P: Serialize + ?Sized>(
+    topic: T,
+    indexed_keys: K,
+    payload: &P,
+) {
+    let data = tari_bor::to_value(payload).expect("Failed to encode event payload");
+    call_engine::<_, ()>(EngineOp::EmitEvent, &EmitEventArg {
+        topic: topic.into(),
+        payload: indexed_keys.into(),
+        data: Some(data),
+        indexed_fields: vec![],
+    });
+}
+
+/// Emits an event with a typed payload, marking the named top-level fields of the payload as indexed. The engine adds
+/// the value of each indexed field to the event's search keys so that events can be filtered without decoding the
+/// payload. Only scalar and address fields can be indexed and at most [`MAX_INDEXED_EVENT_FIELDS`] may be given.
+pub fn emit_indexed_event<T: Into<String>, P: Serialize + ?Sized>(topic: T, indexed_fields: &[&str], payload: &P) {
+    let data = tari_bor::to_value(payload).expect("Failed to encode event payload");
+    call_engine::<_, ()>(EngineOp::EmitEvent, &EmitEventArg {
+        topic: topic.into(),
+        payload: Metadata::new(),
+        data: Some(data),
+        indexed_fields: indexed_fields.iter().map(|f| f.to_string()).collect(),
+    });
+}
+
+/// Requests the engine to emit an event that will be permanently recorded in the transaction result.
+/// The string key-value payload is recorded as the indexed keys of the event and the event has no typed payload. Prefer
+/// [`emit_typed_event`] for structured payloads.
 pub fn emit_event<T: Into<String>, P: Into<Metadata>>(topic: T, payload: P) {
     call_engine::<_, ()>(EngineOp::EmitEvent, &EmitEventArg {
         topic: topic.into(),
         payload: payload.into(),
+        data: None,
+        indexed_fields: vec![],
     });
 }
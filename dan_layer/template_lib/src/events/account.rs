@@ -0,0 +1,47 @@
+//  Copyright 2024 The Tari Project
+//  SPDX-License-Identifier: BSD-3-Clause
+
+//! Typed events emitted by the builtin account template
+//!
+//! Wallets and indexers should decode the typed payload of account events into these structs (e.g. with
+//! `Event::decode_data`) rather than parsing the string search keys of the event.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Amount, ResourceAddress};
+
+/// The topic of a [`DepositEvent`]
+pub const DEPOSIT_EVENT_TOPIC: &str = "deposit";
+/// The topic of a [`WithdrawEvent`]
+pub const WITHDRAW_EVENT_TOPIC: &str = "withdraw";
+/// The topic of a [`FeePaidEvent`]
+pub const FEE_PAID_EVENT_TOPIC: &str = "pay_fee";
+
+/// The payload fields of deposit and withdraw events that are indexed as search keys. The resource is indexed as
+/// `"resource"`, the key used by account events before they carried a typed payload.
+pub const ACCOUNT_EVENT_INDEXED_FIELDS: &[&str] = &["resource", "amount"];
+/// The payload fields of fee events that are indexed as search keys
+pub const FEE_PAID_EVENT_INDEXED_FIELDS: &[&str] = &["amount"];
+
+/// Emitted when a bucket is deposited into an account. The amount of a confidential deposit only includes the revealed
+/// funds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositEvent {
+    #[serde(rename = "resource")]
+    pub resource_address: ResourceAddress,
+    pub amount: Amount,
+}
+
+/// Emitted when an amount of a fungible resource is withdrawn from an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WithdrawEvent {
+    #[serde(rename = "resource")]
+    pub resource_address: ResourceAddress,
+    pub amount: Amount,
+}
+
+/// Emitted when an account pays transaction fees from its revealed Tari balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeePaidEvent {
+    pub amount: Amount,
+}
@@ -42,6 +42,7 @@ mod consensus;
 pub use consensus::Consensus;
 
 pub mod caller_context;
+pub mod collections;
 mod context;
 pub use context::{get_context, init_context, AbiContext};
 
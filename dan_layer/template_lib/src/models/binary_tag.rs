@@ -34,6 +34,7 @@ pub enum BinaryTag {
     FeeClaim = 135,
     ProofId = 136,
     UnclaimedConfidentialOutputAddress = 137,
+    KeyValueStoreId = 138,
 }
 
 impl BinaryTag {
@@ -48,6 +49,7 @@ impl BinaryTag {
             134 => Some(Self::TransactionReceipt),
             135 => Some(Self::FeeClaim),
             136 => Some(Self::ProofId),
+            138 => Some(Self::KeyValueStoreId),
             _ => None,
         }
     }
@@ -73,6 +75,7 @@ mod tests {
             BinaryTag::TransactionReceipt,
             BinaryTag::FeeClaim,
             BinaryTag::ProofId,
+            BinaryTag::KeyValueStoreId,
         ];
 
         for case in cases {
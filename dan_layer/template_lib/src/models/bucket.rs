@@ -22,7 +22,11 @@
 
 use serde::{Deserialize, Serialize};
 use tari_bor::BorTag;
-use tari_template_abi::{call_engine, rust::fmt, EngineOp};
+use tari_template_abi::{
+    call_engine,
+    rust::{collections::BTreeSet, fmt},
+    EngineOp,
+};
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
@@ -106,6 +110,19 @@ impl Bucket {
         resp.decode().expect("Bucket Take returned invalid bucket")
     }
 
+    /// Withdraws the specified non-fungible tokens from the bucket into a new bucket.
+    /// It will panic if the bucket does not contain all of the specified tokens
+    pub fn take_non_fungibles<I: IntoIterator<Item = NonFungibleId>>(&mut self, ids: I) -> Self {
+        let ids: BTreeSet<NonFungibleId> = ids.into_iter().collect();
+        let resp: InvokeResult = call_engine(EngineOp::BucketInvoke, &BucketInvokeArg {
+            bucket_ref: BucketRef::Ref(self.id),
+            action: BucketAction::TakeNonFungibles,
+            args: invoke_args![ids],
+        });
+
+        resp.decode().expect("Bucket TakeNonFungibles returned invalid bucket")
+    }
+
     /// Withdraws an amount (specified in the `proof`) of confidential tokens from the bucket into a new bucket.
     /// It will panic if the proof is invalid or there are not enough tokens in the bucket
     pub fn take_confidential(&mut self, proof: ConfidentialWithdrawProof) -> Self {
@@ -137,6 +154,13 @@ impl Bucket {
         (new_bucket, self)
     }
 
+    /// Split the current bucket, returning two new buckets, one with the specified non-fungible tokens and the other
+    /// with the rest. It will panic if the bucket does not contain all of the specified tokens
+    pub fn split_non_fungibles<I: IntoIterator<Item = NonFungibleId>>(mut self, ids: I) -> (Self, Self) {
+        let new_bucket = self.take_non_fungibles(ids);
+        (new_bucket, self)
+    }
+
     /// Split the current bucket, returning two new buckets, one with an amount (specified in the `proof`) of
     /// confidential tokens and the other with the rest. It will panic if the proof is invalid or there are not
     /// enough tokens in the bucket
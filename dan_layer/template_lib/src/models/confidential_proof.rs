@@ -59,6 +59,16 @@ pub struct ConfidentialStatement {
     pub minimum_value_promise: u64,
     /// If the view key is enabled for a given resource, this proof MUST be provided, otherwise it MUST NOT.
     pub viewable_balance_proof: Option<ViewableBalanceProof>,
+    /// An optional memo for the recipient (e.g. a deposit reference). The memo is encrypted with a key derived from
+    /// the same shared secret as `encrypted_data` and the commitment, so it can only be decrypted for this output.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "Array<number> | null"))]
+    pub encrypted_memo: Option<Vec<u8>>,
+}
+
+impl ConfidentialStatement {
+    /// The maximum size in bytes of an encrypted memo, including the AEAD nonce and tag
+    pub const MAX_ENCRYPTED_MEMO_SIZE: usize = 256;
 }
 
 /// ### Verifiable encryption
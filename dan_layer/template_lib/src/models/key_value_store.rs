@@ -0,0 +1,182 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::marker::PhantomData;
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use tari_bor::BorTag;
+use tari_template_abi::{
+    call_engine,
+    rust::{
+        fmt,
+        fmt::{Display, Formatter},
+        str::FromStr,
+    },
+    EngineOp,
+};
+#[cfg(feature = "ts")]
+use ts_rs::TS;
+
+use super::{BinaryTag, EntityId, KeyParseError, ObjectKey};
+use crate::{
+    args::{InvokeResult, KeyValueStoreAction, KeyValueStoreInvokeArg, KeyValueStoreRef},
+    newtype_struct_serde_impl,
+};
+
+const TAG: u64 = BinaryTag::KeyValueStoreId as u64;
+
+/// A key-value store's unique identification in the Tari network
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
+pub struct KeyValueStoreId(#[cfg_attr(feature = "ts", ts(type = "string"))] BorTag<ObjectKey, TAG>);
+
+impl KeyValueStoreId {
+    pub const fn new(key: ObjectKey) -> Self {
+        Self(BorTag::new(key))
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, KeyParseError> {
+        let key = ObjectKey::from_hex(hex)?;
+        Ok(Self::new(key))
+    }
+
+    pub fn as_object_key(&self) -> &ObjectKey {
+        self.0.inner()
+    }
+
+    pub fn entity_id(&self) -> EntityId {
+        self.0.inner().as_entity_id()
+    }
+}
+
+impl From<ObjectKey> for KeyValueStoreId {
+    fn from(key: ObjectKey) -> Self {
+        Self::new(key)
+    }
+}
+
+impl Display for KeyValueStoreId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "kvstore_{}", *self.0)
+    }
+}
+
+impl AsRef<[u8]> for KeyValueStoreId {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl FromStr for KeyValueStoreId {
+    type Err = KeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix("kvstore_").unwrap_or(s);
+        Self::from_hex(s)
+    }
+}
+
+newtype_struct_serde_impl!(KeyValueStoreId, BorTag<ObjectKey, TAG>);
+
+/// A key-value map that is loaded lazily. Only the id of the store is kept in the component state, each entry is
+/// stored in its own substate, so large maps are never serialized into the component body and only the accessed
+/// entries are loaded and locked.
+///
+/// Entries that already exist must be included as inputs of the transaction that accesses them, otherwise they will be
+/// treated as missing.
+pub struct KeyValueStore<K, V> {
+    id: KeyValueStoreId,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: Serialize, V: Serialize + DeserializeOwned> KeyValueStore<K, V> {
+    /// Creates a new empty store. The store must be added to the state of a component before the end of the
+    /// transaction.
+    pub fn new() -> Self {
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::KeyValueStore,
+            action: KeyValueStoreAction::Create,
+            args: invoke_args![],
+        });
+
+        Self {
+            id: resp.decode().expect("failed to decode KeyValueStoreId"),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> KeyValueStoreId {
+        self.id
+    }
+
+    /// Returns the value of the entry with the given key, if any
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.invoke(KeyValueStoreAction::Get, invoke_args![encode_key(key)])
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a value for the given key, returning the previous value if any
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.invoke(KeyValueStoreAction::Insert, invoke_args![encode_key(&key), value])
+    }
+
+    /// Removes the entry with the given key, returning its value if any
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.invoke(KeyValueStoreAction::Remove, invoke_args![encode_key(key)])
+    }
+
+    fn invoke(&self, action: KeyValueStoreAction, args: Vec<Vec<u8>>) -> Option<V> {
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::Ref(self.id),
+            action,
+            args,
+        });
+
+        resp.decode().expect("failed to decode key-value store entry")
+    }
+}
+
+impl<K: Serialize, V: Serialize + DeserializeOwned> Default for KeyValueStore<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode_key<K: Serialize>(key: &K) -> Vec<u8> {
+    tari_bor::encode(key).expect("failed to encode key-value store key")
+}
+
+impl<K, V> Clone for KeyValueStore<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for KeyValueStore<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyValueStore").field("id", &self.id).finish()
+    }
+}
+
+// Only the store id is serialized, the entries live in their own substates
+impl<K, V> Serialize for KeyValueStore<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for KeyValueStore<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = KeyValueStoreId::deserialize(deserializer)?;
+        Ok(Self {
+            id,
+            _marker: PhantomData,
+        })
+    }
+}
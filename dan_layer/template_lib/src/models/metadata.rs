@@ -20,7 +20,7 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::str::FromStr;
+use std::{collections::btree_map, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use tari_bor::BorTag;
@@ -61,6 +61,10 @@ impl Metadata {
         self.0.extend(other.0.into_inner());
         self
     }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, String, String> {
+        self.0.iter()
+    }
 }
 
 impl FromStr for Metadata {
@@ -47,6 +47,9 @@ pub use confidential_proof::*;
 mod entity_id;
 pub use entity_id::*;
 
+mod key_value_store;
+pub use key_value_store::{KeyValueStore, KeyValueStoreId};
+
 mod layer_one_commitment;
 pub use layer_one_commitment::UnclaimedConfidentialOutputAddress;
 
@@ -337,6 +337,15 @@ impl NonFungible {
         ResourceManager::get(*self.address.resource_address())
             .update_non_fungible_data(self.address.id().clone(), data);
     }
+
+    /// Update individual fields of the mutable data of the token. The fields of `patch` are merged into the existing
+    /// data and all other fields are left unchanged.
+    /// Note that this operation may be protected via access rules, resulting in a panic if the caller does not have the
+    /// appropriate permissions
+    pub fn patch_mutable_data<T: Serialize + ?Sized>(&mut self, patch: &T) {
+        ResourceManager::get(*self.address.resource_address())
+            .patch_non_fungible_data(self.address.id().clone(), patch);
+    }
 }
 
 /// All the types of errors that can occur when parsing a non-fungible ID
@@ -70,6 +70,22 @@ impl Proof {
         self.id
     }
 
+    /// Combines the given proofs into a single composite proof. Access rules are checked against all of the resources
+    /// of the composite proof, so a single proof can satisfy rules that require several badges. The given proofs are
+    /// consumed and the resource getters (e.g. [`Proof::resource_address`]) are not supported on the composite proof.
+    /// It will panic if `proofs` is empty.
+    pub fn combine(proofs: Vec<Proof>) -> Proof {
+        let mut proof_ids = proofs.into_iter().map(|p| p.id);
+        let first = proof_ids.next().expect("Proof::combine requires at least one proof");
+        let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
+            proof_ref: ProofRef::Ref(first),
+            action: ProofAction::Combine,
+            args: invoke_args![proof_ids.collect::<Vec<_>>()],
+        });
+
+        Proof::from_id(resp.decode().expect("Proof Combine returned invalid proof id"))
+    }
+
     pub fn resource_address(&self) -> ResourceAddress {
         let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
             proof_ref: ProofRef::Ref(self.id),
@@ -288,6 +288,30 @@ impl Vault {
             .expect("get_non_fungible_ids returned invalid non fungible ids")
     }
 
+    /// Returns up to `limit` IDs of the non-fungibles in this vault, in order, skipping the first `offset` IDs.
+    /// Use this instead of `get_non_fungible_ids` for vaults that may hold a large number of non-fungibles.
+    pub fn get_non_fungible_ids_paged(&self, offset: u32, limit: u32) -> Vec<NonFungibleId> {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::GetNonFungibleIdsPaged,
+            args: invoke_args![offset, limit],
+        });
+
+        resp.decode()
+            .expect("get_non_fungible_ids_paged returned invalid non fungible ids")
+    }
+
+    /// Returns the number of non-fungibles in this vault
+    pub fn non_fungible_count(&self) -> u32 {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::GetNonFungibleCount,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("non_fungible_count returned invalid count")
+    }
+
     /// Returns all the non-fungibles in this vault
     pub fn get_non_fungibles(&self) -> Vec<NonFungible> {
         let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
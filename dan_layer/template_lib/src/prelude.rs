@@ -23,6 +23,8 @@
 //! The prelude contains all the commonly used types and functions that are used. To use it, add the import `use
 //! tari_template_lib::prelude::*;`
 
+#[cfg(feature = "macro")]
+pub use tari_template_macros::interface;
 #[cfg(all(feature = "macro", target_arch = "wasm32"))]
 pub use tari_template_macros::template;
 #[cfg(all(feature = "macro", not(target_arch = "wasm32")))]
@@ -32,13 +34,14 @@ pub use crate::{
     args,
     auth::{ComponentAccessRules as AccessRules, RestrictedAccessRule::*, *},
     caller_context::CallerContext,
+    collections::{IndexMap, IndexSet},
     component::{Component, ComponentManager},
     consensus::Consensus,
     constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, PUBLIC_IDENTITY_RESOURCE_ADDRESS, XTR},
     crypto::{PedersonCommitmentBytes, RistrettoPublicKeyBytes},
     debug,
     error,
-    events::emit_event,
+    events::{emit_event, emit_indexed_event, emit_typed_event},
     info,
     invoke_args,
     log,
@@ -50,6 +53,7 @@ pub use crate::{
         ComponentAddress,
         ConfidentialOutputStatement,
         ConfidentialWithdrawProof,
+        KeyValueStore,
         Metadata,
         NonFungible,
         NonFungibleAddress,
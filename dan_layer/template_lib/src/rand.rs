@@ -2,6 +2,11 @@
 //  SPDX-License-Identifier: BSD-3-Clause
 
 //! Utilities to get random values inside templates
+//!
+//! Random values are derived by the engine from a seed that is agreed on by consensus, mixed with the transaction
+//! hash. Every validator executing the transaction will therefore produce the same values. The seed includes the
+//! random beacon of the transaction, which is only known once the transaction has been prepared in a block, so the
+//! values cannot be predicted when the transaction is signed.
 
 use tari_template_abi::{call_engine, EngineOp};
 
@@ -20,3 +25,9 @@ pub fn random_u32() -> u32 {
     let v = random_bytes(4);
     u32::from_le_bytes(v.as_slice().try_into().unwrap())
 }
+
+/// Returns a `u64` representing a random value
+pub fn random_u64() -> u64 {
+    let v = random_bytes(8);
+    u64::from_le_bytes(v.as_slice().try_into().unwrap())
+}
@@ -6,7 +6,7 @@ use crate::{
     args::MintArg,
     auth::{AccessRule, AuthHook, OwnerRule, ResourceAccessRules},
     crypto::RistrettoPublicKeyBytes,
-    models::{Bucket, ComponentAddress, Metadata, ResourceAddress},
+    models::{AddressAllocation, Amount, Bucket, ComponentAddress, Metadata, ResourceAddress},
     prelude::ConfidentialOutputStatement,
     resource::{ResourceManager, ResourceType},
 };
@@ -19,6 +19,8 @@ pub struct ConfidentialResourceBuilder {
     token_symbol: Option<String>,
     owner_rule: OwnerRule,
     authorize_hook: Option<AuthHook>,
+    max_supply: Option<Amount>,
+    address_allocation: Option<AddressAllocation<ResourceAddress>>,
 }
 
 impl ConfidentialResourceBuilder {
@@ -31,6 +33,8 @@ impl ConfidentialResourceBuilder {
             token_symbol: None,
             owner_rule: OwnerRule::default(),
             authorize_hook: None,
+            max_supply: None,
+            address_allocation: None,
         }
     }
 
@@ -86,6 +90,18 @@ impl ConfidentialResourceBuilder {
         self
     }
 
+    /// Sets up who can update the metadata of the resource after its creation
+    pub fn update_metadata(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.update_metadata(rule);
+        self
+    }
+
+    /// Sets up who can freeze and unfreeze the resource, halting mints, withdrawals and deposits while frozen
+    pub fn freezable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.freezable(rule);
+        self
+    }
+
     /// Sets up the specified `symbol` as the token symbol in the metadata of the resource
     pub fn with_token_symbol<S: Into<String>>(mut self, symbol: S) -> Self {
         self.token_symbol = Some(symbol.into());
@@ -109,6 +125,20 @@ impl ConfidentialResourceBuilder {
         self.add_metadata(IMAGE_URL, url)
     }
 
+    /// Sets the maximum total supply of the resource. Only revealed amounts count towards the total supply of a
+    /// confidential resource, so the engine enforces this limit on the revealed amount of every mint.
+    pub fn with_max_supply<A: Into<Amount>>(mut self, max_supply: A) -> Self {
+        self.max_supply = Some(max_supply.into());
+        self
+    }
+
+    /// Creates the resource at a previously allocated address, for example one obtained from
+    /// `CallerContext::allocate_named_resource_address`
+    pub fn with_address_allocation(mut self, allocation: AddressAllocation<ResourceAddress>) -> Self {
+        self.address_allocation = Some(allocation);
+        self
+    }
+
     /// Specify a hook method that will be called to authorize actions on the resource.
     /// The signature of the method must be `fn(action: ResourceAuthAction, caller: CallerContext)`.
     /// The method should panic to deny the action.
@@ -168,6 +198,10 @@ impl ConfidentialResourceBuilder {
             mint_arg,
             self.view_key,
             self.authorize_hook,
+            None,
+            false,
+            self.max_supply,
+            self.address_allocation,
         )
     }
 }
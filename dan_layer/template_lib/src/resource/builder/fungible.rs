@@ -5,7 +5,7 @@ use super::{IMAGE_URL, TOKEN_SYMBOL};
 use crate::{
     args::MintArg,
     auth::{AccessRule, AuthHook, OwnerRule, ResourceAccessRules},
-    models::{Amount, Bucket, ComponentAddress, Metadata, ResourceAddress},
+    models::{AddressAllocation, Amount, Bucket, ComponentAddress, Metadata, ResourceAddress},
     resource::{ResourceManager, ResourceType},
 };
 
@@ -16,6 +16,9 @@ pub struct FungibleResourceBuilder {
     token_symbol: Option<String>,
     metadata: Metadata,
     authorize_hook: Option<AuthHook>,
+    is_soulbound: bool,
+    max_supply: Option<Amount>,
+    address_allocation: Option<AddressAllocation<ResourceAddress>>,
 }
 
 impl FungibleResourceBuilder {
@@ -27,6 +30,9 @@ impl FungibleResourceBuilder {
             token_symbol: None,
             metadata: Metadata::new(),
             authorize_hook: None,
+            is_soulbound: false,
+            max_supply: None,
+            address_allocation: None,
         }
     }
 
@@ -74,6 +80,18 @@ impl FungibleResourceBuilder {
         self
     }
 
+    /// Sets up who can update the metadata of the resource after its creation
+    pub fn update_metadata(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.update_metadata(rule);
+        self
+    }
+
+    /// Sets up who can freeze and unfreeze the resource, halting mints, withdrawals and deposits while frozen
+    pub fn freezable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.freezable(rule);
+        self
+    }
+
     /// Sets up the specified `symbol` as the token symbol in the metadata of the resource
     pub fn with_token_symbol<S: Into<String>>(mut self, symbol: S) -> Self {
         self.token_symbol = Some(symbol.into());
@@ -97,6 +115,27 @@ impl FungibleResourceBuilder {
         self.add_metadata(IMAGE_URL, url)
     }
 
+    /// Marks the tokens of the resource as soulbound (non-transferable). Once deposited into a vault, the tokens cannot
+    /// be moved to any other vault. They can still be withdrawn, but only to be burnt.
+    pub fn soulbound(mut self) -> Self {
+        self.is_soulbound = true;
+        self
+    }
+
+    /// Sets the maximum total supply of the resource. The engine rejects any mint, including the initial supply, that
+    /// would increase the total supply above this amount.
+    pub fn with_max_supply<A: Into<Amount>>(mut self, max_supply: A) -> Self {
+        self.max_supply = Some(max_supply.into());
+        self
+    }
+
+    /// Creates the resource at a previously allocated address, for example one obtained from
+    /// `CallerContext::allocate_named_resource_address`
+    pub fn with_address_allocation(mut self, allocation: AddressAllocation<ResourceAddress>) -> Self {
+        self.address_allocation = Some(allocation);
+        self
+    }
+
     /// Specify a hook method that will be called to authorize actions on the resource.
     /// The signature of the method must be `fn(action: ResourceAuthAction, caller: CallerContext)`.
     /// The method should panic to deny the action.
@@ -156,6 +195,10 @@ impl FungibleResourceBuilder {
             mint_arg,
             None,
             self.authorize_hook,
+            None,
+            self.is_soulbound,
+            self.max_supply,
+            self.address_allocation,
         )
     }
 }
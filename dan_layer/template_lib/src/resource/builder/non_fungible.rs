@@ -8,8 +8,8 @@ use super::{IMAGE_URL, TOKEN_SYMBOL};
 use crate::{
     args::MintArg,
     auth::{AccessRule, AuthHook, OwnerRule, ResourceAccessRules},
-    models::{Bucket, ComponentAddress, Metadata, NonFungibleId, ResourceAddress},
-    resource::{ResourceManager, ResourceType},
+    models::{AddressAllocation, Amount, Bucket, ComponentAddress, Metadata, NonFungibleId, ResourceAddress, VaultId},
+    resource::{ResourceManager, ResourceType, RoyaltyConfig},
 };
 
 /// Utility for building non-fungible resources inside templates
@@ -19,6 +19,10 @@ pub struct NonFungibleResourceBuilder {
     access_rules: ResourceAccessRules,
     token_symbol: Option<String>,
     authorize_hook: Option<AuthHook>,
+    is_soulbound: bool,
+    royalty: Option<RoyaltyConfig>,
+    max_supply: Option<Amount>,
+    address_allocation: Option<AddressAllocation<ResourceAddress>>,
 }
 
 impl NonFungibleResourceBuilder {
@@ -30,6 +34,10 @@ impl NonFungibleResourceBuilder {
             access_rules: ResourceAccessRules::new(),
             token_symbol: None,
             authorize_hook: None,
+            is_soulbound: false,
+            royalty: None,
+            max_supply: None,
+            address_allocation: None,
         }
     }
 
@@ -77,6 +85,18 @@ impl NonFungibleResourceBuilder {
         self
     }
 
+    /// Sets up who can update the metadata of the resource after its creation
+    pub fn update_metadata(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.update_metadata(rule);
+        self
+    }
+
+    /// Sets up who can freeze and unfreeze the resource, halting mints, withdrawals and deposits while frozen
+    pub fn freezable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.freezable(rule);
+        self
+    }
+
     /// Sets up who can update the mutable data of the tokens in the resource
     pub fn update_non_fungible_data(mut self, rule: AccessRule) -> Self {
         self.access_rules = self.access_rules.update_non_fungible_data(rule);
@@ -106,6 +126,27 @@ impl NonFungibleResourceBuilder {
         self.add_metadata(IMAGE_URL, url)
     }
 
+    /// Marks the tokens of the resource as soulbound (non-transferable). Once deposited into a vault, the tokens cannot
+    /// be moved to any other vault. They can still be withdrawn, but only to be burnt.
+    pub fn soulbound(mut self) -> Self {
+        self.is_soulbound = true;
+        self
+    }
+
+    /// Sets the maximum total supply of the resource. The engine rejects any mint, including the initial supply, that
+    /// would increase the total supply above this amount.
+    pub fn with_max_supply<A: Into<Amount>>(mut self, max_supply: A) -> Self {
+        self.max_supply = Some(max_supply.into());
+        self
+    }
+
+    /// Creates the resource at a previously allocated address, for example one obtained from
+    /// `CallerContext::allocate_named_resource_address`
+    pub fn with_address_allocation(mut self, allocation: AddressAllocation<ResourceAddress>) -> Self {
+        self.address_allocation = Some(allocation);
+        self
+    }
+
     /// Specify a hook method that will be called to authorize actions on the resource.
     /// The signature of the method must be `fn(action: ResourceAuthAction, caller: CallerContext)`.
     /// The method should panic to deny the action.
@@ -136,6 +177,14 @@ impl NonFungibleResourceBuilder {
         self
     }
 
+    /// Collect a royalty of `percentage` (0-100) into the vault `vault_id` whenever tokens of the resource are
+    /// deposited into a component that is not owned by the resource owner. Royalties are only charged on payments
+    /// in the resource held by the royalty vault, which must already exist. See [`RoyaltyConfig`] for details.
+    pub fn with_royalty(mut self, percentage: u8, vault_id: VaultId) -> Self {
+        self.royalty = Some(RoyaltyConfig::new(percentage, vault_id));
+        self
+    }
+
     /// Build the resource, returning the address
     pub fn build(self) -> ResourceAddress {
         let (address, _) = self.build_internal(None);
@@ -192,6 +241,10 @@ impl NonFungibleResourceBuilder {
             mint_arg,
             None,
             self.authorize_hook,
+            self.royalty,
+            self.is_soulbound,
+            self.max_supply,
+            self.address_allocation,
         )
     }
 }
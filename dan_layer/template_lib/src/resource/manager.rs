@@ -37,12 +37,15 @@ use crate::{
         ResourceDiscriminator,
         ResourceGetNonFungibleArg,
         ResourceInvokeArg,
+        ResourcePatchNonFungibleDataArg,
         ResourceRef,
+        ResourceUpdateMetadataArg,
         ResourceUpdateNonFungibleDataArg,
     },
     auth::{OwnerRule, ResourceAccessRules},
     crypto::{PedersonCommitmentBytes, RistrettoPublicKeyBytes},
     models::{
+        AddressAllocation,
         Amount,
         Bucket,
         ConfidentialOutputStatement,
@@ -53,6 +56,7 @@ use crate::{
         VaultId,
     },
     prelude::{AuthHook, ResourceType},
+    resource::RoyaltyConfig,
 };
 
 /// Utility for managing resources inside templates
@@ -104,6 +108,12 @@ impl ResourceManager {
     /// * `access_rules` - Rules that will govern access to the resource
     /// * `metadata` - Collection of information used to describe the resource
     /// * `mint_arg` - Specification of the initial tokens that will be minted on resource creation
+    /// * `view_key` - The view key of a confidential resource
+    /// * `authorize_hook` - A component method that is called to authorize actions on the resource
+    /// * `royalty` - The royalty that is collected when tokens of a non-fungible resource are sold
+    /// * `is_soulbound` - If true, tokens cannot be moved out of the vault they were first deposited into
+    /// * `max_supply` - The maximum total supply of the resource. Any mint that would exceed it will fail.
+    /// * `address_allocation` - A previously allocated address for the resource. If None, a new address is generated.
     pub fn create(
         &self,
         resource_type: ResourceType,
@@ -113,6 +123,10 @@ impl ResourceManager {
         mint_arg: Option<MintArg>,
         view_key: Option<RistrettoPublicKeyBytes>,
         authorize_hook: Option<AuthHook>,
+        royalty: Option<RoyaltyConfig>,
+        is_soulbound: bool,
+        max_supply: Option<Amount>,
+        address_allocation: Option<AddressAllocation<ResourceAddress>>,
     ) -> (ResourceAddress, Option<Bucket>) {
         let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
             resource_ref: ResourceRef::Resource,
@@ -125,6 +139,10 @@ impl ResourceManager {
                 mint_arg,
                 view_key,
                 authorize_hook,
+                royalty,
+                is_soulbound,
+                max_supply,
+                address_allocation,
             }],
         });
 
@@ -358,6 +376,23 @@ impl ResourceManager {
         resp.decode().expect("[update_non_fungible_data] Failed")
     }
 
+    /// Updates individual fields of the `mutable_data` of the non-fungible token identified by `id`. The `patch` must
+    /// encode as a map (e.g. a struct or a `BTreeMap`) and its fields are merged into the existing data, so that only
+    /// the changed fields need to be provided.
+    /// It will panic if the resource has no tokens identified with `id` or if the data cannot be patched
+    pub fn patch_non_fungible_data<T: Serialize + ?Sized>(&self, id: NonFungibleId, patch: &T) {
+        let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
+            resource_ref: self.expect_resource_address(),
+            action: ResourceAction::PatchNonFungibleData,
+            args: invoke_args![ResourcePatchNonFungibleDataArg {
+                id,
+                patch: to_value(patch).unwrap()
+            }],
+        });
+
+        resp.decode().expect("[patch_non_fungible_data] Failed")
+    }
+
     /// Updates access rules that determine who can operate the resource
     /// It will panic if the caller doesn't have permissions for updating access rules
     pub fn set_access_rules(&self, access_rules: ResourceAccessRules) {
@@ -370,6 +405,42 @@ impl ResourceManager {
         resp.decode().expect("[set_access_rules] Failed")
     }
 
+    /// Replaces the metadata of the resource (e.g. to correct the token symbol or image URL)
+    /// It will panic if the caller doesn't have permissions for updating the resource metadata
+    pub fn update_metadata(&self, metadata: Metadata) {
+        let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
+            resource_ref: self.expect_resource_address(),
+            action: ResourceAction::UpdateResourceMetadata,
+            args: invoke_args![ResourceUpdateMetadataArg { metadata }],
+        });
+
+        resp.decode().expect("[update_metadata] Failed")
+    }
+
+    /// Freezes the resource so that no tokens can be minted, withdrawn or deposited until it is unfrozen
+    /// It will panic if the caller doesn't have permissions for freezing the resource
+    pub fn freeze(&self) {
+        let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
+            resource_ref: self.expect_resource_address(),
+            action: ResourceAction::Freeze,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("[freeze] Failed")
+    }
+
+    /// Lifts a previous freeze of the resource
+    /// It will panic if the caller doesn't have permissions for freezing the resource
+    pub fn unfreeze(&self) {
+        let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
+            resource_ref: self.expect_resource_address(),
+            action: ResourceAction::Unfreeze,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("[unfreeze] Failed")
+    }
+
     fn recall_internal(&self, arg: RecallResourceArg) -> Bucket {
         let resp: InvokeResult = call_engine(EngineOp::ResourceInvoke, &ResourceInvokeArg {
             resource_ref: self.expect_resource_address(),
@@ -0,0 +1,40 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_template_abi::rust::fmt;
+
+use crate::models::VaultId;
+
+/// Royalty configuration of a non-fungible resource.
+///
+/// When tokens of the resource are deposited into a component that is not owned by the resource owner (i.e. they are
+/// bought), the engine credits `percentage` of every payment that is deposited in the same transaction into any other
+/// component that is not owned by the resource owner to the royalty vault, whether the payment is made before or
+/// after the tokens change hands. Each payment is charged at most once. Only payments in the resource held by the
+/// royalty vault are charged, so the royalty vault also determines the currency of the royalty.
+#[cfg_attr(
+    feature = "ts",
+    derive(ts_rs::TS),
+    ts(export, export_to = "../../bindings/src/types/")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoyaltyConfig {
+    /// The percentage (0-100) of each payment that is credited to the royalty vault
+    pub percentage: u8,
+    /// The vault that receives the royalties. The vault must exist when the resource is created and must be included
+    /// in the inputs of transactions that sell tokens of the resource.
+    pub vault_id: VaultId,
+}
+
+impl RoyaltyConfig {
+    pub fn new(percentage: u8, vault_id: VaultId) -> Self {
+        Self { percentage, vault_id }
+    }
+}
+
+impl fmt::Display for RoyaltyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% to {}", self.percentage, self.vault_id)
+    }
+}
@@ -23,6 +23,6 @@
 //! Public types that are available to internal template code.
 
 pub use tari_bor::{decode, decode_exact, encode_with_len, from_value, serde};
-pub use tari_template_abi::{wrap_ptr, CallInfo};
+pub use tari_template_abi::{wrap_ptr, ArgDef, CallInfo, FunctionDef, Type};
 
 pub use crate::{args::LogLevel, debug, engine, get_context as context, init_context, panic_hook::register_panic_hook};
@@ -0,0 +1,211 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse2, Error, FnArg, ItemTrait, Pat, Result, TraitItem, TraitItemMethod};
+use tari_template_abi::{ArgDef, FunctionDef, Type as ArgType};
+
+use crate::template::{
+    abi::{convert_to_arg_def, convert_to_arg_type},
+    ast::{TemplateAst, TypeAst},
+};
+
+/// Generates an `<Trait>Interface` struct that implements the annotated trait by calling the methods of a component.
+/// The signature of each trait method is passed to the engine, which checks it against the ABI of the component's
+/// template before making the call.
+pub fn generate_interface(input: TokenStream) -> Result<TokenStream> {
+    let item_trait = parse2::<ItemTrait>(input)?;
+    let trait_name = &item_trait.ident;
+    let vis = &item_trait.vis;
+    let interface_name = format_ident!("{}Interface", trait_name);
+
+    let methods = item_trait
+        .items
+        .iter()
+        .map(|item| match item {
+            TraitItem::Method(method) => generate_interface_method(&trait_name.to_string(), method),
+            item => Err(Error::new_spanned(item, "an interface may only contain methods")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = quote! {
+        #item_trait
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #interface_name {
+            address: ::tari_template_lib::models::ComponentAddress,
+        }
+
+        impl #interface_name {
+            /// Returns an interface to the component at `address`
+            pub fn new(address: ::tari_template_lib::models::ComponentAddress) -> Self {
+                Self { address }
+            }
+
+            pub fn component_address(&self) -> ::tari_template_lib::models::ComponentAddress {
+                self.address
+            }
+        }
+
+        impl #trait_name for #interface_name {
+            #(#methods)*
+        }
+    };
+
+    Ok(output)
+}
+
+fn generate_interface_method(trait_name: &str, method: &TraitItemMethod) -> Result<TokenStream> {
+    let sig = &method.sig;
+    if method.default.is_some() {
+        return Err(Error::new_spanned(
+            sig,
+            "interface methods cannot have a default implementation",
+        ));
+    }
+
+    let mut inputs = sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {},
+        _ => {
+            return Err(Error::new_spanned(
+                sig,
+                "interface methods must take &self or &mut self as the first argument",
+            ))
+        },
+    }
+
+    let arg_names = inputs
+        .map(|arg| match arg {
+            FnArg::Typed(pat_type) => match pat_type.pat.as_ref() {
+                Pat::Ident(pat_ident) => Ok(pat_ident.ident.clone()),
+                pat => Err(Error::new_spanned(pat, "interface method arguments must be named")),
+            },
+            FnArg::Receiver(receiver) => Err(Error::new_spanned(receiver, "unexpected receiver")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let input_types = TemplateAst::get_input_types(&sig.inputs);
+    let output_type = TemplateAst::get_output_type_token(&sig.output);
+    if let Some(TypeAst::Typed { type_path, .. }) = output_type.as_ref() {
+        if type_path.path.is_ident("Self") {
+            return Err(Error::new_spanned(type_path, "interface methods cannot return Self"));
+        }
+    }
+
+    let function_def = FunctionDef {
+        name: sig.ident.to_string(),
+        is_mut: input_types
+            .iter()
+            .any(|t| matches!(t, TypeAst::Receiver { mutability: true })),
+        arguments: input_types
+            .iter()
+            .map(|ty| convert_to_arg_def(trait_name, ty))
+            .collect::<Result<_>>()?,
+        output: output_type
+            .as_ref()
+            .map(|ty| convert_to_arg_type(trait_name, ty))
+            .unwrap_or(ArgType::Unit),
+    };
+    let function_def = function_def_tokens(&function_def);
+
+    Ok(quote! {
+        #sig {
+            ::tari_template_lib::component::ComponentManager::get(self.address)
+                .call_interface(#function_def, ::tari_template_lib::args![#(#arg_names),*])
+        }
+    })
+}
+
+fn function_def_tokens(def: &FunctionDef) -> TokenStream {
+    let name = &def.name;
+    let is_mut = def.is_mut;
+    let arguments = def.arguments.iter().map(arg_def_tokens);
+    let output = arg_type_tokens(&def.output);
+    quote! {
+        ::tari_template_lib::template_dependencies::FunctionDef {
+            name: #name.to_string(),
+            arguments: vec![#(#arguments),*],
+            output: #output,
+            is_mut: #is_mut,
+        }
+    }
+}
+
+fn arg_def_tokens(def: &ArgDef) -> TokenStream {
+    let name = &def.name;
+    let arg_type = arg_type_tokens(&def.arg_type);
+    quote! {
+        ::tari_template_lib::template_dependencies::ArgDef {
+            name: #name.to_string(),
+            arg_type: #arg_type,
+        }
+    }
+}
+
+fn arg_type_tokens(ty: &ArgType) -> TokenStream {
+    let variant = match ty {
+        ArgType::Unit => quote!(Unit),
+        ArgType::Bool => quote!(Bool),
+        ArgType::I8 => quote!(I8),
+        ArgType::I16 => quote!(I16),
+        ArgType::I32 => quote!(I32),
+        ArgType::I64 => quote!(I64),
+        ArgType::I128 => quote!(I128),
+        ArgType::U8 => quote!(U8),
+        ArgType::U16 => quote!(U16),
+        ArgType::U32 => quote!(U32),
+        ArgType::U64 => quote!(U64),
+        ArgType::U128 => quote!(U128),
+        ArgType::String => quote!(String),
+        ArgType::Vec(ty) => {
+            let ty = arg_type_tokens(ty);
+            quote!(Vec(Box::new(#ty)))
+        },
+        ArgType::Tuple(types) => {
+            let types = types.iter().map(arg_type_tokens);
+            quote!(Tuple(vec![#(#types),*]))
+        },
+        ArgType::Other { name } => quote!(Other { name: #name.to_string() }),
+    };
+    quote!(::tari_template_lib::template_dependencies::Type::#variant)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use indoc::indoc;
+    use proc_macro2::TokenStream;
+
+    use super::generate_interface;
+
+    #[test]
+    fn it_generates_an_interface_struct() {
+        let input = TokenStream::from_str(indoc! {"
+            pub trait Counter {
+                fn get(&self) -> u32;
+                fn set(&mut self, value: u32);
+            }
+        "})
+        .unwrap();
+
+        let output = generate_interface(input).unwrap().to_string();
+        assert!(output.contains("pub struct CounterInterface"));
+        assert!(output.contains("impl Counter for CounterInterface"));
+        assert!(output.contains("call_interface"));
+    }
+
+    #[test]
+    fn it_rejects_functions_without_a_receiver() {
+        let input = TokenStream::from_str(indoc! {"
+            pub trait Counter {
+                fn new() -> Self;
+            }
+        "})
+        .unwrap();
+
+        generate_interface(input).unwrap_err();
+    }
+}
@@ -20,6 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+mod interface;
 mod template;
 
 use proc_macro::TokenStream;
@@ -40,3 +41,13 @@ pub fn template_non_wasm(_attr: TokenStream, item: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Generates an `<Trait>Interface` struct from a trait that describes the methods of another component. Calling a
+/// method on the interface calls the corresponding method of the component with compile-time checked argument and
+/// return types. The engine rejects the call if the component's template ABI does not match the trait.
+#[proc_macro_attribute]
+pub fn interface(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    interface::generate_interface(proc_macro2::TokenStream::from(item))
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -30,6 +30,8 @@ use tari_template_abi::{
     TemplateDefV1,
     Type as ArgType,
     ABI_TEMPLATE_DEF_GLOBAL_NAME,
+    ABI_VERSION,
+    ABI_VERSION_GLOBAL_NAME,
 };
 
 use crate::template::ast::{TemplateAst, TypeAst};
@@ -67,16 +69,20 @@ pub fn generate_abi(ast: &TemplateAst) -> Result<TokenStream> {
     let template_def_data = tari_bor::encode_with_len(&template_def);
     let len = template_def_data.len();
     let template_def_name = format_ident!("{ABI_TEMPLATE_DEF_GLOBAL_NAME}");
+    let abi_version_name = format_ident!("{ABI_VERSION_GLOBAL_NAME}");
 
     let output = quote! {
         #[no_mangle]
         pub static #template_def_name: [u8;#len] = [#(#template_def_data),*];
+
+        #[no_mangle]
+        pub static #abi_version_name: u32 = #ABI_VERSION;
     };
 
     Ok(output)
 }
 
-fn convert_to_arg_type(template_name: &str, ty: &TypeAst) -> ArgType {
+pub(crate) fn convert_to_arg_type(template_name: &str, ty: &TypeAst) -> ArgType {
     match ty {
         TypeAst::Receiver { mutability: true } => ArgType::Other {
             name: "&mut self".to_string(),
@@ -89,7 +95,7 @@ fn convert_to_arg_type(template_name: &str, ty: &TypeAst) -> ArgType {
     }
 }
 
-fn convert_to_arg_def(template_name: &str, rust_type: &TypeAst) -> Result<ArgDef> {
+pub(crate) fn convert_to_arg_def(template_name: &str, rust_type: &TypeAst) -> Result<ArgDef> {
     match rust_type {
         // on "&self" we want to pass the component id
         TypeAst::Receiver { mutability: false } => Ok(ArgDef {
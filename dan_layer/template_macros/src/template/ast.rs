@@ -147,7 +147,7 @@ impl TemplateAst {
         }
     }
 
-    fn get_input_types(inputs: &Punctuated<FnArg, Comma>) -> Vec<TypeAst> {
+    pub(crate) fn get_input_types(inputs: &Punctuated<FnArg, Comma>) -> Vec<TypeAst> {
         inputs
             .iter()
             .map(|arg| match arg {
@@ -165,7 +165,7 @@ impl TemplateAst {
             .collect()
     }
 
-    fn get_output_type_token(ast_type: &ReturnType) -> Option<TypeAst> {
+    pub(crate) fn get_output_type_token(ast_type: &ReturnType) -> Option<TypeAst> {
         match ast_type {
             ReturnType::Default => None, // the function does not return anything
             ReturnType::Type(_, t) => Some(Self::get_type_ast(None, t)),
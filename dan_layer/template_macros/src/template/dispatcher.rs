@@ -205,6 +205,8 @@ fn replace_self_in_single_value(type_path: &TypePath) -> Option<Stmt> {
                 ::tari_template_lib::auth::OwnerRule::default(),
                 ::tari_template_lib::auth::ComponentAccessRules::new(),
                 None,
+                false,
+                None,
             );
         });
     }
@@ -231,6 +233,8 @@ fn replace_self_in_tuple(type_tuple: &TypeTuple) -> Stmt {
                             ::tari_template_lib::auth::OwnerRule::default(),
                             :tari_template_lib::auth::ComponentAccessRules::new(),
                             None,
+                            false,
+                            None,
                         )
                     }
                 } else {
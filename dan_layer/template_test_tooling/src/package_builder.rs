@@ -7,7 +7,7 @@ use tari_dan_common_types::services::template_provider::TemplateProvider;
 use tari_dan_engine::{
     abi::TemplateDef,
     template::{LoadedTemplate, TemplateModuleLoader},
-    wasm::{compile::compile_template, WasmModule},
+    wasm::{compile::compile_template, WasmBackendKind, WasmModule},
 };
 use tari_engine_types::hashing::template_hasher32;
 use tari_template_builtin::get_template_builtin;
@@ -46,15 +46,23 @@ impl Package {
 #[derive(Debug, Clone, Default)]
 pub struct PackageBuilder {
     templates: HashMap<TemplateAddress, LoadedTemplate>,
+    wasm_backend: WasmBackendKind,
 }
 
 impl PackageBuilder {
     pub fn new() -> Self {
         Self {
             templates: HashMap::new(),
+            wasm_backend: WasmBackendKind::default(),
         }
     }
 
+    /// Sets the backend used to load the templates that are added after this call
+    pub fn with_wasm_backend(&mut self, backend: WasmBackendKind) -> &mut Self {
+        self.wasm_backend = backend;
+        self
+    }
+
     pub fn add_template<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
         self.add_template_with_features(path, &[])
     }
@@ -62,7 +70,7 @@ impl PackageBuilder {
     pub fn add_template_with_features<P: AsRef<Path>>(&mut self, path: P, features: &[&str]) -> &mut Self {
         let wasm = compile_template(path, features).unwrap();
         let template_addr = template_hasher32().chain(wasm.code()).result();
-        let wasm = wasm.load_template().unwrap();
+        let wasm = wasm.with_backend(self.wasm_backend).load_template().unwrap();
         self.add_loaded_template(template_addr, wasm);
         self
     }
@@ -74,7 +82,10 @@ impl PackageBuilder {
 
     pub fn add_builtin_template(&mut self, address: &TemplateAddress) -> &mut Self {
         let wasm = get_template_builtin(address);
-        let template = WasmModule::from_code(wasm.to_vec()).load_template().unwrap();
+        let template = WasmModule::from_code(wasm.to_vec())
+            .with_backend(self.wasm_backend)
+            .load_template()
+            .unwrap();
         self.add_loaded_template(*address, template);
 
         self
@@ -39,6 +39,7 @@ fn generate_confidential_proof_internal(
         minimum_value_promise: 0,
         encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
         resource_view_key: view_key.clone(),
+        encrypted_memo: None,
     };
 
     let change_mask = PrivateKey::random(&mut OsRng);
@@ -49,6 +50,7 @@ fn generate_confidential_proof_internal(
         minimum_value_promise: 0,
         encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
         resource_view_key: view_key,
+        encrypted_memo: None,
     });
 
     let proof = tari_dan_wallet_crypto::create_confidential_output_statement(
@@ -150,6 +152,7 @@ fn generate_withdraw_proof_internal(
         minimum_value_promise: 0,
         encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
         resource_view_key: view_key.clone(),
+        encrypted_memo: None,
     };
     let change_proof = change_amount.map(|amount| ConfidentialProofStatement {
         amount,
@@ -158,6 +161,7 @@ fn generate_withdraw_proof_internal(
         minimum_value_promise: 0,
         encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
         resource_view_key: view_key,
+        encrypted_memo: None,
     });
 
     let proof = tari_dan_wallet_crypto::create_withdraw_proof(
@@ -25,7 +25,7 @@ use tari_dan_engine::{
     state_store::{memory::MemoryStateStore, new_memory_store, StateWriter},
     template::LoadedTemplate,
     transaction::{TransactionError, TransactionProcessor},
-    wasm::LoadedWasmTemplate,
+    wasm::{LoadedWasmTemplate, WasmBackendKind},
 };
 use tari_engine_types::{
     commit_result::{ExecuteResult, RejectReason},
@@ -37,7 +37,18 @@ use tari_engine_types::{
     vault::Vault,
     virtual_substate::{VirtualSubstate, VirtualSubstateId, VirtualSubstates},
 };
-use tari_template_builtin::{ACCOUNT_NFT_TEMPLATE_ADDRESS, ACCOUNT_TEMPLATE_ADDRESS};
+use tari_template_builtin::{
+    ACCOUNT_NFT_TEMPLATE_ADDRESS,
+    ACCOUNT_TEMPLATE_ADDRESS,
+    AMM_TEMPLATE_ADDRESS,
+    AUCTION_TEMPLATE_ADDRESS,
+    ESCROW_TEMPLATE_ADDRESS,
+    FAUCET_TEMPLATE_ADDRESS,
+    MULTISIG_TEMPLATE_ADDRESS,
+    NAME_SERVICE_TEMPLATE_ADDRESS,
+    PAYMENT_SPLITTER_TEMPLATE_ADDRESS,
+    STAKING_TEMPLATE_ADDRESS,
+};
 use tari_template_lib::{
     args,
     args::Arg,
@@ -72,11 +83,28 @@ pub struct TemplateTest {
 
 impl TemplateTest {
     pub fn new<I: IntoIterator<Item = P>, P: AsRef<Path>>(template_paths: I) -> Self {
+        Self::new_with_wasm_backend(WasmBackendKind::default(), template_paths)
+    }
+
+    /// Creates a test in which all templates, including the builtin templates, run on the given WASM backend
+    pub fn new_with_wasm_backend<I: IntoIterator<Item = P>, P: AsRef<Path>>(
+        wasm_backend: WasmBackendKind,
+        template_paths: I,
+    ) -> Self {
         let mut builder = Package::builder();
+        builder.with_wasm_backend(wasm_backend);
 
         // Add builtin templates
         builder.add_builtin_template(&ACCOUNT_TEMPLATE_ADDRESS);
         builder.add_builtin_template(&ACCOUNT_NFT_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&MULTISIG_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&ESCROW_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&AMM_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&AUCTION_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&NAME_SERVICE_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&PAYMENT_SPLITTER_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&STAKING_TEMPLATE_ADDRESS);
+        builder.add_builtin_template(&FAUCET_TEMPLATE_ADDRESS);
 
         // Add the faucet template for fungible tokens
         builder.add_template(concat!(env!("CARGO_MANIFEST_DIR"), "/templates/faucet"));
@@ -112,6 +140,14 @@ impl TemplateTest {
 
         let mut virtual_substates = VirtualSubstates::new();
         virtual_substates.insert(VirtualSubstateId::CurrentEpoch, VirtualSubstate::CurrentEpoch(0));
+        virtual_substates.insert(
+            VirtualSubstateId::CurrentTimestamp,
+            VirtualSubstate::CurrentTimestamp(0),
+        );
+        virtual_substates.insert(
+            VirtualSubstateId::RandomBeacon,
+            VirtualSubstate::RandomBeacon(Hash::default()),
+        );
 
         Self {
             package: Arc::new(package),
@@ -128,6 +164,10 @@ impl TemplateTest {
                 per_byte_storage_cost: 1,
                 per_event_cost: 1,
                 per_log_cost: 1,
+                max_logs_per_transaction: FeeTable::DEFAULT_MAX_LOGS_PER_TRANSACTION,
+                max_log_bytes_per_transaction: FeeTable::DEFAULT_MAX_LOG_BYTES_PER_TRANSACTION,
+                per_byte_rent_cost_per_epoch: 0,
+                initial_rent_epochs: 0,
             },
             key_seed: 1,
         }
@@ -172,10 +212,14 @@ impl TemplateTest {
                 Substate::new(0, ComponentHeader {
                     template_address: test_faucet_template_address,
                     module_name: "TestFaucet".to_string(),
+                    previous_template_address: None,
                     owner_key: Some(RistrettoPublicKeyBytes::from_bytes(signer_public_key.as_bytes()).unwrap()),
                     owner_rule: OwnerRule::None,
                     access_rules: ComponentAccessRules::allow_all(),
                     entity_id,
+                    non_reentrant: false,
+                    fee_sponsor: None,
+                    rent_paid_until: None,
                     body: ComponentBody { state },
                 }),
             )
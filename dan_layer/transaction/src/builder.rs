@@ -3,7 +3,12 @@
 
 use tari_common_types::types::{PrivateKey, PublicKey};
 use tari_dan_common_types::{Epoch, SubstateRequirement};
-use tari_engine_types::{confidential::ConfidentialClaim, instruction::Instruction, TemplateAddress};
+use tari_engine_types::{
+    confidential::ConfidentialClaim,
+    instruction::Instruction,
+    substate::SubstateId,
+    TemplateAddress,
+};
 use tari_template_lib::{
     args,
     args::Arg,
@@ -12,7 +17,7 @@ use tari_template_lib::{
     prelude::AccessRules,
 };
 
-use crate::{unsigned_transaction::UnsignedTransaction, Transaction, TransactionSignature};
+use crate::{unsigned_transaction::UnsignedTransaction, IdempotencyKey, Transaction, TransactionSignature};
 
 #[derive(Debug, Clone, Default)]
 pub struct TransactionBuilder {
@@ -134,6 +139,23 @@ impl TransactionBuilder {
         })
     }
 
+    /// Creates a proof from the bucket on the workspace under `label`. The proof is the output of the instruction and
+    /// can be named using `put_last_instruction_output_on_workspace`.
+    pub fn create_proof_from_bucket<T: AsRef<[u8]>>(self, label: T) -> Self {
+        self.add_instruction(Instruction::CreateProofFromBucket {
+            key: label.as_ref().to_vec(),
+        })
+    }
+
+    /// Extends the rent of a component, vault or key-value store entry by the given number of epochs. The substate must
+    /// be an input of the transaction.
+    pub fn pay_rent<T: Into<SubstateId>>(self, substate_id: T, epochs: u64) -> Self {
+        self.add_instruction(Instruction::PayRent {
+            substate_id: substate_id.into(),
+            epochs,
+        })
+    }
+
     pub fn claim_burn(self, claim: ConfidentialClaim) -> Self {
         self.add_instruction(Instruction::ClaimBurn { claim: Box::new(claim) })
     }
@@ -212,6 +234,22 @@ impl TransactionBuilder {
         self
     }
 
+    pub fn with_idempotency_key(mut self, idempotency_key: Option<IdempotencyKey>) -> Self {
+        self.unsigned_transaction.idempotency_key = idempotency_key;
+        // Reset the signatures as they are no longer valid
+        self.signatures = vec![];
+        self
+    }
+
+    /// Reserves `num_reserved_outputs` component addresses derived from the transaction hash. Templates may create
+    /// components at these addresses using `CallerContext::allocate_reserved_component_address`.
+    pub fn with_reserved_outputs(mut self, num_reserved_outputs: u32) -> Self {
+        self.unsigned_transaction.num_reserved_outputs = num_reserved_outputs;
+        // Reset the signatures as they are no longer valid
+        self.signatures = vec![];
+        self
+    }
+
     pub fn build_unsigned_transaction(self) -> UnsignedTransaction {
         self.unsigned_transaction
     }
@@ -0,0 +1,87 @@
+//    Copyright 2024 The Tari Project
+//    SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+};
+
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHashSizeError;
+use tari_crypto::tari_utilities::hex::{from_hex, Hex};
+use tari_engine_types::serde_with;
+
+/// A client-chosen key that identifies the intent of a transaction. Validators reject a second transaction from the
+/// same signer carrying the same key within the idempotency window, so a wallet may safely resubmit a transaction
+/// (e.g. after a timeout) without risking it being applied twice.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, Default)]
+#[serde(transparent)]
+pub struct IdempotencyKey {
+    #[serde(with = "serde_with::hex")]
+    key: [u8; 32],
+}
+
+impl IdempotencyKey {
+    pub const fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    pub fn random() -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+        Self::new(key)
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+
+    pub fn into_array(self) -> [u8; 32] {
+        self.key
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, FixedHashSizeError> {
+        let bytes = from_hex(hex).map_err(|_| FixedHashSizeError)?;
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl AsRef<[u8]> for IdempotencyKey {
+    fn as_ref(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+}
+
+impl Display for IdempotencyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key.to_hex())
+    }
+}
+
+impl TryFrom<Vec<u8>> for IdempotencyKey {
+    type Error = FixedHashSizeError;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::try_from(value.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for IdempotencyKey {
+    type Error = FixedHashSizeError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        if value.len() != 32 {
+            return Err(FixedHashSizeError);
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(value);
+        Ok(Self::new(key))
+    }
+}
+
+impl From<[u8; 32]> for IdempotencyKey {
+    fn from(key: [u8; 32]) -> Self {
+        Self::new(key)
+    }
+}
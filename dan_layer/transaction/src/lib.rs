@@ -21,12 +21,14 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 mod builder;
+mod idempotency_key;
 mod signature;
 mod transaction;
 mod transaction_id;
 mod unsigned_transaction;
 
 pub use builder::TransactionBuilder;
+pub use idempotency_key::IdempotencyKey;
 pub use signature::TransactionSignature;
 pub use tari_engine_types::instruction::Instruction;
 pub use transaction::*;
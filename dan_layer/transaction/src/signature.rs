@@ -17,7 +17,7 @@ use tari_engine_types::{
 #[cfg(feature = "ts")]
 use ts_rs::TS;
 
-use crate::unsigned_transaction::UnsignedTransaction;
+use crate::{unsigned_transaction::UnsignedTransaction, IdempotencyKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[cfg_attr(feature = "ts", derive(TS), ts(export, export_to = "../../bindings/src/types/"))]
@@ -71,6 +71,8 @@ struct TransactionSignatureFields<'a> {
     inputs: &'a IndexSet<SubstateRequirement>,
     min_epoch: Option<Epoch>,
     max_epoch: Option<Epoch>,
+    idempotency_key: Option<&'a IdempotencyKey>,
+    num_reserved_outputs: u32,
 }
 
 impl<'a> From<&'a UnsignedTransaction> for TransactionSignatureFields<'a> {
@@ -81,6 +83,8 @@ impl<'a> From<&'a UnsignedTransaction> for TransactionSignatureFields<'a> {
             inputs: &transaction.inputs,
             min_epoch: transaction.min_epoch,
             max_epoch: transaction.max_epoch,
+            idempotency_key: transaction.idempotency_key.as_ref(),
+            num_reserved_outputs: transaction.num_reserved_outputs,
         }
     }
 }
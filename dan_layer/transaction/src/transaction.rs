@@ -5,10 +5,12 @@ use std::{collections::HashSet, fmt::Display};
 
 use indexmap::IndexSet;
 use serde::{Deserialize, Serialize};
+use tari_bor::BorError;
 use tari_common_types::types::PublicKey;
 use tari_crypto::ristretto::RistrettoSecretKey;
 use tari_dan_common_types::{committee::CommitteeInfo, Epoch, SubstateRequirement, VersionedSubstateId};
 use tari_engine_types::{
+    component::new_reserved_component_address,
     hashing::{hasher32, EngineHashDomainLabel},
     indexed_value::{IndexedValue, IndexedValueError},
     instruction::Instruction,
@@ -16,7 +18,13 @@ use tari_engine_types::{
 };
 use tari_template_lib::{models::ComponentAddress, Hash};
 
-use crate::{builder::TransactionBuilder, transaction_id::TransactionId, TransactionSignature, UnsignedTransaction};
+use crate::{
+    builder::TransactionBuilder,
+    transaction_id::TransactionId,
+    IdempotencyKey,
+    TransactionSignature,
+    UnsignedTransaction,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(
@@ -154,6 +162,17 @@ impl Transaction {
         self.all_inputs_substate_ids_iter().count()
     }
 
+    /// Returns the total number of instructions, including fee instructions.
+    pub fn num_instructions(&self) -> usize {
+        self.fee_instructions().len() + self.instructions().len()
+    }
+
+    /// Returns the encoded size in bytes of the signed transaction. Filled inputs are excluded because they are not
+    /// part of the transaction hash and may differ between nodes.
+    pub fn encoded_size(&self) -> Result<usize, BorError> {
+        tari_bor::encoded_len(&(&self.transaction, &self.signatures))
+    }
+
     pub fn filled_inputs(&self) -> &IndexSet<VersionedSubstateId> {
         &self.filled_inputs
     }
@@ -187,6 +206,41 @@ impl Transaction {
         self.transaction.max_epoch
     }
 
+    pub fn idempotency_key(&self) -> Option<&IdempotencyKey> {
+        self.transaction.idempotency_key.as_ref()
+    }
+
+    /// Returns the idempotency key bound to the first signer of this transaction. Two transactions conflict if they
+    /// have the same signer-bound key. Returns None if the transaction has no idempotency key or is not signed.
+    pub fn signer_idempotency_key(&self) -> Option<IdempotencyKey> {
+        let key = self.idempotency_key()?;
+        let signer = self.signatures.first()?.public_key();
+        let hash = hasher32(EngineHashDomainLabel::IdempotencyKey)
+            .chain(signer)
+            .chain(key)
+            .result();
+        Some(IdempotencyKey::new(hash.into_array()))
+    }
+
+    pub fn num_reserved_outputs(&self) -> u32 {
+        self.transaction.num_reserved_outputs
+    }
+
+    /// Returns the component addresses reserved by this transaction
+    pub fn reserved_output_addresses(&self) -> impl Iterator<Item = ComponentAddress> + '_ {
+        let hash = self.hash();
+        (0..self.num_reserved_outputs()).map(move |index| new_reserved_component_address(&hash, index))
+    }
+
+    /// Returns true if the transaction tops up the rent of the substate. Only these transactions may use a substate
+    /// whose rent has expired.
+    pub fn pays_rent_for(&self, substate_id: &SubstateId) -> bool {
+        self.fee_instructions()
+            .iter()
+            .chain(self.instructions())
+            .any(|instruction| matches!(instruction, Instruction::PayRent { substate_id: id, .. } if id == substate_id))
+    }
+
     pub fn as_referenced_components(&self) -> impl Iterator<Item = &ComponentAddress> + '_ {
         self.instructions()
             .iter()
@@ -227,6 +281,9 @@ impl Transaction {
                 Instruction::ClaimBurn { claim } => {
                     substates.insert(SubstateId::UnclaimedConfidentialOutput(claim.output_address));
                 },
+                Instruction::PayRent { substate_id, .. } => {
+                    substates.insert(substate_id.clone());
+                },
                 _ => {},
             }
         }
@@ -14,7 +14,7 @@ use tari_engine_types::{
 };
 use tari_template_lib::models::ComponentAddress;
 
-use crate::{builder::TransactionBuilder, Transaction, TransactionSignature};
+use crate::{builder::TransactionBuilder, IdempotencyKey, Transaction, TransactionSignature};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[cfg_attr(
@@ -30,6 +30,15 @@ pub struct UnsignedTransaction {
     pub inputs: IndexSet<SubstateRequirement>,
     pub min_epoch: Option<Epoch>,
     pub max_epoch: Option<Epoch>,
+    /// Optional key that prevents a second transaction with the same intent from the same signer from being accepted
+    /// within the idempotency window.
+    #[serde(default)]
+    #[cfg_attr(feature = "ts", ts(type = "string | null"))]
+    pub idempotency_key: Option<IdempotencyKey>,
+    /// The number of component addresses reserved by this transaction. These addresses are derived from the
+    /// transaction hash, allowing validators to pledge the output shards before execution.
+    #[serde(default)]
+    pub num_reserved_outputs: u32,
 }
 
 impl UnsignedTransaction {
@@ -50,6 +59,8 @@ impl UnsignedTransaction {
             inputs,
             min_epoch,
             max_epoch,
+            idempotency_key: None,
+            num_reserved_outputs: 0,
         }
     }
 
@@ -78,6 +89,14 @@ impl UnsignedTransaction {
         self.max_epoch
     }
 
+    pub fn idempotency_key(&self) -> Option<&IdempotencyKey> {
+        self.idempotency_key.as_ref()
+    }
+
+    pub fn num_reserved_outputs(&self) -> u32 {
+        self.num_reserved_outputs
+    }
+
     pub fn as_referenced_components(&self) -> impl Iterator<Item = &ComponentAddress> + '_ {
         self.instructions()
             .iter()
@@ -118,6 +137,9 @@ impl UnsignedTransaction {
                 Instruction::ClaimBurn { claim } => {
                     substates.insert(SubstateId::UnclaimedConfidentialOutput(claim.output_address));
                 },
+                Instruction::PayRent { substate_id, .. } => {
+                    substates.insert(substate_id.clone());
+                },
                 _ => {},
             }
         }
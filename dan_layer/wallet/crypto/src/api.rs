@@ -8,7 +8,16 @@ use tari_crypto::{
     ristretto::{pedersen::PedersenCommitment, RistrettoPublicKey, RistrettoSchnorr, RistrettoSecretKey},
     tari_utilities::ByteArray,
 };
-use tari_engine_types::confidential::{challenges, get_commitment_factory, ConfidentialOutput};
+use tari_engine_types::{
+    confidential::{
+        challenges,
+        get_commitment_factory,
+        validate_confidential_withdraw,
+        ConfidentialOutput,
+        ConfidentialProofBatch,
+    },
+    resource_container::ResourceError,
+};
 use tari_template_lib::{
     crypto::{BalanceProofSignature, PedersonCommitmentBytes},
     models::{Amount, ConfidentialOutputStatement, ConfidentialWithdrawProof, EncryptedData},
@@ -17,7 +26,14 @@ use tari_template_lib::{
 use crate::{
     confidential_output::ConfidentialOutputMaskAndValue,
     kdfs,
-    proof::{create_confidential_output_statement, decrypt_data_and_mask, encrypt_data},
+    proof::{
+        create_confidential_output_statement,
+        decrypt_data_and_mask,
+        decrypt_memo,
+        encrypt_data,
+        encrypt_memo,
+        max_memo_plaintext_size,
+    },
     ConfidentialProofError,
     ConfidentialProofStatement,
 };
@@ -85,6 +101,36 @@ pub fn encrypt_value_and_mask(
     Ok(encrypted_data)
 }
 
+pub fn encrypt_memo_for_output(
+    memo: &[u8],
+    commitment: &PedersenCommitment,
+    public_nonce: &RistrettoPublicKey,
+    secret: &RistrettoSecretKey,
+) -> Result<Vec<u8>, WalletCryptoError> {
+    if memo.len() > max_memo_plaintext_size() {
+        return Err(WalletCryptoError::InvalidArgument {
+            name: "memo",
+            details: format!(
+                "memo is {} bytes which exceeds the maximum of {} bytes",
+                memo.len(),
+                max_memo_plaintext_size()
+            ),
+        });
+    }
+    let key = kdfs::encrypted_data_dh_kdf_aead(secret, public_nonce);
+    let encrypted_memo = encrypt_memo(&key, commitment, memo)?;
+    Ok(encrypted_memo)
+}
+
+pub fn extract_memo(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_memo: &[u8],
+) -> Result<Vec<u8>, WalletCryptoError> {
+    decrypt_memo(encryption_key, commitment, encrypted_memo)
+        .map_err(|e| WalletCryptoError::FailedDecryptData { details: e.to_string() })
+}
+
 pub fn extract_value_and_mask(
     encryption_key: &RistrettoSecretKey,
     commitment: &PedersenCommitment,
@@ -136,6 +182,7 @@ pub fn create_output_for_dest(
         encrypted_data,
         minimum_value_promise: 0,
         viewable_balance: None,
+        encrypted_memo: None,
     })
 }
 
@@ -166,6 +213,28 @@ fn generate_balance_proof(
     BalanceProofSignature::try_from_parts(sig.get_public_nonce().as_bytes(), sig.get_signature().as_bytes()).unwrap()
 }
 
+/// Checks that the given withdraw proofs are valid before they are submitted in a transaction. All of their range
+/// proofs and balance proofs are verified in a single batch. Each proof is paired with the view key of its resource, if
+/// any.
+pub fn verify_withdraw_proofs<'a, I>(proofs: I) -> Result<(), WalletCryptoError>
+where I: IntoIterator<Item = (&'a ConfidentialWithdrawProof, Option<&'a RistrettoPublicKey>)> {
+    let mut batch = ConfidentialProofBatch::new();
+    for (proof, view_key) in proofs {
+        let inputs = proof
+            .inputs
+            .iter()
+            .map(|input| PedersenCommitment::from_canonical_bytes(input.as_bytes()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| WalletCryptoError::InvalidArgument {
+                name: "proofs",
+                details: format!("Invalid input commitment: {}", e),
+            })?;
+        validate_confidential_withdraw(&inputs, view_key, proof.clone(), &mut batch)?;
+    }
+    batch.verify()?;
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum WalletCryptoError {
     #[error("Confidential proof error: {0}")]
@@ -178,6 +247,8 @@ pub enum WalletCryptoError {
     InvalidArgument { name: &'static str, details: String },
     #[error("AEAD error: {0}")]
     AeadError(aead::Error),
+    #[error("Invalid proof: {0}")]
+    InvalidProof(#[from] ResourceError),
 }
 
 impl From<aead::Error> for WalletCryptoError {
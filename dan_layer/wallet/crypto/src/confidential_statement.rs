@@ -16,6 +16,7 @@ pub struct ConfidentialProofStatement {
     pub minimum_value_promise: u64,
     pub encrypted_data: EncryptedData,
     pub resource_view_key: Option<RistrettoPublicKey>,
+    pub encrypted_memo: Option<Vec<u8>>,
 }
 
 impl ConfidentialProofStatement {
@@ -76,6 +76,7 @@ pub fn create_confidential_output_statement(
                         view_key,
                     )
                 }),
+                encrypted_memo: stmt.encrypted_memo.clone(),
             })
         })
         .transpose()?;
@@ -96,6 +97,7 @@ pub fn create_confidential_output_statement(
             viewable_balance_proof: stmt.resource_view_key.as_ref().map(|view_key| {
                 create_viewable_balance_proof(&stmt.mask, confidential_output_value, &commitment, view_key)
             }),
+            encrypted_memo: stmt.encrypted_memo.clone(),
         }
     });
 
@@ -122,6 +124,18 @@ fn inner_encrypted_data_kdf_aead(
     aead_key
 }
 
+fn inner_encrypted_memo_kdf_aead(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+) -> EncryptedDataKey {
+    let mut aead_key = EncryptedDataKey::from(SafeArray::default());
+    DomainSeparatedHasher::<Blake2b<U32>, TransactionSecureNonceKdfDomain>::new_with_label("encrypted_memo")
+        .chain(encryption_key.as_bytes())
+        .chain(commitment.as_bytes())
+        .finalize_into(GenericArray::from_mut_slice(aead_key.reveal_mut()));
+    aead_key
+}
+
 pub fn create_viewable_balance_proof(
     mask: &RistrettoSecretKey,
     output_amount: u64,
@@ -265,6 +279,66 @@ pub fn decrypt_data_and_mask(
     ))
 }
 
+const ENCRYPTED_MEMO_TAG: &[u8] = b"TARI_AAD_CONFIDENTIAL_MEMO_EXTEND_NONCE_VARIANT";
+const ENCRYPTED_MEMO_SIZE_TAG: usize = EncryptedData::SIZE_TAG;
+const ENCRYPTED_MEMO_SIZE_NONCE: usize = EncryptedData::SIZE_NONCE;
+const ENCRYPTED_MEMO_OVERHEAD: usize = ENCRYPTED_MEMO_SIZE_TAG + ENCRYPTED_MEMO_SIZE_NONCE;
+
+/// Returns the maximum length of a plaintext memo that fits in [ConfidentialStatement::MAX_ENCRYPTED_MEMO_SIZE] once
+/// encrypted.
+pub const fn max_memo_plaintext_size() -> usize {
+    ConfidentialStatement::MAX_ENCRYPTED_MEMO_SIZE - ENCRYPTED_MEMO_OVERHEAD
+}
+
+/// Encrypts a memo so that only the holder of the encryption key can read it. The key is bound to the output
+/// commitment, so the memo cannot be moved to a different output. The result is encoded as `tag || nonce ||
+/// ciphertext`.
+pub fn encrypt_memo(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    memo: &[u8],
+) -> Result<Vec<u8>, aead::Error> {
+    if memo.len() > max_memo_plaintext_size() {
+        return Err(aead::Error);
+    }
+
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let aead_key = inner_encrypted_memo_kdf_aead(encryption_key, commitment);
+    let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(aead_key.reveal()));
+
+    let mut bytes = vec![0; ENCRYPTED_MEMO_OVERHEAD + memo.len()];
+    bytes[ENCRYPTED_MEMO_OVERHEAD..].copy_from_slice(memo);
+    match cipher.encrypt_in_place_detached(&nonce, ENCRYPTED_MEMO_TAG, &mut bytes[ENCRYPTED_MEMO_OVERHEAD..]) {
+        Ok(tag) => {
+            bytes[..ENCRYPTED_MEMO_SIZE_TAG].copy_from_slice(&tag);
+            bytes[ENCRYPTED_MEMO_SIZE_TAG..ENCRYPTED_MEMO_OVERHEAD].copy_from_slice(&nonce);
+            Ok(bytes)
+        },
+        Err(err) => {
+            bytes.zeroize();
+            Err(err)
+        },
+    }
+}
+
+pub fn decrypt_memo(
+    encryption_key: &RistrettoSecretKey,
+    commitment: &PedersenCommitment,
+    encrypted_memo: &[u8],
+) -> Result<Vec<u8>, aead::Error> {
+    if encrypted_memo.len() < ENCRYPTED_MEMO_OVERHEAD {
+        return Err(aead::Error);
+    }
+    let tag = Tag::from_slice(&encrypted_memo[..ENCRYPTED_MEMO_SIZE_TAG]);
+    let nonce = XNonce::from_slice(&encrypted_memo[ENCRYPTED_MEMO_SIZE_TAG..ENCRYPTED_MEMO_OVERHEAD]);
+    let mut bytes = encrypted_memo[ENCRYPTED_MEMO_OVERHEAD..].to_vec();
+
+    let aead_key = inner_encrypted_memo_kdf_aead(encryption_key, commitment);
+    let cipher = XChaCha20Poly1305::new(GenericArray::from_slice(aead_key.reveal()));
+    cipher.decrypt_in_place_detached(nonce, ENCRYPTED_MEMO_TAG, bytes.as_mut_slice(), tag)?;
+    Ok(bytes)
+}
+
 fn generate_extended_bullet_proof(
     output_statement: Option<&ConfidentialProofStatement>,
     change_statement: Option<&ConfidentialProofStatement>,
@@ -306,7 +380,7 @@ fn generate_extended_bullet_proof(
 mod tests {
     use rand::rngs::OsRng;
     use tari_crypto::{keys::SecretKey, ristretto::RistrettoSecretKey};
-    use tari_engine_types::confidential::validate_confidential_proof;
+    use tari_engine_types::confidential::{validate_confidential_proof, ConfidentialProofBatch};
     use tari_template_lib::models::Amount;
 
     use super::*;
@@ -325,6 +399,7 @@ mod tests {
                     sender_public_nonce: Default::default(),
                     encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
                     resource_view_key: None,
+                    encrypted_memo: None,
                 }),
                 Default::default(),
                 None,
@@ -336,16 +411,22 @@ mod tests {
         #[test]
         fn it_is_valid_if_proof_is_valid() {
             let proof = create_valid_proof(100.into(), 0);
-            validate_confidential_proof(&proof, None).unwrap();
+            let mut batch = ConfidentialProofBatch::new();
+            validate_confidential_proof(&proof, None, &mut batch).unwrap();
+            batch.verify().unwrap();
         }
 
         #[test]
         fn it_is_invalid_if_minimum_value_changed() {
             let mut proof = create_valid_proof(100.into(), 100);
             proof.output_statement.as_mut().unwrap().minimum_value_promise = 99;
-            validate_confidential_proof(&proof, None).unwrap_err();
+            let mut batch = ConfidentialProofBatch::new();
+            validate_confidential_proof(&proof, None, &mut batch).unwrap();
+            batch.verify().unwrap_err();
             proof.output_statement.as_mut().unwrap().minimum_value_promise = 1000;
-            validate_confidential_proof(&proof, None).unwrap_err();
+            let mut batch = ConfidentialProofBatch::new();
+            validate_confidential_proof(&proof, None, &mut batch).unwrap();
+            batch.verify().unwrap_err();
         }
     }
 
@@ -365,5 +446,22 @@ mod tests {
             let val = decrypt_data_and_mask(&key, &commitment, &encrypted).unwrap();
             assert_eq!(val.0, 100);
         }
+
+        #[test]
+        fn it_encrypts_and_decrypts_memos() {
+            let key = RistrettoSecretKey::random(&mut OsRng);
+            let commitment = get_commitment_factory().commit_value(&key, 100);
+            let encrypted = encrypt_memo(&key, &commitment, b"deposit ref 1234").unwrap();
+            assert!(encrypted.len() <= ConfidentialStatement::MAX_ENCRYPTED_MEMO_SIZE);
+
+            let memo = decrypt_memo(&key, &commitment, &encrypted).unwrap();
+            assert_eq!(memo, b"deposit ref 1234");
+
+            // The memo is bound to the commitment
+            let other_commitment = get_commitment_factory().commit_value(&key, 101);
+            decrypt_memo(&key, &other_commitment, &encrypted).unwrap_err();
+
+            encrypt_memo(&key, &commitment, &[0u8; max_memo_plaintext_size() + 1]).unwrap_err();
+        }
     }
 }
@@ -0,0 +1,79 @@
+//   Copyright 2024 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use rand::rngs::OsRng;
+use tari_crypto::{keys::SecretKey, ristretto::RistrettoSecretKey};
+use tari_dan_wallet_crypto::{
+    create_withdraw_proof,
+    verify_withdraw_proofs,
+    ConfidentialOutputMaskAndValue,
+    ConfidentialProofStatement,
+};
+use tari_template_lib::models::{Amount, ConfidentialWithdrawProof, EncryptedData};
+
+fn create_statement(amount: Amount) -> ConfidentialProofStatement {
+    ConfidentialProofStatement {
+        amount,
+        mask: RistrettoSecretKey::random(&mut OsRng),
+        sender_public_nonce: Default::default(),
+        minimum_value_promise: 0,
+        encrypted_data: EncryptedData::try_from(vec![0; EncryptedData::min_size()]).unwrap(),
+        resource_view_key: None,
+        encrypted_memo: None,
+    }
+}
+
+fn create_proof(input_values: &[u64], output: u64) -> ConfidentialWithdrawProof {
+    let inputs = input_values
+        .iter()
+        .map(|value| ConfidentialOutputMaskAndValue {
+            value: *value,
+            mask: RistrettoSecretKey::random(&mut OsRng),
+        })
+        .collect::<Vec<_>>();
+    let change = input_values.iter().sum::<u64>() - output;
+    create_withdraw_proof(
+        &inputs,
+        Amount::zero(),
+        Some(&create_statement(Amount::try_from(output).unwrap())),
+        Amount::zero(),
+        Some(&create_statement(Amount::try_from(change).unwrap())),
+        Amount::zero(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn it_verifies_many_withdraw_proofs_in_a_batch() {
+    let proofs = (1..=5)
+        .map(|i| create_proof(&[100 * i, 50], 25 * i))
+        .collect::<Vec<_>>();
+
+    verify_withdraw_proofs(proofs.iter().map(|proof| (proof, None))).unwrap();
+}
+
+#[test]
+fn it_rejects_a_batch_containing_an_invalid_balance_proof() {
+    let mut proofs = (1..=5)
+        .map(|i| create_proof(&[100 * i, 50], 25 * i))
+        .collect::<Vec<_>>();
+    // Swap the balance proofs so that each signature is valid on its own but signs the wrong excess
+    let balance_proof = proofs[1].balance_proof;
+    proofs[1].balance_proof = proofs[3].balance_proof;
+    proofs[3].balance_proof = balance_proof;
+
+    verify_withdraw_proofs(proofs.iter().map(|proof| (proof, None))).unwrap_err();
+}
+
+#[test]
+fn it_rejects_a_batch_containing_an_invalid_range_proof() {
+    let mut proofs = (1..=3).map(|i| create_proof(&[100 * i], 10)).collect::<Vec<_>>();
+    proofs[2]
+        .output_proof
+        .output_statement
+        .as_mut()
+        .unwrap()
+        .minimum_value_promise = 11;
+
+    verify_withdraw_proofs(proofs.iter().map(|proof| (proof, None))).unwrap_err();
+}
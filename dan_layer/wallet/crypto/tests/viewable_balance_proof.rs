@@ -30,6 +30,7 @@ fn create_output_statement(value: Amount, view_key: &RistrettoPublicKey) -> Conf
         minimum_value_promise: 0,
         encrypted_data: EncryptedData::try_from(vec![123; EncryptedData::min_size()]).unwrap(),
         resource_view_key: Some(view_key.clone()),
+        encrypted_memo: None,
     }
 }
 
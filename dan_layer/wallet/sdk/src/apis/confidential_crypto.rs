@@ -8,10 +8,13 @@ use tari_dan_wallet_crypto::{
     create_confidential_output_statement,
     create_output_for_dest,
     create_withdraw_proof,
+    encrypt_memo_for_output,
     encrypt_value_and_mask,
+    extract_memo,
     extract_value_and_mask,
     kdfs,
     unblind_output,
+    verify_withdraw_proofs,
     ConfidentialOutputMaskAndValue,
     ConfidentialProofError,
     ConfidentialProofStatement,
@@ -55,6 +58,14 @@ impl ConfidentialCryptoApi {
         Ok(proof)
     }
 
+    /// Verifies the given withdraw proofs in a single batch so that invalid proofs are caught before the transaction is
+    /// submitted
+    pub fn verify_withdraw_proofs<'a, I>(&self, proofs: I) -> Result<(), ConfidentialCryptoApiError>
+    where I: IntoIterator<Item = (&'a ConfidentialWithdrawProof, Option<&'a PublicKey>)> {
+        verify_withdraw_proofs(proofs)?;
+        Ok(())
+    }
+
     pub fn encrypt_value_and_mask(
         &self,
         amount: u64,
@@ -76,6 +87,27 @@ impl ConfidentialCryptoApi {
         Ok(value_and_mask)
     }
 
+    pub fn encrypt_memo(
+        &self,
+        memo: &[u8],
+        commitment: &Commitment,
+        public_nonce: &PublicKey,
+        secret: &PrivateKey,
+    ) -> Result<Vec<u8>, ConfidentialCryptoApiError> {
+        let encrypted_memo = encrypt_memo_for_output(memo, commitment, public_nonce, secret)?;
+        Ok(encrypted_memo)
+    }
+
+    pub fn decrypt_memo(
+        &self,
+        encryption_key: &PrivateKey,
+        commitment: &Commitment,
+        encrypted_memo: &[u8],
+    ) -> Result<Vec<u8>, ConfidentialCryptoApiError> {
+        let memo = extract_memo(encryption_key, commitment, encrypted_memo)?;
+        Ok(memo)
+    }
+
     pub fn generate_output_proof(
         &self,
         statement: &ConfidentialProofStatement,
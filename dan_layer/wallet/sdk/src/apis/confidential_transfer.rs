@@ -385,6 +385,7 @@ where
             &params.destination_public_key,
             params.confidential_amount(),
             resource_view_key.clone(),
+            params.memo.as_deref(),
         )?;
 
         let remaining_left_to_pay = params
@@ -405,6 +406,7 @@ where
                 &account_public_key,
                 change_confidential_amount,
                 resource_view_key,
+                None,
             )?;
 
             let change_value = statement.amount.as_u64_checked().unwrap();
@@ -485,6 +487,7 @@ where
         dest_public_key: &PublicKey,
         confidential_amount: Amount,
         resource_view_key: Option<PublicKey>,
+        memo: Option<&str>,
     ) -> Result<ConfidentialProofStatement, ConfidentialTransferApiError> {
         let mask = if confidential_amount.is_zero() {
             PrivateKey::default()
@@ -502,14 +505,25 @@ where
             &nonce,
         )?;
 
-        Ok(ConfidentialProofStatement {
+        let mut statement = ConfidentialProofStatement {
             amount: confidential_amount,
             mask,
             sender_public_nonce: public_nonce,
             encrypted_data,
             minimum_value_promise: 0,
             resource_view_key,
-        })
+            encrypted_memo: None,
+        };
+
+        // A memo can only be attached to a confidential output
+        if let Some(memo) = memo.filter(|_| !confidential_amount.is_zero()) {
+            let encrypted_memo =
+                self.crypto_api
+                    .encrypt_memo(memo.as_bytes(), &statement.to_commitment(), dest_public_key, &nonce)?;
+            statement.encrypted_memo = Some(encrypted_memo);
+        }
+
+        Ok(statement)
     }
 }
 
@@ -540,6 +554,8 @@ pub struct TransferParams {
     pub proof_from_resource: Option<ResourceAddress>,
     /// Run as a dry run, no funds will be transferred if true
     pub is_dry_run: bool,
+    /// Optional memo that is encrypted for the destination and attached to the confidential output
+    pub memo: Option<String>,
 }
 
 impl TransferParams {
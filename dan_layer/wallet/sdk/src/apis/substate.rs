@@ -93,11 +93,10 @@ where
                 None => {
                     let ValidatorScanResult { address, substate, .. } =
                         self.scan_for_substate(parent_addr, None).await?;
-                    substate_addresses.insert(address.substate_id.clone(), address.version);
-
                     if substate_addresses.contains_key(&address.substate_id) {
                         continue;
                     }
+                    substate_addresses.insert(address.substate_id.clone(), address.version);
 
                     match substate {
                         SubstateValue::Component(data) => {
@@ -145,6 +144,22 @@ where
                         },
                         SubstateValue::UnclaimedConfidentialOutput(_) => {},
                         SubstateValue::FeeClaim(_) => {},
+                        SubstateValue::KeyValueStore(_) => {},
+                        SubstateValue::KeyValueEntry(entry) => {
+                            let Some(value) = entry.value() else {
+                                continue;
+                            };
+                            let value = IndexedWellKnownTypes::from_value(value)?;
+                            for addr in value.referenced_substates() {
+                                if substate_addresses.contains_key(&addr) {
+                                    continue;
+                                }
+
+                                let ValidatorScanResult { address: addr, .. } =
+                                    self.scan_for_substate(&addr, None).await?;
+                                substate_addresses.insert(addr.substate_id, addr.version);
+                            }
+                        },
                     }
                 },
             }
@@ -0,0 +1,2 @@
+ALTER TABLE transactions
+    ADD COLUMN idempotency_key text NULL;
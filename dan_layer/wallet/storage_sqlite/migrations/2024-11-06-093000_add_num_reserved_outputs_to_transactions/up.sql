@@ -0,0 +1,2 @@
+ALTER TABLE transactions
+    ADD COLUMN num_reserved_outputs integer NOT NULL DEFAULT 0;
@@ -9,7 +9,7 @@ use tari_dan_wallet_sdk::{
     models::{TransactionStatus, WalletTransaction},
     storage::WalletStorageError,
 };
-use tari_transaction::UnsignedTransaction;
+use tari_transaction::{IdempotencyKey, UnsignedTransaction};
 
 use crate::{schema::transactions, serialization::deserialize_json};
 
@@ -35,6 +35,8 @@ pub struct Transaction {
     pub finalized_time_ms: Option<i64>,
     pub required_substates: String,
     pub new_account_info: Option<String>,
+    pub idempotency_key: Option<String>,
+    pub num_reserved_outputs: i32,
 }
 
 impl Transaction {
@@ -50,6 +52,17 @@ impl Transaction {
                     inputs,
                     min_epoch: self.min_epoch.map(|epoch| Epoch(epoch as u64)),
                     max_epoch: self.max_epoch.map(|epoch| Epoch(epoch as u64)),
+                    idempotency_key: self
+                        .idempotency_key
+                        .as_deref()
+                        .map(IdempotencyKey::from_hex)
+                        .transpose()
+                        .map_err(|e| WalletStorageError::DecodingError {
+                            operation: "try_into_wallet_transaction",
+                            item: "idempotency_key",
+                            details: e.to_string(),
+                        })?,
+                    num_reserved_outputs: self.num_reserved_outputs as u32,
                 },
                 signatures,
             ),
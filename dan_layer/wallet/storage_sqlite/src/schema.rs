@@ -121,6 +121,8 @@ diesel::table! {
         finalized_time_ms -> Nullable<BigInt>,
         required_substates -> Text,
         new_account_info -> Nullable<Text>,
+        idempotency_key -> Nullable<Text>,
+        num_reserved_outputs -> Integer,
     }
 }
 
@@ -283,6 +283,8 @@ impl WalletStoreWriter for WriteTransaction<'_> {
                 transactions::required_substates.eq(serialize_json(&required_substates)?),
                 transactions::new_account_info.eq(new_account_info.map(serialize_json).transpose()?),
                 transactions::dry_run.eq(is_dry_run),
+                transactions::idempotency_key.eq(transaction.idempotency_key().map(|k| k.to_string())),
+                transactions::num_reserved_outputs.eq(transaction.num_reserved_outputs() as i32),
             ))
             .execute(self.connection())
             .map_err(|e| WalletStorageError::general("transactions_insert", e))?;
@@ -6,6 +6,7 @@ use std::{collections::HashMap, path::PathBuf, str::FromStr};
 use tari_dan_common_types::SubstateRequirement;
 use tari_engine_types::{
     commit_result::RejectReason,
+    execution_failure::ExecutionFailure,
     instruction::Instruction,
     substate::{SubstateDiff, SubstateId},
 };
@@ -135,7 +136,7 @@ pub async fn create_component(
 
 pub(crate) fn add_substate_ids(world: &mut TariWorld, outputs_name: String, diff: &SubstateDiff) {
     let outputs = world.outputs.entry(outputs_name).or_default();
-    let mut counters = [0usize, 0, 0, 0, 0, 0, 0, 0, 0];
+    let mut counters = [0usize, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
     for (addr, data) in diff.up_iter() {
         match addr {
             SubstateId::Component(_) => {
@@ -195,6 +196,20 @@ pub(crate) fn add_substate_ids(world: &mut TariWorld, outputs_name: String, diff
                 });
                 counters[7] += 1;
             },
+            SubstateId::KeyValueStore(_) => {
+                outputs.insert(format!("key_value_stores/{}", counters[8]), SubstateRequirement {
+                    substate_id: addr.clone(),
+                    version: Some(data.version()),
+                });
+                counters[8] += 1;
+            },
+            SubstateId::KeyValueEntry(_) => {
+                outputs.insert(format!("key_value_entries/{}", counters[9]), SubstateRequirement {
+                    substate_id: addr.clone(),
+                    version: Some(data.version()),
+                });
+                counters[9] += 1;
+            },
         }
     }
 }
@@ -227,7 +242,7 @@ pub async fn concurrent_call_method(
     for handle in handles {
         let result = handle
             .await
-            .map_err(|e| RejectReason::ExecutionFailure(e.to_string()))?;
+            .map_err(|e| RejectReason::ExecutionFailure(ExecutionFailure::other(e.to_string())))?;
         match result {
             Ok(response) => last_resp = Some(response),
             Err(e) => return Err(e),
@@ -237,9 +252,9 @@ pub async fn concurrent_call_method(
     if let Some(res) = last_resp {
         Ok(res)
     } else {
-        Err(RejectReason::ExecutionFailure(
-            "No responses from any of the concurrent calls".to_owned(),
-        ))
+        Err(RejectReason::ExecutionFailure(ExecutionFailure::other(
+            "No responses from any of the concurrent calls",
+        )))
     }
 }
 
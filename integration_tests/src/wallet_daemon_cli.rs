@@ -897,6 +897,7 @@ pub async fn confidential_transfer(
         dry_run: false,
         input_selection: ConfidentialTransferInputSelection::PreferRevealed,
         output_to_revealed: false,
+        memo: None,
     };
 
     let resp = client.accounts_confidential_transfer(request).await.unwrap();
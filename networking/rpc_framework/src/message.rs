@@ -5,6 +5,7 @@ use std::{convert::TryFrom, fmt, time::Duration};
 
 use bitflags::bitflags;
 use bytes::Bytes;
+use libp2p::PeerId;
 
 use crate::{
     body::{Body, IntoBody},
@@ -19,6 +20,7 @@ use crate::{
 #[derive(Debug)]
 pub struct Request<T> {
     inner: BaseRequest<T>,
+    peer_id: Option<PeerId>,
 }
 
 impl Request<Bytes> {
@@ -26,6 +28,7 @@ impl Request<Bytes> {
         let message = T::decode(&mut self.inner.message)?;
         Ok(Request {
             inner: BaseRequest::new(self.inner.method, message),
+            peer_id: self.peer_id,
         })
     }
 }
@@ -34,13 +37,24 @@ impl<T> Request<T> {
     pub(super) fn new(method: RpcMethod, message: T) -> Self {
         Self {
             inner: BaseRequest::new(method, message),
+            peer_id: None,
         }
     }
 
+    pub(super) fn with_peer_id(mut self, peer_id: PeerId) -> Self {
+        self.peer_id = Some(peer_id);
+        self
+    }
+
     pub fn method(&self) -> RpcMethod {
         self.inner.method
     }
 
+    /// The peer that sent this request. This is None for requests that were not received from a peer e.g. in tests.
+    pub fn peer_id(&self) -> Option<&PeerId> {
+        self.peer_id.as_ref()
+    }
+
     #[inline]
     pub fn message(&self) -> &T {
         &self.inner.message
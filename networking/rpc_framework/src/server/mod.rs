@@ -634,7 +634,7 @@ where TSvc: Service<Request<Bytes>, Response = Response<Body>, Error = RpcStatus
             method.id()
         );
 
-        let req = Request::new(method, decoded_msg.payload.into());
+        let req = Request::new(method, decoded_msg.payload.into()).with_peer_id(self.peer_id);
 
         let service_call = log_timing(
             self.logging_context_string.clone(),
@@ -23,6 +23,15 @@
 use tari_template_abi::rust::collections::HashMap;
 use tari_template_lib::prelude::*;
 
+/// Returns the share of `balance` that `amount` of `total` entitles its holder to, rounded up
+fn proportional_amount(balance: Amount, amount: Amount, total: Amount) -> Amount {
+    let balance = i128::from(balance.value());
+    let amount = i128::from(amount.value());
+    let total = i128::from(total.value());
+    let share = (balance * amount + total - 1) / total;
+    Amount::new(share as i64)
+}
+
 #[template]
 mod tariswap {
     use super::*;
@@ -142,14 +151,15 @@ mod tariswap {
 
             // get the pool information
             let a_resource = self.get_a_resource();
-            let a_balance = self.get_pool_balance(a_resource).value() as f64;
+            let a_balance = self.get_pool_balance(a_resource);
             let b_resource = self.get_b_resource();
-            let b_balance = self.get_pool_balance(b_resource).value() as f64;
+            let b_balance = self.get_pool_balance(b_resource);
 
-            // calculate the amount of tokens to take from each pool
-            let lp_ratio = lp_bucket.amount().value() as f64 / self.lp_total_supply().value() as f64;
-            let a_amount = Amount::new((lp_ratio * a_balance).ceil() as i64);
-            let b_amount = Amount::new((lp_ratio * b_balance).ceil() as i64);
+            // calculate the amount of tokens to take from each pool, rounding up
+            let lp_amount = lp_bucket.amount();
+            let lp_total_supply = self.lp_total_supply();
+            let a_amount = proportional_amount(a_balance, lp_amount, lp_total_supply);
+            let b_amount = proportional_amount(b_balance, lp_amount, lp_total_supply);
 
             // burn the LP tokens
             lp_bucket.burn();